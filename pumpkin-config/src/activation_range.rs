@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Spigot-style activation ranges, lowering the cost of mobs far away from players.
+///
+/// Mobs further away from every player than the range of their category are inactive: they
+/// skip their AI but still move and fall, and only get a full tick every
+/// `inactive_tick_interval` ticks.
+/// Leashed mobs, mobs that ride or are ridden, and recently hurt mobs always stay active.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ActivationRangeConfig {
+    /// Whether far away mobs tick less often.
+    pub enabled: bool,
+    /// Horizontal range around players in which monsters are active, in blocks.
+    pub monster_range: u32,
+    /// Horizontal range around players in which animals and other creatures are active.
+    pub creature_range: u32,
+    /// Horizontal range around players in which fish, squids and axolotls are active.
+    pub water_range: u32,
+    /// Horizontal range around players in which bats are active.
+    pub ambient_range: u32,
+    /// Horizontal range around players in which other mobs, like villagers and golems, are
+    /// active.
+    pub misc_range: u32,
+    /// How often inactive mobs still get a full tick, in ticks.
+    pub inactive_tick_interval: u32,
+}
+
+impl Default for ActivationRangeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            monster_range: 32,
+            creature_range: 32,
+            water_range: 16,
+            ambient_range: 16,
+            misc_range: 16,
+            inactive_tick_interval: 20,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Which AI provider sets up the goals of new mobs. Providers are added by plugins under a name,
+/// `vanilla` is always available. Unknown names fall back to `vanilla`.
+///
+/// The most specific setting wins: a world's category, then the world, then the global category
+/// and finally the global provider. Plugins choosing a provider for a single entity type
+/// override all of these.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AiConfig {
+    /// The provider of mobs no other setting applies to.
+    pub provider: String,
+    /// Providers per mob category.
+    pub categories: AiCategoryProviders,
+    /// Overrides per world, keyed by dimension name, e.g. `the_nether`.
+    pub worlds: HashMap<String, AiWorldConfig>,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            provider: "vanilla".to_string(),
+            categories: AiCategoryProviders::default(),
+            worlds: HashMap::new(),
+        }
+    }
+}
+
+/// The overrides of a single world.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct AiWorldConfig {
+    /// The provider of mobs in this world that have no category override.
+    pub provider: Option<String>,
+    /// Providers per mob category in this world.
+    pub categories: AiCategoryProviders,
+}
+
+/// Providers per mob category. Mobs outside these categories use the provider of their world.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct AiCategoryProviders {
+    /// Monsters, e.g. zombies and creepers.
+    pub hostile: Option<String>,
+    /// Land animals and ambient mobs, e.g. cows and bats.
+    pub passive: Option<String>,
+    /// Fish, squids, dolphins and axolotls.
+    pub water: Option<String>,
+}
+
+/// The mob categories providers can be chosen for.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AiCategory {
+    #[serde(rename = "hostile")]
+    Hostile,
+    #[serde(rename = "passive")]
+    Passive,
+    #[serde(rename = "water")]
+    Water,
+}
+
+impl AiCategory {
+    pub const ALL: [Self; 3] = [Self::Hostile, Self::Passive, Self::Water];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Hostile => "hostile",
+            Self::Passive => "passive",
+            Self::Water => "water",
+        }
+    }
+}
+
+impl AiCategoryProviders {
+    #[must_use]
+    pub fn get(&self, category: AiCategory) -> Option<&str> {
+        match category {
+            AiCategory::Hostile => self.hostile.as_deref(),
+            AiCategory::Passive => self.passive.as_deref(),
+            AiCategory::Water => self.water.as_deref(),
+        }
+    }
+
+    pub const fn get_mut(&mut self, category: AiCategory) -> &mut Option<String> {
+        match category {
+            AiCategory::Hostile => &mut self.hostile,
+            AiCategory::Passive => &mut self.passive,
+            AiCategory::Water => &mut self.water,
+        }
+    }
+}
+
+impl AiConfig {
+    /// The name of the provider for mobs of `category` in `world`. The `minecraft:` prefix of
+    /// world names is optional.
+    #[must_use]
+    pub fn provider_for(&self, world: &str, category: Option<AiCategory>) -> &str {
+        let world = self.world(world);
+        category
+            .and_then(|category| world.and_then(|world| world.categories.get(category)))
+            .or_else(|| world.and_then(|world| world.provider.as_deref()))
+            .or_else(|| category.and_then(|category| self.categories.get(category)))
+            .unwrap_or(&self.provider)
+    }
+
+    #[must_use]
+    pub fn world(&self, world: &str) -> Option<&AiWorldConfig> {
+        let short = world.strip_prefix("minecraft:").unwrap_or(world);
+        self.worlds.get(short).or_else(|| self.worlds.get(world))
+    }
+
+    /// The overrides of `world`, stored under the name without the `minecraft:` prefix.
+    pub fn world_mut(&mut self, world: &str) -> &mut AiWorldConfig {
+        let short = world.strip_prefix("minecraft:").unwrap_or(world);
+        if let Some(config) = self.worlds.remove(world) {
+            self.worlds.entry(short.to_string()).or_insert(config);
+        }
+        self.worlds.entry(short.to_string()).or_default()
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for alert webhooks.
+///
+/// Alerts are fired on anomalies such as sustained high MSPT, failed saves, plugin panics,
+/// or low disk space, and are delivered to every configured webhook.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AlertingConfig {
+    /// Whether alerting is enabled.
+    pub enabled: bool,
+    /// Name of this server, available as `{server}` in message templates.
+    pub server_name: String,
+    /// Webhooks that receive every alert.
+    pub webhooks: Vec<WebhookConfig>,
+    /// Minimum time in seconds between two alerts of the same kind.
+    pub cooldown_seconds: u64,
+    /// MSPT above which the server is considered overloaded.
+    pub mspt_threshold: f64,
+    /// How long in seconds the MSPT has to stay above the threshold before alerting.
+    pub mspt_duration_seconds: u64,
+    /// Free space in megabytes on the world disk below which an alert is fired. `0` disables the check.
+    pub min_free_disk_mb: u64,
+    /// Time interval in seconds between disk space checks.
+    pub disk_check_interval_seconds: u64,
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server_name: "Pumpkin".to_string(),
+            webhooks: Vec::new(),
+            cooldown_seconds: 300,
+            mspt_threshold: 50.0,
+            mspt_duration_seconds: 30,
+            min_free_disk_mb: 1024,
+            disk_check_interval_seconds: 60,
+        }
+    }
+}
+
+/// A single webhook alerts are posted to.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// The URL the alert is posted to.
+    pub url: String,
+    /// The payload format expected by the receiver.
+    pub format: WebhookFormat,
+    /// Message template. Supports `{server}`, `{event}`, `{message}` and `{timestamp}`.
+    pub template: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            format: WebhookFormat::Json,
+            template: "[{server}] {event}: {message}".to_string(),
+        }
+    }
+}
+
+/// Payload formats understood by common webhook receivers.
+#[derive(Deserialize, Serialize, Clone, Copy, Default)]
+pub enum WebhookFormat {
+    /// Discord webhook, sent as `{"content": ...}`.
+    #[serde(rename = "discord")]
+    Discord,
+    /// Slack incoming webhook, sent as `{"text": ...}`.
+    #[serde(rename = "slack")]
+    Slack,
+    /// Generic JSON with the event, message, server and timestamp as separate fields.
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Hides ores from modified clients that see through blocks. Ores surrounded by opaque blocks are
+/// sent as something else, and their real state once a block next to them changes.
+///
+/// Settings of a world override the global ones.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AntiXrayConfig {
+    /// Whether ores are hidden in worlds that don't override it.
+    pub enabled: bool,
+    /// How hidden blocks are disguised.
+    pub mode: AntiXrayMode,
+    /// The blocks hidden while no side of them can be seen, e.g. `minecraft:diamond_ore`.
+    pub hidden_blocks: Vec<String>,
+    /// The blocks that become random hidden blocks in [`AntiXrayMode::Obfuscate`] when no side of
+    /// them can be seen.
+    pub replacement_blocks: Vec<String>,
+    /// Blocks at and above this height are sent as they are. Lower values mean less work when
+    /// chunks are sent.
+    pub max_block_height: i32,
+    /// How far from a changed block hidden blocks are revealed, in blocks. Higher values mean
+    /// more blocks are looked up for every change.
+    pub update_radius: u8,
+    /// Overrides per world, keyed by dimension name, e.g. `the_nether`.
+    pub worlds: HashMap<String, AntiXrayWorldConfig>,
+}
+
+impl Default for AntiXrayConfig {
+    fn default() -> Self {
+        let names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| format!("minecraft:{name}"))
+                .collect()
+        };
+        Self {
+            enabled: false,
+            mode: AntiXrayMode::Hide,
+            hidden_blocks: names(&[
+                "coal_ore",
+                "deepslate_coal_ore",
+                "copper_ore",
+                "deepslate_copper_ore",
+                "iron_ore",
+                "deepslate_iron_ore",
+                "gold_ore",
+                "deepslate_gold_ore",
+                "redstone_ore",
+                "deepslate_redstone_ore",
+                "lapis_ore",
+                "deepslate_lapis_ore",
+                "diamond_ore",
+                "deepslate_diamond_ore",
+                "emerald_ore",
+                "deepslate_emerald_ore",
+                "nether_gold_ore",
+                "nether_quartz_ore",
+                "ancient_debris",
+                "raw_iron_block",
+                "raw_copper_block",
+                "raw_gold_block",
+            ]),
+            replacement_blocks: names(&[
+                "stone",
+                "deepslate",
+                "tuff",
+                "granite",
+                "diorite",
+                "andesite",
+                "netherrack",
+                "basalt",
+                "blackstone",
+                "end_stone",
+            ]),
+            max_block_height: 64,
+            update_radius: 2,
+            worlds: HashMap::new(),
+        }
+    }
+}
+
+/// The overrides of a single world.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct AntiXrayWorldConfig {
+    pub enabled: Option<bool>,
+    pub mode: Option<AntiXrayMode>,
+    pub hidden_blocks: Option<Vec<String>>,
+    pub replacement_blocks: Option<Vec<String>>,
+    pub max_block_height: Option<i32>,
+}
+
+/// How hidden blocks are disguised.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AntiXrayMode {
+    /// Hidden blocks look like a block next to them, e.g. stone.
+    #[serde(rename = "hide")]
+    Hide,
+    /// Hidden and replacement blocks look like random hidden blocks, so that X-ray clients show
+    /// ores everywhere.
+    #[serde(rename = "obfuscate")]
+    Obfuscate,
+}
+
+/// The settings of a single world, after applying its overrides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AntiXrayWorldSettings<'a> {
+    pub mode: AntiXrayMode,
+    pub hidden_blocks: &'a [String],
+    pub replacement_blocks: &'a [String],
+    pub max_block_height: i32,
+    pub update_radius: u8,
+}
+
+impl AntiXrayConfig {
+    /// The settings of `world`, or `None` if ores aren't hidden there. The `minecraft:` prefix of
+    /// world names is optional.
+    #[must_use]
+    pub fn for_world(&self, world: &str) -> Option<AntiXrayWorldSettings<'_>> {
+        let short = world.strip_prefix("minecraft:").unwrap_or(world);
+        let overrides = self.worlds.get(short).or_else(|| self.worlds.get(world));
+        if !overrides
+            .and_then(|overrides| overrides.enabled)
+            .unwrap_or(self.enabled)
+        {
+            return None;
+        }
+        Some(AntiXrayWorldSettings {
+            mode: overrides
+                .and_then(|overrides| overrides.mode)
+                .unwrap_or(self.mode),
+            hidden_blocks: overrides
+                .and_then(|overrides| overrides.hidden_blocks.as_deref())
+                .unwrap_or(&self.hidden_blocks),
+            replacement_blocks: overrides
+                .and_then(|overrides| overrides.replacement_blocks.as_deref())
+                .unwrap_or(&self.replacement_blocks),
+            max_block_height: overrides
+                .and_then(|overrides| overrides.max_block_height)
+                .unwrap_or(self.max_block_height),
+            update_radius: self.update_radius,
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for world backups.
+///
+/// Backups are compressed tar archives of the world folder, taken after all pending
+/// chunk and player data writes were flushed.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Whether periodic backups are enabled. `/backup now` works regardless.
+    pub enabled: bool,
+    /// Directory the archives are written to.
+    pub directory: String,
+    /// Time interval in minutes between periodic backups.
+    pub interval_minutes: u64,
+    /// Number of most recent hours to keep one backup of.
+    pub keep_hourly: u32,
+    /// Number of most recent days to keep one backup of.
+    pub keep_daily: u32,
+    /// Compression used for the archives.
+    pub compression: BackupCompression,
+    /// Optional S3-compatible storage every new backup is uploaded to.
+    pub upload: Option<BackupUploadConfig>,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "backups".to_string(),
+            interval_minutes: 60,
+            keep_hourly: 24,
+            keep_daily: 7,
+            compression: BackupCompression::Zstd,
+            upload: None,
+        }
+    }
+}
+
+/// Compression algorithms for backup archives.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum BackupCompression {
+    /// `.tar.zst` archives.
+    #[serde(rename = "zstd")]
+    Zstd,
+    /// `.tar.gz` archives.
+    #[serde(rename = "gzip")]
+    Gzip,
+}
+
+/// Credentials and location of an S3-compatible bucket.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct BackupUploadConfig {
+    /// Endpoint URL, e.g. `https://s3.eu-central-1.amazonaws.com` or a `MinIO` address.
+    pub endpoint: String,
+    /// Bucket name. Objects are addressed path-style (`endpoint/bucket/key`).
+    pub bucket: String,
+    /// Region used for request signing.
+    pub region: String,
+    /// Prefix prepended to every object key.
+    pub prefix: String,
+    /// Access key ID.
+    pub access_key: String,
+    /// Secret access key.
+    pub secret_key: String,
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Overrides of how biomes look and sound, sent to clients when they join. Clients play the
+/// ambience and music of the biome they are in on their own.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct BiomeEffectsConfig {
+    /// Overrides per biome, keyed by biome name, e.g. `minecraft:deep_dark`.
+    pub biomes: HashMap<String, BiomeEffects>,
+}
+
+impl BiomeEffectsConfig {
+    /// The overrides of `biome`. The `minecraft:` prefix of biome names is optional.
+    #[must_use]
+    pub fn for_biome(&self, biome: &str) -> Option<&BiomeEffects> {
+        let short = biome.strip_prefix("minecraft:").unwrap_or(biome);
+        self.biomes
+            .get(short)
+            .or_else(|| self.biomes.get(&format!("minecraft:{short}")))
+    }
+}
+
+/// The effects of a single biome. Unset effects keep their vanilla value.
+#[derive(Deserialize, Serialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct BiomeEffects {
+    /// Colors as `#rrggbb`.
+    pub fog_color: Option<String>,
+    pub sky_color: Option<String>,
+    pub water_color: Option<String>,
+    pub water_fog_color: Option<String>,
+    pub grass_color: Option<String>,
+    pub foliage_color: Option<String>,
+    /// The sound looping while in the biome, e.g. `minecraft:ambient.crimson_forest.loop`.
+    pub ambient_sound: Option<String>,
+    /// The sound played now and then in the dark, e.g. `minecraft:ambient.cave`.
+    pub mood_sound: Option<String>,
+    /// The sound played at random, e.g. `minecraft:ambient.soul_sand_valley.additions`.
+    pub additions_sound: Option<String>,
+    /// The music played in the biome, e.g. `minecraft:music.overworld.deep_dark`.
+    pub music: Option<String>,
+}
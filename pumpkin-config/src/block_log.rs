@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration of the block change log used by `/blocklog`.
+///
+/// Every block broken or placed by a player is appended to a compact binary log, which can be
+/// queried and used to roll back or restore an area.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct BlockLogConfig {
+    /// Whether block changes are recorded.
+    pub enabled: bool,
+    /// Directory the log is stored in.
+    pub directory: String,
+    /// Changes older than this many days are dropped when the server starts. `0` keeps everything.
+    pub retention_days: u32,
+    /// Largest radius accepted by lookups, rollbacks and restores.
+    pub max_radius: u32,
+}
+
+impl Default for BlockLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "block_log".to_string(),
+            retention_days: 30,
+            max_radius: 64,
+        }
+    }
+}
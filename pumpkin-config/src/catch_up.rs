@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Catching up on ticks after the server stalled, e.g. during a long save.
+///
+/// Normally, a tick that overruns only delays the next one, so game time, crop growth and
+/// scheduled block ticks fall behind wall-clock time for good. With catch-up, the missed ticks
+/// run back to back until the server is on schedule again.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct CatchUpConfig {
+    /// Whether missed ticks are caught up on.
+    pub enabled: bool,
+    /// How many ticks the server may be behind. Ticks missed beyond that are skipped, so that a
+    /// very long stall doesn't keep the server busy for minutes.
+    pub max_ticks: u32,
+}
+
+impl Default for CatchUpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_ticks: 100,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+/// Rate limits of chat messages and commands per player.
+///
+/// Each player may send a burst of messages, and then some more every second. Going over the
+/// limit is a violation, met with the next of `consequences`. By default, players going over
+/// the limit are kicked, like in vanilla.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ChatLimitsConfig {
+    /// Whether chat messages and commands are rate limited.
+    pub enabled: bool,
+    /// The limit of chat messages.
+    pub chat: RateLimit,
+    /// The limit of commands.
+    pub commands: RateLimit,
+    /// What happens on the first, second, etc. violation. The last one repeats.
+    pub consequences: Vec<SpamConsequence>,
+    /// Seconds without violations after which the consequences start over.
+    pub forgive_after_seconds: u64,
+    /// Players with this permission aren't limited. By default, operators of level 2 and up have
+    /// it.
+    pub exempt_permission: String,
+}
+
+impl Default for ChatLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            chat: RateLimit::default(),
+            commands: RateLimit::default(),
+            consequences: vec![SpamConsequence::Kick],
+            forgive_after_seconds: 300,
+            exempt_permission: "pumpkin:chat.spam_exempt".to_string(),
+        }
+    }
+}
+
+impl ChatLimitsConfig {
+    /// The consequence of the violation after `previous` others.
+    #[must_use]
+    pub fn consequence(&self, previous: usize) -> SpamConsequence {
+        self.consequences[previous.min(self.consequences.len() - 1)]
+    }
+
+    pub fn validate(&self) {
+        assert!(
+            !self.consequences.is_empty(),
+            "Chat limits need at least one consequence"
+        );
+    }
+}
+
+/// How many messages a player may send.
+#[derive(Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct RateLimit {
+    /// How many messages may be sent in a row.
+    pub burst: u32,
+    /// How many more messages may be sent each second.
+    pub per_second: f64,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 10,
+            per_second: 1.0,
+        }
+    }
+}
+
+/// What happens to a player going over a rate limit.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpamConsequence {
+    /// Drops the message and tells the player to slow down.
+    #[serde(rename = "warn")]
+    Warn,
+    /// Drops the message and keeps the player from chatting for this many seconds.
+    #[serde(rename = "mute")]
+    Mute(u64),
+    /// Kicks the player.
+    #[serde(rename = "kick")]
+    Kick,
+}
@@ -22,6 +22,14 @@ impl Default for ChunkConfig {
     }
 }
 
+impl ChunkConfig {
+    pub fn validate(&self) {
+        if let Self::Anvil(anvil) = self {
+            anvil.compression.validate();
+        }
+    }
+}
+
 /// Configuration for Anvil chunk storage.
 #[derive(Deserialize, Serialize, Default, Clone)]
 #[serde(default)]
@@ -37,10 +45,23 @@ pub struct AnvilChunkConfig {
 pub struct ChunkCompression {
     /// Compression algorithm to use.
     pub algorithm: Compression,
-    /// Compression level (algorithm-specific).
+    /// Compression level (algorithm-specific): 0 to 9 for `GZip` and `ZLib`, and the block size
+    /// as a power of two above 1 KiB for LZ4. zstd has no levels and must keep the default.
     pub level: u32,
 }
 
+impl ChunkCompression {
+    pub fn validate(&self) {
+        let default = Self::default().level;
+        // ruzstd only compresses with its fastest level
+        assert!(
+            !matches!(self.algorithm, Compression::Zstd) || self.level == default,
+            "zstd chunk compression has no levels, the level has to stay {default} instead of {}",
+            self.level
+        );
+    }
+}
+
 impl Default for ChunkCompression {
     fn default() -> Self {
         Self {
@@ -58,8 +79,9 @@ pub enum Compression {
     ZLib,
     /// LZ4 Compression (since 24w04a).
     LZ4,
-    /// Custom compression algorithm (since 24w05a).
-    Custom,
+    /// Zstandard Compression, stored as a custom compression algorithm (since 24w05a) that
+    /// other tools may not read.
+    Zstd,
 }
 
 /// Configuration for Linear chunk storage.
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// The workers that load and generate chunks, apart from the main tick.
+///
+/// Chunks are generated closest to players first, and the destination of a teleport before
+/// anything else.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ChunkGenerationConfig {
+    /// Generation threads per dimension. 0 uses half of the cores, keeping two for the rest of
+    /// the server.
+    pub threads: usize,
+    /// Tasks per dimension reading chunks from disk.
+    pub io_threads: usize,
+    /// The most chunks sent to a player per tick, however many the client asks for.
+    pub max_chunks_per_tick: usize,
+}
+
+impl Default for ChunkGenerationConfig {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            io_threads: 2,
+            max_chunks_per_tick: 64,
+        }
+    }
+}
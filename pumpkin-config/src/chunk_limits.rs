@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Per-chunk caps protecting the server against entity and block entity build-up,
+/// e.g. mob farms or lag machines.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ChunkLimitsConfig {
+    /// Whether the caps are enforced.
+    pub enabled: bool,
+    /// Maximum number of entities in a chunk, excluding players and item frames. Items,
+    /// experience orbs and projectiles are never capped. `0` disables the cap.
+    pub max_entities: u32,
+    /// Maximum number of item frames (including glow item frames) in a chunk. `0` disables the cap.
+    pub max_item_frames: u32,
+    /// Maximum number of block entities in a chunk. `0` disables the cap.
+    pub max_block_entities: u32,
+    /// What happens when a cap is reached.
+    pub strategy: ChunkLimitStrategy,
+    /// Whether operators are notified in chat when a cap is reached.
+    pub notify_admins: bool,
+    /// Minimum time in seconds between two notifications about the same chunk.
+    pub notify_cooldown_seconds: u64,
+}
+
+impl Default for ChunkLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entities: 256,
+            max_item_frames: 64,
+            max_block_entities: 512,
+            strategy: ChunkLimitStrategy::Deny,
+            notify_admins: true,
+            notify_cooldown_seconds: 60,
+        }
+    }
+}
+
+/// Enforcement of a reached chunk cap.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkLimitStrategy {
+    /// Prevents the entity from spawning or the block from being placed.
+    #[serde(rename = "deny")]
+    Deny,
+    /// Removes the oldest entity of the same kind to make room. Block entities are denied instead,
+    /// as removing one would destroy its contents.
+    #[serde(rename = "cull_oldest")]
+    CullOldest,
+    /// Allows the spawn and only reports it.
+    #[serde(rename = "notify")]
+    Notify,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Putting the server into a deep sleep while nobody is online.
+///
+/// Once the server has been empty for a while, every chunk without a forced ticket is unloaded,
+/// entities stop ticking and all data is flushed to disk. The first player to join wakes it up
+/// again.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct DeepSleepConfig {
+    /// Whether the server falls into a deep sleep while empty.
+    pub enabled: bool,
+    /// How many minutes the server has to be empty before it falls asleep.
+    pub delay_minutes: u64,
+}
+
+impl Default for DeepSleepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_minutes: 5,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::net::SocketAddr;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::LoadConfiguration;
+
+/// Configuration of the connectors to services outside the server, loaded from
+/// `integrations.toml`.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct IntegrationsConfiguration {
+    /// Publish/subscribe messaging between the servers of a network.
+    pub redis: RedisConfig,
+    /// Relaying the chat to Discord and back.
+    pub chat_bridge: ChatBridgeConfig,
+}
+
+impl LoadConfiguration for IntegrationsConfiguration {
+    fn get_path() -> &'static Path {
+        Path::new("integrations.toml")
+    }
+
+    fn validate(&self) {}
+}
+
+/// Configuration of the Redis connector.
+///
+/// The servers of a network connected to the same Redis share broadcast messages and their
+/// player counts, and plugins can publish and subscribe to their own channels.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct RedisConfig {
+    /// Whether the server connects to Redis.
+    pub enabled: bool,
+    /// Address of the Redis server, as `host:port`.
+    pub address: String,
+    /// Username for `AUTH`, when Redis uses access control lists.
+    pub username: Option<String>,
+    /// Password for `AUTH`. Leave unset if Redis has none.
+    pub password: Option<String>,
+    /// The database selected after connecting.
+    pub database: u32,
+    /// Name of this server in the network. Must be unique across the network.
+    pub server_name: String,
+    /// Prefix of the channels used by the server itself, so several networks can share Redis.
+    pub channel_prefix: String,
+    /// Whether the server list shows the players of the whole network instead of this server.
+    pub global_player_count: bool,
+    /// Time interval in seconds between two announcements of the player count of this server.
+    pub player_count_interval_seconds: u64,
+    /// Time in seconds before the first reconnect attempt after the connection was lost. It
+    /// doubles with every failed attempt.
+    pub reconnect_delay_seconds: u64,
+    /// Longest time in seconds between two reconnect attempts.
+    pub max_reconnect_delay_seconds: u64,
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:6379".to_string(),
+            username: None,
+            password: None,
+            database: 0,
+            server_name: "Pumpkin".to_string(),
+            channel_prefix: "pumpkin:".to_string(),
+            global_player_count: false,
+            player_count_interval_seconds: 5,
+            reconnect_delay_seconds: 1,
+            max_reconnect_delay_seconds: 30,
+        }
+    }
+}
+
+/// Configuration of the chat bridge.
+///
+/// The bridge posts the chat, joins, leaves and deaths of the server to a Discord webhook.
+/// Messages written in a Discord channel, read with a bot, or posted to the HTTP endpoint by any
+/// other service are shown in the game.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ChatBridgeConfig {
+    /// Whether the bridge runs. Turning it off stops all relaying in both directions.
+    pub enabled: bool,
+    /// URL of the Discord webhook the server's messages are posted to. Leave empty to post
+    /// nothing.
+    pub webhook_url: String,
+    /// Name the webhook posts as. Leave unset for the name of the webhook.
+    pub webhook_username: Option<String>,
+    /// Whether chat messages are posted.
+    pub relay_chat: bool,
+    /// Whether players joining and leaving are posted.
+    pub relay_joins: bool,
+    /// Whether death messages are posted.
+    pub relay_deaths: bool,
+    /// Template of posted chat messages. Supports `{player}` and `{message}`.
+    pub chat_format: String,
+    /// Template of posted joins, leaves and deaths. Supports `{message}`.
+    pub event_format: String,
+    /// Token of the Discord bot reading `channel_id`. Leave unset to not read from Discord.
+    ///
+    /// The bot needs the Message Content intent and access to the channel.
+    pub bot_token: Option<String>,
+    /// ID of the Discord channel whose messages are shown in the game.
+    pub channel_id: String,
+    /// Time interval in seconds between two reads of the Discord channel.
+    pub poll_interval_seconds: u64,
+    /// Address of the HTTP endpoint accepting messages, as `POST /message` with a JSON body of
+    /// `author` and `message`. Leave unset for no endpoint.
+    pub http_address: Option<SocketAddr>,
+    /// Token the HTTP endpoint requires as `Authorization: Bearer <token>`. The endpoint is not
+    /// started without one.
+    pub http_token: String,
+    /// Template of the messages shown in the game. Supports `{author}` and `{message}`, and `&`
+    /// color codes.
+    pub inbound_format: String,
+}
+
+impl Default for ChatBridgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhook_url: String::new(),
+            webhook_username: None,
+            relay_chat: true,
+            relay_joins: true,
+            relay_deaths: true,
+            chat_format: "**{player}**: {message}".to_string(),
+            event_format: "*{message}*".to_string(),
+            bot_token: None,
+            channel_id: String::new(),
+            poll_interval_seconds: 2,
+            http_address: None,
+            http_token: String::new(),
+            inbound_format: "&9[Discord] &r<{author}> {message}".to_string(),
+        }
+    }
+}
@@ -1,25 +1,58 @@
+use ai::AiConfig;
+use alerting::AlertingConfig;
+use backup::BackupConfig;
+use block_log::BlockLogConfig;
+use catch_up::CatchUpConfig;
+use chat_limits::ChatLimitsConfig;
+use chunk_limits::ChunkLimitsConfig;
+use deep_sleep::DeepSleepConfig;
 use fun::FunConfig;
 use logging::LoggingConfig;
+use machines::MachinesConfig;
+use movement::MovementConfig;
 use pumpkin_util::world_seed::Seed;
 use pumpkin_util::{Difficulty, GameMode, PermissionLvl, random};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tps_mitigation::TpsMitigationConfig;
 
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::{fs, num::NonZeroU8, path::Path};
+pub mod activation_range;
+pub mod ai;
+pub mod alerting;
+pub mod anti_xray;
+pub mod backup;
+pub mod biome_effects;
+pub mod block_log;
+pub mod catch_up;
+pub mod chat_limits;
+pub mod chunk_generation;
+pub mod chunk_limits;
+pub mod deep_sleep;
 pub mod fun;
+pub mod integrations;
 pub mod logging;
+pub mod machines;
+pub mod movement;
 pub mod networking;
 
 pub mod resource_pack;
+pub mod restart;
+pub mod seed_privacy;
+pub mod spawning;
+pub mod tps_mitigation;
+pub mod watchdog;
+pub mod world_template;
 
 pub use chat::ChatConfig;
 pub use commands::CommandsConfig;
+pub use integrations::IntegrationsConfiguration;
 pub use networking::auth::AuthenticationConfig;
 pub use networking::compression::CompressionConfig;
 pub use networking::lan_broadcast::LANBroadcastConfig;
 pub use networking::rcon::RCONConfig;
-pub use pvp::PVPConfig;
+pub use pvp::{CombatProfile, PVPConfig};
 pub use server_links::ServerLinksConfig;
 
 mod commands;
@@ -36,6 +69,8 @@ pub mod world;
 use networking::NetworkingConfig;
 use player_data::PlayerDataConfig;
 use resource_pack::ResourcePackConfig;
+use restart::RestartConfig;
+use watchdog::WatchdogConfig;
 use world::LevelConfig;
 
 /// Advanced configuration for optional and feature-specific server settings.
@@ -67,6 +102,32 @@ pub struct AdvancedConfiguration {
     pub player_data: PlayerDataConfig,
     /// Optional fun and experimental features.
     pub fun: FunConfig,
+    /// Webhook alerts for anomalies such as lag spikes or failed saves.
+    pub alerting: AlertingConfig,
+    /// Periodic world backups and their retention.
+    pub backup: BackupConfig,
+    /// Countdown, kick message and exit behaviour of `/restart`.
+    pub restart: RestartConfig,
+    /// Recording of player block changes for lookups and rollbacks.
+    pub block_log: BlockLogConfig,
+    /// Per-chunk caps for entities, item frames and block entities.
+    pub chunk_limits: ChunkLimitsConfig,
+    /// The AI providers of mobs, globally, per world and per mob category.
+    pub ai: AiConfig,
+    /// Checks of player moves against speed and fly hacks.
+    pub movement: MovementConfig,
+    /// Rate limits of chat messages and commands, against spam.
+    pub chat_limits: ChatLimitsConfig,
+    /// Detection of hung ticks.
+    pub watchdog: WatchdogConfig,
+    /// Running missed ticks after the server stalled.
+    pub catch_up: CatchUpConfig,
+    /// Unloading chunks and pausing entities while nobody is online.
+    pub deep_sleep: DeepSleepConfig,
+    /// Blocks that process items by configured recipes.
+    pub machines: MachinesConfig,
+    /// Lowering the load while the TPS is low, like reducing the view distance.
+    pub tps_mitigation: TpsMitigationConfig,
 }
 
 /// Basic configuration for core server settings.
@@ -330,6 +391,9 @@ impl LoadConfiguration for AdvancedConfiguration {
 
     fn validate(&self) {
         self.resource_pack.validate();
+        self.pvp.validate();
+        self.chat_limits.validate();
+        self.world.chunk.validate();
     }
 }
 
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// Custom machines: vanilla blocks that process items in a 3x3 container, like a furnace with
+/// several inputs.
+///
+/// Every placed block of a machine's type becomes that machine. Its first `input_slots` slots
+/// hold the inputs, the eighth slot the fuel and the ninth slot the output.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct MachinesConfig {
+    pub machines: Vec<MachineConfig>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct MachineConfig {
+    /// A unique name, saved with every placed machine.
+    pub name: String,
+    /// The block that acts as this machine, like `minecraft:lodestone`.
+    pub block: String,
+    /// The title of the container.
+    pub title: String,
+    /// How many slots hold inputs, from 1 to 7.
+    pub input_slots: u8,
+    /// How long one recipe takes, in ticks.
+    pub processing_time: u32,
+    /// Items that power the machine. Without any, the machine needs no fuel.
+    pub fuel: Vec<MachineFuel>,
+    pub recipes: Vec<MachineRecipe>,
+}
+
+impl Default for MachineConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            block: String::new(),
+            title: String::new(),
+            input_slots: 2,
+            processing_time: 200,
+            fuel: Vec::new(),
+            recipes: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MachineFuel {
+    pub item: String,
+    /// How long one item burns, in ticks.
+    pub burn_time: u32,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MachineRecipe {
+    /// Consumed from any of the input slots.
+    pub inputs: Vec<MachineItem>,
+    pub output: MachineItem,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MachineItem {
+    /// The item, like `minecraft:iron_ingot`.
+    pub item: String,
+    #[serde(default = "default_count")]
+    pub count: u8,
+}
+
+const fn default_count() -> u8 {
+    1
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// Server-side checks of the moves players report, catching speed and fly hacks.
+///
+/// Every tick, players may move a bit further, depending on their effects and on whether they fly
+/// or glide. Moves beyond that, or hovering in the air for too long, are violations.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct MovementConfig {
+    /// Whether moves are checked.
+    pub enabled: bool,
+    /// What happens to players whose moves are violations.
+    pub enforcement: MovementEnforcement,
+    /// How far players may move horizontally per tick on foot, in blocks. Each level of Speed
+    /// adds 20%.
+    pub max_horizontal_speed: f64,
+    /// How far players may move up per tick on foot, in blocks. Each level of Jump Boost or
+    /// Levitation adds 0.1.
+    pub max_vertical_speed: f64,
+    /// How far players may move per tick while flying, in blocks, at the default flying speed.
+    pub max_flying_speed: f64,
+    /// How far players may move per tick while gliding with an elytra, in blocks.
+    pub max_elytra_speed: f64,
+    /// How many ticks of unused movement players may catch up on, e.g. after lagging.
+    pub buffered_ticks: u32,
+    /// How many moves in a row players may go up or stay level in the air, without anything
+    /// holding them up. `0` disables the check.
+    pub max_hover_moves: u32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            enforcement: MovementEnforcement::Rubberband,
+            max_horizontal_speed: 1.0,
+            max_vertical_speed: 0.8,
+            max_flying_speed: 1.5,
+            max_elytra_speed: 4.0,
+            buffered_ticks: 20,
+            max_hover_moves: 40,
+        }
+    }
+}
+
+/// What happens to players whose moves are violations.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MovementEnforcement {
+    /// Teleports the player back to where they were before the move.
+    #[serde(rename = "rubberband")]
+    Rubberband,
+    /// Kicks the player.
+    #[serde(rename = "kick")]
+    Kick,
+    /// Allows the move and only logs it.
+    #[serde(rename = "log_only")]
+    LogOnly,
+}
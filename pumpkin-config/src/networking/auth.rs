@@ -15,6 +15,12 @@ pub struct AuthenticationConfig {
     pub connect_timeout: u32,
     /// Read timeout in milliseconds.
     pub read_timeout: u32,
+    /// Failed authentication requests in a row after which logins fail right away, instead of
+    /// waiting for the timeouts. `0` disables this.
+    pub circuit_breaker_failures: u32,
+    /// How long logins fail right away before the authentication servers are tried again, in
+    /// milliseconds.
+    pub circuit_breaker_cooldown: u32,
     /// Whether to prevent connections via proxy.
     pub prevent_proxy_connections: bool,
     /// Optional auth URL used when preventing proxy connections.
@@ -39,6 +45,8 @@ impl Default for AuthenticationConfig {
             services_url: None,
             connect_timeout: 5000,
             read_timeout: 5000,
+            circuit_breaker_failures: 5,
+            circuit_breaker_cooldown: 30000,
         }
     }
 }
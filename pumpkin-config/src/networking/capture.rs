@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Packet capture of Java connections, for debugging protocol issues.
+///
+/// Captured packets are stored decrypted and uncompressed, so they contain everything the
+/// client sent, including chat and commands. Only enable this while debugging.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct PacketCaptureConfig {
+    pub enabled: bool,
+    /// Folder the captures are written to, one file per connection.
+    pub directory: PathBuf,
+    /// Names of the players whose connections are captured, starting at their login.
+    /// If empty, every connection is captured from its handshake.
+    pub players: Vec<String>,
+}
+
+impl Default for PacketCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("captures"),
+            players: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use serde::{Deserialize, Serialize};
+
+/// Support for Bedrock players joining through Geyser with Floodgate-style accounts.
+///
+/// Geyser logs such players in with a UUID whose upper half is zero and the Bedrock XUID as
+/// lower half, and a name starting with `username_prefix`. They have no Java account, so they
+/// are let in without Mojang authentication, but only from the Geyser instances listed in
+/// `trusted_addresses`.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct FloodgateConfig {
+    /// Whether Floodgate players are recognized.
+    pub enabled: bool,
+    /// Prefix Geyser puts in front of Bedrock player names.
+    pub username_prefix: String,
+    /// Addresses of the Geyser instances allowed to log in Floodgate players directly.
+    /// Players forwarded by a proxy are recognized by their UUID alone.
+    pub trusted_addresses: Vec<IpAddr>,
+}
+
+impl Default for FloodgateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            username_prefix: ".".to_string(),
+            trusted_addresses: vec![Ipv4Addr::LOCALHOST.into()],
+        }
+    }
+}
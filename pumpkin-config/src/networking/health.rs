@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+
+/// Configuration for the HTTP health endpoint and readiness signaling.
+///
+/// The endpoint listens separately from the game port so container orchestrators and
+/// supervisors can probe the server without speaking the Minecraft protocol.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Whether the health endpoint is enabled.
+    pub enabled: bool,
+    /// The address and port the health endpoint binds to.
+    pub address: SocketAddr,
+    /// TPS below which `/health` reports the server as degraded. `0` disables the check.
+    pub min_tps: f32,
+    /// Whether to send `READY=1`/`STOPPING=1` to systemd when `NOTIFY_SOCKET` is set.
+    pub systemd_notify: bool,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 25580),
+            min_tps: 15.0,
+            systemd_notify: true,
+        }
+    }
+}
@@ -2,7 +2,8 @@ use serde::{Deserialize, Serialize};
 
 /// Configuration for LAN broadcast of the server.
 ///
-/// Controls whether the server is discoverable on the local network, and optional MOTD and port settings.
+/// Controls whether the server is discoverable on the local network, either through the UDP
+/// broadcast clients listen for or through mDNS, and optional MOTD and port settings.
 #[derive(Deserialize, Serialize, Default)]
 #[serde(default)]
 pub struct LANBroadcastConfig {
@@ -14,4 +15,9 @@ pub struct LANBroadcastConfig {
     /// Optional port for LAN broadcast.
     /// Useful for predictable ports in environments like Docker containers.
     pub port: Option<u16>,
+    /// Whether to advertise the server as a `_minecraft._tcp` service over mDNS.
+    /// Works independently of `enabled`.
+    pub mdns: bool,
+    /// Optional mDNS service instance name. Defaults to the LAN MOTD.
+    pub mdns_name: Option<String>,
 }
@@ -0,0 +1,31 @@
+use std::net::{Ipv6Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::networking::proxy::ProxyForwarding;
+
+/// An additional address accepting Java Edition connections, besides `java_edition_address`.
+///
+/// Useful to listen on IPv4 and IPv6 at once, or to accept proxy traffic on an internal port
+/// while players connect directly on the public one.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ListenerConfig {
+    /// The address and port to bind to.
+    pub address: SocketAddr,
+    /// The player info forwarding expected from clients connecting to this listener.
+    pub proxy: ProxyForwarding,
+    /// Whether connections must start with a PROXY protocol header, sent by one of the
+    /// `proxy_protocol.trusted_proxies`.
+    pub proxy_protocol: bool,
+}
+
+impl Default for ListenerConfig {
+    fn default() -> Self {
+        Self {
+            address: SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 25565),
+            proxy: ProxyForwarding::Inherit,
+            proxy_protocol: false,
+        }
+    }
+}
@@ -1,22 +1,37 @@
 use auth::AuthenticationConfig;
+use capture::PacketCaptureConfig;
+use floodgate::FloodgateConfig;
+use health::HealthConfig;
+use listener::ListenerConfig;
 use proxy::ProxyConfig;
+use proxy_protocol::ProxyProtocolConfig;
 use query::QueryConfig;
 use rcon::RCONConfig;
 use serde::{Deserialize, Serialize};
+use server_list::ServerListConfig;
+use transfer::TransferConfig;
 
 use crate::{CompressionConfig, LANBroadcastConfig};
 
 pub mod auth;
+pub mod capture;
 pub mod compression;
+pub mod floodgate;
+pub mod health;
 pub mod lan_broadcast;
+pub mod listener;
 pub mod proxy;
+pub mod proxy_protocol;
 pub mod query;
 pub mod rcon;
+pub mod server_list;
+pub mod transfer;
 
 /// Configuration for server networking features.
 ///
 /// Covers authentication, query, RCON, proxying, packet compression,
-/// and LAN broadcast behaviour.
+/// LAN broadcast, health check, server list, transfer, Floodgate, packet capture and
+/// additional listener behaviour.
 #[derive(Deserialize, Serialize, Default)]
 pub struct NetworkingConfig {
     /// Authentication settings for client connections.
@@ -27,8 +42,22 @@ pub struct NetworkingConfig {
     pub rcon: RCONConfig,
     /// Proxy-related networking settings.
     pub proxy: ProxyConfig,
+    /// `HAProxy` PROXY protocol settings for TCP load balancers.
+    pub proxy_protocol: ProxyProtocolConfig,
     /// Packet compression settings.
     pub packet_compression: CompressionConfig,
     /// LAN broadcast settings.
     pub lan_broadcast: LANBroadcastConfig,
+    /// HTTP health endpoint and systemd readiness notification settings.
+    pub health: HealthConfig,
+    /// Player sample, MOTD rotation and version text of the server list response.
+    pub server_list: ServerListConfig,
+    /// Transfers from other servers and cookie signing.
+    pub transfer: TransferConfig,
+    /// Recognition of Bedrock players joining through Geyser.
+    pub floodgate: FloodgateConfig,
+    /// Recording of Java connections to replay files.
+    pub capture: PacketCaptureConfig,
+    /// Additional Java Edition listen addresses, each with its own proxy forwarding.
+    pub listeners: Vec<ListenerConfig>,
 }
@@ -31,3 +31,48 @@ pub struct VelocityConfig {
     /// Shared secret for authenticating connections from the Velocity proxy.
     pub secret: String,
 }
+
+impl ProxyConfig {
+    /// The forwarding expected on the primary listener.
+    #[must_use]
+    pub const fn forwarding(&self) -> ProxyForwarding {
+        if !self.enabled {
+            ProxyForwarding::None
+        } else if self.velocity.enabled {
+            ProxyForwarding::Velocity
+        } else if self.bungeecord.enabled {
+            ProxyForwarding::BungeeCord
+        } else {
+            ProxyForwarding::None
+        }
+    }
+}
+
+/// The player info forwarding a listener expects from connecting clients.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ProxyForwarding {
+    /// Uses the forwarding of the `proxy` section, like the primary listener.
+    #[default]
+    #[serde(rename = "inherit")]
+    Inherit,
+    /// Clients connect directly, forwarded player info is not accepted.
+    #[serde(rename = "none")]
+    None,
+    /// Velocity modern forwarding, verified with the secret of the `proxy.velocity` section.
+    #[serde(rename = "velocity")]
+    Velocity,
+    /// `BungeeCord` legacy forwarding.
+    #[serde(rename = "bungeecord")]
+    BungeeCord,
+}
+
+impl ProxyForwarding {
+    /// Replaces `Inherit` with the forwarding of `proxy`.
+    #[must_use]
+    pub const fn resolve(self, proxy: &ProxyConfig) -> Self {
+        match self {
+            Self::Inherit => proxy.forwarding(),
+            forwarding => forwarding,
+        }
+    }
+}
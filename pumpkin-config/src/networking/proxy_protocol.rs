@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for `HAProxy` PROXY protocol headers (v1 and v2) on the game port.
+///
+/// TCP load balancers put the real client address in front of the connection, so that bans,
+/// logs and IP limits see the player instead of the load balancer.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ProxyProtocolConfig {
+    /// Whether connections to `java_edition_address` must start with a PROXY header.
+    pub enabled: bool,
+    /// Addresses or CIDR ranges (e.g. `10.0.0.0/8`) allowed to send PROXY headers.
+    /// Connections from anywhere else are refused while the header is required.
+    pub trusted_proxies: Vec<String>,
+    /// How long to wait for the header, in milliseconds.
+    pub header_timeout_ms: u64,
+}
+
+impl Default for ProxyProtocolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trusted_proxies: vec!["127.0.0.1".to_string(), "::1".to_string()],
+            header_timeout_ms: 5000,
+        }
+    }
+}
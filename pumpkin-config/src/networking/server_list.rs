@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// Customization of the response shown in the multiplayer server list.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ServerListConfig {
+    /// Lines shown when hovering the player count. Empty shows nothing, like vanilla with no players.
+    pub player_sample: Vec<String>,
+    /// MOTDs cycled through in order. Empty always uses the `motd` of the basic configuration.
+    pub motds: Vec<String>,
+    /// How long each MOTD of `motds` is shown, in seconds.
+    pub motd_rotation_seconds: u64,
+    /// Version text shown to clients whose protocol is not supported, instead of the version name.
+    /// `{version}` is replaced with the server version. Empty keeps the version name.
+    pub incompatible_version_text: String,
+    /// MOTDs that replace the rotation during certain hours of the day.
+    pub motd_schedule: Vec<ScheduledMotd>,
+}
+
+impl Default for ServerListConfig {
+    fn default() -> Self {
+        Self {
+            player_sample: Vec::new(),
+            motds: Vec::new(),
+            motd_rotation_seconds: 30,
+            incompatible_version_text: String::new(),
+            motd_schedule: Vec::new(),
+        }
+    }
+}
+
+/// A MOTD shown between two hours of the day (UTC).
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ScheduledMotd {
+    /// First hour (0-23) the MOTD is shown.
+    pub from_hour: u8,
+    /// Hour (0-23) at which the MOTD stops being shown. Ranges may wrap around midnight.
+    pub to_hour: u8,
+    pub motd: String,
+}
+
+impl ScheduledMotd {
+    #[must_use]
+    pub const fn is_active(&self, hour: u8) -> bool {
+        if self.from_hour <= self.to_hour {
+            hour >= self.from_hour && hour < self.to_hour
+        } else {
+            hour >= self.from_hour || hour < self.to_hour
+        }
+    }
+}
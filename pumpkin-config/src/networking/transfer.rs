@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for players transferred between servers with the transfer packet, and the
+/// cookies they carry along.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct TransferConfig {
+    /// Whether players transferred from another server may join. Players can always be
+    /// transferred away from this server. On by default, as transferred players could always
+    /// join before this option existed.
+    pub accept_transfers: bool,
+    /// Secret shared by the servers of a network to sign cookies, so that clients can't forge
+    /// them. Signed cookies are unavailable while it is empty.
+    pub cookie_secret: String,
+    /// How long to wait for a client to answer a cookie request, in milliseconds.
+    pub cookie_timeout_ms: u64,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            accept_transfers: true,
+            cookie_secret: String::new(),
+            cookie_timeout_ms: 5000,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Configuration for player-versus-player mechanics.
@@ -16,6 +18,11 @@ pub struct PVPConfig {
     pub knockback: bool,
     /// Whether players swing their hand when attacking.
     pub swing: bool,
+    /// The combat tuning of player attacks, by name: one of `profiles`, or the preset `vanilla` or
+    /// `legacy-1.8-feel`.
+    pub profile: String,
+    /// Custom combat tunings, keyed by name. They take precedence over presets of the same name.
+    pub profiles: HashMap<String, CombatProfile>,
 }
 
 impl Default for PVPConfig {
@@ -26,6 +33,87 @@ impl Default for PVPConfig {
             protect_creative: true,
             knockback: true,
             swing: true,
+            profile: "vanilla".to_string(),
+            profiles: HashMap::new(),
+        }
+    }
+}
+
+impl PVPConfig {
+    /// The combat tuning named by `profile`.
+    #[must_use]
+    pub fn combat_profile(&self) -> CombatProfile {
+        self.profiles
+            .get(&self.profile)
+            .copied()
+            .or_else(|| CombatProfile::preset(&self.profile))
+            .unwrap_or_default()
+    }
+
+    pub fn validate(&self) {
+        assert!(
+            self.profiles.contains_key(&self.profile)
+                || CombatProfile::preset(&self.profile).is_some(),
+            "Unknown combat profile {}",
+            self.profile
+        );
+    }
+}
+
+/// Tuning of player attacks. Missing values are taken from the `vanilla` preset.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct CombatProfile {
+    /// Horizontal knockback of an attack, in blocks per tick.
+    pub knockback: f64,
+    /// Horizontal knockback added when the attacker sprints.
+    pub sprint_knockback: f64,
+    /// Upward knockback of victims on the ground, in blocks per tick.
+    pub knockback_vertical: f64,
+    /// The share of their velocity victims keep when knocked back.
+    pub knockback_friction: f64,
+    /// The share of their horizontal velocity attackers keep after knocking back.
+    pub attacker_slowdown: f64,
+    /// Whether attacks deal less damage until the weapon recharged.
+    pub attack_cooldown: bool,
+    /// The share of damage dealt by attacks right after another one, with `attack_cooldown`.
+    pub cooldown_min_damage: f64,
+    /// Damage multiplier of critical hits.
+    pub critical_multiplier: f64,
+    /// Whether charged sword attacks sweep.
+    pub sweeping: bool,
+}
+
+impl Default for CombatProfile {
+    fn default() -> Self {
+        Self {
+            knockback: 0.5,
+            sprint_knockback: 0.5,
+            knockback_vertical: 0.4,
+            knockback_friction: 0.5,
+            attacker_slowdown: 0.6,
+            attack_cooldown: true,
+            cooldown_min_damage: 0.2,
+            critical_multiplier: 1.5,
+            sweeping: true,
+        }
+    }
+}
+
+impl CombatProfile {
+    /// The built-in profile named `name`.
+    #[must_use]
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "vanilla" => Some(Self::default()),
+            // Spammable attacks without sweeping, and a little less knockback
+            "legacy-1.8-feel" => Some(Self {
+                knockback: 0.4,
+                attack_cooldown: false,
+                sweeping: false,
+                ..Self::default()
+            }),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the `/restart` command.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct RestartConfig {
+    /// Countdown in seconds used when `/restart` is run without an argument.
+    pub default_countdown_seconds: u32,
+    /// Message shown to players when they are disconnected for the restart.
+    pub kick_message: String,
+    /// What the process does once the server has stopped.
+    pub mode: RestartMode,
+    /// Exit code used in `exit` mode, so process supervisors can tell a restart from a stop.
+    pub exit_code: i32,
+}
+
+impl Default for RestartConfig {
+    fn default() -> Self {
+        Self {
+            default_countdown_seconds: 30,
+            kick_message: "Server is restarting, please reconnect in a moment.".to_string(),
+            mode: RestartMode::Exit,
+            exit_code: 75,
+        }
+    }
+}
+
+/// How the server is brought back up after a restart.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum RestartMode {
+    /// Exit with `exit_code` and let a supervisor (systemd, Docker, a script) start it again.
+    #[serde(rename = "exit")]
+    Exit,
+    /// Replace the process with a fresh instance of the same binary.
+    #[serde(rename = "reexec")]
+    Reexec,
+}
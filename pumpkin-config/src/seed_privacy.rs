@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Keeping the world seed from players, against seed cracking.
+///
+/// Besides `/seed`, clients learn a hash of the seed when joining a world, which they use to
+/// blend biome colors. It can't be reversed, but helps to confirm a guessed seed.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct SeedPrivacyConfig {
+    /// Whether `/seed` only works for operators, even if others were given its permission.
+    pub hide_from_non_ops: bool,
+    /// What clients get as the hashed seed. Bedrock clients get the seed itself with `real`, and
+    /// `0` otherwise.
+    pub hashed_seed: HashedSeedMode,
+}
+
+/// What clients get as the hashed seed.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HashedSeedMode {
+    /// The hash of the world seed, like vanilla.
+    #[default]
+    #[serde(rename = "real")]
+    Real,
+    /// The hash of the world seed mixed with a secret that differs per player and per server
+    /// start. Biome colors blend slightly differently, but players can't compare their hashes.
+    #[serde(rename = "salted")]
+    Salted,
+    /// Always `0`.
+    #[serde(rename = "zero")]
+    Zero,
+}
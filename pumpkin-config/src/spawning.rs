@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The conditions under which mobs spawn naturally. The defaults match vanilla.
+///
+/// Settings of a world override the global ones.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct SpawningConfig {
+    /// Monsters spawn where the light level is at most a random level from 0 to this one. Night
+    /// and storms darken the sky light, so monsters spawn on the surface only then.
+    pub monster_max_light: u8,
+    /// Monsters don't spawn where the block light is above this, whatever the sky light. Unset,
+    /// it is 15 in the nether and 0 elsewhere, like in vanilla; 15 brings back the light level 7
+    /// spawning from before 1.18.
+    pub monster_block_light_limit: Option<u8>,
+    /// Whether mobs spawn below the surface, where the sky can't be seen.
+    pub cave_spawns: bool,
+    /// Whether mobs spawn on the surface, where the sky can be seen.
+    pub surface_spawns: bool,
+    /// The heights at which mobs spawn, keyed by mob, e.g. `minecraft:zombie`.
+    pub heights: HashMap<String, SpawnHeights>,
+    /// Overrides per world, keyed by dimension name, e.g. `the_nether`.
+    pub worlds: HashMap<String, SpawningWorldConfig>,
+}
+
+impl Default for SpawningConfig {
+    fn default() -> Self {
+        Self {
+            monster_max_light: 7,
+            monster_block_light_limit: None,
+            cave_spawns: true,
+            surface_spawns: true,
+            heights: HashMap::new(),
+            worlds: HashMap::new(),
+        }
+    }
+}
+
+/// The overrides of a single world. Its `heights` replace the global ones.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct SpawningWorldConfig {
+    pub monster_max_light: Option<u8>,
+    pub monster_block_light_limit: Option<u8>,
+    pub cave_spawns: Option<bool>,
+    pub surface_spawns: Option<bool>,
+    pub heights: Option<HashMap<String, SpawnHeights>>,
+}
+
+/// The lowest and highest heights at which a mob spawns, both inclusive.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct SpawnHeights {
+    pub min_y: Option<i32>,
+    pub max_y: Option<i32>,
+}
+
+impl SpawnHeights {
+    #[must_use]
+    pub fn contains(&self, y: i32) -> bool {
+        self.min_y.is_none_or(|min_y| y >= min_y) && self.max_y.is_none_or(|max_y| y <= max_y)
+    }
+}
+
+/// The settings of a single world, after applying its overrides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpawningWorldSettings {
+    pub monster_max_light: u8,
+    pub monster_block_light_limit: Option<u8>,
+    pub cave_spawns: bool,
+    pub surface_spawns: bool,
+    pub heights: HashMap<String, SpawnHeights>,
+}
+
+impl SpawningWorldSettings {
+    /// The heights at which `mob` spawns, if they are limited. The `minecraft:` prefix of mob
+    /// names is optional.
+    #[must_use]
+    pub fn heights(&self, mob: &str) -> Option<SpawnHeights> {
+        let full = format!(
+            "minecraft:{}",
+            mob.strip_prefix("minecraft:").unwrap_or(mob)
+        );
+        self.heights
+            .get(&full)
+            .or_else(|| self.heights.get(&full["minecraft:".len()..]))
+            .copied()
+    }
+}
+
+impl SpawningConfig {
+    /// The settings of `world`. The `minecraft:` prefix of world names is optional.
+    #[must_use]
+    pub fn for_world(&self, world: &str) -> SpawningWorldSettings {
+        let short = world.strip_prefix("minecraft:").unwrap_or(world);
+        let overrides = self.worlds.get(short).or_else(|| self.worlds.get(world));
+        SpawningWorldSettings {
+            monster_max_light: overrides
+                .and_then(|overrides| overrides.monster_max_light)
+                .unwrap_or(self.monster_max_light),
+            monster_block_light_limit: overrides
+                .and_then(|overrides| overrides.monster_block_light_limit)
+                .or(self.monster_block_light_limit),
+            cave_spawns: overrides
+                .and_then(|overrides| overrides.cave_spawns)
+                .unwrap_or(self.cave_spawns),
+            surface_spawns: overrides
+                .and_then(|overrides| overrides.surface_spawns)
+                .unwrap_or(self.surface_spawns),
+            heights: overrides
+                .and_then(|overrides| overrides.heights.clone())
+                .unwrap_or_else(|| self.heights.clone()),
+        }
+    }
+}
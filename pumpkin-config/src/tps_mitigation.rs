@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// Mitigations that kick in while the server can't keep up its TPS.
+///
+/// Each policy applies its action once the TPS stayed below its threshold for a while, and
+/// reverts it once the TPS recovered for a while. Policies with the same kind of action combine
+/// to the strictest of them.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct TpsMitigationConfig {
+    /// Whether the policies are applied.
+    pub enabled: bool,
+    pub policies: Vec<MitigationPolicy>,
+}
+
+impl Default for TpsMitigationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            policies: vec![
+                MitigationPolicy {
+                    name: "view-distance".to_string(),
+                    below_tps: 17.0,
+                    action: MitigationAction::ReduceViewDistance { view_distance: 6 },
+                    ..Default::default()
+                },
+                MitigationPolicy {
+                    name: "empty-worlds".to_string(),
+                    below_tps: 17.0,
+                    action: MitigationAction::PauseRandomTicksInEmptyWorlds,
+                    ..Default::default()
+                },
+                MitigationPolicy {
+                    name: "mob-farms".to_string(),
+                    below_tps: 14.0,
+                    action: MitigationAction::CapEntitiesPerChunk { max_entities: 48 },
+                    ..Default::default()
+                },
+                MitigationPolicy {
+                    name: "chunk-generation".to_string(),
+                    below_tps: 14.0,
+                    action: MitigationAction::DeferChunkGeneration,
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+}
+
+/// A mitigation and when it applies.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct MitigationPolicy {
+    /// Name of the policy in the log.
+    pub name: String,
+    /// TPS below which the policy triggers.
+    pub below_tps: f64,
+    /// How long in seconds the TPS has to stay below `below_tps` before the action applies.
+    pub trigger_seconds: u64,
+    /// TPS the server has to get back to before the action is reverted. Keeping it above
+    /// `below_tps` stops the policy from flapping.
+    pub recover_tps: f64,
+    /// How long in seconds the TPS has to stay at `recover_tps` before the action is reverted.
+    pub recover_seconds: u64,
+    pub action: MitigationAction,
+}
+
+impl Default for MitigationPolicy {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            below_tps: 15.0,
+            trigger_seconds: 30,
+            recover_tps: 19.0,
+            recover_seconds: 60,
+            action: MitigationAction::PauseRandomTicksInEmptyWorlds,
+        }
+    }
+}
+
+/// What a policy does while it applies.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(tag = "type")]
+pub enum MitigationAction {
+    /// Lowers the view distance of every player to at most `view_distance`.
+    #[serde(rename = "reduce_view_distance")]
+    ReduceViewDistance { view_distance: u8 },
+    /// Caps the entities in a chunk to `max_entities`, with the strategy of the chunk limits,
+    /// even if the chunk limits are disabled.
+    #[serde(rename = "cap_entities_per_chunk")]
+    CapEntitiesPerChunk { max_entities: u32 },
+    /// Stops random ticks, like crop growth, in worlds without players.
+    #[serde(rename = "pause_random_ticks_in_empty_worlds")]
+    PauseRandomTicksInEmptyWorlds,
+    /// Generates new chunks one at a time, leaving the cores to the tick. Loading chunks from
+    /// disk isn't affected.
+    #[serde(rename = "defer_chunk_generation")]
+    DeferChunkGeneration,
+}
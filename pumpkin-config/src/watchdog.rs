@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Detection of ticks that hang, like vanilla's `max-tick-time`.
+///
+/// A separate thread watches the server tick. If one takes longer than `max_tick_time_seconds`,
+/// it logs the threads of the server and the latest time spent in each part of the tick, then
+/// acts as configured.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    /// Whether hung ticks are detected.
+    pub enabled: bool,
+    /// How long a single tick may take, in seconds.
+    pub max_tick_time_seconds: u64,
+    /// What happens when a tick hangs.
+    pub action: WatchdogAction,
+    /// Whether to try saving players and worlds before stopping or restarting.
+    pub save_before_stopping: bool,
+    /// How long saving may take, in seconds. It can hang too if the tick holds on to a world.
+    pub save_timeout_seconds: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_tick_time_seconds: 60,
+            action: WatchdogAction::Stop,
+            save_before_stopping: false,
+            save_timeout_seconds: 30,
+        }
+    }
+}
+
+/// What happens when a tick hangs.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WatchdogAction {
+    /// Only logs the hung tick, once.
+    #[serde(rename = "log_only")]
+    LogOnly,
+    /// Exits the process with code 1, like vanilla.
+    #[serde(rename = "stop")]
+    Stop,
+    /// Restarts the server the way `/restart` does, see the `restart` config.
+    #[serde(rename = "restart")]
+    Restart,
+}
@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+use crate::activation_range::ActivationRangeConfig;
+use crate::anti_xray::AntiXrayConfig;
+use crate::biome_effects::BiomeEffectsConfig;
 use crate::chunk::ChunkConfig;
+use crate::chunk_generation::ChunkGenerationConfig;
+use crate::seed_privacy::SeedPrivacyConfig;
+use crate::spawning::SpawningConfig;
+use crate::world_template::{InstanceConfig, WorldTemplateConfig};
 
 /// Configuration for world and level-specific settings.
 ///
@@ -9,5 +16,59 @@ use crate::chunk::ChunkConfig;
 pub struct LevelConfig {
     /// Configuration for chunk behaviour and management.
     pub chunk: ChunkConfig,
+    /// Threads and priorities of chunk generation.
+    #[serde(default)]
+    pub generation: ChunkGenerationConfig,
+    /// Experimental parallel ticking of entities and blocks, grouped by region.
+    #[serde(default)]
+    pub region_ticking: RegionTickingConfig,
+    /// Hiding of ores from clients that see through blocks.
+    #[serde(default)]
+    pub anti_xray: AntiXrayConfig,
+    /// Keeping the world seed from players.
+    #[serde(default)]
+    pub seed_privacy: SeedPrivacyConfig,
+    /// Ticking mobs far away from players less often.
+    #[serde(default)]
+    pub activation_range: ActivationRangeConfig,
+    /// Light levels and heights at which mobs spawn naturally.
+    #[serde(default)]
+    pub spawning: SpawningConfig,
+    /// Fog and sky colors, ambience and music of biomes.
+    #[serde(default)]
+    pub biome_effects: BiomeEffectsConfig,
+    /// Cloning the world from a prebuilt template world.
+    #[serde(default)]
+    pub template: WorldTemplateConfig,
+    /// Arena worlds created from template worlds while the server runs.
+    #[serde(default)]
+    pub instances: InstanceConfig,
     // TODO: More options
 }
+
+/// Folia-style ticking of entities and blocks on several worker tasks.
+///
+/// Loaded chunks are grouped into regions, and the entities and scheduled block ticks of regions
+/// that are not next to each other are ticked in parallel. Entities and ticks close to another
+/// region run one at a time once every region has finished its tick.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct RegionTickingConfig {
+    /// Whether entities are ticked per region. When disabled, every entity is ticked in turn.
+    pub enabled: bool,
+    /// Width of a region in chunks, rounded up to a power of two.
+    pub region_size: u8,
+    /// Whether scheduled block and fluid ticks and random ticks are also ticked per region. Ticks
+    /// keep their order within a region, but not between regions, so this is off by default.
+    pub blocks: bool,
+}
+
+impl Default for RegionTickingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            region_size: 8,
+            blocks: false,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Creating the world as a clone of a prebuilt template world instead of generating it.
+///
+/// Region files are shared with the template until the server changes them, so cloning is fast
+/// and takes little disk space. Meant for minigame servers that start every round from the same
+/// map.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct WorldTemplateConfig {
+    /// Whether a missing world is cloned from the template.
+    pub enabled: bool,
+    /// Folder of the template world, relative to the server folder.
+    pub path: String,
+    /// Whether the world is deleted and cloned again on every start, throwing away all changes
+    /// made to it.
+    pub reset_on_start: bool,
+}
+
+impl Default for WorldTemplateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "templates/world".to_string(),
+            reset_on_start: false,
+        }
+    }
+}
+
+/// Short-lived arena worlds created from template worlds at runtime, see the instance API of the
+/// server.
+///
+/// Instances read their chunks from the template and keep every change in memory, so they never
+/// write to disk and are thrown away when disposed.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct InstanceConfig {
+    /// Folder holding the template worlds, one folder per template, relative to the server
+    /// folder.
+    pub templates: String,
+    /// The most instances that exist at once.
+    pub max_instances: usize,
+}
+
+impl Default for InstanceConfig {
+    fn default() -> Self {
+        Self {
+            templates: "templates".to_string(),
+            max_instances: 64,
+        }
+    }
+}
@@ -154,6 +154,13 @@ impl ToTokens for PropertyStruct {
                         _ => panic!("Invalid value: {value}"),
                     }
                 }
+
+                fn try_from_value(value: &str) -> Option<Self> {
+                    match value {
+                        #(#literals => Some(Self::#variants)),*,
+                        _ => None,
+                    }
+                }
             }
         });
     }
@@ -260,6 +267,30 @@ impl ToTokens for BlockPropertyStruct {
             }
         });
 
+        let try_from_props_values = self.data.variant_mappings.iter().map(|entry| {
+            let key = &entry.original_name;
+            let field_name = Ident::new_raw(&entry.original_name, Span::call_site());
+            match &entry.property_type {
+                PropertyType::Bool => quote! {
+                    #key => {
+                        block_props.#field_name = match *value {
+                            "true" => true,
+                            "false" => false,
+                            _ => return None,
+                        }
+                    }
+                },
+                PropertyType::Enum { name } => {
+                    let enum_ident = Ident::new(name, Span::call_site());
+                    quote! {
+                        #key => {
+                            block_props.#field_name = #enum_ident::try_from_value(value)?
+                        }
+                    }
+                }
+            }
+        });
+
         tokens.extend(quote! {
             #[derive(Clone, Copy, Debug, Eq, PartialEq)]
             pub struct #name {
@@ -340,6 +371,17 @@ impl ToTokens for BlockPropertyStruct {
                     }
                     block_props
                 }
+
+                fn try_from_props(props: &[(&str, &str)], block: &Block) -> Option<Self> {
+                    let mut block_props = Self::default(block);
+                    for (key, value) in props {
+                        match *key {
+                            #(#try_from_props_values),*,
+                            _ => return None,
+                        }
+                    }
+                    Some(block_props)
+                }
             }
         });
     }
@@ -826,6 +868,7 @@ pub(crate) fn build() -> TokenStream {
 
     let mut block_properties_from_state_and_block_id_arms = Vec::new();
     let mut block_properties_from_props_and_name_arms = Vec::new();
+    let mut block_properties_try_from_props_arms = Vec::new();
 
     for property_group in property_collection_map.into_values() {
         let property_name = Ident::new(
@@ -847,6 +890,9 @@ pub(crate) fn build() -> TokenStream {
             block_properties_from_props_and_name_arms.push(quote! {
                 #id_lit => Box::new(#property_name::from_props(props, &Block::#const_block_name)),
             });
+            block_properties_try_from_props_arms.push(quote! {
+                #id_lit => Box::new(#property_name::try_from_props(props, &Block::#const_block_name)?),
+            });
         }
 
         block_properties.push(BlockPropertyStruct {
@@ -938,6 +984,9 @@ pub(crate) fn build() -> TokenStream {
             fn default(block: &Block) -> Self where Self: Sized;
             fn to_props(&self) -> Vec<(&'static str, &'static str)>;
             fn from_props(props: &[(&str, &str)], block: &Block) -> Self where Self: Sized;
+            /// Like `from_props`, but returns `None` for unknown properties or values instead of
+            /// panicking.
+            fn try_from_props(props: &[(&str, &str)], block: &Block) -> Option<Self> where Self: Sized;
         }
 
         pub trait EnumVariants {
@@ -946,6 +995,7 @@ pub(crate) fn build() -> TokenStream {
             fn from_index(index: u16) -> Self;
             fn to_value(&self) -> &'static str;
             fn from_value(value: &str) -> Self;
+            fn try_from_value(value: &str) -> Option<Self> where Self: Sized;
         }
 
         pub const COLLISION_SHAPES: &[CollisionShape] = &[
@@ -1090,6 +1140,15 @@ pub(crate) fn build() -> TokenStream {
                     _ => panic!("Invalid props")
                 }
             }
+
+            #[doc = r" Get the properties of the block, or `None` if the block has no properties or"]
+            #[doc = r" one of them is unknown or has an invalid value."]
+            pub fn try_from_properties(&self, props: &[(&str, &str)]) -> Option<Box<dyn BlockProperties>> {
+                Some(match self.id {
+                    #(#block_properties_try_from_props_arms)*
+                    _ => return None,
+                })
+            }
         }
 
         #(#properties)*
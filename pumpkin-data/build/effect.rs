@@ -68,6 +68,7 @@ pub(crate) fn build() -> TokenStream {
     let mut variants = TokenStream::new();
     let mut name_to_type = TokenStream::new();
     let mut minecraft_name_to_type = TokenStream::new();
+    let mut id_to_type = TokenStream::new();
 
     for (name, effect) in effects {
         let format_name = format_ident!("{}", name.to_shouty_snake_case());
@@ -93,6 +94,7 @@ pub(crate) fn build() -> TokenStream {
         name_to_type.extend(quote! { #name => Some(&Self::#format_name), });
 
         minecraft_name_to_type.extend(quote! { #minecraft_name => Some(&Self::#format_name), });
+        id_to_type.extend(quote! { #id => Some(&Self::#format_name), });
     }
 
     quote! {
@@ -149,7 +151,13 @@ pub(crate) fn build() -> TokenStream {
             }
             pub fn from_minecraft_name(name: &str) -> Option<&'static Self> {
                 match name {
-                    #name_to_type
+                    #minecraft_name_to_type
+                    _ => None
+                }
+            }
+            pub fn from_id(id: u8) -> Option<&'static Self> {
+                match id {
+                    #id_to_type
                     _ => None
                 }
             }
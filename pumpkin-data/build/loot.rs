@@ -614,6 +614,9 @@ pub enum LootTableTypeStruct {
     #[serde(rename = "minecraft:chest")]
     /// An item will be dropped.
     Chest,
+    #[serde(rename = "minecraft:fishing")]
+    /// An item will be reeled in.
+    Fishing,
 }
 
 impl ToTokens for LootTableTypeStruct {
@@ -623,6 +626,7 @@ impl ToTokens for LootTableTypeStruct {
             Self::Entity => quote! { LootTableType::Entity },
             Self::Block => quote! { LootTableType::Block },
             Self::Chest => quote! { LootTableType::Chest },
+            Self::Fishing => quote! { LootTableType::Fishing },
         };
 
         tokens.extend(name);
@@ -58,6 +58,7 @@ pub(crate) fn build() -> TokenStream {
 
     let mut variants = TokenStream::new();
     let mut name_to_type = TokenStream::new();
+    let mut id_to_type = TokenStream::new();
 
     for (name, potion) in potions {
         let format_name = format_ident!("{}", name.to_shouty_snake_case());
@@ -74,6 +75,7 @@ pub(crate) fn build() -> TokenStream {
         }]);
 
         name_to_type.extend(quote! { #name => Some(&Self::#format_name), });
+        id_to_type.extend(quote! { #id => Some(&Self::#format_name), });
     }
 
     quote! {
@@ -106,6 +108,13 @@ pub(crate) fn build() -> TokenStream {
                     _ => None
                 }
             }
+
+            pub fn from_id(id: u8) -> Option<&'static Self> {
+                match id {
+                    #id_to_type
+                    _ => None
+                }
+            }
         }
     }
 }
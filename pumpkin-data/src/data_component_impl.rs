@@ -4,8 +4,8 @@ use crate::attributes::Attributes;
 use crate::data_component::DataComponent;
 use crate::data_component::DataComponent::{
     AttributeModifiers, BlocksAttacks, Consumable, CustomData, CustomName, Damage, DeathProtection,
-    Enchantments, Equippable, Food, ItemName, JukeboxPlayable, MaxDamage, MaxStackSize,
-    PotionContents, Tool, Unbreakable,
+    Enchantments, Equippable, Food, ItemName, JukeboxPlayable, MapId, MapPostProcessing,
+    MaxDamage, MaxStackSize, PotionContents, Tool, Unbreakable,
 };
 use crate::entity_type::EntityType;
 use crate::tag::{Tag, Taggable};
@@ -49,6 +49,8 @@ pub fn read_data(id: DataComponent, data: &NbtTag) -> Option<Box<dyn DataCompone
         Enchantments => Some(EnchantmentsImpl::read_data(data)?.to_dyn()),
         Damage => Some(DamageImpl::read_data(data)?.to_dyn()),
         Unbreakable => Some(UnbreakableImpl::read_data(data)?.to_dyn()),
+        MapId => Some(MapIdImpl::read_data(data)?.to_dyn()),
+        MapPostProcessing => Some(MapPostProcessingImpl::read_data(data)?.to_dyn()),
         _ => None,
     }
 }
@@ -620,11 +622,60 @@ pub struct DyedColorImpl;
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub struct MapColorImpl;
 #[derive(Clone, Debug, Hash, PartialEq)]
-pub struct MapIdImpl;
+pub struct MapIdImpl {
+    pub id: i32,
+}
+impl MapIdImpl {
+    fn read_data(data: &NbtTag) -> Option<Self> {
+        data.extract_int().map(|id| Self { id })
+    }
+}
+impl DataComponentImpl for MapIdImpl {
+    fn write_data(&self) -> NbtTag {
+        NbtTag::Int(self.id)
+    }
+    fn get_hash(&self) -> i32 {
+        get_i32_hash(self.id) as i32
+    }
+    default_impl!(MapId);
+}
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub struct MapDecorationsImpl;
-#[derive(Clone, Debug, Hash, PartialEq)]
-pub struct MapPostProcessingImpl;
+/// What happens to a map once it is taken out of a cartography table.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum MapPostProcessingImpl {
+    Lock,
+    Scale,
+}
+impl MapPostProcessingImpl {
+    #[must_use]
+    pub const fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(Self::Lock),
+            1 => Some(Self::Scale),
+            _ => None,
+        }
+    }
+    #[must_use]
+    pub const fn id(self) -> i32 {
+        match self {
+            Self::Lock => 0,
+            Self::Scale => 1,
+        }
+    }
+    fn read_data(data: &NbtTag) -> Option<Self> {
+        data.extract_int().and_then(Self::from_id)
+    }
+}
+impl DataComponentImpl for MapPostProcessingImpl {
+    fn write_data(&self) -> NbtTag {
+        NbtTag::Int(self.id())
+    }
+    fn get_hash(&self) -> i32 {
+        get_i32_hash(self.id()) as i32
+    }
+    default_impl!(MapPostProcessing);
+}
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub struct ChargedProjectilesImpl;
 #[derive(Clone, Debug, Hash, PartialEq)]
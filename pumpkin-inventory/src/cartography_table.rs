@@ -6,17 +6,23 @@
 //! - Slot 2: Output
 //! - Slots 3-38: Player inventory (3-29 main, 30-38 hotbar)
 
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::{any::Any, sync::Arc};
 
+use pumpkin_data::data_component::DataComponent;
+use pumpkin_data::data_component_impl::{DataComponentImpl, MapIdImpl, MapPostProcessingImpl};
 use pumpkin_data::item::Item;
 use pumpkin_data::screen::WindowType;
 use pumpkin_world::inventory::{Clearable, Inventory, InventoryFuture, split_stack};
 use pumpkin_world::item::ItemStack;
+use pumpkin_world::map::MAX_SCALE;
 use tokio::sync::Mutex;
 
 use crate::player::player_inventory::PlayerInventory;
 use crate::screen_handler::{
     InventoryPlayer, ItemStackFuture, ScreenHandler, ScreenHandlerBehaviour, ScreenHandlerFuture,
+    ScreenHandlerListener,
 };
 use crate::slot::{BoxFuture, Slot};
 
@@ -103,31 +109,59 @@ impl Clearable for CartographyTableInventory {
     }
 }
 
+/// Looks up the maps of the world for the cartography table, which only sees the map ids of the
+/// items put into it.
+pub trait MapLookup: Send + Sync {
+    /// Returns the state of the map `map_id`, or `None` if there is no such map.
+    fn get_map_info(&self, map_id: i32) -> BoxFuture<'_, Option<MapInfo>>;
+}
+
+/// The parts of a map's state that decide what a cartography table can do with it.
+#[derive(Clone, Copy, Debug)]
+pub struct MapInfo {
+    pub scale: u8,
+    pub locked: bool,
+}
+
 /// Output slot for the cartography table — cannot insert directly.
+///
+/// Like the crafting result slot, the result is recomputed whenever an input changes and taking
+/// it consumes one item from each input.
 pub struct CartographyOutputSlot {
-    id: std::sync::atomic::AtomicU8,
+    inventory: Arc<CartographyTableInventory>,
+    maps: Arc<dyn MapLookup>,
+    id: AtomicU8,
     result: Arc<Mutex<ItemStack>>,
 }
 
-impl Default for CartographyOutputSlot {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl CartographyOutputSlot {
     #[must_use]
-    pub fn new() -> Self {
+    pub fn new(inventory: Arc<CartographyTableInventory>, maps: Arc<dyn MapLookup>) -> Self {
         Self {
-            id: std::sync::atomic::AtomicU8::new(0),
+            inventory,
+            maps,
+            id: AtomicU8::new(0),
             result: Arc::new(Mutex::new(ItemStack::EMPTY.clone())),
         }
     }
+
+    async fn refill_output(&self) -> ItemStack {
+        let map = self.inventory.slots[0].lock().await.clone();
+        let additional = self.inventory.slots[1].lock().await.clone();
+        let info = match map.get_data_component::<MapIdImpl>() {
+            Some(map_id) => self.maps.get_map_info(map_id.id).await,
+            None => None,
+        };
+        let result = compute_cartography_result(&map, &additional, info)
+            .unwrap_or_else(|| ItemStack::EMPTY.clone());
+        *self.result.lock().await = result.clone();
+        result
+    }
 }
 
 impl Slot for CartographyOutputSlot {
     fn get_inventory(&self) -> Arc<dyn Inventory> {
-        Arc::new(CartographyTableInventory::new())
+        self.inventory.clone()
     }
 
     fn get_index(&self) -> usize {
@@ -135,8 +169,33 @@ impl Slot for CartographyOutputSlot {
     }
 
     fn set_id(&self, id: usize) {
-        self.id
-            .store(id as u8, std::sync::atomic::Ordering::Relaxed);
+        self.id.store(id as u8, Ordering::Relaxed);
+    }
+
+    fn on_quick_move_crafted(
+        &self,
+        _stack: ItemStack,
+        _stack_prev: ItemStack,
+    ) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.refill_output().await;
+        })
+    }
+
+    fn on_take_item<'a>(
+        &'a self,
+        _player: &'a dyn InventoryPlayer,
+        _stack: &'a ItemStack,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            for slot in &self.inventory.slots {
+                let mut stack = slot.lock().await;
+                if !stack.is_empty() {
+                    stack.decrement(1);
+                }
+            }
+            self.mark_dirty().await;
+        })
     }
 
     fn can_insert(&self, _stack: &ItemStack) -> BoxFuture<'_, bool> {
@@ -155,20 +214,22 @@ impl Slot for CartographyOutputSlot {
         Box::pin(async move { !self.result.lock().await.is_empty() })
     }
 
-    fn set_stack(&self, stack: ItemStack) -> BoxFuture<'_, ()> {
+    fn set_stack(&self, _stack: ItemStack) -> BoxFuture<'_, ()> {
         Box::pin(async move {
-            *self.result.lock().await = stack;
+            self.refill_output().await;
         })
     }
 
-    fn set_stack_prev(&self, stack: ItemStack, _previous: ItemStack) -> BoxFuture<'_, ()> {
+    fn set_stack_prev(&self, _stack: ItemStack, _previous: ItemStack) -> BoxFuture<'_, ()> {
         Box::pin(async move {
-            *self.result.lock().await = stack;
+            self.refill_output().await;
         })
     }
 
     fn mark_dirty(&self) -> BoxFuture<'_, ()> {
-        Box::pin(async move {})
+        Box::pin(async move {
+            self.inventory.mark_dirty();
+        })
     }
 
     fn get_max_item_count(&self) -> BoxFuture<'_, u8> {
@@ -176,10 +237,28 @@ impl Slot for CartographyOutputSlot {
     }
 
     fn take_stack(&self, _amount: u8) -> BoxFuture<'_, ItemStack> {
+        // The inputs are only consumed in `on_take_item`, so the whole result is always taken.
+        Box::pin(async move { self.result.lock().await.clone() })
+    }
+}
+
+impl ScreenHandlerListener for CartographyOutputSlot {
+    fn on_slot_update<'a>(
+        &'a self,
+        screen_handler: &'a ScreenHandlerBehaviour,
+        slot: u8,
+        _stack: ItemStack,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
         Box::pin(async move {
-            let stack = self.result.lock().await.clone();
-            *self.result.lock().await = ItemStack::EMPTY.clone();
-            stack
+            if slot <= 1 {
+                let result = self.refill_output().await;
+                let next_revision = screen_handler.next_revision();
+                if let Some(sync_handler) = screen_handler.sync_handler.as_ref() {
+                    sync_handler
+                        .update_slot(screen_handler, 2, &result, next_revision)
+                        .await;
+                }
+            }
         })
     }
 }
@@ -187,14 +266,19 @@ impl Slot for CartographyOutputSlot {
 /// Compute the cartography table result.
 ///
 /// Operations:
-/// - Map + Paper → Extended map (zoom out)
+/// - Map + Paper → Extended map (zoom out), unless the map is locked or fully zoomed out
 /// - Map + Empty Map → Cloned map
-/// - Map + Glass Pane → Locked map
+/// - Map + Glass Pane → Locked map, unless the map is already locked
 ///
-/// TODO: All operations require `MapIdImpl` (stub). Currently returns a copy of
-/// the map input as a placeholder.
+/// Extending and locking only mark the result with `MapPostProcessingImpl`; the new map is
+/// created once the result is in the player's inventory. `info` is the state of the input map,
+/// without it there is no result.
 #[must_use]
-pub fn compute_cartography_result(map: &ItemStack, additional: &ItemStack) -> Option<ItemStack> {
+pub fn compute_cartography_result(
+    map: &ItemStack,
+    additional: &ItemStack,
+    info: Option<MapInfo>,
+) -> Option<ItemStack> {
     if map.is_empty() || map.item != &Item::FILLED_MAP {
         return None;
     }
@@ -202,20 +286,38 @@ pub fn compute_cartography_result(map: &ItemStack, additional: &ItemStack) -> Op
     if additional.is_empty() {
         return None;
     }
+    let info = info?;
+
+    let with_post_processing = |processing: MapPostProcessingImpl| {
+        let mut result = map.copy_with_count(1);
+        result
+            .patch
+            .retain(|(id, _)| *id != DataComponent::MapPostProcessing);
+        result
+            .patch
+            .push((DataComponent::MapPostProcessing, Some(processing.to_dyn())));
+        result
+    };
 
     // Paper → extend
     if additional.item == &Item::PAPER {
-        return Some(ItemStack::new(1, &Item::FILLED_MAP));
+        if info.locked || info.scale >= MAX_SCALE {
+            return None;
+        }
+        return Some(with_post_processing(MapPostProcessingImpl::Scale));
     }
 
     // Empty map → clone
     if additional.item == &Item::MAP {
-        return Some(ItemStack::new(2, &Item::FILLED_MAP));
+        return Some(map.copy_with_count(2));
     }
 
     // Glass pane → lock
     if additional.item == &Item::GLASS_PANE {
-        return Some(ItemStack::new(1, &Item::FILLED_MAP));
+        if info.locked {
+            return None;
+        }
+        return Some(with_post_processing(MapPostProcessingImpl::Lock));
     }
 
     None
@@ -231,19 +333,20 @@ pub fn compute_cartography_result(map: &ItemStack, additional: &ItemStack) -> Op
 pub struct CartographyTableScreenHandler {
     behaviour: ScreenHandlerBehaviour,
     inventory: Arc<CartographyTableInventory>,
-    output_slot: Arc<CartographyOutputSlot>,
 }
 
 impl CartographyTableScreenHandler {
-    #[allow(clippy::unused_async)]
-    pub async fn new(sync_id: u8, player_inventory: &Arc<PlayerInventory>) -> Self {
+    pub async fn new(
+        sync_id: u8,
+        player_inventory: &Arc<PlayerInventory>,
+        maps: Arc<dyn MapLookup>,
+    ) -> Self {
         let inventory = Arc::new(CartographyTableInventory::new());
-        let output_slot = Arc::new(CartographyOutputSlot::new());
+        let output_slot = Arc::new(CartographyOutputSlot::new(inventory.clone(), maps));
 
         let mut handler = Self {
             behaviour: ScreenHandlerBehaviour::new(sync_id, Some(WindowType::CartographyTable)),
             inventory: inventory.clone(),
-            output_slot: output_slot.clone(),
         };
 
         // Slot 0: Map input
@@ -251,28 +354,15 @@ impl CartographyTableScreenHandler {
         // Slot 1: Additional input
         handler.add_slot(Arc::new(crate::slot::NormalSlot::new(inventory, 1)));
         // Slot 2: Output
-        handler.add_slot(output_slot);
+        handler.add_slot(output_slot.clone());
 
         // Slots 3-38: Player inventory
         let player_inv: Arc<dyn Inventory> = player_inventory.clone();
         handler.add_player_slots(&player_inv);
 
+        handler.add_listener(output_slot).await;
         handler
     }
-
-    /// Recalculate the output based on current inputs.
-    pub async fn update_result(&mut self) {
-        let map_stack = self.inventory.get_stack(0).await;
-        let map = map_stack.lock().await;
-        let additional_stack = self.inventory.get_stack(1).await;
-        let additional = additional_stack.lock().await;
-
-        if let Some(result) = compute_cartography_result(&map, &additional) {
-            *self.output_slot.result.lock().await = result;
-        } else {
-            *self.output_slot.result.lock().await = ItemStack::EMPTY.clone();
-        }
-    }
 }
 
 impl ScreenHandler for CartographyTableScreenHandler {
@@ -370,6 +460,23 @@ impl ScreenHandler for CartographyTableScreenHandler {
 mod tests {
     use super::*;
 
+    const UNLOCKED: Option<MapInfo> = Some(MapInfo {
+        scale: 0,
+        locked: false,
+    });
+
+    struct NoMaps;
+
+    impl MapLookup for NoMaps {
+        fn get_map_info(&self, _map_id: i32) -> BoxFuture<'_, Option<MapInfo>> {
+            Box::pin(async { None })
+        }
+    }
+
+    fn post_processing(stack: &ItemStack) -> Option<MapPostProcessingImpl> {
+        stack.get_data_component::<MapPostProcessingImpl>().copied()
+    }
+
     #[test]
     fn cartography_inventory_size() {
         let inv = CartographyTableInventory::new();
@@ -378,7 +485,10 @@ mod tests {
 
     #[test]
     fn cartography_output_cannot_insert() {
-        let slot = CartographyOutputSlot::new();
+        let slot = CartographyOutputSlot::new(
+            Arc::new(CartographyTableInventory::new()),
+            Arc::new(NoMaps),
+        );
         let stack = ItemStack::new(1, &Item::FILLED_MAP);
         let rt = tokio::runtime::Runtime::new().unwrap();
         assert!(!rt.block_on(slot.can_insert(&stack)));
@@ -388,17 +498,29 @@ mod tests {
     fn cartography_extend_map() {
         let map = ItemStack::new(1, &Item::FILLED_MAP);
         let paper = ItemStack::new(1, &Item::PAPER);
-        let result = compute_cartography_result(&map, &paper);
+        let result = compute_cartography_result(&map, &paper, UNLOCKED);
         assert!(result.is_some(), "Map + paper should extend");
         let result = result.unwrap();
         assert!(result.item == &Item::FILLED_MAP);
+        assert_eq!(post_processing(&result), Some(MapPostProcessingImpl::Scale));
+    }
+
+    #[test]
+    fn cartography_cannot_extend_past_max_scale() {
+        let map = ItemStack::new(1, &Item::FILLED_MAP);
+        let paper = ItemStack::new(1, &Item::PAPER);
+        let info = Some(MapInfo {
+            scale: MAX_SCALE,
+            locked: false,
+        });
+        assert!(compute_cartography_result(&map, &paper, info).is_none());
     }
 
     #[test]
     fn cartography_clone_map() {
         let map = ItemStack::new(1, &Item::FILLED_MAP);
         let empty_map = ItemStack::new(1, &Item::MAP);
-        let result = compute_cartography_result(&map, &empty_map);
+        let result = compute_cartography_result(&map, &empty_map, UNLOCKED);
         assert!(result.is_some(), "Map + empty map should clone");
         let result = result.unwrap();
         assert_eq!(result.item_count, 2, "Clone produces 2 maps");
@@ -408,8 +530,27 @@ mod tests {
     fn cartography_lock_map() {
         let map = ItemStack::new(1, &Item::FILLED_MAP);
         let pane = ItemStack::new(1, &Item::GLASS_PANE);
-        let result = compute_cartography_result(&map, &pane);
+        let result = compute_cartography_result(&map, &pane, UNLOCKED);
         assert!(result.is_some(), "Map + glass pane should lock");
+        assert_eq!(
+            post_processing(&result.unwrap()),
+            Some(MapPostProcessingImpl::Lock)
+        );
+    }
+
+    #[test]
+    fn cartography_locked_map_cannot_change() {
+        let map = ItemStack::new(1, &Item::FILLED_MAP);
+        let locked = Some(MapInfo {
+            scale: 0,
+            locked: true,
+        });
+        let pane = ItemStack::new(1, &Item::GLASS_PANE);
+        let paper = ItemStack::new(1, &Item::PAPER);
+        let empty_map = ItemStack::new(1, &Item::MAP);
+        assert!(compute_cartography_result(&map, &pane, locked).is_none());
+        assert!(compute_cartography_result(&map, &paper, locked).is_none());
+        assert!(compute_cartography_result(&map, &empty_map, locked).is_some());
     }
 
     #[test]
@@ -417,7 +558,7 @@ mod tests {
         let diamond = ItemStack::new(1, &Item::DIAMOND);
         let paper = ItemStack::new(1, &Item::PAPER);
         assert!(
-            compute_cartography_result(&diamond, &paper).is_none(),
+            compute_cartography_result(&diamond, &paper, UNLOCKED).is_none(),
             "Non-map input should fail"
         );
     }
@@ -426,6 +567,6 @@ mod tests {
     fn cartography_empty_additional_no_result() {
         let map = ItemStack::new(1, &Item::FILLED_MAP);
         let empty = ItemStack::EMPTY.clone();
-        assert!(compute_cartography_result(&map, &empty).is_none());
+        assert!(compute_cartography_result(&map, &empty, UNLOCKED).is_none());
     }
 }
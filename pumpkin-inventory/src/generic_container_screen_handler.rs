@@ -28,6 +28,22 @@ pub async fn create_generic_9x3(
     .await
 }
 
+pub async fn create_generic_9x4(
+    sync_id: u8,
+    player_inventory: &Arc<PlayerInventory>,
+    inventory: Arc<dyn Inventory>,
+) -> GenericContainerScreenHandler {
+    GenericContainerScreenHandler::new(
+        WindowType::Generic9x4,
+        sync_id,
+        player_inventory,
+        inventory,
+        4,
+        9,
+    )
+    .await
+}
+
 pub async fn create_generic_9x6(
     sync_id: u8,
     player_inventory: &Arc<PlayerInventory>,
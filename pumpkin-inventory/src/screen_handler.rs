@@ -484,6 +484,26 @@ pub trait ScreenHandler: Send + Sync {
         })
     }
 
+    /// Counts the slots, the cursor included, whose contents the client predicted differently
+    /// than they turned out after a click. Call after the hashes of the click were received.
+    fn count_mispredicted_slots(&mut self) -> ScreenHandlerFuture<'_, usize> {
+        Box::pin(async move {
+            let behaviour = self.get_behaviour_mut();
+            let mut mispredicted = 0;
+            for i in 0..behaviour.slots.len() {
+                let stack = behaviour.slots[i].get_cloned_stack().await;
+                if !behaviour.previous_tracked_stacks[i].is_in_sync(&stack) {
+                    mispredicted += 1;
+                }
+            }
+            let cursor_stack = behaviour.cursor_stack.lock().await.clone();
+            if !behaviour.previous_cursor_stack.is_in_sync(&cursor_stack) {
+                mispredicted += 1;
+            }
+            mispredicted
+        })
+    }
+
     fn is_slot_valid(&self, slot: i32) -> ScreenHandlerFuture<'_, bool> {
         Box::pin(async move {
             slot == -1 || slot == -999 || slot < self.get_behaviour().slots.len() as i32
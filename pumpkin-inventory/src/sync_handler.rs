@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use pumpkin_protocol::{
     codec::{
@@ -14,6 +15,47 @@ use tokio::sync::Mutex;
 
 use crate::screen_handler::{InventoryPlayer, ScreenHandlerBehaviour};
 
+static STALE_STATE_RESYNCS: AtomicU64 = AtomicU64::new(0);
+static MISPREDICTED_RESYNCS: AtomicU64 = AtomicU64::new(0);
+
+/// Why the whole content of a container was sent again after a click.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResyncReason {
+    /// The click was based on a state id the server had moved past.
+    StaleState,
+    /// The client predicted other slot contents than the click resulted in.
+    Mispredicted,
+}
+
+impl ResyncReason {
+    pub const ALL: [Self; 2] = [Self::StaleState, Self::Mispredicted];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::StaleState => "stale_state",
+            Self::Mispredicted => "mispredicted",
+        }
+    }
+
+    const fn counter(self) -> &'static AtomicU64 {
+        match self {
+            Self::StaleState => &STALE_STATE_RESYNCS,
+            Self::Mispredicted => &MISPREDICTED_RESYNCS,
+        }
+    }
+
+    pub fn record(self) {
+        self.counter().fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// How many resyncs happened for this reason since the server started.
+    #[must_use]
+    pub fn count(self) -> u64 {
+        self.counter().load(Ordering::Relaxed)
+    }
+}
+
 pub struct SyncHandler {
     player: Mutex<Option<Arc<dyn InventoryPlayer>>>,
 }
@@ -0,0 +1,158 @@
+//! Reading and writing the two payloads that dominate world IO: chunks, which are mostly long
+//! arrays and block palettes, and player data, which is mostly small compounds and strings.
+
+use std::hint::black_box;
+use std::io::Cursor;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::deserializer::NbtReadHelper;
+use pumpkin_nbt::tag::NbtTag;
+use pumpkin_nbt::{Nbt, from_bytes, from_slice, nbt_long_array, to_bytes};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PaletteEntry {
+    name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlockStates {
+    #[serde(serialize_with = "nbt_long_array")]
+    data: Vec<i64>,
+    palette: Vec<PaletteEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Section {
+    #[serde(rename = "Y")]
+    y: i8,
+    block_states: BlockStates,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Chunk {
+    #[serde(rename = "DataVersion")]
+    data_version: i32,
+    #[serde(rename = "xPos")]
+    x_pos: i32,
+    #[serde(rename = "zPos")]
+    z_pos: i32,
+    #[serde(rename = "Status")]
+    status: String,
+    sections: Vec<Section>,
+}
+
+/// An overworld chunk: 24 sections with 8 block states each.
+fn chunk() -> Chunk {
+    let blocks = [
+        "stone",
+        "deepslate",
+        "dirt",
+        "gravel",
+        "coal_ore",
+        "iron_ore",
+        "water",
+        "air",
+    ];
+    Chunk {
+        data_version: 4440,
+        x_pos: 12,
+        z_pos: -7,
+        status: "minecraft:full".to_string(),
+        sections: (-4..20)
+            .map(|y| Section {
+                y,
+                block_states: BlockStates {
+                    data: (0..256).map(|i| i * 0x0123_4567_89ab).collect(),
+                    palette: blocks
+                        .iter()
+                        .map(|name| PaletteEntry {
+                            name: format!("minecraft:{name}"),
+                        })
+                        .collect(),
+                },
+            })
+            .collect(),
+    }
+}
+
+/// A player with a full inventory of enchanted items.
+fn player() -> Nbt {
+    let items = (0..36)
+        .map(|slot| {
+            let mut enchantment = NbtCompound::new();
+            enchantment.put_string("id", "minecraft:efficiency".to_string());
+            enchantment.put_short("lvl", 5);
+            let mut item = NbtCompound::new();
+            item.put_byte("Slot", slot);
+            item.put_string("id", "minecraft:diamond_pickaxe".to_string());
+            item.put_int("count", 1);
+            item.put_list("Enchantments", vec![NbtTag::Compound(enchantment)]);
+            NbtTag::Compound(item)
+        })
+        .collect();
+
+    let mut player = NbtCompound::new();
+    player.put_int("DataVersion", 4440);
+    player.put_string("Dimension", "minecraft:overworld".to_string());
+    player.put_list(
+        "Pos",
+        vec![
+            NbtTag::Double(12.5),
+            NbtTag::Double(64.0),
+            NbtTag::Double(-3.5),
+        ],
+    );
+    player.put_float("Health", 20.0);
+    player.put_int("XpLevel", 30);
+    player.put_list("Inventory", items);
+    Nbt::new(String::new(), player)
+}
+
+fn chunk_payload(c: &mut Criterion) {
+    let chunk = chunk();
+    let mut bytes = Vec::new();
+    to_bytes(&chunk, &mut bytes).unwrap();
+
+    let mut group = c.benchmark_group("chunk");
+    group.bench_function("read", |b| {
+        b.iter(|| from_bytes::<Chunk>(Cursor::new(black_box(&bytes))).unwrap());
+    });
+    group.bench_function("read_slice", |b| {
+        b.iter(|| from_slice::<Chunk>(black_box(&bytes)).unwrap());
+    });
+    group.bench_function("write", |b| {
+        let mut out = Vec::with_capacity(bytes.len());
+        b.iter(|| {
+            out.clear();
+            to_bytes(black_box(&chunk), &mut out).unwrap();
+        });
+    });
+    group.finish();
+}
+
+fn player_payload(c: &mut Criterion) {
+    let bytes = player().write();
+
+    let mut group = c.benchmark_group("player");
+    group.bench_function("read", |b| {
+        b.iter(|| Nbt::read(&mut NbtReadHelper::new(Cursor::new(black_box(&bytes)))).unwrap());
+    });
+    group.bench_function("write", |b| {
+        let mut out = Vec::with_capacity(bytes.len());
+        b.iter_batched(
+            player,
+            |player| {
+                out.clear();
+                player.write_to_writer(&mut out).unwrap();
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, chunk_payload, player_payload);
+criterion_main!(benches);
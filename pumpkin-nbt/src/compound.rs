@@ -95,6 +95,12 @@ impl NbtCompound {
         }
     }
 
+    /// Removes a tag, returning it if it was present.
+    pub fn remove(&mut self, name: &str) -> Option<NbtTag> {
+        let index = self.child_tags.iter().position(|(key, _)| key == name)?;
+        Some(self.child_tags.remove(index).1)
+    }
+
     pub fn put_string(&mut self, name: &str, value: String) {
         self.put(name, NbtTag::String(value));
     }
@@ -1,9 +1,10 @@
+use std::borrow::Cow;
 use std::cell::RefCell;
-use std::io::{Seek, SeekFrom};
+use std::io::{Cursor, Seek, SeekFrom};
 
 use crate::{
     BYTE_ARRAY_ID, BYTE_ID, COMPOUND_ID, END_ID, Error, INT_ARRAY_ID, INT_ID, LIST_ID,
-    LONG_ARRAY_ID, LONG_ID, NbtTag, get_nbt_string, io,
+    LONG_ARRAY_ID, LONG_ID, NbtTag, STRING_ID, get_nbt_string, io,
 };
 use io::Read;
 use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
@@ -49,6 +50,10 @@ macro_rules! define_get_number_be {
 }
 
 impl<R: Read + Seek> NbtReadHelper<R> {
+    fn position(&mut self) -> Result<u64> {
+        self.reader.stream_position().map_err(Error::Incomplete)
+    }
+
     pub fn skip_bytes(&mut self, count: i64) -> Result<()> {
         self.reader
             .seek(SeekFrom::Current(count))
@@ -77,18 +82,38 @@ impl<R: Read + Seek> NbtReadHelper<R> {
     }
 }
 
-pub struct Deserializer<R: Read + Seek> {
+pub struct Deserializer<'de, R: Read + Seek> {
     input: NbtReadHelper<R>,
+    /// The whole input when reading from a slice, strings are borrowed from it instead of copied.
+    slice: Option<&'de [u8]>,
     tag_to_deserialize_stack: Option<u8>,
     // Yes, this breaks with recursion. Just an attempt at a sanity check
     in_list: bool,
     is_named: bool,
 }
 
-impl<R: Read + Seek> Deserializer<R> {
+impl<R: Read + Seek> Deserializer<'_, R> {
     pub const fn new(input: R, is_named: bool) -> Self {
         Self {
             input: NbtReadHelper { reader: input },
+            slice: None,
+            tag_to_deserialize_stack: None,
+            in_list: false,
+            is_named,
+        }
+    }
+}
+
+impl<'de> Deserializer<'de, Cursor<&'de [u8]>> {
+    /// Reads from `input` without copying strings that are valid UTF-8, which is nearly all of
+    /// them.
+    #[must_use]
+    pub const fn from_slice(input: &'de [u8], is_named: bool) -> Self {
+        Self {
+            input: NbtReadHelper {
+                reader: Cursor::new(input),
+            },
+            slice: Some(input),
             tag_to_deserialize_stack: None,
             in_list: false,
             is_named,
@@ -96,6 +121,29 @@ impl<R: Read + Seek> Deserializer<R> {
     }
 }
 
+impl<'de, R: Read + Seek> Deserializer<'de, R> {
+    fn read_string(&mut self) -> Result<Cow<'de, str>> {
+        let Some(slice) = self.slice else {
+            return get_nbt_string(&mut self.input).map(Cow::Owned);
+        };
+
+        let len = self.input.get_u16_be()? as usize;
+        let start = self.input.position()? as usize;
+        let bytes = slice
+            .get(start..start + len)
+            .ok_or_else(|| Error::Incomplete(io::ErrorKind::UnexpectedEof.into()))?;
+        self.input.skip_bytes(len as i64)?;
+        cesu8::from_java_cesu8(bytes).map_err(|_| Error::Cesu8DecodingError)
+    }
+
+    fn visit_string<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value> {
+        match self.read_string()? {
+            Cow::Borrowed(string) => visitor.visit_borrowed_str(string),
+            Cow::Owned(string) => visitor.visit_string(string),
+        }
+    }
+}
+
 /// Deserializes struct using Serde Deserializer from normal NBT
 pub fn from_bytes<'a, T: Deserialize<'a>>(r: impl Read + Seek) -> Result<T> {
     let mut deserializer = Deserializer::new(r, true);
@@ -108,7 +156,21 @@ pub fn from_bytes_unnamed<'a, T: Deserialize<'a>>(r: impl Read + Seek) -> Result
     T::deserialize(&mut deserializer)
 }
 
-impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<R> {
+/// Deserializes struct from normal NBT in memory, borrowing strings from `input` where `T`
+/// allows it
+pub fn from_slice<'de, T: Deserialize<'de>>(input: &'de [u8]) -> Result<T> {
+    let mut deserializer = Deserializer::from_slice(input, true);
+    T::deserialize(&mut deserializer)
+}
+
+/// Deserializes struct from network NBT in memory, borrowing strings from `input` where `T`
+/// allows it
+pub fn from_slice_unnamed<'de, T: Deserialize<'de>>(input: &'de [u8]) -> Result<T> {
+    let mut deserializer = Deserializer::from_slice(input, false);
+    T::deserialize(&mut deserializer)
+}
+
+impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<'de, R> {
     type Error = Error;
 
     forward_to_deserialize_any! {
@@ -161,6 +223,7 @@ impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<R> {
                 Ok(result)
             }
             COMPOUND_ID => visitor.visit_map(CompoundAccess { de: self }),
+            STRING_ID => self.visit_string(visitor),
             _ => {
                 let result = match NbtTag::deserialize_data(&mut self.input, tag_to_deserialize)? {
                     NbtTag::Byte(value) => visitor.visit_i8::<Error>(value)?,
@@ -219,7 +282,7 @@ impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<R> {
         _variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
-        let variant = get_nbt_string(&mut self.input)?;
+        let variant = self.read_string()?;
         visitor.visit_enum(variant.into_deserializer())
     }
 
@@ -262,8 +325,7 @@ impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<R> {
     }
 
     fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        let str = get_nbt_string(&mut self.input)?;
-        visitor.visit_string(str)
+        self.visit_string(visitor)
     }
 
     fn is_human_readable(&self) -> bool {
@@ -271,11 +333,11 @@ impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<R> {
     }
 }
 
-struct CompoundAccess<'a, R: Read + Seek> {
-    de: &'a mut Deserializer<R>,
+struct CompoundAccess<'a, 'de, R: Read + Seek> {
+    de: &'a mut Deserializer<'de, R>,
 }
 
-impl<'de, R: Read + Seek> MapAccess<'de> for CompoundAccess<'_, R> {
+impl<'de, R: Read + Seek> MapAccess<'de> for CompoundAccess<'_, 'de, R> {
     type Error = Error;
 
     fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
@@ -294,16 +356,15 @@ impl<'de, R: Read + Seek> MapAccess<'de> for CompoundAccess<'_, R> {
     }
 }
 
-struct MapKey<'a, R: Read + Seek> {
-    de: &'a mut Deserializer<R>,
+struct MapKey<'a, 'de, R: Read + Seek> {
+    de: &'a mut Deserializer<'de, R>,
 }
 
-impl<'de, R: Read + Seek> de::Deserializer<'de> for MapKey<'_, R> {
+impl<'de, R: Read + Seek> de::Deserializer<'de> for MapKey<'_, 'de, R> {
     type Error = Error;
 
     fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        let key = get_nbt_string(&mut self.de.input)?;
-        visitor.visit_string(key)
+        self.de.visit_string(visitor)
     }
 
     forward_to_deserialize_any! {
@@ -312,13 +373,13 @@ impl<'de, R: Read + Seek> de::Deserializer<'de> for MapKey<'_, R> {
     }
 }
 
-struct ListAccess<'a, R: Read + Seek> {
-    de: &'a mut Deserializer<R>,
+struct ListAccess<'a, 'de, R: Read + Seek> {
+    de: &'a mut Deserializer<'de, R>,
     remaining_values: usize,
     list_type: u8,
 }
 
-impl<'de, R: Read + Seek> SeqAccess<'de> for ListAccess<'_, R> {
+impl<'de, R: Read + Seek> SeqAccess<'de> for ListAccess<'_, 'de, R> {
     type Error = Error;
 
     fn size_hint(&self) -> Option<usize> {
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     fmt::Display,
     io::{self, Read, Seek, Write},
     ops::Deref,
@@ -21,7 +22,7 @@ pub mod serializer;
 pub mod snbt;
 pub mod tag;
 
-pub use deserializer::{from_bytes, from_bytes_unnamed};
+pub use deserializer::{from_bytes, from_bytes_unnamed, from_slice, from_slice_unnamed};
 pub use serializer::{to_bytes, to_bytes_named, to_bytes_unnamed};
 
 // This NBT crate is inspired from CrabNBT
@@ -117,36 +118,31 @@ impl Nbt {
     #[must_use]
     pub fn write(self) -> Bytes {
         let mut bytes = Vec::new();
-        let mut writer = WriteAdaptor::new(&mut bytes);
-        writer.write_u8_be(COMPOUND_ID).unwrap();
-        NbtTag::String(self.name)
-            .serialize_data(&mut writer)
-            .unwrap();
-        self.root_tag.serialize_content(&mut writer).unwrap();
-
+        self.write_to_writer(&mut bytes).unwrap();
         bytes.into()
     }
 
-    pub fn write_to_writer<W: Write>(self, mut writer: W) -> Result<(), io::Error> {
-        writer.write_all(&self.write())?;
-        Ok(())
+    /// Writes the tag straight into `writer`, without building it in memory first.
+    pub fn write_to_writer<W: Write>(self, writer: W) -> Result<(), Error> {
+        let mut writer = WriteAdaptor::new(writer);
+        writer.write_u8_be(COMPOUND_ID)?;
+        NbtTag::write_string(&self.name, &mut writer)?;
+        self.root_tag.serialize_content(&mut writer)
     }
 
     /// Writes an NBT tag without a root `Compound` name.
     #[must_use]
     pub fn write_unnamed(self) -> Bytes {
         let mut bytes = Vec::new();
-        let mut writer = WriteAdaptor::new(&mut bytes);
-
-        writer.write_u8_be(COMPOUND_ID).unwrap();
-        self.root_tag.serialize_content(&mut writer).unwrap();
-
+        self.write_unnamed_to_writer(&mut bytes).unwrap();
         bytes.into()
     }
 
-    pub fn write_unnamed_to_writer<W: Write>(self, mut writer: W) -> Result<(), io::Error> {
-        writer.write_all(&self.write_unnamed())?;
-        Ok(())
+    /// Writes the tag without a root `Compound` name straight into `writer`.
+    pub fn write_unnamed_to_writer<W: Write>(self, writer: W) -> Result<(), Error> {
+        let mut writer = WriteAdaptor::new(writer);
+        writer.write_u8_be(COMPOUND_ID)?;
+        self.root_tag.serialize_content(&mut writer)
     }
 }
 
@@ -184,7 +180,13 @@ pub fn get_nbt_string<R: Read + Seek>(bytes: &mut NbtReadHelper<R>) -> Result<St
     let len = bytes.get_u16_be()? as usize;
     let string_bytes = bytes.read_boxed_slice(len)?;
     let string = cesu8::from_java_cesu8(&string_bytes).map_err(|_| Error::Cesu8DecodingError)?;
-    Ok(string.into_owned())
+    match string {
+        // Already valid UTF-8, which saves copying it again
+        Cow::Borrowed(_) => {
+            String::from_utf8(string_bytes.into_vec()).map_err(|_| Error::Cesu8DecodingError)
+        }
+        Cow::Owned(string) => Ok(string),
+    }
 }
 
 // TODO: This is a bit hacky
@@ -215,7 +217,7 @@ mod test {
 
     use crate::Error;
     use crate::Nbt;
-    use crate::deserializer::from_bytes;
+    use crate::deserializer::{from_bytes, from_slice};
     use crate::nbt_byte_array;
     use crate::nbt_int_array;
     use crate::nbt_long_array;
@@ -278,6 +280,36 @@ mod test {
         assert_eq!(test, recreated_struct);
     }
 
+    #[derive(Deserialize)]
+    struct BorrowedTest<'a> {
+        int: i32,
+        string: &'a str,
+    }
+
+    #[test]
+    fn slice_de_borrows_strings() {
+        let test = Test {
+            byte: 1,
+            short: 2,
+            int: 3,
+            long: 4,
+            float: 5.0,
+            string: "Hello test".to_string(),
+        };
+
+        let mut bytes = Vec::new();
+        to_bytes(&test, &mut bytes).unwrap();
+        let borrowed: BorrowedTest = from_slice(&bytes).unwrap();
+        assert_eq!(borrowed.int, 3);
+        assert_eq!(borrowed.string, "Hello test");
+        assert!(bytes.as_ptr_range().contains(&borrowed.string.as_ptr()));
+
+        let owned: Test = from_slice(&bytes).unwrap();
+        assert_eq!(owned, test);
+        // Borrowing can't work from a reader
+        assert!(from_bytes::<BorrowedTest>(Cursor::new(&bytes)).is_err());
+    }
+
     #[test]
     fn simple_ser_de_named() {
         let name = String::from("Test");
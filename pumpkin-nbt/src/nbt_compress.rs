@@ -1,7 +1,7 @@
 use crate::deserializer::NbtReadHelper;
 use crate::{Error, Nbt, NbtCompound, deserializer, serializer};
 use flate2::{Compression, read::GzDecoder, write::GzEncoder};
-use std::io::{Cursor, Read, Seek, Write};
+use std::io::{BufWriter, Cursor, Read, Seek, Write};
 
 /// Reads a `GZipped` NBT compound tag from any reader.
 ///
@@ -40,10 +40,13 @@ pub fn write_gzip_compound_tag(compound: NbtCompound, output: impl Write) -> Res
     // Create a GZip encoder that writes to the output
     let mut encoder = GzEncoder::new(output, Compression::default());
 
-    // Create an NBT wrapper and write directly to the encoder
+    // Create an NBT wrapper and write directly to the encoder, batching the small writes of
+    // single values
     let nbt = Nbt::new(String::new(), compound);
-    nbt.write_to_writer(&mut encoder)
-        .map_err(Error::Incomplete)?;
+    let mut writer = BufWriter::new(&mut encoder);
+    nbt.write_to_writer(&mut writer)?;
+    writer.flush().map_err(Error::Incomplete)?;
+    drop(writer);
 
     // Finish the encoder to ensure all data is written
     encoder.finish().map_err(Error::Incomplete)?;
@@ -1,5 +1,6 @@
 use serde::ser::Impossible;
 use serde::{Serialize, ser};
+use std::borrow::Cow;
 use std::io::Write;
 
 use crate::tag::NbtTag;
@@ -73,7 +74,8 @@ impl<W: Write> Serializer<W> {
 enum State {
     // In network NBT, the root name is not present.
     Root(Option<String>),
-    Named(String),
+    // Struct fields borrow their names, only map keys are copied.
+    Named(Cow<'static, str>),
     // Used by maps to check if key is a `String`.
     MapKey,
     FirstListElement {
@@ -82,7 +84,7 @@ enum State {
     ListElement,
     CheckedListElement,
     Array {
-        name: String,
+        name: Cow<'static, str>,
         array_type: &'static str,
     },
 }
@@ -242,7 +244,7 @@ impl<W: Write> ser::Serializer for &mut Serializer<W> {
         self.parse_state(STRING_ID)?;
 
         if self.state == State::MapKey {
-            self.state = State::Named(v.to_string());
+            self.state = State::Named(Cow::Owned(v.to_string()));
         } else {
             NbtTag::write_string(v, &mut self.output)?;
         }
@@ -307,9 +309,8 @@ impl<W: Write> ser::Serializer for &mut Serializer<W> {
         value: &T,
     ) -> Result<()> {
         if name == NBT_ARRAY_TAG {
-            let name = match self.state {
-                State::Named(ref name) => name.clone(),
-                _ => return Err(Error::SerdeError("Invalid `Serializer` state!".to_string())),
+            let State::Named(name) = std::mem::replace(&mut self.state, State::ListElement) else {
+                return Err(Error::SerdeError("Invalid `Serializer` state!".to_string()));
             };
 
             self.state = State::Array {
@@ -455,7 +456,7 @@ impl<W: Write> ser::SerializeStruct for &mut Serializer<W> {
         key: &'static str,
         value: &T,
     ) -> Result<()> {
-        self.state = State::Named(key.to_string());
+        self.state = State::Named(Cow::Borrowed(key));
         value.serialize(&mut **self)
     }
 
@@ -0,0 +1,122 @@
+//! Outgoing packet path for 100 simulated clients: packets written and flushed one by one,
+//! against packets serialized into pooled buffers and written per client in one batch.
+//!
+//! Besides the timings, the number of allocations of one tick of traffic is printed.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytes::{BufMut, Bytes};
+use criterion::{Criterion, criterion_group, criterion_main};
+use pumpkin_protocol::buffer_pool::PACKET_BUFFERS;
+use pumpkin_protocol::java::packet_encoder::TCPNetworkEncoder;
+use tokio::io::{Sink, sink};
+use tokio::runtime::Runtime;
+
+const CLIENTS: usize = 100;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A tick of traffic for one client: mostly entity movement, a few block updates and a chunk.
+fn tick_packets() -> Vec<Vec<u8>> {
+    let mut packets = vec![vec![0x33; 24]; 40];
+    packets.extend(vec![vec![0x08; 300]; 6]);
+    packets.push((0..12_000).map(|i| (i % 7) as u8).collect());
+    packets
+}
+
+fn encoders() -> Vec<TCPNetworkEncoder<Sink>> {
+    (0..CLIENTS)
+        .map(|_| {
+            let mut encoder = TCPNetworkEncoder::new(sink());
+            encoder.set_compression((256, 4));
+            encoder
+        })
+        .collect()
+}
+
+/// Every packet is serialized into its own allocation, then written and flushed on its own.
+async fn per_packet(encoders: &mut [TCPNetworkEncoder<Sink>], packets: &[Vec<u8>]) {
+    for encoder in encoders {
+        for packet in packets {
+            let packet = Bytes::from(packet.clone());
+            encoder.write_packet(packet).await.unwrap();
+        }
+    }
+}
+
+/// Packets are serialized into pooled buffers and each client's packets are flushed together.
+async fn pooled(encoders: &mut [TCPNetworkEncoder<Sink>], packets: &[Vec<u8>]) {
+    let mut batch = Vec::with_capacity(packets.len());
+    for encoder in encoders {
+        for packet in packets {
+            let mut buf = PACKET_BUFFERS.take();
+            buf.put_slice(packet);
+            batch.push(buf.split().freeze());
+            PACKET_BUFFERS.put(buf);
+        }
+        encoder.write_packets(&batch).await.unwrap();
+        batch.clear();
+    }
+}
+
+fn count_allocations(run: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    run();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[allow(clippy::print_stdout)]
+fn report_allocations(runtime: &Runtime, packets: &[Vec<u8>]) {
+    let mut encoders = encoders();
+    // Warms up the pool and the compressors
+    runtime.block_on(pooled(&mut encoders, packets));
+
+    let per_packet = count_allocations(|| runtime.block_on(per_packet(&mut encoders, packets)));
+    let pooled = count_allocations(|| runtime.block_on(pooled(&mut encoders, packets)));
+    println!(
+        "Allocations for a tick of {CLIENTS} clients ({} packets): {per_packet} written one by one, {pooled} pooled and batched",
+        CLIENTS * packets.len()
+    );
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let packets = tick_packets();
+    report_allocations(&runtime, &packets);
+
+    let mut encoders = encoders();
+    c.bench_function("packet_writing_per_packet", |b| {
+        b.iter(|| runtime.block_on(per_packet(&mut encoders, black_box(&packets))));
+    });
+    c.bench_function("packet_writing_pooled", |b| {
+        b.iter(|| runtime.block_on(pooled(&mut encoders, black_box(&packets))));
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,12 @@
+use pumpkin_macros::packet;
+
+use crate::{codec::var_uint::VarUInt, serial::PacketWrite};
+
+#[derive(PacketWrite)]
+#[packet(100)]
+pub struct CModalFormRequest {
+    // https://mojang.github.io/bedrock-protocol-docs/html/ModalFormRequestPacket.html
+    pub form_id: VarUInt,
+    /// The form as JSON.
+    pub form_data: String,
+}
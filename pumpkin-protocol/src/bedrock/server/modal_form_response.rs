@@ -0,0 +1,14 @@
+use pumpkin_macros::packet;
+
+use crate::{codec::var_uint::VarUInt, serial::PacketRead};
+
+#[derive(Debug, PacketRead)]
+#[packet(101)]
+pub struct SModalFormResponse {
+    // https://mojang.github.io/bedrock-protocol-docs/html/ModalFormResponsePacket.html
+    pub form_id: VarUInt,
+    /// The answer as JSON, `None` if the form was closed.
+    pub response: Option<String>,
+    /// Why the form was closed: 0 by the player, 1 because the client was busy.
+    pub cancel_reason: Option<u8>,
+}
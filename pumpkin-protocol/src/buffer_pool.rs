@@ -0,0 +1,91 @@
+//! Reusable buffers for serializing and framing outgoing packets.
+//!
+//! Packets are split off the front of a pooled [`BytesMut`], so many small packets share one
+//! allocation. The allocation is freed once every packet split off it has been dropped, or
+//! reused by the next [`BufferPool::take`] if it was returned without any packet left alive.
+
+use std::sync::Mutex;
+
+use bytes::BytesMut;
+
+/// Capacity of the buffers created when the pool is empty.
+pub const BUFFER_CAPACITY: usize = 8 * 1024;
+/// Buffers with less spare capacity than this are not worth keeping.
+const MIN_SPARE_CAPACITY: usize = 512;
+/// Buffers beyond this are dropped when returned.
+const MAX_POOLED_BUFFERS: usize = 256;
+
+/// Buffers shared by every connection.
+pub static PACKET_BUFFERS: BufferPool = BufferPool::new();
+
+pub struct BufferPool {
+    buffers: Mutex<Vec<BytesMut>>,
+}
+
+impl BufferPool {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns an empty buffer, which grows like any other [`BytesMut`] when needed.
+    pub fn take(&self) -> BytesMut {
+        self.buffers
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_else(|| BytesMut::with_capacity(BUFFER_CAPACITY))
+    }
+
+    /// Returns a buffer to the pool. Anything still in it is discarded.
+    pub fn put(&self, mut buffer: BytesMut) {
+        buffer.clear();
+        // Reclaims the whole allocation if nothing split off it is still alive
+        if buffer.capacity() < MIN_SPARE_CAPACITY && !buffer.try_reclaim(MIN_SPARE_CAPACITY) {
+            return;
+        }
+        let mut buffers = self.buffers.lock().unwrap();
+        if buffers.len() < MAX_POOLED_BUFFERS {
+            buffers.push(buffer);
+        }
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+
+    use super::*;
+
+    #[test]
+    fn split_packets_share_the_pooled_allocation() {
+        let pool = BufferPool::new();
+        let mut buffer = pool.take();
+        buffer.put_slice(b"first");
+        let first = buffer.split().freeze();
+        pool.put(buffer);
+
+        let mut buffer = pool.take();
+        buffer.put_slice(b"second");
+        let second = buffer.split().freeze();
+        assert_eq!(&first[..], b"first");
+        assert_eq!(&second[..], b"second");
+        assert_eq!(first.as_ptr().wrapping_add(first.len()), second.as_ptr());
+
+        // Once the packets are gone the buffer starts over at the beginning of its allocation
+        let start = first.as_ptr();
+        drop((first, second));
+        pool.put(buffer);
+        let mut buffer = pool.take();
+        assert!(buffer.try_reclaim(BUFFER_CAPACITY));
+        assert_eq!(buffer.as_ptr(), start);
+    }
+}
@@ -2,8 +2,8 @@ use crate::codec::var_int::VarInt;
 use pumpkin_data::Enchantment;
 use pumpkin_data::data_component::DataComponent;
 use pumpkin_data::data_component_impl::{
-    DamageImpl, DataComponentImpl, EnchantmentsImpl, MaxStackSizeImpl, PotionContentsImpl,
-    StatusEffectInstance, UnbreakableImpl, get,
+    DamageImpl, DataComponentImpl, EnchantmentsImpl, MapIdImpl, MapPostProcessingImpl,
+    MaxStackSizeImpl, PotionContentsImpl, StatusEffectInstance, UnbreakableImpl, get,
 };
 use serde::de;
 use serde::de::SeqAccess;
@@ -43,6 +43,32 @@ impl DataComponentCodec<Self> for DamageImpl {
     }
 }
 
+impl DataComponentCodec<Self> for MapIdImpl {
+    fn serialize<T: SerializeStruct>(&self, seq: &mut T) -> Result<(), T::Error> {
+        seq.serialize_field::<VarInt>("", &VarInt::from(self.id))
+    }
+    fn deserialize<'a, A: SeqAccess<'a>>(seq: &mut A) -> Result<Self, A::Error> {
+        let id = seq
+            .next_element::<VarInt>()?
+            .ok_or(de::Error::custom("No map id VarInt!"))?
+            .0;
+        Ok(Self { id })
+    }
+}
+
+impl DataComponentCodec<Self> for MapPostProcessingImpl {
+    fn serialize<T: SerializeStruct>(&self, seq: &mut T) -> Result<(), T::Error> {
+        seq.serialize_field::<VarInt>("", &VarInt::from(self.id()))
+    }
+    fn deserialize<'a, A: SeqAccess<'a>>(seq: &mut A) -> Result<Self, A::Error> {
+        let id = seq
+            .next_element::<VarInt>()?
+            .ok_or(de::Error::custom("No map post processing VarInt!"))?
+            .0;
+        Self::from_id(id).ok_or(de::Error::custom("Invalid map post processing!"))
+    }
+}
+
 impl DataComponentCodec<Self> for EnchantmentsImpl {
     fn serialize<T: SerializeStruct>(&self, seq: &mut T) -> Result<(), T::Error> {
         seq.serialize_field::<VarInt>("", &VarInt::from(self.enchantment.len() as i32))?;
@@ -270,6 +296,8 @@ pub fn deserialize<'a, A: SeqAccess<'a>>(
         DataComponent::Damage => Ok(DamageImpl::deserialize(seq)?.to_dyn()),
         DataComponent::Unbreakable => Ok(UnbreakableImpl::deserialize(seq)?.to_dyn()),
         DataComponent::PotionContents => Ok(PotionContentsImpl::deserialize(seq)?.to_dyn()),
+        DataComponent::MapId => Ok(MapIdImpl::deserialize(seq)?.to_dyn()),
+        DataComponent::MapPostProcessing => Ok(MapPostProcessingImpl::deserialize(seq)?.to_dyn()),
         _ => todo!("{} not yet implemented", id.to_name()),
     }
 }
@@ -284,6 +312,8 @@ pub fn serialize<T: SerializeStruct>(
         DataComponent::Damage => get::<DamageImpl>(value).serialize(seq),
         DataComponent::Unbreakable => get::<UnbreakableImpl>(value).serialize(seq),
         DataComponent::PotionContents => get::<PotionContentsImpl>(value).serialize(seq),
+        DataComponent::MapId => get::<MapIdImpl>(value).serialize(seq),
+        DataComponent::MapPostProcessing => get::<MapPostProcessingImpl>(value).serialize(seq),
         _ => todo!("{} not yet implemented", id.to_name()),
     }
 }
@@ -0,0 +1,606 @@
+//! Packet captures of Java connections, for debugging protocol issues.
+//!
+//! A capture starts with [`MAGIC`] and [`FORMAT_VERSION`], followed by one record per packet.
+//! Packets are stored as the server sees them: after decryption and before compression.
+//!
+//! |----------------------------------------------|
+//! | Milliseconds since the capture started (u64) |
+//! |----------------------------------------------|
+//! | Direction (u8)                               |
+//! |----------------------------------------------|
+//! | Connection state (u8)                        |
+//! |----------------------------------------------|
+//! | Protocol version (u32)                       |
+//! |----------------------------------------------|
+//! | Packet length (u32)                          |
+//! |----------------------------------------------|
+//! | Packet ID (`VarInt`) and data                |
+//! |----------------------------------------------|
+//!
+//! All integers are big-endian.
+
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use pumpkin_data::packet::PacketId;
+use pumpkin_util::version::MinecraftVersion;
+use thiserror::Error;
+
+use super::packet_decoder::TCPNetworkDecoder;
+use super::packet_encoder::TCPNetworkEncoder;
+use super::server::config::{
+    SAcknowledgeFinishConfig, SClientInformationConfig, SConfigCookieResponse, SConfigResourcePack,
+    SKnownPacks, SPluginMessage,
+};
+use super::server::handshake::SHandShake;
+use super::server::login::{
+    SEncryptionResponse, SLoginAcknowledged, SLoginCookieResponse, SLoginPluginResponse,
+    SLoginStart,
+};
+use super::server::play::{
+    SChangeDifficulty, SChangeGameMode, SChatCommand, SChatMessage, SChunkBatch, SClickSlot,
+    SClientCommand, SClientInformationPlay, SClientTickEnd, SCloseContainer, SCommandSuggestion,
+    SConfigurationAcknowledged, SConfirmTeleport, SContainerButtonClick, SCookieResponse,
+    SCustomPayload, SDebugSubscriptionRequest, SEditBook, SInteract, SKeepAlive, SLockDifficulty,
+    SMoveVehicle, SPaddleBoat, SPickItemFromBlock, SPickItemFromEntity, SPlaceRecipe,
+    SPlayPingRequest, SPlayerAbilities, SPlayerAction, SPlayerCommand, SPlayerInput, SPlayerLoaded,
+    SPlayerPosition, SPlayerPositionRotation, SPlayerRotation, SPlayerSession, SPong,
+    SRecipeBookChangeSettings, SRecipeBookSeenRecipe, SRenameItem, SSeenAdvancements, SSelectTrade,
+    SSetBeacon, SSetCommandBlock, SSetCreativeSlot, SSetHeldItem, SSetPlayerGround, SSwingArm,
+    STeleportToEntity, SUpdateSign, SUseItem, SUseItemOn,
+};
+use super::server::status::{SStatusPingRequest, SStatusRequest};
+use crate::packet::MultiVersionJavaPacket;
+use crate::{
+    CompressionThreshold, ConnectionState, MAX_PACKET_DATA_SIZE, PacketDecodeError,
+    PacketEncodeError, RawPacket, ReadingError, ServerPacket, VarInt,
+};
+
+pub const MAGIC: [u8; 4] = *b"PKCP";
+pub const FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureDirection {
+    Serverbound,
+    Clientbound,
+}
+
+#[derive(Error, Debug)]
+pub enum CaptureError {
+    #[error("Failed to read the capture: {0}")]
+    Io(#[from] io::Error),
+    #[error("Not a packet capture")]
+    NotACapture,
+    #[error("Unsupported capture format version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Invalid record: {0}")]
+    InvalidRecord(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error(transparent)]
+    Capture(#[from] CaptureError),
+    #[error("Failed to encode packet {index}: {error}")]
+    Encode {
+        index: usize,
+        error: PacketEncodeError,
+    },
+    #[error("Failed to decode packet {index}: {error}")]
+    Decode {
+        index: usize,
+        error: PacketDecodeError,
+    },
+    #[error("Packet {0} changed after being decoded")]
+    Mismatch(usize),
+    #[error("Failed to read packet {index}: {error}")]
+    Read { index: usize, error: ReadingError },
+}
+
+const fn state_to_byte(state: ConnectionState) -> u8 {
+    match state {
+        ConnectionState::HandShake => 0,
+        ConnectionState::Status => 1,
+        ConnectionState::Login => 2,
+        ConnectionState::Transfer => 3,
+        ConnectionState::Config => 4,
+        ConnectionState::Play => 5,
+    }
+}
+
+const fn state_from_byte(byte: u8) -> Option<ConnectionState> {
+    Some(match byte {
+        0 => ConnectionState::HandShake,
+        1 => ConnectionState::Status,
+        2 => ConnectionState::Login,
+        3 => ConnectionState::Transfer,
+        4 => ConnectionState::Config,
+        5 => ConnectionState::Play,
+        _ => return None,
+    })
+}
+
+/// A packet read back from a capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedPacket {
+    /// Time since the capture started, with millisecond precision.
+    pub elapsed: Duration,
+    pub direction: CaptureDirection,
+    pub state: ConnectionState,
+    pub protocol: u32,
+    /// The packet ID followed by its data.
+    pub data: Bytes,
+}
+
+impl CapturedPacket {
+    /// Splits the packet into its ID and payload.
+    pub fn raw(&self) -> Result<RawPacket, CaptureError> {
+        let mut data = &self.data[..];
+        let id = VarInt::decode(&mut data)
+            .map_err(|err| CaptureError::InvalidRecord(err.to_string()))?;
+        Ok(RawPacket {
+            id: id.0,
+            payload: self.data.slice(self.data.len() - data.len()..),
+        })
+    }
+}
+
+/// Writes packets to a capture as they are sent and received.
+pub struct CaptureWriter<W: Write> {
+    writer: W,
+    started: Instant,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION])?;
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+        })
+    }
+
+    /// Records a packet, given as its ID followed by its data.
+    pub fn write(
+        &mut self,
+        direction: CaptureDirection,
+        state: ConnectionState,
+        protocol: u32,
+        packet: &[u8],
+    ) -> io::Result<()> {
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        self.writer.write_all(&elapsed.to_be_bytes())?;
+        self.writer.write_all(&[
+            match direction {
+                CaptureDirection::Serverbound => 0,
+                CaptureDirection::Clientbound => 1,
+            },
+            state_to_byte(state),
+        ])?;
+        self.writer.write_all(&protocol.to_be_bytes())?;
+        self.writer
+            .write_all(&(packet.len() as u32).to_be_bytes())?;
+        self.writer.write_all(packet)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reads the packets of a capture in the order they were recorded.
+pub struct CaptureReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, CaptureError> {
+        let mut header = [0; MAGIC.len() + 1];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| CaptureError::NotACapture)?;
+        if header[..MAGIC.len()] != MAGIC {
+            return Err(CaptureError::NotACapture);
+        }
+        if header[MAGIC.len()] != FORMAT_VERSION {
+            return Err(CaptureError::UnsupportedVersion(header[MAGIC.len()]));
+        }
+        Ok(Self { reader })
+    }
+
+    fn read_record(&mut self) -> Result<Option<CapturedPacket>, CaptureError> {
+        let mut elapsed = [0; 8];
+        let mut filled = 0;
+        while filled < elapsed.len() {
+            match self.reader.read(&mut elapsed[filled..]) {
+                // The capture ends after the last complete record
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        let mut header = [0; 10];
+        self.reader.read_exact(&mut header)?;
+        let direction = match header[0] {
+            0 => CaptureDirection::Serverbound,
+            1 => CaptureDirection::Clientbound,
+            other => {
+                return Err(CaptureError::InvalidRecord(format!(
+                    "unknown direction {other}"
+                )));
+            }
+        };
+        let state = state_from_byte(header[1]).ok_or_else(|| {
+            CaptureError::InvalidRecord(format!("unknown connection state {}", header[1]))
+        })?;
+        let protocol = u32::from_be_bytes(header[2..6].try_into().unwrap());
+        let len = u32::from_be_bytes(header[6..10].try_into().unwrap()) as usize;
+        if len > MAX_PACKET_DATA_SIZE {
+            return Err(CaptureError::InvalidRecord(format!(
+                "packet length {len} is too large"
+            )));
+        }
+        let mut data = vec![0; len];
+        self.reader.read_exact(&mut data)?;
+        Ok(Some(CapturedPacket {
+            elapsed: Duration::from_millis(u64::from_be_bytes(elapsed)),
+            direction,
+            state,
+            protocol,
+            data: data.into(),
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<CapturedPacket, CaptureError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Reads a packet with its definition, making sure nothing of the payload is left over.
+fn read_packet<P: ServerPacket>(mut payload: &[u8]) -> Result<(), ReadingError> {
+    P::read(&mut payload)?;
+    if payload.is_empty() {
+        Ok(())
+    } else {
+        Err(ReadingError::Message(format!(
+            "{} bytes left over",
+            payload.len()
+        )))
+    }
+}
+
+/// Packets without any data, which have no reader of their own.
+fn read_empty(payload: &[u8]) -> Result<(), ReadingError> {
+    if payload.is_empty() {
+        Ok(())
+    } else {
+        Err(ReadingError::Message(format!(
+            "{} bytes left over",
+            payload.len()
+        )))
+    }
+}
+
+type PacketReader = fn(&[u8]) -> Result<(), ReadingError>;
+
+const STATUS_PACKETS: &[(PacketId, PacketReader)] = &[
+    (SStatusRequest::PACKET_ID, read_empty),
+    (
+        SStatusPingRequest::PACKET_ID,
+        read_packet::<SStatusPingRequest>,
+    ),
+];
+
+const LOGIN_PACKETS: &[(PacketId, PacketReader)] = &[
+    (SLoginStart::PACKET_ID, read_packet::<SLoginStart>),
+    (
+        SEncryptionResponse::PACKET_ID,
+        read_packet::<SEncryptionResponse>,
+    ),
+    (
+        SLoginPluginResponse::PACKET_ID,
+        read_packet::<SLoginPluginResponse>,
+    ),
+    (SLoginAcknowledged::PACKET_ID, read_empty),
+    (
+        SLoginCookieResponse::PACKET_ID,
+        read_packet::<SLoginCookieResponse>,
+    ),
+];
+
+const CONFIG_PACKETS: &[(PacketId, PacketReader)] = &[
+    (
+        SClientInformationConfig::PACKET_ID,
+        read_packet::<SClientInformationConfig>,
+    ),
+    (SPluginMessage::PACKET_ID, read_packet::<SPluginMessage>),
+    (SAcknowledgeFinishConfig::PACKET_ID, read_empty),
+    (SKnownPacks::PACKET_ID, read_packet::<SKnownPacks>),
+    (
+        SConfigCookieResponse::PACKET_ID,
+        read_packet::<SConfigCookieResponse>,
+    ),
+    (
+        SConfigResourcePack::PACKET_ID,
+        read_packet::<SConfigResourcePack>,
+    ),
+];
+
+const PLAY_PACKETS: &[(PacketId, PacketReader)] = &[
+    (SConfirmTeleport::PACKET_ID, read_packet::<SConfirmTeleport>),
+    (
+        SChangeDifficulty::PACKET_ID,
+        read_packet::<SChangeDifficulty>,
+    ),
+    (SChangeGameMode::PACKET_ID, read_packet::<SChangeGameMode>),
+    (SChatCommand::PACKET_ID, read_packet::<SChatCommand>),
+    (SChatMessage::PACKET_ID, read_packet::<SChatMessage>),
+    (SChunkBatch::PACKET_ID, read_packet::<SChunkBatch>),
+    (SClickSlot::PACKET_ID, read_packet::<SClickSlot>),
+    (SClientCommand::PACKET_ID, read_packet::<SClientCommand>),
+    (
+        SClientInformationPlay::PACKET_ID,
+        read_packet::<SClientInformationPlay>,
+    ),
+    (SClientTickEnd::PACKET_ID, read_empty),
+    (SCloseContainer::PACKET_ID, read_packet::<SCloseContainer>),
+    (
+        SCommandSuggestion::PACKET_ID,
+        read_packet::<SCommandSuggestion>,
+    ),
+    (
+        SConfigurationAcknowledged::PACKET_ID,
+        read_packet::<SConfigurationAcknowledged>,
+    ),
+    (
+        SContainerButtonClick::PACKET_ID,
+        read_packet::<SContainerButtonClick>,
+    ),
+    (SCookieResponse::PACKET_ID, read_packet::<SCookieResponse>),
+    (SCustomPayload::PACKET_ID, read_packet::<SCustomPayload>),
+    (
+        SDebugSubscriptionRequest::PACKET_ID,
+        read_packet::<SDebugSubscriptionRequest>,
+    ),
+    (SEditBook::PACKET_ID, read_packet::<SEditBook>),
+    (SInteract::PACKET_ID, read_packet::<SInteract>),
+    (SKeepAlive::PACKET_ID, read_packet::<SKeepAlive>),
+    (SLockDifficulty::PACKET_ID, read_packet::<SLockDifficulty>),
+    (SMoveVehicle::PACKET_ID, read_packet::<SMoveVehicle>),
+    (SPaddleBoat::PACKET_ID, read_packet::<SPaddleBoat>),
+    (
+        SPickItemFromBlock::PACKET_ID,
+        read_packet::<SPickItemFromBlock>,
+    ),
+    (
+        SPickItemFromEntity::PACKET_ID,
+        read_packet::<SPickItemFromEntity>,
+    ),
+    (SPlayPingRequest::PACKET_ID, read_packet::<SPlayPingRequest>),
+    (SPlaceRecipe::PACKET_ID, read_packet::<SPlaceRecipe>),
+    (SPlayerAbilities::PACKET_ID, read_packet::<SPlayerAbilities>),
+    (SPlayerAction::PACKET_ID, read_packet::<SPlayerAction>),
+    (SPlayerCommand::PACKET_ID, read_packet::<SPlayerCommand>),
+    (SSetPlayerGround::PACKET_ID, read_packet::<SSetPlayerGround>),
+    (SPlayerInput::PACKET_ID, read_packet::<SPlayerInput>),
+    (SPlayerLoaded::PACKET_ID, read_packet::<SPlayerLoaded>),
+    (SPlayerPosition::PACKET_ID, read_packet::<SPlayerPosition>),
+    (
+        SPlayerPositionRotation::PACKET_ID,
+        read_packet::<SPlayerPositionRotation>,
+    ),
+    (SPlayerRotation::PACKET_ID, read_packet::<SPlayerRotation>),
+    (SPlayerSession::PACKET_ID, read_packet::<SPlayerSession>),
+    (SPong::PACKET_ID, read_packet::<SPong>),
+    (
+        SRecipeBookChangeSettings::PACKET_ID,
+        read_packet::<SRecipeBookChangeSettings>,
+    ),
+    (
+        SRecipeBookSeenRecipe::PACKET_ID,
+        read_packet::<SRecipeBookSeenRecipe>,
+    ),
+    (SRenameItem::PACKET_ID, read_packet::<SRenameItem>),
+    (
+        SSeenAdvancements::PACKET_ID,
+        read_packet::<SSeenAdvancements>,
+    ),
+    (SSelectTrade::PACKET_ID, read_packet::<SSelectTrade>),
+    (SSetBeacon::PACKET_ID, read_packet::<SSetBeacon>),
+    (SSetCommandBlock::PACKET_ID, read_packet::<SSetCommandBlock>),
+    (SSetCreativeSlot::PACKET_ID, read_packet::<SSetCreativeSlot>),
+    (SSetHeldItem::PACKET_ID, read_packet::<SSetHeldItem>),
+    (SSwingArm::PACKET_ID, read_packet::<SSwingArm>),
+    (
+        STeleportToEntity::PACKET_ID,
+        read_packet::<STeleportToEntity>,
+    ),
+    (SUpdateSign::PACKET_ID, read_packet::<SUpdateSign>),
+    (SUseItem::PACKET_ID, read_packet::<SUseItem>),
+    (SUseItemOn::PACKET_ID, read_packet::<SUseItemOn>),
+];
+
+/// Reads a serverbound packet with the definition for its ID in the protocol it was sent with.
+///
+/// Clientbound packets only have writers, so they are only checked to be framed correctly.
+fn read_serverbound(
+    state: ConnectionState,
+    protocol: u32,
+    packet: &RawPacket,
+) -> Result<(), ReadingError> {
+    let packets = match state {
+        ConnectionState::HandShake => {
+            return if packet.id == 0 {
+                read_packet::<SHandShake>(&packet.payload)
+            } else {
+                Err(ReadingError::Message(format!(
+                    "Unknown handshake packet {}",
+                    packet.id
+                )))
+            };
+        }
+        ConnectionState::Status => STATUS_PACKETS,
+        ConnectionState::Login | ConnectionState::Transfer => LOGIN_PACKETS,
+        ConnectionState::Config => CONFIG_PACKETS,
+        ConnectionState::Play => PLAY_PACKETS,
+    };
+    let version = MinecraftVersion::from_protocol(protocol);
+    let (_, read) = packets
+        .iter()
+        .find(|(id, _)| id.to_id(version) == packet.id)
+        .ok_or_else(|| ReadingError::Message(format!("Unknown {state:?} packet {}", packet.id)))?;
+    read(&packet.payload)
+}
+
+/// Feeds every packet of a capture through the network encoder and decoder, with compression
+/// enabled if `compression_threshold` is set, and reads every serverbound packet with its
+/// definition. Returns the decoded packets.
+///
+/// Fails on the first packet that can't be framed and read back unchanged, or that doesn't
+/// match its definition, so captures of past protocol issues can be kept as regression tests.
+pub async fn replay<R: Read>(
+    capture: CaptureReader<R>,
+    compression_threshold: Option<CompressionThreshold>,
+) -> Result<Vec<(CapturedPacket, RawPacket)>, ReplayError> {
+    let mut replayed = Vec::new();
+    for (index, packet) in capture.enumerate() {
+        let packet = packet?;
+        let framed = frame(packet.data.clone(), compression_threshold)
+            .await
+            .map_err(|error| ReplayError::Encode { index, error })?;
+
+        let mut decoder = TCPNetworkDecoder::new(framed.as_slice());
+        if let Some(threshold) = compression_threshold {
+            decoder.set_compression(threshold);
+        }
+        let read_back = decoder
+            .get_raw_packet()
+            .await
+            .map_err(|error| ReplayError::Decode { index, error })?;
+        let expected = packet.raw()?;
+        if read_back.id != expected.id || read_back.payload != expected.payload {
+            return Err(ReplayError::Mismatch(index));
+        }
+        if packet.direction == CaptureDirection::Serverbound {
+            read_serverbound(packet.state, packet.protocol, &read_back)
+                .map_err(|error| ReplayError::Read { index, error })?;
+        }
+        replayed.push((packet, read_back));
+    }
+    Ok(replayed)
+}
+
+async fn frame(
+    packet: Bytes,
+    compression_threshold: Option<CompressionThreshold>,
+) -> Result<Vec<u8>, PacketEncodeError> {
+    let mut framed = Vec::new();
+    let mut encoder = TCPNetworkEncoder::new(&mut framed);
+    if let Some(threshold) = compression_threshold {
+        encoder.set_compression((threshold, 6));
+    }
+    encoder.write_packet(packet).await?;
+    Ok(framed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn captures_replay_through_the_decoder() {
+        let mut capture = CaptureWriter::new(Vec::new()).unwrap();
+        // Handshake: protocol 773, "localhost", port 25565, login intent
+        let handshake = b"\x00\x85\x06\x09localhost\x63\xDD\x02";
+        capture
+            .write(
+                CaptureDirection::Serverbound,
+                ConnectionState::HandShake,
+                773,
+                handshake,
+            )
+            .unwrap();
+        let mut large = vec![0x2C];
+        large.extend(std::iter::repeat_n(7, 1000));
+        capture
+            .write(
+                CaptureDirection::Clientbound,
+                ConnectionState::Play,
+                773,
+                &large,
+            )
+            .unwrap();
+        // Simulates a capture cut off in the middle of a record
+        let mut bytes = capture.writer;
+        bytes.extend_from_slice(&[0, 0, 0]);
+
+        let reader = CaptureReader::new(bytes.as_slice()).unwrap();
+        assert!(matches!(
+            replay(reader, Some(256)).await,
+            Err(ReplayError::Capture(CaptureError::Io(_)))
+        ));
+
+        bytes.truncate(bytes.len() - 3);
+        let Ok(replayed) = replay(CaptureReader::new(bytes.as_slice()).unwrap(), Some(256)).await
+        else {
+            panic!("The capture should replay");
+        };
+        assert_eq!(replayed.len(), 2);
+        assert_eq!(replayed[0].0.state, ConnectionState::HandShake);
+        assert_eq!(replayed[0].1.id, 0);
+        assert_eq!(&replayed[0].1.payload[..], &handshake[1..]);
+        assert_eq!(replayed[1].0.direction, CaptureDirection::Clientbound);
+        assert_eq!(replayed[1].1.payload.len(), 1000);
+
+        assert!(matches!(
+            CaptureReader::new(&b"\x10\x00"[..]),
+            Err(CaptureError::NotACapture)
+        ));
+    }
+
+    async fn replay_one(state: ConnectionState, packet: &[u8]) -> Result<(), ReplayError> {
+        let mut capture = CaptureWriter::new(Vec::new()).unwrap();
+        capture
+            .write(CaptureDirection::Serverbound, state, 773, packet)
+            .unwrap();
+        replay(CaptureReader::new(capture.writer.as_slice()).unwrap(), None)
+            .await
+            .map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn serverbound_packets_are_read_with_their_definition() {
+        let version = MinecraftVersion::from_protocol(773);
+        let teleport = SConfirmTeleport::PACKET_ID.to_id(version) as u8;
+        let status = SStatusRequest::PACKET_ID.to_id(version) as u8;
+
+        assert!(
+            replay_one(ConnectionState::Play, &[teleport, 5])
+                .await
+                .is_ok()
+        );
+        assert!(replay_one(ConnectionState::Status, &[status]).await.is_ok());
+
+        // Missing data, data left over and packets the protocol doesn't know
+        for (state, packet) in [
+            (ConnectionState::Play, vec![teleport]),
+            (ConnectionState::Play, vec![teleport, 5, 0]),
+            (ConnectionState::Status, vec![status, 1]),
+            (ConnectionState::Play, vec![0x7F]),
+            (ConnectionState::HandShake, vec![1]),
+        ] {
+            assert!(matches!(
+                replay_one(state, &packet).await,
+                Err(ReplayError::Read { index: 0, .. })
+            ));
+        }
+    }
+}
@@ -6,17 +6,22 @@ use pumpkin_macros::java_packet;
 use pumpkin_nbt::END_ID;
 use pumpkin_util::math::position::get_local_cord;
 use pumpkin_util::version::MinecraftVersion;
+use pumpkin_world::chunk::ChunkData;
 use pumpkin_world::chunk::format::LightContainer;
-use pumpkin_world::chunk::{ChunkData, palette::NetworkPalette};
+use pumpkin_world::chunk::palette::{BlockPalette, NetworkPalette};
 use std::io::Write;
+use std::sync::Arc;
 
 /// Sent by the server to provide the client with the full data for a chunk.
 ///
 /// This includes heightmaps, the actual block and biome data (organized into sections),
 /// block entities (like signs or chests), and the light level information for both
 /// sky and block light.
+///
+/// The second field replaces the block sections of the chunk when set, e.g. with ores hidden by
+/// anti-X-ray.
 #[java_packet(PLAY_LEVEL_CHUNK_WITH_LIGHT)]
-pub struct CChunkData<'a>(pub &'a ChunkData);
+pub struct CChunkData<'a>(pub &'a ChunkData, pub Option<&'a [Arc<BlockPalette>]>);
 
 impl ClientPacket for CChunkData<'_> {
     #[expect(clippy::too_many_lines)]
@@ -50,7 +55,8 @@ impl ClientPacket for CChunkData<'_> {
 
         {
             let mut blocks_and_biomes_buf = Vec::new();
-            let block_sections = self.0.section.block_sections.read().unwrap();
+            let chunk_block_sections = self.0.section.block_sections.read().unwrap();
+            let block_sections = self.1.unwrap_or(&chunk_block_sections);
             let biome_sections = self.0.section.biome_sections.read().unwrap();
 
             for (block_palette, biome_palette) in block_sections.iter().zip(biome_sections.iter()) {
@@ -122,8 +128,12 @@ impl ClientPacket for CChunkData<'_> {
         };
 
         let block_entities = self.0.block_entities.lock().unwrap();
+        let block_entities: Vec<_> = block_entities
+            .values()
+            .filter(|block_entity| block_entity.is_client_side())
+            .collect();
         write.write_var_int(&VarInt(block_entities.len() as i32))?;
-        for block_entity in block_entities.values() {
+        for block_entity in block_entities {
             let pos = block_entity.get_position();
             let local_xz = ((get_local_cord(pos.0.x) & 0xF) << 4) | (get_local_cord(pos.0.z) & 0xF);
 
@@ -138,58 +148,73 @@ impl ClientPacket for CChunkData<'_> {
             }
         }
 
-        {
-            // todo: these masks are 64 bits long, we should use a bitset instead of a u64
-            //  in higher maps
-            let mut sky_light_empty_mask = 0;
-            let mut block_light_empty_mask = 0;
-            let mut sky_light_mask = 0;
-            let mut block_light_mask = 0;
-            for light_index in 0..self.0.light_engine.sky_light.len() {
-                if let LightContainer::Full(_) = &self.0.light_engine.sky_light[light_index] {
-                    sky_light_mask |= 1 << light_index;
-                } else {
-                    sky_light_empty_mask |= 1 << light_index;
-                }
+        let light = self.0.light_engine.read().unwrap();
+        let sections: Vec<_> = light
+            .sky_light
+            .iter()
+            .zip(light.block_light.iter())
+            .enumerate()
+            .map(|(index, (sky, block))| (index, sky, block))
+            .collect();
+        write_light_data(&mut write, self.0.section.count, &sections)?;
+        Ok(())
+    }
+}
 
-                if let LightContainer::Full(_) = &self.0.light_engine.block_light[light_index] {
-                    block_light_mask |= 1 << light_index;
-                } else {
-                    block_light_empty_mask |= 1 << light_index;
-                }
-            }
-            // Sky Light Mask
-            // All of the chunks, this is not optimal and uses way more data than needed but will be
-            // overhauled with a full lighting system.
-
-            // Sky Light Mask
-            write.write_bitset(&BitSet(Box::new([sky_light_mask])))?;
-            // Block Light Mask
-            write.write_bitset(&BitSet(Box::new([block_light_mask])))?;
-            // Empty Sky Light Mask
-            write.write_bitset(&BitSet(Box::new([sky_light_empty_mask])))?;
-            // Empty Block Light Mask
-            write.write_bitset(&BitSet(Box::new([block_light_empty_mask])))?;
-
-            let light_data_size: VarInt = LightContainer::ARRAY_SIZE.try_into().unwrap();
-            // Sky light
-            write.write_var_int(&VarInt(sky_light_mask.count_ones() as i32))?;
-            for light_index in 0..self.0.light_engine.sky_light.len() {
-                if let LightContainer::Full(data) = &self.0.light_engine.sky_light[light_index] {
-                    write.write_var_int(&light_data_size)?;
-                    write.write_slice(data)?;
-                }
+/// Writes the sky and block light of the given sections, as `(index, sky, block)`. Sections left
+/// out are kept as they are by the client.
+///
+/// The client also keeps the light of the sections just below and above the world, so the
+/// sections are shifted up by one in the masks.
+pub(crate) fn write_light_data(
+    write: &mut impl Write,
+    section_count: usize,
+    sections: &[(usize, &LightContainer, &LightContainer)],
+) -> Result<(), WritingError> {
+    let words = (section_count + 2).div_ceil(64);
+    let mut sky_mask = vec![0; words];
+    let mut block_mask = vec![0; words];
+    let mut empty_sky_mask = vec![0; words];
+    let mut empty_block_mask = vec![0; words];
+    let mut sky_arrays = Vec::new();
+    let mut block_arrays = Vec::new();
+    for &(index, sky, block) in sections {
+        let (word, bit) = ((index + 1) / 64, (index + 1) % 64);
+        for (light, mask, empty_mask, arrays) in [
+            (sky, &mut sky_mask, &mut empty_sky_mask, &mut sky_arrays),
+            (
+                block,
+                &mut block_mask,
+                &mut empty_block_mask,
+                &mut block_arrays,
+            ),
+        ] {
+            if matches!(light, LightContainer::Empty(0)) {
+                empty_mask[word] |= 1 << bit;
+            } else {
+                mask[word] |= 1 << bit;
+                arrays.push(light);
             }
+        }
+    }
 
-            // Block Light
-            write.write_var_int(&VarInt(block_light_mask.count_ones() as i32))?;
-            for light_index in 0..self.0.light_engine.block_light.len() {
-                if let LightContainer::Full(data) = &self.0.light_engine.block_light[light_index] {
-                    write.write_var_int(&light_data_size)?;
-                    write.write_slice(data)?;
+    write.write_bitset(&BitSet(sky_mask.into_boxed_slice()))?;
+    write.write_bitset(&BitSet(block_mask.into_boxed_slice()))?;
+    write.write_bitset(&BitSet(empty_sky_mask.into_boxed_slice()))?;
+    write.write_bitset(&BitSet(empty_block_mask.into_boxed_slice()))?;
+
+    let light_data_size: VarInt = LightContainer::ARRAY_SIZE.try_into().unwrap();
+    for arrays in [sky_arrays, block_arrays] {
+        write.write_var_int(&VarInt(arrays.len() as i32))?;
+        for light in arrays {
+            write.write_var_int(&light_data_size)?;
+            match light {
+                LightContainer::Full(data) => write.write_slice(data)?,
+                LightContainer::Empty(level) => {
+                    write.write_slice(&[*level << 4 | *level; LightContainer::ARRAY_SIZE])?;
                 }
             }
         }
-        Ok(())
     }
+    Ok(())
 }
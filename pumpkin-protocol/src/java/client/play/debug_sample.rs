@@ -0,0 +1,27 @@
+use pumpkin_data::packet::clientbound::PLAY_DEBUG_SAMPLE;
+use pumpkin_macros::java_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Feeds a chart of the debug screen to clients that subscribed to it.
+#[derive(Serialize)]
+#[java_packet(PLAY_DEBUG_SAMPLE)]
+pub struct CDebugSample<'a> {
+    pub sample: &'a [i64],
+    pub sample_type: VarInt,
+}
+
+impl<'a> CDebugSample<'a> {
+    /// The type of tick time samples, which hold the nanoseconds of the whole tick, of the tick
+    /// itself, of scheduled tasks and of idling.
+    pub const TICK_TIME: VarInt = VarInt(0);
+
+    #[must_use]
+    pub const fn new(sample: &'a [i64], sample_type: VarInt) -> Self {
+        Self {
+            sample,
+            sample_type,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::io::Write;
+
+use pumpkin_data::packet::clientbound::PLAY_LIGHT_UPDATE;
+use pumpkin_macros::java_packet;
+use pumpkin_util::version::MinecraftVersion;
+use pumpkin_world::chunk::ChunkData;
+use pumpkin_world::chunk::format::LightContainer;
+
+use super::chunk_data::write_light_data;
+use crate::{ClientPacket, VarInt, WritingError, ser::NetworkWriteExt};
+
+/// Sent when the light of a chunk the client already has changes, with the light of the changed
+/// sections only.
+#[java_packet(PLAY_LIGHT_UPDATE)]
+pub struct CLightUpdate {
+    pub chunk_x: VarInt,
+    pub chunk_z: VarInt,
+    section_count: usize,
+    /// The sky and block light of the changed sections, by index in the chunk.
+    sections: Vec<(usize, LightContainer, LightContainer)>,
+}
+
+impl CLightUpdate {
+    /// Copies the current light of `sections` of `chunk`.
+    #[must_use]
+    pub fn new(chunk: &ChunkData, sections: impl IntoIterator<Item = usize>) -> Self {
+        let light = chunk.light_engine.read().unwrap();
+        let sections = sections
+            .into_iter()
+            .filter_map(|index| {
+                Some((
+                    index,
+                    light.sky_light.get(index)?.clone(),
+                    light.block_light.get(index)?.clone(),
+                ))
+            })
+            .collect();
+        Self {
+            chunk_x: VarInt(chunk.x),
+            chunk_z: VarInt(chunk.z),
+            section_count: chunk.section.count,
+            sections,
+        }
+    }
+}
+
+impl ClientPacket for CLightUpdate {
+    fn write_packet_data(
+        &self,
+        write: impl Write,
+        _version: &MinecraftVersion,
+    ) -> Result<(), WritingError> {
+        let mut write = write;
+        write.write_var_int(&self.chunk_x)?;
+        write.write_var_int(&self.chunk_z)?;
+        let sections: Vec<_> = self
+            .sections
+            .iter()
+            .map(|(index, sky, block)| (*index, sky, block))
+            .collect();
+        write_light_data(&mut write, self.section_count, &sections)
+    }
+}
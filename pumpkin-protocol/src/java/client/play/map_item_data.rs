@@ -0,0 +1,100 @@
+use std::io::Write;
+
+use pumpkin_data::packet::clientbound::PLAY_MAP_ITEM_DATA;
+use pumpkin_macros::java_packet;
+use pumpkin_util::{text::TextComponent, version::MinecraftVersion};
+
+use crate::{ClientPacket, VarInt, WritingError, ser::NetworkWriteExt};
+
+/// Updates the contents of a filled map on the client.
+///
+/// Maps are 128x128 pixels. The server only sends the decorations and the rectangle of
+/// pixels that changed since the last update.
+#[java_packet(PLAY_MAP_ITEM_DATA)]
+pub struct CMapItemData<'a> {
+    /// The id of the map, as stored in the `map_id` component of the item.
+    pub map_id: VarInt,
+    /// How zoomed out the map is, from 0 (1 block per pixel) to 4 (16 blocks per pixel).
+    pub scale: i8,
+    /// Whether the map was locked in a cartography table.
+    pub locked: bool,
+    /// The markers on the map, or `None` to keep the ones the client has.
+    pub decorations: Option<&'a [MapDecoration]>,
+    /// The pixels that changed, or `None` if none did.
+    pub colors: Option<MapColorPatch<'a>>,
+}
+
+impl<'a> CMapItemData<'a> {
+    #[must_use]
+    pub const fn new(
+        map_id: VarInt,
+        scale: i8,
+        locked: bool,
+        decorations: Option<&'a [MapDecoration]>,
+        colors: Option<MapColorPatch<'a>>,
+    ) -> Self {
+        Self {
+            map_id,
+            scale,
+            locked,
+            decorations,
+            colors,
+        }
+    }
+}
+
+/// A marker on a map, like a player or a banner.
+pub struct MapDecoration {
+    /// The id of the decoration type in the `map_decoration_type` registry.
+    pub decoration_type: VarInt,
+    /// The position on the map, from -128 (left edge) to 127 (right edge).
+    pub x: i8,
+    /// The position on the map, from -128 (top edge) to 127 (bottom edge).
+    pub z: i8,
+    /// The rotation in steps of 22.5 degrees, from 0 to 15.
+    pub rotation: i8,
+    /// The name shown below the marker.
+    pub name: Option<TextComponent>,
+}
+
+/// A rectangle of map pixels, stored row by row.
+pub struct MapColorPatch<'a> {
+    pub start_x: u8,
+    pub start_z: u8,
+    pub width: u8,
+    pub height: u8,
+    pub colors: &'a [u8],
+}
+
+impl ClientPacket for CMapItemData<'_> {
+    fn write_packet_data(
+        &self,
+        write: impl Write,
+        _version: &MinecraftVersion,
+    ) -> Result<(), WritingError> {
+        let mut write = write;
+
+        write.write_var_int(&self.map_id)?;
+        write.write_i8(self.scale)?;
+        write.write_bool(self.locked)?;
+        write.write_option(&self.decorations, |p, decorations| {
+            p.write_list(decorations, |p, decoration| {
+                p.write_var_int(&decoration.decoration_type)?;
+                p.write_i8(decoration.x)?;
+                p.write_i8(decoration.z)?;
+                p.write_i8(decoration.rotation)?;
+                p.write_option(&decoration.name, |p, name| p.write_slice(&name.encode()))
+            })
+        })?;
+        match &self.colors {
+            Some(patch) => {
+                write.write_u8(patch.width)?;
+                write.write_u8(patch.height)?;
+                write.write_u8(patch.start_x)?;
+                write.write_u8(patch.start_z)?;
+                write.write_list(patch.colors, |p, color| p.write_u8(*color))
+            }
+            None => write.write_u8(0),
+        }
+    }
+}
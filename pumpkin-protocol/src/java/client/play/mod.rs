@@ -20,6 +20,7 @@ mod commands;
 mod cookie_request;
 mod cooldown;
 mod damage_event;
+mod debug_sample;
 mod disconnect;
 mod disguised_chat_message;
 mod display_objective;
@@ -36,7 +37,9 @@ mod hurt_animation;
 mod initialize_world_border;
 mod keep_alive;
 mod level_event;
+mod light_update;
 mod login;
+mod map_item_data;
 mod multi_block_update;
 mod open_book;
 mod open_screen;
@@ -100,6 +103,7 @@ mod update_entity_rot;
 mod update_mob_effect;
 mod update_objectives;
 mod update_score;
+mod update_teams;
 mod worldevent;
 
 pub use acknowledge_block::*;
@@ -124,6 +128,7 @@ pub use commands::*;
 pub use cookie_request::*;
 pub use cooldown::*;
 pub use damage_event::*;
+pub use debug_sample::*;
 pub use disconnect::*;
 pub use disguised_chat_message::*;
 pub use display_objective::*;
@@ -140,7 +145,9 @@ pub use hurt_animation::*;
 pub use initialize_world_border::*;
 pub use keep_alive::*;
 pub use level_event::*;
+pub use light_update::*;
 pub use login::*;
+pub use map_item_data::*;
 pub use multi_block_update::*;
 pub use open_book::*;
 pub use open_screen::*;
@@ -204,4 +211,5 @@ pub use update_entity_rot::*;
 pub use update_mob_effect::*;
 pub use update_objectives::*;
 pub use update_score::*;
+pub use update_teams::*;
 pub use worldevent::*;
@@ -0,0 +1,172 @@
+use std::io::Write;
+
+use pumpkin_data::packet::clientbound::PLAY_SET_PLAYER_TEAM;
+use pumpkin_macros::java_packet;
+use pumpkin_util::{
+    text::{TextComponent, color::NamedColor},
+    version::MinecraftVersion,
+};
+
+use crate::{ClientPacket, VarInt, WritingError, ser::NetworkWriteExt};
+
+/// Creates, changes or removes a scoreboard team, or changes who is on it.
+#[java_packet(PLAY_SET_PLAYER_TEAM)]
+pub struct CUpdateTeams<'a> {
+    pub team_name: &'a str,
+    pub action: TeamAction<'a>,
+}
+
+impl<'a> CUpdateTeams<'a> {
+    #[must_use]
+    pub const fn new(team_name: &'a str, action: TeamAction<'a>) -> Self {
+        Self { team_name, action }
+    }
+}
+
+/// What a [`CUpdateTeams`] does to a team. Members are player names or entity UUIDs.
+pub enum TeamAction<'a> {
+    Create {
+        info: TeamInfo<'a>,
+        members: &'a [String],
+    },
+    Remove,
+    Update(TeamInfo<'a>),
+    AddMembers(&'a [String]),
+    RemoveMembers(&'a [String]),
+}
+
+impl TeamAction<'_> {
+    const fn id(&self) -> u8 {
+        match self {
+            Self::Create { .. } => 0,
+            Self::Remove => 1,
+            Self::Update(_) => 2,
+            Self::AddMembers(_) => 3,
+            Self::RemoveMembers(_) => 4,
+        }
+    }
+}
+
+pub struct TeamInfo<'a> {
+    pub display_name: &'a TextComponent,
+    pub friendly_fire: bool,
+    pub see_friendly_invisibles: bool,
+    pub name_tag_visibility: NameTagVisibility,
+    pub collision_rule: CollisionRule,
+    /// `None` resets the color.
+    pub color: Option<NamedColor>,
+    pub prefix: &'a TextComponent,
+    pub suffix: &'a TextComponent,
+}
+
+impl TeamInfo<'_> {
+    fn write(
+        &self,
+        write: &mut impl Write,
+        version: &MinecraftVersion,
+    ) -> Result<(), WritingError> {
+        write.write_slice(&self.display_name.encode())?;
+        let mut flags = 0;
+        if self.friendly_fire {
+            flags |= 0x01;
+        }
+        if self.see_friendly_invisibles {
+            flags |= 0x02;
+        }
+        write.write_u8(flags)?;
+        // Older clients take the rules by name
+        if *version >= MinecraftVersion::V_1_21_5 {
+            write.write_var_int(&VarInt(self.name_tag_visibility as i32))?;
+            write.write_var_int(&VarInt(self.collision_rule as i32))?;
+        } else {
+            write.write_string(self.name_tag_visibility.name())?;
+            write.write_string(self.collision_rule.name())?;
+        }
+        // 21 is the reset formatting
+        write.write_var_int(&VarInt(self.color.map_or(21, |color| color as i32)))?;
+        write.write_slice(&self.prefix.encode())?;
+        write.write_slice(&self.suffix.encode())
+    }
+}
+
+impl ClientPacket for CUpdateTeams<'_> {
+    fn write_packet_data(
+        &self,
+        write: impl Write,
+        version: &MinecraftVersion,
+    ) -> Result<(), WritingError> {
+        let mut write = write;
+
+        write.write_string(self.team_name)?;
+        write.write_u8(self.action.id())?;
+        match &self.action {
+            TeamAction::Create { info, members } => {
+                info.write(&mut write, version)?;
+                write.write_list(members, |p, v| p.write_string(v))
+            }
+            TeamAction::Remove => Ok(()),
+            TeamAction::Update(info) => info.write(&mut write, version),
+            TeamAction::AddMembers(members) | TeamAction::RemoveMembers(members) => {
+                write.write_list(members, |p, v| p.write_string(v))
+            }
+        }
+    }
+}
+
+/// Whose name tags the members of a team see.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum NameTagVisibility {
+    #[default]
+    Always,
+    Never,
+    HideForOtherTeams,
+    HideForOwnTeam,
+}
+
+impl NameTagVisibility {
+    pub const ALL: [Self; 4] = [
+        Self::Always,
+        Self::Never,
+        Self::HideForOtherTeams,
+        Self::HideForOwnTeam,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Never => "never",
+            Self::HideForOtherTeams => "hideForOtherTeams",
+            Self::HideForOwnTeam => "hideForOwnTeam",
+        }
+    }
+}
+
+/// Which entities the members of a team push around.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CollisionRule {
+    #[default]
+    Always,
+    Never,
+    PushOtherTeams,
+    PushOwnTeam,
+}
+
+impl CollisionRule {
+    pub const ALL: [Self; 4] = [
+        Self::Always,
+        Self::Never,
+        Self::PushOtherTeams,
+        Self::PushOwnTeam,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Never => "never",
+            Self::PushOtherTeams => "pushOtherTeams",
+            Self::PushOwnTeam => "pushOwnTeam",
+        }
+    }
+}
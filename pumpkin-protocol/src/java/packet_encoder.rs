@@ -1,9 +1,12 @@
+use std::io::{self, IoSlice};
+
 use aes::cipher::KeyIvInit;
-use async_compression::{Level, tokio::write::ZlibEncoder};
-use bytes::Bytes;
+use bytes::{BufMut, Bytes, BytesMut};
+use flate2::{Compress, Compression, FlushCompress, Status};
 use thiserror::Error;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 
+use crate::buffer_pool::PACKET_BUFFERS;
 use crate::{
     Aes128Cfb8Enc, CompressionLevel, CompressionThreshold, MAX_PACKET_DATA_SIZE, MAX_PACKET_SIZE,
     PacketEncodeError, StreamEncryptor, VarInt,
@@ -44,6 +47,30 @@ impl<W: AsyncWrite + Unpin> AsyncWrite for EncryptionWriter<W> {
         }
     }
 
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> std::task::Poll<Result<usize, std::io::Error>> {
+        match self.get_mut() {
+            Self::Encrypt(writer) => {
+                let writer = std::pin::Pin::new(writer);
+                writer.poll_write_vectored(cx, bufs)
+            }
+            Self::None(writer) => {
+                let writer = std::pin::Pin::new(writer);
+                writer.poll_write_vectored(cx, bufs)
+            }
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Encrypt(writer) => writer.is_write_vectored(),
+            Self::None(writer) => writer.is_write_vectored(),
+        }
+    }
+
     fn poll_flush(
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
@@ -77,13 +104,22 @@ impl<W: AsyncWrite + Unpin> AsyncWrite for EncryptionWriter<W> {
     }
 }
 
+/// Packets shorter than this are copied next to their frame header, so that runs of small
+/// packets are written as one slice.
+const COALESCE_THRESHOLD: usize = 512;
+/// Slices handed to a single vectored write.
+const MAX_WRITE_SLICES: usize = 64;
+
 /// Encoder: Server -> Client
 /// Supports `ZLib` endecoding/compression
 /// Supports Aes128 Encryption
 pub struct TCPNetworkEncoder<W: AsyncWrite + Unpin> {
     writer: EncryptionWriter<W>,
-    // compression and compression threshold
-    compression: Option<(CompressionThreshold, CompressionLevel)>,
+    // compression threshold and the compressor, which is reused between packets
+    compression: Option<(CompressionThreshold, Compress)>,
+    /// The frames of the flush in progress, pointing into a pooled buffer or at the packet data
+    /// itself. Kept to reuse its allocation.
+    frames: Vec<Bytes>,
 }
 
 impl<W: AsyncWrite + Unpin> TCPNetworkEncoder<W> {
@@ -91,14 +127,13 @@ impl<W: AsyncWrite + Unpin> TCPNetworkEncoder<W> {
         Self {
             writer: EncryptionWriter::None(writer),
             compression: None,
+            frames: Vec::new(),
         }
     }
 
-    pub const fn set_compression(
-        &mut self,
-        compression_info: (CompressionThreshold, CompressionLevel),
-    ) {
-        self.compression = Some(compression_info);
+    pub fn set_compression(&mut self, compression_info: (CompressionThreshold, CompressionLevel)) {
+        let (threshold, level) = compression_info;
+        self.compression = Some((threshold, Compress::new(Compression::new(level), true)));
     }
 
     /// NOTE: Encryption can only be set; a minecraft stream cannot go back to being unencrypted
@@ -110,7 +145,13 @@ impl<W: AsyncWrite + Unpin> TCPNetworkEncoder<W> {
         take_mut::take(&mut self.writer, |encoder| encoder.upgrade(cipher));
     }
 
-    /// Appends a Clientbound `ClientPacket` to the internal buffer and applies compression when needed.
+    /// Writes a single Clientbound packet and flushes it, see [`Self::write_packets`].
+    pub async fn write_packet(&mut self, packet_data: Bytes) -> Result<(), PacketEncodeError> {
+        self.write_packets(std::slice::from_ref(&packet_data)).await
+    }
+
+    /// Frames Clientbound packets, applying compression when needed, and writes them with as
+    /// few vectored writes as possible before flushing once.
     ///
     /// If compression is enabled and the packet size exceeds the threshold, the packet is compressed.
     /// The packet is prefixed with its length and, if compressed, the uncompressed data length.
@@ -140,10 +181,42 @@ impl<W: AsyncWrite + Unpin> TCPNetworkEncoder<W> {
     /// -   `Data Length`: (Only present in compressed packets) The length of the uncompressed `Packet ID` and `Data`.
     /// -   `Packet ID`: The ID of the packet.
     /// -   `Data`: The packet's data.
-    pub async fn write_packet(&mut self, packet_data: Bytes) -> Result<(), PacketEncodeError> {
-        // We need to know the length of the compressed buffer and serde is not async :(
-        // We need to write to a buffer here 😔
+    ///
+    /// Nothing is written if any of the packets can't be framed.
+    pub async fn write_packets(&mut self, packets: &[Bytes]) -> Result<(), PacketEncodeError> {
+        let mut buffer = PACKET_BUFFERS.take();
+        let result = self.write_frames(packets, &mut buffer).await;
+        self.frames.clear();
+        PACKET_BUFFERS.put(buffer);
+        result
+    }
+
+    async fn write_frames(
+        &mut self,
+        packets: &[Bytes],
+        buffer: &mut BytesMut,
+    ) -> Result<(), PacketEncodeError> {
+        for packet in packets {
+            self.frame(packet, buffer)?;
+        }
+        push_coalesced(&mut self.frames, buffer);
 
+        write_all_vectored(&mut self.writer, &self.frames)
+            .await
+            .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
+        self.writer
+            .flush()
+            .await
+            .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Appends the frame of `packet_data` to `buffer`, or to the frames if it is large.
+    fn frame(
+        &mut self,
+        packet_data: &Bytes,
+        buffer: &mut BytesMut,
+    ) -> Result<(), PacketEncodeError> {
         let data_len = packet_data.len();
         if data_len > MAX_PACKET_DATA_SIZE {
             return Err(PacketEncodeError::TooLong(data_len));
@@ -155,118 +228,120 @@ impl<W: AsyncWrite + Unpin> TCPNetworkEncoder<W> {
             ))
         })?;
 
-        if let Some((compression_threshold, compression_level)) = self.compression {
-            if data_len >= compression_threshold {
+        match &mut self.compression {
+            Some((compression_threshold, compressor)) if data_len >= *compression_threshold => {
                 // Pushed before data:
                 // Length of (Data Length) + length of compressed (Packet ID + Data)
                 // Length of uncompressed (Packet ID + Data)
 
-                // TODO: We need the compressed length at the beginning of the packet so we need to write to
-                // buf here :( Is there a magic way to find a compressed length?
-                let mut compressed_buf = Vec::new();
-                let mut compressor = ZlibEncoder::with_quality(
-                    &mut compressed_buf,
-                    Level::Precise(compression_level as i32),
-                );
-
-                compressor
-                    .write_all(&packet_data)
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-                compressor
-                    .shutdown()
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-                debug_assert!(!compressed_buf.is_empty());
-
-                let full_packet_len_var_int: VarInt = (data_len_var_int.written_size()
-                    + compressed_buf.len())
-                .try_into()
-                .map_err(|_| {
-                    PacketEncodeError::Message(format!(
-                        "Full packet length is too large to fit in VarInt! ({data_len})"
-                    ))
-                })?;
-
-                let complete_serialization_length =
-                    full_packet_len_var_int.written_size() + full_packet_len_var_int.0 as usize;
-                if complete_serialization_length > MAX_PACKET_SIZE as usize {
-                    return Err(PacketEncodeError::TooLong(complete_serialization_length));
-                }
-
-                full_packet_len_var_int
-                    .encode_async(&mut self.writer)
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-                data_len_var_int
-                    .encode_async(&mut self.writer)
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-                self.writer
-                    .write_all(&compressed_buf)
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-            } else {
+                // The compressed length is needed before the compressed data, so the header
+                // becomes its own frame
+                push_coalesced(&mut self.frames, buffer);
+                compress_into(compressor, packet_data, buffer)
+                    .map_err(|err| PacketEncodeError::CompressionFailed(err.to_string()))?;
+                debug_assert!(!buffer.is_empty());
+                let compressed = buffer.split().freeze();
+
+                let full_packet_len_var_int =
+                    full_packet_len(data_len_var_int.written_size() + compressed.len())?;
+                put_var_int(buffer, full_packet_len_var_int);
+                put_var_int(buffer, data_len_var_int);
+                push_coalesced(&mut self.frames, buffer);
+                self.frames.push(compressed);
+            }
+            compression => {
                 // Pushed before data:
-                // Length of (Data Length) + length of compressed (Packet ID + Data)
-                // 0 to indicate uncompressed
-
-                let data_len_var_int: VarInt = 0.into();
-                let full_packet_len_var_int: VarInt = (data_len_var_int.written_size() + data_len)
-                    .try_into()
-                    .map_err(|_| {
-                        PacketEncodeError::Message(format!(
-                            "Full packet length is too large to fit in VarInt! ({data_len})"
-                        ))
-                    })?;
-
-                let complete_serialization_length =
-                    full_packet_len_var_int.written_size() + full_packet_len_var_int.0 as usize;
-                if complete_serialization_length > MAX_PACKET_SIZE as usize {
-                    return Err(PacketEncodeError::TooLong(complete_serialization_length));
+                // Length of Packet ID + Data, plus the 0 indicating uncompressed data if
+                // compression is enabled
+                let uncompressed_marker = compression.is_some().then_some(VarInt(0));
+                let full_packet_len_var_int = full_packet_len(
+                    uncompressed_marker.map_or(0, |marker| marker.written_size()) + data_len,
+                )?;
+                put_var_int(buffer, full_packet_len_var_int);
+                if let Some(marker) = uncompressed_marker {
+                    put_var_int(buffer, marker);
                 }
 
-                full_packet_len_var_int
-                    .encode_async(&mut self.writer)
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-                data_len_var_int
-                    .encode_async(&mut self.writer)
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-                self.writer
-                    .write_all(&packet_data)
-                    .await
-                    .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
+                if data_len < COALESCE_THRESHOLD {
+                    buffer.extend_from_slice(packet_data);
+                } else {
+                    push_coalesced(&mut self.frames, buffer);
+                    self.frames.push(packet_data.clone());
+                }
             }
-        } else {
-            // Pushed before data:
-            // Length of Packet ID + Data
+        }
+        Ok(())
+    }
+}
+
+/// Checks the length of a whole packet and returns its `Packet Length` field.
+fn full_packet_len(len: usize) -> Result<VarInt, PacketEncodeError> {
+    let full_packet_len_var_int: VarInt = len.try_into().map_err(|_| {
+        PacketEncodeError::Message(format!(
+            "Full packet length is too large to fit in VarInt! ({len})"
+        ))
+    })?;
+
+    let complete_serialization_length = full_packet_len_var_int.written_size() + len;
+    if complete_serialization_length > MAX_PACKET_SIZE as usize {
+        return Err(PacketEncodeError::TooLong(complete_serialization_length));
+    }
+    Ok(full_packet_len_var_int)
+}
 
-            let full_packet_len_var_int: VarInt = data_len_var_int;
+fn put_var_int(buffer: &mut BytesMut, value: VarInt) {
+    value
+        .encode(&mut buffer.writer())
+        .expect("Writing to a BytesMut can't fail");
+}
 
-            let complete_serialization_length =
-                full_packet_len_var_int.written_size() + full_packet_len_var_int.0 as usize;
-            if complete_serialization_length > MAX_PACKET_SIZE as usize {
-                return Err(PacketEncodeError::TooLong(complete_serialization_length));
-            }
+/// Turns what was written to `buffer` so far into a frame, sharing the buffer's allocation.
+fn push_coalesced(frames: &mut Vec<Bytes>, buffer: &mut BytesMut) {
+    if !buffer.is_empty() {
+        frames.push(buffer.split().freeze());
+    }
+}
 
-            full_packet_len_var_int
-                .encode_async(&mut self.writer)
-                .await
-                .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-            self.writer
-                .write_all(&packet_data)
-                .await
-                .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
+/// Appends `data` as a complete zlib stream to `buffer`.
+fn compress_into(compressor: &mut Compress, data: &[u8], buffer: &mut BytesMut) -> io::Result<()> {
+    compressor.reset();
+    let (start_in, start_out) = (compressor.total_in(), compressor.total_out());
+    loop {
+        let read = (compressor.total_in() - start_in) as usize;
+        let filled = buffer.len();
+        // Incompressible data grows by a few bytes, so this is almost always a single pass
+        buffer.resize(filled + data.len() - read + 64, 0);
+        let before = compressor.total_out();
+        let status = compressor
+            .compress(&data[read..], &mut buffer[filled..], FlushCompress::Finish)
+            .map_err(io::Error::other)?;
+        buffer.truncate(filled + (compressor.total_out() - before) as usize);
+        if status == Status::StreamEnd {
+            debug_assert!(compressor.total_out() > start_out);
+            return Ok(());
         }
+    }
+}
 
-        self.writer
-            .flush()
-            .await
-            .map_err(|err| PacketEncodeError::Message(err.to_string()))?;
-        Ok(())
+async fn write_all_vectored<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    frames: &[Bytes],
+) -> io::Result<()> {
+    for frames in frames.chunks(MAX_WRITE_SLICES) {
+        let mut slices = [IoSlice::new(&[]); MAX_WRITE_SLICES];
+        for (slice, frame) in slices.iter_mut().zip(frames) {
+            *slice = IoSlice::new(frame);
+        }
+        let mut slices = &mut slices[..frames.len()];
+        while !slices.is_empty() {
+            let written = writer.write_vectored(slices).await?;
+            if written == 0 {
+                return Err(io::ErrorKind::WriteZero.into());
+            }
+            IoSlice::advance_slices(&mut slices, written);
+        }
     }
+    Ok(())
 }
 
 #[derive(Error, Debug)]
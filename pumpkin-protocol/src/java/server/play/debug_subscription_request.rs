@@ -0,0 +1,18 @@
+use pumpkin_data::packet::serverbound::PLAY_DEBUG_SUBSCRIPTION_REQUEST;
+use pumpkin_macros::java_packet;
+
+use crate::VarInt;
+
+/// Sent when the debug screen starts or stops showing data that the server provides.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[java_packet(PLAY_DEBUG_SUBSCRIPTION_REQUEST)]
+pub struct SDebugSubscriptionRequest {
+    /// Every subscription the client wants from now on, by id in the debug subscription
+    /// registry.
+    pub subscriptions: Vec<VarInt>,
+}
+
+impl SDebugSubscriptionRequest {
+    /// The id of `minecraft:dedicated_server_tick_time`, the tick time chart.
+    pub const TICK_TIME: VarInt = VarInt(0);
+}
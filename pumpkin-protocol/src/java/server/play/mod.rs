@@ -14,6 +14,7 @@ mod confirm_teleport;
 mod container_button_click;
 mod cookie_response;
 mod custom_payload;
+mod debug_subscription_request;
 mod edit_book;
 mod interact;
 mod keep_alive;
@@ -65,6 +66,7 @@ pub use confirm_teleport::*;
 pub use container_button_click::*;
 pub use cookie_response::*;
 pub use custom_payload::*;
+pub use debug_subscription_request::*;
 pub use edit_book::*;
 pub use interact::*;
 pub use keep_alive::*;
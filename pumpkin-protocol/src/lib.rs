@@ -24,6 +24,7 @@ use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use crate::packet::{MultiVersionJavaPacket, Packet};
 
 pub mod bedrock;
+pub mod buffer_pool;
 pub mod codec;
 pub mod dto;
 pub mod java;
@@ -157,6 +157,7 @@ pub enum LootTableType {
     Entity,
     Block,
     Chest,
+    Fishing,
 }
 
 #[derive(Deserialize, PartialEq, Clone, Copy, Debug)]
@@ -0,0 +1,79 @@
+use std::pin::Pin;
+
+use pumpkin_nbt::{compound::NbtCompound, tag::NbtTag};
+use pumpkin_util::math::position::BlockPos;
+
+use super::BlockEntity;
+
+/// Remembers where an end gateway leads, once it was found or the gateway was built with one.
+pub struct EndGatewayBlockEntity {
+    pub position: BlockPos,
+    pub exit_portal: Option<BlockPos>,
+    /// Whether entities arrive right at the exit instead of on a safe block near it.
+    pub exact_teleport: bool,
+}
+
+impl EndGatewayBlockEntity {
+    pub const ID: &'static str = "minecraft:end_gateway";
+
+    #[must_use]
+    pub const fn new(
+        position: BlockPos,
+        exit_portal: Option<BlockPos>,
+        exact_teleport: bool,
+    ) -> Self {
+        Self {
+            position,
+            exit_portal,
+            exact_teleport,
+        }
+    }
+}
+
+impl BlockEntity for EndGatewayBlockEntity {
+    fn resource_location(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn get_position(&self) -> BlockPos {
+        self.position
+    }
+
+    fn from_nbt(nbt: &NbtCompound, position: BlockPos) -> Self
+    where
+        Self: Sized,
+    {
+        let exit_portal = match nbt.get("exit_portal") {
+            Some(NbtTag::IntArray(pos)) if pos.len() == 3 => {
+                Some(BlockPos::new(pos[0], pos[1], pos[2]))
+            }
+            _ => None,
+        };
+        Self {
+            position,
+            exit_portal,
+            exact_teleport: nbt.get_bool("ExactTeleport").unwrap_or(false),
+        }
+    }
+
+    fn write_nbt<'a>(
+        &'a self,
+        nbt: &'a mut NbtCompound,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            if let Some(pos) = self.exit_portal {
+                nbt.put(
+                    "exit_portal",
+                    NbtTag::IntArray(vec![pos.0.x, pos.0.y, pos.0.z]),
+                );
+            }
+            if self.exact_teleport {
+                nbt.put_bool("ExactTeleport", true);
+            }
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,380 @@
+use std::any::Any;
+use std::array::from_fn;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+
+use pumpkin_config::machines::{MachineConfig, MachineItem};
+use pumpkin_data::item::Item;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::tag::NbtTag;
+use pumpkin_util::math::position::BlockPos;
+use tokio::sync::{Mutex, MutexGuard};
+
+use super::BlockEntity;
+use crate::inventory::{Clearable, Inventory, InventoryFuture, split_stack};
+use crate::item::ItemStack;
+use crate::world::SimpleWorld;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MachineError {
+    #[error("Machine {0} has no name")]
+    Unnamed(String),
+    #[error("Machine {0} needs 1 to 7 input slots")]
+    InputSlots(String),
+    #[error("Machine {0} uses the unknown item {1}")]
+    UnknownItem(String, String),
+}
+
+pub struct MachineRecipe {
+    pub inputs: Vec<(&'static Item, u8)>,
+    pub output: (&'static Item, u8),
+}
+
+/// A machine of the config, with its items looked up.
+pub struct MachineDefinition {
+    pub name: String,
+    pub title: String,
+    pub input_slots: usize,
+    pub processing_time: u32,
+    /// Fuel items and how long they burn. Empty if the machine needs no fuel.
+    pub fuel: Vec<(&'static Item, u32)>,
+    pub recipes: Vec<MachineRecipe>,
+}
+
+fn item(machine: &str, name: &str) -> Result<&'static Item, MachineError> {
+    Item::from_registry_key(name.strip_prefix("minecraft:").unwrap_or(name))
+        .ok_or_else(|| MachineError::UnknownItem(machine.to_string(), name.to_string()))
+}
+
+fn stack(machine: &str, stack: &MachineItem) -> Result<(&'static Item, u8), MachineError> {
+    Ok((item(machine, &stack.item)?, stack.count.max(1)))
+}
+
+impl MachineDefinition {
+    pub fn from_config(config: &MachineConfig) -> Result<Self, MachineError> {
+        let name = &config.name;
+        if name.is_empty() {
+            return Err(MachineError::Unnamed(config.block.clone()));
+        }
+        if !(1..=MachineBlockEntity::FUEL_SLOT).contains(&usize::from(config.input_slots)) {
+            return Err(MachineError::InputSlots(name.clone()));
+        }
+        Ok(Self {
+            name: name.clone(),
+            title: config.title.clone(),
+            input_slots: usize::from(config.input_slots),
+            processing_time: config.processing_time.max(1),
+            fuel: config
+                .fuel
+                .iter()
+                .map(|fuel| -> Result<_, MachineError> {
+                    Ok((item(name, &fuel.item)?, fuel.burn_time.max(1)))
+                })
+                .collect::<Result<_, _>>()?,
+            recipes: config
+                .recipes
+                .iter()
+                .map(|recipe| -> Result<_, MachineError> {
+                    Ok(MachineRecipe {
+                        inputs: recipe
+                            .inputs
+                            .iter()
+                            .map(|input| stack(name, input))
+                            .collect::<Result<_, _>>()?,
+                        output: stack(name, &recipe.output)?,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn burn_time(&self, stack: &ItemStack) -> Option<u32> {
+        self.fuel
+            .iter()
+            .find(|(item, _)| item.id == stack.item.id)
+            .map(|(_, burn_time)| *burn_time)
+    }
+}
+
+static MACHINES: LazyLock<RwLock<HashMap<String, Arc<MachineDefinition>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Makes a machine known to machines loaded from disk. Replaces a machine of the same name.
+#[must_use]
+pub fn register_machine(machine: MachineDefinition) -> Arc<MachineDefinition> {
+    let machine = Arc::new(machine);
+    MACHINES
+        .write()
+        .unwrap()
+        .insert(machine.name.clone(), machine.clone());
+    machine
+}
+
+/// A configured machine, see [`MachinesConfig`](pumpkin_config::machines::MachinesConfig).
+pub struct MachineBlockEntity {
+    pub position: BlockPos,
+    /// Kept even if the machine is no longer configured, so that it works again once it is.
+    name: String,
+    /// `None` if the machine is no longer configured, in which case it only keeps its items.
+    machine: Option<Arc<MachineDefinition>>,
+    pub items: [Arc<Mutex<ItemStack>>; Self::INVENTORY_SIZE],
+    /// Ticks spent on the current recipe.
+    pub progress: AtomicU32,
+    /// Ticks the current fuel item still burns.
+    pub burn_time: AtomicU32,
+    pub dirty: AtomicBool,
+}
+
+impl MachineBlockEntity {
+    pub const INVENTORY_SIZE: usize = 9;
+    pub const FUEL_SLOT: usize = 7;
+    pub const OUTPUT_SLOT: usize = 8;
+    pub const ID: &'static str = "pumpkin:machine";
+
+    #[must_use]
+    pub fn new(position: BlockPos, machine: Arc<MachineDefinition>) -> Self {
+        Self {
+            position,
+            name: machine.name.clone(),
+            machine: Some(machine),
+            items: from_fn(|_| Arc::new(Mutex::new(ItemStack::EMPTY.clone()))),
+            progress: AtomicU32::new(0),
+            burn_time: AtomicU32::new(0),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Advances the current recipe by one tick. Returns whether anything changed.
+    fn process(
+        &self,
+        machine: &MachineDefinition,
+        slots: &mut [MutexGuard<'_, ItemStack>],
+    ) -> bool {
+        let inputs = &slots[..machine.input_slots];
+        let output = &slots[Self::OUTPUT_SLOT];
+        let Some(recipe) = machine.recipes.iter().find(|recipe| {
+            let (item, count) = recipe.output;
+            let fits = output.is_empty()
+                || (output.item.id == item.id
+                    && output.patch.is_empty()
+                    && output
+                        .item_count
+                        .checked_add(count)
+                        .is_some_and(|total| total <= output.get_max_stack_size()));
+            fits && recipe.inputs.iter().all(|(item, count)| {
+                let available: u32 = inputs
+                    .iter()
+                    .filter(|stack| stack.item.id == item.id)
+                    .map(|stack| u32::from(stack.item_count))
+                    .sum();
+                available >= u32::from(*count)
+            })
+        }) else {
+            return self.progress.swap(0, Ordering::Relaxed) != 0;
+        };
+
+        if !machine.fuel.is_empty() {
+            if self.burn_time.load(Ordering::Relaxed) == 0 {
+                let fuel = &mut slots[Self::FUEL_SLOT];
+                let Some(burn_time) = machine.burn_time(fuel) else {
+                    return false;
+                };
+                fuel.item_count -= 1;
+                if fuel.item_count == 0 {
+                    **fuel = ItemStack::EMPTY.clone();
+                }
+                self.burn_time.store(burn_time, Ordering::Relaxed);
+            }
+            self.burn_time.fetch_sub(1, Ordering::Relaxed);
+        }
+
+        if self.progress.fetch_add(1, Ordering::Relaxed) + 1 < machine.processing_time {
+            return true;
+        }
+        self.progress.store(0, Ordering::Relaxed);
+        for (item, count) in &recipe.inputs {
+            let mut count = *count;
+            for stack in &mut slots[..machine.input_slots] {
+                if count == 0 {
+                    break;
+                }
+                if stack.item.id == item.id {
+                    let taken = count.min(stack.item_count);
+                    stack.item_count -= taken;
+                    count -= taken;
+                    if stack.item_count == 0 {
+                        **stack = ItemStack::EMPTY.clone();
+                    }
+                }
+            }
+        }
+        let (item, count) = recipe.output;
+        let output = &mut slots[Self::OUTPUT_SLOT];
+        if output.is_empty() {
+            **output = ItemStack::new(count, item);
+        } else {
+            output.item_count += count;
+        }
+        true
+    }
+}
+
+impl BlockEntity for MachineBlockEntity {
+    fn resource_location(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn get_position(&self) -> BlockPos {
+        self.position
+    }
+
+    fn from_nbt(nbt: &NbtCompound, position: BlockPos) -> Self
+    where
+        Self: Sized,
+    {
+        let name = nbt.get_string("machine").unwrap_or_default().to_string();
+        let machine = MACHINES.read().unwrap().get(&name).cloned();
+        if machine.is_none() {
+            log::warn!("The machine at {position} is of the unknown type {name}");
+        }
+        let entity = Self {
+            position,
+            name,
+            machine,
+            items: from_fn(|_| Arc::new(Mutex::new(ItemStack::EMPTY.clone()))),
+            progress: AtomicU32::new(nbt.get_int("progress").unwrap_or(0) as u32),
+            burn_time: AtomicU32::new(nbt.get_int("burn_time").unwrap_or(0) as u32),
+            dirty: AtomicBool::new(false),
+        };
+        entity.read_data(nbt, &entity.items);
+        entity
+    }
+
+    fn write_nbt<'a>(
+        &'a self,
+        nbt: &'a mut NbtCompound,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            nbt.put_string("machine", self.name.clone());
+            nbt.put_int("progress", self.progress.load(Ordering::Relaxed) as i32);
+            nbt.put_int("burn_time", self.burn_time.load(Ordering::Relaxed) as i32);
+            let mut items = Vec::new();
+            for (slot, stack) in self.items.iter().enumerate() {
+                let stack = stack.lock().await;
+                if !stack.is_empty() {
+                    let mut item = NbtCompound::new();
+                    item.put_byte("Slot", slot as i8);
+                    stack.write_item_stack(&mut item);
+                    items.push(NbtTag::Compound(item));
+                }
+            }
+            nbt.put("Items", NbtTag::List(items));
+        })
+    }
+
+    fn tick<'a>(
+        &'a self,
+        _world: &'a Arc<dyn SimpleWorld>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(machine) = &self.machine else {
+                return;
+            };
+            let mut slots = Vec::with_capacity(Self::INVENTORY_SIZE);
+            for stack in &self.items {
+                slots.push(stack.lock().await);
+            }
+            let changed = self.process(machine, &mut slots);
+            drop(slots);
+            if changed {
+                self.mark_dirty();
+            }
+        })
+    }
+
+    fn get_inventory(self: Arc<Self>) -> Option<Arc<dyn Inventory>> {
+        Some(self)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty.load(Ordering::Relaxed)
+    }
+
+    fn is_client_side(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Inventory for MachineBlockEntity {
+    fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    fn is_empty(&self) -> InventoryFuture<'_, bool> {
+        Box::pin(async move {
+            for slot in &self.items {
+                if !slot.lock().await.is_empty() {
+                    return false;
+                }
+            }
+
+            true
+        })
+    }
+
+    fn get_stack(&self, slot: usize) -> InventoryFuture<'_, Arc<Mutex<ItemStack>>> {
+        Box::pin(async move { self.items[slot].clone() })
+    }
+
+    fn remove_stack(&self, slot: usize) -> InventoryFuture<'_, ItemStack> {
+        Box::pin(async move {
+            let mut removed = ItemStack::EMPTY.clone();
+            let mut guard = self.items[slot].lock().await;
+            std::mem::swap(&mut removed, &mut *guard);
+            removed
+        })
+    }
+
+    fn remove_stack_specific(&self, slot: usize, amount: u8) -> InventoryFuture<'_, ItemStack> {
+        Box::pin(async move { split_stack(&self.items, slot, amount).await })
+    }
+
+    fn set_stack(&self, slot: usize, stack: ItemStack) -> InventoryFuture<'_, ()> {
+        Box::pin(async move {
+            *self.items[slot].lock().await = stack;
+        })
+    }
+
+    fn is_valid_slot_for(&self, slot: usize, stack: &ItemStack) -> bool {
+        match (&self.machine, slot) {
+            (_, Self::OUTPUT_SLOT) => false,
+            (Some(machine), Self::FUEL_SLOT) => machine.burn_time(stack).is_some(),
+            (Some(machine), slot) => slot < machine.input_slots,
+            (None, _) => true,
+        }
+    }
+
+    fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Clearable for MachineBlockEntity {
+    fn clear(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            for slot in &self.items {
+                *slot.lock().await = ItemStack::EMPTY.clone();
+            }
+        })
+    }
+}
@@ -5,9 +5,11 @@ use barrel::BarrelBlockEntity;
 use bed::BedBlockEntity;
 use chest::ChestBlockEntity;
 use comparator::ComparatorBlockEntity;
+use end_gateway::EndGatewayBlockEntity;
 use end_portal::EndPortalBlockEntity;
 use furnace::FurnaceBlockEntity;
 use furnace_like_block_entity::ExperienceContainer;
+use machine::MachineBlockEntity;
 use piston::PistonBlockEntity;
 use pumpkin_data::{Block, block_properties::BLOCK_ENTITY_TYPES};
 use pumpkin_nbt::compound::NbtCompound;
@@ -35,12 +37,14 @@ pub mod chiseled_bookshelf;
 pub mod command_block;
 pub mod comparator;
 pub mod dropper;
+pub mod end_gateway;
 pub mod end_portal;
 pub mod ender_chest;
 pub mod furnace;
 pub mod furnace_like_block_entity;
 pub mod hopper;
 pub mod jukebox;
+pub mod machine;
 pub mod mob_spawner;
 pub mod piston;
 pub mod shulker_box;
@@ -91,6 +95,12 @@ pub trait BlockEntity: Send + Sync {
         None
     }
 
+    /// Whether clients know this type of block entity. Block entities only the server knows,
+    /// like configured machines, are left out of chunk data.
+    fn is_client_side(&self) -> bool {
+        true
+    }
+
     fn get_inventory(self: Arc<Self>) -> Option<Arc<dyn Inventory>> {
         None
     }
@@ -133,7 +143,7 @@ pub fn block_entity_from_generic<T: BlockEntity>(nbt: &NbtCompound) -> T {
 
 #[must_use]
 pub fn block_entity_from_nbt(nbt: &NbtCompound) -> Option<Arc<dyn BlockEntity>> {
-    Some(match nbt.get_string("id").unwrap() {
+    Some(match nbt.get_string("id")? {
         ChestBlockEntity::ID => Arc::new(block_entity_from_generic::<ChestBlockEntity>(nbt)),
         EnderChestBlockEntity::ID => {
             Arc::new(block_entity_from_generic::<EnderChestBlockEntity>(nbt))
@@ -157,6 +167,9 @@ pub fn block_entity_from_nbt(nbt: &NbtCompound) -> Option<Arc<dyn BlockEntity>>
         EndPortalBlockEntity::ID => {
             Arc::new(block_entity_from_generic::<EndPortalBlockEntity>(nbt))
         }
+        EndGatewayBlockEntity::ID => {
+            Arc::new(block_entity_from_generic::<EndGatewayBlockEntity>(nbt))
+        }
         ChiseledBookshelfBlockEntity::ID => Arc::new(block_entity_from_generic::<
             ChiseledBookshelfBlockEntity,
         >(nbt)),
@@ -166,6 +179,7 @@ pub fn block_entity_from_nbt(nbt: &NbtCompound) -> Option<Arc<dyn BlockEntity>>
             Arc::new(block_entity_from_generic::<BlastingFurnaceBlockEntity>(nbt))
         }
         SmokerBlockEntity::ID => Arc::new(block_entity_from_generic::<SmokerBlockEntity>(nbt)),
+        MachineBlockEntity::ID => Arc::new(block_entity_from_generic::<MachineBlockEntity>(nbt)),
         _ => return None,
     })
 }
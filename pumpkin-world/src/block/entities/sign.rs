@@ -171,10 +171,10 @@ impl From<NbtTag> for Text {
     fn from(tag: NbtTag) -> Self {
         let nbt = tag.extract_compound().unwrap();
         let has_glowing_text = nbt.get_bool("has_glowing_text").unwrap_or(false);
-        let color = nbt.get_string("color").unwrap();
+        let color = nbt.get_string("color").unwrap_or("black");
         let messages: Vec<String> = nbt
             .get_list("messages")
-            .unwrap()
+            .unwrap_or_default()
             .iter()
             .filter_map(|tag| tag.extract_string().map(std::string::ToString::to_string))
             .collect();
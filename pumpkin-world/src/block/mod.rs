@@ -3,8 +3,10 @@ pub mod state;
 pub mod viewer;
 
 use std::collections::HashMap;
+use std::fmt;
 
 use pumpkin_data::{Block, BlockState, chunk_gen_settings::BlockBlueprint};
+use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub use state::RawBlockState;
 
@@ -27,10 +29,22 @@ pub struct BlockStateCodec {
 fn parse_block_name<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<&'static Block, D::Error> {
-    let s = String::deserialize(deserializer)?;
-    let block =
-        Block::from_name(s.as_str()).ok_or(serde::de::Error::custom("Invalid block name"))?;
-    Ok(block)
+    // Looks the name up where it is instead of copying it into a `String` first
+    struct BlockNameVisitor;
+
+    impl Visitor<'_> for BlockNameVisitor {
+        type Value = &'static Block;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a block name")
+        }
+
+        fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+            Block::from_name(name).ok_or_else(|| E::custom("Invalid block name"))
+        }
+    }
+
+    deserializer.deserialize_str(BlockNameVisitor)
 }
 
 fn block_to_string<S: Serializer>(block: &'static Block, serializer: S) -> Result<S::Ok, S::Error> {
@@ -4,6 +4,8 @@ use itertools::Itertools;
 use lz4_java_wrc::Context;
 use pumpkin_config::chunk::AnvilChunkConfig;
 use pumpkin_util::math::vector2::Vector2;
+use ruzstd::decoding::StreamingDecoder;
+use ruzstd::encoding::{CompressionLevel, compress_to_vec};
 use std::{
     collections::HashSet,
     io::{Read, SeekFrom, Write},
@@ -41,16 +43,16 @@ const SECTOR_BYTES: usize = 4096;
 pub const WORLD_DATA_VERSION: i32 = 4671;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u8)]
 pub enum Compression {
     /// `GZip` Compression
-    GZip = Self::GZIP_ID,
+    GZip,
     /// `ZLib` Compression
-    ZLib = Self::ZLIB_ID,
+    ZLib,
     /// LZ4 Compression (since 24w04a)
-    LZ4 = Self::LZ4_ID,
-    /// Custom compression algorithm (since 24w05a)
-    Custom = Self::CUSTOM_ID,
+    LZ4,
+    /// Zstandard Compression, stored as a custom compression algorithm (since 24w05a) named
+    /// [`Compression::ZSTD_NAME`]
+    Zstd,
 }
 
 pub enum CompressionRead<R: Read> {
@@ -121,6 +123,26 @@ impl Compression {
     const NO_COMPRESSION_ID: u8 = 3;
     const LZ4_ID: u8 = 4;
     const CUSTOM_ID: u8 = 127;
+    /// The name of zstd after the custom compression byte.
+    const ZSTD_NAME: &str = "pumpkin:zstd";
+
+    /// The compression byte in front of the chunk data.
+    const fn id(self) -> u8 {
+        match self {
+            Self::GZip => Self::GZIP_ID,
+            Self::ZLib => Self::ZLIB_ID,
+            Self::LZ4 => Self::LZ4_ID,
+            Self::Zstd => Self::CUSTOM_ID,
+        }
+    }
+
+    /// The name following the compression byte of custom compression algorithms.
+    const fn custom_name(self) -> Option<&'static str> {
+        match self {
+            Self::Zstd => Some(Self::ZSTD_NAME),
+            _ => None,
+        }
+    }
 
     fn decompress_data(&self, compressed_data: &[u8]) -> Result<Box<[u8]>, CompressionError> {
         fn decode<R: std::io::Read>(mut reader: R, capacity: usize) -> std::io::Result<Box<[u8]>> {
@@ -141,7 +163,10 @@ impl Compression {
                 initial_capacity,
             )
             .map_err(CompressionError::LZ4Error),
-            Self::Custom => todo!(),
+            Self::Zstd => StreamingDecoder::new(compressed_data)
+                .map_err(|err| std::io::Error::other(err.to_string()))
+                .and_then(|decoder| decode(decoder, initial_capacity))
+                .map_err(CompressionError::ZstdError),
         }
     }
 
@@ -189,11 +214,16 @@ impl Compression {
                 drop(encoder);
                 Ok(compressed_data)
             }
-            Self::Custom => todo!(),
+            // ruzstd only has its fastest level, the config rejects other levels
+            Self::Zstd => Ok(compress_to_vec(
+                uncompressed_data,
+                CompressionLevel::Fastest,
+            )),
         }
     }
 
-    /// Returns Ok when a compression is found otherwise an Err
+    /// Returns Ok when a compression is found otherwise an Err. Custom compression algorithms are
+    /// identified by their name instead, see [`Compression::from_custom_name`].
     #[expect(clippy::result_unit_err)]
     pub const fn from_byte(byte: u8) -> Result<Option<Self>, ()> {
         match byte {
@@ -202,11 +232,16 @@ impl Compression {
             // Uncompressed (since a version before 1.15.1)
             Self::NO_COMPRESSION_ID => Ok(None),
             Self::LZ4_ID => Ok(Some(Self::LZ4)),
-            Self::CUSTOM_ID => Ok(Some(Self::Custom)),
             // Unknown format
             _ => Err(()),
         }
     }
+
+    /// Returns the custom compression algorithm named `name`, if it is known.
+    #[must_use]
+    pub fn from_custom_name(name: &[u8]) -> Option<Self> {
+        (name == Self::ZSTD_NAME.as_bytes()).then_some(Self::Zstd)
+    }
 }
 
 impl From<pumpkin_config::chunk::Compression> for Compression {
@@ -216,7 +251,7 @@ impl From<pumpkin_config::chunk::Compression> for Compression {
             pumpkin_config::chunk::Compression::GZip => Self::GZip,
             pumpkin_config::chunk::Compression::ZLib => Self::ZLib,
             pumpkin_config::chunk::Compression::LZ4 => Self::LZ4,
-            pumpkin_config::chunk::Compression::Custom => Self::Custom,
+            pumpkin_config::chunk::Compression::Zstd => Self::Zstd,
         }
     }
 }
@@ -226,7 +261,19 @@ impl AnvilChunkData {
     #[inline]
     const fn raw_write_size(&self) -> usize {
         // 4 bytes for the *length* and 1 byte for the *compression* method
-        self.compressed_data.len() + 4 + 1
+        self.compressed_data.len() + 4 + 1 + self.custom_header_size()
+    }
+
+    /// Size of the length prefixed name of a custom compression algorithm
+    #[inline]
+    const fn custom_header_size(&self) -> usize {
+        match self.compression {
+            Some(compression) => match compression.custom_name() {
+                Some(name) => 2 + name.len(),
+                None => 0,
+            },
+            None => 0,
+        }
     }
 
     /// Size of serialized chunk with padding
@@ -258,8 +305,24 @@ impl AnvilChunkData {
         }
 
         let compression_method = bytes.get_u8();
-        let compression = Compression::from_byte(compression_method)
-            .map_err(|()| ChunkReadingError::Compression(CompressionError::UnknownCompression))?;
+        let unknown = || ChunkReadingError::Compression(CompressionError::UnknownCompression);
+        let mut length = length;
+        let compression = if compression_method == Compression::CUSTOM_ID {
+            // The algorithm is named by a string prefixed with its length as an unsigned short
+            if length < 2 {
+                return Err(unknown());
+            }
+            let name_length = bytes.get_u16() as usize;
+            length -= 2;
+            if name_length > length {
+                return Err(unknown());
+            }
+            let name = bytes.split_to(name_length);
+            length -= name_length;
+            Some(Compression::from_custom_name(&name).ok_or_else(unknown)?)
+        } else {
+            Compression::from_byte(compression_method).map_err(|()| unknown())?
+        };
 
         Ok(Self {
             compression,
@@ -271,13 +334,17 @@ impl AnvilChunkData {
     async fn write(&self, w: &mut (impl AsyncWrite + Unpin + Send)) -> Result<(), std::io::Error> {
         let padded_size = self.padded_size();
 
-        w.write_u32((self.compressed_data.remaining() + 1) as u32)
+        w.write_u32((self.compressed_data.remaining() + 1 + self.custom_header_size()) as u32)
             .await?;
         w.write_u8(
             self.compression
-                .map_or(Compression::NO_COMPRESSION_ID, |c| c as u8),
+                .map_or(Compression::NO_COMPRESSION_ID, Compression::id),
         )
         .await?;
+        if let Some(name) = self.compression.and_then(Compression::custom_name) {
+            w.write_u16(name.len() as u16).await?;
+            w.write_all(name.as_bytes()).await?;
+        }
 
         w.write_all(&self.compressed_data).await?;
         for _ in 0..(padded_size - self.raw_write_size()) {
@@ -291,14 +358,15 @@ impl AnvilChunkData {
     where
         S: SingleChunkDataSerializer,
     {
-        if let Some(compression) = self.compression {
-            let decompress_bytes = compression
-                .decompress_data(&self.compressed_data)
-                .map_err(ChunkReadingError::Compression)?;
+        let bytes = self.decompress().map_err(ChunkReadingError::Compression)?;
+        S::from_bytes(&bytes, pos)
+    }
 
-            S::from_bytes(&decompress_bytes.into(), pos)
-        } else {
-            S::from_bytes(&self.compressed_data, pos)
+    /// Returns the raw chunk NBT.
+    fn decompress(&self) -> Result<Bytes, CompressionError> {
+        match self.compression {
+            Some(compression) => Ok(compression.decompress_data(&self.compressed_data)?.into()),
+            None => Ok(self.compressed_data.clone()),
         }
     }
 
@@ -344,6 +412,39 @@ impl<S: SingleChunkDataSerializer> AnvilChunkFile<S> {
         index as usize
     }
 
+    /// Decompresses every chunk present in the file, keyed by chunk index. Used to inspect chunks
+    /// without loading them, see [`crate::verify`].
+    pub(crate) fn decompress_all(&self) -> Vec<(usize, Result<Bytes, CompressionError>)> {
+        self.chunks_data
+            .iter()
+            .enumerate()
+            .filter_map(|(index, metadata)| {
+                Some((index, metadata.as_ref()?.serialized_data.decompress()))
+            })
+            .collect()
+    }
+
+    /// Compresses every chunk in the file again with `compression`, marking the whole file for
+    /// writing. Returns the number of chunks. Used to convert regions offline, see
+    /// [`crate::recompress`].
+    pub(crate) fn recompress(
+        &mut self,
+        compression: Compression,
+        level: u32,
+    ) -> Result<usize, CompressionError> {
+        let mut chunks = 0;
+        for metadata in self.chunks_data.iter_mut().flatten() {
+            let raw_bytes = metadata.serialized_data.decompress()?;
+            metadata.serialized_data = AnvilChunkData {
+                compression: Some(compression),
+                compressed_data: compression.compress_data(&raw_bytes, level)?.into(),
+            };
+            chunks += 1;
+        }
+        *self.write_action.get_mut() = WriteAction::All;
+        Ok(chunks)
+    }
+
     async fn write_indices<I>(&self, path: &Path, indices: I) -> Result<(), std::io::Error>
     where
         I: IntoIterator<Item = usize>,
@@ -538,6 +639,8 @@ impl<S: SingleChunkDataSerializer> ChunkSerializer for AnvilChunkFile<S> {
                 Ok(())
             }
             WriteAction::All => self.write_all(path).await,
+            // Writing in place would change the other worlds cloned from the same template too
+            WriteAction::Parts(_) if crate::template::is_shared(path) => self.write_all(path).await,
             WriteAction::Parts(parts) => self.write_indices(path, parts.iter().copied()).await,
         }?;
 
@@ -817,6 +920,7 @@ impl<S: SingleChunkDataSerializer> ChunkSerializer for AnvilChunkFile<S> {
 #[cfg(test)]
 mod tests {
 
+    use bytes::Bytes;
     use pumpkin_config::{AdvancedConfiguration, advanced_config, override_config_for_testing};
     use pumpkin_data::BlockDirection;
     use pumpkin_util::math::position::BlockPos;
@@ -828,7 +932,9 @@ mod tests {
     use tokio::sync::RwLock;
 
     use crate::chunk::ChunkData;
-    use crate::chunk::format::anvil::{AnvilChunkFile, SingleChunkDataSerializer};
+    use crate::chunk::format::anvil::{
+        AnvilChunkData, AnvilChunkFile, Compression, SingleChunkDataSerializer,
+    };
     use crate::chunk::io::file_manager::{ChunkFileManager, PathFromLevelFolder};
     use crate::chunk::io::{FileIO, LoadedData};
     use crate::dimension::Dimension;
@@ -885,6 +991,23 @@ mod tests {
         read_chunks.into_boxed_slice()
     }
 
+    #[tokio::test]
+    async fn custom_compression_round_trip() {
+        let raw = Bytes::from_static(b"some chunk data, some chunk data, some chunk data");
+        let chunk = AnvilChunkData {
+            compression: Some(Compression::Zstd),
+            compressed_data: Compression::Zstd.compress_data(&raw, 0).unwrap().into(),
+        };
+        let mut written = Vec::new();
+        chunk.write(&mut written).await.unwrap();
+        assert_eq!(written.len(), chunk.padded_size());
+        assert_eq!(written[4], 127);
+
+        let read = AnvilChunkData::from_bytes(Bytes::from(written)).unwrap();
+        assert_eq!(read.compression, Some(Compression::Zstd));
+        assert_eq!(read.decompress().unwrap(), raw);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn not_existing() {
         let region_path = PathBuf::from("not_existing");
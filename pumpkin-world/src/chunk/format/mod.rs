@@ -1,17 +1,18 @@
 use std::{
-    io::Cursor,
+    collections::HashMap,
+    fmt,
     path::PathBuf,
     pin::Pin,
     sync::{
-        RwLock,
-        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+        atomic::{AtomicBool, AtomicI64, Ordering},
     },
 };
 
 use bytes::Bytes;
 use futures::future::join_all;
-use pumpkin_data::{Block, chunk::ChunkStatus, fluid::Fluid};
-use pumpkin_nbt::{compound::NbtCompound, from_bytes, nbt_long_array};
+use pumpkin_data::{Block, BlockState, chunk::ChunkStatus, fluid::Fluid};
+use pumpkin_nbt::{compound::NbtCompound, from_slice, nbt_long_array};
 use rustc_hash::FxHashMap;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -28,16 +29,18 @@ use crate::{
     tick::{ScheduledTick, scheduler::ChunkTickScheduler},
 };
 use pumpkin_util::math::vector2::Vector2;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use super::{
-    ChunkData, ChunkHeightmaps, ChunkLight, ChunkParsingError, ChunkSections,
+    ChunkData, ChunkHeightmapType, ChunkHeightmaps, ChunkLight, ChunkParsingError, ChunkSections,
     palette::{BiomePalette, BlockPalette},
 };
-use crate::block::BlockStateCodec;
+use crate::BlockStateId;
+use upgrade::DataKind;
 
 pub mod anvil;
 pub mod linear;
+pub mod upgrade;
 
 impl SingleChunkDataSerializer for ChunkData {
     #[inline]
@@ -82,8 +85,8 @@ impl ChunkData {
         chunk_data: &[u8],
         position: Vector2<i32>,
     ) -> Result<Self, ChunkParsingError> {
-        let chunk_data = from_bytes::<ChunkNbt>(Cursor::new(chunk_data))
-            .map_err(|e| ChunkParsingError::ErrorDeserializingChunk(e.to_string()))?;
+        let (chunk_data, upgraded) =
+            parse_upgrading::<ChunkNbt>(chunk_data, DataKind::Chunk, |nbt| nbt.data_version)?;
 
         if chunk_data.light_correct {
             for section in &chunk_data.sections {
@@ -126,8 +129,28 @@ impl ChunkData {
                 position.x, position.y, chunk_data.x_pos, chunk_data.z_pos,
             )));
         }
-        let (block_lights, sky_lights, block_palettes, biome_palettes) = chunk_data
+        if chunk_data.data_version != WORLD_DATA_VERSION {
+            log::debug!(
+                "Chunk {},{} was saved with data version {} instead of {WORLD_DATA_VERSION}",
+                position.x,
+                position.y,
+                chunk_data.data_version
+            );
+        }
+
+        // The game also saves the light of the sections just below and above the world, which
+        // have no blocks
+        let mut sections: Vec<_> = chunk_data
             .sections
+            .into_iter()
+            .filter(|section| {
+                section.block_states.is_some() && i32::from(section.y) >= chunk_data.min_y_section
+            })
+            .collect();
+        sections.sort_unstable_by_key(|section| section.y);
+
+        let mut invalid_states = Vec::new();
+        let (block_lights, sky_lights, block_palettes, biome_palettes) = sections
             .into_iter()
             .map(|section| {
                 // Map light data to the LightContainer enum
@@ -143,7 +166,7 @@ impl ChunkData {
                 // Convert NBT to Palettes
                 let block_palette = section
                     .block_states
-                    .map(BlockPalette::from_disk_nbt)
+                    .map(|states| BlockPalette::from_disk_nbt(states, &mut invalid_states))
                     .unwrap_or_default();
                 let biome_palette = section
                     .biomes
@@ -157,12 +180,21 @@ impl ChunkData {
                 |(mut bl, mut sl, mut bp, mut bip), (b_l, s_l, b_p, bi_p)| {
                     bl.push(b_l);
                     sl.push(s_l);
-                    bp.push(b_p);
-                    bip.push(bi_p);
+                    bp.push(Arc::new(b_p));
+                    bip.push(Arc::new(bi_p));
                     (bl, sl, bp, bip)
                 },
             );
 
+        if !invalid_states.is_empty() {
+            log::warn!(
+                "Chunk {},{} has block states unknown to this version, loaded as their block's default or air: {}",
+                position.x,
+                position.y,
+                invalid_states.join(", ")
+            );
+        }
+
         // 2. Assemble the LightEngine
         let light_engine = ChunkLight {
             block_light: block_lights.into_boxed_slice(),
@@ -177,13 +209,14 @@ impl ChunkData {
             biome_sections: RwLock::new(biome_palettes.into_boxed_slice()),
             min_y,
         };
-        Ok(Self {
+        let mut chunk = Self {
             section,
-            heightmap: std::sync::Mutex::new(chunk_data.heightmaps),
+            heightmap: std::sync::Mutex::default(),
             x: position.x,
             z: position.y,
-            // This chunk is read from disk, so it has not been modified
-            dirty: AtomicBool::new(false),
+            // This chunk is read from disk, so it has not been modified, unless it was upgraded and
+            // should be saved in the current format
+            dirty: AtomicBool::new(upgraded),
             block_ticks: ChunkTickScheduler::from_iter(chunk_data.block_ticks),
             fluid_ticks: ChunkTickScheduler::from_iter(chunk_data.fluid_ticks),
             block_entities: {
@@ -196,15 +229,21 @@ impl ChunkData {
                 }
                 std::sync::Mutex::new(block_entities)
             },
-            light_engine,
+            light_engine: RwLock::new(light_engine),
+            light_correct: AtomicBool::new(chunk_data.light_correct),
             status: chunk_data.status,
-        })
+            inhabited_time: AtomicI64::new(chunk_data.inhabited_time),
+        };
+        // Saved heightmaps aren't read, as they may have been written by another program
+        chunk.heightmap = std::sync::Mutex::new(chunk.calculate_heightmap());
+        Ok(chunk)
     }
 
     async fn internal_to_bytes(&self) -> Result<Bytes, ChunkSerializingError> {
         let sections: Vec<ChunkSectionNBT> = {
             let block_lock = self.section.block_sections.read().unwrap();
             let biome_lock = self.section.biome_sections.read().unwrap();
+            let light = self.light_engine.read().unwrap();
             let min_section_y = (self.section.min_y >> 4) as i8;
 
             (0..self.section.count)
@@ -214,20 +253,14 @@ impl ChunkData {
                         // Convert the palettes to their NBT disk representation
                         block_states: Some(block_lock[i].to_disk_nbt()),
                         biomes: Some(biome_lock[i].to_disk_nbt()),
-                        block_light: match self.light_engine.block_light.get(i) {
-                            Some(LightContainer::Full(data)) => Some(data.clone()),
-                            _ => None,
-                        },
-                        sky_light: match self.light_engine.sky_light.get(i) {
-                            Some(LightContainer::Full(data)) => Some(data.clone()),
-                            _ => None,
-                        },
+                        block_light: light.block_light.get(i).and_then(LightContainer::to_disk),
+                        sky_light: light.sky_light.get(i).and_then(LightContainer::to_disk),
                     }
                 })
                 .collect()
         };
 
-        let heightmaps = self.heightmap.lock().unwrap().clone();
+        let heightmaps = self.disk_heightmaps();
 
         let entities_to_serialize = {
             let entities_guard = self.block_entities.lock().unwrap();
@@ -254,7 +287,9 @@ impl ChunkData {
             block_ticks: self.block_ticks.to_vec(),
             fluid_ticks: self.fluid_ticks.to_vec(),
             block_entities: block_entities_nbt,
-            light_correct: false,
+            // Otherwise the game relights the chunk on load
+            light_correct: self.light_correct.load(Ordering::Relaxed),
+            inhabited_time: self.inhabited_time.load(Ordering::Relaxed),
         };
 
         let mut result = Vec::new();
@@ -262,6 +297,46 @@ impl ChunkData {
             .map_err(ChunkSerializingError::ErrorSerializingChunk)?;
         Ok(result.into())
     }
+
+    /// The heightmaps as the game saves them: the height above the highest block of a column,
+    /// counted from the bottom of the world, packed without spanning longs.
+    fn disk_heightmaps(&self) -> ChunkHeightmaps {
+        let heightmaps = self.heightmap.lock().unwrap();
+        let min_y = self.section.min_y;
+        let height = self.section.count * BlockPalette::SIZE;
+        let pack = |heightmap| {
+            pack_heightmap(
+                height,
+                (0..16 * 16).map(|column| {
+                    let (x, z) = (column % 16, column / 16);
+                    let top = heightmaps.get(heightmap, x as i32, z as i32, min_y);
+                    // The bottom block and empty columns are saved alike
+                    let empty = top == min_y
+                        && self
+                            .section
+                            .get_block_absolute_y(x, min_y, z)
+                            .is_none_or(|state| BlockState::from_id(state).is_air());
+                    if empty { 0 } else { (top + 1 - min_y) as u64 }
+                }),
+            )
+        };
+        ChunkHeightmaps {
+            world_surface: pack(ChunkHeightmapType::WorldSurface),
+            motion_blocking: pack(ChunkHeightmapType::MotionBlocking),
+            motion_blocking_no_leaves: pack(ChunkHeightmapType::MotionBlockingNoLeaves),
+        }
+    }
+}
+
+/// Packs the 256 heights of a chunk like the game: as many per long as fit, from the lowest bits.
+fn pack_heightmap(height: usize, heights: impl Iterator<Item = u64>) -> Box<[i64]> {
+    let bits = height.ilog2() as usize + 1;
+    let per_long = 64 / bits;
+    let mut data = vec![0u64; (16 * 16usize).div_ceil(per_long)];
+    for (i, value) in heights.enumerate() {
+        data[i / per_long] |= (value & ((1 << bits) - 1)) << (i % per_long * bits);
+    }
+    data.into_iter().map(|long| long as i64).collect()
 }
 
 impl PathFromLevelFolder for ChunkEntityData {
@@ -307,8 +382,8 @@ impl ChunkEntityData {
         chunk_data: &[u8],
         position: Vector2<i32>,
     ) -> Result<Self, ChunkParsingError> {
-        let chunk_entity_data = pumpkin_nbt::from_bytes::<EntityNbt>(Cursor::new(chunk_data))
-            .map_err(|e| ChunkParsingError::ErrorDeserializingChunk(e.to_string()))?;
+        let (chunk_entity_data, upgraded) =
+            parse_upgrading::<EntityNbt>(chunk_data, DataKind::Entities, |nbt| nbt.data_version)?;
 
         if chunk_entity_data.position[0] != position.x
             || chunk_entity_data.position[1] != position.y
@@ -347,7 +422,7 @@ impl ChunkEntityData {
             x: position.x,
             z: position.y,
             data: Mutex::new(map),
-            dirty: AtomicBool::new(false),
+            dirty: AtomicBool::new(upgraded),
         })
     }
 
@@ -404,7 +479,54 @@ pub struct ChunkSectionBlockStates {
         skip_serializing_if = "Option::is_none"
     )]
     pub(crate) data: Option<Box<[i64]>>,
-    pub(crate) palette: Vec<BlockStateCodec>,
+    pub(crate) palette: Vec<PaletteBlockEntry>,
+}
+
+/// A block state as saved in a section's palette. Kept by name, so that blocks unknown to this
+/// version don't fail the whole chunk.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct PaletteBlockEntry {
+    /// Block name
+    pub name: String,
+    /// Key-value pairs of properties
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<HashMap<String, String>>,
+}
+
+impl PaletteBlockEntry {
+    /// The state this entry names, or `None` if the block is unknown or one of the properties is
+    /// unknown or has an invalid value.
+    #[must_use]
+    pub fn try_state_id(&self) -> Option<BlockStateId> {
+        let block = Block::from_name(&self.name)?;
+        match &self.properties {
+            Some(properties) if !properties.is_empty() => {
+                let props: Vec<_> = properties
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect();
+                Some(block.try_from_properties(&props)?.to_state_id(block))
+            }
+            _ => Some(block.default_state.id),
+        }
+    }
+}
+
+/// Formats the entry as `name[key=value,...]`, with the properties sorted.
+impl fmt::Display for PaletteBlockEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(properties) = &self.properties {
+            let mut properties: Vec<_> = properties
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect();
+            properties.sort();
+            write!(f, "[{}]", properties.join(","))?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -445,6 +567,16 @@ impl LightContainer {
         matches!(self, Self::Empty(_))
     }
 
+    /// The light as saved in a section, or `None` if it's dark, which the game leaves out.
+    #[must_use]
+    pub fn to_disk(&self) -> Option<Box<[u8]>> {
+        match self {
+            Self::Full(data) => Some(data.clone()),
+            Self::Empty(0) => None,
+            Self::Empty(default) => Some([*default << 4 | *default; Self::ARRAY_SIZE].into()),
+        }
+    }
+
     const fn index(x: usize, y: usize, z: usize) -> usize {
         y * 16 * 16 + z * 16 + x
     }
@@ -497,15 +629,18 @@ struct ChunkNbt {
     status: ChunkStatus,
     #[serde(rename = "sections")]
     sections: Vec<ChunkSectionNBT>,
+    #[serde(skip_deserializing)]
     heightmaps: ChunkHeightmaps,
-    #[serde(rename = "block_ticks")]
+    #[serde(rename = "block_ticks", default)]
     block_ticks: Vec<ScheduledTick<&'static Block>>,
-    #[serde(rename = "fluid_ticks")]
+    #[serde(rename = "fluid_ticks", default)]
     fluid_ticks: Vec<ScheduledTick<&'static Fluid>>,
-    #[serde(rename = "block_entities")]
+    #[serde(rename = "block_entities", default)]
     block_entities: Vec<NbtCompound>,
-    #[serde(rename = "isLightOn")]
+    #[serde(rename = "isLightOn", default)]
     light_correct: bool,
+    #[serde(default)]
+    inhabited_time: i64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -515,3 +650,48 @@ struct EntityNbt {
     position: [i32; 2],
     entities: Vec<NbtCompound>,
 }
+
+/// Parses `bytes`, upgrading them first if they were saved by an older version of the game.
+/// Also returns whether they were upgraded.
+fn parse_upgrading<T: DeserializeOwned>(
+    bytes: &[u8],
+    kind: DataKind,
+    data_version: fn(&T) -> i32,
+) -> Result<(T, bool), ChunkParsingError> {
+    let parsed = match from_slice::<T>(bytes) {
+        Ok(nbt) if !upgrade::needs_upgrade(data_version(&nbt)) => return Ok((nbt, false)),
+        parsed => parsed.map(|nbt| (nbt, false)),
+    };
+    match upgrade::upgrade(bytes, kind) {
+        Ok(Some(upgraded)) => from_slice(&upgraded).map(|nbt| (nbt, true)).map_err(|e| {
+            ChunkParsingError::ErrorDeserializingChunk(format!("After upgrading: {e}"))
+        }),
+        Ok(None) => parsed.map_err(|e| ChunkParsingError::ErrorDeserializingChunk(e.to_string())),
+        Err(err) => parsed.map_err(|e| {
+            ChunkParsingError::ErrorDeserializingChunk(format!("{e}, and can't upgrade: {err}"))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_heightmaps_like_the_game() {
+        // 384 blocks high: 9 bits, 7 heights per long
+        let packed = pack_heightmap(384, (0..256).map(|i| i % 2 + 1));
+        assert_eq!(packed.len(), 37);
+        assert_eq!(
+            packed[0],
+            0b000000001_000000010_000000001_000000010_000000001_000000010_000000001
+        );
+        assert_eq!(packed[36], 0b000000010_000000001_000000010_000000001);
+
+        // The nether, 256 blocks high, still needs 9 bits
+        assert_eq!(
+            pack_heightmap(256, std::iter::repeat_n(256, 256))[0] >> 54,
+            256
+        );
+    }
+}
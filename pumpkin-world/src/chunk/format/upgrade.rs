@@ -0,0 +1,832 @@
+//! Upgrades of chunks and entities saved by older versions of the game, like its `DataFixers`.
+//!
+//! Data is upgraded on load by every migration newer than the version it was saved with, in
+//! order. Only data from the flattening of 17w47a on can be upgraded, as older chunks store
+//! blocks by numeric id.
+
+use std::io::Cursor;
+
+use pumpkin_nbt::{Nbt, compound::NbtCompound, deserializer::NbtReadHelper, tag::NbtTag};
+use pumpkin_util::encompassing_bits;
+use thiserror::Error;
+
+use super::anvil::WORLD_DATA_VERSION;
+
+/// 17w47a, the first version saving block states by name.
+pub const FLATTENING_DATA_VERSION: i32 = 1451;
+
+#[derive(Error, Debug)]
+pub enum UpgradeError {
+    #[error("Saved without a data version, before 1.9")]
+    MissingDataVersion,
+    #[error("Saved with data version {0}, before the flattening of 1.13")]
+    BeforeFlattening(i32),
+    #[error("Invalid NBT: {0}")]
+    Nbt(#[from] pumpkin_nbt::Error),
+}
+
+/// What the upgraded data holds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataKind {
+    /// The blocks, biomes and block entities of a chunk, from the region folder.
+    Chunk,
+    /// The entities of a chunk, from the entities folder.
+    Entities,
+}
+
+struct Migration {
+    /// The data version that introduced the change.
+    data_version: i32,
+    /// Upgrades data saved before `data_version`.
+    upgrade: fn(&mut NbtCompound, DataKind),
+}
+
+const MIGRATIONS: &[Migration] = &[
+    // 20w06a: zombie pigmen became zombified piglins
+    Migration {
+        data_version: 2509,
+        upgrade: rename_zombie_pigmen,
+    },
+    // 20w17a: packed values no longer span two longs
+    Migration {
+        data_version: 2527,
+        upgrade: align_block_states,
+    },
+    // 20w45a: grass paths became dirt paths
+    Migration {
+        data_version: 2680,
+        upgrade: rename_grass_paths,
+    },
+    // 20w46a: copper blocks got their final names
+    Migration {
+        data_version: 2691,
+        upgrade: rename_copper,
+    },
+    // 21w37a: the overworld goes down to -64 and biomes are saved per section
+    Migration {
+        data_version: 2832,
+        upgrade: extend_height_and_biomes,
+    },
+    // 21w43a: the fields of the `Level` compound moved to the root and were renamed
+    Migration {
+        data_version: 2842,
+        upgrade: flatten_level,
+    },
+    // 23w46a: grass became short grass
+    Migration {
+        data_version: 3692,
+        upgrade: rename_grass,
+    },
+    // 24w09a: item stacks keep their data in components
+    Migration {
+        data_version: 3818,
+        upgrade: componentize_item_stacks,
+    },
+];
+
+/// Whether data saved with `data_version` is upgraded on load.
+#[must_use]
+pub fn needs_upgrade(data_version: i32) -> bool {
+    MIGRATIONS
+        .last()
+        .is_some_and(|migration| data_version < migration.data_version)
+}
+
+/// Upgrades the serialized `bytes` to the current data version, or returns `None` if they are
+/// up to date.
+pub fn upgrade(bytes: &[u8], kind: DataKind) -> Result<Option<Vec<u8>>, UpgradeError> {
+    let mut nbt = Nbt::read(&mut NbtReadHelper::new(Cursor::new(bytes)))?;
+    let data_version = nbt
+        .root_tag
+        .get_int("DataVersion")
+        .ok_or(UpgradeError::MissingDataVersion)?;
+    if !needs_upgrade(data_version) {
+        return Ok(None);
+    }
+    if data_version < FLATTENING_DATA_VERSION {
+        return Err(UpgradeError::BeforeFlattening(data_version));
+    }
+    upgrade_nbt(&mut nbt.root_tag, data_version, kind);
+    Ok(Some(nbt.write().to_vec()))
+}
+
+/// Runs the migrations newer than `data_version` on `nbt`.
+pub fn upgrade_nbt(nbt: &mut NbtCompound, data_version: i32, kind: DataKind) {
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.data_version > data_version)
+    {
+        (migration.upgrade)(nbt, kind);
+    }
+    set(nbt, "DataVersion", NbtTag::Int(WORLD_DATA_VERSION));
+}
+
+fn rename_zombie_pigmen(nbt: &mut NbtCompound, _kind: DataKind) {
+    rename_ids(
+        nbt,
+        &[
+            ("minecraft:zombie_pigman", "minecraft:zombified_piglin"),
+            (
+                "minecraft:zombie_pigman_spawn_egg",
+                "minecraft:zombified_piglin_spawn_egg",
+            ),
+        ],
+    );
+}
+
+fn rename_grass_paths(nbt: &mut NbtCompound, kind: DataKind) {
+    rename_blocks_and_items(
+        nbt,
+        kind,
+        &[("minecraft:grass_path", "minecraft:dirt_path")],
+    );
+}
+
+fn rename_copper(nbt: &mut NbtCompound, kind: DataKind) {
+    rename_blocks_and_items(
+        nbt,
+        kind,
+        &[
+            ("minecraft:waxed_copper", "minecraft:waxed_copper_block"),
+            (
+                "minecraft:oxidized_copper_block",
+                "minecraft:oxidized_copper",
+            ),
+            (
+                "minecraft:weathered_copper_block",
+                "minecraft:weathered_copper",
+            ),
+        ],
+    );
+}
+
+fn rename_grass(nbt: &mut NbtCompound, kind: DataKind) {
+    rename_blocks_and_items(nbt, kind, &[("minecraft:grass", "minecraft:short_grass")]);
+}
+
+fn rename_blocks_and_items(nbt: &mut NbtCompound, kind: DataKind, renames: &[(&str, &str)]) {
+    if kind == DataKind::Chunk {
+        for section in sections_mut(nbt) {
+            for entry in block_palette_mut(section).into_iter().flatten() {
+                if let NbtTag::Compound(entry) = entry {
+                    rename_value(entry, "Name", renames);
+                }
+            }
+        }
+    }
+    rename_ids(nbt, renames);
+}
+
+/// Renames the `id` of every compound in `nbt`: item stacks, entities and block entities.
+fn rename_ids(nbt: &mut NbtCompound, renames: &[(&str, &str)]) {
+    for_each_compound(nbt, &mut |compound| rename_value(compound, "id", renames));
+}
+
+fn rename_value(compound: &mut NbtCompound, key: &str, renames: &[(&str, &str)]) {
+    let Some(value) = compound.get_string(key) else {
+        return;
+    };
+    if let Some((_, new)) = renames.iter().find(|(old, _)| *old == value) {
+        set(compound, key, NbtTag::String((*new).to_string()));
+    }
+}
+
+/// Repacks the block states of sections, which were packed across longs.
+fn align_block_states(nbt: &mut NbtCompound, kind: DataKind) {
+    if kind != DataKind::Chunk {
+        return;
+    }
+    for section in sections_mut(nbt) {
+        let palette_len = section.get_list("Palette").map_or(0, <[NbtTag]>::len);
+        let Some(NbtTag::LongArray(data)) = section.remove("BlockStates") else {
+            continue;
+        };
+        let bits = encompassing_bits(palette_len).max(4);
+        let values = unpack_spanning(&data, bits, 4096);
+        section.put("BlockStates", NbtTag::LongArray(pack(&values, bits)));
+    }
+}
+
+fn unpack_spanning(data: &[i64], bits: u8, count: usize) -> Vec<u64> {
+    let bits = usize::from(bits);
+    let mask = (1u64 << bits) - 1;
+    (0..count)
+        .map(|i| {
+            let (word, shift) = (i * bits / 64, i * bits % 64);
+            let low = data.get(word).map_or(0, |&long| long as u64 >> shift);
+            let high = if shift + bits > 64 {
+                data.get(word + 1)
+                    .map_or(0, |&long| (long as u64) << (64 - shift))
+            } else {
+                0
+            };
+            (low | high) & mask
+        })
+        .collect()
+}
+
+/// Packs `values` as the game does since 20w17a: as many per long as fit, from the lowest bits.
+fn pack(values: &[u64], bits: u8) -> Vec<i64> {
+    let bits = usize::from(bits);
+    let per_long = 64 / bits;
+    let mut data = vec![0u64; values.len().div_ceil(per_long)];
+    for (i, value) in values.iter().enumerate() {
+        data[i / per_long] |= value << (i % per_long * bits);
+    }
+    data.into_iter().map(|long| long as i64).collect()
+}
+
+/// Adds the sections of the overworld below 0 and above 255, and moves the biomes of the chunk
+/// into its sections, like the game's `ChunkHeightAndBiomeFix`.
+fn extend_height_and_biomes(nbt: &mut NbtCompound, kind: DataKind) {
+    if kind != DataKind::Chunk {
+        return;
+    }
+    let Some(level) = compound_mut(nbt, "Level") else {
+        return;
+    };
+    let biomes = match level.remove("Biomes") {
+        Some(NbtTag::IntArray(biomes)) => biomes,
+        _ => Vec::new(),
+    };
+    // Nether and end chunks keep their height, but chunks don't know their dimension
+    let overworld = biomes.is_empty()
+        || biomes
+            .iter()
+            .any(|&biome| !is_nether_or_end(legacy_biome(biome)));
+    let (min_section, max_section) = if overworld { (-4, 19) } else { (0, 15) };
+
+    let mut old_sections = match level.remove("Sections") {
+        Some(NbtTag::List(sections)) => sections,
+        _ => Vec::new(),
+    };
+    let mut sections = Vec::new();
+    for y in min_section..=max_section {
+        let existing = old_sections.iter().position(|section| {
+            matches!(section, NbtTag::Compound(section)
+                if section.get_byte("Y") == Some(y as i8) && section.get("Palette").is_some())
+        });
+        let mut section = match existing.map(|index| old_sections.swap_remove(index)) {
+            Some(NbtTag::Compound(section)) => section,
+            _ if y < 0 => deepslate_section(y == min_section),
+            _ => uniform_section("minecraft:air"),
+        };
+        set(&mut section, "Y", NbtTag::Byte(y as i8));
+        if overworld && y == 0 {
+            // The old bedrock floor is inside the world now
+            for entry in block_palette_mut(&mut section).into_iter().flatten() {
+                if let NbtTag::Compound(entry) = entry {
+                    rename_value(
+                        entry,
+                        "Name",
+                        &[("minecraft:bedrock", "minecraft:deepslate")],
+                    );
+                }
+            }
+        }
+        let biome_y = y.clamp(0, 15);
+        set(
+            &mut section,
+            "biomes",
+            NbtTag::Compound(section_biomes(&biomes, biome_y)),
+        );
+        sections.push(NbtTag::Compound(section));
+    }
+    // Light of the sections just below and above the world
+    sections.extend(old_sections);
+
+    level.put("Sections", NbtTag::List(sections));
+    set(level, "yPos", NbtTag::Int(min_section));
+    // The new sections have no light yet
+    level.remove("isLightOn");
+}
+
+fn uniform_section(block: &str) -> NbtCompound {
+    let mut entry = NbtCompound::new();
+    entry.put_string("Name", block.to_string());
+    let mut section = NbtCompound::new();
+    section.put_list("Palette", vec![NbtTag::Compound(entry)]);
+    section
+}
+
+/// A section of deepslate filling the new depths of the overworld, with a bedrock floor at the
+/// bottom of the world.
+fn deepslate_section(bottom: bool) -> NbtCompound {
+    if !bottom {
+        return uniform_section("minecraft:deepslate");
+    }
+    let mut section = uniform_section("minecraft:deepslate");
+    let mut bedrock = NbtCompound::new();
+    bedrock.put_string("Name", "minecraft:bedrock".to_string());
+    if let Some(palette) = list_mut(&mut section, "Palette") {
+        palette.push(NbtTag::Compound(bedrock));
+    }
+    let values: Vec<u64> = (0..4096).map(|i| u64::from(i < 256)).collect();
+    section.put("BlockStates", NbtTag::LongArray(pack(&values, 4)));
+    section
+}
+
+/// The biomes of the section at `section_y`, from the 4x4x4 cells of 1.15 or the columns of
+/// older versions.
+fn section_biomes(biomes: &[i32], section_y: i32) -> NbtCompound {
+    let cells: Vec<&'static str> = (0..64)
+        .map(|cell: usize| {
+            let (x, y, z) = (cell & 3, cell >> 4, (cell >> 2) & 3);
+            let index = if biomes.len() == 1024 {
+                (section_y as usize * 4 + y) << 4 | z << 2 | x
+            } else {
+                z * 4 * 16 + x * 4
+            };
+            legacy_biome(biomes.get(index).copied().unwrap_or(1))
+        })
+        .collect();
+    let mut palette: Vec<&str> = Vec::new();
+    let indices: Vec<u64> = cells
+        .iter()
+        .map(|biome| {
+            let index = palette.iter().position(|entry| entry == biome);
+            index.unwrap_or_else(|| {
+                palette.push(biome);
+                palette.len() - 1
+            }) as u64
+        })
+        .collect();
+
+    let mut section = NbtCompound::new();
+    if palette.len() > 1 {
+        let bits = encompassing_bits(palette.len());
+        section.put("data", NbtTag::LongArray(pack(&indices, bits)));
+    }
+    section.put_list(
+        "palette",
+        palette
+            .into_iter()
+            .map(|biome| NbtTag::String(format!("minecraft:{biome}")))
+            .collect(),
+    );
+    section
+}
+
+/// The current name of a biome saved by numeric id before 1.18.
+const fn legacy_biome(id: i32) -> &'static str {
+    match id {
+        0 => "ocean",
+        2 | 130 => "desert",
+        3 | 20 => "windswept_hills",
+        4 | 18 => "forest",
+        5 | 19 | 133 => "taiga",
+        6 | 134 => "swamp",
+        7 => "river",
+        8 => "nether_wastes",
+        9 => "the_end",
+        10 => "frozen_ocean",
+        11 => "frozen_river",
+        12 | 13 => "snowy_plains",
+        14 | 15 => "mushroom_fields",
+        16 => "beach",
+        21 | 22 | 149 => "jungle",
+        23 | 151 => "sparse_jungle",
+        24 => "deep_ocean",
+        25 => "stony_shore",
+        26 => "snowy_beach",
+        27 | 28 => "birch_forest",
+        29 | 157 => "dark_forest",
+        30 | 31 | 158 => "snowy_taiga",
+        32 | 33 => "old_growth_pine_taiga",
+        34 => "windswept_forest",
+        35 => "savanna",
+        36 => "savanna_plateau",
+        37 | 39 | 167 => "badlands",
+        38 | 166 => "wooded_badlands",
+        40 => "small_end_islands",
+        41 => "end_midlands",
+        42 => "end_highlands",
+        43 => "end_barrens",
+        44 | 47 => "warm_ocean",
+        45 => "lukewarm_ocean",
+        46 => "cold_ocean",
+        48 => "deep_lukewarm_ocean",
+        49 => "deep_cold_ocean",
+        50 => "deep_frozen_ocean",
+        127 => "the_void",
+        129 => "sunflower_plains",
+        131 | 162 => "windswept_gravelly_hills",
+        132 => "flower_forest",
+        140 => "ice_spikes",
+        155 | 156 => "old_growth_birch_forest",
+        160 | 161 => "old_growth_spruce_taiga",
+        163 | 164 => "windswept_savanna",
+        165 => "eroded_badlands",
+        168 | 169 => "bamboo_jungle",
+        170 => "soul_sand_valley",
+        171 => "crimson_forest",
+        172 => "warped_forest",
+        173 => "basalt_deltas",
+        174 => "dripstone_caves",
+        175 => "lush_caves",
+        _ => "plains",
+    }
+}
+
+fn is_nether_or_end(biome: &str) -> bool {
+    matches!(
+        biome,
+        "nether_wastes"
+            | "soul_sand_valley"
+            | "crimson_forest"
+            | "warped_forest"
+            | "basalt_deltas"
+            | "the_end"
+            | "small_end_islands"
+            | "end_midlands"
+            | "end_highlands"
+            | "end_barrens"
+    )
+}
+
+/// Moves the fields of the `Level` compound to the root under their new names, like the game's
+/// `ChunkRenamesFix`.
+fn flatten_level(nbt: &mut NbtCompound, kind: DataKind) {
+    if kind != DataKind::Chunk {
+        return;
+    }
+    let Some(NbtTag::Compound(level)) = nbt.remove("Level") else {
+        return;
+    };
+    for (key, value) in level.child_tags {
+        let key = match key.as_str() {
+            "Sections" => "sections",
+            "TileEntities" => "block_entities",
+            "TileTicks" => "block_ticks",
+            "LiquidTicks" => "fluid_ticks",
+            "Structures" => "structures",
+            "CarvingMasks" => "carving_masks",
+            key => key,
+        }
+        .to_string();
+        set(nbt, &key, value);
+    }
+
+    for section in list_mut(nbt, "sections").into_iter().flatten() {
+        let NbtTag::Compound(section) = section else {
+            continue;
+        };
+        let palette = section.remove("Palette");
+        let data = section.remove("BlockStates");
+        if let Some(palette) = palette {
+            let mut block_states = NbtCompound::new();
+            block_states.put("palette", palette);
+            if let Some(data) = data {
+                block_states.put("data", data);
+            }
+            section.put_component("block_states", block_states);
+        }
+    }
+
+    if let Some(status) = nbt.get_string("Status") {
+        let status = upgrade_status(status.strip_prefix("minecraft:").unwrap_or(status));
+        set(nbt, "Status", NbtTag::String(format!("minecraft:{status}")));
+    }
+}
+
+/// The current name of a generation status of 1.13 or later.
+fn upgrade_status(status: &str) -> &str {
+    match status {
+        "base" => "noise",
+        "carved" | "liquid_carved" | "liquid_carvers" => "carvers",
+        "decorated" => "features",
+        "lighted" => "light",
+        "mobs_spawned" | "heightmaps" => "spawn",
+        "finalized" | "fullchunk" | "postprocessed" => "full",
+        status => status,
+    }
+}
+
+/// Converts item stacks from a byte `Count` and a `tag` compound to an int `count` and
+/// components. Only the data Pumpkin reads is converted.
+fn componentize_item_stacks(nbt: &mut NbtCompound, _kind: DataKind) {
+    for_each_compound(nbt, &mut |stack| {
+        if stack.get_string("id").is_none() {
+            return;
+        }
+        let Some(count) = stack.remove("Count") else {
+            return;
+        };
+        stack.put("count", NbtTag::Int(number(&count).unwrap_or(1) as i32));
+        let Some(NbtTag::Compound(tag)) = stack.remove("tag") else {
+            return;
+        };
+
+        let mut components = NbtCompound::new();
+        if let Some(damage) = tag.get("Damage").and_then(number)
+            && damage > 0
+        {
+            components.put("minecraft:damage", NbtTag::Int(damage as i32));
+        }
+        if tag.get("Unbreakable").and_then(number) == Some(1) {
+            components.put_component("minecraft:unbreakable", NbtCompound::new());
+        }
+        for (old, new) in [
+            ("Enchantments", "minecraft:enchantments"),
+            ("StoredEnchantments", "minecraft:stored_enchantments"),
+        ] {
+            let mut levels = NbtCompound::new();
+            for enchantment in tag.get_list(old).into_iter().flatten() {
+                if let NbtTag::Compound(enchantment) = enchantment
+                    && let Some(id) = enchantment.get_string("id")
+                    && let Some(level) = enchantment.get("lvl").and_then(number)
+                {
+                    levels.put(id, NbtTag::Int(level as i32));
+                }
+            }
+            if !levels.is_empty() {
+                components.put_component(new, levels);
+            }
+        }
+        if !components.is_empty() {
+            stack.put_component("components", components);
+        }
+    });
+}
+
+fn number(tag: &NbtTag) -> Option<i64> {
+    match tag {
+        NbtTag::Byte(value) => Some(i64::from(*value)),
+        NbtTag::Short(value) => Some(i64::from(*value)),
+        NbtTag::Int(value) => Some(i64::from(*value)),
+        NbtTag::Long(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// The sections of a chunk, in the `Level` compound before 21w43a.
+fn sections_mut(chunk: &mut NbtCompound) -> impl Iterator<Item = &mut NbtCompound> {
+    let sections = if chunk.get("Level").is_some() {
+        compound_mut(chunk, "Level").and_then(|level| list_mut(level, "Sections"))
+    } else {
+        list_mut(chunk, "sections")
+    };
+    sections
+        .into_iter()
+        .flatten()
+        .filter_map(|section| match section {
+            NbtTag::Compound(section) => Some(section),
+            _ => None,
+        })
+}
+
+/// The block palette of a section, in `block_states` since 21w43a.
+fn block_palette_mut(section: &mut NbtCompound) -> Option<&mut Vec<NbtTag>> {
+    if section.get("Palette").is_some() {
+        list_mut(section, "Palette")
+    } else {
+        compound_mut(section, "block_states").and_then(|states| list_mut(states, "palette"))
+    }
+}
+
+fn for_each_compound(compound: &mut NbtCompound, f: &mut impl FnMut(&mut NbtCompound)) {
+    f(compound);
+    for (_, tag) in &mut compound.child_tags {
+        for_each_compound_in(tag, f);
+    }
+}
+
+fn for_each_compound_in(tag: &mut NbtTag, f: &mut impl FnMut(&mut NbtCompound)) {
+    match tag {
+        NbtTag::Compound(compound) => for_each_compound(compound, f),
+        NbtTag::List(list) => {
+            for tag in list {
+                for_each_compound_in(tag, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn compound_mut<'a>(compound: &'a mut NbtCompound, key: &str) -> Option<&'a mut NbtCompound> {
+    compound
+        .child_tags
+        .iter_mut()
+        .find(|(name, _)| name == key)
+        .and_then(|(_, tag)| match tag {
+            NbtTag::Compound(compound) => Some(compound),
+            _ => None,
+        })
+}
+
+fn list_mut<'a>(compound: &'a mut NbtCompound, key: &str) -> Option<&'a mut Vec<NbtTag>> {
+    compound
+        .child_tags
+        .iter_mut()
+        .find(|(name, _)| name == key)
+        .and_then(|(_, tag)| match tag {
+            NbtTag::List(list) => Some(list),
+            _ => None,
+        })
+}
+
+/// Sets `key`, replacing its value if there is one.
+fn set(compound: &mut NbtCompound, key: &str, value: NbtTag) {
+    compound.remove(key);
+    compound.put(key, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_data::{Block, chunk::Biome};
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::*;
+    use crate::chunk::{ChunkData, io::Dirtiable};
+
+    const BLOCKS: [&str; 17] = [
+        "minecraft:air",
+        "minecraft:grass_path",
+        "minecraft:grass",
+        "minecraft:stone",
+        "minecraft:dirt",
+        "minecraft:cobblestone",
+        "minecraft:oak_planks",
+        "minecraft:sand",
+        "minecraft:gravel",
+        "minecraft:gold_ore",
+        "minecraft:iron_ore",
+        "minecraft:coal_ore",
+        "minecraft:glass",
+        "minecraft:sandstone",
+        "minecraft:bricks",
+        "minecraft:obsidian",
+        "minecraft:bedrock",
+    ];
+
+    fn compound(tags: Vec<(&str, NbtTag)>) -> NbtCompound {
+        let mut compound = NbtCompound::new();
+        for (key, value) in tags {
+            compound.put(key, value);
+        }
+        compound
+    }
+
+    fn string(value: &str) -> NbtTag {
+        NbtTag::String(value.to_string())
+    }
+
+    fn pack_spanning(values: &[u64], bits: usize) -> Vec<i64> {
+        let mut data = vec![0u64; (values.len() * bits).div_ceil(64)];
+        for (i, value) in values.iter().enumerate() {
+            let (word, shift) = (i * bits / 64, i * bits % 64);
+            data[word] |= value << shift;
+            if shift + bits > 64 {
+                data[word + 1] |= value >> (64 - shift);
+            }
+        }
+        data.into_iter().map(|long| long as i64).collect()
+    }
+
+    /// A chunk of 1.15.2 in the desert, with a single section of 17 blocks, which take 5 bits.
+    fn old_chunk() -> NbtCompound {
+        let palette = BLOCKS
+            .iter()
+            .map(|name| NbtTag::Compound(compound(vec![("Name", string(name))])))
+            .collect();
+        let values: Vec<u64> = (0..4096).map(|i| i % 17).collect();
+        let section = compound(vec![
+            ("Y", NbtTag::Byte(0)),
+            ("Palette", NbtTag::List(palette)),
+            ("BlockStates", NbtTag::LongArray(pack_spanning(&values, 5))),
+        ]);
+        let sword = compound(vec![
+            ("Slot", NbtTag::Byte(1)),
+            ("id", string("minecraft:diamond_sword")),
+            ("Count", NbtTag::Byte(1)),
+            (
+                "tag",
+                NbtTag::Compound(compound(vec![
+                    ("Damage", NbtTag::Int(5)),
+                    (
+                        "Enchantments",
+                        NbtTag::List(vec![NbtTag::Compound(compound(vec![
+                            ("id", string("minecraft:sharpness")),
+                            ("lvl", NbtTag::Short(2)),
+                        ]))]),
+                    ),
+                ])),
+            ),
+        ]);
+        let grass = compound(vec![
+            ("Slot", NbtTag::Byte(0)),
+            ("id", string("minecraft:grass")),
+            ("Count", NbtTag::Byte(3)),
+        ]);
+        let chest = compound(vec![
+            ("id", string("minecraft:chest")),
+            ("x", NbtTag::Int(0)),
+            ("y", NbtTag::Int(80)),
+            ("z", NbtTag::Int(0)),
+            (
+                "Items",
+                NbtTag::List(vec![NbtTag::Compound(grass), NbtTag::Compound(sword)]),
+            ),
+        ]);
+        let level = compound(vec![
+            ("xPos", NbtTag::Int(2)),
+            ("zPos", NbtTag::Int(-3)),
+            ("Status", string("full")),
+            ("isLightOn", NbtTag::Byte(1)),
+            ("Sections", NbtTag::List(vec![NbtTag::Compound(section)])),
+            ("Biomes", NbtTag::IntArray(vec![2; 1024])),
+            ("TileEntities", NbtTag::List(vec![NbtTag::Compound(chest)])),
+        ]);
+        compound(vec![
+            ("DataVersion", NbtTag::Int(2230)),
+            ("Level", NbtTag::Compound(level)),
+        ])
+    }
+
+    #[test]
+    fn upgrades_chunks_of_1_15() {
+        let bytes = Nbt::new(String::new(), old_chunk()).write();
+        let upgraded = upgrade(&bytes, DataKind::Chunk).unwrap().unwrap();
+        let chunk = ChunkData::internal_from_bytes(&upgraded, Vector2::new(2, -3)).unwrap();
+        assert!(chunk.is_dirty());
+
+        let block = |x, y, z| chunk.section.get_block_absolute_y(x, y, z).unwrap();
+        let default_state = |name| Block::from_name(name).unwrap().default_state.id;
+        for i in [0, 1, 2, 16, 17, 100, 4095] {
+            let (x, y, z) = (i % 16, (i / 256) as i32, i / 16 % 16);
+            let name = match BLOCKS[i % 17] {
+                "minecraft:grass_path" => "minecraft:dirt_path",
+                "minecraft:grass" => "minecraft:short_grass",
+                "minecraft:bedrock" => "minecraft:deepslate",
+                name => name,
+            };
+            assert_eq!(block(x, y, z), default_state(name), "block {i}");
+        }
+        assert_eq!(block(0, -64, 0), default_state("minecraft:bedrock"));
+        assert_eq!(block(5, -1, 9), default_state("minecraft:deepslate"));
+        assert_eq!(block(5, 300, 9), default_state("minecraft:air"));
+        assert_eq!(
+            chunk.section.get_noise_biome(0, 1, 2, 3),
+            Some(Biome::DESERT.id)
+        );
+    }
+
+    #[test]
+    fn componentizes_item_stacks() {
+        let mut chunk = old_chunk();
+        upgrade_nbt(&mut chunk, 2230, DataKind::Chunk);
+        assert_eq!(chunk.get_int("DataVersion"), Some(WORLD_DATA_VERSION));
+        assert_eq!(chunk.get_string("Status"), Some("minecraft:full"));
+
+        let chest = &chunk.get_list("block_entities").unwrap()[0];
+        let NbtTag::Compound(chest) = chest else {
+            panic!("not a compound");
+        };
+        let items = chest.get_list("Items").unwrap();
+        let (NbtTag::Compound(grass), NbtTag::Compound(sword)) = (&items[0], &items[1]) else {
+            panic!("not compounds");
+        };
+        assert_eq!(grass.get_string("id"), Some("minecraft:short_grass"));
+        assert_eq!(grass.get_int("count"), Some(3));
+        assert!(grass.get("components").is_none());
+
+        let components = sword.get_compound("components").unwrap();
+        assert_eq!(sword.get_int("count"), Some(1));
+        assert_eq!(components.get_int("minecraft:damage"), Some(5));
+        assert_eq!(
+            components
+                .get_compound("minecraft:enchantments")
+                .and_then(|enchantments| enchantments.get_int("minecraft:sharpness")),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn renames_entities() {
+        let pigman = compound(vec![("id", string("minecraft:zombie_pigman"))]);
+        let mut entities = compound(vec![
+            ("DataVersion", NbtTag::Int(2230)),
+            ("Entities", NbtTag::List(vec![NbtTag::Compound(pigman)])),
+        ]);
+        upgrade_nbt(&mut entities, 2230, DataKind::Entities);
+        let NbtTag::Compound(entity) = &entities.get_list("Entities").unwrap()[0] else {
+            panic!("not a compound");
+        };
+        assert_eq!(entity.get_string("id"), Some("minecraft:zombified_piglin"));
+    }
+
+    #[test]
+    fn keeps_recent_data() {
+        assert!(!needs_upgrade(WORLD_DATA_VERSION));
+        assert!(needs_upgrade(FLATTENING_DATA_VERSION));
+        let bytes = Nbt::new(
+            String::new(),
+            compound(vec![("DataVersion", NbtTag::Int(1343))]),
+        )
+        .write();
+        assert!(matches!(
+            upgrade(&bytes, DataKind::Chunk),
+            Err(UpgradeError::BeforeFlattening(1343))
+        ));
+    }
+}
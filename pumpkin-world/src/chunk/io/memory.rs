@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use pumpkin_util::math::vector2::Vector2;
+
+use crate::{
+    chunk::{
+        ChunkReadingError, ChunkWritingError,
+        io::{BoxFuture, Dirtiable},
+    },
+    level::LevelFolder,
+};
+
+use super::{FileIO, LoadedData};
+
+/// Keeps saved chunks in memory instead of writing them, on top of another `FileIO` that is
+/// only ever read from.
+///
+/// Used for worlds that must never touch the disk, like arenas created from a template world:
+/// chunks are read from the template until they are saved once, and all changes are lost when
+/// the overlay is dropped.
+pub struct MemoryOverlay<D> {
+    base: Arc<dyn FileIO<Data = Arc<D>>>,
+    saved: DashMap<Vector2<i32>, Arc<D>>,
+}
+
+impl<D> MemoryOverlay<D> {
+    #[must_use]
+    pub fn new(base: Arc<dyn FileIO<Data = Arc<D>>>) -> Self {
+        Self {
+            base,
+            saved: DashMap::new(),
+        }
+    }
+}
+
+impl<D> FileIO for MemoryOverlay<D>
+where
+    D: Dirtiable + Send + Sync + 'static,
+{
+    type Data = Arc<D>;
+
+    fn fetch_chunks<'a>(
+        &'a self,
+        folder: &'a LevelFolder,
+        chunk_coords: &'a [Vector2<i32>],
+        stream: tokio::sync::mpsc::Sender<LoadedData<Self::Data, ChunkReadingError>>,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let mut unsaved = Vec::new();
+            for pos in chunk_coords {
+                let saved = self.saved.get(pos).map(|chunk| chunk.value().clone());
+                match saved {
+                    Some(chunk) => {
+                        if stream.send(LoadedData::Loaded(chunk)).await.is_err() {
+                            return;
+                        }
+                    }
+                    None => unsaved.push(*pos),
+                }
+            }
+            if !unsaved.is_empty() {
+                self.base.fetch_chunks(folder, &unsaved, stream).await;
+            }
+        })
+    }
+
+    fn save_chunks<'a>(
+        &'a self,
+        _folder: &'a LevelFolder,
+        chunks_data: Vec<(Vector2<i32>, Self::Data)>,
+    ) -> BoxFuture<'a, Result<(), ChunkWritingError>> {
+        Box::pin(async move {
+            for (pos, chunk) in chunks_data {
+                // Unchanged chunks are still the same in the base
+                if chunk.is_dirty() {
+                    chunk.mark_dirty(false);
+                    self.saved.insert(pos, chunk);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn watch_chunks<'a>(
+        &'a self,
+        folder: &'a LevelFolder,
+        chunks: &'a [Vector2<i32>],
+    ) -> BoxFuture<'a, ()> {
+        self.base.watch_chunks(folder, chunks)
+    }
+
+    fn unwatch_chunks<'a>(
+        &'a self,
+        folder: &'a LevelFolder,
+        chunks: &'a [Vector2<i32>],
+    ) -> BoxFuture<'a, ()> {
+        self.base.unwatch_chunks(folder, chunks)
+    }
+
+    fn clear_watched_chunks(&self) -> BoxFuture<'_, ()> {
+        self.base.clear_watched_chunks()
+    }
+
+    fn block_and_await_ongoing_tasks(&self) -> BoxFuture<'_, ()> {
+        self.base.block_and_await_ongoing_tasks()
+    }
+}
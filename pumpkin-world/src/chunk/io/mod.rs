@@ -7,6 +7,7 @@ use super::{ChunkReadingError, ChunkWritingError};
 use crate::level::LevelFolder;
 
 pub mod file_manager;
+pub mod memory;
 
 /// The result of loading a chunk data.
 ///
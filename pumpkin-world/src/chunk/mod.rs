@@ -15,7 +15,7 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 use std::ops::{BitAnd, BitOr};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicI64};
 use std::sync::{Arc, RwLock};
 use thiserror::Error;
 use tokio::sync::Mutex;
@@ -23,6 +23,7 @@ use tokio::sync::Mutex;
 pub mod format;
 pub mod io;
 pub mod palette;
+pub mod snapshot;
 
 // TODO
 pub const CHUNK_WIDTH: usize = BlockPalette::SIZE;
@@ -80,8 +81,15 @@ pub struct ChunkData {
     pub block_ticks: ChunkTickScheduler<&'static Block>,
     pub fluid_ticks: ChunkTickScheduler<&'static Fluid>,
     pub block_entities: std::sync::Mutex<FxHashMap<BlockPos, Arc<dyn BlockEntity>>>,
-    pub light_engine: ChunkLight,
+    /// Sky and block light, one container per section. Written by the light engine of the world
+    /// while the chunk is loaded.
+    pub light_engine: RwLock<ChunkLight>,
+    /// Whether the light was spread across the chunk and its neighbours. Generated chunks start
+    /// without it and are relit once their neighbours are loaded.
+    pub light_correct: AtomicBool,
     pub status: ChunkStatus,
+    /// Ticks players have spent near this chunk, which raises its regional difficulty.
+    pub inhabited_time: AtomicI64,
     pub dirty: AtomicBool,
 }
 
@@ -101,10 +109,13 @@ pub struct ChunkEntityData {
 ///
 /// A chunk can be:
 /// - Subchunks: 24 separate subchunks are stored.
+///
+/// Sections are copied on write, so [snapshots](snapshot::ChunkSnapshot) share them until the
+/// chunk changes. Use [`Arc::make_mut`] to modify a section.
 pub struct ChunkSections {
     pub count: usize,
-    pub block_sections: RwLock<Box<[BlockPalette]>>,
-    pub biome_sections: RwLock<Box<[BiomePalette]>>,
+    pub block_sections: RwLock<Box<[Arc<BlockPalette>]>>,
+    pub biome_sections: RwLock<Box<[Arc<BiomePalette>]>>,
     pub min_y: i32,
 }
 
@@ -289,8 +300,12 @@ impl Default for ChunkHeightmaps {
 impl ChunkSections {
     #[must_use]
     pub fn new(num_sections: usize, min_y: i32) -> Self {
-        let block_sections = vec![BlockPalette::default(); num_sections].into_boxed_slice();
-        let biome_sections = vec![BiomePalette::default(); num_sections].into_boxed_slice();
+        let block_sections = std::iter::repeat_with(Arc::default)
+            .take(num_sections)
+            .collect();
+        let biome_sections = std::iter::repeat_with(Arc::default)
+            .take(num_sections)
+            .collect();
 
         Self {
             count: num_sections,
@@ -404,7 +419,7 @@ impl ChunkSections {
         let section_index = relative_y / BlockPalette::SIZE;
         let relative_y = relative_y % BlockPalette::SIZE;
         if let Some(section) = self.block_sections.write().unwrap().get_mut(section_index) {
-            return section.set(relative_x, relative_y, relative_z, block_state_id);
+            return Arc::make_mut(section).set(relative_x, relative_y, relative_z, block_state_id);
         }
         0
     }
@@ -422,7 +437,7 @@ impl ChunkSections {
         let section_index = relative_y / BiomePalette::SIZE;
         let relative_y = relative_y % BiomePalette::SIZE;
         if let Some(section) = self.biome_sections.write().unwrap().get_mut(section_index) {
-            section.set(relative_x, relative_y, relative_z, biome_id);
+            Arc::make_mut(section).set(relative_x, relative_y, relative_z, biome_id);
         }
     }
 
@@ -1,29 +1,158 @@
 use std::{collections::HashMap, hash::Hash};
 
-use pumpkin_data::{Block, BlockState, block_properties::is_air, chunk::Biome};
+use pumpkin_data::{
+    Block, BlockState,
+    block_properties::{has_random_ticks, is_air},
+    chunk::Biome,
+};
 use pumpkin_util::encompassing_bits;
 
-use crate::block::BlockStateCodec;
+use super::format::{
+    ChunkSectionBiomes, ChunkSectionBlockStates, PaletteBiomeEntry, PaletteBlockEntry,
+};
+
+/// Values stored in a [`PalettedContainer`], with the entry sizes of its storage tiers.
+pub trait PaletteEntry: Hash + Eq + Copy + Default {
+    /// Fewest bits per entry of the indirect tier.
+    const MIN_INDIRECT_BITS: u8;
+    /// Most bits per entry of the indirect tier. Beyond this, values are stored directly.
+    const MAX_INDIRECT_BITS: u8;
+    /// Bits per entry of the direct tier, which hold registry ids of the global palette.
+    const DIRECT_BITS: u8;
+
+    fn to_bits(self) -> u64;
+    fn from_bits(bits: u64) -> Self;
+
+    /// Whether the value is picked up by random ticks.
+    fn ticks_randomly(self) -> bool {
+        false
+    }
+}
+
+impl PaletteEntry for u16 {
+    const MIN_INDIRECT_BITS: u8 = BLOCK_NETWORK_MIN_MAP_BITS;
+    const MAX_INDIRECT_BITS: u8 = BLOCK_NETWORK_MAX_MAP_BITS;
+    const DIRECT_BITS: u8 = BLOCK_NETWORK_MAX_BITS;
 
-use super::format::{ChunkSectionBiomes, ChunkSectionBlockStates, PaletteBiomeEntry};
+    fn to_bits(self) -> u64 {
+        u64::from(self)
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        bits as Self
+    }
 
-/// 3d array indexed by y,z,x
-type AbstractCube<T, const DIM: usize> = [[[T; DIM]; DIM]; DIM];
+    fn ticks_randomly(self) -> bool {
+        has_random_ticks(self)
+    }
+}
+
+#[expect(clippy::use_self)]
+impl PaletteEntry for u8 {
+    const MIN_INDIRECT_BITS: u8 = BIOME_NETWORK_MIN_MAP_BITS;
+    const MAX_INDIRECT_BITS: u8 = BIOME_NETWORK_MAX_MAP_BITS;
+    const DIRECT_BITS: u8 = BIOME_NETWORK_MAX_BITS;
+
+    fn to_bits(self) -> u64 {
+        u64::from(self)
+    }
 
+    fn from_bits(bits: u64) -> Self {
+        bits as Self
+    }
+}
+
+/// Entries of a fixed number of bits, packed into 64-bit words the way the network and disk
+/// formats expect: from the least significant bit, never spanning two words.
 #[derive(Clone)]
-pub struct HeterogeneousPaletteData<V: Hash + Eq + Copy, const DIM: usize> {
-    cube: Box<AbstractCube<V, DIM>>,
+struct PackedArray {
+    bits: u8,
+    words: Box<[i64]>,
+}
+
+impl PackedArray {
+    fn new(bits: u8, len: usize) -> Self {
+        debug_assert!((1..=32).contains(&bits));
+        Self {
+            bits,
+            words: vec![0; len.div_ceil(64 / bits as usize)].into_boxed_slice(),
+        }
+    }
+
+    const fn entries_per_word(&self) -> usize {
+        64 / self.bits as usize
+    }
+
+    const fn mask(&self) -> u64 {
+        (1 << self.bits) - 1
+    }
+
+    fn get(&self, index: usize) -> u64 {
+        let per_word = self.entries_per_word();
+        let shift = (index % per_word) * self.bits as usize;
+        (self.words[index / per_word] as u64 >> shift) & self.mask()
+    }
+
+    fn set(&mut self, index: usize, value: u64) {
+        debug_assert!(value <= self.mask());
+        let per_word = self.entries_per_word();
+        let shift = (index % per_word) * self.bits as usize;
+        let mask = self.mask() << shift;
+        let word = &mut self.words[index / per_word];
+        *word = ((*word as u64 & !mask) | (value << shift)) as i64;
+    }
+}
+
+/// Bits per entry of the storage tier that fits `palette_len` values.
+fn storage_bits<V: PaletteEntry>(palette_len: usize) -> u8 {
+    let bits = encompassing_bits(palette_len).max(V::MIN_INDIRECT_BITS);
+    if bits > V::MAX_INDIRECT_BITS {
+        V::DIRECT_BITS
+    } else {
+        bits
+    }
+}
+
+#[derive(Clone)]
+pub struct HeterogeneousPaletteData<V: PaletteEntry, const DIM: usize> {
+    /// Indices into the palette, or registry ids in the direct tier, in y,z,x order.
+    storage: PackedArray,
+    /// Values that occur in the container. Entries whose count dropped to zero stay in place so
+    /// that the stored indices remain valid, and are reused by the next new value.
     palette: Vec<V>,
     counts: Vec<u16>,
+    /// Entries that tick randomly, kept up to date on every change.
+    random_ticking: u16,
 }
 
-impl<V: Hash + Eq + Copy, const DIM: usize> HeterogeneousPaletteData<V, DIM> {
+impl<V: PaletteEntry, const DIM: usize> HeterogeneousPaletteData<V, DIM> {
+    const fn index(x: usize, y: usize, z: usize) -> usize {
+        (y * DIM + z) * DIM + x
+    }
+
+    const fn is_direct(&self) -> bool {
+        self.storage.bits > V::MAX_INDIRECT_BITS
+    }
+
+    fn position(&self, value: V) -> Option<usize> {
+        self.palette.iter().position(|v| *v == value)
+    }
+
+    fn value_at(&self, index: usize) -> V {
+        let entry = self.storage.get(index);
+        if self.is_direct() {
+            V::from_bits(entry)
+        } else {
+            self.palette[entry as usize]
+        }
+    }
+
     fn get(&self, x: usize, y: usize, z: usize) -> V {
         debug_assert!(x < DIM);
         debug_assert!(y < DIM);
         debug_assert!(z < DIM);
 
-        self.cube[y][z][x]
+        self.value_at(Self::index(x, y, z))
     }
 
     /// Returns the Original
@@ -32,76 +161,150 @@ impl<V: Hash + Eq + Copy, const DIM: usize> HeterogeneousPaletteData<V, DIM> {
         debug_assert!(y < DIM);
         debug_assert!(z < DIM);
 
-        let original = self.cube[y][z][x];
-        let original_index = self.palette.iter().position(|v| v == &original).unwrap();
-        self.counts[original_index] -= 1;
+        let index = Self::index(x, y, z);
+        let original = self.value_at(index);
+        if original == value {
+            return original;
+        }
 
-        if self.counts[original_index] == 0 {
-            // Remove from palette and counts Vecs if the count hits zero.
-            self.palette.swap_remove(original_index);
-            self.counts.swap_remove(original_index);
+        let original_index = self.position(original).unwrap();
+        self.counts[original_index] -= 1;
+        self.random_ticking = self.random_ticking - u16::from(original.ticks_randomly())
+            + u16::from(value.ticks_randomly());
+
+        // Find or add the new value to the palette, reusing an unused entry if there is one
+        let new_index = self
+            .position(value)
+            .or_else(|| {
+                let free = self.counts.iter().position(|&count| count == 0)?;
+                self.palette[free] = value;
+                Some(free)
+            })
+            .unwrap_or_else(|| {
+                self.palette.push(value);
+                self.counts.push(0);
+                self.palette.len() - 1
+            });
+        self.counts[new_index] += 1;
+
+        if !self.is_direct() && encompassing_bits(self.palette.len()) > self.storage.bits {
+            self.resize();
         }
+        let entry = if self.is_direct() {
+            value.to_bits()
+        } else {
+            new_index as u64
+        };
+        self.storage.set(index, entry);
 
-        // Set the new value in the cube
-        self.cube[y][z][x] = value;
+        original
+    }
 
-        // Find or add the new value to the palette.
-        if let Some(new_index) = self.palette.iter().position(|v| v == &value) {
-            self.counts[new_index] += 1;
-        } else {
-            self.palette.push(value);
-            self.counts.push(1);
+    /// Moves the entries into the tier that fits the palette.
+    fn resize(&mut self) {
+        let mut storage = PackedArray::new(storage_bits::<V>(self.palette.len()), DIM * DIM * DIM);
+        let direct = storage.bits > V::MAX_INDIRECT_BITS;
+        for index in 0..DIM * DIM * DIM {
+            let entry = if direct {
+                self.value_at(index).to_bits()
+            } else {
+                self.storage.get(index)
+            };
+            storage.set(index, entry);
         }
+        self.storage = storage;
+    }
 
-        original
+    /// The palette without unused entries, and the new index of every entry.
+    fn compacted_palette(&self) -> (Vec<V>, Vec<u64>) {
+        let mut palette = Vec::with_capacity(self.palette.len());
+        let remap = self
+            .palette
+            .iter()
+            .zip(&self.counts)
+            .map(|(value, &count)| {
+                if count > 0 {
+                    palette.push(*value);
+                }
+                palette.len().saturating_sub(1) as u64
+            })
+            .collect();
+        (palette, remap)
     }
 }
 
 /// A paletted container is a cube of registry ids. It uses a custom compression scheme based on how
 /// may distinct registry ids are in the cube.
+///
+/// A single value is stored on its own, like the single value palette of the network format.
+/// Otherwise the cube is bit-packed into palette indices of at least
+/// [`PaletteEntry::MIN_INDIRECT_BITS`], growing as values are added, until it holds registry ids
+/// of the global palette once [`PaletteEntry::MAX_INDIRECT_BITS`] are exceeded. This is also the
+/// network layout, so sending a section copies its words.
 #[derive(Clone)]
-pub enum PalettedContainer<V: Hash + Eq + Copy + Default, const DIM: usize> {
+pub enum PalettedContainer<V: PaletteEntry, const DIM: usize> {
     Homogeneous(V),
     Heterogeneous(Box<HeterogeneousPaletteData<V, DIM>>),
 }
 
-impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM> {
+impl<V: PaletteEntry, const DIM: usize> PalettedContainer<V, DIM> {
     pub const SIZE: usize = DIM;
     pub const VOLUME: usize = DIM * DIM * DIM;
 
-    fn from_cube(cube: Box<AbstractCube<V, DIM>>) -> Self {
+    fn from_values(values: &[V]) -> Self {
+        debug_assert_eq!(values.len(), Self::VOLUME);
         let mut palette: Vec<V> = Vec::new();
         let mut counts: Vec<u16> = Vec::new();
+        let mut indices = Vec::with_capacity(values.len());
 
-        // Iterate over the flattened cube to populate the palette and counts
-        for val in cube.as_flattened().as_flattened() {
+        for val in values {
             if let Some(index) = palette.iter().position(|v| v == val) {
                 // Value already exists, increment its count
                 counts[index] += 1;
+                indices.push(index);
             } else {
                 // New value, add it to the palette and start its count
                 palette.push(*val);
                 counts.push(1);
+                indices.push(palette.len() - 1);
             }
         }
 
         if palette.len() == 1 {
             // Fast path: the cube is homogeneous, so we can store just one value
-            Self::Homogeneous(palette[0])
-        } else {
-            // Heterogeneous cube, store the full data
-            Self::Heterogeneous(Box::new(HeterogeneousPaletteData {
-                cube,
-                palette,
-                counts,
-            }))
+            return Self::Homogeneous(palette[0]);
         }
+
+        let mut storage = PackedArray::new(storage_bits::<V>(palette.len()), Self::VOLUME);
+        let direct = storage.bits > V::MAX_INDIRECT_BITS;
+        for (index, (value, palette_index)) in values.iter().zip(indices).enumerate() {
+            let entry = if direct {
+                value.to_bits()
+            } else {
+                palette_index as u64
+            };
+            storage.set(index, entry);
+        }
+        let random_ticking = palette
+            .iter()
+            .zip(&counts)
+            .filter(|(value, _)| value.ticks_randomly())
+            .map(|(_, count)| count)
+            .sum();
+        Self::Heterogeneous(Box::new(HeterogeneousPaletteData {
+            storage,
+            palette,
+            counts,
+            random_ticking,
+        }))
     }
 
     fn bits_per_entry(&self) -> u8 {
         match self {
             Self::Homogeneous(_) => 0,
-            Self::Heterogeneous(data) => encompassing_bits(data.counts.len()),
+            Self::Heterogeneous(data) => {
+                encompassing_bits(data.counts.iter().filter(|&&count| count > 0).count())
+            }
         }
     }
 
@@ -109,37 +312,30 @@ impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM>
         match self {
             Self::Homogeneous(registry_id) => (Box::new([*registry_id]), Box::new([])),
             Self::Heterogeneous(data) => {
-                debug_assert!(bits_per_entry >= encompassing_bits(data.counts.len()));
+                let (palette, remap) = data.compacted_palette();
+                debug_assert!(bits_per_entry >= encompassing_bits(palette.len()));
                 debug_assert!(bits_per_entry <= 15);
 
-                // Don't use HashMap's here, because its slow
-                let blocks_per_i64 = 64 / bits_per_entry;
-
-                let packed_indices: Box<[i64]> = data
-                    .cube
-                    .as_flattened()
-                    .as_flattened()
-                    .chunks(blocks_per_i64 as usize)
-                    .map(|chunk| {
-                        chunk.iter().enumerate().fold(0, |acc, (index, key)| {
-                            let key_index = data.palette.iter().position(|&x| x == *key).unwrap();
-                            debug_assert!((1 << bits_per_entry) > key_index);
-
-                            let packed_offset_index =
-                                (key_index as u64) << (bits_per_entry as u64 * index as u64);
-                            acc | packed_offset_index as i64
-                        })
-                    })
-                    .collect();
+                let mut packed = PackedArray::new(bits_per_entry, Self::VOLUME);
+                for index in 0..Self::VOLUME {
+                    let entry = data.storage.get(index);
+                    let key_index = if data.is_direct() {
+                        let value = V::from_bits(entry);
+                        palette.iter().position(|v| *v == value).unwrap() as u64
+                    } else {
+                        remap[entry as usize]
+                    };
+                    packed.set(index, key_index);
+                }
 
-                (data.palette.clone().into_boxed_slice(), packed_indices)
+                (palette.into_boxed_slice(), packed.words)
             }
         }
     }
 
     #[must_use]
     pub fn from_palette_and_packed_data(
-        palette: Vec<V>,
+        palette: &[V],
         packed_data: &[i64],
         minimum_bits_per_entry: u8,
     ) -> Self {
@@ -158,9 +354,6 @@ impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM>
 
         let mut decompressed_values = Vec::with_capacity(Self::VOLUME);
 
-        // We already have the palette from the input `palette_slice`.
-        // The counts will be created in the next step.
-
         let mut packed_data_iter = packed_data.iter();
         let mut current_packed_word = *packed_data_iter.next().unwrap_or(&0);
 
@@ -186,30 +379,7 @@ impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM>
             decompressed_values.push(value);
         }
 
-        // Now, with all decompressed values, build the counts.
-        let mut counts = vec![0; palette.len()];
-
-        for &value in &decompressed_values {
-            // This is the key optimization: find the index in the palette Vec
-            // and increment the corresponding count.
-            if let Some(index) = palette.iter().position(|v| v == &value) {
-                counts[index] += 1;
-            } else {
-                // This case should ideally not happen if the palette is complete.
-                log::warn!("Decompressed value not found in palette!");
-            }
-        }
-
-        let mut cube = Box::new([[[V::default(); DIM]; DIM]; DIM]);
-        cube.as_flattened_mut()
-            .as_flattened_mut()
-            .copy_from_slice(&decompressed_values);
-
-        Self::Heterogeneous(Box::new(HeterogeneousPaletteData {
-            cube,
-            palette,
-            counts,
-        }))
+        Self::from_values(&decompressed_values)
     }
 
     pub fn get(&self, x: usize, y: usize, z: usize) -> V {
@@ -228,16 +398,24 @@ impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM>
             Self::Homogeneous(original) => {
                 let original = *original;
                 if value != original {
-                    let mut cube = Box::new([[[original; DIM]; DIM]; DIM]);
-                    cube[y][z][x] = value;
-                    *self = Self::from_cube(cube);
+                    // Every entry starts out as index 0, the original value
+                    let mut data = HeterogeneousPaletteData {
+                        storage: PackedArray::new(V::MIN_INDIRECT_BITS, Self::VOLUME),
+                        palette: vec![original],
+                        counts: vec![Self::VOLUME as u16],
+                        random_ticking: Self::random_ticking_of(original),
+                    };
+                    data.set(x, y, z, value);
+                    *self = Self::Heterogeneous(Box::new(data));
                 }
                 original
             }
             Self::Heterogeneous(data) => {
                 let original = data.set(x, y, z, value);
-                if data.counts.len() == 1 {
-                    *self = Self::Homogeneous(data.palette[0]);
+                if let Some(index) = data.position(value)
+                    && usize::from(data.counts[index]) == Self::VOLUME
+                {
+                    *self = Self::Homogeneous(value);
                 }
                 original
             }
@@ -255,14 +433,26 @@ impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM>
                 }
             }
             Self::Heterogeneous(data) => {
-                data.cube
-                    .as_flattened()
-                    .as_flattened()
-                    .iter()
-                    .for_each(|value| {
-                        f(*value);
-                    });
+                for index in 0..Self::VOLUME {
+                    f(data.value_at(index));
+                }
+            }
+        }
+    }
+
+    /// Whether any entry matches `predicate`. Only the palette is checked, unless the registry
+    /// ids are stored directly.
+    pub fn contains_any(&self, mut predicate: impl FnMut(V) -> bool) -> bool {
+        match self {
+            Self::Homogeneous(value) => predicate(*value),
+            Self::Heterogeneous(data) if data.is_direct() => {
+                (0..Self::VOLUME).any(|index| predicate(data.value_at(index)))
             }
+            Self::Heterogeneous(data) => data
+                .palette
+                .iter()
+                .zip(&data.counts)
+                .any(|(value, count)| *count > 0 && predicate(*value)),
         }
     }
 
@@ -272,72 +462,71 @@ impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM>
             Self::Heterogeneous(_) => false,
         }
     }
-}
 
-impl<V: Default + Hash + Eq + Copy, const DIM: usize> Default for PalettedContainer<V, DIM> {
-    fn default() -> Self {
-        Self::Homogeneous(V::default())
+    /// How many entries tick randomly. Sections without any can skip random ticks entirely.
+    #[must_use]
+    pub fn random_ticking_count(&self) -> u16 {
+        match self {
+            Self::Homogeneous(value) => Self::random_ticking_of(*value),
+            Self::Heterogeneous(data) => data.random_ticking,
+        }
     }
-}
 
-impl BiomePalette {
-    #[must_use]
-    pub fn convert_network(&self) -> NetworkSerialization<u8> {
+    fn random_ticking_of(value: V) -> u16 {
+        if value.ticks_randomly() {
+            Self::VOLUME as u16
+        } else {
+            0
+        }
+    }
+
+    /// The storage as sent over the network: the section's own words, with its palette unless
+    /// the entries are registry ids.
+    fn network_serialization(&self) -> NetworkSerialization<V> {
         match self {
             Self::Homogeneous(registry_id) => NetworkSerialization {
                 bits_per_entry: 0,
                 palette: NetworkPalette::Single(*registry_id),
                 packed_data: Box::new([]),
             },
-            Self::Heterogeneous(data) => {
-                let raw_bits_per_entry = encompassing_bits(data.counts.len());
-                if raw_bits_per_entry > BIOME_NETWORK_MAX_MAP_BITS {
-                    let bits_per_entry = BIOME_NETWORK_MAX_BITS;
-                    let values_per_i64 = 64 / bits_per_entry;
-                    let packed_data = data
-                        .cube
-                        .as_flattened()
-                        .as_flattened()
-                        .chunks(values_per_i64 as usize)
-                        .map(|chunk| {
-                            chunk.iter().enumerate().fold(0, |acc, (index, value)| {
-                                debug_assert!((1 << bits_per_entry) > *value);
-                                let packed_offset_index =
-                                    (*value as u64) << (bits_per_entry as u64 * index as u64);
-                                acc | packed_offset_index as i64
-                            })
-                        })
-                        .collect();
-
-                    NetworkSerialization {
-                        bits_per_entry,
-                        palette: NetworkPalette::Direct,
-                        packed_data,
-                    }
+            Self::Heterogeneous(data) => NetworkSerialization {
+                bits_per_entry: data.storage.bits,
+                palette: if data.is_direct() {
+                    NetworkPalette::Direct
                 } else {
-                    let bits_per_entry = raw_bits_per_entry.max(BIOME_NETWORK_MIN_MAP_BITS);
-                    let (palette, packed) = self.to_palette_and_packed_data(bits_per_entry);
-
-                    NetworkSerialization {
-                        bits_per_entry,
-                        palette: NetworkPalette::Indirect(palette),
-                        packed_data: packed,
-                    }
-                }
-            }
+                    NetworkPalette::Indirect(data.palette.clone().into_boxed_slice())
+                },
+                packed_data: data.storage.words.clone(),
+            },
         }
     }
+}
+
+impl<V: PaletteEntry, const DIM: usize> Default for PalettedContainer<V, DIM> {
+    fn default() -> Self {
+        Self::Homogeneous(V::default())
+    }
+}
+
+impl BiomePalette {
+    #[must_use]
+    pub fn convert_network(&self) -> NetworkSerialization<u8> {
+        self.network_serialization()
+    }
 
     #[must_use]
     pub fn from_disk_nbt(nbt: ChunkSectionBiomes) -> Self {
         let palette = nbt
             .palette
             .into_iter()
-            .map(|entry| Biome::from_name(&entry.name).unwrap_or(&Biome::PLAINS).id)
+            .map(|entry| {
+                let name = entry.name.strip_prefix("minecraft:").unwrap_or(&entry.name);
+                Biome::from_name(name).unwrap_or(&Biome::PLAINS).id
+            })
             .collect::<Vec<_>>();
 
         Self::from_palette_and_packed_data(
-            palette,
+            &palette,
             nbt.data.as_ref().unwrap_or(&Box::default()),
             BIOME_DISK_MIN_BITS,
         )
@@ -357,7 +546,10 @@ impl BiomePalette {
             palette: palette
                 .into_iter()
                 .map(|registry_id| PaletteBiomeEntry {
-                    name: Biome::from_id(registry_id).unwrap().registry_id.into(),
+                    name: format!(
+                        "minecraft:{}",
+                        Biome::from_id(registry_id).unwrap().registry_id
+                    ),
                 })
                 .collect(),
         }
@@ -367,50 +559,7 @@ impl BiomePalette {
 impl BlockPalette {
     #[must_use]
     pub fn convert_network(&self) -> NetworkSerialization<u16> {
-        match self {
-            Self::Homogeneous(registry_id) => NetworkSerialization {
-                bits_per_entry: 0,
-                palette: NetworkPalette::Single(*registry_id),
-                packed_data: Box::new([]),
-            },
-            Self::Heterogeneous(data) => {
-                let raw_bits_per_entry = encompassing_bits(data.counts.len());
-                if raw_bits_per_entry > BLOCK_NETWORK_MAX_MAP_BITS {
-                    let bits_per_entry = BLOCK_NETWORK_MAX_BITS;
-                    let values_per_i64 = 64 / bits_per_entry;
-                    let packed_data = data
-                        .cube
-                        .as_flattened()
-                        .as_flattened()
-                        .chunks(values_per_i64 as usize)
-                        .map(|chunk| {
-                            chunk.iter().enumerate().fold(0, |acc, (index, value)| {
-                                debug_assert!((1 << bits_per_entry) > *value);
-
-                                let packed_offset_index =
-                                    (*value as i64) << (bits_per_entry as u64 * index as u64);
-                                acc | packed_offset_index
-                            })
-                        })
-                        .collect();
-
-                    NetworkSerialization {
-                        bits_per_entry,
-                        palette: NetworkPalette::Direct,
-                        packed_data,
-                    }
-                } else {
-                    let bits_per_entry = raw_bits_per_entry.max(BLOCK_NETWORK_MIN_MAP_BITS);
-                    let (palette, packed) = self.to_palette_and_packed_data(bits_per_entry);
-
-                    NetworkSerialization {
-                        bits_per_entry,
-                        palette: NetworkPalette::Indirect(palette),
-                        packed_data: packed,
-                    }
-                }
-            }
-        }
+        self.network_serialization()
     }
 
     #[must_use]
@@ -484,7 +633,11 @@ impl BlockPalette {
     pub fn has_only_air(&self) -> bool {
         match self {
             Self::Homogeneous(id) => is_air(*id),
-            Self::Heterogeneous(data) => data.palette.iter().all(|&id| is_air(id)),
+            Self::Heterogeneous(data) => data
+                .palette
+                .iter()
+                .zip(&data.counts)
+                .all(|(&id, &count)| count == 0 || is_air(id)),
         }
     }
 
@@ -507,16 +660,27 @@ impl BlockPalette {
         }
     }
 
+    /// Reads a section's block states. Entries that don't name a valid state are read as their
+    /// block's default state, or air if the block is unknown, and added to `invalid`.
     #[must_use]
-    pub fn from_disk_nbt(nbt: ChunkSectionBlockStates) -> Self {
+    pub fn from_disk_nbt(nbt: ChunkSectionBlockStates, invalid: &mut Vec<String>) -> Self {
         let palette = nbt
             .palette
             .into_iter()
-            .map(|entry| entry.get_state_id())
+            .map(|entry| {
+                entry.try_state_id().unwrap_or_else(|| {
+                    let state = entry.to_string();
+                    if !invalid.contains(&state) {
+                        invalid.push(state);
+                    }
+                    Block::from_name(&entry.name)
+                        .map_or(Block::AIR.default_state.id, |block| block.default_state.id)
+                })
+            })
             .collect::<Vec<_>>();
 
         Self::from_palette_and_packed_data(
-            palette,
+            &palette,
             nbt.data.as_ref().unwrap_or(&Box::default()),
             BLOCK_DISK_MIN_BITS,
         )
@@ -538,11 +702,11 @@ impl BlockPalette {
         }
     }
 
-    fn block_state_id_to_palette_entry(registry_id: u16) -> BlockStateCodec {
+    fn block_state_id_to_palette_entry(registry_id: u16) -> PaletteBlockEntry {
         let block = Block::from_state_id(registry_id);
 
-        BlockStateCodec {
-            name: block,
+        PaletteBlockEntry {
+            name: format!("minecraft:{}", block.name),
             properties: block.properties(registry_id).map(|p| {
                 p.to_props()
                     .into_iter()
@@ -604,3 +768,131 @@ const BIOME_DISK_MIN_BITS: u8 = 0;
 const BIOME_NETWORK_MIN_MAP_BITS: u8 = 1;
 const BIOME_NETWORK_MAX_MAP_BITS: u8 = 3;
 pub(crate) const BIOME_NETWORK_MAX_BITS: u8 = 7;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_moves_between_tiers() {
+        let mut palette = BlockPalette::default();
+        let position = |i: usize| (i % 16, i / 256, (i / 16) % 16);
+
+        // 200 distinct values need the widest indirect tier
+        for i in 0..200 {
+            let (x, y, z) = position(i);
+            palette.set(x, y, z, i as u16 + 1);
+        }
+        let network = palette.convert_network();
+        assert_eq!(network.bits_per_entry, 8);
+        let NetworkPalette::Indirect(entries) = &network.palette else {
+            panic!("Expected an indirect palette");
+        };
+        assert_eq!(entries.len(), 201);
+        assert_eq!(network.packed_data.len(), BlockPalette::VOLUME / 8);
+
+        // Past 256 values the registry ids are stored directly
+        for i in 200..300 {
+            let (x, y, z) = position(i);
+            palette.set(x, y, z, i as u16 + 1);
+        }
+        let network = palette.convert_network();
+        assert_eq!(network.bits_per_entry, BLOCK_NETWORK_MAX_BITS);
+        assert!(matches!(network.palette, NetworkPalette::Direct));
+        for i in 0..BlockPalette::VOLUME {
+            let (x, y, z) = position(i);
+            let expected = if i < 300 { i as u16 + 1 } else { 0 };
+            assert_eq!(palette.get(x, y, z), expected);
+        }
+        assert_eq!(palette.non_air_block_count(), 300);
+
+        // The disk format always uses a palette
+        let (disk_palette, packed) = palette.to_palette_and_packed_data(9);
+        let read_back = BlockPalette::from_palette_and_packed_data(&disk_palette, &packed, 4);
+        for i in 0..BlockPalette::VOLUME {
+            let (x, y, z) = position(i);
+            assert_eq!(read_back.get(x, y, z), palette.get(x, y, z));
+        }
+
+        // Clearing every value collapses the container to a single value
+        for i in 0..300 {
+            let (x, y, z) = position(i);
+            palette.set(x, y, z, 0);
+        }
+        assert!(matches!(palette, PalettedContainer::Homogeneous(0)));
+    }
+
+    #[test]
+    fn reads_unknown_disk_states_as_defaults() {
+        let read = |name: &str, properties: &[(&str, &str)], invalid: &mut Vec<String>| {
+            let entry = PaletteBlockEntry {
+                name: name.to_string(),
+                properties: (!properties.is_empty()).then(|| {
+                    properties
+                        .iter()
+                        .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                        .collect()
+                }),
+            };
+            let states = ChunkSectionBlockStates {
+                data: None,
+                palette: vec![entry],
+            };
+            BlockPalette::from_disk_nbt(states, invalid).get(0, 0, 0)
+        };
+        let mut invalid = Vec::new();
+
+        let log = read("minecraft:oak_log", &[("axis", "x")], &mut invalid);
+        assert_eq!(Block::from_state_id(log).id, Block::OAK_LOG.id);
+        assert_ne!(log, Block::OAK_LOG.default_state.id);
+        assert_eq!(
+            read("stone", &[], &mut invalid),
+            Block::STONE.default_state.id
+        );
+        assert!(invalid.is_empty());
+
+        assert_eq!(
+            read("minecraft:oak_log", &[("axis", "w")], &mut invalid),
+            Block::OAK_LOG.default_state.id
+        );
+        assert_eq!(
+            read("minecraft:oak_log", &[("color", "red")], &mut invalid),
+            Block::OAK_LOG.default_state.id
+        );
+        assert_eq!(
+            read("othermod:thing", &[], &mut invalid),
+            Block::AIR.default_state.id
+        );
+        assert_eq!(
+            invalid,
+            [
+                "minecraft:oak_log[axis=w]",
+                "minecraft:oak_log[color=red]",
+                "othermod:thing"
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_random_ticking_blocks() {
+        let grass = Block::GRASS_BLOCK.default_state.id;
+        let stone = Block::STONE.default_state.id;
+        let mut palette = BlockPalette::default();
+        assert_eq!(palette.random_ticking_count(), 0);
+
+        for x in 0..10 {
+            palette.set(x, 0, 0, grass);
+        }
+        palette.set(0, 1, 0, stone);
+        assert_eq!(palette.random_ticking_count(), 10);
+        palette.set(0, 0, 0, stone);
+        palette.set(1, 0, 0, grass);
+        assert_eq!(palette.random_ticking_count(), 9);
+
+        let grass_section = BlockPalette::Homogeneous(grass);
+        assert_eq!(
+            grass_section.random_ticking_count(),
+            BlockPalette::VOLUME as u16
+        );
+    }
+}
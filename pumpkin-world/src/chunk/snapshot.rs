@@ -0,0 +1,172 @@
+//! Immutable copies of chunks for readers outside of the tick, like map rendering, structure
+//! searches or plugins.
+//!
+//! Taking a snapshot only clones the `Arc`s of the chunk sections. A section is copied when the
+//! chunk changes while a snapshot still holds it, so snapshots never block the tick and the tick
+//! never waits for them.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use pumpkin_data::Block;
+use pumpkin_data::biome::Biome;
+use pumpkin_data::block_properties::is_air;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector2::Vector2;
+
+use super::ChunkSections;
+use super::palette::{BiomePalette, BlockPalette};
+use crate::BlockStateId;
+use crate::block::RawBlockState;
+use crate::level::Level;
+
+/// The blocks and biomes of one chunk at the moment the snapshot was taken.
+#[derive(Clone)]
+pub struct ChunkSnapshot {
+    min_y: i32,
+    blocks: Box<[Arc<BlockPalette>]>,
+    biomes: Box<[Arc<BiomePalette>]>,
+}
+
+impl ChunkSections {
+    #[must_use]
+    pub fn snapshot(&self) -> ChunkSnapshot {
+        ChunkSnapshot {
+            min_y: self.min_y,
+            blocks: self.block_sections.read().unwrap().clone(),
+            biomes: self.biome_sections.read().unwrap().clone(),
+        }
+    }
+}
+
+impl ChunkSnapshot {
+    /// Returns the block state at the chunk relative `x` and `z` and the absolute `y`, or `None`
+    /// if `y` is outside of the chunk.
+    #[must_use]
+    pub fn get_block_state_id(
+        &self,
+        relative_x: usize,
+        y: i32,
+        relative_z: usize,
+    ) -> Option<BlockStateId> {
+        let relative_y = usize::try_from(y - self.min_y).ok()?;
+        self.blocks
+            .get(relative_y / BlockPalette::SIZE)
+            .map(|section| section.get(relative_x, relative_y % BlockPalette::SIZE, relative_z))
+    }
+
+    /// Returns the absolute `y` of the highest block that is not air in the column at the chunk
+    /// relative `x` and `z`, or `None` if the column is empty.
+    #[must_use]
+    pub fn get_top_y(&self, relative_x: usize, relative_z: usize) -> Option<i32> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, section)| !section.has_only_air())
+            .find_map(|(index, section)| {
+                (0..BlockPalette::SIZE).rev().find_map(|relative_y| {
+                    (!is_air(section.get(relative_x, relative_y, relative_z))).then(|| {
+                        self.min_y + (index * BlockPalette::SIZE + relative_y) as i32
+                    })
+                })
+            })
+    }
+
+    /// Returns the biome id at the chunk relative `x` and `z` and the absolute `y`, or `None` if
+    /// `y` is outside of the chunk.
+    #[must_use]
+    pub fn get_rough_biome_id(&self, relative_x: usize, y: i32, relative_z: usize) -> Option<u8> {
+        let relative_y = usize::try_from(y - self.min_y).ok()?;
+        self.biomes
+            .get(relative_y / BlockPalette::SIZE)
+            .map(|section| {
+                section.get(
+                    relative_x >> 2 & 3,
+                    relative_y >> 2 & 3,
+                    relative_z >> 2 & 3,
+                )
+            })
+    }
+}
+
+/// Snapshots of a set of chunks of a level. Each chunk is consistent on its own. A snapshot
+/// taken on the tick, e.g. from an event handler, is also consistent across its chunks.
+#[derive(Clone, Default)]
+pub struct WorldSnapshot {
+    chunks: HashMap<Vector2<i32>, ChunkSnapshot>,
+}
+
+impl WorldSnapshot {
+    #[must_use]
+    pub fn chunk(&self, position: Vector2<i32>) -> Option<&ChunkSnapshot> {
+        self.chunks.get(&position)
+    }
+
+    #[must_use]
+    pub fn contains_chunk(&self, position: Vector2<i32>) -> bool {
+        self.chunks.contains_key(&position)
+    }
+
+    /// Returns the block state at `position`, or `None` if its chunk is not in the snapshot.
+    /// Positions above or below the world are void air, like in [`Level::get_block_state`].
+    #[must_use]
+    pub fn get_block_state(&self, position: &BlockPos) -> Option<RawBlockState> {
+        let (chunk, relative) = position.chunk_and_chunk_relative_position();
+        let id = self
+            .chunk(chunk)?
+            .get_block_state_id(relative.x as usize, relative.y, relative.z as usize)
+            .unwrap_or(Block::VOID_AIR.default_state.id);
+        Some(RawBlockState(id))
+    }
+
+    /// Returns the biome at `position`, or `None` if its chunk is not in the snapshot. Positions
+    /// above or below the world are in the void, like in [`Level::get_rough_biome`].
+    #[must_use]
+    pub fn get_rough_biome(&self, position: &BlockPos) -> Option<&'static Biome> {
+        let (chunk, relative) = position.chunk_and_chunk_relative_position();
+        let biome = self
+            .chunk(chunk)?
+            .get_rough_biome_id(relative.x as usize, relative.y, relative.z as usize)
+            .map_or(&Biome::THE_VOID, |id| Biome::from_id(id).unwrap());
+        Some(biome)
+    }
+}
+
+impl Level {
+    /// Takes a snapshot of the chunks at `positions` that are loaded. Chunks that are not loaded
+    /// are left out instead of being loaded or generated.
+    #[must_use]
+    pub fn snapshot(&self, positions: impl IntoIterator<Item = Vector2<i32>>) -> WorldSnapshot {
+        let chunks = positions
+            .into_iter()
+            .filter_map(|position| {
+                let chunk = self.try_get_chunk(&position)?;
+                Some((position, chunk.section.snapshot()))
+            })
+            .collect();
+        WorldSnapshot { chunks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_keep_the_blocks_they_were_taken_with() {
+        let sections = ChunkSections::new(2, -16);
+        sections.set_block_absolute_y(3, -10, 5, 1);
+        let snapshot = sections.snapshot();
+
+        sections.set_block_absolute_y(3, -10, 5, 2);
+        sections.set_block_absolute_y(0, 4, 0, 3);
+        assert_eq!(snapshot.get_block_state_id(3, -10, 5), Some(1));
+        assert_eq!(snapshot.get_block_state_id(0, 4, 0), Some(0));
+        assert_eq!(sections.snapshot().get_block_state_id(3, -10, 5), Some(2));
+        assert_eq!(snapshot.get_block_state_id(0, -17, 0), None);
+        assert_eq!(snapshot.get_block_state_id(0, 16, 0), None);
+        assert_eq!(snapshot.get_top_y(3, 5), Some(-10));
+        assert_eq!(snapshot.get_top_y(0, 0), None);
+    }
+}
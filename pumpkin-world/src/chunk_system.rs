@@ -9,6 +9,7 @@ TODO
 use crate::block::RawBlockState;
 use crate::chunk::io::LoadedData::Loaded;
 use crate::chunk::{ChunkData, ChunkHeightmapType, ChunkLight, ChunkSections};
+use crate::dimension::generator_dimension;
 use crate::generation::biome_coords;
 use crate::generation::generator::VanillaGenerator;
 use pumpkin_data::block_properties::is_air;
@@ -16,7 +17,7 @@ use pumpkin_data::chunk_gen_settings::GenerationSettings;
 use pumpkin_data::dimension::Dimension;
 use std::default::Default;
 use std::pin::Pin;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicI64};
 
 use crate::generation::height_limit::HeightLimitView;
 
@@ -42,7 +43,7 @@ use std::cmp::{Ordering, PartialEq, max, min};
 use std::collections::hash_map::Entry;
 use std::collections::{BinaryHeap, HashMap};
 use std::mem::swap;
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 
 use crate::chunk::format::LightContainer;
 use crate::chunk::io::LoadedData;
@@ -174,6 +175,8 @@ pub struct ChunkLoading {
     change: HashMapType<ChunkPos, (StagedChunkEnum, StagedChunkEnum)>,
     pub ticket: HashMapType<ChunkPos, Vec<i8>>, // TODO lifetime & id
     pub high_priority: Vec<ChunkPos>,
+    /// Chunks generated before all others without a ticket of their own, like teleport targets.
+    priority_targets: Vec<ChunkPos>,
     pub sender: Arc<LevelChannel>,
     pub increase_update: BinaryHeap<HeapNode>,
     pub decrease_update: BinaryHeap<HeapNode>,
@@ -245,6 +248,8 @@ impl ChunkLoading {
     // pub const FULL_CHUNK_LEVEL: i8 = 33;
     pub const FULL_CHUNK_LEVEL: i8 = 43;
     pub const MAX_LEVEL: i8 = 46; // level 46 will be unloaded.
+    /// Level of the temporary ticket [`crate::level::Level::get_chunk`] holds while loading.
+    pub const LOAD_TICKET_LEVEL: i8 = 31;
     fn debug_check_error(&self) -> bool {
         let mut temp = ChunkLevel::default();
         for (ticket_pos, levels) in &self.ticket {
@@ -328,6 +333,7 @@ impl ChunkLoading {
             change: HashMapType::default(),
             ticket: HashMapType::default(),
             high_priority: Vec::new(),
+            priority_targets: Vec::new(),
             sender,
             increase_update: BinaryHeap::default(),
             decrease_update: BinaryHeap::default(),
@@ -343,14 +349,36 @@ impl ChunkLoading {
             if self.is_priority_dirty {
                 self.is_priority_dirty = false;
                 self.sender
-                    .set_both((tmp, self.pos_level.clone()), self.high_priority.clone());
+                    .set_both((tmp, self.pos_level.clone()), self.priorities());
             } else {
                 self.sender.set_level((tmp, self.pos_level.clone()));
             }
         }
         if self.is_priority_dirty {
             self.is_priority_dirty = false;
-            self.sender.set_priority(self.high_priority.clone());
+            self.sender.set_priority(self.priorities());
+        }
+    }
+
+    fn priorities(&self) -> Vec<ChunkPos> {
+        self.high_priority
+            .iter()
+            .chain(&self.priority_targets)
+            .copied()
+            .collect()
+    }
+
+    /// Generates `pos` and its dependencies before every other chunk, until
+    /// [`Self::remove_priority_target`] is called. Unlike a forced ticket, this doesn't load it.
+    pub fn add_priority_target(&mut self, pos: ChunkPos) {
+        self.priority_targets.push(pos);
+        self.is_priority_dirty = true;
+    }
+
+    pub fn remove_priority_target(&mut self, pos: ChunkPos) {
+        if let Some(index) = self.priority_targets.iter().position(|x| *x == pos) {
+            self.priority_targets.remove(index);
+            self.is_priority_dirty = true;
         }
     }
 
@@ -803,6 +831,7 @@ impl Chunk {
                 .unwrap()
                 .get_mut(section_index)
             {
+                let section = Arc::make_mut(section);
                 let absolute_biome_y = biome_min_y + y_offset as i32;
 
                 for z in 0..4 {
@@ -826,6 +855,7 @@ impl Chunk {
                 .unwrap()
                 .get_mut(section_index)
             {
+                let section = Arc::make_mut(section);
                 for z in 0..16 {
                     for x in 0..16 {
                         let block =
@@ -838,7 +868,7 @@ impl Chunk {
 
         let len = sections.count;
         let mut chunk = ChunkData {
-            light_engine: ChunkLight {
+            light_engine: RwLock::new(ChunkLight {
                 sky_light: (0..len)
                     .map(|_| {
                         if dimension.has_skylight {
@@ -851,12 +881,14 @@ impl Chunk {
                     })
                     .collect(),
                 block_light: (0..len).map(|_| LightContainer::new_empty(0)).collect(),
-            },
+            }),
+            light_correct: AtomicBool::new(false),
             section: sections,
             heightmap: Default::default(),
             x: proto_chunk.x,
             z: proto_chunk.z,
             dirty: AtomicBool::new(true),
+            inhabited_time: AtomicI64::new(0),
             block_ticks: Default::default(),
             fluid_ticks: Default::default(),
             block_entities: Default::default(),
@@ -872,6 +904,7 @@ struct Cache {
     x: i32,
     z: i32,
     size: i32,
+    seed: u64,
     pub chunks: Vec<Chunk>,
 }
 
@@ -927,6 +960,10 @@ impl BlockAccessor for Cache {
 }
 
 impl GenerationCache for Cache {
+    fn world_seed(&self) -> u64 {
+        self.seed
+    }
+
     fn get_chunk_mut(&mut self, chunk_x: i32, chunk_z: i32) -> Option<&mut ProtoChunk> {
         let dx = chunk_x - self.x;
         let dz = chunk_z - self.z;
@@ -1149,11 +1186,12 @@ impl GenerationCache for Cache {
 }
 
 impl Cache {
-    fn new(x: i32, z: i32, size: i32) -> Self {
+    fn new(x: i32, z: i32, size: i32, seed: u64) -> Self {
         Self {
             x,
             z,
             size,
+            seed,
             chunks: Vec::with_capacity((size * size) as usize),
         }
     }
@@ -1390,6 +1428,8 @@ pub struct GenerationSchedule {
     unload_chunks: HashSetType<ChunkPos>,
 
     io_lock: IOLock,
+    /// The number of batches sent to the io write task.
+    io_batches_sent: u64,
     running_task_count: u16,
     recv_chunk: crossfire::compat::MRx<(ChunkPos, RecvChunk)>,
     io_read: crossfire::compat::MTx<ChunkPos>,
@@ -1461,6 +1501,7 @@ impl GenerationSchedule {
                     public_chunk_map: level_sched.loaded_chunks.clone(),
                     unload_chunks: HashSetType::default(),
                     io_lock,
+                    io_batches_sent: 0,
                     running_task_count: 0,
                     recv_chunk,
                     io_read: send_read_io,
@@ -1725,11 +1766,13 @@ impl GenerationSchedule {
                 }
             }
             let pos = vec.iter().map(|(pos, _)| *pos).collect_vec();
-            level
+            if let Err(error) = level
                 .chunk_saver
                 .save_chunks(&level.level_folder, vec)
                 .await
-                .unwrap();
+            {
+                level.report_save_failure(format!("Failed writing chunks to disk: {error}"));
+            }
             for i in pos {
                 let mut data = lock.0.lock().unwrap();
                 match data.entry(i) {
@@ -1746,6 +1789,7 @@ impl GenerationSchedule {
                     Entry::Vacant(_) => panic!(),
                 }
             }
+            level.save_progress.mark_batch_written();
         }
         log::info!(
             "io write thread stop id: {:?} name: {}",
@@ -1765,7 +1809,8 @@ impl GenerationSchedule {
             thread::current().name().unwrap_or("unknown")
         );
 
-        let settings = GenerationSettings::from_dimension(&level.world_gen.dimension);
+        let settings =
+            GenerationSettings::from_dimension(&generator_dimension(&level.world_gen.dimension));
         while let Ok((pos, mut cache, stage)) = recv.recv() {
             // debug!("generation thread receive chunk pos {pos:?} to stage {stage:?}");
             cache.advance(
@@ -1838,9 +1883,10 @@ impl GenerationSchedule {
         }
         drop(data);
         self.io_write.send(chunks).expect("io write thread stop");
+        self.io_batches_sent += 1;
     }
 
-    fn save_all_chunk(&self, save_proto_chunk: bool) {
+    fn save_all_chunk(&mut self, save_proto_chunk: bool) {
         let mut chunks = Vec::with_capacity(self.chunk_map.len());
         for (pos, chunk) in &self.chunk_map {
             if let Some(chunk) = &chunk.chunk {
@@ -1866,6 +1912,7 @@ impl GenerationSchedule {
         }
         drop(data);
         self.io_write.send(chunks).expect("io write thread stop");
+        self.io_batches_sent += 1;
     }
 
     fn drop_node(&mut self, node: NodeKey) {
@@ -1999,11 +2046,16 @@ impl GenerationSchedule {
         );
         // let mut clock = Instant::now();
         loop {
-            if level.should_unload.swap(false, Relaxed) {
+            // Unloading writes chunks, so it waits for the next pass while saving is suspended
+            if level.should_unload.swap(false, Relaxed) && !level.saving_suspended.load(Relaxed) {
                 self.unload_chunk();
             }
-            if level.should_save.swap(false, Relaxed) {
+            if level.should_save.swap(false, SeqCst) {
+                let request = level.save_progress.last_request();
                 self.save_all_chunk(false);
+                level
+                    .save_progress
+                    .mark_queued(request, self.io_batches_sent);
             }
             if level.shut_down_chunk_system.load(Relaxed) {
                 // log::debug!("shut down signal");
@@ -2046,6 +2098,13 @@ impl GenerationSchedule {
                             .send(node.pos)
                             .expect("io thread close unexpectedly");
                     } else {
+                        if self.running_task_count > 0 && level.generation_deferred.load(Relaxed) {
+                            // Wait for the running task instead of starting another one
+                            self.queue.push(task);
+                            let (pos, data) = self.recv_chunk.recv().expect("recv_chunk stop");
+                            self.receive_chunk(pos, data);
+                            continue;
+                        }
                         let occupy = self.graph.nodes.insert(Node::new(
                             ChunkPos::new(i32::MAX, i32::MAX),
                             StagedChunkEnum::None,
@@ -2071,6 +2130,7 @@ impl GenerationSchedule {
                             node.pos.x - write_radius,
                             node.pos.y - write_radius,
                             write_radius << 1 | 1,
+                            level.world_gen.random_config.seed,
                         );
                         #[cfg(debug_assertions)]
                         {
@@ -2214,10 +2274,15 @@ pub fn generate_single_chunk(
     chunk_z: i32,
     target_stage: StagedChunkEnum,
 ) -> Chunk {
-    let settings = GenerationSettings::from_dimension(dimension);
+    let settings = GenerationSettings::from_dimension(&generator_dimension(dimension));
     let radius = target_stage.get_direct_radius();
 
-    let mut cache = Cache::new(chunk_x - radius, chunk_z - radius, radius * 2 + 1);
+    let mut cache = Cache::new(
+        chunk_x - radius,
+        chunk_z - radius,
+        radius * 2 + 1,
+        generator.random_config.seed,
+    );
 
     for dx in -radius..=radius {
         for dz in -radius..=radius {
@@ -0,0 +1,79 @@
+use std::fs::{File, create_dir_all};
+use std::io;
+use std::path::PathBuf;
+
+use pumpkin_nbt::compound::NbtCompound;
+
+use crate::chunk::format::anvil::WORLD_DATA_VERSION;
+use crate::map::MapState;
+
+/// Stores filled maps in `map_<id>.dat` and the last map id in `idcounts.dat`, in the `data`
+/// directory of the world.
+pub struct MapDataStorage {
+    data_path: PathBuf,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MapDataError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("NBT error: {0}")]
+    Nbt(String),
+}
+
+impl MapDataStorage {
+    #[must_use]
+    pub fn new(data_path: impl Into<PathBuf>) -> Self {
+        Self {
+            data_path: data_path.into(),
+        }
+    }
+
+    fn read(&self, name: &str) -> Result<Option<NbtCompound>, MapDataError> {
+        let mut file = match File::open(self.data_path.join(name)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(MapDataError::Io(e)),
+        };
+        let nbt = pumpkin_nbt::nbt_compress::read_gzip_compound_tag(&mut file)
+            .map_err(|e| MapDataError::Nbt(e.to_string()))?;
+        Ok(nbt.get_compound("data").cloned())
+    }
+
+    fn write(&self, name: &str, data: NbtCompound) -> Result<(), MapDataError> {
+        create_dir_all(&self.data_path)?;
+        let mut nbt = NbtCompound::new();
+        nbt.put_component("data", data);
+        nbt.put_int("DataVersion", WORLD_DATA_VERSION);
+        let file = File::create(self.data_path.join(name))?;
+        pumpkin_nbt::nbt_compress::write_gzip_compound_tag(nbt, file)
+            .map_err(|e| MapDataError::Nbt(e.to_string()))
+    }
+
+    /// Loads the map `id`, or returns `None` if it was never saved.
+    pub fn load_map(&self, id: i32) -> Result<Option<MapState>, MapDataError> {
+        let Some(data) = self.read(&format!("map_{id}.dat"))? else {
+            return Ok(None);
+        };
+        MapState::from_nbt(&data)
+            .map(Some)
+            .ok_or_else(|| MapDataError::Nbt(format!("map {id} is missing its center")))
+    }
+
+    pub fn save_map(&self, id: i32, state: &MapState) -> Result<(), MapDataError> {
+        self.write(&format!("map_{id}.dat"), state.to_nbt())
+    }
+
+    /// Returns the id of the last map that was created, or `None` if there are no maps yet.
+    pub fn load_last_id(&self) -> Result<Option<i32>, MapDataError> {
+        Ok(self
+            .read("idcounts.dat")?
+            .and_then(|data| data.get_int("map")))
+    }
+
+    pub fn save_last_id(&self, id: i32) -> Result<(), MapDataError> {
+        let mut data = NbtCompound::new();
+        data.put_int("map", id);
+        self.write("idcounts.dat", data)
+    }
+}
@@ -1 +1,2 @@
+pub mod map_data;
 pub mod player_data;
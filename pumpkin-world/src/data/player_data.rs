@@ -1,6 +1,6 @@
 use pumpkin_nbt::compound::NbtCompound;
-use std::fs::{File, create_dir_all};
-use std::io;
+use std::fs::{File, OpenOptions, create_dir_all};
+use std::io::{self, Seek, SeekFrom};
 use std::path::PathBuf;
 use uuid::Uuid;
 
@@ -87,7 +87,7 @@ impl PlayerDataStorage {
             return Ok((false, NbtCompound::new()));
         }
 
-        let file = match File::open(&path) {
+        let mut file = match File::open(&path).and_then(|file| file.lock_shared().map(|()| file)) {
             Ok(file) => file,
             Err(e) => {
                 log::error!("Failed to open player data file for {uuid}: {e}");
@@ -95,7 +95,7 @@ impl PlayerDataStorage {
             }
         };
 
-        match pumpkin_nbt::nbt_compress::read_gzip_compound_tag(file) {
+        match pumpkin_nbt::nbt_compress::read_gzip_compound_tag(&mut file) {
             Ok(nbt) => {
                 log::debug!("Loaded player data for {uuid} from disk");
                 Ok((true, nbt))
@@ -136,8 +136,19 @@ impl PlayerDataStorage {
             return Err(PlayerDataError::Io(e));
         }
 
-        // Create the file and write directly with GZip compression
-        match File::create(&path) {
+        // Create the file and write directly with GZip compression. The file is only truncated
+        // once the lock is held, so offline edits are never interleaved with a save.
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .and_then(|file| {
+                file.lock()?;
+                file.set_len(0)?;
+                Ok(file)
+            });
+        match file {
             Ok(file) => {
                 if let Err(e) = pumpkin_nbt::nbt_compress::write_gzip_compound_tag(data, file) {
                     log::error!("Failed to write compressed player data for {uuid}: {e}");
@@ -153,4 +164,34 @@ impl PlayerDataStorage {
             }
         }
     }
+
+    /// Loads, modifies and saves a player's data while holding an exclusive lock on the file.
+    ///
+    /// Intended for editing the data of offline players. Returns `None` without calling `edit`
+    /// if the player has no data file.
+    pub fn edit_player_data<R>(
+        &self,
+        uuid: &Uuid,
+        edit: impl FnOnce(&mut NbtCompound) -> R,
+    ) -> Result<Option<R>, PlayerDataError> {
+        let path = self.get_player_data_path(uuid);
+        let mut file = match OpenOptions::new().read(true).write(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(PlayerDataError::Io(e)),
+        };
+        file.lock()?;
+
+        let mut data = pumpkin_nbt::nbt_compress::read_gzip_compound_tag(&mut file)
+            .map_err(|e| PlayerDataError::Nbt(e.to_string()))?;
+        let result = edit(&mut data);
+
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        pumpkin_nbt::nbt_compress::write_gzip_compound_tag(data, &mut file)
+            .map_err(|e| PlayerDataError::Nbt(e.to_string()))?;
+        file.sync_all()?;
+        log::debug!("Edited player data for {uuid}");
+        Ok(Some(result))
+    }
 }
@@ -0,0 +1,311 @@
+//! Dimensions added by the datapacks in the `datapacks` folder of a world.
+//!
+//! A datapack adds a dimension with a `data/<namespace>/dimension/<name>.json` file, which names
+//! its dimension type: a vanilla one or one of `data/<namespace>/dimension_type/`. The type
+//! decides the height, light and time of the dimension.
+//!
+//! Only the noise generators of the vanilla dimensions exist, so a dimension generates the
+//! terrain and biomes of the vanilla dimension of its noise settings. Zipped datapacks are not
+//! read.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use pumpkin_data::dimension::Dimension;
+use serde::Deserialize;
+
+use crate::dimension::DatapackDimension;
+use crate::world_info::DataPacks;
+
+/// The ids of the dimension type registry the vanilla dimensions don't use.
+const FIRST_ID: u8 = 4;
+/// The lowest and highest bottom of a dimension, chunks being generated store it in a byte.
+const MIN_Y_RANGE: (i32, i32) = (-128, 112);
+/// The time of day of dimension types in the 1.21.11 format with a fixed time, noon like in the
+/// End.
+const NOON: i64 = 6000;
+
+#[derive(Deserialize)]
+struct DimensionJson {
+    /// Inline dimension types are not supported.
+    #[serde(rename = "type")]
+    dimension_type: String,
+    generator: GeneratorJson,
+}
+
+#[derive(Deserialize)]
+struct GeneratorJson {
+    #[serde(rename = "type")]
+    generator_type: String,
+    /// The name of the noise settings, or inline settings.
+    #[serde(default)]
+    settings: Option<serde_json::Value>,
+}
+
+/// A dimension type, in the format up to 1.21.9 with `fixed_time` or since 1.21.11 with
+/// `has_fixed_time`.
+#[derive(Deserialize)]
+struct DimensionTypeJson {
+    #[serde(default)]
+    fixed_time: Option<i64>,
+    #[serde(default)]
+    has_fixed_time: bool,
+    has_skylight: bool,
+    has_ceiling: bool,
+    coordinate_scale: f64,
+    min_y: i32,
+    height: i32,
+    logical_height: i32,
+    infiniburn: String,
+    ambient_light: f32,
+}
+
+/// Reads the dimensions of the datapacks in `world_folder` that `data_packs` doesn't disable.
+///
+/// Datapacks are read in alphabetical order, their files replace those of the same name in
+/// earlier ones. The dimensions get ids in the order of their names. Invalid dimensions are
+/// skipped with a warning.
+#[must_use]
+pub fn load_dimensions(world_folder: &Path, data_packs: &DataPacks) -> Vec<DatapackDimension> {
+    let Ok(packs) = fs::read_dir(world_folder.join("datapacks")) else {
+        return Vec::new();
+    };
+    let mut packs: Vec<_> = packs
+        .filter_map(Result::ok)
+        .map(|pack| pack.path())
+        .collect();
+    packs.sort();
+
+    let mut dimensions = BTreeMap::new();
+    let mut dimension_types = BTreeMap::new();
+    for pack in packs {
+        let name = pack.file_name().unwrap_or_default().to_string_lossy();
+        if data_packs.disabled.contains(&format!("file/{name}")) {
+            continue;
+        }
+        if !pack.join("pack.mcmeta").is_file() {
+            if pack.extension().is_some_and(|extension| extension == "zip") {
+                log::warn!("Zipped datapacks are not supported, unzip {name} to load it");
+            }
+            continue;
+        }
+        let Ok(namespaces) = fs::read_dir(pack.join("data")) else {
+            continue;
+        };
+        for namespace in namespaces.filter_map(Result::ok) {
+            let namespace_name = namespace.file_name().to_string_lossy().into_owned();
+            let prefix = format!("{namespace_name}:");
+            json_files(
+                &namespace.path().join("dimension"),
+                &prefix,
+                &mut dimensions,
+            );
+            json_files(
+                &namespace.path().join("dimension_type"),
+                &prefix,
+                &mut dimension_types,
+            );
+        }
+    }
+
+    let mut loaded = Vec::new();
+    for (name, json) in dimensions {
+        if Dimension::from_name(&name).is_some() {
+            log::warn!("Datapacks can't replace the vanilla dimension {name}, skipping it");
+            continue;
+        }
+        let Ok(id) = u8::try_from(usize::from(FIRST_ID) + loaded.len()) else {
+            log::warn!("Too many datapack dimensions, skipping {name}");
+            continue;
+        };
+        match parse_dimension(&name, &json, &dimension_types) {
+            Ok((mut dimension, generator)) => {
+                dimension.id = id;
+                // Lives as long as the server, like the names of the vanilla dimensions
+                dimension.minecraft_name = Box::leak(name.into_boxed_str());
+                loaded.push(DatapackDimension {
+                    dimension,
+                    generator,
+                });
+            }
+            Err(err) => log::warn!("Skipping the datapack dimension {name}: {err}"),
+        }
+    }
+    loaded
+}
+
+/// Collects the JSON files under `folder` by their resource name, `prefix` followed by their
+/// path without the extension.
+fn json_files(folder: &Path, prefix: &str, files: &mut BTreeMap<String, String>) {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_dir() {
+            json_files(&path, &format!("{prefix}{file_name}/"), files);
+        } else if let Some(name) = file_name.strip_suffix(".json") {
+            match fs::read_to_string(&path) {
+                Ok(json) => {
+                    files.insert(format!("{prefix}{name}"), json);
+                }
+                Err(err) => log::warn!("Failed to read {}: {err}", path.display()),
+            }
+        }
+    }
+}
+
+/// The dimension described by `json`, still with the id and name of its type, and the vanilla
+/// dimension it generates like.
+fn parse_dimension(
+    name: &str,
+    json: &str,
+    dimension_types: &BTreeMap<String, String>,
+) -> Result<(Dimension, Dimension), String> {
+    let parsed: DimensionJson = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    let dimension = match dimension_types.get(&parsed.dimension_type) {
+        Some(dimension_type) => {
+            let dimension_type: DimensionTypeJson = serde_json::from_str(dimension_type)
+                .map_err(|err| format!("invalid type {}: {err}", parsed.dimension_type))?;
+            dimension_type.into_dimension()?
+        }
+        None => *Dimension::from_name(&parsed.dimension_type)
+            .ok_or_else(|| format!("unknown type {}", parsed.dimension_type))?,
+    };
+
+    let settings = parsed
+        .generator
+        .settings
+        .as_ref()
+        .and_then(serde_json::Value::as_str);
+    let generator = match (parsed.generator.generator_type.as_str(), settings) {
+        ("minecraft:noise", Some("minecraft:overworld")) => Dimension::OVERWORLD,
+        ("minecraft:noise", Some("minecraft:nether")) => Dimension::THE_NETHER,
+        ("minecraft:noise", Some("minecraft:end")) => Dimension::THE_END,
+        (generator_type, settings) => {
+            log::warn!(
+                "The datapack dimension {name} uses the generator {generator_type} with the \
+                 settings {}, which is not supported, generating it like the overworld",
+                settings.unwrap_or("inline")
+            );
+            Dimension::OVERWORLD
+        }
+    };
+    Ok((dimension, generator))
+}
+
+impl DimensionTypeJson {
+    fn into_dimension(self) -> Result<Dimension, String> {
+        if self.min_y % 16 != 0 || !(MIN_Y_RANGE.0..=MIN_Y_RANGE.1).contains(&self.min_y) {
+            return Err(format!(
+                "min_y must be a multiple of 16 from {} to {}",
+                MIN_Y_RANGE.0, MIN_Y_RANGE.1
+            ));
+        }
+        if self.height % 16 != 0 || !(16..=4064).contains(&self.height) {
+            return Err("height must be a multiple of 16 from 16 to 4064".to_string());
+        }
+        if !(1..=self.height).contains(&self.logical_height) {
+            return Err("logical_height must be from 1 to the height".to_string());
+        }
+        Ok(Dimension {
+            id: 0,
+            minecraft_name: "",
+            fixed_time: self
+                .fixed_time
+                .or_else(|| self.has_fixed_time.then_some(NOON)),
+            has_skylight: self.has_skylight,
+            has_ceiling: self.has_ceiling,
+            coordinate_scale: self.coordinate_scale,
+            min_y: self.min_y,
+            height: self.height,
+            logical_height: self.logical_height,
+            infiniburn: Box::leak(self.infiniburn.into_boxed_str()),
+            ambient_light: self.ambient_light,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_packs() -> DataPacks {
+        DataPacks {
+            disabled: vec!["file/disabled".to_string()],
+            enabled: vec!["vanilla".to_string()],
+        }
+    }
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn loads_dimensions_of_datapacks() {
+        let world = temp_dir::TempDir::new().unwrap();
+        let root = world.path();
+        write(root, "datapacks/mining/pack.mcmeta", "{}");
+        write(
+            root,
+            "datapacks/mining/data/mining/dimension_type/deep.json",
+            r##"{"has_skylight": false, "has_ceiling": true, "coordinate_scale": 1.0,
+                "min_y": -128, "height": 512, "logical_height": 512,
+                "infiniburn": "#minecraft:infiniburn_overworld", "ambient_light": 0.5,
+                "fixed_time": 18000}"##,
+        );
+        write(
+            root,
+            "datapacks/mining/data/mining/dimension/deep.json",
+            r#"{"type": "mining:deep", "generator": {"type": "minecraft:noise",
+                "settings": "minecraft:nether"}}"#,
+        );
+        write(
+            root,
+            "datapacks/mining/data/mining/dimension/caves/flat.json",
+            r#"{"type": "minecraft:the_end", "generator": {"type": "minecraft:flat"}}"#,
+        );
+        write(root, "datapacks/disabled/pack.mcmeta", "{}");
+        write(
+            root,
+            "datapacks/disabled/data/other/dimension/skipped.json",
+            r#"{"type": "minecraft:overworld", "generator": {"type": "minecraft:noise",
+                "settings": "minecraft:overworld"}}"#,
+        );
+
+        let dimensions = load_dimensions(root, &data_packs());
+        assert_eq!(dimensions.len(), 2);
+
+        let flat = dimensions[0];
+        assert_eq!(flat.dimension.minecraft_name, "mining:caves/flat");
+        assert_eq!(flat.dimension.id, FIRST_ID);
+        assert_eq!(flat.dimension.height, Dimension::THE_END.height);
+        assert_eq!(flat.generator, Dimension::OVERWORLD);
+
+        let deep = dimensions[1];
+        assert_eq!(deep.dimension.minecraft_name, "mining:deep");
+        assert_eq!(deep.dimension.id, FIRST_ID + 1);
+        assert_eq!(deep.dimension.min_y, -128);
+        assert_eq!(deep.dimension.height, 512);
+        assert_eq!(deep.dimension.fixed_time, Some(18000));
+        assert_eq!(deep.generator, Dimension::THE_NETHER);
+    }
+
+    #[test]
+    fn rejects_unsupported_heights() {
+        let types = BTreeMap::from([(
+            "test:low".to_string(),
+            r##"{"has_skylight": true, "has_ceiling": false, "coordinate_scale": 1.0,
+                "min_y": -256, "height": 384, "logical_height": 384,
+                "infiniburn": "#minecraft:infiniburn_overworld", "ambient_light": 0.0}"##
+                .to_string(),
+        )]);
+        let dimension = r#"{"type": "test:low", "generator": {"type": "minecraft:noise",
+            "settings": "minecraft:overworld"}}"#;
+        assert!(parse_dimension("test:low", dimension, &types).is_err());
+    }
+}
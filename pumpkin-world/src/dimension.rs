@@ -1,3 +1,4 @@
+use std::sync::{LazyLock, RwLock};
 use std::{path::PathBuf, sync::Arc};
 
 use pumpkin_config::world::LevelConfig;
@@ -5,6 +6,63 @@ use pumpkin_data::dimension::Dimension;
 
 use crate::{level::Level, world::BlockRegistryExt};
 
+/// A dimension added by a datapack, see [`crate::datapack`].
+#[derive(Debug, Clone, Copy)]
+pub struct DatapackDimension {
+    pub dimension: Dimension,
+    /// The vanilla dimension whose terrain and biomes it generates.
+    pub generator: Dimension,
+}
+
+static DATAPACK_DIMENSIONS: LazyLock<RwLock<Vec<DatapackDimension>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Makes a dimension of a datapack known to [`dimension_from_name`] and to world generation.
+/// Replaces a dimension of the same name.
+pub fn register_datapack_dimension(dimension: DatapackDimension) {
+    let mut dimensions = DATAPACK_DIMENSIONS.write().unwrap();
+    dimensions.retain(|registered| {
+        registered.dimension.minecraft_name != dimension.dimension.minecraft_name
+    });
+    dimensions.push(dimension);
+}
+
+/// The registered datapack dimensions, in the order of their ids.
+#[must_use]
+pub fn datapack_dimensions() -> Vec<DatapackDimension> {
+    let mut dimensions = DATAPACK_DIMENSIONS.read().unwrap().clone();
+    dimensions.sort_by_key(|datapack| datapack.dimension.id);
+    dimensions
+}
+
+/// The vanilla or datapack dimension called `name`.
+#[must_use]
+pub fn dimension_from_name(name: &str) -> Option<Dimension> {
+    Dimension::from_name(name).copied().or_else(|| {
+        DATAPACK_DIMENSIONS
+            .read()
+            .unwrap()
+            .iter()
+            .find(|datapack| datapack.dimension.minecraft_name == name)
+            .map(|datapack| datapack.dimension)
+    })
+}
+
+/// The vanilla dimension whose terrain and biomes `dimension` generates, itself unless it is
+/// from a datapack.
+#[must_use]
+pub fn generator_dimension(dimension: &Dimension) -> Dimension {
+    if Dimension::from_name(dimension.minecraft_name).is_some() {
+        return *dimension;
+    }
+    DATAPACK_DIMENSIONS
+        .read()
+        .unwrap()
+        .iter()
+        .find(|datapack| datapack.dimension == *dimension)
+        .map_or(Dimension::OVERWORLD, |datapack| datapack.generator)
+}
+
 pub fn into_level(
     dimension: Dimension,
     level_config: &LevelConfig,
@@ -17,6 +75,15 @@ pub fn into_level(
         base_directory.push("DIM-1");
     } else if dimension == Dimension::THE_END {
         base_directory.push("DIM1");
+    } else {
+        // Where vanilla keeps the dimensions of datapacks
+        let (namespace, path) = dimension
+            .minecraft_name
+            .split_once(':')
+            .unwrap_or(("minecraft", dimension.minecraft_name));
+        base_directory.push("dimensions");
+        base_directory.push(namespace);
+        base_directory.push(path);
     }
     Level::from_root_folder(
         level_config,
@@ -1,11 +1,11 @@
 use itertools::Itertools;
 use pumpkin_data::fluid::{Fluid, FluidState};
-use pumpkin_data::tag::{RegistryKey, get_tag_ids};
 use pumpkin_data::{Block, BlockDirection, BlockState};
 use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
 use serde::Deserialize;
 
 use crate::block::RawBlockState;
+use crate::generation::identifiers::deserialize_block_tag;
 use crate::generation::proto_chunk::GenerationCache;
 use crate::{block::BlockStateCodec, world::BlockRegistryExt};
 
@@ -125,15 +125,14 @@ impl MatchingFluidsBlockPredicate {
 pub struct MatchingBlockTagPredicate {
     #[serde(flatten)]
     offset: OffsetBlocksBlockPredicate,
-    tag: String,
+    #[serde(deserialize_with = "deserialize_block_tag")]
+    tag: &'static [u16],
 }
 
 impl MatchingBlockTagPredicate {
     pub fn test<T: GenerationCache>(&self, chunk: &T, pos: &BlockPos) -> bool {
         let block = self.offset.get_raw(chunk, pos);
-        get_tag_ids(RegistryKey::Block, &self.tag)
-            .unwrap()
-            .contains(&block.to_block_id())
+        self.tag.contains(&block.to_block_id())
     }
 }
 
@@ -265,6 +265,8 @@ impl ConfiguredFeature {
                 random,
                 pos,
             ),
+            Self::EndIsland(feature) => feature.generate(chunk, random, pos),
+            Self::EndGateway(feature) => feature.generate(chunk, random, pos),
             Self::SpringFeature(feature) => feature.generate(block_registry, chunk, random, pos),
             Self::SimpleBlock(feature) => feature.generate(block_registry, chunk, random, pos),
             Self::Flower(feature) => feature.generate(
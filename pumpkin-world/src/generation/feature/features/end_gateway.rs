@@ -1,6 +1,57 @@
+use pumpkin_data::{Block, BlockState};
+use pumpkin_util::{
+    math::{position::BlockPos, vector3::Vector3},
+    random::RandomGenerator,
+};
 use serde::Deserialize;
 
+use crate::generation::proto_chunk::GenerationCache;
+
 #[derive(Deserialize)]
 pub struct EndGatewayFeature {
-    // TODO
+    /// Where the gateway leads. Generated gateways can't keep it, since features can't place
+    /// block entities, so players entering them are sent to the main island.
+    exit: Option<Vector3<i32>>,
+    exact: bool,
+}
+
+/// The blocks of an end gateway around `origin`: the gateway between two bedrock blocks, in a
+/// frame of bedrock.
+#[must_use]
+pub fn end_gateway_blocks(origin: BlockPos) -> Vec<(BlockPos, &'static BlockState)> {
+    BlockPos::iterate(
+        origin.offset(Vector3::new(-1, -2, -1)),
+        origin.offset(Vector3::new(1, 2, 1)),
+    )
+    .map(|pos| {
+        let center_x = pos.0.x == origin.0.x;
+        let center_y = pos.0.y == origin.0.y;
+        let center_z = pos.0.z == origin.0.z;
+        let end_y = (pos.0.y - origin.0.y).abs() == 2;
+        let block = if center_x && center_y && center_z {
+            &Block::END_GATEWAY
+        } else if center_y {
+            &Block::AIR
+        } else if (center_x || center_z) && (!end_y || (center_x && center_z)) {
+            &Block::BEDROCK
+        } else {
+            &Block::AIR
+        };
+        (pos, block.default_state)
+    })
+    .collect()
+}
+
+impl EndGatewayFeature {
+    pub fn generate<T: GenerationCache>(
+        &self,
+        chunk: &mut T,
+        _random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        for (pos, state) in end_gateway_blocks(pos) {
+            chunk.set_block_state(&pos.0, state);
+        }
+        true
+    }
 }
@@ -1,6 +1,45 @@
+use pumpkin_data::Block;
+use pumpkin_util::{
+    math::{position::BlockPos, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::generation::proto_chunk::GenerationCache;
+
+/// A small floating island of end stone, narrowing towards the bottom.
 #[derive(Deserialize)]
-pub struct EndIslandFeature {
-    // TODO
+pub struct EndIslandFeature {}
+
+/// The end stone blocks of an island with its top layer at `origin`.
+pub fn end_island_blocks<R: RandomImpl>(random: &mut R, origin: BlockPos) -> Vec<BlockPos> {
+    let mut blocks = Vec::new();
+    let mut radius = random.next_bounded_i32(3) as f32 + 4.0;
+    let mut y = 0;
+    while radius > 0.5 {
+        for x in (-radius).floor() as i32..=radius.ceil() as i32 {
+            for z in (-radius).floor() as i32..=radius.ceil() as i32 {
+                if (x * x + z * z) as f32 <= (radius + 1.0) * (radius + 1.0) {
+                    blocks.push(origin.offset(Vector3::new(x, y, z)));
+                }
+            }
+        }
+        radius -= random.next_bounded_i32(2) as f32 + 0.5;
+        y -= 1;
+    }
+    blocks
+}
+
+impl EndIslandFeature {
+    pub fn generate<T: GenerationCache>(
+        &self,
+        chunk: &mut T,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        for pos in end_island_blocks(random, pos) {
+            chunk.set_block_state(&pos.0, Block::END_STONE.default_state);
+        }
+        true
+    }
 }
@@ -1,5 +1,8 @@
 use pumpkin_data::Block;
-use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
+use pumpkin_util::{
+    math::{position::BlockPos, vector3::Vector3},
+    random::RandomGenerator,
+};
 use serde::Deserialize;
 
 use crate::generation::proto_chunk::GenerationCache;
@@ -20,18 +23,19 @@ impl EndPlatformFeature {
         _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
-        for _ in -2..2 {
-            for _ in -2..2 {
-                for t in -1..3 {
-                    let state = if t == -1 {
+        for x in -2..=2 {
+            for z in -2..=2 {
+                for y in -1..3 {
+                    let state = if y == -1 {
                         Block::OBSIDIAN.default_state
                     } else {
                         Block::AIR.default_state
                     };
-                    if GenerationCache::get_block_state(chunk, &pos.0).0 == state.id {
+                    let block_pos = pos.0.add(&Vector3::new(x, y, z));
+                    if GenerationCache::get_block_state(chunk, &block_pos).0 == state.id {
                         continue;
                     }
-                    chunk.set_block_state(&pos.0, state);
+                    chunk.set_block_state(&block_pos, state);
                 }
             }
         }
@@ -1,7 +1,10 @@
-use pumpkin_data::Block;
+use pumpkin_data::{
+    Block, BlockState,
+    block_properties::{BlockProperties, OakFenceLikeProperties},
+};
 use pumpkin_util::{
     math::position::BlockPos,
-    random::{RandomGenerator, RandomImpl},
+    random::{RandomGenerator, RandomImpl, legacy_rand::LegacyRand},
 };
 use serde::Deserialize;
 
@@ -11,20 +14,23 @@ use crate::{generation::section_coords, world::BlockRegistryExt};
 #[derive(Deserialize)]
 pub struct EndSpikeFeature {
     crystal_invulnerable: bool,
-    spikes: Vec<Spike>,
+    spikes: Vec<EndSpike>,
 }
 
-#[derive(Deserialize, Clone)]
+/// One of the obsidian pillars around the main island of the End.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-struct Spike {
-    center_x: i32,
-    center_z: i32,
-    radius: i32,
-    height: i32,
-    guarded: bool,
+pub struct EndSpike {
+    pub center_x: i32,
+    pub center_z: i32,
+    pub radius: i32,
+    /// The y of the bedrock block on top, under the end crystal.
+    pub height: i32,
+    /// Whether iron bars protect the crystal.
+    pub guarded: bool,
 }
 
-impl Spike {
+impl EndSpike {
     pub fn is_in_chunk(&self, pos: &BlockPos) -> bool {
         section_coords::block_to_section(pos.0.x) == section_coords::block_to_section(self.center_x)
             && section_coords::block_to_section(pos.0.z)
@@ -32,6 +38,33 @@ impl Spike {
     }
 }
 
+/// The ten pillars of a world, the same in every chunk since their sizes only depend on the seed.
+#[must_use]
+pub fn end_spikes(world_seed: u64) -> Vec<EndSpike> {
+    let spike_seed = LegacyRand::from_seed(world_seed).next_i64() & 0xFFFF;
+    let mut random = LegacyRand::from_seed(spike_seed as u64);
+    let mut sizes: Vec<i32> = (0..10).collect();
+    for i in (2..=sizes.len()).rev() {
+        let j = random.next_bounded_i32(i as i32) as usize;
+        sizes.swap(i - 1, j);
+    }
+
+    sizes
+        .into_iter()
+        .enumerate()
+        .map(|(i, size)| {
+            let angle = 2.0 * (-std::f64::consts::PI + std::f64::consts::PI / 10.0 * i as f64);
+            EndSpike {
+                center_x: (42.0 * angle.cos()).floor() as i32,
+                center_z: (42.0 * angle.sin()).floor() as i32,
+                radius: 2 + size / 3,
+                height: 76 + size * 3,
+                guarded: size == 1 || size == 2,
+            }
+        })
+        .collect()
+}
+
 impl EndSpikeFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate<T: GenerationCache>(
@@ -41,30 +74,14 @@ impl EndSpikeFeature {
         _min_y: i8,
         _height: u16,
         _feature: &str, // This placed feature
-        random: &mut RandomGenerator,
+        _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
-        let mut spikes = self.spikes.clone();
-        if spikes.is_empty() {
-            for i in 0..10 {
-                let angle = 2.0 * (-std::f64::consts::PI + 0.3141592653589793 * i as f64);
-                let center_x = (42.0 * angle.cos()).floor() as i32;
-                let center_z = (42.0 * angle.sin()).floor() as i32;
-
-                let l = random.next_bounded_i32(10); // TODO
-                let radius = 2 + l / 3;
-                let height = 76 + l * 3;
-                let guarded = l == 1 || l == 2;
-
-                spikes.push(Spike {
-                    center_x,
-                    center_z,
-                    radius,
-                    height,
-                    guarded,
-                });
-            }
-        }
+        let spikes = if self.spikes.is_empty() {
+            end_spikes(chunk.world_seed())
+        } else {
+            self.spikes.clone()
+        };
         for spike in spikes {
             if !spike.is_in_chunk(&pos) {
                 continue;
@@ -75,7 +92,7 @@ impl EndSpikeFeature {
         true
     }
 
-    fn gen_spike<T: GenerationCache>(spike: &Spike, chunk: &mut T) {
+    fn gen_spike<T: GenerationCache>(spike: &EndSpike, chunk: &mut T) {
         let radius = spike.radius;
         for pos in BlockPos::iterate(
             BlockPos::new(
@@ -85,7 +102,7 @@ impl EndSpikeFeature {
             ),
             BlockPos::new(
                 spike.center_x + radius,
-                chunk.height() as i32 + 10,
+                spike.height + 10,
                 spike.center_z + radius,
             ),
         ) {
@@ -103,6 +120,40 @@ impl EndSpikeFeature {
             }
             chunk.set_block_state(&pos.0, Block::AIR.default_state);
         }
-        // TODO
+
+        if spike.guarded {
+            Self::gen_cage(spike, chunk);
+        }
+        // The end crystal is spawned by the dragon fight, features can't spawn entities
+        chunk.set_block_state(
+            &BlockPos::new(spike.center_x, spike.height, spike.center_z).0,
+            Block::BEDROCK.default_state,
+        );
+    }
+
+    /// Surrounds the top of the pillar with iron bars, connected to each other.
+    fn gen_cage<T: GenerationCache>(spike: &EndSpike, chunk: &mut T) {
+        for x in -2..=2 {
+            for z in -2..=2 {
+                for y in 0..=3 {
+                    let roof = y == 3;
+                    if x.abs() != 2 && z.abs() != 2 && !roof {
+                        continue;
+                    }
+                    let along_x = x.abs() == 2 || roof;
+                    let along_z = z.abs() == 2 || roof;
+                    let mut props = OakFenceLikeProperties::default(&Block::IRON_BARS);
+                    props.north = along_x && z != -2;
+                    props.south = along_x && z != 2;
+                    props.west = along_z && x != -2;
+                    props.east = along_z && x != 2;
+                    let state = BlockState::from_id(props.to_state_id(&Block::IRON_BARS));
+                    chunk.set_block_state(
+                        &BlockPos::new(spike.center_x + x, spike.height + y, spike.center_z + z).0,
+                        state,
+                    );
+                }
+            }
+        }
     }
 }
@@ -3,5 +3,11 @@ mod configured_features;
 /// then if we want to place a feature we place it using the `configured_features`, there is the logic for how we are going to place the feature
 pub mod placed_features;
 
-mod features;
+pub(super) mod features;
 mod size;
+
+/// Loads the configured and placed features if they were not used yet.
+pub(super) fn load() {
+    std::sync::LazyLock::force(&configured_features::CONFIGURED_FEATURES);
+    std::sync::LazyLock::force(&placed_features::PLACED_FEATURES);
+}
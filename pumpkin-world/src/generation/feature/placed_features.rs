@@ -145,7 +145,7 @@ pub enum PlacementModifier {
     #[serde(rename = "minecraft:random_offset")]
     RandomOffset(RandomOffsetPlacementModifier),
     #[serde(rename = "minecraft:fixed_placement")]
-    FixedPlacement,
+    FixedPlacement(FixedPlacementModifier),
 }
 
 impl PlacementModifier {
@@ -185,7 +185,7 @@ impl PlacementModifier {
             Self::HeightRange(modifier) => modifier.get_positions(min_y, height, random, pos),
             Self::InSquare(_) => SquarePlacementModifier::get_positions(random, pos),
             Self::RandomOffset(modifier) => modifier.get_positions(random, pos),
-            Self::FixedPlacement => Box::new(iter::empty()),
+            Self::FixedPlacement(modifier) => modifier.get_positions(pos),
         }
     }
 }
@@ -293,6 +293,25 @@ impl RandomOffsetPlacementModifier {
     }
 }
 
+#[derive(Deserialize)]
+pub struct FixedPlacementModifier {
+    positions: Vec<Vector3<i32>>,
+}
+
+impl FixedPlacementModifier {
+    /// The positions inside the chunk being generated.
+    pub fn get_positions(&self, pos: BlockPos) -> Box<dyn Iterator<Item = BlockPos>> {
+        let chunk = pos.chunk_position();
+        let positions: Vec<_> = self
+            .positions
+            .iter()
+            .map(|position| BlockPos(*position))
+            .filter(|position| position.chunk_position() == chunk)
+            .collect();
+        Box::new(positions.into_iter())
+    }
+}
+
 #[derive(Deserialize)]
 pub struct CountOnEveryLayerPlacementModifier {
     count: IntProvider,
@@ -7,6 +7,7 @@ use pumpkin_data::noise_router::{
 
 use super::noise::router::proto_noise_router::ProtoNoiseRouters;
 use crate::block::to_state_from_blueprint;
+use crate::dimension::generator_dimension;
 use crate::generation::proto_chunk::TerrainCache;
 use crate::generation::{GlobalRandomConfig, Seed};
 
@@ -30,17 +31,18 @@ impl GeneratorInit for VanillaGenerator {
 
         // TODO: The generation settings contains (part of?) the noise routers too; do we keep the separate or
         // use only the generation settings?
-        let base = if dimension == Dimension::OVERWORLD {
+        let generator = generator_dimension(&dimension);
+        let base = if generator == Dimension::OVERWORLD {
             OVERWORLD_BASE_NOISE_ROUTER
-        } else if dimension == Dimension::THE_NETHER {
+        } else if generator == Dimension::THE_NETHER {
             NETHER_BASE_NOISE_ROUTER
-        } else if dimension == Dimension::THE_END {
+        } else if generator == Dimension::THE_END {
             END_BASE_NOISE_ROUTER
         } else {
             unreachable!()
         };
         let terrain_cache = TerrainCache::from_random(&random_config);
-        let generation_settings = GenerationSettings::from_dimension(&dimension);
+        let generation_settings = GenerationSettings::from_dimension(&generator);
 
         let default_block = to_state_from_blueprint(&generation_settings.default_block);
         let base_router = ProtoNoiseRouters::generate(&base, &random_config);
@@ -0,0 +1,29 @@
+//! Resolves the identifiers of world generation data once when it is loaded, so that the tests
+//! run for every generated block compare ids instead of names.
+
+use pumpkin_data::Block;
+use pumpkin_data::tag::{RegistryKey, get_tag_ids};
+use serde::{Deserialize, Deserializer, de};
+
+/// Deserializes a block name into its raw block id.
+pub fn deserialize_block_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Block::from_name(&name)
+        .map(|block| block.id)
+        .ok_or_else(|| de::Error::custom(format!("Unknown block {name}")))
+}
+
+/// Deserializes a block tag, with or without a leading `#`, into the raw ids of its blocks.
+pub fn deserialize_block_tag<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<&'static [u16], D::Error> {
+    let tag = String::deserialize(deserializer)?;
+    let tag = tag.strip_prefix('#').unwrap_or(&tag);
+    let tag = if tag.contains(':') {
+        tag.to_string()
+    } else {
+        format!("minecraft:{tag}")
+    };
+    get_tag_ids(RegistryKey::Block, &tag)
+        .ok_or_else(|| de::Error::custom(format!("Unknown block tag {tag}")))
+}
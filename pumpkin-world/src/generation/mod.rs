@@ -9,6 +9,7 @@ mod feature;
 pub mod generator;
 pub mod height_limit;
 pub mod height_provider;
+mod identifiers;
 pub mod noise;
 pub mod positions;
 pub mod proto_chunk;
@@ -18,6 +19,9 @@ mod rule_test;
 pub mod structure;
 mod surface;
 
+pub use feature::features::end_gateway::end_gateway_blocks;
+pub use feature::features::end_island::end_island_blocks;
+pub use feature::features::end_spike::{EndSpike, end_spikes};
 use generator::{GeneratorInit, VanillaGenerator};
 use pumpkin_data::dimension::Dimension;
 use pumpkin_util::{
@@ -28,6 +32,11 @@ use pumpkin_util::{
     world_seed::Seed,
 };
 
+/// Loads the world generation data and resolves the blocks and tags it refers to.
+pub fn load_data() {
+    feature::load();
+}
+
 #[must_use]
 pub fn get_world_gen(seed: Seed, dimension: Dimension) -> Box<VanillaGenerator> {
     // TODO decide which WorldGenerator to pick based on config.
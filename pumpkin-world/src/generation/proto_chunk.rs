@@ -31,6 +31,7 @@ use super::{
 };
 use crate::chunk::{ChunkData, ChunkHeightmapType};
 use crate::chunk_system::StagedChunkEnum;
+use crate::dimension::generator_dimension;
 use crate::generation::height_limit::HeightLimitView;
 use crate::generation::noise::aquifer_sampler::{
     FluidLevel, FluidLevelSampler, FluidLevelSamplerImpl,
@@ -52,6 +53,9 @@ use crate::{
 };
 
 pub trait GenerationCache: HeightLimitView + BlockAccessor {
+    /// The seed of the world the chunks are generated for.
+    fn world_seed(&self) -> u64;
+
     fn get_center_chunk_mut(&mut self) -> &mut ProtoChunk;
     fn get_center_chunk(&self) -> &ProtoChunk;
 
@@ -606,6 +610,8 @@ impl ProtoChunk {
         dimension: Dimension,
         multi_noise_sampler: &mut MultiNoiseSampler,
     ) {
+        // Datapack dimensions have the biomes of the dimension they generate like
+        let dimension = generator_dimension(&dimension);
         let min_y = self.bottom_y();
         let bottom_section = section_coords::block_to_section(min_y) as i32;
         let top_section = section_coords::block_to_section(min_y as i32 + self.height() as i32 - 1);
@@ -1,15 +1,16 @@
 use serde::Deserialize;
 
 use crate::block::RawBlockState;
+use crate::generation::identifiers::deserialize_block_id;
 
 #[derive(Deserialize)]
 pub struct BlockMatchRuleTest {
-    // This should be a Block codec, so this is wrong
-    block: String,
+    #[serde(deserialize_with = "deserialize_block_id")]
+    block: u16,
 }
 
 impl BlockMatchRuleTest {
     pub fn test(&self, state: &RawBlockState) -> bool {
-        state.to_block().name == self.block.strip_prefix("minecraft:").unwrap_or(&self.block)
+        state.to_block_id() == self.block
     }
 }
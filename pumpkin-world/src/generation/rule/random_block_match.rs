@@ -2,17 +2,17 @@ use pumpkin_util::random::{RandomGenerator, RandomImpl};
 use serde::Deserialize;
 
 use crate::block::RawBlockState;
+use crate::generation::identifiers::deserialize_block_id;
 
 #[derive(Deserialize)]
 pub struct RandomBlockMatchRuleTest {
-    // This should be a Block codec, so this is wrong
-    block: String,
+    #[serde(deserialize_with = "deserialize_block_id")]
+    block: u16,
     probability: f32,
 }
 
 impl RandomBlockMatchRuleTest {
     pub fn test(&self, state: &RawBlockState, random: &mut RandomGenerator) -> bool {
-        state.to_block().name == self.block.strip_prefix("minecraft:").unwrap_or(&self.block)
-            && random.next_f32() < self.probability
+        state.to_block_id() == self.block && random.next_f32() < self.probability
     }
 }
@@ -1,16 +1,16 @@
-use pumpkin_data::tag::{RegistryKey, get_tag_ids};
 use serde::Deserialize;
 
 use crate::block::RawBlockState;
+use crate::generation::identifiers::deserialize_block_tag;
 
 #[derive(Deserialize)]
 pub struct TagMatchRuleTest {
-    tag: String,
+    #[serde(deserialize_with = "deserialize_block_tag")]
+    tag: &'static [u16],
 }
 
 impl TagMatchRuleTest {
     pub fn test(&self, state: &RawBlockState) -> bool {
-        let values = get_tag_ids(RegistryKey::Block, &self.tag).unwrap();
-        values.contains(&state.to_block_id())
+        self.tag.contains(&state.to_block_id())
     }
 }
@@ -1,6 +1,8 @@
-use pumpkin_data::{Block, tag::Taggable};
+use pumpkin_data::Block;
 use serde::Deserialize;
 
+use crate::generation::identifiers::{deserialize_block_id, deserialize_block_tag};
+
 /// Rule tests are used in structure or features generation to check if a block state matches some condition.
 #[derive(Deserialize)]
 pub enum RuleTest {
@@ -19,23 +21,24 @@ impl AlwaysTrueRuleTest {
 
 #[derive(Deserialize)]
 pub struct BlockMatchRuleTest {
-    block: String,
+    #[serde(deserialize_with = "deserialize_block_id")]
+    block: u16,
 }
 
 impl BlockMatchRuleTest {
     pub fn test(&self, block: &'static Block) -> bool {
-        let test_block = Block::from_registry_key(&self.block).expect("Failed to find block");
-        test_block == block
+        block.id == self.block
     }
 }
 
 #[derive(Deserialize)]
 pub struct TagMatchTest {
-    tag: String,
+    #[serde(deserialize_with = "deserialize_block_tag")]
+    tag: &'static [u16],
 }
 
 impl TagMatchTest {
     pub fn test(&self, block: &'static Block) -> bool {
-        block.is_tagged_with(&self.tag).unwrap()
+        self.tag.contains(&block.id)
     }
 }
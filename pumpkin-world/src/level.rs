@@ -6,13 +6,15 @@ use crate::{
     chunk::{
         ChunkData, ChunkEntityData, ChunkReadingError,
         format::{anvil::AnvilChunkFile, linear::LinearFile},
-        io::{Dirtiable, FileIO, LoadedData, file_manager::ChunkFileManager},
+        io::{
+            Dirtiable, FileIO, LoadedData, file_manager::ChunkFileManager, memory::MemoryOverlay,
+        },
     },
     generation::get_world_gen,
     tick::{OrderedTick, ScheduledTick, TickPriority},
     world::BlockRegistryExt,
 };
-use crossbeam::channel::Sender;
+use crossbeam::channel::{Receiver, Sender};
 use dashmap::DashMap;
 use log::trace;
 use num_traits::Zero;
@@ -40,6 +42,7 @@ use std::{
 use tokio::{
     select,
     sync::{
+        Notify,
         mpsc::{self, UnboundedReceiver},
         oneshot,
     },
@@ -77,6 +80,8 @@ pub struct Level {
 
     pub chunk_saver: Arc<dyn FileIO<Data = SyncChunk>>,
     entity_saver: Arc<dyn FileIO<Data = SyncEntityChunk>>,
+    /// Whether chunks are kept in memory instead of written to disk, see [`Level::in_memory`].
+    pub in_memory: bool,
 
     pub world_gen: Arc<VanillaGenerator>,
 
@@ -89,6 +94,13 @@ pub struct Level {
     pub shut_down_chunk_system: AtomicBool,
     pub should_save: AtomicBool,
     pub should_unload: AtomicBool,
+    /// Set while saving is turned off: chunks are neither autosaved nor unloaded, so nothing is
+    /// written to disk except by explicit saves.
+    pub saving_suspended: AtomicBool,
+    /// Set while the server is overloaded: new chunks are generated one at a time, so that the
+    /// generation threads leave the cores to the tick. Loading chunks isn't affected.
+    pub generation_deferred: AtomicBool,
+    pub(crate) save_progress: SaveProgress,
 
     gen_entity_request_tx: Sender<Vector2<i32>>,
     pending_entity_generations: Arc<DashMap<Vector2<i32>, Vec<oneshot::Sender<SyncEntityChunk>>>>,
@@ -96,6 +108,49 @@ pub struct Level {
     pub level_channel: Arc<LevelChannel>,
     pub thread_tracker: Mutex<Vec<thread::JoinHandle<()>>>,
     pub chunk_listener: Arc<ChunkListener>,
+    save_failure_tx: Sender<String>,
+    /// Messages of failed chunk and entity writes, for the server to alert about.
+    pub save_failures: Receiver<String>,
+}
+
+/// Follows save requests through the chunk scheduler and the io write task, so that callers can
+/// wait until the chunks of a request are written.
+#[derive(Default)]
+pub(crate) struct SaveProgress {
+    /// The number of save requests made.
+    requested: AtomicU64,
+    /// The last request whose chunks the scheduler has sent to the io write task.
+    queued: AtomicU64,
+    /// The number of write batches that have to finish for `queued` to be written.
+    queued_batches: AtomicU64,
+    /// The number of write batches the io write task has finished.
+    written_batches: AtomicU64,
+    notify: Notify,
+}
+
+impl SaveProgress {
+    /// Records that all requests up to `request` are sent to the io write task, as part of the
+    /// first `batches` write batches.
+    pub(crate) fn mark_queued(&self, request: u64, batches: u64) {
+        self.queued_batches.fetch_max(batches, Ordering::SeqCst);
+        self.queued.fetch_max(request, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub(crate) fn mark_batch_written(&self) {
+        self.written_batches.fetch_add(1, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub(crate) fn last_request(&self) -> u64 {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    fn is_written(&self, request: u64) -> bool {
+        self.queued.load(Ordering::SeqCst) >= request
+            && self.written_batches.load(Ordering::SeqCst)
+                >= self.queued_batches.load(Ordering::SeqCst)
+    }
 }
 
 pub struct TickData {
@@ -112,6 +167,35 @@ pub struct LevelFolder {
     pub entities_folder: PathBuf,
 }
 
+impl LevelFolder {
+    #[must_use]
+    pub fn new(root_folder: PathBuf) -> Self {
+        Self {
+            region_folder: root_folder.join("region"),
+            entities_folder: root_folder.join("entities"),
+            root_folder,
+        }
+    }
+}
+
+/// What keeps a chunk loaded that no player is near, see [`Level::stale_chunks`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChunkRetainer {
+    /// A forced ticket at the given chunk.
+    ForcedTicket(Vector2<i32>),
+    /// A ticket at the given chunk and level that no player owns, e.g. a leaked view ticket or a
+    /// load that never finished.
+    Ticket(Vector2<i32>, i8),
+    /// No ticket covers the chunk. It is either waiting for the next unload pass or still
+    /// referenced from elsewhere; holds the number of other references.
+    Referenced(usize),
+}
+
+pub struct StaleChunk {
+    pub pos: Vector2<i32>,
+    pub retainer: ChunkRetainer,
+}
+
 #[ignore]
 #[cfg(feature = "tokio_taskdump")]
 pub async fn dump() {
@@ -133,21 +217,54 @@ impl Level {
         seed: i64,
         dimension: Dimension,
     ) -> Arc<Self> {
-        let region_folder = root_folder.join("region");
-        let entities_folder = root_folder.join("entities");
-
-        std::fs::create_dir_all(&region_folder).expect("Failed to create Region folder");
-        std::fs::create_dir_all(&entities_folder).expect("Failed to create Entities folder");
-
-        let level_folder = LevelFolder {
-            root_folder,
-            region_folder,
-            entities_folder,
-        };
+        let level_folder = LevelFolder::new(root_folder);
+        std::fs::create_dir_all(&level_folder.region_folder)
+            .expect("Failed to create Region folder");
+        std::fs::create_dir_all(&level_folder.entities_folder)
+            .expect("Failed to create Entities folder");
+
+        let (chunk_saver, entity_saver) = Self::file_savers(level_config);
+        Self::new(
+            level_config,
+            level_folder,
+            chunk_saver,
+            entity_saver,
+            false,
+            block_registry,
+            seed,
+            dimension,
+        )
+    }
 
-        let seed = Seed(seed as u64);
-        let world_gen = get_world_gen(seed, dimension).into();
+    /// A level that reads its chunks from the world at `template_folder`, but keeps all changes
+    /// in memory. Nothing is ever written to disk, and the changes are lost once the level is
+    /// dropped.
+    pub fn in_memory(
+        level_config: &LevelConfig,
+        template_folder: PathBuf,
+        block_registry: Arc<dyn BlockRegistryExt>,
+        seed: i64,
+        dimension: Dimension,
+    ) -> Arc<Self> {
+        let (chunk_saver, entity_saver) = Self::file_savers(level_config);
+        Self::new(
+            level_config,
+            LevelFolder::new(template_folder),
+            Arc::new(MemoryOverlay::new(chunk_saver)),
+            Arc::new(MemoryOverlay::new(entity_saver)),
+            true,
+            block_registry,
+            seed,
+            dimension,
+        )
+    }
 
+    fn file_savers(
+        level_config: &LevelConfig,
+    ) -> (
+        Arc<dyn FileIO<Data = SyncChunk>>,
+        Arc<dyn FileIO<Data = SyncEntityChunk>>,
+    ) {
         let chunk_saver: Arc<dyn FileIO<Data = SyncChunk>> = match &level_config.chunk {
             ChunkConfig::Linear(config) => Arc::new(
                 ChunkFileManager::<LinearFile<ChunkData>>::new(config.clone()),
@@ -164,12 +281,29 @@ impl Level {
                 AnvilChunkFile<ChunkEntityData>,
             >::new(config.clone())),
         };
+        (chunk_saver, entity_saver)
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    fn new(
+        level_config: &LevelConfig,
+        level_folder: LevelFolder,
+        chunk_saver: Arc<dyn FileIO<Data = SyncChunk>>,
+        entity_saver: Arc<dyn FileIO<Data = SyncEntityChunk>>,
+        in_memory: bool,
+        block_registry: Arc<dyn BlockRegistryExt>,
+        seed: i64,
+        dimension: Dimension,
+    ) -> Arc<Self> {
+        let seed = Seed(seed as u64);
+        let world_gen = get_world_gen(seed, dimension).into();
 
         let (gen_entity_request_tx, gen_entity_request_rx) = crossbeam::channel::unbounded();
         let pending_entity_generations = Arc::new(DashMap::new());
         let level_channel = Arc::new(LevelChannel::new());
         let thread_tracker = Mutex::new(Vec::new());
         let listener = Arc::new(ChunkListener::new());
+        let (save_failure_tx, save_failures) = crossbeam::channel::unbounded();
 
         let level_ref = Arc::new(Self {
             seed,
@@ -178,6 +312,7 @@ impl Level {
             level_folder,
             chunk_saver,
             entity_saver,
+            in_memory,
             schedule_tick_counts: AtomicU64::new(0),
             loaded_chunks: Arc::new(DashMap::new()),
             loaded_entity_chunks: Arc::new(DashMap::new()),
@@ -189,20 +324,28 @@ impl Level {
             shut_down_chunk_system: AtomicBool::new(false),
             should_save: AtomicBool::new(false),
             should_unload: AtomicBool::new(false),
+            saving_suspended: AtomicBool::new(false),
+            generation_deferred: AtomicBool::new(false),
+            save_progress: SaveProgress::default(),
             gen_entity_request_tx,
             pending_entity_generations: pending_entity_generations.clone(),
             level_channel: level_channel.clone(),
             thread_tracker,
             chunk_listener: listener.clone(),
+            save_failure_tx,
+            save_failures,
         });
 
-        // TODO
-        let total_cores = num_cpus::get().saturating_sub(2).max(1);
-        let threads_per_dimension = (total_cores / 2).max(1);
+        let generation = &level_config.generation;
+        let threads_per_dimension = if generation.threads == 0 {
+            (num_cpus::get().saturating_sub(2).max(1) / 2).max(1)
+        } else {
+            generation.threads
+        };
         let entity_threads = (threads_per_dimension / 2).max(1);
 
         GenerationSchedule::create(
-            2,
+            generation.io_threads.max(1),
             threads_per_dimension,
             level_ref.clone(),
             level_channel,
@@ -268,6 +411,31 @@ impl Level {
         self.tasks.spawn(task)
     }
 
+    /// Asks the chunk system to write all dirty chunks, even while saving is suspended. Returns
+    /// the request to pass to [`Level::await_save`].
+    pub fn request_save(&self) -> u64 {
+        let request = self.save_progress.requested.fetch_add(1, Ordering::SeqCst) + 1;
+        self.should_save.store(true, Ordering::SeqCst);
+        self.level_channel.notify();
+        request
+    }
+
+    /// Waits until the chunks of `request` and everything queued for writing before them are on
+    /// disk.
+    pub async fn await_save(&self, request: u64) {
+        loop {
+            // Created before checking, so that a notification in between isn't missed
+            let notified = self.save_progress.notify.notified();
+            if self.save_progress.is_written(request)
+                || self.shut_down_chunk_system.load(Ordering::Relaxed)
+            {
+                break;
+            }
+            notified.await;
+        }
+        self.chunk_saver.block_and_await_ongoing_tasks().await;
+    }
+
     pub async fn shutdown(&self) {
         log::info!("Saving level...");
         self.cancel_token.cancel();
@@ -436,34 +604,32 @@ impl Level {
             block_entities: Vec::new(),
         };
 
-        let r = rand::random::<u32>();
-
         for chunk in self.loaded_chunks.iter() {
             let chunk_x_base = chunk.x * 16;
             let chunk_z_base = chunk.z * 16;
-            let section_count = chunk.section.count;
 
             ticks
                 .block_entities
                 .extend(chunk.block_entities.lock().unwrap().values().cloned());
 
-            for i in 0..section_count {
-                let y_base = i as i32 * 16;
+            let sections = chunk.section.block_sections.read().unwrap();
+            for (i, section) in sections.iter().enumerate() {
+                // Most sections are air or stone, which never tick randomly
+                if section.random_ticking_count() == 0 {
+                    continue;
+                }
+                let y_base = chunk.section.min_y + i as i32 * 16;
                 for _ in 0..3 {
+                    let r = rand::random::<u32>();
                     let x_offset = (r & 0xF) as usize;
                     let z_offset = (r >> 8 & 0xF) as usize;
-                    let y_in_section = ((r >> 4) & 0xF) as i32;
-                    let absolute_y = y_base + y_in_section;
-
-                    if let Some(block_state_id) = chunk
-                        .section
-                        .get_block_absolute_y(x_offset, absolute_y, z_offset)
-                        && has_random_ticks(block_state_id)
-                    {
+                    let y_in_section = ((r >> 4) & 0xF) as usize;
+
+                    if has_random_ticks(section.get(x_offset, y_in_section, z_offset)) {
                         ticks.random_ticks.push(ScheduledTick {
                             position: BlockPos::new(
                                 chunk_x_base + x_offset as i32,
-                                absolute_y,
+                                y_base + y_in_section as i32,
                                 chunk_z_base + z_offset as i32,
                             ),
                             delay: 0,
@@ -473,6 +639,7 @@ impl Level {
                     }
                 }
             }
+            drop(sections);
             ticks.block_ticks.append(&mut chunk.block_ticks.step_tick());
             ticks.fluid_ticks.append(&mut chunk.fluid_ticks.step_tick());
         }
@@ -491,6 +658,80 @@ impl Level {
         self.chunk_watchers.get(chunk).is_some()
     }
 
+    /// Finds loaded chunks that no player watches, along with what keeps each of them loaded.
+    ///
+    /// `players` are the chunks the players of this level are in. Tickets next to a player are
+    /// treated as that player's view ticket, so chunks covered by them are not reported.
+    pub fn stale_chunks(&self, players: &[Vector2<i32>]) -> Vec<StaleChunk> {
+        let distance = |a: Vector2<i32>, b: Vector2<i32>| (a.x - b.x).abs().max((a.y - b.y).abs());
+        let (owned, unowned, forced) = {
+            let loading = self.chunk_loading.lock().unwrap();
+            let (owned, unowned): (Vec<_>, Vec<_>) = loading
+                .ticket
+                .iter()
+                .filter_map(|(pos, levels)| Some((*pos, *levels.iter().min()?)))
+                .partition(|(pos, _)| players.iter().any(|player| distance(*pos, *player) <= 1));
+            (owned, unowned, loading.high_priority.clone())
+        };
+        // Whether a ticket keeps `pos` at a level below unloading
+        let covers = |pos: Vector2<i32>, (at, level): (Vector2<i32>, i8)| {
+            i32::from(level) + distance(pos, at) < i32::from(ChunkLoading::MAX_LEVEL)
+        };
+
+        let mut stale = Vec::new();
+        for entry in self.loaded_chunks.iter() {
+            let pos = *entry.key();
+            if self.is_chunk_watched(&pos) || owned.iter().any(|ticket| covers(pos, *ticket)) {
+                continue;
+            }
+            let retainer = unowned
+                .iter()
+                .filter(|ticket| covers(pos, **ticket))
+                .min_by_key(|(at, level)| i32::from(*level) + distance(pos, *at))
+                .map_or_else(
+                    // One reference is held by the loaded chunk map
+                    || ChunkRetainer::Referenced(Arc::strong_count(entry.value()) - 1),
+                    |(at, level)| {
+                        if forced.contains(at) {
+                            ChunkRetainer::ForcedTicket(*at)
+                        } else {
+                            ChunkRetainer::Ticket(*at, *level)
+                        }
+                    },
+                );
+            stale.push(StaleChunk { pos, retainer });
+        }
+        stale
+    }
+
+    /// Removes all tickets at the given unowned positions, as reported by [`Self::stale_chunks`],
+    /// and schedules an unload pass. Forced tickets and the load tickets of [`Self::get_chunk`]
+    /// calls still waiting for their chunk are left alone since their owner removes them
+    /// explicitly. Returns the number of removed tickets.
+    pub fn release_tickets(&self, positions: &[Vector2<i32>]) -> usize {
+        let mut released = 0;
+        {
+            let mut loading = self.chunk_loading.lock().unwrap();
+            for pos in positions {
+                if loading.high_priority.contains(pos) {
+                    continue;
+                }
+                let levels = loading.ticket.get(pos).cloned().unwrap_or_default();
+                for level in levels
+                    .into_iter()
+                    .filter(|level| *level != ChunkLoading::LOAD_TICKET_LEVEL)
+                {
+                    loading.remove_ticket(*pos, level);
+                    released += 1;
+                }
+            }
+            loading.send_change();
+        }
+        self.should_unload.store(true, Ordering::Relaxed);
+        self.level_channel.notify();
+        released
+    }
+
     pub fn clean_memory(&self) {
         self.chunk_watchers.retain(|_, watcher| !watcher.is_zero());
         self.loaded_entity_chunks
@@ -523,7 +764,7 @@ impl Level {
 
         {
             let mut lock = self.chunk_loading.lock().unwrap();
-            lock.add_ticket(pos, 31);
+            lock.add_ticket(pos, ChunkLoading::LOAD_TICKET_LEVEL);
             lock.send_change();
         };
 
@@ -536,7 +777,7 @@ impl Level {
 
         {
             let mut lock = self.chunk_loading.lock().unwrap();
-            lock.remove_ticket(pos, 31);
+            lock.remove_ticket(pos, ChunkLoading::LOAD_TICKET_LEVEL);
             lock.send_change();
         }
 
@@ -716,7 +957,7 @@ impl Level {
             .save_chunks(&level_folder, chunks_to_write)
             .await
         {
-            log::error!("Failed writing Chunk to disk {error}");
+            self.report_save_failure(format!("Failed writing chunks to disk: {error}"));
         }
     }
 
@@ -733,10 +974,17 @@ impl Level {
             .save_chunks(&level_folder, chunks_to_write)
             .await
         {
-            log::error!("Failed writing Chunk to disk {error}");
+            self.report_save_failure(format!("Failed writing entity chunks to disk: {error}"));
         }
     }
 
+    /// Logs a failed chunk or entity write and reports it through [`Level::save_failures`].
+    pub(crate) fn report_save_failure(&self, message: String) {
+        log::error!("{message}");
+        // Both ends live as long as the level, so this can't fail
+        let _ = self.save_failure_tx.send(message);
+    }
+
     pub fn try_get_chunk(&self, coordinates: &Vector2<i32>) -> Option<Arc<ChunkData>> {
         self.loaded_chunks
             .get(coordinates)
@@ -9,14 +9,21 @@ pub mod chunk;
 pub mod chunk_system;
 pub mod cylindrical_chunk_iterator;
 pub mod data;
+pub mod datapack;
 pub mod dimension;
 pub mod generation;
 pub mod inventory;
 pub mod item;
 pub mod level;
+pub mod light;
 pub mod lock;
+pub mod map;
 pub mod poi;
+pub mod recompress;
+pub mod schematic;
+pub mod template;
 pub mod tick;
+pub mod verify;
 pub mod world;
 pub mod world_info;
 
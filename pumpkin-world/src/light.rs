@@ -0,0 +1,487 @@
+//! Spreads sky and block light through the loaded chunks of a level.
+//!
+//! Light is spread breadth first, like the game does it: a change first removes all light that
+//! may have come through the changed blocks, then spreads light back in from the edges of the
+//! darkened area and from the light sources in it. Only loaded chunks are touched, light reaching
+//! an unloaded chunk stops at its edge until that chunk is relit.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::atomic::Ordering;
+
+use dashmap::DashMap;
+use pumpkin_data::{Block, BlockDirection, BlockState};
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector2::Vector2;
+use rustc_hash::FxHashMap;
+
+use crate::chunk::format::LightContainer;
+use crate::chunk::io::Dirtiable;
+use crate::chunk::palette::BlockPalette;
+use crate::chunk::{ChunkData, ChunkLight};
+use crate::level::SyncChunk;
+
+/// The light sections that changed per chunk, by index in the chunk.
+pub type ChangedSections = FxHashMap<Vector2<i32>, BTreeSet<usize>>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LightType {
+    Sky,
+    Block,
+}
+
+impl LightType {
+    /// The light `level` leaves in the neighbour in `direction`, which has the given opacity.
+    /// Full sky light goes straight down through transparent blocks without getting weaker.
+    fn spread(self, level: u8, direction: BlockDirection, opacity: u8) -> u8 {
+        if self == Self::Sky && level == 15 && opacity == 0 && direction == BlockDirection::Down {
+            15
+        } else {
+            level.saturating_sub(opacity.max(1))
+        }
+    }
+
+    /// The light a block gives off by itself. The sky is lit from above the world instead.
+    const fn emission(self, state: &BlockState) -> u8 {
+        match self {
+            Self::Sky => 0,
+            Self::Block => state.luminance,
+        }
+    }
+
+    const fn containers(self, light: &ChunkLight) -> &[LightContainer] {
+        match self {
+            Self::Sky => &light.sky_light,
+            Self::Block => &light.block_light,
+        }
+    }
+
+    const fn containers_mut(self, light: &mut ChunkLight) -> &mut [LightContainer] {
+        match self {
+            Self::Sky => &mut light.sky_light,
+            Self::Block => &mut light.block_light,
+        }
+    }
+}
+
+/// Updates the light of the loaded chunks of a level. Light is only written, changes are
+/// collected with [`LightPropagator::finish`] to be sent to the players.
+///
+/// Meant to run off the tick, as relighting whole chunks takes a while.
+pub struct LightPropagator<'a> {
+    chunks: &'a DashMap<Vector2<i32>, SyncChunk>,
+    has_skylight: bool,
+    /// The chunks looked up so far, including those that aren't loaded.
+    cache: FxHashMap<Vector2<i32>, Option<SyncChunk>>,
+    changed: ChangedSections,
+}
+
+impl<'a> LightPropagator<'a> {
+    #[must_use]
+    pub fn new(chunks: &'a DashMap<Vector2<i32>, SyncChunk>, has_skylight: bool) -> Self {
+        Self {
+            chunks,
+            has_skylight,
+            cache: FxHashMap::default(),
+            changed: ChangedSections::default(),
+        }
+    }
+
+    /// The light sections that were written, per chunk.
+    #[must_use]
+    pub fn finish(self) -> ChangedSections {
+        self.changed
+    }
+
+    const fn light_types(&self) -> &'static [LightType] {
+        if self.has_skylight {
+            &[LightType::Sky, LightType::Block]
+        } else {
+            &[LightType::Block]
+        }
+    }
+
+    /// Updates the light around blocks whose opacity or luminance changed.
+    pub fn update_blocks(&mut self, positions: &[BlockPos]) {
+        for &light_type in self.light_types() {
+            let mut decrease = VecDeque::new();
+            let mut increase = VecDeque::new();
+            for &pos in positions {
+                let Some(old) = self.light(light_type, pos) else {
+                    continue;
+                };
+                if old > 0 && self.set_light(light_type, pos, 0) {
+                    decrease.push_back((pos, old));
+                }
+                let emission = light_type.emission(self.state(pos));
+                if emission > 0 && self.set_light(light_type, pos, emission) {
+                    increase.push_back((pos, emission));
+                }
+                // The block may let light through now
+                for direction in BlockDirection::all() {
+                    let neighbor = pos.offset(direction.to_offset());
+                    if let Some(level) = self.light(light_type, neighbor)
+                        && level > 0
+                    {
+                        increase.push_back((neighbor, level));
+                    }
+                }
+            }
+            self.spread(light_type, decrease, increase);
+        }
+    }
+
+    /// Recomputes all light of the given chunks and marks them as correctly lit. Chunks that
+    /// aren't loaded are skipped.
+    pub fn relight_chunks(&mut self, positions: &[Vector2<i32>]) {
+        let chunks: Vec<_> = positions
+            .iter()
+            .filter_map(|pos| self.chunk(*pos))
+            .collect();
+        for &light_type in self.light_types() {
+            // Light that left the chunks through their edges is removed from the neighbours too
+            let mut decrease = VecDeque::new();
+            for chunk in &chunks {
+                for pos in edge(chunk, 0) {
+                    if let Some(level) = self.light(light_type, pos)
+                        && level > 0
+                    {
+                        decrease.push_back((pos, level));
+                    }
+                }
+            }
+            for chunk in &chunks {
+                self.clear(light_type, chunk);
+            }
+            self.spread(light_type, decrease, VecDeque::new());
+
+            let mut increase = VecDeque::new();
+            for chunk in &chunks {
+                self.push_sources(light_type, chunk, &mut increase);
+            }
+            self.spread(light_type, VecDeque::new(), increase);
+        }
+        for chunk in &chunks {
+            chunk.light_correct.store(true, Ordering::Relaxed);
+            chunk.mark_dirty(true);
+        }
+    }
+
+    /// Removes the light in `decrease`, then spreads the light in `increase` and the light
+    /// around the darkened area.
+    fn spread(
+        &mut self,
+        light_type: LightType,
+        mut decrease: VecDeque<(BlockPos, u8)>,
+        mut increase: VecDeque<(BlockPos, u8)>,
+    ) {
+        while let Some((pos, level)) = decrease.pop_front() {
+            for direction in BlockDirection::all() {
+                let neighbor = pos.offset(direction.to_offset());
+                let Some(current) = self.light(light_type, neighbor) else {
+                    continue;
+                };
+                if current == 0 {
+                    continue;
+                }
+                let state = self.state(neighbor);
+                if current <= light_type.spread(level, direction, state.opacity)
+                    && self.set_light(light_type, neighbor, 0)
+                {
+                    decrease.push_back((neighbor, current));
+                    let emission = light_type.emission(state);
+                    if emission > 0 && self.set_light(light_type, neighbor, emission) {
+                        increase.push_back((neighbor, emission));
+                    }
+                } else {
+                    // Lit from somewhere else, so it spreads back into the darkened area
+                    increase.push_back((neighbor, current));
+                }
+            }
+        }
+
+        while let Some((pos, level)) = increase.pop_front() {
+            // Darkened or brightened since it was queued
+            if self.light(light_type, pos) != Some(level) {
+                continue;
+            }
+            for direction in BlockDirection::all() {
+                let neighbor = pos.offset(direction.to_offset());
+                let Some(current) = self.light(light_type, neighbor) else {
+                    continue;
+                };
+                let spread = light_type.spread(level, direction, self.state(neighbor).opacity);
+                if spread > current && self.set_light(light_type, neighbor, spread) && spread > 1 {
+                    increase.push_back((neighbor, spread));
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self, light_type: LightType, chunk: &ChunkData) {
+        let mut light = chunk.light_engine.write().unwrap();
+        let containers = light_type.containers_mut(&mut light);
+        containers.fill(LightContainer::new_empty(0));
+        let sections = self
+            .changed
+            .entry(Vector2::new(chunk.x, chunk.z))
+            .or_default();
+        sections.extend(0..containers.len());
+    }
+
+    /// Queues the light sources of a cleared chunk, and the light of its neighbours next to it.
+    fn push_sources(
+        &mut self,
+        light_type: LightType,
+        chunk: &ChunkData,
+        increase: &mut VecDeque<(BlockPos, u8)>,
+    ) {
+        let (start_x, start_z) = (chunk.x * 16, chunk.z * 16);
+        match light_type {
+            LightType::Sky => {
+                // Just above the world, where the sky light is always full
+                let top = chunk.section.min_y + (chunk.section.count * BlockPalette::SIZE) as i32;
+                for x in 0..16 {
+                    for z in 0..16 {
+                        increase.push_back((BlockPos::new(start_x + x, top, start_z + z), 15));
+                    }
+                }
+            }
+            LightType::Block => {
+                let sections = chunk.section.block_sections.read().unwrap().clone();
+                for (index, section) in sections.iter().enumerate() {
+                    if !section.contains_any(|id| BlockState::from_id(id).luminance > 0) {
+                        continue;
+                    }
+                    let base_y = chunk.section.min_y + (index * BlockPalette::SIZE) as i32;
+                    for y in 0..BlockPalette::SIZE {
+                        for z in 0..16 {
+                            for x in 0..16 {
+                                let luminance = BlockState::from_id(section.get(x, y, z)).luminance;
+                                let pos = BlockPos::new(
+                                    start_x + x as i32,
+                                    base_y + y as i32,
+                                    start_z + z as i32,
+                                );
+                                if luminance > 0 && self.set_light(light_type, pos, luminance) {
+                                    increase.push_back((pos, luminance));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        for pos in edge(chunk, 1) {
+            if let Some(level) = self.light(light_type, pos)
+                && level > 0
+            {
+                increase.push_back((pos, level));
+            }
+        }
+    }
+
+    fn chunk(&mut self, pos: Vector2<i32>) -> Option<SyncChunk> {
+        let chunks = self.chunks;
+        self.cache
+            .entry(pos)
+            .or_insert_with(|| chunks.get(&pos).map(|chunk| chunk.clone()))
+            .clone()
+    }
+
+    /// The light at `pos`, or `None` if its chunk isn't loaded or it is below the world. Above
+    /// the world the sky light is full.
+    fn light(&mut self, light_type: LightType, pos: BlockPos) -> Option<u8> {
+        let chunk = self.chunk(pos.chunk_position())?;
+        let y = usize::try_from(pos.0.y - chunk.section.min_y).ok()?;
+        let light = chunk.light_engine.read().unwrap();
+        match light_type.containers(&light).get(y / BlockPalette::SIZE) {
+            Some(container) => Some(container.get(
+                (pos.0.x & 15) as usize,
+                y % BlockPalette::SIZE,
+                (pos.0.z & 15) as usize,
+            )),
+            None if light_type == LightType::Sky => Some(15),
+            None => None,
+        }
+    }
+
+    /// Sets the light at `pos`. Returns whether it is stored, which it isn't outside of the world
+    /// or the loaded chunks.
+    fn set_light(&mut self, light_type: LightType, pos: BlockPos, level: u8) -> bool {
+        let Some(chunk) = self.chunk(pos.chunk_position()) else {
+            return false;
+        };
+        let Ok(y) = usize::try_from(pos.0.y - chunk.section.min_y) else {
+            return false;
+        };
+        let section = y / BlockPalette::SIZE;
+        let mut light = chunk.light_engine.write().unwrap();
+        let Some(container) = light_type.containers_mut(&mut light).get_mut(section) else {
+            return false;
+        };
+        container.set(
+            (pos.0.x & 15) as usize,
+            y % BlockPalette::SIZE,
+            (pos.0.z & 15) as usize,
+            level,
+        );
+        drop(light);
+        chunk.mark_dirty(true);
+        self.changed
+            .entry(Vector2::new(chunk.x, chunk.z))
+            .or_default()
+            .insert(section);
+        true
+    }
+
+    fn state(&mut self, pos: BlockPos) -> &'static BlockState {
+        self.chunk(pos.chunk_position())
+            .and_then(|chunk| {
+                let x = (pos.0.x & 15) as usize;
+                let z = (pos.0.z & 15) as usize;
+                chunk.section.get_block_absolute_y(x, pos.0.y, z)
+            })
+            .map_or(Block::AIR.default_state, BlockState::from_id)
+    }
+}
+
+/// The columns of blocks along the inside of the edge of `chunk` for an `offset` of 0, or along
+/// the outside for 1, from the bottom to the top of the world.
+fn edge(chunk: &ChunkData, offset: i32) -> impl Iterator<Item = BlockPos> + use<> {
+    let (start_x, start_z) = (chunk.x * 16, chunk.z * 16);
+    let min_y = chunk.section.min_y;
+    let max_y = min_y + (chunk.section.count * BlockPalette::SIZE) as i32;
+    let (low, high) = (-offset, 15 + offset);
+    (0..16).flat_map(move |i| {
+        [(low, i), (high, i), (i, low), (i, high)]
+            .into_iter()
+            .flat_map(move |(x, z)| {
+                (min_y..max_y).map(move |y| BlockPos::new(start_x + x, y, start_z + z))
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicI64};
+    use std::sync::{Arc, Mutex, RwLock};
+
+    use pumpkin_data::chunk::ChunkStatus;
+
+    use super::*;
+    use crate::chunk::ChunkSections;
+
+    const SECTIONS: usize = 4;
+    const MIN_Y: i32 = -16;
+
+    fn chunk(x: i32, z: i32) -> SyncChunk {
+        Arc::new(ChunkData {
+            section: ChunkSections::new(SECTIONS, MIN_Y),
+            heightmap: Mutex::default(),
+            x,
+            z,
+            block_ticks: Default::default(),
+            fluid_ticks: Default::default(),
+            block_entities: Mutex::default(),
+            light_engine: RwLock::new(ChunkLight {
+                sky_light: (0..SECTIONS)
+                    .map(|_| LightContainer::new_empty(0))
+                    .collect(),
+                block_light: (0..SECTIONS)
+                    .map(|_| LightContainer::new_empty(0))
+                    .collect(),
+            }),
+            light_correct: AtomicBool::new(false),
+            status: ChunkStatus::Full,
+            inhabited_time: AtomicI64::new(0),
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    fn set_block(chunks: &DashMap<Vector2<i32>, SyncChunk>, pos: BlockPos, block: &Block) {
+        let chunk = chunks.get(&pos.chunk_position()).unwrap();
+        let y = (pos.0.y - MIN_Y) as usize;
+        let mut sections = chunk.section.block_sections.write().unwrap();
+        Arc::make_mut(&mut sections[y / 16]).set(
+            (pos.0.x & 15) as usize,
+            y % 16,
+            (pos.0.z & 15) as usize,
+            block.default_state.id,
+        );
+    }
+
+    fn light(
+        chunks: &DashMap<Vector2<i32>, SyncChunk>,
+        light_type: LightType,
+        pos: [i32; 3],
+    ) -> u8 {
+        let [x, y, z] = pos;
+        LightPropagator::new(chunks, true)
+            .light(light_type, BlockPos::new(x, y, z))
+            .unwrap()
+    }
+
+    fn loaded_chunks() -> DashMap<Vector2<i32>, SyncChunk> {
+        let chunks = DashMap::new();
+        for x in -1..=1 {
+            for z in -1..=1 {
+                chunks.insert(Vector2::new(x, z), chunk(x, z));
+            }
+        }
+        chunks
+    }
+
+    #[test]
+    fn block_light_spreads_across_chunks_and_goes_out() {
+        let chunks = loaded_chunks();
+        let glowstone = BlockPos::new(15, 10, 3);
+        set_block(&chunks, glowstone, &Block::GLOWSTONE);
+        let mut propagator = LightPropagator::new(&chunks, true);
+        propagator.update_blocks(&[glowstone]);
+        let changed = propagator.finish();
+
+        assert_eq!(light(&chunks, LightType::Block, [15, 10, 3]), 15);
+        assert_eq!(light(&chunks, LightType::Block, [16, 10, 3]), 14);
+        assert_eq!(light(&chunks, LightType::Block, [20, 12, 3]), 8);
+        assert!(changed[&Vector2::new(1, 0)].contains(&1));
+
+        set_block(&chunks, glowstone, &Block::AIR);
+        LightPropagator::new(&chunks, true).update_blocks(&[glowstone]);
+        assert_eq!(light(&chunks, LightType::Block, [15, 10, 3]), 0);
+        assert_eq!(light(&chunks, LightType::Block, [20, 12, 3]), 0);
+    }
+
+    #[test]
+    fn sky_light_is_blocked_by_a_roof() {
+        let chunks = loaded_chunks();
+        let center = Vector2::new(0, 0);
+        LightPropagator::new(&chunks, true).relight_chunks(&[center]);
+        assert_eq!(light(&chunks, LightType::Sky, [4, MIN_Y, 4]), 15);
+        assert!(
+            chunks
+                .get(&center)
+                .unwrap()
+                .light_correct
+                .load(Ordering::Relaxed)
+        );
+
+        let mut roof = Vec::new();
+        for x in 0..16 {
+            for z in 0..16 {
+                let pos = BlockPos::new(x, 20, z);
+                set_block(&chunks, pos, &Block::STONE);
+                roof.push(pos);
+            }
+        }
+        LightPropagator::new(&chunks, true).update_blocks(&roof);
+        assert_eq!(light(&chunks, LightType::Sky, [4, 21, 4]), 15);
+        assert_eq!(light(&chunks, LightType::Sky, [4, 20, 4]), 0);
+        assert!(light(&chunks, LightType::Sky, [4, 19, 4]) < 15);
+
+        // Under the roof, the sky light comes in from the side
+        LightPropagator::new(&chunks, true).relight_chunks(&[Vector2::new(-1, 0), center]);
+        assert_eq!(light(&chunks, LightType::Sky, [-1, 19, 4]), 15);
+        assert_eq!(light(&chunks, LightType::Sky, [0, 19, 4]), 14);
+        assert_eq!(light(&chunks, LightType::Sky, [3, 10, 4]), 11);
+    }
+}
@@ -0,0 +1,271 @@
+//! The colors a block can have on a map.
+//!
+//! A map pixel stores a color id and a brightness packed as `id * 4 + brightness`. The client
+//! turns that into the actual RGB value.
+
+use pumpkin_data::Block;
+
+/// The dye colors in the order of their ids, which is also the order of the colored map colors.
+pub const DYE_NAMES: [&str; 16] = [
+    "white",
+    "orange",
+    "magenta",
+    "light_blue",
+    "yellow",
+    "lime",
+    "pink",
+    "gray",
+    "light_gray",
+    "cyan",
+    "purple",
+    "blue",
+    "brown",
+    "green",
+    "red",
+    "black",
+];
+
+/// Splits a block name like `light_blue_wool` into the dye id and the rest of the name.
+#[must_use]
+pub fn split_dye(name: &str) -> Option<(u8, &str)> {
+    DYE_NAMES.iter().enumerate().find_map(|(id, dye)| {
+        name.strip_prefix(dye)
+            .and_then(|rest| rest.strip_prefix('_'))
+            .map(|rest| (id as u8, rest))
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MapBrightness {
+    Low = 0,
+    Normal = 1,
+    High = 2,
+    Lowest = 3,
+}
+
+/// A color id of the `MapColor` table of the client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MapColor(pub u8);
+
+impl MapColor {
+    pub const NONE: Self = Self(0);
+    pub const GRASS: Self = Self(1);
+    pub const SAND: Self = Self(2);
+    pub const WOOL: Self = Self(3);
+    pub const FIRE: Self = Self(4);
+    pub const ICE: Self = Self(5);
+    pub const METAL: Self = Self(6);
+    pub const PLANT: Self = Self(7);
+    pub const WHITE: Self = Self(8);
+    pub const CLAY: Self = Self(9);
+    pub const DIRT: Self = Self(10);
+    pub const STONE: Self = Self(11);
+    pub const WATER: Self = Self(12);
+    pub const OAK: Self = Self(13);
+    pub const QUARTZ: Self = Self(14);
+    pub const ORANGE: Self = Self(15);
+    pub const YELLOW: Self = Self(18);
+    pub const BROWN: Self = Self(26);
+    pub const GREEN: Self = Self(27);
+    pub const RED: Self = Self(28);
+    pub const BLACK: Self = Self(29);
+    pub const GOLD: Self = Self(30);
+    pub const DIAMOND_BLUE: Self = Self(31);
+    pub const LAPIS_BLUE: Self = Self(32);
+    pub const EMERALD_GREEN: Self = Self(33);
+    pub const SPRUCE_BROWN: Self = Self(34);
+    pub const DARK_RED: Self = Self(35);
+    pub const TERRACOTTA_WHITE: Self = Self(36);
+    pub const DULL_RED: Self = Self(52);
+    pub const DULL_PINK: Self = Self(53);
+    pub const DARK_CRIMSON: Self = Self(54);
+    pub const TEAL: Self = Self(55);
+    pub const DARK_AQUA: Self = Self(56);
+    pub const BRIGHT_TEAL: Self = Self(58);
+    pub const DEEPSLATE_GRAY: Self = Self(59);
+    pub const RAW_IRON_PINK: Self = Self(60);
+    pub const LICHEN_GREEN: Self = Self(61);
+
+    /// The color of blocks dyed with the dye of id `dye`. White maps to the color of snow.
+    #[must_use]
+    pub const fn from_dye(dye: u8) -> Self {
+        if dye == 0 { Self::WHITE } else { Self(14 + dye) }
+    }
+
+    /// The color of terracotta dyed with the dye of id `dye`.
+    #[must_use]
+    pub const fn from_terracotta(dye: u8) -> Self {
+        Self(Self::TERRACOTTA_WHITE.0 + dye)
+    }
+
+    #[must_use]
+    pub const fn packed(self, brightness: MapBrightness) -> u8 {
+        self.0 * 4 + brightness as u8
+    }
+
+    /// Returns the map color of `block`.
+    // TODO: The map colors are not part of the extracted block data yet, so they are guessed from
+    // the block names. This covers the common terrain but not every block.
+    #[must_use]
+    pub fn of_block(block: &Block) -> Self {
+        Self::of_block_name(block.name)
+    }
+
+    fn of_block_name(name: &str) -> Self {
+        if let Some((dye, rest)) = split_dye(name) {
+            return match rest {
+                "terracotta" => Self::from_terracotta(dye),
+                "stained_glass" | "stained_glass_pane" => Self::NONE,
+                _ => Self::from_dye(dye),
+            };
+        }
+
+        match name {
+            "air" | "cave_air" | "void_air" | "glass" | "glass_pane" | "tinted_glass"
+            | "barrier" | "light" | "structure_void" | "torch" | "wall_torch"
+            | "redstone_wire" | "tripwire" | "rail" | "powered_rail" | "detector_rail"
+            | "activator_rail" | "lever" | "ladder" | "end_rod" => Self::NONE,
+            "grass_block" | "slime_block" => Self::GRASS,
+            "sand" | "sandstone" | "cut_sandstone" | "smooth_sandstone" | "chiseled_sandstone"
+            | "end_stone" | "end_stone_bricks" | "birch_planks" | "birch_log" | "glowstone"
+            | "bone_block" | "sandstone_stairs" | "sandstone_slab" | "sandstone_wall" => {
+                Self::SAND
+            }
+            "cobweb" | "mushroom_stem" => Self::WOOL,
+            "lava" | "tnt" | "fire" | "redstone_block" => Self::FIRE,
+            "ice" | "packed_ice" | "blue_ice" | "frosted_ice" => Self::ICE,
+            "iron_block" | "iron_door" | "iron_trapdoor" | "iron_bars" | "anvil"
+            | "chipped_anvil" | "damaged_anvil" | "brewing_stand" | "lantern" | "heavy_core" => {
+                Self::METAL
+            }
+            "snow" | "snow_block" | "powder_snow" => Self::WHITE,
+            "clay" | "infested_stone" => Self::CLAY,
+            "dirt" | "coarse_dirt" | "farmland" | "dirt_path" | "rooted_dirt" | "jungle_planks"
+            | "jungle_log" | "brown_mushroom_block" | "granite" | "polished_granite" => Self::DIRT,
+            "water" | "bubble_column" | "kelp" | "kelp_plant" | "seagrass" | "tall_seagrass" => {
+                Self::WATER
+            }
+            "quartz_block" | "smooth_quartz" | "quartz_pillar" | "chiseled_quartz_block"
+            | "quartz_bricks" | "diorite" | "polished_diorite" | "sea_lantern" | "target" => {
+                Self::QUARTZ
+            }
+            "red_sand" | "red_sandstone" | "cut_red_sandstone" | "smooth_red_sandstone"
+            | "acacia_planks" | "acacia_log" | "pumpkin" | "carved_pumpkin" | "jack_o_lantern"
+            | "terracotta" | "honey_block" | "honeycomb_block" | "copper_block" => Self::ORANGE,
+            "hay_block" | "sponge" | "wet_sponge" | "bamboo_block" | "bamboo_planks"
+            | "bee_nest" => Self::YELLOW,
+            "dark_oak_planks" | "dark_oak_log" | "soul_sand" | "soul_soil" | "mud" => Self::BROWN,
+            "moss_block" | "moss_carpet" | "dried_kelp_block" | "cactus" | "sea_pickle" => {
+                Self::GREEN
+            }
+            "mangrove_planks" | "mangrove_log" | "red_mushroom_block" | "bricks" | "nether_wart"
+            | "enchanting_table" => Self::RED,
+            "obsidian" | "crying_obsidian" | "coal_block" | "basalt" | "polished_basalt"
+            | "blackstone" | "end_gateway" | "end_portal" | "dragon_egg" => Self::BLACK,
+            "gold_block" | "raw_gold_block" | "bell" => Self::GOLD,
+            "diamond_block" | "prismarine_bricks" | "dark_prismarine" | "beacon" | "conduit" => {
+                Self::DIAMOND_BLUE
+            }
+            "lapis_block" => Self::LAPIS_BLUE,
+            "emerald_block" => Self::EMERALD_GREEN,
+            "podzol" | "spruce_planks" | "spruce_log" | "oak_log" => Self::SPRUCE_BROWN,
+            "netherrack" | "nether_bricks" | "nether_quartz_ore" | "nether_gold_ore"
+            | "magma_block" | "nether_wart_block" => Self::DARK_RED,
+            "cherry_planks" | "cherry_log" | "calcite" => Self::TERRACOTTA_WHITE,
+            "crimson_nylium" => Self::DULL_RED,
+            "crimson_planks" | "crimson_stem" => Self::DULL_PINK,
+            "crimson_hyphae" => Self::DARK_CRIMSON,
+            "warped_nylium" => Self::TEAL,
+            "warped_planks" | "warped_stem" => Self::DARK_AQUA,
+            "warped_wart_block" => Self::BRIGHT_TEAL,
+            "raw_iron_block" => Self::RAW_IRON_PINK,
+            "glow_lichen" | "verdant_froglight" => Self::LICHEN_GREEN,
+            "mycelium" | "amethyst_block" | "budding_amethyst" | "shulker_box" => Self(24),
+            _ => Self::of_block_suffix(name),
+        }
+    }
+
+    fn of_block_suffix(name: &str) -> Self {
+        if name.ends_with("_leaves")
+            || name.ends_with("_sapling")
+            || name.ends_with("_grass")
+            || name.ends_with("_fern")
+            || name.ends_with("_tulip")
+            || name.ends_with("vine")
+            || name.ends_with("vines")
+            || matches!(
+                name,
+                "fern"
+                    | "dandelion"
+                    | "poppy"
+                    | "blue_orchid"
+                    | "allium"
+                    | "azure_bluet"
+                    | "oxeye_daisy"
+                    | "cornflower"
+                    | "lily_of_the_valley"
+                    | "sunflower"
+                    | "lilac"
+                    | "rose_bush"
+                    | "peony"
+                    | "wheat"
+                    | "carrots"
+                    | "potatoes"
+                    | "beetroots"
+                    | "sugar_cane"
+                    | "lily_pad"
+                    | "melon"
+                    | "bamboo"
+                    | "sweet_berry_bush"
+                    | "azalea"
+                    | "flowering_azalea"
+                    | "dead_bush"
+            )
+        {
+            return Self::PLANT;
+        }
+        if name.starts_with("oak_") || name.ends_with("_wood") || name.contains("bookshelf") {
+            return Self::OAK;
+        }
+        if name.contains("deepslate") || name.contains("tuff") || name.contains("sculk") {
+            return Self::DEEPSLATE_GRAY;
+        }
+        if name.contains("prismarine") {
+            return Self::DIAMOND_BLUE;
+        }
+        if name.contains("copper") {
+            return Self::ORANGE;
+        }
+        if name.contains("sandstone") {
+            return Self::SAND;
+        }
+        if name.contains("quartz") {
+            return Self::QUARTZ;
+        }
+        if name.contains("nether_brick") {
+            return Self::DARK_RED;
+        }
+        if name.contains("blackstone") {
+            return Self::BLACK;
+        }
+        Self::STONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dyed_blocks_use_the_colors_of_their_dye() {
+        assert_eq!(split_dye("light_blue_wool"), Some((3, "wool")));
+        assert_eq!(split_dye("blue_wool"), Some((11, "wool")));
+        assert_eq!(split_dye("stone"), None);
+        assert_eq!(MapColor::of_block_name("white_wool"), MapColor::WHITE);
+        assert_eq!(MapColor::of_block_name("black_concrete"), MapColor::BLACK);
+        assert_eq!(MapColor::of_block_name("red_terracotta"), MapColor(50));
+        assert_eq!(MapColor::of_block_name("oak_leaves"), MapColor::PLANT);
+        assert_eq!(MapColor::WATER.packed(MapBrightness::High), 50);
+    }
+}
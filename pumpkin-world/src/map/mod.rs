@@ -0,0 +1,653 @@
+//! The state of filled maps: the pixels, the scale, where the map is centered and the markers
+//! drawn on it.
+//!
+//! Each map is stored in `data/map_<id>.dat`. The players that have a map in their inventory are
+//! its trackers; every tracker keeps its own rectangle of changed pixels so that players only
+//! receive what they haven't seen yet.
+
+use std::collections::BTreeMap;
+
+use pumpkin_nbt::{compound::NbtCompound, tag::NbtTag};
+use pumpkin_util::math::position::BlockPos;
+use uuid::Uuid;
+
+pub mod color;
+pub mod render;
+
+/// Width and height of a map in pixels.
+pub const MAP_SIZE: usize = 128;
+/// The most a map can be zoomed out, 16 blocks per pixel.
+pub const MAX_SCALE: u8 = 4;
+/// How many ticks a tracker is kept after the player last had the map.
+const TRACKER_TIMEOUT: u64 = 20;
+
+/// Ids of the `map_decoration_type` registry.
+pub mod decoration_type {
+    pub const PLAYER: i32 = 0;
+    pub const FRAME: i32 = 1;
+    pub const PLAYER_OFF_MAP: i32 = 6;
+    pub const PLAYER_OFF_LIMITS: i32 = 7;
+    /// The banner markers follow in the order of the dye colors.
+    pub const WHITE_BANNER: i32 = 10;
+}
+
+/// A marker drawn on a map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapDecoration {
+    pub kind: i32,
+    pub x: i8,
+    pub z: i8,
+    pub rotation: i8,
+    pub name: Option<String>,
+}
+
+/// A banner that was marked on a map by using the map on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapBanner {
+    pub pos: BlockPos,
+    /// The dye id of the banner.
+    pub color: u8,
+    pub name: Option<String>,
+}
+
+impl MapBanner {
+    fn key(&self) -> String {
+        format!("banner-{},{},{}", self.pos.0.x, self.pos.0.y, self.pos.0.z)
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+        nbt.put(
+            "pos",
+            NbtTag::IntArray(vec![self.pos.0.x, self.pos.0.y, self.pos.0.z]),
+        );
+        nbt.put_string("color", color::DYE_NAMES[self.color as usize].to_string());
+        if let Some(name) = &self.name {
+            nbt.put_string("name", name.clone());
+        }
+        nbt
+    }
+
+    fn from_nbt(nbt: &NbtCompound) -> Option<Self> {
+        let pos = nbt.get_int_array("pos")?;
+        let color = nbt.get_string("color")?;
+        Some(Self {
+            pos: BlockPos::new(*pos.first()?, *pos.get(1)?, *pos.get(2)?),
+            color: color::DYE_NAMES.iter().position(|dye| *dye == color)? as u8,
+            name: nbt.get_string("name").map(str::to_string),
+        })
+    }
+}
+
+/// An item frame that shows the map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapFrame {
+    pub pos: BlockPos,
+    /// The yaw of the frame in degrees.
+    pub rotation: i32,
+    pub entity_id: i32,
+}
+
+impl MapFrame {
+    fn key(&self) -> String {
+        format!("frame-{}", self.entity_id)
+    }
+
+    fn to_nbt(&self) -> NbtCompound {
+        let mut nbt = NbtCompound::new();
+        nbt.put(
+            "pos",
+            NbtTag::IntArray(vec![self.pos.0.x, self.pos.0.y, self.pos.0.z]),
+        );
+        nbt.put_int("rotation", self.rotation);
+        nbt.put_int("entity_id", self.entity_id);
+        nbt
+    }
+
+    fn from_nbt(nbt: &NbtCompound) -> Option<Self> {
+        let pos = nbt.get_int_array("pos")?;
+        Some(Self {
+            pos: BlockPos::new(*pos.first()?, *pos.get(1)?, *pos.get(2)?),
+            rotation: nbt.get_int("rotation")?,
+            entity_id: nbt.get_int("entity_id")?,
+        })
+    }
+}
+
+/// A rectangle of map pixels, as sent to the client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapPatch {
+    pub start_x: u8,
+    pub start_z: u8,
+    pub width: u8,
+    pub height: u8,
+    pub colors: Vec<u8>,
+}
+
+/// What a tracker hasn't seen of a map yet.
+#[derive(Default)]
+pub struct MapUpdate {
+    pub decorations: Option<Vec<MapDecoration>>,
+    pub patch: Option<MapPatch>,
+}
+
+struct MapTracker {
+    uuid: Uuid,
+    /// The changed pixels as `(min_x, min_z, max_x, max_z)`.
+    dirty: Option<(u8, u8, u8, u8)>,
+    decorations_dirty: bool,
+    last_seen: u64,
+    /// Counts the ticks the map was rendered for this tracker, which decides the column that is
+    /// rendered next.
+    step: u32,
+}
+
+impl MapTracker {
+    const fn new(uuid: Uuid, tick: u64) -> Self {
+        Self {
+            uuid,
+            dirty: Some((0, 0, MAP_SIZE as u8 - 1, MAP_SIZE as u8 - 1)),
+            decorations_dirty: true,
+            last_seen: tick,
+            step: 0,
+        }
+    }
+
+    fn mark_dirty(&mut self, x: u8, z: u8) {
+        self.dirty = Some(match self.dirty {
+            Some((min_x, min_z, max_x, max_z)) => {
+                (min_x.min(x), min_z.min(z), max_x.max(x), max_z.max(z))
+            }
+            None => (x, z, x, z),
+        });
+    }
+}
+
+pub struct MapState {
+    pub center_x: i32,
+    pub center_z: i32,
+    /// The dimension the map shows, like `minecraft:overworld`.
+    pub dimension: String,
+    pub scale: u8,
+    /// Whether the map shows the players that have it.
+    pub tracking_position: bool,
+    /// Whether the players are still shown when they are far away from the map.
+    pub unlimited_tracking: bool,
+    /// Locked maps are never rendered again.
+    pub locked: bool,
+    colors: Box<[u8]>,
+    banners: Vec<MapBanner>,
+    frames: Vec<MapFrame>,
+    decorations: BTreeMap<String, MapDecoration>,
+    trackers: Vec<MapTracker>,
+    /// Whether the map changed since it was last saved.
+    dirty: bool,
+}
+
+impl MapState {
+    /// Creates an empty map of `scale` that covers the position `x`, `z`. Maps are aligned to a
+    /// grid, so the center of the map is not exactly at the position.
+    #[must_use]
+    pub fn new(
+        x: f64,
+        z: f64,
+        scale: u8,
+        tracking_position: bool,
+        unlimited_tracking: bool,
+        dimension: String,
+    ) -> Self {
+        let size = (MAP_SIZE as i32) << scale;
+        let align = |coordinate: f64| {
+            let cell = ((coordinate + 64.0) / f64::from(size)).floor() as i32;
+            cell * size + size / 2 - 64
+        };
+        Self {
+            center_x: align(x),
+            center_z: align(z),
+            dimension,
+            scale,
+            tracking_position,
+            unlimited_tracking,
+            locked: false,
+            colors: vec![0; MAP_SIZE * MAP_SIZE].into_boxed_slice(),
+            banners: Vec::new(),
+            frames: Vec::new(),
+            decorations: BTreeMap::new(),
+            trackers: Vec::new(),
+            dirty: true,
+        }
+    }
+
+    /// Returns an empty map that shows the area of this map zoomed out by one level.
+    #[must_use]
+    pub fn scaled(&self) -> Self {
+        Self::new(
+            f64::from(self.center_x),
+            f64::from(self.center_z),
+            (self.scale + 1).min(MAX_SCALE),
+            self.tracking_position,
+            self.unlimited_tracking,
+            self.dimension.clone(),
+        )
+    }
+
+    /// Returns a locked copy of this map with the same pixels and banners.
+    #[must_use]
+    pub fn locked_copy(&self) -> Self {
+        let mut copy = Self::new(
+            f64::from(self.center_x),
+            f64::from(self.center_z),
+            self.scale,
+            self.tracking_position,
+            self.unlimited_tracking,
+            self.dimension.clone(),
+        );
+        copy.locked = true;
+        copy.colors.clone_from(&self.colors);
+        for banner in &self.banners {
+            copy.add_banner(banner.clone());
+        }
+        copy
+    }
+
+    #[must_use]
+    pub fn get_color(&self, x: usize, z: usize) -> u8 {
+        self.colors[z * MAP_SIZE + x]
+    }
+
+    /// Sets the pixel at `x`, `z` and returns whether it changed.
+    pub fn set_color(&mut self, x: usize, z: usize, color: u8) -> bool {
+        let pixel = &mut self.colors[z * MAP_SIZE + x];
+        if *pixel == color {
+            return false;
+        }
+        *pixel = color;
+        self.dirty = true;
+        for tracker in &mut self.trackers {
+            tracker.mark_dirty(x as u8, z as u8);
+        }
+        true
+    }
+
+    /// Converts a block position to map coordinates, where the map spans -64 to 64.
+    fn to_map_coordinates(&self, x: f64, z: f64) -> (f32, f32) {
+        let blocks_per_pixel = f64::from(1 << self.scale);
+        (
+            ((x - f64::from(self.center_x)) / blocks_per_pixel) as f32,
+            ((z - f64::from(self.center_z)) / blocks_per_pixel) as f32,
+        )
+    }
+
+    fn is_on_map(x: f32, z: f32) -> bool {
+        (-63.0..=63.0).contains(&x) && (-63.0..=63.0).contains(&z)
+    }
+
+    /// Adds or moves the decoration `key`. Players that are off the map are drawn at the edge
+    /// while they are close enough; other decorations are removed when they are off the map.
+    fn set_decoration(
+        &mut self,
+        key: String,
+        mut kind: i32,
+        x: f64,
+        z: f64,
+        yaw: f64,
+        name: Option<String>,
+    ) {
+        let (map_x, map_z) = self.to_map_coordinates(x, z);
+        let mut pixel_x = (map_x * 2.0 + 0.5) as i8;
+        let mut pixel_z = (map_z * 2.0 + 0.5) as i8;
+        let rotation;
+        if Self::is_on_map(map_x, map_z) {
+            let yaw = yaw + if yaw < 0.0 { -8.0 } else { 8.0 };
+            rotation = (yaw * 16.0 / 360.0) as i32 as i8;
+        } else {
+            if kind != decoration_type::PLAYER {
+                self.remove_decoration(&key);
+                return;
+            }
+            if map_x.abs() < 320.0 && map_z.abs() < 320.0 {
+                kind = decoration_type::PLAYER_OFF_MAP;
+            } else if self.unlimited_tracking {
+                kind = decoration_type::PLAYER_OFF_LIMITS;
+            } else {
+                self.remove_decoration(&key);
+                return;
+            }
+            rotation = 0;
+            if map_x <= -63.0 {
+                pixel_x = i8::MIN;
+            }
+            if map_z <= -63.0 {
+                pixel_z = i8::MIN;
+            }
+            if map_x >= 63.0 {
+                pixel_x = i8::MAX;
+            }
+            if map_z >= 63.0 {
+                pixel_z = i8::MAX;
+            }
+        }
+
+        let decoration = MapDecoration {
+            kind,
+            x: pixel_x,
+            z: pixel_z,
+            rotation,
+            name,
+        };
+        if self.decorations.get(&key) != Some(&decoration) {
+            self.decorations.insert(key, decoration);
+            self.mark_decorations_dirty();
+        }
+    }
+
+    fn remove_decoration(&mut self, key: &str) {
+        if self.decorations.remove(key).is_some() {
+            self.mark_decorations_dirty();
+        }
+    }
+
+    fn mark_decorations_dirty(&mut self) {
+        for tracker in &mut self.trackers {
+            tracker.decorations_dirty = true;
+        }
+    }
+
+    /// Marks `banner` on the map, or removes the marker if it is already there. Returns whether
+    /// the banner is on the map at all.
+    pub fn toggle_banner(&mut self, banner: MapBanner) -> bool {
+        let (map_x, map_z) = self.to_map_coordinates(
+            f64::from(banner.pos.0.x) + 0.5,
+            f64::from(banner.pos.0.z) + 0.5,
+        );
+        if !Self::is_on_map(map_x, map_z) {
+            return false;
+        }
+        if let Some(index) = self.banners.iter().position(|other| *other == banner) {
+            let removed = self.banners.remove(index);
+            self.remove_decoration(&removed.key());
+        } else {
+            self.banners.retain(|other| other.pos != banner.pos);
+            self.add_banner(banner);
+        }
+        self.dirty = true;
+        true
+    }
+
+    fn add_banner(&mut self, banner: MapBanner) {
+        self.set_decoration(
+            banner.key(),
+            decoration_type::WHITE_BANNER + i32::from(banner.color),
+            f64::from(banner.pos.0.x) + 0.5,
+            f64::from(banner.pos.0.z) + 0.5,
+            180.0,
+            banner.name.clone(),
+        );
+        self.banners.push(banner);
+    }
+
+    /// Removes the banners in the column `x`, `z` for which `is_banner` returns `false`.
+    pub fn remove_missing_banners(&mut self, x: i32, z: i32, is_banner: impl Fn(&MapBanner) -> bool) {
+        let missing: Vec<_> = self
+            .banners
+            .iter()
+            .filter(|banner| banner.pos.0.x == x && banner.pos.0.z == z && !is_banner(banner))
+            .map(MapBanner::key)
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        self.banners.retain(|banner| !missing.contains(&banner.key()));
+        for key in &missing {
+            self.remove_decoration(key);
+        }
+        self.dirty = true;
+    }
+
+    /// Adds or moves the marker of an item frame that shows this map.
+    pub fn set_frame(&mut self, frame: MapFrame) {
+        self.set_decoration(
+            frame.key(),
+            decoration_type::FRAME,
+            f64::from(frame.pos.0.x),
+            f64::from(frame.pos.0.z),
+            f64::from(frame.rotation),
+            None,
+        );
+        self.frames.retain(|other| other.entity_id != frame.entity_id);
+        self.frames.push(frame);
+        self.dirty = true;
+    }
+
+    pub fn remove_frame(&mut self, entity_id: i32) {
+        let before = self.frames.len();
+        self.frames.retain(|frame| frame.entity_id != entity_id);
+        if self.frames.len() != before {
+            self.remove_decoration(&format!("frame-{entity_id}"));
+            self.dirty = true;
+        }
+    }
+
+    /// Registers that the player `uuid` has the map in its inventory at `tick`. Returns the
+    /// render step of the player.
+    pub fn track(&mut self, uuid: Uuid, tick: u64) -> u32 {
+        self.trackers
+            .retain(|tracker| tracker.uuid == uuid || tracker.last_seen + TRACKER_TIMEOUT >= tick);
+        let removed: Vec<_> = self
+            .decorations
+            .keys()
+            .filter(|key| {
+                key.strip_prefix("player-").is_some_and(|player| {
+                    !self
+                        .trackers
+                        .iter()
+                        .any(|tracker| tracker.uuid.to_string() == player)
+                })
+            })
+            .cloned()
+            .collect();
+        for key in &removed {
+            self.remove_decoration(key);
+        }
+
+        if let Some(tracker) = self.trackers.iter_mut().find(|tracker| tracker.uuid == uuid) {
+            tracker.last_seen = tick;
+            tracker.step = tracker.step.wrapping_add(1);
+            tracker.step
+        } else {
+            self.trackers.push(MapTracker::new(uuid, tick));
+            0
+        }
+    }
+
+    /// Updates the marker of the player `uuid`, or removes it if the player is in another
+    /// dimension.
+    pub fn update_player(
+        &mut self,
+        uuid: Uuid,
+        name: &str,
+        x: f64,
+        z: f64,
+        yaw: f32,
+        same_dimension: bool,
+    ) {
+        let key = format!("player-{uuid}");
+        if self.tracking_position && same_dimension {
+            self.set_decoration(
+                key,
+                decoration_type::PLAYER,
+                x,
+                z,
+                f64::from(yaw),
+                Some(name.to_string()),
+            );
+        } else {
+            self.remove_decoration(&key);
+        }
+    }
+
+    /// Returns what the player `uuid` hasn't seen of the map yet and marks it as seen.
+    /// Decorations are resent at most every 5 steps.
+    pub fn take_update(&mut self, uuid: Uuid) -> Option<MapUpdate> {
+        let tracker = self
+            .trackers
+            .iter_mut()
+            .find(|tracker| tracker.uuid == uuid)?;
+
+        let mut update = MapUpdate::default();
+        if let Some((min_x, min_z, max_x, max_z)) = tracker.dirty.take() {
+            let width = max_x - min_x + 1;
+            let height = max_z - min_z + 1;
+            let mut colors = Vec::with_capacity(width as usize * height as usize);
+            for z in min_z..=max_z {
+                let row = z as usize * MAP_SIZE;
+                colors.extend_from_slice(&self.colors[row + min_x as usize..=row + max_x as usize]);
+            }
+            update.patch = Some(MapPatch {
+                start_x: min_x,
+                start_z: min_z,
+                width,
+                height,
+                colors,
+            });
+        }
+        if tracker.decorations_dirty && tracker.step % 5 == 0 {
+            tracker.decorations_dirty = false;
+            update.decorations = Some(self.decorations.values().cloned().collect());
+        }
+        Some(update)
+    }
+
+    /// Returns the whole map, for clients that see it for the first time without tracking it,
+    /// like players near an item frame.
+    #[must_use]
+    pub fn full_update(&self) -> MapUpdate {
+        MapUpdate {
+            decorations: Some(self.decorations.values().cloned().collect()),
+            patch: Some(MapPatch {
+                start_x: 0,
+                start_z: 0,
+                width: MAP_SIZE as u8,
+                height: MAP_SIZE as u8,
+                colors: self.colors.to_vec(),
+            }),
+        }
+    }
+
+    /// Whether the map changed since the last call to [`MapState::mark_saved`].
+    #[must_use]
+    pub const fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub const fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+
+    #[must_use]
+    pub fn to_nbt(&self) -> NbtCompound {
+        let mut data = NbtCompound::new();
+        data.put_string("dimension", self.dimension.clone());
+        data.put_int("xCenter", self.center_x);
+        data.put_int("zCenter", self.center_z);
+        data.put_byte("scale", self.scale as i8);
+        data.put_bool("trackingPosition", self.tracking_position);
+        data.put_bool("unlimitedTracking", self.unlimited_tracking);
+        data.put_bool("locked", self.locked);
+        data.put("colors", NbtTag::ByteArray(self.colors.clone()));
+        data.put_list(
+            "banners",
+            self.banners
+                .iter()
+                .map(|banner| NbtTag::Compound(banner.to_nbt()))
+                .collect(),
+        );
+        data.put_list(
+            "frames",
+            self.frames
+                .iter()
+                .map(|frame| NbtTag::Compound(frame.to_nbt()))
+                .collect(),
+        );
+        data
+    }
+
+    #[must_use]
+    pub fn from_nbt(data: &NbtCompound) -> Option<Self> {
+        let mut state = Self::new(
+            0.0,
+            0.0,
+            (data.get_byte("scale").unwrap_or(0) as u8).min(MAX_SCALE),
+            data.get_bool("trackingPosition").unwrap_or(true),
+            data.get_bool("unlimitedTracking").unwrap_or(false),
+            data.get_string("dimension")
+                .unwrap_or("minecraft:overworld")
+                .to_string(),
+        );
+        state.center_x = data.get_int("xCenter")?;
+        state.center_z = data.get_int("zCenter")?;
+        state.locked = data.get_bool("locked").unwrap_or(false);
+        if let Some(colors) = data.get("colors").and_then(NbtTag::extract_byte_array)
+            && colors.len() == MAP_SIZE * MAP_SIZE
+        {
+            state.colors = colors.into();
+        }
+        for banner in data.get_list("banners").unwrap_or_default() {
+            if let Some(banner) = banner.extract_compound().and_then(MapBanner::from_nbt) {
+                state.add_banner(banner);
+            }
+        }
+        for frame in data.get_list("frames").unwrap_or_default() {
+            if let Some(frame) = frame.extract_compound().and_then(MapFrame::from_nbt) {
+                state.set_frame(frame);
+            }
+        }
+        state.dirty = false;
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_are_aligned_to_their_scale() {
+        let map = MapState::new(10.0, -10.0, 0, true, false, String::new());
+        assert_eq!((map.center_x, map.center_z), (0, 0));
+        let map = MapState::new(70.0, 0.0, 0, true, false, String::new());
+        assert_eq!(map.center_x, 128);
+        let scaled = map.scaled();
+        assert_eq!((scaled.scale, scaled.center_x, scaled.center_z), (1, 64, 64));
+    }
+
+    #[test]
+    fn trackers_only_receive_changed_pixels() {
+        let mut map = MapState::new(0.0, 0.0, 0, true, false, String::new());
+        let player = Uuid::new_v4();
+        map.track(player, 0);
+        let full = map.take_update(player).unwrap().patch.unwrap();
+        assert_eq!((full.width, full.height), (128, 128));
+
+        map.set_color(3, 4, 12);
+        map.set_color(5, 2, 13);
+        let patch = map.take_update(player).unwrap().patch.unwrap();
+        assert_eq!((patch.start_x, patch.start_z, patch.width, patch.height), (3, 2, 3, 3));
+        assert_eq!(patch.colors[2 * 3], 12);
+        assert!(map.take_update(player).unwrap().patch.is_none());
+    }
+
+    #[test]
+    fn players_off_the_map_are_drawn_at_the_edge() {
+        let mut map = MapState::new(0.0, 0.0, 0, true, false, String::new());
+        let player = Uuid::new_v4();
+        map.track(player, 0);
+        map.update_player(player, "Steve", 100.0, 0.0, 0.0, true);
+        let decoration = map.decorations.values().next().unwrap();
+        assert_eq!(decoration.kind, decoration_type::PLAYER_OFF_MAP);
+        assert_eq!(decoration.x, i8::MAX);
+
+        map.update_player(player, "Steve", 1000.0, 0.0, 0.0, true);
+        assert!(map.decorations.is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+//! Draws the world onto maps, like vanilla `FilledMapItem::updateColors`.
+//!
+//! Every tick a player holds a map, one of every 16 columns of the map around the player is
+//! redrawn. A pixel shows the most common color of the blocks it covers, shaded by how much
+//! higher the pixel is than the one north of it, or for water by how deep the water is.
+
+use std::collections::HashMap;
+
+use pumpkin_data::Block;
+use pumpkin_util::math::vector2::Vector2;
+
+use super::color::{MapBrightness, MapColor, split_dye};
+use super::{MAP_SIZE, MapBanner, MapState};
+use crate::chunk::snapshot::{ChunkSnapshot, WorldSnapshot};
+
+/// Where the holder is on the map and how far around them the map is drawn.
+fn render_area(state: &MapState, holder_x: f64, holder_z: f64, has_ceiling: bool) -> RenderArea {
+    let blocks_per_pixel = 1 << state.scale;
+    let mut radius = MAP_SIZE as i32 / blocks_per_pixel;
+    if has_ceiling {
+        radius /= 2;
+    }
+    RenderArea {
+        blocks_per_pixel,
+        radius,
+        holder_x: (holder_x - f64::from(state.center_x)).floor() as i32 / blocks_per_pixel + 64,
+        holder_z: (holder_z - f64::from(state.center_z)).floor() as i32 / blocks_per_pixel + 64,
+        origin_x: state.center_x / blocks_per_pixel - 64,
+        origin_z: state.center_z / blocks_per_pixel - 64,
+    }
+}
+
+struct RenderArea {
+    blocks_per_pixel: i32,
+    radius: i32,
+    holder_x: i32,
+    holder_z: i32,
+    /// The top left corner of the map, in pixels from the world origin.
+    origin_x: i32,
+    origin_z: i32,
+}
+
+impl RenderArea {
+    /// Returns the block coordinates of the top left corner of the pixel.
+    const fn block_of(&self, pixel_x: i32, pixel_z: i32) -> (i32, i32) {
+        (
+            (self.origin_x + pixel_x) * self.blocks_per_pixel,
+            (self.origin_z + pixel_z) * self.blocks_per_pixel,
+        )
+    }
+}
+
+/// Returns the chunks that [`update_colors`] reads when the map is held at `holder_x`,
+/// `holder_z`, to take a snapshot of them.
+#[must_use]
+pub fn chunks_to_render(
+    state: &MapState,
+    holder_x: f64,
+    holder_z: f64,
+    has_ceiling: bool,
+) -> Vec<Vector2<i32>> {
+    let area = render_area(state, holder_x, holder_z, has_ceiling);
+    let min_x = (area.holder_x - area.radius + 1).max(0);
+    let max_x = (area.holder_x + area.radius - 1).min(MAP_SIZE as i32 - 1);
+    let min_z = (area.holder_z - area.radius - 1).max(-1);
+    let max_z = (area.holder_z + area.radius - 1).min(MAP_SIZE as i32 - 1);
+    if min_x > max_x || min_z > max_z {
+        return Vec::new();
+    }
+    let (first_x, first_z) = area.block_of(min_x, min_z);
+    let (last_x, last_z) = area.block_of(max_x + 1, max_z + 1);
+    let mut chunks = Vec::new();
+    for chunk_x in first_x >> 4..=(last_x - 1) >> 4 {
+        for chunk_z in first_z >> 4..=(last_z - 1) >> 4 {
+            chunks.push(Vector2::new(chunk_x, chunk_z));
+        }
+    }
+    chunks
+}
+
+/// Redraws the columns of `state` that are due at `step` around the holder. Chunks that are not
+/// in `world` are left as they are.
+pub fn update_colors(
+    state: &mut MapState,
+    world: &WorldSnapshot,
+    holder_x: f64,
+    holder_z: f64,
+    step: u32,
+    has_ceiling: bool,
+    min_y: i32,
+) {
+    let area = render_area(state, holder_x, holder_z, has_ceiling);
+    let radius = area.radius;
+    let samples = area.blocks_per_pixel * area.blocks_per_pixel;
+    let mut redraw_next = false;
+
+    for pixel_x in area.holder_x - radius + 1..area.holder_x + radius {
+        if (pixel_x & 15) as u32 != step & 15 && !redraw_next {
+            continue;
+        }
+        redraw_next = false;
+        let mut previous_height = 0.0;
+        for pixel_z in area.holder_z - radius - 1..area.holder_z + radius {
+            if !(0..MAP_SIZE as i32).contains(&pixel_x) || !(-1..MAP_SIZE as i32).contains(&pixel_z)
+            {
+                continue;
+            }
+            let distance = (pixel_x - area.holder_x).pow(2) + (pixel_z - area.holder_z).pow(2);
+            let near_edge = distance > (radius - 2) * (radius - 2);
+            let (block_x, block_z) = area.block_of(pixel_x, pixel_z);
+            let chunk_position = Vector2::new(block_x >> 4, block_z >> 4);
+            let Some(chunk) = world.chunk(chunk_position) else {
+                continue;
+            };
+
+            let mut colors: HashMap<MapColor, u32> = HashMap::new();
+            let mut water_depth = 0;
+            let mut height = 0.0;
+            if has_ceiling {
+                let mut noise = block_x.wrapping_add(block_z.wrapping_mul(231_871));
+                noise = noise
+                    .wrapping_mul(noise)
+                    .wrapping_mul(31_287_121)
+                    .wrapping_add(noise.wrapping_mul(11));
+                if (noise >> 20) & 1 == 0 {
+                    colors.insert(MapColor::DIRT, 10);
+                } else {
+                    colors.insert(MapColor::STONE, 100);
+                }
+                height = 100.0;
+            } else {
+                for offset_x in 0..area.blocks_per_pixel {
+                    for offset_z in 0..area.blocks_per_pixel {
+                        let x = block_x + offset_x;
+                        let z = block_z + offset_z;
+                        let column = sample_column(chunk, x, z, min_y);
+                        water_depth += column.water_depth;
+                        height += f64::from(column.y) / f64::from(samples);
+                        *colors.entry(column.color).or_default() += 1;
+                        state.remove_missing_banners(x, z, |banner| is_banner_at(world, banner));
+                    }
+                }
+            }
+            water_depth /= samples;
+
+            let color = colors
+                .into_iter()
+                .max_by_key(|(color, count)| (*count, std::cmp::Reverse(color.0)))
+                .map_or(MapColor::NONE, |(color, _)| color);
+            let checker = f64::from((pixel_x + pixel_z) & 1);
+            let brightness = if color == MapColor::WATER {
+                let shade = f64::from(water_depth) * 0.1 + checker * 0.2;
+                if shade < 0.5 {
+                    MapBrightness::High
+                } else if shade > 0.9 {
+                    MapBrightness::Low
+                } else {
+                    MapBrightness::Normal
+                }
+            } else {
+                let shade = (height - previous_height) * 4.0
+                    / f64::from(area.blocks_per_pixel + 4)
+                    + (checker - 0.5) * 0.4;
+                if shade > 0.6 {
+                    MapBrightness::High
+                } else if shade < -0.6 {
+                    MapBrightness::Low
+                } else {
+                    MapBrightness::Normal
+                }
+            };
+            previous_height = height;
+
+            if pixel_z >= 0
+                && distance < radius * radius
+                && (!near_edge || (pixel_x + pixel_z) & 1 != 0)
+            {
+                redraw_next |= state.set_color(
+                    pixel_x as usize,
+                    pixel_z as usize,
+                    color.packed(brightness),
+                );
+            }
+        }
+    }
+}
+
+struct Column {
+    y: i32,
+    color: MapColor,
+    water_depth: i32,
+}
+
+/// Finds the highest block with a color in the column at `x`, `z`.
+fn sample_column(chunk: &ChunkSnapshot, x: i32, z: i32, min_y: i32) -> Column {
+    let relative_x = (x & 15) as usize;
+    let relative_z = (z & 15) as usize;
+    let block_at = |y| {
+        chunk
+            .get_block_state_id(relative_x, y, relative_z)
+            .map_or(&Block::AIR, Block::from_state_id)
+    };
+
+    let Some(mut y) = chunk.get_top_y(relative_x, relative_z) else {
+        return Column {
+            y: min_y,
+            color: MapColor::STONE,
+            water_depth: 0,
+        };
+    };
+    let mut color = MapColor::of_block(block_at(y));
+    while color == MapColor::NONE && y > min_y {
+        y -= 1;
+        color = MapColor::of_block(block_at(y));
+    }
+
+    let mut water_depth = 0;
+    if color == MapColor::WATER {
+        let mut below = y - 1;
+        loop {
+            water_depth += 1;
+            if below <= min_y || MapColor::of_block(block_at(below)) != MapColor::WATER {
+                break;
+            }
+            below -= 1;
+        }
+    }
+    Column {
+        y,
+        color,
+        water_depth,
+    }
+}
+
+/// Whether the banner that was marked on a map is still there. Banners in chunks that are not
+/// loaded are kept.
+fn is_banner_at(world: &WorldSnapshot, banner: &MapBanner) -> bool {
+    let Some(state) = world.get_block_state(&banner.pos) else {
+        return true;
+    };
+    let block = Block::from_state_id(state.0);
+    split_dye(block.name).is_some_and(|(dye, rest)| {
+        dye == banner.color && (rest == "banner" || rest == "wall_banner")
+    })
+}
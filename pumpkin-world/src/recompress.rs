@@ -0,0 +1,85 @@
+//! Offline recompression of a world's region files, used by `--recompress`.
+//!
+//! Every chunk of the region and entity files is decompressed and compressed again with the
+//! configured algorithm and level. A file is only replaced once all of its chunks were converted,
+//! so a corrupt chunk leaves its file as it was.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use pumpkin_config::chunk::ChunkCompression;
+use serde::Serialize;
+
+use crate::chunk::ChunkData;
+use crate::chunk::format::anvil::{AnvilChunkFile, Compression};
+use crate::chunk::io::ChunkSerializer;
+use crate::verify::{DIMENSIONS, region_files};
+
+#[derive(Serialize, Debug)]
+pub struct RecompressFailure {
+    pub file: PathBuf,
+    pub detail: String,
+}
+
+/// Result of [`recompress_world`].
+#[derive(Serialize, Debug, Default)]
+pub struct RecompressReport {
+    pub region_files: usize,
+    pub chunks: usize,
+    /// Size of the converted files before and after, in bytes.
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// Files that were left unchanged because they couldn't be converted.
+    pub failures: Vec<RecompressFailure>,
+}
+
+/// Recompresses every Anvil region and entity file of the world at `world` with `compression`.
+/// The server must not be running on the world meanwhile.
+pub async fn recompress_world(world: &Path, compression: &ChunkCompression) -> RecompressReport {
+    let mut report = RecompressReport::default();
+    let algorithm = Compression::from(compression.algorithm);
+    for (folder, _) in DIMENSIONS {
+        let root = world.join(folder);
+        for subfolder in ["region", "entities"] {
+            for file in region_files(&root.join(subfolder)) {
+                if let Err(detail) =
+                    recompress_file(&file, algorithm, compression.level, &mut report).await
+                {
+                    report.failures.push(RecompressFailure { file, detail });
+                }
+            }
+        }
+    }
+    report
+}
+
+async fn recompress_file(
+    file: &Path,
+    algorithm: Compression,
+    level: u32,
+    report: &mut RecompressReport,
+) -> Result<(), String> {
+    let bytes = fs::read(file).map_err(|err| err.to_string())?;
+    // An empty file is how the game stores a region without chunks
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    let bytes_before = bytes.len() as u64;
+    // Only the raw chunk data is touched, so the chunk type doesn't matter
+    let mut region =
+        AnvilChunkFile::<ChunkData>::read(Bytes::from(bytes)).map_err(|err| err.to_string())?;
+    let chunks = region
+        .recompress(algorithm, level)
+        .map_err(|err| err.to_string())?;
+    region
+        .write(&file.to_path_buf())
+        .await
+        .map_err(|err| err.to_string())?;
+
+    report.region_files += 1;
+    report.chunks += chunks;
+    report.bytes_before += bytes_before;
+    report.bytes_after += fs::metadata(file).map_or(0, |metadata| metadata.len());
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! The schematic format of Litematica. Its regions are merged into one schematic.
+
+use std::collections::HashMap;
+
+use pumpkin_data::Block;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_util::math::vector3::Vector3;
+
+use super::{Schematic, SchematicBlockEntity, SchematicError, parse_state};
+use crate::BlockStateId;
+
+fn read_vector(nbt: &NbtCompound, key: &str) -> Option<Vector3<i32>> {
+    let vector = nbt.get_compound(key)?;
+    Some(Vector3::new(
+        vector.get_int("x")?,
+        vector.get_int("y")?,
+        vector.get_int("z")?,
+    ))
+}
+
+/// Reads entry `index` of a bit array whose entries may span two longs.
+fn packed_entry(longs: &[i64], bits: usize, index: usize) -> Option<u32> {
+    let mask = (1u64 << bits) - 1;
+    let start_bit = index * bits;
+    let (start_long, offset) = (start_bit / 64, start_bit % 64);
+    let end_long = (start_bit + bits - 1) / 64;
+    let mut value = *longs.get(start_long)? as u64 >> offset;
+    if end_long != start_long {
+        value |= (*longs.get(end_long)? as u64) << (64 - offset);
+    }
+    Some((value & mask) as u32)
+}
+
+struct Region<'a> {
+    nbt: &'a NbtCompound,
+    /// The corner with the smallest coordinates, relative to the schematic origin.
+    min: Vector3<i32>,
+    size: Vector3<i32>,
+}
+
+impl<'a> Region<'a> {
+    fn read(nbt: &'a NbtCompound) -> Option<Self> {
+        let position = read_vector(nbt, "Position")?;
+        let size = read_vector(nbt, "Size")?;
+        // A negative size extends the region from its position towards smaller coordinates
+        let corner = |position: i32, size: i32| {
+            if size < 0 {
+                position + size + 1
+            } else {
+                position
+            }
+        };
+        Some(Self {
+            nbt,
+            min: Vector3::new(
+                corner(position.x, size.x),
+                corner(position.y, size.y),
+                corner(position.z, size.z),
+            ),
+            size: Vector3::new(size.x.abs(), size.y.abs(), size.z.abs()),
+        })
+    }
+
+    fn max(&self) -> Vector3<i32> {
+        self.min + self.size
+    }
+}
+
+fn read_block_entities(region: &Region, min: Vector3<i32>, schematic: &mut Schematic) {
+    for entity in region.nbt.get_list("TileEntities").unwrap_or_default() {
+        let Some(entity) = entity.extract_compound() else {
+            continue;
+        };
+        let (Some(x), Some(y), Some(z), Some(id)) = (
+            entity.get_int("x"),
+            entity.get_int("y"),
+            entity.get_int("z"),
+            entity.get_string("id"),
+        ) else {
+            continue;
+        };
+        let mut data = entity.clone();
+        for key in ["x", "y", "z", "id"] {
+            data.remove(key);
+        }
+        schematic.block_entities.push(SchematicBlockEntity {
+            pos: region.min - min + Vector3::new(x, y, z),
+            id: id.to_string(),
+            data,
+        });
+    }
+}
+
+pub(super) fn read(root: &NbtCompound) -> Result<Schematic, SchematicError> {
+    let regions = root
+        .get_compound("Regions")
+        .ok_or(SchematicError::Missing("regions"))?;
+    let regions = regions
+        .child_tags
+        .iter()
+        .map(|(_, region)| region.extract_compound().and_then(Region::read))
+        .collect::<Option<Vec<_>>>()
+        .filter(|regions| !regions.is_empty())
+        .ok_or(SchematicError::Missing("regions"))?;
+
+    let min = regions.iter().fold(regions[0].min, |min, region| {
+        Vector3::new(
+            min.x.min(region.min.x),
+            min.y.min(region.min.y),
+            min.z.min(region.min.z),
+        )
+    });
+    let max = regions.iter().fold(regions[0].max(), |max, region| {
+        let region = region.max();
+        Vector3::new(
+            max.x.max(region.x),
+            max.y.max(region.y),
+            max.z.max(region.z),
+        )
+    });
+
+    let mut schematic = Schematic {
+        size: max - min,
+        offset: min,
+        // Space outside of all regions is air
+        palette: vec![Block::AIR.default_state.id],
+        blocks: Vec::new(),
+        block_entities: Vec::new(),
+        unknown_blocks: Vec::new(),
+    };
+    schematic.blocks = vec![0; schematic.volume()];
+    let mut palette_indices: HashMap<BlockStateId, u32> = HashMap::new();
+    palette_indices.insert(Block::AIR.default_state.id, 0);
+
+    for region in regions {
+        // Indices into the merged palette
+        let palette: Vec<u32> = region
+            .nbt
+            .get_list("BlockStatePalette")
+            .ok_or(SchematicError::Missing("palette"))?
+            .iter()
+            .map(|entry| {
+                let entry = entry.extract_compound()?;
+                let props = entry
+                    .get_compound("Properties")
+                    .map_or_else(Vec::new, |props| {
+                        props
+                            .child_tags
+                            .iter()
+                            .filter_map(|(key, value)| {
+                                Some((key.as_str(), value.extract_string()?))
+                            })
+                            .collect()
+                    });
+                let state = parse_state(
+                    entry.get_string("Name")?,
+                    props.into_iter(),
+                    &mut schematic.unknown_blocks,
+                );
+                let next = schematic.palette.len() as u32;
+                Some(*palette_indices.entry(state).or_insert_with(|| {
+                    schematic.palette.push(state);
+                    next
+                }))
+            })
+            .collect::<Option<_>>()
+            .filter(|palette: &Vec<u32>| !palette.is_empty())
+            .ok_or(SchematicError::Missing("palette"))?;
+
+        let states = region
+            .nbt
+            .get_long_array("BlockStates")
+            .ok_or(SchematicError::Missing("block data"))?;
+        let bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(2) as usize;
+        let mut index = 0;
+        for y in 0..region.size.y {
+            for z in 0..region.size.z {
+                for x in 0..region.size.x {
+                    let entry = packed_entry(states, bits, index)
+                        .and_then(|entry| palette.get(entry as usize))
+                        .ok_or(SchematicError::Missing("block data"))?;
+                    index += 1;
+                    let pos = region.min - min + Vector3::new(x, y, z);
+                    let target = schematic.index(pos);
+                    schematic.blocks[target] = *entry;
+                }
+            }
+        }
+
+        read_block_entities(&region, min, &mut schematic);
+    }
+    schematic.validate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_entries_across_longs() {
+        // 3 bit entries 0..=7 repeated, the 22nd entry spans the first two longs
+        let entries: Vec<u64> = (0..64).map(|i| i % 8).collect();
+        let mut longs = vec![0i64; 3];
+        for (i, entry) in entries.iter().enumerate() {
+            let bit = i * 3;
+            longs[bit / 64] |= (entry << (bit % 64)) as i64;
+            if bit % 64 > 61 {
+                longs[bit / 64 + 1] |= (entry >> (64 - bit % 64)) as i64;
+            }
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            assert_eq!(u64::from(packed_entry(&longs, 3, i).unwrap()), *entry);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Schematics: block structures saved by other tools, in the Sponge `.schem` format or, read-only,
+//! the Litematica `.litematic` format.
+//!
+//! Both are read into a [`Schematic`], a palette of block states and a grid of indices into it,
+//! which is pasted with a [`Transform`].
+
+mod litematic;
+mod sponge;
+pub mod transform;
+
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+
+use pumpkin_data::{Block, BlockState};
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::nbt_compress::{read_gzip_compound_tag, write_gzip_compound_tag};
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::BlockStateId;
+pub use transform::{Mirror, Rotation, Transform};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchematicError {
+    #[error("Failed to read the schematic: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid NBT: {0}")]
+    Nbt(#[from] pumpkin_nbt::Error),
+    #[error("Unsupported schematic file {0}, expected .schem or .litematic")]
+    UnknownFormat(String),
+    #[error("Unsupported version {0} of the schematic format")]
+    UnsupportedVersion(i32),
+    #[error("The schematic has no valid {0}")]
+    Missing(&'static str),
+}
+
+/// A block entity of a schematic.
+#[derive(Clone)]
+pub struct SchematicBlockEntity {
+    /// Relative to the minimum corner of the schematic.
+    pub pos: Vector3<i32>,
+    /// The block entity id, like `minecraft:chest`.
+    pub id: String,
+    /// The block entity data, without id and position.
+    pub data: NbtCompound,
+}
+
+pub struct Schematic {
+    /// The width (x), height (y) and length (z).
+    pub size: Vector3<i32>,
+    /// Where the minimum corner lies relative to the paste origin.
+    pub offset: Vector3<i32>,
+    pub palette: Vec<BlockStateId>,
+    /// Indices into the palette, x first, then z, then y.
+    pub blocks: Vec<u32>,
+    pub block_entities: Vec<SchematicBlockEntity>,
+    /// Blocks this server doesn't know, replaced with air.
+    pub unknown_blocks: Vec<String>,
+}
+
+impl Schematic {
+    /// Reads a `.schem` or `.litematic` file, depending on its extension.
+    pub fn load(path: &Path) -> Result<Self, SchematicError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        let read: fn(&NbtCompound) -> Result<Self, SchematicError> = match extension {
+            "schem" => sponge::read,
+            "litematic" => litematic::read,
+            _ => return Err(SchematicError::UnknownFormat(path.display().to_string())),
+        };
+        let nbt = read_gzip_compound_tag(BufReader::new(File::open(path)?))?;
+        read(&nbt)
+    }
+
+    /// Writes the schematic in the Sponge format, version 3.
+    pub fn save(&self, output: impl Write) -> Result<(), SchematicError> {
+        write_gzip_compound_tag(sponge::write(self), output)?;
+        Ok(())
+    }
+
+    #[must_use]
+    pub const fn volume(&self) -> usize {
+        self.size.x as usize * self.size.y as usize * self.size.z as usize
+    }
+
+    /// The index of a position relative to the minimum corner in [`Self::blocks`].
+    #[must_use]
+    pub const fn index(&self, pos: Vector3<i32>) -> usize {
+        ((pos.y * self.size.z + pos.z) * self.size.x + pos.x) as usize
+    }
+
+    /// The block state at a position relative to the minimum corner.
+    #[must_use]
+    pub fn get(&self, pos: Vector3<i32>) -> BlockStateId {
+        self.palette[self.blocks[self.index(pos)] as usize]
+    }
+
+    /// The blocks to place relative to the paste origin, in layers from the bottom up.
+    /// With `skip_air`, air in the schematic leaves the world as it is.
+    pub fn placements(
+        &self,
+        transform: Transform,
+        skip_air: bool,
+    ) -> impl Iterator<Item = (Vector3<i32>, BlockStateId)> + '_ {
+        // Transforming the palette is enough to transform all blocks
+        let palette: Vec<_> = self
+            .palette
+            .iter()
+            .map(|state| transform.apply_to_state(*state))
+            .collect();
+        let size = self.size;
+        (0..size.y)
+            .flat_map(move |y| (0..size.z).flat_map(move |z| (0..size.x).map(move |x| (x, y, z))))
+            .zip(&self.blocks)
+            .filter_map(move |((x, y, z), index)| {
+                let state = palette[*index as usize];
+                if skip_air && BlockState::from_id(state).is_air() {
+                    return None;
+                }
+                let pos = Vector3::new(x, y, z) + self.offset;
+                Some((transform.apply_to_pos(pos), state))
+            })
+    }
+
+    /// The block entities with their positions relative to the paste origin.
+    pub fn placed_block_entities(
+        &self,
+        transform: Transform,
+    ) -> impl Iterator<Item = (Vector3<i32>, &SchematicBlockEntity)> {
+        self.block_entities
+            .iter()
+            .map(move |entity| (transform.apply_to_pos(entity.pos + self.offset), entity))
+    }
+
+    /// Checks that the sizes of the block grid and the palette match.
+    fn validate(self) -> Result<Self, SchematicError> {
+        if self.size.x < 0 || self.size.y < 0 || self.size.z < 0 {
+            return Err(SchematicError::Missing("size"));
+        }
+        if self.blocks.len() != self.volume() {
+            return Err(SchematicError::Missing("block data"));
+        }
+        if self
+            .blocks
+            .iter()
+            .any(|index| *index as usize >= self.palette.len())
+        {
+            return Err(SchematicError::Missing("palette"));
+        }
+        Ok(self)
+    }
+}
+
+/// Parses a palette entry, reporting unknown blocks and invalid properties as air.
+fn parse_state<'a, V: AsRef<str>>(
+    name: &str,
+    props: impl Iterator<Item = (&'a str, V)>,
+    unknown: &mut Vec<String>,
+) -> BlockStateId {
+    let props: Vec<_> = props.collect();
+    let state = Block::from_name(name).and_then(|block| transform::state_with_props(block, &props));
+    state.unwrap_or_else(|| {
+        if !unknown.iter().any(|unknown| unknown == name) {
+            unknown.push(name.to_string());
+        }
+        Block::AIR.default_state.id
+    })
+}
+
+/// Parses a block state string such as `minecraft:oak_stairs[facing=north,half=top]`.
+fn parse_state_string(state: &str, unknown: &mut Vec<String>) -> BlockStateId {
+    let (name, props) = state
+        .strip_suffix(']')
+        .and_then(|state| state.split_once('['))
+        .unwrap_or((state, ""));
+    let props = props.split(',').filter_map(|prop| prop.split_once('='));
+    parse_state(name, props, unknown)
+}
+
+/// Formats a block state the way [`parse_state_string`] reads it.
+fn state_string(state_id: BlockStateId) -> String {
+    let block = Block::from_state_id(state_id);
+    let name = format!("minecraft:{}", block.name);
+    match block.properties(state_id) {
+        Some(props) => {
+            let props: Vec<_> = props
+                .to_props()
+                .into_iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect();
+            format!("{name}[{}]", props.join(","))
+        }
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stairs(facing: &str) -> BlockStateId {
+        parse_state_string(
+            &format!("minecraft:oak_stairs[facing={facing},half=bottom]"),
+            &mut Vec::new(),
+        )
+    }
+
+    #[test]
+    fn parses_states() {
+        let mut unknown = Vec::new();
+        assert_eq!(
+            parse_state_string("minecraft:stone", &mut unknown),
+            Block::STONE.default_state.id
+        );
+        let state = parse_state_string("minecraft:oak_log[axis=x]", &mut unknown);
+        assert_eq!(
+            parse_state_string(&state_string(state), &mut unknown),
+            state
+        );
+        assert_ne!(state, Block::OAK_LOG.default_state.id);
+
+        parse_state_string("minecraft:oak_log[axis=w]", &mut unknown);
+        parse_state_string("othermod:thing", &mut unknown);
+        assert_eq!(unknown, ["minecraft:oak_log", "othermod:thing"]);
+    }
+
+    #[test]
+    fn transforms_states() {
+        let rotate = |rotation| Transform {
+            rotation,
+            mirror: Mirror::None,
+        };
+        assert_eq!(
+            rotate(Rotation::Clockwise90).apply_to_state(stairs("north")),
+            stairs("east")
+        );
+        assert_eq!(
+            rotate(Rotation::CounterClockwise90).apply_to_state(stairs("north")),
+            stairs("west")
+        );
+        let mirror = Transform {
+            rotation: Rotation::None,
+            mirror: Mirror::LeftRight,
+        };
+        assert_eq!(mirror.apply_to_state(stairs("north")), stairs("south"));
+        assert_eq!(mirror.apply_to_state(stairs("east")), stairs("east"));
+
+        let mut unknown = Vec::new();
+        let rail =
+            |shape| parse_state_string(&format!("minecraft:rail[shape={shape}]"), &mut Vec::new());
+        assert_eq!(
+            rotate(Rotation::Clockwise90).apply_to_state(rail("north_east")),
+            rail("south_east")
+        );
+        let fence = parse_state_string("minecraft:oak_fence[north=true]", &mut unknown);
+        assert_eq!(
+            rotate(Rotation::Clockwise90).apply_to_state(fence),
+            parse_state_string("minecraft:oak_fence[east=true]", &mut unknown)
+        );
+    }
+
+    #[test]
+    fn transforms_positions() {
+        let transform = Transform {
+            rotation: Rotation::Clockwise90,
+            mirror: Mirror::None,
+        };
+        // North (negative z) turns to east (positive x)
+        assert_eq!(
+            transform.apply_to_pos(Vector3::new(0, 5, -2)),
+            Vector3::new(2, 5, 0)
+        );
+    }
+}
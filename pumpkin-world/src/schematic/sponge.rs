@@ -0,0 +1,212 @@
+//! The Sponge schematic format of `WorldEdit` and others, versions 1 to 3.
+
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::tag::NbtTag;
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::chunk::format::anvil::WORLD_DATA_VERSION;
+
+use super::{Schematic, SchematicBlockEntity, SchematicError, parse_state_string, state_string};
+
+fn read_vector(nbt: &NbtCompound, key: &str) -> Option<Vector3<i32>> {
+    match nbt.get_int_array(key)? {
+        [x, y, z] => Some(Vector3::new(*x, *y, *z)),
+        _ => None,
+    }
+}
+
+/// Decodes the `VarInt` palette indices of the block data.
+fn read_varints(bytes: &[u8]) -> Option<Vec<u32>> {
+    let mut values = Vec::with_capacity(bytes.len());
+    let mut value = 0u32;
+    let mut shift = 0;
+    for byte in bytes {
+        if shift >= 32 {
+            return None;
+        }
+        value |= u32::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            values.push(value);
+            value = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+        }
+    }
+    (shift == 0).then_some(values)
+}
+
+fn write_varints(values: &[u32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(values.len());
+    for value in values {
+        let mut value = *value;
+        while value >= 0x80 {
+            bytes.push((value & 0x7F) as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+    bytes
+}
+
+pub(super) fn read(root: &NbtCompound) -> Result<Schematic, SchematicError> {
+    // Version 3 wraps everything in a compound, versions 1 and 2 are the root itself
+    let nbt = root.get_compound("Schematic").unwrap_or(root);
+    let version = nbt.get_int("Version").unwrap_or(1);
+    let (blocks, block_entities_key) = match version {
+        1 => (nbt, "TileEntities"),
+        2 => (nbt, "BlockEntities"),
+        3 => (
+            nbt.get_compound("Blocks")
+                .ok_or(SchematicError::Missing("blocks"))?,
+            "BlockEntities",
+        ),
+        version => return Err(SchematicError::UnsupportedVersion(version)),
+    };
+
+    // Sizes are unsigned shorts
+    let dimension = |key| {
+        nbt.get_short(key)
+            .map(|size| i32::from(size as u16))
+            .ok_or(SchematicError::Missing("size"))
+    };
+    let size = Vector3::new(
+        dimension("Width")?,
+        dimension("Height")?,
+        dimension("Length")?,
+    );
+
+    let palette_nbt = blocks
+        .get_compound("Palette")
+        .ok_or(SchematicError::Missing("palette"))?;
+    let mut unknown_blocks = Vec::new();
+    let mut palette = vec![0; palette_nbt.child_tags.len()];
+    for (state, index) in &palette_nbt.child_tags {
+        let index = index
+            .extract_int()
+            .and_then(|index| usize::try_from(index).ok())
+            .filter(|index| *index < palette.len())
+            .ok_or(SchematicError::Missing("palette"))?;
+        palette[index] = parse_state_string(state, &mut unknown_blocks);
+    }
+
+    let data_key = if version == 3 { "Data" } else { "BlockData" };
+    let data = blocks
+        .get(data_key)
+        .and_then(NbtTag::extract_byte_array)
+        .and_then(read_varints)
+        .ok_or(SchematicError::Missing("block data"))?;
+
+    let mut block_entities = Vec::new();
+    for entity in blocks.get_list(block_entities_key).unwrap_or_default() {
+        let Some(entity) = entity.extract_compound() else {
+            continue;
+        };
+        let (Some(pos), Some(id)) = (read_vector(entity, "Pos"), entity.get_string("Id")) else {
+            continue;
+        };
+        let data = if version == 3 {
+            entity.get_compound("Data").cloned().unwrap_or_default()
+        } else {
+            let mut data = entity.clone();
+            data.remove("Pos");
+            data.remove("Id");
+            data
+        };
+        block_entities.push(SchematicBlockEntity {
+            pos,
+            id: id.to_string(),
+            data,
+        });
+    }
+
+    Schematic {
+        size,
+        offset: read_vector(nbt, "Offset").unwrap_or_default(),
+        palette,
+        blocks: data,
+        block_entities,
+        unknown_blocks,
+    }
+    .validate()
+}
+
+pub(super) fn write(schematic: &Schematic) -> NbtCompound {
+    let mut palette = NbtCompound::new();
+    for (index, state) in schematic.palette.iter().enumerate() {
+        palette.put_int(&state_string(*state), index as i32);
+    }
+
+    let block_entities = schematic
+        .block_entities
+        .iter()
+        .map(|entity| {
+            let mut nbt = NbtCompound::new();
+            let pos = entity.pos;
+            nbt.put("Pos", NbtTag::IntArray(vec![pos.x, pos.y, pos.z]));
+            nbt.put_string("Id", entity.id.clone());
+            nbt.put_component("Data", entity.data.clone());
+            NbtTag::Compound(nbt)
+        })
+        .collect();
+
+    let mut blocks = NbtCompound::new();
+    blocks.put_component("Palette", palette);
+    blocks.put(
+        "Data",
+        NbtTag::ByteArray(write_varints(&schematic.blocks).into_boxed_slice()),
+    );
+    blocks.put_list("BlockEntities", block_entities);
+
+    let offset = schematic.offset;
+    let mut nbt = NbtCompound::new();
+    nbt.put_int("Version", 3);
+    nbt.put_int("DataVersion", WORLD_DATA_VERSION);
+    nbt.put_short("Width", schematic.size.x as i16);
+    nbt.put_short("Height", schematic.size.y as i16);
+    nbt.put_short("Length", schematic.size.z as i16);
+    nbt.put(
+        "Offset",
+        NbtTag::IntArray(vec![offset.x, offset.y, offset.z]),
+    );
+    nbt.put_component("Blocks", blocks);
+
+    let mut root = NbtCompound::new();
+    root.put_component("Schematic", nbt);
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_data::Block;
+
+    use super::*;
+
+    #[test]
+    fn varints_round_trip() {
+        let values = [0, 1, 127, 128, 300, 70_000];
+        assert_eq!(read_varints(&write_varints(&values)).unwrap(), values);
+        // Cut off in the middle of a value
+        assert!(read_varints(&[0x80]).is_none());
+    }
+
+    #[test]
+    fn round_trips() {
+        let schematic = Schematic {
+            size: Vector3::new(2, 1, 1),
+            offset: Vector3::new(-1, 0, 3),
+            palette: vec![Block::AIR.default_state.id, Block::OAK_LOG.default_state.id],
+            blocks: vec![1, 0],
+            block_entities: Vec::new(),
+            unknown_blocks: Vec::new(),
+        };
+        let read = read(&write(&schematic)).unwrap();
+        assert_eq!(read.size, schematic.size);
+        assert_eq!(read.offset, schematic.offset);
+        assert_eq!(
+            read.get(Vector3::new(0, 0, 0)),
+            Block::OAK_LOG.default_state.id
+        );
+        assert_eq!(read.get(Vector3::new(1, 0, 0)), Block::AIR.default_state.id);
+    }
+}
@@ -0,0 +1,217 @@
+use pumpkin_data::Block;
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::BlockStateId;
+
+/// A rotation around the vertical axis, seen from above.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    CounterClockwise90,
+}
+
+impl Rotation {
+    /// Parses a rotation in degrees clockwise, a multiple of 90.
+    #[must_use]
+    pub const fn from_degrees(degrees: i32) -> Option<Self> {
+        match degrees.rem_euclid(360) {
+            0 => Some(Self::None),
+            90 => Some(Self::Clockwise90),
+            180 => Some(Self::Clockwise180),
+            270 => Some(Self::CounterClockwise90),
+            _ => None,
+        }
+    }
+
+    /// Quarter turns clockwise.
+    const fn quarter_turns(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Clockwise90 => 1,
+            Self::Clockwise180 => 2,
+            Self::CounterClockwise90 => 3,
+        }
+    }
+}
+
+/// A mirror across a vertical plane.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Mirror {
+    #[default]
+    None,
+    /// Swaps north and south.
+    LeftRight,
+    /// Swaps east and west.
+    FrontBack,
+}
+
+/// Mirrors, then rotates a schematic around the paste origin, like structure blocks do.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub mirror: Mirror,
+}
+
+const HORIZONTAL: [&str; 4] = ["north", "east", "south", "west"];
+
+fn rotate_direction(direction: &str, quarter_turns: u8) -> Option<&'static str> {
+    let index = HORIZONTAL.iter().position(|d| *d == direction)?;
+    Some(HORIZONTAL[(index + usize::from(quarter_turns)) % 4])
+}
+
+/// Whether `shape` is the shape of a rail rather than of stairs.
+fn is_rail_shape(shape: &str) -> bool {
+    shape
+        .split('_')
+        .all(|part| part == "ascending" || HORIZONTAL.contains(&part))
+}
+
+impl Transform {
+    /// Transforms a position relative to the paste origin.
+    #[must_use]
+    pub const fn apply_to_pos(self, pos: Vector3<i32>) -> Vector3<i32> {
+        let (mut x, y, mut z) = (pos.x, pos.y, pos.z);
+        match self.mirror {
+            Mirror::None => {}
+            Mirror::LeftRight => z = -z,
+            Mirror::FrontBack => x = -x,
+        }
+        let (x, z) = match self.rotation {
+            Rotation::None => (x, z),
+            Rotation::Clockwise90 => (-z, x),
+            Rotation::Clockwise180 => (-x, -z),
+            Rotation::CounterClockwise90 => (z, -x),
+        };
+        Vector3::new(x, y, z)
+    }
+
+    fn mirror_direction(self, direction: &str) -> Option<&'static str> {
+        match (self.mirror, direction) {
+            (Mirror::LeftRight, "north") => Some("south"),
+            (Mirror::LeftRight, "south") => Some("north"),
+            (Mirror::FrontBack, "east") => Some("west"),
+            (Mirror::FrontBack, "west") => Some("east"),
+            _ => HORIZONTAL.iter().find(|d| **d == direction).copied(),
+        }
+    }
+
+    /// Where a horizontal direction points after the transform.
+    fn direction(self, direction: &str) -> Option<&'static str> {
+        rotate_direction(
+            self.mirror_direction(direction)?,
+            self.rotation.quarter_turns(),
+        )
+    }
+
+    /// Transforms a rail shape such as `north_east` or `ascending_west`.
+    fn rail_shape(self, shape: &str) -> Option<String> {
+        if let Some(direction) = shape.strip_prefix("ascending_") {
+            return Some(format!("ascending_{}", self.direction(direction)?));
+        }
+        let (a, b) = shape.split_once('_')?;
+        let (a, b) = (self.direction(a)?, self.direction(b)?);
+        // Rail shapes name north or south first, and straight ones north or east first
+        let shape = match (a, b) {
+            ("north" | "south", "north" | "south") => "north_south".to_string(),
+            ("east" | "west", "east" | "west") => "east_west".to_string(),
+            ("north" | "south", _) => format!("{a}_{b}"),
+            _ => format!("{b}_{a}"),
+        };
+        Some(shape)
+    }
+
+    /// The value of the property `key` after the transform, if it changes.
+    fn property(self, key: &str, value: &str) -> Option<String> {
+        let mirrored = self.mirror != Mirror::None;
+        let quarter_turns = self.rotation.quarter_turns();
+        match key {
+            "facing" | "horizontal_facing" => self.direction(value).map(str::to_string),
+            "axis" if quarter_turns % 2 == 1 => match value {
+                "x" => Some("z".to_string()),
+                "z" => Some("x".to_string()),
+                _ => None,
+            },
+            "rotation" => {
+                let mut rotation: i32 = value.parse().ok()?;
+                rotation = match self.mirror {
+                    Mirror::None => rotation,
+                    Mirror::LeftRight => 8 - rotation,
+                    Mirror::FrontBack => 16 - rotation,
+                };
+                rotation += 4 * i32::from(quarter_turns);
+                Some(rotation.rem_euclid(16).to_string())
+            }
+            "shape" if is_rail_shape(value) => self.rail_shape(value),
+            "shape" | "hinge" | "type" if mirrored => Some(
+                value
+                    .replace("left", "\0")
+                    .replace("right", "left")
+                    .replace('\0', "right"),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Transforms a block state, turning the directions in its properties.
+    #[must_use]
+    pub fn apply_to_state(self, state_id: BlockStateId) -> BlockStateId {
+        if self == Self::default() {
+            return state_id;
+        }
+        let block = Block::from_state_id(state_id);
+        let Some(properties) = block.properties(state_id) else {
+            return state_id;
+        };
+        let props = properties.to_props();
+        let mut transformed: Vec<(&str, String)> = props
+            .iter()
+            .map(|(key, value)| {
+                let value = self
+                    .property(key, value)
+                    .unwrap_or_else(|| (*value).to_string());
+                (*key, value)
+            })
+            .collect();
+        // Connections of fences, walls, vines and redstone move to the transformed side
+        for (key, value) in &props {
+            if let Some(side) = self.direction(key)
+                && let Some(entry) = transformed.iter_mut().find(|(k, _)| *k == side)
+            {
+                entry.1 = (*value).to_string();
+            }
+        }
+        state_with_props(block, &transformed).unwrap_or(state_id)
+    }
+}
+
+/// The state of `block` with the given properties. Properties the block doesn't have are
+/// ignored, missing ones keep their default value. Returns `None` if a value is invalid.
+#[must_use]
+pub fn state_with_props<V: AsRef<str>>(block: &Block, props: &[(&str, V)]) -> Option<BlockStateId> {
+    let Some(default) = block.properties(block.default_state.id) else {
+        return Some(block.default_state.id);
+    };
+    let default = default.to_props();
+    block
+        .states
+        .iter()
+        .find(|state| {
+            let Some(state_props) = block.properties(state.id) else {
+                return false;
+            };
+            state_props
+                .to_props()
+                .into_iter()
+                .zip(&default)
+                .all(|((key, value), (_, default))| {
+                    props
+                        .iter()
+                        .find(|(k, _)| *k == key)
+                        .map_or(value == *default, |(_, v)| v.as_ref() == value)
+                })
+        })
+        .map(|state| state.id)
+}
@@ -0,0 +1,154 @@
+//! Cloning of prebuilt template worlds.
+//!
+//! Minigame servers create the same world over and over. Instead of generating it every time,
+//! a template world is generated once and cloned. Region files are hard linked instead of copied
+//! where the platform allows it, so a clone takes no time and no disk space until it is changed.
+//! Region files are written copy-on-write: a file still shared with the template is never
+//! written in place, but replaced by a new file, see [`is_shared`].
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use pumpkin_config::world_template::WorldTemplateConfig;
+
+/// Folders whose files are only ever replaced by the server, never rewritten in place.
+const SHARED_FOLDERS: [&str; 2] = ["region", "entities"];
+
+/// Files that must never be cloned.
+const SKIPPED_FILES: [&str; 1] = ["session.lock"];
+
+/// How the files of a template were cloned.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TemplateClone {
+    /// Region files shared with the template.
+    pub linked: usize,
+    /// Files copied from the template.
+    pub copied: usize,
+}
+
+/// Clones the world at `template` into `target`, which must not exist yet.
+pub fn clone_template(template: &Path, target: &Path) -> io::Result<TemplateClone> {
+    if !template.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not a world folder", template.display()),
+        ));
+    }
+    if target.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", target.display()),
+        ));
+    }
+    let mut clone = TemplateClone::default();
+    clone_folder(template, target, false, &mut clone)?;
+    Ok(clone)
+}
+
+/// Clones the template of `config` into `world` if the world is missing, or on every start with
+/// `reset_on_start`. Returns `None` if nothing was cloned.
+pub fn apply_template(
+    config: &WorldTemplateConfig,
+    world: &Path,
+) -> io::Result<Option<TemplateClone>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    let template = Path::new(&config.path);
+    if world.exists() {
+        if !config.reset_on_start {
+            return Ok(None);
+        }
+        if fs::canonicalize(template)? == fs::canonicalize(world)? {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the template is the world itself",
+            ));
+        }
+        fs::remove_dir_all(world)?;
+    }
+    clone_template(template, world).map(Some)
+}
+
+fn clone_folder(
+    source: &Path,
+    target: &Path,
+    shared: bool,
+    clone: &mut TemplateClone,
+) -> io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name_str = name.to_str().unwrap_or_default();
+        if SKIPPED_FILES.contains(&name_str) {
+            continue;
+        }
+        let (source, target) = (entry.path(), target.join(&name));
+        if entry.file_type()?.is_dir() {
+            let shared = SHARED_FOLDERS.contains(&name_str);
+            clone_folder(&source, &target, shared, clone)?;
+        } else if shared && link(&source, &target) {
+            clone.linked += 1;
+        } else {
+            fs::copy(&source, &target)?;
+            clone.copied += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Hard links `target` to `source`, returning whether it worked. Linking fails across file
+/// systems, in which case the file is copied instead.
+#[cfg(unix)]
+fn link(source: &Path, target: &Path) -> bool {
+    fs::hard_link(source, target).is_ok()
+}
+
+/// Without a way to tell whether a file is shared, files are always copied.
+#[cfg(not(unix))]
+const fn link(_source: &Path, _target: &Path) -> bool {
+    false
+}
+
+/// Whether the file at `path` is still shared with a template or other clones, so it must be
+/// replaced instead of written in place.
+#[cfg(unix)]
+#[must_use]
+pub fn is_shared(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).is_ok_and(|metadata| metadata.nlink() > 1)
+}
+
+#[cfg(not(unix))]
+#[must_use]
+pub const fn is_shared(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_shares_region_files_only() {
+        let template = temp_dir::TempDir::new().unwrap();
+        let target = temp_dir::TempDir::new().unwrap();
+        let target = target.path().join("world");
+        fs::create_dir(template.path().join("region")).unwrap();
+        fs::write(template.path().join("region/r.0.0.mca"), b"region").unwrap();
+        fs::write(template.path().join("level.dat"), b"level").unwrap();
+        fs::write(template.path().join("session.lock"), b"lock").unwrap();
+
+        let clone = clone_template(template.path(), &target).unwrap();
+        assert_eq!(clone.linked + clone.copied, 2);
+        assert!(!target.join("session.lock").exists());
+        assert!(!is_shared(&target.join("level.dat")));
+        assert_eq!(
+            is_shared(&target.join("region/r.0.0.mca")),
+            clone.linked == 1
+        );
+        assert!(clone_template(template.path(), &target).is_err());
+    }
+}
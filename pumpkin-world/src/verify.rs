@@ -0,0 +1,320 @@
+//! Read-only world verification, used by `--verify-world`.
+//!
+//! Walks the region and entity files of every dimension, parses each chunk the same way the
+//! server would and reports what fails, without modifying anything.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use pumpkin_util::math::vector2::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::format::PaletteBlockEntry;
+use crate::chunk::format::anvil::{
+    AnvilChunkFile, REGION_SIZE, SingleChunkDataSerializer, WORLD_DATA_VERSION,
+};
+use crate::chunk::io::ChunkSerializer;
+use crate::chunk::{ChunkData, ChunkEntityData};
+
+/// Dimension folders relative to the world folder.
+pub(crate) const DIMENSIONS: [(&str, &str); 3] = [
+    ("", "minecraft:overworld"),
+    ("DIM-1", "minecraft:the_nether"),
+    ("DIM1", "minecraft:the_end"),
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// The region file header or chunk table is unreadable.
+    CorruptRegion,
+    /// The chunk could not be decompressed or parsed.
+    CorruptChunk,
+    /// A block name or property combination that does not exist in this version.
+    UnknownBlockState,
+    /// The chunk was saved by a different game version.
+    DataVersion,
+}
+
+#[derive(Serialize, Debug)]
+pub struct VerifyIssue {
+    pub file: PathBuf,
+    /// Chunk coordinates, or `None` if the issue affects the whole file.
+    pub chunk: Option<[i32; 2]>,
+    pub kind: IssueKind,
+    pub detail: String,
+}
+
+/// Machine-readable result of [`verify_world`].
+#[derive(Serialize, Debug, Default)]
+pub struct VerifyReport {
+    pub world: PathBuf,
+    pub expected_data_version: i32,
+    pub dimensions: Vec<&'static str>,
+    pub region_files: usize,
+    pub chunks: usize,
+    /// Number of chunks saved with each data version.
+    pub data_versions: BTreeMap<i32, usize>,
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    #[must_use]
+    pub fn count(&self, kind: IssueKind) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.kind == kind)
+            .count()
+    }
+}
+
+/// The parts of a chunk's NBT that are checked before the full parse.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChunkHeader {
+    data_version: i32,
+    #[serde(rename = "sections", default)]
+    sections: Vec<SectionHeader>,
+}
+
+#[derive(Deserialize)]
+struct SectionHeader {
+    block_states: Option<BlockStatesHeader>,
+}
+
+#[derive(Deserialize)]
+struct BlockStatesHeader {
+    palette: Vec<PaletteBlockEntry>,
+}
+
+#[derive(Clone, Copy)]
+enum FileKind {
+    Region,
+    Entities,
+}
+
+/// Verifies every Anvil region and entity file of the world at `world`.
+///
+/// A silent panic hook is installed while chunks are parsed, so that a panicking chunk is
+/// reported like any other issue, and the previous one is restored afterwards.
+#[must_use]
+pub fn verify_world(world: &Path) -> VerifyReport {
+    let mut report = VerifyReport {
+        world: world.to_path_buf(),
+        expected_data_version: WORLD_DATA_VERSION,
+        ..Default::default()
+    };
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for (folder, dimension) in DIMENSIONS {
+        let root = world.join(folder);
+        if !root.join("region").exists() {
+            continue;
+        }
+        report.dimensions.push(dimension);
+        for (subfolder, kind) in [
+            ("region", FileKind::Region),
+            ("entities", FileKind::Entities),
+        ] {
+            for file in region_files(&root.join(subfolder)) {
+                verify_region_file(&file, kind, &mut report);
+            }
+        }
+    }
+    panic::set_hook(hook);
+    report
+}
+
+pub(crate) fn region_files(folder: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "mca"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Parses the region coordinates from an `r.<x>.<z>.mca` file name.
+fn region_coords(file: &Path) -> Option<(i32, i32)> {
+    let name = file.file_stem()?.to_str()?;
+    let mut parts = name.strip_prefix("r.")?.split('.');
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+    Some((x, z))
+}
+
+fn verify_region_file(file: &Path, kind: FileKind, report: &mut VerifyReport) {
+    let mut issue = |chunk: Option<Vector2<i32>>, kind: IssueKind, detail: String| {
+        report.issues.push(VerifyIssue {
+            file: file.to_path_buf(),
+            chunk: chunk.map(|chunk| [chunk.x, chunk.y]),
+            kind,
+            detail,
+        });
+    };
+    let Some((region_x, region_z)) = region_coords(file) else {
+        issue(
+            None,
+            IssueKind::CorruptRegion,
+            "File name is not r.<x>.<z>.mca".to_string(),
+        );
+        return;
+    };
+    let bytes = match fs::read(file) {
+        Ok(bytes) => Bytes::from(bytes),
+        Err(err) => {
+            issue(None, IssueKind::CorruptRegion, err.to_string());
+            return;
+        }
+    };
+    // An empty file is how the game stores a region without chunks
+    if bytes.is_empty() {
+        return;
+    }
+    let region = match panic::catch_unwind(AssertUnwindSafe(|| {
+        AnvilChunkFile::<ChunkData>::read(bytes)
+    })) {
+        Ok(Ok(region)) => region,
+        Ok(Err(err)) => {
+            issue(None, IssueKind::CorruptRegion, err.to_string());
+            return;
+        }
+        Err(panic) => {
+            issue(None, IssueKind::CorruptRegion, panic_message(&*panic));
+            return;
+        }
+    };
+    report.region_files += 1;
+
+    for (index, data) in region.decompress_all() {
+        let pos = Vector2::new(
+            region_x * REGION_SIZE as i32 + (index % REGION_SIZE) as i32,
+            region_z * REGION_SIZE as i32 + (index / REGION_SIZE) as i32,
+        );
+        report.chunks += 1;
+        let data = match data {
+            Ok(data) => data,
+            Err(err) => {
+                issue(Some(pos), IssueKind::CorruptChunk, err.to_string());
+                continue;
+            }
+        };
+        let header = match pumpkin_nbt::from_slice::<ChunkHeader>(&data) {
+            Ok(header) => header,
+            Err(err) => {
+                issue(Some(pos), IssueKind::CorruptChunk, err.to_string());
+                continue;
+            }
+        };
+        *report.data_versions.entry(header.data_version).or_default() += 1;
+        if header.data_version != WORLD_DATA_VERSION {
+            let relation = if header.data_version < WORLD_DATA_VERSION {
+                "older"
+            } else {
+                "newer"
+            };
+            issue(
+                Some(pos),
+                IssueKind::DataVersion,
+                format!(
+                    "Data version {} is {relation} than {WORLD_DATA_VERSION}",
+                    header.data_version
+                ),
+            );
+        }
+
+        // The server loads these as their block's default state or air
+        for state in unknown_block_states(&header) {
+            issue(Some(pos), IssueKind::UnknownBlockState, state);
+        }
+        let parsed = panic::catch_unwind(AssertUnwindSafe(|| match kind {
+            FileKind::Region => ChunkData::from_bytes(&data, pos).map(drop),
+            FileKind::Entities => ChunkEntityData::from_bytes(&data, pos).map(drop),
+        }));
+        match parsed {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => issue(Some(pos), IssueKind::CorruptChunk, err.to_string()),
+            Err(panic) => issue(Some(pos), IssueKind::CorruptChunk, panic_message(&*panic)),
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    let message = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| panic.downcast_ref::<&str>().copied())
+        .unwrap_or("unknown panic");
+    format!("Reading panicked: {message}")
+}
+
+/// Returns the palette entries of `header` that do not map to a block state, deduplicated.
+fn unknown_block_states(header: &ChunkHeader) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let entries = header
+        .sections
+        .iter()
+        .filter_map(|section| section.block_states.as_ref())
+        .flat_map(|states| &states.palette);
+    for entry in entries {
+        if entry.try_state_id().is_some() {
+            continue;
+        }
+        let state = entry.to_string();
+        if !unknown.contains(&state) {
+            unknown.push(state);
+        }
+    }
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECTOR_BYTES: usize = 4096;
+
+    /// A region file with a single chunk whose zlib data is garbage.
+    fn region_with_corrupt_chunk() -> Vec<u8> {
+        let mut bytes = vec![0; 3 * SECTOR_BYTES];
+        // The first chunk takes one sector right after the headers
+        bytes[..4].copy_from_slice(&((2 << 8) | 1u32).to_be_bytes());
+        let chunk = &mut bytes[2 * SECTOR_BYTES..];
+        chunk[..4].copy_from_slice(&5u32.to_be_bytes());
+        chunk[4] = 2;
+        chunk[5..9].copy_from_slice(&[1, 2, 3, 4]);
+        bytes
+    }
+
+    #[test]
+    fn reports_corrupt_regions_and_chunks() {
+        let world = temp_dir::TempDir::new().unwrap();
+        let region = world.path().join("region");
+        fs::create_dir_all(&region).unwrap();
+        fs::write(region.join("r.0.0.mca"), region_with_corrupt_chunk()).unwrap();
+        // Shorter than the region headers
+        fs::write(region.join("r.1.0.mca"), [7; 100]).unwrap();
+
+        let report = verify_world(world.path());
+        assert_eq!(report.dimensions, ["minecraft:overworld"]);
+        assert_eq!(report.region_files, 1);
+        assert_eq!(report.chunks, 1);
+        assert_eq!(report.count(IssueKind::CorruptRegion), 1);
+        assert_eq!(report.count(IssueKind::CorruptChunk), 1);
+        let chunk_issue = report
+            .issues
+            .iter()
+            .find(|issue| issue.kind == IssueKind::CorruptChunk)
+            .unwrap();
+        assert_eq!(chunk_issue.chunk, Some([0, 0]));
+        assert!(chunk_issue.file.ends_with("r.0.0.mca"));
+    }
+}
@@ -148,7 +148,9 @@ mod test {
 
     use crate::{
         global_path,
-        world_info::{DataPacks, LevelData, WorldGenSettings, WorldInfoError, WorldVersion},
+        world_info::{
+            DataPacks, DragonFightData, LevelData, WorldGenSettings, WorldInfoError, WorldVersion,
+        },
     };
 
     use super::{AnvilLevelInfo, LEVEL_DAT_FILE_NAME, LevelDat, WorldInfoReader, WorldInfoWriter};
@@ -240,6 +242,16 @@ mod test {
                 snapshot: false,
                 series: "main".to_string(),
             },
+            dragon_fight: Some(DragonFightData {
+                needs_state_scanning: true,
+                dragon_killed: false,
+                previously_killed: false,
+                dragon: None,
+                exit_portal_location: None,
+                gateways: vec![
+                    2, 17, 6, 15, 11, 13, 19, 8, 7, 12, 14, 10, 9, 0, 4, 3, 16, 1, 18, 5,
+                ],
+            }),
         },
     });
 
@@ -269,6 +281,25 @@ mod test {
         assert_eq!(level_dat_again, *LEVEL_DAT);
     }
 
+    #[test]
+    fn serialize_dragon_fight_in_progress() {
+        let fight = DragonFightData {
+            needs_state_scanning: false,
+            dragon_killed: false,
+            previously_killed: true,
+            dragon: Some(vec![1, -2, 3, -4]),
+            exit_portal_location: Some(vec![0, 64, 0]),
+            gateways: vec![5, 18],
+        };
+        let mut serialized = Vec::new();
+        to_bytes(&fight, &mut serialized).expect("Failed to encode to bytes");
+
+        let fight_again: DragonFightData =
+            from_bytes(Cursor::new(serialized)).expect("Failed to decode from bytes");
+
+        assert_eq!(fight_again, fight);
+    }
+
     #[test]
     fn failed_deserialize_old_level_dat() {
         let temp_dir = TempDir::new().unwrap();
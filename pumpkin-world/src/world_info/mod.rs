@@ -3,6 +3,7 @@ use std::path::Path;
 
 use crate::CURRENT_MC_VERSION;
 use pumpkin_data::game_rules::GameRuleRegistry;
+use pumpkin_nbt::tag::NbtTag;
 use pumpkin_util::{Difficulty, serde_enum_as_integer, world_seed::Seed};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -92,9 +93,63 @@ pub struct LevelData {
     pub world_version: WorldVersion,
     #[serde(rename = "version")]
     pub level_version: i32, // TODO: Implement the rest of the fields
+    /// The state of the fight against the ender dragon in the End.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dragon_fight: Option<DragonFightData>,
 }
 
+/// The fight against the ender dragon, saved with the End.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct DragonFightData {
+    /// Whether the End has to be searched for a dragon and exit portal left by an older
+    /// version, before the fight can start.
+    pub needs_state_scanning: bool,
+    pub dragon_killed: bool,
+    /// Whether a dragon was ever killed. Only the first one drops an egg.
+    pub previously_killed: bool,
+    /// UUID of the living dragon, as four ints.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "int_array_option"
+    )]
+    pub dragon: Option<Vec<i32>>,
+    /// Position of the bedrock pillar of the exit portal.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "int_array_option"
+    )]
+    pub exit_portal_location: Option<Vec<i32>>,
+    /// Indices of the end gateways not spawned yet, the next one last.
+    #[serde(serialize_with = "pumpkin_nbt::nbt_int_array")]
+    pub gateways: Vec<i32>,
+}
+
+impl Default for DragonFightData {
+    fn default() -> Self {
+        Self {
+            needs_state_scanning: true,
+            dragon_killed: false,
+            previously_killed: false,
+            dragon: None,
+            exit_portal_location: None,
+            gateways: Vec::new(),
+        }
+    }
+}
+
+#[expect(clippy::ref_option)] // The signature serde calls
+fn int_array_option<S: serde::Serializer>(
+    value: &Option<Vec<i32>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => pumpkin_nbt::nbt_int_array(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct WorldGenSettings {
     // the numerical seed of the world
     pub seed: i64,
@@ -102,17 +157,22 @@ pub struct WorldGenSettings {
 }
 
 pub type Dimensions = HashMap<String, Dimension>;
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Dimension {
     pub generator: Generator,
     #[serde(rename = "type")]
     pub dimension_type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Generator {
-    pub settings: String,
-    pub biome_source: BiomeSource,
+    /// The name of the noise settings, or the layers and biome of a flat world. Debug worlds
+    /// have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<NbtTag>,
+    /// Flat and debug worlds have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub biome_source: Option<BiomeSource>,
     #[serde(rename = "type")]
     pub generator_type: String,
 }
@@ -149,11 +209,11 @@ impl WorldGenSettings {
             "minecraft:overworld".to_string(),
             Dimension {
                 generator: Generator {
-                    settings: "minecraft:overworld".to_string(),
-                    biome_source: BiomeSource::WithPreset {
+                    settings: Some(NbtTag::String("minecraft:overworld".to_string())),
+                    biome_source: Some(BiomeSource::WithPreset {
                         preset: "minecraft:overworld".to_string(),
                         biome_type: "minecraft:multi_noise".to_string(),
-                    },
+                    }),
                     generator_type: "minecraft:noise".to_string(),
                 },
                 dimension_type: "minecraft:overworld".to_string(),
@@ -163,11 +223,11 @@ impl WorldGenSettings {
             "minecraft:the_nether".to_string(),
             Dimension {
                 generator: Generator {
-                    settings: "minecraft:nether".to_string(),
-                    biome_source: BiomeSource::WithPreset {
+                    settings: Some(NbtTag::String("minecraft:nether".to_string())),
+                    biome_source: Some(BiomeSource::WithPreset {
                         preset: "minecraft:nether".to_string(),
                         biome_type: "minecraft:multi_noise".to_string(),
-                    },
+                    }),
                     generator_type: "minecraft:noise".to_string(),
                 },
                 dimension_type: "minecraft:the_nether".to_string(),
@@ -177,10 +237,10 @@ impl WorldGenSettings {
             "minecraft:the_end".to_string(),
             Dimension {
                 generator: Generator {
-                    settings: "minecraft:end".to_string(),
-                    biome_source: BiomeSource::Simple {
+                    settings: Some(NbtTag::String("minecraft:end".to_string())),
+                    biome_source: Some(BiomeSource::Simple {
                         biome_type: "minecraft:the_end".to_string(),
-                    },
+                    }),
                     generator_type: "minecraft:noise".to_string(),
                 },
                 dimension_type: "minecraft:the_end".to_string(),
@@ -252,6 +312,7 @@ impl LevelData {
             spawn_pitch: 0.0,
             world_version: WorldVersion::default(),
             level_version: MAXIMUM_SUPPORTED_LEVEL_VERSION,
+            dragon_fight: None,
         }
     }
 
@@ -0,0 +1,56 @@
+use crate::block::registry::BlockActionResult;
+use crate::block::{BlockBehaviour, BlockFuture, NormalUseArgs};
+use crate::server::maps::ServerMaps;
+
+use pumpkin_inventory::cartography_table::CartographyTableScreenHandler;
+use pumpkin_inventory::player::player_inventory::PlayerInventory;
+use pumpkin_inventory::screen_handler::{
+    BoxFuture, InventoryPlayer, ScreenHandlerFactory, SharedScreenHandler,
+};
+use pumpkin_macros::pumpkin_block;
+use pumpkin_util::text::TextComponent;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[pumpkin_block("minecraft:cartography_table")]
+pub struct CartographyTableBlock;
+
+impl BlockBehaviour for CartographyTableBlock {
+    fn normal_use<'a>(&'a self, args: NormalUseArgs<'a>) -> BlockFuture<'a, BlockActionResult> {
+        Box::pin(async move {
+            args.player
+                .open_handled_screen(&CartographyTableScreenFactory {
+                    maps: args.server.maps.clone(),
+                })
+                .await;
+
+            BlockActionResult::Success
+        })
+    }
+}
+
+struct CartographyTableScreenFactory {
+    maps: Arc<ServerMaps>,
+}
+
+impl ScreenHandlerFactory for CartographyTableScreenFactory {
+    fn create_screen_handler<'a>(
+        &'a self,
+        sync_id: u8,
+        player_inventory: &'a Arc<PlayerInventory>,
+        _player: &'a dyn InventoryPlayer,
+    ) -> BoxFuture<'a, Option<SharedScreenHandler>> {
+        Box::pin(async move {
+            let handler =
+                CartographyTableScreenHandler::new(sync_id, player_inventory, self.maps.clone())
+                    .await;
+            let concrete_arc = Arc::new(Mutex::new(handler));
+
+            Some(concrete_arc as SharedScreenHandler)
+        })
+    }
+
+    fn get_display_name(&self) -> TextComponent {
+        TextComponent::translate("container.cartography_table", &[])
+    }
+}
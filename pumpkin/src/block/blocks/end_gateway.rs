@@ -0,0 +1,190 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use pumpkin_data::Block;
+use pumpkin_data::dimension::Dimension;
+use pumpkin_macros::pumpkin_block;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_util::random::legacy_rand::LegacyRand;
+use pumpkin_world::block::entities::end_gateway::EndGatewayBlockEntity;
+use pumpkin_world::generation::{end_gateway_blocks, end_island_blocks};
+use pumpkin_world::world::BlockFlags;
+
+use crate::block::{BlockBehaviour, BlockFuture, OnEntityCollisionArgs, OnSyncedBlockEventArgs};
+use crate::world::World;
+use crate::world::portal::end::END_SPAWN_POINT;
+
+/// The block event showing the beam of the gateway.
+const BEAM_EVENT_TYPE: u8 = 1;
+/// How far from the center of the End the gateways of the main island lead.
+const EXIT_DISTANCE: f64 = 1024.0;
+
+/// A gateway between the main island of the End and its outer islands.
+///
+/// The gateways around the main island find their exit on first use, far out in the direction
+/// they are in, and build a gateway back there. Generated gateways on the outer islands have no
+/// block entity and lead back to the main island.
+#[pumpkin_block("minecraft:end_gateway")]
+pub struct EndGatewayBlock;
+
+impl BlockBehaviour for EndGatewayBlock {
+    fn on_entity_collision<'a>(&'a self, args: OnEntityCollisionArgs<'a>) -> BlockFuture<'a, ()> {
+        Box::pin(async move {
+            let entity = args.entity.get_entity();
+            if entity.portal_cooldown.load(Ordering::Relaxed) > 0 || entity.has_vehicle().await {
+                return;
+            }
+            let world = args.world;
+            let Some(entity) = world.get_entity_by_id(entity.entity_id) else {
+                return;
+            };
+
+            let gateway = world.get_block_entity(args.position).await;
+            let gateway = gateway
+                .as_ref()
+                .and_then(|gateway| gateway.as_any().downcast_ref::<EndGatewayBlockEntity>());
+            let (exit, exact) = match gateway {
+                Some(EndGatewayBlockEntity {
+                    exit_portal: Some(exit),
+                    exact_teleport,
+                    ..
+                }) => (*exit, *exact_teleport),
+                Some(_) if world.dimension == Dimension::THE_END => {
+                    let exit = Self::find_or_create_exit(world, *args.position).await;
+                    world
+                        .add_block_entity(Arc::new(EndGatewayBlockEntity::new(
+                            *args.position,
+                            Some(exit),
+                            false,
+                        )))
+                        .await;
+                    (exit, false)
+                }
+                Some(_) => return,
+                None => (END_SPAWN_POINT, true),
+            };
+
+            let arrival = if exact {
+                exit
+            } else {
+                Self::tallest_block(world, exit.up_height(2), 5, false)
+                    .await
+                    .up()
+            };
+            let entity_data = entity.get_entity();
+            entity_data
+                .portal_cooldown
+                .store(entity_data.default_portal_cooldown(), Ordering::Relaxed);
+            world
+                .add_synced_block_event(*args.position, BEAM_EVENT_TYPE, 0)
+                .await;
+            let pos = arrival.to_f64().add_raw(0.5, 0.0, 0.5);
+            entity.teleport(pos, None, None, world.clone()).await;
+        })
+    }
+
+    fn on_synced_block_event<'a>(
+        &'a self,
+        args: OnSyncedBlockEventArgs<'a>,
+    ) -> BlockFuture<'a, bool> {
+        Box::pin(async move { args.r#type == BEAM_EVENT_TYPE })
+    }
+}
+
+impl EndGatewayBlock {
+    /// Finds the outer island in the direction of the gateway at `origin`, making one if there
+    /// is none, and builds a gateway back to `origin` above it. Returns where that gateway is.
+    async fn find_or_create_exit(world: &Arc<World>, origin: BlockPos) -> BlockPos {
+        let direction = Vector3::new(f64::from(origin.0.x), 0.0, f64::from(origin.0.z)).normalize();
+        let step = direction * 16.0;
+        let mut target = direction * EXIT_DISTANCE;
+        // Back towards the center out of the islands, then outwards to the first one
+        for _ in 0..16 {
+            if Self::is_column_empty(world, target).await {
+                break;
+            }
+            target = target.sub(&step);
+        }
+        for _ in 0..16 {
+            if !Self::is_column_empty(world, target).await {
+                break;
+            }
+            target = target.add(&step);
+        }
+
+        let mut island = BlockPos::floored(target.x + 0.5, 75.0, target.z + 0.5);
+        if Self::is_column_empty(world, target).await {
+            let mut random = LegacyRand::from_seed(island.as_long() as u64);
+            for pos in end_island_blocks(&mut random, island) {
+                world
+                    .set_block_state(
+                        &pos,
+                        Block::END_STONE.default_state.id,
+                        BlockFlags::NOTIFY_ALL,
+                    )
+                    .await;
+            }
+        } else {
+            island.0.y = world
+                .get_motion_blocking_height(island.0.x, island.0.z)
+                .await;
+        }
+
+        let exit = Self::tallest_block(world, island, 16, true)
+            .await
+            .up_height(10);
+        for (pos, state) in end_gateway_blocks(exit) {
+            world
+                .set_block_state(&pos, state.id, BlockFlags::NOTIFY_ALL)
+                .await;
+        }
+        world
+            .add_block_entity(Arc::new(EndGatewayBlockEntity::new(
+                exit,
+                Some(origin),
+                false,
+            )))
+            .await;
+        exit
+    }
+
+    async fn is_column_empty(world: &World, pos: Vector3<f64>) -> bool {
+        let pos = BlockPos::floored(pos.x, 0.0, pos.z);
+        world.get_motion_blocking_height(pos.0.x, pos.0.z).await <= world.min_y
+    }
+
+    /// The highest block within `radius` around `center`, or `center` if there is none. Bedrock
+    /// only counts if `allow_bedrock`, so players don't arrive on a gateway's frame.
+    async fn tallest_block(
+        world: &World,
+        center: BlockPos,
+        radius: i32,
+        allow_bedrock: bool,
+    ) -> BlockPos {
+        let mut tallest: Option<BlockPos> = None;
+        for column in BlockPos::iterate(
+            center.offset(Vector3::new(-radius, 0, -radius)),
+            center.offset(Vector3::new(radius, 0, radius)),
+        ) {
+            let top = world
+                .get_motion_blocking_height(column.0.x, column.0.z)
+                .await;
+            for y in (world.min_y..top).rev() {
+                if tallest.is_some_and(|tallest| tallest.0.y >= y) {
+                    break;
+                }
+                let pos = BlockPos::new(column.0.x, y, column.0.z);
+                let (block, state) = world.get_block_and_state(&pos).await;
+                if block == &Block::BEDROCK && !allow_bedrock {
+                    continue;
+                }
+                if state.is_full_cube() {
+                    tallest = Some(pos);
+                    break;
+                }
+            }
+        }
+        tallest.unwrap_or(center)
+    }
+}
@@ -1,6 +1,9 @@
 use crate::block::BlockFuture;
 use crate::block::GetStateForNeighborUpdateArgs;
+use crate::block::NormalUseArgs;
 use crate::block::OnPlaceArgs;
+use crate::block::registry::BlockActionResult;
+use crate::entity::leash;
 use pumpkin_data::BlockDirection;
 use pumpkin_data::BlockState;
 use pumpkin_data::block_properties::BlockProperties;
@@ -41,6 +44,17 @@ impl BlockBehaviour for FenceBlock {
             compute_fence_state(fence_props, args.world, args.block, args.position).await
         })
     }
+
+    fn normal_use<'a>(&'a self, args: NormalUseArgs<'a>) -> BlockFuture<'a, BlockActionResult> {
+        Box::pin(async move {
+            // Ties the mobs the player leads to the fence
+            if leash::attach_held_mobs_to_fence(args.world, args.player, *args.position).await {
+                BlockActionResult::Success
+            } else {
+                BlockActionResult::Pass
+            }
+        })
+    }
 }
 
 pub async fn compute_fence_state(
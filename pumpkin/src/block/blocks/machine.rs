@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use crate::block::registry::{BlockActionResult, BlockRegistry};
+use crate::block::{BlockBehaviour, BlockFuture, NormalUseArgs, PlacedArgs};
+
+use pumpkin_config::machines::MachinesConfig;
+use pumpkin_data::Block;
+use pumpkin_inventory::generic_container_screen_handler::create_generic_3x3;
+use pumpkin_inventory::player::player_inventory::PlayerInventory;
+use pumpkin_inventory::screen_handler::{
+    BoxFuture, InventoryPlayer, ScreenHandlerFactory, SharedScreenHandler,
+};
+use pumpkin_util::text::TextComponent;
+use pumpkin_world::block::entities::BlockEntity;
+use pumpkin_world::block::entities::machine::{
+    MachineBlockEntity, MachineDefinition, register_machine,
+};
+use pumpkin_world::inventory::Inventory;
+use tokio::sync::Mutex;
+
+struct MachineScreenFactory(Arc<dyn Inventory>, TextComponent);
+
+impl ScreenHandlerFactory for MachineScreenFactory {
+    fn create_screen_handler<'a>(
+        &'a self,
+        sync_id: u8,
+        player_inventory: &'a Arc<PlayerInventory>,
+        _player: &'a dyn InventoryPlayer,
+    ) -> BoxFuture<'a, Option<SharedScreenHandler>> {
+        Box::pin(async move {
+            let handler = create_generic_3x3(sync_id, player_inventory, self.0.clone()).await;
+            let concrete_arc = Arc::new(Mutex::new(handler));
+
+            Some(concrete_arc as SharedScreenHandler)
+        })
+    }
+
+    fn get_display_name(&self) -> TextComponent {
+        self.1.clone()
+    }
+}
+
+/// A block turned into a machine by the config, replacing its vanilla behaviour.
+pub struct MachineBlock {
+    machine: Arc<MachineDefinition>,
+}
+
+impl BlockBehaviour for MachineBlock {
+    fn normal_use<'a>(&'a self, args: NormalUseArgs<'a>) -> BlockFuture<'a, BlockActionResult> {
+        Box::pin(async move {
+            let block_entity = match args.world.get_block_entity(args.position).await {
+                Some(block_entity) => block_entity,
+                // Placed before the block became a machine
+                None => {
+                    let block_entity: Arc<dyn BlockEntity> = Arc::new(MachineBlockEntity::new(
+                        *args.position,
+                        self.machine.clone(),
+                    ));
+                    args.world.add_block_entity(block_entity.clone()).await;
+                    block_entity
+                }
+            };
+            if let Some(inventory) = block_entity.get_inventory() {
+                let title = if self.machine.title.is_empty() {
+                    &self.machine.name
+                } else {
+                    &self.machine.title
+                };
+                args.player
+                    .open_handled_screen(&MachineScreenFactory(
+                        inventory,
+                        TextComponent::text(title.clone()),
+                    ))
+                    .await;
+            }
+
+            BlockActionResult::Success
+        })
+    }
+
+    fn placed<'a>(&'a self, args: PlacedArgs<'a>) -> BlockFuture<'a, ()> {
+        Box::pin(async move {
+            let block_entity = MachineBlockEntity::new(*args.position, self.machine.clone());
+            args.world.add_block_entity(Arc::new(block_entity)).await;
+        })
+    }
+}
+
+/// Registers the machines of the config, skipping and logging invalid ones.
+pub fn register_machines(registry: &mut BlockRegistry, config: &MachinesConfig) {
+    for machine in &config.machines {
+        let Some(block) = Block::from_name(&machine.block) else {
+            log::warn!(
+                "Skipped the machine {}: unknown block {}",
+                machine.name,
+                machine.block
+            );
+            continue;
+        };
+        match MachineDefinition::from_config(machine) {
+            Ok(definition) => {
+                let machine = register_machine(definition);
+                log::debug!("Registered the machine {} as {}", machine.name, block.name);
+                registry.register_dynamic(block, Arc::new(MachineBlock { machine }));
+            }
+            Err(err) => log::warn!("Skipped a machine: {err}"),
+        }
+    }
+}
@@ -7,6 +7,7 @@ pub mod campfire;
 pub mod candle_cakes;
 pub mod candles;
 pub mod carpet;
+pub mod cartography_table;
 pub mod carved_pumpkin;
 pub mod chain;
 pub mod chests;
@@ -16,6 +17,7 @@ pub mod composter;
 pub mod crafting_table;
 pub mod dirt_path;
 pub mod doors;
+pub mod end_gateway;
 pub mod end_portal;
 pub mod end_portal_frame;
 pub mod end_rod;
@@ -36,6 +38,7 @@ pub mod jukebox;
 pub mod ladder;
 pub mod lanterns;
 pub mod logs;
+pub mod machine;
 pub mod mangrove_roots;
 pub mod nether_portal;
 pub mod note;
@@ -56,6 +59,7 @@ pub mod stairs;
 pub mod tnt;
 pub mod torches;
 pub mod trapdoor;
+pub mod turtle_egg;
 pub mod vine;
 pub mod walls;
 pub mod wither_skull;
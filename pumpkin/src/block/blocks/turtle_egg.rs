@@ -0,0 +1,282 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use pumpkin_data::{
+    Block,
+    block_properties::{
+        BlockProperties, EnumVariants, Integer0To2, Integer1To4, TurtleEggLikeProperties,
+    },
+    entity::{EntityPose, EntityType},
+    sound::{Sound, SoundCategory},
+    tag::{self, Taggable},
+    world::WorldEvent,
+};
+use pumpkin_macros::pumpkin_block;
+use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
+use pumpkin_world::{BlockStateId, world::BlockFlags};
+use rand::RngExt;
+
+use crate::{
+    block::{
+        BlockBehaviour, BlockFuture, BlockIsReplacing, CanUpdateAtArgs, OnLandedUponArgs,
+        OnPlaceArgs, OnSteppedOnArgs, RandomTickArgs,
+    },
+    entity::{Entity, EntityBase, passive::turtle::TurtleEntity},
+    world::World,
+};
+
+#[pumpkin_block("minecraft:turtle_egg")]
+pub struct TurtleEggBlock;
+
+impl TurtleEggBlock {
+    /// Whether turtle eggs at `position` hatch, which they only do on sand.
+    pub async fn is_sand_below(world: &World, position: &BlockPos) -> bool {
+        world
+            .get_block(&position.down())
+            .await
+            .has_tag(&tag::Block::MINECRAFT_SAND)
+    }
+
+    /// Whether `entity` tramples turtle eggs, which mobs only do if mob griefing is on.
+    fn breaks_eggs(world: &World, entity: &dyn EntityBase) -> bool {
+        let entity_type = entity.get_entity().entity_type;
+        if entity_type == &EntityType::TURTLE || entity_type == &EntityType::BAT {
+            return false;
+        }
+        entity.get_living_entity().is_some()
+            && (entity.get_player().is_some() || world.level_info.load().game_rules.mob_griefing)
+    }
+
+    /// Breaks one of the eggs at `position` one time in `inverse_chance`.
+    async fn try_break_egg(
+        world: &Arc<World>,
+        position: &BlockPos,
+        entity: &dyn EntityBase,
+        inverse_chance: u32,
+    ) {
+        if !Self::breaks_eggs(world, entity) || rand::rng().random_range(0..inverse_chance) != 0 {
+            return;
+        }
+        let (block, state) = world.get_block_and_state_id(position).await;
+        if block != &Block::TURTLE_EGG {
+            return;
+        }
+        let pitch = rand::rng().random::<f32>().mul_add(0.2, 0.9);
+        world
+            .play_sound_fine(
+                Sound::EntityTurtleEggBreak,
+                SoundCategory::Blocks,
+                &position.to_centered_f64(),
+                0.7,
+                pitch,
+            )
+            .await;
+
+        let properties = TurtleEggLikeProperties::from_state_id(state, block);
+        if let Some(properties) = Self::remove_egg(properties) {
+            world
+                .set_block_state(
+                    position,
+                    properties.to_state_id(block),
+                    BlockFlags::NOTIFY_LISTENERS,
+                )
+                .await;
+            world
+                .sync_world_event(WorldEvent::BlockBroken, *position, i32::from(state))
+                .await;
+        } else {
+            world
+                .break_block(position, None, BlockFlags::SKIP_DROPS)
+                .await;
+        }
+    }
+
+    /// The eggs left after one broke, or `None` if it was the last one.
+    fn remove_egg(mut properties: TurtleEggLikeProperties) -> Option<TurtleEggLikeProperties> {
+        let eggs = properties.eggs.to_index();
+        if eggs == 0 {
+            return None;
+        }
+        properties.eggs = Integer1To4::from_index(eggs - 1);
+        Some(properties)
+    }
+
+    /// The eggs with one more crack, or `None` if they hatch instead.
+    fn crack(mut properties: TurtleEggLikeProperties) -> Option<TurtleEggLikeProperties> {
+        if properties.hatch == Integer0To2::L2 {
+            return None;
+        }
+        properties.hatch = Integer0To2::from_index(properties.hatch.to_index() + 1);
+        Some(properties)
+    }
+
+    const fn is_dawn(sky_angle: f64) -> bool {
+        sky_angle > 0.65 && sky_angle < 0.69
+    }
+
+    /// Whether eggs crack in this random tick, which they mostly do at dawn.
+    async fn should_hatch_progress(world: &World) -> bool {
+        let sky_angle = world.level_time.lock().await.sky_angle();
+        Self::is_dawn(sky_angle) || rand::rng().random_range(0..500) == 0
+    }
+
+    async fn hatch(world: &Arc<World>, position: &BlockPos, state: BlockStateId, eggs: usize) {
+        world
+            .set_block_state(
+                position,
+                Block::AIR.default_state.id,
+                BlockFlags::NOTIFY_ALL,
+            )
+            .await;
+        for i in 0..eggs {
+            world
+                .sync_world_event(WorldEvent::BlockBroken, *position, i32::from(state))
+                .await;
+            let spawn_pos = Vector3::new(
+                f64::from(position.0.x) + 0.3 + i as f64 * 0.2,
+                f64::from(position.0.y),
+                f64::from(position.0.z) + 0.3,
+            );
+            let turtle =
+                TurtleEntity::new(Entity::new(world.clone(), spawn_pos, &EntityType::TURTLE)).await;
+            turtle
+                .mob_entity
+                .living_entity
+                .entity
+                .age
+                .store(TurtleEntity::HATCHLING_AGE, Ordering::Relaxed);
+            turtle.set_home_pos(*position);
+            world.spawn_entity(turtle).await;
+        }
+    }
+}
+
+impl BlockBehaviour for TurtleEggBlock {
+    fn on_place<'a>(&'a self, args: OnPlaceArgs<'a>) -> BlockFuture<'a, BlockStateId> {
+        Box::pin(async move {
+            if let BlockIsReplacing::Itself(state_id) = args.replacing {
+                let mut properties = TurtleEggLikeProperties::from_state_id(state_id, args.block);
+                if properties.eggs.to_index() < 3 {
+                    properties.eggs = Integer1To4::from_index(properties.eggs.to_index() + 1);
+                }
+                return properties.to_state_id(args.block);
+            }
+            args.block.default_state.id
+        })
+    }
+
+    fn can_update_at<'a>(&'a self, args: CanUpdateAtArgs<'a>) -> BlockFuture<'a, bool> {
+        Box::pin(async move {
+            args.player.get_entity().pose.load() != EntityPose::Crouching
+                && args.world.get_block(args.position).await == &Block::TURTLE_EGG
+                && TurtleEggLikeProperties::from_state_id(args.state_id, args.block).eggs
+                    != Integer1To4::L4
+        })
+    }
+
+    fn random_tick<'a>(&'a self, args: RandomTickArgs<'a>) -> BlockFuture<'a, ()> {
+        Box::pin(async move {
+            if !Self::should_hatch_progress(args.world).await
+                || !Self::is_sand_below(args.world, args.position).await
+            {
+                return;
+            }
+            let state = args.world.get_block_state_id(args.position).await;
+            let properties = TurtleEggLikeProperties::from_state_id(state, args.block);
+            let pitch = rand::rng().random::<f32>().mul_add(0.2, 0.9);
+            if let Some(cracked) = Self::crack(properties) {
+                args.world
+                    .play_sound_fine(
+                        Sound::EntityTurtleEggCrack,
+                        SoundCategory::Blocks,
+                        &args.position.to_centered_f64(),
+                        0.7,
+                        pitch,
+                    )
+                    .await;
+                args.world
+                    .set_block_state(
+                        args.position,
+                        cracked.to_state_id(args.block),
+                        BlockFlags::NOTIFY_LISTENERS,
+                    )
+                    .await;
+            } else {
+                args.world
+                    .play_sound_fine(
+                        Sound::EntityTurtleEggHatch,
+                        SoundCategory::Blocks,
+                        &args.position.to_centered_f64(),
+                        0.7,
+                        pitch,
+                    )
+                    .await;
+                let eggs = properties.eggs.to_index() as usize + 1;
+                Self::hatch(args.world, args.position, state, eggs).await;
+            }
+        })
+    }
+
+    fn on_stepped_on<'a>(&'a self, args: OnSteppedOnArgs<'a>) -> BlockFuture<'a, ()> {
+        Box::pin(async move {
+            Self::try_break_egg(args.world, args.position, args.entity, 100).await;
+        })
+    }
+
+    fn on_landed_upon<'a>(&'a self, args: OnLandedUponArgs<'a>) -> BlockFuture<'a, ()> {
+        Box::pin(async move {
+            let entity = args.entity.get_entity();
+            // Zombies only break eggs on purpose
+            if entity.entity_type != &EntityType::ZOMBIE {
+                let position = entity.block_pos.load();
+                Self::try_break_egg(args.world, &position, args.entity, 3).await;
+            }
+            if let Some(living) = args.entity.get_living_entity() {
+                living.handle_fall_damage(args.fall_distance, 1.0).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::time::LevelTime;
+
+    #[test]
+    fn eggs_crack_twice_before_hatching() {
+        let eggs = TurtleEggLikeProperties::default(&Block::TURTLE_EGG);
+        assert_eq!(eggs.hatch, Integer0To2::L0);
+        let eggs = TurtleEggBlock::crack(eggs).unwrap();
+        assert_eq!(eggs.hatch, Integer0To2::L1);
+        let eggs = TurtleEggBlock::crack(eggs).unwrap();
+        assert_eq!(eggs.hatch, Integer0To2::L2);
+        assert_eq!(TurtleEggBlock::crack(eggs), None);
+    }
+
+    #[test]
+    fn breaking_the_last_egg_removes_the_block() {
+        let mut eggs = TurtleEggLikeProperties::default(&Block::TURTLE_EGG);
+        eggs.eggs = Integer1To4::L3;
+        eggs.hatch = Integer0To2::L1;
+        let eggs = TurtleEggBlock::remove_egg(eggs).unwrap();
+        assert_eq!(eggs.eggs, Integer1To4::L2);
+        assert_eq!(eggs.hatch, Integer0To2::L1);
+        let eggs = TurtleEggBlock::remove_egg(eggs).unwrap();
+        assert_eq!(eggs.eggs, Integer1To4::L1);
+        assert_eq!(TurtleEggBlock::remove_egg(eggs), None);
+    }
+
+    #[test]
+    fn eggs_crack_before_sunrise() {
+        let at = |time_of_day| {
+            let mut time = LevelTime::new();
+            time.time_of_day = time_of_day;
+            TurtleEggBlock::is_dawn(time.sky_angle())
+        };
+        assert!(at(21_500));
+        assert!(!at(0));
+        assert!(!at(6000));
+        assert!(!at(18_000));
+    }
+}
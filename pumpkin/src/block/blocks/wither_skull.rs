@@ -1,4 +1,5 @@
 use pumpkin_data::{Block, BlockDirection, entity::EntityType, world::WorldEvent};
+use pumpkin_util::Difficulty;
 use pumpkin_world::{BlockStateId, world::BlockFlags};
 
 use crate::{
@@ -26,6 +27,9 @@ impl BlockBehaviour for WitherSkeletonSkullBlock {
         Box::pin(async move {
             let world = args.world;
             let pos = args.position;
+            if world.get_difficulty() == Difficulty::Peaceful {
+                return;
+            }
 
             let is_soul_block =
                 |block: &Block| block == &Block::SOUL_SAND || block == &Block::SOUL_SOIL;
@@ -85,11 +89,19 @@ impl BlockBehaviour for WitherSkeletonSkullBlock {
 
                         let entity = Entity::new(
                             world.clone(),
-                            top_middle.to_centered_f64(),
+                            base.to_f64().add_raw(0.5, 0.55, 0.5),
                             &EntityType::WITHER,
                         );
+                        let yaw = if dir == BlockDirection::North {
+                            0.0
+                        } else {
+                            90.0
+                        };
+                        entity.set_rotation(yaw, 0.0);
+                        entity.body_yaw.store(yaw);
                         let wither = WitherEntity::new(entity).await;
-                        world.spawn_entity(wither).await;
+                        world.spawn_entity(wither.clone()).await;
+                        wither.make_invulnerable().await;
                         return;
                     }
                 }
@@ -100,6 +100,11 @@ pub trait BlockBehaviour: Send + Sync {
         })
     }
 
+    /// onSteppedOn in source code, called while an entity walks on top of the block
+    fn on_stepped_on<'a>(&'a self, _args: OnSteppedOnArgs<'a>) -> BlockFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
     fn broken<'a>(&'a self, _args: BrokenArgs<'a>) -> BlockFuture<'a, ()> {
         Box::pin(async {})
     }
@@ -272,6 +277,14 @@ pub struct OnLandedUponArgs<'a> {
     pub entity: &'a dyn EntityBase,
 }
 
+pub struct OnSteppedOnArgs<'a> {
+    pub world: &'a Arc<World>,
+    pub block: &'a Block,
+    pub state: &'a BlockState,
+    pub position: &'a BlockPos,
+    pub entity: &'a dyn EntityBase,
+}
+
 pub struct BrokenArgs<'a> {
     pub block: &'a Block,
     pub player: &'a Arc<Player>,
@@ -10,6 +10,7 @@ use crate::block::blocks::command::CommandBlock;
 use crate::block::blocks::composter::ComposterBlock;
 use crate::block::blocks::dirt_path::DirtPathBlock;
 use crate::block::blocks::doors::DoorBlock;
+use crate::block::blocks::end_gateway::EndGatewayBlock;
 use crate::block::blocks::end_portal::EndPortalBlock;
 use crate::block::blocks::end_portal_frame::EndPortalFrameBlock;
 use crate::block::blocks::falling::FallingBlock;
@@ -78,6 +79,7 @@ use crate::block::blocks::stairs::StairBlock;
 use crate::block::blocks::tnt::TNTBlock;
 use crate::block::blocks::torches::TorchBlock;
 use crate::block::blocks::trapdoor::TrapDoorBlock;
+use crate::block::blocks::turtle_egg::TurtleEggBlock;
 use crate::block::blocks::vine::VineBlock;
 use crate::block::blocks::walls::WallBlock;
 use crate::block::blocks::wither_skull::WitherSkeletonSkullBlock;
@@ -90,6 +92,7 @@ use crate::entity::EntityBase;
 use crate::entity::player::Player;
 use crate::server::Server;
 use crate::world::World;
+use pumpkin_config::machines::MachinesConfig;
 use pumpkin_data::fluid::Fluid;
 use pumpkin_data::item::Item;
 use pumpkin_data::{Block, BlockDirection, BlockState};
@@ -133,6 +136,7 @@ use super::{
     PrepareArgs, UseWithItemArgs,
 };
 use crate::block::blocks::blast_furnace::BlastFurnaceBlock;
+use crate::block::blocks::cartography_table::CartographyTableBlock;
 use crate::block::blocks::chain::ChainBlock;
 use crate::block::blocks::crafting_table::CraftingTableBlock;
 use crate::block::blocks::end_rod::EndRodBlock;
@@ -142,13 +146,26 @@ use crate::block::blocks::jukebox::JukeboxBlock;
 use crate::block::blocks::ladder::LadderBlock;
 use crate::block::blocks::lanterns::LanternBlock;
 use crate::block::blocks::lectern::LecternBlock;
+use crate::block::blocks::machine::register_machines;
 use crate::block::blocks::shulker_box::ShulkerBoxBlock;
 use crate::block::blocks::skull_block::SkullBlock;
 use crate::block::blocks::smoker::SmokerBlock;
 
 #[must_use]
-#[expect(clippy::too_many_lines)]
 pub fn default_registry() -> Arc<BlockRegistry> {
+    Arc::new(vanilla_registry())
+}
+
+/// The default registry, with the machines of the config on top.
+#[must_use]
+pub fn registry_with_machines(machines: &MachinesConfig) -> Arc<BlockRegistry> {
+    let mut manager = vanilla_registry();
+    register_machines(&mut manager, machines);
+    Arc::new(manager)
+}
+
+#[expect(clippy::too_many_lines)]
+fn vanilla_registry() -> BlockRegistry {
     let mut manager = BlockRegistry::default();
 
     // Blocks
@@ -166,6 +183,7 @@ pub fn default_registry() -> Arc<BlockRegistry> {
     manager.register(CopperChestBlock);
     manager.register(EnderChestBlock);
     manager.register(CraftingTableBlock);
+    manager.register(CartographyTableBlock);
     manager.register(DirtPathBlock);
     manager.register(DoorBlock);
     manager.register(FarmlandBlock);
@@ -208,6 +226,7 @@ pub fn default_registry() -> Arc<BlockRegistry> {
     manager.register(WheatBlock);
     manager.register(TorchBlock);
     manager.register(TrapDoorBlock);
+    manager.register(TurtleEggBlock);
     manager.register(MushroomPlantBlock);
     manager.register(FlowerbedBlock);
     manager.register(LeafLitterBlock);
@@ -224,6 +243,7 @@ pub fn default_registry() -> Arc<BlockRegistry> {
     manager.register(ComposterBlock);
     manager.register(PressurePlateBlock);
     manager.register(WeightedPressurePlateBlock);
+    manager.register(EndGatewayBlock);
     manager.register(EndPortalBlock);
     manager.register(SpawnerBlock);
     manager.register(EndPortalFrameBlock);
@@ -286,7 +306,7 @@ pub fn default_registry() -> Arc<BlockRegistry> {
     // Fluids
     manager.register_fluid(FlowingWater);
     manager.register_fluid(FlowingLava);
-    Arc::new(manager)
+    manager
 }
 
 // ActionResult.java
@@ -354,6 +374,11 @@ impl BlockRegistry {
         }
     }
 
+    /// Registers a behaviour made at runtime, replacing the one `block` had.
+    pub fn register_dynamic(&mut self, block: &Block, behaviour: Arc<dyn BlockBehaviour>) {
+        self.blocks.insert(block.id, behaviour);
+    }
+
     pub fn register_fluid<T: FluidBehaviour + BlockMetadata + 'static>(&mut self, fluid: T) {
         let ids = T::ids();
         let val = Arc::new(fluid);
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use pumpkin_protocol::java::client::play::{ArgumentType, SuggestionProviders};
+use pumpkin_world::dimension::dimension_from_name;
+
+use crate::command::CommandSender;
+use crate::command::args::{
+    Arg, ArgumentConsumer, ConsumeResult, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+use crate::command::dispatcher::CommandError;
+use crate::command::tree::RawArgs;
+use crate::server::Server;
+use crate::world::World;
+
+/// Consumes the name of a dimension, vanilla or from a datapack, and resolves it to its world.
+pub struct DimensionArgumentConsumer;
+
+impl GetClientSideArgParser for DimensionArgumentConsumer {
+    fn get_client_side_parser(&self) -> ArgumentType<'_> {
+        // The client suggests the dimensions of the login packet
+        ArgumentType::Dimension
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<SuggestionProviders> {
+        None
+    }
+}
+
+impl ArgumentConsumer for DimensionArgumentConsumer {
+    fn consume<'a, 'b>(
+        &'a self,
+        _sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'b mut RawArgs<'a>,
+    ) -> ConsumeResult<'a> {
+        let world = args.pop().and_then(|s| {
+            let dimension = if s.contains(':') {
+                dimension_from_name(s)
+            } else {
+                dimension_from_name(&format!("minecraft:{s}"))
+            }?;
+            server
+                .worlds
+                .load()
+                .iter()
+                .find(|world| world.dimension == dimension)
+                .cloned()
+        });
+
+        Box::pin(async move { world.map(Arg::World) })
+    }
+}
+
+impl DefaultNameArgConsumer for DimensionArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "dimension"
+    }
+}
+
+impl<'a> FindArg<'a> for DimensionArgumentConsumer {
+    type Data = Arc<World>;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::World(world)) => Ok(world.clone()),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -21,6 +21,7 @@ use super::{
     tree::{CommandTree, RawArgs},
 };
 use crate::entity::EntityBase;
+use crate::world::World;
 use crate::world::bossbar::{BossbarColor, BossbarDivisions};
 use crate::{entity::player::Player, server::Server};
 
@@ -34,6 +35,7 @@ pub mod bounded_num;
 pub mod command;
 mod coordinate;
 pub mod difficulty;
+pub mod dimension;
 pub mod entities;
 pub mod entity;
 pub mod entity_anchor;
@@ -123,6 +125,7 @@ pub enum Arg<'a> {
     Effect(&'static StatusEffect),
     Enchantment(&'static Enchantment),
     EntityAnchor(EntityAnchor),
+    World(Arc<World>),
 }
 
 /// see [`crate::commands::tree::builder::argument`] and [`CommandTree::execute`]/[`crate::commands::tree::builder::NonLeafNodeBuilder::execute`]
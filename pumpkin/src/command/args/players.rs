@@ -42,10 +42,7 @@ impl ArgumentConsumer for PlayersArgumentConsumer {
         };
 
         let sync_result: Option<Vec<Arc<Player>>> = match s {
-            "@s" => match sender {
-                CommandSender::Player(p) => Some(vec![p.clone()]),
-                _ => None,
-            },
+            "@s" => sender.as_player().map(|p| vec![p]),
             #[expect(clippy::match_same_arms)]
             // todo: implement for non-players and remove this line
             "@n" | "@p" => match sender {
@@ -1,9 +1,8 @@
-use pumpkin_data::sound::Sound;
 use pumpkin_protocol::java::client::play::{ArgumentType, SuggestionProviders};
-use pumpkin_util::text::TextComponent;
 
 use crate::{
     command::{args::ConsumeResult, dispatcher::CommandError},
+    entity::feedback::SoundId,
     server::Server,
 };
 
@@ -47,20 +46,12 @@ impl DefaultNameArgConsumer for SoundArgumentConsumer {
 }
 
 impl<'a> FindArg<'a> for SoundArgumentConsumer {
-    type Data = Sound;
+    type Data = SoundId;
 
     fn find_arg(args: &'a super::ConsumedArgs, name: &str) -> Result<Self::Data, CommandError> {
         match args.get(name) {
-            Some(Arg::Block(name)) => {
-                Sound::from_name(name.strip_prefix("minecraft:").unwrap_or(name)).map_or_else(
-                    || {
-                        Err(CommandError::CommandFailed(TextComponent::text(format!(
-                            "Sound {name} does not exist."
-                        ))))
-                    },
-                    Result::Ok,
-                )
-            }
+            // Sounds that are not vanilla may come with the server resource pack
+            Some(Arg::Block(name)) => Ok(SoundId::from_name(name)),
             _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
         }
     }
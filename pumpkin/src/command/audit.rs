@@ -0,0 +1,39 @@
+//! Audit trail for privileged inspection and editing commands.
+//!
+//! Entries go to the regular log under the `audit` target and are appended to `logs/audit.log`,
+//! so they survive log rotation and can be reviewed separately.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use time::OffsetDateTime;
+
+use crate::command::CommandSender;
+
+const AUDIT_LOG: &str = "logs/audit.log";
+
+/// Records that `sender` performed `action`.
+pub fn record(sender: &CommandSender, action: &str) {
+    log::info!(target: "audit", "{sender} {action}");
+
+    let now = OffsetDateTime::now_utc();
+    let line = format!(
+        "[{:04}-{:02}-{:02} {:02}:{:02}:{:02}] {sender}: {action}\n",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    let path = Path::new(AUDIT_LOG);
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| OpenOptions::new().create(true).append(true).open(path))
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+    if let Err(err) = result {
+        log::warn!("Failed to write audit log: {err}");
+    }
+}
@@ -0,0 +1,195 @@
+use pumpkin_config::ai::{AiCategory, AiCategoryProviders};
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::simple::SimpleArgConsumer;
+use crate::command::args::{Arg, ConsumedArgs};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{NonLeafNodeBuilder, argument, literal};
+use crate::command::{
+    CommandError, CommandExecutor, CommandResult, CommandSender, audit, resolve_world,
+};
+use crate::server::Server;
+use CommandError::{CommandFailed, InvalidConsumption};
+
+const NAMES: [&str; 1] = ["aiprovider"];
+
+const DESCRIPTION: &str = "Shows and changes the AI providers of new mobs.";
+
+const ARG_PROVIDER: &str = "provider";
+const ARG_WORLD: &str = "world";
+
+#[derive(Clone, Copy)]
+enum Action {
+    List,
+    Reset,
+    Set {
+        world: bool,
+        category: Option<AiCategory>,
+    },
+}
+
+fn describe_categories(categories: &AiCategoryProviders) -> String {
+    AiCategory::ALL
+        .into_iter()
+        .filter_map(|category| {
+            categories
+                .get(category)
+                .map(|provider| format!("{}: {provider}", category.name()))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn list(sender: &CommandSender, server: &Server) {
+    let registry = &server.ai_providers;
+    let selection = registry.selection();
+    sender
+        .send_message(
+            TextComponent::text(format!("Default provider: {}", selection.provider))
+                .color_named(NamedColor::Gold),
+        )
+        .await;
+    let categories = describe_categories(&selection.categories);
+    if !categories.is_empty() {
+        sender
+            .send_message(TextComponent::text(format!("  {categories}")))
+            .await;
+    }
+
+    let mut worlds: Vec<_> = selection.worlds.iter().collect();
+    worlds.sort_by_key(|(name, _)| *name);
+    for (name, world) in worlds {
+        let mut overrides = Vec::new();
+        if let Some(provider) = &world.provider {
+            overrides.push(provider.clone());
+        }
+        let categories = describe_categories(&world.categories);
+        if !categories.is_empty() {
+            overrides.push(categories);
+        }
+        if !overrides.is_empty() {
+            sender
+                .send_message(TextComponent::text(format!(
+                    "  {name}: {}",
+                    overrides.join(", ")
+                )))
+                .await;
+        }
+    }
+
+    sender
+        .send_message(
+            TextComponent::text(format!("Available: {}", registry.names().join(", ")))
+                .color_named(NamedColor::Gray),
+        )
+        .await;
+}
+
+struct Executor(Action);
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let (world, category) = match self.0 {
+                Action::List => {
+                    list(sender, server).await;
+                    return Ok(1);
+                }
+                Action::Reset => {
+                    server.ai_providers.reset();
+                    audit::record(sender, "reset the AI providers to the config");
+                    sender
+                        .send_message(TextComponent::text(
+                            "Reset the AI providers to the config, new mobs use them from now on.",
+                        ))
+                        .await;
+                    return Ok(1);
+                }
+                Action::Set { world, category } => (world, category),
+            };
+
+            let Some(Arg::Simple(provider)) = args.get(&ARG_PROVIDER) else {
+                return Err(InvalidConsumption(Some(ARG_PROVIDER.into())));
+            };
+            let world = if world {
+                let Some(Arg::Simple(world)) = args.get(&ARG_WORLD) else {
+                    return Err(InvalidConsumption(Some(ARG_WORLD.into())));
+                };
+                Some(
+                    resolve_world(sender, server, Some(world))?
+                        .dimension
+                        .minecraft_name,
+                )
+            } else {
+                None
+            };
+            if !server.ai_providers.select(provider, world, category) {
+                return Err(CommandFailed(TextComponent::text(format!(
+                    "Unknown AI provider: {provider}. Available: {}",
+                    server.ai_providers.names().join(", ")
+                ))));
+            }
+
+            let mut target = category.map_or_else(
+                || "mobs".to_string(),
+                |category| format!("{} mobs", category.name()),
+            );
+            if let Some(world) = world {
+                target = format!("{target} in {world}");
+            }
+            audit::record(
+                sender,
+                &format!("set the AI provider of {target} to {provider}"),
+            );
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "New {target} now use the {provider} AI provider."
+                    ))
+                    .color_named(NamedColor::Green),
+                )
+                .await;
+            Ok(1)
+        })
+    }
+}
+
+/// Adds the `hostile`, `passive` and `water` literals below `node`.
+fn with_categories(mut node: NonLeafNodeBuilder, world: bool) -> NonLeafNodeBuilder {
+    for category in AiCategory::ALL {
+        node = node.then(literal(category.name()).execute(Executor(Action::Set {
+            world,
+            category: Some(category),
+        })));
+    }
+    node
+}
+
+pub fn init_command_tree() -> CommandTree {
+    let world = with_categories(
+        argument(ARG_WORLD, SimpleArgConsumer).execute(Executor(Action::Set {
+            world: true,
+            category: None,
+        })),
+        true,
+    );
+    let provider = with_categories(
+        argument(ARG_PROVIDER, SimpleArgConsumer).execute(Executor(Action::Set {
+            world: false,
+            category: None,
+        })),
+        false,
+    )
+    .then(literal("world").then(world));
+
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(literal("list").execute(Executor(Action::List)))
+        .then(literal("reset").execute(Executor(Action::Reset)))
+        .then(literal("set").then(provider))
+}
@@ -0,0 +1,134 @@
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::simple::SimpleArgConsumer;
+use crate::command::args::{Arg, ConsumedArgs};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument, literal};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender};
+use crate::net::traffic::format_bytes;
+use CommandError::{CommandFailed, InvalidConsumption};
+
+const NAMES: [&str; 1] = ["backup"];
+
+const DESCRIPTION: &str = "Creates, lists and restores world backups.";
+
+const ARG_NAME: &str = "name";
+
+struct NowExecutor;
+
+impl CommandExecutor for NowExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            sender
+                .send_message(TextComponent::text("Creating backup..."))
+                .await;
+            let info = server
+                .backups
+                .create_backup(server)
+                .await
+                .map_err(|err| CommandFailed(TextComponent::text(err.to_string())))?;
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "Created backup {} ({})",
+                        info.name,
+                        format_bytes(info.size)
+                    ))
+                    .color_named(NamedColor::Green),
+                )
+                .await;
+            Ok(1)
+        })
+    }
+}
+
+struct ListExecutor;
+
+impl CommandExecutor for ListExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let backups = server.backups.list().map_err(|err| {
+                CommandFailed(TextComponent::text(format!(
+                    "Failed to list backups: {err}"
+                )))
+            })?;
+            if backups.is_empty() {
+                sender
+                    .send_message(TextComponent::text("There are no backups."))
+                    .await;
+                return Ok(0);
+            }
+
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "{} backups in {}:",
+                        backups.len(),
+                        server.backups.directory().display()
+                    ))
+                    .color_named(NamedColor::Gold),
+                )
+                .await;
+            for backup in &backups {
+                sender
+                    .send_message(TextComponent::text(format!(
+                        "  {} ({})",
+                        backup.name,
+                        format_bytes(backup.size)
+                    )))
+                    .await;
+            }
+            Ok(backups.len() as i32)
+        })
+    }
+}
+
+struct RestoreExecutor;
+
+impl CommandExecutor for RestoreExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(Arg::Simple(name)) = args.get(&ARG_NAME) else {
+                return Err(InvalidConsumption(Some(ARG_NAME.into())));
+            };
+            server
+                .backups
+                .schedule_restore(name)
+                .map_err(|err| CommandFailed(TextComponent::text(err.to_string())))?;
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "Backup {name} will be restored the next time the server starts. The current world is kept next to it."
+                    ))
+                    .color_named(NamedColor::Yellow),
+                )
+                .await;
+            Ok(1)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(literal("now").execute(NowExecutor))
+        .then(literal("list").execute(ListExecutor))
+        .then(
+            literal("restore").then(argument(ARG_NAME, SimpleArgConsumer).execute(RestoreExecutor)),
+        )
+}
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use pumpkin_data::Block;
+use pumpkin_data::block_properties::is_air;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+use pumpkin_world::world::BlockFlags;
+
+use crate::command::args::bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::simple::SimpleArgConsumer;
+use crate::command::args::time::TimeArgumentConsumer;
+use crate::command::args::{Arg, ConsumedArgs, FindArg, FindArgDefaultName};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{
+    NonLeafNodeBuilder, argument, argument_default_name, literal, require,
+};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender, audit};
+use crate::server::block_log::{BlockChange, BlockLogQuery, rollback_targets, unix_time};
+use crate::world::World;
+use CommandError::CommandFailed;
+
+const NAMES: [&str; 2] = ["blocklog", "co"];
+
+const DESCRIPTION: &str = "Looks up, rolls back and restores block changes made by players.";
+
+const ARG_TIME: &str = "time";
+const ARG_PLAYER: &str = "player";
+
+/// Entries shown by a lookup, most recent first.
+const LOOKUP_LIMIT: usize = 10;
+
+const fn radius_consumer() -> BoundedNumArgumentConsumer<i32> {
+    BoundedNumArgumentConsumer::new().name("radius").min(0)
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    Lookup,
+    Rollback,
+    Restore,
+}
+
+struct Executor(Action);
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            if !server.block_log.is_enabled() {
+                return Err(CommandFailed(TextComponent::text(
+                    "The block log is disabled in the configuration.",
+                )));
+            }
+            let (Some(world), Some(position)) = (sender.world(), sender.position()) else {
+                return Err(CommandError::InvalidRequirement);
+            };
+
+            let max_radius = server.block_log.config().max_radius;
+            let radius = match radius_consumer().find_arg_default_name(args) {
+                Ok(Ok(radius)) if radius as u32 <= max_radius => radius,
+                _ => {
+                    return Err(CommandFailed(TextComponent::text(format!(
+                        "The radius must be between 0 and {max_radius}."
+                    ))));
+                }
+            };
+            // Lookups without a time cover the whole log
+            let since = TimeArgumentConsumer::find_arg(args, ARG_TIME)
+                .map_or(0, |ticks| unix_time().saturating_sub((ticks / 20) as u64));
+            let player = match args.get(ARG_PLAYER) {
+                Some(Arg::Simple(name)) => Some(*name),
+                _ => None,
+            };
+
+            let changes = server
+                .block_log
+                .query(BlockLogQuery {
+                    dimension: world.dimension.id,
+                    center: BlockPos::floored_v(position),
+                    radius,
+                    since,
+                    player: player.map(str::to_string),
+                })
+                .await
+                .map_err(|err| {
+                    CommandFailed(TextComponent::text(format!(
+                        "Failed to read the block log: {err}"
+                    )))
+                })?;
+
+            match self.0 {
+                Action::Lookup => lookup(sender, &changes).await,
+                Action::Rollback | Action::Restore => {
+                    let restore = matches!(self.0, Action::Restore);
+                    let applied = apply(&world, &changes, restore).await;
+                    let verb = if restore { "Restored" } else { "Rolled back" };
+                    audit::record(
+                        sender,
+                        &format!(
+                            "{} {applied} blocks within {radius} blocks of {} in {}{}",
+                            verb.to_lowercase(),
+                            BlockPos::floored_v(position),
+                            world.dimension.minecraft_name,
+                            player.map_or_else(String::new, |name| format!(" changed by {name}"))
+                        ),
+                    );
+                    sender
+                        .send_message(
+                            TextComponent::text(format!(
+                                "{verb} {applied} blocks from {} changes.",
+                                changes.len()
+                            ))
+                            .color_named(NamedColor::Green),
+                        )
+                        .await;
+                    Ok(applied as i32)
+                }
+            }
+        })
+    }
+}
+
+async fn lookup(sender: &CommandSender, changes: &[BlockChange]) -> Result<i32, CommandError> {
+    if changes.is_empty() {
+        sender
+            .send_message(TextComponent::text("No block changes found."))
+            .await;
+        return Ok(0);
+    }
+
+    sender
+        .send_message(
+            TextComponent::text(format!(
+                "{} block changes found, showing the latest {}:",
+                changes.len(),
+                changes.len().min(LOOKUP_LIMIT)
+            ))
+            .color_named(NamedColor::Gold),
+        )
+        .await;
+    let now = unix_time();
+    for change in changes.iter().rev().take(LOOKUP_LIMIT) {
+        let old = Block::from_state_id(change.old_state).name;
+        let new = Block::from_state_id(change.new_state).name;
+        let action = if is_air(change.new_state) {
+            format!("broke {old}")
+        } else if is_air(change.old_state) {
+            format!("placed {new}")
+        } else {
+            format!("replaced {old} with {new}")
+        };
+        sender
+            .send_message(
+                TextComponent::text(format!(
+                    "{} ago ",
+                    format_age(now.saturating_sub(change.time))
+                ))
+                .color_named(NamedColor::Gray)
+                .add_child(
+                    TextComponent::text(change.player_name.clone()).color_named(NamedColor::Aqua),
+                )
+                .add_child(
+                    TextComponent::text(format!(" {action} at {}", change.pos))
+                        .color_named(NamedColor::White),
+                ),
+            )
+            .await;
+    }
+    Ok(changes.len() as i32)
+}
+
+/// Sets every affected block back and returns how many blocks were actually changed.
+async fn apply(world: &Arc<World>, changes: &[BlockChange], restore: bool) -> usize {
+    let mut applied = 0;
+    for (pos, state) in rollback_targets(changes, restore) {
+        if world.get_block_state_id(&pos).await == state {
+            continue;
+        }
+        world
+            .set_block_state(
+                &pos,
+                state,
+                BlockFlags::FORCE_STATE | BlockFlags::NOTIFY_ALL,
+            )
+            .await;
+        applied += 1;
+    }
+    applied
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{:.1}h", seconds as f64 / 3600.0),
+        _ => format!("{:.1}d", seconds as f64 / 86400.0),
+    }
+}
+
+/// `<radius> <time> [player]`, with the time optional when `time_optional` is set.
+fn area_args(action: Action, time_optional: bool) -> NonLeafNodeBuilder {
+    let time = argument(ARG_TIME, TimeArgumentConsumer)
+        .then(argument(ARG_PLAYER, SimpleArgConsumer).execute(Executor(action)))
+        .execute(Executor(action));
+    let radius = argument_default_name(radius_consumer()).then(time);
+    if time_optional {
+        radius.execute(Executor(action))
+    } else {
+        radius
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION).then(
+        require(|sender| sender.world().is_some())
+            .then(literal("lookup").then(area_args(Action::Lookup, true)))
+            .then(literal("rollback").then(area_args(Action::Rollback, false)))
+            .then(literal("restore").then(area_args(Action::Restore, false))),
+    )
+}
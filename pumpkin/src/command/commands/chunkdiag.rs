@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+use pumpkin_world::chunk_system::ChunkLoading;
+use pumpkin_world::level::{ChunkRetainer, StaleChunk};
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::literal;
+use crate::command::{CommandExecutor, CommandResult, CommandSender, audit};
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["chunkdiag"];
+
+const DESCRIPTION: &str = "Finds chunks that stay loaded with no player nearby.";
+
+/// Retainers listed per world, the rest are only counted.
+const REPORT_LIMIT: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Report,
+    /// Reports what `unload confirm` would release.
+    Preview,
+    Unload,
+}
+
+fn describe(retainer: ChunkRetainer) -> String {
+    match retainer {
+        ChunkRetainer::ForcedTicket(at) => format!("forced ticket at {} {}", at.x, at.y),
+        ChunkRetainer::Ticket(at, ChunkLoading::LOAD_TICKET_LEVEL) => {
+            format!("unfinished chunk load at {} {}", at.x, at.y)
+        }
+        ChunkRetainer::Ticket(at, level) => {
+            format!(
+                "ticket at {} {} with level {level} and no player",
+                at.x, at.y
+            )
+        }
+        ChunkRetainer::Referenced(0) => "nothing, unloads on the next pass".to_string(),
+        ChunkRetainer::Referenced(_) => "references held outside the chunk system".to_string(),
+    }
+}
+
+/// Groups stale chunks by what retains them, largest group first.
+fn group(stale: &[StaleChunk]) -> Vec<(ChunkRetainer, Vec<Vector2<i32>>)> {
+    let mut groups: HashMap<ChunkRetainer, Vec<Vector2<i32>>> = HashMap::new();
+    for chunk in stale {
+        // Reference counts differ per chunk, so those are grouped together
+        let key = match chunk.retainer {
+            ChunkRetainer::Referenced(references) => ChunkRetainer::Referenced(references.min(1)),
+            retainer => retainer,
+        };
+        groups.entry(key).or_default().push(chunk.pos);
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, chunks)| std::cmp::Reverse(chunks.len()));
+    groups
+}
+
+fn stale_chunks(world: &Arc<World>) -> Vec<StaleChunk> {
+    let players: Vec<_> = world
+        .players
+        .load()
+        .iter()
+        .map(|player| player.living_entity.entity.chunk_pos.load())
+        .collect();
+    world.level.stale_chunks(&players)
+}
+
+/// Positions of the tickets that `unload confirm` releases.
+fn releasable(stale: &[StaleChunk]) -> Vec<Vector2<i32>> {
+    let mut positions = Vec::new();
+    for chunk in stale {
+        if let ChunkRetainer::Ticket(at, _) = chunk.retainer
+            && !positions.contains(&at)
+        {
+            positions.push(at);
+        }
+    }
+    positions
+}
+
+async fn report(sender: &CommandSender, world: &Arc<World>, stale: &[StaleChunk]) {
+    let name = world.dimension.minecraft_name;
+    let loaded = world.level.loaded_chunk_count();
+    if stale.is_empty() {
+        sender
+            .send_message(
+                TextComponent::text(format!("{name}: no stale chunks out of {loaded} loaded."))
+                    .color_named(NamedColor::Green),
+            )
+            .await;
+        return;
+    }
+
+    sender
+        .send_message(
+            TextComponent::text(format!(
+                "{name}: {} of {loaded} loaded chunks have no player nearby:",
+                stale.len()
+            ))
+            .color_named(NamedColor::Gold),
+        )
+        .await;
+    let groups = group(stale);
+    for (retainer, chunks) in groups.iter().take(REPORT_LIMIT) {
+        let color = match retainer {
+            ChunkRetainer::Referenced(0) => NamedColor::Gray,
+            ChunkRetainer::ForcedTicket(_) => NamedColor::Yellow,
+            _ => NamedColor::Red,
+        };
+        sender
+            .send_message(
+                TextComponent::text(format!(
+                    "  {} chunks (e.g. {} {}) kept by ",
+                    chunks.len(),
+                    chunks[0].x,
+                    chunks[0].y
+                ))
+                .add_child(TextComponent::text(describe(*retainer)).color_named(color)),
+            )
+            .await;
+    }
+    if groups.len() > REPORT_LIMIT {
+        sender
+            .send_message(TextComponent::text(format!(
+                "  ...and {} more retainers",
+                groups.len() - REPORT_LIMIT
+            )))
+            .await;
+    }
+}
+
+struct Executor(Mode);
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let worlds = sender
+                .world()
+                .map_or_else(|| server.worlds.load().to_vec(), |world| vec![world]);
+
+            let mut total = 0;
+            let mut tickets = 0;
+            for world in &worlds {
+                let stale = stale_chunks(world);
+                total += stale.len();
+                let positions = releasable(&stale);
+                match self.0 {
+                    Mode::Report | Mode::Preview => {
+                        report(sender, world, &stale).await;
+                        tickets += positions.len();
+                    }
+                    Mode::Unload => {
+                        let released = world.level.release_tickets(&positions);
+                        tickets += released;
+                        if released > 0 {
+                            log::warn!(
+                                "Released {released} orphaned chunk tickets in {}",
+                                world.dimension.minecraft_name
+                            );
+                        }
+                    }
+                }
+            }
+
+            match self.0 {
+                Mode::Report => {}
+                Mode::Preview => {
+                    let message = if tickets == 0 {
+                        TextComponent::text("There are no orphaned tickets to release.")
+                    } else {
+                        TextComponent::text(format!(
+                            "Run /chunkdiag unload confirm to release {tickets} orphaned tickets. \
+                            Referenced chunks and forced tickets are kept."
+                        ))
+                        .color_named(NamedColor::Yellow)
+                    };
+                    sender.send_message(message).await;
+                }
+                Mode::Unload => {
+                    audit::record(
+                        sender,
+                        &format!("released {tickets} orphaned chunk tickets"),
+                    );
+                    sender
+                        .send_message(
+                            TextComponent::text(format!(
+                                "Released {tickets} orphaned tickets, stale chunks unload on the \
+                                next pass."
+                            ))
+                            .color_named(NamedColor::Green),
+                        )
+                        .await;
+                }
+            }
+            Ok(total as i32)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(Executor(Mode::Report))
+        .then(
+            literal("unload")
+                .execute(Executor(Mode::Preview))
+                .then(literal("confirm").execute(Executor(Mode::Unload))),
+        )
+}
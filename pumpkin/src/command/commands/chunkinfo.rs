@@ -0,0 +1,91 @@
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::require;
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender};
+use crate::server::chunk_limits::{ChunkCounts, ChunkLimitKind};
+
+const NAMES: [&str; 1] = ["chunkinfo"];
+
+const DESCRIPTION: &str = "Shows entity and block entity counts of the chunk you are in.";
+
+struct Executor;
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let (Some(world), Some(position)) = (sender.world(), sender.position()) else {
+                return Err(CommandError::InvalidRequirement);
+            };
+            let chunk = BlockPos::floored_v(position).chunk_position();
+            let counts = ChunkCounts::count(&world, chunk).await;
+            let players = world
+                .players
+                .load()
+                .iter()
+                .filter(|player| {
+                    player
+                        .living_entity
+                        .entity
+                        .block_pos
+                        .load()
+                        .chunk_position()
+                        == chunk
+                })
+                .count();
+
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "Chunk {} {} in {}:",
+                        chunk.x, chunk.y, world.dimension.minecraft_name
+                    ))
+                    .color_named(NamedColor::Gold),
+                )
+                .await;
+            for (name, count, kind) in [
+                ("Entities", counts.entities, ChunkLimitKind::Entity),
+                ("Item frames", counts.item_frames, ChunkLimitKind::ItemFrame),
+                (
+                    "Block entities",
+                    counts.block_entities,
+                    ChunkLimitKind::BlockEntity,
+                ),
+            ] {
+                let line = match server.chunk_limits.limit(kind) {
+                    Some(limit) => {
+                        let color = if count >= limit {
+                            NamedColor::Red
+                        } else if count * 4 >= limit * 3 {
+                            NamedColor::Yellow
+                        } else {
+                            NamedColor::Green
+                        };
+                        TextComponent::text(format!("  {name}: ")).add_child(
+                            TextComponent::text(format!("{count}/{limit}")).color_named(color),
+                        )
+                    }
+                    None => TextComponent::text(format!("  {name}: {count}")),
+                };
+                sender.send_message(line).await;
+            }
+            sender
+                .send_message(TextComponent::text(format!("  Players: {players}")))
+                .await;
+            Ok((counts.entities + counts.item_frames + counts.block_entities) as i32)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(require(|sender| sender.world().is_some()).execute(Executor))
+}
@@ -1,16 +1,30 @@
 use std::sync::atomic::Ordering;
+use std::time::Instant;
 
 use crate::command::CommandResult;
+use crate::command::args::FindArgDefaultName;
+use crate::command::args::bounded_num::BoundedNumArgumentConsumer;
 use crate::command::{
     CommandError, CommandExecutor, CommandSender, args::ConsumedArgs, tree::CommandTree,
-    tree::builder::literal,
+    tree::builder::argument_default_name, tree::builder::literal,
 };
+use pumpkin_util::math::vector2::Vector2;
 use pumpkin_util::text::TextComponent;
 use pumpkin_util::text::color::NamedColor;
 
 const NAMES: [&str; 1] = ["debug"];
 
-const DESCRIPTION: &str = "Starts or stops a debugging session.";
+const DESCRIPTION: &str = "Starts or stops a debugging session, or relights chunks.";
+
+/// The largest radius in chunks `relight` accepts.
+const MAX_RELIGHT_RADIUS: i32 = 16;
+
+const fn radius_consumer() -> BoundedNumArgumentConsumer<i32> {
+    BoundedNumArgumentConsumer::new()
+        .name("radius")
+        .min(0)
+        .max(MAX_RELIGHT_RADIUS)
+}
 
 struct StartExecutor;
 
@@ -117,8 +131,57 @@ impl CommandExecutor for StopExecutor {
     }
 }
 
+/// Recomputes the light of the chunks around the sender, for when it looks wrong.
+struct RelightExecutor;
+
+impl CommandExecutor for RelightExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let radius = match radius_consumer().find_arg_default_name(args) {
+                Ok(radius) => radius?,
+                // Only the chunk of the sender
+                Err(_) => 0,
+            };
+            let (Some(world), Some(position)) = (sender.world(), sender.position()) else {
+                return Err(CommandError::InvalidRequirement);
+            };
+
+            let center = position.to_block_pos().chunk_position();
+            let mut chunks = Vec::new();
+            for x in -radius..=radius {
+                for z in -radius..=radius {
+                    chunks.push(Vector2::new(center.x + x, center.y + z));
+                }
+            }
+            let start = Instant::now();
+            let relit = world.lighting.relight(&world, chunks).await;
+
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "Relit {relit} chunks in {} ms.",
+                        start.elapsed().as_millis()
+                    ))
+                    .color_named(NamedColor::Green),
+                )
+                .await;
+            Ok(relit as i32)
+        })
+    }
+}
+
 pub fn init_command_tree() -> CommandTree {
     CommandTree::new(NAMES, DESCRIPTION)
         .then(literal("start").execute(StartExecutor))
         .then(literal("stop").execute(StopExecutor))
+        .then(
+            literal("relight")
+                .execute(RelightExecutor)
+                .then(argument_default_name(radius_consumer()).execute(RelightExecutor)),
+        )
 }
@@ -6,6 +6,7 @@ use crate::command::tree::builder::argument;
 use crate::command::{CommandExecutor, CommandSender, args::ConsumedArgs, tree::CommandTree};
 use pumpkin_util::GameMode;
 use pumpkin_util::text::TextComponent;
+use std::sync::Arc;
 
 const NAMES: [&str; 1] = ["defaultgamemode"];
 
@@ -51,7 +52,9 @@ impl CommandExecutor for DefaultGamemodeExecutor {
                 .await;
 
             //Change the default gamemode (not in configuration.toml)
-            server.defaultgamemode.lock().await.gamemode = gamemode;
+            server
+                .defaultgamemode
+                .store(Arc::new(DefaultGamemode { gamemode }));
 
             Ok(successful_changes)
         })
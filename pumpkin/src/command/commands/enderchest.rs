@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use pumpkin_util::text::TextComponent;
+use pumpkin_world::inventory::Inventory;
+
+use super::invsee::{InspectScreenFactory, single_target};
+use crate::command::args::ConsumedArgs;
+use crate::command::args::players::PlayersArgumentConsumer;
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender, audit};
+
+const NAMES: [&str; 1] = ["enderchest"];
+
+const DESCRIPTION: &str = "Opens another player's ender chest.";
+
+const ARG_TARGET: &str = "target";
+
+struct Executor;
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(viewer) = sender.as_player() else {
+                return Err(CommandError::InvalidRequirement);
+            };
+            let target = single_target(args, ARG_TARGET)?;
+
+            let inventory: Arc<dyn Inventory> = target.ender_chest_inventory.clone();
+            viewer
+                .open_handled_screen(&InspectScreenFactory {
+                    inventory,
+                    title: TextComponent::text(format!(
+                        "{}'s ender chest",
+                        target.gameprofile.name
+                    )),
+                    rows: 3,
+                })
+                .await;
+            audit::record(
+                sender,
+                &format!("opened the ender chest of {}", target.gameprofile.name),
+            );
+            Ok(1)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION).then(
+        require(CommandSender::is_player)
+            .then(argument(ARG_TARGET, PlayersArgumentConsumer).execute(Executor)),
+    )
+}
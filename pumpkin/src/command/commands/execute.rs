@@ -0,0 +1,53 @@
+use crate::command::{
+    CommandError, CommandExecutor, CommandResult, CommandSender,
+    args::{
+        Arg, ConsumedArgs, FindArg, dimension::DimensionArgumentConsumer, message::MsgArgConsumer,
+    },
+    tree::{
+        CommandTree,
+        builder::{argument, literal},
+    },
+};
+use CommandError::InvalidConsumption;
+
+const NAMES: [&str; 1] = ["execute"];
+
+const DESCRIPTION: &str = "Executes another command.";
+
+const ARG_DIMENSION: &str = "dimension";
+const ARG_COMMAND: &str = "command";
+
+// TODO: The other subcommands, and running subcommands one after another
+struct InExecutor;
+
+impl CommandExecutor for InExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let world = DimensionArgumentConsumer::find_arg(args, ARG_DIMENSION)?;
+            let Some(Arg::Msg(command)) = args.get(ARG_COMMAND) else {
+                return Err(InvalidConsumption(Some(ARG_COMMAND.into())));
+            };
+
+            let sender = CommandSender::InWorld(Box::new(sender.clone()), world);
+            let dispatcher = server.command_dispatcher.read().await;
+            dispatcher.dispatch(&sender, server, command).await?;
+
+            Ok(1)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION).then(
+        literal("in").then(
+            argument(ARG_DIMENSION, DimensionArgumentConsumer).then(
+                literal("run").then(argument(ARG_COMMAND, MsgArgConsumer).execute(InExecutor)),
+            ),
+        ),
+    )
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use pumpkin_inventory::generic_container_screen_handler::{create_generic_9x3, create_generic_9x4};
+use pumpkin_inventory::player::player_inventory::PlayerInventory;
+use pumpkin_inventory::screen_handler::{
+    BoxFuture, InventoryPlayer, ScreenHandlerFactory, SharedScreenHandler,
+};
+use pumpkin_util::text::TextComponent;
+use pumpkin_world::inventory::Inventory;
+use tokio::sync::Mutex;
+
+use crate::command::args::players::PlayersArgumentConsumer;
+use crate::command::args::{Arg, ConsumedArgs};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender, audit};
+use crate::entity::player::Player;
+use CommandError::{CommandFailed, InvalidConsumption};
+
+const NAMES: [&str; 1] = ["invsee"];
+
+const DESCRIPTION: &str = "Opens another player's inventory.";
+
+const ARG_TARGET: &str = "target";
+
+/// Opens a live view of another player's inventory. Slots are shared with the target, so
+/// changes made by either side are visible to both.
+pub struct InspectScreenFactory {
+    pub inventory: Arc<dyn Inventory>,
+    pub title: TextComponent,
+    /// Rows of the container, either 3 (ender chest) or 4 (main inventory).
+    pub rows: u8,
+}
+
+impl ScreenHandlerFactory for InspectScreenFactory {
+    fn create_screen_handler<'a>(
+        &'a self,
+        sync_id: u8,
+        player_inventory: &'a Arc<PlayerInventory>,
+        _player: &'a dyn InventoryPlayer,
+    ) -> BoxFuture<'a, Option<SharedScreenHandler>> {
+        Box::pin(async move {
+            let handler = if self.rows == 3 {
+                create_generic_9x3(sync_id, player_inventory, self.inventory.clone()).await
+            } else {
+                create_generic_9x4(sync_id, player_inventory, self.inventory.clone()).await
+            };
+            Some(Arc::new(Mutex::new(handler)) as SharedScreenHandler)
+        })
+    }
+
+    fn get_display_name(&self) -> TextComponent {
+        self.title.clone()
+    }
+}
+
+/// Resolves the single target of an inspection command.
+pub fn single_target<'a>(
+    args: &'a ConsumedArgs<'a>,
+    arg_name: &str,
+) -> Result<&'a Arc<Player>, CommandError> {
+    let Some(Arg::Players(targets)) = args.get(arg_name) else {
+        return Err(InvalidConsumption(Some(arg_name.into())));
+    };
+    match targets.as_slice() {
+        [target] => Ok(target),
+        _ => Err(CommandFailed(TextComponent::translate(
+            "argument.player.toomany",
+            [],
+        ))),
+    }
+}
+
+struct Executor;
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(viewer) = sender.as_player() else {
+                return Err(CommandError::InvalidRequirement);
+            };
+            let target = single_target(args, ARG_TARGET)?;
+            if Arc::ptr_eq(&viewer, target) {
+                return Err(CommandFailed(TextComponent::text(
+                    "You cannot inspect your own inventory.",
+                )));
+            }
+
+            // Only the main inventory (hotbar included) maps onto a container without gaps
+            let inventory: Arc<dyn Inventory> = target.inventory.clone();
+            viewer
+                .open_handled_screen(&InspectScreenFactory {
+                    inventory,
+                    title: TextComponent::text(format!("{}'s inventory", target.gameprofile.name)),
+                    rows: 4,
+                })
+                .await;
+            audit::record(
+                sender,
+                &format!("opened the inventory of {}", target.gameprofile.name),
+            );
+            Ok(1)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION).then(
+        require(CommandSender::is_player)
+            .then(argument(ARG_TARGET, PlayersArgumentConsumer).execute(Executor)),
+    )
+}
@@ -1,4 +1,3 @@
-use pumpkin_config::BasicConfiguration;
 use pumpkin_util::{
     PermissionLvl,
     permission::{Permission, PermissionDefault, PermissionRegistry},
@@ -7,10 +6,15 @@ use tokio::sync::RwLock;
 
 use super::dispatcher::CommandDispatcher;
 
+mod aiprovider;
+mod backup;
 mod ban;
 mod banip;
 mod banlist;
+mod blocklog;
 mod bossbar;
+mod chunkdiag;
+mod chunkinfo;
 mod clear;
 mod damage;
 mod data;
@@ -20,31 +24,42 @@ mod deop;
 mod difficulty;
 mod effect;
 mod enchant;
+mod enderchest;
+mod execute;
 mod experience;
 mod fill;
 mod gamemode;
 mod gamerule;
 mod give;
 mod help;
+mod invsee;
 mod kick;
 mod kill;
 mod list;
 mod me;
 mod msg;
+mod mspt;
+mod nbtview;
+mod netstats;
 mod op;
 mod pardon;
 mod pardonip;
 mod particle;
 mod perf;
+mod playerdata;
 mod playsound;
 mod plugin;
 mod plugins;
+mod pregen;
+mod profile;
 mod pumpkin;
+mod restart;
 mod rotate;
 mod save_all;
 mod save_off;
 mod save_on;
 mod say;
+mod schem;
 mod seed;
 mod setblock;
 mod setidletimeout;
@@ -53,6 +68,7 @@ mod spawnpoint;
 mod stop;
 mod stopsound;
 mod summon;
+mod team;
 mod teleport;
 mod tellraw;
 mod tick;
@@ -64,19 +80,16 @@ mod whitelist;
 mod worldborder;
 
 #[must_use]
-pub async fn default_dispatcher(
-    registry: &RwLock<PermissionRegistry>,
-    basic_config: &BasicConfiguration,
-) -> CommandDispatcher {
+pub async fn default_dispatcher(registry: &RwLock<PermissionRegistry>) -> CommandDispatcher {
     let mut dispatcher = CommandDispatcher::default();
 
     register_permissions(registry).await;
-    register_commands(&mut dispatcher, basic_config);
+    register_commands(&mut dispatcher);
 
     dispatcher
 }
 
-fn register_commands(dispatcher: &mut CommandDispatcher, basic_config: &BasicConfiguration) {
+fn register_commands(dispatcher: &mut CommandDispatcher) {
     // Zero
     dispatcher.register(pumpkin::init_command_tree(), "pumpkin:command.pumpkin");
     dispatcher.register(help::init_command_tree(), "minecraft:command.help");
@@ -92,10 +105,7 @@ fn register_commands(dispatcher: &mut CommandDispatcher, basic_config: &BasicCon
     dispatcher.register(effect::init_command_tree(), "minecraft:command.effect");
     dispatcher.register(teleport::init_command_tree(), "minecraft:command.teleport");
     dispatcher.register(time::init_command_tree(), "minecraft:command.time");
-    dispatcher.register(
-        tick::init_command_tree(basic_config.tps),
-        "minecraft:command.tick",
-    );
+    dispatcher.register(tick::init_command_tree(), "minecraft:command.tick");
     dispatcher.register(give::init_command_tree(), "minecraft:command.give");
     dispatcher.register(enchant::init_command_tree(), "minecraft:command.enchant");
     dispatcher.register(clear::init_command_tree(), "minecraft:command.clear");
@@ -114,10 +124,12 @@ fn register_commands(dispatcher: &mut CommandDispatcher, basic_config: &BasicCon
         "minecraft:command.experience",
     );
     dispatcher.register(weather::init_command_tree(), "minecraft:command.weather");
+    dispatcher.register(execute::init_command_tree(), "minecraft:command.execute");
     dispatcher.register(particle::init_command_tree(), "minecraft:command.particle");
     dispatcher.register(rotate::init_command_tree(), "minecraft:command.rotate");
     dispatcher.register(damage::init_command_tree(), "minecraft:command.damage");
     dispatcher.register(bossbar::init_command_tree(), "minecraft:command.bossbar");
+    dispatcher.register(team::init_command_tree(), "minecraft:command.team");
     dispatcher.register(say::init_command_tree(), "minecraft:command.say");
     dispatcher.register(gamemode::init_command_tree(), "minecraft:command.gamemode");
     dispatcher.register(gamerule::init_command_tree(), "minecraft:command.gamerule");
@@ -142,6 +154,8 @@ fn register_commands(dispatcher: &mut CommandDispatcher, basic_config: &BasicCon
         "minecraft:command.spawnpoint",
     );
     dispatcher.register(data::init_command_tree(), "minecraft:command.data");
+    dispatcher.register(chunkinfo::init_command_tree(), "pumpkin:command.chunkinfo");
+    dispatcher.register(mspt::init_command_tree(), "pumpkin:command.mspt");
     // Three
     dispatcher.register(op::init_command_tree(), "minecraft:command.op");
     dispatcher.register(deop::init_command_tree(), "minecraft:command.deop");
@@ -163,12 +177,34 @@ fn register_commands(dispatcher: &mut CommandDispatcher, basic_config: &BasicCon
         "minecraft:command.setidletimeout",
     );
     dispatcher.register(debug::init_command_tree(), "minecraft:command.debug");
+    dispatcher.register(netstats::init_command_tree(), "pumpkin:command.netstats");
+    dispatcher.register(invsee::init_command_tree(), "pumpkin:command.invsee");
+    dispatcher.register(
+        enderchest::init_command_tree(),
+        "pumpkin:command.enderchest",
+    );
+    dispatcher.register(nbtview::init_command_tree(), "pumpkin:command.nbtview");
+    dispatcher.register(blocklog::init_command_tree(), "pumpkin:command.blocklog");
+    dispatcher.register(
+        playerdata::init_command_tree(),
+        "pumpkin:command.playerdata",
+    );
     // Four
     dispatcher.register(stop::init_command_tree(), "minecraft:command.stop");
     dispatcher.register(perf::init_command_tree(), "minecraft:command.perf");
+    dispatcher.register(profile::init_command_tree(), "pumpkin:command.profile");
     dispatcher.register(save_all::init_command_tree(), "minecraft:command.save-all");
     dispatcher.register(save_off::init_command_tree(), "minecraft:command.save-off");
     dispatcher.register(save_on::init_command_tree(), "minecraft:command.save-on");
+    dispatcher.register(backup::init_command_tree(), "pumpkin:command.backup");
+    dispatcher.register(restart::init_command_tree(), "pumpkin:command.restart");
+    dispatcher.register(pregen::init_command_tree(), "pumpkin:command.pregen");
+    dispatcher.register(chunkdiag::init_command_tree(), "pumpkin:command.chunkdiag");
+    dispatcher.register(schem::init_command_tree(), "pumpkin:command.schem");
+    dispatcher.register(
+        aiprovider::init_command_tree(),
+        "pumpkin:command.aiprovider",
+    );
 }
 
 async fn register_permissions(permission_registry: &RwLock<PermissionRegistry>) {
@@ -229,6 +265,27 @@ fn register_level_0_permissions(registry: &mut PermissionRegistry) {
 #[expect(clippy::too_many_lines)]
 fn register_level_2_permissions(registry: &mut PermissionRegistry) {
     // Register permissions for commands with PermissionLvl::Two
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:chat.spam_exempt",
+            "Exempts from the rate limits of chat messages and commands",
+            PermissionDefault::Op(PermissionLvl::Two),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.mspt",
+            "Shows tick times and TPS",
+            PermissionDefault::Op(PermissionLvl::Two),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:debug.tick_time",
+            "Feeds the tick time chart of the debug screen",
+            PermissionDefault::Op(PermissionLvl::Two),
+        ))
+        .unwrap();
     registry
         .register_permission(Permission::new(
             "minecraft:command.kill",
@@ -341,6 +398,13 @@ fn register_level_2_permissions(registry: &mut PermissionRegistry) {
             PermissionDefault::Op(PermissionLvl::Two),
         ))
         .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "minecraft:command.execute",
+            "Executes a command in another dimension",
+            PermissionDefault::Op(PermissionLvl::Two),
+        ))
+        .unwrap();
     registry
         .register_permission(Permission::new(
             "minecraft:command.particle",
@@ -369,6 +433,13 @@ fn register_level_2_permissions(registry: &mut PermissionRegistry) {
             PermissionDefault::Op(PermissionLvl::Two),
         ))
         .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "minecraft:command.team",
+            "Creates and manages teams",
+            PermissionDefault::Op(PermissionLvl::Two),
+        ))
+        .unwrap();
     registry
         .register_permission(Permission::new(
             "minecraft:command.say",
@@ -432,6 +503,13 @@ fn register_level_2_permissions(registry: &mut PermissionRegistry) {
             PermissionDefault::Op(PermissionLvl::Two),
         ))
         .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.chunkinfo",
+            "Allows the player to view entity and block entity counts of a chunk",
+            PermissionDefault::Op(PermissionLvl::Two),
+        ))
+        .unwrap();
 }
 
 #[expect(clippy::too_many_lines)]
@@ -549,6 +627,48 @@ fn register_level_3_permissions(registry: &mut PermissionRegistry) {
             PermissionDefault::Op(PermissionLvl::Three),
         ))
         .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.netstats",
+            "Shows network traffic statistics",
+            PermissionDefault::Op(PermissionLvl::Three),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.invsee",
+            "Allows the player to open another player's inventory",
+            PermissionDefault::Op(PermissionLvl::Three),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.enderchest",
+            "Allows the player to open another player's ender chest",
+            PermissionDefault::Op(PermissionLvl::Three),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.nbtview",
+            "Allows the player to view block entity and entity NBT",
+            PermissionDefault::Op(PermissionLvl::Three),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.blocklog",
+            "Allows the player to look up and roll back block changes",
+            PermissionDefault::Op(PermissionLvl::Three),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.playerdata",
+            "Allows the player to edit the saved data of offline players",
+            PermissionDefault::Op(PermissionLvl::Three),
+        ))
+        .unwrap();
 }
 
 fn register_level_4_permissions(registry: &mut PermissionRegistry) {
@@ -588,4 +708,53 @@ fn register_level_4_permissions(registry: &mut PermissionRegistry) {
             PermissionDefault::Op(PermissionLvl::Four),
         ))
         .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.profile",
+            "Profiles the parts of each tick",
+            PermissionDefault::Op(PermissionLvl::Four),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.backup",
+            "Creates, lists and restores world backups",
+            PermissionDefault::Op(PermissionLvl::Four),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.restart",
+            "Restarts the server after a countdown",
+            PermissionDefault::Op(PermissionLvl::Four),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.pregen",
+            "Generates the chunks around the world spawn ahead of time",
+            PermissionDefault::Op(PermissionLvl::Four),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.chunkdiag",
+            "Finds and unloads chunks kept loaded with no player nearby.",
+            PermissionDefault::Op(PermissionLvl::Four),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.schem",
+            "Loads and pastes schematics.",
+            PermissionDefault::Op(PermissionLvl::Four),
+        ))
+        .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "pumpkin:command.aiprovider",
+            "Shows and changes the AI providers of new mobs.",
+            PermissionDefault::Op(PermissionLvl::Four),
+        ))
+        .unwrap();
 }
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::{CommandExecutor, CommandResult, CommandSender};
+use crate::server::Server;
+use crate::server::tick_stats::TickWindow;
+
+const NAMES: [&str; 1] = ["mspt"];
+
+const DESCRIPTION: &str = "Shows tick times and TPS over the last second, five seconds and minute.";
+
+fn millis(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
+
+/// Green within the tick budget, yellow past half of it, red over it.
+fn budget_color(time: Duration, budget: Duration) -> NamedColor {
+    if time > budget {
+        NamedColor::Red
+    } else if time * 2 > budget {
+        NamedColor::Yellow
+    } else {
+        NamedColor::Green
+    }
+}
+
+struct Executor;
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let budget =
+                Duration::from_nanos(server.tick_rate_manager.nanoseconds_per_tick() as u64);
+            sender
+                .send_message(
+                    TextComponent::text("Tick times (mean, p50, p95, p99, max) and TPS")
+                        .color_named(NamedColor::Gold),
+                )
+                .await;
+            for window in TickWindow::ALL {
+                let summary = server.tick_stats.summary(window);
+                let line = format!(
+                    "{}: {:.1}, {:.1}, {:.1}, {:.1}, {:.1} ms, {:.1} TPS",
+                    window.name(),
+                    millis(summary.mean),
+                    millis(summary.p50),
+                    millis(summary.p95),
+                    millis(summary.p99),
+                    millis(summary.max),
+                    summary.tps(),
+                );
+                sender
+                    .send_message(
+                        TextComponent::text(line).color_named(budget_color(summary.p95, budget)),
+                    )
+                    .await;
+            }
+            Ok(1)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION).execute(Executor)
+}
@@ -0,0 +1,159 @@
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::tag::NbtTag;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use super::data::snbt_colorful_display;
+use crate::command::args::block_pos::BlockPosArgumentConsumer;
+use crate::command::args::bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::entity::EntityArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg, FindArgDefaultName};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument, argument_default_name, literal};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender, audit};
+use crate::entity::{EntityBase, NBTStorage};
+use CommandError::CommandFailed;
+
+const NAMES: [&str; 1] = ["nbtview"];
+
+const DESCRIPTION: &str = "Shows the NBT data of a block entity or entity, paged in chat.";
+
+const ARG_POS: &str = "pos";
+const ARG_ENTITY: &str = "entity";
+
+/// Top-level tags shown per page.
+const TAGS_PER_PAGE: usize = 10;
+
+const fn page_consumer() -> BoundedNumArgumentConsumer<i32> {
+    BoundedNumArgumentConsumer::new().name("page").min(1)
+}
+
+fn find_page(args: &ConsumedArgs) -> Result<usize, CommandError> {
+    match page_consumer().find_arg_default_name(args) {
+        Err(_) => Ok(1),
+        Ok(Ok(page)) => Ok(page as usize),
+        Ok(Err(_)) => Err(CommandFailed(TextComponent::text("Invalid page."))),
+    }
+}
+
+/// Sends one page of the root compound's tags, one tag per line.
+async fn send_page(
+    sender: &CommandSender,
+    title: String,
+    nbt: NbtCompound,
+    page: usize,
+) -> Result<i32, CommandError> {
+    let tags = nbt.child_tags;
+    let pages = tags.len().div_ceil(TAGS_PER_PAGE).max(1);
+    if page > pages {
+        return Err(CommandFailed(TextComponent::text(format!(
+            "Page {page} does not exist, there are {pages} pages."
+        ))));
+    }
+
+    sender
+        .send_message(
+            TextComponent::text(format!(
+                "NBT of {title} ({} tags, page {page}/{pages})",
+                tags.len()
+            ))
+            .color_named(NamedColor::Gold),
+        )
+        .await;
+    for (key, tag) in tags
+        .iter()
+        .skip((page - 1) * TAGS_PER_PAGE)
+        .take(TAGS_PER_PAGE)
+    {
+        let value =
+            snbt_colorful_display(tag, 1).map_err(|err| CommandFailed(TextComponent::text(err)))?;
+        sender
+            .send_message(
+                TextComponent::text(format!("{key}: "))
+                    .color_named(NamedColor::Aqua)
+                    .add_child(value),
+            )
+            .await;
+    }
+    if page < pages {
+        sender
+            .send_message(
+                TextComponent::text(format!("Use page {} to see more.", page + 1))
+                    .color_named(NamedColor::Gray),
+            )
+            .await;
+    }
+    Ok(tags.len() as i32)
+}
+
+struct BlockExecutor;
+
+impl CommandExecutor for BlockExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let pos = BlockPosArgumentConsumer::find_arg(args, ARG_POS)?;
+            let page = find_page(args)?;
+            let Some(world) = sender.world() else {
+                return Err(CommandFailed(TextComponent::text(
+                    "This command can only be used in a world.",
+                )));
+            };
+            let Some(block_entity) = world.get_block_entity(&pos).await else {
+                return Err(CommandFailed(TextComponent::translate(
+                    "commands.data.block.invalid",
+                    [],
+                )));
+            };
+
+            let mut nbt = NbtCompound::new();
+            block_entity.write_nbt(&mut nbt).await;
+            audit::record(sender, &format!("viewed the NBT of the block at {pos}"));
+            send_page(sender, format!("block at {pos}"), nbt, page).await
+        })
+    }
+}
+
+struct EntityExecutor;
+
+impl CommandExecutor for EntityExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let entity = EntityArgumentConsumer::find_arg(args, ARG_ENTITY)?;
+            let page = find_page(args)?;
+
+            let mut nbt = NbtCompound::new();
+            entity.as_nbt_storage().write_nbt(&mut nbt).await;
+            let name = entity.get_display_name().await.get_text();
+            audit::record(sender, &format!("viewed the NBT of entity {name}"));
+            send_page(sender, name, nbt, page).await
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(
+            literal("block").then(
+                argument(ARG_POS, BlockPosArgumentConsumer)
+                    .then(argument_default_name(page_consumer()).execute(BlockExecutor))
+                    .execute(BlockExecutor),
+            ),
+        )
+        .then(
+            literal("entity").then(
+                argument(ARG_ENTITY, EntityArgumentConsumer)
+                    .then(argument_default_name(page_consumer()).execute(EntityExecutor))
+                    .execute(EntityExecutor),
+            ),
+        )
+}
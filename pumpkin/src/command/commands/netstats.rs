@@ -0,0 +1,140 @@
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::players::PlayersArgumentConsumer;
+use crate::command::args::{Arg, ConsumedArgs};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::argument;
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender};
+use crate::net::traffic::{self, TrafficSample, TrafficSnapshot, format_bytes};
+use CommandError::InvalidConsumption;
+
+const NAMES: [&str; 1] = ["netstats"];
+
+const DESCRIPTION: &str = "Shows network traffic statistics per player and packet type.";
+
+const ARG_TARGETS: &str = "targets";
+
+/// How many packet types are listed in a report.
+const TOP_PACKETS: usize = 8;
+
+async fn send_report(
+    sender: &CommandSender,
+    title: String,
+    snapshot: &TrafficSnapshot,
+    lifetime: TrafficSample,
+) {
+    let average = snapshot.average_per_second();
+    let lines = [
+        TextComponent::text(title).color_named(NamedColor::Gold),
+        TextComponent::text(format!(
+            "Total: in {} ({} packets), out {} ({} packets)",
+            format_bytes(lifetime.inbound.bytes),
+            lifetime.inbound.packets,
+            format_bytes(lifetime.outbound.bytes),
+            lifetime.outbound.packets,
+        )),
+        TextComponent::text(format!(
+            "Last {}s: in {}/s ({} packets/s), out {}/s ({} packets/s)",
+            snapshot.history.len(),
+            format_bytes(average.inbound.bytes),
+            average.inbound.packets,
+            format_bytes(average.outbound.bytes),
+            average.outbound.packets,
+        )),
+    ];
+    for line in lines {
+        sender.send_message(line).await;
+    }
+
+    for (key, counter) in snapshot.top_by_bytes(TOP_PACKETS) {
+        sender
+            .send_message(
+                TextComponent::text(format!(
+                    "  {}: {} ({} packets)",
+                    key.label(),
+                    format_bytes(counter.bytes),
+                    counter.packets
+                ))
+                .color_named(NamedColor::Gray),
+            )
+            .await;
+    }
+}
+
+struct ServerExecutor;
+
+impl CommandExecutor for ServerExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let mut snapshot = TrafficSnapshot::default();
+            let players = server.get_all_players();
+            for player in &players {
+                if let Some(traffic) = player.client.traffic() {
+                    snapshot.merge(&traffic.snapshot());
+                }
+            }
+
+            send_report(
+                sender,
+                format!("Network traffic of {} online players", players.len()),
+                &snapshot,
+                traffic::global_totals(),
+            )
+            .await;
+            Ok(players.len() as i32)
+        })
+    }
+}
+
+struct PlayersExecutor;
+
+impl CommandExecutor for PlayersExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(Arg::Players(targets)) = args.get(&ARG_TARGETS) else {
+                return Err(InvalidConsumption(Some(ARG_TARGETS.into())));
+            };
+
+            let mut reported = 0;
+            for target in targets {
+                let Some(traffic) = target.client.traffic() else {
+                    sender
+                        .send_message(TextComponent::text(format!(
+                            "No traffic statistics are tracked for {}",
+                            target.gameprofile.name
+                        )))
+                        .await;
+                    continue;
+                };
+                let snapshot = traffic.snapshot();
+                send_report(
+                    sender,
+                    format!("Network traffic of {}", target.gameprofile.name),
+                    &snapshot,
+                    snapshot.totals,
+                )
+                .await;
+                reported += 1;
+            }
+
+            Ok(reported)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(ServerExecutor)
+        .then(argument(ARG_TARGETS, PlayersArgumentConsumer).execute(PlayersExecutor))
+}
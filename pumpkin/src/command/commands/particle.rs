@@ -67,6 +67,13 @@ impl CommandExecutor for Executor {
                 CommandSender::CommandBlock(c, w) => {
                     (w.clone(), c.get_position().to_centered_f64())
                 }
+                CommandSender::InWorld(inner, w) => {
+                    let pos = pos
+                        .ok()
+                        .or_else(|| inner.position())
+                        .ok_or(CommandError::InvalidRequirement)?;
+                    (w.clone(), pos)
+                }
             };
 
             world
@@ -0,0 +1,203 @@
+use pumpkin_data::dimension::Dimension;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::tag::NbtTag;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+use uuid::Uuid;
+
+use crate::command::args::gamemode::GamemodeArgumentConsumer;
+use crate::command::args::position_block::BlockPosArgumentConsumer;
+use crate::command::args::simple::SimpleArgConsumer;
+use crate::command::args::{Arg, ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument, literal};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender, audit};
+use crate::net::offline_uuid;
+use crate::server::Server;
+use CommandError::{CommandFailed, InvalidConsumption};
+
+const NAMES: [&str; 1] = ["playerdata"];
+
+const DESCRIPTION: &str = "Edits the saved data of offline players.";
+
+const ARG_PLAYER: &str = "player";
+const ARG_GAMEMODE: &str = "gamemode";
+const ARG_POS: &str = "pos";
+
+/// Tags reset by `resetstats`, together with the values a fresh player starts with.
+fn fresh_stats() -> [(&'static str, NbtTag); 5] {
+    [
+        ("XpTotal", NbtTag::Int(0)),
+        ("Health", NbtTag::Float(20.0)),
+        ("foodLevel", NbtTag::Int(20)),
+        ("foodSaturationLevel", NbtTag::Float(5.0)),
+        ("foodExhaustionLevel", NbtTag::Float(0.0)),
+    ]
+}
+
+enum Edit {
+    Clear,
+    Gamemode,
+    Spawn,
+    ResetStats,
+}
+
+/// Resolves a player name or UUID to the UUID their data is stored under.
+fn resolve_uuid(server: &Server, player: &str) -> Result<Uuid, CommandError> {
+    if let Ok(uuid) = Uuid::parse_str(player) {
+        return Ok(uuid);
+    }
+    if server.basic_config.online_mode {
+        // There is no name cache to look up offline players in
+        return Err(CommandFailed(TextComponent::text(
+            "Player names can only be resolved in offline mode, use the player's UUID instead.",
+        )));
+    }
+    offline_uuid(player).map_err(|_| CommandFailed(TextComponent::text("Invalid player name.")))
+}
+
+/// Replaces a tag, since [`NbtCompound::put`] keeps existing values.
+fn set(nbt: &mut NbtCompound, name: &str, value: impl Into<NbtTag>) {
+    nbt.remove(name);
+    nbt.put(name, value);
+}
+
+struct Executor(Edit);
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(Arg::Simple(player)) = args.get(&ARG_PLAYER) else {
+                return Err(InvalidConsumption(Some(ARG_PLAYER.into())));
+            };
+            let uuid = resolve_uuid(server, player)?;
+            if server.get_player_by_uuid(uuid).is_some() {
+                return Err(CommandFailed(TextComponent::text(format!(
+                    "{player} is online, use the regular commands instead."
+                ))));
+            }
+
+            let storage = &server.player_data_storage;
+            let (action, result) = match self.0 {
+                Edit::Clear => {
+                    let removed = storage
+                        .edit_offline_data(uuid, |nbt| {
+                            let items = match nbt.remove("Inventory") {
+                                Some(NbtTag::List(items)) => items.len(),
+                                _ => 0,
+                            };
+                            let equipment = match nbt.remove("equipment") {
+                                Some(NbtTag::Compound(equipment)) => equipment.child_tags.len(),
+                                _ => 0,
+                            };
+                            nbt.put("Inventory", NbtTag::List(Vec::new()));
+                            (items + equipment) as i32
+                        })
+                        .await;
+                    (format!("cleared the inventory of {player}"), removed)
+                }
+                Edit::Gamemode => {
+                    let gamemode = GamemodeArgumentConsumer::find_arg(args, ARG_GAMEMODE)?;
+                    let changed = storage
+                        .edit_offline_data(uuid, move |nbt| {
+                            if let Some(NbtTag::Byte(previous)) = nbt.remove("playerGameType") {
+                                set(nbt, "previousPlayerGameType", NbtTag::Byte(previous));
+                            }
+                            nbt.put("playerGameType", NbtTag::Byte(gamemode as i8));
+                            1
+                        })
+                        .await;
+                    (
+                        format!(
+                            "set the game mode of {player} to {}",
+                            gamemode.to_str().to_lowercase()
+                        ),
+                        changed,
+                    )
+                }
+                Edit::Spawn => {
+                    let pos: BlockPos = BlockPosArgumentConsumer::find_arg(args, ARG_POS)?;
+                    let dimension = sender
+                        .world()
+                        .map_or(Dimension::OVERWORLD.minecraft_name, |world| {
+                            world.dimension.minecraft_name
+                        });
+                    let changed = storage
+                        .edit_offline_data(uuid, move |nbt| {
+                            set(nbt, "SpawnX", NbtTag::Int(pos.0.x));
+                            set(nbt, "SpawnY", NbtTag::Int(pos.0.y));
+                            set(nbt, "SpawnZ", NbtTag::Int(pos.0.z));
+                            set(nbt, "SpawnDimension", NbtTag::String(dimension.to_string()));
+                            set(nbt, "SpawnForced", NbtTag::Byte(1));
+                            1
+                        })
+                        .await;
+                    (
+                        format!("moved the spawn point of {player} to {pos} in {dimension}"),
+                        changed,
+                    )
+                }
+                Edit::ResetStats => {
+                    let changed = storage
+                        .edit_offline_data(uuid, |nbt| {
+                            for (name, value) in fresh_stats() {
+                                set(nbt, name, value);
+                            }
+                            nbt.remove("foodTickTimer");
+                            nbt.remove("active_effects");
+                            1
+                        })
+                        .await;
+                    (
+                        format!("reset the experience, health, hunger and effects of {player}"),
+                        changed,
+                    )
+                }
+            };
+
+            let result = match result {
+                Ok(Some(result)) => result,
+                Ok(None) => {
+                    return Err(CommandFailed(TextComponent::text(format!(
+                        "No saved data found for {player}."
+                    ))));
+                }
+                Err(err) => {
+                    return Err(CommandFailed(TextComponent::text(format!(
+                        "Failed to edit the data of {player}: {err}"
+                    ))));
+                }
+            };
+            audit::record(sender, &format!("{action} ({uuid}, offline)"));
+            sender
+                .send_message(
+                    TextComponent::text(format!("Successfully {action}."))
+                        .color_named(NamedColor::Green),
+                )
+                .await;
+            Ok(result)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION).then(
+        argument(ARG_PLAYER, SimpleArgConsumer)
+            .then(literal("clear").execute(Executor(Edit::Clear)))
+            .then(literal("gamemode").then(
+                argument(ARG_GAMEMODE, GamemodeArgumentConsumer).execute(Executor(Edit::Gamemode)),
+            ))
+            .then(
+                literal("spawn").then(
+                    argument(ARG_POS, BlockPosArgumentConsumer).execute(Executor(Edit::Spawn)),
+                ),
+            )
+            .then(literal("resetstats").execute(Executor(Edit::ResetStats))),
+    )
+}
@@ -16,7 +16,7 @@ use crate::{
         dispatcher::CommandError,
         tree::{CommandTree, builder::argument},
     },
-    entity::player::Player,
+    entity::{feedback::SoundEffect, player::Player},
 };
 
 /// Command: playsound <sound> [<source>] [<targets>] [<pos>] [<volume>] [<pitch>] [<minVolume>]
@@ -115,8 +115,13 @@ impl CommandExecutor for Executor {
                 _ => 0.0, // Default minimum volume
             };
 
+            let sound_name = sound.name();
             // Use same random seed for all targets to ensure sound synchronization
-            let seed = rng().random::<f64>();
+            let effect = SoundEffect::new(sound)
+                .category(source)
+                .volume(volume)
+                .pitch(pitch)
+                .seed(rng().random::<f64>());
 
             // Track how many players actually received the sound
             let mut players_who_heard = 0;
@@ -131,9 +136,7 @@ impl CommandExecutor for Executor {
                 let max_distance: f64 = (16.0 * volume).into(); // 16 blocks is base distance at volume 1.0
 
                 if distance <= max_distance || min_volume > 0.0 {
-                    target
-                        .play_sound(sound as u16, source, &pos, volume, pitch, seed)
-                        .await;
+                    target.play_sound(&effect.clone().at(pos)).await;
                     players_who_heard += 1;
                 }
             }
@@ -145,7 +148,6 @@ impl CommandExecutor for Executor {
                     [],
                 )))
             } else {
-                let sound_name = sound.to_name();
                 if players_who_heard == 1 {
                     sender
                         .send_message(TextComponent::translate(
@@ -0,0 +1,134 @@
+use pumpkin_data::dimension::Dimension;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArgDefaultName};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument_default_name, literal};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender};
+use crate::server::pregen::{PregenJob, PregenShape, format_duration};
+use CommandError::CommandFailed;
+
+const NAMES: [&str; 1] = ["pregen"];
+
+const DESCRIPTION: &str = "Generates the chunks around the world spawn ahead of time.";
+
+/// The largest radius in blocks, about 1.5 million chunks.
+const MAX_RADIUS: i32 = 10_000;
+
+const fn radius_consumer() -> BoundedNumArgumentConsumer<i32> {
+    BoundedNumArgumentConsumer::new()
+        .name("radius")
+        .min(1)
+        .max(MAX_RADIUS)
+}
+
+struct StartExecutor(PregenShape);
+
+impl CommandExecutor for StartExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Ok(Ok(radius)) = radius_consumer().find_arg_default_name(args) else {
+                return Err(CommandFailed(TextComponent::text(format!(
+                    "The radius must be between 1 and {MAX_RADIUS} blocks."
+                ))));
+            };
+            let world = sender
+                .world()
+                .unwrap_or_else(|| server.get_world_from_dimension(&Dimension::OVERWORLD));
+            let job = PregenJob::around_spawn(server, &world, radius, self.0);
+            let total = job.positions().len();
+            server
+                .pregen
+                .start(server, job)
+                .map_err(|err| CommandFailed(TextComponent::text(err.to_string())))?;
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "Pregenerating {total} chunks of {}, the progress is logged to the console.",
+                        world.dimension.minecraft_name
+                    ))
+                    .color_named(NamedColor::Green),
+                )
+                .await;
+            Ok(total as i32)
+        })
+    }
+}
+
+struct StatusExecutor;
+
+impl CommandExecutor for StatusExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(status) = server.pregen.status() else {
+                sender
+                    .send_message(TextComponent::text("No pregeneration is running."))
+                    .await;
+                return Ok(0);
+            };
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Pregenerating {}: {} of {} chunks, {:.1} chunks/s, ETA {}",
+                    status.dimension,
+                    status.done,
+                    status.total,
+                    status.chunks_per_second,
+                    status
+                        .eta
+                        .map_or_else(|| "unknown".to_string(), format_duration)
+                )))
+                .await;
+            Ok(status.done as i32)
+        })
+    }
+}
+
+struct CancelExecutor;
+
+impl CommandExecutor for CancelExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            if !server.pregen.cancel() {
+                return Err(CommandFailed(TextComponent::text(
+                    "No pregeneration is running.",
+                )));
+            }
+            sender
+                .send_message(
+                    TextComponent::text("Cancelled the pregeneration.")
+                        .color_named(NamedColor::Yellow),
+                )
+                .await;
+            Ok(1)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(literal("status").execute(StatusExecutor))
+        .then(literal("cancel").execute(CancelExecutor))
+        .then(
+            argument_default_name(radius_consumer())
+                .execute(StartExecutor(PregenShape::Square))
+                .then(literal("square").execute(StartExecutor(PregenShape::Square)))
+                .then(literal("circle").execute(StartExecutor(PregenShape::Circle))),
+        )
+}
@@ -0,0 +1,85 @@
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::literal;
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender};
+
+const NAMES: [&str; 1] = ["profile"];
+
+const DESCRIPTION: &str = "Times the parts of each tick to find what slows the server down.";
+
+struct StartExecutor;
+
+impl CommandExecutor for StartExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            if !server.tick_profiler.start_session() {
+                return Err(CommandError::CommandFailed(TextComponent::text(
+                    "The profiler is already running",
+                )));
+            }
+            sender
+                .send_message(
+                    TextComponent::text("Started profiling, stop with /profile stop")
+                        .color_named(NamedColor::Green),
+                )
+                .await;
+            Ok(1)
+        })
+    }
+}
+
+struct StopExecutor;
+
+impl CommandExecutor for StopExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(report) = server.tick_profiler.stop_session() else {
+                return Err(CommandError::CommandFailed(TextComponent::text(
+                    "The profiler is not running",
+                )));
+            };
+
+            let header = format!(
+                "Profiled {} ticks over {:.1}s",
+                report.ticks,
+                report.duration.as_secs_f64()
+            );
+            log::info!("{header}");
+            sender
+                .send_message(TextComponent::text(header).color_named(NamedColor::Green))
+                .await;
+            for (title, lines) in [("Call tree:", report.tree()), ("Self time:", report.flat())] {
+                log::info!("{title}");
+                sender
+                    .send_message(TextComponent::text(title).color_named(NamedColor::Gold))
+                    .await;
+                for line in lines {
+                    log::info!("{line}");
+                    sender
+                        .send_message(TextComponent::text(line).color_named(NamedColor::Gray))
+                        .await;
+                }
+            }
+            Ok(report.ticks as i32)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(literal("start").execute(StartExecutor))
+        .then(literal("stop").execute(StopExecutor))
+}
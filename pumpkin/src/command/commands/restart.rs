@@ -0,0 +1,83 @@
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::command::args::bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArgDefaultName};
+use crate::command::dispatcher::CommandError;
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument_default_name, literal};
+use crate::command::{CommandExecutor, CommandResult, CommandSender};
+
+const NAMES: [&str; 1] = ["restart"];
+
+const DESCRIPTION: &str = "Restarts the server after a countdown.";
+
+const fn seconds_consumer() -> BoundedNumArgumentConsumer<i32> {
+    BoundedNumArgumentConsumer::new().name("seconds").min(0)
+}
+
+struct Executor;
+
+impl CommandExecutor for Executor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let seconds = match seconds_consumer().find_arg_default_name(args) {
+                Err(_) => server.advanced_config.restart.default_countdown_seconds,
+                Ok(Ok(seconds)) => seconds as u32,
+                Ok(Err(_)) => {
+                    return Err(CommandError::CommandFailed(TextComponent::text(
+                        "Invalid countdown.",
+                    )));
+                }
+            };
+
+            if !server.restart.schedule(server, seconds).await {
+                return Err(CommandError::CommandFailed(TextComponent::text(
+                    "A restart is already scheduled. Use /restart cancel to abort it.",
+                )));
+            }
+            sender
+                .send_message(
+                    TextComponent::text(format!("Restarting the server in {seconds} seconds"))
+                        .color_named(NamedColor::Red),
+                )
+                .await;
+            Ok(seconds as i32)
+        })
+    }
+}
+
+struct CancelExecutor;
+
+impl CommandExecutor for CancelExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            if !server.restart.cancel(server).await {
+                return Err(CommandError::CommandFailed(TextComponent::text(
+                    "No restart is scheduled.",
+                )));
+            }
+            sender
+                .send_message(TextComponent::text("Cancelled the scheduled restart."))
+                .await;
+            Ok(1)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(literal("cancel").execute(CancelExecutor))
+        .then(argument_default_name(seconds_consumer()).execute(Executor))
+        .execute(Executor)
+}
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use crate::command::CommandResult;
 use crate::command::{
     CommandExecutor, CommandSender, args::ConsumedArgs, tree::CommandTree, tree::builder::literal,
@@ -8,31 +10,12 @@ const NAMES: [&str; 1] = ["save-all"];
 
 const DESCRIPTION: &str = "Saves the server to disk.";
 
-struct SaveExecutor;
-
-impl CommandExecutor for SaveExecutor {
-    fn execute<'a>(
-        &'a self,
-        sender: &'a CommandSender,
-        server: &'a crate::server::Server,
-        _args: &'a ConsumedArgs<'a>,
-    ) -> CommandResult<'a> {
-        Box::pin(async move {
-            sender
-                .send_message(TextComponent::translate("commands.save.saving", []))
-                .await;
-            server.save_all(false).await;
-            sender
-                .send_message(TextComponent::translate("commands.save.success", []))
-                .await;
-            Ok(1)
-        })
-    }
+/// Saves the server, waiting for all chunk writes to finish if `flush` is set.
+struct SaveExecutor {
+    flush: bool,
 }
 
-struct FlushExecutor;
-
-impl CommandExecutor for FlushExecutor {
+impl CommandExecutor for SaveExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
@@ -43,9 +26,14 @@ impl CommandExecutor for FlushExecutor {
             sender
                 .send_message(TextComponent::translate("commands.save.saving", []))
                 .await;
-            server.save_all(true).await;
+            let start = Instant::now();
+            server.save_all(self.flush).await;
+            // Backup scripts rely on the time to see how long saves block them
             sender
-                .send_message(TextComponent::translate("commands.save.success", []))
+                .send_message(
+                    TextComponent::translate("commands.save.success", [])
+                        .add_text(format!(" ({} ms)", start.elapsed().as_millis())),
+                )
                 .await;
             Ok(1)
         })
@@ -54,6 +42,6 @@ impl CommandExecutor for FlushExecutor {
 
 pub fn init_command_tree() -> CommandTree {
     CommandTree::new(NAMES, DESCRIPTION)
-        .execute(SaveExecutor)
-        .then(literal("flush").execute(FlushExecutor))
+        .execute(SaveExecutor { flush: false })
+        .then(literal("flush").execute(SaveExecutor { flush: true }))
 }
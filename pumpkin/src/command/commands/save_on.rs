@@ -1,6 +1,5 @@
-use std::sync::atomic::Ordering;
-
 use crate::command::CommandResult;
+use crate::command::dispatcher::CommandError;
 use crate::command::{CommandExecutor, CommandSender, args::ConsumedArgs, tree::CommandTree};
 use pumpkin_util::text::TextComponent;
 
@@ -18,7 +17,12 @@ impl CommandExecutor for Executor {
         _args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            server.autosave_enabled.store(true, Ordering::Relaxed);
+            if server.set_autosave(true) {
+                return Err(CommandError::CommandFailed(TextComponent::translate(
+                    "commands.save.alreadyOn",
+                    [],
+                )));
+            }
             sender
                 .send_message(TextComponent::translate("commands.save.enabled", []))
                 .await;
@@ -0,0 +1,171 @@
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+use pumpkin_world::schematic::{Mirror, Rotation, Transform};
+
+use crate::command::args::bool::BoolArgConsumer;
+use crate::command::args::position_block::BlockPosArgumentConsumer;
+use crate::command::args::simple::SimpleArgConsumer;
+use crate::command::args::{Arg, ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument, literal};
+use crate::command::{CommandError, CommandExecutor, CommandResult, CommandSender, audit};
+use crate::server::schematics::Schematics;
+use CommandError::{CommandFailed, InvalidConsumption, InvalidRequirement};
+
+const NAMES: [&str; 1] = ["schem"];
+
+const DESCRIPTION: &str = "Loads and pastes .schem and .litematic schematics.";
+
+const ARG_NAME: &str = "name";
+const ARG_POS: &str = "pos";
+const ARG_ROTATION: &str = "rotation";
+const ARG_MIRROR: &str = "mirror";
+const ARG_SKIP_AIR: &str = "skip_air";
+
+fn parse_transform(args: &ConsumedArgs) -> Result<Transform, CommandError> {
+    let rotation = match args.get(&ARG_ROTATION) {
+        Some(Arg::Simple(rotation)) => rotation
+            .parse()
+            .ok()
+            .and_then(Rotation::from_degrees)
+            .ok_or_else(|| {
+                CommandFailed(TextComponent::text(format!(
+                    "Invalid rotation {rotation}, expected 0, 90, 180 or 270"
+                )))
+            })?,
+        _ => Rotation::None,
+    };
+    let mirror = match args.get(&ARG_MIRROR) {
+        Some(Arg::Simple("none")) | None => Mirror::None,
+        Some(Arg::Simple("left_right")) => Mirror::LeftRight,
+        Some(Arg::Simple("front_back")) => Mirror::FrontBack,
+        Some(Arg::Simple(mirror)) => {
+            return Err(CommandFailed(TextComponent::text(format!(
+                "Invalid mirror {mirror}, expected none, left_right or front_back"
+            ))));
+        }
+        Some(_) => return Err(InvalidConsumption(Some(ARG_MIRROR.into()))),
+    };
+    Ok(Transform { rotation, mirror })
+}
+
+struct LoadExecutor;
+
+impl CommandExecutor for LoadExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let Some(Arg::Simple(name)) = args.get(&ARG_NAME) else {
+                return Err(InvalidConsumption(Some(ARG_NAME.into())));
+            };
+            let schematic = server
+                .schematics
+                .load(sender.to_string(), name)
+                .await
+                .map_err(|err| {
+                    CommandFailed(TextComponent::text(format!(
+                        "Failed to load {name} from {}: {err}",
+                        Schematics::directory().display()
+                    )))
+                })?;
+
+            let size = schematic.size;
+            sender
+                .send_message(
+                    TextComponent::text(format!(
+                        "Loaded {name} ({} x {} x {}), paste it with /schem paste",
+                        size.x, size.y, size.z
+                    ))
+                    .color_named(NamedColor::Green),
+                )
+                .await;
+            if !schematic.unknown_blocks.is_empty() {
+                sender
+                    .send_message(
+                        TextComponent::text(format!(
+                            "Unknown blocks are pasted as air: {}",
+                            schematic.unknown_blocks.join(", ")
+                        ))
+                        .color_named(NamedColor::Yellow),
+                    )
+                    .await;
+            }
+            Ok(1)
+        })
+    }
+}
+
+struct PasteExecutor;
+
+impl CommandExecutor for PasteExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a crate::server::Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let origin = BlockPosArgumentConsumer::find_arg(args, ARG_POS)?;
+            let transform = parse_transform(args)?;
+            let skip_air = matches!(args.get(&ARG_SKIP_AIR), Some(Arg::Bool(true)));
+            let world = match sender.world() {
+                Some(world) => world,
+                None => server
+                    .worlds
+                    .load()
+                    .first()
+                    .cloned()
+                    .ok_or(InvalidRequirement)?,
+            };
+            let owner = sender.to_string();
+            let schematic = server.schematics.clipboard(&owner).await.ok_or_else(|| {
+                CommandFailed(TextComponent::text(
+                    "Load a schematic with /schem load first",
+                ))
+            })?;
+
+            let blocks = server
+                .schematics
+                .paste(&schematic, owner, world, origin, transform, skip_air)
+                .await;
+            audit::record(
+                sender,
+                &format!("pasted a schematic of {blocks} blocks at {origin}"),
+            );
+            sender
+                .send_message(
+                    TextComponent::text(format!("Pasting {blocks} blocks at {origin}"))
+                        .color_named(NamedColor::Green),
+                )
+                .await;
+            Ok(blocks as i32)
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(literal("load").then(argument(ARG_NAME, SimpleArgConsumer).execute(LoadExecutor)))
+        .then(
+            literal("paste").then(
+                argument(ARG_POS, BlockPosArgumentConsumer)
+                    .execute(PasteExecutor)
+                    .then(
+                        argument(ARG_ROTATION, SimpleArgConsumer)
+                            .execute(PasteExecutor)
+                            .then(
+                                argument(ARG_MIRROR, SimpleArgConsumer)
+                                    .execute(PasteExecutor)
+                                    .then(
+                                        argument(ARG_SKIP_AIR, BoolArgConsumer)
+                                            .execute(PasteExecutor),
+                                    ),
+                            ),
+                    ),
+            ),
+        )
+}
@@ -2,6 +2,7 @@ use crate::command::CommandResult;
 use crate::command::{
     CommandError, CommandExecutor, CommandSender, args::ConsumedArgs, tree::CommandTree,
 };
+use pumpkin_util::PermissionLvl;
 use pumpkin_util::text::click::ClickEvent;
 use pumpkin_util::text::hover::HoverEvent;
 use pumpkin_util::text::{TextComponent, color::NamedColor};
@@ -21,6 +22,11 @@ impl CommandExecutor for Executor {
         _args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
+            if server.advanced_config.world.seed_privacy.hide_from_non_ops
+                && !sender.has_permission_lvl(PermissionLvl::One)
+            {
+                return Err(CommandError::PermissionDenied);
+            }
             let seed = match sender {
                 CommandSender::Player(player) => {
                     player.living_entity.entity.world.load().level.seed.0
@@ -54,7 +54,7 @@ impl CommandExecutor for Executor {
                         .ok_or(CommandError::InvalidRequirement)?
                 }
                 CommandSender::Player(player) => player.world().clone(),
-                CommandSender::CommandBlock(_, w) => w.clone(),
+                CommandSender::CommandBlock(_, w) | CommandSender::InWorld(_, w) => w.clone(),
             };
 
             if !world.is_in_build_limit(pos) {
@@ -6,6 +6,7 @@ use crate::command::{
     },
     tree::{CommandTree, builder::argument},
 };
+use crate::entity::feedback::StopSound;
 use pumpkin_util::text::TextComponent;
 
 const NAMES: [&str; 1] = ["stopsound"];
@@ -30,24 +31,20 @@ impl CommandExecutor for Executor {
             let category = SoundCategoryArgumentConsumer::find_arg(args, ARG_SOURCE);
             let sound = SoundArgumentConsumer::find_arg(args, ARG_SOUND);
 
-            for target in targets {
-                target
-                    .stop_sound(
-                        sound
-                            .as_ref()
-                            .cloned()
-                            .map(|s| format!("minecraft:{}", s.to_name()))
-                            .ok(),
-                        category.as_ref().map(|s| **s).ok(),
-                    )
-                    .await;
+            let mut stop = StopSound::all();
+            if let Ok(sound) = &sound {
+                stop = stop.sound(sound.name());
             }
+            if let Ok(category) = &category {
+                stop = stop.category(**category);
+            }
+            stop.send_to(targets).await;
 
             let text = match (category, sound) {
                 (Ok(c), Ok(s)) => TextComponent::translate(
                     "commands.stopsound.success.source.sound",
                     [
-                        TextComponent::text(s.to_name()),
+                        TextComponent::text(s.name()),
                         TextComponent::text(c.to_name()),
                     ],
                 ),
@@ -57,7 +54,7 @@ impl CommandExecutor for Executor {
                 ),
                 (Err(_), Ok(s)) => TextComponent::translate(
                     "commands.stopsound.success.sourceless.sound",
-                    [TextComponent::text(s.to_name())],
+                    [TextComponent::text(s.name())],
                 ),
                 (Err(_), Err(_)) => {
                     TextComponent::translate("commands.stopsound.success.sourceless.any", [])
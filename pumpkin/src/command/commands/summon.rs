@@ -60,6 +60,13 @@ impl CommandExecutor for Executor {
                     let pos = pos.unwrap_or(c.get_position().to_centered_f64());
                     (w.clone(), pos)
                 }
+                CommandSender::InWorld(inner, w) => {
+                    let pos = pos
+                        .ok()
+                        .or_else(|| inner.position())
+                        .ok_or(CommandError::InvalidRequirement)?;
+                    (w.clone(), pos)
+                }
             };
             let entity = from_type(entity_type, pos, &world, Uuid::new_v4()).await;
             let name = entity.get_display_name().await;
@@ -0,0 +1,644 @@
+use pumpkin_protocol::java::client::play::{CollisionRule, NameTagVisibility};
+use pumpkin_util::text::{TextComponent, color::NamedColor};
+
+use crate::command::args::bool::BoolArgConsumer;
+use crate::command::args::entities::EntitiesArgumentConsumer;
+use crate::command::args::simple::SimpleArgConsumer;
+use crate::command::args::textcomponent::TextComponentArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::dispatcher::CommandError;
+use crate::command::tree::CommandTree;
+use crate::command::tree::builder::{argument, literal};
+use crate::command::{CommandExecutor, CommandResult, CommandSender, resolve_world};
+use crate::server::Server;
+use crate::world::World;
+use crate::world::scoreboard::{Scoreboard, Team};
+
+const NAMES: [&str; 1] = ["team"];
+const DESCRIPTION: &str = "Controls teams.";
+
+const ARG_TEAM: &str = "team";
+const ARG_DISPLAY_NAME: &str = "displayName";
+const ARG_MEMBERS: &str = "members";
+const ARG_VALUE: &str = "value";
+
+const COLORS: [&str; 17] = [
+    "black",
+    "dark_blue",
+    "dark_green",
+    "dark_aqua",
+    "dark_red",
+    "dark_purple",
+    "gold",
+    "gray",
+    "dark_gray",
+    "blue",
+    "green",
+    "aqua",
+    "red",
+    "light_purple",
+    "yellow",
+    "white",
+    "reset",
+];
+
+fn failed(key: &str) -> CommandError {
+    CommandError::CommandFailed(TextComponent::translate(key.to_string(), []))
+}
+
+fn unknown_team(name: &str) -> CommandError {
+    CommandError::CommandFailed(TextComponent::translate(
+        "team.notFound",
+        [TextComponent::text(name.to_string())],
+    ))
+}
+
+/// The scoreboard names of the members of the command, or of the sender if there are none.
+fn member_names(sender: &CommandSender, args: &ConsumedArgs) -> Result<Vec<String>, CommandError> {
+    if let Ok(entities) = EntitiesArgumentConsumer::find_arg(args, ARG_MEMBERS) {
+        return Ok(entities
+            .iter()
+            .map(|entity| Scoreboard::holder_name(entity.as_ref()))
+            .collect());
+    }
+    let player = sender.as_player().ok_or(CommandError::InvalidRequirement)?;
+    Ok(vec![player.gameprofile.name.clone()])
+}
+
+/// Sets `field` to `value`, returning whether that changed it.
+fn replace<T: PartialEq>(field: &mut T, value: T) -> bool {
+    if *field == value {
+        return false;
+    }
+    *field = value;
+    true
+}
+
+struct ListExecutor;
+
+impl CommandExecutor for ListExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let world = resolve_world(sender, server, None)?;
+            let teams: Vec<TextComponent> = world
+                .scoreboard
+                .lock()
+                .await
+                .teams()
+                .map(Team::formatted_display_name)
+                .collect();
+            if teams.is_empty() {
+                sender
+                    .send_message(TextComponent::translate(
+                        "commands.team.list.teams.empty",
+                        [],
+                    ))
+                    .await;
+                return Ok(0);
+            }
+
+            let count = teams.len();
+            let mut list = TextComponent::text("");
+            for (i, team) in teams.into_iter().enumerate() {
+                if i > 0 {
+                    list = list.add_child(TextComponent::text(", "));
+                }
+                list = list.add_child(team);
+            }
+            sender
+                .send_message(TextComponent::translate(
+                    "commands.team.list.teams.success",
+                    [TextComponent::text(count.to_string()), list],
+                ))
+                .await;
+            Ok(count as i32)
+        })
+    }
+}
+
+struct ListMembersExecutor;
+
+impl CommandExecutor for ListMembersExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+            let world = resolve_world(sender, server, None)?;
+            let (team, members) = {
+                let scoreboard = world.scoreboard.lock().await;
+                let team = scoreboard
+                    .get_team(name)
+                    .ok_or_else(|| unknown_team(name))?;
+                (team.formatted_display_name(), team.members().to_vec())
+            };
+
+            if members.is_empty() {
+                sender
+                    .send_message(TextComponent::translate(
+                        "commands.team.list.members.empty",
+                        [team],
+                    ))
+                    .await;
+                return Ok(0);
+            }
+
+            sender
+                .send_message(TextComponent::translate(
+                    "commands.team.list.members.success",
+                    [
+                        team,
+                        TextComponent::text(members.len().to_string()),
+                        TextComponent::text(members.join(", ")),
+                    ],
+                ))
+                .await;
+            Ok(members.len() as i32)
+        })
+    }
+}
+
+struct AddExecutor;
+
+impl CommandExecutor for AddExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+            let display_name = TextComponentArgConsumer::find_arg(args, ARG_DISPLAY_NAME)
+                .unwrap_or_else(|_| TextComponent::text(name.to_string()));
+            let world = resolve_world(sender, server, None)?;
+
+            let (team, count) = {
+                let mut scoreboard = world.scoreboard.lock().await;
+                if !scoreboard.add_team(&world, name, display_name).await {
+                    return Err(failed("commands.team.add.duplicate"));
+                }
+                let team = scoreboard
+                    .get_team(name)
+                    .map(Team::formatted_display_name)
+                    .ok_or_else(|| unknown_team(name))?;
+                (team, scoreboard.teams().count())
+            };
+
+            sender
+                .send_message(TextComponent::translate(
+                    "commands.team.add.success",
+                    [team],
+                ))
+                .await;
+            Ok(count as i32)
+        })
+    }
+}
+
+struct RemoveExecutor;
+
+impl CommandExecutor for RemoveExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+            let world = resolve_world(sender, server, None)?;
+
+            let (team, count) = {
+                let mut scoreboard = world.scoreboard.lock().await;
+                let team = scoreboard
+                    .remove_team(&world, name)
+                    .await
+                    .ok_or_else(|| unknown_team(name))?;
+                (team.formatted_display_name(), scoreboard.teams().count())
+            };
+
+            sender
+                .send_message(TextComponent::translate(
+                    "commands.team.remove.success",
+                    [team],
+                ))
+                .await;
+            Ok(count as i32)
+        })
+    }
+}
+
+struct EmptyExecutor;
+
+impl CommandExecutor for EmptyExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+            let world = resolve_world(sender, server, None)?;
+
+            let (team, count) = {
+                let mut scoreboard = world.scoreboard.lock().await;
+                let count = scoreboard
+                    .empty_team(&world, name)
+                    .await
+                    .ok_or_else(|| unknown_team(name))?;
+                let team = scoreboard
+                    .get_team(name)
+                    .map(Team::formatted_display_name)
+                    .ok_or_else(|| unknown_team(name))?;
+                (team, count)
+            };
+
+            if count == 0 {
+                return Err(failed("commands.team.empty.unchanged"));
+            }
+            sender
+                .send_message(TextComponent::translate(
+                    "commands.team.empty.success",
+                    [TextComponent::text(count.to_string()), team],
+                ))
+                .await;
+            Ok(count as i32)
+        })
+    }
+}
+
+struct JoinExecutor;
+
+impl CommandExecutor for JoinExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+            let members = member_names(sender, args)?;
+            let world = resolve_world(sender, server, None)?;
+
+            let team = {
+                let mut scoreboard = world.scoreboard.lock().await;
+                if !scoreboard.join_team(&world, name, &members).await {
+                    return Err(unknown_team(name));
+                }
+                scoreboard
+                    .get_team(name)
+                    .map(Team::formatted_display_name)
+                    .ok_or_else(|| unknown_team(name))?
+            };
+
+            let message = if let [member] = members.as_slice() {
+                TextComponent::translate(
+                    "commands.team.join.success.single",
+                    [TextComponent::text(member.clone()), team],
+                )
+            } else {
+                TextComponent::translate(
+                    "commands.team.join.success.multiple",
+                    [TextComponent::text(members.len().to_string()), team],
+                )
+            };
+            sender.send_message(message).await;
+            Ok(members.len() as i32)
+        })
+    }
+}
+
+struct LeaveExecutor;
+
+impl CommandExecutor for LeaveExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let members = member_names(sender, args)?;
+            let world = resolve_world(sender, server, None)?;
+
+            {
+                let mut scoreboard = world.scoreboard.lock().await;
+                for member in &members {
+                    scoreboard.leave_team(&world, member).await;
+                }
+            }
+
+            let message = if let [member] = members.as_slice() {
+                TextComponent::translate(
+                    "commands.team.leave.success.single",
+                    [TextComponent::text(member.clone())],
+                )
+            } else {
+                TextComponent::translate(
+                    "commands.team.leave.success.multiple",
+                    [TextComponent::text(members.len().to_string())],
+                )
+            };
+            sender.send_message(message).await;
+            Ok(members.len() as i32)
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum TeamOption {
+    Color {
+        color: Option<NamedColor>,
+        name: &'static str,
+    },
+    DisplayName,
+    Prefix,
+    Suffix,
+    FriendlyFire,
+    SeeFriendlyInvisibles,
+    NameTagVisibility(NameTagVisibility),
+    CollisionRule(CollisionRule),
+}
+
+struct ModifyExecutor(TeamOption);
+
+impl ModifyExecutor {
+    /// Applies `update` to the team and returns whether it changed along with the name of the
+    /// team to show.
+    async fn modify(
+        world: &World,
+        name: &str,
+        update: impl FnOnce(&mut Team) -> bool,
+    ) -> Result<(bool, TextComponent), CommandError> {
+        let mut scoreboard = world.scoreboard.lock().await;
+        let changed = scoreboard
+            .update_team(world, name, update)
+            .await
+            .ok_or_else(|| unknown_team(name))?;
+        let team = scoreboard
+            .get_team(name)
+            .map(Team::formatted_display_name)
+            .ok_or_else(|| unknown_team(name))?;
+        Ok((changed, team))
+    }
+
+    /// The messages of the flag options, which only differ in their key.
+    async fn set_flag(
+        sender: &CommandSender,
+        world: &World,
+        name: &str,
+        value: bool,
+        key: &str,
+        field: impl FnOnce(&mut Team) -> &mut bool,
+    ) -> Result<i32, CommandError> {
+        let (changed, team) = Self::modify(world, name, |team| replace(field(team), value)).await?;
+        let state = if value { "Enabled" } else { "Disabled" };
+        if !changed {
+            return Err(failed(&format!(
+                "commands.team.option.{key}.already{state}"
+            )));
+        }
+        sender
+            .send_message(TextComponent::translate(
+                format!("commands.team.option.{key}.{}", state.to_lowercase()),
+                [team],
+            ))
+            .await;
+        Ok(1)
+    }
+}
+
+impl CommandExecutor for ModifyExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+            let world = resolve_world(sender, server, None)?;
+
+            match self.0 {
+                TeamOption::Color {
+                    color,
+                    name: color_name,
+                } => {
+                    let (changed, team) =
+                        Self::modify(&world, name, |team| replace(&mut team.color, color)).await?;
+                    if !changed {
+                        return Err(failed("commands.team.option.color.unchanged"));
+                    }
+                    sender
+                        .send_message(TextComponent::translate(
+                            "commands.team.option.color.success",
+                            [team, TextComponent::text(color_name)],
+                        ))
+                        .await;
+                    Ok(1)
+                }
+                TeamOption::DisplayName => {
+                    let display_name = TextComponentArgConsumer::find_arg(args, ARG_VALUE)?;
+                    let (changed, team) = Self::modify(&world, name, |team| {
+                        replace(&mut team.display_name, display_name)
+                    })
+                    .await?;
+                    if !changed {
+                        return Err(failed("commands.team.option.name.unchanged"));
+                    }
+                    sender
+                        .send_message(TextComponent::translate(
+                            "commands.team.option.name.success",
+                            [team],
+                        ))
+                        .await;
+                    Ok(1)
+                }
+                TeamOption::Prefix | TeamOption::Suffix => {
+                    let text = TextComponentArgConsumer::find_arg(args, ARG_VALUE)?;
+                    let is_prefix = matches!(self.0, TeamOption::Prefix);
+                    Self::modify(&world, name, |team| {
+                        let field = if is_prefix {
+                            &mut team.prefix
+                        } else {
+                            &mut team.suffix
+                        };
+                        replace(field, text.clone())
+                    })
+                    .await?;
+                    let key = if is_prefix {
+                        "commands.team.option.prefix.success"
+                    } else {
+                        "commands.team.option.suffix.success"
+                    };
+                    sender
+                        .send_message(TextComponent::translate(key, [text]))
+                        .await;
+                    Ok(1)
+                }
+                TeamOption::FriendlyFire => {
+                    let value = BoolArgConsumer::find_arg(args, ARG_VALUE)?;
+                    Self::set_flag(sender, &world, name, value, "friendlyfire", |team| {
+                        &mut team.friendly_fire
+                    })
+                    .await
+                }
+                TeamOption::SeeFriendlyInvisibles => {
+                    let value = BoolArgConsumer::find_arg(args, ARG_VALUE)?;
+                    Self::set_flag(
+                        sender,
+                        &world,
+                        name,
+                        value,
+                        "seeFriendlyInvisibles",
+                        |team| &mut team.see_friendly_invisibles,
+                    )
+                    .await
+                }
+                TeamOption::NameTagVisibility(visibility) => {
+                    let (changed, team) = Self::modify(&world, name, |team| {
+                        replace(&mut team.name_tag_visibility, visibility)
+                    })
+                    .await?;
+                    if !changed {
+                        return Err(failed("commands.team.option.nametagVisibility.unchanged"));
+                    }
+                    sender
+                        .send_message(TextComponent::translate(
+                            "commands.team.option.nametagVisibility.success",
+                            [
+                                team,
+                                TextComponent::translate(
+                                    format!("team.visibility.{}", visibility.name()),
+                                    [],
+                                ),
+                            ],
+                        ))
+                        .await;
+                    Ok(1)
+                }
+                TeamOption::CollisionRule(rule) => {
+                    let (changed, team) =
+                        Self::modify(&world, name, |team| replace(&mut team.collision_rule, rule))
+                            .await?;
+                    if !changed {
+                        return Err(failed("commands.team.option.collisionRule.unchanged"));
+                    }
+                    sender
+                        .send_message(TextComponent::translate(
+                            "commands.team.option.collisionRule.success",
+                            [
+                                team,
+                                TextComponent::translate(
+                                    format!("team.collision.{}", rule.name()),
+                                    [],
+                                ),
+                            ],
+                        ))
+                        .await;
+                    Ok(1)
+                }
+            }
+        })
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    let mut name_tag_visibility = literal("nametagVisibility");
+    for visibility in NameTagVisibility::ALL {
+        name_tag_visibility = name_tag_visibility.then(
+            literal(visibility.name())
+                .execute(ModifyExecutor(TeamOption::NameTagVisibility(visibility))),
+        );
+    }
+    let mut collision_rule = literal("collisionRule");
+    for rule in CollisionRule::ALL {
+        collision_rule = collision_rule
+            .then(literal(rule.name()).execute(ModifyExecutor(TeamOption::CollisionRule(rule))));
+    }
+    let mut color = literal("color");
+    for name in COLORS {
+        // Everything but `reset` is a named color
+        let option = TeamOption::Color {
+            color: NamedColor::try_from(name).ok(),
+            name,
+        };
+        color = color.then(literal(name).execute(ModifyExecutor(option)));
+    }
+
+    CommandTree::new(NAMES, DESCRIPTION)
+        .then(
+            literal("list")
+                .then(argument(ARG_TEAM, SimpleArgConsumer).execute(ListMembersExecutor))
+                .execute(ListExecutor),
+        )
+        .then(
+            literal("add").then(
+                argument(ARG_TEAM, SimpleArgConsumer)
+                    .then(argument(ARG_DISPLAY_NAME, TextComponentArgConsumer).execute(AddExecutor))
+                    .execute(AddExecutor),
+            ),
+        )
+        .then(literal("remove").then(argument(ARG_TEAM, SimpleArgConsumer).execute(RemoveExecutor)))
+        .then(literal("empty").then(argument(ARG_TEAM, SimpleArgConsumer).execute(EmptyExecutor)))
+        .then(
+            literal("join").then(
+                argument(ARG_TEAM, SimpleArgConsumer)
+                    .then(argument(ARG_MEMBERS, EntitiesArgumentConsumer).execute(JoinExecutor))
+                    .execute(JoinExecutor),
+            ),
+        )
+        .then(
+            literal("leave")
+                .then(argument(ARG_MEMBERS, EntitiesArgumentConsumer).execute(LeaveExecutor)),
+        )
+        .then(
+            literal("modify").then(
+                argument(ARG_TEAM, SimpleArgConsumer)
+                    .then(color)
+                    .then(
+                        literal("displayName").then(
+                            argument(ARG_VALUE, TextComponentArgConsumer)
+                                .execute(ModifyExecutor(TeamOption::DisplayName)),
+                        ),
+                    )
+                    .then(
+                        literal("prefix").then(
+                            argument(ARG_VALUE, TextComponentArgConsumer)
+                                .execute(ModifyExecutor(TeamOption::Prefix)),
+                        ),
+                    )
+                    .then(
+                        literal("suffix").then(
+                            argument(ARG_VALUE, TextComponentArgConsumer)
+                                .execute(ModifyExecutor(TeamOption::Suffix)),
+                        ),
+                    )
+                    .then(
+                        literal("friendlyFire").then(
+                            argument(ARG_VALUE, BoolArgConsumer)
+                                .execute(ModifyExecutor(TeamOption::FriendlyFire)),
+                        ),
+                    )
+                    .then(
+                        literal("seeFriendlyInvisibles").then(
+                            argument(ARG_VALUE, BoolArgConsumer)
+                                .execute(ModifyExecutor(TeamOption::SeeFriendlyInvisibles)),
+                        ),
+                    )
+                    .then(name_tag_visibility)
+                    .then(collision_rule),
+            ),
+        )
+}
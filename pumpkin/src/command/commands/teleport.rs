@@ -115,7 +115,7 @@ impl CommandExecutor for EntitiesToPosFacingPosExecutor {
                     server.worlds.load().first().unwrap().clone()
                 }
                 CommandSender::Player(player) => player.world().clone(),
-                CommandSender::CommandBlock(_, w) => w.clone(),
+                CommandSender::CommandBlock(_, w) | CommandSender::InWorld(_, w) => w.clone(),
             };
 
             for target in targets {
@@ -232,7 +232,7 @@ impl CommandExecutor for EntitiesToPosExecutor {
                     server.worlds.load().first().unwrap().clone()
                 }
                 CommandSender::Player(player) => player.world().clone(),
-                CommandSender::CommandBlock(_, w) => w.clone(),
+                CommandSender::CommandBlock(_, w) | CommandSender::InWorld(_, w) => w.clone(),
             };
             for target in targets {
                 let yaw = target.get_entity().yaw.load();
@@ -262,8 +262,8 @@ impl CommandExecutor for SelfToEntityExecutor {
             let pos = destination.get_entity().pos.load();
             let world = destination.get_entity().world.load_full();
 
-            match sender {
-                CommandSender::Player(player) => {
+            match sender.as_player() {
+                Some(player) => {
                     let yaw = player.living_entity.entity.yaw.load();
                     let pitch = player.living_entity.entity.pitch.load();
                     if !World::is_valid(BlockPos(pos.floor_to_i32())) {
@@ -272,14 +272,11 @@ impl CommandExecutor for SelfToEntityExecutor {
                             [],
                         )));
                     }
-                    player
-                        .clone()
-                        .teleport(pos, Some(yaw), Some(pitch), world)
-                        .await;
+                    player.teleport(pos, Some(yaw), Some(pitch), world).await;
 
                     Ok(1)
                 }
-                _ => Err(CommandError::CommandFailed(TextComponent::translate(
+                None => Err(CommandError::CommandFailed(TextComponent::translate(
                     "permissions.requires.player",
                     [],
                 ))),
@@ -297,8 +294,8 @@ impl CommandExecutor for SelfToPosExecutor {
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            match sender {
-                CommandSender::Player(player) => {
+            match sender.as_player() {
+                Some(player) => {
                     let pos = Position3DArgumentConsumer::find_arg(args, ARG_LOCATION)?;
                     let yaw = player.living_entity.entity.yaw.load();
                     let pitch = player.living_entity.entity.pitch.load();
@@ -308,14 +305,13 @@ impl CommandExecutor for SelfToPosExecutor {
                             [],
                         )));
                     }
-                    player
-                        .clone()
-                        .teleport(pos, Some(yaw), Some(pitch), player.world().clone())
-                        .await;
+                    // In the world of `/execute in`, if any
+                    let world = sender.world().unwrap_or_else(|| player.world().clone());
+                    player.teleport(pos, Some(yaw), Some(pitch), world).await;
 
                     Ok(1)
                 }
-                _ => Err(CommandError::CommandFailed(TextComponent::translate(
+                None => Err(CommandError::CommandFailed(TextComponent::translate(
                     "permissions.requires.player",
                     [],
                 ))),
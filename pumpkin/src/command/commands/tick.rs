@@ -7,9 +7,11 @@ use std::sync::atomic::Ordering;
 use crate::command::{
     CommandExecutor, CommandResult, CommandSender,
     args::{
-        ConsumedArgs, FindArg, bounded_num::BoundedNumArgumentConsumer, time::TimeArgumentConsumer,
+        Arg, ConsumedArgs, FindArg, bounded_num::BoundedNumArgumentConsumer,
+        simple::SimpleArgConsumer, time::TimeArgumentConsumer,
     },
     dispatcher::CommandError,
+    resolve_world,
     tree::{
         CommandTree,
         builder::{argument, literal},
@@ -19,6 +21,8 @@ use crate::command::{
 const NAMES: [&str; 1] = ["tick"];
 const DESCRIPTION: &str = "Controls or queries the game's ticking state.";
 
+const ARG_WORLD: &str = "world";
+
 // Helper function to format nanoseconds to milliseconds with 2 decimal places
 fn nanos_to_millis_string(nanos: i64) -> String {
     format!("{:.2}", nanos as f64 / 1_000_000.0)
@@ -47,6 +51,24 @@ enum SubCommand {
     SprintTimed,
     SprintLiteral(i32),
     SprintStop,
+    WorldRate,
+    WorldRateReset,
+    WorldFreeze(bool),
+}
+
+/// The `time` argument of `step` and `sprint`, which must be at least one tick.
+fn find_tick_count(args: &ConsumedArgs<'_>) -> Result<i32, CommandError> {
+    let ticks = TimeArgumentConsumer::find_arg(args, "time")?;
+    if ticks < 1 {
+        return Err(CommandError::CommandFailed(TextComponent::translate(
+            "argument.time.tick_count_too_low",
+            [
+                TextComponent::text("1"),
+                TextComponent::text(ticks.to_string()),
+            ],
+        )));
+    }
+    Ok(ticks)
 }
 
 struct TickExecutor(SubCommand);
@@ -94,6 +116,16 @@ impl TickExecutor {
         }
 
         Self::send_percentiles(sender, server).await;
+        for (world, rate, frozen) in manager.world_overrides() {
+            let state = if frozen {
+                "frozen".to_string()
+            } else {
+                format!("{:.1}", rate.unwrap_or(tickrate))
+            };
+            sender
+                .send_message(TextComponent::text(format!("{world}: {state}")))
+                .await;
+        }
         Ok(tickrate as i32)
     }
     async fn handle_non_sprinting_status(
@@ -204,6 +236,50 @@ impl TickExecutor {
             .await;
         Ok(rate as i32)
     }
+
+    async fn handle_world_command(
+        &self,
+        sender: &CommandSender,
+        server: &crate::server::Server,
+        args: &ConsumedArgs<'_>,
+    ) -> Result<i32, CommandError> {
+        let manager = &server.tick_rate_manager;
+        let Some(Arg::Simple(world)) = args.get(&ARG_WORLD) else {
+            return Err(CommandError::InvalidConsumption(Some(ARG_WORLD.into())));
+        };
+        let world = resolve_world(sender, server, Some(world))?;
+        let name = world.dimension.minecraft_name;
+        match self.0 {
+            SubCommand::WorldRate => {
+                let rate = BoundedNumArgumentConsumer::<f32>::find_arg(args, "rate")??;
+                manager.set_world_tick_rate(&world, Some(rate)).await;
+                sender
+                    .send_message(TextComponent::text(format!(
+                        "Set the tick rate of {name} to {rate:.1}"
+                    )))
+                    .await;
+                Ok(rate as i32)
+            }
+            SubCommand::WorldRateReset => {
+                manager.set_world_tick_rate(&world, None).await;
+                sender
+                    .send_message(TextComponent::text(format!(
+                        "{name} follows the server tick rate again"
+                    )))
+                    .await;
+                Ok(manager.tickrate() as i32)
+            }
+            SubCommand::WorldFreeze(freeze) => {
+                manager.set_world_frozen(&world, freeze).await;
+                let state = if freeze { "frozen" } else { "running" };
+                sender
+                    .send_message(TextComponent::text(format!("{name} is {state}")))
+                    .await;
+                Ok(freeze as i32)
+            }
+            _ => Err(CommandError::InvalidRequirement),
+        }
+    }
 }
 
 impl CommandExecutor for TickExecutor {
@@ -225,6 +301,11 @@ impl CommandExecutor for TickExecutor {
                     Self::handle_set_tick_rate(sender, server, manager, rate).await
                 }
                 SubCommand::Freeze(freeze) => {
+                    if freeze {
+                        // Like vanilla, freezing ends sprints and steps in progress
+                        manager.stop_sprinting(server).await;
+                        manager.stop_stepping(server).await;
+                    }
                     manager.set_frozen(server, freeze).await;
                     let message_key = if freeze {
                         "commands.tick.status.frozen"
@@ -241,7 +322,7 @@ impl CommandExecutor for TickExecutor {
                     Ok(1)
                 }
                 SubCommand::StepTimed => {
-                    let ticks = TimeArgumentConsumer::find_arg(args, "time")?;
+                    let ticks = find_tick_count(args)?;
                     Self::handle_step_command(sender, server, manager, ticks).await;
                     Ok(1)
                 }
@@ -270,19 +351,17 @@ impl CommandExecutor for TickExecutor {
                     }
                 }
                 SubCommand::SprintTimed => {
-                    Self::handle_sprint_command(
-                        sender,
-                        server,
-                        manager,
-                        TimeArgumentConsumer::find_arg(args, "time")?,
-                    )
-                    .await;
+                    Self::handle_sprint_command(sender, server, manager, find_tick_count(args)?)
+                        .await;
                     Ok(1)
                 }
                 SubCommand::SprintLiteral(ticks) => {
                     Self::handle_sprint_command(sender, server, manager, ticks).await;
                     Ok(1)
                 }
+                SubCommand::WorldRate | SubCommand::WorldRateReset | SubCommand::WorldFreeze(_) => {
+                    self.handle_world_command(sender, server, args).await
+                }
                 SubCommand::SprintStop => {
                     if manager.stop_sprinting(server).await {
                         sender
@@ -308,16 +387,38 @@ impl CommandExecutor for TickExecutor {
     }
 }
 
-pub fn init_command_tree(default_tps: f32) -> CommandTree {
+pub fn init_command_tree() -> CommandTree {
     CommandTree::new(NAMES, DESCRIPTION)
         .then(literal("query").execute(TickExecutor(SubCommand::Query)))
         .then(
             literal("rate")
-                .then(literal("20").execute(TickExecutor(SubCommand::RateLiteral(default_tps))))
-                .then(argument("rate", rate_consumer()).execute(TickExecutor(SubCommand::Rate))),
+                .then(literal("20").execute(TickExecutor(SubCommand::RateLiteral(20.0))))
+                .then(argument("rate", rate_consumer()).execute(TickExecutor(SubCommand::Rate)))
+                .then(
+                    argument(ARG_WORLD, SimpleArgConsumer)
+                        .then(literal("reset").execute(TickExecutor(SubCommand::WorldRateReset)))
+                        .then(
+                            argument("rate", rate_consumer())
+                                .execute(TickExecutor(SubCommand::WorldRate)),
+                        ),
+                ),
+        )
+        .then(
+            literal("freeze")
+                .then(
+                    argument(ARG_WORLD, SimpleArgConsumer)
+                        .execute(TickExecutor(SubCommand::WorldFreeze(true))),
+                )
+                .execute(TickExecutor(SubCommand::Freeze(true))),
+        )
+        .then(
+            literal("unfreeze")
+                .then(
+                    argument(ARG_WORLD, SimpleArgConsumer)
+                        .execute(TickExecutor(SubCommand::WorldFreeze(false))),
+                )
+                .execute(TickExecutor(SubCommand::Freeze(false))),
         )
-        .then(literal("freeze").execute(TickExecutor(SubCommand::Freeze(true))))
-        .then(literal("unfreeze").execute(TickExecutor(SubCommand::Freeze(false))))
         .then(
             literal("step")
                 .then(literal("stop").execute(TickExecutor(SubCommand::StepStop)))
@@ -53,11 +53,11 @@ impl CommandExecutor for QueryExecutor {
     ) -> CommandResult<'a> {
         Box::pin(async move {
             let mode = self.0;
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
+            // The console and RCON query the overworld
+            let world = sender
+                .world()
+                .or_else(|| server.worlds.load().first().cloned())
+                .ok_or(CommandError::InvalidRequirement)?;
             let level_time = world.level_time.lock().await;
 
             let curr_time = match mode {
@@ -96,41 +96,30 @@ impl CommandExecutor for ChangeExecutor {
                 )));
             };
 
-            let mode = self.0;
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut level_time = world.level_time.lock().await;
-
-            match mode {
-                Mode::Add => {
-                    // add
-                    level_time.add_time(time_count.into());
-                    level_time.send_time(world).await;
-                    let curr_time = level_time.query_daytime();
-                    sender
-                        .send_message(TextComponent::translate(
-                            "commands.time.set",
-                            [TextComponent::text(curr_time.to_string())],
-                        ))
-                        .await;
-                    Ok(curr_time as i32)
-                }
-                Mode::Set(_) => {
-                    // set
-                    level_time.set_time(time_count.into());
-                    level_time.send_time(world).await;
-                    sender
-                        .send_message(TextComponent::translate(
-                            "commands.time.set",
-                            [TextComponent::text(time_count.to_string())],
-                        ))
-                        .await;
-                    Ok(time_count)
+            // Like in vanilla, the time changes in every world
+            let mut day_time = 0;
+            for world in server.worlds.load().iter() {
+                let mut level_time = world.level_time.lock().await;
+                match self.0 {
+                    Mode::Add => level_time.add_time(time_count.into()),
+                    Mode::Set(_) => level_time.set_time(time_count.into()),
                 }
+                level_time.send_time(world).await;
+                day_time = level_time.query_daytime();
             }
+
+            // Vanilla reports the new time of day when adding and the given time when setting
+            let result = match self.0 {
+                Mode::Add => day_time as i32,
+                Mode::Set(_) => time_count,
+            };
+            sender
+                .send_message(TextComponent::translate(
+                    "commands.time.set",
+                    [TextComponent::text(result.to_string())],
+                ))
+                .await;
+            Ok(result)
         })
     }
 }
@@ -1,4 +1,3 @@
-use pumpkin_protocol::java::client::play::CClearTitle;
 use pumpkin_util::text::TextComponent;
 
 use crate::command::CommandResult;
@@ -14,7 +13,7 @@ use crate::{
         tree::CommandTree,
         tree::builder::{argument, literal},
     },
-    entity::player::TitleMode,
+    entity::feedback::{self, Title, TitleTimes},
 };
 
 const NAMES: [&str; 1] = ["title"];
@@ -44,7 +43,7 @@ impl CommandExecutor for ClearOrResetExecutor {
             let reset = self.0;
 
             for target in targets {
-                target.client.enqueue_packet(&CClearTitle::new(reset)).await;
+                target.clear_title(reset).await;
             }
             sender
                 .send_message(if targets.len() == 1 {
@@ -69,7 +68,24 @@ impl CommandExecutor for ClearOrResetExecutor {
     }
 }
 
-struct TitleExecutor(TitleMode);
+#[derive(Clone, Copy)]
+enum TitlePart {
+    Title,
+    Subtitle,
+    ActionBar,
+}
+
+impl TitlePart {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Subtitle => "subtitle",
+            Self::ActionBar => "actionbar",
+        }
+    }
+}
+
+struct TitleExecutor(TitlePart);
 
 impl CommandExecutor for TitleExecutor {
     fn execute<'a>(
@@ -85,13 +101,13 @@ impl CommandExecutor for TitleExecutor {
 
             let text = TextComponentArgConsumer::find_arg(args, ARG_TITLE)?;
 
-            let mode = &self.0;
-
-            for target in targets {
-                target.show_title(&text, mode).await;
+            match self.0 {
+                TitlePart::Title => Title::new(text).send_to(targets).await,
+                TitlePart::Subtitle => Title::default().subtitle(text).send_to(targets).await,
+                TitlePart::ActionBar => feedback::send_action_bar_to(targets, &text).await,
             }
 
-            let mode_name = format!("{mode:?}").to_lowercase();
+            let mode_name = self.0.name();
             sender
                 .send_message(if targets.len() == 1 {
                     TextComponent::translate(
@@ -129,9 +145,10 @@ impl CommandExecutor for TimesTitleExecutor {
             let stay = TimeArgumentConsumer::find_arg(args, ARG_STAY)?;
             let fade_out = TimeArgumentConsumer::find_arg(args, ARG_FADE_OUT)?;
 
-            for target in targets {
-                target.send_title_animation(fade_in, stay, fade_out).await;
-            }
+            Title::default()
+                .times(TitleTimes::new(fade_in, stay, fade_out))
+                .send_to(targets)
+                .await;
 
             sender
                 .send_message(if targets.len() == 1 {
@@ -160,19 +177,19 @@ pub fn init_command_tree() -> CommandTree {
             .then(
                 literal("title").then(
                     argument(ARG_TITLE, TextComponentArgConsumer)
-                        .execute(TitleExecutor(TitleMode::Title)),
+                        .execute(TitleExecutor(TitlePart::Title)),
                 ),
             )
             .then(
                 literal("subtitle").then(
                     argument(ARG_TITLE, TextComponentArgConsumer)
-                        .execute(TitleExecutor(TitleMode::SubTitle)),
+                        .execute(TitleExecutor(TitlePart::Subtitle)),
                 ),
             )
             .then(
                 literal("actionbar").then(
                     argument(ARG_TITLE, TextComponentArgConsumer)
-                        .execute(TitleExecutor(TitleMode::ActionBar)),
+                        .execute(TitleExecutor(TitlePart::ActionBar)),
                 ),
             )
             .then(literal("times").then(
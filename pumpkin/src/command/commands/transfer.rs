@@ -1,5 +1,3 @@
-use pumpkin_protocol::codec::var_int::VarInt;
-use pumpkin_protocol::java::client::play::CTransfer;
 use pumpkin_util::text::TextComponent;
 
 use crate::command::CommandResult;
@@ -54,11 +52,7 @@ impl CommandExecutor for TargetSelfExecutor {
             if let CommandSender::Player(player) = sender {
                 let name = &player.gameprofile.name;
                 log::info!("[{name}: Transferring {name} to {hostname}:{port}]");
-                player
-                    .client
-                    .enqueue_packet(&CTransfer::new(hostname, VarInt(port)))
-                    .await;
-                Ok(1)
+                Ok(i32::from(player.transfer(hostname, port).await))
             } else {
                 Err(InvalidRequirement)
             }
@@ -103,13 +97,12 @@ impl CommandExecutor for TargetPlayerExecutor {
             }
 
             for p in players {
-                p.client
-                    .enqueue_packet(&CTransfer::new(hostname, VarInt(port)))
-                    .await;
-                log::info!(
-                    "[{sender}: Transferring {} to {hostname}:{port}]",
-                    p.gameprofile.name
-                );
+                if p.transfer(hostname, port).await {
+                    log::info!(
+                        "[{sender}: Transferring {} to {hostname}:{port}]",
+                        p.gameprofile.name
+                    );
+                }
             }
 
             if players.len() == 1 {
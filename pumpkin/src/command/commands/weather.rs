@@ -32,14 +32,11 @@ impl CommandExecutor for Executor {
     ) -> CommandResult<'a> {
         Box::pin(async move {
             let duration = TimeArgumentConsumer::find_arg(args, ARG_DURATION).ok();
-            let world = {
-                let guard = server.worlds.load();
-
-                guard
-                    .first()
-                    .cloned()
-                    .ok_or(CommandError::InvalidRequirement)?
-            };
+            // The console and RCON change the weather of the overworld
+            let world = sender
+                .world()
+                .or_else(|| server.worlds.load().first().cloned())
+                .ok_or(CommandError::InvalidRequirement)?;
             let mut weather = world.weather.lock().await;
 
             match self.mode {
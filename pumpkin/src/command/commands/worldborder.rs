@@ -6,20 +6,26 @@ use crate::command::{
         ConsumedArgs, DefaultNameArgConsumer, FindArgDefaultName,
         bounded_num::BoundedNumArgumentConsumer, position_2d::Position2DArgumentConsumer,
     },
+    resolve_world,
     tree::{
         CommandTree,
         builder::{argument_default_name, literal},
     },
 };
+use crate::server::Server;
+use crate::world::border::{MAX_CENTER, MAX_DIAMETER};
 
 const NAMES: [&str; 1] = ["worldborder"];
 
-const DESCRIPTION: &str = "Worldborder command.";
+const DESCRIPTION: &str = "Manages the world border.";
 
 const NOTHING_CHANGED_EXCEPTION: &str = "commands.worldborder.set.failed.nochange";
 
 const fn distance_consumer() -> BoundedNumArgumentConsumer<f64> {
-    BoundedNumArgumentConsumer::new().min(0.0).name("distance")
+    BoundedNumArgumentConsumer::new()
+        .min(-MAX_DIAMETER)
+        .max(MAX_DIAMETER)
+        .name("distance")
 }
 
 const fn time_consumer() -> BoundedNumArgumentConsumer<i32> {
@@ -40,271 +46,115 @@ const fn warning_distance_consumer() -> BoundedNumArgumentConsumer<i32> {
     BoundedNumArgumentConsumer::new().min(0).name("distance")
 }
 
+fn out_of_bounds(name: &str) -> CommandError {
+    CommandError::CommandFailed(TextComponent::text(format!("{name} is out of bounds.")))
+}
+
 struct GetExecutor;
 
 impl CommandExecutor for GetExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a crate::server::Server,
+        server: &'a Server,
         _args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let border = world.worldborder.lock().await;
-
-            let diameter = border.new_diameter.round() as i32;
+            let world = resolve_world(sender, server, None)?;
+            let diameter = world.worldborder.lock().await.diameter();
+
             sender
                 .send_message(TextComponent::translate(
                     "commands.worldborder.get",
-                    [TextComponent::text(diameter.to_string())],
+                    [TextComponent::text(format!("{diameter:.0}"))],
                 ))
                 .await;
 
-            Ok(diameter)
+            Ok((diameter + 0.5) as i32)
         })
     }
 }
 
-struct SetExecutor;
-
-impl CommandExecutor for SetExecutor {
-    fn execute<'a>(
-        &'a self,
-        sender: &'a CommandSender,
-        server: &'a crate::server::Server,
-        args: &'a ConsumedArgs<'a>,
-    ) -> CommandResult<'a> {
-        Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
-            let Ok(distance) = distance_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
-            };
-
-            if (distance - border.new_diameter).abs() < f64::EPSILON {
-                return Err(CommandError::CommandFailed(TextComponent::translate(
-                    NOTHING_CHANGED_EXCEPTION,
-                    [],
-                )));
-            }
-
-            sender
-                .send_message(TextComponent::translate(
-                    "commands.worldborder.set.immediate",
-                    [TextComponent::text(format!("{distance:.1}"))],
-                ))
-                .await;
-
-            let d = border.new_diameter;
-            border.set_diameter(world, distance, None).await;
-
-            Ok((distance - d) as i32)
-        })
-    }
+/// `set` and `add`, with or without a time in seconds to move the border over.
+struct SizeExecutor {
+    relative: bool,
+    timed: bool,
 }
 
-struct SetTimeExecutor;
-
-impl CommandExecutor for SetTimeExecutor {
+impl CommandExecutor for SizeExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a crate::server::Server,
+        server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
             let Ok(distance) = distance_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+                return Err(out_of_bounds(distance_consumer().default_name()));
             };
-            let Ok(time) = time_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+            let time = if self.timed {
+                let Ok(time) = time_consumer().find_arg_default_name(args)? else {
+                    return Err(out_of_bounds(time_consumer().default_name()));
+                };
+                time
+            } else {
+                0
             };
 
-            match distance.total_cmp(&border.new_diameter) {
-                std::cmp::Ordering::Equal => {
-                    return Err(CommandError::CommandFailed(TextComponent::translate(
-                        NOTHING_CHANGED_EXCEPTION,
-                        [],
-                    )));
-                }
-                std::cmp::Ordering::Less => {
-                    let dist = format!("{distance:.1}");
-                    sender
-                        .send_message(TextComponent::translate(
-                            "commands.worldborder.set.shrink",
-                            [
-                                TextComponent::text(dist),
-                                TextComponent::text(time.to_string()),
-                            ],
-                        ))
-                        .await;
-                }
-                std::cmp::Ordering::Greater => {
-                    let dist = format!("{distance:.1}");
-                    sender
-                        .send_message(TextComponent::translate(
-                            "commands.worldborder.set.grow",
-                            [
-                                TextComponent::text(dist),
-                                TextComponent::text(time.to_string()),
-                            ],
-                        ))
-                        .await;
-                }
-            }
-
-            let d = border.new_diameter;
-            border
-                .set_diameter(world, distance, Some(i64::from(time) * 1000))
-                .await;
-
-            Ok((distance - d) as i32)
-        })
-    }
-}
-
-struct AddExecutor;
-
-impl CommandExecutor for AddExecutor {
-    fn execute<'a>(
-        &'a self,
-        sender: &'a CommandSender,
-        server: &'a crate::server::Server,
-        args: &'a ConsumedArgs<'a>,
-    ) -> CommandResult<'a> {
-        Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
+            let world = resolve_world(sender, server, None)?;
             let mut border = world.worldborder.lock().await;
-
-            let Ok(distance_add) = distance_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+            let current = border.diameter();
+            let diameter = if self.relative {
+                current + distance
+            } else {
+                distance
             };
 
-            if distance_add == 0.0 {
+            if (diameter - current).abs() < f64::EPSILON {
                 return Err(CommandError::CommandFailed(TextComponent::translate(
                     NOTHING_CHANGED_EXCEPTION,
                     [],
                 )));
             }
+            if diameter < 1.0 {
+                return Err(CommandError::CommandFailed(TextComponent::translate(
+                    "commands.worldborder.set.failed.small",
+                    [],
+                )));
+            }
+            if diameter > MAX_DIAMETER {
+                return Err(CommandError::CommandFailed(TextComponent::translate(
+                    "commands.worldborder.set.failed.big",
+                    [TextComponent::text(format!("{MAX_DIAMETER:.1}"))],
+                )));
+            }
 
-            let distance = border.new_diameter + distance_add;
-
-            let dist = format!("{distance:.1}");
-            sender
-                .send_message(TextComponent::translate(
+            let message = if time > 0 {
+                let key = if diameter > current {
+                    "commands.worldborder.set.grow"
+                } else {
+                    "commands.worldborder.set.shrink"
+                };
+                TextComponent::translate(
+                    key,
+                    [
+                        TextComponent::text(format!("{diameter:.1}")),
+                        TextComponent::text(time.to_string()),
+                    ],
+                )
+            } else {
+                TextComponent::translate(
                     "commands.worldborder.set.immediate",
-                    [TextComponent::text(dist)],
-                ))
-                .await;
-            border.set_diameter(world, distance, None).await;
-            Ok(distance_add as i32)
-        })
-    }
-}
-
-struct AddTimeExecutor;
-
-impl CommandExecutor for AddTimeExecutor {
-    fn execute<'a>(
-        &'a self,
-        sender: &'a CommandSender,
-        server: &'a crate::server::Server,
-        args: &'a ConsumedArgs<'a>,
-    ) -> CommandResult<'a> {
-        Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
-            let Ok(distance_add) = distance_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
-            };
-            let Ok(time) = time_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+                    [TextComponent::text(format!("{diameter:.1}"))],
+                )
             };
-
-            let distance = distance_add + border.new_diameter;
-
-            match distance.total_cmp(&border.new_diameter) {
-                std::cmp::Ordering::Equal => {
-                    return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                        "{} is out of bounds.",
-                        distance_consumer().default_name()
-                    ))));
-                }
-                std::cmp::Ordering::Less => {
-                    let dist = format!("{distance:.1}");
-                    sender
-                        .send_message(TextComponent::translate(
-                            "commands.worldborder.set.shrink",
-                            [
-                                TextComponent::text(dist),
-                                TextComponent::text(time.to_string()),
-                            ],
-                        ))
-                        .await;
-                }
-                std::cmp::Ordering::Greater => {
-                    let dist = format!("{distance:.1}");
-                    sender
-                        .send_message(TextComponent::translate(
-                            "commands.worldborder.set.grow",
-                            [
-                                TextComponent::text(dist),
-                                TextComponent::text(time.to_string()),
-                            ],
-                        ))
-                        .await;
-                }
-            }
-
             border
-                .set_diameter(world, distance, Some(i64::from(time) * 1000))
+                .set_diameter(&world, diameter, Some(i64::from(time) * 1000))
                 .await;
+            drop(border);
+            sender.send_message(message).await;
 
-            Ok(distance_add as i32)
+            Ok((diameter - current) as i32)
         })
     }
 }
@@ -315,18 +165,30 @@ impl CommandExecutor for CenterExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a crate::server::Server,
+        server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
             let Vector2 { x, y } = Position2DArgumentConsumer.find_arg_default_name(args)?;
+            if x.abs() > MAX_CENTER || y.abs() > MAX_CENTER {
+                return Err(CommandError::CommandFailed(TextComponent::translate(
+                    "commands.worldborder.set.failed.far",
+                    [TextComponent::text(format!("{MAX_CENTER:.1}"))],
+                )));
+            }
+
+            let world = resolve_world(sender, server, None)?;
+            let mut border = world.worldborder.lock().await;
+            if (border.center_x - x).abs() < f64::EPSILON
+                && (border.center_z - y).abs() < f64::EPSILON
+            {
+                return Err(CommandError::CommandFailed(TextComponent::translate(
+                    "commands.worldborder.center.failed",
+                    [],
+                )));
+            }
+            border.set_center(&world, x, y).await;
+            drop(border);
 
             sender
                 .send_message(TextComponent::translate(
@@ -337,7 +199,6 @@ impl CommandExecutor for CenterExecutor {
                     ],
                 ))
                 .await;
-            border.set_center(world, x, y).await;
             Ok(0)
         })
     }
@@ -349,40 +210,32 @@ impl CommandExecutor for DamageAmountExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a crate::server::Server,
+        server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
             let Ok(damage_per_block) = damage_per_block_consumer().find_arg_default_name(args)?
             else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+                return Err(out_of_bounds(damage_per_block_consumer().default_name()));
             };
 
+            let world = resolve_world(sender, server, None)?;
+            let mut border = world.worldborder.lock().await;
             if (damage_per_block - border.damage_per_block).abs() < f32::EPSILON {
                 return Err(CommandError::CommandFailed(TextComponent::translate(
                     "commands.worldborder.damage.amount.failed",
                     [],
                 )));
             }
+            border.damage_per_block = damage_per_block;
+            drop(border);
 
-            let damage = format!("{damage_per_block:.2}");
             sender
                 .send_message(TextComponent::translate(
                     "commands.worldborder.damage.amount.success",
-                    [TextComponent::text(damage)],
+                    [TextComponent::text(format!("{damage_per_block:.2}"))],
                 ))
                 .await;
-            border.damage_per_block = damage_per_block;
             Ok(damage_per_block as i32)
         })
     }
@@ -394,39 +247,31 @@ impl CommandExecutor for DamageBufferExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a crate::server::Server,
+        server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
             let Ok(buffer) = damage_buffer_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+                return Err(out_of_bounds(damage_buffer_consumer().default_name()));
             };
 
+            let world = resolve_world(sender, server, None)?;
+            let mut border = world.worldborder.lock().await;
             if (buffer - border.buffer).abs() < f32::EPSILON {
                 return Err(CommandError::CommandFailed(TextComponent::translate(
-                    "commands.worldborder.damage.amount.failed",
+                    "commands.worldborder.damage.buffer.failed",
                     [],
                 )));
             }
+            border.buffer = buffer;
+            drop(border);
 
-            let buf = format!("{buffer:.2}");
             sender
                 .send_message(TextComponent::translate(
                     "commands.worldborder.damage.buffer.success",
-                    [TextComponent::text(buf)],
+                    [TextComponent::text(format!("{buffer:.2}"))],
                 ))
                 .await;
-            border.buffer = buffer;
             Ok(buffer as i32)
         })
     }
@@ -438,30 +283,24 @@ impl CommandExecutor for WarningDistanceExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a crate::server::Server,
+        server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
             let Ok(distance) = warning_distance_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+                return Err(out_of_bounds(warning_distance_consumer().default_name()));
             };
 
+            let world = resolve_world(sender, server, None)?;
+            let mut border = world.worldborder.lock().await;
             if distance == border.warning_blocks {
                 return Err(CommandError::CommandFailed(TextComponent::translate(
                     "commands.worldborder.warning.distance.failed",
                     [],
                 )));
             }
+            border.set_warning_distance(&world, distance).await;
+            drop(border);
 
             sender
                 .send_message(TextComponent::translate(
@@ -469,7 +308,6 @@ impl CommandExecutor for WarningDistanceExecutor {
                     [TextComponent::text(distance.to_string())],
                 ))
                 .await;
-            border.set_warning_distance(world, distance).await;
             Ok(distance)
         })
     }
@@ -481,30 +319,24 @@ impl CommandExecutor for WarningTimeExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a crate::server::Server,
+        server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            // TODO: Maybe ask player for world, or get the current world
-            let worlds = server.worlds.load();
-            let world = worlds
-                .first()
-                .expect("There should always be at least one world");
-            let mut border = world.worldborder.lock().await;
-
             let Ok(time) = time_consumer().find_arg_default_name(args)? else {
-                return Err(CommandError::CommandFailed(TextComponent::text(format!(
-                    "{} is out of bounds.",
-                    distance_consumer().default_name()
-                ))));
+                return Err(out_of_bounds(time_consumer().default_name()));
             };
 
+            let world = resolve_world(sender, server, None)?;
+            let mut border = world.worldborder.lock().await;
             if time == border.warning_time {
                 return Err(CommandError::CommandFailed(TextComponent::translate(
                     "commands.worldborder.warning.time.failed",
                     [],
                 )));
             }
+            border.set_warning_delay(&world, time).await;
+            drop(border);
 
             sender
                 .send_message(TextComponent::translate(
@@ -512,7 +344,6 @@ impl CommandExecutor for WarningTimeExecutor {
                     [TextComponent::text(time.to_string())],
                 ))
                 .await;
-            border.set_warning_delay(world, time).await;
             Ok(time)
         })
     }
@@ -523,8 +354,16 @@ pub fn init_command_tree() -> CommandTree {
         .then(
             literal("add").then(
                 argument_default_name(distance_consumer())
-                    .execute(AddExecutor)
-                    .then(argument_default_name(time_consumer()).execute(AddTimeExecutor)),
+                    .execute(SizeExecutor {
+                        relative: true,
+                        timed: false,
+                    })
+                    .then(
+                        argument_default_name(time_consumer()).execute(SizeExecutor {
+                            relative: true,
+                            timed: true,
+                        }),
+                    ),
             ),
         )
         .then(
@@ -547,8 +386,16 @@ pub fn init_command_tree() -> CommandTree {
         .then(
             literal("set").then(
                 argument_default_name(distance_consumer())
-                    .execute(SetExecutor)
-                    .then(argument_default_name(time_consumer()).execute(SetTimeExecutor)),
+                    .execute(SizeExecutor {
+                        relative: false,
+                        timed: false,
+                    })
+                    .then(
+                        argument_default_name(time_consumer()).execute(SizeExecutor {
+                            relative: false,
+                            timed: true,
+                        }),
+                    ),
             ),
         )
         .then(
@@ -444,16 +444,14 @@ impl CommandDispatcher {
 
 #[cfg(test)]
 mod test {
-    use pumpkin_config::BasicConfiguration;
     use pumpkin_util::permission::PermissionRegistry;
     use tokio::sync::RwLock;
 
     use crate::command::{commands::default_dispatcher, tree::CommandTree};
     #[tokio::test]
     async fn dynamic_command() {
-        let config = BasicConfiguration::default();
         let registry = RwLock::new(PermissionRegistry::new());
-        let mut dispatcher = default_dispatcher(&registry, &config).await;
+        let mut dispatcher = default_dispatcher(&registry).await;
         let tree = CommandTree::new(["test"], "test_desc");
         dispatcher.register(tree, "minecraft:test");
     }
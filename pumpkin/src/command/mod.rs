@@ -9,6 +9,7 @@ use crate::world::World;
 use args::ConsumedArgs;
 
 use dispatcher::CommandError;
+use pumpkin_data::dimension::Dimension;
 use pumpkin_util::math::vector3::Vector3;
 use pumpkin_util::permission::{PermissionDefault, PermissionLvl};
 use pumpkin_util::text::TextComponent;
@@ -17,6 +18,7 @@ use pumpkin_world::block::entities::BlockEntity;
 use pumpkin_world::block::entities::command_block::CommandBlockEntity;
 
 pub mod args;
+pub mod audit;
 pub mod client_suggestions;
 pub mod commands;
 pub mod dispatcher;
@@ -29,6 +31,7 @@ pub mod tree;
 /// Different senders have different permissions, output targets, and
 /// positions in the world. This enum abstracts those differences for the
 /// command dispatcher.
+#[derive(Clone)]
 pub enum CommandSender {
     /// A remote console connection via the RCON protocol.
     ///
@@ -50,6 +53,10 @@ pub enum CommandSender {
     /// Contains the block entity responsible for the command and the
     /// world context it exists in for coordinate-relative execution (e.g., `~ ~ ~`).
     CommandBlock(Arc<dyn BlockEntity>, Arc<World>),
+    /// Another sender running a command in a different world, as with `/execute in`.
+    ///
+    /// Behaves like the wrapped sender, except for the world it is in.
+    InWorld(Box<Self>, Arc<World>),
 }
 
 impl fmt::Display for CommandSender {
@@ -62,6 +69,7 @@ impl fmt::Display for CommandSender {
                 Self::Rcon(_) => "Rcon",
                 Self::Player(p) => &p.gameprofile.name,
                 Self::CommandBlock(..) => "@",
+                Self::InWorld(sender, _) => return sender.fmt(f),
             }
         )
     }
@@ -84,31 +92,45 @@ impl CommandSender {
 
                 *last_output = format!("[{}] {}", timestamp, text.get_text());
             }
+            Self::InWorld(sender, _) => Box::pin(sender.send_message(text)).await,
         }
     }
 
     pub fn set_success_count(&self, count: u32) {
-        if let Self::CommandBlock(c, _) = self {
-            let block: &CommandBlockEntity = c.as_any().downcast_ref().unwrap();
-            block
-                .success_count
-                .store(count, std::sync::atomic::Ordering::SeqCst);
+        match self {
+            Self::CommandBlock(c, _) => {
+                let block: &CommandBlockEntity = c.as_any().downcast_ref().unwrap();
+                block
+                    .success_count
+                    .store(count, std::sync::atomic::Ordering::SeqCst);
+            }
+            Self::InWorld(sender, _) => sender.set_success_count(count),
+            _ => {}
         }
     }
 
     #[must_use]
-    pub const fn is_player(&self) -> bool {
-        matches!(self, Self::Player(_))
+    pub fn is_player(&self) -> bool {
+        match self {
+            Self::Player(_) => true,
+            Self::InWorld(sender, _) => sender.is_player(),
+            _ => false,
+        }
     }
 
     #[must_use]
-    pub const fn is_console(&self) -> bool {
-        matches!(self, Self::Console)
+    pub fn is_console(&self) -> bool {
+        match self {
+            Self::Console => true,
+            Self::InWorld(sender, _) => sender.is_console(),
+            _ => false,
+        }
     }
     #[must_use]
     pub fn as_player(&self) -> Option<Arc<Player>> {
         match self {
             Self::Player(player) => Some(player.clone()),
+            Self::InWorld(sender, _) => sender.as_player(),
             _ => None,
         }
     }
@@ -120,6 +142,7 @@ impl CommandSender {
             Self::Console | Self::Rcon(_) => PermissionLvl::Four,
             Self::Player(p) => p.permission_lvl.load(),
             Self::CommandBlock(..) => PermissionLvl::Two,
+            Self::InWorld(sender, _) => sender.permission_lvl(),
         }
     }
 
@@ -129,6 +152,7 @@ impl CommandSender {
             Self::Console | Self::Rcon(_) => true,
             Self::Player(p) => p.permission_lvl.load().ge(&lvl),
             Self::CommandBlock(..) => PermissionLvl::Two >= lvl,
+            Self::InWorld(sender, _) => sender.has_permission_lvl(lvl),
         }
     }
 
@@ -148,6 +172,7 @@ impl CommandSender {
                     PermissionDefault::Op(o) => o <= PermissionLvl::Two,
                 }
             }
+            Self::InWorld(sender, _) => Box::pin(sender.has_permission(server, node)).await,
         }
     }
 
@@ -157,6 +182,7 @@ impl CommandSender {
             Self::Console | Self::Rcon(..) => None,
             Self::Player(p) => Some(p.living_entity.entity.pos.load()),
             Self::CommandBlock(c, _) => Some(c.get_position().to_centered_f64()),
+            Self::InWorld(sender, _) => sender.position(),
         }
     }
 
@@ -166,7 +192,7 @@ impl CommandSender {
             // TODO: maybe return first world when console
             Self::Console | Self::Rcon(..) => None,
             Self::Player(p) => Some(p.living_entity.entity.world.load_full()),
-            Self::CommandBlock(_, w) => Some(w.clone()),
+            Self::CommandBlock(_, w) | Self::InWorld(_, w) => Some(w.clone()),
         }
     }
 
@@ -177,10 +203,39 @@ impl CommandSender {
             Self::Player(player) => {
                 Locale::from_str(&player.config.load().locale).unwrap_or(Locale::EnUs)
             }
+            Self::InWorld(sender, _) => sender.get_locale(),
         }
     }
 }
 
+/// Resolves the world a command acts on: the loaded world called `name`, with or without the
+/// `minecraft:` prefix, or without a name the world of the sender. The console and RCON use the
+/// overworld.
+pub fn resolve_world(
+    sender: &CommandSender,
+    server: &Server,
+    name: Option<&str>,
+) -> Result<Arc<World>, CommandError> {
+    let Some(name) = name else {
+        return Ok(sender
+            .world()
+            .unwrap_or_else(|| server.get_world_from_dimension(&Dimension::OVERWORLD)));
+    };
+    let short = name.strip_prefix("minecraft:").unwrap_or(name);
+    server
+        .worlds
+        .load()
+        .iter()
+        .find(|world| {
+            let dimension = world.dimension.minecraft_name;
+            dimension.strip_prefix("minecraft:").unwrap_or(dimension) == short
+        })
+        .cloned()
+        .ok_or_else(|| {
+            CommandError::CommandFailed(TextComponent::text(format!("Unknown world: {name}")))
+        })
+}
+
 /// Represents the result of running a command after completion.
 ///
 /// If the command **ran successfully**, an [`Ok`] is returned containing an [`i32`].
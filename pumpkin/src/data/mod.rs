@@ -11,6 +11,7 @@ pub mod banlist_serializer;
 pub mod banned_ip;
 pub mod banned_player;
 pub mod player_server;
+pub mod player_sync;
 pub mod whitelist;
 
 pub struct VanillaData {
@@ -1,16 +1,22 @@
 use crate::{
+    data::player_sync::{PendingFlush, PlayerDataSync},
     entity::{NBTStorage, player::Player},
-    server::Server,
+    server::{Server, alerting::AlertKind},
 };
 use crossbeam::atomic::AtomicCell;
+use futures::FutureExt;
+use pumpkin_inventory::player::ender_chest_inventory::EnderChestInventory;
 use pumpkin_inventory::screen_handler::ScreenHandler;
 use pumpkin_nbt::compound::NbtCompound;
 use pumpkin_world::data::player_data::{PlayerDataError, PlayerDataStorage};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
 use std::{
     path::PathBuf,
     time::{Duration, Instant},
 };
+const ENDER_ITEMS: &str = "EnderItems";
+
 /// Helper for managing player data in the server context.
 ///
 /// This struct provides server-wide access to the `PlayerDataStorage` and
@@ -19,6 +25,9 @@ pub struct ServerPlayerData {
     storage: Arc<PlayerDataStorage>,
     save_interval: Duration,
     last_save: AtomicCell<Instant>,
+    syncs: RwLock<Vec<Arc<dyn PlayerDataSync>>>,
+    /// Flushes of players who left that are still running.
+    flushes: Arc<Mutex<HashMap<uuid::Uuid, PendingFlush>>>,
 }
 
 impl ServerPlayerData {
@@ -28,12 +37,25 @@ impl ServerPlayerData {
             storage: Arc::new(PlayerDataStorage::new(data_path, enabled)),
             save_interval,
             last_save: AtomicCell::new(Instant::now()),
+            syncs: RwLock::new(Vec::new()),
+            flushes: Arc::default(),
         }
     }
 
+    /// Registers a sync that fetches the data of joining players and gets the data of players
+    /// who left. Syncs are asked in the order they were registered.
+    pub fn register_sync(&self, sync: Arc<dyn PlayerDataSync>) {
+        self.syncs.write().unwrap().push(sync);
+    }
+
+    fn syncs(&self) -> Vec<Arc<dyn PlayerDataSync>> {
+        self.syncs.read().unwrap().clone()
+    }
+
     /// Handles a player leaving the server.
     ///
-    /// This function saves player data when they disconnect.
+    /// This function saves player data when they disconnect, then flushes it to the registered
+    /// syncs in the background. See [`Self::pending_flush`].
     ///
     /// # Arguments
     ///
@@ -54,10 +76,71 @@ impl ServerPlayerData {
         let mut nbt = NbtCompound::new();
         player.write_nbt(&mut nbt).await;
 
-        // Save to disk
-        self.storage.save_player_data(&player.gameprofile.id, nbt)?;
+        // Save to disk first, the syncs get the data even if that fails
+        let uuid = player.gameprofile.id;
+        let saved = self.storage.save_player_data(&uuid, nbt.clone());
+        self.start_flush(uuid, nbt);
+        saved
+    }
 
-        Ok(())
+    /// Hands the data of a player who left to the syncs in the background. A flush still
+    /// running for the player finishes first, so the syncs never get older data last.
+    fn start_flush(&self, uuid: uuid::Uuid, nbt: NbtCompound) {
+        let syncs = self.syncs();
+        if syncs.is_empty() {
+            return;
+        }
+        let previous = self.pending_flush(&uuid);
+        let flush = async move {
+            if let Some(previous) = previous {
+                previous.await;
+            }
+            for sync in syncs {
+                if let Err(e) = sync.flush(uuid, &nbt).await {
+                    log::error!("Failed to flush player data for {uuid}: {e}");
+                }
+            }
+        }
+        .boxed()
+        .shared();
+        self.flushes.lock().unwrap().insert(uuid, flush.clone());
+
+        let flushes = self.flushes.clone();
+        tokio::spawn(async move {
+            flush.clone().await;
+            let mut flushes = flushes.lock().unwrap();
+            if flushes
+                .get(&uuid)
+                .is_some_and(|pending| pending.ptr_eq(&flush))
+            {
+                flushes.remove(&uuid);
+            }
+        });
+    }
+
+    /// The flush of the data of a player who left, if it is still running.
+    ///
+    /// Plugins await it before sending the player to another server of the network, so that
+    /// server fetches the data the player left with.
+    #[must_use]
+    pub fn pending_flush(&self, uuid: &uuid::Uuid) -> Option<PendingFlush> {
+        self.flushes.lock().unwrap().get(uuid).cloned()
+    }
+
+    /// Flushes the data of all online players to the syncs and waits for all flushes, those of
+    /// players who left before included. Used on shutdown.
+    pub async fn flush_all_players(&self, server: &Server) {
+        if !self.syncs().is_empty() {
+            for world in server.worlds.load().iter() {
+                for player in world.players.load().iter() {
+                    let mut nbt = NbtCompound::new();
+                    player.write_nbt(&mut nbt).await;
+                    self.start_flush(player.gameprofile.id, nbt);
+                }
+            }
+        }
+        let pending: Vec<_> = self.flushes.lock().unwrap().values().cloned().collect();
+        futures::future::join_all(pending).await;
     }
 
     /// Performs periodic maintenance tasks.
@@ -85,6 +168,10 @@ impl ServerPlayerData {
                             "Failed to save player data for {}: {e}",
                             player.gameprofile.id,
                         );
+                        server.alerting.fire(
+                            AlertKind::SaveFailure,
+                            format!("Failed to save player data: {e}"),
+                        );
                     }
                 }
             }
@@ -149,6 +236,76 @@ impl ServerPlayerData {
         }
     }
 
+    /// Fetches the data of a joining player, from the first sync that has it or else from the
+    /// file store.
+    ///
+    /// Waits for the flush of the player's last session first, so a quick rejoin never reads
+    /// data older than what the player left with.
+    pub async fn fetch_data(
+        &self,
+        uuid: &uuid::Uuid,
+    ) -> Result<Option<NbtCompound>, PlayerDataError> {
+        if let Some(flush) = self.pending_flush(uuid) {
+            flush.await;
+        }
+        for sync in self.syncs() {
+            if let Some(data) = sync.fetch(*uuid).await? {
+                return Ok(Some(data));
+            }
+        }
+        self.load_data(uuid)
+    }
+
+    /// Edits the saved data of a player who is not online.
+    ///
+    /// The file stays locked while it is patched, so a concurrent save or a player joining in
+    /// the meantime waits for the edit to finish. Returns `None` if the player has no data.
+    pub async fn edit_offline_data<R: Send + 'static>(
+        &self,
+        uuid: uuid::Uuid,
+        edit: impl FnOnce(&mut NbtCompound) -> R + Send + 'static,
+    ) -> Result<Option<R>, PlayerDataError> {
+        let storage = self.storage.clone();
+        tokio::task::spawn_blocking(move || storage.edit_player_data(&uuid, edit))
+            .await
+            .map_err(|e| PlayerDataError::Io(std::io::Error::other(e)))?
+    }
+
+    /// Loads the ender chest of a player who is not online, or returns `None` if the player has
+    /// no data.
+    pub async fn load_offline_ender_chest(
+        &self,
+        uuid: &uuid::Uuid,
+    ) -> Result<Option<EnderChestInventory>, PlayerDataError> {
+        let (exists, nbt) = self.storage.load_player_data(uuid)?;
+        if !exists {
+            return Ok(None);
+        }
+        let inventory = EnderChestInventory::new();
+        inventory.read_nbt_non_mut(&nbt).await;
+        Ok(Some(inventory))
+    }
+
+    /// Replaces the ender chest of a player who is not online with `inventory`. Returns `false`
+    /// if the player has no data.
+    pub async fn save_offline_ender_chest(
+        &self,
+        uuid: uuid::Uuid,
+        inventory: &EnderChestInventory,
+    ) -> Result<bool, PlayerDataError> {
+        let mut items = NbtCompound::new();
+        inventory.write_nbt(&mut items).await;
+        let saved = self
+            .edit_offline_data(uuid, move |nbt| {
+                nbt.remove(ENDER_ITEMS);
+                if let Some(items) = items.remove(ENDER_ITEMS) {
+                    nbt.put(ENDER_ITEMS, items);
+                }
+            })
+            .await?;
+        Ok(saved.is_some())
+    }
+
     /// Extracts and saves data from a player.
     ///
     /// This function extracts NBT data from a player and saves it to disk.
@@ -179,7 +336,8 @@ impl ServerPlayerData {
 mod test {
     use crate::data::player_server::ServerPlayerData;
     use pumpkin_nbt::compound::NbtCompound;
-    use pumpkin_world::data::player_data::PlayerDataStorage;
+    use pumpkin_nbt::tag::NbtTag;
+    use pumpkin_world::data::player_data::{PlayerDataError, PlayerDataStorage};
     use std::time::Duration;
     use std::time::Instant;
     use tempfile::tempdir;
@@ -272,6 +430,39 @@ mod test {
         assert_eq!(empty_nbt.child_tags.len(), 0);
     }
 
+    #[tokio::test]
+    async fn player_data_storage_edit() {
+        let temp_dir = tempdir().unwrap();
+        let storage = PlayerDataStorage::new(temp_dir.path(), true);
+        let uuid = Uuid::new_v4();
+
+        // Players without data are left alone
+        assert!(storage.edit_player_data(&uuid, |_| ()).unwrap().is_none());
+        assert!(!storage.get_player_data_path(&uuid).exists());
+
+        let mut nbt = NbtCompound::new();
+        nbt.put_string(
+            "TestKey",
+            "A much longer value than the edited one".to_string(),
+        );
+        storage.save_player_data(&uuid, nbt).unwrap();
+
+        let previous = storage
+            .edit_player_data(&uuid, |nbt| {
+                let previous = nbt.remove("TestKey");
+                nbt.put_string("TestKey", "Short".to_string());
+                previous
+            })
+            .unwrap();
+        assert_eq!(
+            previous.flatten().as_ref().and_then(NbtTag::extract_string),
+            Some("A much longer value than the edited one")
+        );
+
+        let (_, loaded_nbt) = storage.load_player_data(&uuid).unwrap();
+        assert_eq!(loaded_nbt.get_string("TestKey").unwrap(), "Short");
+    }
+
     #[tokio::test]
     async fn server_player_data_new() {
         let temp_dir = tempdir().unwrap();
@@ -310,4 +501,47 @@ mod test {
         assert_eq!(loaded_data.get_string("name").unwrap(), "TestPlayer");
         assert_eq!(loaded_data.get_int("level").unwrap(), 42);
     }
+
+    /// A sync keeping the data in memory, slow to flush so a rejoin has to wait for it.
+    #[derive(Default)]
+    struct SlowSync {
+        data: std::sync::Mutex<std::collections::HashMap<Uuid, NbtCompound>>,
+    }
+
+    impl crate::data::player_sync::PlayerDataSync for SlowSync {
+        fn fetch(
+            &self,
+            uuid: Uuid,
+        ) -> crate::plugin::BoxFuture<'_, Result<Option<NbtCompound>, PlayerDataError>> {
+            Box::pin(async move { Ok(self.data.lock().unwrap().get(&uuid).cloned()) })
+        }
+
+        fn flush<'a>(
+            &'a self,
+            uuid: Uuid,
+            data: &'a NbtCompound,
+        ) -> crate::plugin::BoxFuture<'a, Result<(), PlayerDataError>> {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                self.data.lock().unwrap().insert(uuid, data.clone());
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn rejoin_waits_for_the_flush() {
+        let temp_dir = tempdir().unwrap();
+        let data = ServerPlayerData::new(temp_dir.path(), Duration::from_secs(60), true);
+        data.register_sync(std::sync::Arc::new(SlowSync::default()));
+
+        let uuid = Uuid::new_v4();
+        let mut nbt = NbtCompound::new();
+        nbt.put_int("XpLevel", 30);
+        data.start_flush(uuid, nbt);
+        assert!(data.pending_flush(&uuid).is_some());
+
+        let fetched = data.fetch_data(&uuid).await.unwrap().unwrap();
+        assert_eq!(fetched.get_int("XpLevel"), Some(30));
+    }
 }
@@ -0,0 +1,49 @@
+use futures::future::Shared;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_world::data::player_data::PlayerDataError;
+use uuid::Uuid;
+
+use crate::plugin::BoxFuture;
+
+/// Resolves once the data of a player who left was flushed to every registered
+/// [`PlayerDataSync`].
+pub type PendingFlush = Shared<BoxFuture<'static, ()>>;
+
+/// Keeps player data in sync with a store shared by the servers of a network, like Redis or a
+/// SQL database, so inventories and advancements follow players between servers.
+///
+/// Plugins register syncs with `Context::register_player_data_sync`. The file store stays the
+/// local copy: a player's data is still written to it on quit, and read from it when no sync
+/// has data for the player.
+///
+/// ```ignore
+/// struct RedisSync { /* ... */ }
+///
+/// impl PlayerDataSync for RedisSync {
+///     fn fetch(&self, uuid: Uuid) -> BoxFuture<'_, Result<Option<NbtCompound>, PlayerDataError>> {
+///         Box::pin(async move { self.get(uuid).await })
+///     }
+///
+///     fn flush<'a>(&'a self, uuid: Uuid, data: &'a NbtCompound) -> BoxFuture<'a, Result<(), PlayerDataError>> {
+///         Box::pin(async move { self.set(uuid, data).await })
+///     }
+/// }
+/// ```
+pub trait PlayerDataSync: Send + Sync {
+    /// Fetches the data of a joining player before they are created.
+    ///
+    /// The first sync returning `Some` wins over the file store. An error denies the login,
+    /// since falling back to the local data could bring back items the player used up on
+    /// another server.
+    fn fetch(&self, uuid: Uuid) -> BoxFuture<'_, Result<Option<NbtCompound>, PlayerDataError>>;
+
+    /// Stores the data of a player who left, after it was written to the file store. Errors
+    /// are logged.
+    ///
+    /// A rejoin on this server waits for the flush to finish before fetching again.
+    fn flush<'a>(
+        &'a self,
+        uuid: Uuid,
+        data: &'a NbtCompound,
+    ) -> BoxFuture<'a, Result<(), PlayerDataError>>;
+}
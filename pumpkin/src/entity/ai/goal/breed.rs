@@ -23,6 +23,8 @@ pub struct BreedGoal {
     pub breed_cooldown: i32,
     /// Entity ID of the current breeding partner (0 = none).
     partner_id: i32,
+    /// Entity ID of the partner of the last mating, until taken.
+    bred_with: Option<i32>,
 }
 
 impl BreedGoal {
@@ -43,6 +45,7 @@ impl BreedGoal {
             love_ticks: 0,
             breed_cooldown: 0,
             partner_id: 0,
+            bred_with: None,
         })
     }
 
@@ -59,6 +62,12 @@ impl BreedGoal {
         self.love_ticks > 0
     }
 
+    /// Takes the entity ID of the partner of the last mating, for mobs that do more when they
+    /// mate, like turtles getting pregnant.
+    pub const fn take_bred_partner(&mut self) -> Option<i32> {
+        self.bred_with.take()
+    }
+
     /// Whether this mob can breed (in love and not on cooldown).
     #[must_use]
     pub const fn can_breed(&self) -> bool {
@@ -197,6 +206,7 @@ impl Goal for BreedGoal {
                 // Reset love mode and set breed cooldown
                 self.love_ticks = 0;
                 self.breed_cooldown = Self::BREED_COOLDOWN;
+                self.bred_with = Some(self.partner_id);
                 self.partner_id = 0;
 
                 // Spawn heart particles around the mob
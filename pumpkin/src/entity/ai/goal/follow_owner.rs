@@ -117,10 +117,21 @@ impl Goal for FollowOwnerGoal {
             let dist_sq = mob_pos.squared_distance_to_vec(&owner_pos);
 
             if dist_sq > self.teleport_distance_sq {
-                // Teleport to owner when too far
-                mob_entity.living_entity.entity.set_pos(owner_pos);
-                let mut navigator = mob_entity.navigator.lock().await;
-                navigator.cancel();
+                // Teleport to owner when too far. The owner may be in another region
+                mob_entity.navigator.lock().await.cancel();
+                let mob_id = mob_entity.living_entity.entity.entity_id;
+                world
+                    .queue_synchronized(Box::new(move |world| {
+                        Box::pin(async move {
+                            if let (Some(mob), Some(owner)) = (
+                                world.get_entity_by_id(mob_id),
+                                world.get_player_by_id(owner_id),
+                            ) {
+                                mob.get_entity().set_pos(owner.position());
+                            }
+                        })
+                    }))
+                    .await;
             } else {
                 // Navigate toward owner
                 let mut navigator = mob_entity.navigator.lock().await;
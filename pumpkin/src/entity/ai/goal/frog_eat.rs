@@ -0,0 +1,231 @@
+use std::sync::{Arc, Weak};
+
+use pumpkin_data::{
+    damage::DamageType,
+    entity::{EntityPose, EntityType},
+    meta_data_type::MetaDataType,
+    sound::{Sound, SoundCategory},
+    tag::{self, Taggable},
+    tracked_data::TrackedData,
+};
+use pumpkin_protocol::{
+    codec::{optional_int::OptionalInt, var_int::VarInt},
+    java::client::play::Metadata,
+};
+use pumpkin_world::item::ItemStack;
+
+use super::{Controls, Goal, GoalFuture};
+use crate::entity::ai::path::NavigatorGoal;
+use crate::entity::mob::Mob;
+use crate::entity::passive::frog::FrogEntity;
+use crate::entity::{Entity, EntityBase};
+
+/// How far frogs look for food.
+const SEARCH_RANGE: f64 = 10.0;
+/// How far the tongue of a frog reaches, squared.
+const TONGUE_RANGE_SQ: f64 = 1.75 * 1.75;
+/// Frogs only eat slimes and magma cubes of the smallest size.
+const MAX_FOOD_WIDTH: f32 = 0.52;
+/// Ticks from shooting the tongue to swallowing.
+const EAT_TICK: i32 = 6;
+/// Ticks from shooting the tongue to pulling it back in.
+const TONGUE_TICKS: i32 = 10;
+/// Damage of the tongue, enough to kill small slimes.
+const TONGUE_DAMAGE: f32 = 10.0;
+
+/// Makes a frog walk up to a small slime or magma cube and eat it with its tongue. Magma cubes
+/// eaten by a frog drop the froglight of its variant.
+pub struct FrogEatGoal {
+    frog: Weak<FrogEntity>,
+    speed: f64,
+    target: Option<Arc<dyn EntityBase>>,
+    /// Ticks since the tongue was shot, 0 while walking up to the target.
+    tongue_ticks: i32,
+}
+
+impl FrogEatGoal {
+    #[must_use]
+    pub fn new(frog: Weak<FrogEntity>, speed: f64) -> Box<Self> {
+        Box::new(Self {
+            frog,
+            speed,
+            target: None,
+            tongue_ticks: 0,
+        })
+    }
+
+    fn is_food(entity: &Entity) -> bool {
+        entity
+            .entity_type
+            .has_tag(&tag::EntityType::MINECRAFT_FROG_FOOD)
+            && entity.is_alive()
+            && entity.entity_dimension.load().width <= MAX_FOOD_WIDTH
+    }
+
+    async fn set_tongue(entity: &Entity, target: Option<i32>) {
+        let pose = if target.is_some() {
+            EntityPose::UsingTongue
+        } else {
+            EntityPose::Standing
+        };
+        // Frogs keep their hitbox while using the tongue, so the pose isn't set the usual way
+        entity.pose.store(pose);
+        entity
+            .send_meta_data(&[
+                Metadata::new(
+                    TrackedData::DATA_POSE,
+                    MetaDataType::EntityPose,
+                    VarInt(pose as i32),
+                ),
+                Metadata::new(
+                    FrogEntity::DATA_TONGUE_TARGET,
+                    MetaDataType::OptionalInt,
+                    OptionalInt(target),
+                ),
+            ])
+            .await;
+    }
+
+    async fn eat(frog: &FrogEntity, target: &dyn EntityBase) {
+        let entity = &frog.mob_entity.living_entity.entity;
+        let world = entity.world.load();
+        world
+            .play_sound(
+                Sound::EntityFrogEat,
+                SoundCategory::Neutral,
+                &entity.pos.load(),
+            )
+            .await;
+        let food = target.get_entity();
+        if !food.is_alive() {
+            return;
+        }
+        if food.entity_type == &EntityType::MAGMA_CUBE {
+            // The loot table of magma cubes can't tell the variant of the frog, so the froglight
+            // is dropped here
+            let pos = food.block_pos.load();
+            food.remove().await;
+            world
+                .drop_stack(&pos, ItemStack::new(1, frog.variant().froglight()))
+                .await;
+        } else {
+            target
+                .damage_with_context(
+                    target,
+                    TONGUE_DAMAGE,
+                    DamageType::MOB_ATTACK,
+                    None,
+                    Some(frog),
+                    Some(frog),
+                )
+                .await;
+        }
+    }
+}
+
+impl Goal for FrogEatGoal {
+    fn can_start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            let entity = mob.get_entity();
+            let pos = entity.pos.load();
+            self.target = entity
+                .world
+                .load()
+                .get_nearby_entities(pos, SEARCH_RANGE)
+                .into_values()
+                .filter(|candidate| Self::is_food(candidate.get_entity()))
+                .min_by(|a, b| {
+                    let a = pos.squared_distance_to_vec(&a.get_entity().pos.load());
+                    let b = pos.squared_distance_to_vec(&b.get_entity().pos.load());
+                    a.total_cmp(&b)
+                });
+            self.target.is_some()
+        })
+    }
+
+    fn should_continue<'a>(&'a self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            let Some(target) = &self.target else {
+                return false;
+            };
+            if self.tongue_ticks > 0 {
+                return self.tongue_ticks < TONGUE_TICKS;
+            }
+            let food = target.get_entity();
+            Self::is_food(food)
+                && mob
+                    .get_entity()
+                    .pos
+                    .load()
+                    .squared_distance_to_vec(&food.pos.load())
+                    <= SEARCH_RANGE * SEARCH_RANGE
+        })
+    }
+
+    fn start<'a>(&'a mut self, _mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            self.tongue_ticks = 0;
+        })
+    }
+
+    fn stop<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            self.target = None;
+            mob.get_mob_entity().navigator.lock().await.cancel();
+            if self.tongue_ticks > 0 {
+                self.tongue_ticks = 0;
+                Self::set_tongue(mob.get_entity(), None).await;
+            }
+        })
+    }
+
+    fn tick<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            let (Some(frog), Some(target)) = (self.frog.upgrade(), self.target.clone()) else {
+                return;
+            };
+            let mob_entity = mob.get_mob_entity();
+            let entity = &mob_entity.living_entity.entity;
+            let target_pos = target.get_entity().pos.load();
+            entity.look_at(target_pos);
+
+            if self.tongue_ticks > 0 {
+                self.tongue_ticks += 1;
+                if self.tongue_ticks == EAT_TICK {
+                    Self::eat(&frog, target.as_ref()).await;
+                }
+                return;
+            }
+
+            let pos = entity.pos.load();
+            if pos.squared_distance_to_vec(&target_pos) > TONGUE_RANGE_SQ {
+                mob_entity
+                    .navigator
+                    .lock()
+                    .await
+                    .set_progress(NavigatorGoal {
+                        current_progress: pos,
+                        destination: target_pos,
+                        speed: self.speed,
+                    });
+                return;
+            }
+            mob_entity.navigator.lock().await.cancel();
+            self.tongue_ticks = 1;
+            entity
+                .world
+                .load()
+                .play_sound(Sound::EntityFrogTongue, SoundCategory::Neutral, &pos)
+                .await;
+            Self::set_tongue(entity, Some(target.get_entity().entity_id)).await;
+        })
+    }
+
+    fn should_run_every_tick(&self) -> bool {
+        true
+    }
+
+    fn controls(&self) -> Controls {
+        Controls::MOVE | Controls::LOOK
+    }
+}
@@ -22,6 +22,14 @@ impl GoalSelector {
             .push(PrioritizedGoal::new(TypeId::of::<G>(), priority, goal));
     }
 
+    /// Removes all goals. Only use this before the selector is first ticked, e.g. when a
+    /// [`MobAiProvider`](crate::entity::ai::provider::MobAiProvider) replaces the vanilla goals,
+    /// as running goals are not stopped.
+    pub fn clear(&mut self) {
+        self.goals.clear();
+        self.goals_by_control = [usize::MAX; 4];
+    }
+
     pub async fn remove_goal<G: Goal + 'static>(&mut self, mob: &dyn Mob) {
         let mut goals_to_remove = Vec::with_capacity(2);
         for (i, prioritized_goal) in &mut self.goals.iter_mut().enumerate() {
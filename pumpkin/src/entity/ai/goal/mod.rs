@@ -8,6 +8,7 @@ pub mod creeper_ignite;
 pub mod flee_entity;
 pub mod follow_owner;
 pub mod follow_parent;
+pub mod frog_eat;
 pub mod goal_selector;
 pub mod look_around;
 pub mod look_at_entity;
@@ -15,10 +16,13 @@ pub mod melee_attack;
 pub mod move_to_target_pos;
 pub mod panic;
 pub mod ranged_attack;
+pub mod skeleton_trap;
+pub mod sniffer_dig;
 pub mod step_and_destroy_block;
 pub mod swim;
 pub mod tempt;
 mod track_target;
+pub mod turtle;
 pub mod wander_around;
 pub mod zombie_attack;
 
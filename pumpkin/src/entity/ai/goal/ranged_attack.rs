@@ -6,10 +6,10 @@ use crate::entity::mob::Mob;
 /// A goal that makes the mob attack its target from range.
 ///
 /// The mob navigates toward its target when out of range, then stands and
-/// fires at a fixed interval. Projectile spawning is not yet implemented —
-/// the goal currently deals direct damage as a placeholder.
+/// fires at a fixed interval. What it fires is up to the mob, see
+/// [`Mob::perform_ranged_attack`].
 ///
-/// Used by: Skeleton, Blaze, Ghast, Pillager, Drowned, Witch.
+/// Used by: Skeleton, Blaze, Ghast, Pillager, Drowned, Witch, Wither.
 pub struct RangedAttackGoal {
     goal_control: Controls,
     speed: f64,
@@ -105,10 +105,9 @@ impl Goal for RangedAttackGoal {
             if self.cooldown <= 0 && self.seen_target_ticks > 0 {
                 self.cooldown = self.attack_interval;
 
-                // TODO: Spawn actual projectile (arrow, fireball, trident, potion)
-                // based on the mob type. For now this is a stub — the ranged attack
-                // goal provides correct targeting and timing behavior, but projectile
-                // creation requires the projectile entity system.
+                let pull_progress = (dist_sq / self.attack_radius_sq).sqrt().clamp(0.1, 1.0);
+                mob.perform_ranged_attack(target.as_ref(), pull_progress as f32)
+                    .await;
             }
         })
     }
@@ -0,0 +1,92 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Weak};
+
+use pumpkin_data::entity::EntityType;
+use pumpkin_util::math::vector3::Vector3;
+use rand::RngExt;
+
+use super::{Goal, GoalFuture};
+use crate::entity::lightning::LightningEntity;
+use crate::entity::mob::Mob;
+use crate::entity::mob::skeleton::SkeletonEntityBase;
+use crate::entity::passive::skeleton_horse::SkeletonHorseEntity;
+use crate::entity::{Entity, EntityBase};
+use crate::world::World;
+
+/// Distance at which a player springs the trap.
+const TRIGGER_DISTANCE: f64 = 10.0;
+
+/// Springs a skeleton trap when a player comes close: the trap horse and three more horses
+/// spawn with skeletons riding them.
+pub struct SkeletonTrapGoal {
+    horse: Weak<SkeletonHorseEntity>,
+}
+
+impl SkeletonTrapGoal {
+    #[must_use]
+    pub fn new(horse: Weak<SkeletonHorseEntity>) -> Box<Self> {
+        Box::new(Self { horse })
+    }
+
+    /// Spawns a skeleton riding `horse`.
+    async fn spawn_rider(world: &Arc<World>, horse: Arc<dyn EntityBase>) {
+        let pos = horse.get_entity().pos.load();
+        let skeleton =
+            SkeletonEntityBase::make(Entity::new(world.clone(), pos, &EntityType::SKELETON)).await;
+        // TODO: Vanilla gives the skeletons an iron helmet and an enchanted bow
+        world.spawn_entity(skeleton.clone()).await;
+        world.start_riding(horse, skeleton).await;
+    }
+}
+
+impl Goal for SkeletonTrapGoal {
+    fn can_start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            let Some(horse) = self.horse.upgrade() else {
+                return false;
+            };
+            let entity = mob.get_entity();
+            horse.trap.load(Ordering::Relaxed)
+                && entity
+                    .world
+                    .load()
+                    .get_closest_player(entity.pos.load(), TRIGGER_DISTANCE)
+                    .is_some()
+        })
+    }
+
+    fn start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            let Some(horse) = self.horse.upgrade() else {
+                return;
+            };
+            horse.trap.store(false, Ordering::Relaxed);
+            let entity = mob.get_entity();
+            let world = entity.world.load_full();
+            let pos = entity.pos.load();
+
+            let lightning = Entity::new(world.clone(), pos, &EntityType::LIGHTNING_BOLT);
+            world
+                .spawn_entity(Arc::new(LightningEntity::new(lightning, true)))
+                .await;
+
+            Self::spawn_rider(&world, horse).await;
+            for _ in 0..3 {
+                let other = SkeletonHorseEntity::new(Entity::new(
+                    world.clone(),
+                    pos,
+                    &EntityType::SKELETON_HORSE,
+                ))
+                .await;
+                let velocity = {
+                    let mut rng = rand::rng();
+                    let mut triangle = || 1.1485 * (rng.random::<f64>() - rng.random::<f64>());
+                    Vector3::new(triangle(), 0.0, triangle())
+                };
+                world.spawn_entity(other.clone()).await;
+                other.get_entity().set_velocity(velocity).await;
+                Self::spawn_rider(&world, other).await;
+            }
+        })
+    }
+}
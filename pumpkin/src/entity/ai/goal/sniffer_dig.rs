@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+use std::sync::Weak;
+use std::sync::atomic::Ordering;
+
+use pumpkin_data::{
+    item::Item,
+    sound::{Sound, SoundCategory},
+    tag::{self, Taggable},
+};
+use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
+use pumpkin_world::item::ItemStack;
+use rand::RngExt;
+
+use super::{Controls, Goal, GoalFuture};
+use crate::entity::ai::path::NavigatorGoal;
+use crate::entity::mob::Mob;
+use crate::entity::passive::sniffer::{SnifferEntity, SnifferState};
+
+/// One in how many goal checks an idle sniffer starts sniffing.
+const SNIFF_CHANCE: i32 = 200;
+/// How far from itself a sniffer looks for a block to dig in.
+const SEARCH_RANGE: i32 = 8;
+/// Ticks of sniffing the air before searching.
+const SNIFFING_TICKS: i32 = 40;
+/// Ticks a sniffer searches for its block before giving up.
+const SEARCHING_TICKS: i32 = 600;
+const DIGGING_TICKS: i32 = 120;
+const RISING_TICKS: i32 = 40;
+/// Ticks after digging until the sniffer sniffs again.
+const DIG_COOLDOWN: i32 = 9600;
+/// How many dug blocks a sniffer remembers, which it won't dig again.
+const EXPLORED_POSITIONS: usize = 20;
+
+/// Makes an adult sniffer sniff the air, walk to a block it can dig in and dig up torchflower
+/// seeds or a pitcher pod there.
+pub struct SnifferDigGoal {
+    sniffer: Weak<SnifferEntity>,
+    speed: f64,
+    /// The block the sniffer digs in.
+    target: Option<BlockPos>,
+    /// Ticks spent in the current state.
+    state_ticks: i32,
+    cooldown: i32,
+    explored: VecDeque<BlockPos>,
+}
+
+impl SnifferDigGoal {
+    #[must_use]
+    pub fn new(sniffer: Weak<SnifferEntity>, speed: f64) -> Box<Self> {
+        Box::new(Self {
+            sniffer,
+            speed,
+            target: None,
+            state_ticks: 0,
+            cooldown: 0,
+            explored: VecDeque::with_capacity(EXPLORED_POSITIONS),
+        })
+    }
+
+    /// Picks a random diggable block with room above that wasn't dug yet.
+    async fn find_target(&self, mob: &dyn Mob) -> Option<BlockPos> {
+        let entity = mob.get_entity();
+        let world = entity.world.load();
+        let origin = entity.block_pos.load().down();
+        for _ in 0..10 {
+            let (dx, dy, dz) = {
+                let mut rng = mob.get_random();
+                (
+                    rng.random_range(-SEARCH_RANGE..=SEARCH_RANGE),
+                    rng.random_range(-1..=1),
+                    rng.random_range(-SEARCH_RANGE..=SEARCH_RANGE),
+                )
+            };
+            let pos = origin.offset(Vector3::new(dx, dy, dz));
+            if self.explored.contains(&pos) {
+                continue;
+            }
+            if world
+                .get_block(&pos)
+                .await
+                .has_tag(&tag::Block::MINECRAFT_SNIFFER_DIGGABLE_BLOCK)
+                && world.get_block_state(&pos.up()).await.is_air()
+            {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    /// The state a sniffer moves on to after `ticks` in `state`, for the states that only last
+    /// a while. Rising ends in idle, when the goal stops.
+    const fn next_state(state: SnifferState, ticks: i32) -> Option<SnifferState> {
+        match state {
+            SnifferState::Sniffing if ticks >= SNIFFING_TICKS => Some(SnifferState::Searching),
+            SnifferState::Digging if ticks >= DIGGING_TICKS => Some(SnifferState::Rising),
+            SnifferState::Rising if ticks >= RISING_TICKS => Some(SnifferState::Idle),
+            _ => None,
+        }
+    }
+
+    fn remember_explored(&mut self, pos: BlockPos) {
+        if self.explored.len() == EXPLORED_POSITIONS {
+            self.explored.pop_front();
+        }
+        self.explored.push_back(pos);
+    }
+
+    async fn play_sound(mob: &dyn Mob, sound: Sound) {
+        let entity = mob.get_entity();
+        entity
+            .world
+            .load()
+            .play_sound(sound, SoundCategory::Neutral, &entity.pos.load())
+            .await;
+    }
+
+    async fn enter(&mut self, sniffer: &SnifferEntity, mob: &dyn Mob, state: SnifferState) {
+        self.state_ticks = 0;
+        sniffer.set_state(state).await;
+        let sound = match state {
+            SnifferState::Sniffing => Sound::EntitySnifferSniffing,
+            SnifferState::Searching => Sound::EntitySnifferSearching,
+            SnifferState::Digging => Sound::EntitySnifferDigging,
+            SnifferState::Rising => Sound::EntitySnifferDiggingStop,
+            _ => return,
+        };
+        Self::play_sound(mob, sound).await;
+    }
+
+    async fn drop_seed(mob: &dyn Mob, target: BlockPos) {
+        let seed = if mob.get_random().random_bool(0.5) {
+            &Item::TORCHFLOWER_SEEDS
+        } else {
+            &Item::PITCHER_POD
+        };
+        mob.get_entity()
+            .world
+            .load()
+            .drop_stack(&target.up(), ItemStack::new(1, seed))
+            .await;
+        Self::play_sound(mob, Sound::EntitySnifferDropSeed).await;
+    }
+}
+
+impl Goal for SnifferDigGoal {
+    fn can_start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            if self.cooldown > 0 {
+                self.cooldown -= 1;
+                return false;
+            }
+            let entity = mob.get_entity();
+            if entity.age.load(Ordering::Relaxed) < 0
+                || !entity.on_ground.load(Ordering::Relaxed)
+                || mob.get_random().random_range(0..SNIFF_CHANCE) != 0
+            {
+                return false;
+            }
+            self.target = self.find_target(mob).await;
+            self.target.is_some()
+        })
+    }
+
+    fn should_continue<'a>(&'a self, _mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move { self.target.is_some() })
+    }
+
+    fn start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(sniffer) = self.sniffer.upgrade() {
+                self.enter(&sniffer, mob, SnifferState::Sniffing).await;
+            }
+        })
+    }
+
+    fn stop<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            self.target = None;
+            mob.get_mob_entity().navigator.lock().await.cancel();
+            if let Some(sniffer) = self.sniffer.upgrade() {
+                sniffer.set_state(SnifferState::Idle).await;
+            }
+        })
+    }
+
+    fn tick<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            let (Some(sniffer), Some(target)) = (self.sniffer.upgrade(), self.target) else {
+                return;
+            };
+            self.state_ticks += 1;
+            let mob_entity = mob.get_mob_entity();
+            let entity = &mob_entity.living_entity.entity;
+            match sniffer.state() {
+                SnifferState::Searching => {
+                    if entity.block_pos.load() == target.up() {
+                        mob_entity.navigator.lock().await.cancel();
+                        self.enter(&sniffer, mob, SnifferState::Digging).await;
+                    } else if self.state_ticks >= SEARCHING_TICKS
+                        || mob_entity.navigator.lock().await.is_idle()
+                    {
+                        // Couldn't get there, try somewhere else later
+                        self.remember_explored(target);
+                        self.target = None;
+                    }
+                }
+                SnifferState::Digging => {
+                    entity.look_at(target.to_centered_f64());
+                    if self.state_ticks % 20 == 0 {
+                        Self::play_sound(mob, Sound::EntitySnifferDigging).await;
+                    }
+                }
+                _ => {}
+            }
+            match Self::next_state(sniffer.state(), self.state_ticks) {
+                Some(SnifferState::Searching) => {
+                    mob_entity
+                        .navigator
+                        .lock()
+                        .await
+                        .set_progress(NavigatorGoal {
+                            current_progress: entity.pos.load(),
+                            destination: target.up().to_centered_f64(),
+                            speed: self.speed,
+                        });
+                    self.enter(&sniffer, mob, SnifferState::Searching).await;
+                }
+                Some(SnifferState::Rising) => {
+                    Self::drop_seed(mob, target).await;
+                    self.remember_explored(target);
+                    self.cooldown = DIG_COOLDOWN;
+                    self.enter(&sniffer, mob, SnifferState::Rising).await;
+                }
+                Some(_) => self.target = None,
+                None => {}
+            }
+        })
+    }
+
+    fn should_run_every_tick(&self) -> bool {
+        true
+    }
+
+    fn controls(&self) -> Controls {
+        Controls::MOVE | Controls::LOOK | Controls::JUMP
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffer_sniffs_digs_and_rises() {
+        let next = SnifferDigGoal::next_state;
+        assert_eq!(next(SnifferState::Sniffing, SNIFFING_TICKS - 1), None);
+        assert_eq!(
+            next(SnifferState::Sniffing, SNIFFING_TICKS),
+            Some(SnifferState::Searching)
+        );
+        // Searching ends when the sniffer arrives or gives up, not after a fixed time
+        assert_eq!(next(SnifferState::Searching, SEARCHING_TICKS), None);
+        assert_eq!(next(SnifferState::Digging, DIGGING_TICKS - 1), None);
+        assert_eq!(
+            next(SnifferState::Digging, DIGGING_TICKS),
+            Some(SnifferState::Rising)
+        );
+        assert_eq!(
+            next(SnifferState::Rising, RISING_TICKS),
+            Some(SnifferState::Idle)
+        );
+        assert_eq!(next(SnifferState::Idle, i32::MAX), None);
+    }
+
+    #[test]
+    fn sniffer_forgets_the_oldest_explored_block() {
+        let mut goal = SnifferDigGoal::new(Weak::new(), 1.0);
+        for x in 0..=EXPLORED_POSITIONS as i32 {
+            goal.remember_explored(BlockPos::new(x, 0, 0));
+        }
+        assert_eq!(goal.explored.len(), EXPLORED_POSITIONS);
+        assert!(!goal.explored.contains(&BlockPos::new(0, 0, 0)));
+        assert!(goal.explored.contains(&BlockPos::new(1, 0, 0)));
+        assert!(
+            goal.explored
+                .contains(&BlockPos::new(EXPLORED_POSITIONS as i32, 0, 0))
+        );
+    }
+}
@@ -55,7 +55,7 @@ impl Goal for TemptGoal {
             let world = mob_entity.living_entity.entity.world.load();
 
             // Find nearby players sorted by distance
-            let players = world.get_nearby_players(mob_pos, self.range);
+            let players = world.nearby_players.load().within(mob_pos, self.range);
 
             // Find the closest player holding a tempt item
             for player in &players {
@@ -100,8 +100,7 @@ impl TrackTargetGoal {
 impl Goal for TrackTargetGoal {
     fn should_continue<'a>(&'a self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
         Box::pin(async {
-            let mob = mob.get_mob_entity();
-            let mob_target = mob.target.lock().await;
+            let mob_target = mob.get_mob_entity().target.lock().await;
 
             let target = if mob_target.is_some() {
                 (*mob_target).clone()
@@ -111,10 +110,17 @@ impl Goal for TrackTargetGoal {
 
             drop(mob_target);
 
-            if target.is_none() {
+            let Some(target) = target else {
                 return false;
-            } // TODO: continue when scoreboard team are implemented
-            true
+            };
+            // Mobs leave their teammates alone
+            let world = mob.get_entity().world.load_full();
+            let scoreboard = world.scoreboard.lock().await;
+            !scoreboard.team_of_entity(mob).is_some_and(|team| {
+                scoreboard
+                    .team_of_entity(target.as_ref())
+                    .is_some_and(|other| other.name == team.name)
+            })
         })
     }
 
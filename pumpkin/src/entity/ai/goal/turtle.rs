@@ -0,0 +1,317 @@
+use std::sync::Weak;
+
+use pumpkin_data::{
+    Block,
+    block_properties::{BlockProperties, EnumVariants, Integer1To4, TurtleEggLikeProperties},
+    sound::{Sound, SoundCategory},
+    tag::{self, Taggable},
+};
+use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
+use pumpkin_world::world::BlockFlags;
+use rand::RngExt;
+
+use super::{Controls, Goal, GoalFuture, breed::BreedGoal};
+use crate::entity::ai::path::NavigatorGoal;
+use crate::entity::mob::Mob;
+use crate::entity::passive::turtle::TurtleEntity;
+
+/// How far a pregnant turtle lays its eggs from its home.
+const NEST_RANGE: i32 = 8;
+/// How long a turtle digs before its eggs are laid.
+const DIG_TICKS: i32 = 200;
+
+/// Mating of turtles, which fall in love when fed seagrass. Of each pair, the turtle with the
+/// lower entity ID becomes pregnant.
+pub struct TurtleMateGoal {
+    turtle: Weak<TurtleEntity>,
+    breed: Box<BreedGoal>,
+}
+
+impl TurtleMateGoal {
+    #[must_use]
+    pub fn new(turtle: Weak<TurtleEntity>, speed: f64) -> Box<Self> {
+        Box::new(Self {
+            turtle,
+            breed: BreedGoal::new(speed),
+        })
+    }
+}
+
+impl Goal for TurtleMateGoal {
+    fn can_start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            if let Some(turtle) = self.turtle.upgrade()
+                && turtle.take_love_request()
+            {
+                self.breed.set_in_love();
+            }
+            self.breed.can_start(mob).await
+        })
+    }
+
+    fn should_continue<'a>(&'a self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        self.breed.should_continue(mob)
+    }
+
+    fn start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        self.breed.start(mob)
+    }
+
+    fn stop<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        self.breed.stop(mob)
+    }
+
+    fn tick<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            self.breed.tick(mob).await;
+            let Some(partner_id) = self.breed.take_bred_partner() else {
+                return;
+            };
+            if let Some(turtle) = self.turtle.upgrade()
+                && turtle.mob_entity.living_entity.entity.entity_id < partner_id
+            {
+                turtle.set_has_egg(true).await;
+            }
+        })
+    }
+
+    fn should_run_every_tick(&self) -> bool {
+        self.breed.should_run_every_tick()
+    }
+
+    fn controls(&self) -> Controls {
+        self.breed.controls()
+    }
+}
+
+/// Brings a pregnant turtle back to the beach it hatched on.
+pub struct GoHomeGoal {
+    turtle: Weak<TurtleEntity>,
+    speed: f64,
+}
+
+impl GoHomeGoal {
+    #[must_use]
+    pub fn new(turtle: Weak<TurtleEntity>, speed: f64) -> Box<Self> {
+        Box::new(Self { turtle, speed })
+    }
+
+    fn is_far_from_home(turtle: &TurtleEntity) -> bool {
+        let home = turtle.home_pos().to_centered_f64();
+        let pos = turtle.mob_entity.living_entity.entity.pos.load();
+        pos.squared_distance_to_vec(&home) > f64::from(NEST_RANGE * NEST_RANGE)
+    }
+}
+
+impl Goal for GoHomeGoal {
+    fn can_start<'a>(&'a mut self, _mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            self.turtle
+                .upgrade()
+                .is_some_and(|turtle| turtle.has_egg() && Self::is_far_from_home(&turtle))
+        })
+    }
+
+    fn should_continue<'a>(&'a self, mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            self.turtle
+                .upgrade()
+                .is_some_and(|turtle| turtle.has_egg() && Self::is_far_from_home(&turtle))
+                && !mob.get_mob_entity().navigator.lock().await.is_idle()
+        })
+    }
+
+    fn start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            let Some(turtle) = self.turtle.upgrade() else {
+                return;
+            };
+            let mob_entity = mob.get_mob_entity();
+            mob_entity
+                .navigator
+                .lock()
+                .await
+                .set_progress(NavigatorGoal {
+                    current_progress: mob_entity.living_entity.entity.pos.load(),
+                    destination: turtle.home_pos().to_centered_f64(),
+                    speed: self.speed,
+                });
+        })
+    }
+
+    fn stop<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            mob.get_mob_entity().navigator.lock().await.cancel();
+        })
+    }
+
+    fn controls(&self) -> Controls {
+        Controls::MOVE
+    }
+}
+
+/// Makes a pregnant turtle at home dig into the sand and lay one to four eggs there.
+pub struct LayEggGoal {
+    turtle: Weak<TurtleEntity>,
+    speed: f64,
+    /// The sand the eggs are laid on.
+    nest: Option<BlockPos>,
+    dig_ticks: i32,
+}
+
+impl LayEggGoal {
+    #[must_use]
+    pub fn new(turtle: Weak<TurtleEntity>, speed: f64) -> Box<Self> {
+        Box::new(Self {
+            turtle,
+            speed,
+            nest: None,
+            dig_ticks: 0,
+        })
+    }
+
+    /// Finds sand with room for eggs above, close to the turtle and its home.
+    async fn find_nest(turtle: &TurtleEntity) -> Option<BlockPos> {
+        let entity = &turtle.mob_entity.living_entity.entity;
+        let world = entity.world.load();
+        let origin = entity.block_pos.load();
+        let home = turtle.home_pos();
+        let mut best: Option<(BlockPos, i32)> = None;
+        for dy in -1..=1 {
+            for dx in -NEST_RANGE..=NEST_RANGE {
+                for dz in -NEST_RANGE..=NEST_RANGE {
+                    let pos = origin.offset(Vector3::new(dx, dy, dz));
+                    let distance = dx * dx + dy * dy + dz * dz;
+                    if best.is_some_and(|(_, best_distance)| best_distance <= distance)
+                        || home.squared_distance(&pos) > NEST_RANGE * NEST_RANGE
+                    {
+                        continue;
+                    }
+                    if world
+                        .get_block(&pos)
+                        .await
+                        .has_tag(&tag::Block::MINECRAFT_SAND)
+                        && world.get_block_state(&pos.up()).await.is_air()
+                    {
+                        best = Some((pos, distance));
+                    }
+                }
+            }
+        }
+        best.map(|(pos, _)| pos)
+    }
+
+    async fn lay_eggs(turtle: &TurtleEntity, nest: BlockPos) {
+        let entity = &turtle.mob_entity.living_entity.entity;
+        let world = entity.world.load();
+        let egg_pos = nest.up();
+        world
+            .play_sound_fine(
+                Sound::EntityTurtleLayEgg,
+                SoundCategory::Blocks,
+                &egg_pos.to_centered_f64(),
+                0.3,
+                rand::rng().random::<f32>().mul_add(0.2, 0.9),
+            )
+            .await;
+        let mut properties = TurtleEggLikeProperties::from_state_id(
+            Block::TURTLE_EGG.default_state.id,
+            &Block::TURTLE_EGG,
+        );
+        properties.eggs = Integer1To4::from_index(rand::rng().random_range(0..4));
+        world
+            .set_block_state(
+                &egg_pos,
+                properties.to_state_id(&Block::TURTLE_EGG),
+                BlockFlags::NOTIFY_ALL,
+            )
+            .await;
+        turtle.set_has_egg(false).await;
+    }
+}
+
+impl Goal for LayEggGoal {
+    fn can_start<'a>(&'a mut self, _mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            let Some(turtle) = self.turtle.upgrade() else {
+                return false;
+            };
+            if !turtle.has_egg() || GoHomeGoal::is_far_from_home(&turtle) {
+                return false;
+            }
+            self.nest = Self::find_nest(&turtle).await;
+            self.nest.is_some()
+        })
+    }
+
+    fn should_continue<'a>(&'a self, _mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async move {
+            self.nest.is_some() && self.turtle.upgrade().is_some_and(|turtle| turtle.has_egg())
+        })
+    }
+
+    fn start<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            self.dig_ticks = 0;
+            let Some(nest) = self.nest else {
+                return;
+            };
+            let mob_entity = mob.get_mob_entity();
+            mob_entity
+                .navigator
+                .lock()
+                .await
+                .set_progress(NavigatorGoal {
+                    current_progress: mob_entity.living_entity.entity.pos.load(),
+                    destination: nest.up().to_centered_f64(),
+                    speed: self.speed,
+                });
+        })
+    }
+
+    fn stop<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            self.nest = None;
+            mob.get_mob_entity().navigator.lock().await.cancel();
+            if self.dig_ticks > 0
+                && let Some(turtle) = self.turtle.upgrade()
+            {
+                turtle.set_laying_egg(false).await;
+            }
+        })
+    }
+
+    fn tick<'a>(&'a mut self, mob: &'a dyn Mob) -> GoalFuture<'a, ()> {
+        Box::pin(async move {
+            let (Some(turtle), Some(nest)) = (self.turtle.upgrade(), self.nest) else {
+                return;
+            };
+            let entity = &mob.get_mob_entity().living_entity.entity;
+            if entity.block_pos.load() != nest.up() {
+                // Still on the way, or pushed away while digging
+                if mob.get_mob_entity().navigator.lock().await.is_idle() {
+                    self.nest = None;
+                }
+                return;
+            }
+            if self.dig_ticks == 0 {
+                turtle.set_laying_egg(true).await;
+            }
+            self.dig_ticks += 1;
+            if self.dig_ticks > DIG_TICKS {
+                Self::lay_eggs(&turtle, nest).await;
+                turtle.set_laying_egg(false).await;
+                self.dig_ticks = 0;
+                self.nest = None;
+            }
+        })
+    }
+
+    fn should_run_every_tick(&self) -> bool {
+        true
+    }
+
+    fn controls(&self) -> Controls {
+        Controls::MOVE | Controls::JUMP
+    }
+}
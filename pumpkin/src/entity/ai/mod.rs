@@ -1,4 +1,5 @@
 pub mod control;
 pub mod goal;
 pub mod path;
+pub mod provider;
 pub mod target_predicate;
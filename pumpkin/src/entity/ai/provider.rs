@@ -0,0 +1,234 @@
+//! Pluggable goal selection for mobs.
+//!
+//! Every mob gets the vanilla goals when it is created. Right after that, the [`MobAiProvider`]
+//! selected for it may replace or extend them, so plugins can give mobs their own
+//! behaviour without touching the mob implementations.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use pumpkin_config::ai::{AiCategory, AiConfig};
+use pumpkin_data::entity::{EntityType, MobCategory};
+
+use crate::entity::EntityBaseFuture;
+use crate::entity::mob::Mob;
+
+/// Sets up the goals of newly created mobs.
+pub trait MobAiProvider: Send + Sync {
+    /// The name of the provider, for logs and commands.
+    fn name(&self) -> &str;
+
+    /// Called once for every mob of the entity types this provider is selected for, after the
+    /// vanilla goals were added to its selectors. Use [`GoalSelector::clear`] to replace them.
+    ///
+    /// [`GoalSelector::clear`]: crate::entity::ai::goal::goal_selector::GoalSelector::clear
+    fn init_goals<'a>(&'a self, mob: &'a Arc<dyn Mob>) -> EntityBaseFuture<'a, ()>;
+}
+
+/// Keeps the goals the mobs are created with.
+pub struct VanillaAiProvider;
+
+impl MobAiProvider for VanillaAiProvider {
+    fn name(&self) -> &str {
+        "vanilla"
+    }
+
+    fn init_goals<'a>(&'a self, _mob: &'a Arc<dyn Mob>) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// Which [`MobAiProvider`] is used for which mob. Starts with the `[ai]` section of the config
+/// and can be changed at runtime, e.g. by `/aiprovider`. Changes apply to mobs created afterwards.
+pub struct AiProviderRegistry {
+    providers: RwLock<HashMap<String, Arc<dyn MobAiProvider>>>,
+    /// The selection from the config, restored by [`Self::reset`].
+    configured: AiConfig,
+    selection: RwLock<AiConfig>,
+    by_type: RwLock<HashMap<u16, Arc<dyn MobAiProvider>>>,
+}
+
+impl Default for AiProviderRegistry {
+    fn default() -> Self {
+        Self::new(AiConfig::default())
+    }
+}
+
+impl AiProviderRegistry {
+    #[must_use]
+    pub fn new(config: AiConfig) -> Self {
+        let vanilla: Arc<dyn MobAiProvider> = Arc::new(VanillaAiProvider);
+        Self {
+            providers: RwLock::new(HashMap::from([(vanilla.name().to_string(), vanilla)])),
+            selection: RwLock::new(config.clone()),
+            configured: config,
+            by_type: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Makes `provider` selectable by its name, replacing a provider with the same name.
+    pub fn add(&self, provider: Arc<dyn MobAiProvider>) {
+        self.providers
+            .write()
+            .unwrap()
+            .insert(provider.name().to_string(), provider);
+    }
+
+    /// The names of the selectable providers, sorted.
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.providers.read().unwrap().keys().cloned().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Sets the provider for the mobs that no other setting applies to.
+    pub fn set_default(&self, provider: Arc<dyn MobAiProvider>) {
+        let name = provider.name().to_string();
+        self.add(provider);
+        self.selection.write().unwrap().provider = name;
+    }
+
+    /// Selects the provider called `name` for `category` in `world`. Without a world the
+    /// global setting is changed, without a category the one of all other mobs. Returns
+    /// `false` if there is no such provider.
+    pub fn select(&self, name: &str, world: Option<&str>, category: Option<AiCategory>) -> bool {
+        if !self.providers.read().unwrap().contains_key(name) {
+            return false;
+        }
+        let name = name.to_string();
+        let mut selection = self.selection.write().unwrap();
+        match (world, category) {
+            (None, None) => selection.provider = name,
+            (None, Some(category)) => *selection.categories.get_mut(category) = Some(name),
+            (Some(world), None) => selection.world_mut(world).provider = Some(name),
+            (Some(world), Some(category)) => {
+                *selection.world_mut(world).categories.get_mut(category) = Some(name);
+            }
+        }
+        true
+    }
+
+    /// Restores the selection from the config. Entity types chosen by plugins are kept.
+    pub fn reset(&self) {
+        *self.selection.write().unwrap() = self.configured.clone();
+    }
+
+    /// The current selection, in the format of the config.
+    #[must_use]
+    pub fn selection(&self) -> AiConfig {
+        self.selection.read().unwrap().clone()
+    }
+
+    /// Uses `provider` for mobs of `entity_type` that are created from now on, whatever the
+    /// selection says.
+    pub fn register(&self, entity_type: &EntityType, provider: Arc<dyn MobAiProvider>) {
+        self.by_type
+            .write()
+            .unwrap()
+            .insert(entity_type.id, provider);
+    }
+
+    /// Makes `entity_type` follow the selection again. Returns the provider it used.
+    pub fn unregister(&self, entity_type: &EntityType) -> Option<Arc<dyn MobAiProvider>> {
+        self.by_type.write().unwrap().remove(&entity_type.id)
+    }
+
+    /// The provider for a mob of `entity_type` created in `world`.
+    #[must_use]
+    pub fn get(&self, entity_type: &EntityType, world: &str) -> Arc<dyn MobAiProvider> {
+        if let Some(provider) = self.by_type.read().unwrap().get(&entity_type.id) {
+            return provider.clone();
+        }
+        let selection = self.selection.read().unwrap();
+        let name = selection.provider_for(world, category_of(entity_type));
+        let providers = self.providers.read().unwrap();
+        providers
+            .get(name)
+            .or_else(|| providers.get(VanillaAiProvider.name()))
+            .cloned()
+            .unwrap_or_else(|| Arc::new(VanillaAiProvider))
+    }
+}
+
+/// The category providers can be selected for, if `entity_type` is in one.
+#[must_use]
+pub fn category_of(entity_type: &EntityType) -> Option<AiCategory> {
+    let category = entity_type.category;
+    if category == &MobCategory::MONSTER {
+        Some(AiCategory::Hostile)
+    } else if category == &MobCategory::CREATURE || category == &MobCategory::AMBIENT {
+        Some(AiCategory::Passive)
+    } else if category == &MobCategory::WATER_CREATURE
+        || category == &MobCategory::UNDERGROUND_WATER_CREATURE
+        || category == &MobCategory::WATER_AMBIENT
+        || category == &MobCategory::AXOLOTLS
+    {
+        Some(AiCategory::Water)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OVERWORLD: &str = "minecraft:overworld";
+    const NETHER: &str = "minecraft:the_nether";
+    const END: &str = "minecraft:the_end";
+
+    struct NamedProvider(&'static str);
+
+    impl MobAiProvider for NamedProvider {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn init_goals<'a>(&'a self, _mob: &'a Arc<dyn Mob>) -> EntityBaseFuture<'a, ()> {
+            Box::pin(async {})
+        }
+    }
+
+    #[test]
+    fn providers_are_selected_per_entity_type() {
+        let registry = AiProviderRegistry::default();
+        assert_eq!(
+            registry.get(&EntityType::ZOMBIE, OVERWORLD).name(),
+            "vanilla"
+        );
+
+        registry.register(&EntityType::ZOMBIE, Arc::new(NamedProvider("horde")));
+        registry.set_default(Arc::new(NamedProvider("calm")));
+        assert_eq!(registry.get(&EntityType::ZOMBIE, OVERWORLD).name(), "horde");
+        assert_eq!(registry.get(&EntityType::COW, OVERWORLD).name(), "calm");
+
+        assert!(registry.unregister(&EntityType::ZOMBIE).is_some());
+        assert_eq!(registry.get(&EntityType::ZOMBIE, OVERWORLD).name(), "calm");
+    }
+
+    #[test]
+    fn selection_prefers_the_most_specific_setting() {
+        let mut config = AiConfig::default();
+        config.categories.hostile = Some("horde".to_string());
+        config.world_mut("the_nether").provider = Some("calm".to_string());
+        config.world_mut("minecraft:the_end").categories.water = Some("missing".to_string());
+        let registry = AiProviderRegistry::new(config);
+        registry.add(Arc::new(NamedProvider("horde")));
+        registry.add(Arc::new(NamedProvider("calm")));
+
+        assert_eq!(registry.get(&EntityType::ZOMBIE, OVERWORLD).name(), "horde");
+        assert_eq!(registry.get(&EntityType::COW, OVERWORLD).name(), "vanilla");
+        assert_eq!(registry.get(&EntityType::ZOMBIE, NETHER).name(), "calm");
+        // Unknown providers fall back to vanilla
+        assert_eq!(registry.get(&EntityType::COD, END).name(), "vanilla");
+
+        assert!(registry.select("horde", Some(NETHER), Some(AiCategory::Hostile)));
+        assert!(!registry.select("missing", None, None));
+        assert_eq!(registry.get(&EntityType::ZOMBIE, NETHER).name(), "horde");
+        assert_eq!(registry.get(&EntityType::PIG, NETHER).name(), "calm");
+
+        registry.reset();
+        assert_eq!(registry.get(&EntityType::ZOMBIE, NETHER).name(), "calm");
+    }
+}
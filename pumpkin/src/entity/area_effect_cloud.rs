@@ -0,0 +1,505 @@
+//! Area effect clouds, left behind by lingering potions and the breath of the ender dragon. They
+//! give their effects to the living entities standing in them, shrinking as they do.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicI32};
+
+use crossbeam::atomic::AtomicCell;
+use pumpkin_data::data_component_impl::{DataComponentImpl, PotionContentsImpl};
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::particle::Particle;
+use pumpkin_data::potion::Effect;
+use pumpkin_data::tracked_data::TrackedData;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::player_data::{uuid_from_int_array, uuid_to_nbt};
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::math::boundingbox::{BoundingBox, EntityDimensions};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::entity::effect::{apply_effect, potion_color, potion_contents_from_nbt, potion_effects};
+use crate::entity::living::LivingEntity;
+use crate::entity::{Entity, EntityBase, EntityBaseFuture, NBTStorage, NbtFuture};
+use crate::server::Server;
+use crate::world::particles::{ParticleData, ParticleEffect};
+
+pub const DEFAULT_RADIUS: f32 = 3.0;
+/// Clouds that shrink below this radius disappear.
+pub const MIN_RADIUS: f32 = 0.5;
+pub const MAX_RADIUS: f32 = 32.0;
+const HEIGHT: f32 = 0.5;
+/// How often, in ticks, the cloud looks for entities to affect.
+const APPLY_INTERVAL: i32 = 5;
+/// Instant effects are only half as strong from a cloud.
+const INSTANT_EFFECT_STRENGTH: f64 = 0.5;
+
+pub struct AreaEffectCloudEntity {
+    entity: Entity,
+    radius: AtomicCell<f32>,
+    /// Added to the radius each time an entity is affected.
+    pub radius_on_use: AtomicCell<f32>,
+    /// Added to the radius each tick.
+    pub radius_per_tick: AtomicCell<f32>,
+    /// How many ticks the cloud lasts after waiting, -1 for ever.
+    pub duration: AtomicI32,
+    /// Added to the duration each time an entity is affected.
+    pub duration_on_use: AtomicI32,
+    /// How many ticks the cloud waits before it does anything.
+    pub wait_time: AtomicI32,
+    /// How many ticks an affected entity is left alone before it is affected again.
+    pub reapplication_delay: AtomicI32,
+    pub owner: AtomicCell<Option<Uuid>>,
+    cloud_age: AtomicI32,
+    waiting: AtomicBool,
+    potion: Mutex<PotionContentsImpl>,
+    /// Scales the durations of the effects, lingering potions last a quarter as long.
+    potion_duration_scale: AtomicCell<f32>,
+    custom_particle: Mutex<Option<ParticleEffect>>,
+    /// The entities affected lately, with the age of the cloud from which on they can be again.
+    victims: Mutex<HashMap<i32, i32>>,
+}
+
+impl AreaEffectCloudEntity {
+    pub fn new(entity: Entity) -> Self {
+        let cloud = Self {
+            entity,
+            radius: AtomicCell::new(DEFAULT_RADIUS),
+            radius_on_use: AtomicCell::new(0.0),
+            radius_per_tick: AtomicCell::new(0.0),
+            duration: AtomicI32::new(-1),
+            duration_on_use: AtomicI32::new(0),
+            wait_time: AtomicI32::new(20),
+            reapplication_delay: AtomicI32::new(20),
+            owner: AtomicCell::new(None),
+            cloud_age: AtomicI32::new(0),
+            waiting: AtomicBool::new(true),
+            potion: Mutex::new(PotionContentsImpl {
+                potion_id: None,
+                custom_color: None,
+                custom_effects: Vec::new(),
+                custom_name: None,
+            }),
+            potion_duration_scale: AtomicCell::new(1.0),
+            custom_particle: Mutex::new(None),
+            victims: Mutex::new(HashMap::new()),
+        };
+        cloud.store_radius(DEFAULT_RADIUS);
+        cloud
+    }
+
+    #[must_use]
+    pub fn radius(&self) -> f32 {
+        self.radius.load()
+    }
+
+    /// Resizes the cloud, at most to [`MAX_RADIUS`].
+    pub async fn set_radius(&self, radius: f32) {
+        self.store_radius(radius);
+        self.entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_RADIUS,
+                MetaDataType::Float,
+                self.radius.load(),
+            )])
+            .await;
+    }
+
+    fn store_radius(&self, radius: f32) {
+        let radius = radius.clamp(0.0, MAX_RADIUS);
+        self.radius.store(radius);
+        let dimensions = EntityDimensions::new(radius * 2.0, HEIGHT, HEIGHT * 0.85);
+        let pos = self.entity.pos.load();
+        self.entity.entity_dimension.store(dimensions);
+        self.entity
+            .bounding_box
+            .store(BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &dimensions));
+    }
+
+    /// Sets the effects the cloud gives and their color, their durations scaled by
+    /// `duration_scale`.
+    pub async fn set_potion(&self, contents: PotionContentsImpl, duration_scale: f32) {
+        *self.potion.lock().await = contents;
+        self.potion_duration_scale.store(duration_scale);
+        self.send_particle().await;
+    }
+
+    /// Like [`Self::set_potion`], for clouds that are not spawned yet.
+    #[must_use]
+    pub fn with_potion(mut self, contents: PotionContentsImpl, duration_scale: f32) -> Self {
+        *self.potion.get_mut() = contents;
+        self.potion_duration_scale.store(duration_scale);
+        self
+    }
+
+    /// Sets the particle the cloud is made of. Without one, it swirls in the color of its
+    /// effects.
+    pub async fn set_particle(&self, particle: Option<ParticleEffect>) {
+        *self.custom_particle.lock().await = particle;
+        self.send_particle().await;
+    }
+
+    /// Like [`Self::set_particle`], for clouds that are not spawned yet.
+    #[must_use]
+    pub fn with_particle(mut self, particle: ParticleEffect) -> Self {
+        *self.custom_particle.get_mut() = Some(particle);
+        self
+    }
+
+    /// The particle the cloud is made of.
+    pub async fn particle(&self) -> ParticleEffect {
+        let custom_particle = self.custom_particle.lock().await.clone();
+        if let Some(particle) = custom_particle {
+            return particle;
+        }
+        let potion = self.potion.lock().await;
+        let empty = potion.potion_id.is_none()
+            && potion.custom_color.is_none()
+            && potion.custom_effects.is_empty();
+        ParticleEffect::entity_effect(if empty { 0 } else { potion_color(&potion) })
+    }
+
+    async fn send_particle(&self) {
+        self.entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_PARTICLE,
+                MetaDataType::Particle,
+                self.particle().await.metadata(),
+            )])
+            .await;
+    }
+
+    /// The effects the cloud gives, with their durations scaled.
+    async fn effects(&self) -> Vec<Effect> {
+        let scale = self.potion_duration_scale.load();
+        let mut effects = potion_effects(&*self.potion.lock().await);
+        for effect in &mut effects {
+            if effect.duration != -1 {
+                effect.duration = ((effect.duration as f32 * scale).floor() as i32).max(1);
+            }
+        }
+        effects
+    }
+
+    /// Gives the effects to the living entities within the radius that were not affected
+    /// lately. The cloud changes in size and duration with every entity it affects, and may
+    /// disappear. Returns whether it is still there.
+    async fn apply_effects(&self, age: i32) -> bool {
+        let mut victims = self.victims.lock().await;
+        victims.retain(|_, until| age < *until);
+        let effects = self.effects().await;
+        if effects.is_empty() {
+            victims.clear();
+            return true;
+        }
+
+        let world = self.entity.world.load_full();
+        let bounding_box = self.entity.bounding_box.load();
+        let mut targets = world.get_entities_at_box(&bounding_box);
+        targets.extend(
+            world
+                .get_players_at_box(&bounding_box)
+                .into_iter()
+                .map(|player| player as Arc<dyn EntityBase>),
+        );
+        let owner = self
+            .owner
+            .load()
+            .and_then(|uuid| world.get_entity_by_uuid(uuid));
+        let center = self.entity.pos.load();
+
+        for target in targets {
+            let Some(living) = target.get_living_entity() else {
+                continue;
+            };
+            let entity = &living.entity;
+            if living.dead.load(Relaxed)
+                || target.is_spectator()
+                || entity.entity_type == &EntityType::ARMOR_STAND
+                || victims.contains_key(&entity.entity_id)
+            {
+                continue;
+            }
+            let pos = entity.pos.load();
+            let (dx, dz) = (pos.x - center.x, pos.z - center.z);
+            let radius = f64::from(self.radius.load());
+            if dx * dx + dz * dz > radius * radius {
+                continue;
+            }
+
+            victims.insert(
+                entity.entity_id,
+                age + self.reapplication_delay.load(Relaxed),
+            );
+            for effect in &effects {
+                apply_effect(
+                    target.as_ref(),
+                    effect.clone(),
+                    INSTANT_EFFECT_STRENGTH,
+                    Some(self as &dyn EntityBase),
+                    owner.as_deref(),
+                )
+                .await;
+            }
+
+            let radius_on_use = self.radius_on_use.load();
+            if radius_on_use != 0.0 {
+                let radius = self.radius.load() + radius_on_use;
+                if radius < MIN_RADIUS {
+                    return false;
+                }
+                self.set_radius(radius).await;
+            }
+            let duration_on_use = self.duration_on_use.load(Relaxed);
+            let duration = self.duration.load(Relaxed);
+            if duration_on_use != 0 && duration != -1 {
+                let duration = duration + duration_on_use;
+                if duration <= 0 {
+                    return false;
+                }
+                self.duration.store(duration, Relaxed);
+            }
+        }
+        true
+    }
+}
+
+fn particle_to_nbt(particle: &ParticleEffect) -> NbtCompound {
+    let mut nbt = NbtCompound::new();
+    nbt.put_string(
+        "type",
+        format!("minecraft:{}", particle.particle().to_name()),
+    );
+    match particle.data() {
+        ParticleData::Color(color) => nbt.put_int("color", *color),
+        ParticleData::Power(power) => nbt.put_float("power", *power),
+        _ => {}
+    }
+    nbt
+}
+
+fn particle_from_nbt(nbt: &NbtCompound) -> Option<ParticleEffect> {
+    let name = nbt.get_string("type")?;
+    let particle = Particle::from_name(name.strip_prefix("minecraft:").unwrap_or(name))?;
+    let data = if particle == Particle::DragonBreath {
+        ParticleData::Power(nbt.get_float("power").unwrap_or(1.0))
+    } else if let Some(color) = nbt.get_int("color") {
+        ParticleData::Color(color)
+    } else {
+        ParticleData::None
+    };
+    ParticleEffect::with_data(particle, data)
+}
+
+impl NBTStorage for AreaEffectCloudEntity {
+    fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.entity.write_nbt(nbt).await;
+            nbt.put_int("Age", self.cloud_age.load(Relaxed));
+            nbt.put_int("Duration", self.duration.load(Relaxed));
+            nbt.put_int("WaitTime", self.wait_time.load(Relaxed));
+            nbt.put_int("ReapplicationDelay", self.reapplication_delay.load(Relaxed));
+            nbt.put_int("DurationOnUse", self.duration_on_use.load(Relaxed));
+            nbt.put_float("RadiusOnUse", self.radius_on_use.load());
+            nbt.put_float("RadiusPerTick", self.radius_per_tick.load());
+            nbt.put_float("Radius", self.radius.load());
+            if let Some(owner) = self.owner.load() {
+                nbt.put("Owner", uuid_to_nbt(owner.as_u128()));
+            }
+            let custom_particle = self.custom_particle.lock().await.clone();
+            if let Some(particle) = custom_particle {
+                nbt.put_component("custom_particle", particle_to_nbt(&particle));
+            }
+            nbt.put("potion_contents", self.potion.lock().await.write_data());
+            nbt.put_float("potion_duration_scale", self.potion_duration_scale.load());
+        })
+    }
+
+    fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.entity.read_nbt_non_mut(nbt).await;
+            self.cloud_age
+                .store(nbt.get_int("Age").unwrap_or(0), Relaxed);
+            self.duration
+                .store(nbt.get_int("Duration").unwrap_or(-1), Relaxed);
+            self.wait_time
+                .store(nbt.get_int("WaitTime").unwrap_or(20), Relaxed);
+            self.reapplication_delay
+                .store(nbt.get_int("ReapplicationDelay").unwrap_or(20), Relaxed);
+            self.duration_on_use
+                .store(nbt.get_int("DurationOnUse").unwrap_or(0), Relaxed);
+            self.radius_on_use
+                .store(nbt.get_float("RadiusOnUse").unwrap_or(0.0));
+            self.radius_per_tick
+                .store(nbt.get_float("RadiusPerTick").unwrap_or(0.0));
+            self.store_radius(nbt.get_float("Radius").unwrap_or(DEFAULT_RADIUS));
+            self.owner.store(
+                nbt.get_int_array("Owner")
+                    .and_then(uuid_from_int_array)
+                    .map(Uuid::from_u128),
+            );
+            *self.custom_particle.lock().await = nbt
+                .get_compound("custom_particle")
+                .and_then(particle_from_nbt);
+            if let Some(contents) = nbt.get_compound("potion_contents") {
+                *self.potion.lock().await = potion_contents_from_nbt(contents);
+            }
+            self.potion_duration_scale
+                .store(nbt.get_float("potion_duration_scale").unwrap_or(1.0));
+        })
+    }
+}
+
+impl EntityBase for AreaEffectCloudEntity {
+    fn tick<'a>(
+        &'a self,
+        caller: Arc<dyn EntityBase>,
+        server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            self.entity.tick(caller, server).await;
+            let age = self.cloud_age.fetch_add(1, Relaxed) + 1;
+            let wait_time = self.wait_time.load(Relaxed);
+            let duration = self.duration.load(Relaxed);
+            if duration != -1 && age - wait_time >= duration {
+                self.entity.remove().await;
+                return;
+            }
+
+            let waiting = age < wait_time;
+            if self.waiting.swap(waiting, Relaxed) != waiting {
+                self.entity
+                    .send_meta_data(&[Metadata::new(
+                        TrackedData::DATA_WAITING,
+                        MetaDataType::Boolean,
+                        waiting,
+                    )])
+                    .await;
+            }
+            if waiting {
+                return;
+            }
+
+            let radius_per_tick = self.radius_per_tick.load();
+            if radius_per_tick != 0.0 {
+                let radius = self.radius.load() + radius_per_tick;
+                if radius < MIN_RADIUS {
+                    self.entity.remove().await;
+                    return;
+                }
+                self.set_radius(radius).await;
+            }
+
+            if age % APPLY_INTERVAL == 0 && !self.apply_effects(age).await {
+                self.entity.remove().await;
+            }
+        })
+    }
+
+    fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            self.entity
+                .send_meta_data(&[Metadata::new(
+                    TrackedData::DATA_RADIUS,
+                    MetaDataType::Float,
+                    self.radius.load(),
+                )])
+                .await;
+            self.entity
+                .send_meta_data(&[Metadata::new(
+                    TrackedData::DATA_WAITING,
+                    MetaDataType::Boolean,
+                    self.waiting.load(Relaxed),
+                )])
+                .await;
+            self.send_particle().await;
+        })
+    }
+
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&LivingEntity> {
+        None
+    }
+
+    fn get_area_effect_cloud(&self) -> Option<&AreaEffectCloudEntity> {
+        Some(self)
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_data::potion::Potion;
+    use pumpkin_util::math::vector3::Vector3;
+
+    use super::*;
+    use crate::world::World;
+
+    fn cloud(world: &Arc<World>) -> AreaEffectCloudEntity {
+        AreaEffectCloudEntity::new(Entity::new(
+            world.clone(),
+            Vector3::new(0.0, 64.0, 0.0),
+            &EntityType::AREA_EFFECT_CLOUD,
+        ))
+    }
+
+    #[test]
+    fn particles_survive_a_save_and_load() {
+        let breath =
+            particle_from_nbt(&particle_to_nbt(&ParticleEffect::dragon_breath(0.5))).unwrap();
+        assert_eq!(breath.particle(), Particle::DragonBreath);
+        assert!(matches!(breath.data(), ParticleData::Power(power) if *power == 0.5));
+
+        let swirl =
+            particle_from_nbt(&particle_to_nbt(&ParticleEffect::entity_effect(-1))).unwrap();
+        assert_eq!(swirl.particle(), Particle::EntityEffect);
+        assert!(matches!(swirl.data(), ParticleData::Color(-1)));
+    }
+
+    #[test]
+    fn unknown_particles_are_not_loaded() {
+        let mut nbt = NbtCompound::new();
+        nbt.put_string("type", "minecraft:not_a_particle".to_string());
+        assert!(particle_from_nbt(&nbt).is_none());
+        // Dust needs a color and scale, which clouds don't save
+        nbt.put_string("type", "minecraft:dust".to_string());
+        assert!(particle_from_nbt(&nbt).is_none());
+    }
+
+    #[tokio::test]
+    async fn lingering_effects_are_shortened() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let contents = PotionContentsImpl {
+            potion_id: Some(i32::from(Potion::SWIFTNESS.id)),
+            custom_color: None,
+            custom_effects: Vec::new(),
+            custom_name: None,
+        };
+        let cloud = cloud(&world).with_potion(contents, 0.25);
+        let effects = cloud.effects().await;
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].duration, 900);
+    }
+
+    #[tokio::test]
+    async fn radius_is_clamped() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let cloud = cloud(&world);
+        assert_eq!(cloud.radius(), DEFAULT_RADIUS);
+        cloud.set_radius(100.0).await;
+        assert_eq!(cloud.radius(), MAX_RADIUS);
+        assert_eq!(cloud.entity.entity_dimension.load().width, MAX_RADIUS * 2.0);
+        cloud.set_radius(-1.0).await;
+        assert_eq!(cloud.radius(), 0.0);
+    }
+}
@@ -0,0 +1,848 @@
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicI32};
+use std::sync::{Arc, Weak};
+
+use crossbeam::atomic::AtomicCell;
+use pumpkin_data::damage::DamageType;
+use pumpkin_data::data_component_impl::{PotionContentsImpl, StatusEffectInstance};
+use pumpkin_data::effect::StatusEffect;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_data::tracked_data::TrackedData;
+use pumpkin_data::world::WorldEvent;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_util::math::wrap_degrees;
+use rand::Rng;
+
+use crate::entity::area_effect_cloud::AreaEffectCloudEntity;
+use crate::entity::experience_orb::ExperienceOrbEntity;
+use crate::entity::mob::{Mob, MobEntity};
+use crate::entity::player::Player;
+use crate::entity::projectile::dragon_fireball::DragonFireballEntity;
+use crate::entity::{Entity, EntityBase, EntityBaseFuture, NbtFuture, reserve_entity_ids};
+use crate::world::World;
+use crate::world::particles::ParticleEffect;
+
+/// The parts clients split the dragon into, numbered right after it. Players hit these parts
+/// instead of the dragon itself.
+pub const PART_COUNT: i32 = 8;
+/// How long the dragon takes to die, in ticks.
+const DEATH_TICKS: i32 = 200;
+/// The experience dropped by the first dragon of a world, later ones drop less.
+const FIRST_KILL_EXPERIENCE: f32 = 12000.0;
+const EXPERIENCE: f32 = 500.0;
+/// The radius of the circle the dragon flies around the main island.
+const HOLDING_RADIUS: f64 = 60.0;
+/// How long the dragon breathes flames while sitting on the portal, in ticks.
+const FLAME_TICKS: i32 = 200;
+/// How many times the dragon breathes flames before it takes off again.
+const MAX_FLAMES: i32 = 4;
+/// Damage types that hurt the dragon even when no player dealt them.
+const ALWAYS_HURTS: [DamageType; 4] = [
+    DamageType::FIREWORKS,
+    DamageType::EXPLOSION,
+    DamageType::PLAYER_EXPLOSION,
+    DamageType::BAD_RESPAWN_POINT,
+];
+
+/// What the dragon is doing, with the ids clients know the phases by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DragonPhase {
+    HoldingPattern = 0,
+    Strafe = 1,
+    LandingApproach = 2,
+    Landing = 3,
+    Takeoff = 4,
+    SittingFlaming = 5,
+    SittingScanning = 6,
+    SittingAttacking = 7,
+    Charging = 8,
+    Dying = 9,
+    Hover = 10,
+}
+
+impl DragonPhase {
+    const ALL: [Self; 11] = [
+        Self::HoldingPattern,
+        Self::Strafe,
+        Self::LandingApproach,
+        Self::Landing,
+        Self::Takeoff,
+        Self::SittingFlaming,
+        Self::SittingScanning,
+        Self::SittingAttacking,
+        Self::Charging,
+        Self::Dying,
+        Self::Hover,
+    ];
+
+    #[must_use]
+    pub fn from_id(id: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|phase| *phase as i32 == id)
+    }
+
+    /// Whether the dragon sits on the exit portal, where arrows don't hurt it.
+    #[must_use]
+    pub const fn is_sitting(self) -> bool {
+        matches!(
+            self,
+            Self::SittingFlaming | Self::SittingScanning | Self::SittingAttacking
+        )
+    }
+
+    const fn fly_speed(self) -> f64 {
+        match self {
+            Self::Landing => 1.5,
+            Self::Charging => 3.0,
+            _ => 0.6,
+        }
+    }
+
+    /// The phase after breathing flames on the portal `flames` times.
+    const fn after_flames(flames: i32) -> Self {
+        if flames >= MAX_FLAMES {
+            Self::Takeoff
+        } else {
+            Self::SittingScanning
+        }
+    }
+}
+
+/// Whether the dragon took enough damage while sitting to take off again.
+const fn sitting_damage_exceeded(damage: f32, max_health: f32) -> bool {
+    damage > max_health * 0.25
+}
+
+/// The ender dragon. It circles the main island of the End, healed by the end crystals on the
+/// pillars, shoots fireballs at players and lands on the exit portal every now and then to
+/// breathe flames. The [`DragonFight`](crate::world::dragon_fight::DragonFight) of the End
+/// spawns it and opens the portal once it died.
+pub struct EnderDragonEntity {
+    pub mob_entity: MobEntity,
+    this: Weak<Self>,
+    phase: AtomicCell<DragonPhase>,
+    /// Ticks since the current phase started.
+    phase_ticks: AtomicI32,
+    /// Where the dragon flies to, if anywhere.
+    fly_target: AtomicCell<Option<Vector3<f64>>>,
+    /// The player shot at or charged, or 0.
+    attack_target: AtomicI32,
+    /// Fireball charge while strafing, flames breathed while sitting.
+    phase_counter: AtomicI32,
+    /// How fast the dragon turns, in degrees per tick.
+    turn_velocity: AtomicCell<f32>,
+    /// The dragon's own momentum, since it flies unlike other mobs.
+    flight_velocity: AtomicCell<Vector3<f64>>,
+    /// Whether the dragon circles clockwise.
+    clockwise: AtomicBool,
+    /// The end crystal healing the dragon, or 0.
+    nearest_crystal: AtomicI32,
+    /// Damage taken while sitting. The dragon takes off when it is too much.
+    sitting_damage: AtomicCell<f32>,
+    last_health: AtomicCell<f32>,
+    death_ticks: AtomicI32,
+}
+
+impl EnderDragonEntity {
+    pub fn new(mut entity: Entity) -> Arc<Self> {
+        // Clients number the parts after the dragon, no other entity may use their ids
+        entity.entity_id = reserve_entity_ids(PART_COUNT + 1);
+        entity.no_clip.store(true, Relaxed);
+        let max_health = entity.entity_type.max_health.unwrap_or(200.0);
+        let mob_entity = MobEntity::new(entity);
+        Arc::new_cyclic(|this| Self {
+            mob_entity,
+            this: this.clone(),
+            phase: AtomicCell::new(DragonPhase::HoldingPattern),
+            phase_ticks: AtomicI32::new(0),
+            fly_target: AtomicCell::new(None),
+            attack_target: AtomicI32::new(0),
+            phase_counter: AtomicI32::new(0),
+            turn_velocity: AtomicCell::new(0.0),
+            flight_velocity: AtomicCell::new(Vector3::default()),
+            clockwise: AtomicBool::new(rand::random()),
+            nearest_crystal: AtomicI32::new(0),
+            sitting_damage: AtomicCell::new(0.0),
+            last_health: AtomicCell::new(max_health),
+            death_ticks: AtomicI32::new(0),
+        })
+    }
+
+    #[must_use]
+    pub fn phase(&self) -> DragonPhase {
+        self.phase.load()
+    }
+
+    pub async fn set_phase(&self, phase: DragonPhase) {
+        if self.phase.swap(phase) == phase {
+            return;
+        }
+        self.phase_ticks.store(0, Relaxed);
+        self.fly_target.store(None);
+        self.sitting_damage.store(0.0);
+        if phase != DragonPhase::SittingFlaming {
+            self.phase_counter.store(0, Relaxed);
+        }
+        let entity = &self.mob_entity.living_entity.entity;
+        entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_PHASE_TYPE,
+                MetaDataType::Integer,
+                phase as i32,
+            )])
+            .await;
+        if phase == DragonPhase::SittingAttacking {
+            entity
+                .world
+                .load()
+                .play_sound(
+                    Sound::EntityEnderDragonGrowl,
+                    SoundCategory::Hostile,
+                    &entity.pos.load(),
+                )
+                .await;
+        }
+    }
+
+    /// Makes the dragon charge at `target`, for example after a player broke a crystal.
+    pub async fn charge(&self, target: Vector3<f64>) {
+        self.set_phase(DragonPhase::Charging).await;
+        self.fly_target.store(Some(target));
+    }
+
+    /// Called when an end crystal of the fight was destroyed, by `cause` if a player did it.
+    /// The dragon is hurt if the crystal was healing it, and goes after the player.
+    pub async fn on_crystal_destroyed(&self, crystal: &Entity, cause: Option<&dyn EntityBase>) {
+        if self
+            .nearest_crystal
+            .compare_exchange(crystal.entity_id, 0, Relaxed, Relaxed)
+            .is_ok()
+        {
+            self.damage_with_context(self, 10.0, DamageType::EXPLOSION, None, None, cause)
+                .await;
+        }
+        if let Some(player) = cause.and_then(|cause| cause.get_player())
+            && self.phase() == DragonPhase::HoldingPattern
+            && is_valid_target(player)
+        {
+            self.strafe(player.get_entity().entity_id).await;
+        }
+    }
+
+    async fn strafe(&self, player_id: i32) {
+        self.set_phase(DragonPhase::Strafe).await;
+        self.attack_target.store(player_id, Relaxed);
+    }
+
+    /// The top of the exit portal, where the dragon lands.
+    async fn portal_top(world: &World) -> Vector3<f64> {
+        let y = world.get_motion_blocking_height(0, 0).await;
+        Vector3::new(0.5, f64::from(y), 0.5)
+    }
+
+    fn crystals_alive(world: &World) -> i32 {
+        world
+            .dragon_fight
+            .as_ref()
+            .map_or(0, |fight| fight.crystals_alive())
+    }
+
+    /// Where the dragon looks, as it flies backwards compared to other mobs.
+    fn facing(&self) -> Vector3<f64> {
+        let yaw = f64::from(self.mob_entity.living_entity.entity.yaw.load()).to_radians();
+        Vector3::new(yaw.sin(), 0.0, -yaw.cos())
+    }
+
+    fn head_position(&self) -> Vector3<f64> {
+        let pos = self.mob_entity.living_entity.entity.pos.load();
+        let facing = self.facing();
+        Vector3::new(
+            facing.x.mul_add(6.5, pos.x),
+            pos.y + 2.5,
+            facing.z.mul_add(6.5, pos.z),
+        )
+    }
+
+    /// Flies the dragon towards its target, turning like vanilla's dragon does.
+    fn tick_flight(&self, phase: DragonPhase) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let Some(target) = self.fly_target.load() else {
+            self.flight_velocity.store(Vector3::default());
+            entity.velocity.store(Vector3::default());
+            return;
+        };
+        let pos = entity.pos.load();
+        let delta = target.sub(&pos);
+        let horizontal = delta.horizontal_length();
+        let fly_speed = phase.fly_speed();
+        let climb = if horizontal > 0.0 {
+            (delta.y / horizontal).clamp(-fly_speed, fly_speed)
+        } else {
+            delta.y
+        };
+        let mut velocity = self.flight_velocity.load();
+        velocity.y += climb * 0.01;
+
+        let yaw = wrap_degrees(entity.yaw.load());
+        let mut speed = velocity.horizontal_length() as f32 + 1.0;
+        let turn_speed = if phase == DragonPhase::Landing {
+            speed.min(40.0) / speed
+        } else {
+            0.7 / speed.min(40.0) / speed
+        };
+        let mut yaw_velocity = self.turn_velocity.load() * 0.8;
+        if delta.x.abs() > 1.0e-5 || delta.z.abs() > 1.0e-5 {
+            let wanted = 180.0 - delta.x.atan2(delta.z).to_degrees() as f32;
+            yaw_velocity += wrap_degrees(wanted - yaw).clamp(-50.0, 50.0) * turn_speed;
+        }
+        self.turn_velocity.store(yaw_velocity);
+        let yaw = yaw_velocity.mul_add(0.1, yaw);
+        entity.yaw.store(yaw);
+        entity.body_yaw.store(yaw);
+
+        let facing = self.facing();
+        let look = normalize(Vector3::new(facing.x, velocity.y, facing.z));
+        let direction = normalize(delta);
+        let alignment =
+            ((look.x * direction.x + look.y * direction.y + look.z * direction.z) as f32 + 0.5)
+                / 1.5;
+        let near = (2.0 / (delta.length_squared() + 1.0)) as f32;
+        speed = 0.06 * (alignment.max(0.0) * near + (1.0 - near));
+        velocity.x += facing.x * f64::from(speed);
+        velocity.z += facing.z * f64::from(speed);
+        entity.velocity.store(velocity);
+
+        let moving = normalize(velocity);
+        let drag = 0.15f64.mul_add(
+            (moving.x * look.x + moving.y * look.y + moving.z * look.z + 1.0) / 2.0,
+            0.8,
+        );
+        self.flight_velocity
+            .store(velocity.multiply(drag, 0.91, drag));
+    }
+
+    /// Picks the next point of the circle around the island, or decides to land or attack.
+    async fn tick_holding_pattern(&self, world: &World) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let pos = entity.pos.load();
+        if self
+            .fly_target
+            .load()
+            .is_some_and(|target| target.squared_distance_to_vec(&pos) >= 100.0)
+        {
+            return;
+        }
+        if self.fly_target.load().is_some() {
+            let crystals = Self::crystals_alive(world);
+            if rand::rng().random_range(0..crystals + 3) == 0 {
+                self.set_phase(DragonPhase::LandingApproach).await;
+                return;
+            }
+            let portal = Self::portal_top(world).await;
+            if let Some(player) = world.get_closest_player(portal, 128.0)
+                && is_valid_target(&player)
+                && rand::rng().random_range(0..crystals + 2) == 0
+            {
+                self.strafe(player.get_entity().entity_id).await;
+                return;
+            }
+        }
+
+        let step = std::f64::consts::PI / 6.0;
+        let angle = pos.z.atan2(pos.x)
+            + if self.clockwise.load(Relaxed) {
+                -step
+            } else {
+                step
+            };
+        let x = HOLDING_RADIUS * angle.cos();
+        let z = HOLDING_RADIUS * angle.sin();
+        let ground = world
+            .get_motion_blocking_height(x.floor() as i32, z.floor() as i32)
+            .await;
+        let y = f64::from(ground.max(world.sea_level + 10) + 10) + rand::random::<f64>() * 20.0;
+        self.fly_target.store(Some(Vector3::new(x, y, z)));
+    }
+
+    /// Flies above the strafed player and shoots a fireball once it faces them.
+    async fn tick_strafe(&self, world: &Arc<World>) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let target_id = self.attack_target.load(Relaxed);
+        let Some(target) = world
+            .get_player_by_id(target_id)
+            .filter(|player| is_valid_target(player))
+        else {
+            self.set_phase(DragonPhase::HoldingPattern).await;
+            return;
+        };
+        let pos = entity.pos.load();
+        let target_pos = target.get_entity().pos.load();
+        let horizontal = Vector3::new(target_pos.x - pos.x, 0.0, target_pos.z - pos.z);
+        let above = (0.4 + horizontal.length() / 80.0 - 1.0).min(10.0);
+        self.fly_target.store(Some(Vector3::new(
+            target_pos.x,
+            target_pos.y + above,
+            target_pos.z,
+        )));
+        if self.phase_ticks.load(Relaxed) > 400 {
+            self.set_phase(DragonPhase::HoldingPattern).await;
+            return;
+        }
+
+        if target_pos.squared_distance_to_vec(&pos) >= 64.0 * 64.0 {
+            Self::decrement(&self.phase_counter);
+            return;
+        }
+        let charge = self.phase_counter.fetch_add(1, Relaxed) + 1;
+        let direction = normalize(horizontal);
+        let facing = self.facing();
+        let angle = (facing.x * direction.x + facing.z * direction.z)
+            .clamp(-1.0, 1.0)
+            .acos()
+            .to_degrees()
+            + 0.5;
+        if charge >= 5 && angle < 10.0 {
+            let head = self.head_position();
+            world
+                .sync_world_event(WorldEvent::EnderDragonShoots, entity.block_pos.load(), 0)
+                .await;
+            let fireball_entity = Entity::new(world.clone(), head, &EntityType::DRAGON_FIREBALL);
+            let eye_y = target.get_entity().get_eye_y();
+            let aim = Vector3::new(target_pos.x, eye_y, target_pos.z).sub(&head);
+            let fireball = DragonFireballEntity::new_shot(fireball_entity, entity, aim);
+            world.spawn_entity(Arc::new(fireball)).await;
+            self.set_phase(DragonPhase::HoldingPattern).await;
+        }
+    }
+
+    fn decrement(counter: &AtomicI32) {
+        let _ = counter.fetch_update(Relaxed, Relaxed, |value| (value > 0).then(|| value - 1));
+    }
+
+    async fn tick_landing(&self, world: &World, phase: DragonPhase) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let pos = entity.pos.load();
+        let portal = Self::portal_top(world).await;
+        match phase {
+            DragonPhase::LandingApproach => {
+                let above = portal.add_raw(0.0, 20.0, 0.0);
+                self.fly_target.store(Some(above));
+                if above.squared_distance_to_vec(&pos) < 100.0 {
+                    self.set_phase(DragonPhase::Landing).await;
+                }
+            }
+            DragonPhase::Landing => {
+                self.fly_target.store(Some(portal));
+                if portal.squared_distance_to_vec(&pos) < 1.0 {
+                    entity.set_pos(portal);
+                    self.set_phase(DragonPhase::SittingScanning).await;
+                }
+            }
+            _ => {
+                // Takeoff, away from the portal in the direction the dragon faces
+                if self.fly_target.load().is_none() {
+                    let facing = self.facing();
+                    self.fly_target.store(Some(Vector3::new(
+                        facing.x * 40.0,
+                        portal.y + 20.0,
+                        facing.z * 40.0,
+                    )));
+                }
+                if portal.squared_distance_to_vec(&pos) > 100.0 {
+                    self.set_phase(DragonPhase::HoldingPattern).await;
+                }
+            }
+        }
+    }
+
+    /// Looks for players around the portal while sitting on it, roars at them and breathes
+    /// flames, then takes off again.
+    async fn tick_sitting(&self, world: &Arc<World>, phase: DragonPhase) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let ticks = self.phase_ticks.load(Relaxed);
+        match phase {
+            DragonPhase::SittingScanning => {
+                let nearby = world
+                    .get_closest_player(entity.pos.load(), 20.0)
+                    .filter(|player| is_valid_target(player));
+                if let Some(player) = nearby {
+                    if ticks > 25 {
+                        self.set_phase(DragonPhase::SittingAttacking).await;
+                    } else {
+                        self.look_towards(player.get_entity().pos.load());
+                    }
+                } else if ticks >= 100 {
+                    let target = world
+                        .get_closest_player(entity.pos.load(), 150.0)
+                        .filter(|player| is_valid_target(player));
+                    match target {
+                        Some(player) => self.charge(player.get_entity().pos.load()).await,
+                        None => self.set_phase(DragonPhase::Takeoff).await,
+                    }
+                }
+            }
+            DragonPhase::SittingAttacking => {
+                if ticks >= 40 {
+                    self.set_phase(DragonPhase::SittingFlaming).await;
+                }
+            }
+            _ => {
+                if ticks == 0 {
+                    self.phase_counter.fetch_add(1, Relaxed);
+                } else if ticks == 10 {
+                    self.breathe_flames(world).await;
+                } else if ticks >= FLAME_TICKS {
+                    let flames = self.phase_counter.load(Relaxed);
+                    self.set_phase(DragonPhase::after_flames(flames)).await;
+                }
+            }
+        }
+    }
+
+    fn look_towards(&self, target: Vector3<f64>) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let head = self.head_position();
+        let wanted = 180.0 - (target.x - head.x).atan2(target.z - head.z).to_degrees() as f32;
+        let yaw = entity.yaw.load();
+        let yaw = yaw + wrap_degrees(wanted - yaw).clamp(-10.0, 10.0);
+        entity.yaw.store(yaw);
+        entity.body_yaw.store(yaw);
+    }
+
+    /// Leaves a cloud of dragon's breath on the ground in front of the head.
+    async fn breathe_flames(&self, world: &Arc<World>) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let head = self.head_position();
+        let facing = self.facing();
+        let x = facing.x.mul_add(2.5, head.x);
+        let z = facing.z.mul_add(2.5, head.z);
+        let mut y = head.y;
+        while y > f64::from(world.min_y)
+            && world
+                .get_block_state(&BlockPos::floored(x, y, z))
+                .await
+                .is_air()
+        {
+            y -= 1.0;
+        }
+
+        let contents = PotionContentsImpl {
+            potion_id: None,
+            custom_color: None,
+            custom_effects: vec![StatusEffectInstance {
+                effect_id: i32::from(StatusEffect::INSTANT_DAMAGE.id),
+                amplifier: 0,
+                duration: 1,
+                ambient: false,
+                show_particles: true,
+                show_icon: true,
+            }],
+            custom_name: None,
+        };
+        let cloud_entity = Entity::new(
+            world.clone(),
+            Vector3::new(x, y.floor() + 1.0, z),
+            &EntityType::AREA_EFFECT_CLOUD,
+        );
+        let cloud = AreaEffectCloudEntity::new(cloud_entity)
+            .with_potion(contents, 0.25)
+            .with_particle(ParticleEffect::dragon_breath(1.0));
+        cloud.owner.store(Some(entity.entity_uuid));
+        cloud.duration.store(FLAME_TICKS, Relaxed);
+        let cloud = Arc::new(cloud);
+        world.spawn_entity(cloud.clone()).await;
+        cloud.set_radius(5.0).await;
+    }
+
+    async fn tick_charging(&self) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let Some(target) = self.fly_target.load() else {
+            self.set_phase(DragonPhase::HoldingPattern).await;
+            return;
+        };
+        let distance = target.squared_distance_to_vec(&entity.pos.load());
+        let ticks = self.phase_counter.load(Relaxed);
+        if ticks > 0 || !(100.0..=22500.0).contains(&distance) {
+            if ticks >= 10 {
+                self.set_phase(DragonPhase::HoldingPattern).await;
+            } else {
+                self.phase_counter.fetch_add(1, Relaxed);
+            }
+        }
+    }
+
+    /// Heals the dragon from the nearest end crystal, looking for another one now and then.
+    fn tick_crystals(&self, world: &World) -> Option<f32> {
+        let living = &self.mob_entity.living_entity;
+        let entity = &living.entity;
+        let mut heal = None;
+        let crystal_id = self.nearest_crystal.load(Relaxed);
+        if crystal_id != 0 {
+            if world.get_entity_by_id(crystal_id).is_none() {
+                self.nearest_crystal.store(0, Relaxed);
+            } else if entity.age.load(Relaxed) % 10 == 0 {
+                heal = Some(1.0);
+            }
+        }
+        if rand::rng().random_range(0..10) == 0 {
+            let pos = entity.pos.load();
+            let area = entity.bounding_box.load().expand(32.0, 32.0, 32.0);
+            let nearest = world
+                .get_entities_at_box(&area)
+                .into_iter()
+                .filter(|crystal| crystal.get_entity().entity_type == &EntityType::END_CRYSTAL)
+                .min_by(|a, b| {
+                    let a = a.get_entity().pos.load().squared_distance_to_vec(&pos);
+                    let b = b.get_entity().pos.load().squared_distance_to_vec(&pos);
+                    a.total_cmp(&b)
+                });
+            self.nearest_crystal.store(
+                nearest.map_or(0, |crystal| crystal.get_entity().entity_id),
+                Relaxed,
+            );
+        }
+        heal
+    }
+
+    /// Rises and spins for ten seconds, dropping experience, then tells the fight it died.
+    async fn tick_death(&self, world: &Arc<World>) {
+        let living = &self.mob_entity.living_entity;
+        let entity = &living.entity;
+        // The dragon takes longer to die than other mobs
+        living.death_time.store(0, Relaxed);
+        if self.phase() != DragonPhase::Dying {
+            self.set_phase(DragonPhase::Dying).await;
+        }
+        let ticks = self.death_ticks.fetch_add(1, Relaxed) + 1;
+        let fight = world.dragon_fight.as_ref();
+        let experience = if fight.is_some_and(|fight| !fight.previously_killed()) {
+            FIRST_KILL_EXPERIENCE
+        } else {
+            EXPERIENCE
+        };
+        let pos = entity.pos.load();
+        if ticks == 1 {
+            world
+                .sync_global_world_event(WorldEvent::EnderDragonDies, entity.block_pos.load(), 0)
+                .await;
+        }
+        if ticks > 150 && ticks % 5 == 0 {
+            ExperienceOrbEntity::spawn(world, pos, (experience * 0.08).floor() as u32).await;
+        }
+
+        entity.velocity.store(Vector3::default());
+        entity.set_pos(pos.add_raw(0.0, 0.1, 0.0));
+        let yaw = entity.yaw.load() + 20.0;
+        entity.yaw.store(yaw);
+        entity.body_yaw.store(yaw);
+        entity.send_pos_rot().await;
+
+        if ticks >= DEATH_TICKS {
+            ExperienceOrbEntity::spawn(world, pos, (experience * 0.2).floor() as u32).await;
+            if let Some(fight) = fight {
+                fight.on_dragon_killed(world, entity.entity_uuid).await;
+            }
+            entity.remove().await;
+        }
+    }
+}
+
+impl Mob for EnderDragonEntity {
+    fn get_mob_entity(&self) -> &MobEntity {
+        &self.mob_entity
+    }
+
+    fn mob_tick(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            let living = &self.mob_entity.living_entity;
+            let entity = &living.entity;
+            let world = entity.world.load_full();
+            if living.dead.load(Relaxed) {
+                self.tick_death(&world).await;
+                return;
+            }
+
+            let health = living.health.load();
+            let phase = self.phase();
+            if phase.is_sitting() {
+                let damage = self.sitting_damage.load() + (self.last_health.load() - health);
+                self.sitting_damage.store(damage.max(0.0));
+                let max_health = entity.entity_type.max_health.unwrap_or(200.0);
+                if sitting_damage_exceeded(damage, max_health) {
+                    self.set_phase(DragonPhase::Takeoff).await;
+                }
+            }
+            if let Some(fight) = &world.dragon_fight {
+                fight.update_dragon(&world, &self.this, health).await;
+            } else if phase != DragonPhase::Hover && !phase.is_sitting() {
+                // Without the fight's island there is nothing to circle around
+                self.set_phase(DragonPhase::Hover).await;
+            }
+
+            let phase = self.phase();
+            match phase {
+                DragonPhase::HoldingPattern => self.tick_holding_pattern(&world).await,
+                DragonPhase::Strafe => self.tick_strafe(&world).await,
+                DragonPhase::LandingApproach | DragonPhase::Landing | DragonPhase::Takeoff => {
+                    self.tick_landing(&world, phase).await;
+                }
+                DragonPhase::SittingFlaming
+                | DragonPhase::SittingScanning
+                | DragonPhase::SittingAttacking => self.tick_sitting(&world, phase).await,
+                DragonPhase::Charging => self.tick_charging().await,
+                DragonPhase::Dying | DragonPhase::Hover => {}
+            }
+            self.phase_ticks.fetch_add(1, Relaxed);
+            self.tick_flight(self.phase());
+
+            if let Some(heal) = self.tick_crystals(&world)
+                && health < entity.entity_type.max_health.unwrap_or(200.0)
+            {
+                living.heal(heal).await;
+            }
+            self.last_health.store(living.health.load());
+        })
+    }
+
+    fn should_take_damage(&self, damage_type: DamageType, cause: Option<&dyn EntityBase>) -> bool {
+        let phase = self.phase();
+        if phase == DragonPhase::Dying {
+            return false;
+        }
+        if damage_type == DamageType::GENERIC_KILL || damage_type == DamageType::OUT_OF_WORLD {
+            return true;
+        }
+        if phase.is_sitting() && damage_type == DamageType::ARROW {
+            return false;
+        }
+        cause.is_some_and(|cause| cause.get_player().is_some())
+            || ALWAYS_HURTS.contains(&damage_type)
+    }
+
+    fn should_despawn_in_peaceful(&self) -> bool {
+        false
+    }
+
+    fn init_mob_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            self.mob_entity
+                .living_entity
+                .entity
+                .send_meta_data(&[Metadata::new(
+                    TrackedData::DATA_PHASE_TYPE,
+                    MetaDataType::Integer,
+                    self.phase() as i32,
+                )])
+                .await;
+        })
+    }
+
+    fn write_mob_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            nbt.put_int("DragonPhase", self.phase() as i32);
+            nbt.put_int("DragonDeathTime", self.death_ticks.load(Relaxed));
+        })
+    }
+
+    fn read_mob_nbt<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(phase) = nbt.get_int("DragonPhase").and_then(DragonPhase::from_id) {
+                self.phase.store(phase);
+            }
+            self.death_ticks
+                .store(nbt.get_int("DragonDeathTime").unwrap_or(0), Relaxed);
+        })
+    }
+
+    fn get_gravity(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Whether `player` is someone the dragon attacks.
+fn is_valid_target(player: &Player) -> bool {
+    !player.is_creative() && !player.is_spectator() && !player.living_entity.dead.load(Relaxed)
+}
+
+/// Like [`Vector3::normalize`], but zero for vectors too short to have a direction.
+fn normalize(vector: Vector3<f64>) -> Vector3<f64> {
+    if vector.length_squared() < 1.0e-8 {
+        Vector3::default()
+    } else {
+        vector.normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_round_trip_through_their_ids() {
+        for phase in DragonPhase::ALL {
+            assert_eq!(DragonPhase::from_id(phase as i32), Some(phase));
+        }
+        assert_eq!(DragonPhase::from_id(-1), None);
+        assert_eq!(DragonPhase::from_id(11), None);
+    }
+
+    #[test]
+    fn only_the_sitting_phases_sit() {
+        let sitting: Vec<_> = DragonPhase::ALL
+            .into_iter()
+            .filter(|phase| phase.is_sitting())
+            .collect();
+        assert_eq!(
+            sitting,
+            [
+                DragonPhase::SittingFlaming,
+                DragonPhase::SittingScanning,
+                DragonPhase::SittingAttacking
+            ]
+        );
+    }
+
+    #[test]
+    fn charging_is_the_fastest_phase() {
+        assert!(
+            DragonPhase::ALL
+                .into_iter()
+                .all(|phase| phase == DragonPhase::Charging
+                    || phase.fly_speed() < DragonPhase::Charging.fly_speed())
+        );
+        assert!(DragonPhase::Landing.fly_speed() > DragonPhase::HoldingPattern.fly_speed());
+    }
+
+    #[test]
+    fn dragon_takes_off_after_the_last_flames() {
+        for flames in 1..MAX_FLAMES {
+            assert_eq!(
+                DragonPhase::after_flames(flames),
+                DragonPhase::SittingScanning
+            );
+        }
+        assert_eq!(DragonPhase::after_flames(MAX_FLAMES), DragonPhase::Takeoff);
+    }
+
+    #[test]
+    fn dragon_takes_off_after_a_quarter_of_its_health() {
+        assert!(!sitting_damage_exceeded(50.0, 200.0));
+        assert!(sitting_damage_exceeded(50.5, 200.0));
+    }
+
+    #[test]
+    fn short_vectors_have_no_direction() {
+        assert_eq!(
+            normalize(Vector3::new(0.0, 1.0e-5, 0.0)),
+            Vector3::default()
+        );
+        assert_eq!(
+            normalize(Vector3::new(0.0, 2.0, 0.0)),
+            Vector3::new(0.0, 1.0, 0.0)
+        );
+    }
+}
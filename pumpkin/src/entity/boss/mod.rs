@@ -1 +1,2 @@
+pub mod ender_dragon;
 pub mod wither;
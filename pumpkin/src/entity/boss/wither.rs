@@ -1,21 +1,104 @@
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering::Relaxed;
 use std::sync::{Arc, Weak};
 
+use pumpkin_data::damage::DamageType;
 use pumpkin_data::entity::EntityType;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::tag::{self, Taggable};
+use pumpkin_data::tracked_data::{TrackedData, TrackedId};
+use pumpkin_data::world::WorldEvent;
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::Difficulty;
+use pumpkin_util::loot_table::{
+    ItemEntry, LootNumberProviderTypes, LootPool, LootPoolEntry, LootPoolEntryTypes, LootTable,
+    LootTableType,
+};
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_util::text::TextComponent;
+use pumpkin_world::world::BlockFlags;
 
 use crate::entity::{
-    Entity, NBTStorage,
-    ai::goal::{look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal},
+    Entity, EntityBase, EntityBaseFuture,
+    ai::goal::{
+        Controls, Goal, GoalFuture, active_target::ActiveTargetGoal, look_around::LookAroundGoal,
+        look_at_entity::LookAtEntityGoal, ranged_attack::RangedAttackGoal,
+    },
     mob::{Mob, MobEntity},
+    player::Player,
+    projectile::wither_skull::WitherSkullEntity,
+};
+use crate::world::World;
+use crate::world::bossbar::{Bossbar, BossbarColor, BossbarFlags, EntityBossbar};
+
+/// How long a summoned wither grows before it explodes and starts fighting, in ticks.
+pub const SUMMON_TICKS: i32 = 220;
+const SUMMON_EXPLOSION_POWER: f32 = 7.0;
+/// How far away players see the bossbar, as far as clients keep track of the wither.
+const BOSSBAR_RANGE: f64 = 160.0;
+/// How far the heads shoot at the entities they target.
+const HEAD_RANGE: f64 = 30.0;
+const TARGET_DATA: [TrackedId; 3] = [
+    TrackedData::DATA_TRACKED_ENTITY_ID_1,
+    TrackedData::DATA_TRACKED_ENTITY_ID_2,
+    TrackedData::DATA_TRACKED_ENTITY_ID_3,
+];
+
+/// The wither always drops a nether star.
+static LOOT_TABLE: LootTable = LootTable {
+    r#type: LootTableType::Entity,
+    random_sequence: Some("minecraft:entities/wither"),
+    pools: Some(&[LootPool {
+        entries: &[LootPoolEntry {
+            content: LootPoolEntryTypes::Item(ItemEntry {
+                name: "minecraft:nether_star",
+            }),
+            conditions: None,
+            functions: None,
+            weight: 1,
+        }],
+        rolls: LootNumberProviderTypes::Constant(1.0),
+        bonus_rolls: 0.0,
+        conditions: None,
+        functions: None,
+    }]),
 };
 
+/// The wither boss. It flies after its target shooting skulls from its three heads, the side
+/// heads picking targets of their own, and breaks the blocks around it when hurt.
 pub struct WitherEntity {
     pub mob_entity: MobEntity,
+    /// Ticks left until a summoned wither is done growing. It can't be hurt until then.
+    invulnerable_ticks: AtomicI32,
+    /// The entity each head targets, the main head first, or 0.
+    head_targets: [AtomicI32; 3],
+    /// The age at which each side head next looks for a target or shoots.
+    next_head_updates: [AtomicI32; 2],
+    /// How many times in a row each side head had nothing to shoot at.
+    idle_head_updates: [AtomicI32; 2],
+    /// Ticks until the wither breaks the blocks around it, after it was hurt.
+    destroy_blocks_ticks: AtomicI32,
+    bossbar: EntityBossbar,
 }
 
 impl WitherEntity {
-    pub async fn new(entity: Entity) -> Arc<Self> {
+    pub async fn new(mut entity: Entity) -> Arc<Self> {
+        entity.damage_immunities.push(DamageType::DROWN);
         let mob_entity = MobEntity::new(entity);
-        let wither = Self { mob_entity };
+        let mut bossbar = Bossbar::new(TextComponent::translate("entity.minecraft.wither", []));
+        bossbar.color = BossbarColor::Purple;
+        bossbar.flags = BossbarFlags::DarkenSky;
+        bossbar.health = 1.0;
+        let wither = Self {
+            mob_entity,
+            invulnerable_ticks: AtomicI32::new(0),
+            head_targets: Default::default(),
+            next_head_updates: Default::default(),
+            idle_head_updates: Default::default(),
+            destroy_blocks_ticks: AtomicI32::new(0),
+            bossbar: EntityBossbar::new(bossbar),
+        };
         let mob_arc = Arc::new(wither);
         let mob_weak: Weak<dyn Mob> = {
             let mob_arc: Arc<dyn Mob> = mob_arc.clone();
@@ -24,23 +107,467 @@ impl WitherEntity {
 
         {
             let mut goal_selector = mob_arc.mob_entity.goals_selector.lock().await;
+            let mut target_selector = mob_arc.mob_entity.target_selector.lock().await;
 
-            // TODO
             goal_selector.add_goal(
-                8,
+                0,
+                Box::new(SummoningGoal {
+                    wither: Arc::downgrade(&mob_arc),
+                }),
+            );
+            goal_selector.add_goal(2, RangedAttackGoal::new(1.0, 40, 20.0));
+            goal_selector.add_goal(
+                6,
                 LookAtEntityGoal::with_default(mob_weak, &EntityType::PLAYER, 8.0),
             );
-            goal_selector.add_goal(8, Box::new(LookAroundGoal::default()));
+            goal_selector.add_goal(7, Box::new(LookAroundGoal::default()));
+
+            target_selector.add_goal(
+                2,
+                ActiveTargetGoal::with_default(&mob_arc.mob_entity, &EntityType::PLAYER, false),
+            );
         };
 
         mob_arc
     }
-}
 
-impl NBTStorage for WitherEntity {}
+    pub fn get_invulnerable_ticks(&self) -> i32 {
+        self.invulnerable_ticks.load(Relaxed)
+    }
+
+    pub async fn set_invulnerable_ticks(&self, ticks: i32) {
+        self.invulnerable_ticks.store(ticks, Relaxed);
+        self.mob_entity
+            .living_entity
+            .entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_INVUL_TIMER,
+                MetaDataType::Integer,
+                ticks,
+            )])
+            .await;
+    }
+
+    /// Starts growing a freshly summoned wither from a third of its health.
+    pub async fn make_invulnerable(&self) {
+        let living = &self.mob_entity.living_entity;
+        let max_health = living.entity.entity_type.max_health.unwrap_or(300.0);
+        self.set_invulnerable_ticks(SUMMON_TICKS).await;
+        living.set_health(max_health / 3.0).await;
+        self.bossbar
+            .set_health(&living.entity.world.load(), 0.0)
+            .await;
+    }
+
+    /// Below half health the wither gets armor, which arrows bounce off.
+    pub fn is_powered(&self) -> bool {
+        let living = &self.mob_entity.living_entity;
+        let max_health = living.entity.entity_type.max_health.unwrap_or(300.0);
+        living.health.load() <= max_health / 2.0
+    }
+
+    async fn set_head_target(&self, head: usize, entity_id: i32) {
+        if self.head_targets[head].swap(entity_id, Relaxed) == entity_id {
+            return;
+        }
+        self.mob_entity
+            .living_entity
+            .entity
+            .send_meta_data(&[Metadata::new(
+                TARGET_DATA[head],
+                MetaDataType::Integer,
+                entity_id,
+            )])
+            .await;
+    }
+
+    fn head_position(&self, head: usize) -> Vector3<f64> {
+        let entity = &self.mob_entity.living_entity.entity;
+        let pos = entity.pos.load();
+        if head == 0 {
+            return Vector3::new(pos.x, pos.y + 3.0, pos.z);
+        }
+        let angle = (entity.body_yaw.load() + 180.0 * (head - 1) as f32).to_radians();
+        Vector3::new(
+            f64::from(angle.cos()).mul_add(1.3, pos.x),
+            pos.y + 2.2,
+            f64::from(angle.sin()).mul_add(1.3, pos.z),
+        )
+    }
+
+    /// Whether the heads go after `target`. The wither leaves undead mobs alone.
+    fn can_target(&self, target: &dyn EntityBase) -> bool {
+        let Some(living) = target.get_living_entity() else {
+            return false;
+        };
+        let entity = target.get_entity();
+        entity.entity_id != self.mob_entity.living_entity.entity.entity_id
+            && !living.dead.load(Relaxed)
+            && !target.is_spectator()
+            && !target.get_player().is_some_and(Player::is_creative)
+            && !entity
+                .entity_type
+                .has_tag(&tag::EntityType::MINECRAFT_WITHER_FRIENDS)
+    }
+
+    async fn shoot_skull(&self, head: usize, target: Vector3<f64>, dangerous: bool) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let world = entity.world.load_full();
+        world
+            .sync_world_event(WorldEvent::WitherShoots, entity.block_pos.load(), 0)
+            .await;
+        let origin = self.head_position(head);
+        let skull_entity = Entity::new(world.clone(), origin, &EntityType::WITHER_SKULL);
+        let skull =
+            WitherSkullEntity::new_shot(skull_entity, entity, target.sub(&origin), dangerous);
+        world.spawn_entity(Arc::new(skull)).await;
+    }
+
+    async fn shoot_skull_at(&self, head: usize, target: &dyn EntityBase) {
+        let target = target.get_entity();
+        let pos = target.pos.load();
+        let eye_height = f64::from(target.entity_dimension.load().eye_height);
+        let dangerous = head == 0 && rand::random::<f32>() < 0.001;
+        self.shoot_skull(
+            head,
+            Vector3::new(pos.x, eye_height.mul_add(0.5, pos.y), pos.z),
+            dangerous,
+        )
+        .await;
+    }
+
+    /// Grows the wither while it is being summoned, until it explodes.
+    async fn tick_summoning(&self, world: &Arc<World>, ticks_left: i32) {
+        let entity = &self.mob_entity.living_entity.entity;
+        self.bossbar
+            .set_health(world, 1.0 - ticks_left as f32 / SUMMON_TICKS as f32)
+            .await;
+        if ticks_left <= 0 {
+            let pos = entity.pos.load();
+            world
+                .explode_with_source(
+                    Vector3::new(pos.x, entity.get_eye_y(), pos.z),
+                    SUMMON_EXPLOSION_POWER,
+                    Some(self),
+                    None,
+                )
+                .await;
+            world
+                .sync_global_world_event(WorldEvent::WitherSpawns, entity.block_pos.load(), 0)
+                .await;
+        }
+        self.set_invulnerable_ticks(ticks_left).await;
+        if entity.age.load(Relaxed) % 10 == 0 {
+            self.mob_entity.living_entity.heal(10.0).await;
+        }
+    }
+
+    /// Flies towards the main head's target, staying above it until the wither is powered.
+    async fn tick_movement(&self, world: &World) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let mut velocity = entity.velocity.load().multiply(1.0, 0.6, 1.0);
+        let target_id = self.head_targets[0].load(Relaxed);
+        if let Some(target) = world.get_entity_by_id(target_id).filter(|_| target_id > 0) {
+            let pos = entity.pos.load();
+            let target_pos = target.get_entity().pos.load();
+            if pos.y < target_pos.y || (!self.is_powered() && pos.y < target_pos.y + 5.0) {
+                velocity.y = velocity.y.max(0.0).mul_add(0.4, 0.3);
+            }
+            let horizontal = Vector3::new(target_pos.x - pos.x, 0.0, target_pos.z - pos.z);
+            if horizontal.length_squared() > 9.0 {
+                let direction = horizontal.normalize();
+                velocity.x += direction.x.mul_add(0.3, -velocity.x * 0.6);
+                velocity.z += direction.z.mul_add(0.3, -velocity.z * 0.6);
+            }
+        }
+        entity.velocity.store(velocity);
+        if velocity.x.mul_add(velocity.x, velocity.z * velocity.z) > 0.05 {
+            let yaw = velocity.z.atan2(velocity.x).to_degrees() as f32 - 90.0;
+            entity.yaw.store(yaw);
+            entity.body_yaw.store(yaw);
+        }
+    }
+
+    /// Lets the side heads pick targets near the wither and shoot at them. On Normal and Hard
+    /// difficulty, heads without a target every now and then shoot dangerous skulls around.
+    async fn tick_heads(&self, world: &Arc<World>) {
+        let entity = &self.mob_entity.living_entity.entity;
+        let age = entity.age.load(Relaxed);
+        for head in 1..3 {
+            let index = head - 1;
+            if age < self.next_head_updates[index].load(Relaxed) {
+                continue;
+            }
+            self.next_head_updates[index].store(age + rand::random_range(10..20), Relaxed);
+
+            if matches!(
+                world.get_difficulty(),
+                Difficulty::Normal | Difficulty::Hard
+            ) && self.idle_head_updates[index].fetch_add(1, Relaxed) > 15
+            {
+                let pos = entity.pos.load();
+                let target = Vector3::new(
+                    pos.x + rand::random_range(-10.0..10.0),
+                    pos.y + rand::random_range(-5.0..5.0),
+                    pos.z + rand::random_range(-10.0..10.0),
+                );
+                self.shoot_skull(head, target, true).await;
+                self.idle_head_updates[index].store(0, Relaxed);
+            }
+
+            let target_id = self.head_targets[head].load(Relaxed);
+            if target_id > 0 {
+                let target = world.get_entity_by_id(target_id).filter(|target| {
+                    self.can_target(target.as_ref())
+                        && target
+                            .get_entity()
+                            .pos
+                            .load()
+                            .squared_distance_to_vec(&entity.pos.load())
+                            <= HEAD_RANGE * HEAD_RANGE
+                });
+                if let Some(target) = target {
+                    self.shoot_skull_at(head, target.as_ref()).await;
+                    self.next_head_updates[index].store(age + rand::random_range(40..60), Relaxed);
+                    self.idle_head_updates[index].store(0, Relaxed);
+                } else {
+                    self.set_head_target(head, 0).await;
+                }
+            } else {
+                let area = entity.bounding_box.load().expand(20.0, 8.0, 20.0);
+                let mut candidates = world.get_entities_at_box(&area);
+                candidates.extend(
+                    world
+                        .get_players_at_box(&area)
+                        .into_iter()
+                        .map(|player| player as Arc<dyn EntityBase>),
+                );
+                candidates.retain(|candidate| self.can_target(candidate.as_ref()));
+                if !candidates.is_empty() {
+                    let target = &candidates[rand::random_range(0..candidates.len())];
+                    self.set_head_target(head, target.get_entity().entity_id)
+                        .await;
+                }
+            }
+        }
+
+        let target_id = self
+            .mob_entity
+            .target
+            .lock()
+            .await
+            .as_ref()
+            .map_or(0, |target| target.get_entity().entity_id);
+        self.set_head_target(0, target_id).await;
+    }
+
+    /// Breaks the blocks the wither is stuck in, a second after it was hurt.
+    async fn tick_destroy_blocks(&self, world: &Arc<World>) {
+        let ticks = self.destroy_blocks_ticks.load(Relaxed);
+        if ticks <= 0 {
+            return;
+        }
+        self.destroy_blocks_ticks.store(ticks - 1, Relaxed);
+        if ticks > 1 || !world.level_info.load().game_rules.mob_griefing {
+            return;
+        }
+
+        let entity = &self.mob_entity.living_entity.entity;
+        let block_pos = entity.block_pos.load();
+        let reach = (entity.width() / 2.0 + 1.0).floor() as i32;
+        let height = entity.height().floor() as i32;
+        let mut broke = false;
+        for pos in BlockPos::iterate(
+            block_pos.offset(Vector3::new(-reach, 0, -reach)),
+            block_pos.offset(Vector3::new(reach, height, reach)),
+        ) {
+            let (block, state) = world.get_block_and_state(&pos).await;
+            if state.is_air() || block.has_tag(&tag::Block::MINECRAFT_WITHER_IMMUNE) {
+                continue;
+            }
+            broke |= world
+                .break_block(&pos, None, BlockFlags::NOTIFY_ALL)
+                .await
+                .is_some();
+        }
+        if broke {
+            world
+                .sync_world_event(WorldEvent::WitherBreaksBlock, block_pos, 0)
+                .await;
+        }
+    }
+}
 
 impl Mob for WitherEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    fn mob_tick(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            let living = &self.mob_entity.living_entity;
+            let entity = &living.entity;
+            let world = entity.world.load_full();
+            if living.dead.load(Relaxed) {
+                self.bossbar.clear(&world).await;
+                return;
+            }
+            self.bossbar
+                .update_viewers(&world, entity.pos.load(), BOSSBAR_RANGE)
+                .await;
+
+            let invulnerable_ticks = self.get_invulnerable_ticks();
+            if invulnerable_ticks > 0 {
+                self.tick_summoning(&world, invulnerable_ticks - 1).await;
+                return;
+            }
+
+            self.tick_movement(&world).await;
+            self.tick_heads(&world).await;
+            self.tick_destroy_blocks(&world).await;
+            if entity.age.load(Relaxed) % 20 == 0 {
+                living.heal(1.0).await;
+            }
+            let max_health = entity.entity_type.max_health.unwrap_or(300.0);
+            self.bossbar
+                .set_health(&world, living.health.load() / max_health)
+                .await;
+        })
+    }
+
+    fn should_take_damage(&self, damage_type: DamageType, cause: Option<&dyn EntityBase>) -> bool {
+        if cause.is_some_and(|cause| cause.get_entity().entity_type == &EntityType::WITHER) {
+            return false;
+        }
+        if self.get_invulnerable_ticks() > 0
+            && damage_type != DamageType::GENERIC_KILL
+            && damage_type != DamageType::OUT_OF_WORLD
+        {
+            return false;
+        }
+        if self.is_powered() && damage_type == DamageType::ARROW {
+            return false;
+        }
+
+        if self.destroy_blocks_ticks.load(Relaxed) <= 0 {
+            self.destroy_blocks_ticks.store(20, Relaxed);
+        }
+        for idle in &self.idle_head_updates {
+            idle.fetch_add(3, Relaxed);
+        }
+        true
+    }
+
+    fn perform_ranged_attack<'a>(
+        &'a self,
+        target: &'a dyn EntityBase,
+        _pull_progress: f32,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move { self.shoot_skull_at(0, target).await })
+    }
+
+    fn get_loot_table(&self) -> Option<&'static LootTable> {
+        Some(&LOOT_TABLE)
+    }
+
+    fn get_gravity(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Keeps the wither still while it is being summoned.
+struct SummoningGoal {
+    wither: Weak<WitherEntity>,
+}
+
+impl SummoningGoal {
+    fn is_summoning(&self) -> bool {
+        self.wither
+            .upgrade()
+            .is_some_and(|wither| wither.get_invulnerable_ticks() > 0)
+    }
+}
+
+impl Goal for SummoningGoal {
+    fn can_start<'a>(&'a mut self, _mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async { self.is_summoning() })
+    }
+
+    fn should_continue<'a>(&'a self, _mob: &'a dyn Mob) -> GoalFuture<'a, bool> {
+        Box::pin(async { self.is_summoning() })
+    }
+
+    fn controls(&self) -> Controls {
+        Controls::MOVE | Controls::JUMP | Controls::LOOK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn new_wither(world: &Arc<World>) -> Arc<WitherEntity> {
+        let entity = Entity::new(
+            world.clone(),
+            Vector3::new(0.0, 64.0, 0.0),
+            &EntityType::WITHER,
+        );
+        WitherEntity::new(entity).await
+    }
+
+    #[tokio::test]
+    async fn growing_wither_only_takes_void_damage() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let wither = new_wither(&world).await;
+        wither.make_invulnerable().await;
+
+        assert_eq!(wither.get_invulnerable_ticks(), SUMMON_TICKS);
+        assert_eq!(wither.mob_entity.living_entity.health.load(), 100.0);
+        assert!(!wither.should_take_damage(DamageType::PLAYER_ATTACK, None));
+        assert!(wither.should_take_damage(DamageType::OUT_OF_WORLD, None));
+        assert!(wither.should_take_damage(DamageType::GENERIC_KILL, None));
+    }
+
+    #[tokio::test]
+    async fn growing_wither_counts_down() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let wither = new_wither(&world).await;
+        wither.make_invulnerable().await;
+
+        wither.mob_tick().await;
+        assert_eq!(wither.get_invulnerable_ticks(), SUMMON_TICKS - 1);
+        // It heals while growing
+        assert!(wither.mob_entity.living_entity.health.load() > 100.0);
+    }
+
+    #[tokio::test]
+    async fn arrows_bounce_off_below_half_health() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let wither = new_wither(&world).await;
+        let health = &wither.mob_entity.living_entity.health;
+
+        health.store(300.0);
+        assert!(!wither.is_powered());
+        assert!(wither.should_take_damage(DamageType::ARROW, None));
+
+        health.store(150.0);
+        assert!(wither.is_powered());
+        assert!(!wither.should_take_damage(DamageType::ARROW, None));
+        assert!(wither.should_take_damage(DamageType::PLAYER_ATTACK, None));
+    }
+
+    #[tokio::test]
+    async fn withers_leave_each_other_alone() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let wither = new_wither(&world).await;
+        let other = new_wither(&world).await;
+        let other: &dyn EntityBase = &*other;
+
+        assert!(!wither.should_take_damage(DamageType::MOB_ATTACK, Some(other)));
+        assert!(!wither.can_target(other));
+    }
 }
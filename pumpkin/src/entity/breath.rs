@@ -87,7 +87,7 @@ impl BreathManager {
         }
     }
 
-    async fn is_eye_in_water(&self, player: &Player) -> bool {
+    pub async fn is_eye_in_water(&self, player: &Player) -> bool {
         let e = &player.living_entity.entity;
         let pos = e.pos.load();
         let eye_y = e.get_eye_y();
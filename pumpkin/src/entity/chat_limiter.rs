@@ -0,0 +1,214 @@
+//! Rate limits of the chat messages and commands of players, see [`ChatLimitsConfig`].
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pumpkin_config::chat_limits::{ChatLimitsConfig, RateLimit, SpamConsequence};
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::entity::player::Player;
+use crate::net::DisconnectReason;
+use crate::plugin::player::player_spam::PlayerSpamEvent;
+use crate::server::Server;
+
+/// What a player sent.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MessageKind {
+    Chat,
+    Command,
+}
+
+/// Messages a player may still send, refilled over time.
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(now: Instant) -> Self {
+        Self {
+            // Capped at the burst on first use
+            tokens: f64::INFINITY,
+            updated: now,
+        }
+    }
+
+    /// Uses up one message, if there is one left.
+    fn take(&mut self, limit: RateLimit, now: Instant) -> bool {
+        let refill = now.duration_since(self.updated).as_secs_f64() * limit.per_second;
+        self.tokens = (self.tokens + refill).min(f64::from(limit.burst));
+        self.updated = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+struct LimiterState {
+    chat: Bucket,
+    commands: Bucket,
+    /// Violations since the player was last forgiven.
+    violations: usize,
+    last_violation: Option<Instant>,
+    muted_until: Option<Instant>,
+}
+
+pub struct ChatLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl Default for ChatLimiter {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            state: Mutex::new(LimiterState {
+                chat: Bucket::new(now),
+                commands: Bucket::new(now),
+                violations: 0,
+                last_violation: None,
+                muted_until: None,
+            }),
+        }
+    }
+}
+
+impl ChatLimiter {
+    /// Whether `player` may send `message` now. If they went over the limit, this also deals
+    /// with them, after firing a [`PlayerSpamEvent`].
+    pub async fn allow(
+        &self,
+        player: &Arc<Player>,
+        server: &Server,
+        kind: MessageKind,
+        message: &str,
+    ) -> bool {
+        let config = &server.advanced_config.chat_limits;
+        if !config.enabled {
+            return true;
+        }
+        let now = Instant::now();
+        if kind == MessageKind::Chat
+            && let Some(remaining) = self.muted_for(now)
+        {
+            player
+                .send_system_message(
+                    &TextComponent::text(format!(
+                        "You are muted for {} more seconds",
+                        remaining.as_secs() + 1
+                    ))
+                    .color_named(NamedColor::Red),
+                )
+                .await;
+            return false;
+        }
+        if player
+            .has_permission(server, &config.exempt_permission)
+            .await
+        {
+            return true;
+        }
+        let Some(previous) = self.take(kind, config, now) else {
+            return true;
+        };
+
+        let event = PlayerSpamEvent::new(
+            player.clone(),
+            message.to_string(),
+            kind,
+            config.consequence(previous),
+        );
+        let event = server.plugin_manager.fire(event).await;
+        if event.cancelled {
+            return true;
+        }
+        log::info!(
+            "{} sent messages too fast, {:?}",
+            player.gameprofile.name,
+            event.consequence
+        );
+        self.punish(player, event.consequence, now).await;
+        false
+    }
+
+    /// How much longer the player is muted.
+    fn muted_for(&self, now: Instant) -> Option<Duration> {
+        let muted_until = self.state.lock().unwrap().muted_until?;
+        muted_until.checked_duration_since(now)
+    }
+
+    /// Uses up a message of `kind`. Returns the number of earlier violations if there was none
+    /// left.
+    fn take(&self, kind: MessageKind, config: &ChatLimitsConfig, now: Instant) -> Option<usize> {
+        let mut state = self.state.lock().unwrap();
+        let allowed = match kind {
+            MessageKind::Chat => state.chat.take(config.chat, now),
+            MessageKind::Command => state.commands.take(config.commands, now),
+        };
+        if allowed {
+            return None;
+        }
+        let forgive_after = Duration::from_secs(config.forgive_after_seconds);
+        if state
+            .last_violation
+            .is_some_and(|last| now.duration_since(last) > forgive_after)
+        {
+            state.violations = 0;
+        }
+        let previous = state.violations;
+        state.violations += 1;
+        state.last_violation = Some(now);
+        Some(previous)
+    }
+
+    async fn punish(&self, player: &Player, consequence: SpamConsequence, now: Instant) {
+        let warning = match consequence {
+            SpamConsequence::Warn => "You are sending messages too fast".to_string(),
+            SpamConsequence::Mute(seconds) => {
+                self.state.lock().unwrap().muted_until = Some(now + Duration::from_secs(seconds));
+                format!("You are muted for {seconds} seconds for sending messages too fast")
+            }
+            SpamConsequence::Kick => {
+                player
+                    .kick(
+                        DisconnectReason::Kicked,
+                        TextComponent::translate("disconnect.spam", []),
+                    )
+                    .await;
+                return;
+            }
+        };
+        player
+            .send_system_message(&TextComponent::text(warning).color_named(NamedColor::Red))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_refills_up_to_burst() {
+        let limit = RateLimit {
+            burst: 3,
+            per_second: 2.0,
+        };
+        let start = Instant::now();
+        let mut bucket = Bucket::new(start);
+        for _ in 0..3 {
+            assert!(bucket.take(limit, start));
+        }
+        assert!(!bucket.take(limit, start));
+        assert!(bucket.take(limit, start + Duration::from_millis(500)));
+        assert!(!bucket.take(limit, start + Duration::from_millis(500)));
+
+        let later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(bucket.take(limit, later));
+        }
+        assert!(!bucket.take(limit, later));
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::atomic::Ordering;
 
+use pumpkin_config::CombatProfile;
 use pumpkin_data::{
     particle::Particle,
     sound::{Sound, SoundCategory},
@@ -50,16 +51,32 @@ impl AttackType {
     }
 }
 
-pub fn handle_knockback(attacker: &Entity, victim: &Entity, strength: f64) {
+/// Knocks `victim` back in the direction `attacker` looks, tuned by `profile`.
+pub fn handle_knockback(
+    attacker: &Entity,
+    victim: &Entity,
+    profile: &CombatProfile,
+    sprinting: bool,
+) {
+    let strength = if sprinting {
+        profile.knockback + profile.sprint_knockback
+    } else {
+        profile.knockback
+    };
     let yaw = attacker.yaw.load();
     victim.knockback(
-        strength * 0.5,
+        strength,
         f64::from((yaw.to_radians()).sin()),
         f64::from(-(yaw.to_radians()).cos()),
+        profile.knockback_friction,
+        profile.knockback_vertical,
     );
 
+    let slowdown = profile.attacker_slowdown;
     let velocity = attacker.velocity.load();
-    attacker.velocity.store(velocity.multiply(0.6, 1.0, 0.6));
+    attacker
+        .velocity
+        .store(velocity.multiply(slowdown, 1.0, slowdown));
 }
 
 pub async fn spawn_sweep_particle(attacker_entity: &Entity, world: &World, pos: &Vector3<f64>) {
@@ -1,22 +1,28 @@
+use std::mem;
 use std::sync::atomic::{AtomicI32, AtomicI64, AtomicU8, Ordering};
 
 use crate::entity::{
     Entity, EntityBase, EntityBaseFuture, NBTStorage, NbtFuture, living::LivingEntity,
+    player::Player,
 };
 use crossbeam::atomic::AtomicCell;
 use pumpkin_data::{
     damage::DamageType,
-    data_component_impl::{EquipmentSlot, EquipmentType},
+    data_component_impl::{EquipmentSlot, EquipmentType, EquippableImpl},
     entity::EntityStatus,
     item::Item,
+    meta_data_type::MetaDataType,
     particle::Particle,
     sound::{Sound, SoundCategory},
+    tracked_data::TrackedData,
 };
 use pumpkin_nbt::{compound::NbtCompound, tag::NbtTag};
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::GameMode;
 use pumpkin_util::math::{euler_angle::EulerAngle, vector3::Vector3};
 use pumpkin_world::item::ItemStack;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PackedRotation {
     pub head: EulerAngle,
     pub body: EulerAngle,
@@ -39,6 +45,61 @@ impl Default for PackedRotation {
     }
 }
 
+impl PackedRotation {
+    /// The poses sneaking players cycle through by using an armor stand with an empty hand, like
+    /// on Bedrock Edition. Starts with the default pose.
+    #[must_use]
+    pub fn presets() -> [Self; 6] {
+        let default = Self::default();
+        let zero = EulerAngle::new(0.0, 0.0, 0.0);
+        [
+            default,
+            Self {
+                head: zero,
+                body: zero,
+                left_arm: zero,
+                right_arm: zero,
+                left_leg: zero,
+                right_leg: zero,
+            },
+            Self {
+                right_arm: EulerAngle::new(-110.0, 35.0, 0.0),
+                ..default
+            },
+            Self {
+                left_arm: EulerAngle::new(-90.0, 0.0, 0.0),
+                right_arm: EulerAngle::new(-90.0, 0.0, 0.0),
+                ..default
+            },
+            Self {
+                head: EulerAngle::new(-5.0, 0.0, 0.0),
+                left_arm: EulerAngle::new(10.0, 0.0, -5.0),
+                right_arm: EulerAngle::new(-60.0, 20.0, -10.0),
+                left_leg: EulerAngle::new(-3.0, -3.0, -3.0),
+                right_leg: EulerAngle::new(3.0, 3.0, 3.0),
+                ..default
+            },
+            Self {
+                head: EulerAngle::new(-15.0, 0.0, 0.0),
+                left_arm: EulerAngle::new(-110.0, 0.0, -10.0),
+                right_arm: EulerAngle::new(-110.0, 0.0, 10.0),
+                ..default
+            },
+        ]
+    }
+
+    /// The preset after this pose, or the first one if this pose is not a preset.
+    #[must_use]
+    pub fn next_preset(&self) -> Self {
+        let presets = Self::presets();
+        let next = presets
+            .iter()
+            .position(|preset| preset == self)
+            .map_or(0, |index| (index + 1) % presets.len());
+        presets[next]
+    }
+}
+
 impl From<PackedRotation> for NbtTag {
     fn from(val: PackedRotation) -> Self {
         let mut compound = NbtCompound::new();
@@ -187,6 +248,144 @@ impl ArmorStandEntity {
         self.rotation.store(packed.to_owned());
     }
 
+    /// Changes the pose and shows it to the players that see the armor stand.
+    pub async fn set_pose(&self, pose: &PackedRotation) {
+        self.unpack_rotation(pose);
+        self.send_pose().await;
+    }
+
+    /// Sends the flags, e.g. after they were changed with [`Self::set_small`].
+    pub async fn send_flags(&self) {
+        self.get_entity()
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_ARMOR_STAND_FLAGS,
+                MetaDataType::Byte,
+                self.armor_stand_flags.load(Ordering::Relaxed),
+            )])
+            .await;
+    }
+
+    async fn send_pose(&self) {
+        let pose = self.pack_rotation();
+        self.get_entity()
+            .send_meta_data(&[
+                Metadata::new(
+                    TrackedData::DATA_TRACKER_HEAD_ROTATION,
+                    MetaDataType::Rotation,
+                    pose.head,
+                ),
+                Metadata::new(
+                    TrackedData::DATA_TRACKER_BODY_ROTATION,
+                    MetaDataType::Rotation,
+                    pose.body,
+                ),
+                Metadata::new(
+                    TrackedData::DATA_TRACKER_LEFT_ARM_ROTATION,
+                    MetaDataType::Rotation,
+                    pose.left_arm,
+                ),
+                Metadata::new(
+                    TrackedData::DATA_TRACKER_RIGHT_ARM_ROTATION,
+                    MetaDataType::Rotation,
+                    pose.right_arm,
+                ),
+                Metadata::new(
+                    TrackedData::DATA_TRACKER_LEFT_LEG_ROTATION,
+                    MetaDataType::Rotation,
+                    pose.left_leg,
+                ),
+                Metadata::new(
+                    TrackedData::DATA_TRACKER_RIGHT_LEG_ROTATION,
+                    MetaDataType::Rotation,
+                    pose.right_leg,
+                ),
+            ])
+            .await;
+    }
+
+    async fn get_equipped(&self, slot: &EquipmentSlot) -> ItemStack {
+        let equipment = self.living_entity.entity_equipment.lock().await.get(slot);
+        equipment.lock().await.clone()
+    }
+
+    async fn has_equipped(&self, slot: &EquipmentSlot) -> bool {
+        !self.get_equipped(slot).await.is_empty()
+    }
+
+    /// The slot a player with an empty hand takes the item from, depending on the height of the
+    /// clicked point, like vanilla `ArmorStandEntity::getSlotFromPosition`.
+    async fn slot_from_position(&self, hit: Vector3<f64>) -> EquipmentSlot {
+        let small = self.is_small();
+        let y = if small { hit.y * 2.0 } else { hit.y };
+        // The heights of the feet, chest and legs, relative to a normal sized armor stand
+        let (feet, chest, legs) = if small {
+            (0.1..0.9, 1.2..1.9, 0.4..1.4)
+        } else {
+            (0.1..0.55, 0.9..1.6, 0.4..1.2)
+        };
+
+        if feet.contains(&y) && self.has_equipped(&EquipmentSlot::FEET).await {
+            EquipmentSlot::FEET
+        } else if chest.contains(&y) && self.has_equipped(&EquipmentSlot::CHEST).await {
+            EquipmentSlot::CHEST
+        } else if legs.contains(&y) && self.has_equipped(&EquipmentSlot::LEGS).await {
+            EquipmentSlot::LEGS
+        } else if y >= 1.6 && self.has_equipped(&EquipmentSlot::HEAD).await {
+            EquipmentSlot::HEAD
+        } else if !self.has_equipped(&EquipmentSlot::MAIN_HAND).await
+            && self.has_equipped(&EquipmentSlot::OFF_HAND).await
+        {
+            EquipmentSlot::OFF_HAND
+        } else {
+            EquipmentSlot::MAIN_HAND
+        }
+    }
+
+    /// Swaps `stack` with the item in `slot`. Only a single item of larger stacks is placed, and
+    /// only into an empty slot.
+    async fn equip(&self, player: &Player, slot: &EquipmentSlot, stack: &mut ItemStack) -> bool {
+        let disabled_slots = self.disabled_slots.load(Ordering::Relaxed);
+        let equipped = self.get_equipped(slot).await;
+        if !equipped.is_empty() && disabled_slots & (1 << slot.get_offset_entity_slot_id(8)) != 0 {
+            return false;
+        }
+        if equipped.is_empty() && disabled_slots & (1 << slot.get_offset_entity_slot_id(16)) != 0 {
+            return false;
+        }
+
+        let placed = if player.is_creative() && equipped.is_empty() && !stack.is_empty() {
+            stack.copy_with_count(1)
+        } else if stack.item_count <= 1 {
+            mem::replace(stack, equipped)
+        } else if equipped.is_empty() {
+            stack.split(1)
+        } else {
+            return false;
+        };
+
+        self.living_entity
+            .entity_equipment
+            .lock()
+            .await
+            .put(slot, placed.clone())
+            .await;
+        self.living_entity
+            .send_equipment_changes(&[(slot.clone(), placed)])
+            .await;
+        true
+    }
+
+    async fn drop_equipment(&self) {
+        let entity = self.get_entity();
+        let world = entity.world.load();
+        for (slot, stack) in &self.living_entity.entity_equipment.lock().await.equipment {
+            let stack = mem::replace(&mut *stack.lock().await, ItemStack::EMPTY.clone());
+            if !stack.is_empty() {
+                world.drop_stack(&entity.block_pos.load(), stack).await;
+            }
+        }
+    }
+
     async fn break_and_drop_items(&self) {
         let entity = self.get_entity();
         //let name = entity.custom_name.unwrap_or(entity.get_name());
@@ -200,6 +399,7 @@ impl ArmorStandEntity {
             .await;
 
         self.on_break(entity).await;
+        self.drop_equipment().await;
     }
 
     async fn on_break(&self, entity: &Entity) {
@@ -211,8 +411,6 @@ impl ArmorStandEntity {
                 &entity.pos.load(),
             )
             .await;
-
-        // TODO: Implement equipment slots and make them drop all of their stored items.
     }
 
     /// Spawns break particles at the armor stand's position.
@@ -238,7 +436,9 @@ impl ArmorStandEntity {
 
 impl NBTStorage for ArmorStandEntity {
     fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
-        Box::pin(async {
+        Box::pin(async move {
+            self.living_entity.write_nbt(nbt).await;
+            self.living_entity.write_equipment(nbt).await;
             let disabled_slots = self.disabled_slots.load(Ordering::Relaxed);
 
             nbt.put_bool("Invisible", self.is_invisible());
@@ -256,6 +456,8 @@ impl NBTStorage for ArmorStandEntity {
 
     fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
         Box::pin(async {
+            self.living_entity.read_nbt_non_mut(nbt).await;
+            self.living_entity.read_equipment(nbt).await;
             let mut flags = 0u8;
 
             if let Some(invisible) = nbt.get_bool("Invisible")
@@ -280,11 +482,7 @@ impl NBTStorage for ArmorStandEntity {
                 self.disabled_slots.store(disabled_slots, Ordering::Relaxed);
             }
 
-            if let Some(no_base_plate) = nbt.get_bool("NoBasePlate") {
-                if !no_base_plate {
-                    flags |= ArmorStandFlags::HideBasePlate as u8;
-                }
-            } else {
+            if nbt.get_bool("NoBasePlate").unwrap_or(false) {
                 flags |= ArmorStandFlags::HideBasePlate as u8;
             }
 
@@ -317,6 +515,59 @@ impl EntityBase for ArmorStandEntity {
         self
     }
 
+    fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async {
+            self.living_entity.entity.init_data_tracker().await;
+            if self.armor_stand_flags.load(Ordering::Relaxed) != 0 {
+                self.send_flags().await;
+            }
+            if self.pack_rotation() != PackedRotation::default() {
+                self.send_pose().await;
+            }
+            self.living_entity.send_all_equipment().await;
+        })
+    }
+
+    fn interact_at<'a>(
+        &'a self,
+        player: &'a Player,
+        hit: Vector3<f64>,
+        stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async move {
+            if self.is_marker() || stack.item.id == Item::NAME_TAG.id {
+                return false;
+            }
+            if player.gamemode.load() == GameMode::Spectator {
+                return true;
+            }
+
+            if stack.is_empty() {
+                if player.living_entity.entity.sneaking.load(Ordering::Relaxed) {
+                    self.set_pose(&self.pack_rotation().next_preset()).await;
+                    return true;
+                }
+                let slot = self.slot_from_position(hit).await;
+                let slot = if self.is_slot_disabled(&slot) {
+                    EquipmentSlot::MAIN_HAND
+                } else {
+                    slot
+                };
+                return self.has_equipped(&slot).await && self.equip(player, &slot, stack).await;
+            }
+
+            let slot = stack
+                .get_data_component::<EquippableImpl>()
+                .map(|equippable| equippable.slot.clone())
+                .filter(|slot| !matches!(slot, EquipmentSlot::Body(_) | EquipmentSlot::Saddle(_)))
+                .unwrap_or(EquipmentSlot::MAIN_HAND);
+            if self.is_slot_disabled(&slot) {
+                return false;
+            }
+            self.equip(player, &slot, stack).await
+        })
+    }
+
     fn damage_with_context<'a>(
         &'a self,
         caller: &'a dyn EntityBase,
@@ -347,8 +598,8 @@ impl EntityBase for ArmorStandEntity {
             // TODO: <DamageSource>.isIn(DamageTypeTags::BYPASSES_INVULNERABILITY)
 
             if damage_type == DamageType::EXPLOSION {
-                // TODO: Implement Dropping Items that are in the Equipment Slots & entity.kill()
                 self.on_break(entity).await;
+                self.drop_equipment().await;
                 entity.kill(caller).await;
                 //entity.remove().await;
                 return false;
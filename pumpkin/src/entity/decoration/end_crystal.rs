@@ -1,22 +1,43 @@
 use core::f32;
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
 
-use crate::entity::{Entity, EntityBase, EntityBaseFuture, NBTStorage, living::LivingEntity};
+use crate::entity::{
+    Entity, EntityBase, EntityBaseFuture, NBTStorage, NbtFuture, RemovalReason,
+    living::LivingEntity,
+};
+use crossbeam::atomic::AtomicCell;
 use pumpkin_data::{damage::DamageType, meta_data_type::MetaDataType, tracked_data::TrackedData};
+use pumpkin_nbt::{compound::NbtCompound, tag::NbtTag};
 use pumpkin_protocol::java::client::play::Metadata;
-use pumpkin_util::math::vector3::Vector3;
+use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
 
+const EXPLOSION_POWER: f32 = 6.0;
+
+/// An end crystal. Anything hitting it makes it explode, except for other explosions, which
+/// just break it.
 pub struct EndCrystalEntity {
     entity: Entity,
+    pub show_bottom: AtomicBool,
+    beam_target: AtomicCell<Option<BlockPos>>,
 }
 
 impl EndCrystalEntity {
     pub const fn new(entity: Entity) -> Self {
-        Self { entity }
+        Self {
+            entity,
+            show_bottom: AtomicBool::new(true),
+            beam_target: AtomicCell::new(None),
+        }
     }
 }
 
 impl EndCrystalEntity {
+    pub fn shows_bottom(&self) -> bool {
+        self.show_bottom.load(Relaxed)
+    }
+
     pub async fn set_show_bottom(&self, show_bottom: bool) {
+        self.show_bottom.store(show_bottom, Relaxed);
         self.entity
             .send_meta_data(&[Metadata::new(
                 TrackedData::DATA_SHOW_BOTTOM,
@@ -25,11 +46,74 @@ impl EndCrystalEntity {
             )])
             .await;
     }
+
+    pub fn get_beam_target(&self) -> Option<BlockPos> {
+        self.beam_target.load()
+    }
+
+    /// Points the crystal's beam at `target`, or stops the beam.
+    pub async fn set_beam_target(&self, target: Option<BlockPos>) {
+        self.beam_target.store(target);
+        self.entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_BEAM_TARGET,
+                MetaDataType::OptionalBlockPos,
+                target,
+            )])
+            .await;
+    }
 }
 
-impl NBTStorage for EndCrystalEntity {}
+impl NBTStorage for EndCrystalEntity {
+    fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.entity.write_nbt(nbt).await;
+            nbt.put_bool("ShowBottom", self.shows_bottom());
+            if let Some(pos) = self.get_beam_target() {
+                nbt.put(
+                    "beam_target",
+                    NbtTag::IntArray(vec![pos.0.x, pos.0.y, pos.0.z]),
+                );
+            }
+        })
+    }
+
+    fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.entity.read_nbt_non_mut(nbt).await;
+            if let Some(show_bottom) = nbt.get_bool("ShowBottom") {
+                self.show_bottom.store(show_bottom, Relaxed);
+            }
+            if let Some(NbtTag::IntArray(pos)) = nbt.get("beam_target")
+                && pos.len() == 3
+            {
+                self.beam_target
+                    .store(Some(BlockPos::new(pos[0], pos[1], pos[2])));
+            }
+        })
+    }
+}
 
 impl EntityBase for EndCrystalEntity {
+    fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            self.entity
+                .send_meta_data(&[
+                    Metadata::new(
+                        TrackedData::DATA_BEAM_TARGET,
+                        MetaDataType::OptionalBlockPos,
+                        self.get_beam_target(),
+                    ),
+                    Metadata::new(
+                        TrackedData::DATA_SHOW_BOTTOM,
+                        MetaDataType::Boolean,
+                        self.shows_bottom(),
+                    ),
+                ])
+                .await;
+        })
+    }
+
     fn get_entity(&self) -> &Entity {
         &self.entity
     }
@@ -38,6 +122,10 @@ impl EntityBase for EndCrystalEntity {
         None
     }
 
+    fn can_hit(&self) -> bool {
+        true
+    }
+
     fn damage_with_context<'a>(
         &'a self,
         _caller: &'a dyn EntityBase,
@@ -45,19 +133,35 @@ impl EntityBase for EndCrystalEntity {
         damage_type: DamageType,
         _position: Option<Vector3<f64>>,
         _source: Option<&'a dyn EntityBase>,
-        _cause: Option<&'a dyn EntityBase>,
+        cause: Option<&'a dyn EntityBase>,
     ) -> EntityBaseFuture<'a, bool> {
         Box::pin(async move {
-            if damage_type != DamageType::EXPLOSION {
-                self.entity
-                    .world
-                    .load()
-                    .explode(self.entity.pos.load(), 6.0)
-                    .await;
+            // Killed first, so the explosion doesn't hit the crystal again
+            if self
+                .entity
+                .removal_reason
+                .swap(Some(RemovalReason::Killed))
+                .is_some()
+            {
+                return false;
             }
-
-            // TODO
             self.entity.remove().await;
+            let world = self.entity.world.load_full();
+            if damage_type != DamageType::EXPLOSION && damage_type != DamageType::PLAYER_EXPLOSION {
+                world
+                    .explode_with_source(
+                        self.entity.pos.load(),
+                        EXPLOSION_POWER,
+                        Some(self as &dyn EntityBase),
+                        cause,
+                    )
+                    .await;
+            }
+            if let Some(dragon_fight) = &world.dragon_fight {
+                dragon_fight
+                    .on_crystal_destroyed(&world, &self.entity, cause)
+                    .await;
+            }
             true
         })
     }
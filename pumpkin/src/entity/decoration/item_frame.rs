@@ -0,0 +1,320 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::entity::player::Player;
+use crate::entity::{
+    Entity, EntityBase, EntityBaseFuture, NBTStorage, NbtFuture, living::LivingEntity,
+};
+use pumpkin_data::BlockDirection;
+use pumpkin_data::damage::DamageType;
+use pumpkin_data::data_component_impl::MapIdImpl;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::item::Item;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_data::tracked_data::TrackedData;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_protocol::codec::item_stack_seralizer::ItemStackSerializer;
+use pumpkin_protocol::codec::var_int::VarInt;
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::item::ItemStack;
+use pumpkin_world::map::MapFrame;
+use tokio::sync::Mutex;
+
+/// An item frame or glow item frame. The side of the block it hangs on is stored in the entity
+/// data, like for paintings.
+pub struct ItemFrameEntity {
+    entity: Entity,
+    item: Mutex<ItemStack>,
+    /// The rotation of the item in steps of 45 degrees, from 0 to 7.
+    rotation: AtomicU8,
+}
+
+impl ItemFrameEntity {
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            item: Mutex::new(ItemStack::EMPTY.clone()),
+            rotation: AtomicU8::new(0),
+        }
+    }
+
+    /// Sets the side of the block the frame hangs on, before it is spawned.
+    pub fn set_facing(&self, facing: BlockDirection) {
+        self.entity
+            .data
+            .store(i32::from(facing.to_index()), Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn facing(&self) -> BlockDirection {
+        BlockDirection::from_index(self.entity.data.load(Ordering::Relaxed) as u8)
+            .unwrap_or(BlockDirection::South)
+    }
+
+    fn is_glow(&self) -> bool {
+        self.entity.entity_type == &EntityType::GLOW_ITEM_FRAME
+    }
+
+    fn sound(&self, normal: Sound, glow: Sound) -> Sound {
+        if self.is_glow() { glow } else { normal }
+    }
+
+    async fn play_sound(&self, normal: Sound, glow: Sound) {
+        self.entity
+            .world
+            .load()
+            .play_sound(
+                self.sound(normal, glow),
+                SoundCategory::Blocks,
+                &self.entity.pos.load(),
+            )
+            .await;
+    }
+
+    /// The yaw of the frame on maps, like vanilla `ItemFrameEntity::getHorizontalFacing`.
+    fn map_rotation(&self) -> i32 {
+        match self.facing() {
+            BlockDirection::West => 90,
+            BlockDirection::North => 180,
+            BlockDirection::East => 270,
+            _ => 0,
+        }
+    }
+
+    async fn send_item(&self, stack: &ItemStack) {
+        self.entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_ITEM_STACK,
+                MetaDataType::ItemStack,
+                &ItemStackSerializer::from(stack.clone()),
+            )])
+            .await;
+    }
+
+    async fn send_rotation(&self) {
+        self.entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_ROTATION,
+                MetaDataType::Integer,
+                VarInt(i32::from(self.rotation.load(Ordering::Relaxed))),
+            )])
+            .await;
+    }
+
+    /// Marks the frame on the map it holds, or removes the marker of `previous`, and sends the
+    /// map to the players of the world so that they can see it in the frame.
+    async fn update_map(&self, stack: &ItemStack, previous: Option<&ItemStack>) {
+        let world = self.entity.world.load_full();
+        let Some(server) = world.server.upgrade() else {
+            return;
+        };
+        if let Some(map_id) =
+            previous.and_then(|previous| previous.get_data_component::<MapIdImpl>())
+            && let Some(map) = server.maps.get(map_id.id).await
+        {
+            map.lock().await.remove_frame(self.entity.entity_id);
+        }
+        let Some(map_id) = stack.get_data_component::<MapIdImpl>() else {
+            return;
+        };
+        let Some(map) = server.maps.get(map_id.id).await else {
+            return;
+        };
+        map.lock().await.set_frame(MapFrame {
+            pos: BlockPos::floored_v(self.entity.pos.load()),
+            rotation: self.map_rotation() + i32::from(self.rotation.load(Ordering::Relaxed)) * 45,
+            entity_id: self.entity.entity_id,
+        });
+        for player in world.players.load().iter() {
+            server.maps.send_full(player, map_id.id).await;
+        }
+    }
+
+    /// Drops the held item, or the frame itself if it is empty. Players in creative mode don't
+    /// get any drops.
+    async fn break_frame(&self, drops: bool) {
+        let world = self.entity.world.load_full();
+        let held = std::mem::replace(&mut *self.item.lock().await, ItemStack::EMPTY.clone());
+        let pos = BlockPos::floored_v(self.entity.pos.load());
+        if held.is_empty() {
+            self.play_sound(Sound::EntityItemFrameBreak, Sound::EntityGlowItemFrameBreak)
+                .await;
+            if drops {
+                let frame = if self.is_glow() {
+                    &Item::GLOW_ITEM_FRAME
+                } else {
+                    &Item::ITEM_FRAME
+                };
+                world.drop_stack(&pos, ItemStack::new(1, frame)).await;
+            }
+            self.entity.remove().await;
+            return;
+        }
+
+        self.play_sound(
+            Sound::EntityItemFrameRemoveItem,
+            Sound::EntityGlowItemFrameRemoveItem,
+        )
+        .await;
+        self.rotation.store(0, Ordering::Relaxed);
+        self.send_item(ItemStack::EMPTY).await;
+        self.send_rotation().await;
+        self.update_map(ItemStack::EMPTY, Some(&held)).await;
+        if drops {
+            world.drop_stack(&pos, held).await;
+        }
+    }
+}
+
+impl NBTStorage for ItemFrameEntity {
+    fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.entity.write_nbt(nbt).await;
+            nbt.put_byte("Facing", self.entity.data.load(Ordering::Relaxed) as i8);
+            nbt.put_byte("ItemRotation", self.rotation.load(Ordering::Relaxed) as i8);
+            let item = self.item.lock().await;
+            if !item.is_empty() {
+                let mut item_compound = NbtCompound::new();
+                item.write_item_stack(&mut item_compound);
+                nbt.put_component("Item", item_compound);
+            }
+        })
+    }
+
+    fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async {
+            self.entity.read_nbt_non_mut(nbt).await;
+            if let Some(facing) = nbt.get_byte("Facing") {
+                self.entity.data.store(i32::from(facing), Ordering::Relaxed);
+            }
+            if let Some(rotation) = nbt.get_byte("ItemRotation") {
+                self.rotation.store(rotation as u8 % 8, Ordering::Relaxed);
+            }
+            if let Some(item) = nbt
+                .get_compound("Item")
+                .and_then(ItemStack::read_item_stack)
+            {
+                *self.item.lock().await = item;
+            }
+        })
+    }
+}
+
+impl EntityBase for ItemFrameEntity {
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&LivingEntity> {
+        None
+    }
+
+    fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async {
+            let item = self.item.lock().await.clone();
+            if item.is_empty() {
+                return;
+            }
+            self.send_item(&item).await;
+            self.send_rotation().await;
+            self.update_map(&item, None).await;
+        })
+    }
+
+    fn can_hit(&self) -> bool {
+        true
+    }
+
+    fn damage_with_context<'a>(
+        &'a self,
+        _caller: &'a dyn EntityBase,
+        _amount: f32,
+        damage_type: DamageType,
+        _position: Option<Vector3<f64>>,
+        source: Option<&'a dyn EntityBase>,
+        _cause: Option<&'a dyn EntityBase>,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async move {
+            let creative = source
+                .and_then(|source| source.get_player())
+                .is_some_and(Player::is_creative);
+            if damage_type == DamageType::EXPLOSION && !self.item.lock().await.is_empty() {
+                // Explosions break the frame together with its item
+                self.break_frame(true).await;
+            }
+            self.break_frame(!creative).await;
+            true
+        })
+    }
+
+    fn interact<'a>(
+        &'a self,
+        player: &'a Player,
+        stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async move {
+            let mut item = self.item.lock().await;
+            if item.is_empty() {
+                if stack.is_empty() {
+                    return false;
+                }
+                *item = stack.copy_with_count(1);
+                let inserted = item.clone();
+                drop(item);
+                stack.decrement_unless_creative(player.gamemode.load(), 1);
+                self.play_sound(
+                    Sound::EntityItemFrameAddItem,
+                    Sound::EntityGlowItemFrameAddItem,
+                )
+                .await;
+                self.send_item(&inserted).await;
+                self.update_map(&inserted, None).await;
+                return true;
+            }
+
+            let held = item.clone();
+            drop(item);
+            let rotation = (self.rotation.load(Ordering::Relaxed) + 1) % 8;
+            self.rotation.store(rotation, Ordering::Relaxed);
+            self.play_sound(
+                Sound::EntityItemFrameRotateItem,
+                Sound::EntityGlowItemFrameRotateItem,
+            )
+            .await;
+            self.send_rotation().await;
+            self.update_map(&held, None).await;
+            true
+        })
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+}
+
+/// Spawns an item frame of `entity_type` on the `face` of the block at `location`.
+pub async fn place(
+    player: &Player,
+    entity_type: &'static EntityType,
+    location: BlockPos,
+    face: BlockDirection,
+) {
+    let world = player.world();
+    let offset = face.to_offset();
+    let pos = location.offset(offset);
+    let position = Vector3::new(
+        f64::from(pos.0.x) + 0.5 - f64::from(offset.x) * 0.468_75,
+        f64::from(pos.0.y) + 0.5 - f64::from(offset.y) * 0.468_75,
+        f64::from(pos.0.z) + 0.5 - f64::from(offset.z) * 0.468_75,
+    );
+    let entity = Entity::new(world.clone(), position, entity_type);
+    let frame = Arc::new(ItemFrameEntity::new(entity));
+    frame.set_facing(face);
+    frame
+        .play_sound(Sound::EntityItemFramePlace, Sound::EntityGlowItemFramePlace)
+        .await;
+    world.spawn_entity(frame).await;
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use crate::entity::leash::{self, LeashHolder};
+use crate::entity::mob::Mob;
+use crate::entity::player::Player;
+use crate::entity::{Entity, EntityBase, EntityBaseFuture, NBTStorage, living::LivingEntity};
+use crate::server::Server;
+use crate::world::World;
+use pumpkin_data::damage::DamageType;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_data::tag::{self, Taggable};
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::item::ItemStack;
+
+/// The knot a lead makes when mobs are tied to a fence. Knots are not saved, the mobs remember
+/// the fence and tie the knot again when they are loaded.
+pub struct LeashKnotEntity {
+    entity: Entity,
+}
+
+impl LeashKnotEntity {
+    pub const fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+
+    /// The knot on the fence at `pos`, spawning it if there is none yet.
+    pub async fn get_or_create(world: &Arc<World>, pos: BlockPos) -> Arc<dyn EntityBase> {
+        if let Some(knot) = world.entities.load().iter().find(|entity| {
+            let entity = entity.get_entity();
+            entity.entity_type == &EntityType::LEASH_KNOT
+                && !entity.is_removed()
+                && BlockPos::floored_v(entity.pos.load()) == pos
+        }) {
+            return knot.clone();
+        }
+
+        let position = Vector3::new(
+            f64::from(pos.0.x) + 0.5,
+            f64::from(pos.0.y) + 0.375,
+            f64::from(pos.0.z) + 0.5,
+        );
+        let knot: Arc<dyn EntityBase> = Arc::new(Self::new(Entity::new(
+            world.clone(),
+            position,
+            &EntityType::LEASH_KNOT,
+        )));
+        world.spawn_entity(knot.clone()).await;
+        knot
+    }
+
+    /// The position of the fence the knot is tied to.
+    fn fence(&self) -> BlockPos {
+        BlockPos::floored_v(self.entity.pos.load())
+    }
+
+    /// Unties all mobs from the knot and removes it.
+    async fn untie(&self, drops: bool) {
+        let world = self.entity.world.load_full();
+        for mob in leash::leashed_to(&world, LeashHolder::Knot(self.fence()), None).await {
+            if let Some(mob) = mob.get_mob() {
+                mob.get_mob_entity().detach_leash(drops).await;
+            }
+        }
+        world
+            .play_sound(
+                Sound::ItemLeadUntied,
+                SoundCategory::Blocks,
+                &self.entity.pos.load(),
+            )
+            .await;
+        self.entity.remove().await;
+    }
+}
+
+impl NBTStorage for LeashKnotEntity {}
+
+impl EntityBase for LeashKnotEntity {
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&LivingEntity> {
+        None
+    }
+
+    fn tick<'a>(
+        &'a self,
+        _caller: Arc<dyn EntityBase>,
+        _server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            if self.entity.is_removed() {
+                return;
+            }
+            let world = self.entity.world.load();
+            if !world
+                .get_block(&self.fence())
+                .await
+                .has_tag(&tag::Block::MINECRAFT_FENCES)
+            {
+                self.untie(true).await;
+            }
+        })
+    }
+
+    fn can_hit(&self) -> bool {
+        true
+    }
+
+    fn damage_with_context<'a>(
+        &'a self,
+        _caller: &'a dyn EntityBase,
+        _amount: f32,
+        _damage_type: DamageType,
+        _position: Option<Vector3<f64>>,
+        source: Option<&'a dyn EntityBase>,
+        _cause: Option<&'a dyn EntityBase>,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async move {
+            let creative = source
+                .and_then(|source| source.get_player())
+                .is_some_and(Player::is_creative);
+            self.untie(!creative).await;
+            true
+        })
+    }
+
+    fn interact<'a>(
+        &'a self,
+        player: &'a Player,
+        _stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async move {
+            let world = self.entity.world.load_full();
+            if !leash::attach_held_mobs_to_fence(&world, player, self.fence()).await {
+                // Without mobs to tie, the player unties the knot
+                self.untie(!player.is_creative()).await;
+            }
+            true
+        })
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+}
@@ -1,3 +1,5 @@
 pub mod armor_stand;
 pub mod end_crystal;
+pub mod item_frame;
+pub mod leash_knot;
 pub mod painting;
@@ -20,15 +20,19 @@ impl PaintingEntity {
 
 impl NBTStorage for PaintingEntity {
     fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
-        Box::pin(async {
+        Box::pin(async move {
+            self.entity.write_nbt(nbt).await;
             nbt.put_byte("facing", self.entity.data.load(Ordering::Relaxed) as i8);
         })
     }
 
-    fn read_nbt_non_mut<'a>(&'a self, _nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+    fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
         Box::pin(async {
-            // TODO
-            self.entity.data.store(3, Ordering::Relaxed);
+            self.entity.read_nbt_non_mut(nbt).await;
+            self.entity.data.store(
+                i32::from(nbt.get_byte("facing").unwrap_or(3)),
+                Ordering::Relaxed,
+            );
         })
     }
 }
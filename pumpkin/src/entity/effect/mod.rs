@@ -1,9 +1,13 @@
-use crate::entity::{NBTInitFuture, NBTStorage, NBTStorageInit, NbtFuture};
+use crate::entity::{EntityBase, NBTInitFuture, NBTStorage, NBTStorageInit, NbtFuture};
+use pumpkin_data::damage::DamageType;
+use pumpkin_data::data_component_impl::{PotionContentsImpl, StatusEffectInstance};
 use pumpkin_data::effect::StatusEffect;
+use pumpkin_data::potion::{Effect, Potion};
+use pumpkin_data::tag::{self, Taggable};
 use pumpkin_nbt::compound::NbtCompound;
 use pumpkin_nbt::tag::NbtTag;
 
-impl NBTStorage for pumpkin_data::potion::Effect {
+impl NBTStorage for Effect {
     fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
         Box::pin(async {
             nbt.put("id", self.effect_type.minecraft_name);
@@ -23,7 +27,7 @@ impl NBTStorage for pumpkin_data::potion::Effect {
     }
 }
 
-impl NBTStorageInit for pumpkin_data::potion::Effect {
+impl NBTStorageInit for Effect {
     fn create_from_nbt<'a>(nbt: &'a mut NbtCompound) -> NBTInitFuture<'a, Self>
     where
         Self: 'a,
@@ -58,3 +62,269 @@ impl NBTStorageInit for pumpkin_data::potion::Effect {
         })
     }
 }
+
+/// The color potions without effects have.
+pub const DEFAULT_POTION_COLOR: i32 = -13_083_194;
+
+/// The effects of potion contents: those of the base potion, then the custom ones.
+#[must_use]
+pub fn potion_effects(contents: &PotionContentsImpl) -> Vec<Effect> {
+    let mut effects: Vec<Effect> = contents
+        .potion_id
+        .and_then(|id| u8::try_from(id).ok())
+        .and_then(Potion::from_id)
+        .map(|potion| potion.effects.to_vec())
+        .unwrap_or_default();
+    for instance in &contents.custom_effects {
+        let Some(effect_type) = u8::try_from(instance.effect_id)
+            .ok()
+            .and_then(StatusEffect::from_id)
+        else {
+            continue;
+        };
+        effects.push(Effect {
+            effect_type,
+            duration: instance.duration,
+            amplifier: instance.amplifier.clamp(0, 255) as u8,
+            ambient: instance.ambient,
+            show_particles: instance.show_particles,
+            show_icon: instance.show_icon,
+            blend: false,
+        });
+    }
+    effects
+}
+
+/// The opaque ARGB color of potion contents. Without a custom color, the colors of the effects
+/// that show particles are mixed, stronger effects weighing more.
+#[must_use]
+pub fn potion_color(contents: &PotionContentsImpl) -> i32 {
+    if let Some(color) = contents.custom_color {
+        return color;
+    }
+    let (mut red, mut green, mut blue, mut weight) = (0, 0, 0, 0);
+    for effect in potion_effects(contents) {
+        if !effect.show_particles {
+            continue;
+        }
+        let color = effect.effect_type.color;
+        let amount = i32::from(effect.amplifier) + 1;
+        red += amount * ((color >> 16) & 0xFF);
+        green += amount * ((color >> 8) & 0xFF);
+        blue += amount * (color & 0xFF);
+        weight += amount;
+    }
+    if weight == 0 {
+        return DEFAULT_POTION_COLOR;
+    }
+    i32::from_be_bytes([
+        0xFF,
+        (red / weight) as u8,
+        (green / weight) as u8,
+        (blue / weight) as u8,
+    ])
+}
+
+/// Reads potion contents saved as `potion_contents`. Potions and effects may be given by id or
+/// by name.
+#[must_use]
+pub fn potion_contents_from_nbt(nbt: &NbtCompound) -> PotionContentsImpl {
+    let potion_id = match nbt.get("potion") {
+        Some(NbtTag::Int(id)) => Some(*id),
+        Some(NbtTag::String(name)) => {
+            Potion::from_name(name.strip_prefix("minecraft:").unwrap_or(name))
+                .map(|potion| i32::from(potion.id))
+        }
+        _ => None,
+    };
+    let custom_effects = nbt
+        .get_list("custom_effects")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(NbtTag::extract_compound)
+        .filter_map(|effect| {
+            let effect_id = match effect.get("id")? {
+                NbtTag::Int(id) => *id,
+                NbtTag::String(name) => i32::from(StatusEffect::from_minecraft_name(name)?.id),
+                _ => return None,
+            };
+            Some(StatusEffectInstance {
+                effect_id,
+                amplifier: effect.get_int("amplifier").unwrap_or(0),
+                duration: effect.get_int("duration").unwrap_or(0),
+                ambient: effect.get_bool("ambient").unwrap_or(false),
+                show_particles: effect.get_bool("show_particles").unwrap_or(true),
+                show_icon: effect.get_bool("show_icon").unwrap_or(true),
+            })
+        })
+        .collect();
+    PotionContentsImpl {
+        potion_id,
+        custom_color: nbt.get_int("custom_color"),
+        custom_effects,
+        custom_name: nbt.get_string("custom_name").map(str::to_string),
+    }
+}
+
+/// Gives `target` an effect from a potion. Instant health and damage take effect at once,
+/// `strength` scaling how much, and work the other way round on the undead. Other effects are
+/// added unless the target already has a stronger or longer one.
+///
+/// `source` is what carried the effect, like a thrown potion or an area effect cloud, and `cause`
+/// who is behind it.
+pub async fn apply_effect(
+    target: &dyn EntityBase,
+    effect: Effect,
+    strength: f64,
+    source: Option<&dyn EntityBase>,
+    cause: Option<&dyn EntityBase>,
+) {
+    let Some(living) = target.get_living_entity() else {
+        return;
+    };
+    let effect_type = effect.effect_type;
+    if effect_type == &StatusEffect::INSTANT_HEALTH || effect_type == &StatusEffect::INSTANT_DAMAGE
+    {
+        let inverted = living
+            .entity
+            .entity_type
+            .has_tag(&tag::EntityType::MINECRAFT_INVERTED_HEALING_AND_HARM);
+        if (effect_type == &StatusEffect::INSTANT_DAMAGE) == inverted {
+            let amount = (strength * f64::from(4 << effect.amplifier.min(16)) + 0.5) as i32;
+            if amount > 0 {
+                if let Some(player) = target.get_player() {
+                    player.heal(amount as f32).await;
+                } else {
+                    living.heal(amount as f32).await;
+                }
+            }
+        } else {
+            let amount = (strength * f64::from(6 << effect.amplifier.min(16)) + 0.5) as i32;
+            let damage_type = if source.is_some() {
+                DamageType::INDIRECT_MAGIC
+            } else {
+                DamageType::MAGIC
+            };
+            target
+                .damage_with_context(target, amount as f32, damage_type, None, source, cause)
+                .await;
+        }
+        return;
+    }
+
+    if let Some(current) = living.get_effect(effect_type).await
+        && (current.amplifier > effect.amplifier
+            || (current.amplifier == effect.amplifier
+                && (current.duration == -1 || current.duration >= effect.duration)))
+    {
+        return;
+    }
+    if let Some(player) = target.get_player() {
+        player.add_effect(effect).await;
+    } else {
+        living.add_effect(effect).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_data::data_component_impl::DataComponentImpl;
+
+    use super::*;
+
+    fn contents(
+        potion: Option<&Potion>,
+        custom_effects: Vec<StatusEffectInstance>,
+    ) -> PotionContentsImpl {
+        PotionContentsImpl {
+            potion_id: potion.map(|potion| i32::from(potion.id)),
+            custom_color: None,
+            custom_effects,
+            custom_name: None,
+        }
+    }
+
+    fn regeneration(amplifier: i32, show_particles: bool) -> StatusEffectInstance {
+        StatusEffectInstance {
+            effect_id: i32::from(StatusEffect::REGENERATION.id),
+            amplifier,
+            duration: 100,
+            ambient: false,
+            show_particles,
+            show_icon: true,
+        }
+    }
+
+    #[test]
+    fn base_potion_effects_come_first() {
+        let effects = potion_effects(&contents(
+            Some(&Potion::SWIFTNESS),
+            vec![regeneration(300, true)],
+        ));
+        assert_eq!(effects.len(), 2);
+        assert!(effects[0].effect_type == &StatusEffect::SPEED);
+        assert_eq!(effects[0].duration, 3600);
+        assert!(effects[1].effect_type == &StatusEffect::REGENERATION);
+        assert_eq!(effects[1].amplifier, 255);
+    }
+
+    #[test]
+    fn unknown_potions_and_effects_are_skipped() {
+        let mut unknown = regeneration(0, true);
+        unknown.effect_id = 1000;
+        let mut contents = contents(None, vec![unknown]);
+        contents.potion_id = Some(-1);
+        assert!(potion_effects(&contents).is_empty());
+    }
+
+    #[test]
+    fn potion_color_mixes_the_visible_effects() {
+        let speed = StatusEffect::SPEED.color | i32::from_be_bytes([0xFF, 0, 0, 0]);
+        assert_eq!(
+            potion_color(&contents(None, Vec::new())),
+            DEFAULT_POTION_COLOR
+        );
+        assert_eq!(
+            potion_color(&contents(Some(&Potion::SWIFTNESS), Vec::new())),
+            speed
+        );
+        // Hidden effects don't change the color
+        assert_eq!(
+            potion_color(&contents(
+                Some(&Potion::SWIFTNESS),
+                vec![regeneration(0, false)]
+            )),
+            speed
+        );
+
+        let mut custom = contents(Some(&Potion::SWIFTNESS), Vec::new());
+        custom.custom_color = Some(0x12_3456);
+        assert_eq!(potion_color(&custom), 0x12_3456);
+    }
+
+    #[test]
+    fn potion_contents_survive_a_save_and_load() {
+        let mut contents = contents(Some(&Potion::SWIFTNESS), vec![regeneration(2, false)]);
+        contents.custom_color = Some(0x12_3456);
+        contents.custom_name = Some("test".to_string());
+        let NbtTag::Compound(nbt) = contents.write_data() else {
+            panic!("potion contents are saved as a compound");
+        };
+        assert_eq!(potion_contents_from_nbt(&nbt), contents);
+    }
+
+    #[test]
+    fn potion_contents_are_read_by_name() {
+        let mut effect = NbtCompound::new();
+        effect.put_string("id", "minecraft:regeneration".to_string());
+        effect.put_int("duration", 100);
+        let mut nbt = NbtCompound::new();
+        nbt.put_string("potion", "minecraft:swiftness".to_string());
+        nbt.put_list("custom_effects", vec![NbtTag::Compound(effect)]);
+
+        assert_eq!(
+            potion_contents_from_nbt(&nbt),
+            contents(Some(&Potion::SWIFTNESS), vec![regeneration(0, true)])
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Titles, action bars and sounds shown to players.
+//!
+//! The builders here are sent to a single player with the matching [`Player`] methods, or to
+//! many players at once with their `send_to`/`play_to` methods.
+
+use std::sync::Arc;
+
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_protocol::java::client::play::{
+    CActionBar, CSoundEffect, CStopSound, CSubtitle, CTitleAnimation, CTitleText,
+};
+use pumpkin_protocol::{IdOr, SoundEvent};
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_util::resource_location::ResourceLocation;
+use pumpkin_util::text::TextComponent;
+use rand::{RngExt, rng};
+
+use crate::entity::player::Player;
+
+/// How long a title fades in, stays and fades out, in ticks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TitleTimes {
+    pub fade_in: i32,
+    pub stay: i32,
+    pub fade_out: i32,
+}
+
+impl TitleTimes {
+    /// The times clients use when none were sent.
+    pub const DEFAULT: Self = Self::new(10, 70, 20);
+
+    #[must_use]
+    pub const fn new(fade_in: i32, stay: i32, fade_out: i32) -> Self {
+        Self {
+            fade_in,
+            stay,
+            fade_out,
+        }
+    }
+}
+
+/// A title with an optional subtitle and times. Any part may be left out, e.g. to only change
+/// the subtitle of the next title like `/title subtitle`.
+#[derive(Clone, Default)]
+pub struct Title {
+    title: Option<TextComponent>,
+    subtitle: Option<TextComponent>,
+    times: Option<TitleTimes>,
+}
+
+impl Title {
+    #[must_use]
+    pub fn new(title: TextComponent) -> Self {
+        Self {
+            title: Some(title),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn subtitle(mut self, subtitle: TextComponent) -> Self {
+        self.subtitle = Some(subtitle);
+        self
+    }
+
+    #[must_use]
+    pub const fn times(mut self, times: TitleTimes) -> Self {
+        self.times = Some(times);
+        self
+    }
+
+    /// Sends the title to `player`. The times and subtitle go first, as the client shows the
+    /// title as soon as it arrives.
+    pub(crate) async fn send(&self, player: &Player) {
+        if let Some(times) = self.times {
+            player
+                .client
+                .enqueue_packet(&CTitleAnimation::new(
+                    times.fade_in,
+                    times.stay,
+                    times.fade_out,
+                ))
+                .await;
+        }
+        if let Some(subtitle) = &self.subtitle {
+            player
+                .client
+                .enqueue_packet(&CSubtitle::new(subtitle))
+                .await;
+        }
+        if let Some(title) = &self.title {
+            player.client.enqueue_packet(&CTitleText::new(title)).await;
+        }
+    }
+
+    pub async fn send_to(&self, players: &[Arc<Player>]) {
+        for player in players {
+            self.send(player).await;
+        }
+    }
+}
+
+/// Shows `text` above the hotbar of all `players`.
+pub async fn send_action_bar_to(players: &[Arc<Player>], text: &TextComponent) {
+    let packet = CActionBar::new(text);
+    for player in players {
+        player.client.enqueue_packet(&packet).await;
+    }
+}
+
+/// A vanilla sound, or a custom one that clients find in the server resource pack, e.g.
+/// `myplugin:boss.roar`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SoundId {
+    Vanilla(Sound),
+    Custom {
+        name: ResourceLocation,
+        /// How far away the sound can be heard. Clients work it out from the volume if not set.
+        range: Option<f32>,
+    },
+}
+
+impl SoundId {
+    /// Looks up a sound by name like `/playsound` does. Names of vanilla sounds, with or without
+    /// the `minecraft` namespace, are vanilla sounds. Everything else is custom.
+    #[must_use]
+    pub fn from_name(name: &str) -> Self {
+        Sound::from_name(name.strip_prefix("minecraft:").unwrap_or(name)).map_or_else(
+            || {
+                let name = if name.contains(':') {
+                    name.to_string()
+                } else {
+                    format!("minecraft:{name}")
+                };
+                Self::custom(name)
+            },
+            Self::Vanilla,
+        )
+    }
+
+    #[must_use]
+    pub fn custom(name: impl Into<ResourceLocation>) -> Self {
+        Self::Custom {
+            name: name.into(),
+            range: None,
+        }
+    }
+
+    /// The full name of the sound, e.g. `minecraft:entity.zombie.hurt`.
+    #[must_use]
+    pub fn name(&self) -> ResourceLocation {
+        match self {
+            Self::Vanilla(sound) => format!("minecraft:{}", sound.to_name()),
+            Self::Custom { name, .. } => name.clone(),
+        }
+    }
+
+    fn to_protocol(&self) -> IdOr<SoundEvent> {
+        match self {
+            Self::Vanilla(sound) => IdOr::Id(*sound as u16),
+            Self::Custom { name, range } => IdOr::Value(SoundEvent {
+                sound_name: name.clone(),
+                range: *range,
+            }),
+        }
+    }
+}
+
+impl From<Sound> for SoundId {
+    fn from(sound: Sound) -> Self {
+        Self::Vanilla(sound)
+    }
+}
+
+/// A sound played to players. Without a position it plays where each player is.
+#[derive(Clone)]
+pub struct SoundEffect {
+    sound: SoundId,
+    category: SoundCategory,
+    position: Option<Vector3<f64>>,
+    volume: f32,
+    pitch: f32,
+    seed: Option<f64>,
+}
+
+impl SoundEffect {
+    #[must_use]
+    pub fn new(sound: impl Into<SoundId>) -> Self {
+        Self {
+            sound: sound.into(),
+            category: SoundCategory::Master,
+            position: None,
+            volume: 1.0,
+            pitch: 1.0,
+            seed: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn category(mut self, category: SoundCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    #[must_use]
+    pub const fn at(mut self, position: Vector3<f64>) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    #[must_use]
+    pub const fn volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    #[must_use]
+    pub const fn pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
+
+    /// The seed of the variant of the sound the clients pick. Random if not set.
+    #[must_use]
+    pub const fn seed(mut self, seed: f64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    fn packet(&self, player: &Player, seed: f64) -> CSoundEffect {
+        CSoundEffect::new(
+            self.sound.to_protocol(),
+            self.category,
+            &self.position.unwrap_or_else(|| player.position()),
+            self.volume,
+            self.pitch,
+            seed,
+        )
+    }
+
+    pub(crate) async fn play(&self, player: &Player) {
+        let seed = self.seed.unwrap_or_else(|| rng().random());
+        player
+            .client
+            .enqueue_packet(&self.packet(player, seed))
+            .await;
+    }
+
+    /// Plays the sound to all `players`. They all hear the same variant of it.
+    pub async fn play_to(&self, players: &[Arc<Player>]) {
+        let seed = self.seed.unwrap_or_else(|| rng().random());
+        for player in players {
+            player
+                .client
+                .enqueue_packet(&self.packet(player, seed))
+                .await;
+        }
+    }
+}
+
+/// Which sounds to stop. Stops all sounds unless narrowed down to a sound or category.
+#[derive(Clone, Default)]
+pub struct StopSound {
+    sound: Option<ResourceLocation>,
+    category: Option<SoundCategory>,
+}
+
+impl StopSound {
+    #[must_use]
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Only stops `sound`, e.g. `minecraft:music.game`.
+    #[must_use]
+    pub fn sound(mut self, sound: ResourceLocation) -> Self {
+        self.sound = Some(sound);
+        self
+    }
+
+    #[must_use]
+    pub const fn category(mut self, category: SoundCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    fn packet(&self) -> CStopSound {
+        CStopSound::new(self.sound.clone(), self.category)
+    }
+
+    pub(crate) async fn send(&self, player: &Player) {
+        player.client.enqueue_packet(&self.packet()).await;
+    }
+
+    pub async fn send_to(&self, players: &[Arc<Player>]) {
+        let packet = self.packet();
+        for player in players {
+            player.client.enqueue_packet(&packet).await;
+        }
+    }
+}
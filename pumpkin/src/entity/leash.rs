@@ -0,0 +1,171 @@
+//! Leads that tie mobs to players, other mobs or leash knots on fences.
+
+use std::sync::Arc;
+
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_data::tag::{self, Taggable};
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::player_data::{uuid_from_int_array, uuid_to_nbt};
+use pumpkin_nbt::tag::NbtTag;
+use pumpkin_util::math::boundingbox::BoundingBox;
+use pumpkin_util::math::position::BlockPos;
+use uuid::Uuid;
+
+use crate::entity::EntityBase;
+use crate::entity::decoration::leash_knot::LeashKnotEntity;
+use crate::entity::mob::Mob;
+use crate::entity::player::Player;
+use crate::world::World;
+
+/// Leashed mobs are pulled towards their holder from this distance on.
+pub const PULL_DISTANCE: f64 = 6.0;
+/// The lead breaks when its mob gets further away from the holder than this.
+pub const BREAK_DISTANCE: f64 = 10.0;
+/// How far away the mobs a player leads may be to be tied to a fence.
+const FENCE_RANGE: f64 = 7.0;
+/// How long the holder of a loaded mob may be missing before the lead drops, e.g. because the
+/// player is still joining.
+pub const MISSING_HOLDER_TICKS: u32 = 100;
+
+/// What a leashed mob is tied to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LeashHolder {
+    /// A player or another mob.
+    Entity(Uuid),
+    /// The leash knot on the fence at this position.
+    Knot(BlockPos),
+}
+
+impl LeashHolder {
+    /// The holder for `entity`, which is a knot if it is a leash knot.
+    #[must_use]
+    pub fn of(entity: &dyn EntityBase) -> Self {
+        let base = entity.get_entity();
+        if base.entity_type == &EntityType::LEASH_KNOT {
+            Self::Knot(BlockPos::floored_v(base.pos.load()))
+        } else {
+            Self::Entity(base.entity_uuid)
+        }
+    }
+
+    /// Writes the holder to the `leash` tag like vanilla: a compound with the UUID of an entity
+    /// or the position of a knot.
+    pub fn write_nbt(self, nbt: &mut NbtCompound) {
+        match self {
+            Self::Entity(uuid) => {
+                let mut leash = NbtCompound::new();
+                leash.put("UUID", uuid_to_nbt(uuid.as_u128()));
+                nbt.put_component("leash", leash);
+            }
+            Self::Knot(pos) => {
+                nbt.put("leash", NbtTag::IntArray(vec![pos.0.x, pos.0.y, pos.0.z]));
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn read_nbt(nbt: &NbtCompound) -> Option<Self> {
+        match nbt.get("leash")? {
+            NbtTag::IntArray(pos) if pos.len() == 3 => {
+                Some(Self::Knot(BlockPos::new(pos[0], pos[1], pos[2])))
+            }
+            NbtTag::Compound(leash) => leash
+                .get_int_array("UUID")
+                .and_then(uuid_from_int_array)
+                .map(|uuid| Self::Entity(Uuid::from_u128(uuid))),
+            _ => None,
+        }
+    }
+
+    /// Finds the holder in `world`. Knots are created again if their fence is still there, as
+    /// they are not saved themselves.
+    pub async fn find(self, world: &Arc<World>) -> Option<Arc<dyn EntityBase>> {
+        match self {
+            Self::Entity(uuid) => world.get_entity_by_uuid(uuid),
+            Self::Knot(pos) => {
+                if world
+                    .get_block(&pos)
+                    .await
+                    .has_tag(&tag::Block::MINECRAFT_FENCES)
+                {
+                    Some(LeashKnotEntity::get_or_create(world, pos).await)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// The lead of a mob.
+pub struct Leash {
+    pub holder: LeashHolder,
+    /// The entity id of the holder once it was found, for the link packet.
+    pub holder_id: Option<i32>,
+    /// For how many ticks the holder could not be found.
+    pub missing_ticks: u32,
+}
+
+impl Leash {
+    #[must_use]
+    pub const fn new(holder: LeashHolder) -> Self {
+        Self {
+            holder,
+            holder_id: None,
+            missing_ticks: 0,
+        }
+    }
+}
+
+/// The mobs in `world` tied to `holder`.
+pub async fn leashed_to(
+    world: &World,
+    holder: LeashHolder,
+    area: Option<BoundingBox>,
+) -> Vec<Arc<dyn EntityBase>> {
+    let entities = area.map_or_else(
+        || world.entities.load().iter().cloned().collect(),
+        |area| world.get_entities_at_box(&area),
+    );
+    let mut leashed = Vec::new();
+    for entity in entities {
+        let Some(mob) = entity.clone().get_mob() else {
+            continue;
+        };
+        if mob.get_mob_entity().leash_holder().await == Some(holder) {
+            leashed.push(entity);
+        }
+    }
+    leashed
+}
+
+/// Ties the mobs `player` leads around the fence at `pos` to a knot on it, like vanilla
+/// `LeadItem::attachHeldMobsToBlock`. Returns whether there were any.
+pub async fn attach_held_mobs_to_fence(world: &Arc<World>, player: &Player, pos: BlockPos) -> bool {
+    let area = BoundingBox::from_block(&pos).expand_all(FENCE_RANGE);
+    let mobs = leashed_to(
+        world,
+        LeashHolder::Entity(player.gameprofile.id),
+        Some(area),
+    )
+    .await;
+    if mobs.is_empty() {
+        return false;
+    }
+
+    let knot = LeashKnotEntity::get_or_create(world, pos).await;
+    for mob in mobs {
+        if let Some(mob) = mob.get_mob() {
+            mob.get_mob_entity().attach_leash(&*knot).await;
+        }
+    }
+    world
+        .play_sound(
+            Sound::ItemLeadTied,
+            SoundCategory::Blocks,
+            &knot.get_entity().pos.load(),
+        )
+        .await;
+    true
+}
@@ -0,0 +1,152 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicI32, Ordering::Relaxed},
+};
+
+use pumpkin_util::{
+    Difficulty,
+    math::{boundingbox::BoundingBox, position::BlockPos, vector3::Vector3},
+};
+use pumpkin_world::world::BlockFlags;
+use rand::RngExt;
+use tokio::sync::Mutex;
+
+use super::{Entity, EntityBase, EntityBaseFuture, NBTStorage, living::LivingEntity};
+use crate::{block::blocks::fire::FireBlockBase, server::Server, world::World};
+
+/// A lightning bolt. It strikes the entities around it and sets fire to the ground, unless it is
+/// only cosmetic. The thunder and the flash are played by the clients when the bolt spawns.
+pub struct LightningEntity {
+    entity: Entity,
+    /// Ticks until the current flash ends. The bolt strikes while it is not negative.
+    life: AtomicI32,
+    /// Flashes left after the current one.
+    flashes: AtomicI32,
+    cosmetic: bool,
+    /// Entities that were struck by an earlier flash of this bolt.
+    struck: Mutex<Vec<i32>>,
+}
+
+impl LightningEntity {
+    #[must_use]
+    pub fn new(entity: Entity, cosmetic: bool) -> Self {
+        Self {
+            entity,
+            life: AtomicI32::new(2),
+            flashes: AtomicI32::new(rand::rng().random_range(1..=3)),
+            cosmetic,
+            struck: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Places fire where the bolt struck and at `spread` random blocks around it.
+    async fn spawn_fire(&self, world: &Arc<World>, spread: usize) {
+        if self.cosmetic
+            || world
+                .level_info
+                .load()
+                .game_rules
+                .fire_spread_radius_around_player
+                <= 0
+        {
+            return;
+        }
+        let center = self.entity.block_pos.load();
+        Self::place_fire(world, &center).await;
+        for _ in 0..spread {
+            let offset = {
+                let mut rng = rand::rng();
+                Vector3::new(
+                    rng.random_range(-1..=1),
+                    rng.random_range(-1..=1),
+                    rng.random_range(-1..=1),
+                )
+            };
+            Self::place_fire(world, &BlockPos(center.0.add(&offset))).await;
+        }
+    }
+
+    async fn place_fire(world: &Arc<World>, pos: &BlockPos) {
+        if FireBlockBase::can_place_at(world, pos).await {
+            let fire = FireBlockBase::get_fire_type(world, pos).await;
+            world
+                .set_block_state(pos, fire.default_state.id, BlockFlags::NOTIFY_ALL)
+                .await;
+        }
+    }
+
+    /// Strikes the entities around the bolt that weren't struck by an earlier flash.
+    async fn strike_entities(&self, world: &Arc<World>) {
+        let pos = self.entity.pos.load();
+        let area = BoundingBox::new(pos.add_raw(-3.0, -3.0, -3.0), pos.add_raw(3.0, 9.0, 3.0));
+        let mut struck = self.struck.lock().await;
+        let entities = world.get_entities_at_box(&area).into_iter().chain(
+            world
+                .get_players_at_box(&area)
+                .into_iter()
+                .map(|player| player as Arc<dyn EntityBase>),
+        );
+        for entity in entities {
+            let base = entity.get_entity();
+            if base.entity_id == self.entity.entity_id
+                || !base.is_alive()
+                || struck.contains(&base.entity_id)
+            {
+                continue;
+            }
+            struck.push(base.entity_id);
+            entity.on_struck_by_lightning(&*entity, self).await;
+        }
+    }
+}
+
+impl NBTStorage for LightningEntity {}
+
+impl EntityBase for LightningEntity {
+    fn tick<'a>(
+        &'a self,
+        _caller: Arc<dyn EntityBase>,
+        _server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            let world = self.entity.world.load_full();
+            if self.life.load(Relaxed) == 2 && !self.cosmetic {
+                let difficulty = world.level_info.load().difficulty;
+                if matches!(difficulty, Difficulty::Normal | Difficulty::Hard) {
+                    self.spawn_fire(&world, 4).await;
+                }
+            }
+
+            let life = self.life.fetch_sub(1, Relaxed) - 1;
+            if life < 0 {
+                if self.flashes.load(Relaxed) == 0 {
+                    self.entity.remove().await;
+                } else if life < -rand::rng().random_range(0..10) {
+                    self.flashes.fetch_sub(1, Relaxed);
+                    self.life.store(1, Relaxed);
+                    self.spawn_fire(&world, 0).await;
+                }
+            }
+
+            if self.life.load(Relaxed) >= 0 && !self.cosmetic {
+                self.strike_entities(&world).await;
+            }
+        })
+    }
+
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&LivingEntity> {
+        None
+    }
+
+    fn get_gravity(&self) -> f64 {
+        0.0
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+}
@@ -6,6 +6,7 @@ use pumpkin_inventory::build_equipment_slots;
 use pumpkin_inventory::player::player_inventory::PlayerInventory;
 use pumpkin_inventory::screen_handler::InventoryPlayer;
 use pumpkin_util::Hand;
+use pumpkin_util::loot_table::LootTable;
 use pumpkin_util::math::position::BlockPos;
 use std::mem;
 use std::sync::Arc;
@@ -19,21 +20,22 @@ use std::{collections::HashMap, sync::atomic::AtomicI32};
 
 use super::{Entity, NBTStorage};
 use super::{EntityBase, NBTStorageInit};
-use crate::block::OnLandedUponArgs;
-use crate::entity::{EntityBaseFuture, NbtFuture};
+use crate::block::{OnLandedUponArgs, OnSteppedOnArgs};
+use crate::entity::{EntityBaseFuture, NbtFuture, sounds};
 use crate::plugin::api::events::entity::entity_damage::EntityDamageEvent;
 use crate::plugin::api::events::entity::entity_damage_by_entity::EntityDamageByEntityEvent;
 use crate::plugin::api::events::entity::entity_death::EntityDeathEvent;
 use crate::plugin::api::events::player::player_item_consume::PlayerItemConsumeEvent;
 use crate::server::Server;
+use crate::server::chat_bridge::BridgeEvent;
 use crate::world::loot::{LootContextParameters, LootTableExt};
+use crate::world::scoreboard::Scoreboard;
 use crossbeam::atomic::AtomicCell;
 use pumpkin_data::Block;
 use pumpkin_data::damage::DeathMessageType;
 use pumpkin_data::data_component_impl::{DeathProtectionImpl, EquipmentSlot, FoodImpl};
 use pumpkin_data::effect::StatusEffect;
 use pumpkin_data::entity::{EntityPose, EntityStatus, EntityType};
-use pumpkin_data::sound::SoundCategory;
 use pumpkin_data::{damage::DamageType, sound::Sound};
 use pumpkin_inventory::entity_equipment::EntityEquipment;
 use pumpkin_nbt::compound::NbtCompound;
@@ -159,6 +161,20 @@ impl LivingEntity {
             .await;
     }
 
+    /// Sends everything this entity has equipped, e.g. after it spawned with equipment.
+    pub async fn send_all_equipment(&self) {
+        let mut equipment = Vec::new();
+        for (slot, stack) in &self.entity_equipment.lock().await.equipment {
+            let stack = stack.lock().await;
+            if !stack.is_empty() {
+                equipment.push((slot.clone(), stack.clone()));
+            }
+        }
+        if !equipment.is_empty() {
+            self.send_equipment_changes(&equipment).await;
+        }
+    }
+
     /// Picks up and Item entity or XP Orb
     pub async fn pickup(&self, item: &Entity, stack_amount: u32) {
         // TODO: Only nearby
@@ -210,7 +226,8 @@ impl LivingEntity {
 
     pub async fn heal(&self, additional_health: f32) {
         assert!(additional_health > 0.0);
-        self.set_health(self.health.load() + additional_health)
+        let max_health = self.entity.entity_type.max_health.unwrap_or(20.0);
+        self.set_health((self.health.load() + additional_health).min(max_health))
             .await;
     }
 
@@ -457,6 +474,8 @@ impl LivingEntity {
             self.travel_in_air(caller.clone()).await;
         }
 
+        self.push_entities(caller.as_ref()).await;
+
         //self.entity.tick_block_underneath(&caller);
 
         let suffocating = self.entity.tick_block_collisions(&caller, server).await;
@@ -466,6 +485,84 @@ impl LivingEntity {
         }
     }
 
+    /// Hurts the entity while it is further outside the world border than its buffer.
+    async fn tick_world_border(&self, caller: &dyn EntityBase) {
+        let pos = self.entity.pos.load();
+        let world = self.entity.world.load_full();
+        let damage = world.worldborder.lock().await.damage_at(pos.x, pos.z);
+        if let Some(damage) = damage {
+            caller
+                .damage(caller, damage, DamageType::OUTSIDE_BORDER)
+                .await;
+        }
+    }
+
+    /// Plays a sound from the mouth of this entity, at the pitch of its voice.
+    pub async fn play_voice(&self, sound: Sound) {
+        let baby = self.entity.age.load(Relaxed) < 0;
+        let pitch = sounds::voice_pitch(baby);
+        self.entity
+            .world
+            .load()
+            .play_sound_fine(
+                sound,
+                sounds::sound_category(self.entity.entity_type),
+                &self.entity.pos.load(),
+                1.0,
+                pitch,
+            )
+            .await;
+    }
+
+    /// Pushes this entity and the living entities it overlaps apart, as far as the collision rules
+    /// of their teams allow. Players are moved by their clients, which push them on their own.
+    async fn push_entities(&self, caller: &dyn EntityBase) {
+        if caller.is_spectator() || self.entity.no_clip.load(Relaxed) {
+            return;
+        }
+        let world = self.entity.world.load_full();
+        let bounding_box = self.entity.bounding_box.load();
+        let others: Vec<_> = world
+            .get_entities_at_box(&bounding_box)
+            .into_iter()
+            .chain(
+                world
+                    .get_players_at_box(&bounding_box)
+                    .into_iter()
+                    .map(|player| player as Arc<dyn EntityBase>),
+            )
+            .filter(|other| {
+                other.get_entity().entity_id != self.entity.entity_id
+                    && !other.is_spectator()
+                    && other
+                        .get_living_entity()
+                        .is_some_and(|living| !living.dead.load(Relaxed))
+            })
+            .collect();
+        if others.is_empty() {
+            return;
+        }
+        let scoreboard = world.scoreboard.lock().await;
+        let team = scoreboard.team_of_entity(caller);
+        for other in others {
+            if !Scoreboard::can_push(team, scoreboard.team_of_entity(other.as_ref())) {
+                continue;
+            }
+            let Some(push) = self.entity.push_away_from(other.get_entity()) else {
+                continue;
+            };
+            if caller.get_player().is_none() {
+                self.entity
+                    .velocity
+                    .store(self.entity.velocity.load() - push);
+            }
+            if other.get_player().is_none() {
+                let other = other.get_entity();
+                other.velocity.store(other.velocity.load() + push);
+            }
+        }
+    }
+
     async fn travel_in_air(&self, caller: Arc<dyn EntityBase>) {
         // applyMovementInput
 
@@ -813,30 +910,44 @@ impl LivingEntity {
         dont_damage: bool,
     ) {
         if ground {
+            let world = self.entity.world.load();
+            let landing_pos = self.entity.get_pos_with_y_offset(0.2).await.0;
+            let (block, state) = world.get_block_and_state(&landing_pos).await;
+            let pumpkin_block = world.block_registry.get_pumpkin_block(block.id);
+
             let fall_distance = self.fall_distance.swap(0.0);
-            if fall_distance <= 0.0
-                || dont_damage
-                || self.should_prevent_fall_damage().await
-                || self.should_prevent_fall_damage_in_area().await
-                || self.is_immune_to_fall_damage()
+            if fall_distance > 0.0
+                && !dont_damage
+                && !self.should_prevent_fall_damage().await
+                && !self.should_prevent_fall_damage_in_area().await
+                && !self.is_immune_to_fall_damage()
             {
-                return;
+                if let Some(pumpkin_block) = pumpkin_block {
+                    pumpkin_block
+                        .on_landed_upon(OnLandedUponArgs {
+                            world: &world,
+                            fall_distance,
+                            entity: caller.as_ref(),
+                        })
+                        .await;
+                } else {
+                    self.handle_fall_damage(fall_distance, 1.0).await;
+                }
             }
-            let world = self.entity.world.load();
-            let block = world
-                .get_block(&self.entity.get_pos_with_y_offset(0.2).await.0)
-                .await;
-            let pumpkin_block = world.block_registry.get_pumpkin_block(block.id);
-            if let Some(pumpkin_block) = pumpkin_block {
+
+            // Sneaking entities tread carefully
+            if let Some(pumpkin_block) = pumpkin_block
+                && !self.entity.sneaking.load(Ordering::Relaxed)
+            {
                 pumpkin_block
-                    .on_landed_upon(OnLandedUponArgs {
+                    .on_stepped_on(OnSteppedOnArgs {
                         world: &world,
-                        fall_distance,
+                        block,
+                        state,
+                        position: &landing_pos,
                         entity: caller.as_ref(),
                     })
                     .await;
-            } else {
-                self.handle_fall_damage(fall_distance, 1.0).await;
             }
         } else if height_difference < 0.0 {
             let new_fall_distance = if !self.should_prevent_fall_damage().await
@@ -962,24 +1073,43 @@ impl LivingEntity {
             self.entity.velocity_dirty.store(true, SeqCst);
             self.movement_input.store(Vector3::default());
             self.jumping.store(false, Relaxed);
-            // Plays the death sound
             world
                 .send_entity_status(
                     &self.entity,
                     EntityStatus::PlayDeathSoundOrAddProjectileHitParticles,
                 )
                 .await;
+            self.play_voice(sounds::death_sound(self.entity.entity_type))
+                .await;
             let params = LootContextParameters {
                 killed_by_player: cause.map(|c| c.get_entity().entity_type == &EntityType::PLAYER),
                 ..Default::default()
             };
 
-            self.drop_loot(params).await;
+            let mob = dyn_self.clone().get_mob();
+            let loot_table = mob
+                .as_ref()
+                .map_or(self.entity.entity_type.loot_table.as_ref(), |mob| {
+                    mob.get_loot_table()
+                });
+            self.drop_loot(loot_table, params).await;
             self.entity.pose.store(EntityPose::Dying);
 
             let block_pos = self.entity.block_pos.load();
+            if let Some(mob) = &mob {
+                mob.get_mob_entity().detach_leash(true).await;
+            }
+            let killed_by_player =
+                cause.is_some_and(|cause| cause.get_entity().entity_type == &EntityType::PLAYER);
 
             for slot in self.equipment_slots.values() {
+                // Mobs only drop their equipment by chance, unless it always drops
+                if let Some(mob) = &mob {
+                    let chance = mob.get_mob_entity().drop_chance(slot);
+                    if chance <= 1.0 && !(killed_by_player && rand::random::<f32>() < chance) {
+                        continue;
+                    }
+                }
                 let item = {
                     let lock = self.entity_equipment.lock().await;
                     let equipment = lock.get(slot);
@@ -998,13 +1128,16 @@ impl LivingEntity {
                     for player in server.get_all_players() {
                         player.send_system_message(&death_message).await;
                     }
+                    server
+                        .chat_bridge
+                        .relay_event(BridgeEvent::Death, &death_message);
                 }
             }
         }
     }
 
-    async fn drop_loot(&self, params: LootContextParameters) {
-        if let Some(loot_table) = &self.get_entity().entity_type.loot_table {
+    async fn drop_loot(&self, loot_table: Option<&LootTable>, params: LootContextParameters) {
+        if let Some(loot_table) = loot_table {
             let pos = self.entity.block_pos.load();
             for stack in loot_table.get_loot(params) {
                 self.entity.world.load().drop_stack(&pos, stack).await;
@@ -1165,6 +1298,58 @@ impl LivingEntity {
     }
 }
 
+impl LivingEntity {
+    /// Writes the non-empty equipment slots to the `equipment` compound.
+    pub async fn write_equipment(&self, nbt: &mut NbtCompound) {
+        let mut equipment = NbtCompound::new();
+        let entity_equipment = self.entity_equipment.lock().await;
+        for (name, slot) in &EQUIPMENT_SLOTS {
+            let stack = entity_equipment.get(slot);
+            let stack = stack.lock().await;
+            if !stack.is_empty() {
+                let mut item = NbtCompound::new();
+                stack.write_item_stack(&mut item);
+                equipment.put_component(name, item);
+            }
+        }
+        drop(entity_equipment);
+        if !equipment.child_tags.is_empty() {
+            nbt.put_component("equipment", equipment);
+        }
+    }
+
+    pub async fn read_equipment(&self, nbt: &NbtCompound) {
+        let Some(equipment) = nbt.get_compound("equipment") else {
+            return;
+        };
+        let mut entity_equipment = self.entity_equipment.lock().await;
+        for (name, slot) in &EQUIPMENT_SLOTS {
+            if let Some(stack) = equipment
+                .get_compound(name)
+                .and_then(ItemStack::read_item_stack)
+            {
+                entity_equipment.put(slot, stack).await;
+            }
+        }
+    }
+}
+
+/// The equipment slots with their names in the `equipment` NBT compound, in the order of
+/// [`EquipmentSlot::discriminant`].
+pub const EQUIPMENT_SLOTS: [(&str, EquipmentSlot); 8] = [
+    ("mainhand", EquipmentSlot::MAIN_HAND),
+    ("offhand", EquipmentSlot::OFF_HAND),
+    ("feet", EquipmentSlot::FEET),
+    ("legs", EquipmentSlot::LEGS),
+    ("chest", EquipmentSlot::CHEST),
+    ("head", EquipmentSlot::HEAD),
+    ("body", EquipmentSlot::BODY),
+    ("saddle", EquipmentSlot::SADDLE),
+];
+
+/// The only attribute living entities keep so far.
+const MOVEMENT_SPEED_ATTRIBUTE: &str = "minecraft:movement_speed";
+
 impl NBTStorage for LivingEntity {
     fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
         Box::pin(async move {
@@ -1190,7 +1375,15 @@ impl NBTStorage for LivingEntity {
                     nbt.put("active_effects", NbtTag::List(effects_list));
                 }
             }
-            //TODO: write equipment
+            let mut movement_speed = NbtCompound::new();
+            movement_speed.put_string("id", MOVEMENT_SPEED_ATTRIBUTE.to_string());
+            movement_speed.put_double("base", self.movement_speed.load());
+            nbt.put(
+                "attributes",
+                NbtTag::List(vec![NbtTag::Compound(movement_speed)]),
+            );
+            // Equipment is written by the entities that have any, players keep it in their
+            // inventory
             // todo more...
         })
     }
@@ -1198,7 +1391,9 @@ impl NBTStorage for LivingEntity {
     fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
         Box::pin(async {
             self.entity.read_nbt_non_mut(nbt).await;
-            self.health.store(nbt.get_float("Health").unwrap_or(0.0));
+            if let Some(health) = nbt.get_float("Health") {
+                self.health.store(health);
+            }
             // Load fall distance, but if this entity is currently marked dead ensure we don't restore
             // a lethal fall distance that would immediately re-kill on spawn.
             let fd = nbt.get_float("fall_distance").unwrap_or(0.0);
@@ -1225,6 +1420,14 @@ impl NBTStorage for LivingEntity {
                     }
                 }
             }
+            for attribute in nbt.get_list("attributes").unwrap_or_default() {
+                if let Some(attribute) = attribute.extract_compound()
+                    && attribute.get_string("id") == Some(MOVEMENT_SPEED_ATTRIBUTE)
+                    && let Some(base) = attribute.get_double("base")
+                {
+                    self.movement_speed.store(base);
+                }
+            }
         })
         // todo more...
     }
@@ -1343,19 +1546,13 @@ impl EntityBase for LivingEntity {
                 ))
                 .await;
 
-            if play_sound {
-                world
-                    .play_sound(
-                        // Sound::EntityPlayerHurt,
-                        Sound::EntityGenericHurt,
-                        SoundCategory::Players,
-                        &self.entity.pos.load(),
-                    )
+            let new_health = self.health.load() - damage_amount;
+            // Dying entities make their death sound instead
+            if play_sound && new_health > 0.0 {
+                self.play_voice(sounds::hurt_sound(self.entity.entity_type, damage_type))
                     .await;
                 // todo: calculate knockback
             }
-
-            let new_health = self.health.load() - damage_amount;
             if damage_amount > 0.0 {
                 //self.on_actually_hurt(damage_amount, damage_type).await;
                 self.set_health(new_health).await;
@@ -1406,6 +1603,9 @@ impl EntityBase for LivingEntity {
                 // self.entity.send_velocity().await;
             }
             self.tick_effects().await;
+            if !self.dead.load(Relaxed) && self.health.load() > 0.0 {
+                self.tick_world_border(caller.as_ref()).await;
+            }
             // Current active item
             {
                 let item_in_use = self.item_in_use.lock().await.clone();
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity, skeleton::SkeletonEntityBase},
 };
 
@@ -20,8 +20,6 @@ impl BoggedEntity {
     }
 }
 
-impl NBTStorage for BoggedEntity {}
-
 impl Mob for BoggedEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.skeleton.mob_entity
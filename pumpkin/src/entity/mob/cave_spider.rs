@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity, spider::SpiderEntity},
 };
 
@@ -19,8 +19,6 @@ impl CaveSpiderEntity {
     }
 }
 
-impl NBTStorage for CaveSpiderEntity {}
-
 impl Mob for CaveSpiderEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.spider.mob_entity
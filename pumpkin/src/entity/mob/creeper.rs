@@ -1,24 +1,28 @@
 use std::sync::{
     Arc, Weak,
-    atomic::{AtomicI32, Ordering},
+    atomic::{AtomicBool, AtomicI32, Ordering},
 };
 
 use pumpkin_data::{entity::EntityType, meta_data_type::MetaDataType, tracked_data::TrackedData};
+use pumpkin_nbt::compound::NbtCompound;
 use pumpkin_protocol::java::client::play::Metadata;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBase, EntityBaseFuture, NbtFuture,
     ai::goal::{
         active_target::ActiveTargetGoal, creeper_ignite::CreeperIgniteGoal,
         flee_entity::FleeEntityGoal, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
         swim::SwimGoal, wander_around::WanderAroundGoal,
     },
+    lightning::LightningEntity,
     mob::{Mob, MobEntity},
 };
 
 pub struct CreeperEntity {
     pub mob_entity: MobEntity,
     pub fuse_speed: AtomicI32,
+    /// Whether the creeper was charged by a lightning strike.
+    pub charged: AtomicBool,
 }
 
 impl CreeperEntity {
@@ -27,6 +31,7 @@ impl CreeperEntity {
         let entity = Self {
             mob_entity,
             fuse_speed: AtomicI32::new(-1),
+            charged: AtomicBool::new(false),
         };
         let mob_arc = Arc::new(entity);
         let mob_weak: Weak<dyn Mob> = {
@@ -70,12 +75,97 @@ impl CreeperEntity {
             )])
             .await;
     }
-}
 
-impl NBTStorage for CreeperEntity {}
+    pub async fn set_charged(&self, charged: bool) {
+        self.charged.store(charged, Ordering::Relaxed);
+        self.send_charged().await;
+    }
+
+    async fn send_charged(&self) {
+        let charged = self.charged.load(Ordering::Relaxed);
+        self.mob_entity
+            .living_entity
+            .entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_CHARGED,
+                MetaDataType::Boolean,
+                charged,
+            )])
+            .await;
+    }
+}
 
 impl Mob for CreeperEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    fn on_struck_by_lightning<'a>(
+        &'a self,
+        caller: &'a dyn EntityBase,
+        _lightning: &'a LightningEntity,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            self.get_entity().struck_by_lightning(caller).await;
+            self.set_charged(true).await;
+        })
+    }
+
+    fn write_mob_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            nbt.put_bool("powered", self.charged.load(Ordering::Relaxed));
+        })
+    }
+
+    fn read_mob_nbt<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.charged
+                .store(nbt.get_bool("powered").unwrap_or(false), Ordering::Relaxed);
+        })
+    }
+
+    fn init_mob_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            if self.charged.load(Ordering::Relaxed) {
+                self.send_charged().await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_util::math::vector3::Vector3;
+
+    use super::*;
+    use crate::entity::NBTStorage;
+    use crate::world::World;
+
+    async fn new_creeper(world: &Arc<World>) -> Arc<CreeperEntity> {
+        let entity = Entity::new(
+            world.clone(),
+            Vector3::new(0.0, 64.0, 0.0),
+            &EntityType::CREEPER,
+        );
+        CreeperEntity::new(entity).await
+    }
+
+    #[tokio::test]
+    async fn charged_creepers_stay_charged() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let creeper = new_creeper(&world).await;
+        creeper.set_charged(true).await;
+        let mut nbt = NbtCompound::new();
+        creeper.write_nbt(&mut nbt).await;
+        assert_eq!(nbt.get_bool("powered"), Some(true));
+
+        let loaded = new_creeper(&world).await;
+        loaded.read_nbt_non_mut(&nbt).await;
+        assert!(loaded.charged.load(Ordering::Relaxed));
+
+        nbt.put_bool("powered", false);
+        loaded.read_nbt_non_mut(&nbt).await;
+        assert!(!loaded.charged.load(Ordering::Relaxed));
+    }
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{active_target::ActiveTargetGoal, ranged_attack::RangedAttackGoal},
     mob::{Mob, MobEntity, zombie::ZombieEntity},
 };
@@ -38,8 +38,6 @@ impl DrownedEntity {
     }
 }
 
-impl NBTStorage for DrownedEntity {}
-
 impl Mob for DrownedEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.entity.mob_entity
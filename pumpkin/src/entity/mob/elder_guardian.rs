@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity, guardian::GuardianEntity},
 };
 
@@ -20,8 +20,6 @@ impl ElderGuardianEntity {
     }
 }
 
-impl NBTStorage for ElderGuardianEntity {}
-
 impl Mob for ElderGuardianEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.guardian.mob_entity
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         active_target::ActiveTargetGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, melee_attack::MeleeAttackGoal, swim::SwimGoal,
@@ -50,8 +50,6 @@ impl EndermiteEntity {
     }
 }
 
-impl NBTStorage for EndermiteEntity {}
-
 impl Mob for EndermiteEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         active_target::ActiveTargetGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, swim::SwimGoal, wander_around::WanderAroundGoal,
@@ -51,8 +51,6 @@ impl EvokerEntity {
     }
 }
 
-impl NBTStorage for EvokerEntity {}
-
 impl Mob for EvokerEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
         wander_around::WanderAroundGoal,
@@ -43,8 +43,6 @@ impl GiantEntity {
     }
 }
 
-impl NBTStorage for GiantEntity {}
-
 impl Mob for GiantEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBaseFuture,
     mob::{Mob, MobEntity, zombie::ZombieEntity},
 };
+use crate::world::regional_difficulty::RegionalDifficulty;
 
 /// Husk — a desert variant of the Zombie that doesn't burn in sunlight.
 ///
@@ -19,10 +20,15 @@ impl HuskEntity {
     }
 }
 
-impl NBTStorage for HuskEntity {}
-
 impl Mob for HuskEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.zombie.mob_entity
     }
+
+    fn finalize_spawn<'a>(
+        &'a self,
+        difficulty: &'a RegionalDifficulty,
+    ) -> EntityBaseFuture<'a, ()> {
+        Mob::finalize_spawn(&*self.zombie, difficulty)
+    }
 }
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         active_target::ActiveTargetGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, swim::SwimGoal, wander_around::WanderAroundGoal,
@@ -51,8 +51,6 @@ impl IllusionerEntity {
     }
 }
 
-impl NBTStorage for IllusionerEntity {}
-
 impl Mob for IllusionerEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -1,21 +1,38 @@
-use super::{Entity, EntityBase, NBTStorage, ai::path::Navigator, living::LivingEntity};
-use crate::entity::EntityBaseFuture;
+use super::{
+    Entity, EntityBase, NBTStorage, NbtFuture,
+    ai::path::{Navigator, NavigatorGoal},
+    leash::{self, Leash, LeashHolder},
+    lightning::LightningEntity,
+    living::{EQUIPMENT_SLOTS, LivingEntity},
+    player::Player,
+};
 use crate::entity::ai::control::look_control::LookControl;
 use crate::entity::ai::goal::goal_selector::GoalSelector;
+use crate::entity::{EntityBaseFuture, sounds};
 use crate::server::Server;
 use crate::world::World;
+use crate::world::regional_difficulty::RegionalDifficulty;
 use crossbeam::atomic::AtomicCell;
 use pumpkin_data::damage::DamageType;
+use pumpkin_data::data_component_impl::EquipmentSlot;
+use pumpkin_data::entity::{EntityType, MobCategory};
+use pumpkin_data::item::Item;
 use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::sound::{Sound, SoundCategory};
 use pumpkin_data::tracked_data::TrackedData;
-use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_protocol::java::client::play::{CSetEntityLink, Metadata};
+use pumpkin_util::Difficulty;
+use pumpkin_util::loot_table::LootTable;
 use pumpkin_util::math::boundingbox::BoundingBox;
 use pumpkin_util::math::position::BlockPos;
 use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::item::ItemStack;
+use rand::RngExt;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::Ordering::Relaxed;
-use std::sync::atomic::{AtomicI32, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU8, Ordering};
 use tokio::sync::Mutex;
 
 pub mod blaze;
@@ -67,15 +84,67 @@ pub struct MobEntity {
     pub position_target: AtomicCell<BlockPos>,
     pub position_target_range: AtomicI32,
     mob_flags: AtomicU8,
+    /// Whether the mob never despawns, e.g. because it was named.
+    persistence_required: AtomicBool,
+    /// The chance of the equipment in each slot to drop on death, indexed by
+    /// [`EquipmentSlot::discriminant`].
+    drop_chances: [AtomicCell<f32>; 8],
+    /// What the mob is tied to with a lead, if anything.
+    leash: Mutex<Option<Leash>>,
+    /// Counts up while the mob is quiet, the higher the more likely it makes its ambient sound.
+    ambient_sound_time: AtomicI32,
 }
 
+/// The armor slots in the order mobs are equipped, from the feet up.
+const ARMOR_SLOTS: [EquipmentSlot; 4] = [
+    EquipmentSlot::FEET,
+    EquipmentSlot::LEGS,
+    EquipmentSlot::CHEST,
+    EquipmentSlot::HEAD,
+];
+
+/// The armor mobs spawn with for each tier, in the order of [`ARMOR_SLOTS`].
+const ARMOR_TIERS: [[&Item; 4]; 5] = [
+    [
+        &Item::LEATHER_BOOTS,
+        &Item::LEATHER_LEGGINGS,
+        &Item::LEATHER_CHESTPLATE,
+        &Item::LEATHER_HELMET,
+    ],
+    [
+        &Item::GOLDEN_BOOTS,
+        &Item::GOLDEN_LEGGINGS,
+        &Item::GOLDEN_CHESTPLATE,
+        &Item::GOLDEN_HELMET,
+    ],
+    [
+        &Item::CHAINMAIL_BOOTS,
+        &Item::CHAINMAIL_LEGGINGS,
+        &Item::CHAINMAIL_CHESTPLATE,
+        &Item::CHAINMAIL_HELMET,
+    ],
+    [
+        &Item::IRON_BOOTS,
+        &Item::IRON_LEGGINGS,
+        &Item::IRON_CHESTPLATE,
+        &Item::IRON_HELMET,
+    ],
+    [
+        &Item::DIAMOND_BOOTS,
+        &Item::DIAMOND_LEGGINGS,
+        &Item::DIAMOND_CHESTPLATE,
+        &Item::DIAMOND_HELMET,
+    ],
+];
+
 impl MobEntity {
-    #[expect(dead_code)]
     const AI_DISABLED_FLAG: u8 = 1;
-    #[expect(dead_code)]
     const LEFT_HANDED_FLAG: u8 = 2;
     const ATTACKING_FLAG: u8 = 4;
 
+    /// The drop chance of equipment the mob was not given explicitly.
+    pub const DEFAULT_DROP_CHANCE: f32 = 0.085;
+
     #[must_use]
     pub fn new(entity: Entity) -> Self {
         Self {
@@ -88,8 +157,276 @@ impl MobEntity {
             position_target: AtomicCell::new(BlockPos::ZERO),
             position_target_range: AtomicI32::new(-1),
             mob_flags: AtomicU8::new(0),
+            persistence_required: AtomicBool::new(false),
+            drop_chances: std::array::from_fn(|_| AtomicCell::new(Self::DEFAULT_DROP_CHANCE)),
+            leash: Mutex::new(None),
+            ambient_sound_time: AtomicI32::new(0),
+        }
+    }
+
+    #[must_use]
+    pub fn drop_chance(&self, slot: &EquipmentSlot) -> f32 {
+        self.drop_chances[slot.discriminant() as usize].load()
+    }
+
+    /// Sets the chance of the equipment in `slot` to drop on death. Chances of 1 or more always
+    /// drop it.
+    pub fn set_drop_chance(&self, slot: &EquipmentSlot, chance: f32) {
+        self.drop_chances[slot.discriminant() as usize].store(chance);
+    }
+
+    #[must_use]
+    pub fn is_persistent(&self) -> bool {
+        self.persistence_required.load(Relaxed)
+    }
+
+    /// Keeps the mob from ever despawning.
+    pub fn set_persistent(&self) {
+        self.persistence_required.store(true, Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_ai_disabled(&self) -> bool {
+        self.mob_flags.load(Relaxed) & Self::AI_DISABLED_FLAG != 0
+    }
+
+    /// Stops the mob from running its goals, like the `NoAI` tag.
+    pub async fn set_ai_disabled(&self, disabled: bool) {
+        self.set_mob_flag(Self::AI_DISABLED_FLAG, disabled).await;
+    }
+
+    #[must_use]
+    pub fn is_left_handed(&self) -> bool {
+        self.mob_flags.load(Relaxed) & Self::LEFT_HANDED_FLAG != 0
+    }
+
+    pub async fn set_left_handed(&self, left_handed: bool) {
+        self.set_mob_flag(Self::LEFT_HANDED_FLAG, left_handed).await;
+    }
+
+    /// What the mob is tied to, if it is leashed.
+    pub async fn leash_holder(&self) -> Option<LeashHolder> {
+        self.leash.lock().await.as_ref().map(|leash| leash.holder)
+    }
+
+    /// Whether a player may put a lead on the mob. Like vanilla, these are animals, axolotls and
+    /// a few other mobs that are not leashed yet.
+    pub async fn can_be_leashed(&self) -> bool {
+        let entity_type = self.living_entity.entity.entity_type;
+        self.leash.lock().await.is_none()
+            && (entity_type.category == &MobCategory::CREATURE
+                || entity_type.category == &MobCategory::AXOLOTLS
+                || entity_type == &EntityType::IRON_GOLEM
+                || entity_type == &EntityType::SNOW_GOLEM
+                || entity_type == &EntityType::HOGLIN)
+    }
+
+    /// Ties the mob to `holder`, replacing its current lead.
+    pub async fn attach_leash(&self, holder: &dyn EntityBase) {
+        let holder_id = holder.get_entity().entity_id;
+        let mut leash = Leash::new(LeashHolder::of(holder));
+        leash.holder_id = Some(holder_id);
+        *self.leash.lock().await = Some(leash);
+        self.set_persistent();
+        self.send_leash_link(holder_id).await;
+    }
+
+    /// Unties the mob, dropping a lead if `drop_lead` is set.
+    pub async fn detach_leash(&self, drop_lead: bool) {
+        if self.leash.lock().await.take().is_none() {
+            return;
+        }
+        self.send_leash_link(-1).await;
+        if drop_lead {
+            let entity = &self.living_entity.entity;
+            entity
+                .world
+                .load()
+                .drop_stack(&entity.block_pos.load(), ItemStack::new(1, &Item::LEAD))
+                .await;
+        }
+    }
+
+    async fn send_leash_link(&self, holder_id: i32) {
+        let entity = &self.living_entity.entity;
+        entity
+            .world
+            .load()
+            .broadcast_packet_all(&CSetEntityLink::new(entity.entity_id, holder_id))
+            .await;
+    }
+
+    /// Sends the lead to the players that see the mob, once its holder was found.
+    async fn send_leash(&self) {
+        let holder_id = self
+            .leash
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|leash| leash.holder_id);
+        if let Some(holder_id) = holder_id {
+            self.send_leash_link(holder_id).await;
+        }
+    }
+
+    /// Now and then makes the mob play its ambient sound, at least
+    /// [`sounds::AMBIENT_SOUND_INTERVAL`] ticks apart.
+    async fn tick_ambient_sound(&self) {
+        let living = &self.living_entity;
+        if living.dead.load(Relaxed) {
+            return;
+        }
+        let quiet_time = self.ambient_sound_time.fetch_add(1, Relaxed);
+        let roll = rand::rng().random_range(0..1000);
+        if roll >= quiet_time {
+            return;
+        }
+        self.ambient_sound_time
+            .store(-sounds::AMBIENT_SOUND_INTERVAL, Relaxed);
+        if let Some(sound) = sounds::ambient_sound(living.entity.entity_type) {
+            living.play_voice(sound).await;
+        }
+    }
+
+    /// Keeps a leashed mob close to its holder: it follows it, is pulled towards it from
+    /// [`leash::PULL_DISTANCE`] on and breaks the lead beyond [`leash::BREAK_DISTANCE`].
+    async fn tick_leash(&self, path_aware: Option<&dyn PathAwareEntity>) {
+        let Some(holder) = self.leash_holder().await else {
+            return;
+        };
+        let entity = &self.living_entity.entity;
+        let world = entity.world.load_full();
+
+        let Some(holder) = holder.find(&world).await else {
+            let missing_ticks = {
+                let mut leash = self.leash.lock().await;
+                let Some(leash) = leash.as_mut() else {
+                    return;
+                };
+                leash.missing_ticks += 1;
+                leash.missing_ticks
+            };
+            if missing_ticks > leash::MISSING_HOLDER_TICKS {
+                self.detach_leash(true).await;
+            }
+            return;
+        };
+        let holder = holder.get_entity();
+        if holder.is_removed() {
+            return;
+        }
+
+        let newly_found = {
+            let mut leash = self.leash.lock().await;
+            let Some(leash) = leash.as_mut() else {
+                return;
+            };
+            leash.missing_ticks = 0;
+            leash.holder_id.replace(holder.entity_id) != Some(holder.entity_id)
+        };
+        if newly_found {
+            self.send_leash_link(holder.entity_id).await;
+        }
+
+        let pos = entity.pos.load();
+        let holder_pos = holder.pos.load();
+        let distance = pos.squared_distance_to_vec(&holder_pos).sqrt();
+        if distance > leash::BREAK_DISTANCE {
+            self.detach_leash(true).await;
+            world
+                .play_sound(Sound::ItemLeadBreak, SoundCategory::Neutral, &pos)
+                .await;
+            return;
+        }
+
+        if let Some(path_aware) = path_aware {
+            path_aware.before_leash_tick();
+        }
+        if distance > leash::PULL_DISTANCE {
+            let delta = holder_pos.sub(&pos);
+            let pull = |d: f64| (d * d * 0.4).copysign(d) / distance / distance;
+            entity.velocity.store(entity.velocity.load().add_raw(
+                pull(delta.x),
+                pull(delta.y),
+                pull(delta.z),
+            ));
+            entity.velocity_dirty.store(true, Ordering::SeqCst);
+            self.navigator.lock().await.cancel();
+        } else if let Some(path_aware) = path_aware
+            && path_aware.should_follow_leash()
+        {
+            path_aware.on_short_leash_tick();
+            if distance > 2.0 {
+                self.navigator.lock().await.set_progress(NavigatorGoal {
+                    current_progress: pos,
+                    destination: holder_pos,
+                    speed: f64::from(path_aware.get_follow_leash_speed()),
+                });
+            }
+        }
+    }
+
+    /// Sends the mob flags to the players that see the mob, if any are set.
+    async fn send_mob_flags(&self) {
+        let flags = self.mob_flags.load(Relaxed);
+        if flags != 0 {
+            self.living_entity
+                .entity
+                .send_meta_data(&[Metadata::new(
+                    TrackedData::DATA_MOB_FLAGS,
+                    MetaDataType::Byte,
+                    flags,
+                )])
+                .await;
+        }
+    }
+
+    /// Sets a flag read from NBT, before the mob is sent to players.
+    fn load_mob_flag(&self, flag: u8, value: bool) {
+        if value {
+            self.mob_flags.fetch_or(flag, Relaxed);
+        } else {
+            self.mob_flags.fetch_and(!flag, Relaxed);
         }
     }
+
+    /// Gives the mob random armor, more likely and better the higher the regional difficulty.
+    /// Slots that are already filled are kept.
+    pub async fn populate_armor(&self, difficulty: &RegionalDifficulty) {
+        let armor = {
+            let mut rng = rand::rng();
+            if rng.random::<f32>() >= 0.15 * difficulty.special_multiplier() {
+                return;
+            }
+            let mut tier = rng.random_range(0..2);
+            for _ in 0..3 {
+                if rng.random::<f32>() < 0.095 {
+                    tier += 1;
+                }
+            }
+            let stop_chance = if difficulty.base == Difficulty::Hard {
+                0.1
+            } else {
+                0.25
+            };
+            let mut armor = Vec::with_capacity(ARMOR_SLOTS.len());
+            for (i, slot) in ARMOR_SLOTS.iter().enumerate() {
+                if i > 0 && rng.random::<f32>() < stop_chance {
+                    break;
+                }
+                armor.push((slot.clone(), ItemStack::new(1, ARMOR_TIERS[tier][i])));
+            }
+            armor
+        };
+
+        let mut equipment = self.living_entity.entity_equipment.lock().await;
+        for (slot, stack) in armor {
+            if equipment.get(&slot).lock().await.is_empty() {
+                equipment.put(&slot, stack).await;
+            }
+        }
+    }
+
     pub fn is_in_position_target_range(&self) -> bool {
         self.is_in_position_target_range_pos(&self.living_entity.entity.block_pos.load())
     }
@@ -198,6 +535,66 @@ impl MobEntity {
     }
 }
 
+impl NBTStorage for MobEntity {
+    fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.living_entity.write_nbt(nbt).await;
+            nbt.put_int("Age", self.living_entity.entity.age.load(Relaxed));
+            nbt.put_bool("PersistenceRequired", self.is_persistent());
+            nbt.put_bool("LeftHanded", self.is_left_handed());
+            if self.is_ai_disabled() {
+                nbt.put_bool("NoAI", true);
+            }
+
+            self.living_entity.write_equipment(nbt).await;
+            let mut drop_chances = NbtCompound::new();
+            for (name, slot) in &EQUIPMENT_SLOTS {
+                let chance = self.drop_chance(slot);
+                if chance.to_bits() != Self::DEFAULT_DROP_CHANCE.to_bits() {
+                    drop_chances.put_float(name, chance);
+                }
+            }
+            if !drop_chances.child_tags.is_empty() {
+                nbt.put_component("drop_chances", drop_chances);
+            }
+            if let Some(holder) = self.leash_holder().await {
+                holder.write_nbt(nbt);
+            }
+        })
+    }
+
+    fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.living_entity.read_nbt_non_mut(nbt).await;
+            if let Some(age) = nbt.get_int("Age") {
+                self.living_entity.entity.age.store(age, Relaxed);
+            }
+            self.persistence_required.store(
+                nbt.get_bool("PersistenceRequired").unwrap_or(false),
+                Relaxed,
+            );
+            self.load_mob_flag(
+                Self::LEFT_HANDED_FLAG,
+                nbt.get_bool("LeftHanded").unwrap_or(false),
+            );
+            self.load_mob_flag(
+                Self::AI_DISABLED_FLAG,
+                nbt.get_bool("NoAI").unwrap_or(false),
+            );
+
+            self.living_entity.read_equipment(nbt).await;
+            let drop_chances = nbt.get_compound("drop_chances");
+            for (name, slot) in &EQUIPMENT_SLOTS {
+                let chance = drop_chances
+                    .and_then(|drop_chances| drop_chances.get_float(name))
+                    .unwrap_or(Self::DEFAULT_DROP_CHANCE);
+                self.set_drop_chance(slot, chance);
+            }
+            *self.leash.lock().await = LeashHolder::read_nbt(nbt).map(Leash::new);
+        })
+    }
+}
+
 // This trait contains all overridable functions
 pub trait Mob: EntityBase + Send + Sync {
     fn get_random(&self) -> rand::rngs::ThreadRng {
@@ -221,6 +618,92 @@ pub trait Mob: EntityBase + Send + Sync {
     fn get_path_aware_entity(&self) -> Option<&dyn PathAwareEntity> {
         None
     }
+
+    /// Called when a lightning bolt strikes this mob, for mobs that are converted or changed by
+    /// lightning.
+    fn on_struck_by_lightning<'a>(
+        &'a self,
+        caller: &'a dyn EntityBase,
+        _lightning: &'a LightningEntity,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move { self.get_entity().struck_by_lightning(caller).await })
+    }
+
+    /// Called before a naturally spawned mob is added to the world, for mobs that spawn with
+    /// equipment or effects depending on the difficulty.
+    fn finalize_spawn<'a>(
+        &'a self,
+        _difficulty: &'a RegionalDifficulty,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Called every tick before the goals, for mobs that do more than their goals, like the
+    /// wither. Not called while the mob's AI is disabled.
+    fn mob_tick(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async {})
+    }
+
+    /// Whether this mob takes damage of `damage_type` dealt by `cause`, for mobs that ignore
+    /// some of it.
+    fn should_take_damage(
+        &self,
+        _damage_type: DamageType,
+        _cause: Option<&dyn EntityBase>,
+    ) -> bool {
+        true
+    }
+
+    /// Called by [`RangedAttackGoal`](crate::entity::ai::goal::ranged_attack::RangedAttackGoal)
+    /// when this mob attacks `target` from range. `pull_progress` is how far away the target
+    /// is, relative to the range of the attack.
+    fn perform_ranged_attack<'a>(
+        &'a self,
+        _target: &'a dyn EntityBase,
+        _pull_progress: f32,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Called when `player` right clicks this mob with `stack` and it isn't about a lead, for
+    /// mobs that take items, like breeding food. Returns whether the interaction did something.
+    fn mob_interact<'a>(
+        &'a self,
+        _player: &'a Player,
+        _stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async { false })
+    }
+
+    /// Writes the data kept by this kind of mob, besides that of every [`MobEntity`].
+    fn write_mob_nbt<'a>(&'a self, _nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Reads the data written by [`Self::write_mob_nbt`].
+    fn read_mob_nbt<'a>(&'a self, _nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Sends the tracked data of this kind of mob, for mobs that look different by it, like
+    /// variants.
+    fn init_mob_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async {})
+    }
+
+    /// The loot this mob drops when it dies.
+    fn get_loot_table(&self) -> Option<&'static LootTable> {
+        self.get_entity().entity_type.loot_table.as_ref()
+    }
+
+    fn get_gravity(&self) -> f64 {
+        self.get_mob_entity().living_entity.get_gravity()
+    }
+
+    /// Whether this mob is removed when the difficulty is Peaceful, true for monsters.
+    fn should_despawn_in_peaceful(&self) -> bool {
+        self.get_entity().entity_type.category == &MobCategory::MONSTER
+    }
 }
 
 impl<T: Mob + Send + 'static> EntityBase for T {
@@ -232,6 +715,23 @@ impl<T: Mob + Send + 'static> EntityBase for T {
         Box::pin(async move {
             let mob_entity = self.get_mob_entity();
 
+            let entity = &mob_entity.living_entity.entity;
+            if self.should_despawn_in_peaceful()
+                && entity.world.load().get_difficulty() == Difficulty::Peaceful
+            {
+                entity.remove().await;
+                return;
+            }
+
+            mob_entity.tick_leash(self.get_path_aware_entity()).await;
+            mob_entity.tick_ambient_sound().await;
+
+            if mob_entity.is_ai_disabled() {
+                mob_entity.living_entity.tick(caller, server).await;
+                return;
+            }
+            self.mob_tick().await;
+
             let age = mob_entity.living_entity.entity.age.load(Relaxed);
             if (age + mob_entity.living_entity.entity.entity_id) % 2 != 0 && age > 1 {
                 mob_entity
@@ -264,6 +764,19 @@ impl<T: Mob + Send + 'static> EntityBase for T {
         })
     }
 
+    fn inactive_tick<'a>(
+        &'a self,
+        caller: Arc<dyn EntityBase>,
+        server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            // Skips goals and navigation, but the mob still falls, moves and burns
+            let living = &self.get_mob_entity().living_entity;
+            living.jumping.store(false, Ordering::SeqCst);
+            living.tick(caller, server).await;
+        })
+    }
+
     fn damage_with_context<'a>(
         &'a self,
         caller: &'a dyn EntityBase,
@@ -274,6 +787,9 @@ impl<T: Mob + Send + 'static> EntityBase for T {
         cause: Option<&'a dyn EntityBase>,
     ) -> EntityBaseFuture<'a, bool> {
         Box::pin(async move {
+            if !self.should_take_damage(damage_type, cause) {
+                return false;
+            }
             self.get_mob_entity()
                 .living_entity
                 .damage_with_context(caller, amount, damage_type, position, source, cause)
@@ -289,12 +805,85 @@ impl<T: Mob + Send + 'static> EntityBase for T {
         Some(&self.get_mob_entity().living_entity)
     }
 
+    fn get_mob(self: Arc<Self>) -> Option<Arc<dyn Mob>> {
+        Some(self)
+    }
+
+    fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            let mob_entity = self.get_mob_entity();
+            mob_entity.living_entity.entity.init_data_tracker().await;
+            mob_entity.send_mob_flags().await;
+            mob_entity.send_leash().await;
+            self.init_mob_data_tracker().await;
+        })
+    }
+
+    fn interact<'a>(
+        &'a self,
+        player: &'a Player,
+        stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async move {
+            let mob_entity = self.get_mob_entity();
+            let pos = mob_entity.living_entity.entity.pos.load();
+            let world = player.world();
+            if mob_entity.leash_holder().await == Some(LeashHolder::Entity(player.gameprofile.id)) {
+                mob_entity.detach_leash(!player.is_creative()).await;
+                world
+                    .play_sound(Sound::ItemLeadUntied, SoundCategory::Neutral, &pos)
+                    .await;
+                return true;
+            }
+            if stack.item.id == Item::LEAD.id && mob_entity.can_be_leashed().await {
+                mob_entity.attach_leash(player).await;
+                world
+                    .play_sound(Sound::ItemLeadTied, SoundCategory::Neutral, &pos)
+                    .await;
+                stack.decrement_unless_creative(player.gamemode.load(), 1);
+                return true;
+            }
+            self.mob_interact(player, stack).await
+        })
+    }
+
     fn as_nbt_storage(&self) -> &dyn NBTStorage {
         self
     }
 
     fn get_gravity(&self) -> f64 {
-        self.get_mob_entity().living_entity.get_gravity()
+        Mob::get_gravity(self)
+    }
+
+    fn on_struck_by_lightning<'a>(
+        &'a self,
+        caller: &'a dyn EntityBase,
+        lightning: &'a LightningEntity,
+    ) -> EntityBaseFuture<'a, ()> {
+        Mob::on_struck_by_lightning(self, caller, lightning)
+    }
+
+    fn finalize_spawn<'a>(
+        &'a self,
+        difficulty: &'a RegionalDifficulty,
+    ) -> EntityBaseFuture<'a, ()> {
+        Mob::finalize_spawn(self, difficulty)
+    }
+}
+
+impl<T: Mob + Send + 'static> NBTStorage for T {
+    fn write_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.get_mob_entity().write_nbt(nbt).await;
+            self.write_mob_nbt(nbt).await;
+        })
+    }
+
+    fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            self.get_mob_entity().read_nbt_non_mut(nbt).await;
+            self.read_mob_nbt(nbt).await;
+        })
     }
 }
 
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         active_target::ActiveTargetGoal, flee_entity::FleeEntityGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, melee_attack::MeleeAttackGoal, swim::SwimGoal,
@@ -55,8 +55,6 @@ impl PhantomEntity {
     }
 }
 
-impl NBTStorage for PhantomEntity {}
-
 impl Mob for PhantomEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity, piglin::PiglinEntity},
 };
 
@@ -19,8 +19,6 @@ impl PiglinBruteEntity {
     }
 }
 
-impl NBTStorage for PiglinBruteEntity {}
-
 impl Mob for PiglinBruteEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.piglin.mob_entity
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{active_target::ActiveTargetGoal, look_at_entity::LookAtEntityGoal},
     mob::{Mob, MobEntity},
 };
@@ -45,8 +45,6 @@ impl ShulkerEntity {
     }
 }
 
-impl NBTStorage for ShulkerEntity {}
-
 impl Mob for ShulkerEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
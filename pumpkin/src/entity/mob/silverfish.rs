@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         active_target::ActiveTargetGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, melee_attack::MeleeAttackGoal, swim::SwimGoal,
@@ -49,8 +49,6 @@ impl SilverfishEntity {
     }
 }
 
-impl NBTStorage for SilverfishEntity {}
-
 impl Mob for SilverfishEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -1,9 +1,16 @@
 use std::sync::{Arc, Weak};
 
 use pumpkin_data::entity::EntityType;
+use pumpkin_data::tag::{Taggable, WorldgenBiome::MINECRAFT_ALLOWS_SURFACE_SLIME_SPAWNS};
+use pumpkin_util::Difficulty;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_util::random::RandomImpl;
+use pumpkin_util::random::legacy_rand::LegacyRand;
+use rand::RngExt;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         active_target::ActiveTargetGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, melee_attack::MeleeAttackGoal, swim::SwimGoal,
@@ -11,6 +18,8 @@ use crate::entity::{
     },
     mob::{Mob, MobEntity},
 };
+use crate::world::World;
+use crate::world::natural_spawner::SpawnLight;
 
 /// Slime — a bouncing hostile mob that splits into smaller slimes on death.
 ///
@@ -51,12 +60,80 @@ impl SlimeEntity {
 
         mob_arc
     }
-}
 
-impl NBTStorage for SlimeEntity {}
+    /// Whether slimes spawn below y 40 in `chunk`, which is decided by the world seed.
+    #[must_use]
+    pub fn is_slime_chunk(seed: u64, chunk: Vector2<i32>) -> bool {
+        let (x, z) = (chunk.x, chunk.y);
+        let chunk_seed = (seed as i64)
+            .wrapping_add(i64::from(x.wrapping_mul(x).wrapping_mul(4_987_142)))
+            .wrapping_add(i64::from(x.wrapping_mul(5_947_611)))
+            .wrapping_add(i64::from(z.wrapping_mul(z)).wrapping_mul(4_392_871))
+            .wrapping_add(i64::from(z.wrapping_mul(389_711)))
+            ^ 987_234_911;
+        LegacyRand::from_seed(chunk_seed as u64).next_bounded_i32(10) == 0
+    }
+
+    /// The natural spawn rules of slimes: in swamps on the surface, more often the fuller the
+    /// moon, and deep underground in slime chunks.
+    pub async fn can_spawn_naturally(world: &Arc<World>, pos: &BlockPos) -> bool {
+        if world.level_info.load().difficulty == Difficulty::Peaceful {
+            return false;
+        }
+
+        let y = pos.0.y;
+        if (51..70).contains(&y)
+            && world
+                .level
+                .get_rough_biome(pos)
+                .await
+                .has_tag(&MINECRAFT_ALLOWS_SURFACE_SLIME_SPAWNS)
+        {
+            let moon_brightness = world.level_time.lock().await.moon_brightness();
+            let light = SpawnLight::at(world, pos).await.brightness();
+            let (chance, moon, dark) = {
+                let mut rng = rand::rng();
+                (
+                    rng.random::<f32>(),
+                    rng.random::<f32>(),
+                    rng.random_range(0..8),
+                )
+            };
+            if chance < 0.5 && moon < moon_brightness && light <= dark {
+                return true;
+            }
+        }
+
+        y < 40
+            && Self::is_slime_chunk(world.level.seed.0, pos.chunk_position())
+            && rand::rng().random_range(0..10) == 0
+    }
+}
 
 impl Mob for SlimeEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slime_chunks_match_vanilla() {
+        for (x, z) in [
+            (-19, 5),
+            (-17, -3000),
+            (-14, 0),
+            (-4, -7),
+            (2, 2000),
+            (19, 0),
+        ] {
+            assert!(SlimeEntity::is_slime_chunk(0, Vector2::new(x, z)));
+        }
+        for (x, z) in [(-19, 0), (0, 0), (1, 5), (19, 2000)] {
+            assert!(!SlimeEntity::is_slime_chunk(0, Vector2::new(x, z)));
+        }
+    }
+}
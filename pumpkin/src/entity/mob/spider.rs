@@ -1,9 +1,13 @@
 use std::sync::{Arc, Weak};
 
+use pumpkin_data::effect::StatusEffect;
 use pumpkin_data::entity::EntityType;
+use pumpkin_data::potion::Effect;
+use pumpkin_util::Difficulty;
+use rand::RngExt;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBaseFuture,
     ai::goal::{
         active_target::ActiveTargetGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, swim::SwimGoal, wander_around::WanderAroundGoal,
@@ -11,6 +15,7 @@ use crate::entity::{
     },
     mob::{Mob, MobEntity},
 };
+use crate::world::regional_difficulty::RegionalDifficulty;
 
 pub struct SpiderEntity {
     pub mob_entity: MobEntity,
@@ -50,10 +55,44 @@ impl SpiderEntity {
     }
 }
 
-impl NBTStorage for SpiderEntity {}
-
 impl Mob for SpiderEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    fn finalize_spawn<'a>(
+        &'a self,
+        difficulty: &'a RegionalDifficulty,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            if difficulty.base != Difficulty::Hard {
+                return;
+            }
+            let effect = {
+                let mut rng = rand::rng();
+                if rng.random::<f32>() >= 0.1 * difficulty.special_multiplier() {
+                    return;
+                }
+                match rng.random_range(0..5) {
+                    1 => &StatusEffect::SPEED,
+                    2 => &StatusEffect::STRENGTH,
+                    3 => &StatusEffect::REGENERATION,
+                    4 => &StatusEffect::INVISIBILITY,
+                    _ => return,
+                }
+            };
+            self.mob_entity
+                .living_entity
+                .add_effect(Effect {
+                    effect_type: effect,
+                    duration: -1,
+                    amplifier: 0,
+                    ambient: false,
+                    show_particles: true,
+                    show_icon: true,
+                    blend: false,
+                })
+                .await;
+        })
+    }
 }
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity, skeleton::SkeletonEntityBase},
 };
 
@@ -19,8 +19,6 @@ impl StrayEntity {
     }
 }
 
-impl NBTStorage for StrayEntity {}
-
 impl Mob for StrayEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.skeleton.mob_entity
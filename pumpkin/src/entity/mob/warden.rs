@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         active_target::ActiveTargetGoal, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, melee_attack::MeleeAttackGoal, swim::SwimGoal,
@@ -53,8 +53,6 @@ impl WardenEntity {
     }
 }
 
-impl NBTStorage for WardenEntity {}
-
 impl Mob for WardenEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
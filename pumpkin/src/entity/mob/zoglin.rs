@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity, hoglin::HoglinEntity},
 };
 
@@ -20,8 +20,6 @@ impl ZoglinEntity {
     }
 }
 
-impl NBTStorage for ZoglinEntity {}
-
 impl Mob for ZoglinEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.hoglin.mob_entity
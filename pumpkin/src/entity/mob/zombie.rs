@@ -7,14 +7,19 @@ use crate::entity::ai::goal::step_and_destroy_block::{
 use crate::entity::ai::goal::zombie_attack::ZombieAttackGoal;
 use crate::entity::ai::goal::{Controls, Goal, GoalFuture, ParentHandle};
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBaseFuture,
     ai::goal::{active_target::ActiveTargetGoal, look_at_entity::LookAtEntityGoal, swim::SwimGoal},
 };
 use crate::world::World;
+use crate::world::regional_difficulty::RegionalDifficulty;
 use pumpkin_data::Block;
+use pumpkin_data::data_component_impl::EquipmentSlot;
 use pumpkin_data::entity::EntityType;
+use pumpkin_data::item::Item;
 use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_util::Difficulty;
 use pumpkin_util::math::position::BlockPos;
+use pumpkin_world::item::ItemStack;
 use rand::{RngExt, rng};
 use std::pin::Pin;
 use std::sync::{Arc, Weak};
@@ -56,12 +61,44 @@ impl ZombieEntity {
     }
 }
 
-impl NBTStorage for ZombieEntity {}
-
 impl Mob for ZombieEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    fn finalize_spawn<'a>(
+        &'a self,
+        difficulty: &'a RegionalDifficulty,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            self.mob_entity.populate_armor(difficulty).await;
+
+            let weapon = {
+                let mut rng = rng();
+                let chance = if difficulty.base == Difficulty::Hard {
+                    0.05
+                } else {
+                    0.01
+                };
+                (rng.random::<f32>() < chance).then(|| {
+                    if rng.random_range(0..3) == 0 {
+                        &Item::IRON_SWORD
+                    } else {
+                        &Item::IRON_SHOVEL
+                    }
+                })
+            };
+            if let Some(weapon) = weapon {
+                self.mob_entity
+                    .living_entity
+                    .entity_equipment
+                    .lock()
+                    .await
+                    .put(&EquipmentSlot::MAIN_HAND, ItemStack::new(1, weapon))
+                    .await;
+            }
+        })
+    }
 }
 
 pub struct DestroyEggGoal {
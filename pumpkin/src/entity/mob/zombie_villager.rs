@@ -1,6 +1,6 @@
 use super::{Mob, MobEntity};
+use crate::entity::Entity;
 use crate::entity::mob::zombie::ZombieEntity;
-use crate::entity::{Entity, NBTStorage};
 use std::sync::Arc;
 
 pub struct ZombieVillagerEntity {
@@ -15,8 +15,6 @@ impl ZombieVillagerEntity {
     }
 }
 
-impl NBTStorage for ZombieVillagerEntity {}
-
 impl Mob for ZombieVillagerEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity.mob_entity
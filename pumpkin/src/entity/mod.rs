@@ -1,14 +1,20 @@
+use crate::entity::area_effect_cloud::AreaEffectCloudEntity;
 use crate::entity::item::ItemEntity;
 use crate::net::ClientPlatform;
 use crate::world::World;
+use crate::world::regional_difficulty::RegionalDifficulty;
 use crate::{
     server::Server,
-    world::portal::{NetherPortal, PortalManager, PortalSearchResult, SourcePortalInfo},
+    world::portal::{
+        NetherPortal, PortalManager, PortalSearchResult, SourcePortalInfo, end::EndPortal,
+    },
 };
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use bytes::BufMut;
 use crossbeam::atomic::AtomicCell;
+use lightning::LightningEntity;
 use living::LivingEntity;
+use mob::Mob;
 use player::Player;
 use pumpkin_data::BlockState;
 use pumpkin_data::block_properties::{EnumVariants, Integer0To15};
@@ -22,7 +28,7 @@ use pumpkin_data::{
     block_properties::{Facing, HorizontalFacing},
     damage::DamageType,
     entity::{EntityPose, EntityType},
-    sound::{Sound, SoundCategory},
+    sound::Sound,
 };
 use pumpkin_nbt::{compound::NbtCompound, tag::NbtTag};
 use pumpkin_protocol::java::client::play::{CUpdateEntityPos, CUpdateEntityPosRot};
@@ -45,6 +51,7 @@ use pumpkin_util::math::{
 use pumpkin_util::text::TextComponent;
 use pumpkin_util::text::hover::HoverEvent;
 use pumpkin_util::version::MinecraftVersion;
+use pumpkin_world::item::ItemStack;
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::pin::Pin;
@@ -59,20 +66,27 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 pub mod ai;
+pub mod area_effect_cloud;
 pub mod boss;
 pub mod breath;
+pub mod chat_limiter;
 pub mod decoration;
 pub mod effect;
 pub mod experience_orb;
 pub mod falling;
+pub mod feedback;
 pub mod hunger;
 pub mod item;
+pub mod leash;
+pub mod lightning;
 pub mod living;
 pub mod mob;
+pub mod movement;
 pub mod passive;
 pub mod player;
 pub mod projectile;
 pub mod projectile_deflection;
+pub mod sounds;
 pub mod tnt;
 pub mod r#type;
 
@@ -105,6 +119,16 @@ pub trait EntityBase: Send + Sync + NBTStorage {
         })
     }
 
+    /// Called instead of [`EntityBase::tick`] while the entity is out of its activation range.
+    /// Entities that have no cheaper tick are ticked as usual.
+    fn inactive_tick<'a>(
+        &'a self,
+        caller: Arc<dyn EntityBase>,
+        server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        self.tick(caller, server)
+    }
+
     fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
         Box::pin(async move {
             let entity = self.get_entity();
@@ -121,6 +145,7 @@ pub trait EntityBase: Send + Sync + NBTStorage {
                     )])
                     .await;
             }
+            entity.send_custom_name().await;
         })
     }
 
@@ -209,6 +234,47 @@ pub trait EntityBase: Send + Sync + NBTStorage {
         Box::pin(async {})
     }
 
+    /// Called when a player uses `stack` on this entity. Returns whether the interaction did
+    /// something, so that the other hand is not used as well.
+    fn interact<'a>(
+        &'a self,
+        _player: &'a Player,
+        _stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async { false })
+    }
+
+    /// Called when a player uses `stack` on the point `hit` of this entity, relative to its
+    /// position. Entities where the clicked part matters, like armor stands, handle this instead
+    /// of [`Self::interact`].
+    fn interact_at<'a>(
+        &'a self,
+        _player: &'a Player,
+        _hit: Vector3<f64>,
+        _stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async { false })
+    }
+
+    /// Called when a lightning bolt strikes this entity. Sets it on fire and damages it by
+    /// default.
+    fn on_struck_by_lightning<'a>(
+        &'a self,
+        caller: &'a dyn EntityBase,
+        _lightning: &'a LightningEntity,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move { self.get_entity().struck_by_lightning(caller).await })
+    }
+
+    /// Called before a naturally spawned entity is added to the world, to equip it for the
+    /// difficulty where it spawns.
+    fn finalize_spawn<'a>(
+        &'a self,
+        _difficulty: &'a RegionalDifficulty,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
     fn get_entity(&self) -> &Entity;
     fn get_living_entity(&self) -> Option<&LivingEntity>;
 
@@ -216,33 +282,45 @@ pub trait EntityBase: Send + Sync + NBTStorage {
         None
     }
 
+    fn get_mob(self: Arc<Self>) -> Option<Arc<dyn Mob>> {
+        None
+    }
+
     fn get_player(&self) -> Option<&Player> {
         None
     }
 
+    fn get_area_effect_cloud(&self) -> Option<&AreaEffectCloudEntity> {
+        None
+    }
+
     /// Should return the name of the entity without click or hover events.
     fn get_name(&self) -> TextComponent {
         let entity = self.get_entity();
-        entity
-            .custom_name
-            .clone()
-            .unwrap_or(TextComponent::translate(
-                format!("entity.minecraft.{}", entity.entity_type.resource_name),
-                [],
-            ))
+        entity.custom_name.load_full().map_or_else(
+            || {
+                TextComponent::translate(
+                    format!("entity.minecraft.{}", entity.entity_type.resource_name),
+                    [],
+                )
+            },
+            |name| (*name).clone(),
+        )
     }
 
     fn get_display_name(&self) -> EntityBaseFuture<'_, TextComponent> {
         Box::pin(async move {
             // TODO: team color
             let entity = self.get_entity();
-            let mut name = entity
-                .custom_name
-                .clone()
-                .unwrap_or(TextComponent::translate(
-                    format!("entity.minecraft.{}", entity.entity_type.resource_name),
-                    [],
-                ));
+            let mut name = entity.custom_name.load_full().map_or_else(
+                || {
+                    TextComponent::translate(
+                        format!("entity.minecraft.{}", entity.entity_type.resource_name),
+                        [],
+                    )
+                },
+                |name| (*name).clone(),
+            );
             let name_clone = name.clone();
             name = name.hover_event(HoverEvent::show_entity(
                 entity.entity_uuid.to_string(),
@@ -303,6 +381,12 @@ impl RemovalReason {
 
 static CURRENT_ID: AtomicI32 = AtomicI32::new(0);
 
+/// Reserves `count` entity ids in a row and returns the first one, for entities whose parts
+/// clients number after them, like the ender dragon.
+pub fn reserve_entity_ids(count: i32) -> i32 {
+    CURRENT_ID.fetch_add(count, Relaxed)
+}
+
 /// Represents a non-living Entity (e.g. Item, Egg, Snowball...)
 pub struct Entity {
     /// A unique identifier for the entity
@@ -332,6 +416,8 @@ pub struct Entity {
     pub invisible: AtomicBool,
     /// Indicates whether the entity is flying due to a fall
     pub fall_flying: AtomicBool,
+    /// Indicates whether the entity is swimming
+    pub swimming: AtomicBool,
     /// The entity's current velocity vector, aka knockback
     pub velocity: AtomicCell<Vector3<f64>>,
     /// Tracks a horizontal collision
@@ -383,9 +469,9 @@ pub struct Entity {
 
     pub portal_manager: Mutex<Option<Mutex<PortalManager>>>,
     /// Custom name for the entity
-    pub custom_name: Option<TextComponent>,
-    /// Indicates whether the entity's custom name is visible
-    pub custom_name_visible: bool,
+    pub custom_name: ArcSwapOption<TextComponent>,
+    /// Indicates whether the entity's custom name is always shown, not only when looked at
+    pub custom_name_visible: AtomicBool,
     /// The data send in the Entity Spawn packet
     pub data: AtomicI32,
     /// If true, the entity cannot collide with anything (e.g. spectator)
@@ -536,6 +622,7 @@ impl Entity {
             world: ArcSwap::new(world),
             sprinting: AtomicBool::new(false),
             fall_flying: AtomicBool::new(false),
+            swimming: AtomicBool::new(false),
             yaw: AtomicCell::new(0.0),
             head_yaw: AtomicCell::new(0.0),
             body_yaw: AtomicCell::new(0.0),
@@ -562,8 +649,8 @@ impl Entity {
             age: AtomicI32::new(0),
             portal_cooldown: AtomicU32::new(0),
             portal_manager: Mutex::new(None),
-            custom_name: None,
-            custom_name_visible: false,
+            custom_name: ArcSwapOption::empty(),
+            custom_name_visible: AtomicBool::new(false),
             no_clip: AtomicBool::new(false),
             movement_multiplier: AtomicCell::new(Vector3::default()),
             velocity_dirty: AtomicBool::new(true),
@@ -590,6 +677,7 @@ impl Entity {
 
     /// Sets a custom name for the entity, typically used with nametags
     pub async fn set_custom_name(&self, name: TextComponent) {
+        self.custom_name.store(Some(Arc::new(name.clone())));
         self.send_meta_data(&[Metadata::new(
             TrackedData::DATA_CUSTOM_NAME,
             MetaDataType::OptionalTextComponent,
@@ -598,6 +686,37 @@ impl Entity {
         .await;
     }
 
+    /// Sets whether the custom name is always shown above the entity.
+    pub async fn set_custom_name_visible(&self, visible: bool) {
+        self.custom_name_visible.store(visible, Relaxed);
+        self.send_meta_data(&[Metadata::new(
+            TrackedData::DATA_NAME_VISIBLE,
+            MetaDataType::Boolean,
+            visible,
+        )])
+        .await;
+    }
+
+    /// Sends the custom name to the players that see the entity, if it has one.
+    async fn send_custom_name(&self) {
+        let Some(name) = self.custom_name.load_full() else {
+            return;
+        };
+        self.send_meta_data(&[
+            Metadata::new(
+                TrackedData::DATA_CUSTOM_NAME,
+                MetaDataType::OptionalTextComponent,
+                Some((*name).clone()),
+            ),
+            Metadata::new(
+                TrackedData::DATA_NAME_VISIBLE,
+                MetaDataType::Boolean,
+                self.custom_name_visible.load(Relaxed),
+            ),
+        ])
+        .await;
+    }
+
     pub async fn send_velocity(&self) {
         let velocity = self.velocity.load();
         self.world
@@ -650,10 +769,15 @@ impl Entity {
                 if get_section_cord(floor_x) != chunk_pos.x
                     || get_section_cord(floor_z) != chunk_pos.y
                 {
-                    self.chunk_pos.store(Vector2::new(
+                    let new_chunk_pos = Vector2::new(
                         get_section_cord(new_block_pos.x),
                         get_section_cord(new_block_pos.z),
-                    ));
+                    );
+                    self.chunk_pos.store(new_chunk_pos);
+                    self.world
+                        .load()
+                        .chunk_entity_counts
+                        .moved(self.entity_id, new_chunk_pos);
                 }
             }
         }
@@ -717,7 +841,8 @@ impl Entity {
             .await;
     }
 
-    fn default_portal_cooldown(&self) -> u32 {
+    /// Ticks until the entity can use a portal again after it used one.
+    pub fn default_portal_cooldown(&self) -> u32 {
         if self.entity_type == &EntityType::PLAYER {
             10
         } else {
@@ -798,13 +923,13 @@ impl Entity {
         let velocity = self.velocity.load();
 
         self.velocity.store(Vector3::new(
-            velocity.x / 2.0 - var8.x,
+            velocity.x.mul_add(friction, -var8.x),
             if self.on_ground.load(Relaxed) {
-                (velocity.y / 2.0 + strength).min(0.4)
+                velocity.y.mul_add(friction, strength).min(max_vertical)
             } else {
                 velocity.y
             },
-            velocity.z / 2.0 - var8.z,
+            velocity.z.mul_add(friction, -var8.z),
         ));
     }
 
@@ -1164,6 +1289,26 @@ impl Entity {
         }
     }
 
+    /// The horizontal push `other` gets away from this entity when they overlap. This entity
+    /// gets the opposite push. `None` if they are at the same spot or either one has no clip.
+    #[must_use]
+    pub fn push_away_from(&self, other: &Self) -> Option<Vector3<f64>> {
+        if self.no_clip.load(Ordering::Relaxed) || other.no_clip.load(Ordering::Relaxed) {
+            return None;
+        }
+        let pos = self.pos.load();
+        let other_pos = other.pos.load();
+        let dx = other_pos.x - pos.x;
+        let dz = other_pos.z - pos.z;
+        let distance = dx.abs().max(dz.abs());
+        if distance < 0.01 {
+            return None;
+        }
+        let distance = distance.sqrt();
+        let scale = (1.0 / distance).min(1.0) * 0.05 / distance;
+        Some(Vector3::new(dx * scale, 0.0, dz * scale))
+    }
+
     async fn get_pos_with_y_offset(
         &self,
         offset: f64,
@@ -1410,6 +1555,13 @@ impl Entity {
             if portal_manager.tick() {
                 self.portal_cooldown
                     .store(self.default_portal_cooldown(), Ordering::Relaxed);
+                if self.world.load().get_block(&portal_manager.pos).await == &Block::END_PORTAL {
+                    let dest_world = portal_manager.portal_world.clone();
+                    drop(portal_manager);
+                    *manager_guard = None;
+                    EndPortal::teleport(caller.clone(), dest_world).await;
+                    return;
+                }
                 let pos = self.pos.load();
                 let current_yaw = self.yaw.load();
                 let dimensions = self.entity_dimension.load();
@@ -1417,14 +1569,20 @@ impl Entity {
                 let scale_factor_current = self.world.load().dimension.coordinate_scale;
 
                 let scale_factor = scale_factor_current / scale_factor_new;
-                let target_pos =
-                    BlockPos::floored(pos.x * scale_factor, pos.y, pos.z * scale_factor);
 
                 let dest_world = portal_manager.portal_world.clone();
                 let source_portal = portal_manager.source_portal.clone();
                 let source_axis = source_portal.as_ref().map(|p| p.axis);
                 drop(portal_manager);
 
+                // Scaling by 8 can land far outside the border of the other world
+                let (target_x, target_z) = dest_world
+                    .worldborder
+                    .lock()
+                    .await
+                    .clamp(pos.x * scale_factor, pos.z * scale_factor);
+                let target_pos = BlockPos::floored(target_x, pos.y, target_z);
+
                 let (teleport_pos, new_yaw) = if let Some(dest_result) =
                     NetherPortal::search_for_portal(&dest_world, target_pos).await
                 {
@@ -1608,6 +1766,14 @@ impl Entity {
         self.fire_ticks.store(0, Ordering::Relaxed);
     }
 
+    /// The default effect of a lightning strike on an entity.
+    pub async fn struck_by_lightning(&self, caller: &dyn EntityBase) {
+        if self.fire_ticks.fetch_add(1, Ordering::Relaxed) == -1 {
+            self.set_on_fire_for(8.0);
+        }
+        caller.damage(caller, 5.0, DamageType::LIGHTNING_BOLT).await;
+    }
+
     pub fn set_on_fire_for(&self, seconds: f32) {
         self.set_on_fire_for_ticks((seconds * 20.0).floor() as u32);
     }
@@ -1719,7 +1885,9 @@ impl Entity {
     /// Applies knockback to the entity, following vanilla Minecraft's mechanics.
     ///
     /// This function calculates the entity's new velocity based on the specified knockback strength and direction.
-    pub fn knockback(&self, strength: f64, x: f64, z: f64) {
+    /// Pushes the entity away from `x`, `z`. It keeps `friction` of its velocity, and gets at
+    /// most `max_vertical` upward velocity when on the ground.
+    pub fn knockback(&self, strength: f64, x: f64, z: f64, friction: f64, max_vertical: f64) {
         // This has some vanilla magic
         let mut x = x;
         let mut z = z;
@@ -1741,27 +1909,24 @@ impl Entity {
         ));
     }
 
+    /// Sets the sneaking flag. Players crouch once their pose is updated, see
+    /// [`Player::update_pose`](crate::entity::player::Player::update_pose).
     pub async fn set_sneaking(&self, sneaking: bool) {
         //assert!(self.sneaking.load(Relaxed) != sneaking);
         self.sneaking.store(sneaking, Relaxed);
-        self.set_flag(Flag::Sneaking, sneaking).await;
-        if sneaking {
-            self.set_pose(EntityPose::Crouching).await;
-        } else {
-            self.set_pose(EntityPose::Standing).await;
-        }
+        self.send_flags().await;
     }
 
     pub async fn set_invisible(&self, invisible: bool) {
         assert!(self.invisible.load(Relaxed) != invisible);
         self.invisible.store(invisible, Relaxed);
-        self.set_flag(Flag::Invisible, invisible).await;
+        self.send_flags().await;
     }
 
     pub async fn set_on_fire(&self, on_fire: bool) {
         if self.has_visual_fire.load(Ordering::Relaxed) != on_fire {
             self.has_visual_fire.store(on_fire, Ordering::Relaxed);
-            self.set_flag(Flag::OnFire, on_fire).await;
+            self.send_flags().await;
         }
     }
 
@@ -1892,40 +2057,55 @@ impl Entity {
     pub async fn set_sprinting(&self, sprinting: bool) {
         //assert!(self.sprinting.load(Relaxed) != sprinting);
         self.sprinting.store(sprinting, Relaxed);
-        self.set_flag(Flag::Sprinting, sprinting).await;
-    }
-
-    pub fn check_fall_flying(&self) -> bool {
-        !self.on_ground.load(Relaxed)
+        self.send_flags().await;
     }
 
     pub async fn set_fall_flying(&self, fall_flying: bool) {
         assert!(self.fall_flying.load(Relaxed) != fall_flying);
         self.fall_flying.store(fall_flying, Relaxed);
-        self.set_flag(Flag::FallFlying, fall_flying).await;
+        self.send_flags().await;
     }
 
-    async fn set_flag(&self, flag: Flag, value: bool) {
-        let index = flag as u8;
-        let mut b = 0i8;
-        if value {
-            b |= 1 << index;
-        } else {
-            b &= !(1 << index);
-        }
+    pub async fn set_swimming(&self, swimming: bool) {
+        self.swimming.store(swimming, Relaxed);
+        self.send_flags().await;
+    }
+
+    /// Sends all of the entity's flags, as clients only get them together.
+    async fn send_flags(&self) {
         self.send_meta_data(&[Metadata::new(
             TrackedData::DATA_FLAGS,
             MetaDataType::Byte,
-            b,
+            self.flags(),
         )])
         .await;
     }
 
+    /// The entity's flags, packed into one byte.
+    fn flags(&self) -> i8 {
+        let flags = [
+            (Flag::OnFire, self.has_visual_fire.load(Relaxed)),
+            (Flag::Sneaking, self.sneaking.load(Relaxed)),
+            (Flag::Sprinting, self.sprinting.load(Relaxed)),
+            (Flag::Swimming, self.swimming.load(Relaxed)),
+            (Flag::Invisible, self.invisible.load(Relaxed)),
+            (Flag::FallFlying, self.fall_flying.load(Relaxed)),
+        ];
+        flags
+            .into_iter()
+            .filter(|(_, set)| *set)
+            .fold(0i8, |b, (flag, _)| b | 1 << flag as u8)
+    }
+
     /// Plays sound at this entity's position with the entity's sound category
     pub async fn play_sound(&self, sound: Sound) {
         self.world
             .load()
-            .play_sound(sound, SoundCategory::Neutral, &self.pos.load())
+            .play_sound(
+                sound,
+                sounds::sound_category(self.entity_type),
+                &self.pos.load(),
+            )
             .await;
     }
 
@@ -1957,28 +2137,36 @@ impl Entity {
         }
     }
 
-    pub async fn set_pose(&self, pose: EntityPose) {
+    /// Whether the entity would fit where it is in `pose`.
+    pub async fn can_change_into_pose(&self, pose: EntityPose) -> bool {
         let dimension = Self::get_entity_dimensions(pose);
         let position = self.pos.load();
         let aabb = BoundingBox::new_from_pos(position.x, position.y, position.z, &dimension);
-        if self
-            .world
+        self.world
             .load()
             .is_space_empty(aabb.contract_all(1.0E-7))
             .await
-        {
-            self.pose.store(pose);
-            let dimension = Self::get_entity_dimensions(pose);
-            self.bounding_box.store(aabb);
-            self.entity_dimension.store(dimension);
-            let pose = pose as i32;
-            self.send_meta_data(&[Metadata::new(
-                TrackedData::DATA_POSE,
-                MetaDataType::EntityPose,
-                VarInt(pose),
-            )])
-            .await;
+    }
+
+    /// Switches to `pose` along with its bounding box, whether the entity fits or not. Check
+    /// [`Self::can_change_into_pose`] first where that matters.
+    pub async fn set_pose(&self, pose: EntityPose) {
+        if self.pose.swap(pose) == pose {
+            return;
         }
+        let dimension = Self::get_entity_dimensions(pose);
+        let position = self.pos.load();
+        self.bounding_box.store(BoundingBox::new_from_pos(
+            position.x, position.y, position.z, &dimension,
+        ));
+        self.entity_dimension.store(dimension);
+        let pose = pose as i32;
+        self.send_meta_data(&[Metadata::new(
+            TrackedData::DATA_POSE,
+            MetaDataType::EntityPose,
+            VarInt(pose),
+        )])
+        .await;
     }
 
     pub fn is_invulnerable_to(&self, damage_type: &DamageType) -> bool {
@@ -2090,6 +2278,7 @@ impl Entity {
     pub async fn reset_state(&self) {
         self.pose.store(EntityPose::Standing);
         self.fall_flying.store(false, Relaxed);
+        self.swimming.store(false, Relaxed);
         self.extinguish();
         self.set_on_fire(false).await;
     }
@@ -2141,6 +2330,16 @@ impl NBTStorage for Entity {
             if self.has_visual_fire.load(Relaxed) {
                 nbt.put_bool("HasVisualFire", true);
             }
+            let frozen_ticks = self.frozen_ticks.load(Relaxed);
+            if frozen_ticks > 0 {
+                nbt.put_int("TicksFrozen", frozen_ticks);
+            }
+            if let Some(name) = self.custom_name.load_full() {
+                nbt.put_string("CustomName", (*name).clone().get_text());
+                if self.custom_name_visible.load(Relaxed) {
+                    nbt.put_bool("CustomNameVisible", true);
+                }
+            }
 
             // todo more...
         })
@@ -2148,21 +2347,34 @@ impl NBTStorage for Entity {
 
     fn read_nbt_non_mut<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
         Box::pin(async {
-            let position = nbt.get_list("Pos").unwrap();
-            let x = position[0].extract_double().unwrap_or(0.0);
-            let y = position[1].extract_double().unwrap_or(0.0);
-            let z = position[2].extract_double().unwrap_or(0.0);
-            let pos = Vector3::new(x, y, z);
+            // Malformed entities are loaded at the origin rather than crashing the chunk load
+            let double = |list: Option<&[NbtTag]>, i: usize| {
+                list.and_then(|list| list.get(i))
+                    .and_then(NbtTag::extract_double)
+                    .unwrap_or(0.0)
+            };
+            let position = nbt.get_list("Pos");
+            let pos = Vector3::new(
+                double(position, 0),
+                double(position, 1),
+                double(position, 2),
+            );
             self.set_pos(pos);
             self.first_loaded_chunk_position.store(Some(pos.to_i32()));
-            let velocity = nbt.get_list("Motion").unwrap();
-            let x = velocity[0].extract_double().unwrap_or(0.0);
-            let y = velocity[1].extract_double().unwrap_or(0.0);
-            let z = velocity[2].extract_double().unwrap_or(0.0);
-            self.velocity.store(Vector3::new(x, y, z));
-            let rotation = nbt.get_list("Rotation").unwrap();
-            let yaw = rotation[0].extract_float().unwrap_or(0.0);
-            let pitch = rotation[1].extract_float().unwrap_or(0.0);
+            let velocity = nbt.get_list("Motion");
+            self.velocity.store(Vector3::new(
+                double(velocity, 0),
+                double(velocity, 1),
+                double(velocity, 2),
+            ));
+            let rotation = nbt.get_list("Rotation");
+            let float = |i: usize| {
+                rotation
+                    .and_then(|rotation| rotation.get(i))
+                    .and_then(NbtTag::extract_float)
+                    .unwrap_or(0.0)
+            };
+            let (yaw, pitch) = (float(0), float(1));
             self.set_rotation(yaw, pitch);
             self.head_yaw.store(yaw);
             self.fire_ticks
@@ -2175,6 +2387,14 @@ impl NBTStorage for Entity {
                 .store(nbt.get_int("PortalCooldown").unwrap_or(0) as u32, Relaxed);
             self.has_visual_fire
                 .store(nbt.get_bool("HasVisualFire").unwrap_or(false), Relaxed);
+            self.frozen_ticks
+                .store(nbt.get_int("TicksFrozen").unwrap_or(0), Relaxed);
+            self.custom_name.store(
+                nbt.get_string("CustomName")
+                    .map(|name| Arc::new(TextComponent::text(name.to_string()))),
+            );
+            self.custom_name_visible
+                .store(nbt.get_bool("CustomNameVisible").unwrap_or(false), Relaxed);
             // todo more...
         })
     }
@@ -2294,3 +2514,51 @@ pub enum Flag {
     /// Indicates if the entity is flying due to a fall.
     FallFlying = 7,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_entity(world: Arc<World>) -> Entity {
+        Entity::new(world, Vector3::new(0.0, 64.0, 0.0), &EntityType::PLAYER)
+    }
+
+    #[tokio::test]
+    async fn flags_are_sent_together() {
+        let folder = tempfile::tempdir().unwrap();
+        let entity = new_entity(World::for_test(folder.path()));
+        assert_eq!(entity.flags(), 0);
+
+        entity.set_sneaking(true).await;
+        entity.set_sprinting(true).await;
+        entity.set_on_fire(true).await;
+        assert_eq!(
+            entity.flags(),
+            1 << Flag::OnFire as u8 | 1 << Flag::Sneaking as u8 | 1 << Flag::Sprinting as u8
+        );
+
+        entity.set_sneaking(false).await;
+        entity.set_fall_flying(true).await;
+        assert_eq!(
+            entity.flags(),
+            1 << Flag::OnFire as u8 | 1 << Flag::Sprinting as u8 | 1 << Flag::FallFlying as u8
+        );
+    }
+
+    #[tokio::test]
+    async fn poses_switch_the_bounding_box() {
+        let folder = tempfile::tempdir().unwrap();
+        let entity = new_entity(World::for_test(folder.path()));
+
+        entity.set_pose(EntityPose::Swimming).await;
+        assert!(entity.pose.load() == EntityPose::Swimming);
+        assert_eq!(entity.entity_dimension.load().height, 0.6);
+        let bounding_box = entity.bounding_box.load();
+        assert!((bounding_box.max.y - bounding_box.min.y - 0.6).abs() < 1.0E-6);
+
+        entity.set_pose(EntityPose::Crouching).await;
+        assert_eq!(entity.entity_dimension.load().height, 1.5);
+        let bounding_box = entity.bounding_box.load();
+        assert!((bounding_box.max.y - bounding_box.min.y - 1.5).abs() < 1.0E-6);
+    }
+}
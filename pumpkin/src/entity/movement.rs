@@ -0,0 +1,300 @@
+//! Checks of the moves players report, see [`MovementConfig`].
+
+use std::sync::atomic::Ordering::Relaxed;
+
+use crossbeam::atomic::AtomicCell;
+use pumpkin_config::movement::MovementConfig;
+use pumpkin_data::Block;
+use pumpkin_data::effect::StatusEffect;
+use pumpkin_data::tag::{self, Taggable};
+use pumpkin_protocol::java::client::play::CPlayerPosition;
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::entity::player::Player;
+use crate::net::ClientPlatform;
+
+/// Ticks after which an unconfirmed teleport is sent again, in case the client missed it.
+const TELEPORT_RESEND_TICKS: u32 = 20;
+
+/// The check a move failed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MovementViolation {
+    /// The player moved too far horizontally.
+    HorizontalSpeed,
+    /// The player moved up too far.
+    VerticalSpeed,
+    /// The player stayed in the air for too long without anything holding them up.
+    Hovering,
+}
+
+/// How far a player may still move, refilled every tick.
+pub struct MovementValidator {
+    horizontal_allowance: AtomicCell<f64>,
+    vertical_allowance: AtomicCell<f64>,
+    /// Moves in a row that went up or stayed level in the air.
+    hover_moves: AtomicCell<u32>,
+    /// Ticks since the pending teleport was last sent.
+    teleport_ticks: AtomicCell<u32>,
+}
+
+impl Default for MovementValidator {
+    fn default() -> Self {
+        Self {
+            // Capped by the first tick, so that moves before it pass
+            horizontal_allowance: AtomicCell::new(f64::INFINITY),
+            vertical_allowance: AtomicCell::new(f64::INFINITY),
+            hover_moves: AtomicCell::new(0),
+            teleport_ticks: AtomicCell::new(0),
+        }
+    }
+}
+
+impl MovementValidator {
+    pub async fn tick(&self, player: &Player, config: &MovementConfig) {
+        self.resend_unconfirmed_teleport(player).await;
+        if !config.enabled {
+            return;
+        }
+        let (horizontal, vertical) = Self::limits(player, config).await;
+        self.refill(horizontal, vertical, config.buffered_ticks);
+    }
+
+    /// Adds a tick of movement to the allowance, keeping at most `buffered_ticks` of it.
+    fn refill(&self, horizontal: f64, vertical: f64, buffered_ticks: u32) {
+        let buffered = f64::from(buffered_ticks.max(1));
+        self.horizontal_allowance
+            .store((self.horizontal_allowance.load() + horizontal).min(horizontal * buffered));
+        self.vertical_allowance
+            .store((self.vertical_allowance.load() + vertical).min(vertical * buffered));
+    }
+
+    /// Checks the move of `player` from `from` to `to`, using up some of their allowance.
+    pub async fn check(
+        &self,
+        player: &Player,
+        from: Vector3<f64>,
+        to: Vector3<f64>,
+        on_ground: bool,
+        config: &MovementConfig,
+    ) -> Option<MovementViolation> {
+        let entity = &player.living_entity.entity;
+        if !config.enabled || entity.vehicle.lock().await.is_some() {
+            return None;
+        }
+
+        if let Some(violation) = self.use_allowance(from, to) {
+            return Some(violation);
+        }
+        if config.max_hover_moves == 0 {
+            return None;
+        }
+        let hovering = !on_ground && to.y >= from.y && !Self::is_held_up(player).await;
+        self.count_hover(hovering, config.max_hover_moves)
+    }
+
+    /// Takes the move from `from` to `to` off the allowance. Moving down is free.
+    fn use_allowance(&self, from: Vector3<f64>, to: Vector3<f64>) -> Option<MovementViolation> {
+        let allowance = self.horizontal_allowance.load() - (to.x - from.x).hypot(to.z - from.z);
+        self.horizontal_allowance.store(allowance.max(0.0));
+        if allowance < 0.0 {
+            return Some(MovementViolation::HorizontalSpeed);
+        }
+        let rise = to.y - from.y;
+        if rise > 0.0 {
+            let allowance = self.vertical_allowance.load() - rise;
+            self.vertical_allowance.store(allowance.max(0.0));
+            if allowance < 0.0 {
+                return Some(MovementViolation::VerticalSpeed);
+            }
+        }
+        None
+    }
+
+    /// Counts a move that went up or stayed level in the air, or resets the count otherwise.
+    fn count_hover(&self, hovering: bool, max_hover_moves: u32) -> Option<MovementViolation> {
+        if !hovering {
+            self.hover_moves.store(0);
+            return None;
+        }
+        let hover_moves = self.hover_moves.load() + 1;
+        self.hover_moves.store(hover_moves);
+        (hover_moves > max_hover_moves).then_some(MovementViolation::Hovering)
+    }
+
+    /// How far `player` may move horizontally and up in a tick.
+    async fn limits(player: &Player, config: &MovementConfig) -> (f64, f64) {
+        let living = &player.living_entity;
+        let levels = async |effect: &'static StatusEffect| {
+            living
+                .get_effect(effect)
+                .await
+                .map_or(0.0, |effect| f64::from(effect.amplifier) + 1.0)
+        };
+        let (flying, fly_speed) = {
+            let abilities = player.abilities.lock().await;
+            (abilities.flying, f64::from(abilities.fly_speed))
+        };
+
+        let (horizontal, vertical) = if living.entity.fall_flying.load(Relaxed) {
+            (config.max_elytra_speed, config.max_elytra_speed)
+        } else if flying {
+            let speed = config.max_flying_speed * fly_speed / 0.05;
+            (speed, speed)
+        } else {
+            let boost =
+                levels(&StatusEffect::JUMP_BOOST).await + levels(&StatusEffect::LEVITATION).await;
+            (
+                config.max_horizontal_speed * levels(&StatusEffect::SPEED).await.mul_add(0.2, 1.0),
+                boost.mul_add(0.1, config.max_vertical_speed),
+            )
+        };
+        // Knockback and other pushes from the server
+        let velocity = living.entity.velocity.load();
+        (
+            horizontal + velocity.x.hypot(velocity.z),
+            vertical + velocity.y.max(0.0),
+        )
+    }
+
+    /// Whether something other than the ground keeps `player` from falling.
+    async fn is_held_up(player: &Player) -> bool {
+        let entity = &player.living_entity.entity;
+        if player.abilities.lock().await.allow_flying
+            || entity.fall_flying.load(Relaxed)
+            || entity.touching_water.load(Relaxed)
+            || entity.touching_lava.load(Relaxed)
+            || entity.velocity.load().y > 0.0
+            || player
+                .living_entity
+                .has_effect(&StatusEffect::LEVITATION)
+                .await
+        {
+            return true;
+        }
+        let block = entity
+            .world
+            .load()
+            .get_block(&entity.block_pos.load())
+            .await;
+        block.has_tag(&tag::Block::MINECRAFT_CLIMBABLE) || block.id == Block::POWDER_SNOW.id
+    }
+
+    /// Sends the pending teleport of `player` again if the client hasn't confirmed it for a while.
+    /// Moves are ignored until it does.
+    async fn resend_unconfirmed_teleport(&self, player: &Player) {
+        let ClientPlatform::Java(client) = &player.client else {
+            return;
+        };
+        let awaiting_teleport = *player.awaiting_teleport.lock().await;
+        let Some((teleport_id, position)) = awaiting_teleport else {
+            self.teleport_ticks.store(0);
+            return;
+        };
+        let ticks = self.teleport_ticks.load() + 1;
+        if ticks < TELEPORT_RESEND_TICKS {
+            self.teleport_ticks.store(ticks);
+            return;
+        }
+        self.teleport_ticks.store(0);
+        let entity = &player.living_entity.entity;
+        client
+            .enqueue_packet(&CPlayerPosition::new(
+                teleport_id,
+                position,
+                Vector3::new(0.0, 0.0, 0.0),
+                entity.yaw.load(),
+                entity.pitch.load(),
+                Vec::new(),
+            ))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moved(x: f64, y: f64, z: f64) -> (Vector3<f64>, Vector3<f64>) {
+        (Vector3::new(0.0, 64.0, 0.0), Vector3::new(x, 64.0 + y, z))
+    }
+
+    #[test]
+    fn moves_before_the_first_tick_pass() {
+        let validator = MovementValidator::default();
+        let (from, to) = moved(100.0, 50.0, 0.0);
+        assert_eq!(validator.use_allowance(from, to), None);
+    }
+
+    #[test]
+    fn horizontal_moves_are_limited_per_tick() {
+        let validator = MovementValidator::default();
+        validator.refill(1.0, 0.8, 1);
+
+        let (from, to) = moved(0.0, 0.0, 1.0);
+        assert_eq!(validator.use_allowance(from, to), None);
+        let (from, to) = moved(0.01, 0.0, 0.0);
+        assert_eq!(
+            validator.use_allowance(from, to),
+            Some(MovementViolation::HorizontalSpeed)
+        );
+
+        validator.refill(1.0, 0.8, 1);
+        let (from, to) = moved(0.5, 0.0, 0.0);
+        assert_eq!(validator.use_allowance(from, to), None);
+        let (from, to) = moved(0.0, 0.0, -0.5);
+        assert_eq!(validator.use_allowance(from, to), None);
+        let (from, to) = moved(0.0, 0.0, 0.1);
+        assert_eq!(
+            validator.use_allowance(from, to),
+            Some(MovementViolation::HorizontalSpeed)
+        );
+    }
+
+    #[test]
+    fn only_rising_uses_the_vertical_allowance() {
+        let validator = MovementValidator::default();
+        validator.refill(1.0, 0.8, 1);
+
+        let (from, to) = moved(0.0, -30.0, 0.0);
+        assert_eq!(validator.use_allowance(from, to), None);
+        let (from, to) = moved(0.0, 0.8, 0.0);
+        assert_eq!(validator.use_allowance(from, to), None);
+        let (from, to) = moved(0.0, 0.1, 0.0);
+        assert_eq!(
+            validator.use_allowance(from, to),
+            Some(MovementViolation::VerticalSpeed)
+        );
+    }
+
+    #[test]
+    fn unused_movement_is_buffered() {
+        let validator = MovementValidator::default();
+        validator.refill(1.0, 0.8, 20);
+        for _ in 0..40 {
+            validator.refill(1.0, 0.8, 20);
+        }
+        // Catching up after lagging is allowed, up to the buffered ticks
+        let (from, to) = moved(20.0, 16.0, 0.0);
+        assert_eq!(validator.use_allowance(from, to), None);
+        let (from, to) = moved(0.1, 0.0, 0.0);
+        assert_eq!(
+            validator.use_allowance(from, to),
+            Some(MovementViolation::HorizontalSpeed)
+        );
+    }
+
+    #[test]
+    fn hovering_is_allowed_for_a_few_moves() {
+        let validator = MovementValidator::default();
+        for _ in 0..3 {
+            assert_eq!(validator.count_hover(true, 3), None);
+        }
+        assert_eq!(
+            validator.count_hover(true, 3),
+            Some(MovementViolation::Hovering)
+        );
+
+        assert_eq!(validator.count_hover(false, 3), None);
+        assert_eq!(validator.count_hover(true, 3), None);
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::look_around::LookAroundGoal,
     mob::{Mob, MobEntity},
 };
@@ -29,8 +29,6 @@ impl BatEntity {
     }
 }
 
-impl NBTStorage for BatEntity {}
-
 impl Mob for BatEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
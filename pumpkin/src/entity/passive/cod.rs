@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::swim::SwimGoal,
     mob::{Mob, MobEntity},
 };
@@ -26,8 +26,6 @@ impl CodEntity {
     }
 }
 
-impl NBTStorage for CodEntity {}
-
 impl Mob for CodEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
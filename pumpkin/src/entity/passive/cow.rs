@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         breed, follow_parent, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
         panic::PanicGoal, swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
@@ -45,8 +45,6 @@ impl CowEntity {
     }
 }
 
-impl NBTStorage for CowEntity {}
-
 impl Mob for CowEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         breed, flee_entity::FleeEntityGoal, follow_parent, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, panic::PanicGoal, swim::SwimGoal, tempt,
@@ -50,8 +50,6 @@ impl FoxEntity {
     }
 }
 
-impl NBTStorage for FoxEntity {}
-
 impl Mob for FoxEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
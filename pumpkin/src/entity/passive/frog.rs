@@ -1,25 +1,116 @@
 use std::sync::{Arc, Weak};
 
-use pumpkin_data::entity::EntityType;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_data::{
+    biome::Biome,
+    entity::EntityType,
+    item::Item,
+    meta_data_type::MetaDataType,
+    tag::{self, Taggable},
+    tracked_data::TrackedId,
+};
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_protocol::{codec::var_int::VarInt, java::client::play::Metadata};
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBaseFuture, NbtFuture,
     ai::goal::{
-        breed, follow_parent, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
-        panic::PanicGoal, swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
+        breed, follow_parent, frog_eat::FrogEatGoal, look_around::LookAroundGoal,
+        look_at_entity::LookAtEntityGoal, panic::PanicGoal, swim::SwimGoal, tempt,
+        wander_around::WanderAroundGoal,
     },
     mob::{Mob, MobEntity},
 };
+use crate::world::regional_difficulty::RegionalDifficulty;
+
+/// The variant of a frog, which decides its color and the froglight it makes of magma cubes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrogVariant {
+    Temperate,
+    Warm,
+    Cold,
+}
+
+impl FrogVariant {
+    const ALL: [Self; 3] = [Self::Temperate, Self::Warm, Self::Cold];
+
+    /// The variant of frogs spawning in `biome`.
+    #[must_use]
+    pub fn from_biome(biome: &Biome) -> Self {
+        if biome.has_tag(&tag::WorldgenBiome::MINECRAFT_SPAWNS_WARM_VARIANT_FROGS) {
+            Self::Warm
+        } else if biome.has_tag(&tag::WorldgenBiome::MINECRAFT_SPAWNS_COLD_VARIANT_FROGS) {
+            Self::Cold
+        } else {
+            Self::Temperate
+        }
+    }
+
+    #[must_use]
+    pub const fn resource_name(self) -> &'static str {
+        match self {
+            Self::Temperate => "minecraft:temperate",
+            Self::Warm => "minecraft:warm",
+            Self::Cold => "minecraft:cold",
+        }
+    }
+
+    #[must_use]
+    pub fn from_resource_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.resource_name() == name)
+    }
+
+    /// The ID in the frog variant registry, which is sorted by name.
+    #[must_use]
+    pub const fn registry_id(self) -> i32 {
+        match self {
+            Self::Cold => 0,
+            Self::Temperate => 1,
+            Self::Warm => 2,
+        }
+    }
+
+    /// The froglight dropped by magma cubes eaten by frogs of this variant.
+    #[must_use]
+    pub const fn froglight(self) -> &'static Item {
+        match self {
+            Self::Temperate => &Item::OCHRE_FROGLIGHT,
+            Self::Warm => &Item::PEARLESCENT_FROGLIGHT,
+            Self::Cold => &Item::VERDANT_FROGLIGHT,
+        }
+    }
+}
 
 /// Frog — a passive mob that eats small slimes and magma cubes.
+///
+/// Frogs catch their food with their tongue. Magma cubes eaten by frogs drop a froglight,
+/// colored by the variant of the frog, which naturally spawned frogs get from their biome.
 pub struct FrogEntity {
     pub mob_entity: MobEntity,
+    variant: AtomicCell<FrogVariant>,
 }
 
 impl FrogEntity {
+    /// The generated tracked data only has indices shared by mobs, which frogs don't use for
+    /// their variant and tongue.
+    pub const DATA_VARIANT: TrackedId = TrackedId {
+        latest: 17,
+        v1_21_7: 17,
+    };
+    /// The entity the tongue of the frog is on.
+    pub const DATA_TONGUE_TARGET: TrackedId = TrackedId {
+        latest: 18,
+        v1_21_7: 18,
+    };
+
     pub async fn new(entity: Entity) -> Arc<Self> {
         let mob_entity = MobEntity::new(entity);
-        let frog = Self { mob_entity };
+        let frog = Self {
+            mob_entity,
+            variant: AtomicCell::new(FrogVariant::Temperate),
+        };
         let mob_arc = Arc::new(frog);
         let mob_weak: Weak<dyn Mob> = {
             let mob_arc: Arc<dyn Mob> = mob_arc.clone();
@@ -31,6 +122,7 @@ impl FrogEntity {
 
             goal_selector.add_goal(0, SwimGoal::new());
             goal_selector.add_goal(1, PanicGoal::new(2.0));
+            goal_selector.add_goal(2, FrogEatGoal::new(Arc::downgrade(&mob_arc), 1.0));
             goal_selector.add_goal(3, tempt::TemptGoal::new(1.25, tempt::TEMPT_FROG, 10.0));
             goal_selector.add_goal(4, breed::BreedGoal::new(1.0));
             goal_selector.add_goal(5, follow_parent::FollowParentGoal::new(1.1));
@@ -44,12 +136,111 @@ impl FrogEntity {
 
         mob_arc
     }
-}
 
-impl NBTStorage for FrogEntity {}
+    pub fn variant(&self) -> FrogVariant {
+        self.variant.load()
+    }
+
+    async fn send_variant(&self) {
+        self.mob_entity
+            .living_entity
+            .entity
+            .send_meta_data(&[Metadata::new(
+                Self::DATA_VARIANT,
+                MetaDataType::FrogVariant,
+                VarInt(self.variant().registry_id()),
+            )])
+            .await;
+    }
+}
 
 impl Mob for FrogEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    fn finalize_spawn<'a>(
+        &'a self,
+        _difficulty: &'a RegionalDifficulty,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            let entity = &self.mob_entity.living_entity.entity;
+            let biome = entity
+                .world
+                .load()
+                .level
+                .get_rough_biome(&entity.block_pos.load())
+                .await;
+            self.variant.store(FrogVariant::from_biome(biome));
+        })
+    }
+
+    fn write_mob_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            nbt.put_string("variant", self.variant().resource_name().to_string());
+        })
+    }
+
+    fn read_mob_nbt<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(variant) = nbt
+                .get_string("variant")
+                .and_then(FrogVariant::from_resource_name)
+            {
+                self.variant.store(variant);
+            }
+        })
+    }
+
+    fn init_mob_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move { self.send_variant().await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_round_trip_through_their_names() {
+        for variant in FrogVariant::ALL {
+            assert_eq!(
+                FrogVariant::from_resource_name(variant.resource_name()),
+                Some(variant)
+            );
+        }
+        assert_eq!(FrogVariant::from_resource_name("minecraft:hot"), None);
+    }
+
+    #[test]
+    fn registry_ids_follow_the_names() {
+        let mut variants = FrogVariant::ALL;
+        variants.sort_by_key(|variant| variant.resource_name());
+        let ids: Vec<_> = variants.into_iter().map(FrogVariant::registry_id).collect();
+        assert_eq!(ids, [0, 1, 2]);
+    }
+
+    #[test]
+    fn biome_decides_the_variant() {
+        assert_eq!(
+            FrogVariant::from_biome(&Biome::PLAINS),
+            FrogVariant::Temperate
+        );
+        assert_eq!(FrogVariant::from_biome(&Biome::DESERT), FrogVariant::Warm);
+        assert_eq!(
+            FrogVariant::from_biome(&Biome::MANGROVE_SWAMP),
+            FrogVariant::Warm
+        );
+        assert_eq!(
+            FrogVariant::from_biome(&Biome::SNOWY_PLAINS),
+            FrogVariant::Cold
+        );
+    }
+
+    #[test]
+    fn each_variant_has_its_own_froglight() {
+        assert!(FrogVariant::Temperate.froglight() == &Item::OCHRE_FROGLIGHT);
+        assert!(FrogVariant::Warm.froglight() == &Item::PEARLESCENT_FROGLIGHT);
+        assert!(FrogVariant::Cold.froglight() == &Item::VERDANT_FROGLIGHT);
+    }
 }
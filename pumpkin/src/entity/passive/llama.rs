@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         breed, follow_parent, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
         panic::PanicGoal, swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
@@ -49,8 +49,6 @@ impl LlamaEntity {
     }
 }
 
-impl NBTStorage for LlamaEntity {}
-
 impl Mob for LlamaEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity},
     passive::cow::CowEntity,
 };
@@ -21,8 +21,6 @@ impl MooshroomEntity {
     }
 }
 
-impl NBTStorage for MooshroomEntity {}
-
 impl Mob for MooshroomEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.cow.mob_entity
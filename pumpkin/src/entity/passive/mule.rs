@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity},
     passive::horse::HorseEntity,
 };
@@ -20,8 +20,6 @@ impl MuleEntity {
     }
 }
 
-impl NBTStorage for MuleEntity {}
-
 impl Mob for MuleEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.horse.mob_entity
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal, panic::PanicGoal,
         swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
@@ -48,8 +48,6 @@ impl OcelotEntity {
     }
 }
 
-impl NBTStorage for OcelotEntity {}
-
 impl Mob for OcelotEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -1,14 +1,18 @@
 use std::sync::{Arc, Weak};
 
 use pumpkin_data::entity::EntityType;
+use pumpkin_util::Difficulty;
+use uuid::Uuid;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBase, EntityBaseFuture,
     ai::goal::{
         breed, follow_parent, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
         panic::PanicGoal, swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
     },
+    lightning::LightningEntity,
     mob::{Mob, MobEntity},
+    r#type::from_type,
 };
 
 pub struct PigEntity {
@@ -45,10 +49,37 @@ impl PigEntity {
     }
 }
 
-impl NBTStorage for PigEntity {}
-
 impl Mob for PigEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    /// Pigs struck by lightning turn into zombified piglins, except in peaceful.
+    fn on_struck_by_lightning<'a>(
+        &'a self,
+        caller: &'a dyn EntityBase,
+        _lightning: &'a LightningEntity,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            let entity = self.get_entity();
+            let world = entity.world.load_full();
+            if world.level_info.load().difficulty == Difficulty::Peaceful {
+                entity.struck_by_lightning(caller).await;
+                return;
+            }
+
+            let piglin = from_type(
+                &EntityType::ZOMBIFIED_PIGLIN,
+                entity.pos.load(),
+                &world,
+                Uuid::new_v4(),
+            )
+            .await;
+            let piglin_entity = piglin.get_entity();
+            piglin_entity.set_rotation(entity.yaw.load(), entity.pitch.load());
+            piglin_entity.head_yaw.store(entity.head_yaw.load());
+            entity.remove().await;
+            world.spawn_entity(piglin).await;
+        })
+    }
 }
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal, panic::PanicGoal,
         swim::SwimGoal, wander_around::WanderAroundGoal,
@@ -46,8 +46,6 @@ impl PolarBearEntity {
     }
 }
 
-impl NBTStorage for PolarBearEntity {}
-
 impl Mob for PolarBearEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
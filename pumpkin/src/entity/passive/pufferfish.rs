@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::swim::SwimGoal,
     mob::{Mob, MobEntity},
 };
@@ -28,8 +28,6 @@ impl PufferfishEntity {
     }
 }
 
-impl NBTStorage for PufferfishEntity {}
-
 impl Mob for PufferfishEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
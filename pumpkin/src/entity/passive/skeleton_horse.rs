@@ -1,12 +1,12 @@
-use std::sync::{Arc, Weak};
+use std::sync::{Arc, Weak, atomic::AtomicBool};
 
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal, panic::PanicGoal,
-        swim::SwimGoal, wander_around::WanderAroundGoal,
+        skeleton_trap::SkeletonTrapGoal, swim::SwimGoal, wander_around::WanderAroundGoal,
     },
     mob::{Mob, MobEntity},
 };
@@ -16,12 +16,18 @@ use crate::entity::{
 /// Categorized as hostile in the registry but uses passive-like AI.
 pub struct SkeletonHorseEntity {
     pub mob_entity: MobEntity,
+    /// Trap horses are spawned by lightning and turn into four skeleton horsemen when a player
+    /// comes close.
+    pub trap: AtomicBool,
 }
 
 impl SkeletonHorseEntity {
     pub async fn new(entity: Entity) -> Arc<Self> {
         let mob_entity = MobEntity::new(entity);
-        let mob = Self { mob_entity };
+        let mob = Self {
+            mob_entity,
+            trap: AtomicBool::new(false),
+        };
         let mob_arc = Arc::new(mob);
         let mob_weak: Weak<dyn Mob> = {
             let mob_arc: Arc<dyn Mob> = mob_arc.clone();
@@ -32,6 +38,7 @@ impl SkeletonHorseEntity {
             let mut goal_selector = mob_arc.mob_entity.goals_selector.lock().await;
 
             goal_selector.add_goal(0, SwimGoal::new());
+            goal_selector.add_goal(1, SkeletonTrapGoal::new(Arc::downgrade(&mob_arc)));
             goal_selector.add_goal(1, PanicGoal::new(1.2));
             goal_selector.add_goal(6, WanderAroundGoal::new(0.7));
             goal_selector.add_goal(
@@ -45,8 +52,6 @@ impl SkeletonHorseEntity {
     }
 }
 
-impl NBTStorage for SkeletonHorseEntity {}
-
 impl Mob for SkeletonHorseEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
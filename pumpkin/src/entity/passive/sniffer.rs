@@ -1,28 +1,47 @@
 use std::sync::{Arc, Weak};
 
-use pumpkin_data::entity::EntityType;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_data::{entity::EntityType, meta_data_type::MetaDataType, tracked_data::TrackedData};
+use pumpkin_protocol::{codec::var_int::VarInt, java::client::play::Metadata};
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBaseFuture,
     ai::goal::{
         breed, follow_parent, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
-        panic::PanicGoal, swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
+        panic::PanicGoal, sniffer_dig::SnifferDigGoal, swim::SwimGoal, tempt,
+        wander_around::WanderAroundGoal,
     },
     mob::{Mob, MobEntity},
 };
 
+/// What a sniffer is doing, which the client animates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnifferState {
+    Idle,
+    FeelingHappy,
+    Scenting,
+    Sniffing,
+    Searching,
+    Digging,
+    Rising,
+}
+
 /// Sniffer — an ancient passive mob that sniffs out seeds.
 ///
-/// Currently has basic AI: swims, panics, wanders, looks at players.
-/// Seed-digging behavior is a future addition.
+/// Adult sniffers now and then sniff the air, walk to a block they can dig in and dig up
+/// torchflower seeds or a pitcher pod.
 pub struct SnifferEntity {
     pub mob_entity: MobEntity,
+    state: AtomicCell<SnifferState>,
 }
 
 impl SnifferEntity {
     pub async fn new(entity: Entity) -> Arc<Self> {
         let mob_entity = MobEntity::new(entity);
-        let mob = Self { mob_entity };
+        let mob = Self {
+            mob_entity,
+            state: AtomicCell::new(SnifferState::Idle),
+        };
         let mob_arc = Arc::new(mob);
         let mob_weak: Weak<dyn Mob> = {
             let mob_arc: Arc<dyn Mob> = mob_arc.clone();
@@ -37,6 +56,7 @@ impl SnifferEntity {
             goal_selector.add_goal(3, tempt::TemptGoal::new(1.0, tempt::TEMPT_SNIFFER, 10.0));
             goal_selector.add_goal(4, breed::BreedGoal::new(1.0));
             goal_selector.add_goal(5, follow_parent::FollowParentGoal::new(1.1));
+            goal_selector.add_goal(5, SnifferDigGoal::new(Arc::downgrade(&mob_arc), 1.25));
             goal_selector.add_goal(6, WanderAroundGoal::new(0.6));
             goal_selector.add_goal(
                 7,
@@ -47,12 +67,36 @@ impl SnifferEntity {
 
         mob_arc
     }
-}
 
-impl NBTStorage for SnifferEntity {}
+    pub fn state(&self) -> SnifferState {
+        self.state.load()
+    }
+
+    pub async fn set_state(&self, state: SnifferState) {
+        if self.state.swap(state) != state {
+            self.send_state().await;
+        }
+    }
+
+    async fn send_state(&self) {
+        self.mob_entity
+            .living_entity
+            .entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_STATE,
+                MetaDataType::SnifferState,
+                VarInt(self.state() as i32),
+            )])
+            .await;
+    }
+}
 
 impl Mob for SnifferEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    fn init_mob_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move { self.send_state().await })
+    }
 }
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
         ranged_attack::RangedAttackGoal, swim::SwimGoal, wander_around::WanderAroundGoal,
@@ -42,8 +42,6 @@ impl SnowGolemEntity {
     }
 }
 
-impl NBTStorage for SnowGolemEntity {}
-
 impl Mob for SnowGolemEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
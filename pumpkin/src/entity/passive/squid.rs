@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::swim::SwimGoal,
     mob::{Mob, MobEntity},
 };
@@ -29,8 +29,6 @@ impl SquidEntity {
     }
 }
 
-impl NBTStorage for SquidEntity {}
-
 impl Mob for SquidEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         breed, follow_parent, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
         panic::PanicGoal, tempt, wander_around::WanderAroundGoal,
@@ -48,8 +48,6 @@ impl StriderEntity {
     }
 }
 
-impl NBTStorage for StriderEntity {}
-
 impl Mob for StriderEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
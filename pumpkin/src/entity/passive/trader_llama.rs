@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     mob::{Mob, MobEntity},
     passive::llama::LlamaEntity,
 };
@@ -20,8 +20,6 @@ impl TraderLlamaEntity {
     }
 }
 
-impl NBTStorage for TraderLlamaEntity {}
-
 impl Mob for TraderLlamaEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.llama.mob_entity
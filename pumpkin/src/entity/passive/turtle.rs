@@ -1,28 +1,56 @@
-use std::sync::{Arc, Weak};
+use std::sync::{
+    Arc, Weak,
+    atomic::{AtomicBool, Ordering},
+};
 
-use pumpkin_data::entity::EntityType;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_data::{entity::EntityType, meta_data_type::MetaDataType, tracked_data::TrackedData};
+use pumpkin_nbt::{compound::NbtCompound, tag::NbtTag};
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_world::item::ItemStack;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity, EntityBaseFuture, NbtFuture,
     ai::goal::{
-        breed, follow_parent, look_around::LookAroundGoal, look_at_entity::LookAtEntityGoal,
-        panic::PanicGoal, swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
+        follow_parent,
+        look_around::LookAroundGoal,
+        look_at_entity::LookAtEntityGoal,
+        panic::PanicGoal,
+        swim::SwimGoal,
+        tempt,
+        turtle::{GoHomeGoal, LayEggGoal, TurtleMateGoal},
+        wander_around::WanderAroundGoal,
     },
     mob::{Mob, MobEntity},
+    player::Player,
 };
 
 /// Turtle — a passive mob that lays eggs on beaches.
 ///
-/// Currently has basic AI: swims, panics, wanders, looks at players.
-/// Egg-laying, homing behavior, and scute drops are future additions.
+/// Turtles remember the beach they hatched on as their home. After mating, the pregnant turtle
+/// swims back home and lays eggs in the sand, which hatch into more turtles for that beach.
 pub struct TurtleEntity {
     pub mob_entity: MobEntity,
+    home_pos: AtomicCell<BlockPos>,
+    has_egg: AtomicBool,
+    /// Set when the turtle is fed seagrass, until the mate goal puts it in love.
+    love_requested: AtomicBool,
 }
 
 impl TurtleEntity {
+    /// The age of turtles hatching from eggs, which grow up in one day.
+    pub const HATCHLING_AGE: i32 = -24000;
+
     pub async fn new(entity: Entity) -> Arc<Self> {
+        let home_pos = entity.block_pos.load();
         let mob_entity = MobEntity::new(entity);
-        let mob = Self { mob_entity };
+        let mob = Self {
+            mob_entity,
+            home_pos: AtomicCell::new(home_pos),
+            has_egg: AtomicBool::new(false),
+            love_requested: AtomicBool::new(false),
+        };
         let mob_arc = Arc::new(mob);
         let mob_weak: Weak<dyn Mob> = {
             let mob_arc: Arc<dyn Mob> = mob_arc.clone();
@@ -34,8 +62,10 @@ impl TurtleEntity {
 
             goal_selector.add_goal(0, SwimGoal::new());
             goal_selector.add_goal(1, PanicGoal::new(1.2));
+            goal_selector.add_goal(1, TurtleMateGoal::new(Arc::downgrade(&mob_arc), 1.0));
+            goal_selector.add_goal(1, LayEggGoal::new(Arc::downgrade(&mob_arc), 1.0));
             goal_selector.add_goal(3, tempt::TemptGoal::new(1.1, tempt::TEMPT_TURTLE, 10.0));
-            goal_selector.add_goal(4, breed::BreedGoal::new(1.0));
+            goal_selector.add_goal(4, GoHomeGoal::new(Arc::downgrade(&mob_arc), 1.0));
             goal_selector.add_goal(5, follow_parent::FollowParentGoal::new(1.1));
             goal_selector.add_goal(6, WanderAroundGoal::new(1.0));
             goal_selector.add_goal(
@@ -47,12 +77,112 @@ impl TurtleEntity {
 
         mob_arc
     }
-}
 
-impl NBTStorage for TurtleEntity {}
+    /// The beach this turtle lays its eggs on.
+    pub fn home_pos(&self) -> BlockPos {
+        self.home_pos.load()
+    }
+
+    pub fn set_home_pos(&self, home_pos: BlockPos) {
+        self.home_pos.store(home_pos);
+    }
+
+    /// Whether this turtle mated and still has to lay its eggs.
+    pub fn has_egg(&self) -> bool {
+        self.has_egg.load(Ordering::Relaxed)
+    }
+
+    pub async fn set_has_egg(&self, has_egg: bool) {
+        self.has_egg.store(has_egg, Ordering::Relaxed);
+        self.mob_entity
+            .living_entity
+            .entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_HAS_EGG,
+                MetaDataType::Boolean,
+                has_egg,
+            )])
+            .await;
+    }
+
+    /// Shows the turtle digging in the sand while it lays its eggs.
+    pub async fn set_laying_egg(&self, laying_egg: bool) {
+        self.mob_entity
+            .living_entity
+            .entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_DIGGING_SAND,
+                MetaDataType::Boolean,
+                laying_egg,
+            )])
+            .await;
+    }
+
+    /// Takes the request to fall in love made by feeding the turtle.
+    pub fn take_love_request(&self) -> bool {
+        self.love_requested.swap(false, Ordering::Relaxed)
+    }
+}
 
 impl Mob for TurtleEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
     }
+
+    fn mob_interact<'a>(
+        &'a self,
+        player: &'a Player,
+        stack: &'a mut ItemStack,
+    ) -> EntityBaseFuture<'a, bool> {
+        Box::pin(async move {
+            let entity = &self.mob_entity.living_entity.entity;
+            if !tempt::TEMPT_TURTLE.contains(&stack.item.id)
+                || entity.age.load(Ordering::Relaxed) < 0
+                || self.has_egg()
+            {
+                return false;
+            }
+            stack.decrement_unless_creative(player.gamemode.load(), 1);
+            self.love_requested.store(true, Ordering::Relaxed);
+            true
+        })
+    }
+
+    fn write_mob_nbt<'a>(&'a self, nbt: &'a mut NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            let home_pos = self.home_pos();
+            nbt.put(
+                "home_pos",
+                NbtTag::IntArray(vec![home_pos.0.x, home_pos.0.y, home_pos.0.z]),
+            );
+            nbt.put_bool("has_egg", self.has_egg());
+        })
+    }
+
+    fn read_mob_nbt<'a>(&'a self, nbt: &'a NbtCompound) -> NbtFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(NbtTag::IntArray(pos)) = nbt.get("home_pos")
+                && pos.len() == 3
+            {
+                self.set_home_pos(BlockPos::new(pos[0], pos[1], pos[2]));
+            }
+            if let Some(has_egg) = nbt.get_bool("has_egg") {
+                self.has_egg.store(has_egg, Ordering::Relaxed);
+            }
+        })
+    }
+
+    fn init_mob_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            self.mob_entity
+                .living_entity
+                .entity
+                .send_meta_data(&[Metadata::new(
+                    TrackedData::DATA_HAS_EGG,
+                    MetaDataType::Boolean,
+                    self.has_egg(),
+                )])
+                .await;
+        })
+    }
 }
@@ -3,7 +3,7 @@ use std::sync::{Arc, Weak};
 use pumpkin_data::entity::EntityType;
 
 use crate::entity::{
-    Entity, NBTStorage,
+    Entity,
     ai::goal::{
         breed, follow_owner::FollowOwnerGoal, follow_parent, look_around::LookAroundGoal,
         look_at_entity::LookAtEntityGoal, swim::SwimGoal, tempt, wander_around::WanderAroundGoal,
@@ -45,8 +45,6 @@ impl WolfEntity {
     }
 }
 
-impl NBTStorage for WolfEntity {}
-
 impl Mob for WolfEntity {
     fn get_mob_entity(&self) -> &MobEntity {
         &self.mob_entity
@@ -35,6 +35,7 @@ use pumpkin_data::data_component_impl::{AttributeModifiersImpl, Operation};
 use pumpkin_data::data_component_impl::{EquipmentSlot, EquippableImpl, ToolImpl};
 use pumpkin_data::effect::StatusEffect;
 use pumpkin_data::entity::{EntityPose, EntityStatus, EntityType};
+use pumpkin_data::item::Item;
 use pumpkin_data::particle::Particle;
 use pumpkin_data::sound::{Sound, SoundCategory};
 use pumpkin_data::tag::Taggable;
@@ -45,24 +46,23 @@ use pumpkin_inventory::player::{
 use pumpkin_inventory::screen_handler::{
     InventoryPlayer, PlayerFuture, ScreenHandler, ScreenHandlerFactory, ScreenHandlerListener,
 };
-use pumpkin_inventory::sync_handler::SyncHandler;
+use pumpkin_inventory::sync_handler::{ResyncReason, SyncHandler};
 use pumpkin_macros::send_cancellable;
 use pumpkin_nbt::compound::NbtCompound;
 
 use crate::plugin::api::events::player::player_death::PlayerDeathEvent;
 use crate::plugin::api::events::player::player_drop_item::PlayerDropItemEvent;
 use pumpkin_nbt::tag::NbtTag;
-use pumpkin_protocol::IdOr;
 use pumpkin_protocol::codec::var_int::VarInt;
 use pumpkin_protocol::java::client::play::{
     Animation, CAcknowledgeBlockChange, CActionBar, CChangeDifficulty, CChunkBatchEnd,
-    CChunkBatchStart, CChunkData, CCloseContainer, CCombatDeath, CDisguisedChatMessage,
+    CChunkBatchStart, CClearTitle, CCloseContainer, CCombatDeath, CDisguisedChatMessage,
     CEntityAnimation, CEntityPositionSync, CGameEvent, CKeepAlive, COpenScreen, CParticle,
     CPlayerAbilities, CPlayerInfoUpdate, CPlayerPosition, CPlayerSpawnPosition, CRespawn,
     CSetContainerContent, CSetContainerProperty, CSetContainerSlot, CSetCursorItem, CSetEquipment,
-    CSetExperience, CSetHealth, CSetPlayerInventory, CSetSelectedSlot, CSoundEffect, CStopSound,
-    CSubtitle, CSystemChatMessage, CTitleAnimation, CTitleText, CUnloadChunk, CUpdateMobEffect,
-    CUpdateTime, GameEvent, Metadata, PlayerAction, PlayerInfoFlags, PreviousMessage,
+    CSetExperience, CSetHealth, CSetPlayerInventory, CSetSelectedSlot, CSystemChatMessage,
+    CUnloadChunk, CUpdateMobEffect, CUpdateTime, GameEvent, Metadata, PlayerAction,
+    PlayerInfoFlags, PreviousMessage,
 };
 use pumpkin_protocol::java::server::play::SClickSlot;
 use pumpkin_util::math::{
@@ -74,8 +74,8 @@ use pumpkin_util::text::TextComponent;
 use pumpkin_util::text::click::ClickEvent;
 use pumpkin_util::text::hover::HoverEvent;
 use pumpkin_util::{GameMode, Hand};
-use pumpkin_world::biome;
 use pumpkin_world::cylindrical_chunk_iterator::Cylindrical;
+use pumpkin_world::dimension::dimension_from_name;
 use pumpkin_world::item::ItemStack;
 use pumpkin_world::level::{Level, SyncChunk, SyncEntityChunk};
 
@@ -83,20 +83,30 @@ use crate::block;
 use crate::block::blocks::bed::BedBlock;
 use crate::command::client_suggestions;
 use crate::command::dispatcher::CommandDispatcher;
+use crate::entity::feedback::{SoundEffect, StopSound, Title, TitleTimes};
+use crate::entity::projectile::fishing_bobber::FishingBobberEntity;
 use crate::entity::{EntityBaseFuture, NbtFuture, TeleportFuture};
+use crate::net::bedrock::form;
+use crate::net::floodgate;
+use crate::net::java::cookie::{self, CookiePayload, TransferError};
 use crate::net::{ClientPlatform, GameProfile};
 use crate::net::{DisconnectReason, PlayerConfig};
+use crate::plugin::Payload;
 use crate::plugin::player::player_change_world::PlayerChangeWorldEvent;
 use crate::plugin::player::player_gamemode_change::PlayerGamemodeChangeEvent;
 use crate::plugin::player::player_teleport::PlayerTeleportEvent;
+use crate::plugin::player::player_transfer::PlayerTransferEvent;
 use crate::server::Server;
 use crate::world::World;
+use crate::world::scoreboard::Scoreboard;
 
 use super::breath::BreathManager;
+use super::chat_limiter::ChatLimiter;
 use super::combat::{self, AttackType, player_attack_sound};
 use super::hunger::HungerManager;
 use super::item::ItemEntity;
 use super::living::LivingEntity;
+use super::movement::MovementValidator;
 use super::{Entity, EntityBase, NBTStorage, NBTStorageInit};
 use pumpkin_data::potion::Effect;
 use pumpkin_world::chunk_system::ChunkLoading;
@@ -135,6 +145,10 @@ impl Ord for HeapNode {
 
 pub struct ChunkManager {
     chunks_per_tick: usize,
+    /// The most chunks sent per tick, however many the client asks for.
+    max_chunks_per_tick: usize,
+    /// A teleport target that is generated before other chunks until it arrives.
+    priority_target: Option<Vector2<i32>>,
     center: Vector2<i32>,
     view_distance: u8,
     chunk_listener: Receiver<(Vector2<i32>, SyncChunk)>,
@@ -152,11 +166,15 @@ impl ChunkManager {
     #[must_use]
     pub fn new(
         chunks_per_tick: usize,
+        max_chunks_per_tick: usize,
         chunk_listener: Receiver<(Vector2<i32>, SyncChunk)>,
         world: Arc<World>,
     ) -> Self {
+        let max_chunks_per_tick = max_chunks_per_tick.max(1);
         Self {
-            chunks_per_tick,
+            chunks_per_tick: chunks_per_tick.min(max_chunks_per_tick),
+            max_chunks_per_tick,
+            priority_target: None,
             center: Vector2::<i32>::new(0, 0),
             view_distance: 0,
             chunk_listener,
@@ -180,6 +198,9 @@ impl ChunkManager {
             let dst = (pos.x - self.center.x)
                 .abs()
                 .max((pos.y - self.center.y).abs());
+            if self.priority_target == Some(pos) {
+                self.clear_priority_target();
+            }
             if dst > i32::from(self.view_distance) {
                 continue;
             }
@@ -192,6 +213,28 @@ impl ChunkManager {
         // log::debug!("chunk_sent size {}", self.chunk_sent.len());
     }
 
+    /// Generates the chunk at `pos` before any other, until it is loaded. Replaces the previous
+    /// target.
+    pub fn prioritize(&mut self, pos: Vector2<i32>) {
+        if self.world.level.loaded_chunks.contains_key(&pos) {
+            return;
+        }
+        let mut lock = self.world.level.chunk_loading.lock().unwrap();
+        if let Some(old) = self.priority_target.replace(pos) {
+            lock.remove_priority_target(old);
+        }
+        lock.add_priority_target(pos);
+        lock.send_change();
+    }
+
+    fn clear_priority_target(&mut self) {
+        if let Some(pos) = self.priority_target.take() {
+            let mut lock = self.world.level.chunk_loading.lock().unwrap();
+            lock.remove_priority_target(pos);
+            lock.send_change();
+        }
+    }
+
     pub fn update_center_and_view_distance(
         &mut self,
         center: Vector2<i32>,
@@ -245,6 +288,7 @@ impl ChunkManager {
     }
 
     pub fn clean_up(&mut self, level: &Arc<Level>) {
+        self.clear_priority_target();
         let mut lock = level.chunk_loading.lock().unwrap();
         lock.remove_ticket(
             self.center,
@@ -256,6 +300,7 @@ impl ChunkManager {
     }
 
     pub fn change_world(&mut self, old_level: &Arc<Level>, new_world: Arc<World>) {
+        self.clear_priority_target();
         let mut lock = old_level.chunk_loading.lock().unwrap();
         lock.remove_ticket(
             self.center,
@@ -272,7 +317,7 @@ impl ChunkManager {
 
     pub fn handle_acknowledge(&mut self, chunks_per_tick: f32) {
         self.batches_sent_since_ack = BatchState::Count(0);
-        self.chunks_per_tick = chunks_per_tick.ceil() as usize;
+        self.chunks_per_tick = (chunks_per_tick.ceil() as usize).min(self.max_chunks_per_tick);
     }
 
     pub fn push_chunk(&mut self, position: Vector2<i32>, chunk: SyncChunk) {
@@ -365,10 +410,16 @@ pub struct Player {
     pub breath_manager: BreathManager,
     /// Manages the player's hunger level.
     pub hunger_manager: HungerManager,
+    /// Checks the moves the player reports.
+    pub movement_validator: MovementValidator,
+    /// Rate limits the player's chat messages and commands.
+    pub chat_limiter: ChatLimiter,
     /// The ID of the currently open container (if any).
     pub open_container: AtomicCell<Option<u64>>,
     /// The item currently being held by the player.
     pub carried_item: Mutex<Option<ItemStack>>,
+    /// The bobber the player has cast with a fishing rod.
+    pub fishing_bobber: Mutex<Option<Arc<FishingBobberEntity>>>,
     /// The player's abilities and special powers.
     ///
     /// This field represents the various abilities that the player possesses, such as flight, invulnerability, and other special effects.
@@ -412,6 +463,8 @@ pub struct Player {
     pub client_loaded: AtomicBool,
     /// The amount of time (in ticks) the client has to report having finished loading before being timed out.
     pub client_loaded_timeout: AtomicU32,
+    /// Whether the debug screen of the client shows the tick time chart, which the server feeds.
+    pub debug_tick_time: AtomicBool,
     /// The player's experience level.
     pub experience_level: AtomicI32,
     /// The player's experience progress (`0.0` to `1.0`)
@@ -421,6 +474,10 @@ pub struct Player {
     pub experience_pick_up_delay: Mutex<u32>,
     pub chunk_manager: Mutex<ChunkManager>,
     pub has_played_before: AtomicBool,
+    /// Whether the player saw the credits, shown again on later returns from the End but skippable.
+    pub seen_credits: AtomicBool,
+    /// Whether the player left the End and is watching the credits, until they respawn.
+    pub won_game: AtomicBool,
     pub chat_session: Arc<Mutex<ChatSession>>,
     pub signature_cache: Mutex<MessageCache>,
     pub player_screen_handler: Arc<Mutex<PlayerScreenHandler>>,
@@ -481,12 +538,15 @@ impl Player {
             breath_manager: BreathManager::default(),
             // TODO: Load this from previous instance
             hunger_manager: HungerManager::default(),
+            movement_validator: MovementValidator::default(),
+            chat_limiter: ChatLimiter::default(),
             current_block_destroy_stage: AtomicI32::new(-1),
             open_container: AtomicCell::new(None),
             tick_counter: AtomicI32::new(0),
             packet_sequence: AtomicI32::new(-1),
             start_mining_time: AtomicI32::new(0),
             carried_item: Mutex::new(None),
+            fishing_bobber: Mutex::new(None),
             experience_pick_up_delay: Mutex::new(0),
             teleport_id_count: AtomicI32::new(0),
             mining: AtomicBool::new(false),
@@ -512,6 +572,7 @@ impl Player {
             ping: AtomicU32::new(0),
             last_attacked_ticks: AtomicU32::new(0),
             client_loaded: AtomicBool::new(false),
+            debug_tick_time: AtomicBool::new(false),
             client_loaded_timeout: AtomicU32::new(60),
             // Minecraft has no way to change the default permission level of new players.
             // Minecraft's default permission level is 0.
@@ -533,6 +594,7 @@ impl Player {
             // Default to sending 16 chunks per tick.
             chunk_manager: Mutex::new(ChunkManager::new(
                 16,
+                server.advanced_config.world.generation.max_chunks_per_tick,
                 world.level.chunk_listener.add_global_chunk_listener(),
                 world.clone(),
             )),
@@ -541,6 +603,8 @@ impl Player {
             last_sent_food: AtomicU8::new(0),
             last_food_saturation: AtomicBool::new(true),
             has_played_before: AtomicBool::new(false),
+            seen_credits: AtomicBool::new(false),
+            won_game: AtomicBool::new(false),
             chat_session: Arc::new(Mutex::new(ChatSession::default())), // Placeholder value until the player actually sets their session id
             signature_cache: Mutex::new(MessageCache::default()),
             player_screen_handler: player_screen_handler.clone(),
@@ -615,6 +679,7 @@ impl Player {
         let victim_entity = victim.get_entity();
         let attacker_entity = &self.living_entity.entity;
         let config = &server.advanced_config.pvp;
+        let profile = config.combat_profile();
 
         let inventory = self.inventory();
         let item_stack = inventory.held_item();
@@ -646,27 +711,33 @@ impl Player {
 
         let attack_speed = base_attack_speed + add_speed;
 
-        let attack_cooldown_progress = self.get_attack_cooldown_progress(
-            f64::from(server.basic_config.tps),
-            0.5,
-            attack_speed,
-        );
+        let attack_cooldown_progress = if profile.attack_cooldown {
+            self.get_attack_cooldown_progress(f64::from(server.basic_config.tps), 0.5, attack_speed)
+        } else {
+            1.0
+        };
         self.last_attacked_ticks.store(0, Ordering::Relaxed);
 
         // Only reduce attack damage if in cooldown
         // TODO: Enchantments are reduced in the same way, just without the square.
         if attack_cooldown_progress < 1.0 {
-            damage_multiplier = attack_cooldown_progress.powi(2).mul_add(0.8, 0.2);
+            damage_multiplier = attack_cooldown_progress.powi(2).mul_add(
+                1.0 - profile.cooldown_min_damage,
+                profile.cooldown_min_damage,
+            );
         }
         // Modify the added damage based on the multiplier.
         let mut damage = base_damage + add_damage * damage_multiplier;
 
         let pos = victim_entity.pos.load();
 
-        let attack_type = AttackType::new(self, attack_cooldown_progress as f32).await;
+        let attack_type = match AttackType::new(self, attack_cooldown_progress as f32).await {
+            AttackType::Sweeping if !profile.sweeping => AttackType::Strong,
+            attack_type => attack_type,
+        };
 
         if matches!(attack_type, AttackType::Critical) {
-            damage *= 1.5;
+            damage *= profile.critical_multiplier;
         }
 
         if !victim
@@ -691,17 +762,17 @@ impl Player {
         }
 
         if victim.get_living_entity().is_some() {
-            let mut knockback_strength = 1.0;
             player_attack_sound(&pos, &world, attack_type).await;
-            match attack_type {
-                AttackType::Knockback => knockback_strength += 1.0,
-                AttackType::Sweeping => {
-                    combat::spawn_sweep_particle(attacker_entity, &world, &pos).await;
-                }
-                _ => {}
+            if matches!(attack_type, AttackType::Sweeping) {
+                combat::spawn_sweep_particle(attacker_entity, &world, &pos).await;
             }
             if config.knockback {
-                combat::handle_knockback(attacker_entity, victim_entity, knockback_strength);
+                combat::handle_knockback(
+                    attacker_entity,
+                    victim_entity,
+                    &profile,
+                    matches!(attack_type, AttackType::Knockback),
+                );
             }
         }
 
@@ -1152,6 +1223,109 @@ impl Player {
         abilities.flying
     }
 
+    /// Whether the player may start sprinting: hungry players can't, unless they may fly.
+    pub async fn can_sprint(&self) -> bool {
+        self.hunger_manager.level.load() > 6 || self.abilities.lock().await.allow_flying
+    }
+
+    /// Whether the player may start gliding, which needs a working elytra and the player to be
+    /// in the air.
+    pub async fn can_start_fall_flying(&self) -> bool {
+        let entity = &self.living_entity.entity;
+        if entity.on_ground.load(Ordering::Relaxed)
+            || entity.fall_flying.load(Ordering::Relaxed)
+            || entity.touching_water.load(Ordering::Relaxed)
+            || entity.has_vehicle().await
+            || self.is_flying().await
+            || self
+                .living_entity
+                .has_effect(&StatusEffect::LEVITATION)
+                .await
+        {
+            return false;
+        }
+        self.has_usable_elytra().await
+    }
+
+    async fn has_usable_elytra(&self) -> bool {
+        let chest = self
+            .inventory
+            .entity_equipment
+            .lock()
+            .await
+            .get(&EquipmentSlot::CHEST);
+        let chest = chest.lock().await;
+        chest.item == &Item::ELYTRA
+            && chest
+                .get_max_damage()
+                .is_none_or(|max_damage| chest.get_damage() < max_damage - 1)
+    }
+
+    /// Stops gliding once the player lands, enters water or loses the elytra.
+    async fn update_fall_flying(&self) {
+        let entity = &self.living_entity.entity;
+        if !entity.fall_flying.load(Ordering::Relaxed) {
+            return;
+        }
+        if entity.on_ground.load(Ordering::Relaxed)
+            || entity.touching_water.load(Ordering::Relaxed)
+            || entity.has_vehicle().await
+            || self
+                .living_entity
+                .has_effect(&StatusEffect::LEVITATION)
+                .await
+            || !self.has_usable_elytra().await
+        {
+            entity.set_fall_flying(false).await;
+        }
+    }
+
+    /// Sprinting players start swimming once their eyes are under water, and keep swimming
+    /// until they stop sprinting or leave the water.
+    async fn update_swimming(&self) {
+        let entity = &self.living_entity.entity;
+        let swimming = entity.swimming.load(Ordering::Relaxed);
+        let can_swim = entity.sprinting.load(Ordering::Relaxed)
+            && !self.is_flying().await
+            && !entity.has_vehicle().await;
+        let should_swim = can_swim
+            && if swimming {
+                entity.touching_water.load(Ordering::Relaxed)
+            } else {
+                self.breath_manager.is_eye_in_water(self).await
+            };
+        if swimming != should_swim {
+            entity.set_swimming(should_swim).await;
+        }
+    }
+
+    /// Picks the pose for what the player is doing. Players who don't fit in it crouch, or
+    /// crawl in the swimming pose, e.g. under slabs or trapdoors.
+    pub async fn update_pose(&self) {
+        let entity = &self.living_entity.entity;
+        if self.living_entity.dead.load(Ordering::Relaxed)
+            || !entity.can_change_into_pose(EntityPose::Swimming).await
+        {
+            return;
+        }
+        let desired = desired_pose(
+            entity,
+            self.sleeping_since.load().is_some(),
+            self.is_flying().await,
+        );
+        let pose = if self.is_spectator()
+            || entity.has_vehicle().await
+            || entity.can_change_into_pose(desired).await
+        {
+            desired
+        } else if entity.can_change_into_pose(EntityPose::Crouching).await {
+            EntityPose::Crouching
+        } else {
+            EntityPose::Swimming
+        };
+        entity.set_pose(pose).await;
+    }
+
     pub async fn wake_up(&self) {
         let world = self.world();
         let respawn_point = self
@@ -1186,18 +1360,38 @@ impl Player {
         self.sleeping_since.store(None);
     }
 
+    /// Shows `title` to the player, see [`Title::send_to`] for many players.
+    pub async fn send_title(&self, title: &Title) {
+        title.send(self).await;
+    }
+
+    #[deprecated(note = "use `send_title` with a `Title`, or `send_action_bar`")]
     pub async fn show_title(&self, text: &TextComponent, mode: &TitleMode) {
         match mode {
-            TitleMode::Title => self.client.enqueue_packet(&CTitleText::new(text)).await,
-            TitleMode::SubTitle => self.client.enqueue_packet(&CSubtitle::new(text)).await,
-            TitleMode::ActionBar => self.client.enqueue_packet(&CActionBar::new(text)).await,
+            TitleMode::Title => self.send_title(&Title::new(text.clone())).await,
+            TitleMode::SubTitle => {
+                self.send_title(&Title::default().subtitle(text.clone()))
+                    .await;
+            }
+            TitleMode::ActionBar => self.send_action_bar(text).await,
         }
     }
 
+    #[deprecated(note = "use `send_title` with `Title::times`")]
     pub async fn send_title_animation(&self, fade_in: i32, stay: i32, fade_out: i32) {
-        self.client
-            .enqueue_packet(&CTitleAnimation::new(fade_in, stay, fade_out))
-            .await;
+        let times = TitleTimes::new(fade_in, stay, fade_out);
+        self.send_title(&Title::default().times(times)).await;
+    }
+
+    /// Hides the current title. Resetting also forgets the subtitle and times.
+    pub async fn clear_title(&self, reset: bool) {
+        self.client.enqueue_packet(&CClearTitle::new(reset)).await;
+    }
+
+    /// Shows `text` above the hotbar, see [`crate::entity::feedback::send_action_bar_to`] for
+    /// many players.
+    pub async fn send_action_bar(&self, text: &TextComponent) {
+        self.client.enqueue_packet(&CActionBar::new(text)).await;
     }
 
     pub async fn spawn_particle(
@@ -1222,41 +1416,14 @@ impl Player {
             .await;
     }
 
-    pub async fn play_sound(
-        &self,
-        sound_id: u16,
-        category: SoundCategory,
-        position: &Vector3<f64>,
-        volume: f32,
-        pitch: f32,
-        seed: f64,
-    ) {
-        self.client
-            .enqueue_packet(&CSoundEffect::new(
-                IdOr::Id(sound_id),
-                category,
-                position,
-                volume,
-                pitch,
-                seed,
-            ))
-            .await;
+    /// Plays `sound` to only this player, see [`SoundEffect::play_to`] for many players.
+    pub async fn play_sound(&self, sound: &SoundEffect) {
+        sound.play(self).await;
     }
 
-    /// Stops a sound playing on the client.
-    ///
-    /// # Arguments
-    ///
-    /// * `sound_id`: An optional [`ResourceLocation`] specifying the sound to stop. If [`None`], all sounds in the specified category (if any) will be stopped.
-    /// * `category`: An optional [`SoundCategory`] specifying the sound category to stop. If [`None`], all sounds with the specified resource location (if any) will be stopped.
-    pub async fn stop_sound(
-        &self,
-        sound_id: Option<ResourceLocation>,
-        category: Option<SoundCategory>,
-    ) {
-        self.client
-            .enqueue_packet(&CStopSound::new(sound_id, category))
-            .await;
+    /// Stops sounds playing on the client, see [`StopSound::send_to`] for many players.
+    pub async fn stop_sound(&self, stop: &StopSound) {
+        stop.send(self).await;
     }
 
     // TODO Abstract the chunk sending
@@ -1306,11 +1473,18 @@ impl Player {
             match &self.client {
                 ClientPlatform::Java(java_client) => {
                     java_client.send_packet_now(&CChunkBatchStart).await;
+                    let world = self.world();
                     for chunk in chunk_of_chunks {
                         // log::debug!("send chunk {:?}", chunk.position);
                         // TODO: Can we check if we still need to send the chunk? Like if it's a fast moving
                         // player or something.
-                        java_client.send_packet_now(&CChunkData(&chunk)).await;
+                        java_client
+                            .send_packet_now_data(world.chunk_packet_cache.encode(
+                                java_client,
+                                &chunk,
+                                &world,
+                            ))
+                            .await;
                     }
                     java_client
                         .send_packet_now(&CChunkBatchEnd::new(chunk_count as u16))
@@ -1363,12 +1537,22 @@ impl Player {
         self.last_attacked_ticks.fetch_add(1, Ordering::Relaxed);
 
         self.living_entity.tick(self.clone(), server).await;
+        self.update_fall_flying().await;
+        self.update_swimming().await;
+        self.update_pose().await;
         self.breath_manager.tick(self).await;
         self.hunger_manager.tick(self).await;
+        self.movement_validator
+            .tick(self, &server.advanced_config.movement)
+            .await;
 
         // experience handling
         self.tick_experience().await;
         self.tick_health().await;
+        server
+            .maps
+            .tick_player(self, server.tick_count.load(Ordering::Relaxed) as u64)
+            .await;
 
         // Timeout/keep alive handling
         self.tick_client_load_timeout();
@@ -1646,6 +1830,7 @@ impl Player {
 
     /// Sends the world time to only this player.
     pub async fn send_time(&self, world: &World) {
+        let advance_time = world.level_info.load().game_rules.advance_time;
         let l_world = world.level_time.lock().await;
         match &self.client {
             ClientPlatform::Java(java_client) => {
@@ -1653,7 +1838,7 @@ impl Player {
                     .enqueue_packet(&CUpdateTime::new(
                         l_world.world_age,
                         l_world.time_of_day,
-                        true,
+                        advance_time,
                     ))
                     .await;
             }
@@ -1741,7 +1926,7 @@ impl Player {
                     .send_packet_now(&CRespawn::new(
                         (new_world.dimension.id).into(),
                         new_world.dimension.minecraft_name.to_string(),
-                        biome::hash_seed(new_world.level.seed.0), // seed
+                        new_world.client_hashed_seed(self), // seed
                         self.gamemode.load() as u8,
                         self.gamemode.load() as i8,
                         false,
@@ -1791,6 +1976,9 @@ impl Player {
 
             'after: {
                 let position = event.to;
+                self.chunk_manager.lock().await.prioritize(
+                    BlockPos::floored_v(position).chunk_position(),
+                );
                 let i = self
                     .teleport_id_count
                     .fetch_add(1, Ordering::Relaxed);
@@ -1837,6 +2025,121 @@ impl Player {
         self.client.kick(reason, message).await;
     }
 
+    /// Transfers the player to another server after firing a [`PlayerTransferEvent`].
+    ///
+    /// Returns whether the transfer was sent. The destination must accept transfers.
+    pub async fn transfer(self: &Arc<Self>, host: &str, port: i32) -> bool {
+        let ClientPlatform::Java(client) = &self.client else {
+            return false;
+        };
+        let server = self.world().server.upgrade().unwrap();
+        let event = server
+            .plugin_manager
+            .fire(PlayerTransferEvent::new(
+                self.clone(),
+                host.to_string(),
+                port,
+            ))
+            .await;
+        if event.is_cancelled() {
+            return false;
+        }
+        if let Err(err) = client.transfer(&event.host, event.port).await {
+            log::warn!(
+                "Failed to transfer {} to {}:{}: {err}",
+                self.gameprofile.name,
+                event.host,
+                event.port
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Whether the player plays on Bedrock Edition, natively or through Geyser with a
+    /// Floodgate account.
+    pub fn is_bedrock(&self) -> bool {
+        matches!(self.client, ClientPlatform::Bedrock(_))
+            || floodgate::is_floodgate_uuid(self.gameprofile.id)
+    }
+
+    /// Whether the player joined through a transfer from another server.
+    pub fn was_transferred(&self) -> bool {
+        matches!(&self.client, ClientPlatform::Java(client) if client.transferred.load(Ordering::Relaxed))
+    }
+
+    /// Stores a cookie on the client, which it keeps across transfers until it quits the game.
+    ///
+    /// Cookies can be changed by the client, use [`Self::store_signed_cookie`] for data other
+    /// servers must be able to trust.
+    pub async fn store_cookie(
+        &self,
+        key: &ResourceLocation,
+        payload: &[u8],
+    ) -> Result<(), TransferError> {
+        let ClientPlatform::Java(client) = &self.client else {
+            return Err(TransferError::NotJava);
+        };
+        client.store_cookie(key, payload).await
+    }
+
+    /// Requests a cookie from the client, `Ok(None)` if it has none under `key`.
+    ///
+    /// The response is read by the same task that handles the player's packets, so this must be
+    /// awaited from a separate task rather than from an event fired by one of its packets.
+    pub async fn request_cookie(
+        &self,
+        key: &ResourceLocation,
+    ) -> Result<CookiePayload, TransferError> {
+        let ClientPlatform::Java(client) = &self.client else {
+            return Err(TransferError::NotJava);
+        };
+        let server = self.world().server.upgrade().unwrap();
+        let timeout =
+            Duration::from_millis(server.advanced_config.networking.transfer.cookie_timeout_ms);
+        client.request_cookie(key, timeout).await
+    }
+
+    /// Stores a cookie signed with the configured `cookie_secret`.
+    pub async fn store_signed_cookie(
+        &self,
+        key: &ResourceLocation,
+        payload: &[u8],
+    ) -> Result<(), TransferError> {
+        let secret = self.cookie_secret()?;
+        self.store_cookie(key, &cookie::sign_cookie(&secret, key, payload))
+            .await
+    }
+
+    /// Requests a cookie stored with [`Self::store_signed_cookie`] on any server sharing the
+    /// `cookie_secret`. Cookies with an invalid signature are treated as missing.
+    pub async fn request_signed_cookie(
+        &self,
+        key: &ResourceLocation,
+    ) -> Result<CookiePayload, TransferError> {
+        let secret = self.cookie_secret()?;
+        let Some(signed) = self.request_cookie(key).await? else {
+            return Ok(None);
+        };
+        let payload = cookie::verify_cookie(&secret, key, &signed).map(Box::from);
+        if payload.is_none() {
+            log::warn!(
+                "{} sent cookie {key} with an invalid signature",
+                self.gameprofile.name
+            );
+        }
+        Ok(payload)
+    }
+
+    fn cookie_secret(&self) -> Result<String, TransferError> {
+        let server = self.world().server.upgrade().unwrap();
+        let secret = &server.advanced_config.networking.transfer.cookie_secret;
+        if secret.is_empty() {
+            return Err(TransferError::NoSecret);
+        }
+        Ok(secret.clone())
+    }
+
     /// Updates the last action time to now. Call this on player actions like movement, chat, etc.
     pub fn update_last_action_time(&self) {
         self.last_action_time.store(std::time::Instant::now());
@@ -1948,6 +2251,24 @@ impl Player {
             .await;
     }
 
+    /// Makes the mobs targeting the player forget about them, as mobs leave players in creative
+    /// and spectator mode alone.
+    async fn clear_mob_targets(&self) {
+        let entities = self.world().entities.load_full();
+        for entity in entities.iter() {
+            let Some(mob) = entity.clone().get_mob() else {
+                continue;
+            };
+            let mut target = mob.get_mob_entity().target.lock().await;
+            if target
+                .as_ref()
+                .is_some_and(|target| target.get_entity().entity_id == self.entity_id())
+            {
+                *target = None;
+            }
+        }
+    }
+
     pub async fn set_gamemode(self: &Arc<Self>, gamemode: GameMode) -> bool {
         // We could send the same gamemode without any problems. But why waste bandwidth?
         // assert_ne!(
@@ -1987,7 +2308,7 @@ impl Player {
                     self.get_entity().set_on_fire(false).await;
                 }
 
-                // Stop elytra flight and reset sneaking when switching to spectator mode
+                // Stop elytra flight, riding and sneaking when switching to spectator mode
                 if gamemode == GameMode::Spectator {
                     let entity = self.get_entity();
                     if entity.fall_flying.load(Ordering::Relaxed) {
@@ -1996,6 +2317,15 @@ impl Player {
                     if entity.sneaking.load(Ordering::Relaxed) {
                         entity.set_sneaking(false).await;
                     }
+                    self.world().stop_riding(self.as_ref()).await;
+                }
+                // Spectators fly through blocks
+                self.get_entity()
+                    .no_clip
+                    .store(gamemode == GameMode::Spectator, Ordering::Relaxed);
+
+                if matches!(gamemode, GameMode::Creative | GameMode::Spectator) {
+                    self.clear_mob_targets().await;
                 }
 
                 self.living_entity.entity.invulnerable.store(
@@ -2572,7 +2902,18 @@ impl Player {
                 .await;
             drop(screen_handler_temp);
             self.on_screen_handler_opened(screen_handler.clone()).await;
-            *self.current_screen_handler.lock().await = screen_handler;
+            *self.current_screen_handler.lock().await = screen_handler.clone();
+            // Bedrock has no container for most Java screens, so they are shown as forms
+            if let ClientPlatform::Bedrock(client) = &self.client
+                && let Some(player) = client.player.lock().await.clone()
+            {
+                client.spawn_task(form::present_screen(
+                    client.clone(),
+                    player,
+                    screen_handler,
+                    screen_handler_factory.get_display_name().get_text(),
+                ));
+            }
             Some(self.screen_handler_sync_id.load(Ordering::Relaxed))
         } else {
             //TODO: Send message if spectator
@@ -2637,7 +2978,22 @@ impl Player {
         screen_handler.set_received_cursor_hash(packet.carried_item);
         screen_handler.enable_sync();
 
-        if not_in_sync {
+        // A client out of sync gets the whole container again, rather than corrections of the
+        // slots it got wrong, so no ghost items are left behind
+        let resync = if not_in_sync {
+            Some(ResyncReason::StaleState)
+        } else if screen_handler.count_mispredicted_slots().await > 0 {
+            Some(ResyncReason::Mispredicted)
+        } else {
+            None
+        };
+        if let Some(reason) = resync {
+            log::debug!(
+                "Resyncing the inventory of {} ({})",
+                self.gameprofile.name,
+                reason.name()
+            );
+            reason.record();
             screen_handler.update_to_client().await;
         } else {
             screen_handler.send_content_updates().await;
@@ -2708,6 +3064,7 @@ impl NBTStorage for Player {
                 "HasPlayedBefore",
                 self.has_played_before.load(Ordering::Relaxed),
             );
+            nbt.put_bool("seenCredits", self.seen_credits.load(Ordering::Relaxed));
 
             // Store food level, saturation, exhaustion, and tick timer
             self.hunger_manager.write_nbt(nbt).await;
@@ -2740,6 +3097,10 @@ impl NBTStorage for Player {
                 nbt.get_bool("HasPlayedBefore").unwrap_or(false),
                 Ordering::Relaxed,
             );
+            self.seen_credits.store(
+                nbt.get_bool("seenCredits").unwrap_or(false),
+                Ordering::Relaxed,
+            );
 
             // Load food level, saturation, exhaustion, and tick timer
             self.hunger_manager.read_nbt(nbt).await;
@@ -2760,7 +3121,7 @@ impl NBTStorage for Player {
             ) {
                 let dim = nbt
                     .get_string("SpawnDimension")
-                    .and_then(|s| Dimension::from_name(s).copied())
+                    .and_then(dimension_from_name)
                     .unwrap_or(self.world().dimension);
                 let force = nbt.get_bool("SpawnForced").unwrap_or(false);
                 self.respawn_point.store(Some(RespawnPoint {
@@ -2914,7 +3275,9 @@ impl NBTStorage for EnderChestInventory {
                         && let Some(slot_byte) = item_compound.get_byte("Slot")
                     {
                         let slot = slot_byte as usize;
-                        if let Some(item_stack) = ItemStack::read_item_stack(item_compound) {
+                        if slot < Self::INVENTORY_SIZE
+                            && let Some(item_stack) = ItemStack::read_item_stack(item_compound)
+                        {
                             self.set_stack(slot, item_stack).await;
                         }
                     }
@@ -2940,6 +3303,15 @@ impl EntityBase for Player {
             if self.abilities.lock().await.invulnerable && damage_type != DamageType::GENERIC_KILL {
                 return false;
             }
+            if let Some(attacker) = cause.and_then(EntityBase::get_player) {
+                let scoreboard = self.world().scoreboard.lock().await;
+                if !Scoreboard::can_harm(
+                    scoreboard.team_of(&attacker.gameprofile.name),
+                    scoreboard.team_of(&self.gameprofile.name),
+                ) {
+                    return false;
+                }
+            }
             let result = self
                 .living_entity
                 .damage_with_context(caller, amount, damage_type, position, source, cause)
@@ -3018,22 +3390,32 @@ impl EntityBase for Player {
     }
 
     fn get_name(&self) -> TextComponent {
-        //TODO: team color
         TextComponent::text(self.gameprofile.name.clone())
     }
 
     fn get_display_name(&self) -> EntityBaseFuture<'_, TextComponent> {
-        let name = self.get_name();
-        let name_clone = name.clone();
-        let mut name = name.click_event(ClickEvent::SuggestCommand {
-            command: format!("/tell {} ", self.gameprofile.name.clone()).into(),
-        });
-        name = name.hover_event(HoverEvent::show_entity(
-            self.living_entity.entity.entity_uuid.to_string(),
-            self.living_entity.entity.entity_type.resource_name.into(),
-            Some(name_clone),
-        ));
-        Box::pin(async move { name.insertion(self.gameprofile.name.clone()) })
+        Box::pin(async move {
+            let mut name = self.get_name();
+            let hover = HoverEvent::show_entity(
+                self.living_entity.entity.entity_uuid.to_string(),
+                self.living_entity.entity.entity_type.resource_name.into(),
+                Some(name.clone()),
+            );
+            if let Some(team) = self
+                .world()
+                .scoreboard
+                .lock()
+                .await
+                .team_of(&self.gameprofile.name)
+            {
+                name = team.format_name(name);
+            }
+            name.click_event(ClickEvent::SuggestCommand {
+                command: format!("/tell {} ", self.gameprofile.name.clone()).into(),
+            })
+            .hover_event(hover)
+            .insertion(self.gameprofile.name.clone())
+        })
     }
 
     fn as_nbt_storage(&self) -> &dyn NBTStorage {
@@ -3041,6 +3423,7 @@ impl EntityBase for Player {
     }
 }
 
+#[deprecated(note = "use `Title` and `Player::send_action_bar`")]
 #[derive(Debug)]
 pub enum TitleMode {
     Title,
@@ -3425,3 +3808,41 @@ impl InventoryPlayer for Player {
         })
     }
 }
+
+/// The pose for what the player is doing, from gliding over sleeping, swimming and sneaking to
+/// standing. Flying players don't crouch.
+fn desired_pose(entity: &Entity, sleeping: bool, flying: bool) -> EntityPose {
+    if entity.fall_flying.load(Ordering::Relaxed) {
+        EntityPose::FallFlying
+    } else if sleeping {
+        EntityPose::Sleeping
+    } else if entity.swimming.load(Ordering::Relaxed) {
+        EntityPose::Swimming
+    } else if entity.sneaking.load(Ordering::Relaxed) && !flying {
+        EntityPose::Crouching
+    } else {
+        EntityPose::Standing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn poses_follow_what_the_player_does() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let entity = Entity::new(world, Vector3::new(0.0, 64.0, 0.0), &EntityType::PLAYER);
+
+        assert!(desired_pose(&entity, false, false) == EntityPose::Standing);
+        entity.sneaking.store(true, Ordering::Relaxed);
+        assert!(desired_pose(&entity, false, false) == EntityPose::Crouching);
+        assert!(desired_pose(&entity, false, true) == EntityPose::Standing);
+        entity.swimming.store(true, Ordering::Relaxed);
+        assert!(desired_pose(&entity, false, false) == EntityPose::Swimming);
+        assert!(desired_pose(&entity, true, false) == EntityPose::Sleeping);
+        entity.fall_flying.store(true, Ordering::Relaxed);
+        assert!(desired_pose(&entity, true, false) == EntityPose::FallFlying);
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+
+use pumpkin_data::data_component_impl::{PotionContentsImpl, StatusEffectInstance};
+use pumpkin_data::effect::StatusEffect;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::world::WorldEvent;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::entity::area_effect_cloud::AreaEffectCloudEntity;
+use crate::entity::projectile::{ProjectileHit, ThrownItemEntity};
+use crate::entity::{Entity, EntityBase, EntityBaseFuture, NBTStorage};
+use crate::server::Server;
+use crate::world::particles::ParticleEffect;
+
+/// How much faster fireballs get every tick, in the direction they fly.
+const ACCELERATION: f64 = 0.1;
+const CLOUD_DURATION: i32 = 600;
+/// The cloud grows to this radius until it disappears.
+const CLOUD_MAX_RADIUS: f32 = 7.0;
+
+/// A fireball shot by the ender dragon. It flies straight without falling, and leaves a cloud of
+/// dragon's breath where it hits.
+pub struct DragonFireballEntity {
+    pub thrown: ThrownItemEntity,
+}
+
+impl DragonFireballEntity {
+    pub const fn new(entity: Entity) -> Self {
+        Self {
+            thrown: ThrownItemEntity {
+                entity,
+                owner_id: None,
+                collides_with_projectiles: false,
+                has_hit: AtomicBool::new(false),
+            },
+        }
+    }
+
+    /// A fireball shot by `owner` in `direction`, from where the entity is.
+    pub fn new_shot(entity: Entity, owner: &Entity, direction: Vector3<f64>) -> Self {
+        let velocity = direction.normalize();
+        entity
+            .velocity
+            .store(velocity.multiply(ACCELERATION, ACCELERATION, ACCELERATION));
+        let mut fireball = Self::new(entity);
+        fireball.thrown.owner_id = Some(owner.entity_id);
+        fireball
+    }
+}
+
+impl NBTStorage for DragonFireballEntity {}
+
+impl EntityBase for DragonFireballEntity {
+    fn tick<'a>(
+        &'a self,
+        caller: Arc<dyn EntityBase>,
+        _server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            let entity = &self.thrown.entity;
+            let inertia = if entity.touching_water.load(Relaxed) {
+                0.8
+            } else {
+                0.95
+            };
+            let velocity = entity.velocity.load();
+            if velocity.length_squared() > 0.0 {
+                let velocity = velocity
+                    .add(
+                        &velocity
+                            .normalize()
+                            .multiply(ACCELERATION, ACCELERATION, ACCELERATION),
+                    )
+                    .multiply(inertia, inertia, inertia);
+                entity.velocity.store(velocity);
+            }
+            self.thrown.move_and_collide(caller).await;
+        })
+    }
+
+    fn get_entity(&self) -> &Entity {
+        &self.thrown.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&crate::entity::living::LivingEntity> {
+        None
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+
+    fn on_hit(&self, hit: ProjectileHit) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            let world = self.thrown.entity.world.load_full();
+            let owner = self
+                .thrown
+                .owner_id
+                .and_then(|id| world.get_entity_by_id(id));
+            let pos = hit.hit_pos();
+            world
+                .sync_world_event(
+                    WorldEvent::DragonBreathCloudSpawns,
+                    BlockPos::floored_v(pos),
+                    1,
+                )
+                .await;
+
+            let contents = PotionContentsImpl {
+                potion_id: None,
+                custom_color: None,
+                custom_effects: vec![StatusEffectInstance {
+                    effect_id: i32::from(StatusEffect::INSTANT_DAMAGE.id),
+                    amplifier: 1,
+                    duration: 1,
+                    ambient: false,
+                    show_particles: true,
+                    show_icon: true,
+                }],
+                custom_name: None,
+            };
+            let entity = Entity::new(world.clone(), pos, &EntityType::AREA_EFFECT_CLOUD);
+            let cloud = AreaEffectCloudEntity::new(entity)
+                .with_potion(contents, 0.25)
+                .with_particle(ParticleEffect::dragon_breath(1.0));
+            cloud
+                .owner
+                .store(owner.map(|owner| owner.get_entity().entity_uuid));
+            cloud.duration.store(CLOUD_DURATION, Relaxed);
+            cloud
+                .radius_per_tick
+                .store((CLOUD_MAX_RADIUS - cloud.radius()) / CLOUD_DURATION as f32);
+            world.spawn_entity(Arc::new(cloud)).await;
+        })
+    }
+}
@@ -0,0 +1,820 @@
+//! The bobber cast with a fishing rod, following vanilla `FishingBobberEntity`.
+//!
+//! A cast bobber flies until it lands in water or hooks an entity. In water it waits for a
+//! fish, which swims towards it and bites. Reeling in while a fish bites catches loot from the
+//! tables in [`loot`], reeling in a hooked entity pulls it towards the player.
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use pumpkin_data::effect::StatusEffect;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::fluid::Fluid;
+use pumpkin_data::item::Item;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::particle::Particle;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_data::tracked_data::TrackedData;
+use pumpkin_data::{Block, Enchantment};
+use pumpkin_protocol::codec::var_int::VarInt;
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::item::ItemStack;
+use rand::RngExt;
+use tokio::sync::Mutex;
+
+use super::calculate_ray_intersection;
+use crate::entity::experience_orb::ExperienceOrbEntity;
+use crate::entity::item::ItemEntity;
+use crate::entity::player::Player;
+use crate::entity::{Entity, EntityBase, EntityBaseFuture, NBTStorage, living::LivingEntity};
+use crate::server::Server;
+use crate::world::World;
+use crate::world::loot::{LootContextParameters, LootTableExt};
+use crate::world::particles::ParticleEffect;
+
+/// The bobber is removed when its owner gets further away than this.
+const MAX_OWNER_DISTANCE: f64 = 32.0;
+/// How long a bobber may lie on the ground before it is removed.
+const MAX_GROUND_TICKS: u32 = 1200;
+/// How many ticks each level of Lure takes off the wait for a fish.
+const LURE_TICKS_PER_LEVEL: i32 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum BobberState {
+    Flying,
+    HookedInEntity,
+    Bobbing,
+}
+
+/// The fishing progress, which goes from waiting for a fish, to the fish swimming towards the
+/// bobber, to the fish biting.
+struct Fishing {
+    state: BobberState,
+    hooked_entity: Option<i32>,
+    ground_ticks: u32,
+    out_of_open_water_ticks: u32,
+    in_open_water: bool,
+    wait_countdown: i32,
+    fish_travel_countdown: i32,
+    hook_countdown: i32,
+    fish_angle: f32,
+    caught_fish: bool,
+}
+
+pub struct FishingBobberEntity {
+    entity: Entity,
+    owner_id: i32,
+    /// The level of Luck of the Sea on the rod.
+    luck: i32,
+    /// How many ticks Lure takes off the wait for a fish.
+    lure_ticks: i32,
+    fishing: Mutex<Fishing>,
+}
+
+impl FishingBobberEntity {
+    /// Casts a bobber from `owner` in the direction they look.
+    #[must_use]
+    pub fn new(owner: &Player, world: Arc<World>, rod: &ItemStack) -> Self {
+        let owner_entity = owner.get_entity();
+        let yaw = owner_entity.yaw.load();
+        let pitch = owner_entity.pitch.load();
+        let h = (-yaw.to_radians() - PI).cos();
+        let i = (-yaw.to_radians() - PI).sin();
+        let j = -(-pitch.to_radians()).cos();
+        let k = (-pitch.to_radians()).sin();
+
+        let owner_pos = owner.position();
+        let position = Vector3::new(
+            owner_pos.x - f64::from(i) * 0.3,
+            owner_entity.get_eye_y(),
+            owner_pos.z - f64::from(h) * 0.3,
+        );
+        let direction = Vector3::new(
+            f64::from(-i),
+            f64::from((-(k / j)).clamp(-5.0, 5.0)),
+            f64::from(-h),
+        );
+        let speed = 0.6 / direction.length();
+        let mut random = rand::rng();
+        let mut triangular =
+            || 0.0103365f64.mul_add(random.random::<f64>() - random.random::<f64>(), 0.5);
+        let velocity = direction.multiply(
+            speed + triangular(),
+            speed + triangular(),
+            speed + triangular(),
+        );
+
+        let entity = Entity::new(world, position, &EntityType::FISHING_BOBBER);
+        entity.velocity.store(velocity);
+        entity.set_rotation(
+            velocity.x.atan2(velocity.z).to_degrees() as f32,
+            velocity.y.atan2(velocity.horizontal_length()).to_degrees() as f32,
+        );
+        // Clients draw the line to the entity in the spawn data
+        entity.data.store(owner.entity_id(), Ordering::Relaxed);
+
+        Self {
+            entity,
+            owner_id: owner.entity_id(),
+            luck: rod.get_enchantment_level(&Enchantment::LUCK_OF_THE_SEA),
+            lure_ticks: rod.get_enchantment_level(&Enchantment::LURE) * LURE_TICKS_PER_LEVEL,
+            fishing: Mutex::new(Fishing {
+                state: BobberState::Flying,
+                hooked_entity: None,
+                ground_ticks: 0,
+                out_of_open_water_ticks: 0,
+                in_open_water: true,
+                wait_countdown: 0,
+                fish_travel_countdown: 0,
+                hook_countdown: 0,
+                fish_angle: 0.0,
+                caught_fish: false,
+            }),
+        }
+    }
+
+    /// Whether `player` holds a fishing rod in either hand.
+    pub async fn is_holding_rod(player: &Player) -> bool {
+        let inventory = player.inventory();
+        inventory.held_item().lock().await.item.id == Item::FISHING_ROD.id
+            || inventory.off_hand_item().await.lock().await.item.id == Item::FISHING_ROD.id
+    }
+
+    /// Whether the bobber lost its owner, e.g. because they put the rod away or walked off.
+    async fn is_invalid(&self, owner: Option<&Arc<Player>>) -> bool {
+        let Some(owner) = owner else {
+            return true;
+        };
+        owner.get_entity().is_removed()
+            || owner.living_entity.dead.load(Ordering::Relaxed)
+            || owner
+                .position()
+                .squared_distance_to_vec(&self.entity.pos.load())
+                > MAX_OWNER_DISTANCE * MAX_OWNER_DISTANCE
+            || !Self::is_holding_rod(owner).await
+    }
+
+    /// Removes the bobber, and lets its owner cast a new one.
+    async fn discard(&self, owner: Option<&Player>) {
+        if let Some(owner) = owner {
+            let mut bobber = owner.fishing_bobber.lock().await;
+            if bobber
+                .as_ref()
+                .is_some_and(|bobber| bobber.entity.entity_id == self.entity.entity_id)
+            {
+                *bobber = None;
+            }
+        }
+        self.entity.remove().await;
+    }
+
+    /// The height of the water at `pos`, or 0 if there is no water.
+    async fn water_height(world: &World, pos: &BlockPos) -> f64 {
+        let fluid = world.get_fluid(pos).await;
+        if fluid.id != Fluid::WATER.id && fluid.id != Fluid::FLOWING_WATER.id {
+            return 0.0;
+        }
+        let above = world.get_fluid(&pos.up()).await;
+        if above.id == Fluid::WATER.id || above.id == Fluid::FLOWING_WATER.id {
+            1.0
+        } else {
+            8.0 / 9.0
+        }
+    }
+
+    async fn set_hooked_entity(&self, hooked: Option<i32>) {
+        self.entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_HOOK_ENTITY_ID,
+                MetaDataType::Integer,
+                VarInt(hooked.map_or(0, |id| id + 1)),
+            )])
+            .await;
+    }
+
+    async fn set_caught_fish(&self, caught_fish: bool) {
+        self.entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_CAUGHT_FISH,
+                MetaDataType::Boolean,
+                caught_fish,
+            )])
+            .await;
+    }
+
+    /// The first entity the bobber flies into this tick, which is not the bobber or its owner.
+    fn find_hit_entity(&self, world: &World) -> Option<Arc<dyn EntityBase>> {
+        let start = self.entity.pos.load();
+        let velocity = self.entity.velocity.load();
+        let area = self
+            .entity
+            .bounding_box
+            .load()
+            .stretch(velocity)
+            .expand(1.0, 1.0, 1.0);
+        let candidates = world.get_entities_at_box(&area).into_iter().chain(
+            world
+                .get_players_at_box(&area)
+                .into_iter()
+                .map(|player| player as Arc<dyn EntityBase>),
+        );
+
+        let mut closest = None;
+        let mut closest_t = 1.0;
+        for candidate in candidates {
+            let base = candidate.get_entity();
+            if base.entity_id == self.entity.entity_id
+                || base.entity_id == self.owner_id
+                || base.is_removed()
+                || !(candidate.can_hit() || base.entity_type == &EntityType::ITEM)
+            {
+                continue;
+            }
+            let target = base.bounding_box.load().expand(0.3, 0.3, 0.3);
+            if let Some(t) = calculate_ray_intersection(&start, &velocity, &target)
+                && t < closest_t
+            {
+                closest_t = t;
+                closest = Some(candidate);
+            }
+        }
+        closest
+    }
+
+    /// Whether the water around the bobber is open water, where treasure can be caught: layers
+    /// of only water below layers of only air or lily pads.
+    async fn is_open_water_around(world: &World, pos: BlockPos) -> bool {
+        let mut previous = None;
+        for dy in -1..=2 {
+            let Some(layer) = Self::layer_kind(world, pos.offset(Vector3::new(0, dy, 0))).await
+            else {
+                return false;
+            };
+            match (previous, layer) {
+                // The lowest layer has to be water, and no water may lie above the air
+                (None, WaterLayer::AboveWater)
+                | (Some(WaterLayer::AboveWater), WaterLayer::InsideWater) => {
+                    return false;
+                }
+                _ => previous = Some(layer),
+            }
+        }
+        true
+    }
+
+    /// What the 5x5 layer of blocks around `center` is made of, if it is all the same.
+    async fn layer_kind(world: &World, center: BlockPos) -> Option<WaterLayer> {
+        let mut kind = None;
+        for dx in -2..=2 {
+            for dz in -2..=2 {
+                let pos = center.offset(Vector3::new(dx, 0, dz));
+                let state_id = world.get_block_state_id(&pos).await;
+                let block = Block::from_state_id(state_id);
+                let block_kind = if block.id == Block::AIR.id || block.id == Block::LILY_PAD.id {
+                    WaterLayer::AboveWater
+                } else if state_id == Block::WATER.default_state.id {
+                    WaterLayer::InsideWater
+                } else {
+                    return None;
+                };
+                if kind.is_some_and(|kind| kind != block_kind) {
+                    return None;
+                }
+                kind = Some(block_kind);
+            }
+        }
+        kind
+    }
+
+    /// Counts down to the next bite while the bobber floats, with the particles of the fish
+    /// swimming towards it. Like vanilla, rain speeds fishing up and a roof slows it down.
+    async fn tick_fishing(&self, world: &Arc<World>, fishing: &mut Fishing, pos: BlockPos) {
+        let mut speed = 1;
+        let above = pos.up();
+        let sky_visible = world.get_motion_blocking_height(above.0.x, above.0.z).await <= above.0.y;
+        let (rain_roll, roof_roll) = (
+            rand::rng().random_range(0..4),
+            rand::rng().random_range(0..2),
+        );
+        if rain_roll == 0 && sky_visible && world.weather.lock().await.raining {
+            speed += 1;
+        }
+        if roof_roll == 0 && !sky_visible {
+            speed -= 1;
+        }
+
+        let bobber_pos = self.entity.pos.load();
+        if fishing.hook_countdown > 0 {
+            fishing.hook_countdown -= 1;
+            if fishing.hook_countdown <= 0 {
+                fishing.wait_countdown = 0;
+                fishing.fish_travel_countdown = 0;
+                fishing.caught_fish = false;
+                self.set_caught_fish(false).await;
+            } else {
+                let mut velocity = self.entity.velocity.load();
+                velocity.y -= 0.2 * f64::from(rand::random::<f32>() * rand::random::<f32>());
+                self.entity.velocity.store(velocity);
+            }
+        } else if fishing.fish_travel_countdown > 0 {
+            fishing.fish_travel_countdown -= speed;
+            if fishing.fish_travel_countdown > 0 {
+                self.show_fish_travel(world, fishing).await;
+            } else {
+                // The fish bites
+                world
+                    .play_sound_fine(
+                        Sound::EntityFishingBobberSplash,
+                        SoundCategory::Neutral,
+                        &bobber_pos,
+                        0.25,
+                        1.0 + (rand::random::<f32>() - rand::random::<f32>()) * 0.4,
+                    )
+                    .await;
+                let width = self.entity.width();
+                let splash_pos = bobber_pos.add_raw(0.0, 0.5, 0.0);
+                for particle in [Particle::Bubble, Particle::Fishing] {
+                    ParticleEffect::new(particle)
+                        .count(1 + (width * 20.0) as i32)
+                        .offset(Vector3::new(width, 0.0, width))
+                        .speed(0.2)
+                        .spawn(world, splash_pos)
+                        .await;
+                }
+                fishing.hook_countdown = rand::rng().random_range(20..=40);
+                fishing.caught_fish = true;
+                self.set_caught_fish(true).await;
+            }
+        } else if fishing.wait_countdown > 0 {
+            fishing.wait_countdown -= speed;
+            let mut chance = 0.15;
+            if fishing.wait_countdown < 20 {
+                chance += (20 - fishing.wait_countdown) as f32 * 0.05;
+            } else if fishing.wait_countdown < 40 {
+                chance += (40 - fishing.wait_countdown) as f32 * 0.02;
+            } else if fishing.wait_countdown < 60 {
+                chance += (60 - fishing.wait_countdown) as f32 * 0.01;
+            }
+            if rand::random::<f32>() < chance {
+                Self::show_splash(world, bobber_pos).await;
+            }
+            if fishing.wait_countdown <= 0 {
+                fishing.fish_angle = rand::rng().random_range(0.0..360.0);
+                fishing.fish_travel_countdown = rand::rng().random_range(20..=80);
+            }
+        } else {
+            fishing.wait_countdown = rand::rng().random_range(100..=600) - self.lure_ticks;
+        }
+    }
+
+    /// The wake of a fish swimming towards the bobber.
+    async fn show_fish_travel(&self, world: &World, fishing: &mut Fishing) {
+        fishing.fish_angle += 9.188 * (rand::random::<f32>() - rand::random::<f32>());
+        let angle = fishing.fish_angle.to_radians();
+        let (sin, cos) = (f64::from(angle.sin()), f64::from(angle.cos()));
+        let bobber_pos = self.entity.pos.load();
+        let distance = f64::from(fishing.fish_travel_countdown) * 0.1;
+        let fish_pos = Vector3::new(
+            sin.mul_add(distance, bobber_pos.x),
+            bobber_pos.y.floor() + 1.0,
+            cos.mul_add(distance, bobber_pos.z),
+        );
+        if world
+            .get_block(&BlockPos::floored_v(fish_pos.add_raw(0.0, -1.0, 0.0)))
+            .await
+            .id
+            != Block::WATER.id
+        {
+            return;
+        }
+
+        if rand::random::<f32>() < 0.15 {
+            ParticleEffect::new(Particle::Bubble)
+                .offset(Vector3::new(sin as f32, 0.1, cos as f32))
+                .spawn(world, fish_pos.add_raw(0.0, -0.1, 0.0))
+                .await;
+        }
+        // With a count of 0 the offset is the direction the particle moves in
+        let (dx, dz) = ((cos * 0.04) as f32, (sin * 0.04) as f32);
+        for offset in [Vector3::new(dx, 0.01, -dz), Vector3::new(-dx, 0.01, dz)] {
+            ParticleEffect::new(Particle::Fishing)
+                .count(0)
+                .offset(offset)
+                .speed(1.0)
+                .spawn(world, fish_pos)
+                .await;
+        }
+    }
+
+    /// A splash somewhere around the bobber, more often the closer a fish is.
+    async fn show_splash(world: &World, bobber_pos: Vector3<f64>) {
+        let angle = rand::rng().random_range(0.0f64..360.0).to_radians();
+        let distance = rand::rng().random_range(25.0f64..60.0) * 0.1;
+        let count = rand::rng().random_range(2..4);
+        let splash_pos = Vector3::new(
+            angle.sin().mul_add(distance, bobber_pos.x),
+            bobber_pos.y.floor() + 1.0,
+            angle.cos().mul_add(distance, bobber_pos.z),
+        );
+        if world
+            .get_block(&BlockPos::floored_v(splash_pos.add_raw(0.0, -1.0, 0.0)))
+            .await
+            .id
+            == Block::WATER.id
+        {
+            ParticleEffect::new(Particle::Splash)
+                .count(count)
+                .offset(Vector3::new(0.1, 0.0, 0.1))
+                .spawn(world, splash_pos)
+                .await;
+        }
+    }
+
+    /// The luck of `owner` for fishing loot: Luck of the Sea plus the Luck and Unluck effects.
+    async fn luck_of(&self, owner: &Player) -> f32 {
+        let living = &owner.living_entity;
+        let luck = living
+            .get_effect(&StatusEffect::LUCK)
+            .await
+            .map_or(0, |effect| i32::from(effect.amplifier) + 1);
+        let unluck = living
+            .get_effect(&StatusEffect::UNLUCK)
+            .await
+            .map_or(0, |effect| i32::from(effect.amplifier) + 1);
+        (self.luck + luck - unluck) as f32
+    }
+
+    /// Reels the bobber in, catching what bites or pulling the hooked entity towards `owner`.
+    /// Returns how much the rod is damaged.
+    pub async fn retrieve(&self, owner: &Player, rod: &ItemStack) -> i32 {
+        let world = self.entity.world.load_full();
+        let (hooked_entity, caught) = {
+            let fishing = self.fishing.lock().await;
+            (fishing.hooked_entity, fishing.hook_countdown > 0)
+        };
+
+        let damage = if let Some(hooked) = hooked_entity.and_then(|id| world.get_entity_by_id(id)) {
+            let hooked = hooked.get_entity();
+            let pull = owner
+                .position()
+                .sub(&self.entity.pos.load())
+                .multiply(0.1, 0.1, 0.1);
+            hooked.set_velocity(hooked.velocity.load().add(&pull)).await;
+            if hooked.entity_type == &EntityType::ITEM {
+                3
+            } else {
+                5
+            }
+        } else if caught {
+            let in_open_water = self.fishing.lock().await.in_open_water;
+            let luck = self.luck_of(owner).await;
+            let table = loot::pick_table(luck, in_open_water);
+            let params = LootContextParameters {
+                tool: Some(rod.clone()),
+                ..Default::default()
+            };
+            let bobber_pos = self.entity.pos.load();
+            for stack in table.get_loot(params) {
+                let delta = owner.position().sub(&bobber_pos);
+                let velocity = Vector3::new(
+                    delta.x * 0.1,
+                    delta.y.mul_add(0.1, delta.length().sqrt() * 0.08),
+                    delta.z * 0.1,
+                );
+                let item = Entity::new(world.clone(), bobber_pos, &EntityType::ITEM);
+                let item = ItemEntity::new_with_velocity(item, stack, velocity, 10).await;
+                world.spawn_entity(Arc::new(item)).await;
+            }
+            let orb_pos = owner.position().add_raw(0.0, 0.5, 0.5);
+            let experience = rand::rng().random_range(1..=6);
+            ExperienceOrbEntity::spawn(&world, orb_pos, experience).await;
+            1
+        } else {
+            0
+        };
+
+        self.discard(Some(owner)).await;
+        // Reeling in a bobber stuck in the ground is the hardest on the rod
+        if self.entity.on_ground.load(Ordering::Relaxed) {
+            2
+        } else {
+            damage
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WaterLayer {
+    AboveWater,
+    InsideWater,
+}
+
+impl NBTStorage for FishingBobberEntity {}
+
+impl EntityBase for FishingBobberEntity {
+    fn tick<'a>(
+        &'a self,
+        caller: Arc<dyn EntityBase>,
+        _server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            if self.entity.is_removed() {
+                return;
+            }
+            let world = self.entity.world.load_full();
+            let owner = world.get_player_by_id(self.owner_id);
+            if self.is_invalid(owner.as_ref()).await {
+                self.discard(owner.as_deref()).await;
+                return;
+            }
+
+            let mut fishing = self.fishing.lock().await;
+            if self.entity.on_ground.load(Ordering::Relaxed) {
+                fishing.ground_ticks += 1;
+                if fishing.ground_ticks >= MAX_GROUND_TICKS {
+                    drop(fishing);
+                    self.discard(owner.as_deref()).await;
+                    return;
+                }
+            } else {
+                fishing.ground_ticks = 0;
+            }
+
+            let pos = self.entity.block_pos.load();
+            let water_height = Self::water_height(&world, &pos).await;
+            match fishing.state {
+                BobberState::Flying => {
+                    if water_height > 0.0 {
+                        let velocity = self.entity.velocity.load();
+                        self.entity.velocity.store(velocity.multiply(0.3, 0.2, 0.3));
+                        fishing.state = BobberState::Bobbing;
+                        return;
+                    }
+                    if let Some(hit) = self.find_hit_entity(&world) {
+                        let id = hit.get_entity().entity_id;
+                        fishing.hooked_entity = Some(id);
+                        fishing.state = BobberState::HookedInEntity;
+                        self.entity.velocity.store(Vector3::new(0.0, 0.0, 0.0));
+                        self.set_hooked_entity(Some(id)).await;
+                        return;
+                    }
+                }
+                BobberState::HookedInEntity => {
+                    let hooked = fishing
+                        .hooked_entity
+                        .and_then(|id| world.get_entity_by_id(id));
+                    match hooked {
+                        Some(hooked) if !hooked.get_entity().is_removed() => {
+                            let hooked = hooked.get_entity();
+                            let hooked_pos = hooked.pos.load();
+                            self.entity.set_pos(hooked_pos.add_raw(
+                                0.0,
+                                f64::from(hooked.height()) * 0.8,
+                                0.0,
+                            ));
+                            self.entity.send_pos_rot().await;
+                        }
+                        _ => {
+                            fishing.hooked_entity = None;
+                            fishing.state = BobberState::Flying;
+                            self.set_hooked_entity(None).await;
+                        }
+                    }
+                    return;
+                }
+                BobberState::Bobbing => {
+                    let velocity = self.entity.velocity.load();
+                    let mut depth =
+                        self.entity.pos.load().y + velocity.y - f64::from(pos.0.y) - water_height;
+                    if depth.abs() < 0.01 {
+                        depth += depth.signum() * 0.1;
+                    }
+                    self.entity.velocity.store(Vector3::new(
+                        velocity.x * 0.9,
+                        depth.mul_add(-f64::from(rand::random::<f32>()) * 0.2, velocity.y),
+                        velocity.z * 0.9,
+                    ));
+
+                    fishing.in_open_water =
+                        if fishing.hook_countdown <= 0 && fishing.fish_travel_countdown <= 0 {
+                            true
+                        } else {
+                            fishing.in_open_water
+                                && fishing.out_of_open_water_ticks < 10
+                                && Self::is_open_water_around(&world, pos).await
+                        };
+
+                    if water_height > 0.0 {
+                        fishing.out_of_open_water_ticks =
+                            fishing.out_of_open_water_ticks.saturating_sub(1);
+                        if fishing.caught_fish {
+                            let mut velocity = self.entity.velocity.load();
+                            velocity.y -=
+                                0.1 * f64::from(rand::random::<f32>() * rand::random::<f32>());
+                            self.entity.velocity.store(velocity);
+                        }
+                        self.tick_fishing(&world, &mut fishing, pos).await;
+                    } else {
+                        fishing.out_of_open_water_ticks =
+                            (fishing.out_of_open_water_ticks + 1).min(10);
+                    }
+                }
+            }
+
+            let state = fishing.state;
+            drop(fishing);
+
+            let mut velocity = self.entity.velocity.load();
+            if water_height <= 0.0 {
+                velocity.y -= 0.03;
+            }
+            self.entity.move_entity(caller, velocity).await;
+            if state == BobberState::Flying
+                && (self.entity.on_ground.load(Ordering::Relaxed)
+                    || self.entity.horizontal_collision.load(Ordering::Relaxed))
+            {
+                velocity = Vector3::new(0.0, 0.0, 0.0);
+            }
+            self.entity
+                .velocity
+                .store(velocity.multiply(0.92, 0.92, 0.92));
+
+            self.entity.send_pos_rot().await;
+            self.entity.send_velocity().await;
+        })
+    }
+
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&LivingEntity> {
+        None
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+}
+
+/// The vanilla `gameplay/fishing` loot tables. Which table is rolled depends on the luck of the
+/// player, and treasure is only caught in open water.
+pub mod loot {
+    use pumpkin_util::loot_table::{
+        ItemEntry, LootCondition, LootFunction, LootFunctionNumberProvider, LootFunctionTypes,
+        LootNumberProviderTypes, LootPool, LootPoolEntry, LootPoolEntryTypes, LootTable,
+        LootTableType,
+    };
+    use rand::RngExt;
+
+    const fn item(name: &'static str, weight: i32) -> LootPoolEntry {
+        LootPoolEntry {
+            content: LootPoolEntryTypes::Item(ItemEntry { name }),
+            conditions: None,
+            functions: None,
+            weight,
+        }
+    }
+
+    const fn table(random_sequence: &'static str, pools: &'static [LootPool]) -> LootTable {
+        LootTable {
+            r#type: LootTableType::Fishing,
+            random_sequence: Some(random_sequence),
+            pools: Some(pools),
+        }
+    }
+
+    // The random enchantments and damage of the bows, rods and boots need loot functions that
+    // are not parsed yet, so they are caught as they are.
+    pub static FISH: LootTable = table(
+        "minecraft:gameplay/fishing/fish",
+        &[LootPool {
+            entries: &[
+                item("minecraft:cod", 60),
+                item("minecraft:salmon", 25),
+                item("minecraft:tropical_fish", 2),
+                item("minecraft:pufferfish", 13),
+            ],
+            rolls: LootNumberProviderTypes::Constant(1.0),
+            bonus_rolls: 0.0,
+            conditions: None,
+            functions: None,
+        }],
+    );
+
+    pub static JUNK: LootTable = table(
+        "minecraft:gameplay/fishing/junk",
+        &[LootPool {
+            entries: &[
+                item("minecraft:lily_pad", 17),
+                item("minecraft:leather_boots", 10),
+                item("minecraft:leather", 10),
+                item("minecraft:bone", 10),
+                item("minecraft:potion", 10),
+                item("minecraft:string", 5),
+                item("minecraft:fishing_rod", 2),
+                item("minecraft:bowl", 10),
+                item("minecraft:stick", 5),
+                LootPoolEntry {
+                    content: LootPoolEntryTypes::Item(ItemEntry {
+                        name: "minecraft:ink_sac",
+                    }),
+                    conditions: None,
+                    functions: Some(&[LootFunction {
+                        content: LootFunctionTypes::SetCount {
+                            count: LootFunctionNumberProvider::Constant { value: 10.0 },
+                            add: false,
+                        },
+                        conditions: None,
+                    }]),
+                    weight: 1,
+                },
+                item("minecraft:tripwire_hook", 10),
+                item("minecraft:rotten_flesh", 10),
+                // Only in jungles in vanilla
+                LootPoolEntry {
+                    content: LootPoolEntryTypes::Item(ItemEntry {
+                        name: "minecraft:bamboo",
+                    }),
+                    conditions: Some(&[LootCondition::LocationCheck]),
+                    functions: None,
+                    weight: 10,
+                },
+            ],
+            rolls: LootNumberProviderTypes::Constant(1.0),
+            bonus_rolls: 0.0,
+            conditions: None,
+            functions: None,
+        }],
+    );
+
+    pub static TREASURE: LootTable = table(
+        "minecraft:gameplay/fishing/treasure",
+        &[LootPool {
+            entries: &[
+                item("minecraft:bow", 1),
+                item("minecraft:enchanted_book", 1),
+                item("minecraft:fishing_rod", 1),
+                item("minecraft:name_tag", 1),
+                item("minecraft:nautilus_shell", 1),
+                item("minecraft:saddle", 1),
+            ],
+            rolls: LootNumberProviderTypes::Constant(1.0),
+            bonus_rolls: 0.0,
+            conditions: None,
+            functions: None,
+        }],
+    );
+
+    /// The weights of the fish, junk and treasure tables. Each point of luck makes fish and
+    /// junk rarer and treasure more common, like the `quality` of the vanilla entries.
+    #[must_use]
+    pub fn weights(luck: f32, open_water: bool) -> [i32; 3] {
+        let weight = |base: i32, quality: i32| {
+            ((quality as f32).mul_add(luck, base as f32).floor() as i32).max(0)
+        };
+        [
+            weight(85, -1),
+            weight(10, -2),
+            if open_water { weight(5, 2) } else { 0 },
+        ]
+    }
+
+    /// Picks the table to roll for a catch.
+    #[must_use]
+    pub fn pick_table(luck: f32, open_water: bool) -> &'static LootTable {
+        let weights = weights(luck, open_water);
+        let total: i32 = weights.iter().sum();
+        if total <= 0 {
+            return &FISH;
+        }
+        let mut roll = rand::rng().random_range(0..total);
+        for (table, weight) in [&FISH, &JUNK, &TREASURE].into_iter().zip(weights) {
+            roll -= weight;
+            if roll < 0 {
+                return table;
+            }
+        }
+        &FISH
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn treasure_needs_open_water() {
+            assert_eq!(weights(0.0, true), [85, 10, 5]);
+            assert_eq!(weights(0.0, false), [85, 10, 0]);
+        }
+
+        #[test]
+        fn luck_favours_treasure() {
+            assert_eq!(weights(3.0, true), [82, 4, 11]);
+            assert_eq!(weights(6.0, true)[1], 0);
+        }
+    }
+}
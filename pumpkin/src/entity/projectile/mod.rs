@@ -9,10 +9,14 @@ use std::{
     sync::Arc,
     sync::atomic::{AtomicBool, Ordering},
 };
+pub mod dragon_fireball;
 pub mod egg;
 pub mod firework_rocket;
+pub mod fishing_bobber;
+pub mod potion;
 pub mod snowball;
 pub mod wind_charge;
+pub mod wither_skull;
 
 #[must_use]
 pub fn is_projectile(entity_type: &EntityType) -> bool {
@@ -20,6 +24,11 @@ pub fn is_projectile(entity_type: &EntityType) -> bool {
         || *entity_type == EntityType::SNOWBALL
         || *entity_type == EntityType::FIREWORK_ROCKET
         || *entity_type == EntityType::WIND_CHARGE
+        || *entity_type == EntityType::FISHING_BOBBER
+        || *entity_type == EntityType::SPLASH_POTION
+        || *entity_type == EntityType::LINGERING_POTION
+        || *entity_type == EntityType::WITHER_SKULL
+        || *entity_type == EntityType::DRAGON_FIREBALL
 }
 
 pub struct ThrownItemEntity {
@@ -96,7 +105,6 @@ impl ThrownItemEntity {
     /// Process a tick for projectile movement and collisions
     pub async fn process_tick(&self, caller: Arc<dyn EntityBase>, _server: &Server) {
         let entity = self.get_entity();
-        let world = entity.world.load();
 
         // Apply gravity and inertia
         let mut velocity = entity.velocity.load();
@@ -112,6 +120,17 @@ impl ThrownItemEntity {
         // Store velocity
         entity.velocity.store(velocity);
 
+        self.move_and_collide(caller).await;
+    }
+
+    /// Moves the projectile by its velocity, and hits the first block or entity in its way.
+    /// Projectiles that move differently from thrown items, like wither skulls, update their
+    /// velocity themselves and call this instead of [`Self::process_tick`].
+    pub async fn move_and_collide(&self, caller: Arc<dyn EntityBase>) {
+        let entity = self.get_entity();
+        let world = entity.world.load();
+        let velocity = entity.velocity.load();
+
         let start_pos = entity.pos.load();
         let delta = velocity;
 
@@ -167,7 +186,13 @@ impl ThrownItemEntity {
         }
 
         // Entity collisions
-        let candidates = world.get_entities_at_box(&search_box);
+        let mut candidates = world.get_entities_at_box(&search_box);
+        candidates.extend(
+            world
+                .get_players_at_box(&search_box)
+                .into_iter()
+                .map(|player| player as Arc<dyn EntityBase>),
+        );
         for cand in candidates {
             if self.should_skip_collision(entity, &cand) {
                 continue;
@@ -203,12 +228,18 @@ impl ThrownItemEntity {
     /// Returns if collision should be skipped (e.g. owner or projectile vs projectile)
     fn should_skip_collision(&self, self_ent: &Entity, other: &Arc<dyn EntityBase>) -> bool {
         let other_ent = other.get_entity();
-        if other_ent.entity_id == self_ent.entity_id {
+        if other_ent.entity_id == self_ent.entity_id || other.is_spectator() {
             return true;
         }
 
-        // Skip owner for initial frames
-        if Some(other_ent.entity_id) == self.owner_id && self_ent.age.load(Ordering::Relaxed) < 5 {
+        // Skip the owner for the initial frames, and as long as it is inside a big owner
+        if Some(other_ent.entity_id) == self.owner_id
+            && (self_ent.age.load(Ordering::Relaxed) < 5
+                || other_ent
+                    .bounding_box
+                    .load()
+                    .intersects(&self_ent.bounding_box.load()))
+        {
             return true;
         }
 
@@ -0,0 +1,275 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+
+use pumpkin_data::data_component_impl::PotionContentsImpl;
+use pumpkin_data::effect::StatusEffect;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::item::Item;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::potion::Effect;
+use pumpkin_data::tracked_data::TrackedData;
+use pumpkin_data::world::WorldEvent;
+use pumpkin_protocol::codec::item_stack_seralizer::ItemStackSerializer;
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::item::ItemStack;
+use tokio::sync::Mutex;
+
+use crate::entity::area_effect_cloud::AreaEffectCloudEntity;
+use crate::entity::effect::{apply_effect, potion_color, potion_effects};
+use crate::entity::projectile::{ProjectileHit, ThrownItemEntity};
+use crate::entity::{Entity, EntityBase, EntityBaseFuture, NBTStorage};
+use crate::server::Server;
+
+/// How far from where a splash potion breaks entities are affected.
+const SPLASH_RANGE: f64 = 4.0;
+/// Lingering potions leave clouds whose effects last a quarter as long.
+const LINGERING_DURATION_SCALE: f32 = 0.25;
+
+/// A thrown splash or lingering potion. Splash potions give their effects to the entities close
+/// to where they break, lingering potions leave an area effect cloud there.
+pub struct ThrownPotionEntity {
+    pub thrown: ThrownItemEntity,
+    item_stack: Mutex<ItemStack>,
+}
+
+impl ThrownPotionEntity {
+    pub fn new(entity: Entity) -> Self {
+        let item = if entity.entity_type == &EntityType::LINGERING_POTION {
+            &Item::LINGERING_POTION
+        } else {
+            &Item::SPLASH_POTION
+        };
+        let thrown = ThrownItemEntity {
+            entity,
+            owner_id: None,
+            collides_with_projectiles: false,
+            has_hit: AtomicBool::new(false),
+        };
+        Self {
+            thrown,
+            item_stack: Mutex::new(ItemStack::new(1, item)),
+        }
+    }
+
+    pub fn new_shot(entity: Entity, shooter: &Entity, item_stack: ItemStack) -> Self {
+        Self {
+            thrown: ThrownItemEntity::new(entity, shooter),
+            item_stack: Mutex::new(item_stack),
+        }
+    }
+
+    async fn contents(&self) -> PotionContentsImpl {
+        self.item_stack
+            .lock()
+            .await
+            .get_data_component::<PotionContentsImpl>()
+            .cloned()
+            .unwrap_or(PotionContentsImpl {
+                potion_id: None,
+                custom_color: None,
+                custom_effects: Vec::new(),
+                custom_name: None,
+            })
+    }
+
+    /// Gives the effects to the living entities within [`SPLASH_RANGE`], the closer the
+    /// stronger. The entity hit directly gets them at full strength.
+    async fn splash(
+        &self,
+        contents: &PotionContentsImpl,
+        position: Vector3<f64>,
+        hit: Option<&Arc<dyn EntityBase>>,
+        owner: Option<&dyn EntityBase>,
+    ) {
+        let effects = potion_effects(contents);
+        if effects.is_empty() {
+            return;
+        }
+        let world = self.thrown.entity.world.load_full();
+        let area = self.thrown.entity.bounding_box.load().expand(
+            SPLASH_RANGE,
+            SPLASH_RANGE / 2.0,
+            SPLASH_RANGE,
+        );
+        let mut targets = world.get_entities_at_box(&area);
+        targets.extend(
+            world
+                .get_players_at_box(&area)
+                .into_iter()
+                .map(|player| player as Arc<dyn EntityBase>),
+        );
+
+        for target in targets {
+            let Some(living) = target.get_living_entity() else {
+                continue;
+            };
+            if living.dead.load(Relaxed) || target.is_spectator() {
+                continue;
+            }
+            let distance_squared = living.entity.pos.load().squared_distance_to_vec(&position);
+            if distance_squared >= SPLASH_RANGE * SPLASH_RANGE {
+                continue;
+            }
+            let strength =
+                if hit.is_some_and(|hit| hit.get_entity().entity_id == living.entity.entity_id) {
+                    1.0
+                } else {
+                    1.0 - distance_squared.sqrt() / SPLASH_RANGE
+                };
+            for effect in effects
+                .iter()
+                .filter_map(|effect| Self::splash_effect(effect, strength))
+            {
+                apply_effect(
+                    target.as_ref(),
+                    effect,
+                    strength,
+                    Some(self as &dyn EntityBase),
+                    owner,
+                )
+                .await;
+            }
+        }
+    }
+
+    /// `effect` at `strength`, which shortens it. Instant effects are weakened instead, by
+    /// [`apply_effect`], and effects that would end within a second are left out.
+    fn splash_effect(effect: &Effect, strength: f64) -> Option<Effect> {
+        let mut effect = effect.clone();
+        let instant = effect.effect_type == &StatusEffect::INSTANT_HEALTH
+            || effect.effect_type == &StatusEffect::INSTANT_DAMAGE;
+        if !instant && effect.duration != -1 {
+            effect.duration = (strength * f64::from(effect.duration) + 0.5) as i32;
+            if effect.duration <= 20 {
+                return None;
+            }
+        }
+        Some(effect)
+    }
+
+    /// Leaves a cloud that shrinks away over 30 seconds, and whenever it affects an entity.
+    async fn linger(
+        &self,
+        contents: PotionContentsImpl,
+        position: Vector3<f64>,
+        owner: Option<&dyn EntityBase>,
+    ) {
+        let world = self.thrown.entity.world.load_full();
+        let entity = Entity::new(world.clone(), position, &EntityType::AREA_EFFECT_CLOUD);
+        let cloud =
+            AreaEffectCloudEntity::new(entity).with_potion(contents, LINGERING_DURATION_SCALE);
+        cloud
+            .owner
+            .store(owner.map(|owner| owner.get_entity().entity_uuid));
+        cloud.radius_on_use.store(-0.5);
+        cloud.duration.store(600, Relaxed);
+        cloud.wait_time.store(10, Relaxed);
+        cloud.radius_per_tick.store(-cloud.radius() / 600.0);
+        world.spawn_entity(Arc::new(cloud)).await;
+    }
+}
+
+impl NBTStorage for ThrownPotionEntity {}
+
+impl EntityBase for ThrownPotionEntity {
+    fn tick<'a>(
+        &'a self,
+        caller: Arc<dyn EntityBase>,
+        server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move { self.thrown.process_tick(caller, server).await })
+    }
+
+    fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            let stack = self.item_stack.lock().await.clone();
+            self.get_entity()
+                .send_meta_data(&[Metadata::new(
+                    TrackedData::DATA_ITEM,
+                    MetaDataType::ItemStack,
+                    &ItemStackSerializer::from(stack),
+                )])
+                .await;
+        })
+    }
+
+    fn get_entity(&self) -> &Entity {
+        &self.thrown.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&crate::entity::living::LivingEntity> {
+        None
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+
+    fn on_hit(&self, hit: ProjectileHit) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            let entity = self.get_entity();
+            let world = entity.world.load_full();
+            let position = hit.hit_pos();
+            let contents = self.contents().await;
+            let owner = self
+                .thrown
+                .owner_id
+                .and_then(|id| world.get_entity_by_id(id));
+
+            let instant = potion_effects(&contents).iter().any(|effect| {
+                effect.effect_type == &StatusEffect::INSTANT_HEALTH
+                    || effect.effect_type == &StatusEffect::INSTANT_DAMAGE
+            });
+            let world_event = if instant {
+                WorldEvent::InstantSplashPotionSplashed
+            } else {
+                WorldEvent::SplashPotionSplashed
+            };
+            world
+                .sync_world_event(
+                    world_event,
+                    BlockPos::floored_v(position),
+                    potion_color(&contents),
+                )
+                .await;
+
+            if entity.entity_type == &EntityType::LINGERING_POTION {
+                self.linger(contents, position, owner.as_deref()).await;
+            } else {
+                let hit_entity = match &hit {
+                    ProjectileHit::Entity { entity, .. } => Some(entity),
+                    ProjectileHit::Block { .. } => None,
+                };
+                self.splash(&contents, position, hit_entity, owner.as_deref())
+                    .await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_data::potion::Potion;
+
+    use super::*;
+
+    #[test]
+    fn splash_shortens_effects_further_away() {
+        let swiftness = &Potion::SWIFTNESS.effects[0];
+        let full = ThrownPotionEntity::splash_effect(swiftness, 1.0).unwrap();
+        assert_eq!(full.duration, 3600);
+        let half = ThrownPotionEntity::splash_effect(swiftness, 0.5).unwrap();
+        assert_eq!(half.duration, 1800);
+        assert!(ThrownPotionEntity::splash_effect(swiftness, 0.005).is_none());
+    }
+
+    #[test]
+    fn splash_keeps_instant_effects() {
+        let healing = &Potion::HEALING.effects[0];
+        let effect = ThrownPotionEntity::splash_effect(healing, 0.1).unwrap();
+        assert_eq!(effect.duration, healing.duration);
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+
+use pumpkin_data::damage::DamageType;
+use pumpkin_data::effect::StatusEffect;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::potion::Effect;
+use pumpkin_data::tracked_data::TrackedData;
+use pumpkin_protocol::java::client::play::Metadata;
+use pumpkin_util::Difficulty;
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::entity::effect::apply_effect;
+use crate::entity::projectile::{ProjectileHit, ThrownItemEntity};
+use crate::entity::{Entity, EntityBase, EntityBaseFuture, NBTStorage};
+use crate::server::Server;
+
+/// How much faster skulls get every tick, in the direction they fly.
+const ACCELERATION: f64 = 0.1;
+const EXPLOSION_POWER: f32 = 1.0;
+/// How much health the wither gets back when one of its skulls kills something.
+const KILL_HEAL: f32 = 5.0;
+
+/// A skull shot by the wither. It flies straight without falling, speeding up, and explodes
+/// where it hits. The blue, dangerous skulls are slower.
+pub struct WitherSkullEntity {
+    pub thrown: ThrownItemEntity,
+    dangerous: AtomicBool,
+}
+
+impl WitherSkullEntity {
+    pub const fn new(entity: Entity) -> Self {
+        Self {
+            thrown: ThrownItemEntity {
+                entity,
+                owner_id: None,
+                collides_with_projectiles: false,
+                has_hit: AtomicBool::new(false),
+            },
+            dangerous: AtomicBool::new(false),
+        }
+    }
+
+    /// A skull shot by `owner` in `direction`, from where the entity is.
+    pub fn new_shot(
+        entity: Entity,
+        owner: &Entity,
+        direction: Vector3<f64>,
+        dangerous: bool,
+    ) -> Self {
+        let velocity = direction.normalize();
+        entity
+            .velocity
+            .store(velocity.multiply(ACCELERATION, ACCELERATION, ACCELERATION));
+        let mut skull = Self::new(entity);
+        skull.thrown.owner_id = Some(owner.entity_id);
+        *skull.dangerous.get_mut() = dangerous;
+        skull
+    }
+
+    pub fn is_dangerous(&self) -> bool {
+        self.dangerous.load(Relaxed)
+    }
+
+    pub async fn set_dangerous(&self, dangerous: bool) {
+        self.dangerous.store(dangerous, Relaxed);
+        self.thrown
+            .entity
+            .send_meta_data(&[Metadata::new(
+                TrackedData::DATA_CHARGED,
+                MetaDataType::Boolean,
+                dangerous,
+            )])
+            .await;
+    }
+
+    /// Hurts the entity hit, and withers it on Normal and Hard difficulty.
+    async fn hit_entity(&self, target: &dyn EntityBase, owner: Option<&dyn EntityBase>) {
+        let world = self.thrown.entity.world.load_full();
+        // Skulls without a living owner only do magic damage
+        let owner = owner.filter(|owner| owner.get_living_entity().is_some());
+        let (amount, damage_type, source) = if owner.is_some() {
+            (8.0, DamageType::WITHER_SKULL, Some(self as &dyn EntityBase))
+        } else {
+            (5.0, DamageType::MAGIC, None)
+        };
+        let hurt = target
+            .damage_with_context(target, amount, damage_type, None, source, owner)
+            .await;
+        let killed = target
+            .get_living_entity()
+            .is_some_and(|living| living.dead.load(Relaxed));
+        if hurt
+            && killed
+            && let Some(living) = owner.and_then(|owner| owner.get_living_entity())
+        {
+            living.heal(KILL_HEAL).await;
+        }
+
+        let seconds = match world.get_difficulty() {
+            Difficulty::Normal => 10,
+            Difficulty::Hard => 40,
+            _ => return,
+        };
+        if hurt && target.get_living_entity().is_some() {
+            let wither = Effect {
+                effect_type: &StatusEffect::WITHER,
+                duration: 20 * seconds,
+                amplifier: 1,
+                ambient: false,
+                show_particles: true,
+                show_icon: true,
+                blend: false,
+            };
+            apply_effect(target, wither, 1.0, Some(self as &dyn EntityBase), owner).await;
+        }
+    }
+}
+
+impl NBTStorage for WitherSkullEntity {}
+
+impl EntityBase for WitherSkullEntity {
+    fn tick<'a>(
+        &'a self,
+        caller: Arc<dyn EntityBase>,
+        _server: &'a Server,
+    ) -> EntityBaseFuture<'a, ()> {
+        Box::pin(async move {
+            let entity = &self.thrown.entity;
+            let inertia = if entity.touching_water.load(Relaxed) {
+                0.8
+            } else if self.is_dangerous() {
+                0.73
+            } else {
+                0.95
+            };
+            let velocity = entity.velocity.load();
+            if velocity.length_squared() > 0.0 {
+                let velocity = velocity
+                    .add(
+                        &velocity
+                            .normalize()
+                            .multiply(ACCELERATION, ACCELERATION, ACCELERATION),
+                    )
+                    .multiply(inertia, inertia, inertia);
+                entity.velocity.store(velocity);
+            }
+            self.thrown.move_and_collide(caller).await;
+        })
+    }
+
+    fn init_data_tracker(&self) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            self.thrown
+                .entity
+                .send_meta_data(&[Metadata::new(
+                    TrackedData::DATA_CHARGED,
+                    MetaDataType::Boolean,
+                    self.is_dangerous(),
+                )])
+                .await;
+        })
+    }
+
+    fn get_entity(&self) -> &Entity {
+        &self.thrown.entity
+    }
+
+    fn get_living_entity(&self) -> Option<&crate::entity::living::LivingEntity> {
+        None
+    }
+
+    fn as_nbt_storage(&self) -> &dyn NBTStorage {
+        self
+    }
+
+    fn on_hit(&self, hit: ProjectileHit) -> EntityBaseFuture<'_, ()> {
+        Box::pin(async move {
+            let world = self.thrown.entity.world.load_full();
+            let owner = self
+                .thrown
+                .owner_id
+                .and_then(|id| world.get_entity_by_id(id));
+            if let ProjectileHit::Entity { entity, .. } = &hit {
+                self.hit_entity(entity.as_ref(), owner.as_deref()).await;
+            }
+            world
+                .explode_with_source(
+                    hit.hit_pos(),
+                    EXPLOSION_POWER,
+                    Some(self as &dyn EntityBase),
+                    owner.as_deref(),
+                )
+                .await;
+        })
+    }
+}
@@ -0,0 +1,76 @@
+//! The sounds entities make on their own. Vanilla names them `entity.<type>.<event>`, e.g.
+//! `entity.zombie.hurt`, which is how they are looked up here.
+
+use pumpkin_data::damage::DamageType;
+use pumpkin_data::entity::{EntityType, MobCategory};
+use pumpkin_data::sound::{Sound, SoundCategory};
+use rand::RngExt;
+
+/// How many ticks mobs wait at least between two ambient sounds.
+pub const AMBIENT_SOUND_INTERVAL: i32 = 80;
+
+/// The category of the sounds entities of `entity_type` make.
+#[must_use]
+pub fn sound_category(entity_type: &EntityType) -> SoundCategory {
+    if entity_type == &EntityType::PLAYER {
+        SoundCategory::Players
+    } else if entity_type.category == &MobCategory::MONSTER {
+        SoundCategory::Hostile
+    } else {
+        SoundCategory::Neutral
+    }
+}
+
+fn lookup(entity_type: &EntityType, event: &str) -> Option<Sound> {
+    Sound::from_name(&format!("entity.{}.{event}", entity_type.resource_name))
+}
+
+/// The sound a mob makes now and then while idle, if it makes any.
+#[must_use]
+pub fn ambient_sound(entity_type: &EntityType) -> Option<Sound> {
+    lookup(entity_type, "ambient")
+}
+
+/// The sound an entity makes when hurt by `damage_type`. Players make different sounds for
+/// burning, drowning, freezing and berry bushes.
+#[must_use]
+pub fn hurt_sound(entity_type: &EntityType, damage_type: DamageType) -> Sound {
+    let event = if entity_type != &EntityType::PLAYER {
+        "hurt"
+    } else if [
+        DamageType::IN_FIRE,
+        DamageType::ON_FIRE,
+        DamageType::CAMPFIRE,
+        DamageType::LAVA,
+        DamageType::HOT_FLOOR,
+        DamageType::FIREBALL,
+        DamageType::UNATTRIBUTED_FIREBALL,
+    ]
+    .contains(&damage_type)
+    {
+        "hurt_on_fire"
+    } else if damage_type == DamageType::DROWN {
+        "hurt_drown"
+    } else if damage_type == DamageType::FREEZE {
+        "hurt_freeze"
+    } else if damage_type == DamageType::SWEET_BERRY_BUSH {
+        "hurt_sweet_berry_bush"
+    } else {
+        "hurt"
+    };
+    lookup(entity_type, event).unwrap_or(Sound::EntityGenericHurt)
+}
+
+/// The sound an entity makes when it dies.
+#[must_use]
+pub fn death_sound(entity_type: &EntityType) -> Sound {
+    lookup(entity_type, "death").unwrap_or(Sound::EntityGenericDeath)
+}
+
+/// The slightly random pitch of the voice of an entity. Babies squeak higher.
+#[must_use]
+pub fn voice_pitch(baby: bool) -> f32 {
+    let mut rng = rand::rng();
+    let base = if baby { 1.5 } else { 1.0 };
+    (rng.random::<f32>() - rng.random::<f32>()).mul_add(0.2, base)
+}
@@ -7,9 +7,11 @@ use uuid::Uuid;
 use crate::{
     entity::{
         Entity, EntityBase,
-        boss::wither::WitherEntity,
+        area_effect_cloud::AreaEffectCloudEntity,
+        boss::{ender_dragon::EnderDragonEntity, wither::WitherEntity},
         decoration::{
-            armor_stand::ArmorStandEntity, end_crystal::EndCrystalEntity, painting::PaintingEntity,
+            armor_stand::ArmorStandEntity, end_crystal::EndCrystalEntity,
+            item_frame::ItemFrameEntity, leash_knot::LeashKnotEntity, painting::PaintingEntity,
         },
         living::LivingEntity,
         mob::{
@@ -41,6 +43,10 @@ use crate::{
             tropical_fish::TropicalFishEntity, turtle::TurtleEntity, wolf::WolfEntity,
             zombie_horse::ZombieHorseEntity,
         },
+        projectile::{
+            dragon_fireball::DragonFireballEntity, potion::ThrownPotionEntity,
+            wither_skull::WitherSkullEntity,
+        },
     },
     world::World,
 };
@@ -63,9 +69,22 @@ pub async fn from_type(
         id if id == EntityType::IRON_GOLEM.id => IronGolemEntity::new(entity).await,
         id if id == EntityType::WOLF.id => WolfEntity::new(entity).await,
         id if id == EntityType::WITHER.id => WitherEntity::new(entity).await,
+        id if id == EntityType::ENDER_DRAGON.id => EnderDragonEntity::new(entity),
         id if id == EntityType::ARMOR_STAND.id => Arc::new(ArmorStandEntity::new(entity)),
         id if id == EntityType::PAINTING.id => Arc::new(PaintingEntity::new(entity)),
+        id if id == EntityType::ITEM_FRAME.id || id == EntityType::GLOW_ITEM_FRAME.id => {
+            Arc::new(ItemFrameEntity::new(entity))
+        }
         id if id == EntityType::END_CRYSTAL.id => Arc::new(EndCrystalEntity::new(entity)),
+        id if id == EntityType::LEASH_KNOT.id => Arc::new(LeashKnotEntity::new(entity)),
+        id if id == EntityType::AREA_EFFECT_CLOUD.id => {
+            Arc::new(AreaEffectCloudEntity::new(entity))
+        }
+        id if id == EntityType::SPLASH_POTION.id || id == EntityType::LINGERING_POTION.id => {
+            Arc::new(ThrownPotionEntity::new(entity))
+        }
+        id if id == EntityType::WITHER_SKULL.id => Arc::new(WitherSkullEntity::new(entity)),
+        id if id == EntityType::DRAGON_FIREBALL.id => Arc::new(DragonFireballEntity::new(entity)),
         id if id == EntityType::SILVERFISH.id => SilverfishEntity::new(entity).await,
         id if id == EntityType::SPIDER.id => SpiderEntity::new(entity).await,
         id if id == EntityType::ENDERMAN.id => EndermanEntity::new(entity).await,
@@ -156,5 +175,182 @@ pub async fn from_type(
         }
     };
 
+    if let Some(server) = world.server.upgrade()
+        && let Some(mob) = mob.clone().get_mob()
+    {
+        server
+            .ai_providers
+            .get(entity_type, world.dimension.minecraft_name)
+            .init_goals(&mob)
+            .await;
+    }
+
     mob
 }
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_data::data_component_impl::EquipmentSlot;
+    use pumpkin_data::effect::StatusEffect;
+    use pumpkin_data::item::Item;
+    use pumpkin_data::potion::Effect;
+    use pumpkin_nbt::compound::NbtCompound;
+    use pumpkin_util::math::position::BlockPos;
+    use pumpkin_world::item::ItemStack;
+    use std::sync::atomic::Ordering::Relaxed;
+
+    use super::*;
+    use crate::entity::NBTStorage;
+    use crate::entity::leash::LeashHolder;
+    use crate::entity::mob::Mob;
+
+    /// A small deterministic generator, so failures can be reproduced.
+    struct SplitMix(u64);
+
+    impl SplitMix {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        fn float(&mut self) -> f32 {
+            (self.next() >> 40) as f32 / (1u64 << 24) as f32
+        }
+    }
+
+    /// Gives the entity random state, as it could have after living in a world for a while.
+    /// Mobs are leashed to another mob or to a leash knot.
+    async fn randomize(entity: &Arc<dyn EntityBase>, world: &Arc<World>, random: &mut SplitMix) {
+        let base = entity.get_entity();
+        base.set_pos(Vector3::new(
+            f64::from(random.float()) * 100.0,
+            64.0 + f64::from(random.float()),
+            f64::from(random.float()) * -100.0,
+        ));
+        base.velocity
+            .store(Vector3::new(f64::from(random.float()), 0.0, 0.5));
+        base.set_rotation(random.float() * 360.0 - 180.0, random.float() * 90.0);
+        base.fire_ticks.store((random.next() % 200) as i32, Relaxed);
+        base.frozen_ticks
+            .store((random.next() % 140) as i32, Relaxed);
+
+        if let Some(living) = entity.get_living_entity() {
+            living.health.store(1.0 + random.float() * 10.0);
+            living.movement_speed.store(0.1 + f64::from(random.float()));
+            // One effect only, the order of several is not kept
+            living
+                .add_effect(Effect {
+                    effect_type: &StatusEffect::SPEED,
+                    duration: (random.next() % 1000) as i32 + 1,
+                    amplifier: (random.next() % 4) as u8,
+                    ambient: false,
+                    show_particles: true,
+                    show_icon: true,
+                    blend: false,
+                })
+                .await;
+            living
+                .entity_equipment
+                .lock()
+                .await
+                .put(
+                    &EquipmentSlot::HEAD,
+                    ItemStack::new(1, &Item::DIAMOND_HELMET),
+                )
+                .await;
+        }
+
+        if let Some(mob) = entity.clone().get_mob() {
+            let mob_entity = mob.get_mob_entity();
+            base.age.store(-((random.next() % 24000) as i32), Relaxed);
+            mob_entity.set_drop_chance(&EquipmentSlot::HEAD, random.float());
+            mob_entity.set_persistent();
+            mob_entity.set_ai_disabled(random.next() % 2 == 0).await;
+            mob_entity.set_left_handed(random.next() % 2 == 0).await;
+
+            let (holder_type, holder_pos) = if random.next() % 2 == 0 {
+                (&EntityType::PIG, Vector3::new(3.0, 64.0, -2.0))
+            } else {
+                (&EntityType::LEASH_KNOT, Vector3::new(10.5, 64.375, -3.5))
+            };
+            let holder = from_type(holder_type, holder_pos, world, Uuid::new_v4()).await;
+            mob_entity.attach_leash(holder.as_ref()).await;
+        }
+    }
+
+    async fn leash_holder(entity: &Arc<dyn EntityBase>) -> Option<LeashHolder> {
+        let mob = entity.clone().get_mob()?;
+        mob.get_mob_entity().leash_holder().await
+    }
+
+    /// The keys of `a` and `b` with different values.
+    fn differences(a: &NbtCompound, b: &NbtCompound) -> Vec<String> {
+        let mut keys: Vec<_> = a
+            .child_tags
+            .iter()
+            .chain(&b.child_tags)
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys.retain(|key| a.get(key) != b.get(key));
+        keys
+    }
+
+    #[tokio::test]
+    async fn every_entity_type_survives_a_save_and_load() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+
+        let mut failures = Vec::new();
+        let (mut entity_holders, mut knot_holders) = (0, 0);
+        for entity_type in (0..=u16::MAX).map_while(EntityType::from_raw) {
+            if !entity_type.saveable || entity_type == &EntityType::PLAYER {
+                continue;
+            }
+            let mut random = SplitMix(u64::from(entity_type.id));
+            let entity = from_type(entity_type, Vector3::default(), &world, Uuid::new_v4()).await;
+            randomize(&entity, &world, &mut random).await;
+            let mut saved = NbtCompound::new();
+            entity.write_nbt(&mut saved).await;
+
+            let loaded = from_type(
+                entity_type,
+                Vector3::default(),
+                &world,
+                entity.get_entity().entity_uuid,
+            )
+            .await;
+            loaded.read_nbt_non_mut(&saved).await;
+            let mut resaved = NbtCompound::new();
+            loaded.write_nbt(&mut resaved).await;
+
+            let holder = leash_holder(&entity).await;
+            match holder {
+                Some(LeashHolder::Entity(_)) => entity_holders += 1,
+                Some(LeashHolder::Knot(pos)) => {
+                    assert_eq!(pos, BlockPos::new(10, 64, -4));
+                    knot_holders += 1;
+                }
+                None => {}
+            }
+            if leash_holder(&loaded).await != holder {
+                failures.push(format!("{}: leash", entity_type.resource_name));
+            }
+
+            let differences = differences(&saved, &resaved);
+            if !differences.is_empty() {
+                failures.push(format!(
+                    "{}: {}",
+                    entity_type.resource_name,
+                    differences.join(", ")
+                ));
+            }
+        }
+        assert!(failures.is_empty(), "changed on reload: {failures:#?}");
+        assert!(entity_holders > 0 && knot_holders > 0);
+    }
+}
@@ -1,5 +1,6 @@
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::atomic::Ordering::Relaxed;
 
 use crate::entity::Entity;
 use crate::entity::decoration::end_crystal::EndCrystalEntity;
@@ -9,6 +10,7 @@ use crate::server::Server;
 use pumpkin_data::entity::EntityType;
 use pumpkin_data::item::Item;
 use pumpkin_data::{Block, BlockDirection};
+use pumpkin_util::math::boundingbox::BoundingBox;
 use pumpkin_util::math::position::BlockPos;
 use pumpkin_util::math::vector3::Vector3;
 use pumpkin_world::item::ItemStack;
@@ -40,10 +42,25 @@ impl ItemBehaviour for EndCrystalItem {
             }
 
             let location = location.up();
-            let entity = Entity::new(world.clone(), location.to_f64(), &EntityType::END_CRYSTAL);
-            let end_crystal = Arc::new(EndCrystalEntity::new(entity));
-            world.spawn_entity(end_crystal.clone()).await;
-            end_crystal.set_show_bottom(false).await;
+            if !world.get_block_state(&location).await.is_air() {
+                return;
+            }
+            let position = location.to_f64();
+            let area = BoundingBox::new(position, position.add(&Vector3::new(1.0, 2.0, 1.0)));
+            if !world.get_entities_at_box(&area).is_empty()
+                || !world.get_players_at_box(&area).is_empty()
+            {
+                return;
+            }
+
+            let entity = Entity::new(
+                world.clone(),
+                position.add_raw(0.5, 0.0, 0.5),
+                &EntityType::END_CRYSTAL,
+            );
+            let end_crystal = EndCrystalEntity::new(entity);
+            end_crystal.show_bottom.store(false, Relaxed);
+            world.spawn_entity(Arc::new(end_crystal)).await;
             item.decrement_unless_creative(player.gamemode.load(), 1);
         })
     }
@@ -0,0 +1,97 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::entity::EntityBase;
+use crate::entity::player::Player;
+use crate::entity::projectile::fishing_bobber::FishingBobberEntity;
+use crate::item::{ItemBehaviour, ItemMetadata};
+use pumpkin_data::item::Item;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_inventory::player::player_inventory::PlayerInventory;
+use pumpkin_util::GameMode;
+
+pub struct FishingRodItem;
+
+impl ItemMetadata for FishingRodItem {
+    fn ids() -> Box<[u16]> {
+        [Item::FISHING_ROD.id].into()
+    }
+}
+
+impl ItemBehaviour for FishingRodItem {
+    fn normal_use<'a>(
+        &'a self,
+        _item: &'a Item,
+        player: &'a Player,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let inventory = player.inventory();
+            let main_hand = inventory.held_item();
+            let (rod_stack, slot) = if main_hand.lock().await.item.id == Item::FISHING_ROD.id {
+                (main_hand, inventory.get_selected_slot() as usize)
+            } else {
+                (
+                    inventory.off_hand_item().await,
+                    PlayerInventory::OFF_HAND_SLOT,
+                )
+            };
+            let rod = rod_stack.lock().await.clone();
+
+            let world = player.world();
+            let position = player.position();
+            let pitch = 0.4 / rand::random::<f32>().mul_add(0.4, 0.8);
+            // The bobber may already be gone, e.g. if the player changed worlds
+            let bobber = player
+                .fishing_bobber
+                .lock()
+                .await
+                .take()
+                .filter(|bobber| !bobber.get_entity().is_removed());
+            if let Some(bobber) = bobber {
+                let damage = bobber.retrieve(player, &rod).await;
+                if damage > 0
+                    && !matches!(
+                        player.gamemode.load(),
+                        GameMode::Creative | GameMode::Spectator
+                    )
+                {
+                    let updated = {
+                        let mut stack = rod_stack.lock().await;
+                        stack
+                            .damage_item_with_context(damage, false)
+                            .then(|| stack.clone())
+                    };
+                    if let Some(updated) = updated {
+                        player.sync_hand_slot(slot, updated).await;
+                    }
+                }
+                world
+                    .play_sound_fine(
+                        Sound::EntityFishingBobberRetrieve,
+                        SoundCategory::Neutral,
+                        &position,
+                        1.0,
+                        pitch,
+                    )
+                    .await;
+            } else {
+                world
+                    .play_sound_fine(
+                        Sound::EntityFishingBobberThrow,
+                        SoundCategory::Neutral,
+                        &position,
+                        0.5,
+                        pitch,
+                    )
+                    .await;
+                let bobber = Arc::new(FishingBobberEntity::new(player, world.clone(), &rod));
+                *player.fishing_bobber.lock().await = Some(bobber.clone());
+                world.spawn_entity(bobber).await;
+            }
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,86 @@
+use std::pin::Pin;
+
+use crate::entity::player::Player;
+use crate::item::{ItemBehaviour, ItemMetadata};
+use pumpkin_data::item::Item;
+use pumpkin_data::particle::Particle;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_world::item::ItemStack;
+
+/// The empty glass bottle, which collects the breath of the ender dragon from its clouds.
+pub struct GlassBottleItem;
+
+impl ItemMetadata for GlassBottleItem {
+    fn ids() -> Box<[u16]> {
+        [Item::GLASS_BOTTLE.id].into()
+    }
+}
+
+/// How far around the player clouds are reached.
+const REACH: f64 = 2.0;
+
+impl ItemBehaviour for GlassBottleItem {
+    fn normal_use<'a>(
+        &'a self,
+        _item: &'a Item,
+        player: &'a Player,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let world = player.world();
+            let area = player
+                .living_entity
+                .entity
+                .bounding_box
+                .load()
+                .expand(REACH, REACH, REACH);
+            let mut breath = None;
+            for entity in world.get_entities_at_box(&area) {
+                if let Some(cloud) = entity.get_area_effect_cloud()
+                    && !entity.get_entity().is_removed()
+                    && cloud.particle().await.particle() == Particle::DragonBreath
+                {
+                    breath = Some(entity);
+                    break;
+                }
+            }
+            let Some(entity) = breath else {
+                return;
+            };
+
+            let inventory = player.inventory();
+            let mut hand = inventory.held_item();
+            if hand.lock().await.item != &Item::GLASS_BOTTLE {
+                hand = inventory.off_hand_item().await;
+            }
+            let mut bottle = hand.lock().await;
+            if bottle.item != &Item::GLASS_BOTTLE {
+                return;
+            }
+
+            if let Some(cloud) = entity.get_area_effect_cloud() {
+                cloud.set_radius(cloud.radius() - 0.5).await;
+            }
+            world
+                .play_sound(
+                    Sound::ItemBottleFillDragonbreath,
+                    SoundCategory::Neutral,
+                    &player.position(),
+                )
+                .await;
+
+            let dragon_breath = ItemStack::new(1, &Item::DRAGON_BREATH);
+            if bottle.item_count == 1 && !player.is_creative() {
+                *bottle = dragon_breath;
+                drop(bottle);
+            } else {
+                bottle.decrement_unless_creative(player.gamemode.load(), 1);
+                drop(bottle);
+                inventory.offer_or_drop_stack(dragon_breath, player).await;
+            }
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,53 @@
+use std::pin::Pin;
+
+use crate::entity::decoration::item_frame;
+use crate::entity::player::Player;
+use crate::item::{ItemBehaviour, ItemMetadata};
+use crate::server::Server;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::item::Item;
+use pumpkin_data::{Block, BlockDirection};
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::item::ItemStack;
+
+pub struct ItemFrameItem;
+
+impl ItemMetadata for ItemFrameItem {
+    fn ids() -> Box<[u16]> {
+        [Item::ITEM_FRAME.id, Item::GLOW_ITEM_FRAME.id].into()
+    }
+}
+
+impl ItemBehaviour for ItemFrameItem {
+    fn use_on_block<'a>(
+        &'a self,
+        item: &'a mut ItemStack,
+        player: &'a Player,
+        location: BlockPos,
+        face: BlockDirection,
+        _cursor_pos: Vector3<f32>,
+        _block: &'a Block,
+        _server: &'a Server,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let world = player.world();
+            let pos = location.offset(face.to_offset());
+            if !world.get_block_state(&pos).await.is_air() {
+                return;
+            }
+
+            let entity_type = if item.item == &Item::GLOW_ITEM_FRAME {
+                &EntityType::GLOW_ITEM_FRAME
+            } else {
+                &EntityType::ITEM_FRAME
+            };
+            item_frame::place(player, entity_type, location, face).await;
+            item.decrement_unless_creative(player.gamemode.load(), 1);
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
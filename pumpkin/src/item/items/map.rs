@@ -0,0 +1,137 @@
+use std::pin::Pin;
+
+use crate::entity::player::Player;
+use crate::item::{ItemBehaviour, ItemMetadata};
+use crate::server::Server;
+use pumpkin_data::data_component::DataComponent;
+use pumpkin_data::data_component_impl::{DataComponentImpl, MapIdImpl};
+use pumpkin_data::item::Item;
+use pumpkin_data::sound::{Sound, SoundCategory};
+use pumpkin_data::{Block, BlockDirection};
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::item::ItemStack;
+use pumpkin_world::map::color::split_dye;
+use pumpkin_world::map::{MapBanner, MapState};
+
+/// The empty map, which becomes a filled map of the area around the player when used.
+pub struct EmptyMapItem;
+
+impl ItemMetadata for EmptyMapItem {
+    fn ids() -> Box<[u16]> {
+        [Item::MAP.id].into()
+    }
+}
+
+impl ItemBehaviour for EmptyMapItem {
+    fn normal_use<'a>(
+        &'a self,
+        _item: &'a Item,
+        player: &'a Player,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let world = player.world();
+            let Some(server) = world.server.upgrade() else {
+                return;
+            };
+            let inventory = player.inventory();
+            let mut hand = inventory.held_item();
+            if hand.lock().await.item != &Item::MAP {
+                hand = inventory.off_hand_item().await;
+            }
+            let mut empty_map = hand.lock().await;
+            if empty_map.item != &Item::MAP {
+                return;
+            }
+
+            let position = player.position();
+            let state = MapState::new(
+                position.x,
+                position.z,
+                0,
+                true,
+                false,
+                world.dimension.minecraft_name.to_string(),
+            );
+            let id = server.maps.create(state).await;
+            let filled_map = ItemStack::new_with_component(
+                1,
+                &Item::FILLED_MAP,
+                vec![(DataComponent::MapId, Some(MapIdImpl { id }.to_dyn()))],
+            );
+
+            let creative = player.is_creative();
+            if empty_map.item_count == 1 && !creative {
+                *empty_map = filled_map;
+                drop(empty_map);
+            } else {
+                empty_map.decrement_unless_creative(player.gamemode.load(), 1);
+                drop(empty_map);
+                inventory.offer_or_drop_stack(filled_map, player).await;
+            }
+
+            world
+                .play_sound(
+                    Sound::UiCartographyTableTakeResult,
+                    SoundCategory::Players,
+                    &position,
+                )
+                .await;
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// The filled map, which marks banners it is used on.
+pub struct FilledMapItem;
+
+impl ItemMetadata for FilledMapItem {
+    fn ids() -> Box<[u16]> {
+        [Item::FILLED_MAP.id].into()
+    }
+}
+
+impl ItemBehaviour for FilledMapItem {
+    fn use_on_block<'a>(
+        &'a self,
+        item: &'a mut ItemStack,
+        player: &'a Player,
+        location: BlockPos,
+        _face: BlockDirection,
+        _cursor_pos: Vector3<f32>,
+        block: &'a Block,
+        server: &'a Server,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let Some((color, rest)) = split_dye(block.name) else {
+                return;
+            };
+            if rest != "banner" && rest != "wall_banner" {
+                return;
+            }
+            let Some(map_id) = item.get_data_component::<MapIdImpl>() else {
+                return;
+            };
+            let Some(map) = server.maps.get(map_id.id).await else {
+                return;
+            };
+
+            let mut state = map.lock().await;
+            if state.dimension != player.world().dimension.minecraft_name {
+                return;
+            }
+            state.toggle_banner(MapBanner {
+                pos: location,
+                color,
+                name: None,
+            });
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -8,14 +8,19 @@ pub mod egg;
 pub mod end_crystal;
 pub mod ender_eye;
 pub mod firework_rocket;
+pub mod fishing_rod;
+pub mod glass_bottle;
 pub mod glowing_ink_sac;
 pub mod hoe;
 pub mod honeycomb;
 pub mod ignite;
 pub mod ink_sac;
+pub mod item_frame;
 pub mod mace;
+pub mod map;
 pub mod minecart;
 pub mod name_tag;
+pub mod potion;
 pub mod shears;
 pub mod shovel;
 pub mod snowball;
@@ -29,6 +34,7 @@ use crate::item::items::boat::BoatItem;
 use crate::item::items::bone_meal::BoneMealItem;
 use crate::item::items::end_crystal::EndCrystalItem;
 use crate::item::items::firework_rocket::FireworkRocketItem;
+use crate::item::items::fishing_rod::FishingRodItem;
 use crate::item::items::minecart::MinecartItem;
 use crate::item::items::name_tag::NameTagItem;
 use crate::item::items::shears::ShearsItem;
@@ -41,13 +47,17 @@ use bucket::{EmptyBucketItem, FilledBucketItem};
 use dye::DyeItem;
 use egg::EggItem;
 use ender_eye::EnderEyeItem;
+use glass_bottle::GlassBottleItem;
 use glowing_ink_sac::GlowingInkSacItem;
 use hoe::HoeItem;
 use honeycomb::HoneyCombItem;
 use ignite::fire_charge::FireChargeItem;
 use ignite::flint_and_steel::FlintAndSteelItem;
 use ink_sac::InkSacItem;
+use item_frame::ItemFrameItem;
 use mace::MaceItem;
+use map::{EmptyMapItem, FilledMapItem};
+use potion::ThrowablePotionItem;
 use shovel::ShovelItem;
 use snowball::SnowBallItem;
 use std::sync::Arc;
@@ -85,6 +95,12 @@ pub fn default_registry() -> Arc<ItemRegistry> {
     manager.register(ShearsItem);
     manager.register(BoatItem);
     manager.register(BoneMealItem);
+    manager.register(EmptyMapItem);
+    manager.register(FilledMapItem);
+    manager.register(ItemFrameItem);
+    manager.register(FishingRodItem);
+    manager.register(ThrowablePotionItem);
+    manager.register(GlassBottleItem);
 
     Arc::new(manager)
 }
@@ -2,6 +2,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::entity::EntityBase;
+use crate::entity::mob::Mob;
 use crate::entity::player::Player;
 use crate::item::{ItemBehaviour, ItemMetadata};
 use pumpkin_data::data_component_impl::CustomNameImpl;
@@ -25,14 +26,19 @@ impl ItemBehaviour for NameTagItem {
         entity: Arc<dyn EntityBase>,
     ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
         Box::pin(async move {
-            let entity = entity.get_entity();
-            if entity.entity_type.saveable
-                && let Some(name) = item.get_data_component::<CustomNameImpl>()
-            {
-                // TODO
-                entity.set_custom_name(TextComponent::text(name.name)).await;
-                item.decrement_unless_creative(player.gamemode.load(), 1);
+            let Some(name) = item.get_data_component::<CustomNameImpl>() else {
+                return;
+            };
+            let base = entity.get_entity();
+            if !base.entity_type.saveable || entity.get_player().is_some() || base.is_removed() {
+                return;
             }
+            base.set_custom_name(TextComponent::text(name.name)).await;
+            // Named mobs never despawn
+            if let Some(mob) = entity.get_mob() {
+                mob.get_mob_entity().set_persistent();
+            }
+            item.decrement_unless_creative(player.gamemode.load(), 1);
         })
     }
     fn as_any(&self) -> &dyn std::any::Any {
@@ -0,0 +1,85 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::entity::Entity;
+use crate::entity::player::Player;
+use crate::entity::projectile::potion::ThrownPotionEntity;
+use crate::item::{ItemBehaviour, ItemMetadata};
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::item::Item;
+use pumpkin_data::sound::{Sound, SoundCategory};
+
+/// Splash and lingering potions, which are thrown.
+pub struct ThrowablePotionItem;
+
+impl ItemMetadata for ThrowablePotionItem {
+    fn ids() -> Box<[u16]> {
+        [Item::SPLASH_POTION.id, Item::LINGERING_POTION.id].into()
+    }
+}
+
+const POWER: f32 = 0.5;
+/// Potions are thrown a bit upwards.
+const ROLL: f32 = -20.0;
+
+impl ItemBehaviour for ThrowablePotionItem {
+    fn normal_use<'a>(
+        &'a self,
+        item: &'a Item,
+        player: &'a Player,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let inventory = player.inventory();
+            let mut hand = inventory.held_item();
+            if hand.lock().await.item != item {
+                hand = inventory.off_hand_item().await;
+            }
+            let thrown_stack = {
+                let mut stack = hand.lock().await;
+                if stack.item != item {
+                    return;
+                }
+                stack.split_unless_creative(player.gamemode.load(), 1)
+            };
+
+            let (sound, entity_type) = if item == &Item::LINGERING_POTION {
+                (
+                    Sound::EntityLingeringPotionThrow,
+                    &EntityType::LINGERING_POTION,
+                )
+            } else {
+                (Sound::EntitySplashPotionThrow, &EntityType::SPLASH_POTION)
+            };
+            let position = player.position();
+            let world = player.world();
+            world
+                .play_sound_fine(
+                    sound,
+                    SoundCategory::Players,
+                    &position,
+                    0.5,
+                    0.4 / rand::random::<f32>().mul_add(0.4, 0.8),
+                )
+                .await;
+
+            let entity = Entity::new(world.clone(), position, entity_type);
+            let potion =
+                ThrownPotionEntity::new_shot(entity, &player.living_entity.entity, thrown_stack);
+            let yaw = player.living_entity.entity.yaw.load();
+            let pitch = player.living_entity.entity.pitch.load();
+            potion.thrown.set_velocity_from(
+                &player.living_entity.entity,
+                pitch,
+                yaw,
+                ROLL,
+                POWER,
+                1.0,
+            );
+            world.spawn_entity(Arc::new(potion)).await;
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
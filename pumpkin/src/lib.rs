@@ -4,26 +4,31 @@
 use crate::data::VanillaData;
 use crate::logging::{GzipRollingLogger, PumpkinCommandCompleter, ReadlineLogWrapper};
 use crate::net::bedrock::BedrockClient;
+use crate::net::health::{self, LifecycleState};
 use crate::net::java::{JavaClient, PacketHandlerResult};
+use crate::net::listener::{self, JavaListener};
 use crate::net::{ClientPlatform, DisconnectReason};
-use crate::net::{lan_broadcast::LANBroadcast, query, rcon::RCONServer};
-use crate::server::{Server, ticker::Ticker};
+use crate::net::{lan_broadcast::LANBroadcast, mdns::MdnsAdvertiser, query, rcon::RCONServer};
+use crate::server::{
+    Server, backup, backup::BackupManager, chat_bridge::ChatBridge, redis::RedisConnector, restart,
+    ticker::Ticker, watchdog::Watchdog,
+};
 use log::LevelFilter;
 use plugin::server::server_command::ServerCommandEvent;
-use pumpkin_config::{AdvancedConfiguration, BasicConfiguration};
+use pumpkin_config::{AdvancedConfiguration, BasicConfiguration, IntegrationsConfiguration};
 use pumpkin_macros::send_cancellable;
 use pumpkin_util::text::TextComponent;
 use rustyline::Editor;
 use rustyline::history::FileHistory;
 use rustyline::{Config, error::ReadlineError};
 use std::collections::HashMap;
-use std::io::{Cursor, ErrorKind, IsTerminal, stdin};
+use std::io::{Cursor, IsTerminal, stdin};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use std::{net::SocketAddr, sync::LazyLock};
-use tokio::net::{TcpListener, UdpSocket};
+use tokio::net::UdpSocket;
 use tokio::select;
 use tokio::sync::Mutex;
 use tokio::time::sleep;
@@ -170,7 +175,7 @@ fn resolve_some<T: Future, D, F: FnOnce(D) -> T>(
 
 pub struct PumpkinServer {
     pub server: Arc<Server>,
-    pub tcp_listener: Option<TcpListener>,
+    pub tcp_listeners: Vec<JavaListener>,
     pub udp_socket: Option<Arc<UdpSocket>>,
 }
 
@@ -179,9 +184,17 @@ impl PumpkinServer {
     pub async fn new(
         basic_config: BasicConfiguration,
         advanced_config: AdvancedConfiguration,
+        integrations_config: IntegrationsConfiguration,
         vanilla_data: VanillaData,
     ) -> Self {
-        let server = Server::new(basic_config, advanced_config, vanilla_data).await;
+        backup::apply_pending_restore(&advanced_config.backup, &basic_config.get_world_path());
+        let server = Server::new(
+            basic_config,
+            advanced_config,
+            integrations_config,
+            vanilla_data,
+        )
+        .await;
 
         let rcon = server.advanced_config.networking.rcon.clone();
 
@@ -210,40 +223,22 @@ impl PumpkinServer {
             });
         }
 
-        let tcp_listener = if server.basic_config.java_edition {
-            let address = server.basic_config.java_edition_address;
-            // Setup the TCP server socket.
-            let listener = match TcpListener::bind(address).await {
-                Ok(l) => l,
-                Err(e) => match e.kind() {
-                    ErrorKind::AddrInUse => {
-                        log::error!("Error: Address {address} is already in use.");
-                        log::error!(
-                            "Make sure another instance of the server isn't already running"
-                        );
-                        std::process::exit(1);
-                    }
-                    ErrorKind::PermissionDenied => {
-                        log::error!("Error: Permission denied when binding to {address}.");
-                        log::error!("You might need sudo/admin privileges to use ports below 1024");
-                        std::process::exit(1);
-                    }
-                    ErrorKind::AddrNotAvailable => {
-                        log::error!(
-                            "Error: The address {address} is not available on this machine"
-                        );
-                        std::process::exit(1);
-                    }
-                    _ => {
-                        log::error!("Failed to start TcpListener on {address}: {e}");
-                        std::process::exit(1);
-                    }
-                },
-            };
+        let health_config = &server.advanced_config.networking.health;
+        if health_config.enabled {
+            // Not a server task: it must keep answering while the server shuts down.
+            tokio::spawn(health::start_health_endpoint(
+                server.clone(),
+                health_config.address,
+            ));
+        }
+
+        let tcp_listeners = if server.basic_config.java_edition {
+            let listeners = listener::bind_java_listeners(
+                &server.basic_config,
+                &server.advanced_config.networking,
+            );
             // In the event the user puts 0 for their port, this will allow us to know what port it is running on
-            let addr = listener
-                .local_addr()
-                .expect("Unable to get the address of the server!");
+            let addr = listeners[0].local_addr();
 
             if server.advanced_config.networking.query.enabled {
                 log::info!("Query protocol is enabled. Starting...");
@@ -263,11 +258,32 @@ impl PumpkinServer {
                 server.spawn_task(lan_broadcast.start(addr));
             }
 
-            Some(listener)
+            if server.advanced_config.networking.lan_broadcast.mdns {
+                let advertiser = MdnsAdvertiser::new(
+                    &server.advanced_config.networking.lan_broadcast,
+                    &server.basic_config,
+                    addr,
+                );
+                server.spawn_task(advertiser.start());
+            }
+
+            listeners
         } else {
-            None
+            Vec::new()
         };
 
+        if server.advanced_config.backup.enabled {
+            server.spawn_task(BackupManager::run_periodic(server.clone()));
+        }
+
+        if server.redis.is_enabled() {
+            server.spawn_task(RedisConnector::run(server.clone()));
+        }
+
+        if server.chat_bridge.is_enabled() {
+            server.spawn_task(ChatBridge::run(server.clone()));
+        }
+
         // Ticker
         {
             let ticker_server = server.clone();
@@ -275,6 +291,7 @@ impl PumpkinServer {
                 Ticker::run(&ticker_server).await;
             });
         };
+        Watchdog::spawn(&server);
 
         let udp_socket = if server.basic_config.bedrock_edition {
             Some(Arc::new(
@@ -288,7 +305,7 @@ impl PumpkinServer {
 
         Self {
             server,
-            tcp_listener,
+            tcp_listeners,
             udp_socket,
         }
     }
@@ -333,6 +350,8 @@ impl PumpkinServer {
         let mut master_client_id: u64 = 0;
         let bedrock_clients = Arc::new(Mutex::new(HashMap::new()));
 
+        health::set_lifecycle_state(&self.server, LifecycleState::Ready);
+
         while !SHOULD_STOP.load(Ordering::Relaxed) {
             if !self
                 .unified_listener_task(&mut master_client_id, &tasks, &bedrock_clients)
@@ -343,6 +362,7 @@ impl PumpkinServer {
         }
 
         log::info!("Stopped accepting incoming connections");
+        health::set_lifecycle_state(&self.server, LifecycleState::Stopping);
 
         // Notify plugins that the server is stopping
         self.server
@@ -360,8 +380,16 @@ impl PumpkinServer {
         {
             log::error!("Error saving all players during shutdown: {e}");
         }
+        self.server
+            .player_data_storage
+            .flush_all_players(&self.server)
+            .await;
 
-        let kick_message = TextComponent::text("Server stopped");
+        let kick_message = if restart::is_restart_requested() {
+            TextComponent::text(self.server.advanced_config.restart.kick_message.clone())
+        } else {
+            TextComponent::text("Server stopped")
+        };
         for player in self.server.get_all_players() {
             player
                 .kick(DisconnectReason::Shutdown, kick_message.clone())
@@ -399,9 +427,11 @@ impl PumpkinServer {
 
         select! {
             // Branch for TCP connections (Java Edition)
-            tcp_result = resolve_some(self.tcp_listener.as_ref(), tokio::net::TcpListener::accept) => {
+            (tcp_result, java_listener) = listener::accept_any(&self.tcp_listeners) => {
+                let proxy = java_listener.proxy;
+                let proxy_protocol = java_listener.proxy_protocol.clone();
                 match tcp_result {
-                    Ok((connection, client_addr)) => {
+                    Ok((mut connection, client_addr)) => {
                         if let Err(e) = connection.set_nodelay(true) {
                             log::warn!("Failed to set TCP_NODELAY: {e}");
                         }
@@ -418,7 +448,18 @@ impl PumpkinServer {
                         let server_clone = self.server.clone();
 
                         tasks.spawn(async move {
-                            let mut java_client = JavaClient::new(connection, client_addr, client_id);
+                            // Bans and logs must see the client behind a TCP load balancer
+                            let client_addr = match proxy_protocol {
+                                Some(proxy_protocol) => {
+                                    let Some(client_addr) = proxy_protocol.client_addr(&mut connection, client_addr).await else {
+                                        return;
+                                    };
+                                    client_addr
+                                }
+                                None => client_addr,
+                            };
+                            let mut java_client = JavaClient::new(connection, client_addr, client_id, proxy);
+                            java_client.capture_connection(&server_clone.advanced_config.networking.capture, client_addr);
                             java_client.start_outgoing_packet_task();
                             let login_result = java_client.handle_login_sequence(&server_clone).await;
 
@@ -435,6 +476,7 @@ impl PumpkinServer {
                                     world
                                         .spawn_java_player(&server_clone.basic_config, &player, &server_clone)
                                         .await;
+                                    server_clone.restart.show_to(&player).await;
                                     if let ClientPlatform::Java(client) = &player.client {
                                         client.progress_player_packets(&player, &server_clone).await;
                                         // Close when done
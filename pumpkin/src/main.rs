@@ -5,9 +5,12 @@
 #[cfg(target_os = "wasi")]
 compile_error!("Compiling for WASI targets is not supported!");
 
+use pumpkin_data::dimension::Dimension;
 use pumpkin_data::packet::CURRENT_MC_PROTOCOL;
 use std::{
+    collections::BTreeMap,
     io::{self},
+    path::Path,
     sync::{Arc, LazyLock, OnceLock},
 };
 #[cfg(not(unix))]
@@ -16,10 +19,17 @@ use tokio::signal::ctrl_c;
 use tokio::signal::unix::{SignalKind, signal};
 
 use pumpkin::data::VanillaData;
+use pumpkin::server::Server;
+use pumpkin::server::pregen::{PregenJob, PregenShape};
 use pumpkin::{LoggerOption, PumpkinServer, SHOULD_STOP, STOP_INTERRUPT, stop_server};
 
-use pumpkin_config::{AdvancedConfiguration, BasicConfiguration, LoadConfiguration};
+use pumpkin_config::chunk::ChunkConfig;
+use pumpkin_config::{
+    AdvancedConfiguration, BasicConfiguration, IntegrationsConfiguration, LoadConfiguration,
+};
+use pumpkin_protocol::java::capture::{self, CaptureDirection, CaptureError, CaptureReader};
 use pumpkin_util::text::{TextComponent, color::NamedColor};
+use pumpkin_world::verify::IssueKind;
 use std::time::Instant;
 
 // Setup some tokens to allow us to identify which event is for which socket.
@@ -55,6 +65,7 @@ async fn main() {
 
     let basic_config = BasicConfiguration::load(&config_dir);
     let advanced_config = AdvancedConfiguration::load(&config_dir);
+    let integrations_config = IntegrationsConfiguration::load(&config_dir);
 
     let vanilla_data = VanillaData::load();
 
@@ -67,6 +78,18 @@ async fn main() {
         // We need to abide by the panic rules here.
         std::process::exit(1);
     }));
+
+    if let Some(code) = run_cli_tool(&basic_config, &advanced_config).await {
+        std::process::exit(code);
+    }
+    let pregen_radius = match pregen_radius() {
+        Some(Ok(radius)) => Some(radius),
+        Some(Err(err)) => {
+            log::error!("{err}");
+            std::process::exit(2);
+        }
+        None => None,
+    };
     log::info!("Starting Pumpkin {CARGO_PKG_VERSION} Minecraft (Protocol {CURRENT_MC_PROTOCOL})",);
 
     log::debug!(
@@ -91,9 +114,23 @@ async fn main() {
             .expect("Unable to setup signal handlers");
     });
 
-    let pumpkin_server = PumpkinServer::new(basic_config, advanced_config, vanilla_data).await;
+    let restart_config = advanced_config.restart.clone();
+    let pumpkin_server = PumpkinServer::new(
+        basic_config,
+        advanced_config,
+        integrations_config,
+        vanilla_data,
+    )
+    .await;
+    if let Some(radius) = pregen_radius {
+        pumpkin_world::generation::load_data();
+        std::process::exit(pregen(&pumpkin_server.server, radius).await);
+    }
     pumpkin_server.init_plugins().await;
+    // Resolve the world generation data now instead of while generating the first chunk
+    pumpkin_world::generation::load_data();
     pumpkin_server.fire_started_event().await;
+    pumpkin_server.server.pregen.resume(&pumpkin_server.server);
 
     log::info!("Started server; took {}ms", time.elapsed().as_millis());
     let basic_config = &pumpkin_server.server.basic_config;
@@ -118,6 +155,196 @@ async fn main() {
 
     pumpkin_server.start().await;
     log::info!("The server has stopped.");
+    pumpkin::server::restart::finish_restart(&restart_config);
+}
+
+/// Runs a one-off tool selected by a command line flag instead of the server, returning the
+/// exit code.
+async fn run_cli_tool(
+    basic_config: &BasicConfiguration,
+    advanced_config: &AdvancedConfiguration,
+) -> Option<i32> {
+    let mut args = std::env::args().skip(1).peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--import" => {
+                let Some(source) = args.next() else {
+                    log::error!("--import needs the path of a Bukkit server folder");
+                    return Some(2);
+                };
+                log::info!("Importing the Bukkit server at {source}...");
+                return Some(
+                    match pumpkin::server::import::import_bukkit(Path::new(&source), basic_config) {
+                        Ok(report) => {
+                            log::info!("{}", report.summary());
+                            0
+                        }
+                        Err(err) => {
+                            log::error!("Import failed: {err}");
+                            1
+                        }
+                    },
+                );
+            }
+            "--verify-world" => {
+                // The report path is optional, a following flag is not one
+                let report_path = args.next_if(|next| !next.starts_with("--"));
+                return Some(verify_world(basic_config, report_path));
+            }
+            "--recompress" => return Some(recompress_world(basic_config, advanced_config).await),
+            "--replay-capture" => {
+                let Some(capture) = args.next() else {
+                    log::error!("--replay-capture needs the path of a packet capture");
+                    return Some(2);
+                };
+                return Some(replay_capture(Path::new(&capture)).await);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Checks every chunk of the world without modifying it and writes a JSON report. Fails if any
+/// chunk is corrupt or contains unknown block states.
+fn verify_world(basic_config: &BasicConfiguration, report_path: Option<String>) -> i32 {
+    let world = basic_config.get_world_path();
+    if !world.exists() {
+        log::error!("The world folder {} does not exist", world.display());
+        return 1;
+    }
+    log::info!("Verifying {}, this may take a while...", world.display());
+    let report = pumpkin_world::verify::verify_world(&world);
+    let report_path = report_path.unwrap_or_else(|| "world-verify-report.json".to_string());
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => {
+            if let Err(err) = std::fs::write(&report_path, json) {
+                log::error!("Failed to write the report to {report_path}: {err}");
+            }
+        }
+        Err(err) => log::error!("Failed to serialize the report: {err}"),
+    }
+
+    let corrupt = report.count(IssueKind::CorruptRegion) + report.count(IssueKind::CorruptChunk);
+    let unknown = report.count(IssueKind::UnknownBlockState);
+    log::info!(
+        "Checked {} chunks in {} region files: {corrupt} corrupt, {unknown} unknown block states, \
+        {} data version mismatches. Report written to {report_path}",
+        report.chunks,
+        report.region_files,
+        report.count(IssueKind::DataVersion)
+    );
+    i32::from(corrupt + unknown > 0)
+}
+
+/// The radius in blocks passed to `--pregen`, if the world should only be pregenerated.
+fn pregen_radius() -> Option<Result<i32, String>> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--pregen");
+    args.next()?;
+    Some(
+        args.next()
+            .and_then(|radius| radius.parse().ok())
+            .filter(|radius| *radius > 0)
+            .ok_or_else(|| "--pregen needs a radius in blocks".to_string()),
+    )
+}
+
+/// Generates the overworld within `radius` blocks of the spawn, or continues the pregeneration
+/// that was interrupted, and stops the server.
+async fn pregen(server: &Arc<Server>, radius: i32) -> i32 {
+    let job = server.pregen.load_checkpoint().unwrap_or_else(|| {
+        let overworld = server.get_world_from_dimension(&Dimension::OVERWORLD);
+        PregenJob::around_spawn(server, &overworld, radius, PregenShape::Square)
+    });
+    let result = server.pregen.run_to_end(server, job).await;
+    server.shutdown().await;
+    match result {
+        Ok(finished) => i32::from(!finished),
+        Err(err) => {
+            log::error!("Pregeneration failed: {err}");
+            1
+        }
+    }
+}
+
+/// Compresses every chunk of the world again with the configured compression.
+async fn recompress_world(
+    basic_config: &BasicConfiguration,
+    advanced_config: &AdvancedConfiguration,
+) -> i32 {
+    let world = basic_config.get_world_path();
+    if !world.exists() {
+        log::error!("The world folder {} does not exist", world.display());
+        return 1;
+    }
+    let ChunkConfig::Anvil(anvil) = &advanced_config.world.chunk else {
+        log::error!("--recompress only works with the Anvil chunk format");
+        return 2;
+    };
+    log::info!(
+        "Recompressing {}, this may take a while...",
+        world.display()
+    );
+    let report = pumpkin_world::recompress::recompress_world(&world, &anvil.compression).await;
+    for failure in &report.failures {
+        log::error!(
+            "Left {} unchanged: {}",
+            failure.file.display(),
+            failure.detail
+        );
+    }
+    log::info!(
+        "Recompressed {} chunks in {} region files: {} MB -> {} MB",
+        report.chunks,
+        report.region_files,
+        report.bytes_before / (1024 * 1024),
+        report.bytes_after / (1024 * 1024)
+    );
+    i32::from(!report.failures.is_empty())
+}
+
+/// Feeds a packet capture back through the network decoder and summarizes it per connection
+/// state. Fails if a packet can't be decoded again.
+async fn replay_capture(path: &Path) -> i32 {
+    let reader = match std::fs::File::open(path)
+        .map_err(CaptureError::from)
+        .and_then(|file| CaptureReader::new(io::BufReader::new(file)))
+    {
+        Ok(reader) => reader,
+        Err(err) => {
+            log::error!("Failed to open {}: {err}", path.display());
+            return 1;
+        }
+    };
+    // Replaying with compression exercises both the compressed and the uncompressed framing
+    let packets = match capture::replay(reader, Some(256)).await {
+        Ok(packets) => packets,
+        Err(err) => {
+            log::error!("Replay of {} failed: {err}", path.display());
+            return 1;
+        }
+    };
+
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for (packet, _) in &packets {
+        let count = counts.entry(format!("{:?}", packet.state)).or_default();
+        match packet.direction {
+            CaptureDirection::Serverbound => count.0 += 1,
+            CaptureDirection::Clientbound => count.1 += 1,
+        }
+    }
+    let duration = packets
+        .last()
+        .map_or(0.0, |(packet, _)| packet.elapsed.as_secs_f64());
+    log::info!(
+        "Replayed {} packets covering {duration:.1}s from {}",
+        packets.len(),
+        path.display()
+    );
+    for (state, (serverbound, clientbound)) in counts {
+        log::info!("  {state}: {serverbound} serverbound, {clientbound} clientbound");
+    }
+    0
 }
 
 fn handle_interrupt() {
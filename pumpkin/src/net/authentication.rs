@@ -1,4 +1,4 @@
-use std::{collections::HashMap, net::IpAddr};
+use std::{collections::HashMap, net::IpAddr, time::Duration};
 
 use base64::{Engine, engine::general_purpose};
 use pumpkin_config::{AuthenticationConfig, networking::auth::TextureConfig};
@@ -7,6 +7,7 @@ use rsa::RsaPublicKey;
 use rsa::pkcs8::DecodePublicKey;
 use serde::Deserialize;
 use thiserror::Error;
+use ureq::Agent;
 use ureq::http::{StatusCode, Uri};
 use uuid::Uuid;
 
@@ -48,6 +49,19 @@ const MOJANG_AUTHENTICATION_URL: &str = "https://sessionserver.mojang.com/sessio
 const MOJANG_PREVENT_PROXY_AUTHENTICATION_URL: &str = "https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}";
 const MOJANG_SERVICES_URL: &str = "https://api.minecraftservices.com/";
 
+/// An HTTP agent that gives up on the authentication servers after the configured timeouts.
+fn agent(auth_config: &AuthenticationConfig) -> Agent {
+    let connect_timeout = Some(Duration::from_millis(auth_config.connect_timeout.into()));
+    let read_timeout = Some(Duration::from_millis(auth_config.read_timeout.into()));
+    Agent::config_builder()
+        .timeout_resolve(connect_timeout)
+        .timeout_connect(connect_timeout)
+        .timeout_recv_response(read_timeout)
+        .timeout_recv_body(read_timeout)
+        .build()
+        .into()
+}
+
 /// Sends a GET request to Mojang's authentication servers to verify a client's Minecraft account.
 ///
 /// **Purpose:**
@@ -60,6 +74,8 @@ const MOJANG_SERVICES_URL: &str = "https://api.minecraftservices.com/";
 /// 2. Mojang's servers verify the client's credentials and add the player to the their Servers
 /// 3. Now our server will send a Request to the Session servers and check if the Player has joined the Session Server .
 ///
+/// This blocks until the request is done, so async callers should run it on a blocking task.
+///
 /// See <https://pumpkinmc.org/developer/networking/authentication>
 pub fn authenticate(
     username: &str,
@@ -88,7 +104,8 @@ pub fn authenticate(
             .replace("{server_hash}", server_hash)
     };
 
-    let mut response = ureq::get(address)
+    let mut response = agent(auth_config)
+        .get(address)
         .call()
         .map_err(|_| AuthError::FailedResponse)?;
     match response.status() {
@@ -150,7 +167,8 @@ pub fn fetch_mojang_public_keys(
 
     let url = format!("{services_url}/publickeys");
 
-    let mut response = ureq::get(url)
+    let mut response = agent(auth_config)
+        .get(url)
         .call()
         .map_err(|_| AuthError::FailedResponse)?;
 
@@ -179,6 +197,18 @@ pub fn fetch_mojang_public_keys(
     Ok(as_rsa_keys)
 }
 
+impl AuthError {
+    /// Whether the error means that the authentication servers are unreachable or broken, rather
+    /// than that the player failed to authenticate.
+    #[must_use]
+    pub const fn is_upstream_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::FailedResponse | Self::FailedParse | Self::UnknownStatusCode(_)
+        )
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum AuthError {
     #[error("Authentication servers are down")]
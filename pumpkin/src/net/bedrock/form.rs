@@ -0,0 +1,181 @@
+//! Forms, the native dialogs of the Bedrock client.
+//!
+//! Bedrock has no equivalent for most Java screens, so screens opened for Bedrock players are
+//! shown as a form listing the container's items. Pressing an item moves it like a shift click.
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use pumpkin_data::data_component_impl::CustomNameImpl;
+use pumpkin_inventory::screen_handler::SharedScreenHandler;
+use pumpkin_protocol::bedrock::client::modal_form_request::CModalFormRequest;
+use pumpkin_protocol::bedrock::server::modal_form_response::SModalFormResponse;
+use pumpkin_protocol::codec::var_uint::VarUInt;
+use pumpkin_protocol::java::server::play::SlotActionType;
+use pumpkin_world::item::ItemStack;
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::entity::player::Player;
+use crate::net::bedrock::BedrockClient;
+
+/// Slots of the player inventory that screens append after their own.
+const PLAYER_INVENTORY_SLOTS: usize = 36;
+
+#[derive(Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum Form {
+    /// A list of buttons.
+    #[serde(rename = "form")]
+    Simple {
+        title: String,
+        content: String,
+        buttons: Vec<FormButton>,
+    },
+    /// A yes/no question.
+    #[serde(rename = "modal")]
+    Modal {
+        title: String,
+        content: String,
+        button1: String,
+        button2: String,
+    },
+}
+
+#[derive(Serialize, Clone)]
+pub struct FormButton {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormResponse {
+    /// Index of the pressed button of a simple form.
+    Button(usize),
+    /// Whether the first button of a modal form was pressed.
+    Modal(bool),
+    /// The form was closed, or the client disconnected.
+    Closed,
+}
+
+impl FormResponse {
+    fn parse(response: Option<&str>) -> Self {
+        match response.map(str::trim) {
+            Some("true") => Self::Modal(true),
+            Some("false") => Self::Modal(false),
+            Some(index) => index.parse().map_or(Self::Closed, Self::Button),
+            None => Self::Closed,
+        }
+    }
+}
+
+impl BedrockClient {
+    /// Shows `form` to the client and waits until it is answered or closed.
+    pub async fn send_form(&self, form: &Form) -> FormResponse {
+        let id = self.next_form_id.fetch_add(1, Ordering::Relaxed);
+        let (send, recv) = oneshot::channel();
+        self.pending_forms.lock().unwrap().insert(id, send);
+        self.send_game_packet(&CModalFormRequest {
+            form_id: VarUInt(id),
+            form_data: serde_json::to_string(form).expect("Forms serialize to JSON"),
+        })
+        .await;
+
+        let response = tokio::select! {
+            response = recv => response.unwrap_or(FormResponse::Closed),
+            () = self.await_close_interrupt() => FormResponse::Closed,
+        };
+        self.pending_forms.lock().unwrap().remove(&id);
+        response
+    }
+
+    pub fn handle_form_response(&self, packet: &SModalFormResponse) {
+        let Some(send) = self.pending_forms.lock().unwrap().remove(&packet.form_id.0) else {
+            return;
+        };
+        let _ = send.send(FormResponse::parse(packet.response.as_deref()));
+    }
+}
+
+fn item_name(stack: &ItemStack) -> String {
+    let name = stack.get_data_component::<CustomNameImpl>().map_or_else(
+        || stack.item.registry_key.replace('_', " "),
+        |custom| custom.name.to_string(),
+    );
+    format!("{}x {name}", stack.item_count)
+}
+
+/// Shows the container slots of `screen_handler` as a form until the player closes it or
+/// another screen replaces it.
+pub async fn present_screen(
+    client: Arc<BedrockClient>,
+    player: Arc<Player>,
+    screen_handler: SharedScreenHandler,
+    title: String,
+) {
+    loop {
+        let mut slots = Vec::new();
+        let mut buttons = Vec::new();
+        {
+            let handler = screen_handler.lock().await;
+            let all_slots = &handler.get_behaviour().slots;
+            let container_slots = if all_slots.len() > PLAYER_INVENTORY_SLOTS {
+                all_slots.len() - PLAYER_INVENTORY_SLOTS
+            } else {
+                all_slots.len()
+            };
+            for (index, slot) in all_slots.iter().take(container_slots).enumerate() {
+                let stack = slot.get_cloned_stack().await;
+                if !stack.is_empty() {
+                    slots.push(index as i32);
+                    buttons.push(FormButton {
+                        text: item_name(&stack),
+                    });
+                }
+            }
+        }
+
+        let form = Form::Simple {
+            title: title.clone(),
+            content: if buttons.is_empty() {
+                "Empty".to_string()
+            } else {
+                String::new()
+            },
+            buttons,
+        };
+        let response = client.send_form(&form).await;
+
+        // Another screen was opened in the meantime
+        if !Arc::ptr_eq(
+            &*player.current_screen_handler.lock().await,
+            &screen_handler,
+        ) {
+            return;
+        }
+        let FormResponse::Button(button) = response else {
+            player.close_handled_screen().await;
+            return;
+        };
+        let Some(&slot) = slots.get(button) else {
+            continue;
+        };
+        let mut handler = screen_handler.lock().await;
+        handler
+            .on_slot_click(slot, 0, SlotActionType::QuickMove, player.as_ref())
+            .await;
+        handler.send_content_updates().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_are_parsed_by_form_kind() {
+        assert_eq!(FormResponse::parse(Some("2\n")), FormResponse::Button(2));
+        assert_eq!(FormResponse::parse(Some("true")), FormResponse::Modal(true));
+        assert_eq!(FormResponse::parse(Some("null")), FormResponse::Closed);
+        assert_eq!(FormResponse::parse(None), FormResponse::Closed);
+    }
+}
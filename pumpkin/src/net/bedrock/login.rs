@@ -115,6 +115,7 @@ impl BedrockClient {
             world
                 .spawn_bedrock_player(&server.basic_config, player.clone(), server)
                 .await;
+            server.restart.show_to(&player).await;
             *self.player.lock().await = Some(player);
         }
 
@@ -26,6 +26,7 @@ use pumpkin_protocol::{
             interaction::SInteraction,
             loading_screen::SLoadingScreen,
             login::SLogin,
+            modal_form_response::SModalFormResponse,
             player_auth_input::SPlayerAuthInput,
             raknet::{
                 connection::{
@@ -49,15 +50,21 @@ use tokio::{
     net::UdpSocket,
     sync::Mutex,
     sync::mpsc::{Receiver, Sender},
+    sync::oneshot,
     task::JoinHandle,
 };
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 pub mod connection;
+pub mod form;
 pub mod login;
 pub mod open_connection;
 pub mod unconnected;
-use crate::{entity::player::Player, net::DisconnectReason, server::Server};
+use crate::{
+    entity::player::Player,
+    net::{DisconnectReason, bedrock::form::FormResponse},
+    server::Server,
+};
 
 pub struct BedrockClient {
     socket: Arc<UdpSocket>,
@@ -89,6 +96,9 @@ pub struct BedrockClient {
     /// Store Fragments until the packet is complete
     compounds: Arc<Mutex<HashMap<u16, Vec<Option<Frame>>>>>,
     //input_sequence_number: AtomicU32,
+    next_form_id: AtomicU32,
+    /// Forms waiting for the client's answer, by form ID.
+    pending_forms: std::sync::Mutex<HashMap<u32, oneshot::Sender<FormResponse>>>,
 }
 
 impl BedrockClient {
@@ -118,6 +128,8 @@ impl BedrockClient {
             compounds: Arc::new(Mutex::new(HashMap::new())),
             close_token: CancellationToken::new(),
             //input_sequence_number: AtomicU32::new(0),
+            next_form_id: AtomicU32::new(0),
+            pending_forms: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
@@ -556,6 +568,15 @@ impl BedrockClient {
                 self.handle_container_close(player, SContainerClose::read(reader).unwrap())
                     .await;
             }
+            SModalFormResponse::PACKET_ID => match SModalFormResponse::read(reader) {
+                Ok(response) => self.handle_form_response(&response),
+                Err(error) => {
+                    log::warn!(
+                        "Bedrock: Received an invalid form response from {}: {error}",
+                        player.gameprofile.name
+                    );
+                }
+            },
             SText::PACKET_ID => {
                 self.handle_chat_message(server, player, SText::read(reader).unwrap())
                     .await;
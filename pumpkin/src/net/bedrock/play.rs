@@ -23,7 +23,7 @@ use pumpkin_util::{math::position::BlockPos, text::TextComponent};
 
 use crate::{
     command::CommandSender,
-    entity::{EntityBase, player::Player},
+    entity::{EntityBase, chat_limiter::MessageKind, player::Player},
     net::{DisconnectReason, bedrock::BedrockClient},
     plugin::player::{player_chat::PlayerChatEvent, player_command_send::PlayerCommandSendEvent},
     server::{Server, seasonal_events},
@@ -94,7 +94,8 @@ impl BedrockClient {
         let entity = player.get_entity();
 
         if input_data.get(InputData::StartSprinting) {
-            entity.set_sprinting(true).await;
+            let sprinting = player.can_sprint().await;
+            entity.set_sprinting(sprinting).await;
         } else if input_data.get(InputData::StopSprinting) {
             entity.set_sprinting(false).await;
         }
@@ -109,8 +110,10 @@ impl BedrockClient {
 
         if input_data.get(InputData::StartSneaking) {
             entity.set_sneaking(true).await;
+            player.update_pose().await;
         } else if input_data.get(InputData::StopSneaking) {
             entity.set_sneaking(false).await;
+            player.update_pose().await;
         }
     }
 
@@ -139,6 +142,13 @@ impl BedrockClient {
 
     pub async fn handle_chat_message(&self, server: &Server, player: &Arc<Player>, packet: SText) {
         let gameprofile = &player.gameprofile;
+        if !player
+            .chat_limiter
+            .allow(player, server, MessageKind::Chat, &packet.message)
+            .await
+        {
+            return;
+        }
 
         send_cancellable! {{
             server;
@@ -153,6 +163,7 @@ impl BedrockClient {
                     Some(m) => m,
                     None => event.message.clone(),
                 };
+                server.chat_bridge.relay_chat(&gameprofile.name, &message);
 
                 let decorated_message = TextComponent::chat_decorated(
                     &config.chat.format,
@@ -186,6 +197,13 @@ impl BedrockClient {
         server: &Arc<Server>,
         command: SCommandRequest,
     ) {
+        if !player
+            .chat_limiter
+            .allow(player, server, MessageKind::Command, &command.command)
+            .await
+        {
+            return;
+        }
         let player_clone = player.clone();
         let server_clone: Arc<Server> = server.clone();
         send_cancellable! {{
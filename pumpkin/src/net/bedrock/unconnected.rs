@@ -17,7 +17,7 @@ impl BedrockClient {
         socket: &UdpSocket,
     ) {
         // TODO
-        let player_count = server
+        let online = server
             .get_status()
             .lock()
             .await
@@ -25,7 +25,12 @@ impl BedrockClient {
             .players
             .as_ref()
             .unwrap()
-            .online as _;
+            .online;
+        let player_count = if server.redis.shows_global_player_count() {
+            server.redis.global_player_count(online)
+        } else {
+            online
+        };
 
         let motd_string = ServerInfo {
             edition: "MCPE",
@@ -33,12 +38,12 @@ impl BedrockClient {
             motd_line_1: "Pumpkin Server",
             protocol_version: CURRENT_BEDROCK_MC_PROTOCOL,
             version_name: CURRENT_BEDROCK_MC_VERSION,
-            player_count,
+            player_count: player_count as _,
             // A large number looks wreird on the client worlds window
             max_player_count: server.basic_config.max_players,
             server_unique_id: server.server_guid,
             motd_line_2: server.basic_config.default_level_name.clone(),
-            game_mode: server.defaultgamemode.lock().await.gamemode.to_str(),
+            game_mode: server.defaultgamemode.load().gamemode.to_str(),
             game_mode_numeric: 1,
             port_ipv4: 19132,
             port_ipv6: 19133,
@@ -0,0 +1,112 @@
+//! Failing fast while an upstream service is down, instead of letting every caller wait for its
+//! timeouts.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+enum State {
+    /// Calls go through. Holds the number of failures in a row.
+    Closed(u32),
+    /// Calls fail fast until the cooldown is over.
+    Open(Instant),
+    /// A single trial call went through after the cooldown, at the given time. The others fail
+    /// fast until it finishes, or for another cooldown if it never reports back.
+    HalfOpen(Instant),
+}
+
+pub struct CircuitBreaker {
+    /// What the service is called in logs.
+    name: &'static str,
+    state: Mutex<State>,
+    /// Failures in a row that open the breaker. `0` never opens it.
+    threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    #[must_use]
+    pub const fn new(name: &'static str, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name,
+            state: Mutex::new(State::Closed(0)),
+            threshold,
+            cooldown,
+        }
+    }
+
+    /// Whether a call may go through now. Callers that get `true` must report how it went with
+    /// [`Self::record_success`] or [`Self::record_failure`].
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            State::Closed(_) => true,
+            State::Open(since) | State::HalfOpen(since) if since.elapsed() >= self.cooldown => {
+                *state = State::HalfOpen(Instant::now());
+                true
+            }
+            State::Open(_) | State::HalfOpen(_) => false,
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.state.lock().unwrap() = State::Closed(0);
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        *state = match *state {
+            State::Closed(failures) if self.threshold == 0 || failures + 1 < self.threshold => {
+                State::Closed(failures + 1)
+            }
+            State::Closed(_) | State::HalfOpen(_) => {
+                log::warn!(
+                    "{} keep failing, failing fast for {:?}",
+                    self.name,
+                    self.cooldown
+                );
+                State::Open(Instant::now())
+            }
+            // A call from before the breaker opened
+            State::Open(since) => State::Open(since),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(60);
+
+    fn cooled_down() -> State {
+        State::Open(Instant::now().checked_sub(COOLDOWN).unwrap())
+    }
+
+    #[test]
+    fn opens_after_threshold() {
+        let breaker = CircuitBreaker::new("Test", 2, COOLDOWN);
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn trial_call_after_cooldown() {
+        let breaker = CircuitBreaker::new("Test", 1, COOLDOWN);
+        *breaker.state.lock().unwrap() = cooled_down();
+
+        // Only one call goes through until it reports back
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        breaker.record_failure();
+        assert!(!breaker.allow());
+
+        *breaker.state.lock().unwrap() = cooled_down();
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert!(breaker.allow());
+        assert!(breaker.allow());
+    }
+}
@@ -0,0 +1,75 @@
+//! Recognition of Bedrock players joining through Geyser with Floodgate-style accounts.
+
+use std::net::IpAddr;
+
+use pumpkin_config::networking::floodgate::FloodgateConfig;
+use uuid::Uuid;
+
+/// Whether `id` has the shape Floodgate gives Bedrock players: zero upper half, XUID below.
+#[must_use]
+pub const fn is_floodgate_uuid(id: Uuid) -> bool {
+    let (most, least) = id.as_u64_pair();
+    most == 0 && least != 0
+}
+
+/// The Xbox user ID of a Floodgate player.
+#[must_use]
+pub const fn xuid(id: Uuid) -> Option<u64> {
+    if is_floodgate_uuid(id) {
+        Some(id.as_u64_pair().1)
+    } else {
+        None
+    }
+}
+
+/// Whether a direct login of `name` with `id` from `address` is a Floodgate player logged in by
+/// a trusted Geyser instance, which skips encryption and Mojang authentication.
+#[must_use]
+pub fn is_trusted_login(config: &FloodgateConfig, address: IpAddr, name: &str, id: Uuid) -> bool {
+    config.enabled
+        && is_floodgate_uuid(id)
+        && name.starts_with(&config.username_prefix)
+        && config
+            .trusted_addresses
+            .iter()
+            .any(|trusted| *trusted == address.to_canonical())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn only_prefixed_floodgate_players_from_trusted_addresses_are_trusted() {
+        let config = FloodgateConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let bedrock = Uuid::from_u64_pair(0, 2_535_412_345_678_901);
+        let java = Uuid::from_u64_pair(0x0123_4567_89ab_cdef, 2_535_412_345_678_901);
+        let geyser = IpAddr::from(Ipv4Addr::LOCALHOST);
+
+        assert_eq!(xuid(bedrock), Some(2_535_412_345_678_901));
+        assert_eq!(xuid(java), None);
+        assert!(!is_floodgate_uuid(Uuid::nil()));
+
+        assert!(is_trusted_login(&config, geyser, ".Steve", bedrock));
+        // IPv4-mapped addresses of dual stack listeners
+        assert!(is_trusted_login(
+            &config,
+            "::ffff:127.0.0.1".parse().unwrap(),
+            ".Steve",
+            bedrock
+        ));
+        assert!(!is_trusted_login(&config, geyser, "Steve", bedrock));
+        assert!(!is_trusted_login(&config, geyser, ".Steve", java));
+        assert!(!is_trusted_login(
+            &config,
+            Ipv4Addr::new(10, 0, 0, 2).into(),
+            ".Steve",
+            bedrock
+        ));
+    }
+}
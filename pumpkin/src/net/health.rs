@@ -0,0 +1,444 @@
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use pumpkin_inventory::sync_handler::ResyncReason;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+
+use crate::net::traffic;
+use crate::server::Server;
+
+/// How long a client may take to send its request line and headers.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a connection may stay open in total, including sending the response.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+/// Requests larger than this are rejected; probes only send a few headers.
+const MAX_REQUEST_SIZE: usize = 4096;
+/// Connections served at once. Further connections are closed right away.
+const MAX_CONNECTIONS: usize = 16;
+
+/// The lifecycle of the server as reported to health probes and systemd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleState {
+    /// Worlds and plugins are still loading.
+    Starting,
+    /// The game listener is up and players can join.
+    Ready,
+    /// The server is saving and shutting down.
+    Stopping,
+}
+
+impl LifecycleState {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Starting => "starting",
+            Self::Ready => "ready",
+            Self::Stopping => "stopping",
+        }
+    }
+}
+
+/// Sends a notification to the service manager over `$NOTIFY_SOCKET`
+/// (see `sd_notify(3)`). Does nothing when the server was not started by systemd.
+pub fn notify_systemd(state: &str) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::net::UnixDatagram;
+
+        let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+            return;
+        };
+        let result = UnixDatagram::unbound().and_then(|socket| {
+            let bytes = path.as_encoded_bytes();
+            // Abstract namespace sockets are given with a leading '@'
+            if let Some(name) = bytes.strip_prefix(b"@") {
+                #[cfg(target_os = "linux")]
+                {
+                    use std::os::linux::net::SocketAddrExt;
+                    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                    socket.send_to_addr(state.as_bytes(), &addr)
+                }
+                #[cfg(not(target_os = "linux"))]
+                {
+                    let _ = name;
+                    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+                }
+            } else {
+                socket.send_to(state.as_bytes(), &path)
+            }
+        });
+        if let Err(err) = result {
+            log::warn!("Failed to notify systemd ({state}): {err}");
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = state;
+    }
+}
+
+/// Changes the lifecycle state of the server and tells systemd about it.
+pub fn set_lifecycle_state(server: &Server, state: LifecycleState) {
+    server.lifecycle_state.store(state);
+    if !server.advanced_config.networking.health.systemd_notify {
+        return;
+    }
+    match state {
+        LifecycleState::Starting => {}
+        LifecycleState::Ready => notify_systemd("READY=1\nSTATUS=Accepting players"),
+        LifecycleState::Stopping => notify_systemd("STOPPING=1\nSTATUS=Shutting down"),
+    }
+}
+
+/// Serves the health endpoint until the process exits.
+///
+/// Unlike other listeners this one deliberately keeps running after the stop signal, so
+/// probes observe the `stopping` state instead of a refused connection.
+pub async fn start_health_endpoint(server: Arc<Server>, address: SocketAddr) {
+    let listener = match TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::error!("Failed to bind health endpoint to {address}: {err}");
+            return;
+        }
+    };
+    log::info!(
+        "Health endpoint running on {}",
+        listener
+            .local_addr()
+            .expect("Unable to find running address!")
+    );
+
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                // Probes are quick, so anything beyond the limit is not a probe worth serving
+                let Ok(permit) = connections.clone().try_acquire_owned() else {
+                    continue;
+                };
+                let server = server.clone();
+                tokio::spawn(async move {
+                    let handled = handle_connection(stream, |path| route(&server, path));
+                    if let Ok(Err(err)) = timeout(CONNECTION_TIMEOUT, handled).await {
+                        log::debug!("Health endpoint connection failed: {err}");
+                    }
+                    drop(permit);
+                });
+            }
+            Err(err) => {
+                log::warn!("Failed to accept health endpoint connection: {err}");
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }
+    }
+}
+
+/// A response to a probe: status code, content type and body.
+type Response = (u16, &'static str, String);
+
+async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    route: impl FnOnce(&str) -> Response,
+) -> std::io::Result<()> {
+    let mut request = Vec::with_capacity(512);
+    let mut buf = [0; 512];
+    let read_headers = async {
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            let read = stream.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..read]);
+            if request.len() > MAX_REQUEST_SIZE {
+                break;
+            }
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    let Ok(result) = timeout(REQUEST_TIMEOUT, read_headers).await else {
+        return Ok(());
+    };
+    result?;
+
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.lines().next().unwrap_or_default().split(' ');
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default();
+
+    let (status, content_type, body) = if request.len() > MAX_REQUEST_SIZE {
+        (431, "text/plain", "request too large\n".to_string())
+    } else if method == "GET" || method == "HEAD" {
+        route(path)
+    } else {
+        (405, "text/plain", "method not allowed\n".to_string())
+    };
+
+    let reason = match status {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    };
+    let mut response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    if method != "HEAD" {
+        response.push_str(&body);
+    }
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// What the probes are told about the server, read once per request.
+struct Status {
+    state: LifecycleState,
+    tps: Option<f64>,
+    mspt: f64,
+    players: usize,
+    /// TPS below which the server is degraded, `0` to never report it.
+    min_tps: f32,
+}
+
+impl Status {
+    fn of(server: &Server) -> Self {
+        Self {
+            state: server.lifecycle_state.load(),
+            tps: current_tps(server),
+            mspt: server.get_mspt(),
+            players: server.get_player_count(),
+            min_tps: server.advanced_config.networking.health.min_tps,
+        }
+    }
+
+    fn respond(&self, path: &str) -> Option<Response> {
+        let state = self.state;
+        Some(match path {
+            "/" | "/health" => {
+                let tps_ok = self.min_tps <= 0.0
+                    || self.tps.is_none_or(|tps| tps >= f64::from(self.min_tps));
+                let healthy = state == LifecycleState::Ready && tps_ok;
+                let status = if state != LifecycleState::Ready {
+                    state.as_str()
+                } else if tps_ok {
+                    "ok"
+                } else {
+                    "degraded"
+                };
+                let body = serde_json::json!({
+                    "status": status,
+                    "state": state.as_str(),
+                    "tps": self.tps,
+                    "mspt": self.mspt,
+                    "players": self.players,
+                });
+                (
+                    if healthy { 200 } else { 503 },
+                    "application/json",
+                    format!("{body}\n"),
+                )
+            }
+            "/ready" => (
+                if state == LifecycleState::Ready {
+                    200
+                } else {
+                    503
+                },
+                "text/plain",
+                format!("{}\n", state.as_str()),
+            ),
+            "/live" => (200, "text/plain", "alive\n".to_string()),
+            _ => return None,
+        })
+    }
+}
+
+fn route(server: &Server, path: &str) -> Response {
+    if path == "/metrics" {
+        return (200, "text/plain; version=0.0.4", render_metrics(server));
+    }
+    Status::of(server)
+        .respond(path)
+        .unwrap_or_else(|| (404, "text/plain", "not found\n".to_string()))
+}
+
+/// The effective TPS, capped at the target tick rate. `None` until a tick was measured.
+fn current_tps(server: &Server) -> Option<f64> {
+    let mspt = server.get_mspt();
+    (mspt > 0.0).then(|| (1000.0 / mspt).min(f64::from(server.tick_rate_manager.tickrate())))
+}
+
+/// Renders server metrics in the Prometheus text exposition format.
+fn render_metrics(server: &Server) -> String {
+    let mut out = String::new();
+    let state = server.lifecycle_state.load();
+    let totals = traffic::global_totals();
+
+    let gauges = [
+        (
+            "pumpkin_ready",
+            "Whether the server accepts players",
+            f64::from(u8::from(state == LifecycleState::Ready)),
+        ),
+        (
+            "pumpkin_tps",
+            "Ticks per second",
+            current_tps(server).unwrap_or_default(),
+        ),
+        (
+            "pumpkin_mspt",
+            "Average milliseconds per tick",
+            server.get_mspt(),
+        ),
+        (
+            "pumpkin_players_online",
+            "Players currently online",
+            server.get_player_count() as f64,
+        ),
+    ];
+    for (name, help, value) in gauges {
+        let _ = writeln!(
+            out,
+            "# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}"
+        );
+    }
+
+    let counters = [
+        (
+            "pumpkin_network_packets_total",
+            "Packets exchanged with clients",
+            totals.inbound.packets,
+            totals.outbound.packets,
+        ),
+        (
+            "pumpkin_network_bytes_total",
+            "Uncompressed packet bytes exchanged with clients",
+            totals.inbound.bytes,
+            totals.outbound.bytes,
+        ),
+    ];
+    for (name, help, inbound, outbound) in counters {
+        let _ = writeln!(
+            out,
+            "# HELP {name} {help}\n# TYPE {name} counter\n{name}{{direction=\"in\"}} {inbound}\n{name}{{direction=\"out\"}} {outbound}"
+        );
+    }
+
+    let name = "pumpkin_inventory_resyncs_total";
+    let _ = writeln!(
+        out,
+        "# HELP {name} Full container updates sent to clients out of sync\n# TYPE {name} counter"
+    );
+    for reason in ResyncReason::ALL {
+        let _ = writeln!(
+            out,
+            "{name}{{reason=\"{}\"}} {}",
+            reason.name(),
+            reason.count()
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(state: LifecycleState, tps: Option<f64>) -> Status {
+        Status {
+            state,
+            tps,
+            mspt: 12.5,
+            players: 3,
+            min_tps: 15.0,
+        }
+    }
+
+    #[test]
+    fn ready_server_is_healthy() {
+        let status = status(LifecycleState::Ready, Some(20.0));
+        let (code, content_type, body) = status.respond("/health").unwrap();
+        assert_eq!(code, 200);
+        assert_eq!(content_type, "application/json");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["players"], 3);
+
+        assert_eq!(status.respond("/ready").unwrap().0, 200);
+        assert_eq!(status.respond("/live").unwrap().0, 200);
+        assert!(status.respond("/nope").is_none());
+    }
+
+    #[test]
+    fn starting_or_slow_server_is_not_healthy() {
+        let starting = status(LifecycleState::Starting, None);
+        let (code, _, body) = starting.respond("/health").unwrap();
+        assert_eq!(code, 503);
+        assert!(body.contains("\"status\":\"starting\""));
+        assert_eq!(
+            starting.respond("/ready").unwrap(),
+            (503, "text/plain", "starting\n".into())
+        );
+        // Still alive, so it isn't restarted while loading
+        assert_eq!(starting.respond("/live").unwrap().0, 200);
+
+        let slow = status(LifecycleState::Ready, Some(10.0));
+        let (code, _, body) = slow.respond("/health").unwrap();
+        assert_eq!(code, 503);
+        assert!(body.contains("\"status\":\"degraded\""));
+        assert_eq!(slow.respond("/ready").unwrap().0, 200);
+    }
+
+    async fn request(request: &[u8]) -> String {
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        client.write_all(request).await.unwrap();
+        client.shutdown().await.unwrap();
+        handle_connection(server, |path| {
+            status(LifecycleState::Ready, Some(20.0))
+                .respond(path)
+                .unwrap_or((404, "text/plain", "not found\n".to_string()))
+        })
+        .await
+        .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn serves_requests() {
+        let response = request(b"GET /ready?probe=1 HTTP/1.1\r\nHost: x\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nready\n"));
+
+        let response = request(b"HEAD /ready HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\n"));
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_requests() {
+        let response = request(b"POST /ready HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 405 "));
+
+        let response = request(b"\xff\xfe garbage").await;
+        assert!(response.starts_with("HTTP/1.1 405 "));
+
+        let response = request(b"GET /../etc/passwd HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404 "));
+
+        let mut oversized = b"GET /ready HTTP/1.1\r\nX: ".to_vec();
+        oversized.extend(std::iter::repeat_n(b'a', MAX_REQUEST_SIZE * 2));
+        let response = request(&oversized).await;
+        assert!(response.starts_with("HTTP/1.1 431 "));
+    }
+}
@@ -0,0 +1,288 @@
+//! Overrides of the biome registry sent to clients, which decides the fog and sky colors,
+//! ambience and music of each biome.
+//!
+//! Up to 1.21.9 all effects of a biome are in its `effects`. Since 1.21.11 most of them are
+//! environment attributes, with only the colors of water and plants left in `effects`.
+
+use std::io::Cursor;
+
+use pumpkin_config::biome_effects::{BiomeEffects, BiomeEffectsConfig};
+use pumpkin_data::registry::Registry;
+use pumpkin_nbt::{Nbt, compound::NbtCompound, deserializer::NbtReadHelper, tag::NbtTag};
+
+const BIOME_REGISTRY: &str = "minecraft:worldgen/biome";
+
+/// How often the additions sound of a biome plays, in vanilla.
+const ADDITIONS_TICK_CHANCE: f64 = 0.0111;
+
+/// Applies the overrides of `config` to the biomes in `registries`.
+pub fn apply(registries: &mut [Registry], config: &BiomeEffectsConfig) {
+    if config.biomes.is_empty() {
+        return;
+    }
+    let Some(biomes) = registries
+        .iter_mut()
+        .find(|registry| registry.registry_id == BIOME_REGISTRY)
+    else {
+        return;
+    };
+    for entry in &mut biomes.registry_entries {
+        let (Some(effects), Some(data)) = (config.for_biome(&entry.entry_id), &entry.data) else {
+            continue;
+        };
+        match Nbt::read_unnamed(&mut NbtReadHelper::new(Cursor::new(&data[..]))) {
+            Ok(nbt) => {
+                let biome = apply_to_biome(nbt.root_tag, effects, &entry.entry_id);
+                entry.data = Some(Nbt::from(biome).write_unnamed().to_vec().into());
+            }
+            Err(err) => log::warn!("Failed to read the biome {}: {err}", entry.entry_id),
+        }
+    }
+}
+
+fn apply_to_biome(mut biome: NbtCompound, overrides: &BiomeEffects, name: &str) -> NbtCompound {
+    let color = |key: &str, color: Option<&str>| {
+        let color = color?;
+        let parsed = parse_color(color);
+        if parsed.is_none() {
+            log::warn!("Ignored the {key} {color} of the biome {name}, it isn't #rrggbb");
+        }
+        parsed
+    };
+    let colors = Colors {
+        fog: color("fog color", overrides.fog_color.as_deref()),
+        sky: color("sky color", overrides.sky_color.as_deref()),
+        water: color("water color", overrides.water_color.as_deref()),
+        water_fog: color("water fog color", overrides.water_fog_color.as_deref()),
+        grass: color("grass color", overrides.grass_color.as_deref()),
+        foliage: color("foliage color", overrides.foliage_color.as_deref()),
+    };
+
+    let mut effects = take_compound(&mut biome, "effects");
+    // Every biome has a sky color in `effects` up to 1.21.9
+    if effects.get("sky_color").is_some() {
+        apply_to_effects(&mut effects, overrides, &colors);
+    } else {
+        let mut attributes = take_compound(&mut biome, "attributes");
+        apply_to_attributes(&mut attributes, &mut effects, overrides, &colors);
+        if !attributes.is_empty() {
+            biome.put_component("attributes", attributes);
+        }
+    }
+    biome.put_component("effects", effects);
+    biome
+}
+
+struct Colors {
+    fog: Option<u32>,
+    sky: Option<u32>,
+    water: Option<u32>,
+    water_fog: Option<u32>,
+    grass: Option<u32>,
+    foliage: Option<u32>,
+}
+
+/// Applies the overrides up to 1.21.9, where colors are numbers.
+fn apply_to_effects(effects: &mut NbtCompound, overrides: &BiomeEffects, colors: &Colors) {
+    for (key, color) in [
+        ("fog_color", colors.fog),
+        ("sky_color", colors.sky),
+        ("water_color", colors.water),
+        ("water_fog_color", colors.water_fog),
+        ("grass_color", colors.grass),
+        ("foliage_color", colors.foliage),
+    ] {
+        if let Some(color) = color {
+            set(effects, key, NbtTag::Int(color as i32));
+        }
+    }
+    if let Some(sound) = &overrides.ambient_sound {
+        set(effects, "ambient_sound", NbtTag::String(sound.clone()));
+    }
+    if let Some(sound) = &overrides.mood_sound {
+        set(effects, "mood_sound", mood_sound(sound));
+    }
+    if let Some(sound) = &overrides.additions_sound {
+        set(effects, "additions_sound", additions_sound(sound));
+    }
+    if let Some(sound) = &overrides.music {
+        let mut data = music(sound);
+        data.put_bool("replace_current_music", false);
+        let mut entry = NbtCompound::new();
+        entry.put_component("data", data);
+        entry.put_int("weight", 1);
+        set(
+            effects,
+            "music",
+            NbtTag::List(vec![NbtTag::Compound(entry)]),
+        );
+    }
+}
+
+/// Applies the overrides since 1.21.11, where colors are `#rrggbb`.
+fn apply_to_attributes(
+    attributes: &mut NbtCompound,
+    effects: &mut NbtCompound,
+    overrides: &BiomeEffects,
+    colors: &Colors,
+) {
+    let hex = |color: u32| NbtTag::String(format!("#{color:06x}"));
+    for (key, color) in [
+        ("minecraft:visual/fog_color", colors.fog),
+        ("minecraft:visual/sky_color", colors.sky),
+        ("minecraft:visual/water_fog_color", colors.water_fog),
+    ] {
+        if let Some(color) = color {
+            set(attributes, key, hex(color));
+        }
+    }
+    for (key, color) in [
+        ("water_color", colors.water),
+        ("grass_color", colors.grass),
+        ("foliage_color", colors.foliage),
+    ] {
+        if let Some(color) = color {
+            set(effects, key, hex(color));
+        }
+    }
+
+    let mut ambient_sounds = take_compound(attributes, "minecraft:audio/ambient_sounds");
+    if let Some(sound) = &overrides.ambient_sound {
+        set(&mut ambient_sounds, "loop", NbtTag::String(sound.clone()));
+    }
+    if let Some(sound) = &overrides.mood_sound {
+        set(&mut ambient_sounds, "mood", mood_sound(sound));
+    }
+    if let Some(sound) = &overrides.additions_sound {
+        set(&mut ambient_sounds, "additions", additions_sound(sound));
+    }
+    if !ambient_sounds.is_empty() {
+        attributes.put_component("minecraft:audio/ambient_sounds", ambient_sounds);
+    }
+    if let Some(sound) = &overrides.music {
+        let mut music_by_mode = NbtCompound::new();
+        music_by_mode.put_component("default", music(sound));
+        set(
+            attributes,
+            "minecraft:audio/background_music",
+            NbtTag::Compound(music_by_mode),
+        );
+    }
+}
+
+/// Parses a `#rrggbb` color.
+fn parse_color(color: &str) -> Option<u32> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+fn mood_sound(sound: &str) -> NbtTag {
+    let mut mood = NbtCompound::new();
+    mood.put_string("sound", sound.to_string());
+    mood.put_int("tick_delay", 6000);
+    mood.put_int("block_search_extent", 8);
+    mood.put_double("offset", 2.0);
+    NbtTag::Compound(mood)
+}
+
+fn additions_sound(sound: &str) -> NbtTag {
+    let mut additions = NbtCompound::new();
+    additions.put_string("sound", sound.to_string());
+    additions.put_double("tick_chance", ADDITIONS_TICK_CHANCE);
+    NbtTag::Compound(additions)
+}
+
+fn music(sound: &str) -> NbtCompound {
+    let mut music = NbtCompound::new();
+    music.put_string("sound", sound.to_string());
+    music.put_int("min_delay", 12000);
+    music.put_int("max_delay", 24000);
+    music
+}
+
+fn take_compound(compound: &mut NbtCompound, key: &str) -> NbtCompound {
+    match compound.remove(key) {
+        Some(NbtTag::Compound(child)) => child,
+        _ => NbtCompound::new(),
+    }
+}
+
+/// Sets `key`, replacing its value if there is one.
+fn set(compound: &mut NbtCompound, key: &str, value: NbtTag) {
+    compound.remove(key);
+    compound.put(key, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn biome(effects_tags: &[(&str, NbtTag)]) -> NbtCompound {
+        let mut effects = NbtCompound::new();
+        for (key, value) in effects_tags {
+            effects.put(key, value.clone());
+        }
+        let mut biome = NbtCompound::new();
+        biome.put_component("effects", effects);
+        biome
+    }
+
+    fn overrides() -> BiomeEffects {
+        BiomeEffects {
+            fog_color: Some("#330808".to_string()),
+            water_color: Some("red".to_string()),
+            ambient_sound: Some("minecraft:ambient.nether_wastes.loop".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn overrides_legacy_effects() {
+        let biome = apply_to_biome(
+            biome(&[
+                ("sky_color", NbtTag::Int(7907327)),
+                ("fog_color", NbtTag::Int(12638463)),
+                ("water_color", NbtTag::Int(4159204)),
+            ]),
+            &overrides(),
+            "minecraft:plains",
+        );
+        let effects = biome.get_compound("effects").unwrap();
+        assert_eq!(effects.get_int("fog_color"), Some(0x330808));
+        assert_eq!(effects.get_int("water_color"), Some(4159204));
+        assert_eq!(
+            effects.get_string("ambient_sound"),
+            Some("minecraft:ambient.nether_wastes.loop")
+        );
+        assert!(biome.get_compound("attributes").is_none());
+    }
+
+    #[test]
+    fn overrides_environment_attributes() {
+        let biome = apply_to_biome(
+            biome(&[("water_color", NbtTag::String("#3f76e4".to_string()))]),
+            &overrides(),
+            "minecraft:the_end",
+        );
+        let attributes = biome.get_compound("attributes").unwrap();
+        assert_eq!(
+            attributes.get_string("minecraft:visual/fog_color"),
+            Some("#330808")
+        );
+        assert_eq!(
+            attributes
+                .get_compound("minecraft:audio/ambient_sounds")
+                .and_then(|sounds| sounds.get_string("loop")),
+            Some("minecraft:ambient.nether_wastes.loop")
+        );
+        assert_eq!(
+            biome
+                .get_compound("effects")
+                .and_then(|effects| effects.get_string("water_color")),
+            Some("#3f76e4")
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Per-connection packet capture, written in the replay format of
+//! [`pumpkin_protocol::java::capture`].
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use pumpkin_config::networking::capture::PacketCaptureConfig;
+use pumpkin_protocol::codec::var_int::VarInt;
+use pumpkin_protocol::java::capture::{CaptureDirection, CaptureWriter};
+use pumpkin_protocol::ser::NetworkWriteExt;
+
+use crate::net::java::JavaClient;
+
+pub type PacketCapture = CaptureWriter<BufWriter<File>>;
+
+impl JavaClient {
+    /// Starts capturing this connection's packets to a new file in `directory`, named after
+    /// `label`, replacing any capture already running. Returns the path of the file.
+    pub fn start_capture(&self, directory: &Path, label: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(directory)?;
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let label: String = label
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = directory.join(format!("{label}-{}-{started}.pkcap", self.id));
+        let capture = CaptureWriter::new(BufWriter::new(File::create(&path)?))?;
+        self.stop_capture();
+        *self.capture.lock().unwrap() = Some(capture);
+        log::info!(
+            "Capturing the packets of client {} to {}",
+            self.id,
+            path.display()
+        );
+        Ok(path)
+    }
+
+    /// Stops the running capture, if any, writing out what is still buffered.
+    pub fn stop_capture(&self) {
+        if let Some(mut capture) = self.capture.lock().unwrap().take()
+            && let Err(err) = capture.flush()
+        {
+            log::warn!("Failed to finish the capture of client {}: {err}", self.id);
+        }
+    }
+
+    #[must_use]
+    pub fn is_capturing(&self) -> bool {
+        self.capture.lock().unwrap().is_some()
+    }
+
+    /// Starts capturing a new connection if every connection is captured.
+    pub fn capture_connection(&self, config: &PacketCaptureConfig, address: SocketAddr) {
+        if config.enabled
+            && config.players.is_empty()
+            && let Err(err) = self.start_capture(&config.directory, &address.ip().to_string())
+        {
+            log::warn!("Failed to start capturing client {}: {err}", self.id);
+        }
+    }
+
+    /// Starts capturing the connection of `name` if that player is captured.
+    pub fn capture_player(&self, config: &PacketCaptureConfig, name: &str) {
+        if config.enabled
+            && !self.is_capturing()
+            && config
+                .players
+                .iter()
+                .any(|player| player.eq_ignore_ascii_case(name))
+            && let Err(err) = self.start_capture(&config.directory, name)
+        {
+            log::warn!("Failed to start capturing {name}: {err}");
+        }
+    }
+
+    pub(super) fn capture_packet(&self, direction: CaptureDirection, packet: &[u8]) {
+        let mut capture = self.capture.lock().unwrap();
+        let Some(writer) = capture.as_mut() else {
+            return;
+        };
+        let version = self.version.load().protocol_version() as u32;
+        if let Err(err) = writer.write(direction, self.connection_state.load(), version, packet) {
+            log::warn!("Stopped capturing client {}: {err}", self.id);
+            *capture = None;
+        }
+    }
+
+    /// Captures a serverbound packet, which the decoder has split into its ID and payload.
+    pub(super) fn capture_serverbound(&self, id: i32, payload: &[u8]) {
+        if !self.is_capturing() {
+            return;
+        }
+        let mut packet = Vec::with_capacity(VarInt(id).written_size() + payload.len());
+        packet
+            .write_var_int(&VarInt(id))
+            .expect("Writing to a Vec can't fail");
+        packet.extend_from_slice(payload);
+        self.capture_packet(CaptureDirection::Serverbound, &packet);
+    }
+}
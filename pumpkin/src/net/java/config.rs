@@ -4,7 +4,7 @@ use crate::{
     entity::player::ChatMode,
     net::{
         PlayerConfig, can_not_join,
-        java::{JavaClient, PacketHandlerResult},
+        java::{JavaClient, PacketHandlerResult, biome_effects, dimension_types},
     },
     server::Server,
 };
@@ -21,6 +21,7 @@ use pumpkin_protocol::{
     },
 };
 use pumpkin_util::{Hand, text::TextComponent, version::MinecraftVersion};
+use pumpkin_world::dimension::datapack_dimensions;
 
 const BRAND_CHANNEL_PREFIX: &str = "minecraft:brand";
 
@@ -141,20 +142,22 @@ impl JavaClient {
         self.send_known_packs().await;
     }
 
-    pub fn handle_config_cookie_response(&self, packet: &SConfigCookieResponse) {
-        // TODO: allow plugins to access this
+    pub fn handle_config_cookie_response(&self, packet: SConfigCookieResponse) {
         log::debug!(
             "Received cookie_response[config]: key: \"{}\", has_payload: \"{}\", payload_length: \"{:?}\"",
             packet.key,
             packet.has_payload,
             packet.payload.as_ref().map(|p| p.len()),
         );
+        self.resolve_cookie(&packet.key, packet.payload);
     }
 
-    pub async fn handle_known_packs(&self, _config_acknowledged: SKnownPacks) {
+    pub async fn handle_known_packs(&self, server: &Server, _config_acknowledged: SKnownPacks) {
         log::debug!("Handling known packs");
         // let mut tags_to_send = Vec::new();
-        let registry = Registry::get_synced(self.version.load());
+        let mut registry = Registry::get_synced(self.version.load());
+        biome_effects::apply(&mut registry, &server.advanced_config.world.biome_effects);
+        dimension_types::apply(&mut registry, &datapack_dimensions());
         for registry in registry {
             let entries: Vec<RegistryEntry> = registry
                 .registry_entries
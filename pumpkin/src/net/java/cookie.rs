@@ -0,0 +1,192 @@
+//! Transfers to other servers and the cookies clients carry between them.
+//!
+//! Cookies are stored by the client, so a server receiving one can't trust it unless it is
+//! signed with the `cookie_secret` shared by the servers of the network.
+
+use std::time::Duration;
+
+use hmac::{Hmac, KeyInit, Mac};
+use pumpkin_protocol::{
+    ConnectionState,
+    codec::var_int::VarInt,
+    java::client::{config, login::CLoginCookieRequest, play},
+};
+use pumpkin_util::resource_location::ResourceLocation;
+use sha2::Sha256;
+use thiserror::Error;
+use tokio::sync::oneshot;
+
+use crate::net::java::JavaClient;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The largest cookie the vanilla client stores.
+pub const MAX_COOKIE_SIZE: usize = 5120;
+const SIGNATURE_SIZE: usize = 32;
+
+pub type CookiePayload = Option<Box<[u8]>>;
+
+fn mac(secret: &str, key: &str, payload: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    // The key is signed too, so that a cookie can't be replayed under another key
+    mac.update(key.as_bytes());
+    mac.update(&[0]);
+    mac.update(payload);
+    mac
+}
+
+/// Appends the signature of `payload` stored under `key`.
+#[must_use]
+pub fn sign_cookie(secret: &str, key: &str, payload: &[u8]) -> Vec<u8> {
+    let mut signed = payload.to_vec();
+    signed.extend_from_slice(&mac(secret, key, payload).finalize().into_bytes());
+    signed
+}
+
+/// Returns the payload of a cookie signed with [`sign_cookie`], or `None` if the signature does
+/// not match.
+#[must_use]
+pub fn verify_cookie<'a>(secret: &str, key: &str, signed: &'a [u8]) -> Option<&'a [u8]> {
+    let split = signed.len().checked_sub(SIGNATURE_SIZE)?;
+    let (payload, signature) = signed.split_at(split);
+    mac(secret, key, payload)
+        .verify_slice(signature)
+        .is_ok()
+        .then_some(payload)
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TransferError {
+    #[error("Only Java Edition clients support transfers and cookies")]
+    NotJava,
+    #[error("No cookie secret is configured")]
+    NoSecret,
+    #[error("The connection is not in a state that allows this packet")]
+    WrongState,
+    #[error("Cookies can't be larger than {MAX_COOKIE_SIZE} bytes")]
+    TooLarge,
+}
+
+impl JavaClient {
+    /// Sends the client to `host:port`. Only possible during configuration and play.
+    pub async fn transfer(&self, host: &str, port: i32) -> Result<(), TransferError> {
+        let port = VarInt(port);
+        match self.connection_state.load() {
+            ConnectionState::Config => {
+                self.enqueue_packet(&config::CTransfer::new(host, &port))
+                    .await;
+            }
+            ConnectionState::Play => self.enqueue_packet(&play::CTransfer::new(host, port)).await,
+            _ => return Err(TransferError::WrongState),
+        }
+        Ok(())
+    }
+
+    /// Stores `payload` on the client under `key`, replacing any previous cookie.
+    /// Only possible during configuration and play.
+    pub async fn store_cookie(
+        &self,
+        key: &ResourceLocation,
+        payload: &[u8],
+    ) -> Result<(), TransferError> {
+        if payload.len() > MAX_COOKIE_SIZE {
+            return Err(TransferError::TooLarge);
+        }
+        match self.connection_state.load() {
+            ConnectionState::Config => {
+                self.enqueue_packet(&config::CStoreCookie::new(key, payload))
+                    .await;
+            }
+            ConnectionState::Play => {
+                self.enqueue_packet(&play::CStoreCookie::new(key, payload))
+                    .await;
+            }
+            _ => return Err(TransferError::WrongState),
+        }
+        Ok(())
+    }
+
+    /// Asks the client for the cookie stored under `key` and waits for its answer.
+    ///
+    /// Returns `Ok(None)` if the client has no such cookie or does not answer within `timeout`.
+    pub async fn request_cookie(
+        &self,
+        key: &ResourceLocation,
+        timeout: Duration,
+    ) -> Result<CookiePayload, TransferError> {
+        let state = self.connection_state.load();
+        if !matches!(
+            state,
+            ConnectionState::Login | ConnectionState::Config | ConnectionState::Play
+        ) {
+            return Err(TransferError::WrongState);
+        }
+        let (send, recv) = oneshot::channel();
+        self.pending_cookies
+            .lock()
+            .unwrap()
+            .push((key.clone(), send));
+        match state {
+            ConnectionState::Login => {
+                self.send_packet_now(&CLoginCookieRequest::new(key)).await;
+            }
+            ConnectionState::Config => {
+                self.enqueue_packet(&config::CCookieRequest::new(key)).await;
+            }
+            _ => {
+                self.enqueue_packet(&play::CPlayCookieRequest::new(key))
+                    .await;
+            }
+        }
+        let payload = tokio::time::timeout(timeout, recv)
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .flatten();
+        // Drops our sender if the client did not answer
+        self.pending_cookies
+            .lock()
+            .unwrap()
+            .retain(|(_, send)| !send.is_closed());
+        Ok(payload)
+    }
+
+    /// Hands a cookie response to the oldest request waiting for `key`.
+    pub(super) fn resolve_cookie(&self, key: &ResourceLocation, payload: CookiePayload) {
+        let mut pending = self.pending_cookies.lock().unwrap();
+        if let Some(index) = pending
+            .iter()
+            .position(|(pending_key, _)| pending_key == key)
+        {
+            let (_, send) = pending.remove(index);
+            let _ = send.send(payload);
+        } else {
+            log::debug!(
+                "Client {} sent cookie {key} that was not requested",
+                self.id
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_cookies_only_verify_unchanged_under_their_key() {
+        let signed = sign_cookie("secret", "lobby:session", b"party=3");
+        assert_eq!(
+            verify_cookie("secret", "lobby:session", &signed),
+            Some(&b"party=3"[..])
+        );
+        assert_eq!(verify_cookie("other", "lobby:session", &signed), None);
+        assert_eq!(verify_cookie("secret", "lobby:rank", &signed), None);
+
+        let mut tampered = signed;
+        tampered[0] ^= 1;
+        assert_eq!(verify_cookie("secret", "lobby:session", &tampered), None);
+        assert_eq!(verify_cookie("secret", "lobby:session", b"short"), None);
+    }
+}
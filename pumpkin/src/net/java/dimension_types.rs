@@ -0,0 +1,79 @@
+//! The dimension types of datapack dimensions, added to the registry sent to clients so that
+//! they know the height, light and time of each dimension.
+//!
+//! Each datapack dimension gets an entry of its own, named like the dimension, at the id of the
+//! dimension. It starts as the vanilla type of the dimension it generates like, which keeps the
+//! sky and music of that dimension and the format of the client's version.
+
+use std::io::Cursor;
+
+use pumpkin_data::dimension::Dimension;
+use pumpkin_data::registry::{Registry, RegistryEntryData};
+use pumpkin_nbt::{Nbt, compound::NbtCompound, deserializer::NbtReadHelper};
+use pumpkin_world::dimension::DatapackDimension;
+
+const DIMENSION_TYPE_REGISTRY: &str = "minecraft:dimension_type";
+
+/// Adds the types of `dimensions` to the dimension types in `registries`.
+pub fn apply(registries: &mut [Registry], dimensions: &[DatapackDimension]) {
+    if dimensions.is_empty() {
+        return;
+    }
+    let Some(types) = registries
+        .iter_mut()
+        .find(|registry| registry.registry_id == DIMENSION_TYPE_REGISTRY)
+    else {
+        return;
+    };
+    for datapack in dimensions {
+        let dimension = &datapack.dimension;
+        // The client knows dimension types by their position in the registry
+        if types.registry_entries.len() != usize::from(dimension.id) {
+            log::error!(
+                "The dimension type of {} doesn't fit its id, clients won't know it",
+                dimension.minecraft_name
+            );
+            return;
+        }
+        let template = types
+            .registry_entries
+            .iter()
+            .find(|entry| entry.entry_id == datapack.generator.minecraft_name)
+            .and_then(|entry| entry.data.as_ref())
+            .and_then(|data| {
+                Nbt::read_unnamed(&mut NbtReadHelper::new(Cursor::new(&data[..]))).ok()
+            });
+        let Some(template) = template else {
+            log::warn!(
+                "Failed to read the dimension type {}",
+                datapack.generator.minecraft_name
+            );
+            return;
+        };
+        let dimension_type = apply_to_type(template.root_tag, dimension);
+        types.registry_entries.push(RegistryEntryData {
+            entry_id: dimension.minecraft_name.to_string(),
+            data: Some(Nbt::from(dimension_type).write_unnamed().to_vec().into()),
+        });
+    }
+}
+
+fn apply_to_type(mut dimension_type: NbtCompound, dimension: &Dimension) -> NbtCompound {
+    dimension_type.put_bool("has_skylight", dimension.has_skylight);
+    dimension_type.put_bool("has_ceiling", dimension.has_ceiling);
+    dimension_type.put_double("coordinate_scale", dimension.coordinate_scale);
+    dimension_type.put_int("min_y", dimension.min_y);
+    dimension_type.put_int("height", dimension.height);
+    dimension_type.put_int("logical_height", dimension.logical_height);
+    dimension_type.put_string("infiniburn", dimension.infiniburn.to_string());
+    dimension_type.put_float("ambient_light", dimension.ambient_light);
+    // Since 1.21.11 the time of day comes from timelines, types only say whether it moves
+    if dimension_type.get("attributes").is_some() {
+        dimension_type.put_bool("has_fixed_time", dimension.fixed_time.is_some());
+    } else if let Some(fixed_time) = dimension.fixed_time {
+        dimension_type.put_long("fixed_time", fixed_time);
+    } else {
+        dimension_type.remove("fixed_time");
+    }
+    dimension_type
+}
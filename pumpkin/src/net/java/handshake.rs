@@ -1,3 +1,5 @@
+use std::sync::atomic::Ordering;
+
 use pumpkin_data::packet::CURRENT_MC_PROTOCOL;
 use pumpkin_protocol::{ConnectionState, java::server::handshake::SHandShake};
 use pumpkin_util::{text::TextComponent, version::MinecraftVersion};
@@ -13,7 +15,14 @@ impl JavaClient {
         self.version.store(MinecraftVersion::from_protocol(version));
 
         log::debug!("Handshake: next state is {:?}", &handshake.next_state);
-        self.connection_state.store(handshake.next_state);
+        // A transfer logs in like any other connection, it is only remembered to be checked
+        // against `accept_transfers` and for plugins
+        if handshake.next_state == ConnectionState::Transfer {
+            self.transferred.store(true, Ordering::Relaxed);
+            self.connection_state.store(ConnectionState::Login);
+        } else {
+            self.connection_state.store(handshake.next_state);
+        }
         if self.connection_state.load() != ConnectionState::Status {
             let protocol = version;
             if protocol < LOWEST_SUPPRORTED_PROTOCOL_VERSION {
@@ -1,3 +1,7 @@
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use pumpkin_config::networking::proxy::ProxyForwarding;
 use pumpkin_protocol::{
     ConnectionState, KnownPack, Label, Link, LinkType,
     java::client::{
@@ -15,7 +19,7 @@ use crate::{
     net::{
         GameProfile,
         authentication::{self, AuthError},
-        is_valid_player_name,
+        floodgate, is_valid_player_name,
         java::JavaClient,
         offline_uuid,
         proxy::{bungeecord, velocity},
@@ -40,19 +44,32 @@ impl JavaClient {
             return;
         }
 
+        if self.transferred.load(Ordering::Relaxed)
+            && !server.advanced_config.networking.transfer.accept_transfers
+        {
+            self.kick(TextComponent::translate(
+                "multiplayer.disconnect.transfers_disabled",
+                [],
+            ))
+            .await;
+            return;
+        }
+
         if !is_valid_player_name(&login_start.name) {
             self.kick(TextComponent::text("Invalid characters in username"))
                 .await;
             return;
         }
+        self.capture_player(
+            &server.advanced_config.networking.capture,
+            &login_start.name,
+        );
         // Default game profile, when no online mode
         // TODO: Make offline UUID
         let mut gameprofile = self.gameprofile.lock().await;
-        let proxy = &server.advanced_config.networking.proxy;
-        if proxy.enabled {
-            if proxy.velocity.enabled {
-                velocity::velocity_login(self).await;
-            } else if proxy.bungeecord.enabled {
+        match self.proxy {
+            ProxyForwarding::Velocity => velocity::velocity_login(self).await,
+            ProxyForwarding::BungeeCord => {
                 match bungeecord::bungeecord_login(
                     &self.address,
                     &self.server_address.lock().await,
@@ -68,38 +85,46 @@ impl JavaClient {
                     Err(error) => self.kick(TextComponent::text(error.to_string())).await,
                 }
             }
-        } else {
-            let id = if server.basic_config.online_mode {
-                login_start.uuid
-            } else {
-                offline_uuid(&login_start.name).expect("This is very not safe and bad")
-            };
-
-            let profile = GameProfile {
-                id,
-                name: login_start.name,
-                properties: vec![],
-                profile_actions: None,
-            };
-
-            if server.advanced_config.networking.packet_compression.enabled {
-                self.enable_compression(server).await;
-            }
+            ProxyForwarding::None | ProxyForwarding::Inherit => {
+                // Bedrock players have no Java account to authenticate or encrypt with
+                let is_floodgate = floodgate::is_trusted_login(
+                    &server.advanced_config.networking.floodgate,
+                    self.address.lock().await.ip(),
+                    &login_start.name,
+                    login_start.uuid,
+                );
+                let id = if server.basic_config.online_mode || is_floodgate {
+                    login_start.uuid
+                } else {
+                    offline_uuid(&login_start.name).expect("This is very not safe and bad")
+                };
+
+                let profile = GameProfile {
+                    id,
+                    name: login_start.name,
+                    properties: vec![],
+                    profile_actions: None,
+                };
+
+                if server.advanced_config.networking.packet_compression.enabled {
+                    self.enable_compression(server).await;
+                }
 
-            if server.basic_config.encryption {
-                let verify_token: [u8; 4] = rand::random();
-                // Wait until we have sent the encryption packet to the client
-                self.send_packet_now(
-                    &server
-                        .encryption_request(&verify_token, server.basic_config.online_mode)
-                        .await,
-                )
-                .await;
-            } else {
-                self.finish_login(&profile).await;
-            }
+                if server.basic_config.encryption && !is_floodgate {
+                    let verify_token: [u8; 4] = rand::random();
+                    // Wait until we have sent the encryption packet to the client
+                    self.send_packet_now(
+                        &server
+                            .encryption_request(&verify_token, server.basic_config.online_mode)
+                            .await,
+                    )
+                    .await;
+                } else {
+                    self.finish_login(&profile).await;
+                }
 
-            *gameprofile = Some(profile);
+                *gameprofile = Some(profile);
+            }
         }
     }
 
@@ -214,12 +239,31 @@ impl JavaClient {
     ) -> Result<GameProfile, AuthError> {
         let hash = server.digest_secret(shared_secret).await;
         let ip = self.address.lock().await.ip();
-        let profile = authentication::authenticate(
-            username,
-            &hash,
-            &ip,
-            &server.advanced_config.networking.authentication,
-        )?;
+        let auth_config = &server.advanced_config.networking.authentication;
+        // While the authentication servers are down, fail right away instead of making every
+        // joining player wait for the timeouts
+        if !server.auth_circuit.allow() {
+            return Err(AuthError::FailedResponse);
+        }
+        // The request blocks, including the DNS lookup, so it must not hold up an async worker
+        let request = tokio::task::spawn_blocking({
+            let username = username.to_string();
+            let auth_config = auth_config.clone();
+            move || authentication::authenticate(&username, &hash, &ip, &auth_config)
+        });
+        let timeout = Duration::from_millis(
+            u64::from(auth_config.connect_timeout) + u64::from(auth_config.read_timeout),
+        );
+        let result = match tokio::time::timeout(timeout, request).await {
+            Ok(Ok(result)) => result,
+            // Hung despite the timeouts of the request, or panicked
+            Ok(Err(_)) | Err(_) => Err(AuthError::FailedResponse),
+        };
+        match &result {
+            Err(error) if error.is_upstream_failure() => server.auth_circuit.record_failure(),
+            _ => server.auth_circuit.record_success(),
+        }
+        let profile = result?;
 
         // Check if the player should join
         if let Some(actions) = &profile.profile_actions {
@@ -259,13 +303,13 @@ impl JavaClient {
         Ok(profile)
     }
 
-    pub fn handle_login_cookie_response(&self, packet: &SLoginCookieResponse) {
-        // TODO: allow plugins to access this
+    pub fn handle_login_cookie_response(&self, packet: SLoginCookieResponse) {
         log::debug!(
             "Received cookie_response[login]: key: \"{}\", payload_length: \"{:?}\"",
             packet.key,
             packet.payload.as_ref().map(|p| p.len())
         );
+        self.resolve_cookie(&packet.key, packet.payload);
     }
     pub async fn handle_plugin_response(
         &self,
@@ -274,7 +318,7 @@ impl JavaClient {
     ) {
         log::debug!("Handling plugin");
         let velocity_config = &server.advanced_config.networking.proxy.velocity;
-        if velocity_config.enabled {
+        if self.proxy == ProxyForwarding::Velocity {
             let mut address = self.address.lock().await;
             match velocity::receive_velocity_plugin_response(
                 address.port(),
@@ -1,17 +1,22 @@
 use std::net::SocketAddr;
+use std::sync::atomic::AtomicBool;
 use std::{io::Write, sync::Arc};
 
-use bytes::Bytes;
+use bytes::{BufMut, Bytes};
 use crossbeam::atomic::AtomicCell;
 use pumpkin_config::networking::compression::CompressionInfo;
+use pumpkin_config::networking::proxy::ProxyForwarding;
 use pumpkin_data::packet::CURRENT_MC_PROTOCOL;
+use pumpkin_protocol::buffer_pool::PACKET_BUFFERS;
+use pumpkin_protocol::java::capture::CaptureDirection;
 use pumpkin_protocol::java::server::play::{
     SChangeGameMode, SChatCommand, SChatMessage, SChunkBatch, SClickSlot, SClientCommand,
     SClientInformationPlay, SClientTickEnd, SCloseContainer, SCommandSuggestion, SConfirmTeleport,
-    SCookieResponse as SPCookieResponse, SCustomPayload, SInteract, SKeepAlive, SPickItemFromBlock,
-    SPlayPingRequest, SPlayerAbilities, SPlayerAction, SPlayerCommand, SPlayerInput, SPlayerLoaded,
-    SPlayerPosition, SPlayerPositionRotation, SPlayerRotation, SPlayerSession, SSetCommandBlock,
-    SSetCreativeSlot, SSetHeldItem, SSetPlayerGround, SSwingArm, SUpdateSign, SUseItem, SUseItemOn,
+    SCookieResponse as SPCookieResponse, SCustomPayload, SDebugSubscriptionRequest, SInteract,
+    SKeepAlive, SPickItemFromBlock, SPlayPingRequest, SPlayerAbilities, SPlayerAction,
+    SPlayerCommand, SPlayerInput, SPlayerLoaded, SPlayerPosition, SPlayerPositionRotation,
+    SPlayerRotation, SPlayerSession, SSetCommandBlock, SSetCreativeSlot, SSetHeldItem,
+    SSetPlayerGround, SSwingArm, SUpdateSign, SUseItem, SUseItemOn,
 };
 use pumpkin_protocol::packet::MultiVersionJavaPacket;
 use pumpkin_protocol::{
@@ -36,6 +41,7 @@ use pumpkin_protocol::{
     },
     ser::{NetworkWriteExt, ReadingError, WritingError},
 };
+use pumpkin_util::resource_location::ResourceLocation;
 use pumpkin_util::text::TextComponent;
 use pumpkin_util::version::MinecraftVersion;
 use tokio::{
@@ -48,21 +54,32 @@ use tokio::{
 };
 use tokio::{
     sync::mpsc::{Receiver, Sender},
+    sync::oneshot,
     task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::TaskTracker;
 
+pub mod biome_effects;
+pub mod capture;
 pub mod config;
+pub mod cookie;
+pub mod dimension_types;
 pub mod handshake;
 pub mod login;
 pub mod play;
 pub mod status;
 
 use crate::entity::player::Player;
+use crate::net::java::capture::PacketCapture;
+use crate::net::java::cookie::CookiePayload;
+use crate::net::traffic::{ConnectionTraffic, TrafficDirection};
 use crate::net::{GameProfile, PlayerConfig};
 use crate::{error::PumpkinError, net::EncryptionError, server::Server};
 
+/// Packets written to the connection at once, at most.
+const MAX_OUTGOING_BATCH: usize = 256;
+
 pub struct JavaClient {
     pub id: u64,
     pub version: AtomicCell<MinecraftVersion>,
@@ -76,8 +93,18 @@ pub struct JavaClient {
     pub connection_state: AtomicCell<ConnectionState>,
     /// The client's IP address.
     pub address: Mutex<SocketAddr>,
+    /// The player info forwarding expected by the listener the client connected to.
+    pub proxy: ProxyForwarding,
+    /// Whether the client was transferred here from another server.
+    pub transferred: AtomicBool,
+    /// Cookie requests waiting for the client's response, oldest first.
+    pending_cookies: std::sync::Mutex<Vec<(ResourceLocation, oneshot::Sender<CookiePayload>)>>,
     /// The client's brand or modpack information, Optional.
     pub brand: Mutex<Option<String>>,
+    /// Bytes and packets exchanged with this client, per packet type.
+    pub traffic: ConnectionTraffic,
+    /// The running packet capture, if this connection is being captured.
+    capture: std::sync::Mutex<Option<PacketCapture>>,
     /// A collection of tasks associated with this client. The tasks await completion when removing the client.
     tasks: TaskTracker,
     /// An notifier that is triggered when this client is closed.
@@ -100,7 +127,12 @@ pub enum PacketHandlerResult {
 
 impl JavaClient {
     #[must_use]
-    pub fn new(tcp_stream: TcpStream, address: SocketAddr, id: u64) -> Self {
+    pub fn new(
+        tcp_stream: TcpStream,
+        address: SocketAddr,
+        id: u64,
+        proxy: ProxyForwarding,
+    ) -> Self {
         let (read, write) = tcp_stream.into_split();
         let (send, recv) = tokio::sync::mpsc::channel(128);
         Self {
@@ -109,6 +141,9 @@ impl JavaClient {
             config: Mutex::new(None),
             server_address: Mutex::new(String::new()),
             address: Mutex::new(address),
+            proxy,
+            transferred: AtomicBool::new(false),
+            pending_cookies: std::sync::Mutex::new(Vec::new()),
             connection_state: AtomicCell::new(ConnectionState::HandShake),
             close_token: CancellationToken::new(),
             tasks: TaskTracker::new(),
@@ -118,6 +153,8 @@ impl JavaClient {
             network_writer: Arc::new(Mutex::new(TCPNetworkEncoder::new(BufWriter::new(write)))),
             network_reader: Mutex::new(TCPNetworkDecoder::new(BufReader::new(read))),
             brand: Mutex::new(None),
+            traffic: ConnectionTraffic::new(),
+            capture: std::sync::Mutex::new(None),
         }
     }
     pub async fn set_encryption(
@@ -227,10 +264,8 @@ impl JavaClient {
     }
 
     pub async fn enqueue_packet<P: ClientPacket>(&self, packet: &P) {
-        let mut buf = Vec::new();
-        let writer = &mut buf;
-        self.write_packet(packet, writer).unwrap();
-        self.enqueue_packet_data(buf.into()).await;
+        self.enqueue_packet_data(self.serialize_packet(packet))
+            .await;
     }
 
     /// Queues a clientbound packet to be sent to the connected client. Queued chunks are sent
@@ -240,6 +275,9 @@ impl JavaClient {
     ///
     /// * `packet`: A reference to a packet object implementing the `ClientPacket` trait.
     pub async fn enqueue_packet_data(&self, packet_data: Bytes) {
+        self.traffic
+            .record_outbound(self.connection_state.load(), &packet_data);
+        self.capture_packet(CaptureDirection::Clientbound, &packet_data);
         if let Err(err) = self.outgoing_packet_queue_send.send(packet_data).await {
             // This is expected to fail if we are closed
             if !self.close_token.is_cancelled() {
@@ -265,7 +303,16 @@ impl JavaClient {
             },
             packet_result = network_reader.get_raw_packet() => {
                 match packet_result {
-                    Ok(packet) => Some(packet),
+                    Ok(packet) => {
+                        self.traffic.record(
+                            TrafficDirection::Inbound,
+                            self.connection_state.load(),
+                            packet.id,
+                            VarInt(packet.id).written_size() + packet.payload.len(),
+                        );
+                        self.capture_serverbound(packet.id, &packet.payload);
+                        Some(packet)
+                    }
                     Err(err) => {
                         if !matches!(err, PacketDecodeError::ConnectionClosed) {
                             log::warn!("Failed to decode packet from client {}: {}", self.id, err);
@@ -300,20 +347,15 @@ impl JavaClient {
     }
 
     pub async fn send_packet_now<P: ClientPacket>(&self, packet: &P) {
-        let mut packet_buf = Vec::new();
-        let writer = &mut packet_buf;
-        self.write_packet(packet, writer).unwrap();
-        self.send_packet_now_data(packet_buf).await;
+        self.send_packet_now_data(self.serialize_packet(packet))
+            .await;
     }
 
-    pub async fn send_packet_now_data(&self, packet: Vec<u8>) {
-        if let Err(err) = self
-            .network_writer
-            .lock()
-            .await
-            .write_packet(packet.into())
-            .await
-        {
+    pub async fn send_packet_now_data(&self, packet: Bytes) {
+        self.traffic
+            .record_outbound(self.connection_state.load(), &packet);
+        self.capture_packet(CaptureDirection::Clientbound, &packet);
+        if let Err(err) = self.network_writer.lock().await.write_packet(packet).await {
             // It is expected that the packet will fail if we are closed
             if !self.close_token.is_cancelled() {
                 log::warn!("Failed to send packet to client {}: {}", self.id, err);
@@ -335,6 +377,15 @@ impl JavaClient {
         packet.write_packet_data(write, &version)
     }
 
+    /// Serializes a packet into a pooled buffer, which it shares with other small packets.
+    pub fn serialize_packet<P: ClientPacket>(&self, packet: &P) -> Bytes {
+        let mut buf = PACKET_BUFFERS.take();
+        self.write_packet(packet, (&mut buf).writer()).unwrap();
+        let packet = buf.split().freeze();
+        PACKET_BUFFERS.put(buf);
+        packet
+    }
+
     /// Handles an incoming packet, routing it to the appropriate handler based on the current connection state.
     ///
     /// This function takes a `RawPacket` and routes it to the corresponding handler based on the current connection state.
@@ -427,17 +478,21 @@ impl JavaClient {
         let writer = self.network_writer.clone();
         let id = self.id;
         self.spawn_task(async move {
+            let mut batch = Vec::with_capacity(MAX_OUTGOING_BATCH);
             while !close_token.is_cancelled() {
-                let recv_result = tokio::select! {
-                    () =  close_token.cancelled() => None,
-                    res = packet_receiver.recv() => res,
+                // Everything queued in the meantime is written together and flushed once
+                let received = tokio::select! {
+                    () =  close_token.cancelled() => 0,
+                    received = packet_receiver.recv_many(&mut batch, MAX_OUTGOING_BATCH) => received,
                 };
 
-                let Some(packet_data) = recv_result else {
+                if received == 0 {
                     break;
-                };
+                }
 
-                if let Err(err) = writer.lock().await.write_packet(packet_data).await {
+                let result = writer.lock().await.write_packets(&batch).await;
+                batch.clear();
+                if let Err(err) = result {
                     // It is expected that the packet will fail if we are closed
                     if !close_token.is_cancelled() {
                         log::warn!("Failed to send packet to client {id}: {err}",);
@@ -492,7 +547,7 @@ impl JavaClient {
                 self.handle_login_acknowledged(server).await;
             }
             id if id == SLoginCookieResponse::PACKET_ID => {
-                self.handle_login_cookie_response(&SLoginCookieResponse::read(payload)?);
+                self.handle_login_cookie_response(SLoginCookieResponse::read(payload)?);
             }
             _ => {
                 log::error!(
@@ -525,10 +580,11 @@ impl JavaClient {
                 return Ok(Some(self.handle_config_acknowledged(server).await));
             }
             id if id == SKnownPacks::PACKET_ID => {
-                self.handle_known_packs(SKnownPacks::read(payload)?).await;
+                self.handle_known_packs(server, SKnownPacks::read(payload)?)
+                    .await;
             }
             id if id == SConfigCookieResponse::PACKET_ID => {
-                self.handle_config_cookie_response(&SConfigCookieResponse::read(payload)?);
+                self.handle_config_cookie_response(SConfigCookieResponse::read(payload)?);
             }
             id if id == SConfigResourcePack::PACKET_ID => {
                 self.handle_resource_pack_response(server, SConfigResourcePack::read(payload)?)
@@ -575,7 +631,7 @@ impl JavaClient {
                     .await;
             }
             id if id == SClientCommand::PACKET_ID => {
-                self.handle_client_status(player, SClientCommand::read(payload)?)
+                self.handle_client_status(server, player, SClientCommand::read(payload)?)
                     .await;
             }
             id if id == SPlayerInput::PACKET_ID => {
@@ -669,7 +725,7 @@ impl JavaClient {
                     .await;
             }
             id if id == SPCookieResponse::PACKET_ID => {
-                self.handle_cookie_response(&SPCookieResponse::read(payload)?);
+                self.handle_cookie_response(SPCookieResponse::read(payload)?);
             }
             id if id == SCloseContainer::PACKET_ID => {
                 self.handle_close_container(player, server, SCloseContainer::read(payload)?)
@@ -679,6 +735,14 @@ impl JavaClient {
                 self.handle_chunk_batch(player, SChunkBatch::read(payload)?)
                     .await;
             }
+            id if id == SDebugSubscriptionRequest::PACKET_ID => {
+                Self::handle_debug_subscription_request(
+                    player,
+                    server,
+                    &SDebugSubscriptionRequest::read(payload)?,
+                )
+                .await;
+            }
             id if id == SPlayerSession::PACKET_ID => {
                 self.handle_chat_session_update(player, server, SPlayerSession::read(payload)?)
                     .await;
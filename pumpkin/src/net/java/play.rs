@@ -14,6 +14,7 @@ use crate::block::registry::BlockActionResult;
 use crate::block::{self, BlockIsReplacing};
 use crate::command::CommandSender;
 use crate::entity::EntityBase;
+use crate::entity::chat_limiter::MessageKind;
 use crate::entity::player::{ChatMode, ChatSession, Player};
 use crate::error::PumpkinError;
 use crate::net::PlayerConfig;
@@ -24,8 +25,11 @@ use crate::plugin::player::player_chat::PlayerChatEvent;
 use crate::plugin::player::player_command_send::PlayerCommandSendEvent;
 use crate::plugin::player::player_interact_event::{InteractAction, PlayerInteractEvent};
 use crate::plugin::player::player_move::PlayerMoveEvent;
+use crate::plugin::player::player_move_violation::PlayerMoveViolationEvent;
+use crate::server::chunk_limits::ChunkLimitKind;
 use crate::server::{Server, seasonal_events};
 use crate::world::{World, chunker};
+use pumpkin_config::movement::MovementEnforcement;
 use pumpkin_data::block_properties::{
     BlockProperties, CommandBlockLikeProperties, WaterLikeProperties,
 };
@@ -46,11 +50,11 @@ use pumpkin_protocol::java::client::play::{
 use pumpkin_protocol::java::server::play::{
     Action, ActionType, CommandBlockMode, FLAG_ON_GROUND, SChangeGameMode, SChatCommand,
     SChatMessage, SChunkBatch, SClientCommand, SClientInformationPlay, SCloseContainer,
-    SCommandSuggestion, SConfirmTeleport, SCookieResponse as SPCookieResponse, SInteract,
-    SKeepAlive, SPickItemFromBlock, SPlayPingRequest, SPlayerAbilities, SPlayerAction,
-    SPlayerCommand, SPlayerInput, SPlayerPosition, SPlayerPositionRotation, SPlayerRotation,
-    SPlayerSession, SSetCommandBlock, SSetCreativeSlot, SSetHeldItem, SSetPlayerGround, SSwingArm,
-    SUpdateSign, SUseItem, SUseItemOn, Status,
+    SCommandSuggestion, SConfirmTeleport, SCookieResponse as SPCookieResponse,
+    SDebugSubscriptionRequest, SInteract, SKeepAlive, SPickItemFromBlock, SPlayPingRequest,
+    SPlayerAbilities, SPlayerAction, SPlayerCommand, SPlayerInput, SPlayerPosition,
+    SPlayerPositionRotation, SPlayerRotation, SPlayerSession, SSetCommandBlock, SSetCreativeSlot,
+    SSetHeldItem, SSetPlayerGround, SSwingArm, SUpdateSign, SUseItem, SUseItemOn, Status,
 };
 use pumpkin_util::math::vector3::Vector3;
 use pumpkin_util::math::{polynomial_rolling_hash, position::BlockPos, wrap_degrees};
@@ -287,6 +291,17 @@ impl JavaClient {
             Self::clamp_vertical(position.y),
             Self::clamp_horizontal(position.z),
         );
+        if !self
+            .validate_move(
+                player,
+                server,
+                position,
+                packet.collision & FLAG_ON_GROUND != 0,
+            )
+            .await
+        {
+            return;
+        }
 
         send_cancellable! {{
             server;
@@ -358,14 +373,7 @@ impl JavaClient {
             }
 
             'cancelled: {
-                self.enqueue_packet(&CPlayerPosition::new(
-                    player.teleport_id_count.load(Ordering::Relaxed).into(),
-                    player.living_entity.entity.pos.load(),
-                    Vector3::new(0.0, 0.0, 0.0),
-                    player.living_entity.entity.yaw.load(),
-                    player.living_entity.entity.pitch.load(),
-                    Vec::new(),
-                )).await;
+                self.force_tp(player, player.living_entity.entity.pos.load()).await;
             }
         }}
     }
@@ -401,6 +409,17 @@ impl JavaClient {
             Self::clamp_vertical(position.y),
             Self::clamp_horizontal(position.z),
         );
+        if !self
+            .validate_move(
+                player,
+                server,
+                position,
+                packet.collision & FLAG_ON_GROUND != 0,
+            )
+            .await
+        {
+            return;
+        }
 
         send_cancellable! {{
             server;
@@ -494,17 +513,109 @@ impl JavaClient {
             }
 
             'cancelled: {
-                self.force_tp(player, position).await;
+                self.force_tp(player, player.living_entity.entity.pos.load()).await;
             }
         }}
     }
 
+    /// Checks a move of `player` to `to` against the movement config and enforces violations.
+    /// Returns whether the move may go ahead.
+    ///
+    /// Moves are ignored while a teleport is pending, as the client didn't know about it yet.
+    async fn validate_move(
+        &self,
+        player: &Arc<Player>,
+        server: &Server,
+        to: Vector3<f64>,
+        on_ground: bool,
+    ) -> bool {
+        if player.awaiting_teleport.lock().await.is_some() {
+            return false;
+        }
+        let from = player.living_entity.entity.pos.load();
+        if !self.stays_inside_border(player, from, to).await {
+            return false;
+        }
+        let config = &server.advanced_config.movement;
+        let Some(violation) = player
+            .movement_validator
+            .check(player, from, to, on_ground, config)
+            .await
+        else {
+            return true;
+        };
+
+        let event = server
+            .plugin_manager
+            .fire(PlayerMoveViolationEvent::new(
+                player.clone(),
+                violation,
+                from,
+                to,
+                config.enforcement,
+            ))
+            .await;
+        if event.cancelled {
+            return true;
+        }
+        log::warn!(
+            "{} moved wrongly ({violation:?}) from {:.2} {:.2} {:.2} to {:.2} {:.2} {:.2}",
+            player.gameprofile.name,
+            from.x,
+            from.y,
+            from.z,
+            to.x,
+            to.y,
+            to.z
+        );
+        match event.enforcement {
+            MovementEnforcement::LogOnly => true,
+            MovementEnforcement::Rubberband => {
+                self.force_tp(player, from).await;
+                false
+            }
+            MovementEnforcement::Kick => {
+                self.kick(TextComponent::translate(
+                    "multiplayer.disconnect.invalid_player_movement",
+                    [],
+                ))
+                .await;
+                false
+            }
+        }
+    }
+
+    /// Keeps players that are inside the world border from crossing it, like clients that collide
+    /// with it. A move across it is clamped to the border.
+    async fn stays_inside_border(
+        &self,
+        player: &Arc<Player>,
+        from: Vector3<f64>,
+        to: Vector3<f64>,
+    ) -> bool {
+        if player.gamemode.load() == GameMode::Spectator {
+            return true;
+        }
+        let world = player.world();
+        let clamped = {
+            let border = world.worldborder.lock().await;
+            if !border.contains(from.x, from.z) || border.contains(to.x, to.z) {
+                return true;
+            }
+            border.clamp(to.x, to.z)
+        };
+        self.force_tp(player, Vector3::new(clamped.0, to.y, clamped.1))
+            .await;
+        false
+    }
+
+    /// Moves the client of `player` back to `position`, e.g. after a rejected move.
     pub async fn force_tp(&self, player: &Arc<Player>, position: Vector3<f64>) {
         let teleport_id = player.teleport_id_count.fetch_add(1, Ordering::Relaxed) + 1;
         *player.awaiting_teleport.lock().await = Some((teleport_id.into(), position));
         self.enqueue_packet(&CPlayerPosition::new(
             teleport_id.into(),
-            player.living_entity.entity.pos.load(),
+            position,
             Vector3::new(0.0, 0.0, 0.0),
             player.living_entity.entity.yaw.load(),
             player.living_entity.entity.pitch.load(),
@@ -556,6 +667,13 @@ impl JavaClient {
         command: &SChatCommand,
     ) {
         player.update_last_action_time();
+        if !player
+            .chat_limiter
+            .allow(player, server, MessageKind::Command, &command.command)
+            .await
+        {
+            return;
+        }
         let player_clone = player.clone();
         let server_clone = server.clone();
         send_cancellable! {{
@@ -761,7 +879,10 @@ impl JavaClient {
             let entity = &player.living_entity.entity;
             match action {
                 Action::StartSprinting => {
-                    if !entity.sprinting.load(Ordering::Relaxed) {
+                    if !player.can_sprint().await {
+                        // Resend the flags so the client stops sprinting too
+                        entity.set_sprinting(false).await;
+                    } else if !entity.sprinting.load(Ordering::Relaxed) {
                         entity.set_sprinting(true).await;
                     }
                 }
@@ -776,11 +897,11 @@ impl JavaClient {
                     log::debug!("todo");
                 }
                 Action::StartFlyingElytra => {
-                    let fall_flying = entity.check_fall_flying();
-                    if entity.fall_flying.load(Ordering::Relaxed) != fall_flying {
-                        entity.set_fall_flying(fall_flying).await;
+                    if player.can_start_fall_flying().await {
+                        entity.set_fall_flying(true).await;
+                        player.update_pose().await;
                     }
-                } // TODO
+                }
             }
         } else {
             self.kick(TextComponent::text("Invalid player command"))
@@ -792,6 +913,7 @@ impl JavaClient {
         let sneak = input.input & SPlayerInput::SNEAK != 0;
         if player.get_entity().sneaking.load(Ordering::Relaxed) != sneak {
             player.get_entity().set_sneaking(sneak).await;
+            player.update_pose().await;
         }
     }
 
@@ -876,6 +998,13 @@ impl JavaClient {
             }
             return;
         }
+        if !player
+            .chat_limiter
+            .allow(player, server, MessageKind::Chat, &chat_message.message)
+            .await
+        {
+            return;
+        }
 
         send_cancellable! {{
             server;
@@ -890,6 +1019,7 @@ impl JavaClient {
                     Some(m) => m,
                     None => event.message.clone(),
                 };
+                server.chat_bridge.relay_chat(&gameprofile.name, &message);
 
                 let decorated_message = TextComponent::chat_decorated(
                     &config.chat.format,
@@ -1148,15 +1278,29 @@ impl JavaClient {
         }
     }
 
-    pub async fn handle_client_status(&self, player: &Arc<Player>, client_status: SClientCommand) {
+    pub async fn handle_client_status(
+        &self,
+        server: &Server,
+        player: &Arc<Player>,
+        client_status: SClientCommand,
+    ) {
         player.update_last_action_time();
         match client_status.action_id.0 {
             0 => {
                 // Perform respawn
+                if player.won_game.swap(false, Ordering::Relaxed) {
+                    // Back from the credits, alive and with everything
+                    player.world().clone().respawn_player(player, true).await;
+                    return;
+                }
                 if player.living_entity.health.load() > 0.0 {
                     return;
                 }
                 player.world().clone().respawn_player(player, false).await;
+                // Players in hardcore only have one life
+                if server.basic_config.hardcore {
+                    player.set_gamemode(GameMode::Spectator).await;
+                }
 
                 let screen_handler = player.current_screen_handler.lock().await;
                 let mut screen_handler = screen_handler.lock().await;
@@ -1196,6 +1340,7 @@ impl JavaClient {
         let player_entity = &player.living_entity.entity;
         if player_entity.sneaking.load(Ordering::Relaxed) != sneaking {
             player_entity.set_sneaking(sneaking).await;
+            player.update_pose().await;
         }
         let Ok(action) = ActionType::try_from(interact.r#type.0) else {
             self.kick(TextComponent::text("Invalid action type")).await;
@@ -1259,14 +1404,49 @@ impl JavaClient {
             }
             ActionType::Interact | ActionType::InteractAt => {
                 // TODO: split this up
-                let entity = player.world().get_player_by_id(entity_id.0);
-                if let Some(entity) = entity {
+                let world = player.world();
+                if let Some(entity) = world.get_player_by_id(entity_id.0) {
                     let held = player.inventory.held_item();
                     let mut stack = held.lock().await;
                     server
                         .item_registry
                         .use_on_entity(&mut stack, player, entity)
                         .await;
+                } else if let Some(entity) = world.get_entity_by_id(entity_id.0) {
+                    let (held, slot_index) = if interact.hand.is_some_and(|hand| hand.0 == 1) {
+                        (
+                            player.inventory.off_hand_item().await,
+                            PlayerInventory::OFF_HAND_SLOT,
+                        )
+                    } else {
+                        (
+                            player.inventory.held_item(),
+                            player.inventory.get_selected_slot() as usize,
+                        )
+                    };
+                    let mut stack = held.lock().await;
+                    let before = stack.clone();
+                    if action == ActionType::InteractAt {
+                        // The client only sends a plain interaction afterwards if the entity
+                        // ignored this one
+                        if let Some(hit) = interact.target_position {
+                            let hit =
+                                Vector3::new(f64::from(hit.x), f64::from(hit.y), f64::from(hit.z));
+                            entity.interact_at(player, hit, &mut stack).await;
+                        }
+                    } else if !entity.interact(player, &mut stack).await {
+                        server
+                            .item_registry
+                            .use_on_entity(&mut stack, player, entity)
+                            .await;
+                    }
+
+                    // The client does not predict every change entities make to the held item
+                    let after = stack.clone();
+                    drop(stack);
+                    if !after.are_equal(&before) {
+                        player.sync_hand_slot(slot_index, after).await;
+                    }
                 }
             }
         }
@@ -1895,6 +2075,23 @@ impl JavaClient {
         );
     }
 
+    /// Starts or stops feeding the tick time chart of the debug screen. Players without
+    /// `pumpkin:debug.tick_time` keep an empty chart, like players on vanilla servers who are not
+    /// operators.
+    pub async fn handle_debug_subscription_request(
+        player: &Player,
+        server: &Server,
+        packet: &SDebugSubscriptionRequest,
+    ) {
+        let tick_time = packet
+            .subscriptions
+            .contains(&SDebugSubscriptionRequest::TICK_TIME)
+            && player
+                .has_permission(server, "pumpkin:debug.tick_time")
+                .await;
+        player.debug_tick_time.store(tick_time, Ordering::Relaxed);
+    }
+
     pub async fn handle_close_container(
         &self,
         player: &Player,
@@ -1937,13 +2134,13 @@ impl JavaClient {
         self.enqueue_packet(&response).await;
     }
 
-    pub fn handle_cookie_response(&self, packet: &SPCookieResponse) {
-        // TODO: allow plugins to access this
+    pub fn handle_cookie_response(&self, packet: SPCookieResponse) {
         log::debug!(
             "Received cookie_response[play]: key: \"{}\", payload_length: \"{:?}\"",
             packet.key,
             packet.payload.as_ref().map(|p| p.len())
         );
+        self.resolve_cookie(&packet.key, packet.payload);
     }
 
     #[expect(clippy::too_many_lines)]
@@ -2135,9 +2332,30 @@ impl JavaClient {
             return Ok(false);
         }
 
-        let _replaced_id = world
+        if state.block_entity_type != u16::MAX
+            && !server
+                .chunk_limits
+                .allow(
+                    &world,
+                    final_block_pos.chunk_position(),
+                    ChunkLimitKind::BlockEntity,
+                )
+                .await
+        {
+            return Ok(false);
+        }
+
+        let replaced_id = world
             .set_block_state(&final_block_pos, new_state, BlockFlags::NOTIFY_ALL)
             .await;
+        server.block_log.record(
+            player.gameprofile.id,
+            &player.gameprofile.name,
+            world.dimension.id,
+            final_block_pos,
+            replaced_id,
+            new_state,
+        );
         self.send_packet_now(&CBlockUpdate::new(
             final_block_pos,
             VarInt(i32::from(new_state)),
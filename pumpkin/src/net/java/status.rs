@@ -1,41 +1,78 @@
+use pumpkin_data::packet::CURRENT_MC_PROTOCOL;
 use pumpkin_protocol::{
     Players, StatusResponse, Version,
     java::client::status::{CPingResponse, CStatusResponse},
     java::server::status::SStatusPingRequest,
 };
+use pumpkin_util::version::MinecraftVersion;
+use pumpkin_world::{CURRENT_MC_VERSION, LOWEST_SUPPRORTED_PROTOCOL_VERSION};
+use time::OffsetDateTime;
 
 use crate::{
-    net::java::JavaClient, plugin::api::events::Payload,
-    plugin::api::events::server::server_list_ping::ServerListPingEvent, server::Server,
+    net::java::JavaClient,
+    plugin::api::events::Payload,
+    plugin::api::events::server::server_list_ping::ServerListPingEvent,
+    server::{Server, connection_cache::CachedStatus},
 };
 
+/// The protocol of a client of `version`, if it can join the server.
+fn supported_protocol(version: MinecraftVersion) -> Option<u32> {
+    u32::try_from(version.protocol_version())
+        .ok()
+        .filter(|protocol| {
+            (LOWEST_SUPPRORTED_PROTOCOL_VERSION..=CURRENT_MC_PROTOCOL).contains(protocol)
+        })
+}
+
 impl JavaClient {
     pub async fn handle_status_request(&self, server: &Server) {
         log::debug!("Handling status request");
+        let config = &server.advanced_config.networking.server_list;
+        let client_version = self.version.load();
+
         let status_lock = server.get_status();
         let cached = status_lock.lock().await;
         let response = &cached.status_response;
 
-        let motd = response.description.clone();
+        let motd =
+            CachedStatus::current_motd(config, &response.description, OffsetDateTime::now_utc());
         let (max_players, online_players) = response
             .players
             .as_ref()
             .map_or((0, 0), |p| (p.max, p.online));
-        let (version_name, protocol_version) = response
+        let (mut version_name, mut protocol_version) = response
             .version
             .as_ref()
             .map_or_else(|| (String::new(), 0), |v| (v.name.clone(), v.protocol));
         let favicon = response.favicon.clone();
         drop(cached);
+        let online_players = if server.redis.shows_global_player_count() {
+            server.redis.global_player_count(online_players)
+        } else {
+            online_players
+        };
+
+        if let Some(protocol) = supported_protocol(client_version) {
+            // Older supported clients would otherwise see the server as incompatible
+            protocol_version = protocol;
+        } else if !config.incompatible_version_text.is_empty() {
+            version_name = config
+                .incompatible_version_text
+                .replace("{version}", CURRENT_MC_VERSION);
+        }
 
         let event = server
             .plugin_manager
             .fire(ServerListPingEvent::new(
+                *self.address.lock().await,
+                client_version,
                 motd,
                 max_players,
                 online_players,
+                config.player_sample.clone(),
                 version_name,
                 protocol_version,
+                favicon,
             ))
             .await;
 
@@ -52,10 +89,10 @@ impl JavaClient {
             players: Some(Players {
                 max: event.max_players,
                 online: event.online_players,
-                sample: vec![],
+                sample: CachedStatus::sample_lines(&event.player_sample),
             }),
             description: event.motd,
-            favicon,
+            favicon: event.favicon,
             enforce_secure_chat: true,
         })
         .expect("Failed to serialize status response");
@@ -0,0 +1,132 @@
+//! Binding of the Java Edition listeners: `java_edition_address` plus the additional
+//! `networking.listeners`, each expecting its own proxy forwarding.
+
+use std::io::{self, ErrorKind};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use pumpkin_config::BasicConfiguration;
+use pumpkin_config::networking::NetworkingConfig;
+use pumpkin_config::networking::proxy::ProxyForwarding;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::proxy_protocol::ProxyProtocol;
+
+/// Pending connections each listener queues before they are accepted.
+const BACKLOG: i32 = 1024;
+
+pub struct JavaListener {
+    pub listener: TcpListener,
+    /// The resolved forwarding, never `Inherit`.
+    pub proxy: ProxyForwarding,
+    /// Set if connections start with a PROXY protocol header.
+    pub proxy_protocol: Option<Arc<ProxyProtocol>>,
+}
+
+impl JavaListener {
+    /// The bound address, which tells the actual port when `0` was configured.
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener
+            .local_addr()
+            .expect("Unable to get the address of the server!")
+    }
+}
+
+/// Binds the primary listener followed by the additional ones, exiting the process if one of
+/// the addresses can't be used.
+#[must_use]
+pub fn bind_java_listeners(
+    basic_config: &BasicConfiguration,
+    networking: &NetworkingConfig,
+) -> Vec<JavaListener> {
+    let mut addresses = vec![(
+        basic_config.java_edition_address,
+        networking.proxy.forwarding(),
+        networking.proxy_protocol.enabled,
+    )];
+    addresses.extend(networking.listeners.iter().map(|listener| {
+        (
+            listener.address,
+            listener.proxy.resolve(&networking.proxy),
+            listener.proxy_protocol,
+        )
+    }));
+    let proxy_protocol = Arc::new(ProxyProtocol::new(&networking.proxy_protocol));
+
+    let mut listeners = Vec::with_capacity(addresses.len());
+    for &(address, proxy, expects_header) in &addresses {
+        // Linux accepts IPv4 on IPv6 sockets by default, which would conflict with an IPv4
+        // listener on the same port
+        let v6_only = address.is_ipv6()
+            && addresses
+                .iter()
+                .any(|(other, _, _)| other.is_ipv4() && other.port() == address.port());
+        let listener = bind(address, v6_only).unwrap_or_else(|e| exit_on_bind_error(address, &e));
+        if !listeners.is_empty() {
+            log::info!("Also listening on {address} with {proxy:?} proxy forwarding");
+        }
+        if expects_header {
+            log::info!("Expecting PROXY protocol headers on {address}");
+        }
+        listeners.push(JavaListener {
+            listener,
+            proxy,
+            proxy_protocol: expects_header.then(|| proxy_protocol.clone()),
+        });
+    }
+    listeners
+}
+
+fn bind(address: SocketAddr, v6_only: bool) -> io::Result<TcpListener> {
+    let socket = Socket::new(
+        Domain::for_address(address),
+        Type::STREAM,
+        Some(Protocol::TCP),
+    )?;
+    if address.is_ipv6() {
+        socket.set_only_v6(v6_only)?;
+    }
+    // Same as `TcpListener::bind`, allows restarting while old connections are in TIME_WAIT
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.bind(&address.into())?;
+    socket.listen(BACKLOG)?;
+    socket.set_nonblocking(true)?;
+    TcpListener::from_std(socket.into())
+}
+
+fn exit_on_bind_error(address: SocketAddr, e: &io::Error) -> ! {
+    match e.kind() {
+        ErrorKind::AddrInUse => {
+            log::error!("Error: Address {address} is already in use.");
+            log::error!("Make sure another instance of the server isn't already running");
+        }
+        ErrorKind::PermissionDenied => {
+            log::error!("Error: Permission denied when binding to {address}.");
+            log::error!("You might need sudo/admin privileges to use ports below 1024");
+        }
+        ErrorKind::AddrNotAvailable => {
+            log::error!("Error: The address {address} is not available on this machine");
+        }
+        _ => {
+            log::error!("Failed to start TcpListener on {address}: {e}");
+        }
+    }
+    std::process::exit(1);
+}
+
+/// Accepts the next connection on any of `listeners`, never resolving if there are none.
+/// Also returns the listener it was accepted on.
+pub async fn accept_any(
+    listeners: &[JavaListener],
+) -> (io::Result<(TcpStream, SocketAddr)>, &JavaListener) {
+    if listeners.is_empty() {
+        return std::future::pending().await;
+    }
+    let accepts = listeners.iter().map(|java_listener| {
+        Box::pin(async move { (java_listener.listener.accept().await, java_listener) })
+    });
+    futures::future::select_all(accepts).await.0
+}
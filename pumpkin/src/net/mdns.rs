@@ -0,0 +1,320 @@
+//! mDNS (DNS-SD) advertisement of the server as a `_minecraft._tcp.local` service, so that
+//! service browsers and launchers on the local network find it without configuration.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket as StdUdpSocket};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use pumpkin_config::{BasicConfiguration, LANBroadcastConfig};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+use tokio::{select, time};
+
+use crate::{SHOULD_STOP, STOP_INTERRUPT};
+
+const MDNS_ADDRESS: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+
+const SERVICE: [&str; 3] = ["_minecraft", "_tcp", "local"];
+const SERVICE_ENUMERATION: [&str; 4] = ["_services", "_dns-sd", "_udp", "local"];
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+/// Tells caches to replace older records of the same name, used for unique records.
+const CACHE_FLUSH: u16 = 0x8000;
+
+/// TTL of records that point at the host, as recommended by RFC 6762.
+const HOST_TTL: u32 = 120;
+/// TTL of the other records.
+const SERVICE_TTL: u32 = 4500;
+
+/// Unsolicited announcements are repeated at this interval, well below the host TTL.
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct MdnsAdvertiser {
+    instance: String,
+    host: String,
+    ip: Ipv4Addr,
+    port: u16,
+    motd: String,
+}
+
+impl MdnsAdvertiser {
+    /// Creates an advertiser for the server listening on `bound_addr`.
+    #[must_use]
+    pub fn new(
+        config: &LANBroadcastConfig,
+        basic_config: &BasicConfiguration,
+        bound_addr: SocketAddr,
+    ) -> Self {
+        let motd = config
+            .motd
+            .clone()
+            .filter(|motd| !motd.is_empty())
+            .unwrap_or_else(|| basic_config.motd.replace('\n', " "));
+        let instance = config
+            .mdns_name
+            .clone()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| motd.clone());
+        let ip = match bound_addr.ip() {
+            IpAddr::V4(ip) if !ip.is_unspecified() => ip,
+            _ => lan_ip().unwrap_or(Ipv4Addr::LOCALHOST),
+        };
+        Self {
+            instance: truncate_label(&instance),
+            host: format!("pumpkin-{}", ip.to_string().replace('.', "-")),
+            ip,
+            port: bound_addr.port(),
+            motd,
+        }
+    }
+
+    /// Announces the service and answers queries for it until the server stops, then sends a
+    /// goodbye so browsers drop it right away.
+    pub async fn start(self) {
+        let socket = match bind_multicast() {
+            Ok(socket) => socket,
+            Err(err) => {
+                log::error!("Unable to start mDNS advertisement: {err}");
+                return;
+            }
+        };
+        let destination = SocketAddr::V4(SocketAddrV4::new(MDNS_ADDRESS, MDNS_PORT));
+        log::info!(
+            "Advertising \"{}\" over mDNS at {}:{}",
+            self.instance,
+            self.ip,
+            self.port
+        );
+
+        let announcement = self.response(false);
+        let mut interval = time::interval(ANNOUNCE_INTERVAL);
+        let mut buf = [0; 1500];
+        while !SHOULD_STOP.load(Ordering::Relaxed) {
+            select! {
+                _ = interval.tick() => {
+                    let _ = socket.send_to(&announcement, destination).await;
+                }
+                received = socket.recv_from(&mut buf) => {
+                    if let Ok((len, _)) = received
+                        && self.is_query_for_us(&buf[..len])
+                    {
+                        let _ = socket.send_to(&announcement, destination).await;
+                    }
+                }
+                () = STOP_INTERRUPT.cancelled() => break,
+            }
+        }
+        let _ = socket.send_to(&self.response(true), destination).await;
+    }
+
+    fn instance_name(&self) -> [&str; 4] {
+        [&self.instance, SERVICE[0], SERVICE[1], SERVICE[2]]
+    }
+
+    /// Whether `packet` is a query that asks for the service, its instance or its host.
+    fn is_query_for_us(&self, packet: &[u8]) -> bool {
+        let Some(questions) = parse_questions(packet) else {
+            return false;
+        };
+        let instance = self.instance_name().join(".");
+        let host = format!("{}.local", self.host);
+        questions.iter().any(|name| {
+            name.eq_ignore_ascii_case(&SERVICE.join("."))
+                || name.eq_ignore_ascii_case(&SERVICE_ENUMERATION.join("."))
+                || name.eq_ignore_ascii_case(&instance)
+                || name.eq_ignore_ascii_case(&host)
+        })
+    }
+
+    /// Builds the PTR, SRV, TXT and A records of the service. A goodbye sets every TTL to 0.
+    fn response(&self, goodbye: bool) -> Vec<u8> {
+        let ttl = |ttl: u32| if goodbye { 0 } else { ttl };
+        let host = [self.host.as_str(), "local"];
+        let instance = self.instance_name();
+
+        let mut packet = Vec::with_capacity(512);
+        // ID 0, flags: response + authoritative, no questions, 5 answers
+        for field in [0, 0x8400, 0, 5, 0, 0] {
+            packet.extend_from_slice(&u16::to_be_bytes(field));
+        }
+
+        record_header(
+            &mut packet,
+            &SERVICE_ENUMERATION,
+            TYPE_PTR,
+            CLASS_IN,
+            ttl(SERVICE_TTL),
+        );
+        with_length(&mut packet, |data| encode_name(data, &SERVICE));
+
+        record_header(&mut packet, &SERVICE, TYPE_PTR, CLASS_IN, ttl(SERVICE_TTL));
+        with_length(&mut packet, |data| encode_name(data, &instance));
+
+        let unique = CLASS_IN | CACHE_FLUSH;
+        record_header(&mut packet, &instance, TYPE_SRV, unique, ttl(HOST_TTL));
+        with_length(&mut packet, |data| {
+            // Priority and weight
+            data.extend_from_slice(&[0, 0, 0, 0]);
+            data.extend_from_slice(&self.port.to_be_bytes());
+            encode_name(data, &host);
+        });
+
+        record_header(&mut packet, &instance, TYPE_TXT, unique, ttl(SERVICE_TTL));
+        with_length(&mut packet, |data| {
+            let entry = format!("motd={}", self.motd);
+            data.push(truncate(&entry, 255).len() as u8);
+            data.extend_from_slice(truncate(&entry, 255).as_bytes());
+        });
+
+        record_header(&mut packet, &host, TYPE_A, unique, ttl(HOST_TTL));
+        with_length(&mut packet, |data| {
+            data.extend_from_slice(&self.ip.octets());
+        });
+        packet
+    }
+}
+
+/// Binds to the mDNS port next to other responders (e.g. Avahi) and joins the multicast group.
+fn bind_multicast() -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, MDNS_PORT)).into())?;
+    socket.join_multicast_v4(&MDNS_ADDRESS, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_nonblocking(true)?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// The address of the interface that routes to the multicast group.
+fn lan_ip() -> Option<Ipv4Addr> {
+    let socket = StdUdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((MDNS_ADDRESS, MDNS_PORT)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+fn truncate(value: &str, max: usize) -> &str {
+    let mut end = value.len().min(max);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
+}
+
+/// DNS labels are limited to 63 bytes.
+fn truncate_label(value: &str) -> String {
+    truncate(value.trim(), 63).to_string()
+}
+
+fn encode_name(packet: &mut Vec<u8>, labels: &[&str]) {
+    for label in labels {
+        let label = truncate(label, 63);
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+}
+
+fn record_header(packet: &mut Vec<u8>, name: &[&str], kind: u16, class: u16, ttl: u32) {
+    encode_name(packet, name);
+    packet.extend_from_slice(&kind.to_be_bytes());
+    packet.extend_from_slice(&class.to_be_bytes());
+    packet.extend_from_slice(&ttl.to_be_bytes());
+}
+
+/// Writes the record data produced by `write`, prefixed with its length.
+fn with_length(packet: &mut Vec<u8>, write: impl FnOnce(&mut Vec<u8>)) {
+    let start = packet.len();
+    packet.extend_from_slice(&[0, 0]);
+    write(packet);
+    let len = (packet.len() - start - 2) as u16;
+    packet[start..start + 2].copy_from_slice(&len.to_be_bytes());
+}
+
+/// Returns the names asked for by a query, or `None` for responses and malformed packets.
+fn parse_questions(packet: &[u8]) -> Option<Vec<String>> {
+    let u16_at = |at: usize| Some(u16::from_be_bytes(packet.get(at..at + 2)?.try_into().ok()?));
+    if u16_at(2)? & 0x8000 != 0 {
+        return None;
+    }
+    let mut offset = 12;
+    let mut names = Vec::new();
+    for _ in 0..u16_at(4)? {
+        let (name, end) = read_name(packet, offset)?;
+        names.push(name);
+        // Type and class
+        offset = end + 4;
+    }
+    Some(names)
+}
+
+/// Reads a possibly compressed name at `offset`, returning it and the offset after it.
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Bounds the number of pointers followed, so that pointer loops terminate
+    for _ in 0..128 {
+        let len = *packet.get(offset)? as usize;
+        if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(offset + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            let pointer = ((len & 0x3F) << 8) | *packet.get(offset + 1)? as usize;
+            end.get_or_insert(offset + 2);
+            offset = pointer;
+            continue;
+        }
+        let label = packet.get(offset + 1..offset + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        offset += 1 + len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_with_compressed_names_are_matched() {
+        let advertiser = MdnsAdvertiser {
+            instance: "A Pumpkin Server".to_string(),
+            host: "pumpkin-10-0-0-2".to_string(),
+            ip: Ipv4Addr::new(10, 0, 0, 2),
+            port: 25565,
+            motd: "A Pumpkin Server".to_string(),
+        };
+
+        let mut query = Vec::new();
+        for field in [0, 0, 2, 0, 0, 0] {
+            query.extend_from_slice(&u16::to_be_bytes(field));
+        }
+        // "_http._tcp.local", then "_minecraft" followed by a pointer to "_tcp.local"
+        encode_name(&mut query, &["_http", "_tcp", "local"]);
+        query.extend_from_slice(&[0, 12, 0, 1]);
+        query.push(10);
+        query.extend_from_slice(b"_minecraft");
+        query.extend_from_slice(&[0xC0, 18]);
+        query.extend_from_slice(&[0, 12, 0, 1]);
+
+        assert_eq!(
+            parse_questions(&query),
+            Some(vec![
+                "_http._tcp.local".to_string(),
+                "_minecraft._tcp.local".to_string()
+            ])
+        );
+        assert!(advertiser.is_query_for_us(&query));
+        // Our own announcements are responses and must not trigger another one
+        assert!(!advertiser.is_query_for_us(&advertiser.response(false)));
+    }
+}
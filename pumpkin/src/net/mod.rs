@@ -6,7 +6,7 @@ use std::{
 
 use crate::{
     entity::player::ChatMode,
-    net::{bedrock::BedrockClient, java::JavaClient},
+    net::{bedrock::BedrockClient, java::JavaClient, traffic::ConnectionTraffic},
     server::Server,
 };
 
@@ -21,11 +21,18 @@ use thiserror::Error;
 use uuid::Uuid;
 pub mod authentication;
 pub mod bedrock;
+pub mod circuit_breaker;
+pub mod floodgate;
+pub mod health;
 pub mod java;
 pub mod lan_broadcast;
+pub mod listener;
+pub mod mdns;
 mod proxy;
+pub mod proxy_protocol;
 pub mod query;
 pub mod rcon;
+pub mod traffic;
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct GameProfile {
@@ -125,6 +132,15 @@ impl ClientPlatform {
         }
     }
 
+    /// Traffic statistics of this connection. Only Java connections are tracked.
+    #[must_use]
+    pub const fn traffic(&self) -> Option<&ConnectionTraffic> {
+        match self {
+            Self::Java(java) => Some(&java.traffic),
+            Self::Bedrock(_) => None,
+        }
+    }
+
     pub async fn await_close_interrupt(&self) {
         match self {
             Self::Java(java) => java.await_close_interrupt().await,
@@ -0,0 +1,272 @@
+//! `HAProxy` PROXY protocol (v1 and v2) on the Java listeners, which lets TCP load balancers
+//! pass on the address of the client they accepted.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use pumpkin_config::networking::proxy_protocol::ProxyProtocolConfig;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::time;
+
+/// Starts every v2 header.
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+/// The longest v1 header allowed by the specification, including the CRLF.
+const V1_MAX_LENGTH: usize = 107;
+
+const V2_COMMAND_LOCAL: u8 = 0x0;
+const V2_COMMAND_PROXY: u8 = 0x1;
+const V2_TCP4: u8 = 0x11;
+const V2_TCP6: u8 = 0x21;
+
+#[derive(Error, Debug)]
+pub enum ProxyHeaderError {
+    #[error("Failed to read the PROXY header: {0}")]
+    Io(#[from] io::Error),
+    #[error("Connection did not start with a PROXY header")]
+    Missing,
+    #[error("Malformed PROXY header")]
+    Malformed,
+    #[error("Unsupported PROXY protocol version {0}")]
+    UnsupportedVersion(u8),
+}
+
+/// An address or CIDR range of trusted proxies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TrustedRange {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl TrustedRange {
+    fn parse(value: &str) -> Option<Self> {
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse::<u8>().ok()?)),
+            None => (value, None),
+        };
+        let network = address.trim().parse::<IpAddr>().ok()?.to_canonical();
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { network, prefix })
+    }
+
+    fn contains(self, ip: IpAddr) -> bool {
+        let bits = |ip: IpAddr| match ip {
+            IpAddr::V4(ip) => u128::from(ip.to_bits()) << 96,
+            IpAddr::V6(ip) => ip.to_bits(),
+        };
+        let ip = ip.to_canonical();
+        if ip.is_ipv4() != self.network.is_ipv4() {
+            return false;
+        }
+        let mask = u128::MAX
+            .checked_shl(128 - u32::from(self.prefix))
+            .unwrap_or(0);
+        bits(ip) & mask == bits(self.network) & mask
+    }
+}
+
+/// Reads PROXY headers on a listener, from the trusted proxies only.
+pub struct ProxyProtocol {
+    trusted: Vec<TrustedRange>,
+    timeout: Duration,
+}
+
+impl ProxyProtocol {
+    #[must_use]
+    pub fn new(config: &ProxyProtocolConfig) -> Self {
+        let trusted = config
+            .trusted_proxies
+            .iter()
+            .filter_map(|entry| {
+                let range = TrustedRange::parse(entry);
+                if range.is_none() {
+                    log::warn!("Ignoring invalid trusted PROXY protocol source \"{entry}\"");
+                }
+                range
+            })
+            .collect();
+        Self {
+            trusted,
+            timeout: Duration::from_millis(config.header_timeout_ms),
+        }
+    }
+
+    #[must_use]
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted.iter().any(|range| range.contains(ip))
+    }
+
+    /// Reads the header of a connection accepted from `peer` and returns the address of the
+    /// actual client, or `None` if the connection must be dropped.
+    ///
+    /// Nothing past the header is consumed, so the stream continues with the first packet.
+    pub async fn client_addr<R: AsyncRead + Unpin>(
+        &self,
+        stream: &mut R,
+        peer: SocketAddr,
+    ) -> Option<SocketAddr> {
+        if !self.is_trusted(peer.ip()) {
+            log::warn!("Refused connection from {peer}, which is not a trusted PROXY source");
+            return None;
+        }
+        match time::timeout(self.timeout, read_header(stream)).await {
+            // Health checks of the load balancer itself carry no client address
+            Ok(Ok(client)) => Some(client.unwrap_or(peer)),
+            Ok(Err(err)) => {
+                log::debug!("Dropped connection from proxy {peer}: {err}");
+                None
+            }
+            Err(_) => {
+                log::debug!("Dropped connection from proxy {peer}: PROXY header timed out");
+                None
+            }
+        }
+    }
+}
+
+/// Reads a v1 or v2 header, returning the source address it carries. `None` means the proxy
+/// opened the connection on its own behalf (v2 `LOCAL`, v1 `UNKNOWN` or a non-TCP family).
+pub async fn read_header<R: AsyncRead + Unpin>(
+    stream: &mut R,
+) -> Result<Option<SocketAddr>, ProxyHeaderError> {
+    // The shortest v1 header, "PROXY UNKNOWN\r\n", is longer than the v2 signature
+    let mut start = [0; V2_SIGNATURE.len()];
+    stream.read_exact(&mut start).await?;
+    if start == V2_SIGNATURE {
+        read_v2(stream).await
+    } else if start.starts_with(b"PROXY ") {
+        read_v1(stream, &start).await
+    } else {
+        Err(ProxyHeaderError::Missing)
+    }
+}
+
+async fn read_v1<R: AsyncRead + Unpin>(
+    stream: &mut R,
+    start: &[u8],
+) -> Result<Option<SocketAddr>, ProxyHeaderError> {
+    let mut line = start.to_vec();
+    // Byte by byte, so that the first packet after the header stays in the stream
+    while !line.ends_with(b"\r\n") {
+        if line.len() >= V1_MAX_LENGTH {
+            return Err(ProxyHeaderError::Malformed);
+        }
+        line.push(stream.read_u8().await?);
+    }
+    let line =
+        std::str::from_utf8(&line[..line.len() - 2]).map_err(|_| ProxyHeaderError::Malformed)?;
+    parse_v1(line)
+}
+
+/// Parses `PROXY <family> <source> <destination> <source port> <destination port>`.
+fn parse_v1(line: &str) -> Result<Option<SocketAddr>, ProxyHeaderError> {
+    let mut fields = line.split(' ').skip(1);
+    let family = fields.next();
+    if family == Some("UNKNOWN") {
+        return Ok(None);
+    }
+    let source = (|| {
+        let family = family?;
+        let source: IpAddr = fields.next()?.parse().ok()?;
+        let _destination: IpAddr = fields.next()?.parse().ok()?;
+        let port: u16 = fields.next()?.parse().ok()?;
+        let _destination_port: u16 = fields.next()?.parse().ok()?;
+        match (family, source) {
+            ("TCP4", IpAddr::V4(_)) | ("TCP6", IpAddr::V6(_)) if fields.next().is_none() => {
+                Some(SocketAddr::new(source, port))
+            }
+            _ => None,
+        }
+    })();
+    source.map(Some).ok_or(ProxyHeaderError::Malformed)
+}
+
+async fn read_v2<R: AsyncRead + Unpin>(
+    stream: &mut R,
+) -> Result<Option<SocketAddr>, ProxyHeaderError> {
+    let version_command = stream.read_u8().await?;
+    let family = stream.read_u8().await?;
+    let len = stream.read_u16().await?;
+    let mut addresses = vec![0; len as usize];
+    stream.read_exact(&mut addresses).await?;
+
+    let version = version_command >> 4;
+    if version != 2 {
+        return Err(ProxyHeaderError::UnsupportedVersion(version));
+    }
+    match version_command & 0x0F {
+        V2_COMMAND_LOCAL => return Ok(None),
+        V2_COMMAND_PROXY => {}
+        _ => return Err(ProxyHeaderError::Malformed),
+    }
+    // Addresses are followed by optional TLVs, which are skipped
+    let source = match family {
+        V2_TCP4 if addresses.len() >= 12 => {
+            let ip: [u8; 4] = addresses[..4].try_into().unwrap();
+            let port = u16::from_be_bytes([addresses[8], addresses[9]]);
+            SocketAddr::new(Ipv4Addr::from(ip).into(), port)
+        }
+        V2_TCP6 if addresses.len() >= 36 => {
+            let ip: [u8; 16] = addresses[..16].try_into().unwrap();
+            let port = u16::from_be_bytes([addresses[32], addresses[33]]);
+            SocketAddr::new(Ipv6Addr::from(ip).into(), port)
+        }
+        V2_TCP4 | V2_TCP6 => return Err(ProxyHeaderError::Malformed),
+        // UDP and UNIX sockets have no meaningful client address for us
+        _ => return Ok(None),
+    };
+    Ok(Some(source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn headers_are_parsed_without_consuming_the_stream() {
+        let mut v1: &[u8] = b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 25565\r\n\x10\x00";
+        assert_eq!(
+            read_header(&mut v1).await.unwrap(),
+            Some("203.0.113.7:51234".parse().unwrap())
+        );
+        assert_eq!(v1, b"\x10\x00");
+
+        let mut v2 = V2_SIGNATURE.to_vec();
+        v2.extend_from_slice(&[0x21, V2_TCP6, 0, 36]);
+        v2.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        v2.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        v2.extend_from_slice(&[0xC8, 0x22, 0x63, 0xDD, 0x10]);
+        let mut stream = v2.as_slice();
+        assert_eq!(
+            read_header(&mut stream).await.unwrap(),
+            Some("[2001:db8::1]:51234".parse().unwrap())
+        );
+        assert_eq!(stream, [0x10]);
+
+        let mut local = V2_SIGNATURE.to_vec();
+        local.extend_from_slice(&[0x20, 0, 0, 0]);
+        assert_eq!(read_header(&mut local.as_slice()).await.unwrap(), None);
+
+        let mut handshake: &[u8] = b"\x10\x00\xff\x05\x09localhost";
+        assert!(matches!(
+            read_header(&mut handshake).await,
+            Err(ProxyHeaderError::Missing)
+        ));
+    }
+
+    #[test]
+    fn trusted_ranges() {
+        let proxy_protocol = ProxyProtocol::new(&ProxyProtocolConfig {
+            enabled: true,
+            trusted_proxies: vec!["10.0.0.0/8".to_string(), "::1".to_string()],
+            header_timeout_ms: 5000,
+        });
+        assert!(proxy_protocol.is_trusted("10.20.30.40".parse().unwrap()));
+        assert!(proxy_protocol.is_trusted("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(proxy_protocol.is_trusted("::1".parse().unwrap()));
+        assert!(!proxy_protocol.is_trusted("11.0.0.1".parse().unwrap()));
+        assert!(!proxy_protocol.is_trusted("127.0.0.1".parse().unwrap()));
+    }
+}
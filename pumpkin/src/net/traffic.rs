@@ -0,0 +1,461 @@
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use pumpkin_protocol::ConnectionState;
+use rustc_hash::FxHashMap;
+
+/// How many one-second buckets of history each connection keeps.
+pub const HISTORY_SECONDS: usize = 60;
+
+/// Lifetime totals across every connection the server has ever accepted.
+static GLOBAL_TOTALS: GlobalTraffic = GlobalTraffic::new();
+
+struct GlobalTraffic {
+    packets_in: AtomicU64,
+    bytes_in: AtomicU64,
+    packets_out: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+impl GlobalTraffic {
+    const fn new() -> Self {
+        Self {
+            packets_in: AtomicU64::new(0),
+            bytes_in: AtomicU64::new(0),
+            packets_out: AtomicU64::new(0),
+            bytes_out: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, direction: TrafficDirection, bytes: usize) {
+        let (packets, total) = match direction {
+            TrafficDirection::Inbound => (&self.packets_in, &self.bytes_in),
+            TrafficDirection::Outbound => (&self.packets_out, &self.bytes_out),
+        };
+        packets.fetch_add(1, Ordering::Relaxed);
+        total.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// Returns the lifetime traffic totals of the whole server, including connections that
+/// have already been closed.
+#[must_use]
+pub fn global_totals() -> TrafficSample {
+    TrafficSample {
+        inbound: TrafficCounter {
+            packets: GLOBAL_TOTALS.packets_in.load(Ordering::Relaxed),
+            bytes: GLOBAL_TOTALS.bytes_in.load(Ordering::Relaxed),
+        },
+        outbound: TrafficCounter {
+            packets: GLOBAL_TOTALS.packets_out.load(Ordering::Relaxed),
+            bytes: GLOBAL_TOTALS.bytes_out.load(Ordering::Relaxed),
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrafficDirection {
+    /// Serverbound packets, read from the client.
+    Inbound,
+    /// Clientbound packets, written to the client.
+    Outbound,
+}
+
+/// A packet and byte count. Byte counts are measured on the uncompressed, unencrypted
+/// packet (id + payload), so they reflect what the server produces rather than what goes
+/// over the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrafficCounter {
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+impl TrafficCounter {
+    const fn add(&mut self, bytes: usize) {
+        self.packets += 1;
+        self.bytes += bytes as u64;
+    }
+
+    const fn merge(&mut self, other: &Self) {
+        self.packets += other.packets;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Inbound and outbound counters for a span of time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrafficSample {
+    pub inbound: TrafficCounter,
+    pub outbound: TrafficCounter,
+}
+
+impl TrafficSample {
+    const fn counter_mut(&mut self, direction: TrafficDirection) -> &mut TrafficCounter {
+        match direction {
+            TrafficDirection::Inbound => &mut self.inbound,
+            TrafficDirection::Outbound => &mut self.outbound,
+        }
+    }
+
+    const fn merge(&mut self, other: &Self) {
+        self.inbound.merge(&other.inbound);
+        self.outbound.merge(&other.outbound);
+    }
+}
+
+/// Identifies a packet type. Packet ids are only unique within a connection state, so the
+/// state is part of the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketKey {
+    pub direction: TrafficDirection,
+    pub state: ConnectionState,
+    pub id: i32,
+}
+
+impl Hash for PacketKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.direction.hash(state);
+        (self.state as u8).hash(state);
+        self.id.hash(state);
+    }
+}
+
+impl PacketKey {
+    /// A short human readable label, e.g. `play/0x27 out`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        let state = match self.state {
+            ConnectionState::HandShake => "handshake",
+            ConnectionState::Status => "status",
+            ConnectionState::Login => "login",
+            ConnectionState::Transfer => "transfer",
+            ConnectionState::Config => "config",
+            ConnectionState::Play => "play",
+        };
+        let direction = match self.direction {
+            TrafficDirection::Inbound => "in",
+            TrafficDirection::Outbound => "out",
+        };
+        format!("{state}/0x{:02X} {direction}", self.id)
+    }
+}
+
+struct TrafficState {
+    totals: TrafficSample,
+    per_packet: FxHashMap<PacketKey, TrafficCounter>,
+    history: [TrafficSample; HISTORY_SECONDS],
+    /// Seconds since `started` of the newest history bucket.
+    current_second: u64,
+}
+
+impl TrafficState {
+    /// Moves the ring buffer forward to `second`, clearing every bucket that was skipped.
+    fn advance(&mut self, second: u64) {
+        if second <= self.current_second {
+            return;
+        }
+        let skipped = (second - self.current_second).min(HISTORY_SECONDS as u64);
+        for offset in 0..skipped {
+            let bucket = (second - offset) as usize % HISTORY_SECONDS;
+            self.history[bucket] = TrafficSample::default();
+        }
+        self.current_second = second;
+    }
+
+    fn record(&mut self, second: u64, key: PacketKey, bytes: usize) {
+        self.advance(second);
+        self.totals.counter_mut(key.direction).add(bytes);
+        self.per_packet.entry(key).or_default().add(bytes);
+        self.history[second as usize % HISTORY_SECONDS]
+            .counter_mut(key.direction)
+            .add(bytes);
+    }
+
+    /// Complete one-second buckets, oldest first. The in-progress second is excluded.
+    fn completed_history(&mut self, second: u64) -> Vec<TrafficSample> {
+        self.advance(second);
+        let available = (second as usize).min(HISTORY_SECONDS - 1);
+        (1..=available)
+            .rev()
+            .map(|age| self.history[(second as usize - age) % HISTORY_SECONDS])
+            .collect()
+    }
+}
+
+/// Traffic statistics of a single connection.
+///
+/// Recording takes a short, uncontended lock; the connection's own reader and writer
+/// tasks are the only writers.
+pub struct ConnectionTraffic {
+    started: Instant,
+    state: Mutex<TrafficState>,
+}
+
+impl Default for ConnectionTraffic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionTraffic {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            state: Mutex::new(TrafficState {
+                totals: TrafficSample::default(),
+                per_packet: FxHashMap::default(),
+                history: [TrafficSample::default(); HISTORY_SECONDS],
+                current_second: 0,
+            }),
+        }
+    }
+
+    /// Records a single packet of `bytes` length.
+    pub fn record(
+        &self,
+        direction: TrafficDirection,
+        state: ConnectionState,
+        id: i32,
+        bytes: usize,
+    ) {
+        GLOBAL_TOTALS.record(direction, bytes);
+        let second = self.started.elapsed().as_secs();
+        let key = PacketKey {
+            direction,
+            state,
+            id,
+        };
+        self.state.lock().unwrap().record(second, key, bytes);
+    }
+
+    /// Records an already serialized clientbound packet, reading its id from the leading
+    /// `VarInt`.
+    pub fn record_outbound(&self, state: ConnectionState, packet_data: &[u8]) {
+        let id = leading_var_int(packet_data).unwrap_or(-1);
+        self.record(TrafficDirection::Outbound, state, id, packet_data.len());
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> TrafficSnapshot {
+        let second = self.started.elapsed().as_secs();
+        let mut state = self.state.lock().unwrap();
+        let history = state.completed_history(second);
+        TrafficSnapshot {
+            totals: state.totals,
+            per_packet: state
+                .per_packet
+                .iter()
+                .map(|(key, counter)| (*key, *counter))
+                .collect(),
+            history,
+        }
+    }
+}
+
+/// A point-in-time copy of traffic statistics, either of one connection or merged over
+/// many.
+#[derive(Debug, Clone, Default)]
+pub struct TrafficSnapshot {
+    pub totals: TrafficSample,
+    pub per_packet: Vec<(PacketKey, TrafficCounter)>,
+    /// Complete one-second buckets, oldest first.
+    pub history: Vec<TrafficSample>,
+}
+
+impl TrafficSnapshot {
+    /// Adds another snapshot into this one. History buckets are aligned on the newest
+    /// second.
+    pub fn merge(&mut self, other: &Self) {
+        self.totals.merge(&other.totals);
+
+        let mut per_packet: FxHashMap<PacketKey, TrafficCounter> =
+            self.per_packet.drain(..).collect();
+        for (key, counter) in &other.per_packet {
+            per_packet.entry(*key).or_default().merge(counter);
+        }
+        self.per_packet = per_packet.into_iter().collect();
+
+        if other.history.len() > self.history.len() {
+            let missing = other.history.len() - self.history.len();
+            self.history
+                .splice(0..0, std::iter::repeat_n(TrafficSample::default(), missing));
+        }
+        let offset = self.history.len() - other.history.len();
+        for (mine, theirs) in self.history[offset..].iter_mut().zip(&other.history) {
+            mine.merge(theirs);
+        }
+    }
+
+    /// Average traffic per second over the recorded history.
+    #[must_use]
+    pub fn average_per_second(&self) -> TrafficSample {
+        let mut sum = TrafficSample::default();
+        for sample in &self.history {
+            sum.merge(sample);
+        }
+        let seconds = self.history.len().max(1) as u64;
+        TrafficSample {
+            inbound: TrafficCounter {
+                packets: sum.inbound.packets / seconds,
+                bytes: sum.inbound.bytes / seconds,
+            },
+            outbound: TrafficCounter {
+                packets: sum.outbound.packets / seconds,
+                bytes: sum.outbound.bytes / seconds,
+            },
+        }
+    }
+
+    /// Packet types ordered by the number of bytes they account for, largest first.
+    #[must_use]
+    pub fn top_by_bytes(&self, limit: usize) -> Vec<(PacketKey, TrafficCounter)> {
+        let mut sorted = self.per_packet.clone();
+        sorted.sort_unstable_by_key(|(_, counter)| std::cmp::Reverse(counter.bytes));
+        sorted.truncate(limit);
+        sorted
+    }
+}
+
+/// Formats a byte count using binary units, e.g. `1.5 KiB`.
+#[must_use]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Decodes the `VarInt` at the start of `data`, if there is a complete one.
+fn leading_var_int(data: &[u8]) -> Option<i32> {
+    let mut value = 0i32;
+    for (i, byte) in data.iter().take(5).enumerate() {
+        value |= i32::from(byte & 0x7F) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(direction: TrafficDirection, id: i32) -> PacketKey {
+        PacketKey {
+            direction,
+            state: ConnectionState::Play,
+            id,
+        }
+    }
+
+    fn empty_state() -> TrafficState {
+        TrafficState {
+            totals: TrafficSample::default(),
+            per_packet: FxHashMap::default(),
+            history: [TrafficSample::default(); HISTORY_SECONDS],
+            current_second: 0,
+        }
+    }
+
+    #[test]
+    fn records_totals_and_packet_types() {
+        let mut state = empty_state();
+        state.record(0, key(TrafficDirection::Outbound, 0x27), 100);
+        state.record(0, key(TrafficDirection::Outbound, 0x27), 50);
+        state.record(0, key(TrafficDirection::Inbound, 0x1D), 10);
+
+        assert_eq!(state.totals.outbound.packets, 2);
+        assert_eq!(state.totals.outbound.bytes, 150);
+        assert_eq!(state.totals.inbound.bytes, 10);
+        assert_eq!(
+            state.per_packet[&key(TrafficDirection::Outbound, 0x27)].bytes,
+            150
+        );
+    }
+
+    #[test]
+    fn history_excludes_current_second() {
+        let mut state = empty_state();
+        state.record(0, key(TrafficDirection::Outbound, 1), 10);
+        state.record(1, key(TrafficDirection::Outbound, 1), 20);
+
+        let history = state.completed_history(1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].outbound.bytes, 10);
+    }
+
+    #[test]
+    fn history_clears_skipped_seconds() {
+        let mut state = empty_state();
+        state.record(0, key(TrafficDirection::Outbound, 1), 10);
+        state.record(
+            HISTORY_SECONDS as u64,
+            key(TrafficDirection::Outbound, 1),
+            5,
+        );
+
+        let history = state.completed_history(HISTORY_SECONDS as u64 + 1);
+        assert_eq!(history.len(), HISTORY_SECONDS - 1);
+        assert_eq!(history.last().unwrap().outbound.bytes, 5);
+        assert!(
+            history[..HISTORY_SECONDS - 2]
+                .iter()
+                .all(|sample| *sample == TrafficSample::default())
+        );
+    }
+
+    #[test]
+    fn merge_aligns_history_on_newest_second() {
+        let mut a = TrafficSnapshot {
+            history: vec![TrafficSample {
+                inbound: TrafficCounter {
+                    packets: 1,
+                    bytes: 1,
+                },
+                outbound: TrafficCounter::default(),
+            }],
+            ..Default::default()
+        };
+        let b = TrafficSnapshot {
+            history: vec![TrafficSample::default(); 3],
+            per_packet: vec![(
+                key(TrafficDirection::Inbound, 2),
+                TrafficCounter {
+                    packets: 1,
+                    bytes: 4,
+                },
+            )],
+            ..Default::default()
+        };
+        a.merge(&b);
+
+        assert_eq!(a.history.len(), 3);
+        assert_eq!(a.history[2].inbound.packets, 1);
+        assert_eq!(a.top_by_bytes(1)[0].1.bytes, 4);
+    }
+
+    #[test]
+    fn decodes_leading_var_int() {
+        assert_eq!(leading_var_int(&[0x27, 0xFF]), Some(0x27));
+        assert_eq!(leading_var_int(&[0x80, 0x01]), Some(128));
+        assert_eq!(leading_var_int(&[0x80]), None);
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+    }
+}
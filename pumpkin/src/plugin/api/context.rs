@@ -4,11 +4,13 @@ use std::{
     sync::{Arc, OnceLock},
 };
 
-use crate::{LoggerOption, command::client_suggestions};
+use crate::{LoggerOption, command::client_suggestions, data::player_sync::PlayerDataSync};
+use pumpkin_inventory::player::ender_chest_inventory::EnderChestInventory;
 use pumpkin_util::{
     PermissionLvl,
     permission::{Permission, PermissionManager},
 };
+use pumpkin_world::{data::player_data::PlayerDataError, inventory::Inventory};
 use tokio::sync::RwLock;
 
 use crate::{
@@ -88,6 +90,70 @@ impl Context {
         self.server.get_player_by_name(player_name)
     }
 
+    /// Retrieves the ender chest of a player, whether they are online or not.
+    ///
+    /// For online players this is the inventory they use, so changes show up right away. For
+    /// offline players it is loaded from their saved data; pass it to [`Self::save_ender_chest`]
+    /// to keep the changes.
+    ///
+    /// # Returns
+    /// The ender chest, or `None` if the player is offline and has no saved data.
+    pub async fn get_ender_chest(
+        &self,
+        player_uuid: uuid::Uuid,
+    ) -> Result<Option<Arc<EnderChestInventory>>, PlayerDataError> {
+        if let Some(player) = self.server.get_player_by_uuid(player_uuid) {
+            return Ok(Some(player.ender_chest_inventory.clone()));
+        }
+        Ok(self
+            .server
+            .player_data_storage
+            .load_offline_ender_chest(&player_uuid)
+            .await?
+            .map(Arc::new))
+    }
+
+    /// Stores `inventory` as the ender chest of a player. If the player joined since the
+    /// inventory was loaded, its items are copied into the inventory they use.
+    ///
+    /// # Returns
+    /// `false` if the player is offline and has no saved data.
+    pub async fn save_ender_chest(
+        &self,
+        player_uuid: uuid::Uuid,
+        inventory: &EnderChestInventory,
+    ) -> Result<bool, PlayerDataError> {
+        if let Some(player) = self.server.get_player_by_uuid(player_uuid) {
+            let target = &player.ender_chest_inventory;
+            if !std::ptr::eq(target.as_ref(), inventory) {
+                for (slot, stack) in inventory.items.iter().enumerate() {
+                    let stack = stack.lock().await.clone();
+                    target.set_stack(slot, stack).await;
+                }
+                target.mark_dirty();
+            }
+            return Ok(true);
+        }
+        self.server
+            .player_data_storage
+            .save_offline_ender_chest(player_uuid, inventory)
+            .await
+    }
+
+    /// Registers a sync that keeps player data in a store shared with the other servers of a
+    /// network. See [`PlayerDataSync`].
+    pub fn register_player_data_sync(&self, sync: Arc<dyn PlayerDataSync>) {
+        self.server.player_data_storage.register_sync(sync);
+    }
+
+    /// Waits until the data of a player who left was flushed to all syncs. Returns right away
+    /// if no flush is running for the player.
+    pub async fn await_player_data_flush(&self, player_uuid: &uuid::Uuid) {
+        if let Some(flush) = self.server.player_data_storage.pending_flush(player_uuid) {
+            flush.await;
+        }
+    }
+
     /// Registers a service with the plugin context.
     ///
     /// This method allows you to associate a service instance with a given name,
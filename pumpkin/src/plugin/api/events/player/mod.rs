@@ -16,7 +16,9 @@ pub mod player_leave;
 pub mod player_level_change;
 pub mod player_login;
 pub mod player_move;
+pub mod player_move_violation;
 pub mod player_respawn;
+pub mod player_spam;
 pub mod player_swap_hand_items;
 pub mod player_teleport;
 pub mod player_toggle_flight;
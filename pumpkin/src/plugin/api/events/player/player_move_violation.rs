@@ -0,0 +1,58 @@
+use pumpkin_config::movement::MovementEnforcement;
+use pumpkin_macros::{Event, cancellable};
+use pumpkin_util::math::vector3::Vector3;
+use std::sync::Arc;
+
+use crate::entity::movement::MovementViolation;
+use crate::entity::player::Player;
+
+use super::PlayerEvent;
+
+/// An event that occurs when a move of a player fails the movement checks.
+///
+/// If the event is cancelled, the move is allowed. Plugins may also change how the violation is
+/// enforced.
+#[cancellable]
+#[derive(Event, Clone)]
+pub struct PlayerMoveViolationEvent {
+    /// The player who moved.
+    pub player: Arc<Player>,
+
+    /// The check the move failed.
+    pub violation: MovementViolation,
+
+    /// The position from which the player moved.
+    pub from: Vector3<f64>,
+
+    /// The position to which the player moved.
+    pub to: Vector3<f64>,
+
+    /// What happens to the player, from the server config by default.
+    pub enforcement: MovementEnforcement,
+}
+
+impl PlayerMoveViolationEvent {
+    #[must_use]
+    pub const fn new(
+        player: Arc<Player>,
+        violation: MovementViolation,
+        from: Vector3<f64>,
+        to: Vector3<f64>,
+        enforcement: MovementEnforcement,
+    ) -> Self {
+        Self {
+            player,
+            violation,
+            from,
+            to,
+            enforcement,
+            cancelled: false,
+        }
+    }
+}
+
+impl PlayerEvent for PlayerMoveViolationEvent {
+    fn get_player(&self) -> &Arc<Player> {
+        &self.player
+    }
+}
@@ -0,0 +1,53 @@
+use pumpkin_config::chat_limits::SpamConsequence;
+use pumpkin_macros::{Event, cancellable};
+use std::sync::Arc;
+
+use crate::entity::chat_limiter::MessageKind;
+use crate::entity::player::Player;
+
+use super::PlayerEvent;
+
+/// An event that occurs when a player sends chat messages or commands faster than the chat
+/// limits allow.
+///
+/// If the event is cancelled, the message goes through. Plugins may also change what happens to
+/// the player.
+#[cancellable]
+#[derive(Event, Clone)]
+pub struct PlayerSpamEvent {
+    /// The player who sent the message.
+    pub player: Arc<Player>,
+
+    /// The chat message or command, without the leading slash.
+    pub message: String,
+
+    /// Whether the message is a chat message or a command.
+    pub kind: MessageKind,
+
+    /// What happens to the player, from the server config by default.
+    pub consequence: SpamConsequence,
+}
+
+impl PlayerSpamEvent {
+    #[must_use]
+    pub const fn new(
+        player: Arc<Player>,
+        message: String,
+        kind: MessageKind,
+        consequence: SpamConsequence,
+    ) -> Self {
+        Self {
+            player,
+            message,
+            kind,
+            consequence,
+            cancelled: false,
+        }
+    }
+}
+
+impl PlayerEvent for PlayerSpamEvent {
+    fn get_player(&self) -> &Arc<Player> {
+        &self.player
+    }
+}
@@ -0,0 +1,40 @@
+use pumpkin_macros::{Event, cancellable};
+use std::sync::Arc;
+
+use crate::entity::player::Player;
+
+use super::PlayerEvent;
+
+/// An event that occurs when a player is about to be transferred to another server.
+///
+/// The destination can be changed. If the event is cancelled, the player stays on this server.
+#[cancellable]
+#[derive(Event, Clone)]
+pub struct PlayerTransferEvent {
+    /// The player being transferred.
+    pub player: Arc<Player>,
+
+    /// The host name or address of the destination server.
+    pub host: String,
+
+    /// The port of the destination server.
+    pub port: i32,
+}
+
+impl PlayerTransferEvent {
+    #[must_use]
+    pub const fn new(player: Arc<Player>, host: String, port: i32) -> Self {
+        Self {
+            player,
+            host,
+            port,
+            cancelled: false,
+        }
+    }
+}
+
+impl PlayerEvent for PlayerTransferEvent {
+    fn get_player(&self) -> &Arc<Player> {
+        &self.player
+    }
+}
@@ -1,9 +1,13 @@
+use std::net::SocketAddr;
+
 use pumpkin_macros::{Event, cancellable};
+use pumpkin_util::version::MinecraftVersion;
 
 /// An event that occurs when the server receives a status (ping) request from a client.
 ///
-/// This allows plugins to customize the server list response: MOTD, max players,
-/// online count, and protocol version name.
+/// This allows plugins to rewrite the whole server list response per request: MOTD,
+/// player counts and sample, version and favicon. The configured MOTD rotation, player
+/// sample and incompatible version text have already been applied.
 ///
 /// If cancelled, the status response is not sent.
 ///
@@ -11,6 +15,12 @@ use pumpkin_macros::{Event, cancellable};
 #[cancellable]
 #[derive(Event, Clone)]
 pub struct ServerListPingEvent {
+    /// The address of the client pinging the server.
+    pub client_address: SocketAddr,
+
+    /// The version the client announced in its handshake, `Unknown` if it is not recognized.
+    pub client_version: MinecraftVersion,
+
     /// The description (MOTD) shown in the server list.
     pub motd: String,
 
@@ -20,28 +30,44 @@ pub struct ServerListPingEvent {
     /// Current online player count shown in the server list.
     pub online_players: u32,
 
+    /// Lines shown when hovering the player count.
+    pub player_sample: Vec<String>,
+
     /// The protocol version name shown in the server list (e.g. "1.21.11").
     pub version_name: String,
 
-    /// The numeric protocol version.
+    /// The numeric protocol version. Clients show `version_name` as incompatible
+    /// unless this matches their own protocol.
     pub protocol_version: u32,
+
+    /// The base64 encoded PNG favicon, with its `data:image/png;base64,` prefix.
+    pub favicon: Option<String>,
 }
 
 impl ServerListPingEvent {
     #[must_use]
+    #[expect(clippy::too_many_arguments)]
     pub const fn new(
+        client_address: SocketAddr,
+        client_version: MinecraftVersion,
         motd: String,
         max_players: u32,
         online_players: u32,
+        player_sample: Vec<String>,
         version_name: String,
         protocol_version: u32,
+        favicon: Option<String>,
     ) -> Self {
         Self {
+            client_address,
+            client_version,
             motd,
             max_players,
             online_players,
+            player_sample,
             version_name,
             protocol_version,
+            favicon,
             cancelled: false,
         }
     }
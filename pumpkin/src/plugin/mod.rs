@@ -13,7 +13,10 @@ use tokio::sync::{Notify, RwLock};
 pub mod api;
 pub mod loader;
 
-use crate::{LOGGER_IMPL, server::Server};
+use crate::{
+    LOGGER_IMPL,
+    server::{Server, alerting::AlertKind},
+};
 pub use api::*;
 
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -328,7 +331,20 @@ impl PluginManager {
         }
 
         // Wait for all plugins to complete loading (but don't block on individual plugin initialization)
-        join_all(load_tasks).await;
+        for result in join_all(load_tasks).await {
+            if let Err(e) = result
+                && e.is_panic()
+            {
+                log::error!("A plugin panicked while loading: {e}");
+                let server = self.server.read().await.clone();
+                if let Some(server) = server {
+                    server.alerting.fire(
+                        AlertKind::PluginFailure,
+                        format!("A plugin panicked while loading: {e}"),
+                    );
+                }
+            }
+        }
 
         Ok(())
     }
@@ -420,6 +436,7 @@ impl PluginManager {
                         Err(e) => {
                             // Handle initialization failure
                             let error_msg = format!("Initialization failed: {e}");
+                            let server = context.server.clone();
                             let _ = instance.on_unload(context).await;
 
                             // Get the loader data before removing the plugin
@@ -452,6 +469,10 @@ impl PluginManager {
                             state_notify.notify_waiters();
 
                             log::error!("Failed to initialize plugin {plugin_name}: {error_msg}",);
+                            server.alerting.fire(
+                                AlertKind::PluginFailure,
+                                format!("Failed to initialize plugin {plugin_name}: {error_msg}"),
+                            );
                         }
                     }
                 });
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use pumpkin_config::alerting::{AlertingConfig, WebhookConfig, WebhookFormat};
+
+/// The kinds of anomalies alerts are fired for. Each kind is rate limited separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// The MSPT stayed above the configured threshold.
+    HighMspt,
+    /// Saving a world, `level.dat`, or player data failed.
+    SaveFailure,
+    /// A plugin panicked or failed to initialize.
+    PluginFailure,
+    /// The disk holding the world is running out of space.
+    LowDiskSpace,
+}
+
+impl AlertKind {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::HighMspt => "high_mspt",
+            Self::SaveFailure => "save_failure",
+            Self::PluginFailure => "plugin_failure",
+            Self::LowDiskSpace => "low_disk_space",
+        }
+    }
+}
+
+#[derive(Default)]
+struct AlertState {
+    last_fired: HashMap<AlertKind, Instant>,
+    mspt_high_since: Option<Instant>,
+    last_disk_check: Option<Instant>,
+}
+
+/// Fires configurable webhooks when the server misbehaves.
+pub struct Alerting {
+    config: AlertingConfig,
+    world_path: PathBuf,
+    state: Mutex<AlertState>,
+}
+
+impl Alerting {
+    #[must_use]
+    pub fn new(config: AlertingConfig, world_path: PathBuf) -> Self {
+        Self {
+            config,
+            world_path,
+            state: Mutex::new(AlertState::default()),
+        }
+    }
+
+    /// Sends an alert to all webhooks unless one of the same kind was sent within the cooldown.
+    pub fn fire(&self, kind: AlertKind, message: impl Into<String>) {
+        if !self.config.enabled || self.config.webhooks.is_empty() {
+            return;
+        }
+
+        if !self.start_cooldown(kind, Instant::now()) {
+            return;
+        }
+
+        let message = message.into();
+        log::warn!("Alert {}: {message}", kind.name());
+
+        let server_name = self.config.server_name.clone();
+        let webhooks = self.config.webhooks.clone();
+        // ureq is blocking, keep it away from the tick loop
+        tokio::task::spawn_blocking(move || {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            for webhook in &webhooks {
+                let body = render_payload(webhook, &server_name, kind, &message, timestamp);
+                if let Err(err) = ureq::post(&webhook.url)
+                    .header("User-Agent", "Pumpkin-MC")
+                    .send_json(&body)
+                {
+                    log::warn!("Failed to deliver alert to webhook: {err}");
+                }
+            }
+        });
+    }
+
+    /// Checks the MSPT of the last tick and the free disk space. Called once per tick.
+    pub fn tick(&self, mspt: f64) {
+        if !self.config.enabled {
+            return;
+        }
+        let now = Instant::now();
+        let high_for = self.sustained_high_mspt(mspt, now);
+        let mut check_disk = false;
+        {
+            let mut state = self.state.lock().unwrap();
+            if self.config.min_free_disk_mb > 0
+                && state.last_disk_check.is_none_or(|last| {
+                    now.duration_since(last)
+                        >= Duration::from_secs(self.config.disk_check_interval_seconds)
+                })
+            {
+                state.last_disk_check = Some(now);
+                check_disk = true;
+            }
+        }
+
+        if let Some(high_for) = high_for {
+            self.fire(
+                AlertKind::HighMspt,
+                format!(
+                    "MSPT has been above {:.1} ms for {}s (currently {mspt:.1} ms)",
+                    self.config.mspt_threshold,
+                    high_for.as_secs()
+                ),
+            );
+        }
+
+        if check_disk && let Some(free) = free_disk_space(&self.world_path) {
+            let free_mb = free / (1024 * 1024);
+            if free_mb < self.config.min_free_disk_mb {
+                self.fire(
+                    AlertKind::LowDiskSpace,
+                    format!(
+                        "Only {free_mb} MB left on the disk holding {}",
+                        self.world_path.display()
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Starts the cooldown of `kind` at `now`, unless it is still running.
+    fn start_cooldown(&self, kind: AlertKind, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let cooldown = Duration::from_secs(self.config.cooldown_seconds);
+        if let Some(last) = state.last_fired.get(&kind)
+            && now.duration_since(*last) < cooldown
+        {
+            return false;
+        }
+        state.last_fired.insert(kind, now);
+        true
+    }
+
+    /// Records the MSPT of the tick that ended at `now`. Returns for how long it has been above
+    /// the threshold once that is long enough to alert.
+    fn sustained_high_mspt(&self, mspt: f64, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        if mspt <= self.config.mspt_threshold {
+            state.mspt_high_since = None;
+            return None;
+        }
+        let high_for = now.duration_since(*state.mspt_high_since.get_or_insert(now));
+        (high_for >= Duration::from_secs(self.config.mspt_duration_seconds)).then_some(high_for)
+    }
+}
+
+fn render_payload(
+    webhook: &WebhookConfig,
+    server_name: &str,
+    kind: AlertKind,
+    message: &str,
+    timestamp: u64,
+) -> serde_json::Value {
+    let text = webhook
+        .template
+        .replace("{server}", server_name)
+        .replace("{event}", kind.name())
+        .replace("{message}", message)
+        .replace("{timestamp}", &timestamp.to_string());
+    match webhook.format {
+        WebhookFormat::Discord => serde_json::json!({ "content": text }),
+        WebhookFormat::Slack => serde_json::json!({ "text": text }),
+        WebhookFormat::Json => serde_json::json!({
+            "server": server_name,
+            "event": kind.name(),
+            "message": message,
+            "text": text,
+            "timestamp": timestamp,
+        }),
+    }
+}
+
+/// Returns the bytes available to unprivileged users on the file system containing `path`.
+#[cfg(unix)]
+// The field types differ between platforms
+#[allow(clippy::useless_conversion)]
+fn free_disk_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is a valid C string and `stat` is only read after a successful call.
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stat.assume_init()
+    };
+    Some(u64::from(stat.f_bavail) * u64::from(stat.f_frsize))
+}
+
+#[cfg(not(unix))]
+const fn free_disk_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_placeholders_are_replaced() {
+        let webhook = WebhookConfig {
+            url: String::new(),
+            format: WebhookFormat::Discord,
+            template: "[{server}] {event}: {message} @{timestamp}".to_string(),
+        };
+        let body = render_payload(&webhook, "Lobby", AlertKind::SaveFailure, "disk full", 42);
+        assert_eq!(body["content"], "[Lobby] save_failure: disk full @42");
+    }
+
+    fn alerting() -> Alerting {
+        Alerting::new(
+            AlertingConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            PathBuf::new(),
+        )
+    }
+
+    #[test]
+    fn alerts_are_rate_limited_per_kind() {
+        let alerting = alerting();
+        let start = Instant::now();
+        assert!(alerting.start_cooldown(AlertKind::SaveFailure, start));
+        assert!(!alerting.start_cooldown(AlertKind::SaveFailure, start + Duration::from_secs(10)));
+        assert!(alerting.start_cooldown(AlertKind::PluginFailure, start + Duration::from_secs(10)));
+        assert!(!alerting.start_cooldown(AlertKind::SaveFailure, start + Duration::from_secs(299)));
+        assert!(alerting.start_cooldown(AlertKind::SaveFailure, start + Duration::from_secs(300)));
+        assert!(!alerting.start_cooldown(AlertKind::SaveFailure, start + Duration::from_secs(301)));
+    }
+
+    #[test]
+    fn high_mspt_alerts_once_it_lasts_long_enough() {
+        let alerting = alerting();
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert_eq!(alerting.sustained_high_mspt(60.0, start), None);
+        assert_eq!(alerting.sustained_high_mspt(60.0, at(29)), None);
+        assert_eq!(
+            alerting.sustained_high_mspt(60.0, at(30)),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            alerting.sustained_high_mspt(60.0, at(45)),
+            Some(Duration::from_secs(45))
+        );
+
+        // A tick at the threshold resets it
+        assert_eq!(alerting.sustained_high_mspt(50.0, at(46)), None);
+        assert_eq!(alerting.sustained_high_mspt(60.0, at(50)), None);
+        assert_eq!(alerting.sustained_high_mspt(60.0, at(75)), None);
+        assert_eq!(
+            alerting.sustained_high_mspt(60.0, at(80)),
+            Some(Duration::from_secs(30))
+        );
+    }
+}
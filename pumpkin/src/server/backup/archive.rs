@@ -0,0 +1,299 @@
+//! Minimal ustar reader and writer for world backups.
+//!
+//! Only regular files and directories are supported, which is all a world folder contains.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use pumpkin_config::backup::BackupCompression;
+use ruzstd::decoding::StreamingDecoder;
+use ruzstd::encoding::{CompressionLevel, compress};
+
+const BLOCK_SIZE: usize = 512;
+
+/// Files that must never end up in a backup.
+const SKIPPED_FILES: [&str; 1] = ["session.lock"];
+
+/// Archives `source` into `target`, compressed with `compression`.
+///
+/// Entries are stored relative to `source`. Paths listed in `exclude` are skipped. Fails if
+/// `target` already exists.
+pub fn create(
+    source: &Path,
+    target: &Path,
+    compression: BackupCompression,
+    exclude: &[PathBuf],
+) -> io::Result<()> {
+    match compression {
+        BackupCompression::Gzip => {
+            let encoder = GzEncoder::new(
+                BufWriter::new(File::create_new(target)?),
+                Compression::fast(),
+            );
+            let mut encoder = write_tar(source, encoder, exclude)?;
+            encoder.try_finish()?;
+            encoder.get_mut().flush()
+        }
+        BackupCompression::Zstd => {
+            // ruzstd only compresses from a reader, so stage the plain tar next to the target
+            let staging = target.with_extension("tmp");
+            let result = create_zstd(source, &staging, target, exclude);
+            let _ = fs::remove_file(&staging);
+            result
+        }
+    }
+}
+
+fn create_zstd(
+    source: &Path,
+    staging: &Path,
+    target: &Path,
+    exclude: &[PathBuf],
+) -> io::Result<()> {
+    let mut output = BufWriter::new(File::create_new(target)?);
+    write_tar(source, BufWriter::new(File::create(staging)?), exclude)?.flush()?;
+    compress(
+        BufReader::new(File::open(staging)?),
+        &mut output,
+        CompressionLevel::Fastest,
+    );
+    output.flush()
+}
+
+/// Extracts the archive at `archive` into `target`, detecting the compression from the file name.
+pub fn extract(archive: &Path, target: &Path) -> io::Result<()> {
+    let file = BufReader::new(File::open(archive)?);
+    let name = archive.to_string_lossy();
+    if name.ends_with(".tar.gz") {
+        read_tar(GzDecoder::new(file), target)
+    } else if name.ends_with(".tar.zst") {
+        let decoder = StreamingDecoder::new(file)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        read_tar(decoder, target)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "unknown archive format",
+        ))
+    }
+}
+
+fn write_tar<W: Write>(source: &Path, mut out: W, exclude: &[PathBuf]) -> io::Result<W> {
+    let mut pending = vec![source.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = fs::read_dir(&dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(fs::DirEntry::file_name);
+        for entry in entries {
+            let path = entry.path();
+            if exclude.contains(&path)
+                || SKIPPED_FILES
+                    .iter()
+                    .any(|skipped| entry.file_name() == *skipped)
+            {
+                continue;
+            }
+            let relative = path
+                .strip_prefix(source)
+                .map_err(|err| io::Error::other(err.to_string()))?;
+            let metadata = entry.metadata()?;
+            let mtime = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_secs());
+
+            if metadata.is_dir() {
+                write_header(&mut out, relative, 0, mtime, true)?;
+                pending.push(path);
+            } else if metadata.is_file() {
+                let mut file = File::open(&path)?;
+                let size = file.metadata()?.len();
+                write_header(&mut out, relative, size, mtime, false)?;
+                // Copied in pieces so that large region files are never held in memory. A file
+                // that shrank since reading its size is padded to the size in the header.
+                let copied = io::copy(&mut (&mut file).take(size), &mut out)?;
+                io::copy(&mut io::repeat(0).take(size - copied), &mut out)?;
+                write_padding(&mut out, size as usize)?;
+            }
+        }
+    }
+    // An archive ends with two zero blocks
+    out.write_all(&[0; BLOCK_SIZE * 2])?;
+    Ok(out)
+}
+
+fn write_header<W: Write>(
+    out: &mut W,
+    path: &Path,
+    size: u64,
+    mtime: u64,
+    directory: bool,
+) -> io::Result<()> {
+    let mut name = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if directory {
+        name.push('/');
+    }
+    let (prefix, name) = split_name(&name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("path too long for tar: {name}"),
+        )
+    })?;
+
+    let mut header = [0u8; BLOCK_SIZE];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], if directory { 0o755 } else { 0o644 });
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], size);
+    write_octal(&mut header[136..148], mtime);
+    header[156] = if directory { b'5' } else { b'0' };
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // The checksum is computed with the checksum field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|byte| u32::from(*byte)).sum();
+    write_octal(&mut header[148..155], u64::from(checksum));
+    header[155] = b' ';
+
+    out.write_all(&header)
+}
+
+/// Splits a path into the ustar `prefix` (155 bytes) and `name` (100 bytes) fields.
+fn split_name(path: &str) -> Option<(&str, &str)> {
+    if path.len() <= 100 {
+        return Some(("", path));
+    }
+    let trimmed = path.trim_end_matches('/');
+    let split = trimmed
+        .char_indices()
+        .filter(|(_, c)| *c == '/')
+        .map(|(index, _)| index)
+        .find(|index| path.len() - index - 1 <= 100)?;
+    (split <= 155).then(|| (&path[..split], &path[split + 1..]))
+}
+
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{value:0width$o}", width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()..].fill(0);
+}
+
+fn write_padding<W: Write>(out: &mut W, len: usize) -> io::Result<()> {
+    let remainder = len % BLOCK_SIZE;
+    if remainder != 0 {
+        out.write_all(&[0; BLOCK_SIZE][..BLOCK_SIZE - remainder])?;
+    }
+    Ok(())
+}
+
+fn read_tar<R: Read>(mut input: R, target: &Path) -> io::Result<()> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let mut header = [0u8; BLOCK_SIZE];
+    loop {
+        input.read_exact(&mut header)?;
+        if header.iter().all(|byte| *byte == 0) {
+            return Ok(());
+        }
+
+        let name = read_str(&header[..100]);
+        let prefix = read_str(&header[345..500]);
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let size = read_octal(&header[124..136]).ok_or_else(|| invalid("invalid entry size"))?;
+
+        let relative = Path::new(&path);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(invalid("archive contains an unsafe path"));
+        }
+        let destination = target.join(relative);
+
+        match header[156] {
+            b'5' => fs::create_dir_all(&destination)?,
+            b'0' | 0 => {
+                if let Some(parent) = destination.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut file = BufWriter::new(File::create(&destination)?);
+                io::copy(&mut (&mut input).take(size), &mut file)?;
+                file.flush()?;
+                let padding = (BLOCK_SIZE - (size as usize % BLOCK_SIZE)) % BLOCK_SIZE;
+                io::copy(&mut (&mut input).take(padding as u64), &mut io::sink())?;
+            }
+            _ => return Err(invalid("unsupported archive entry type")),
+        }
+    }
+}
+
+fn read_str(field: &[u8]) -> &str {
+    let end = field
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(field.len());
+    std::str::from_utf8(&field[..end]).unwrap_or_default()
+}
+
+fn read_octal(field: &[u8]) -> Option<u64> {
+    let digits = read_str(field).trim_matches(|c: char| c == ' ' || c == '\0');
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(digits, 8).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let source = tempfile::tempdir().unwrap();
+        fs::create_dir_all(source.path().join("region")).unwrap();
+        fs::write(source.path().join("level.dat"), b"level").unwrap();
+        fs::write(source.path().join("session.lock"), b"lock").unwrap();
+        fs::write(source.path().join("region/r.0.0.mca"), vec![7; 1000]).unwrap();
+
+        let archives = tempfile::tempdir().unwrap();
+        for (compression, name) in [
+            (BackupCompression::Gzip, "world.tar.gz"),
+            (BackupCompression::Zstd, "world.tar.zst"),
+        ] {
+            let archive = archives.path().join(name);
+            create(source.path(), &archive, compression, &[]).unwrap();
+
+            let target = tempfile::tempdir().unwrap();
+            extract(&archive, target.path()).unwrap();
+            assert_eq!(fs::read(target.path().join("level.dat")).unwrap(), b"level");
+            assert_eq!(
+                fs::read(target.path().join("region/r.0.0.mca")).unwrap(),
+                vec![7; 1000]
+            );
+            assert!(!target.path().join("session.lock").exists());
+        }
+    }
+
+    #[test]
+    fn long_names_use_prefix() {
+        let path = format!("{}/{}", "a".repeat(120), "b".repeat(90));
+        let (prefix, name) = split_name(&path).unwrap();
+        assert_eq!(prefix.len(), 120);
+        assert_eq!(name.len(), 90);
+        assert!(split_name(&"c".repeat(300)).is_none());
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use pumpkin_config::backup::{BackupCompression, BackupConfig};
+use thiserror::Error;
+use time::OffsetDateTime;
+use tokio::select;
+
+use crate::server::Server;
+use crate::{SHOULD_STOP, STOP_INTERRUPT};
+
+pub mod archive;
+pub mod upload;
+
+/// Name of the marker file that requests a restore on the next start.
+const PENDING_RESTORE_FILE: &str = "restore.pending";
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("A backup is already in progress")]
+    AlreadyRunning,
+    #[error("Backup {0} does not exist")]
+    NotFound(String),
+    #[error("Invalid backup name {0}")]
+    InvalidName(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// A backup archive on disk.
+pub struct BackupInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub created: u64,
+}
+
+/// Creates, lists, prunes and restores world backups.
+pub struct BackupManager {
+    config: BackupConfig,
+    directory: PathBuf,
+    running: AtomicBool,
+}
+
+impl BackupManager {
+    #[must_use]
+    pub fn new(config: BackupConfig) -> Self {
+        Self {
+            directory: PathBuf::from(&config.directory),
+            config,
+            running: AtomicBool::new(false),
+        }
+    }
+
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Takes a consistent snapshot of the world folder.
+    ///
+    /// Autosaving is paused while everything pending is flushed to disk and the archive is
+    /// written, then restored to its previous state.
+    pub async fn create_backup(&self, server: &Server) -> Result<BackupInfo, BackupError> {
+        if self.running.swap(true, Ordering::AcqRel) {
+            return Err(BackupError::AlreadyRunning);
+        }
+
+        let autosave = server.set_autosave(false);
+        server.save_all(true).await;
+
+        let world_path = server.basic_config.get_world_path();
+        let stem = format!(
+            "{}-{}",
+            server.basic_config.default_level_name,
+            file_timestamp(OffsetDateTime::now_utc()),
+        );
+        let compression = self.config.compression;
+        let directory = self.directory.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            fs::create_dir_all(&directory)?;
+            let path = directory.join(unused_name(&directory, &stem, extension(compression)));
+            // The backup directory may live inside the world folder
+            let exclude = [directory.canonicalize()?];
+            let world_path = world_path.canonicalize()?;
+            match archive::create(&world_path, &path, compression, &exclude) {
+                Ok(()) => Ok(path),
+                // Someone else created it in the meantime, it's not ours to remove
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Err(err),
+                Err(err) => {
+                    let _ = fs::remove_file(&path);
+                    Err(err)
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|err| Err(io::Error::other(err)));
+
+        server.set_autosave(autosave);
+        self.running.store(false, Ordering::Release);
+
+        let path = result?;
+        let info = BackupInfo {
+            size: fs::metadata(&path)?.len(),
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path,
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+
+        if let Some(upload) = self.config.upload.clone() {
+            let path = info.path.clone();
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = upload::upload(&upload, &path) {
+                    log::error!("Failed to upload backup {}: {err}", path.display());
+                }
+            });
+        }
+
+        if let Err(err) = self.prune() {
+            log::warn!("Failed to prune old backups: {err}");
+        }
+
+        Ok(info)
+    }
+
+    /// Lists all backups, newest first.
+    pub fn list(&self) -> io::Result<Vec<BackupInfo>> {
+        if !self.directory.exists() {
+            return Ok(Vec::new());
+        }
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !is_backup_name(&name) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let created = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_secs());
+            backups.push(BackupInfo {
+                name,
+                path: entry.path(),
+                size: metadata.len(),
+                created,
+            });
+        }
+        backups.sort_unstable_by_key(|backup| std::cmp::Reverse(backup.created));
+        Ok(backups)
+    }
+
+    /// Deletes backups not covered by the hourly and daily retention.
+    pub fn prune(&self) -> io::Result<()> {
+        let backups = self.list()?;
+        let created: Vec<_> = backups.iter().map(|backup| backup.created).collect();
+        let keep = retained(&created, self.config.keep_hourly, self.config.keep_daily);
+        for (index, backup) in backups.iter().enumerate() {
+            if !keep.contains(&index) {
+                log::info!("Removing old backup {}", backup.name);
+                fs::remove_file(&backup.path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Marks a backup to replace the world folder on the next start.
+    ///
+    /// Restoring cannot happen while the world is loaded, as chunks held in memory would
+    /// overwrite the restored files.
+    pub fn schedule_restore(&self, name: &str) -> Result<(), BackupError> {
+        if !is_backup_name(name) || name.contains(['/', '\\']) {
+            return Err(BackupError::InvalidName(name.to_string()));
+        }
+        if !self.directory.join(name).is_file() {
+            return Err(BackupError::NotFound(name.to_string()));
+        }
+        fs::write(self.directory.join(PENDING_RESTORE_FILE), name)?;
+        Ok(())
+    }
+
+    /// Periodically creates backups until the server stops.
+    pub async fn run_periodic(server: Arc<Server>) {
+        let period = Duration::from_secs(server.backups.config.interval_minutes.max(1) * 60);
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+
+        while !SHOULD_STOP.load(Ordering::Relaxed) {
+            select! {
+                _ = interval.tick() => {}
+                () = STOP_INTERRUPT.cancelled() => break,
+            }
+
+            log::info!("Creating scheduled backup...");
+            match server.backups.create_backup(&server).await {
+                Ok(info) => log::info!("Created backup {} ({} bytes)", info.name, info.size),
+                Err(err) => log::error!("Scheduled backup failed: {err}"),
+            }
+        }
+    }
+}
+
+/// Replaces the world folder with a backup if a restore was scheduled with `/backup restore`.
+///
+/// Must run before the world is locked and loaded. The previous world is kept next to it.
+pub fn apply_pending_restore(config: &BackupConfig, world_path: &Path) {
+    let directory = Path::new(&config.directory);
+    let marker = directory.join(PENDING_RESTORE_FILE);
+    let Ok(name) = fs::read_to_string(&marker) else {
+        return;
+    };
+    let _ = fs::remove_file(&marker);
+    let name = name.trim();
+    let archive_path = directory.join(name);
+
+    log::info!("Restoring world from backup {name}...");
+    let staging = world_path.with_extension("restoring");
+    let _ = fs::remove_dir_all(&staging);
+    if let Err(err) = archive::extract(&archive_path, &staging) {
+        log::error!("Failed to restore backup {name}, keeping the current world: {err}");
+        let _ = fs::remove_dir_all(&staging);
+        return;
+    }
+
+    let old = world_path.with_extension(format!(
+        "pre-restore-{}",
+        file_timestamp(OffsetDateTime::now_utc())
+    ));
+    if let Err(err) = replace_world(&staging, world_path, &old) {
+        log::error!(
+            "Failed to restore backup {name}, keeping the current world and the restored one \
+             in {}: {err}",
+            staging.display()
+        );
+        return;
+    }
+    log::info!("Restored world from backup {name}");
+}
+
+/// Moves the world at `world_path` to `old` and `staging` in its place.
+///
+/// If `staging` can't be moved, the previous world is moved back so that there is always a
+/// world to start with.
+fn replace_world(staging: &Path, world_path: &Path, old: &Path) -> io::Result<()> {
+    let had_world = world_path.exists();
+    if had_world {
+        fs::rename(world_path, old)?;
+        log::info!("Moved the previous world to {}", old.display());
+    }
+    if let Err(err) = fs::rename(staging, world_path) {
+        if had_world && let Err(back) = fs::rename(old, world_path) {
+            log::error!(
+                "Failed to move the previous world back from {}: {back}",
+                old.display()
+            );
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+const fn extension(compression: BackupCompression) -> &'static str {
+    match compression {
+        BackupCompression::Zstd => "tar.zst",
+        BackupCompression::Gzip => "tar.gz",
+    }
+}
+
+/// Returns `{stem}.{extension}`, or `{stem}-{n}.{extension}` with the lowest `n` not taken in
+/// `directory`, so that backups created in the same second don't replace each other.
+fn unused_name(directory: &Path, stem: &str, extension: &str) -> String {
+    let mut name = format!("{stem}.{extension}");
+    let mut n = 1;
+    while directory.join(&name).exists() {
+        name = format!("{stem}-{n}.{extension}");
+        n += 1;
+    }
+    name
+}
+
+fn is_backup_name(name: &str) -> bool {
+    name.ends_with(".tar.zst") || name.ends_with(".tar.gz")
+}
+
+fn file_timestamp(time: OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        time.year(),
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
+
+/// Returns the indices of the backups to keep. `created` must be sorted newest first.
+///
+/// The newest backup of each of the last `hourly` hours and `daily` days is kept, and the
+/// newest backup overall is never deleted.
+fn retained(created: &[u64], hourly: u32, daily: u32) -> HashSet<usize> {
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    let mut keep = HashSet::new();
+    if !created.is_empty() {
+        keep.insert(0);
+    }
+    for (bucket_size, count) in [(HOUR, hourly), (DAY, daily)] {
+        let mut buckets = HashSet::new();
+        for (index, time) in created.iter().enumerate() {
+            if buckets.len() >= count as usize {
+                break;
+            }
+            if buckets.insert(time / bucket_size) {
+                keep.insert(index);
+            }
+        }
+    }
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retention_keeps_newest_per_bucket() {
+        const HOUR: u64 = 60 * 60;
+        let base = 100 * 24 * HOUR;
+        // Two backups per hour over the last three hours, then one from two days ago
+        let created = [
+            base + 2 * HOUR + 30,
+            base + 2 * HOUR,
+            base + HOUR + 30,
+            base + HOUR,
+            base + 30,
+            base,
+            base - 2 * 24 * HOUR,
+        ];
+
+        let keep = retained(&created, 2, 0);
+        assert_eq!(keep, HashSet::from([0, 2]));
+
+        let keep = retained(&created, 1, 2);
+        assert_eq!(keep, HashSet::from([0, 6]));
+
+        assert_eq!(retained(&created, 0, 0), HashSet::from([0]));
+    }
+
+    #[test]
+    fn backups_in_the_same_second_get_distinct_names() {
+        let directory = tempfile::tempdir().unwrap();
+        let stem = "world-20260101-120000";
+        assert_eq!(
+            unused_name(directory.path(), stem, "tar.gz"),
+            "world-20260101-120000.tar.gz"
+        );
+
+        fs::write(directory.path().join("world-20260101-120000.tar.gz"), b"").unwrap();
+        fs::write(directory.path().join("world-20260101-120000-1.tar.gz"), b"").unwrap();
+        assert_eq!(
+            unused_name(directory.path(), stem, "tar.gz"),
+            "world-20260101-120000-2.tar.gz"
+        );
+        assert_eq!(
+            unused_name(directory.path(), stem, "tar.zst"),
+            "world-20260101-120000.tar.zst"
+        );
+    }
+
+    #[test]
+    fn failed_restore_keeps_world() {
+        let root = tempfile::tempdir().unwrap();
+        let world = root.path().join("world");
+        let old = root.path().join("world.pre-restore");
+        fs::create_dir_all(&world).unwrap();
+        fs::write(world.join("level.dat"), b"level").unwrap();
+
+        // The restored world is missing, so it can't be moved into place
+        let staging = root.path().join("world.restoring");
+        assert!(replace_world(&staging, &world, &old).is_err());
+        assert_eq!(fs::read(world.join("level.dat")).unwrap(), b"level");
+        assert!(!old.exists());
+
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(staging.join("level.dat"), b"restored").unwrap();
+        replace_world(&staging, &world, &old).unwrap();
+        assert_eq!(fs::read(world.join("level.dat")).unwrap(), b"restored");
+        assert_eq!(fs::read(old.join("level.dat")).unwrap(), b"level");
+    }
+}
@@ -0,0 +1,126 @@
+//! Uploads backups to S3-compatible object storage using AWS Signature Version 4.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::path::Path;
+
+use hmac::{Hmac, KeyInit, Mac};
+use pumpkin_config::backup::BackupUploadConfig;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Payload hash for streamed uploads; the body is protected by TLS instead.
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Uploads the file at `path` as `<prefix><file name>`. Blocking.
+pub fn upload(config: &BackupUploadConfig, path: &Path) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .ok_or("backup has no file name")?
+        .to_string_lossy();
+    let key = format!("{}{file_name}", config.prefix);
+    let endpoint = config.endpoint.trim_end_matches('/');
+    let host = endpoint
+        .split_once("://")
+        .map_or(endpoint, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    let canonical_uri = format!("/{}/{}", uri_encode(&config.bucket), uri_encode(&key));
+
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let length = file.metadata().map_err(|err| err.to_string())?.len();
+
+    let now = OffsetDateTime::now_utc();
+    let date = format!("{:04}{:02}{:02}", now.year(), now.month() as u8, now.day());
+    let timestamp = format!(
+        "{date}T{:02}{:02}{:02}Z",
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    let authorization = authorization(config, host, &canonical_uri, &date, &timestamp);
+
+    ureq::put(format!("{endpoint}{canonical_uri}"))
+        .header("Content-Length", length.to_string())
+        .header("x-amz-content-sha256", UNSIGNED_PAYLOAD)
+        .header("x-amz-date", &timestamp)
+        .header("Authorization", authorization)
+        .send(file)
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn authorization(
+    config: &BackupUploadConfig,
+    host: &str,
+    canonical_uri: &str,
+    date: &str,
+    timestamp: &str,
+) -> String {
+    const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "PUT\n{canonical_uri}\n\nhost:{host}\nx-amz-content-sha256:{UNSIGNED_PAYLOAD}\nx-amz-date:{timestamp}\n\n{SIGNED_HEADERS}\n{UNSIGNED_PAYLOAD}"
+    );
+    let scope = format!("{date}/{}/s3/aws4_request", config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{timestamp}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac(
+        format!("AWS4{}", config.secret_key).as_bytes(),
+        date.as_bytes(),
+    );
+    for part in [config.region.as_str(), "s3", "aws4_request"] {
+        key = hmac(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={SIGNED_HEADERS}, Signature={signature}",
+        config.access_key
+    )
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+/// Percent-encodes everything except unreserved characters and `/`, as S3 expects for keys.
+fn uri_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~' | b'/') {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_object_keys() {
+        assert_eq!(
+            uri_encode("backups/a b+c.tar.zst"),
+            "backups/a%20b%2Bc.tar.zst"
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! Append-only log of block changes made by players, used by `/blocklog` to look up who changed
+//! an area and to roll those changes back.
+//!
+//! Each change is one little-endian record: time (u64 seconds), player UUID, dimension id, x, y,
+//! z, old and new state id, followed by the length-prefixed player name.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use pumpkin_config::block_log::BlockLogConfig;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::BlockStateId;
+use uuid::Uuid;
+
+const LOG_FILE: &str = "changes.bin";
+const HEADER_LEN: usize = 42;
+
+/// A single recorded block change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockChange {
+    /// Unix time in seconds.
+    pub time: u64,
+    pub player: Uuid,
+    pub player_name: String,
+    pub dimension: u8,
+    pub pos: BlockPos,
+    pub old_state: BlockStateId,
+    pub new_state: BlockStateId,
+}
+
+impl BlockChange {
+    const fn encoded_len(&self) -> u64 {
+        let name_len = if self.player_name.len() > u8::MAX as usize {
+            u8::MAX as usize
+        } else {
+            self.player_name.len()
+        };
+        (HEADER_LEN + name_len) as u64
+    }
+
+    fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let name = self.player_name.as_bytes();
+        let name = &name[..name.len().min(u8::MAX as usize)];
+        writer.write_all(&self.time.to_le_bytes())?;
+        writer.write_all(self.player.as_bytes())?;
+        writer.write_all(&[self.dimension])?;
+        writer.write_all(&self.pos.0.x.to_le_bytes())?;
+        writer.write_all(&self.pos.0.y.to_le_bytes())?;
+        writer.write_all(&self.pos.0.z.to_le_bytes())?;
+        writer.write_all(&self.old_state.to_le_bytes())?;
+        writer.write_all(&self.new_state.to_le_bytes())?;
+        writer.write_all(&[name.len() as u8])?;
+        writer.write_all(name)
+    }
+
+    /// Reads the next record, returning `None` at the end of the log. A truncated trailing
+    /// record (e.g. from a crash mid-write) is treated as the end as well.
+    fn read(reader: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut header = [0; HEADER_LEN];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let mut name = vec![0; header[HEADER_LEN - 1] as usize];
+        match reader.read_exact(&mut name) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+
+        let i32_at = |at: usize| i32::from_le_bytes(header[at..at + 4].try_into().unwrap());
+        let u16_at = |at: usize| u16::from_le_bytes(header[at..at + 2].try_into().unwrap());
+        Ok(Some(Self {
+            time: u64::from_le_bytes(header[0..8].try_into().unwrap()),
+            player: Uuid::from_bytes(header[8..24].try_into().unwrap()),
+            dimension: header[24],
+            pos: BlockPos(Vector3::new(i32_at(25), i32_at(29), i32_at(33))),
+            old_state: u16_at(37),
+            new_state: u16_at(39),
+            player_name: String::from_utf8_lossy(&name).into_owned(),
+        }))
+    }
+}
+
+/// Filter for [`BlockLog::query`].
+pub struct BlockLogQuery {
+    pub dimension: u8,
+    pub center: BlockPos,
+    pub radius: i32,
+    /// Only changes at or after this Unix time are returned.
+    pub since: u64,
+    /// Case-insensitive player name filter.
+    pub player: Option<String>,
+}
+
+impl BlockLogQuery {
+    fn matches(&self, change: &BlockChange) -> bool {
+        change.dimension == self.dimension
+            && change.time >= self.since
+            && (change.pos.0.x - self.center.0.x).abs() <= self.radius
+            && (change.pos.0.y - self.center.0.y).abs() <= self.radius
+            && (change.pos.0.z - self.center.0.z).abs() <= self.radius
+            && self
+                .player
+                .as_deref()
+                .is_none_or(|name| change.player_name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Records player block changes if enabled in the config.
+pub struct BlockLog {
+    config: BlockLogConfig,
+    writer: Mutex<Option<BufWriter<File>>>,
+}
+
+impl BlockLog {
+    #[must_use]
+    pub fn new(config: BlockLogConfig) -> Self {
+        let writer = if config.enabled {
+            match Self::open(&config) {
+                Ok(writer) => Some(writer),
+                Err(err) => {
+                    log::error!(
+                        "Failed to open the block log, block changes are not recorded: {err}"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        Self {
+            config,
+            writer: Mutex::new(writer),
+        }
+    }
+
+    fn path(config: &BlockLogConfig) -> PathBuf {
+        Path::new(&config.directory).join(LOG_FILE)
+    }
+
+    fn open(config: &BlockLogConfig) -> io::Result<BufWriter<File>> {
+        fs::create_dir_all(&config.directory)?;
+        let path = Self::path(config);
+        if path.exists() {
+            let cutoff = if config.retention_days > 0 {
+                unix_time().saturating_sub(u64::from(config.retention_days) * 86400)
+            } else {
+                0
+            };
+            let removed = compact(&path, cutoff)?;
+            if removed > 0 {
+                log::info!(
+                    "Removed {removed} block log entries older than {} days",
+                    config.retention_days
+                );
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(BufWriter::new(file))
+    }
+
+    pub const fn config(&self) -> &BlockLogConfig {
+        &self.config
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.writer.lock().unwrap().is_some()
+    }
+
+    /// Appends a change. Does nothing if the log is disabled or the state did not change.
+    pub fn record(
+        &self,
+        player: Uuid,
+        player_name: &str,
+        dimension: u8,
+        pos: BlockPos,
+        old_state: BlockStateId,
+        new_state: BlockStateId,
+    ) {
+        if old_state == new_state {
+            return;
+        }
+        let mut writer = self.writer.lock().unwrap();
+        let Some(writer) = writer.as_mut() else {
+            return;
+        };
+        let change = BlockChange {
+            time: unix_time(),
+            player,
+            player_name: player_name.to_string(),
+            dimension,
+            pos,
+            old_state,
+            new_state,
+        };
+        if let Err(err) = change.write(writer) {
+            log::error!("Failed to write to the block log: {err}");
+        }
+    }
+
+    /// Writes buffered changes to disk.
+    pub fn flush(&self) {
+        if let Some(writer) = self.writer.lock().unwrap().as_mut()
+            && let Err(err) = writer.flush()
+        {
+            log::error!("Failed to flush the block log: {err}");
+        }
+    }
+
+    /// Returns all matching changes, oldest first.
+    ///
+    /// The log is read on a blocking thread, as it may be large.
+    pub async fn query(&self, query: BlockLogQuery) -> io::Result<Vec<BlockChange>> {
+        self.flush();
+        let path = Self::path(&self.config);
+        tokio::task::spawn_blocking(move || read_matching(&path, &query))
+            .await
+            .map_err(io::Error::other)?
+    }
+}
+
+/// Streams the log at `path`, keeping the changes that match `query`.
+fn read_matching(path: &Path, query: &BlockLogQuery) -> io::Result<Vec<BlockChange>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut reader = BufReader::new(file);
+    let mut changes = Vec::new();
+    while let Some(change) = BlockChange::read(&mut reader)? {
+        if query.matches(&change) {
+            changes.push(change);
+        }
+    }
+    Ok(changes)
+}
+
+/// Drops entries older than `cutoff` and any torn trailing record by rewriting the log, so new
+/// records are appended after a valid one. Returns the number of removed entries.
+fn compact(path: &Path, cutoff: u64) -> io::Result<usize> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut kept = Vec::new();
+    let mut removed = 0;
+    let mut valid_len = 0;
+    while let Some(change) = BlockChange::read(&mut reader)? {
+        valid_len += change.encoded_len();
+        if change.time >= cutoff {
+            kept.push(change);
+        } else {
+            removed += 1;
+        }
+    }
+    if removed == 0 && valid_len == file_len {
+        return Ok(0);
+    }
+
+    let tmp = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+    for change in &kept {
+        change.write(&mut writer)?;
+    }
+    writer
+        .into_inner()
+        .map_err(io::IntoInnerError::into_error)?
+        .sync_all()?;
+    fs::rename(tmp, path)?;
+    Ok(removed)
+}
+
+/// Computes the state every affected position should be set to.
+///
+/// A rollback restores the state before the first change, a restore re-applies the state after
+/// the last one. `changes` must be ordered oldest first.
+#[must_use]
+pub fn rollback_targets(changes: &[BlockChange], restore: bool) -> Vec<(BlockPos, BlockStateId)> {
+    let mut targets: Vec<(BlockPos, BlockStateId)> = Vec::new();
+    let mut index = HashMap::new();
+    for change in changes {
+        match index.entry(change.pos) {
+            Entry::Vacant(entry) => {
+                entry.insert(targets.len());
+                let state = if restore {
+                    change.new_state
+                } else {
+                    change.old_state
+                };
+                targets.push((change.pos, state));
+            }
+            Entry::Occupied(entry) => {
+                if restore {
+                    targets[*entry.get()].1 = change.new_state;
+                }
+            }
+        }
+    }
+    targets
+}
+
+/// Current Unix time in seconds, as stored in [`BlockChange::time`].
+#[must_use]
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(time: u64, x: i32, old_state: u16, new_state: u16) -> BlockChange {
+        BlockChange {
+            time,
+            player: Uuid::from_u128(7),
+            player_name: "Alex".to_string(),
+            dimension: 0,
+            pos: BlockPos(Vector3::new(x, 64, -3)),
+            old_state,
+            new_state,
+        }
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let changes = [change(10, 1, 0, 1), change(20, -5, 9, 0)];
+        let mut bytes = Vec::new();
+        for change in &changes {
+            change.write(&mut bytes).unwrap();
+        }
+        // A torn trailing write is ignored
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let mut reader = bytes.as_slice();
+        assert_eq!(
+            BlockChange::read(&mut reader).unwrap(),
+            Some(changes[0].clone())
+        );
+        assert_eq!(
+            BlockChange::read(&mut reader).unwrap(),
+            Some(changes[1].clone())
+        );
+        assert_eq!(BlockChange::read(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn query_filters_streamed_changes() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(LOG_FILE);
+        let mut file = File::create(&path).unwrap();
+        for change in [
+            change(10, 1, 0, 1),
+            change(20, 40, 9, 0),
+            change(30, 2, 1, 0),
+        ] {
+            change.write(&mut file).unwrap();
+        }
+
+        let query = BlockLogQuery {
+            dimension: 0,
+            center: BlockPos(Vector3::new(0, 64, 0)),
+            radius: 5,
+            since: 15,
+            player: Some("alex".to_string()),
+        };
+        assert_eq!(
+            read_matching(&path, &query).unwrap(),
+            vec![change(30, 2, 1, 0)]
+        );
+        let missing = directory.path().join("missing.log");
+        assert_eq!(read_matching(&missing, &query).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn rollback_uses_first_old_and_last_new_state() {
+        let changes = [change(1, 0, 1, 2), change(2, 1, 5, 0), change(3, 0, 2, 3)];
+        assert_eq!(
+            rollback_targets(&changes, false),
+            vec![(changes[0].pos, 1), (changes[1].pos, 5)]
+        );
+        assert_eq!(
+            rollback_targets(&changes, true),
+            vec![(changes[0].pos, 3), (changes[1].pos, 0)]
+        );
+    }
+}
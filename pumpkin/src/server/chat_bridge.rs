@@ -0,0 +1,461 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use pumpkin_config::integrations::ChatBridgeConfig;
+use pumpkin_util::text::TextComponent;
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::select;
+use tokio::sync::mpsc;
+use ureq::Agent;
+
+use crate::STOP_INTERRUPT;
+use crate::server::Server;
+
+/// Messages waiting to be posted to the webhook. Newer ones are dropped while it is full.
+const QUEUE_CAPACITY: usize = 256;
+/// Discord rejects longer messages.
+const MAX_MESSAGE_LENGTH: usize = 2000;
+const DISCORD_API: &str = "https://discord.com/api/v10";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long to wait after Discord limited the rate of the requests.
+const RATE_LIMIT_DELAY: Duration = Duration::from_secs(2);
+/// How long a client of the HTTP endpoint may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Requests larger than this are rejected, messages are short.
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// The events of the server the bridge posts besides chat messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeEvent {
+    Join,
+    Leave,
+    Death,
+}
+
+#[derive(Deserialize)]
+struct DiscordMessage {
+    id: String,
+    content: String,
+    author: DiscordUser,
+    /// Set for messages posted by webhooks, like the bridge's own ones.
+    webhook_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DiscordUser {
+    username: String,
+    global_name: Option<String>,
+    #[serde(default)]
+    bot: bool,
+}
+
+#[derive(Deserialize)]
+struct InboundMessage {
+    author: String,
+    message: String,
+}
+
+/// Relays the chat between the server and Discord, or any service that can post JSON.
+///
+/// Chat messages, joins, leaves and deaths are posted to a Discord webhook. Messages of a
+/// Discord channel are read with a bot, and other services can post messages to a small HTTP
+/// endpoint. Both are shown to every player.
+pub struct ChatBridge {
+    config: ChatBridgeConfig,
+    agent: Agent,
+    outbound: mpsc::Sender<String>,
+    /// Taken by the task posting the messages.
+    outbound_receiver: Mutex<Option<mpsc::Receiver<String>>>,
+}
+
+impl ChatBridge {
+    #[must_use]
+    pub fn new(config: ChatBridgeConfig) -> Self {
+        let (outbound, outbound_receiver) = mpsc::channel(QUEUE_CAPACITY);
+        let agent = Agent::config_builder()
+            .timeout_global(Some(HTTP_TIMEOUT))
+            .build()
+            .into();
+        Self {
+            config,
+            agent,
+            outbound,
+            outbound_receiver: Mutex::new(Some(outbound_receiver)),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Posts a chat message of `player`.
+    pub fn relay_chat(&self, player: &str, message: &str) {
+        if !self.config.relay_chat {
+            return;
+        }
+        let text = self
+            .config
+            .chat_format
+            .replace("{player}", &escape_markdown(player))
+            .replace("{message}", &escape_markdown(message));
+        self.post(text);
+    }
+
+    /// Posts the message shown in the game for `event`.
+    pub fn relay_event(&self, event: BridgeEvent, message: &TextComponent) {
+        let enabled = match event {
+            BridgeEvent::Join | BridgeEvent::Leave => self.config.relay_joins,
+            BridgeEvent::Death => self.config.relay_deaths,
+        };
+        if !enabled {
+            return;
+        }
+        let text = self
+            .config
+            .event_format
+            .replace("{message}", &escape_markdown(&message.clone().get_text()));
+        self.post(text);
+    }
+
+    fn post(&self, text: String) {
+        if !self.config.enabled || self.config.webhook_url.is_empty() {
+            return;
+        }
+        if self.outbound.try_send(text).is_err() {
+            log::debug!("The chat bridge fell behind, dropping a message");
+        }
+    }
+
+    /// Posts the server's messages and shows the inbound ones until the server stops.
+    pub async fn run(server: Arc<Server>) {
+        let bridge = &server.chat_bridge;
+        select! {
+            () = bridge.post_messages() => {}
+            () = bridge.read_discord(&server) => {}
+            () = bridge.serve_http(&server) => {}
+            () = STOP_INTERRUPT.cancelled() => {}
+        }
+    }
+
+    async fn post_messages(&self) {
+        let Some(mut receiver) = self.outbound_receiver.lock().unwrap().take() else {
+            return;
+        };
+        while let Some(mut content) = receiver.recv().await {
+            // Messages sent meanwhile go in one post, Discord limits the rate of webhooks
+            while let Ok(next) = receiver.try_recv() {
+                if content.len() + next.len() >= MAX_MESSAGE_LENGTH {
+                    self.post_webhook(content).await;
+                    content = next;
+                } else {
+                    content.push('\n');
+                    content.push_str(&next);
+                }
+            }
+            self.post_webhook(content).await;
+        }
+    }
+
+    async fn post_webhook(&self, mut content: String) {
+        if content.len() > MAX_MESSAGE_LENGTH {
+            let mut end = MAX_MESSAGE_LENGTH;
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            content.truncate(end);
+        }
+        let mut body = serde_json::json!({
+            "content": content,
+            // Players can't ping the members of the Discord
+            "allowed_mentions": { "parse": [] },
+        });
+        if let Some(username) = &self.config.webhook_username {
+            body["username"] = username.clone().into();
+        }
+        let agent = self.agent.clone();
+        let url = self.config.webhook_url.clone();
+        for _ in 0..2 {
+            let agent = agent.clone();
+            let url = url.clone();
+            let body = body.clone();
+            // ureq is blocking, keep it away from the tick loop
+            let result = tokio::task::spawn_blocking(move || {
+                agent
+                    .post(&url)
+                    .header("User-Agent", "Pumpkin-MC")
+                    .send_json(&body)
+                    .map(|_| ())
+            })
+            .await;
+            match result {
+                Ok(Err(ureq::Error::StatusCode(429))) => {
+                    tokio::time::sleep(RATE_LIMIT_DELAY).await;
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Failed to post to the chat bridge webhook: {err}");
+                    return;
+                }
+                Ok(Ok(())) | Err(_) => return,
+            }
+        }
+    }
+
+    /// Shows the messages written in the Discord channel, read every few seconds.
+    async fn read_discord(&self, server: &Server) {
+        let Some(token) = self
+            .config
+            .bot_token
+            .clone()
+            .filter(|_| self.config.enabled)
+        else {
+            return std::future::pending().await;
+        };
+        let url = format!("{DISCORD_API}/channels/{}/messages", self.config.channel_id);
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            self.config.poll_interval_seconds.max(1),
+        ));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The newest message seen, older ones were written before the server started
+        let mut last_seen: Option<String> = None;
+        loop {
+            interval.tick().await;
+            let query = last_seen.as_ref().map_or_else(
+                || "?limit=1".to_string(),
+                |id| format!("?after={id}&limit=50"),
+            );
+            let agent = self.agent.clone();
+            let request_url = format!("{url}{query}");
+            let token = token.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                agent
+                    .get(&request_url)
+                    .header("User-Agent", "Pumpkin-MC")
+                    .header("Authorization", format!("Bot {token}"))
+                    .call()?
+                    .body_mut()
+                    .read_json::<Vec<DiscordMessage>>()
+            })
+            .await;
+            let messages = match result {
+                Ok(Ok(messages)) => messages,
+                Ok(Err(err)) => {
+                    log::debug!("Failed to read the chat bridge channel: {err}");
+                    continue;
+                }
+                Err(_) => continue,
+            };
+            let first_read = last_seen.is_none();
+            if let Some(newest) = messages.first() {
+                last_seen = Some(newest.id.clone());
+            } else if first_read {
+                // An empty channel, everything from now on is new
+                last_seen = Some("0".to_string());
+            }
+            if first_read {
+                continue;
+            }
+            // Discord returns the newest message first
+            for message in messages.into_iter().rev() {
+                if message.webhook_id.is_some() || message.author.bot || message.content.is_empty()
+                {
+                    continue;
+                }
+                let author = message
+                    .author
+                    .global_name
+                    .unwrap_or(message.author.username);
+                self.show(server, &author, &message.content).await;
+            }
+        }
+    }
+
+    /// Accepts messages posted by other services, until the server stops.
+    async fn serve_http(&self, server: &Server) {
+        let Some(address) = self.config.http_address.filter(|_| self.config.enabled) else {
+            return std::future::pending().await;
+        };
+        if self.config.http_token.is_empty() {
+            log::warn!("The chat bridge endpoint needs an http_token, not starting it");
+            return std::future::pending().await;
+        }
+        let listener = match TcpListener::bind(address).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                log::error!("Failed to bind the chat bridge endpoint to {address}: {err}");
+                return std::future::pending().await;
+            }
+        };
+        log::info!("Chat bridge endpoint running on {address}");
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    if let Err(err) = self.handle_connection(stream, peer, server).await {
+                        log::debug!("Chat bridge connection failed: {err}");
+                    }
+                }
+                Err(err) => {
+                    log::warn!("Failed to accept a chat bridge connection: {err}");
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }
+        }
+    }
+
+    async fn handle_connection(
+        &self,
+        mut stream: TcpStream,
+        peer: SocketAddr,
+        server: &Server,
+    ) -> std::io::Result<()> {
+        let mut request = Vec::with_capacity(512);
+        let mut buf = [0; 512];
+        let read = async {
+            loop {
+                if let Some(end) = header_end(&request)
+                    && request.len() >= end + content_length(&request[..end])
+                {
+                    break;
+                }
+                let read = stream.read(&mut buf).await?;
+                if read == 0 || request.len() > MAX_REQUEST_SIZE {
+                    break;
+                }
+                request.extend_from_slice(&buf[..read]);
+            }
+            Ok::<_, std::io::Error>(())
+        };
+        let Ok(result) = tokio::time::timeout(REQUEST_TIMEOUT, read).await else {
+            return Ok(());
+        };
+        result?;
+
+        let status = match self.parse_request(&request) {
+            Ok(message) => {
+                log::debug!("Chat bridge message from {peer}");
+                self.show(server, &message.author, &message.message).await;
+                "204 No Content"
+            }
+            Err(status) => status,
+        };
+        let response =
+            format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    /// Reads the message of a request, or the status to reject it with.
+    fn parse_request(&self, request: &[u8]) -> Result<InboundMessage, &'static str> {
+        let end = header_end(request).ok_or("400 Bad Request")?;
+        let head = String::from_utf8_lossy(&request[..end]);
+        let mut lines = head.lines();
+        let mut parts = lines.next().unwrap_or_default().split(' ');
+        if parts.next() != Some("POST") {
+            return Err("405 Method Not Allowed");
+        }
+        if parts.next() != Some("/message") {
+            return Err("404 Not Found");
+        }
+        let authorized = lines.any(|line| {
+            line.split_once(':').is_some_and(|(name, value)| {
+                name.eq_ignore_ascii_case("authorization")
+                    && value.trim().strip_prefix("Bearer ") == Some(&self.config.http_token)
+            })
+        });
+        if !authorized {
+            return Err("401 Unauthorized");
+        }
+        serde_json::from_slice(&request[end..]).map_err(|_| "400 Bad Request")
+    }
+
+    /// Shows a message from outside the server to every player.
+    async fn show(&self, server: &Server, author: &str, message: &str) {
+        let text = self
+            .config
+            .inbound_format
+            .replace('&', "§")
+            .replace("{author}", &strip_formatting(author))
+            .replace("{message}", &strip_formatting(message));
+        let text = TextComponent::text(text);
+        log::info!("{}", text.clone().to_pretty_console());
+        for player in server.get_all_players() {
+            player.send_system_message(&text).await;
+        }
+    }
+}
+
+/// The end of the headers of an HTTP request, where the body starts.
+fn header_end(request: &[u8]) -> Option<usize> {
+    request
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|position| position + 4)
+}
+
+fn content_length(head: &[u8]) -> usize {
+    String::from_utf8_lossy(head)
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse().ok())
+                .flatten()
+        })
+        .unwrap_or(0)
+}
+
+/// Escapes the characters Discord formats text with, so names and messages show as written.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '_' | '~' | '`' | '|' | '>' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Removes the color codes of the game, so outside messages can't use them.
+fn strip_formatting(text: &str) -> String {
+    text.chars()
+        .filter(|c| *c != '§' && !c.is_control())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discord_formatting_is_escaped() {
+        assert_eq!(escape_markdown("**bold** _x_"), "\\*\\*bold\\*\\* \\_x\\_");
+    }
+
+    #[test]
+    fn requests_need_the_token() {
+        let bridge = ChatBridge::new(ChatBridgeConfig {
+            http_token: "secret".to_string(),
+            ..Default::default()
+        });
+        let body = r#"{"author":"Alex","message":"hi"}"#;
+        let request = |token: &str| {
+            format!(
+                "POST /message HTTP/1.1\r\nAuthorization: Bearer {token}\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+        };
+        let message = bridge
+            .parse_request(request("secret").as_bytes())
+            .ok()
+            .unwrap();
+        assert_eq!(message.author, "Alex");
+        assert_eq!(
+            bridge.parse_request(request("wrong").as_bytes()).err(),
+            Some("401 Unauthorized")
+        );
+    }
+}
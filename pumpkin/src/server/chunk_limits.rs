@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pumpkin_config::chunk_limits::{ChunkLimitStrategy, ChunkLimitsConfig};
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::tag::{self, Taggable};
+use pumpkin_util::PermissionLvl;
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+
+use crate::entity::{Entity, EntityBase};
+use crate::world::World;
+
+/// Entities that only live briefly and are not capped, so that a full chunk doesn't delete
+/// block drops, loot or shots. Projectiles of the `impact_projectiles` tag are exempt as well.
+const TRANSIENT_ENTITIES: [&EntityType; 10] = [
+    &EntityType::ITEM,
+    &EntityType::EXPERIENCE_ORB,
+    &EntityType::SPLASH_POTION,
+    &EntityType::LINGERING_POTION,
+    &EntityType::ENDER_PEARL,
+    &EntityType::EXPERIENCE_BOTTLE,
+    &EntityType::EYE_OF_ENDER,
+    &EntityType::FISHING_BOBBER,
+    &EntityType::LLAMA_SPIT,
+    &EntityType::SHULKER_BULLET,
+];
+
+/// What a chunk cap applies to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkLimitKind {
+    Entity,
+    ItemFrame,
+    BlockEntity,
+}
+
+impl ChunkLimitKind {
+    /// Returns the cap entities of `entity_type` count against, or `None` if they are transient.
+    #[must_use]
+    pub fn of_entity(entity_type: &EntityType) -> Option<Self> {
+        if TRANSIENT_ENTITIES.contains(&entity_type)
+            || entity_type.has_tag(&tag::EntityType::MINECRAFT_IMPACT_PROJECTILES)
+        {
+            None
+        } else if entity_type == &EntityType::ITEM_FRAME
+            || entity_type == &EntityType::GLOW_ITEM_FRAME
+        {
+            Some(Self::ItemFrame)
+        } else {
+            Some(Self::Entity)
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Entity => "entities",
+            Self::ItemFrame => "item frames",
+            Self::BlockEntity => "block entities",
+        }
+    }
+}
+
+/// Number of entities, item frames and block entities in a chunk.
+#[derive(Default)]
+pub struct ChunkCounts {
+    pub entities: usize,
+    pub item_frames: usize,
+    pub block_entities: usize,
+}
+
+impl ChunkCounts {
+    pub async fn count(world: &Arc<World>, chunk: Vector2<i32>) -> Self {
+        Self {
+            entities: world.chunk_entity_counts.get(chunk, ChunkLimitKind::Entity),
+            item_frames: world
+                .chunk_entity_counts
+                .get(chunk, ChunkLimitKind::ItemFrame),
+            block_entities: Self::block_entities(world, chunk).await,
+        }
+    }
+
+    async fn block_entities(world: &Arc<World>, chunk: Vector2<i32>) -> usize {
+        world
+            .level
+            .get_chunk(chunk)
+            .await
+            .block_entities
+            .lock()
+            .unwrap()
+            .len()
+    }
+}
+
+/// The capped entities of a world by chunk, updated as entities are added, removed and move
+/// between chunks so that spawns don't have to look at every entity.
+#[derive(Default)]
+pub struct ChunkEntityCounts {
+    inner: Mutex<EntityCountsInner>,
+}
+
+#[derive(Default)]
+struct EntityCountsInner {
+    /// The chunk and kind each counted entity is counted in, by entity id.
+    entities: HashMap<i32, (Vector2<i32>, ChunkLimitKind)>,
+    counts: HashMap<(Vector2<i32>, ChunkLimitKind), usize>,
+}
+
+impl EntityCountsInner {
+    fn increment(&mut self, chunk: Vector2<i32>, kind: ChunkLimitKind) {
+        *self.counts.entry((chunk, kind)).or_default() += 1;
+    }
+
+    fn decrement(&mut self, chunk: Vector2<i32>, kind: ChunkLimitKind) {
+        if let Entry::Occupied(mut entry) = self.counts.entry((chunk, kind)) {
+            *entry.get_mut() -= 1;
+            if *entry.get() == 0 {
+                entry.remove();
+            }
+        }
+    }
+}
+
+impl ChunkEntityCounts {
+    /// Counts `entity` in its chunk. Counting an entity twice has no effect.
+    pub fn add(&self, entity: &Entity) {
+        let Some(kind) = ChunkLimitKind::of_entity(entity.entity_type) else {
+            return;
+        };
+        let chunk = entity.chunk_pos.load();
+        let mut inner = self.inner.lock().unwrap();
+        if let Some((old_chunk, old_kind)) = inner.entities.insert(entity.entity_id, (chunk, kind))
+        {
+            inner.decrement(old_chunk, old_kind);
+        }
+        inner.increment(chunk, kind);
+    }
+
+    /// Stops counting the entity with `entity_id`.
+    pub fn remove(&self, entity_id: i32) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some((chunk, kind)) = inner.entities.remove(&entity_id) {
+            inner.decrement(chunk, kind);
+        }
+    }
+
+    /// Moves the entity with `entity_id` to `chunk`, if it is counted.
+    pub fn moved(&self, entity_id: i32, chunk: Vector2<i32>) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(counted) = inner.entities.get_mut(&entity_id) else {
+            return;
+        };
+        let kind = counted.1;
+        let old_chunk = std::mem::replace(&mut counted.0, chunk);
+        if old_chunk != chunk {
+            inner.decrement(old_chunk, kind);
+            inner.increment(chunk, kind);
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, chunk: Vector2<i32>, kind: ChunkLimitKind) -> usize {
+        self.inner
+            .lock()
+            .unwrap()
+            .counts
+            .get(&(chunk, kind))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Enforces the per-chunk caps from [`ChunkLimitsConfig`].
+pub struct ChunkLimits {
+    config: ChunkLimitsConfig,
+    /// Entity cap of an applied TPS mitigation, `0` if there is none.
+    mitigation_max_entities: AtomicU32,
+    last_notified: Mutex<HashMap<(u8, Vector2<i32>), Instant>>,
+}
+
+impl ChunkLimits {
+    #[must_use]
+    pub fn new(config: ChunkLimitsConfig) -> Self {
+        Self {
+            config,
+            mitigation_max_entities: AtomicU32::new(0),
+            last_notified: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &ChunkLimitsConfig {
+        &self.config
+    }
+
+    /// Returns the cap for `kind`, or `None` if it is not limited.
+    #[must_use]
+    pub fn limit(&self, kind: ChunkLimitKind) -> Option<usize> {
+        let limit = match kind {
+            ChunkLimitKind::Entity => self.config.max_entities,
+            ChunkLimitKind::ItemFrame => self.config.max_item_frames,
+            ChunkLimitKind::BlockEntity => self.config.max_block_entities,
+        };
+        let configured = (self.config.enabled && limit != 0).then_some(limit);
+        let mitigation = match self.mitigation_max_entities.load(Ordering::Relaxed) {
+            0 => None,
+            _ if kind != ChunkLimitKind::Entity => None,
+            cap => Some(cap),
+        };
+        configured
+            .into_iter()
+            .chain(mitigation)
+            .min()
+            .map(|limit| limit as usize)
+    }
+
+    /// Caps the entities in every chunk while a TPS mitigation applies, on top of the configured
+    /// cap.
+    pub fn set_mitigation_max_entities(&self, max_entities: Option<u32>) {
+        self.mitigation_max_entities
+            .store(max_entities.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Checks whether one more `kind` may be added to `chunk`, culling the oldest entity or
+    /// notifying operators as configured. Returns `false` if the addition must be denied.
+    pub async fn allow(
+        &self,
+        world: &Arc<World>,
+        chunk: Vector2<i32>,
+        kind: ChunkLimitKind,
+    ) -> bool {
+        let Some(limit) = self.limit(kind) else {
+            return true;
+        };
+        let count = match kind {
+            ChunkLimitKind::BlockEntity => ChunkCounts::block_entities(world, chunk).await,
+            _ => world.chunk_entity_counts.get(chunk, kind),
+        };
+        if count < limit {
+            return true;
+        }
+
+        let allowed = match self.config.strategy {
+            ChunkLimitStrategy::Deny => false,
+            ChunkLimitStrategy::Notify => true,
+            ChunkLimitStrategy::CullOldest => {
+                if kind == ChunkLimitKind::BlockEntity {
+                    false
+                } else {
+                    // The world's entity list is in spawn order, so the first match is the oldest
+                    let oldest = world
+                        .entities
+                        .load()
+                        .iter()
+                        .find(|entity| {
+                            let entity = entity.get_entity();
+                            entity.block_pos.load().chunk_position() == chunk
+                                && ChunkLimitKind::of_entity(entity.entity_type) == Some(kind)
+                        })
+                        .cloned();
+                    if let Some(oldest) = oldest {
+                        oldest.get_entity().remove().await;
+                    }
+                    true
+                }
+            }
+        };
+        self.notify(world, chunk, kind, limit, allowed).await;
+        allowed
+    }
+
+    async fn notify(
+        &self,
+        world: &Arc<World>,
+        chunk: Vector2<i32>,
+        kind: ChunkLimitKind,
+        limit: usize,
+        allowed: bool,
+    ) {
+        let cooldown = Duration::from_secs(self.config.notify_cooldown_seconds);
+        {
+            let mut last_notified = self.last_notified.lock().unwrap();
+            let now = Instant::now();
+            last_notified.retain(|_, at| now.duration_since(*at) < cooldown);
+            match last_notified.entry((world.dimension.id, chunk)) {
+                Entry::Occupied(_) => return,
+                Entry::Vacant(entry) => {
+                    entry.insert(now);
+                }
+            }
+        }
+
+        let action = match (self.config.strategy, allowed) {
+            (_, false) => "denied",
+            (ChunkLimitStrategy::CullOldest, true) => "culled the oldest",
+            _ => "allowed",
+        };
+        let message = format!(
+            "Chunk {} {} in {} reached its cap of {limit} {}, {action}",
+            chunk.x,
+            chunk.y,
+            world.dimension.minecraft_name,
+            kind.name()
+        );
+        log::warn!("{message}");
+        if !self.config.notify_admins {
+            return;
+        }
+        let Some(server) = world.server.upgrade() else {
+            return;
+        };
+        let text = TextComponent::text(message).color_named(NamedColor::Yellow);
+        for player in server.get_all_players() {
+            if player.permission_lvl.load() >= PermissionLvl::Two {
+                player.send_system_message(&text).await;
+            }
+        }
+    }
+}
@@ -2,9 +2,10 @@ use crate::entity::player::Player;
 use base64::{Engine as _, engine::general_purpose};
 use core::error;
 use pumpkin_config::BasicConfiguration;
+use pumpkin_config::networking::server_list::ServerListConfig;
 use pumpkin_data::packet::CURRENT_MC_PROTOCOL;
 use pumpkin_protocol::{
-    Players, StatusResponse, Version,
+    Players, Sample, StatusResponse, Version,
     codec::var_int::VarInt,
     java::client::{config::CPluginMessage, status::CStatusResponse},
 };
@@ -13,6 +14,7 @@ use std::{
     fs::{self},
     path::Path,
 };
+use time::OffsetDateTime;
 
 const DEFAULT_ICON: &[u8] = include_bytes!("../../../assets/default_icon.png");
 
@@ -123,6 +125,36 @@ impl CachedStatus {
             .expect("Failed to parse status response into JSON");
     }
 
+    /// Returns the MOTD to show at `now`: an active scheduled MOTD, else the current one of the
+    /// rotation, else `default`.
+    #[must_use]
+    pub fn current_motd(config: &ServerListConfig, default: &str, now: OffsetDateTime) -> String {
+        if let Some(scheduled) = config
+            .motd_schedule
+            .iter()
+            .find(|scheduled| scheduled.is_active(now.hour()))
+        {
+            return scheduled.motd.clone();
+        }
+        if config.motds.is_empty() {
+            return default.to_string();
+        }
+        let slot = now.unix_timestamp().unsigned_abs() / config.motd_rotation_seconds.max(1);
+        config.motds[(slot % config.motds.len() as u64) as usize].clone()
+    }
+
+    /// Turns text lines into player sample entries, which the client shows as-is.
+    #[must_use]
+    pub fn sample_lines(lines: &[String]) -> Vec<Sample> {
+        lines
+            .iter()
+            .map(|line| Sample {
+                name: line.clone(),
+                id: uuid::Uuid::nil().to_string(),
+            })
+            .collect()
+    }
+
     pub fn build_response(config: &BasicConfiguration) -> StatusResponse {
         let favicon = if config.use_favicon {
             config.favicon_path.as_ref().map_or_else(
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use pumpkin_world::chunk_system::ChunkLoading;
+use pumpkin_world::level::ChunkRetainer;
+
+use crate::server::Server;
+
+/// Puts the server into a deep sleep once it has been empty for a while, see
+/// [`DeepSleepConfig`](pumpkin_config::deep_sleep::DeepSleepConfig).
+#[derive(Default)]
+pub struct DeepSleep {
+    /// Since when no player is online.
+    empty_since: Mutex<Option<Instant>>,
+    asleep: AtomicBool,
+}
+
+/// How the sleep state changed in a tick.
+#[derive(Debug, PartialEq, Eq)]
+enum SleepChange {
+    None,
+    FellAsleep,
+    WokeUp,
+}
+
+impl DeepSleep {
+    /// Whether the server is asleep, in which case entities don't tick.
+    #[must_use]
+    pub fn is_asleep(&self) -> bool {
+        self.asleep.load(Ordering::Relaxed)
+    }
+
+    /// Falls asleep or wakes up depending on the player count. Runs before the worlds tick, so
+    /// that entities already tick in the tick a player joins in.
+    pub fn tick(&self, server: &Arc<Server>) {
+        let config = &server.advanced_config.deep_sleep;
+        let delay = Duration::from_secs(config.delay_minutes.saturating_mul(60));
+        let awake = server.has_n_players(1) || !config.enabled;
+        match self.update(awake, delay, Instant::now()) {
+            SleepChange::None => return,
+            SleepChange::WokeUp => {
+                log::info!("Waking up from deep sleep");
+                return;
+            }
+            SleepChange::FellAsleep => {}
+        }
+
+        log::info!(
+            "Nobody was online for {} minutes, falling into a deep sleep",
+            config.delay_minutes
+        );
+        let mut released = 0;
+        for world in server.worlds.load().iter() {
+            // Without players, every ticket that isn't forced or backs a chunk load in progress is
+            // left behind by something
+            let positions: Vec<_> = world
+                .level
+                .stale_chunks(&[])
+                .into_iter()
+                .filter_map(|chunk| match chunk.retainer {
+                    ChunkRetainer::Ticket(at, level)
+                        if level != ChunkLoading::LOAD_TICKET_LEVEL =>
+                    {
+                        Some(at)
+                    }
+                    _ => None,
+                })
+                .collect();
+            released += world.level.release_tickets(&positions);
+            world.level.clean_memory();
+        }
+        log::debug!("Released {released} chunk tickets for the deep sleep");
+
+        let server = server.clone();
+        server.clone().spawn_task(async move {
+            server.save_all(true).await;
+        });
+    }
+
+    /// Stays awake while `awake` is set, and falls asleep once it was unset for `delay`.
+    fn update(&self, awake: bool, delay: Duration, now: Instant) -> SleepChange {
+        let mut empty_since = self.empty_since.lock().unwrap();
+        if awake {
+            *empty_since = None;
+            return if self.asleep.swap(false, Ordering::Relaxed) {
+                SleepChange::WokeUp
+            } else {
+                SleepChange::None
+            };
+        }
+        let empty_for = now.duration_since(*empty_since.get_or_insert(now));
+        if empty_for < delay || self.asleep.swap(true, Ordering::Relaxed) {
+            return SleepChange::None;
+        }
+        SleepChange::FellAsleep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_asleep_when_empty_and_wakes_up_on_join() {
+        let deep_sleep = DeepSleep::default();
+        let delay = Duration::from_secs(300);
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert_eq!(deep_sleep.update(true, delay, start), SleepChange::None);
+        assert_eq!(deep_sleep.update(false, delay, at(10)), SleepChange::None);
+        assert_eq!(deep_sleep.update(false, delay, at(309)), SleepChange::None);
+        assert!(!deep_sleep.is_asleep());
+        assert_eq!(
+            deep_sleep.update(false, delay, at(310)),
+            SleepChange::FellAsleep
+        );
+        assert!(deep_sleep.is_asleep());
+        assert_eq!(deep_sleep.update(false, delay, at(400)), SleepChange::None);
+        assert!(deep_sleep.is_asleep());
+
+        assert_eq!(deep_sleep.update(true, delay, at(401)), SleepChange::WokeUp);
+        assert!(!deep_sleep.is_asleep());
+        assert_eq!(deep_sleep.update(true, delay, at(402)), SleepChange::None);
+    }
+
+    #[test]
+    fn a_short_visit_restarts_the_delay() {
+        let deep_sleep = DeepSleep::default();
+        let delay = Duration::from_secs(300);
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert_eq!(deep_sleep.update(false, delay, start), SleepChange::None);
+        assert_eq!(deep_sleep.update(true, delay, at(200)), SleepChange::None);
+        assert_eq!(deep_sleep.update(false, delay, at(201)), SleepChange::None);
+        assert_eq!(deep_sleep.update(false, delay, at(400)), SleepChange::None);
+        assert_eq!(
+            deep_sleep.update(false, delay, at(501)),
+            SleepChange::FellAsleep
+        );
+    }
+}
@@ -0,0 +1,449 @@
+//! `--import <path>`: converts a Bukkit/Paper server folder into Pumpkin's layout.
+//!
+//! Bukkit keeps every dimension in its own world folder (`world`, `world_nether/DIM-1`,
+//! `world_the_end/DIM1`), while Pumpkin stores the nether and end inside the main world folder
+//! like vanilla does. Anything that cannot be converted is listed in the report instead,
+//! including Essentials warps and homes, as Pumpkin has neither.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use pumpkin_config::BasicConfiguration;
+use uuid::Uuid;
+
+const DATA_FOLDER: &str = "data";
+
+/// Bukkit world files that have no meaning for Pumpkin.
+const SKIPPED_WORLD_FILES: [&str; 3] = ["uid.dat", "session.lock", "paper-world.yml"];
+
+/// Vanilla data files that Pumpkin reads unchanged from its data folder.
+const DATA_FILES: [&str; 4] = [
+    "ops.json",
+    "whitelist.json",
+    "banned-players.json",
+    "banned-ips.json",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("{0} is not a Bukkit server folder, no world folder {1} was found")]
+    NoWorld(PathBuf, String),
+    #[error("The target world folder {0} already exists, move it away first")]
+    TargetExists(PathBuf),
+    #[error("I/O error at {0}: {1}")]
+    Io(PathBuf, io::Error),
+}
+
+/// Outcome of an import.
+#[derive(Default)]
+pub struct ImportReport {
+    pub copied_files: usize,
+    pub dimensions: Vec<&'static str>,
+    /// Data that was left behind, with the reason.
+    pub unconverted: Vec<String>,
+}
+
+impl ImportReport {
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Imported {} ({} files)",
+            self.dimensions.join(", "),
+            self.copied_files
+        );
+        if !self.unconverted.is_empty() {
+            let _ = write!(summary, "\nNot converted:");
+            for entry in &self.unconverted {
+                let _ = write!(summary, "\n  - {entry}");
+            }
+        }
+        summary
+    }
+}
+
+/// Where an Essentials warp or home was, reported so that it can be recreated by hand.
+#[derive(Debug, PartialEq)]
+struct Location {
+    dimension: &'static str,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+/// A Bukkit world folder and the dimension it holds.
+struct SourceWorld {
+    folder: String,
+    /// Folder inside the world that holds the dimension, empty for the overworld.
+    dimension_folder: &'static str,
+    dimension: &'static str,
+    uid: Option<Uuid>,
+}
+
+/// Converts the Bukkit server folder at `source` into the current directory's layout.
+pub fn import_bukkit(
+    source: &Path,
+    config: &BasicConfiguration,
+) -> Result<ImportReport, ImportError> {
+    let io_err = |path: &Path| {
+        let path = path.to_path_buf();
+        move |err: io::Error| ImportError::Io(path, err)
+    };
+    let level_name = read_level_name(source).unwrap_or_else(|| "world".to_string());
+    if !source.join(&level_name).join("level.dat").exists() {
+        return Err(ImportError::NoWorld(source.to_path_buf(), level_name));
+    }
+    let target = config.get_world_path();
+    if target.exists() {
+        return Err(ImportError::TargetExists(target));
+    }
+
+    let worlds: Vec<SourceWorld> = [
+        (level_name.clone(), "", "minecraft:overworld"),
+        (
+            format!("{level_name}_nether"),
+            "DIM-1",
+            "minecraft:the_nether",
+        ),
+        (format!("{level_name}_the_end"), "DIM1", "minecraft:the_end"),
+    ]
+    .into_iter()
+    .map(|(folder, dimension_folder, dimension)| SourceWorld {
+        uid: read_uid(&source.join(&folder).join("uid.dat")),
+        folder,
+        dimension_folder,
+        dimension,
+    })
+    .collect();
+
+    let mut report = ImportReport::default();
+    for world in &worlds {
+        let root = source.join(&world.folder);
+        if !root.exists() {
+            continue;
+        }
+        let from = root.join(world.dimension_folder);
+        let to = target.join(world.dimension_folder);
+        copy_world(&from, &to, &mut report).map_err(io_err(&from))?;
+        report.dimensions.push(world.dimension);
+        if world.dimension_folder.is_empty() {
+            continue;
+        }
+        // Everything next to DIM-1/DIM1 is a duplicate of the overworld's data or Bukkit specific
+        for entry in fs::read_dir(&root).map_err(io_err(&root))? {
+            let entry = entry.map_err(io_err(&root))?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name != world.dimension_folder
+                && name != "level.dat"
+                && !SKIPPED_WORLD_FILES.contains(&name.as_str())
+            {
+                report.unconverted.push(format!(
+                    "{}/{name}: per-world data of a non-overworld folder",
+                    world.folder
+                ));
+            }
+        }
+    }
+
+    let data = Path::new(DATA_FOLDER);
+    fs::create_dir_all(data).map_err(io_err(data))?;
+    for file in DATA_FILES {
+        let from = source.join(file);
+        let to = data.join(file);
+        if !from.exists() {
+            continue;
+        }
+        if to.exists() {
+            report
+                .unconverted
+                .push(format!("{file}: {} already exists", to.display()));
+            continue;
+        }
+        fs::copy(&from, &to).map_err(io_err(&from))?;
+        report.copied_files += 1;
+    }
+
+    let essentials = source.join("plugins").join("Essentials");
+    if essentials.exists() {
+        report_essentials(&essentials, &worlds, &mut report)?;
+    }
+    if source.join("server.properties").exists() {
+        report.unconverted.push(
+            "server.properties: review config/configuration.toml and config/features.toml by hand"
+                .to_string(),
+        );
+    }
+    Ok(report)
+}
+
+/// Lists Essentials warps and homes as not converted, along with where they were.
+fn report_essentials(
+    essentials: &Path,
+    worlds: &[SourceWorld],
+    report: &mut ImportReport,
+) -> Result<(), ImportError> {
+    for (file, yaml) in read_yaml_dir(&essentials.join("warps"))? {
+        let name = yaml.get("name").and_then(Yaml::as_str).unwrap_or(&file);
+        report.unconverted.push(format!(
+            "warp {name}{}: Pumpkin has no warps",
+            describe(&yaml, worlds)
+        ));
+    }
+
+    for (file, yaml) in read_yaml_dir(&essentials.join("userdata"))? {
+        let Some(Yaml::Map(entries)) = yaml.get("homes") else {
+            continue;
+        };
+        for (name, home) in entries {
+            let Yaml::Map(home) = home else {
+                continue;
+            };
+            report.unconverted.push(format!(
+                "home {name} of {file}{}: Pumpkin has no homes",
+                describe(home, worlds)
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn describe(yaml: &YamlMap, worlds: &[SourceWorld]) -> String {
+    location(yaml, worlds).map_or_else(
+        |reason| format!(" ({reason})"),
+        |location| {
+            format!(
+                " at {} {} {} in {}",
+                location.x, location.y, location.z, location.dimension
+            )
+        },
+    )
+}
+
+/// Reads the location of an Essentials `world`/`x`/`y`/`z` entry.
+fn location(yaml: &YamlMap, worlds: &[SourceWorld]) -> Result<Location, String> {
+    // Newer Essentials versions store the world UUID in `world` and the name in `world-name`
+    let world = yaml
+        .get("world-name")
+        .or_else(|| yaml.get("world"))
+        .and_then(Yaml::as_str)
+        .ok_or("no world")?;
+    let dimension = worlds
+        .iter()
+        .find(|candidate| {
+            candidate.folder == world
+                || Uuid::parse_str(world).is_ok_and(|uid| candidate.uid == Some(uid))
+        })
+        .map(|candidate| candidate.dimension)
+        .ok_or_else(|| format!("world {world} has no Pumpkin dimension"))?;
+    let number = |key: &str| {
+        yaml.get(key)
+            .and_then(Yaml::as_str)
+            .and_then(|value| value.parse::<f64>().ok())
+            .ok_or_else(|| format!("missing or invalid {key}"))
+    };
+    Ok(Location {
+        dimension,
+        x: number("x")?,
+        y: number("y")?,
+        z: number("z")?,
+    })
+}
+
+fn read_level_name(source: &Path) -> Option<String> {
+    let properties = fs::read_to_string(source.join("server.properties")).ok()?;
+    properties
+        .lines()
+        .find_map(|line| line.strip_prefix("level-name="))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Reads a Bukkit `uid.dat`, which holds the world UUID as two big-endian longs.
+fn read_uid(path: &Path) -> Option<Uuid> {
+    let bytes: [u8; 16] = fs::read(path).ok()?.get(..16)?.try_into().ok()?;
+    Some(Uuid::from_bytes(bytes))
+}
+
+/// Copies a world folder, skipping Bukkit-only files and other dimensions' folders.
+fn copy_world(from: &Path, to: &Path, report: &mut ImportReport) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        if SKIPPED_WORLD_FILES.contains(&name_str.as_ref()) {
+            continue;
+        }
+        // A vanilla-style overworld may already contain these, they are copied separately
+        if from.join("level.dat").exists() && (name_str == "DIM-1" || name_str == "DIM1") {
+            report.unconverted.push(format!(
+                "{}: dimension folder inside the overworld, Bukkit does not use it",
+                entry.path().display()
+            ));
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(&name), report)?;
+        } else {
+            fs::copy(entry.path(), to.join(&name))?;
+            report.copied_files += 1;
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path, report: &mut ImportReport) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target, report)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+            report.copied_files += 1;
+        }
+    }
+    Ok(())
+}
+
+fn read_yaml_dir(dir: &Path) -> Result<Vec<(String, YamlMap)>, ImportError> {
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(files);
+    };
+    for entry in entries {
+        let path = entry
+            .map_err(|err| ImportError::Io(dir.to_path_buf(), err))?
+            .path();
+        if path.extension().is_none_or(|extension| extension != "yml") {
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|err| ImportError::Io(path.clone(), err))?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        files.push((name, parse_yaml(&content)));
+    }
+    Ok(files)
+}
+
+type YamlMap = HashMap<String, Yaml>;
+
+/// The subset of YAML used by Essentials data files: nested maps of scalars.
+#[derive(Debug, PartialEq)]
+enum Yaml {
+    Scalar(String),
+    Map(YamlMap),
+}
+
+impl Yaml {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Scalar(value) => Some(value),
+            Self::Map(_) => None,
+        }
+    }
+}
+
+/// Parses block-style maps by indentation. Lists and other YAML features are ignored.
+fn parse_yaml(content: &str) -> YamlMap {
+    // Stack of open maps with the indentation of their keys
+    let mut stack: Vec<(usize, String, YamlMap)> = vec![(0, String::new(), HashMap::new())];
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('-') {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let indent = line.len() - trimmed.len();
+        while stack.len() > 1 && indent < stack.last().unwrap().0 {
+            let (_, name, map) = stack.pop().unwrap();
+            stack.last_mut().unwrap().2.insert(name, Yaml::Map(map));
+        }
+        let key = unquote(key.trim());
+        let value = value.trim();
+        if value.is_empty() {
+            // The nested map's keys are indented further than this one
+            stack.push((indent + 1, key, HashMap::new()));
+        } else {
+            stack
+                .last_mut()
+                .unwrap()
+                .2
+                .insert(key, Yaml::Scalar(unquote(value)));
+        }
+    }
+    while stack.len() > 1 {
+        let (_, name, map) = stack.pop().unwrap();
+        stack.last_mut().unwrap().2.insert(name, Yaml::Map(map));
+    }
+    stack.pop().unwrap().2
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+        .or_else(|| {
+            value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+        })
+        .unwrap_or(value)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn essentials_homes_are_located() {
+        let worlds = [SourceWorld {
+            folder: "world_nether".to_string(),
+            dimension_folder: "DIM-1",
+            dimension: "minecraft:the_nether",
+            uid: Some(Uuid::from_u128(5)),
+        }];
+        let yaml = parse_yaml(
+            "# Essentials userdata\nhomes:\n  base:\n    world: 00000000-0000-0000-0000-000000000005\n    x: 12.5\n    y: 70.0\n    z: '-3.25'\n    yaw: 90.0\n    pitch: 0.0\n  lost:\n    world: world_old\n    x: 0.0\n    y: 0.0\n    z: 0.0\nlastAccountName: Alex\n",
+        );
+        assert_eq!(
+            yaml.get("lastAccountName"),
+            Some(&Yaml::Scalar("Alex".to_string()))
+        );
+        let Some(Yaml::Map(homes)) = yaml.get("homes") else {
+            panic!("homes not parsed");
+        };
+        let Some(Yaml::Map(base)) = homes.get("base") else {
+            panic!("home not parsed");
+        };
+        assert_eq!(
+            location(base, &worlds),
+            Ok(Location {
+                dimension: "minecraft:the_nether",
+                x: 12.5,
+                y: 70.0,
+                z: -3.25,
+            })
+        );
+        assert_eq!(
+            describe(base, &worlds),
+            " at 12.5 70 -3.25 in minecraft:the_nether"
+        );
+        let Some(Yaml::Map(lost)) = homes.get("lost") else {
+            panic!("home not parsed");
+        };
+        assert_eq!(
+            describe(lost, &worlds),
+            " (world world_old has no Pumpkin dimension)"
+        );
+    }
+}
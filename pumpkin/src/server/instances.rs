@@ -0,0 +1,287 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use pumpkin_config::world_template::InstanceConfig;
+use pumpkin_data::dimension::Dimension;
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::level::Level;
+use pumpkin_world::world_info::WorldInfoReader;
+use pumpkin_world::world_info::anvil::AnvilLevelInfo;
+
+use crate::entity::player::Player;
+use crate::server::Server;
+use crate::server::pregen::format_duration;
+use crate::world::World;
+
+/// A short-lived arena world created from a template world.
+///
+/// The world reads its chunks from the template and keeps every change in memory, so it never
+/// writes to disk. It is ticked with the other worlds, but has its own player list and can be
+/// paused on its own.
+pub struct Instance {
+    pub name: String,
+    /// Name of the template the instance was created from.
+    pub template: String,
+    pub world: Arc<World>,
+    paused: AtomicBool,
+    created: Instant,
+}
+
+impl Instance {
+    /// The players currently in the instance.
+    #[must_use]
+    pub fn players(&self) -> Arc<Vec<Arc<Player>>> {
+        self.world.players.load_full()
+    }
+
+    /// Whether the world of the instance stopped ticking. Players in it are still ticked, so
+    /// they stay connected.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    /// How long ago the instance was created.
+    #[must_use]
+    pub fn age(&self) -> Duration {
+        self.created.elapsed()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum InstanceError {
+    #[error("An instance named {0} already exists")]
+    AlreadyExists(String),
+    #[error("No instance named {0} exists")]
+    UnknownInstance(String),
+    #[error("No template named {0} exists")]
+    UnknownTemplate(String),
+    #[error("{0} is not a valid template name")]
+    InvalidTemplateName(String),
+    #[error("There are already {0} instances, which is the most allowed")]
+    TooManyInstances(usize),
+}
+
+/// Creates, lists and disposes the instances of the server, for minigame plugins that need many
+/// short-lived arenas.
+///
+/// ```ignore
+/// let arena = server.instances.create(&server, "arena-1", "skywars").await?;
+/// player.teleport_world(arena.world.clone(), position, None, None).await;
+/// // once the round is over
+/// server.instances.dispose(&server, "arena-1").await?;
+/// ```
+pub struct Instances {
+    config: InstanceConfig,
+    instances: Mutex<Vec<Arc<Instance>>>,
+}
+
+impl Instances {
+    #[must_use]
+    pub const fn new(config: InstanceConfig) -> Self {
+        Self {
+            config,
+            instances: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Arc<Instance>> {
+        self.instances
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|instance| instance.name == name)
+            .cloned()
+    }
+
+    #[must_use]
+    pub fn list(&self) -> Vec<Arc<Instance>> {
+        self.instances.lock().unwrap().clone()
+    }
+
+    /// The instance `world` belongs to, if it is one.
+    #[must_use]
+    pub fn of_world(&self, world: &World) -> Option<Arc<Instance>> {
+        self.instances
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|instance| *instance.world == *world)
+            .cloned()
+    }
+
+    /// Whether `world` is a paused instance.
+    #[must_use]
+    pub fn is_paused(&self, world: &World) -> bool {
+        self.of_world(world)
+            .is_some_and(|instance| instance.is_paused())
+    }
+
+    /// Creates the instance `name` from the template world of the same name in the templates
+    /// folder. Chunks missing from the template are generated with the seed of the template.
+    pub async fn create(
+        &self,
+        server: &Arc<Server>,
+        name: &str,
+        template: &str,
+    ) -> Result<Arc<Instance>, InstanceError> {
+        let template_folder = self.template_folder(template)?;
+        {
+            let instances = self.instances.lock().unwrap();
+            if instances.iter().any(|instance| instance.name == name) {
+                return Err(InstanceError::AlreadyExists(name.to_string()));
+            }
+            if instances.len() >= self.config.max_instances {
+                return Err(InstanceError::TooManyInstances(instances.len()));
+            }
+        }
+
+        let level_info = AnvilLevelInfo
+            .read_world_info(&template_folder)
+            .unwrap_or_else(|_| (**server.level_info.load()).clone());
+        let seed = level_info.world_gen_settings.seed;
+        let level_config = server.advanced_config.world.clone();
+        let registry = server.block_registry.clone();
+        let weak = Arc::downgrade(server);
+        // Starting the generation threads and reading the POI blocks
+        let world = tokio::task::spawn_blocking(move || {
+            World::load(
+                Level::in_memory(
+                    &level_config,
+                    template_folder,
+                    registry.clone(),
+                    seed,
+                    Dimension::OVERWORLD,
+                ),
+                Arc::new(ArcSwap::from_pointee(level_info)),
+                Dimension::OVERWORLD,
+                registry,
+                weak,
+            )
+        })
+        .await
+        .expect("Creating an instance world panicked");
+
+        let instance = Arc::new(Instance {
+            name: name.to_string(),
+            template: template.to_string(),
+            world: Arc::new(world),
+            paused: AtomicBool::new(false),
+            created: Instant::now(),
+        });
+        // Checked again, another instance of the name may have been created meanwhile
+        let added = {
+            let mut instances = self.instances.lock().unwrap();
+            let taken = instances.iter().any(|other| other.name == name);
+            if !taken {
+                instances.push(instance.clone());
+            }
+            !taken
+        };
+        if !added {
+            instance.world.level.shutdown().await;
+            return Err(InstanceError::AlreadyExists(name.to_string()));
+        }
+        server.worlds.rcu(|worlds| {
+            let mut worlds = (**worlds).clone();
+            worlds.push(instance.world.clone());
+            worlds
+        });
+        log::info!("Created the instance {name} from the template {template}");
+        Ok(instance)
+    }
+
+    /// Disposes the instance `name`, throwing away all changes made to it. Players still in it
+    /// are sent to the spawn of the overworld.
+    pub async fn dispose(&self, server: &Server, name: &str) -> Result<(), InstanceError> {
+        let instance = {
+            let mut instances = self.instances.lock().unwrap();
+            let index = instances
+                .iter()
+                .position(|instance| instance.name == name)
+                .ok_or_else(|| InstanceError::UnknownInstance(name.to_string()))?;
+            instances.remove(index)
+        };
+
+        let overworld = server.get_world_from_dimension(&Dimension::OVERWORLD);
+        let players = instance.players();
+        if !players.is_empty() {
+            let spawn = spawn_position(&overworld, server).await;
+            for player in players.iter() {
+                player
+                    .clone()
+                    .teleport_world(overworld.clone(), spawn, None, None)
+                    .await;
+            }
+        }
+
+        server.worlds.rcu(|worlds| {
+            worlds
+                .iter()
+                .filter(|world| ***world != *instance.world)
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        // Stops the generation threads, whatever they save stays in memory
+        instance.world.level.shutdown().await;
+        log::info!(
+            "Disposed the instance {name} after {}",
+            format_duration(instance.age())
+        );
+        Ok(())
+    }
+
+    fn template_folder(&self, template: &str) -> Result<PathBuf, InstanceError> {
+        if !is_valid_template_name(template) {
+            return Err(InstanceError::InvalidTemplateName(template.to_string()));
+        }
+        let folder = Path::new(&self.config.templates).join(template);
+        if !folder.is_dir() {
+            return Err(InstanceError::UnknownTemplate(template.to_string()));
+        }
+        Ok(folder)
+    }
+}
+
+/// Template names are folder names, so they must not reach outside the templates folder.
+fn is_valid_template_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+async fn spawn_position(world: &World, server: &Server) -> Vector3<f64> {
+    let level_info = server.level_info.load();
+    let top = world
+        .get_top_block(Vector2::new(level_info.spawn_x, level_info.spawn_z))
+        .await;
+    Vector3::new(
+        f64::from(level_info.spawn_x) + 0.5,
+        f64::from(top + 1),
+        f64::from(level_info.spawn_z) + 0.5,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_names_stay_in_the_templates_folder() {
+        assert!(is_valid_template_name("skywars_2-large"));
+        assert!(!is_valid_template_name(""));
+        assert!(!is_valid_template_name(".."));
+        assert!(!is_valid_template_name("../world"));
+        assert!(!is_valid_template_name("a/b"));
+    }
+}
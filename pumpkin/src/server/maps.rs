@@ -0,0 +1,268 @@
+//! Filled maps: loading and saving them, drawing them while they are held and sending them to
+//! the players that have them.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use pumpkin_data::data_component::DataComponent;
+use pumpkin_data::data_component_impl::{DataComponentImpl, MapIdImpl, MapPostProcessingImpl};
+use pumpkin_data::item::Item;
+use pumpkin_inventory::cartography_table::{MapInfo, MapLookup};
+use pumpkin_inventory::slot::BoxFuture;
+use pumpkin_protocol::java::client::play::{CMapItemData, MapColorPatch, MapDecoration};
+use pumpkin_util::text::TextComponent;
+use pumpkin_world::data::map_data::MapDataStorage;
+use pumpkin_world::item::ItemStack;
+use pumpkin_world::map::{MapState, MapUpdate, render};
+use tokio::sync::Mutex;
+
+use crate::entity::player::Player;
+
+pub type SharedMapState = Arc<Mutex<MapState>>;
+
+/// The maps of the server, loaded from the `data` directory of the world when they are first
+/// used.
+pub struct ServerMaps {
+    storage: MapDataStorage,
+    maps: Mutex<HashMap<i32, SharedMapState>>,
+    /// The id of the last map that was created, read from `idcounts.dat` on first use.
+    last_id: Mutex<Option<Option<i32>>>,
+}
+
+impl ServerMaps {
+    #[must_use]
+    pub fn new(data_path: PathBuf) -> Self {
+        Self {
+            storage: MapDataStorage::new(data_path),
+            maps: Mutex::new(HashMap::new()),
+            last_id: Mutex::new(None),
+        }
+    }
+
+    /// Returns the map `id`, loading it if needed, or `None` if there is no such map.
+    pub async fn get(&self, id: i32) -> Option<SharedMapState> {
+        let mut maps = self.maps.lock().await;
+        if let Some(map) = maps.get(&id) {
+            return Some(map.clone());
+        }
+        match self.storage.load_map(id) {
+            Ok(Some(state)) => {
+                let map = Arc::new(Mutex::new(state));
+                maps.insert(id, map.clone());
+                Some(map)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                log::error!("Failed to load map {id}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Adds a new map and returns its id.
+    pub async fn create(&self, state: MapState) -> i32 {
+        let mut last_id = self.last_id.lock().await;
+        let previous = match *last_id {
+            Some(previous) => previous,
+            None => self.storage.load_last_id().unwrap_or_else(|e| {
+                log::error!("Failed to load the last map id: {e}");
+                None
+            }),
+        };
+        let id = previous.map_or(0, |previous| previous + 1);
+        *last_id = Some(Some(id));
+        if let Err(e) = self.storage.save_last_id(id) {
+            log::error!("Failed to save the last map id: {e}");
+        }
+        drop(last_id);
+
+        self.maps
+            .lock()
+            .await
+            .insert(id, Arc::new(Mutex::new(state)));
+        id
+    }
+
+    /// Saves the maps that changed since they were last saved.
+    pub async fn save_all(&self) {
+        let maps: Vec<_> = self
+            .maps
+            .lock()
+            .await
+            .iter()
+            .map(|(id, map)| (*id, map.clone()))
+            .collect();
+        for (id, map) in maps {
+            let mut state = map.lock().await;
+            if !state.is_dirty() {
+                continue;
+            }
+            match self.storage.save_map(id, &state) {
+                Ok(()) => state.mark_saved(),
+                Err(e) => log::error!("Failed to save map {id}: {e}"),
+            }
+        }
+    }
+
+    /// Sends the whole map `id` to `player`, e.g. when they see it in an item frame.
+    pub async fn send_full(&self, player: &Player, id: i32) {
+        let Some(map) = self.get(id).await else {
+            return;
+        };
+        let state = map.lock().await;
+        let update = state.full_update();
+        send_update(player, id, &state, &update).await;
+    }
+
+    /// Keeps the maps in the inventory of `player` up to date, like vanilla
+    /// `FilledMapItem::inventoryTick`. Maps in a hand are also drawn.
+    pub async fn tick_player(&self, player: &Player, tick: u64) {
+        let inventory = player.inventory();
+        let held = inventory.held_item();
+        let mut stacks = vec![
+            (held.clone(), true),
+            (inventory.off_hand_item().await, true),
+        ];
+        stacks.extend(
+            inventory
+                .main_inventory
+                .iter()
+                .filter(|stack| !Arc::ptr_eq(stack, &held))
+                .map(|stack| (stack.clone(), false)),
+        );
+
+        let world = player.world();
+        let entity = &player.living_entity.entity;
+        let position = entity.pos.load();
+        let yaw = entity.yaw.load();
+        let uuid = player.gameprofile.id;
+        let mut seen = HashSet::new();
+        for (stack, in_hand) in stacks {
+            let id = {
+                let mut stack = stack.lock().await;
+                if stack.item != &Item::FILLED_MAP {
+                    continue;
+                }
+                self.post_process(&mut stack).await;
+                let Some(map_id) = stack.get_data_component::<MapIdImpl>() else {
+                    continue;
+                };
+                map_id.id
+            };
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(map) = self.get(id).await else {
+                continue;
+            };
+
+            let mut state = map.lock().await;
+            let step = state.track(uuid, tick);
+            let same_dimension = state.dimension == world.dimension.minecraft_name;
+            state.update_player(
+                uuid,
+                &player.gameprofile.name,
+                position.x,
+                position.z,
+                yaw,
+                same_dimension,
+            );
+            if in_hand && same_dimension && !state.locked {
+                let has_ceiling = world.dimension.has_ceiling;
+                let chunks = render::chunks_to_render(&state, position.x, position.z, has_ceiling);
+                let snapshot = world.level.snapshot(chunks);
+                render::update_colors(
+                    &mut state,
+                    &snapshot,
+                    position.x,
+                    position.z,
+                    step,
+                    has_ceiling,
+                    world.dimension.min_y,
+                );
+            }
+            if let Some(update) = state.take_update(uuid)
+                && (update.decorations.is_some() || update.patch.is_some())
+            {
+                send_update(player, id, &state, &update).await;
+            }
+        }
+    }
+
+    /// Creates the map a cartography table asked for, once the result was taken out of it.
+    async fn post_process(&self, stack: &mut ItemStack) {
+        let Some(processing) = stack.get_data_component::<MapPostProcessingImpl>().copied() else {
+            return;
+        };
+        stack
+            .patch
+            .retain(|(id, _)| *id != DataComponent::MapPostProcessing);
+        let Some(old_id) = stack
+            .get_data_component::<MapIdImpl>()
+            .map(|map_id| map_id.id)
+        else {
+            return;
+        };
+        let Some(map) = self.get(old_id).await else {
+            return;
+        };
+        let state = {
+            let old = map.lock().await;
+            match processing {
+                MapPostProcessingImpl::Lock => old.locked_copy(),
+                MapPostProcessingImpl::Scale => old.scaled(),
+            }
+        };
+        let id = self.create(state).await;
+        stack.patch.retain(|(id, _)| *id != DataComponent::MapId);
+        stack
+            .patch
+            .push((DataComponent::MapId, Some(MapIdImpl { id }.to_dyn())));
+    }
+}
+
+impl MapLookup for ServerMaps {
+    fn get_map_info(&self, map_id: i32) -> BoxFuture<'_, Option<MapInfo>> {
+        Box::pin(async move {
+            let map = self.get(map_id).await?;
+            let state = map.lock().await;
+            Some(MapInfo {
+                scale: state.scale,
+                locked: state.locked,
+            })
+        })
+    }
+}
+
+async fn send_update(player: &Player, id: i32, state: &MapState, update: &MapUpdate) {
+    let decorations: Option<Vec<_>> = update.decorations.as_ref().map(|decorations| {
+        decorations
+            .iter()
+            .map(|decoration| MapDecoration {
+                decoration_type: decoration.kind.into(),
+                x: decoration.x,
+                z: decoration.z,
+                rotation: decoration.rotation,
+                name: decoration.name.clone().map(TextComponent::text),
+            })
+            .collect()
+    });
+    let colors = update.patch.as_ref().map(|patch| MapColorPatch {
+        start_x: patch.start_x,
+        start_z: patch.start_z,
+        width: patch.width,
+        height: patch.height,
+        colors: &patch.colors,
+    });
+    player
+        .client
+        .enqueue_packet(&CMapItemData::new(
+            id.into(),
+            state.scale as i8,
+            state.locked,
+            decorations.as_deref(),
+            colors,
+        ))
+        .await;
+}
@@ -3,29 +3,49 @@ use crate::command::commands::default_dispatcher;
 use crate::command::commands::defaultgamemode::DefaultGamemode;
 use crate::data::VanillaData;
 use crate::data::player_server::ServerPlayerData;
+use crate::entity::ai::provider::AiProviderRegistry;
 use crate::entity::{EntityBase, NBTStorage};
 use crate::item::registry::ItemRegistry;
 use crate::net::authentication::fetch_mojang_public_keys;
+use crate::net::circuit_breaker::CircuitBreaker;
+use crate::net::health::LifecycleState;
 use crate::net::{ClientPlatform, DisconnectReason, EncryptionError, GameProfile, PlayerConfig};
 use crate::plugin::PluginManager;
 use crate::plugin::player::player_login::PlayerLoginEvent;
 use crate::plugin::server::server_broadcast::ServerBroadcastEvent;
-use crate::server::tick_profiler::TickProfiler;
+use crate::server::alerting::{AlertKind, Alerting};
+use crate::server::backup::BackupManager;
+use crate::server::block_log::BlockLog;
+use crate::server::chat_bridge::ChatBridge;
+use crate::server::chunk_limits::ChunkLimits;
+use crate::server::deep_sleep::DeepSleep;
+use crate::server::instances::Instances;
+use crate::server::maps::ServerMaps;
+use crate::server::pregen::Pregenerator;
+use crate::server::redis::RedisConnector;
+use crate::server::restart::RestartScheduler;
+use crate::server::schematics::Schematics;
+use crate::server::tick_profiler::{TickProfiler, TickSection};
 use crate::server::tick_rate_manager::ServerTickRateManager;
+use crate::server::tick_stats::TickStats;
+use crate::server::tps_mitigation::TpsMitigation;
+use crate::server::watchdog::Watchdog;
 use crate::world::custom_bossbar::CustomBossbars;
 use crate::{command::dispatcher::CommandDispatcher, entity::player::Player, world::World};
 use arc_swap::ArcSwap;
 use connection_cache::{CachedBranding, CachedStatus};
+use crossbeam::atomic::AtomicCell;
 use key_store::KeyStore;
-use pumpkin_config::{AdvancedConfiguration, BasicConfiguration};
+use pumpkin_config::{AdvancedConfiguration, BasicConfiguration, IntegrationsConfiguration};
 use pumpkin_data::dimension::Dimension;
 use pumpkin_util::permission::{PermissionManager, PermissionRegistry};
-use pumpkin_world::dimension::into_level;
+use pumpkin_world::datapack;
+use pumpkin_world::dimension::{dimension_from_name, into_level, register_datapack_dimension};
 
 use crate::command::CommandSender;
 use pumpkin_macros::send_cancellable;
 use pumpkin_protocol::java::client::login::CEncryptionRequest;
-use pumpkin_protocol::java::client::play::CChangeDifficulty;
+use pumpkin_protocol::java::client::play::{CChangeDifficulty, CDebugSample};
 use pumpkin_protocol::{ClientPacket, java::client::config::CPluginMessage};
 use pumpkin_util::Difficulty;
 use pumpkin_util::math::vector3::Vector3;
@@ -48,21 +68,42 @@ use tokio::sync::{Mutex, OnceCell, RwLock};
 use tokio::task::{JoinHandle, JoinSet};
 use tokio_util::task::TaskTracker;
 
+pub mod alerting;
+pub mod backup;
+pub mod block_log;
+pub mod chat_bridge;
+pub mod chunk_limits;
 mod connection_cache;
+pub mod deep_sleep;
+pub mod import;
+pub mod instances;
 mod key_store;
+pub mod maps;
+pub mod pregen;
+pub mod redis;
+pub mod restart;
+pub mod schematics;
 pub mod seasonal_events;
 pub mod tick_profiler;
 pub mod tick_rate_manager;
+pub mod tick_stats;
 pub mod ticker;
+pub mod tps_mitigation;
+pub mod watchdog;
 
 use super::command::args::entities::{
     EntityFilter, EntityFilterSort, EntitySelectorType, TargetSelector, ValueCondition,
 };
 
 /// Represents a Minecraft server instance.
+///
+/// `worlds` and `defaultgamemode` are read by every joining player, so they are `ArcSwap`s
+/// that joins read without waiting. Server locks are taken before the locks of a [`World`], see
+/// its documentation for their order.
 pub struct Server {
     pub basic_config: BasicConfiguration,
     pub advanced_config: AdvancedConfiguration,
+    pub integrations_config: IntegrationsConfiguration,
 
     pub data: VanillaData,
 
@@ -98,7 +139,7 @@ pub struct Server {
     /// The server's custom bossbars
     pub bossbars: Mutex<CustomBossbars>,
     /// The default gamemode when a player joins the server (reset every restart)
-    pub defaultgamemode: Mutex<DefaultGamemode>,
+    pub defaultgamemode: ArcSwap<DefaultGamemode>,
     /// Manages player data storage
     pub player_data_storage: ServerPlayerData,
     // Whether the server whitelist is on or off
@@ -111,6 +152,8 @@ pub struct Server {
     pub tick_times_nanos: Mutex<[i64; 100]>,
     /// Aggregated tick times for efficient rolling average calculation
     pub aggregated_tick_times_nanos: AtomicI64,
+    /// Tick time percentiles over the last second, five seconds and minute
+    pub tick_stats: TickStats,
     /// Total number of ticks processed by the server
     pub tick_count: AtomicI32,
     /// Random unique Server ID used by Bedrock Edition
@@ -119,6 +162,40 @@ pub struct Server {
     pub player_idle_timeout: AtomicI32,
     /// Whether automatic world/player saving is enabled (toggled by save-off/save-on)
     pub autosave_enabled: AtomicBool,
+    /// Starting/ready/stopping state reported by the health endpoint
+    pub lifecycle_state: AtomicCell<LifecycleState>,
+    /// Fires webhooks on anomalies such as lag spikes or failed saves
+    pub alerting: Alerting,
+    /// Fails logins fast while the authentication servers are down
+    pub auth_circuit: CircuitBreaker,
+    /// Creates and restores world backups
+    pub backups: BackupManager,
+    /// Countdown of a pending `/restart`
+    pub restart: RestartScheduler,
+    /// Unloads chunks and pauses entities while nobody is online
+    pub deep_sleep: DeepSleep,
+    /// Start of the tick in progress, watched for hung ticks
+    pub watchdog: Watchdog,
+    /// Records player block changes for `/blocklog`
+    pub block_log: BlockLog,
+    /// Schematics loaded and being pasted with `/schem`
+    pub schematics: Schematics,
+    /// Per-chunk entity and block entity caps
+    pub chunk_limits: ChunkLimits,
+    /// Lowers the load while the TPS stays low
+    pub tps_mitigation: TpsMitigation,
+    /// Generates chunks ahead of time for `/pregen`
+    pub pregen: Arc<Pregenerator>,
+    /// Arena worlds created from templates at runtime
+    pub instances: Instances,
+    /// The filled maps of the server.
+    pub maps: Arc<ServerMaps>,
+    /// The goal selection of mobs, per entity type.
+    pub ai_providers: AiProviderRegistry,
+    /// Publish/subscribe messaging with the other servers of the network
+    pub redis: RedisConnector,
+    /// Relays the chat to Discord and back
+    pub chat_bridge: ChatBridge,
     tasks: TaskTracker,
 
     // world stuff which maybe should be put into a struct
@@ -135,15 +212,28 @@ impl Server {
     pub async fn new(
         basic_config: BasicConfiguration,
         advanced_config: AdvancedConfiguration,
+        integrations_config: IntegrationsConfiguration,
         vanilla_data: VanillaData,
     ) -> Arc<Self> {
         let permission_registry = Arc::new(RwLock::new(PermissionRegistry::new()));
         // First register the default commands. After that, plugins can put in their own.
-        let command_dispatcher =
-            RwLock::new(default_dispatcher(&permission_registry, &basic_config).await);
+        let command_dispatcher = RwLock::new(default_dispatcher(&permission_registry).await);
         let world_path = basic_config.get_world_path();
 
-        let block_registry = super::block::registry::default_registry();
+        let block_registry =
+            super::block::registry::registry_with_machines(&advanced_config.machines);
+
+        match pumpkin_world::template::apply_template(&advanced_config.world.template, &world_path)
+        {
+            Ok(Some(clone)) => log::info!(
+                "Cloned the world from the template {}: {} region files shared, {} files copied",
+                advanced_config.world.template.path,
+                clone.linked,
+                clone.copied
+            ),
+            Ok(None) => {}
+            Err(err) => log::error!("Failed to clone the world template: {err}"),
+        }
 
         let level_info = AnvilLevelInfo.read_world_info(&world_path);
         if let Err(error) = &level_info {
@@ -183,10 +273,16 @@ impl Server {
         });
 
         let seed = level_info.world_gen_settings.seed;
+        let datapack_dimensions = datapack::load_dimensions(&world_path, &level_info.data_packs);
+        for datapack in &datapack_dimensions {
+            register_datapack_dimension(*datapack);
+        }
         let level_info = Arc::new(ArcSwap::new(Arc::new(level_info)));
 
         let listing = Mutex::new(CachedStatus::new(&basic_config));
-        let defaultgamemode = Mutex::new(DefaultGamemode {
+        let redis = RedisConnector::new(integrations_config.redis.clone());
+        let chat_bridge = ChatBridge::new(integrations_config.chat_bridge.clone());
+        let defaultgamemode = ArcSwap::from_pointee(DefaultGamemode {
             gamemode: basic_config.default_gamemode,
         });
         let player_data_storage = ServerPlayerData::new(
@@ -198,11 +294,27 @@ impl Server {
 
         let tick_rate_manager = Arc::new(ServerTickRateManager::new(basic_config.tps));
         let tick_profiler = Arc::new(TickProfiler::new());
+        let alerting = Alerting::new(advanced_config.alerting.clone(), world_path.clone());
+        let backups = BackupManager::new(advanced_config.backup.clone());
+        let block_log = BlockLog::new(advanced_config.block_log.clone());
+        let chunk_limits = ChunkLimits::new(advanced_config.chunk_limits.clone());
+        let tps_mitigation = TpsMitigation::new(advanced_config.tps_mitigation.clone());
+        let pregen = Arc::new(Pregenerator::new(&world_path));
+        let instances = Instances::new(advanced_config.world.instances.clone());
+        let maps = Arc::new(ServerMaps::new(world_path.join("data")));
+        let ai_providers = AiProviderRegistry::new(advanced_config.ai.clone());
+
+        let auth_config = &advanced_config.networking.authentication;
+        let auth_circuit = CircuitBreaker::new(
+            "Authentication servers",
+            auth_config.circuit_breaker_failures,
+            Duration::from_millis(auth_config.circuit_breaker_cooldown.into()),
+        );
 
-        let mojang_keys_task = tokio::spawn({
-            let auth_config = advanced_config.networking.authentication.clone();
+        let mojang_keys_task = tokio::task::spawn_blocking({
+            let auth_config = auth_config.clone();
             let allow_chat = basic_config.allow_chat_reports;
-            async move {
+            move || {
                 if allow_chat {
                     fetch_mojang_public_keys(&auth_config).unwrap_or_else(|e| {
                         log::error!("Failed to fetch Mojang keys: {e}");
@@ -217,6 +329,7 @@ impl Server {
         let server = Self {
             basic_config,
             advanced_config,
+            integrations_config,
             data: vanilla_data,
             plugin_manager: Arc::new(PluginManager::new()),
             permission_manager: Arc::new(RwLock::new(PermissionManager::new(
@@ -225,11 +338,18 @@ impl Server {
             permission_registry,
             container_id: 0.into(),
             worlds: ArcSwap::from_pointee(vec![]),
-            dimensions: vec![
+            dimensions: [
                 Dimension::OVERWORLD,
                 Dimension::THE_NETHER,
                 Dimension::THE_END,
-            ],
+            ]
+            .into_iter()
+            .chain(
+                datapack_dimensions
+                    .iter()
+                    .map(|datapack| datapack.dimension),
+            )
+            .collect(),
             command_dispatcher,
             block_registry: block_registry.clone(),
             item_registry: super::item::items::default_registry(),
@@ -244,11 +364,29 @@ impl Server {
             tick_profiler,
             tick_times_nanos: Mutex::new([0; 100]),
             aggregated_tick_times_nanos: AtomicI64::new(0),
+            tick_stats: TickStats::default(),
             tick_count: AtomicI32::new(0),
             tasks: TaskTracker::new(),
             server_guid: rand::random(),
             player_idle_timeout: AtomicI32::new(0),
             autosave_enabled: AtomicBool::new(true),
+            lifecycle_state: AtomicCell::new(LifecycleState::Starting),
+            alerting,
+            auth_circuit,
+            backups,
+            restart: RestartScheduler::default(),
+            deep_sleep: DeepSleep::default(),
+            watchdog: Watchdog::default(),
+            block_log,
+            schematics: Schematics::default(),
+            chunk_limits,
+            tps_mitigation,
+            pregen,
+            instances,
+            maps,
+            ai_providers,
+            redis,
+            chat_bridge,
             mojang_public_keys: ArcSwap::from_pointee(Vec::new()),
             world_info_writer: Arc::new(AnvilLevelInfo),
             level_info,
@@ -291,11 +429,18 @@ impl Server {
             mojang_keys_task
         );
 
-        let worlds_vec = vec![
+        let datapack_worlds: Vec<_> = datapack_dimensions
+            .iter()
+            .map(|datapack| world_loader(datapack.dimension))
+            .collect();
+        let mut worlds_vec = vec![
             Arc::new(overworld.expect("Overworld panicked")),
             Arc::new(nether.expect("Nether panicked")),
             Arc::new(end.expect("End panicked")),
         ];
+        for world in datapack_worlds {
+            worlds_vec.push(Arc::new(world.await.expect("Datapack dimension panicked")));
+        }
         server.worlds.store(Arc::new(worlds_vec));
         if let Ok(k) = keys {
             server.mojang_public_keys.store(Arc::new(k));
@@ -316,17 +461,14 @@ impl Server {
     }
 
     pub fn get_world_from_dimension(&self, dimension: &Dimension) -> Arc<World> {
-        // TODO: this is really bad
         let world_guard = self.worlds.load();
-        if dimension == &Dimension::OVERWORLD {
-            world_guard.first()
-        } else if dimension == &Dimension::THE_NETHER {
-            world_guard.get(1)
-        } else {
-            world_guard.get(2)
-        }
-        .cloned()
-        .unwrap()
+        // The worlds of the dimensions come before those of instances
+        world_guard
+            .iter()
+            .find(|world| world.dimension == *dimension)
+            .or_else(|| world_guard.first())
+            .cloned()
+            .unwrap()
     }
 
     /// Adds a new player to the server.
@@ -360,12 +502,25 @@ impl Server {
         profile: GameProfile,
         config: Option<PlayerConfig>,
     ) -> Option<(Arc<Player>, Arc<World>)> {
-        let gamemode = self.defaultgamemode.lock().await.gamemode;
-
-        let (world, nbt) = if let Ok(Some(data)) = self.player_data_storage.load_data(&profile.id) {
+        let gamemode = self.defaultgamemode.load().gamemode;
+
+        let data = match self.player_data_storage.fetch_data(&profile.id).await {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("Failed to fetch player data for {}: {e}", profile.name);
+                client
+                    .kick(
+                        DisconnectReason::Kicked,
+                        TextComponent::text("Failed to load your player data, please rejoin"),
+                    )
+                    .await;
+                return None;
+            }
+        };
+        let (world, nbt) = if let Some(data) = data {
             if let Some(dimension_key) = data.get_string("Dimension") {
-                if let Some(dimension) = Dimension::from_name(dimension_key) {
-                    let world = self.get_world_from_dimension(dimension);
+                if let Some(dimension) = dimension_from_name(dimension_key) {
+                    let world = self.get_world_from_dimension(&dimension);
                     (world, Some(data))
                 } else {
                     log::warn!("Invalid dimension key in player data: {dimension_key}");
@@ -464,6 +619,9 @@ impl Server {
         for world in self.worlds.load().iter() {
             world.shutdown().await;
         }
+        self.alert_save_failures();
+        self.maps.save_all().await;
+        self.store_worldborder().await;
         let level_data = self.level_info.load();
         // then lets save the world info
 
@@ -472,46 +630,89 @@ impl Server {
             .write_world_info(&level_data, &self.basic_config.get_world_path())
         {
             log::error!("Failed to save level.dat: {err}");
+            self.alerting.fire(
+                AlertKind::SaveFailure,
+                format!("Failed to save level.dat: {err}"),
+            );
         }
+        self.block_log.flush();
         log::info!("Completed worlds");
     }
 
+    /// Copies the border of the overworld into the level data, so it is saved to level.dat.
+    async fn store_worldborder(&self) {
+        let overworld = self.get_world_from_dimension(&Dimension::OVERWORLD);
+        let border = overworld.worldborder.lock().await;
+        self.level_info.rcu(|level_data| {
+            let mut level_data = (**level_data).clone();
+            border.write_level_data(&mut level_data);
+            level_data
+        });
+    }
+
+    /// Turns automatic saving of player data and chunks on or off, returning whether it was on.
+    /// While it is off, chunks stay loaded and dirty until saving is turned on again or an
+    /// explicit save writes them.
+    pub fn set_autosave(&self, enabled: bool) -> bool {
+        let was_enabled = self.autosave_enabled.swap(enabled, Ordering::Relaxed);
+        for world in self.worlds.load().iter() {
+            world
+                .level
+                .saving_suspended
+                .store(!enabled, Ordering::Relaxed);
+        }
+        was_enabled
+    }
+
     /// Saves all player data, triggers chunk saves on all worlds, and writes level.dat.
     /// This is the non-destructive save used by the /save-all command.
     /// Unlike `shutdown()`, this does not cancel tasks or join threads.
+    ///
+    /// With `flush`, this only returns once all chunks, including those queued for writing
+    /// before, are on disk.
     pub async fn save_all(&self, flush: bool) {
+        let start = std::time::Instant::now();
         log::info!("Saving all player data...");
         if let Err(e) = self.player_data_storage.save_all_players(self).await {
             log::error!("Error saving player data: {e}");
+            self.alerting.fire(
+                AlertKind::SaveFailure,
+                format!("Failed to save player data: {e}"),
+            );
         }
 
-        // Trigger chunk saves on all worlds via the should_save flag
-        for world in self.worlds.load().iter() {
-            world.level.should_save.store(true, Ordering::Relaxed);
-            world.level.level_channel.notify();
-        }
+        // Request the chunk saves of all worlds first, so that they run in parallel
+        let worlds = self.worlds.load();
+        let requests: Vec<_> = worlds
+            .iter()
+            .map(|world| world.level.request_save())
+            .collect();
 
         // If flush requested, wait for all pending chunk writes to complete
         if flush {
-            for world in self.worlds.load().iter() {
-                world
-                    .level
-                    .chunk_saver
-                    .block_and_await_ongoing_tasks()
-                    .await;
+            for (world, request) in worlds.iter().zip(requests) {
+                world.level.await_save(request).await;
             }
         }
 
+        self.maps.save_all().await;
+
         // Save level.dat
+        self.store_worldborder().await;
         let level_data = self.level_info.load();
         if let Err(err) = self
             .world_info_writer
             .write_world_info(&level_data, &self.basic_config.get_world_path())
         {
             log::error!("Failed to save level.dat: {err}");
+            self.alerting.fire(
+                AlertKind::SaveFailure,
+                format!("Failed to save level.dat: {err}"),
+            );
         }
 
-        log::info!("Save complete.");
+        self.block_log.flush();
+        log::info!("Save complete in {} ms.", start.elapsed().as_millis());
     }
 
     /// Broadcasts a packet to all players in all worlds.
@@ -589,10 +790,10 @@ impl Server {
         self.level_info.store(Arc::new(new_info));
 
         for world in self.worlds.load().iter() {
-            world.set_difficulty(difficulty);
+            world.set_difficulty(new_difficulty);
         }
 
-        self.broadcast_packet_all(&CChangeDifficulty::new(difficulty as u8, locked))
+        self.broadcast_packet_all(&CChangeDifficulty::new(new_difficulty as u8, locked))
             .await;
     }
 
@@ -752,6 +953,7 @@ impl Server {
     /// and world/game logic ticking (which is affected by freeze state).
     pub async fn tick(self: &Arc<Self>) {
         let tick_start = std::time::Instant::now();
+        self.deep_sleep.tick(self);
 
         if self.tick_rate_manager.runs_normally() || self.tick_rate_manager.is_sprinting() {
             self.tick_worlds().await;
@@ -761,9 +963,20 @@ impl Server {
         }
 
         self.tick_profiler.record_total_tick(tick_start);
+        self.tick_profiler
+            .record_section(TickSection::Tick, tick_start.elapsed());
+        self.alerting.tick(self.get_mspt());
+        self.alert_save_failures();
+        self.tps_mitigation.tick(self).await;
+        self.restart.tick(self).await;
+        self.schematics.tick().await;
 
         // Fire server tick event for plugins
         let tick_count = self.tick_count.load(Ordering::Relaxed);
+        // Keep at most a few seconds of block changes in memory
+        if tick_count % 100 == 0 {
+            self.block_log.flush();
+        }
         self.plugin_manager
             .fire(
                 crate::plugin::api::events::server::server_tick::ServerTickEvent::new(
@@ -773,23 +986,29 @@ impl Server {
             .await;
     }
 
+    /// Fires an alert for the chunk and entity writes of each world that failed since the last
+    /// check.
+    fn alert_save_failures(&self) {
+        for world in self.worlds.load().iter() {
+            while let Ok(message) = world.level.save_failures.try_recv() {
+                self.alerting.fire(AlertKind::SaveFailure, message);
+            }
+        }
+    }
+
     /// Ticks essential server functions that must run even when the game is frozen.
     /// This includes player ticking (network, keep-alives) and flushing world updates to clients.
     pub async fn tick_players_and_network(&self) {
         let phase_start = std::time::Instant::now();
 
-        // First, flush pending block updates and synced block events to clients
+        // Flushes pending block updates and synced block events to clients first
         for world in self.worlds.load().iter() {
-            world.flush_block_updates().await;
-            world.flush_synced_block_events().await;
-        }
-
-        let players_to_tick: Vec<_> = self.get_all_players();
-        for player in players_to_tick {
-            player.tick(self).await;
+            world.tick_players(self).await;
         }
 
         self.tick_profiler.record_player_tick(phase_start);
+        self.tick_profiler
+            .record_section(TickSection::Worlds, phase_start.elapsed());
     }
     /// Ticks the game logic for all worlds. This is the part that is affected by `/tick freeze`.
     pub async fn tick_worlds(self: &Arc<Self>) {
@@ -802,18 +1021,36 @@ impl Server {
             let server = self.clone();
 
             set.spawn(async move {
-                world.tick(&server).await;
+                // Worlds with their own tick rate may tick several times, or not at all
+                let ticks = if server.instances.is_paused(&world) {
+                    0
+                } else {
+                    server.tick_rate_manager.world_ticks_due(&world)
+                };
+                match ticks {
+                    0 => world.tick_players(&server).await,
+                    ticks => {
+                        for _ in 0..ticks {
+                            world.tick(&server).await;
+                        }
+                    }
+                }
             });
         }
 
         set.join_all().await;
+        self.tick_profiler
+            .record_section(TickSection::Worlds, phase_start.elapsed());
 
         // Global tasks (only autosave when enabled — /save-off disables this)
+        let saving_start = std::time::Instant::now();
         if self.autosave_enabled.load(Ordering::Relaxed)
             && let Err(e) = self.player_data_storage.tick(self).await
         {
             log::error!("Error ticking player data: {e}");
         }
+        self.tick_profiler
+            .record_section(TickSection::Saving, saving_start.elapsed());
 
         self.tick_profiler.record_world_tick(phase_start);
     }
@@ -830,6 +1067,27 @@ impl Server {
 
         self.aggregated_tick_times_nanos
             .fetch_add(tick_duration_nanos - old_time, Ordering::Relaxed);
+        self.tick_stats
+            .record(Duration::from_nanos(tick_duration_nanos as u64));
+    }
+
+    /// Feeds the tick time chart of the debug screen of every player watching it. `full_tick`
+    /// is the time from the start of the tick to the start of the next one, `tick` the part of it
+    /// spent ticking.
+    pub async fn send_debug_tick_sample(&self, full_tick: Duration, tick: Duration) {
+        let sample = [
+            full_tick.as_nanos() as i64,
+            tick.as_nanos() as i64,
+            // Pumpkin runs no scheduled tasks between ticks
+            0,
+            full_tick.saturating_sub(tick).as_nanos() as i64,
+        ];
+        let packet = CDebugSample::new(&sample, CDebugSample::TICK_TIME);
+        for player in self.get_all_players() {
+            if player.debug_tick_time.load(Ordering::Relaxed) {
+                player.client.enqueue_packet(&packet).await;
+            }
+        }
     }
 
     /// Gets the rolling average tick time over the last 100 ticks, in nanoseconds.
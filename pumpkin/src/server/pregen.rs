@@ -0,0 +1,378 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use pumpkin_data::dimension::Dimension;
+use pumpkin_util::math::vector2::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::SHOULD_STOP;
+use crate::server::Server;
+use crate::world::World;
+
+/// File in the world folder holding the progress of a running pregeneration.
+const CHECKPOINT_FILE: &str = "pregen.json";
+/// Chunks requested from the generation pool at once.
+const BATCH_SIZE: usize = 64;
+/// How often the progress is logged and the checkpoint written.
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PregenShape {
+    Square,
+    Circle,
+}
+
+/// An area to pregenerate, written to the checkpoint file as it progresses.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PregenJob {
+    /// Name of the dimension, like `minecraft:overworld`.
+    pub dimension: String,
+    /// Center chunk.
+    pub center: [i32; 2],
+    /// Radius in chunks.
+    pub radius: i32,
+    pub shape: PregenShape,
+    /// How many chunks of [`PregenJob::positions`] are generated and saved.
+    pub done: usize,
+}
+
+impl PregenJob {
+    /// A job for the chunks within `radius` blocks of the world spawn, or of `0 0` outside the
+    /// overworld.
+    #[must_use]
+    pub fn around_spawn(server: &Server, world: &World, radius: i32, shape: PregenShape) -> Self {
+        let center = if world.dimension == Dimension::OVERWORLD {
+            let level_info = server.level_info.load();
+            [level_info.spawn_x >> 4, level_info.spawn_z >> 4]
+        } else {
+            [0, 0]
+        };
+        Self {
+            dimension: world.dimension.minecraft_name.to_string(),
+            center,
+            radius: (radius + 15) / 16,
+            shape,
+            done: 0,
+        }
+    }
+
+    /// The chunks to generate, in rings from the center outwards.
+    #[must_use]
+    pub fn positions(&self) -> Vec<Vector2<i32>> {
+        let [center_x, center_z] = self.center;
+        let mut positions = Vec::new();
+        for ring in 0..=self.radius {
+            for dx in -ring..=ring {
+                for dz in -ring..=ring {
+                    if dx.abs() != ring && dz.abs() != ring {
+                        continue;
+                    }
+                    if self.shape == PregenShape::Circle
+                        && dx * dx + dz * dz > self.radius * self.radius
+                    {
+                        continue;
+                    }
+                    positions.push(Vector2::new(center_x + dx, center_z + dz));
+                }
+            }
+        }
+        positions
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PregenError {
+    #[error("A pregeneration is already running")]
+    AlreadyRunning,
+    #[error("No world {0} is loaded")]
+    UnknownWorld(String),
+}
+
+/// Progress of the running pregeneration.
+pub struct PregenStatus {
+    pub dimension: String,
+    pub done: usize,
+    pub total: usize,
+    pub chunks_per_second: f64,
+    /// Estimated time until all chunks are generated.
+    pub eta: Option<Duration>,
+}
+
+struct Progress {
+    dimension: String,
+    total: usize,
+    done: AtomicUsize,
+    /// `done` when this run started, so resumed chunks don't count towards the speed.
+    resumed_at: usize,
+    started: Instant,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    fn status(&self) -> PregenStatus {
+        let done = self.done.load(Ordering::Relaxed);
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let chunks_per_second = if elapsed > 0.0 {
+            (done - self.resumed_at) as f64 / elapsed
+        } else {
+            0.0
+        };
+        let eta = (chunks_per_second > 0.0).then(|| {
+            Duration::from_secs_f64(self.total.saturating_sub(done) as f64 / chunks_per_second)
+        });
+        PregenStatus {
+            dimension: self.dimension.clone(),
+            done,
+            total: self.total,
+            chunks_per_second,
+            eta,
+        }
+    }
+}
+
+/// Generates all chunks of an area ahead of time, for `/pregen` and `--pregen`.
+///
+/// The progress is saved to a checkpoint file in the world folder, so a pregeneration that was
+/// interrupted by a stop continues on the next start.
+pub struct Pregenerator {
+    checkpoint: PathBuf,
+    running: Mutex<Option<Arc<Progress>>>,
+}
+
+impl Pregenerator {
+    #[must_use]
+    pub fn new(world_path: &Path) -> Self {
+        Self {
+            checkpoint: world_path.join(CHECKPOINT_FILE),
+            running: Mutex::new(None),
+        }
+    }
+
+    /// The job of an interrupted pregeneration, if there is one.
+    #[must_use]
+    pub fn load_checkpoint(&self) -> Option<PregenJob> {
+        let json = std::fs::read_to_string(&self.checkpoint).ok()?;
+        serde_json::from_str(&json)
+            .inspect_err(|err| log::warn!("Ignoring the pregeneration checkpoint: {err}"))
+            .ok()
+    }
+
+    fn save_checkpoint(&self, job: &PregenJob) {
+        let result = serde_json::to_string_pretty(job)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(&self.checkpoint, json));
+        if let Err(err) = result {
+            log::warn!("Failed to save the pregeneration checkpoint: {err}");
+        }
+    }
+
+    #[must_use]
+    pub fn status(&self) -> Option<PregenStatus> {
+        self.running
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|progress| progress.status())
+    }
+
+    /// Stops the running pregeneration after the current batch and forgets its checkpoint.
+    /// Returns whether one was running.
+    pub fn cancel(&self) -> bool {
+        let running = self.running.lock().unwrap();
+        let Some(progress) = running.as_ref() else {
+            return false;
+        };
+        progress.cancelled.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// Starts `job` in the background.
+    pub fn start(self: &Arc<Self>, server: &Server, job: PregenJob) -> Result<(), PregenError> {
+        let (world, progress) = self.prepare(server, &job)?;
+        let pregenerator = self.clone();
+        server.spawn_task(async move { pregenerator.run(&world, job, &progress).await });
+        Ok(())
+    }
+
+    /// Continues the pregeneration of the checkpoint in the background, if there is one.
+    pub fn resume(self: &Arc<Self>, server: &Server) {
+        let Some(job) = self.load_checkpoint() else {
+            return;
+        };
+        log::info!(
+            "Resuming the pregeneration of {} at {} chunks",
+            job.dimension,
+            job.done
+        );
+        if let Err(err) = self.start(server, job) {
+            log::warn!("Failed to resume the pregeneration: {err}");
+        }
+    }
+
+    /// Runs `job` to the end or until the server stops. Returns whether all chunks were
+    /// generated.
+    pub async fn run_to_end(&self, server: &Server, job: PregenJob) -> Result<bool, PregenError> {
+        let (world, progress) = self.prepare(server, &job)?;
+        Ok(self.run(&world, job, &progress).await)
+    }
+
+    fn prepare(
+        &self,
+        server: &Server,
+        job: &PregenJob,
+    ) -> Result<(Arc<World>, Arc<Progress>), PregenError> {
+        let world = server
+            .worlds
+            .load()
+            .iter()
+            .find(|world| world.dimension.minecraft_name == job.dimension)
+            .cloned()
+            .ok_or_else(|| PregenError::UnknownWorld(job.dimension.clone()))?;
+        let mut running = self.running.lock().unwrap();
+        if running.is_some() {
+            return Err(PregenError::AlreadyRunning);
+        }
+        let progress = Arc::new(Progress {
+            dimension: job.dimension.clone(),
+            total: job.positions().len(),
+            done: AtomicUsize::new(job.done),
+            resumed_at: job.done,
+            started: Instant::now(),
+            cancelled: AtomicBool::new(false),
+        });
+        *running = Some(progress.clone());
+        Ok((world, progress))
+    }
+
+    async fn run(&self, world: &Arc<World>, mut job: PregenJob, progress: &Arc<Progress>) -> bool {
+        let level = &world.level;
+        let positions = job.positions();
+        log::info!(
+            "Pregenerating {} chunks of {} around chunk {} {}",
+            positions.len(),
+            job.dimension,
+            job.center[0],
+            job.center[1]
+        );
+        self.save_checkpoint(&job);
+
+        let mut last_report = Instant::now();
+        let mut finished = true;
+        for batch in positions[job.done.min(positions.len())..].chunks(BATCH_SIZE) {
+            if progress.cancelled.load(Ordering::Relaxed) || SHOULD_STOP.load(Ordering::Relaxed) {
+                finished = false;
+                break;
+            }
+            join_all(batch.iter().map(|pos| level.get_chunk(*pos))).await;
+            let done = progress.done.fetch_add(batch.len(), Ordering::Relaxed) + batch.len();
+
+            if last_report.elapsed() >= REPORT_INTERVAL {
+                last_report = Instant::now();
+                // Let the generated chunks unload, and only record them once they are on disk
+                level.should_unload.store(true, Ordering::Relaxed);
+                level.level_channel.notify();
+                level.await_save(level.request_save()).await;
+                job.done = done;
+                self.save_checkpoint(&job);
+                log_progress(&progress.status());
+            }
+        }
+
+        level.await_save(level.request_save()).await;
+        *self.running.lock().unwrap() = None;
+        if progress.cancelled.load(Ordering::Relaxed) {
+            let _ = std::fs::remove_file(&self.checkpoint);
+            log::info!("Cancelled the pregeneration of {}", job.dimension);
+            return false;
+        }
+        if finished {
+            let _ = std::fs::remove_file(&self.checkpoint);
+            log::info!(
+                "Pregenerated all {} chunks of {} in {}",
+                progress.total,
+                job.dimension,
+                format_duration(progress.started.elapsed())
+            );
+        } else {
+            job.done = progress.done.load(Ordering::Relaxed);
+            self.save_checkpoint(&job);
+            log::info!(
+                "Paused the pregeneration of {} at {} of {} chunks, it continues on the next start",
+                job.dimension,
+                job.done,
+                progress.total
+            );
+        }
+        finished
+    }
+}
+
+fn log_progress(status: &PregenStatus) {
+    log::info!(
+        "Pregenerating {}: {} of {} chunks ({:.1}%), {:.1} chunks/s, ETA {}",
+        status.dimension,
+        status.done,
+        status.total,
+        status.done as f64 * 100.0 / status.total.max(1) as f64,
+        status.chunks_per_second,
+        status
+            .eta
+            .map_or_else(|| "unknown".to_string(), format_duration)
+    );
+}
+
+/// Formats `duration` like `1h 2m 3s`, leaving out leading zero units.
+#[must_use]
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(shape: PregenShape) -> PregenJob {
+        PregenJob {
+            dimension: "minecraft:overworld".to_string(),
+            center: [10, -4],
+            radius: 3,
+            shape,
+            done: 0,
+        }
+    }
+
+    #[test]
+    fn positions_cover_the_shape_from_the_center() {
+        let square = job(PregenShape::Square).positions();
+        assert_eq!(square.len(), 49);
+        assert_eq!(square[0], Vector2::new(10, -4));
+        let mut unique = square.clone();
+        unique.sort_by_key(|pos| (pos.x, pos.y));
+        unique.dedup();
+        assert_eq!(unique.len(), 49);
+
+        let circle = job(PregenShape::Circle).positions();
+        assert!(circle.len() < square.len());
+        assert!(!circle.contains(&Vector2::new(13, -1)));
+        assert!(circle.contains(&Vector2::new(13, -4)));
+    }
+
+    #[test]
+    fn durations_leave_out_zero_units() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 5s");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1h 2m 3s");
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pumpkin_config::integrations::RedisConfig;
+use pumpkin_util::text::TextComponent;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::select;
+use tokio::sync::{Notify, broadcast};
+
+use crate::server::Server;
+use crate::{SHOULD_STOP, STOP_INTERRUPT};
+use resp::Value;
+
+pub mod resp;
+
+/// Messages buffered per channel for subscribers that fall behind.
+const CHANNEL_CAPACITY: usize = 256;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(thiserror::Error, Debug)]
+pub enum RedisError {
+    #[error("The Redis connector is disabled")]
+    Disabled,
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Redis replied with an error: {0}")]
+    Reply(String),
+}
+
+/// A connection to Redis, split so replies can be read while commands are written.
+struct Connection {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl Connection {
+    /// Connects, authenticates and selects the configured database.
+    async fn open(config: &RedisConfig) -> Result<Self, RedisError> {
+        let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&config.address))
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+        let (reader, writer) = stream.into_split();
+        let mut connection = Self {
+            reader: BufReader::new(reader),
+            writer,
+        };
+        if let Some(password) = &config.password {
+            match &config.username {
+                Some(username) => {
+                    connection
+                        .command(&[b"AUTH", username.as_bytes(), password.as_bytes()])
+                        .await?
+                }
+                None => connection.command(&[b"AUTH", password.as_bytes()]).await?,
+            };
+        }
+        if config.database != 0 {
+            connection
+                .command(&[b"SELECT", config.database.to_string().as_bytes()])
+                .await?;
+        }
+        Ok(connection)
+    }
+
+    async fn send(&mut self, args: &[&[u8]]) -> io::Result<()> {
+        self.writer.write_all(&resp::encode_command(args)).await
+    }
+
+    async fn command(&mut self, args: &[&[u8]]) -> Result<Value, RedisError> {
+        self.send(args).await?;
+        match resp::read_value(&mut self.reader).await? {
+            Value::Error(message) => Err(RedisError::Reply(message)),
+            value => Ok(value),
+        }
+    }
+}
+
+/// Publish/subscribe messaging between the servers of a network through Redis.
+///
+/// Broadcasts sent with [`Self::broadcast`] are shown to the players of every server, and the
+/// servers share their player counts for the server list. Plugins can use their own channels:
+///
+/// ```ignore
+/// let mut messages = server.redis.subscribe("parties:invites");
+/// server.redis.publish("parties:invites", "Steve Alex").await?;
+/// while let Ok(message) = messages.recv().await { /* ... */ }
+/// ```
+///
+/// Both connections are reopened when they are lost. Messages published while the
+/// subscription was down are missed, like with any Redis pub/sub client.
+pub struct RedisConnector {
+    config: RedisConfig,
+    /// Connection publishing messages, opened on the first publish.
+    publisher: tokio::sync::Mutex<Option<Connection>>,
+    /// Subscribed channels, the server's own ones are not included.
+    channels: Mutex<HashMap<String, broadcast::Sender<String>>>,
+    /// Wakes the subscription to subscribe to channels added since it connected.
+    new_channels: Notify,
+    /// Player count and time of the last announcement of the other servers, by name.
+    player_counts: Mutex<HashMap<String, (u32, Instant)>>,
+}
+
+impl RedisConnector {
+    #[must_use]
+    pub fn new(config: RedisConfig) -> Self {
+        Self {
+            config,
+            publisher: tokio::sync::Mutex::new(None),
+            channels: Mutex::new(HashMap::new()),
+            new_channels: Notify::new(),
+            player_counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Publishes `message` on `channel`. Returns the number of subscribers that got it, over
+    /// all servers.
+    pub async fn publish(&self, channel: &str, message: &str) -> Result<i64, RedisError> {
+        if !self.is_enabled() {
+            return Err(RedisError::Disabled);
+        }
+        let mut publisher = self.publisher.lock().await;
+        let mut retried = false;
+        loop {
+            let connection = match publisher.as_mut() {
+                Some(connection) => connection,
+                None => publisher.insert(Connection::open(&self.config).await?),
+            };
+            match connection
+                .command(&[b"PUBLISH", channel.as_bytes(), message.as_bytes()])
+                .await
+            {
+                Ok(Value::Integer(receivers)) => return Ok(receivers),
+                Ok(_) => return Ok(0),
+                Err(err) => {
+                    *publisher = None;
+                    // Redis may have closed the connection since the last publish
+                    if retried || !matches!(err, RedisError::Io(_)) {
+                        return Err(err);
+                    }
+                    log::debug!("Reconnecting the Redis publisher: {err}");
+                    retried = true;
+                }
+            }
+        }
+    }
+
+    /// Subscribes to `channel`. Messages published by any server, this one included, are
+    /// received from the returned receiver.
+    pub fn subscribe(&self, channel: &str) -> broadcast::Receiver<String> {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(sender) = channels.get(channel) {
+            return sender.subscribe();
+        }
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        channels.insert(channel.to_string(), sender);
+        drop(channels);
+        self.new_channels.notify_one();
+        receiver
+    }
+
+    /// Shows `message` to the players of every server of the network.
+    pub async fn broadcast(&self, message: &str) -> Result<(), RedisError> {
+        self.publish(&self.broadcast_channel(), message)
+            .await
+            .map(|_| ())
+    }
+
+    /// The players of the whole network, given the `local` players of this server. Servers that
+    /// stopped announcing their count are left out.
+    #[must_use]
+    pub fn global_player_count(&self, local: u32) -> u32 {
+        let expiry = self.player_count_interval() * 3;
+        let mut counts = self.player_counts.lock().unwrap();
+        counts.retain(|_, (_, announced)| announced.elapsed() < expiry);
+        counts
+            .iter()
+            .filter(|(name, _)| **name != self.config.server_name)
+            .fold(local, |total, (_, (count, _))| total.saturating_add(*count))
+    }
+
+    /// Whether the server list should show [`Self::global_player_count`].
+    #[must_use]
+    pub const fn shows_global_player_count(&self) -> bool {
+        self.config.enabled && self.config.global_player_count
+    }
+
+    /// Keeps the subscription open and announces the player count of this server until the
+    /// server stops.
+    pub async fn run(server: Arc<Server>) {
+        let redis = &server.redis;
+        select! {
+            () = redis.run_subscription(&server) => {}
+            () = redis.announce_player_count(&server) => {}
+            () = STOP_INTERRUPT.cancelled() => {}
+        }
+    }
+
+    async fn run_subscription(&self, server: &Server) {
+        let initial_delay = Duration::from_secs(self.config.reconnect_delay_seconds.max(1));
+        let max_delay =
+            Duration::from_secs(self.config.max_reconnect_delay_seconds).max(initial_delay);
+        let mut delay = initial_delay;
+        while !SHOULD_STOP.load(Ordering::Relaxed) {
+            match Connection::open(&self.config).await {
+                Ok(connection) => {
+                    log::info!("Connected to Redis at {}", self.config.address);
+                    delay = initial_delay;
+                    let err = self.subscription(connection, server).await;
+                    log::warn!("Lost the Redis subscription: {err}");
+                }
+                Err(err) => log::warn!(
+                    "Failed to connect to Redis at {}: {err}, retrying in {}s",
+                    self.config.address,
+                    delay.as_secs()
+                ),
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(max_delay);
+        }
+    }
+
+    /// Subscribes to all channels and handles messages until the connection fails.
+    async fn subscription(&self, connection: Connection, server: &Server) -> RedisError {
+        let Connection {
+            mut reader,
+            mut writer,
+        } = connection;
+        let mut subscribed = HashSet::new();
+
+        // Writes the subscriptions, while reading goes on below
+        let subscribe = async {
+            loop {
+                let mut channels: Vec<String> =
+                    self.channels.lock().unwrap().keys().cloned().collect();
+                channels.extend([self.broadcast_channel(), self.player_count_channel()]);
+                channels.retain(|channel| !subscribed.contains(channel));
+                if !channels.is_empty() {
+                    let mut args: Vec<&[u8]> = vec![b"SUBSCRIBE"];
+                    args.extend(channels.iter().map(String::as_bytes));
+                    if let Err(err) = writer.write_all(&resp::encode_command(&args)).await {
+                        return RedisError::Io(err);
+                    }
+                    subscribed.extend(channels);
+                }
+                self.new_channels.notified().await;
+            }
+        };
+        let read = async {
+            loop {
+                match resp::read_value(&mut reader).await {
+                    Ok(value) => self.handle_message(value, server).await,
+                    Err(err) => return RedisError::Io(err),
+                }
+            }
+        };
+        select! {
+            err = subscribe => err,
+            err = read => err,
+        }
+    }
+
+    async fn handle_message(&self, value: Value, server: &Server) {
+        let Value::Array(Some(parts)) = value else {
+            return;
+        };
+        let [kind, channel, payload] = parts.as_slice() else {
+            // Confirmations of subscriptions have a count instead of a payload
+            return;
+        };
+        let (Some(kind), Some(channel), Some(payload)) =
+            (kind.as_string(), channel.as_string(), payload.as_string())
+        else {
+            return;
+        };
+        if kind != "message" {
+            return;
+        }
+        if let Some(sender) = self.channels.lock().unwrap().get(&channel) {
+            // Nobody listening is fine
+            let _ = sender.send(payload.clone());
+        }
+
+        if channel == self.broadcast_channel() {
+            let message = TextComponent::text(payload);
+            for world in server.worlds.load().iter() {
+                for player in world.players.load().iter() {
+                    player.send_system_message(&message).await;
+                }
+            }
+        } else if channel == self.player_count_channel()
+            && let Some((name, count)) = payload.rsplit_once(' ')
+            && let Ok(count) = count.parse()
+        {
+            self.player_counts
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), (count, Instant::now()));
+        }
+    }
+
+    async fn announce_player_count(&self, server: &Server) {
+        let mut interval = tokio::time::interval(self.player_count_interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let message = format!("{} {}", self.config.server_name, server.get_player_count());
+            if let Err(err) = self.publish(&self.player_count_channel(), &message).await {
+                log::debug!("Failed to announce the player count to Redis: {err}");
+            }
+        }
+    }
+
+    fn player_count_interval(&self) -> Duration {
+        Duration::from_secs(self.config.player_count_interval_seconds.max(1))
+    }
+
+    fn broadcast_channel(&self) -> String {
+        format!("{}broadcast", self.config.channel_prefix)
+    }
+
+    fn player_count_channel(&self) -> String {
+        format!("{}players", self.config.channel_prefix)
+    }
+}
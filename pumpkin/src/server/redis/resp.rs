@@ -0,0 +1,146 @@
+//! The parts of RESP, the protocol spoken by Redis, that the connector needs.
+
+use std::io;
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+use crate::plugin::BoxFuture;
+
+/// A value sent by Redis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Self>>),
+}
+
+impl Value {
+    /// The text of a simple or bulk string.
+    #[must_use]
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            Self::Simple(text) => Some(text.clone()),
+            Self::Bulk(Some(bytes)) => Some(String::from_utf8_lossy(bytes).into_owned()),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes a command as an array of bulk strings.
+#[must_use]
+pub fn encode_command(args: &[&[u8]]) -> Vec<u8> {
+    let mut buf = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buf.extend_from_slice(arg);
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf
+}
+
+/// Reads the next value. Not cancel safe, a value read halfway is lost.
+pub fn read_value<R: AsyncBufRead + Unpin + Send>(
+    reader: &mut R,
+) -> BoxFuture<'_, io::Result<Value>> {
+    Box::pin(async move {
+        let line = read_line(reader).await?;
+        let (kind, rest) = line
+            .split_at_checked(1)
+            .ok_or_else(|| invalid("empty line"))?;
+        match kind {
+            "+" => Ok(Value::Simple(rest.to_string())),
+            "-" => Ok(Value::Error(rest.to_string())),
+            ":" => Ok(Value::Integer(parse_int(rest)?)),
+            "$" => {
+                let Ok(len) = usize::try_from(parse_int(rest)?) else {
+                    return Ok(Value::Bulk(None));
+                };
+                let mut bytes = vec![0; len + 2];
+                reader.read_exact(&mut bytes).await?;
+                if !bytes.ends_with(b"\r\n") {
+                    return Err(invalid("bulk string without line end"));
+                }
+                bytes.truncate(len);
+                Ok(Value::Bulk(Some(bytes)))
+            }
+            "*" => {
+                let Ok(len) = usize::try_from(parse_int(rest)?) else {
+                    return Ok(Value::Array(None));
+                };
+                let mut values = Vec::with_capacity(len.min(64));
+                for _ in 0..len {
+                    values.push(read_value(reader).await?);
+                }
+                Ok(Value::Array(Some(values)))
+            }
+            _ => Err(invalid("unknown value type")),
+        }
+    })
+}
+
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<String> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line).await? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    if !line.ends_with(b"\r\n") {
+        return Err(invalid("line without line end"));
+    }
+    line.truncate(line.len() - 2);
+    String::from_utf8(line).map_err(|_| invalid("line is not UTF-8"))
+}
+
+fn parse_int(text: &str) -> io::Result<i64> {
+    text.parse().map_err(|_| invalid("invalid integer"))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(bytes: &[u8]) -> io::Result<Value> {
+        let mut reader = bytes;
+        read_value(&mut reader).await
+    }
+
+    #[test]
+    fn commands_are_arrays_of_bulk_strings() {
+        assert_eq!(
+            encode_command(&[b"PUBLISH", b"chat", b"hi"]),
+            b"*3\r\n$7\r\nPUBLISH\r\n$4\r\nchat\r\n$2\r\nhi\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_pub_sub_messages() {
+        let message = read(b"*3\r\n$7\r\nmessage\r\n$4\r\nchat\r\n$5\r\nhi\r\nx\r\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            message,
+            Value::Array(Some(vec![
+                Value::Bulk(Some(b"message".to_vec())),
+                Value::Bulk(Some(b"chat".to_vec())),
+                Value::Bulk(Some(b"hi\r\nx".to_vec())),
+            ]))
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_replies() {
+        assert_eq!(read(b"+OK\r\n").await.unwrap(), Value::Simple("OK".into()));
+        assert_eq!(read(b":42\r\n").await.unwrap(), Value::Integer(42));
+        assert_eq!(read(b"$-1\r\n").await.unwrap(), Value::Bulk(None));
+        assert_eq!(
+            read(b"-ERR wrong\r\n").await.unwrap(),
+            Value::Error("ERR wrong".into())
+        );
+        assert!(read(b"$5\r\nhi").await.is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use pumpkin_config::restart::{RestartConfig, RestartMode};
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::color::NamedColor;
+use uuid::Uuid;
+
+use crate::entity::feedback::{Title, TitleTimes};
+use crate::entity::player::Player;
+use crate::server::Server;
+use crate::stop_server;
+use crate::world::bossbar::{Bossbar, BossbarColor};
+
+/// Set once a restart countdown has finished and the server is stopping because of it.
+static RESTART_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the current shutdown was triggered by `/restart`.
+pub fn is_restart_requested() -> bool {
+    RESTART_REQUESTED.load(Ordering::Relaxed)
+}
+
+struct Countdown {
+    deadline: Instant,
+    total: Duration,
+    last_announced: Option<u64>,
+    /// The bossbar as currently shown, for players joining during the countdown.
+    bossbar: Bossbar,
+}
+
+/// What a tick of the countdown has to tell the players.
+enum Step {
+    /// `remaining` whole seconds are left.
+    Update {
+        remaining: u64,
+        text: TextComponent,
+        progress: f32,
+        bossbar: Uuid,
+    },
+    /// The countdown ran out.
+    Finish { bossbar: Uuid },
+}
+
+/// Drives the `/restart` countdown from the server tick.
+#[derive(Default)]
+pub struct RestartScheduler {
+    countdown: Mutex<Option<Countdown>>,
+}
+
+impl RestartScheduler {
+    /// Starts a countdown. Returns `false` if a restart is already scheduled.
+    pub async fn schedule(&self, server: &Server, seconds: u32) -> bool {
+        let Some(bossbar) = self.start(seconds, Instant::now()) else {
+            return false;
+        };
+        for player in server.get_all_players() {
+            player.send_bossbar(&bossbar).await;
+        }
+        self.tick(server).await;
+        true
+    }
+
+    /// Cancels a scheduled restart. Returns `false` if none was scheduled.
+    pub async fn cancel(&self, server: &Server) -> bool {
+        let Some(bossbar) = self.stop() else {
+            return false;
+        };
+        let message = TextComponent::text("The scheduled restart was cancelled.")
+            .color_named(NamedColor::Green);
+        for player in server.get_all_players() {
+            player.remove_bossbar(bossbar).await;
+            player.send_system_message(&message).await;
+        }
+        true
+    }
+
+    /// Shows the countdown to a player who joined while it runs.
+    pub async fn show_to(&self, player: &Player) {
+        let bossbar = self
+            .countdown
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|countdown| countdown.bossbar.clone());
+        if let Some(bossbar) = bossbar {
+            player.send_bossbar(&bossbar).await;
+        }
+    }
+
+    /// Updates the countdown once per second and stops the server when it runs out.
+    pub async fn tick(&self, server: &Server) {
+        match self.advance(Instant::now()) {
+            None => {}
+            Some(Step::Finish { bossbar }) => {
+                log::info!("Restarting the server");
+                for player in server.get_all_players() {
+                    player.remove_bossbar(bossbar).await;
+                }
+                RESTART_REQUESTED.store(true, Ordering::Relaxed);
+                stop_server();
+            }
+            Some(Step::Update {
+                remaining,
+                text,
+                progress,
+                bossbar,
+            }) => {
+                let announce = remaining <= 10 || remaining % 30 == 0;
+                let players = server.get_all_players();
+                for player in &players {
+                    player.update_bossbar_title(&bossbar, text.clone()).await;
+                    player.update_bossbar_health(&bossbar, progress).await;
+                }
+                if announce {
+                    Title::new(TextComponent::text("Restarting"))
+                        .subtitle(text)
+                        .times(TitleTimes::new(0, 30, 10))
+                        .send_to(&players)
+                        .await;
+                    log::info!("Server restarting in {remaining}s");
+                }
+            }
+        }
+    }
+
+    /// Starts a countdown of `seconds` from `now` and returns its bossbar, unless one runs.
+    fn start(&self, seconds: u32, now: Instant) -> Option<Bossbar> {
+        let mut countdown = self.countdown.lock().unwrap();
+        if countdown.is_some() {
+            return None;
+        }
+        let bossbar = Bossbar {
+            health: 1.0,
+            color: BossbarColor::Red,
+            ..Bossbar::new(TextComponent::text("Server restart"))
+        };
+        *countdown = Some(Countdown {
+            deadline: now + Duration::from_secs(seconds.into()),
+            total: Duration::from_secs(seconds.max(1).into()),
+            last_announced: None,
+            bossbar: bossbar.clone(),
+        });
+        Some(bossbar)
+    }
+
+    /// Stops the countdown and returns its bossbar, if one runs.
+    fn stop(&self) -> Option<Uuid> {
+        let countdown = self.countdown.lock().unwrap().take()?;
+        Some(countdown.bossbar.uuid)
+    }
+
+    /// Moves the countdown to `now`. Returns `None` if there is none or its second was
+    /// already announced.
+    fn advance(&self, now: Instant) -> Option<Step> {
+        let mut guard = self.countdown.lock().unwrap();
+        let countdown = guard.as_mut()?;
+        let left = countdown.deadline.saturating_duration_since(now);
+        let remaining = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        if countdown.last_announced == Some(remaining) {
+            return None;
+        }
+        countdown.last_announced = Some(remaining);
+        let bossbar = countdown.bossbar.uuid;
+        if remaining == 0 {
+            *guard = None;
+            return Some(Step::Finish { bossbar });
+        }
+
+        let text = TextComponent::text(format!(
+            "Server restarting in {remaining} second{}",
+            if remaining == 1 { "" } else { "s" }
+        ))
+        .color_named(NamedColor::Red);
+        let progress = left.as_secs_f32() / countdown.total.as_secs_f32();
+        countdown.bossbar.title = text.clone();
+        countdown.bossbar.health = progress;
+        Some(Step::Update {
+            remaining,
+            text,
+            progress,
+            bossbar,
+        })
+    }
+}
+
+/// Exits or re-executes the process right away, without a countdown or a shutdown. Used when the
+/// server can't shut down, e.g. because a tick hangs.
+pub fn restart_now(config: &RestartConfig) {
+    RESTART_REQUESTED.store(true, Ordering::Relaxed);
+    finish_restart(config);
+}
+
+/// Exits or re-executes the process after a restart-triggered shutdown. Returns otherwise.
+pub fn finish_restart(config: &RestartConfig) {
+    if !is_restart_requested() {
+        return;
+    }
+    match config.mode {
+        RestartMode::Exit => {
+            log::info!("Exiting with code {} for restart", config.exit_code);
+            std::process::exit(config.exit_code);
+        }
+        RestartMode::Reexec => {
+            let executable = match std::env::current_exe() {
+                Ok(executable) => executable,
+                Err(err) => {
+                    log::error!("Failed to locate the server binary to restart: {err}");
+                    std::process::exit(config.exit_code);
+                }
+            };
+            let mut command = std::process::Command::new(executable);
+            command.args(std::env::args_os().skip(1));
+            log::info!("Restarting the server process");
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::CommandExt;
+                // Only returns on failure
+                let err = command.exec();
+                log::error!("Failed to restart the server process: {err}");
+                std::process::exit(config.exit_code);
+            }
+            #[cfg(not(unix))]
+            {
+                if let Err(err) = command.spawn() {
+                    log::error!("Failed to restart the server process: {err}");
+                    std::process::exit(config.exit_code);
+                }
+                std::process::exit(0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remaining(step: Option<Step>) -> Option<u64> {
+        match step? {
+            Step::Update { remaining, .. } => Some(remaining),
+            Step::Finish { .. } => Some(0),
+        }
+    }
+
+    #[test]
+    fn counts_down_once_per_second() {
+        let scheduler = RestartScheduler::default();
+        let start = Instant::now();
+        let bossbar = scheduler.start(3, start).unwrap();
+        assert!(scheduler.start(5, start).is_none());
+
+        assert_eq!(remaining(scheduler.advance(start)), Some(3));
+        assert_eq!(remaining(scheduler.advance(start)), None);
+        let halfway = start + Duration::from_millis(1500);
+        assert_eq!(remaining(scheduler.advance(halfway)), Some(2));
+
+        // Players joining now see the current state
+        let shown = scheduler
+            .countdown
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .bossbar
+            .clone();
+        assert_eq!(shown.uuid, bossbar.uuid);
+        assert!((shown.health - 0.5).abs() < f32::EPSILON);
+
+        let Some(Step::Finish { bossbar: finished }) =
+            scheduler.advance(start + Duration::from_secs(3))
+        else {
+            panic!("the countdown should have finished");
+        };
+        assert_eq!(finished, bossbar.uuid);
+        assert!(scheduler.advance(start + Duration::from_secs(4)).is_none());
+        assert!(scheduler.start(3, start).is_some());
+    }
+
+    #[test]
+    fn cancel_stops_the_countdown() {
+        let scheduler = RestartScheduler::default();
+        assert!(scheduler.stop().is_none());
+
+        let start = Instant::now();
+        let bossbar = scheduler.start(10, start).unwrap();
+        assert_eq!(scheduler.stop(), Some(bossbar.uuid));
+        assert!(scheduler.advance(start + Duration::from_secs(10)).is_none());
+        assert!(scheduler.stop().is_none());
+        assert!(scheduler.start(10, start).is_some());
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::BlockStateId;
+use pumpkin_world::block::entities::block_entity_from_nbt;
+use pumpkin_world::schematic::{Schematic, SchematicError, Transform};
+use pumpkin_world::world::BlockFlags;
+use tokio::sync::Mutex;
+
+use crate::world::World;
+
+const SCHEMATICS_DIRECTORY: &str = "schematics";
+
+/// Blocks placed per tick over all pastes, so that large schematics don't stall the server.
+const BLOCKS_PER_TICK: usize = 4096;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("Invalid schematic name {0}")]
+    InvalidName(String),
+    #[error("{0}")]
+    Schematic(#[from] SchematicError),
+}
+
+struct Paste {
+    world: Arc<World>,
+    origin: BlockPos,
+    blocks: std::vec::IntoIter<(Vector3<i32>, BlockStateId)>,
+    /// Placed once all blocks are, so that the blocks don't replace them.
+    block_entities: Vec<NbtCompound>,
+    /// Who started the paste.
+    owner: String,
+}
+
+/// Loaded schematics and the pastes in progress, which place a limited number of blocks per tick.
+#[derive(Default)]
+pub struct Schematics {
+    /// The last schematic each command sender loaded.
+    clipboards: Mutex<HashMap<String, Arc<Schematic>>>,
+    pastes: Mutex<VecDeque<Paste>>,
+}
+
+impl Schematics {
+    #[must_use]
+    pub fn directory() -> &'static Path {
+        Path::new(SCHEMATICS_DIRECTORY)
+    }
+
+    /// The path of a schematic file in the schematics directory, keeping names from escaping it.
+    fn path(name: &str) -> Result<PathBuf, LoadError> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(LoadError::InvalidName(name.to_string()));
+        }
+        Ok(Self::directory().join(name))
+    }
+
+    /// Loads a schematic from the schematics directory into the clipboard of `owner`.
+    pub async fn load(&self, owner: String, name: &str) -> Result<Arc<Schematic>, LoadError> {
+        let path = Self::path(name)?;
+        let schematic = tokio::task::spawn_blocking(move || Schematic::load(&path))
+            .await
+            .map_err(|err| SchematicError::Io(std::io::Error::other(err)))??;
+        let schematic = Arc::new(schematic);
+        self.clipboards
+            .lock()
+            .await
+            .insert(owner, schematic.clone());
+        Ok(schematic)
+    }
+
+    pub async fn clipboard(&self, owner: &str) -> Option<Arc<Schematic>> {
+        self.clipboards.lock().await.get(owner).cloned()
+    }
+
+    /// Queues a paste at `origin`. Returns the number of blocks to place.
+    pub async fn paste(
+        &self,
+        schematic: &Schematic,
+        owner: String,
+        world: Arc<World>,
+        origin: BlockPos,
+        transform: Transform,
+        skip_air: bool,
+    ) -> usize {
+        let blocks: Vec<_> = schematic.placements(transform, skip_air).collect();
+        let count = blocks.len();
+        let block_entities = schematic
+            .placed_block_entities(transform)
+            .map(|(pos, entity)| {
+                let pos = origin.0 + pos;
+                let mut nbt = entity.data.clone();
+                nbt.put_string("id", entity.id.clone());
+                nbt.put_int("x", pos.x);
+                nbt.put_int("y", pos.y);
+                nbt.put_int("z", pos.z);
+                nbt
+            })
+            .collect();
+        self.pastes.lock().await.push_back(Paste {
+            world,
+            origin,
+            blocks: blocks.into_iter(),
+            block_entities,
+            owner,
+        });
+        count
+    }
+
+    /// Continues the pastes in progress, oldest first.
+    pub async fn tick(&self) {
+        let mut pastes = self.pastes.lock().await;
+        let mut budget = BLOCKS_PER_TICK;
+        while budget > 0
+            && let Some(paste) = pastes.front_mut()
+        {
+            for (pos, state) in paste.blocks.by_ref().take(budget) {
+                budget -= 1;
+                paste
+                    .world
+                    .set_block_state(&paste.origin.offset(pos), state, BlockFlags::FORCE_STATE)
+                    .await;
+            }
+            if !paste.blocks.as_slice().is_empty() {
+                break;
+            }
+
+            let paste = pastes.pop_front().unwrap();
+            for nbt in &paste.block_entities {
+                match block_entity_from_nbt(nbt) {
+                    Some(block_entity) => paste.world.add_block_entity(block_entity).await,
+                    None => log::debug!(
+                        "Skipped unsupported block entity {:?} pasted by {}",
+                        nbt.get_string("id"),
+                        paste.owner
+                    ),
+                }
+            }
+            log::info!(
+                "Finished pasting the schematic of {} at {}",
+                paste.owner,
+                paste.origin
+            );
+        }
+    }
+}
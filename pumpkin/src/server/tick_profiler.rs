@@ -1,5 +1,7 @@
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use crossbeam::atomic::AtomicCell;
 
 /// Per-tick phase timing for performance diagnostics.
 ///
@@ -23,6 +25,169 @@ pub struct TickProfiler {
     // Slow tick tracking
     slow_tick_threshold_ms: AtomicU64,
     slow_tick_count: AtomicU64,
+
+    // Per-section totals of the running profiling session
+    session_active: AtomicBool,
+    session_start: AtomicCell<Instant>,
+    section_nanos: [AtomicU64; TickSection::ALL.len()],
+    section_calls: [AtomicU64; TickSection::ALL.len()],
+    // The latest time spent in each section, kept for the watchdog
+    last_section_nanos: [AtomicU64; TickSection::ALL.len()],
+}
+
+/// A part of the server tick that profiling sessions time, see [`TickProfiler::start_session`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickSection {
+    /// The whole server tick.
+    Tick,
+    /// Ticking all worlds, which run in parallel.
+    Worlds,
+    /// Sending block changes and block events to clients.
+    BlockUpdates,
+    /// Time of day and weather.
+    Environment,
+    /// Scheduled and random block ticks, and mob spawning.
+    Chunks,
+    BlockEntities,
+    /// Ticking players, including their keep-alives and chunk sending.
+    Players,
+    /// Ticking all other entities.
+    Entities,
+    /// Saving player data.
+    Saving,
+}
+
+impl TickSection {
+    pub const ALL: [Self; 9] = [
+        Self::Tick,
+        Self::Worlds,
+        Self::BlockUpdates,
+        Self::Environment,
+        Self::Chunks,
+        Self::BlockEntities,
+        Self::Players,
+        Self::Entities,
+        Self::Saving,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Tick => "tick",
+            Self::Worlds => "worlds",
+            Self::BlockUpdates => "block updates",
+            Self::Environment => "environment",
+            Self::Chunks => "chunks",
+            Self::BlockEntities => "block entities",
+            Self::Players => "players and networking",
+            Self::Entities => "entities",
+            Self::Saving => "saving",
+        }
+    }
+
+    /// The section this one runs in.
+    #[must_use]
+    pub const fn parent(self) -> Option<Self> {
+        match self {
+            Self::Tick => None,
+            Self::Worlds | Self::Saving => Some(Self::Tick),
+            Self::BlockUpdates
+            | Self::Environment
+            | Self::Chunks
+            | Self::Players
+            | Self::Entities => Some(Self::Worlds),
+            Self::BlockEntities => Some(Self::Chunks),
+        }
+    }
+}
+
+/// The time spent in each [`TickSection`] during a profiling session.
+///
+/// Worlds tick in parallel, so the sections within [`TickSection::Worlds`] may add up to more
+/// than it.
+#[derive(Debug, Clone)]
+pub struct ProfileReport {
+    /// How long the session ran.
+    pub duration: Duration,
+    /// Server ticks during the session.
+    pub ticks: u64,
+    nanos: [u64; TickSection::ALL.len()],
+    calls: [u64; TickSection::ALL.len()],
+}
+
+impl ProfileReport {
+    fn ms_per_tick(&self, nanos: u64) -> f64 {
+        nanos as f64 / 1_000_000.0 / self.ticks.max(1) as f64
+    }
+
+    fn share(&self, nanos: u64) -> f64 {
+        let tick = self.nanos[TickSection::Tick as usize];
+        if tick == 0 {
+            return 0.0;
+        }
+        nanos as f64 / tick as f64 * 100.0
+    }
+
+    /// Time spent in `section` itself, outside of the sections within it.
+    #[must_use]
+    pub fn self_nanos(&self, section: TickSection) -> u64 {
+        let children: u64 = TickSection::ALL
+            .iter()
+            .filter(|child| child.parent() == Some(section))
+            .map(|child| self.nanos[*child as usize])
+            .sum();
+        self.nanos[section as usize].saturating_sub(children)
+    }
+
+    /// The sections by the time spent in them themselves, slowest first.
+    #[must_use]
+    pub fn flat(&self) -> Vec<String> {
+        let mut sections: Vec<_> = TickSection::ALL
+            .into_iter()
+            .map(|section| (section, self.self_nanos(section)))
+            .filter(|(_, nanos)| *nanos > 0)
+            .collect();
+        sections.sort_unstable_by_key(|(_, nanos)| std::cmp::Reverse(*nanos));
+        sections
+            .into_iter()
+            .map(|(section, nanos)| {
+                format!(
+                    "{}: {:.2}ms/tick ({:.1}%)",
+                    section.name(),
+                    self.ms_per_tick(nanos),
+                    self.share(nanos)
+                )
+            })
+            .collect()
+    }
+
+    /// The sections nested in the ones they run in, with the total time spent in them.
+    #[must_use]
+    pub fn tree(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        self.tree_lines(TickSection::Tick, 0, &mut lines);
+        lines
+    }
+
+    fn tree_lines(&self, section: TickSection, depth: usize, lines: &mut Vec<String>) {
+        let nanos = self.nanos[section as usize];
+        if nanos == 0 {
+            return;
+        }
+        lines.push(format!(
+            "{}{}: {:.2}ms/tick ({:.1}%), {:.1} calls/tick",
+            "  ".repeat(depth),
+            section.name(),
+            self.ms_per_tick(nanos),
+            self.share(nanos),
+            self.calls[section as usize] as f64 / self.ticks.max(1) as f64
+        ));
+        for child in TickSection::ALL {
+            if child.parent() == Some(section) {
+                self.tree_lines(child, depth + 1, lines);
+            }
+        }
+    }
 }
 
 /// A lock-free rolling average over a fixed window.
@@ -167,6 +332,11 @@ impl TickProfiler {
             total_tick_nanos: RollingAverage::new(),
             slow_tick_threshold_ms: AtomicU64::new(Self::DEFAULT_SLOW_THRESHOLD_MS),
             slow_tick_count: AtomicU64::new(0),
+            session_active: AtomicBool::new(false),
+            session_start: AtomicCell::new(Instant::now()),
+            section_nanos: std::array::from_fn(|_| AtomicU64::new(0)),
+            section_calls: std::array::from_fn(|_| AtomicU64::new(0)),
+            last_section_nanos: std::array::from_fn(|_| AtomicU64::new(0)),
         }
     }
 
@@ -229,6 +399,63 @@ impl TickProfiler {
         self.slow_tick_count.store(0, Ordering::Relaxed);
     }
 
+    /// Starts timing the sections of each tick. Returns `false` if a session is already running.
+    pub fn start_session(&self) -> bool {
+        if self.session_active.load(Ordering::Relaxed) {
+            return false;
+        }
+        for (nanos, calls) in self.section_nanos.iter().zip(&self.section_calls) {
+            nanos.store(0, Ordering::Relaxed);
+            calls.store(0, Ordering::Relaxed);
+        }
+        self.session_start.store(Instant::now());
+        self.session_active.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// Whether a profiling session is running.
+    pub fn is_session_active(&self) -> bool {
+        self.session_active.load(Ordering::Relaxed)
+    }
+
+    /// Stops the running profiling session and reports on it, if there is one.
+    pub fn stop_session(&self) -> Option<ProfileReport> {
+        if !self.session_active.swap(false, Ordering::Relaxed) {
+            return None;
+        }
+        let load = |counters: &[AtomicU64; TickSection::ALL.len()]| {
+            std::array::from_fn(|i| counters[i].load(Ordering::Relaxed))
+        };
+        let calls: [u64; TickSection::ALL.len()] = load(&self.section_calls);
+        Some(ProfileReport {
+            duration: self.session_start.load().elapsed(),
+            ticks: calls[TickSection::Tick as usize],
+            nanos: load(&self.section_nanos),
+            calls,
+        })
+    }
+
+    /// Records `elapsed` as the latest time spent in `section`, and adds it to the time spent in
+    /// it if a profiling session is running.
+    pub fn record_section(&self, section: TickSection, elapsed: Duration) {
+        let nanos = elapsed.as_nanos() as u64;
+        self.last_section_nanos[section as usize].store(nanos, Ordering::Relaxed);
+        if !self.is_session_active() {
+            return;
+        }
+        self.section_nanos[section as usize].fetch_add(nanos, Ordering::Relaxed);
+        self.section_calls[section as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The latest time spent in each section. Sections within worlds are from whichever world
+    /// finished them last.
+    pub fn last_sections(&self) -> [(TickSection, Duration); TickSection::ALL.len()] {
+        TickSection::ALL.map(|section| {
+            let nanos = self.last_section_nanos[section as usize].load(Ordering::Relaxed);
+            (section, Duration::from_nanos(nanos))
+        })
+    }
+
     /// Get a snapshot of current profiling data.
     pub fn snapshot(&self) -> TickProfileSnapshot {
         TickProfileSnapshot {
@@ -369,6 +596,41 @@ mod tests {
         assert_eq!(snap.slow_tick_count, 0);
     }
 
+    #[test]
+    fn session_reports_sections() {
+        let profiler = TickProfiler::new();
+        profiler.record_section(TickSection::Tick, Duration::from_millis(1));
+        assert!(profiler.stop_session().is_none());
+
+        assert!(profiler.start_session());
+        assert!(!profiler.start_session());
+        for _ in 0..2 {
+            profiler.record_section(TickSection::Tick, Duration::from_millis(10));
+            profiler.record_section(TickSection::Worlds, Duration::from_millis(8));
+            profiler.record_section(TickSection::Chunks, Duration::from_millis(6));
+            profiler.record_section(TickSection::BlockEntities, Duration::from_millis(2));
+        }
+        let report = profiler.stop_session().unwrap();
+        assert_eq!(
+            profiler.last_sections()[TickSection::Chunks as usize],
+            (TickSection::Chunks, Duration::from_millis(6))
+        );
+        assert_eq!(report.ticks, 2);
+        assert_eq!(report.self_nanos(TickSection::Chunks), 8_000_000);
+        assert_eq!(report.self_nanos(TickSection::Worlds), 4_000_000);
+        assert_eq!(
+            report.tree(),
+            [
+                "tick: 10.00ms/tick (100.0%), 1.0 calls/tick",
+                "  worlds: 8.00ms/tick (80.0%), 1.0 calls/tick",
+                "    chunks: 6.00ms/tick (60.0%), 1.0 calls/tick",
+                "      block entities: 2.00ms/tick (20.0%), 1.0 calls/tick",
+            ]
+        );
+        assert_eq!(report.flat()[0], "chunks: 4.00ms/tick (40.0%)");
+        assert!(!profiler.is_session_active());
+    }
+
     #[test]
     fn snapshot_budget_usage() {
         let snap = TickProfileSnapshot {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, Ordering};
 use std::time::Instant;
 
@@ -7,7 +9,22 @@ use pumpkin_util::text::{TextComponent, color::NamedColor};
 
 use crate::entity::player::Player;
 use crate::server::Server;
+use crate::world::World;
 const NANOSECONDS_PER_SECOND: i64 = 1_000_000_000;
+/// The most ticks a world runs in one server tick, when it runs faster than the server.
+const MAX_WORLD_TICKS_PER_TICK: f64 = 10.0;
+
+/// How a world ticks when it doesn't follow the server tick rate.
+#[derive(Clone, Copy, Default)]
+struct WorldTickState {
+    /// Replaces the server tick rate.
+    rate: Option<f32>,
+    frozen: bool,
+    /// World ticks owed but not run yet, below one.
+    progress: f64,
+    /// World ticks to run in the current server tick.
+    due: u32,
+}
 
 pub struct ServerTickRateManager {
     tickrate: AtomicCell<f32>,
@@ -22,6 +39,9 @@ pub struct ServerTickRateManager {
     sprint_time_spend: AtomicI64,
     scheduled_current_sprint_ticks: AtomicI64,
     previous_is_frozen: AtomicBool,
+
+    /// Worlds with their own tick rate or freeze state, by dimension name.
+    worlds: Mutex<HashMap<&'static str, WorldTickState>>,
 }
 
 impl ServerTickRateManager {
@@ -38,6 +58,7 @@ impl ServerTickRateManager {
             sprint_time_spend: AtomicI64::new(0),
             scheduled_current_sprint_ticks: AtomicI64::new(0),
             previous_is_frozen: AtomicBool::new(false),
+            worlds: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -51,6 +72,84 @@ impl ServerTickRateManager {
         if frozen_ticks > 0 {
             self.frozen_ticks_to_run.fetch_sub(1, Ordering::Relaxed);
         }
+
+        let server_rate = f64::from(self.tickrate());
+        let mut worlds = self.worlds.lock().unwrap();
+        for state in worlds.values_mut() {
+            if state.frozen {
+                state.progress = 0.0;
+                state.due = 0;
+                continue;
+            }
+            let rate = state.rate.map_or(server_rate, f64::from);
+            let progress = (state.progress + rate / server_rate).min(MAX_WORLD_TICKS_PER_TICK);
+            state.due = progress as u32;
+            state.progress = progress.fract();
+        }
+    }
+
+    /// How many times `world` ticks in the current server tick.
+    pub fn world_ticks_due(&self, world: &World) -> u32 {
+        self.worlds
+            .lock()
+            .unwrap()
+            .get(world.dimension.minecraft_name)
+            .map_or(1, |state| state.due)
+    }
+
+    /// The tick rate of `world`, which is the server's unless it has its own.
+    pub fn world_tickrate(&self, world: &World) -> f32 {
+        self.world_state(world)
+            .and_then(|state| state.rate)
+            .unwrap_or_else(|| self.tickrate())
+    }
+
+    /// Whether `world` doesn't tick, either on its own or because the server is frozen.
+    pub fn is_world_frozen(&self, world: &World) -> bool {
+        self.is_frozen() || self.world_state(world).is_some_and(|state| state.frozen)
+    }
+
+    /// The worlds with their own tick rate or freeze state, with their rate and whether they are
+    /// frozen.
+    pub fn world_overrides(&self) -> Vec<(&'static str, Option<f32>, bool)> {
+        let mut overrides: Vec<_> = self
+            .worlds
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, state)| (*name, state.rate, state.frozen))
+            .collect();
+        overrides.sort_unstable_by_key(|(name, _, _)| *name);
+        overrides
+    }
+
+    /// Gives `world` its own tick rate, or makes it follow the server's again with `None`.
+    pub async fn set_world_tick_rate(&self, world: &World, rate: Option<f32>) {
+        self.update_world_state(world, |state| state.rate = rate.map(|rate| rate.max(1.0)));
+        self.update_world_to_clients(world).await;
+    }
+
+    pub async fn set_world_frozen(&self, world: &World, frozen: bool) {
+        self.update_world_state(world, |state| state.frozen = frozen);
+        self.update_world_to_clients(world).await;
+    }
+
+    fn world_state(&self, world: &World) -> Option<WorldTickState> {
+        self.worlds
+            .lock()
+            .unwrap()
+            .get(world.dimension.minecraft_name)
+            .copied()
+    }
+
+    fn update_world_state(&self, world: &World, update: impl FnOnce(&mut WorldTickState)) {
+        let mut worlds = self.worlds.lock().unwrap();
+        let name = world.dimension.minecraft_name;
+        let state = worlds.entry(name).or_default();
+        update(state);
+        if state.rate.is_none() && !state.frozen {
+            worlds.remove(name);
+        }
     }
 
     // Getters
@@ -194,10 +293,16 @@ impl ServerTickRateManager {
     }
 
     async fn update_state_to_clients(&self, server: &Server) {
-        server
+        for world in server.worlds.load().iter() {
+            self.update_world_to_clients(world).await;
+        }
+    }
+
+    async fn update_world_to_clients(&self, world: &World) {
+        world
             .broadcast_packet_all(&CTickingState::new(
-                self.tickrate.load(),
-                self.is_frozen.load(Ordering::Relaxed),
+                self.world_tickrate(world),
+                self.is_world_frozen(world),
             ))
             .await;
     }
@@ -209,10 +314,14 @@ impl ServerTickRateManager {
             ))
             .await;
     }
-    pub async fn update_joining_player(&self, player: &Player) {
+    /// Sends the ticking state of `world` to `player`, who just joined it.
+    pub async fn update_joining_player(&self, player: &Player, world: &World) {
         player
             .client
-            .send_packet_now(&CTickingState::new(self.tickrate(), self.is_frozen()))
+            .send_packet_now(&CTickingState::new(
+                self.world_tickrate(world),
+                self.is_world_frozen(world),
+            ))
             .await;
         player
             .client
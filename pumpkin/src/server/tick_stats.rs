@@ -0,0 +1,177 @@
+//! Rolling tick time statistics over the last second, five seconds and minute, for `/mspt`,
+//! the tick time charts of the debug screen and plugins watching server health.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long tick times are kept, the longest [`TickWindow`].
+const RETENTION: Duration = Duration::from_secs(60);
+/// Bounds the memory of sprints, which tick as fast as they can.
+const MAX_SAMPLES: usize = 100_000;
+
+/// A span of time up to now that tick times are summarized over.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TickWindow {
+    OneSecond,
+    FiveSeconds,
+    OneMinute,
+}
+
+impl TickWindow {
+    pub const ALL: [Self; 3] = [Self::OneSecond, Self::FiveSeconds, Self::OneMinute];
+
+    #[must_use]
+    pub const fn duration(self) -> Duration {
+        match self {
+            Self::OneSecond => Duration::from_secs(1),
+            Self::FiveSeconds => Duration::from_secs(5),
+            Self::OneMinute => RETENTION,
+        }
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::OneSecond => "1s",
+            Self::FiveSeconds => "5s",
+            Self::OneMinute => "1m",
+        }
+    }
+}
+
+/// The tick times of a [`TickWindow`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TickTimeSummary {
+    /// The number of ticks that ended in the window.
+    pub ticks: usize,
+    /// The time the window covers, shorter than its duration right after the server started.
+    pub span: Duration,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl TickTimeSummary {
+    /// Ticks per second in the window.
+    #[must_use]
+    pub fn tps(&self) -> f64 {
+        if self.span.is_zero() {
+            return 0.0;
+        }
+        self.ticks as f64 / self.span.as_secs_f64()
+    }
+}
+
+pub struct TickStats {
+    start: Instant,
+    /// When each tick ended and how long it took, oldest first.
+    samples: Mutex<VecDeque<(Instant, Duration)>>,
+}
+
+impl Default for TickStats {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl TickStats {
+    /// Records a tick that just ended.
+    pub fn record(&self, tick_time: Duration) {
+        self.record_at(Instant::now(), tick_time);
+    }
+
+    fn record_at(&self, end: Instant, tick_time: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        while samples.len() >= MAX_SAMPLES
+            || samples
+                .front()
+                .is_some_and(|(time, _)| end.duration_since(*time) > RETENTION)
+        {
+            samples.pop_front();
+        }
+        samples.push_back((end, tick_time));
+    }
+
+    /// Summarizes the ticks that ended in `window`.
+    #[must_use]
+    pub fn summary(&self, window: TickWindow) -> TickTimeSummary {
+        self.summary_at(Instant::now(), window)
+    }
+
+    fn summary_at(&self, now: Instant, window: TickWindow) -> TickTimeSummary {
+        let mut times: Vec<Duration> = {
+            let samples = self.samples.lock().unwrap();
+            samples
+                .iter()
+                .rev()
+                .take_while(|(time, _)| now.duration_since(*time) <= window.duration())
+                .map(|(_, tick_time)| *tick_time)
+                .collect()
+        };
+        let span = window.duration().min(now.duration_since(self.start));
+        if times.is_empty() {
+            return TickTimeSummary {
+                span,
+                ..TickTimeSummary::default()
+            };
+        }
+        times.sort_unstable();
+        let percentile = |p: f64| times[((times.len() - 1) as f64 * p).round() as usize];
+        TickTimeSummary {
+            ticks: times.len(),
+            span,
+            mean: times.iter().sum::<Duration>() / times.len() as u32,
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: times[times.len() - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_windows() {
+        let stats = TickStats::default();
+        let start = stats.start;
+        // Ten seconds of ticks, the last second much slower
+        for tick in 1..=200u32 {
+            let tick_time = if tick > 180 { 40 } else { 10 };
+            stats.record_at(
+                start + Duration::from_millis(u64::from(tick) * 50),
+                Duration::from_millis(tick_time),
+            );
+        }
+        let now = start + Duration::from_secs(10);
+
+        let second = stats.summary_at(now, TickWindow::OneSecond);
+        assert_eq!(second.ticks, 21);
+        assert_eq!(second.p50, Duration::from_millis(40));
+
+        let minute = stats.summary_at(now, TickWindow::OneMinute);
+        assert_eq!(minute.ticks, 200);
+        assert_eq!(minute.span, Duration::from_secs(10));
+        assert!((minute.tps() - 20.0).abs() < f64::EPSILON);
+        assert_eq!(minute.p50, Duration::from_millis(10));
+        assert_eq!(minute.p95, Duration::from_millis(40));
+        assert_eq!(minute.max, Duration::from_millis(40));
+    }
+
+    #[test]
+    fn forgets_old_ticks() {
+        let stats = TickStats::default();
+        let start = stats.start;
+        stats.record_at(start, Duration::from_millis(10));
+        stats.record_at(start + RETENTION * 2, Duration::from_millis(20));
+        assert_eq!(stats.samples.lock().unwrap().len(), 1);
+    }
+}
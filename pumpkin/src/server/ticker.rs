@@ -3,7 +3,7 @@ use std::{
     sync::{Arc, OnceLock, atomic::Ordering},
     time::{Duration, Instant},
 };
-use tokio::time::sleep;
+use tokio::time::{sleep, sleep_until};
 
 pub struct Ticker;
 
@@ -21,11 +21,14 @@ impl Ticker {
     /// IMPORTANT: Run this in a new thread/tokio task.
     pub async fn run(server: &Arc<Server>) {
         let mut last_tick = Instant::now();
+        // When the next tick is due with catch-up, which lags behind while catching up
+        let mut next_tick = Instant::now();
         while !SHOULD_STOP.load(Ordering::Relaxed) {
             let tick_start_time = Instant::now();
             let manager = &server.tick_rate_manager;
 
             manager.tick();
+            server.watchdog.begin_tick();
 
             // Now server.tick() handles both player/network ticking (always)
             // and world logic ticking (conditionally based on freeze state)
@@ -44,11 +47,13 @@ impl Ticker {
                 server.tick().await;
             }
 
+            server.watchdog.end_tick();
+
             // Record the total time this tick took
-            let tick_duration_nanos = tick_start_time.elapsed().as_nanos() as i64;
+            let tick_duration = tick_start_time.elapsed();
+            let tick_duration_nanos = tick_duration.as_nanos() as i64;
             server.update_tick_times(tick_duration_nanos).await;
 
-            // Sleep logic remains the same
             let now = Instant::now();
             let elapsed = now.duration_since(last_tick);
 
@@ -61,13 +66,35 @@ impl Ticker {
                 Duration::from_nanos(manager.nanoseconds_per_tick() as u64)
             };
 
-            if let Some(sleep_time) = tick_interval.checked_sub(elapsed)
-                && !sleep_time.is_zero()
-            {
-                sleep(sleep_time).await;
+            let catch_up = &server.advanced_config.catch_up;
+            if catch_up.enabled && !manager.is_sprinting() && !manager.is_frozen() {
+                next_tick += tick_interval;
+                let max_behind = tick_interval * catch_up.max_ticks;
+                if let Some(behind) = now.checked_duration_since(next_tick) {
+                    // Behind schedule: the next tick runs right away
+                    if let Some(excess) = behind.checked_sub(max_behind)
+                        && !excess.is_zero()
+                    {
+                        let skipped = excess.as_nanos() / tick_interval.as_nanos().max(1);
+                        log::warn!("Can't keep up! Skipping {skipped} ticks");
+                        next_tick = now.checked_sub(max_behind).unwrap_or(now);
+                    }
+                } else {
+                    sleep_until(next_tick.into()).await;
+                }
+            } else {
+                next_tick = now;
+                if let Some(sleep_time) = tick_interval.checked_sub(elapsed)
+                    && !sleep_time.is_zero()
+                {
+                    sleep(sleep_time).await;
+                }
             }
 
             last_tick = Instant::now();
+            server
+                .send_debug_tick_sample(tick_start_time.elapsed(), tick_duration)
+                .await;
         }
         log::debug!("Ticker stopped");
     }
@@ -0,0 +1,230 @@
+use std::num::NonZeroU8;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pumpkin_config::tps_mitigation::{MitigationAction, MitigationPolicy, TpsMitigationConfig};
+use pumpkin_protocol::codec::var_int::VarInt;
+use pumpkin_protocol::java::client::play::CSetChunkCacheRadius;
+
+use crate::server::Server;
+use crate::server::tick_stats::TickWindow;
+use crate::world::chunker;
+
+/// Ticks between two evaluations of the policies.
+const EVALUATE_INTERVAL: i32 = 20;
+
+/// Whether a policy applies and since when the TPS pointed the other way.
+#[derive(Default)]
+struct PolicyState {
+    active: bool,
+    /// When the TPS crossed the threshold towards toggling the policy, reset when it crosses back.
+    since: Option<Instant>,
+}
+
+impl PolicyState {
+    /// Feeds the current TPS into the policy, returning whether it toggled.
+    fn update(&mut self, policy: &MitigationPolicy, tps: f64, now: Instant) -> bool {
+        let (crossed, hold) = if self.active {
+            (tps >= policy.recover_tps, policy.recover_seconds)
+        } else {
+            (tps < policy.below_tps, policy.trigger_seconds)
+        };
+        if !crossed {
+            self.since = None;
+            return false;
+        }
+        let since = *self.since.get_or_insert(now);
+        if now.duration_since(since) < Duration::from_secs(hold) {
+            return false;
+        }
+        self.active = !self.active;
+        self.since = None;
+        true
+    }
+}
+
+/// The combined actions of all applied policies.
+#[derive(Default)]
+struct Effects {
+    view_distance: Option<NonZeroU8>,
+    max_entities: Option<u32>,
+    pause_random_ticks: bool,
+    defer_generation: bool,
+}
+
+impl Effects {
+    fn apply(&mut self, action: MitigationAction) {
+        match action {
+            MitigationAction::ReduceViewDistance { view_distance } => {
+                let view_distance = NonZeroU8::new(view_distance.max(2)).unwrap();
+                self.view_distance = Some(
+                    self.view_distance
+                        .map_or(view_distance, |current| current.min(view_distance)),
+                );
+            }
+            MitigationAction::CapEntitiesPerChunk { max_entities } => {
+                self.max_entities = Some(
+                    self.max_entities
+                        .map_or(max_entities, |current| current.min(max_entities)),
+                );
+            }
+            MitigationAction::PauseRandomTicksInEmptyWorlds => self.pause_random_ticks = true,
+            MitigationAction::DeferChunkGeneration => self.defer_generation = true,
+        }
+    }
+}
+
+/// Lowers the load of the server while its TPS stays low, and lifts it again once the TPS
+/// recovered.
+pub struct TpsMitigation {
+    config: TpsMitigationConfig,
+    policies: Mutex<Vec<PolicyState>>,
+    /// The view distance cap of the applied policies, `0` if there is none.
+    view_distance_cap: AtomicU8,
+    random_ticks_paused: AtomicBool,
+}
+
+impl TpsMitigation {
+    #[must_use]
+    pub fn new(config: TpsMitigationConfig) -> Self {
+        let policies = config
+            .policies
+            .iter()
+            .map(|_| PolicyState::default())
+            .collect();
+        Self {
+            config,
+            policies: Mutex::new(policies),
+            view_distance_cap: AtomicU8::new(0),
+            random_ticks_paused: AtomicBool::new(false),
+        }
+    }
+
+    /// The view distance players are limited to, if a policy reduces it.
+    pub fn view_distance_cap(&self) -> Option<NonZeroU8> {
+        NonZeroU8::new(self.view_distance_cap.load(Ordering::Relaxed))
+    }
+
+    /// Whether worlds without players skip their random ticks.
+    pub fn random_ticks_paused(&self) -> bool {
+        self.random_ticks_paused.load(Ordering::Relaxed)
+    }
+
+    /// Evaluates the policies against the TPS of the last seconds. Called once per tick.
+    pub async fn tick(&self, server: &Arc<Server>) {
+        if !self.config.enabled || self.config.policies.is_empty() {
+            return;
+        }
+        if server.tick_count.load(Ordering::Relaxed) % EVALUATE_INTERVAL != 0 {
+            return;
+        }
+
+        let summary = server.tick_stats.summary(TickWindow::FiveSeconds);
+        if summary.ticks == 0 {
+            return;
+        }
+        let tps = summary.tps();
+        let now = Instant::now();
+
+        let mut effects = Effects::default();
+        {
+            let mut states = self.policies.lock().unwrap();
+            for (policy, state) in self.config.policies.iter().zip(states.iter_mut()) {
+                if state.update(policy, tps, now) {
+                    if state.active {
+                        log::warn!(
+                            "TPS mitigation {} applied: TPS stayed below {:.1} for {}s (currently {tps:.1})",
+                            policy.name,
+                            policy.below_tps,
+                            policy.trigger_seconds
+                        );
+                    } else {
+                        log::info!(
+                            "TPS mitigation {} reverted: TPS stayed at {:.1} for {}s (currently {tps:.1})",
+                            policy.name,
+                            policy.recover_tps,
+                            policy.recover_seconds
+                        );
+                    }
+                }
+                if state.active {
+                    effects.apply(policy.action);
+                }
+            }
+        }
+        // Also stored while nothing toggled, to reach worlds loaded since
+        self.random_ticks_paused
+            .store(effects.pause_random_ticks, Ordering::Relaxed);
+        server
+            .chunk_limits
+            .set_mitigation_max_entities(effects.max_entities);
+        for world in server.worlds.load().iter() {
+            world
+                .level
+                .generation_deferred
+                .store(effects.defer_generation, Ordering::Relaxed);
+        }
+
+        let view_distance = effects.view_distance.map_or(0, NonZeroU8::get);
+        if self
+            .view_distance_cap
+            .swap(view_distance, Ordering::Relaxed)
+            != view_distance
+        {
+            for player in server.get_all_players() {
+                let radius = chunker::get_view_distance(&player);
+                player
+                    .client
+                    .enqueue_packet(&CSetChunkCacheRadius::new(VarInt(i32::from(radius.get()))))
+                    .await;
+                chunker::update_position(&player).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_needs_sustained_tps_to_toggle() {
+        let policy = MitigationPolicy {
+            below_tps: 15.0,
+            trigger_seconds: 10,
+            recover_tps: 19.0,
+            recover_seconds: 20,
+            ..Default::default()
+        };
+        let mut state = PolicyState::default();
+        let start = Instant::now();
+
+        assert!(!state.update(&policy, 12.0, start));
+        // A short recovery resets the trigger
+        assert!(!state.update(&policy, 16.0, start + Duration::from_secs(5)));
+        assert!(!state.update(&policy, 12.0, start + Duration::from_secs(6)));
+        assert!(!state.update(&policy, 12.0, start + Duration::from_secs(15)));
+        assert!(state.update(&policy, 12.0, start + Duration::from_secs(16)));
+        assert!(state.active);
+
+        // Between the thresholds the policy keeps applying
+        assert!(!state.update(&policy, 17.0, start + Duration::from_secs(60)));
+        assert!(!state.update(&policy, 19.5, start + Duration::from_secs(61)));
+        assert!(state.update(&policy, 20.0, start + Duration::from_secs(81)));
+        assert!(!state.active);
+    }
+
+    #[test]
+    fn effects_combine_to_the_strictest() {
+        let mut effects = Effects::default();
+        effects.apply(MitigationAction::ReduceViewDistance { view_distance: 8 });
+        effects.apply(MitigationAction::ReduceViewDistance { view_distance: 4 });
+        effects.apply(MitigationAction::CapEntitiesPerChunk { max_entities: 20 });
+        effects.apply(MitigationAction::CapEntitiesPerChunk { max_entities: 50 });
+        assert_eq!(effects.view_distance, NonZeroU8::new(4));
+        assert_eq!(effects.max_entities, Some(20));
+        assert!(!effects.pause_random_ticks);
+        assert!(!effects.defer_generation);
+    }
+}
@@ -0,0 +1,152 @@
+//! Detects ticks that hang, see [`WatchdogConfig`].
+
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use crossbeam::atomic::AtomicCell;
+use pumpkin_config::watchdog::{WatchdogAction, WatchdogConfig};
+use tokio::runtime::Handle;
+
+use crate::SHOULD_STOP;
+use crate::server::{Server, restart};
+
+/// How often the watchdog thread looks at the tick.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Tracks the tick in progress for the watchdog thread.
+#[derive(Default)]
+pub struct Watchdog {
+    tick_start: AtomicCell<Option<Instant>>,
+}
+
+impl Watchdog {
+    pub fn begin_tick(&self) {
+        self.tick_start.store(Some(Instant::now()));
+    }
+
+    pub fn end_tick(&self) {
+        self.tick_start.store(None);
+    }
+
+    /// Starts the watchdog thread, unless it's disabled.
+    pub fn spawn(server: &Arc<Server>) {
+        let config = server.advanced_config.watchdog.clone();
+        if !config.enabled || config.max_tick_time_seconds == 0 {
+            return;
+        }
+        let server = Arc::downgrade(server);
+        let runtime = Handle::current();
+        if let Err(err) = std::thread::Builder::new()
+            .name("Server Watchdog".to_string())
+            .spawn(move || watch(&server, &runtime, &config))
+        {
+            log::error!("Failed to start the watchdog: {err}");
+        }
+    }
+}
+
+fn watch(server: &Weak<Server>, runtime: &Handle, config: &WatchdogConfig) {
+    let max_tick_time = Duration::from_secs(config.max_tick_time_seconds);
+    // The start of the last tick reported, so that a hung tick is only reported once
+    let mut reported = None;
+    while !SHOULD_STOP.load(Ordering::Relaxed) {
+        std::thread::sleep(CHECK_INTERVAL);
+        let Some(server) = server.upgrade() else {
+            return;
+        };
+        let Some(tick_start) = server.watchdog.tick_start.load() else {
+            continue;
+        };
+        let elapsed = tick_start.elapsed();
+        if elapsed < max_tick_time || reported == Some(tick_start) {
+            continue;
+        }
+        reported = Some(tick_start);
+        report(&server, runtime, elapsed);
+
+        if config.action == WatchdogAction::LogOnly {
+            continue;
+        }
+        if config.save_before_stopping {
+            log::error!("Trying to save before stopping");
+            let timeout = Duration::from_secs(config.save_timeout_seconds);
+            if runtime
+                .block_on(tokio::time::timeout(timeout, server.save_all(true)))
+                .is_err()
+            {
+                log::error!("Saving did not finish within {timeout:?}");
+            }
+        }
+        if config.action == WatchdogAction::Restart {
+            log::error!("Restarting the server");
+            restart::restart_now(&server.advanced_config.restart);
+        }
+        log::error!("Stopping the server");
+        std::process::exit(1);
+    }
+}
+
+fn report(server: &Server, runtime: &Handle, elapsed: Duration) {
+    log::error!("---- The server tick hangs ----");
+    log::error!(
+        "A single server tick took {:.2} seconds (should be max {:.2})",
+        elapsed.as_secs_f64(),
+        1.0 / f64::from(server.tick_rate_manager.tickrate())
+    );
+    log::error!("Latest time spent in each part of the tick:");
+    for (section, duration) in server.tick_profiler.last_sections() {
+        log::error!(
+            "  {}: {:.2}ms",
+            section.name(),
+            duration.as_secs_f64() * 1000.0
+        );
+    }
+    let metrics = runtime.metrics();
+    log::error!(
+        "Async runtime: {} workers, {} tasks alive",
+        metrics.num_workers(),
+        metrics.num_alive_tasks()
+    );
+    log::error!("Threads:");
+    for thread in thread_dump() {
+        log::error!("  {thread}");
+    }
+}
+
+/// Lists the threads of the process, with their state and what the kernel has them wait for.
+#[cfg(target_os = "linux")]
+fn thread_dump() -> Vec<String> {
+    let Ok(tasks) = std::fs::read_dir("/proc/self/task") else {
+        return vec!["Threads are unavailable".to_string()];
+    };
+    tasks
+        .flatten()
+        .map(|task| {
+            let path = task.path();
+            let read = |file: &str| std::fs::read_to_string(path.join(file)).unwrap_or_default();
+            let stat = read("stat");
+            // The state follows the name, which is in parentheses and may contain spaces
+            let state = stat
+                .rsplit_once(") ")
+                .and_then(|(_, rest)| rest.split(' ').next())
+                .unwrap_or("?");
+            let waiting_in = read("wchan");
+            format!(
+                "{} \"{}\" state {state}, waiting in {}",
+                task.file_name().to_string_lossy(),
+                read("comm").trim(),
+                if waiting_in.is_empty() || waiting_in == "0" {
+                    "-"
+                } else {
+                    &waiting_in
+                }
+            )
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_dump() -> Vec<String> {
+    vec!["Thread dumps are only available on Linux".to_string()]
+}
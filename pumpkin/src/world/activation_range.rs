@@ -0,0 +1,202 @@
+//! Spigot-style activation ranges: mobs far away from every player skip most of their ticks, see
+//! [`ActivationRangeConfig`].
+//!
+//! [`ActivationRangeConfig`]: pumpkin_config::activation_range::ActivationRangeConfig
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering::Relaxed;
+
+use pumpkin_config::activation_range::ActivationRangeConfig;
+use pumpkin_data::entity::MobCategory;
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::entity::EntityBase;
+use crate::entity::player::Player;
+
+/// The horizontal range around players in which mobs of `category` are active, in blocks.
+fn range(config: &ActivationRangeConfig, category: &MobCategory) -> u32 {
+    if *category == MobCategory::MONSTER {
+        config.monster_range
+    } else if *category == MobCategory::CREATURE {
+        config.creature_range
+    } else if *category == MobCategory::AMBIENT {
+        config.ambient_range
+    } else if *category == MobCategory::MISC {
+        config.misc_range
+    } else {
+        config.water_range
+    }
+}
+
+/// Whether `entity` gets a full tick this tick. Entities other than mobs are always active.
+pub async fn is_active(
+    entity: &Arc<dyn EntityBase>,
+    players: &[Arc<Player>],
+    config: &ActivationRangeConfig,
+) -> bool {
+    let base = entity.get_entity();
+    if !config.enabled || !base.entity_type.mob || wakes_up(config, base.age.load(Relaxed)) {
+        return true;
+    }
+    let near_player = in_range(
+        config,
+        base.entity_type.category,
+        base.pos.load(),
+        players
+            .iter()
+            .filter(|player| !player.is_spectator())
+            .map(|player| player.living_entity.entity.pos.load()),
+    );
+    near_player || is_immune(entity).await
+}
+
+/// Whether an inactive mob of `age` gets a full tick anyway. This is staggered by the age so that
+/// inactive mobs don't all wake up in the same tick.
+const fn wakes_up(config: &ActivationRangeConfig, age: i32) -> bool {
+    let interval = if config.inactive_tick_interval == 0 {
+        1
+    } else {
+        config.inactive_tick_interval
+    };
+    age.unsigned_abs() % interval == 0
+}
+
+/// Whether a mob of `category` at `pos` is within its activation range of any of `players`.
+fn in_range(
+    config: &ActivationRangeConfig,
+    category: &MobCategory,
+    pos: Vector3<f64>,
+    mut players: impl Iterator<Item = Vector3<f64>>,
+) -> bool {
+    let range = f64::from(range(config, category));
+    players.any(|player| (player.x - pos.x).abs() <= range && (player.z - pos.z).abs() <= range)
+}
+
+/// Whether `entity` stays active however far away it is: while it is leashed, rides or is ridden,
+/// or was hurt recently.
+async fn is_immune(entity: &Arc<dyn EntityBase>) -> bool {
+    let base = entity.get_entity();
+    if base.vehicle.lock().await.is_some() || !base.passengers.lock().await.is_empty() {
+        return true;
+    }
+    if entity
+        .get_living_entity()
+        .is_some_and(|living| living.hurt_cooldown.load(Relaxed) > 0)
+    {
+        return true;
+    }
+    let Some(mob) = entity.clone().get_mob() else {
+        return false;
+    };
+    mob.get_mob_entity().leash_holder().await.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_data::entity::EntityType;
+
+    use super::*;
+    use crate::entity::Entity;
+    use crate::entity::mob::zombie::ZombieEntity;
+    use crate::world::World;
+
+    async fn new_zombie(world: &Arc<World>) -> Arc<dyn EntityBase> {
+        let entity = Entity::new(
+            world.clone(),
+            Vector3::new(0.0, 64.0, 0.0),
+            &EntityType::ZOMBIE,
+        );
+        let zombie: Arc<dyn EntityBase> = ZombieEntity::new(entity).await;
+        zombie.get_entity().age.store(1, Relaxed);
+        zombie
+    }
+
+    fn config() -> ActivationRangeConfig {
+        ActivationRangeConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mobs_are_active_near_players() {
+        let config = config();
+        let pos = Vector3::new(100.0, 64.0, 100.0);
+        let near = |players: &[Vector3<f64>], category| {
+            in_range(&config, category, pos, players.iter().copied())
+        };
+
+        assert!(!near(&[], &MobCategory::MONSTER));
+        // The range is horizontal, height doesn't matter
+        assert!(near(
+            &[Vector3::new(132.0, -60.0, 68.0)],
+            &MobCategory::MONSTER
+        ));
+        assert!(!near(
+            &[Vector3::new(132.5, 64.0, 100.0)],
+            &MobCategory::MONSTER
+        ));
+        assert!(!near(
+            &[Vector3::new(100.0, 64.0, 120.0)],
+            &MobCategory::AMBIENT
+        ));
+        assert!(near(
+            &[Vector3::new(100.0, 64.0, 120.0)],
+            &MobCategory::CREATURE
+        ));
+        assert!(near(
+            &[
+                Vector3::new(0.0, 64.0, 0.0),
+                Vector3::new(90.0, 64.0, 110.0)
+            ],
+            &MobCategory::WATER_CREATURE
+        ));
+    }
+
+    #[test]
+    fn inactive_mobs_wake_up_staggered() {
+        let mut config = config();
+        let woken: Vec<_> = (0..60).filter(|&age| wakes_up(&config, age)).collect();
+        assert_eq!(woken, [0, 20, 40]);
+        assert!(wakes_up(&config, -40));
+        assert!(!wakes_up(&config, -39));
+
+        config.inactive_tick_interval = 0;
+        assert!((0..5).all(|age| wakes_up(&config, age)));
+    }
+
+    #[tokio::test]
+    async fn hurt_riding_and_far_mobs() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let config = config();
+
+        let zombie = new_zombie(&world).await;
+        assert!(!is_immune(&zombie).await);
+        assert!(!is_active(&zombie, &[], &config).await);
+        assert!(is_active(&zombie, &[], &ActivationRangeConfig::default()).await);
+        zombie.get_entity().age.store(40, Relaxed);
+        assert!(is_active(&zombie, &[], &config).await);
+
+        let hurt = new_zombie(&world).await;
+        hurt.get_living_entity()
+            .unwrap()
+            .hurt_cooldown
+            .store(10, Relaxed);
+        assert!(is_immune(&hurt).await);
+        assert!(is_active(&hurt, &[], &config).await);
+
+        let rider = new_zombie(&world).await;
+        *rider.get_entity().vehicle.lock().await = Some(zombie.clone());
+        zombie
+            .get_entity()
+            .passengers
+            .lock()
+            .await
+            .push(rider.clone());
+        zombie.get_entity().age.store(1, Relaxed);
+        assert!(is_immune(&rider).await);
+        assert!(is_immune(&zombie).await);
+        assert!(is_active(&zombie, &[], &config).await);
+    }
+}
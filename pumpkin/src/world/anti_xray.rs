@@ -0,0 +1,275 @@
+//! Hides ores from clients that see through blocks. Chunk packets carry disguised block sections,
+//! and block changes reveal the real blocks around them, see [`AntiXrayConfig`].
+//!
+//! [`AntiXrayConfig`]: pumpkin_config::anti_xray::AntiXrayConfig
+
+use std::sync::Arc;
+
+use pumpkin_config::anti_xray::{AntiXrayMode, AntiXrayWorldSettings};
+use pumpkin_data::{Block, BlockState};
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_world::chunk::ChunkData;
+use pumpkin_world::chunk::palette::BlockPalette;
+
+/// The block sections of a chunk and of the chunks north, south, west and east of it.
+type Neighbours<'a> = [Option<&'a [Arc<BlockPalette>]>; 4];
+
+pub struct AntiXray {
+    mode: AntiXrayMode,
+    /// Per block state id, whether it is hidden.
+    hidden: Box<[bool]>,
+    /// Per block state id, whether it is disguised as well in [`AntiXrayMode::Obfuscate`].
+    replaced: Box<[bool]>,
+    /// The states shown instead in [`AntiXrayMode::Obfuscate`].
+    decoys: Box<[u16]>,
+    /// Shown in [`AntiXrayMode::Hide`] when every block next to a hidden one is hidden too.
+    fallback: u16,
+    max_block_height: i32,
+    update_radius: i32,
+}
+
+impl AntiXray {
+    #[must_use]
+    pub fn new(settings: &AntiXrayWorldSettings) -> Self {
+        let blocks = |names: &[String]| -> Vec<&'static Block> {
+            names
+                .iter()
+                .filter_map(|name| {
+                    let block = Block::from_name(name);
+                    if block.is_none() {
+                        log::warn!("Unknown anti-X-ray block {name}");
+                    }
+                    block
+                })
+                .collect()
+        };
+        let state_set = |blocks: &[&'static Block]| -> Box<[bool]> {
+            let ids = blocks
+                .iter()
+                .flat_map(|block| block.states.iter().map(|state| state.id));
+            let mut set = vec![false; ids.clone().max().map_or(0, |max| usize::from(max) + 1)];
+            for id in ids {
+                set[usize::from(id)] = true;
+            }
+            set.into_boxed_slice()
+        };
+
+        let hidden = blocks(settings.hidden_blocks);
+        let replaced = blocks(settings.replacement_blocks);
+        Self {
+            mode: settings.mode,
+            hidden: state_set(&hidden),
+            replaced: state_set(&replaced),
+            decoys: hidden.iter().map(|block| block.default_state.id).collect(),
+            fallback: replaced
+                .first()
+                .map_or(Block::STONE.default_state.id, |block| {
+                    block.default_state.id
+                }),
+            max_block_height: settings.max_block_height,
+            update_radius: i32::from(settings.update_radius),
+        }
+    }
+
+    #[must_use]
+    pub const fn update_radius(&self) -> i32 {
+        self.update_radius
+    }
+
+    #[must_use]
+    pub const fn max_block_height(&self) -> i32 {
+        self.max_block_height
+    }
+
+    /// Whether clients may be sent something else than `state_id`.
+    #[must_use]
+    pub fn is_disguised(&self, state_id: u16) -> bool {
+        let contains = |set: &[bool]| set.get(usize::from(state_id)).copied().unwrap_or(false);
+        contains(&self.hidden) || (self.mode == AntiXrayMode::Obfuscate && contains(&self.replaced))
+    }
+
+    /// Whether the blocks next to `state_id` can't be seen through it.
+    #[must_use]
+    pub fn occludes(state_id: u16) -> bool {
+        let state = BlockState::from_id(state_id);
+        state.is_full_cube() && state.opacity >= 15
+    }
+
+    /// The block sections of `chunk` as clients get them, or `None` if nothing is disguised.
+    /// `loaded_chunk` looks up the chunks around it. Blocks next to chunks that aren't loaded
+    /// count as seen.
+    pub fn disguise(
+        &self,
+        chunk: &ChunkData,
+        loaded_chunk: impl Fn(Vector2<i32>) -> Option<Arc<ChunkData>>,
+    ) -> Option<Box<[Arc<BlockPalette>]>> {
+        let neighbours = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .map(|(x, z)| loaded_chunk(Vector2::new(chunk.x + x, chunk.z + z)));
+        let neighbour_sections = neighbours.each_ref().map(|neighbour| {
+            neighbour
+                .as_ref()
+                .map(|neighbour| neighbour.section.block_sections.read().unwrap())
+        });
+        let sections = chunk.section.block_sections.read().unwrap();
+        self.disguise_sections(
+            Vector2::new(chunk.x, chunk.z),
+            chunk.section.min_y,
+            &sections,
+            neighbour_sections
+                .each_ref()
+                .map(|sections| sections.as_deref().map(|sections| &**sections)),
+        )
+    }
+
+    fn disguise_sections(
+        &self,
+        chunk: Vector2<i32>,
+        min_y: i32,
+        sections: &[Arc<BlockPalette>],
+        neighbours: Neighbours<'_>,
+    ) -> Option<Box<[Arc<BlockPalette>]>> {
+        let height = (self.max_block_height - min_y).clamp(0, sections.len() as i32 * 16);
+        let state_at = |x: i32, y: i32, z: i32| -> Option<u16> {
+            if !(0..sections.len() as i32 * 16).contains(&y) {
+                return None;
+            }
+            let (column, x, z) = match (x, z) {
+                (0..16, -1) => (neighbours[0]?, x, 15),
+                (0..16, 16) => (neighbours[1]?, x, 0),
+                (-1, 0..16) => (neighbours[2]?, 15, z),
+                (16, 0..16) => (neighbours[3]?, 0, z),
+                _ => (sections, x, z),
+            };
+            let section = column.get((y / 16) as usize)?;
+            Some(section.get(x as usize, (y % 16) as usize, z as usize))
+        };
+        let neighbour_states = |x: i32, y: i32, z: i32| {
+            [
+                (x, y - 1, z),
+                (x, y + 1, z),
+                (x, y, z - 1),
+                (x, y, z + 1),
+                (x - 1, y, z),
+                (x + 1, y, z),
+            ]
+            .map(|(x, y, z)| state_at(x, y, z))
+        };
+
+        let mut disguised: Option<Vec<Arc<BlockPalette>>> = None;
+        for (index, section) in sections
+            .iter()
+            .enumerate()
+            .take((height as usize).div_ceil(16))
+        {
+            if !section.contains_any(|state_id| self.is_disguised(state_id)) {
+                continue;
+            }
+            let mut changed: Option<BlockPalette> = None;
+            for y in 0..16 {
+                let column_y = index as i32 * 16 + y;
+                if column_y >= height {
+                    break;
+                }
+                for z in 0..16 {
+                    for x in 0..16 {
+                        let state_id = section.get(x as usize, y as usize, z as usize);
+                        if !self.is_disguised(state_id) {
+                            continue;
+                        }
+                        let neighbours = neighbour_states(x, column_y, z);
+                        if !neighbours
+                            .iter()
+                            .all(|state| state.is_some_and(Self::occludes))
+                        {
+                            continue;
+                        }
+                        let shown = match self.mode {
+                            AntiXrayMode::Hide => neighbours
+                                .into_iter()
+                                .flatten()
+                                .find(|state| !self.is_disguised(*state))
+                                .unwrap_or(self.fallback),
+                            AntiXrayMode::Obfuscate => {
+                                self.decoy(chunk.x * 16 + x, min_y + column_y, chunk.y * 16 + z)
+                            }
+                        };
+                        if shown != state_id {
+                            changed
+                                .get_or_insert_with(|| (**section).clone())
+                                .set(x as usize, y as usize, z as usize, shown);
+                        }
+                    }
+                }
+            }
+            if let Some(changed) = changed {
+                disguised.get_or_insert_with(|| sections.to_vec())[index] = Arc::new(changed);
+            }
+        }
+        disguised.map(Vec::into_boxed_slice)
+    }
+
+    /// A hidden block picked by position, so that encoding a chunk again shows the same blocks.
+    fn decoy(&self, x: i32, y: i32, z: i32) -> u16 {
+        if self.decoys.is_empty() {
+            return self.fallback;
+        }
+        let hash = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+            ^ (z as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+        self.decoys[((hash >> 32) % self.decoys.len() as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_config::anti_xray::AntiXrayConfig;
+
+    use super::*;
+
+    #[test]
+    fn only_enclosed_ores_are_hidden() {
+        let mut config = AntiXrayConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let anti_xray = AntiXray::new(&config.for_world("overworld").unwrap());
+        let stone = Block::STONE.default_state.id;
+        let diamond = Block::DIAMOND_ORE.default_state.id;
+
+        let mut section = BlockPalette::Homogeneous(stone);
+        section.set(5, 5, 5, diamond);
+        section.set(8, 5, 5, diamond);
+        section.set(9, 5, 5, Block::AIR.default_state.id);
+        // At the edge of the chunk, next to a chunk that isn't loaded
+        section.set(0, 5, 5, diamond);
+        let sections = [Arc::new(section)];
+
+        let disguised = anti_xray
+            .disguise_sections(Vector2::new(0, 0), 0, &sections, [None; 4])
+            .unwrap();
+        assert_eq!(disguised[0].get(5, 5, 5), stone);
+        assert_eq!(disguised[0].get(8, 5, 5), diamond);
+        assert_eq!(disguised[0].get(0, 5, 5), diamond);
+
+        // The loaded chunk to the west hides it
+        let west = [Arc::new(BlockPalette::Homogeneous(stone))];
+        let disguised = anti_xray
+            .disguise_sections(
+                Vector2::new(0, 0),
+                0,
+                &sections,
+                [None, None, Some(&west), None],
+            )
+            .unwrap();
+        assert_eq!(disguised[0].get(0, 5, 5), stone);
+
+        // Nothing is disguised above the height limit
+        config.max_block_height = 5;
+        let anti_xray = AntiXray::new(&config.for_world("overworld").unwrap());
+        assert!(
+            anti_xray
+                .disguise_sections(Vector2::new(0, 0), 0, &sections, [None; 4])
+                .is_none()
+        );
+    }
+}
@@ -3,16 +3,28 @@ use pumpkin_protocol::java::client::play::{
     CSetBorderWarningDelay, CSetBorderWarningDistance,
 };
 
-use crate::net::java::JavaClient;
+use pumpkin_world::world_info::LevelData;
 
 use super::World;
 
+/// Milliseconds a moving border moves along per tick.
+const TICK_MILLIS: i64 = 50;
+/// The largest diameter of a border, as in vanilla.
+pub const MAX_DIAMETER: f64 = 5.999_996_8E7;
+/// How far from `0 0` the center of a border may be.
+pub const MAX_CENTER: f64 = 2.999_998_4E7;
+
 pub struct Worldborder {
     pub center_x: f64,
     pub center_z: f64,
+    /// The diameter the border is moving from.
     pub old_diameter: f64,
+    /// The diameter the border is moving to, which is its diameter while it doesn't move.
     pub new_diameter: f64,
+    /// How long the current move of the border takes in total, in milliseconds.
     pub speed: i64,
+    /// How far the border got in its current move, in milliseconds.
+    elapsed: i64,
     pub portal_teleport_boundary: i32,
     pub warning_blocks: i32,
     pub warning_time: i32,
@@ -36,27 +48,89 @@ impl Worldborder {
             old_diameter: diameter,
             new_diameter: diameter,
             speed,
+            elapsed: 0,
             portal_teleport_boundary: 29_999_984,
             warning_blocks,
             warning_time,
-            damage_per_block: 0.0,
-            buffer: 0.0,
+            damage_per_block: 0.2,
+            buffer: 5.0,
         }
     }
 
-    pub async fn init_client(&self, client: &JavaClient) {
-        client
-            .enqueue_packet(&CInitializeWorldBorder::new(
-                self.center_x,
-                self.center_z,
-                self.old_diameter,
-                self.new_diameter,
-                self.speed.into(),
-                self.portal_teleport_boundary.into(),
-                self.warning_blocks.into(),
-                self.warning_time.into(),
-            ))
-            .await;
+    /// The border as saved in `level.dat`.
+    #[must_use]
+    pub fn from_level_data(data: &LevelData) -> Self {
+        Self {
+            center_x: data.border_center_x,
+            center_z: data.border_center_z,
+            old_diameter: data.border_size,
+            new_diameter: data.border_size_lerp_target,
+            speed: data.border_size_lerp_time.max(0),
+            elapsed: 0,
+            portal_teleport_boundary: 29_999_984,
+            warning_blocks: data.border_warning_blocks as i32,
+            warning_time: data.border_warning_time as i32,
+            damage_per_block: data.border_damage_per_block as f32,
+            buffer: data.border_safe_zone as f32,
+        }
+    }
+
+    /// Saves the border into the fields of `level.dat`.
+    pub fn write_level_data(&self, data: &mut LevelData) {
+        data.border_center_x = self.center_x;
+        data.border_center_z = self.center_z;
+        data.border_size = self.diameter();
+        data.border_size_lerp_target = self.new_diameter;
+        data.border_size_lerp_time = self.remaining();
+        data.border_warning_blocks = f64::from(self.warning_blocks);
+        data.border_warning_time = f64::from(self.warning_time);
+        data.border_damage_per_block = f64::from(self.damage_per_block);
+        data.border_safe_zone = f64::from(self.buffer);
+    }
+
+    /// The current diameter, between the old and the new one while the border moves.
+    #[must_use]
+    pub fn diameter(&self) -> f64 {
+        if self.remaining() <= 0 {
+            return self.new_diameter;
+        }
+        let progress = self.elapsed as f64 / self.speed as f64;
+        (self.new_diameter - self.old_diameter).mul_add(progress, self.old_diameter)
+    }
+
+    /// Milliseconds until the border stops moving.
+    #[must_use]
+    pub const fn remaining(&self) -> i64 {
+        self.speed - self.elapsed
+    }
+
+    /// Moves the border along, once per world tick. The clients move it on their own.
+    pub const fn tick(&mut self) {
+        if self.remaining() <= 0 {
+            return;
+        }
+        self.elapsed += TICK_MILLIS;
+        if self.remaining() <= 0 {
+            self.old_diameter = self.new_diameter;
+            self.speed = 0;
+            self.elapsed = 0;
+        }
+    }
+
+    /// The packet that sends the whole border to a joining client. Built under the lock and
+    /// sent after releasing it.
+    #[must_use]
+    pub fn init_packet(&self) -> CInitializeWorldBorder {
+        CInitializeWorldBorder::new(
+            self.center_x,
+            self.center_z,
+            self.diameter(),
+            self.new_diameter,
+            self.remaining().max(0).into(),
+            self.portal_teleport_boundary.into(),
+            self.warning_blocks.into(),
+            self.warning_time.into(),
+        )
     }
 
     pub async fn set_center(&mut self, world: &World, x: f64, z: f64) {
@@ -68,12 +142,15 @@ impl Worldborder {
             .await;
     }
 
+    /// Sets the diameter right away, or moves the border there over `speed` milliseconds.
     pub async fn set_diameter(&mut self, world: &World, diameter: f64, speed: Option<i64>) {
-        self.old_diameter = self.new_diameter;
+        self.old_diameter = self.diameter();
         self.new_diameter = diameter;
+        self.elapsed = 0;
 
-        match speed {
+        match speed.filter(|speed| *speed > 0) {
             Some(speed) => {
+                self.speed = speed;
                 world
                     .broadcast_packet_all(&CSetBorderLerpSize::new(
                         self.old_diameter,
@@ -83,6 +160,8 @@ impl Worldborder {
                     .await;
             }
             None => {
+                self.old_diameter = diameter;
+                self.speed = 0;
                 world
                     .broadcast_packet_all(&CSetBorderSize::new(self.new_diameter))
                     .await;
@@ -91,7 +170,7 @@ impl Worldborder {
     }
 
     pub async fn add_diameter(&mut self, world: &World, offset: f64, speed: Option<i64>) {
-        self.set_diameter(world, self.new_diameter + offset, speed)
+        self.set_diameter(world, self.diameter() + offset, speed)
             .await;
     }
 
@@ -111,13 +190,21 @@ impl Worldborder {
             .await;
     }
 
+    /// The smallest and largest X and Z inside the border, as `(min_x, min_z, max_x, max_z)`.
+    #[must_use]
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let half = self.diameter() / 2.0;
+        (
+            self.center_x - half,
+            self.center_z - half,
+            self.center_x + half,
+            self.center_z + half,
+        )
+    }
+
     #[must_use]
     pub fn contains(&self, x: f64, z: f64) -> bool {
-        let half = self.new_diameter / 2.0;
-        let min_x = self.center_x - half;
-        let max_x = self.center_x + half;
-        let min_z = self.center_z - half;
-        let max_z = self.center_z + half;
+        let (min_x, min_z, max_x, max_z) = self.bounds();
         x >= min_x && x < max_x && z >= min_z && z < max_z
     }
 
@@ -129,11 +216,77 @@ impl Worldborder {
 
     #[must_use]
     pub fn clamp_block(&self, x: i32, z: i32) -> (i32, i32) {
-        let half = self.new_diameter / 2.0;
-        let min_x = (self.center_x - half).floor() as i32;
-        let max_x = (self.center_x + half).floor() as i32 - 1;
-        let min_z = (self.center_z - half).floor() as i32;
-        let max_z = (self.center_z + half).floor() as i32 - 1;
-        (x.clamp(min_x, max_x), z.clamp(min_z, max_z))
+        let (min_x, min_z, max_x, max_z) = self.bounds();
+        (
+            x.clamp(min_x.floor() as i32, max_x.floor() as i32 - 1),
+            z.clamp(min_z.floor() as i32, max_z.floor() as i32 - 1),
+        )
+    }
+
+    /// The closest position to `x z` inside the border.
+    #[must_use]
+    pub fn clamp(&self, x: f64, z: f64) -> (f64, f64) {
+        const MARGIN: f64 = 1.0E-3;
+        let (min_x, min_z, max_x, max_z) = self.bounds();
+        (
+            x.clamp(min_x, (max_x - MARGIN).max(min_x)),
+            z.clamp(min_z, (max_z - MARGIN).max(min_z)),
+        )
+    }
+
+    /// How far `x z` is inside the border, negative outside of it.
+    #[must_use]
+    pub fn distance_inside(&self, x: f64, z: f64) -> f64 {
+        let (min_x, min_z, max_x, max_z) = self.bounds();
+        (x - min_x).min(max_x - x).min(z - min_z).min(max_z - z)
+    }
+
+    /// The damage an entity at `x z` takes, if it is further outside than the buffer.
+    #[must_use]
+    pub fn damage_at(&self, x: f64, z: f64) -> Option<f32> {
+        let distance = self.distance_inside(x, z) + f64::from(self.buffer);
+        if distance >= 0.0 || self.damage_per_block <= 0.0 {
+            return None;
+        }
+        Some(
+            (-distance * f64::from(self.damage_per_block))
+                .floor()
+                .max(1.0) as f32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_border_interpolates_per_tick() {
+        let mut border = Worldborder::new(0.0, 0.0, 100.0, 0, 5, 15);
+        border.old_diameter = 100.0;
+        border.new_diameter = 50.0;
+        border.speed = 1000;
+        assert!((border.diameter() - 100.0).abs() < f64::EPSILON);
+        for _ in 0..10 {
+            border.tick();
+        }
+        assert!((border.diameter() - 75.0).abs() < f64::EPSILON);
+        assert_eq!(border.remaining(), 500);
+        for _ in 0..10 {
+            border.tick();
+        }
+        assert!((border.diameter() - 50.0).abs() < f64::EPSILON);
+        assert_eq!(border.remaining(), 0);
+    }
+
+    #[test]
+    fn damage_starts_past_the_buffer() {
+        let border = Worldborder::new(0.0, 0.0, 20.0, 0, 5, 15);
+        assert!(border.contains(9.5, 0.0));
+        assert!(!border.contains(10.0, 0.0));
+        assert_eq!(border.damage_at(14.0, 0.0), None);
+        assert_eq!(border.damage_at(16.0, 0.0), Some(1.0));
+        assert_eq!(border.damage_at(0.0, -40.0), Some(5.0));
+        assert_eq!(border.clamp(30.0, -3.0), (10.0 - 1.0E-3, -3.0));
     }
 }
@@ -1,6 +1,9 @@
 use crate::entity::player::Player;
+use crate::world::World;
 use pumpkin_protocol::java::client::play::{BosseventAction, CBossEvent};
+use pumpkin_util::math::vector3::Vector3;
 use pumpkin_util::text::TextComponent;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 #[derive(Clone, PartialEq, Eq)]
@@ -57,6 +60,75 @@ impl Bossbar {
     }
 }
 
+/// The bossbar of a boss, like the wither, shown to the players close to it.
+pub struct EntityBossbar {
+    bossbar: Mutex<Bossbar>,
+    viewers: Mutex<Vec<Uuid>>,
+}
+
+impl EntityBossbar {
+    #[must_use]
+    pub fn new(bossbar: Bossbar) -> Self {
+        Self {
+            bossbar: Mutex::new(bossbar),
+            viewers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Shows the bossbar to the players within `range` of `center`, and hides it from the
+    /// players that left it.
+    pub async fn update_viewers(&self, world: &World, center: Vector3<f64>, range: f64) {
+        let bossbar = self.bossbar.lock().await.clone();
+        let mut viewers = self.viewers.lock().await;
+        let mut in_range = Vec::new();
+        for player in world.players.load().iter() {
+            if player.position().squared_distance_to_vec(&center) > range * range {
+                continue;
+            }
+            let uuid = player.gameprofile.id;
+            if !viewers.contains(&uuid) {
+                player.send_bossbar(&bossbar).await;
+            }
+            in_range.push(uuid);
+        }
+        for uuid in viewers.iter().filter(|uuid| !in_range.contains(uuid)) {
+            if let Some(player) = world.get_player_by_uuid(*uuid) {
+                player.remove_bossbar(bossbar.uuid).await;
+            }
+        }
+        *viewers = in_range;
+    }
+
+    /// Sets how full the bossbar is, between 0 and 1.
+    pub async fn set_health(&self, world: &World, health: f32) {
+        let uuid = {
+            let mut bossbar = self.bossbar.lock().await;
+            if bossbar.health == health {
+                return;
+            }
+            bossbar.health = health;
+            bossbar.uuid
+        };
+        let viewers = self.viewers.lock().await;
+        for viewer in viewers.iter() {
+            if let Some(player) = world.get_player_by_uuid(*viewer) {
+                player.update_bossbar_health(&uuid, health).await;
+            }
+        }
+    }
+
+    /// Hides the bossbar from everyone, for example when the boss dies.
+    pub async fn clear(&self, world: &World) {
+        let uuid = self.bossbar.lock().await.uuid;
+        let mut viewers = self.viewers.lock().await;
+        for viewer in viewers.drain(..) {
+            if let Some(player) = world.get_player_by_uuid(viewer) {
+                player.remove_bossbar(uuid).await;
+            }
+        }
+    }
+}
+
 /// Extra methods for [`Player`] to send and manage the bossbar.
 impl Player {
     pub async fn send_bossbar(&self, bossbar: &Bossbar) {
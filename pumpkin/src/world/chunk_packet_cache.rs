@@ -0,0 +1,165 @@
+//! Encoded chunk data packets, shared between the players viewing a chunk so that each chunk is
+//! serialized once per change instead of once per player.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use pumpkin_protocol::java::client::play::CChunkData;
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_world::chunk::ChunkData;
+
+use crate::net::java::JavaClient;
+use crate::world::World;
+
+/// Chunks kept before the least recently used half is dropped.
+const MAX_CACHED_CHUNKS: usize = 2048;
+
+#[derive(Default)]
+struct CachedChunk {
+    /// Changes whenever the chunk does, so that encodings started before a change are dropped.
+    version: u64,
+    last_used: u64,
+    /// The encoded packet per protocol version.
+    packets: Vec<(i32, Bytes)>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    chunks: HashMap<Vector2<i32>, CachedChunk>,
+    /// Source of chunk versions and use timestamps.
+    counter: u64,
+    /// Bumped when chunks are evicted, which forgets their versions.
+    epoch: u64,
+}
+
+impl CacheState {
+    const fn tick(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    fn ticket(&self, chunk: Vector2<i32>) -> (u64, u64) {
+        (
+            self.epoch,
+            self.chunks.get(&chunk).map_or(0, |cached| cached.version),
+        )
+    }
+
+    /// Forgets the least recently used half of the chunks.
+    fn evict(&mut self) {
+        let mut last_used: Vec<_> = self.chunks.values().map(|c| c.last_used).collect();
+        let middle = last_used.len() / 2;
+        let (_, &mut median, _) = last_used.select_nth_unstable(middle);
+        self.chunks.retain(|_, cached| cached.last_used > median);
+        self.epoch += 1;
+    }
+}
+
+#[derive(Default)]
+pub struct ChunkPacketCache {
+    state: Mutex<CacheState>,
+}
+
+impl ChunkPacketCache {
+    /// Drops the encodings of `chunk`, including any that is being encoded right now.
+    pub fn invalidate(&self, chunk: Vector2<i32>) {
+        let mut state = self.state.lock().unwrap();
+        let version = state.tick();
+        let cached = state.chunks.entry(chunk).or_default();
+        cached.version = version;
+        cached.packets.clear();
+    }
+
+    /// Returns the chunk data packet of `chunk` in `world`, encoded for the protocol version of
+    /// `client`. Ores are hidden as set up for the world.
+    pub fn encode(&self, client: &JavaClient, chunk: &ChunkData, world: &World) -> Bytes {
+        let protocol = client.version.load().protocol_version();
+        self.get_or_encode(Vector2::new(chunk.x, chunk.z), protocol, || {
+            let sections = world.anti_xray().and_then(|anti_xray| {
+                anti_xray.disguise(chunk, |position| {
+                    world
+                        .level
+                        .loaded_chunks
+                        .get(&position)
+                        .map(|chunk| chunk.clone())
+                })
+            });
+            let mut packet = Vec::new();
+            client
+                .write_packet(&CChunkData(chunk, sections.as_deref()), &mut packet)
+                .unwrap();
+            packet.into()
+        })
+    }
+
+    fn get_or_encode(
+        &self,
+        chunk: Vector2<i32>,
+        protocol: i32,
+        encode: impl FnOnce() -> Bytes,
+    ) -> Bytes {
+        let ticket = {
+            let mut state = self.state.lock().unwrap();
+            let now = state.tick();
+            if let Some(cached) = state.chunks.get_mut(&chunk) {
+                cached.last_used = now;
+                if let Some((_, packet)) = cached.packets.iter().find(|(p, _)| *p == protocol) {
+                    return packet.clone();
+                }
+            }
+            state.ticket(chunk)
+        };
+
+        // Encoded without holding the lock, other chunks are served in the meantime
+        let packet = encode();
+
+        let mut state = self.state.lock().unwrap();
+        if state.ticket(chunk) == ticket {
+            if !state.chunks.contains_key(&chunk) && state.chunks.len() >= MAX_CACHED_CHUNKS {
+                state.evict();
+            }
+            let now = state.tick();
+            let cached = state.chunks.entry(chunk).or_default();
+            cached.last_used = now;
+            cached.packets.push((protocol, packet.clone()));
+        }
+        packet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_during_encoding_are_not_cached() {
+        let cache = ChunkPacketCache::default();
+        let chunk = Vector2::new(3, -7);
+
+        let first = cache.get_or_encode(chunk, 774, || Bytes::from_static(b"first"));
+        let reused = cache.get_or_encode(chunk, 774, || unreachable!());
+        assert_eq!(first, reused);
+        // Other protocol versions are encoded separately
+        assert_eq!(
+            cache.get_or_encode(chunk, 773, || Bytes::from_static(b"old")),
+            Bytes::from_static(b"old")
+        );
+
+        // The chunk changes while it is encoded, the result is sent but not kept
+        cache.invalidate(chunk);
+        let racing = cache.get_or_encode(chunk, 774, || {
+            cache.invalidate(chunk);
+            Bytes::from_static(b"racing")
+        });
+        assert_eq!(racing, Bytes::from_static(b"racing"));
+        assert_eq!(
+            cache.get_or_encode(chunk, 774, || Bytes::from_static(b"second")),
+            Bytes::from_static(b"second")
+        );
+        assert_eq!(
+            cache.get_or_encode(chunk, 774, || unreachable!()),
+            Bytes::from_static(b"second")
+        );
+    }
+}
@@ -13,10 +13,17 @@ use crate::{
 
 pub fn get_view_distance(player: &Player) -> NonZeroU8 {
     let server = player.world().server.upgrade().unwrap();
-    player.config.load().view_distance.clamp(
-        NonZeroU8::new(2).unwrap(),
-        server.basic_config.view_distance,
-    )
+    let max_view_distance = server
+        .tps_mitigation
+        .view_distance_cap()
+        .map_or(server.basic_config.view_distance, |cap| {
+            cap.min(server.basic_config.view_distance)
+        });
+    player
+        .config
+        .load()
+        .view_distance
+        .clamp(NonZeroU8::new(2).unwrap(), max_view_distance)
 }
 
 pub async fn update_position(player: &Arc<Player>) {
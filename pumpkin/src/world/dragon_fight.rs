@@ -0,0 +1,498 @@
+use std::cmp::Ordering;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicI32};
+use std::sync::{Arc, Weak};
+
+use pumpkin_data::block_properties::{BlockProperties, HorizontalFacing, WallTorchLikeProperties};
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::world::WorldEvent;
+use pumpkin_data::{Block, BlockState};
+use pumpkin_util::math::boundingbox::BoundingBox;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_util::random::RandomImpl;
+use pumpkin_util::random::legacy_rand::LegacyRand;
+use pumpkin_util::text::TextComponent;
+use pumpkin_world::block::entities::end_gateway::EndGatewayBlockEntity;
+use pumpkin_world::generation::{EndSpike, end_gateway_blocks, end_spikes};
+use pumpkin_world::world::BlockFlags;
+use pumpkin_world::world_info::DragonFightData;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::entity::boss::ender_dragon::{DragonPhase, EnderDragonEntity};
+use crate::entity::decoration::end_crystal::EndCrystalEntity;
+use crate::entity::{Entity, EntityBase};
+use crate::world::World;
+use crate::world::bossbar::{Bossbar, BossbarColor, BossbarFlags, EntityBossbar};
+
+/// Players within this distance of the center of the End see the bossbar of the dragon.
+const ARENA_RANGE: f64 = 192.0;
+/// How often the fight looks for the dragon when it is out of sight, in ticks.
+const DRAGON_SEARCH_INTERVAL: i32 = 1200;
+const CRYSTAL_SCAN_INTERVAL: i32 = 100;
+const PLAYER_SCAN_INTERVAL: i32 = 20;
+/// How far from the center of the End the gateways spawn.
+const GATEWAY_DISTANCE: f64 = 96.0;
+const GATEWAY_COUNT: i32 = 20;
+
+struct FightState {
+    data: DragonFightData,
+    ticks_since_dragon_seen: i32,
+    ticks_since_crystals_scanned: i32,
+    ticks_since_player_scan: i32,
+    /// Whether players are around the main island. The fight waits for them.
+    players_nearby: bool,
+    /// The bottom of the bedrock pillar of the exit portal.
+    portal_location: Option<BlockPos>,
+}
+
+/// The fight against the ender dragon on the main island of the End.
+///
+/// It spawns the dragon and the end crystals on the pillars when players first arrive, shows the
+/// dragon's bossbar, and opens the exit portal and a new end gateway once the dragon died. Its
+/// state is saved with the level data.
+pub struct DragonFight {
+    state: Mutex<FightState>,
+    crystals_alive: AtomicI32,
+    previously_killed: AtomicBool,
+    bossbar: EntityBossbar,
+    dragon: std::sync::Mutex<Weak<EnderDragonEntity>>,
+    spikes: Vec<EndSpike>,
+}
+
+impl DragonFight {
+    /// Continues the fight saved as `data`, or starts a new one.
+    #[must_use]
+    pub fn new(data: Option<DragonFightData>, seed: u64) -> Self {
+        let data = data.unwrap_or_else(|| DragonFightData {
+            gateways: gateway_order(seed),
+            ..Default::default()
+        });
+        let portal_location = portal_location(&data);
+        let mut bossbar = Bossbar::new(TextComponent::translate(
+            "entity.minecraft.ender_dragon",
+            [],
+        ));
+        bossbar.color = BossbarColor::Pink;
+        bossbar.flags = BossbarFlags::DragonBar;
+        bossbar.health = 1.0;
+        Self {
+            crystals_alive: AtomicI32::new(0),
+            previously_killed: AtomicBool::new(data.previously_killed),
+            state: Mutex::new(FightState {
+                data,
+                ticks_since_dragon_seen: 0,
+                ticks_since_crystals_scanned: 0,
+                ticks_since_player_scan: PLAYER_SCAN_INTERVAL,
+                players_nearby: false,
+                portal_location,
+            }),
+            bossbar: EntityBossbar::new(bossbar),
+            dragon: std::sync::Mutex::new(Weak::new()),
+            spikes: end_spikes(seed),
+        }
+    }
+
+    /// The end crystals left on the pillars, each one makes the dragon land less often.
+    #[must_use]
+    pub fn crystals_alive(&self) -> i32 {
+        self.crystals_alive.load(Relaxed)
+    }
+
+    /// Whether a dragon of this fight died before.
+    #[must_use]
+    pub fn previously_killed(&self) -> bool {
+        self.previously_killed.load(Relaxed)
+    }
+
+    pub async fn tick(&self, world: &Arc<World>) {
+        let mut state = self.state.lock().await;
+        state.ticks_since_player_scan += 1;
+        if state.ticks_since_player_scan >= PLAYER_SCAN_INTERVAL {
+            state.ticks_since_player_scan = 0;
+            self.bossbar
+                .update_viewers(world, Self::center(), ARENA_RANGE)
+                .await;
+            state.players_nearby = world.players.load().iter().any(|player| {
+                player.position().squared_distance_to_vec(&Self::center())
+                    <= ARENA_RANGE * ARENA_RANGE
+            });
+        }
+        if !state.players_nearby {
+            return;
+        }
+
+        if state.data.needs_state_scanning {
+            self.scan_state(world, &mut state).await;
+            state.data.needs_state_scanning = false;
+            Self::save(world, &state.data);
+        }
+        if state.data.dragon_killed {
+            return;
+        }
+
+        state.ticks_since_dragon_seen += 1;
+        if state.data.dragon.is_none() || state.ticks_since_dragon_seen >= DRAGON_SEARCH_INTERVAL {
+            state.ticks_since_dragon_seen = 0;
+            self.find_or_create_dragon(world, &mut state).await;
+        }
+        state.ticks_since_crystals_scanned += 1;
+        if state.ticks_since_crystals_scanned >= CRYSTAL_SCAN_INTERVAL {
+            state.ticks_since_crystals_scanned = 0;
+            self.update_crystal_count(world);
+        }
+    }
+
+    /// Sets up a world that has no saved fight: builds the exit portal, puts crystals on the
+    /// pillars and adopts a dragon that is already there.
+    async fn scan_state(&self, world: &Arc<World>, state: &mut FightState) {
+        let portal_active = Self::has_active_exit_portal(world, state).await;
+        if !portal_active {
+            Self::spawn_exit_portal(world, state, false).await;
+        }
+        state.data.previously_killed = portal_active;
+        self.previously_killed.store(portal_active, Relaxed);
+
+        for spike in &self.spikes {
+            Self::spawn_crystal(world, spike).await;
+        }
+        self.update_crystal_count(world);
+
+        let dragons = Self::dragons(world);
+        match dragons.first() {
+            None => state.data.dragon_killed = true,
+            Some(dragon) if portal_active => {
+                state.data.dragon = Some(uuid_to_ints(dragon.get_entity().entity_uuid));
+            }
+            // The portal was never opened, a new dragon starts the fight
+            Some(dragon) => dragon.get_entity().remove().await,
+        }
+        if !state.data.previously_killed && state.data.dragon_killed {
+            state.data.dragon_killed = false;
+        }
+    }
+
+    async fn spawn_crystal(world: &Arc<World>, spike: &EndSpike) {
+        let pos = Vector3::new(
+            f64::from(spike.center_x) + 0.5,
+            f64::from(spike.height + 1),
+            f64::from(spike.center_z) + 0.5,
+        );
+        let area = BoundingBox {
+            min: pos.add_raw(-1.0, -1.0, -1.0),
+            max: pos.add_raw(1.0, 2.0, 1.0),
+        };
+        if world
+            .get_entities_at_box(&area)
+            .iter()
+            .any(|entity| entity.get_entity().entity_type == &EntityType::END_CRYSTAL)
+        {
+            return;
+        }
+        let entity = Entity::new(world.clone(), pos, &EntityType::END_CRYSTAL);
+        world
+            .spawn_entity(Arc::new(EndCrystalEntity::new(entity)))
+            .await;
+    }
+
+    fn dragons(world: &World) -> Vec<Arc<dyn EntityBase>> {
+        world
+            .entities
+            .load()
+            .iter()
+            .filter(|entity| entity.get_entity().entity_type == &EntityType::ENDER_DRAGON)
+            .cloned()
+            .collect()
+    }
+
+    async fn find_or_create_dragon(&self, world: &Arc<World>, state: &mut FightState) {
+        let dragons = Self::dragons(world);
+        if let Some(dragon) = dragons.first() {
+            state.data.dragon = Some(uuid_to_ints(dragon.get_entity().entity_uuid));
+            return;
+        }
+        log::debug!("No dragon found in the End, spawning a new one");
+        let entity = Entity::new(
+            world.clone(),
+            Vector3::new(0.0, 128.0, 0.0),
+            &EntityType::ENDER_DRAGON,
+        );
+        let dragon = EnderDragonEntity::new(entity);
+        dragon.set_phase(DragonPhase::HoldingPattern).await;
+        state.data.dragon = Some(uuid_to_ints(dragon.get_entity().entity_uuid));
+        *self.dragon.lock().unwrap() = Arc::downgrade(&dragon);
+        world.spawn_entity(dragon).await;
+        Self::save(world, &state.data);
+    }
+
+    fn update_crystal_count(&self, world: &World) {
+        let area = BoundingBox {
+            min: Vector3::new(-48.0, f64::from(world.min_y), -48.0),
+            max: Vector3::new(48.0, f64::from(world.min_y + 384), 48.0),
+        };
+        let crystals = world
+            .get_entities_at_box(&area)
+            .iter()
+            .filter(|entity| entity.get_entity().entity_type == &EntityType::END_CRYSTAL)
+            .count();
+        self.crystals_alive.store(crystals as i32, Relaxed);
+    }
+
+    /// Called by the dragon every tick, to show its health and to be found by the fight.
+    pub async fn update_dragon(
+        &self,
+        world: &World,
+        dragon: &Weak<EnderDragonEntity>,
+        health: f32,
+    ) {
+        let Some(dragon) = dragon.upgrade() else {
+            return;
+        };
+        let uuid = dragon.get_entity().entity_uuid;
+        {
+            let mut state = self.state.lock().await;
+            if state.data.dragon != Some(uuid_to_ints(uuid)) {
+                return;
+            }
+            state.ticks_since_dragon_seen = 0;
+        }
+        *self.dragon.lock().unwrap() = Arc::downgrade(&dragon);
+        let max_health = dragon.get_entity().entity_type.max_health.unwrap_or(200.0);
+        self.bossbar.set_health(world, health / max_health).await;
+    }
+
+    /// Called by a dying dragon once it is gone. Opens the exit portal and spawns a gateway.
+    pub async fn on_dragon_killed(&self, world: &Arc<World>, dragon: Uuid) {
+        let mut state = self.state.lock().await;
+        if state.data.dragon != Some(uuid_to_ints(dragon)) {
+            return;
+        }
+        self.bossbar.set_health(world, 0.0).await;
+        self.bossbar.clear(world).await;
+        Self::spawn_exit_portal(world, &mut state, true).await;
+        Self::spawn_gateway(world, &mut state).await;
+        if !state.data.previously_killed {
+            let height = world.get_motion_blocking_height(0, 0).await;
+            world
+                .set_block_state(
+                    &BlockPos::new(0, height, 0),
+                    Block::DRAGON_EGG.default_state.id,
+                    BlockFlags::NOTIFY_ALL,
+                )
+                .await;
+        }
+        state.data.previously_killed = true;
+        state.data.dragon_killed = true;
+        state.data.dragon = None;
+        self.previously_killed.store(true, Relaxed);
+        *self.dragon.lock().unwrap() = Weak::new();
+        Self::save(world, &state.data);
+    }
+
+    /// Called when an end crystal in the End was destroyed, by `cause` if it was a player.
+    pub async fn on_crystal_destroyed(
+        &self,
+        world: &World,
+        crystal: &Entity,
+        cause: Option<&dyn EntityBase>,
+    ) {
+        self.update_crystal_count(world);
+        let dragon = self.dragon.lock().unwrap().upgrade();
+        if let Some(dragon) = dragon {
+            dragon.on_crystal_destroyed(crystal, cause).await;
+        }
+    }
+
+    async fn spawn_gateway(world: &Arc<World>, state: &mut FightState) {
+        let Some(index) = state.data.gateways.pop() else {
+            return;
+        };
+        let angle = 2.0 * (-std::f64::consts::PI + std::f64::consts::PI / 20.0 * f64::from(index));
+        let origin = BlockPos::new(
+            (GATEWAY_DISTANCE * angle.cos()).floor() as i32,
+            75,
+            (GATEWAY_DISTANCE * angle.sin()).floor() as i32,
+        );
+        world
+            .sync_world_event(WorldEvent::EndGatewaySpawns, origin, 0)
+            .await;
+        for (pos, block_state) in end_gateway_blocks(origin) {
+            world
+                .set_block_state(&pos, block_state.id, BlockFlags::NOTIFY_ALL)
+                .await;
+        }
+        world
+            .add_block_entity(Arc::new(EndGatewayBlockEntity::new(origin, None, false)))
+            .await;
+    }
+
+    async fn has_active_exit_portal(world: &World, state: &mut FightState) -> bool {
+        let origin = match state.portal_location {
+            Some(origin) => origin,
+            None => Self::find_portal_origin(world).await,
+        };
+        if world.get_block(&origin.offset(Vector3::new(1, 0, 0))).await == &Block::END_PORTAL {
+            state.portal_location = Some(origin);
+            return true;
+        }
+        false
+    }
+
+    /// The bottom of the portal's bedrock pillar, found from the top of the island.
+    async fn find_portal_origin(world: &World) -> BlockPos {
+        let mut origin = BlockPos::new(0, world.get_motion_blocking_height(0, 0).await - 1, 0);
+        while origin.0.y > 63 && world.get_block(&origin).await == &Block::BEDROCK {
+            origin = origin.down();
+        }
+        origin.0.y = origin.0.y.max(world.min_y + 1);
+        origin
+    }
+
+    /// Builds the exit portal, with the portal itself only if `active`.
+    async fn spawn_exit_portal(world: &World, state: &mut FightState, active: bool) {
+        let origin = match state.portal_location {
+            Some(origin) => origin,
+            None => Self::find_portal_origin(world).await,
+        };
+        state.portal_location = Some(origin);
+        state.data.exit_portal_location = Some(vec![origin.0.x, origin.0.y, origin.0.z]);
+
+        let set = |pos: BlockPos, block_state: &'static BlockState| async move {
+            world
+                .set_block_state(&pos, block_state.id, BlockFlags::NOTIFY_ALL)
+                .await;
+        };
+        for pos in BlockPos::iterate(
+            origin.offset(Vector3::new(-4, -1, -4)),
+            origin.offset(Vector3::new(4, 32, 4)),
+        ) {
+            let dx = f64::from(pos.0.x - origin.0.x);
+            let dz = f64::from(pos.0.z - origin.0.z);
+            let distance = dx.hypot(dz);
+            if distance > 3.5 {
+                continue;
+            }
+            let inner = distance <= 2.5;
+            let block = match pos.0.y.cmp(&origin.0.y) {
+                Ordering::Less if inner => &Block::BEDROCK,
+                Ordering::Less => &Block::END_STONE,
+                Ordering::Greater => &Block::AIR,
+                Ordering::Equal if !inner => &Block::BEDROCK,
+                Ordering::Equal if active => &Block::END_PORTAL,
+                Ordering::Equal => &Block::AIR,
+            };
+            set(pos, block.default_state).await;
+        }
+        for y in 0..4 {
+            set(origin.up_height(y), Block::BEDROCK.default_state).await;
+        }
+        let torch = origin.up_height(2);
+        for (facing, offset) in [
+            (HorizontalFacing::North, Vector3::new(0, 0, -1)),
+            (HorizontalFacing::South, Vector3::new(0, 0, 1)),
+            (HorizontalFacing::West, Vector3::new(-1, 0, 0)),
+            (HorizontalFacing::East, Vector3::new(1, 0, 0)),
+        ] {
+            let mut props = WallTorchLikeProperties::default(&Block::WALL_TORCH);
+            props.facing = facing;
+            let torch_state = BlockState::from_id(props.to_state_id(&Block::WALL_TORCH));
+            set(torch.offset(offset), torch_state).await;
+        }
+    }
+
+    fn center() -> Vector3<f64> {
+        Vector3::new(0.0, 128.0, 0.0)
+    }
+
+    fn save(world: &World, data: &DragonFightData) {
+        world.level_info.rcu(|level_info| {
+            let mut level_info = (**level_info).clone();
+            level_info.dragon_fight = Some(data.clone());
+            level_info
+        });
+    }
+}
+
+/// The order the end gateways of a new fight spawn in, the first one last.
+fn gateway_order(seed: u64) -> Vec<i32> {
+    let mut random = LegacyRand::from_seed(seed);
+    let mut gateways: Vec<i32> = (0..GATEWAY_COUNT).collect();
+    for i in (2..=gateways.len()).rev() {
+        let j = random.next_bounded_i32(i as i32) as usize;
+        gateways.swap(i - 1, j);
+    }
+    gateways
+}
+
+fn portal_location(data: &DragonFightData) -> Option<BlockPos> {
+    data.exit_portal_location
+        .as_deref()
+        .and_then(|pos| match pos {
+            [x, y, z] => Some(BlockPos::new(*x, *y, *z)),
+            _ => None,
+        })
+}
+
+fn uuid_to_ints(uuid: Uuid) -> Vec<i32> {
+    let bits = uuid.as_u128();
+    (0..4)
+        .rev()
+        .map(|i| (bits >> (i * 32)) as u32 as i32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gateway_order_is_a_seeded_permutation() {
+        let order = gateway_order(42);
+        assert_eq!(order, gateway_order(42));
+        assert_ne!(order, gateway_order(43));
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..GATEWAY_COUNT).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn portal_location_needs_three_coordinates() {
+        let mut data = DragonFightData {
+            exit_portal_location: Some(vec![0, 64, 0]),
+            ..Default::default()
+        };
+        assert_eq!(portal_location(&data), Some(BlockPos::new(0, 64, 0)));
+        data.exit_portal_location = Some(vec![0, 64]);
+        assert_eq!(portal_location(&data), None);
+        data.exit_portal_location = None;
+        assert_eq!(portal_location(&data), None);
+    }
+
+    #[test]
+    fn uuid_is_split_most_significant_int_first() {
+        let uuid = Uuid::from_u128(0x0000_0001_0000_0002_0000_0003_ffff_ffff);
+        assert_eq!(uuid_to_ints(uuid), [1, 2, 3, -1]);
+    }
+
+    #[test]
+    fn saved_fight_is_continued() {
+        let data = DragonFightData {
+            needs_state_scanning: false,
+            dragon_killed: true,
+            previously_killed: true,
+            gateways: vec![3, 1],
+            ..Default::default()
+        };
+        let fight = DragonFight::new(Some(data), 0);
+        assert!(fight.previously_killed());
+        assert_eq!(fight.crystals_alive(), 0);
+        assert_eq!(fight.state.try_lock().unwrap().data.gateways, [3, 1]);
+
+        let fight = DragonFight::new(None, 0);
+        assert!(!fight.previously_killed());
+        let state = fight.state.try_lock().unwrap();
+        assert!(state.data.needs_state_scanning);
+        assert_eq!(state.data.gateways.len(), GATEWAY_COUNT as usize);
+    }
+}
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
-use pumpkin_data::{Block, BlockState};
-use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
+use pumpkin_data::{Block, BlockState, damage::DamageType, entity::EntityType};
+use pumpkin_util::math::{boundingbox::BoundingBox, position::BlockPos, vector3::Vector3};
 use rustc_hash::FxHashMap;
 
 use crate::{
     block::{ExplodeArgs, drop_loot},
+    entity::EntityBase,
     world::loot::LootContextParameters,
 };
 
@@ -77,10 +78,153 @@ impl Explosion {
         map
     }
 
+    /// The damage to an entity the explosion has `impact` on, from 0 far away or hidden to 1
+    /// right at the center.
+    fn damage(impact: f64, reach: f64) -> f32 {
+        (impact.mul_add(impact, impact) / 2.0 * 7.0)
+            .mul_add(reach, 1.0)
+            .floor() as f32
+    }
+
+    /// How much of `bounding_box` can be seen from `center`, between 0 and 1.
+    async fn get_exposure(
+        world: &Arc<World>,
+        center: Vector3<f64>,
+        bounding_box: &BoundingBox,
+    ) -> f64 {
+        let size = bounding_box.max.sub(&bounding_box.min);
+        let step_x = 1.0 / size.x.mul_add(2.0, 1.0);
+        let step_y = 1.0 / size.y.mul_add(2.0, 1.0);
+        let step_z = 1.0 / size.z.mul_add(2.0, 1.0);
+        let offset_x = (1.0 / step_x).floor().mul_add(-step_x, 1.0) / 2.0;
+        let offset_z = (1.0 / step_z).floor().mul_add(-step_z, 1.0) / 2.0;
+
+        let mut visible = 0;
+        let mut total = 0;
+        for x in 0..=(1.0 / step_x) as i32 {
+            for y in 0..=(1.0 / step_y) as i32 {
+                for z in 0..=(1.0 / step_z) as i32 {
+                    let sample = Vector3::new(
+                        (f64::from(x) * step_x).mul_add(size.x, bounding_box.min.x) + offset_x,
+                        (f64::from(y) * step_y).mul_add(size.y, bounding_box.min.y),
+                        (f64::from(z) * step_z).mul_add(size.z, bounding_box.min.z) + offset_z,
+                    );
+                    let blocked = world
+                        .raycast(sample, center, async |pos, world| {
+                            !world.get_block_state(pos).await.is_air()
+                        })
+                        .await;
+                    if blocked.is_none() {
+                        visible += 1;
+                    }
+                    total += 1;
+                }
+            }
+        }
+        f64::from(visible) / f64::from(total)
+    }
+
+    /// Hurts and pushes away the entities within twice the power of the explosion, the more the
+    /// closer and less hidden from it they are. `source` is what exploded and is left alone.
+    async fn damage_entities(
+        &self,
+        world: &Arc<World>,
+        source: Option<&dyn EntityBase>,
+        cause: Option<&dyn EntityBase>,
+    ) {
+        let reach = f64::from(self.power * 2.0);
+        let area = BoundingBox::new(
+            Vector3::new(
+                self.pos.x - reach - 1.0,
+                self.pos.y - reach - 1.0,
+                self.pos.z - reach - 1.0,
+            ),
+            Vector3::new(
+                self.pos.x + reach + 1.0,
+                self.pos.y + reach + 1.0,
+                self.pos.z + reach + 1.0,
+            ),
+        );
+        let mut entities = world.get_entities_at_box(&area);
+        entities.extend(
+            world
+                .get_players_at_box(&area)
+                .into_iter()
+                .map(|player| player as Arc<dyn EntityBase>),
+        );
+        let damage_type = if source.is_some() && cause.is_some() {
+            DamageType::PLAYER_EXPLOSION
+        } else {
+            DamageType::EXPLOSION
+        };
+
+        for target in entities {
+            let entity = target.get_entity();
+            if source.is_some_and(|source| source.get_entity().entity_id == entity.entity_id)
+                || target.is_spectator()
+            {
+                continue;
+            }
+            // Only these handle damage themselves
+            if target.get_living_entity().is_none()
+                && !target.can_hit()
+                && entity.entity_type != &EntityType::ITEM
+            {
+                continue;
+            }
+
+            let pos = entity.pos.load();
+            let distance = pos.squared_distance_to_vec(&self.pos).sqrt() / reach;
+            if distance > 1.0 {
+                continue;
+            }
+            let target_y = if entity.entity_type == &EntityType::TNT {
+                pos.y
+            } else {
+                entity.get_eye_y()
+            };
+            let direction = Vector3::new(pos.x, target_y, pos.z).sub(&self.pos);
+            if direction.length() == 0.0 {
+                continue;
+            }
+            let direction = direction.normalize();
+
+            let exposure = Self::get_exposure(world, self.pos, &entity.bounding_box.load()).await;
+            let impact = (1.0 - distance) * exposure;
+            target
+                .damage_with_context(
+                    target.as_ref(),
+                    Self::damage(impact, reach),
+                    damage_type,
+                    Some(self.pos),
+                    source,
+                    cause,
+                )
+                .await;
+
+            if let Some(player) = target.get_player() {
+                let flying = player.abilities.lock().await.flying;
+                if flying && player.is_creative() {
+                    continue;
+                }
+            }
+            let knockback = direction.multiply(impact, impact, impact);
+            entity
+                .set_velocity(entity.velocity.load().add(&knockback))
+                .await;
+        }
+    }
+
     /// Returns the removed block count
-    pub async fn explode(&self, world: &Arc<World>) -> u32 {
+    pub async fn explode(
+        &self,
+        world: &Arc<World>,
+        source: Option<&dyn EntityBase>,
+        cause: Option<&dyn EntityBase>,
+    ) -> u32 {
+        self.damage_entities(world, source, cause).await;
         let blocks = self.get_blocks_to_destroy(world).await;
-        // TODO: Entity damage, fire
+        // TODO: fire
         for (pos, (block, state)) in &blocks {
             world.set_block_state(pos, 0, BlockFlags::NOTIFY_ALL).await;
 
@@ -107,3 +251,16 @@ impl Explosion {
         blocks.len() as u32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damage_grows_with_the_impact() {
+        // TNT reaches twice its power of 4
+        assert_eq!(Explosion::damage(1.0, 8.0), 57.0);
+        assert_eq!(Explosion::damage(0.5, 8.0), 22.0);
+        assert_eq!(Explosion::damage(0.0, 8.0), 1.0);
+    }
+}
@@ -0,0 +1,125 @@
+//! Runs the light engine of a world on its own task, so spreading light never holds up the tick.
+//!
+//! Changed blocks are queued during the tick and lit together in the next batch, along with
+//! chunks that were never lit. Only one batch runs at a time, changes made while one runs wait
+//! for the next.
+
+use std::collections::HashSet;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+use pumpkin_protocol::java::client::play::CLightUpdate;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_world::light::{ChangedSections, LightPropagator};
+
+use crate::server::Server;
+use crate::world::World;
+
+/// Never lit chunks relit per batch, each takes a few milliseconds.
+const MAX_UNLIT_CHUNKS_PER_BATCH: usize = 16;
+
+#[derive(Default)]
+pub struct WorldLighting {
+    /// Blocks whose opacity or luminance changed since the last batch started.
+    changed_blocks: Mutex<HashSet<BlockPos>>,
+    /// Held while a batch runs.
+    worker: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl WorldLighting {
+    /// Queues relighting around a block whose opacity or luminance changed.
+    pub fn queue_block(&self, pos: BlockPos) {
+        self.changed_blocks.lock().unwrap().insert(pos);
+    }
+
+    /// Starts a batch in the background with the queued blocks and some of the chunks that were
+    /// never lit, unless the last batch is still running.
+    pub fn tick(&self, world: &Arc<World>, server: &Server) {
+        let Ok(guard) = self.worker.clone().try_lock_owned() else {
+            return;
+        };
+        let blocks: Vec<_> = std::mem::take(&mut *self.changed_blocks.lock().unwrap())
+            .into_iter()
+            .collect();
+        let chunks = unlit_chunks(world);
+        if blocks.is_empty() && chunks.is_empty() {
+            return;
+        }
+        let world = world.clone();
+        server.spawn_task(async move {
+            let changed = propagate(&world, blocks, chunks).await;
+            drop(guard);
+            send_light_updates(&world, changed).await;
+        });
+    }
+
+    /// Recomputes all light of `chunks` right away, after the running batch. Returns how many of
+    /// them were loaded and relit.
+    pub async fn relight(&self, world: &Arc<World>, chunks: Vec<Vector2<i32>>) -> usize {
+        let chunks: Vec<_> = chunks
+            .into_iter()
+            .filter(|pos| world.level.loaded_chunks.contains_key(pos))
+            .collect();
+        let count = chunks.len();
+        let changed = {
+            let _guard = self.worker.lock().await;
+            propagate(world, Vec::new(), chunks).await
+        };
+        send_light_updates(world, changed).await;
+        count
+    }
+}
+
+/// Loaded chunks that were never lit and whose neighbours are all loaded, so light can spread
+/// in from them.
+fn unlit_chunks(world: &World) -> Vec<Vector2<i32>> {
+    let loaded = &world.level.loaded_chunks;
+    loaded
+        .iter()
+        .filter(|chunk| !chunk.light_correct.load(Ordering::Relaxed))
+        .map(|chunk| *chunk.key())
+        .filter(|pos| {
+            (-1..=1)
+                .all(|x| (-1..=1).all(|z| loaded.contains_key(&Vector2::new(pos.x + x, pos.y + z))))
+        })
+        .take(MAX_UNLIT_CHUNKS_PER_BATCH)
+        .collect()
+}
+
+async fn propagate(
+    world: &World,
+    blocks: Vec<BlockPos>,
+    chunks: Vec<Vector2<i32>>,
+) -> ChangedSections {
+    let level = world.level.clone();
+    let has_skylight = world.dimension.has_skylight;
+    tokio::task::spawn_blocking(move || {
+        let mut propagator = LightPropagator::new(&level.loaded_chunks, has_skylight);
+        propagator.relight_chunks(&chunks);
+        propagator.update_blocks(&blocks);
+        propagator.finish()
+    })
+    .await
+    .unwrap_or_else(|err| {
+        log::error!("Light propagation panicked: {err}");
+        ChangedSections::default()
+    })
+}
+
+/// Sends the changed light sections to the players viewing them.
+async fn send_light_updates(world: &World, changed: ChangedSections) {
+    for (pos, sections) in changed {
+        let Some(chunk) = world
+            .level
+            .loaded_chunks
+            .get(&pos)
+            .map(|chunk| chunk.clone())
+        else {
+            continue;
+        };
+        world.chunk_packet_cache.invalidate(pos);
+        let packet = CLightUpdate::new(&chunk, sections);
+        world.broadcast_to_chunk_viewers(pos, &packet).await;
+    }
+}
@@ -1,16 +1,32 @@
 use std::pin::Pin;
 use std::sync::atomic::Ordering::Relaxed;
-use std::sync::{Arc, Weak};
-use std::{collections::HashMap, sync::atomic::Ordering};
+use std::sync::{Arc, OnceLock, Weak};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::Ordering,
+};
 
+pub mod activation_range;
+pub mod anti_xray;
+pub mod chunk_packet_cache;
 pub mod chunker;
+pub mod dragon_fight;
 pub mod explosion;
+pub mod lighting;
 pub mod loot;
+pub mod nearby_players;
+pub mod particles;
 pub mod portal;
+pub mod regional_difficulty;
+pub mod regions;
 pub mod time;
 
 use crate::block::RandomTickArgs;
+use crate::world::anti_xray::AntiXray;
+use crate::world::chunk_packet_cache::ChunkPacketCache;
+use crate::world::lighting::WorldLighting;
 use crate::world::loot::LootContextParameters;
+use crate::world::particles::ParticleEffect;
 use crate::{
     block::BlockEvent, entity::experience_orb::ExperienceOrbEntity, entity::item::ItemEntity,
 };
@@ -21,7 +37,10 @@ use crate::{
         {OnNeighborUpdateArgs, OnScheduledTickArgs},
     },
     command::client_suggestions,
-    entity::{Entity, EntityBase, player::Player, r#type::from_type},
+    entity::{
+        Entity, EntityBase, boss::ender_dragon, lightning::LightningEntity,
+        passive::skeleton_horse::SkeletonHorseEntity, player::Player, r#type::from_type,
+    },
     error::PumpkinError,
     net::ClientPlatform,
     plugin::{
@@ -38,9 +57,12 @@ use crate::{
 use arc_swap::ArcSwap;
 use border::Worldborder;
 use bytes::BufMut;
-use crossbeam::queue::SegQueue;
+use dashmap::DashMap;
+use dragon_fight::DragonFight;
 use explosion::Explosion;
 use pumpkin_config::BasicConfiguration;
+use pumpkin_config::seed_privacy::HashedSeedMode;
+use pumpkin_config::spawning::{SpawningConfig, SpawningWorldSettings};
 use pumpkin_data::block_properties::is_air;
 use pumpkin_data::chunk_gen_settings::GenerationSettings;
 use pumpkin_data::data_component_impl::EquipmentSlot;
@@ -89,8 +111,8 @@ use pumpkin_protocol::{
         client::play::{
             CBlockEntityData, CEntityStatus, CGameEvent, CLogin, CMultiBlockUpdate,
             CPlayerChatMessage, CPlayerInfoUpdate, CRemoveEntities, CRemovePlayerInfo,
-            CSetSelectedSlot, CSoundEffect, CSpawnEntity, FilterType, GameEvent, InitChat,
-            PlayerAction, PlayerInfoFlags,
+            CSetPassengers, CSetSelectedSlot, CSoundEffect, CSpawnEntity, FilterType, GameEvent,
+            InitChat, PlayerAction, PlayerInfoFlags,
         },
         server::play::SChatMessage,
     },
@@ -102,8 +124,8 @@ use pumpkin_protocol::{
 use pumpkin_protocol::{
     codec::var_int::VarInt,
     java::client::play::{
-        CBlockUpdate, CChunkBatchEnd, CChunkBatchStart, CChunkData, CDisguisedChatMessage,
-        CExplosion, CRespawn, CSetBlockDestroyStage, CWorldEvent,
+        CBlockUpdate, CChunkBatchEnd, CChunkBatchStart, CDisguisedChatMessage, CExplosion,
+        CRespawn, CSetBlockDestroyStage, CWorldEvent,
     },
 };
 use pumpkin_util::resource_location::ResourceLocation;
@@ -117,6 +139,8 @@ use pumpkin_util::{
     random::{RandomImpl, get_seed, xoroshiro128::Xoroshiro},
 };
 use pumpkin_world::chunk::palette::BlockPalette;
+use pumpkin_world::cylindrical_chunk_iterator::Cylindrical;
+use pumpkin_world::dimension::generator_dimension;
 use pumpkin_world::inventory::Clearable;
 use pumpkin_world::world::{GetBlockError, WorldFuture};
 use pumpkin_world::{
@@ -124,7 +148,10 @@ use pumpkin_world::{
     chunk::io::Dirtiable, inventory::Inventory, item::ItemStack, world::SimpleWorld,
 };
 use pumpkin_world::{chunk::ChunkData, world::BlockAccessor};
-use pumpkin_world::{level::Level, tick::TickPriority};
+use pumpkin_world::{
+    level::Level,
+    tick::{OrderedTick, ScheduledTick, TickPriority},
+};
 use pumpkin_world::{world::BlockFlags, world_info::LevelData};
 use rand::seq::SliceRandom;
 use rand::{RngExt, rng};
@@ -139,6 +166,9 @@ pub mod natural_spawner;
 pub mod scoreboard;
 pub mod weather;
 
+use crate::server::chat_bridge::BridgeEvent;
+use crate::server::chunk_limits::{ChunkEntityCounts, ChunkLimitKind};
+use crate::server::tick_profiler::TickSection;
 use crate::world::natural_spawner::{SpawnState, spawn_for_chunk};
 use pumpkin_data::effect::StatusEffect;
 use pumpkin_world::chunk::ChunkHeightmapType::MotionBlocking;
@@ -170,6 +200,24 @@ impl PumpkinError for GetBlockError {
 /// - Manages the `Level` instance for handling chunk-related operations.
 /// - Stores and tracks active `Player` entities within the world.
 /// - Provides a central hub for interacting with the world's entities and environment.
+///
+/// # Locking
+///
+/// `players`, `nearby_players` and `entities` are `ArcSwap`s. Readers take a snapshot with
+/// `load` and never wait, writers copy the list and replace it with `rcu`. Lookups of an entity
+/// by id go through `entity_ids` instead, a sharded map kept next to `entities`, so packets
+/// naming an entity don't scan the whole list.
+///
+/// The remaining state is behind async mutexes. Copy what is needed out of them and release
+/// them before awaiting anything else, packet sends and chunk loads in particular. When more
+/// than one is needed at once, they are locked in this order:
+///
+/// 1. `level_time`
+/// 2. `weather`
+/// 3. `worldborder`, then `scoreboard`
+/// 4. the chunks of `level`
+///
+/// A lock of one world is never held while locking another world.
 pub struct World {
     /// Represents the World's Unique Identifier
     pub uuid: Uuid,
@@ -178,9 +226,15 @@ pub struct World {
     pub level_info: Arc<ArcSwap<LevelData>>,
     /// A map of active players within the world, keyed by their unique UUID.
     pub players: ArcSwap<Vec<Arc<Player>>>,
+    /// The players bucketed by chunk, refreshed every tick.
+    pub nearby_players: ArcSwap<nearby_players::NearbyPlayers>,
     /// A map of active entities within the world, keyed by their unique UUID.
     /// This does not include players.
     pub entities: ArcSwap<Vec<Arc<dyn EntityBase>>>,
+    /// The entities of `entities`, by entity id.
+    pub entity_ids: DashMap<i32, Arc<dyn EntityBase>>,
+    /// The entities counting against chunk caps, by chunk.
+    pub chunk_entity_counts: ChunkEntityCounts,
     /// The world's scoreboard, used for tracking scores, objectives, and display information.
     pub scoreboard: Mutex<Scoreboard>,
     /// The world's worldborder, defining the playable area and controlling its expansion or contraction.
@@ -196,13 +250,25 @@ pub struct World {
     /// Block Behaviour
     pub block_registry: Arc<BlockRegistry>,
     pub server: Weak<Server>,
-    decrease_block_light_queue: SegQueue<(BlockPos, u8)>,
-    increase_block_light_queue: SegQueue<(BlockPos, u8)>,
     synced_block_event_queue: Mutex<Vec<BlockEvent>>,
     /// A map of unsent block changes, keyed by block position.
     unsent_block_changes: Mutex<HashMap<BlockPos, u16>>,
+    /// Block entity data sent after the block changes of the tick, keyed by block position.
+    unsent_block_entity_updates: Mutex<HashMap<BlockPos, CBlockEntityData>>,
+    /// Chunk data packets shared between the players viewing a chunk.
+    pub chunk_packet_cache: ChunkPacketCache,
+    /// Spreads light around changed blocks and through new chunks.
+    pub lighting: WorldLighting,
+    /// Hides ores in the chunks sent to players, set up from the server config on first use.
+    anti_xray: OnceLock<Option<AntiXray>>,
+    /// When mobs spawn naturally, set up from the server config on first use.
+    spawning: OnceLock<SpawningWorldSettings>,
     /// POI storage for fast portal lookups
     pub portal_poi: Mutex<portal::PortalPoiStorage>,
+    /// Actions between regions, run once every region has ticked its entities.
+    synchronized_actions: Mutex<Vec<regions::SynchronizedAction>>,
+    /// The fight against the ender dragon, only in the End.
+    pub dragon_fight: Option<DragonFight>,
 }
 
 impl PartialEq for World {
@@ -223,19 +289,27 @@ impl World {
         server: Weak<Server>,
     ) -> Self {
         // TODO
-        let generation_settings = GenerationSettings::from_dimension(&dimension);
+        let generation_settings =
+            GenerationSettings::from_dimension(&generator_dimension(&dimension));
 
         // Load portal POI from disk (PoiStorage::new automatically loads from disk if files exist)
         let portal_poi = portal::PortalPoiStorage::new(&level.level_folder.root_folder);
+        // Every world starts with the border saved in level.dat, only the overworld saves it back
+        let worldborder = Worldborder::from_level_data(&level_info.load());
+        let dragon_fight = (dimension == Dimension::THE_END)
+            .then(|| DragonFight::new(level_info.load().dragon_fight.clone(), level.seed.0));
 
         Self {
             uuid: Uuid::new_v4(),
             level,
             level_info,
             players: ArcSwap::new(Arc::new(Vec::new())),
+            nearby_players: ArcSwap::default(),
             entities: ArcSwap::new(Arc::new(Vec::new())),
+            entity_ids: DashMap::new(),
+            chunk_entity_counts: ChunkEntityCounts::default(),
             scoreboard: Mutex::new(Scoreboard::default()),
-            worldborder: Mutex::new(Worldborder::new(0.0, 0.0, 5.999_996_8E7, 0, 5, 300)),
+            worldborder: Mutex::new(worldborder),
             level_time: Mutex::new(LevelTime::new()),
             dimension,
             weather: Mutex::new(Weather::new()),
@@ -244,13 +318,45 @@ impl World {
             min_y: i32::from(generation_settings.shape.min_y),
             synced_block_event_queue: Mutex::new(Vec::new()),
             unsent_block_changes: Mutex::new(HashMap::new()),
+            unsent_block_entity_updates: Mutex::new(HashMap::new()),
+            chunk_packet_cache: ChunkPacketCache::default(),
+            lighting: WorldLighting::default(),
+            anti_xray: OnceLock::new(),
+            spawning: OnceLock::new(),
             portal_poi: Mutex::new(portal_poi),
-            decrease_block_light_queue: SegQueue::new(),
-            increase_block_light_queue: SegQueue::new(),
+            synchronized_actions: Mutex::new(Vec::new()),
+            dragon_fight,
             server,
         }
     }
 
+    /// How ores are hidden in this world, or `None` if they aren't.
+    pub fn anti_xray(&self) -> Option<&AntiXray> {
+        if let Some(anti_xray) = self.anti_xray.get() {
+            return anti_xray.as_ref();
+        }
+        let server = self.server.upgrade()?;
+        self.anti_xray
+            .get_or_init(|| {
+                let config = &server.advanced_config.world.anti_xray;
+                config
+                    .for_world(self.dimension.minecraft_name)
+                    .map(|settings| AntiXray::new(&settings))
+            })
+            .as_ref()
+    }
+
+    /// The conditions under which mobs spawn naturally in this world.
+    pub fn spawning(&self) -> &SpawningWorldSettings {
+        self.spawning.get_or_init(|| {
+            let name = self.dimension.minecraft_name;
+            self.server.upgrade().map_or_else(
+                || SpawningConfig::default().for_world(name),
+                |server| server.advanced_config.world.spawning.for_world(name),
+            )
+        })
+    }
+
     pub async fn shutdown(self: &Arc<Self>) {
         for entity in self.entities.load().iter() {
             self.save_entity(entity).await;
@@ -270,10 +376,12 @@ impl World {
             }
         }
 
-        // Save portal POI to disk
-        let save_result = self.portal_poi.lock().await.save_all();
-        if let Err(e) = save_result {
-            log::error!("Failed to save portal POI: {e}");
+        // Save portal POI to disk, unless the world must not touch it
+        if !self.level.in_memory {
+            let save_result = self.portal_poi.lock().await.save_all();
+            if let Err(e) = save_result {
+                log::error!("Failed to save portal POI: {e}");
+            }
         }
 
         self.level.shutdown().await;
@@ -283,6 +391,9 @@ impl World {
         // First lets see if the entity was saved on an other chunk, and if the current chunk does not match we remove it
         // Otherwise we just update the nbt data
         let base_entity = entity.get_entity();
+        if !base_entity.entity_type.saveable {
+            return;
+        }
         let uuid = base_entity.entity_uuid;
         let current_chunk_coordinate = base_entity.block_pos.load().chunk_position();
         let mut nbt = NbtCompound::new();
@@ -343,13 +454,6 @@ impl World {
         .await;
     }
 
-    pub fn get_difficulty(&self, difficulty: Difficulty) {
-        let current_info = self.level_info.load();
-        let mut new_info = (**current_info).clone();
-        new_info.difficulty = difficulty;
-        self.level_info.store(Arc::new(new_info));
-    }
-
     pub fn set_difficulty(&self, difficulty: Difficulty) {
         let current_info = self.level_info.load();
         let mut new_info = (**current_info).clone();
@@ -502,6 +606,8 @@ impl World {
         }
     }
 
+    /// Spawns `particle` for the players close enough to see it. See [`ParticleEffect`] for
+    /// particles with data and shapes.
     pub async fn spawn_particle(
         &self,
         position: Vector3<f64>,
@@ -510,11 +616,12 @@ impl World {
         particle_count: i32,
         particle: Particle,
     ) {
-        for player in self.players.load().iter() {
-            player
-                .spawn_particle(position, offset, max_speed, particle_count, particle)
-                .await;
-        }
+        ParticleEffect::new(particle)
+            .offset(offset)
+            .speed(max_speed)
+            .count(particle_count)
+            .spawn(self, position)
+            .await;
     }
 
     pub async fn play_sound(&self, sound: Sound, category: SoundCategory, position: &Vector3<f64>) {
@@ -603,16 +710,45 @@ impl World {
             .await;
     }
 
+    /// Ticks only the players of a frozen world and sends them its changes, like
+    /// [`Server::tick_players_and_network`] does for every world while the server is frozen.
+    pub async fn tick_players(self: &Arc<Self>, server: &Server) {
+        let flush_start = tokio::time::Instant::now();
+        self.flush_block_updates().await;
+        self.flush_synced_block_events().await;
+        server
+            .tick_profiler
+            .record_section(TickSection::BlockUpdates, flush_start.elapsed());
+
+        let player_start = tokio::time::Instant::now();
+        for player in self.players.load().iter() {
+            player.tick(server).await;
+        }
+        server
+            .tick_profiler
+            .record_section(TickSection::Players, player_start.elapsed());
+    }
+
     pub async fn tick(self: &Arc<Self>, server: &Server) {
         let start = tokio::time::Instant::now();
 
         // IMPORTANT: send flush_block_updates first to prevent issues with CAcknowledgeBlockChange
         self.flush_block_updates().await;
         self.flush_synced_block_events().await;
+        let flush_elapsed = start.elapsed();
+
+        let environment_start = tokio::time::Instant::now();
         self.tick_environment().await;
+        self.worldborder.lock().await.tick();
+        if let Some(dragon_fight) = &self.dragon_fight {
+            dragon_fight.tick(self).await;
+        }
+        let environment_elapsed = environment_start.elapsed();
+        self.refresh_nearby_players();
 
         let chunk_start = tokio::time::Instant::now();
         self.tick_chunks().await;
+        self.lighting.tick(self, server);
         let chunk_elapsed = chunk_start.elapsed();
 
         let player_start = tokio::time::Instant::now();
@@ -627,28 +763,37 @@ impl World {
         let entities_to_tick = self.entities.load();
         let entity_count = entities_to_tick.len();
 
-        for entity in entities_to_tick.iter() {
-            entity.get_entity().age.fetch_add(1, Relaxed);
-            entity.tick(entity.clone(), server).await;
-
-            for player in players.iter() {
-                if player
-                    .living_entity
-                    .entity
-                    .bounding_box
-                    .load()
-                    .expand(1.0, 0.5, 1.0)
-                    .intersects(&entity.get_entity().bounding_box.load())
-                {
-                    entity.on_player_collision(player).await;
-                    break;
+        let region_ticking = &server.advanced_config.world.region_ticking;
+        match self.server.upgrade() {
+            // Entities sleep along with the server
+            _ if server.deep_sleep.is_asleep() => {}
+            Some(server) if region_ticking.enabled => {
+                self.tick_entities_in_regions(
+                    &server,
+                    &entities_to_tick,
+                    &players,
+                    region_ticking.region_size,
+                )
+                .await;
+            }
+            _ => {
+                for entity in entities_to_tick.iter() {
+                    self.tick_entity(entity, &players, server).await;
                 }
             }
         }
+        self.run_synchronized_actions().await;
         let entity_elapsed = entity_start.elapsed();
 
         //self.level.chunk_loading.lock().unwrap().send_change();
 
+        let profiler = &server.tick_profiler;
+        profiler.record_section(TickSection::BlockUpdates, flush_elapsed);
+        profiler.record_section(TickSection::Environment, environment_elapsed);
+        profiler.record_section(TickSection::Chunks, chunk_elapsed);
+        profiler.record_section(TickSection::Players, player_elapsed);
+        profiler.record_section(TickSection::Entities, entity_elapsed);
+
         let total_elapsed = start.elapsed();
         if total_elapsed.as_millis() > 50 {
             log::debug!(
@@ -663,6 +808,40 @@ impl World {
         }
     }
 
+    /// Ticks an entity and lets it collide with the first player it touches. Mobs out of their
+    /// activation range skip their AI.
+    async fn tick_entity(
+        self: &Arc<Self>,
+        entity: &Arc<dyn EntityBase>,
+        players: &[Arc<Player>],
+        server: &Server,
+    ) {
+        entity.get_entity().age.fetch_add(1, Relaxed);
+        let activation_range = &server.advanced_config.world.activation_range;
+        if activation_range::is_active(entity, players, activation_range).await {
+            entity.tick(entity.clone(), server).await;
+        } else {
+            entity.inactive_tick(entity.clone(), server).await;
+        }
+
+        // Spectators do not touch anything
+        if let Some(player) = players.iter().find(|player| {
+            !player.is_spectator()
+                && player
+                    .living_entity
+                    .entity
+                    .bounding_box
+                    .load()
+                    .expand(1.0, 0.5, 1.0)
+                    .intersects(&entity.get_entity().bounding_box.load())
+        }) {
+            entity.on_player_collision(player).await;
+        }
+    }
+
+    /// Sends the block changes of this tick to the players that can see them, batched into one
+    /// packet per chunk section. Block entity data follows, so that clients already have the
+    /// block it belongs to.
     pub async fn flush_block_updates(&self) {
         let mut block_state_updates_by_chunk_section = HashMap::new();
         for (position, block_state_id) in self.unsent_block_changes.lock().await.drain() {
@@ -672,25 +851,101 @@ impl World {
                 .or_insert(Vec::new())
                 .push((position, block_state_id));
         }
+        if let Some(anti_xray) = self.anti_xray() {
+            self.reveal_disguised_blocks(anti_xray, &mut block_state_updates_by_chunk_section)
+                .await;
+        }
+        let block_entity_updates: Vec<_> = self
+            .unsent_block_entity_updates
+            .lock()
+            .await
+            .drain()
+            .map(|(_, packet)| packet)
+            .collect();
 
-        // TODO: only send packet to players who have the chunks loaded
-        // TODO: Send light updates to update the wire directly next to a broken block
-        for chunk_section in block_state_updates_by_chunk_section.values() {
-            if chunk_section.is_empty() {
-                continue;
+        // Light can spread into the neighbouring chunks, so their packets change too
+        let mut changed_chunks = HashSet::new();
+        for section in block_state_updates_by_chunk_section.keys() {
+            for x in -1..=1 {
+                for z in -1..=1 {
+                    changed_chunks.insert(Vector2::new(section.x + x, section.z + z));
+                }
             }
-            if chunk_section.len() == 1 {
-                let (block_pos, block_state_id) = chunk_section[0];
-                self.broadcast_packet_all(&CBlockUpdate::new(
-                    block_pos,
-                    i32::from(block_state_id).into(),
-                ))
+        }
+        for chunk in changed_chunks {
+            self.chunk_packet_cache.invalidate(chunk);
+        }
+
+        // TODO: Send light updates to update the wire directly next to a broken block
+        for (section, updates) in &block_state_updates_by_chunk_section {
+            let chunk = Vector2::new(section.x, section.z);
+            if let [(block_pos, block_state_id)] = updates.as_slice() {
+                self.broadcast_to_chunk_viewers(
+                    chunk,
+                    &CBlockUpdate::new(*block_pos, i32::from(*block_state_id).into()),
+                )
                 .await;
             } else {
-                self.broadcast_packet_all(&CMultiBlockUpdate::new(chunk_section))
+                self.broadcast_to_chunk_viewers(chunk, &CMultiBlockUpdate::new(updates))
                     .await;
             }
         }
+        for packet in block_entity_updates {
+            self.broadcast_to_chunk_viewers(packet.location.chunk_position(), &packet)
+                .await;
+        }
+    }
+
+    /// Adds the real state of the disguised blocks that can be seen after the block changes in
+    /// `updates`, see [`AntiXray`].
+    async fn reveal_disguised_blocks(
+        &self,
+        anti_xray: &AntiXray,
+        updates: &mut HashMap<Vector3<i32>, Vec<(BlockPos, BlockStateId)>>,
+    ) {
+        let opened: Vec<BlockPos> = updates
+            .values()
+            .flatten()
+            .filter(|(_, block_state_id)| !AntiXray::occludes(*block_state_id))
+            .map(|(position, _)| *position)
+            .collect();
+        let radius = anti_xray.update_radius();
+        let mut checked = HashSet::new();
+        for center in opened {
+            for x in -radius..=radius {
+                for y in -radius..=radius {
+                    for z in -radius..=radius {
+                        let position = center.offset(Vector3::new(x, y, z));
+                        if position.0.y >= anti_xray.max_block_height() || !checked.insert(position)
+                        {
+                            continue;
+                        }
+                        let block_state_id = self.get_block_state_id(&position).await;
+                        if !anti_xray.is_disguised(block_state_id) {
+                            continue;
+                        }
+                        let section = updates
+                            .entry(chunk_section_from_pos(&position))
+                            .or_default();
+                        if !section.iter().any(|(changed, _)| *changed == position) {
+                            section.push((position, block_state_id));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends a packet to the players whose view distance includes `chunk`.
+    pub async fn broadcast_to_chunk_viewers<P: ClientPacket>(
+        &self,
+        chunk: Vector2<i32>,
+        packet: &P,
+    ) {
+        let players = self.players.load();
+        for player in chunk_viewers(&players, chunk, |player| player.watched_section.load()) {
+            player.client.enqueue_packet(packet).await;
+        }
     }
 
     async fn tick_environment(&self) {
@@ -711,12 +966,13 @@ impl World {
                 self.level.level_channel.notify();
             }
         }
-        if level_time.world_age % 300 == 0 {
+        if level_time.world_age % 300 == 0 && !self.level.saving_suspended.load(Relaxed) {
             self.level.should_save.store(true, Relaxed);
             self.level.level_channel.notify();
         }
 
         let mut weather = self.weather.lock().await;
+        weather.weather_cycle_enabled = advance_weather;
         weather.tick_weather(self).await;
 
         if self.should_skip_night() && level_time.is_night() {
@@ -736,9 +992,14 @@ impl World {
         }
     }
 
-    pub async fn tick_chunks(self: &Arc<Self>) {
-        let tick_data = self.level.get_tick_data().await;
-        for scheduled_tick in tick_data.block_ticks {
+    /// Runs scheduled block and fluid ticks, then random ticks, in order.
+    async fn run_block_ticks(
+        self: &Arc<Self>,
+        block_ticks: &[OrderedTick<&'static Block>],
+        fluid_ticks: &[OrderedTick<&'static Fluid>],
+        random_ticks: &[ScheduledTick<()>],
+    ) {
+        for scheduled_tick in block_ticks {
             let block = self.get_block(&scheduled_tick.position).await;
             if let Some(pumpkin_block) = self.block_registry.get_pumpkin_block(block.id) {
                 pumpkin_block
@@ -750,7 +1011,7 @@ impl World {
                     .await;
             }
         }
-        for scheduled_tick in tick_data.fluid_ticks {
+        for scheduled_tick in fluid_ticks {
             let fluid = self.get_fluid(&scheduled_tick.position).await;
             if let Some(pumpkin_fluid) = self.block_registry.get_pumpkin_fluid(fluid.id) {
                 pumpkin_fluid
@@ -759,7 +1020,7 @@ impl World {
             }
         }
 
-        for scheduled_tick in tick_data.random_ticks {
+        for scheduled_tick in random_ticks {
             let block = self.get_block(&scheduled_tick.position).await;
             if let Some(pumpkin_block) = self.block_registry.get_pumpkin_block(block.id) {
                 pumpkin_block
@@ -771,6 +1032,37 @@ impl World {
                     .await;
             }
         }
+    }
+
+    pub async fn tick_chunks(self: &Arc<Self>) {
+        let mut tick_data = self.level.get_tick_data().await;
+        let server = self.server.upgrade();
+        if server
+            .as_ref()
+            .is_some_and(|server| server.tps_mitigation.random_ticks_paused())
+            && self.players.load().is_empty()
+        {
+            tick_data.random_ticks.clear();
+        }
+        let region_ticking = server
+            .map(|server| server.advanced_config.world.region_ticking.clone())
+            .unwrap_or_default();
+        if region_ticking.enabled && region_ticking.blocks {
+            self.tick_blocks_in_regions(
+                tick_data.block_ticks,
+                tick_data.fluid_ticks,
+                tick_data.random_ticks,
+                region_ticking.region_size,
+            )
+            .await;
+        } else {
+            self.run_block_ticks(
+                &tick_data.block_ticks,
+                &tick_data.fluid_ticks,
+                &tick_data.random_ticks,
+            )
+            .await;
+        }
 
         let mut spawning_chunks_map = HashMap::new();
         // TODO use FixedPlayerDistanceChunkTracker
@@ -797,11 +1089,12 @@ impl World {
 
         // TODO gamerule this.spawnEnemies || this.spawnFriendlies
         let spawn_passives = self.level_time.lock().await.time_of_day % 400 == 0;
+        let spawn_enemies = self.get_difficulty() != Difficulty::Peaceful;
         let spawn_list: Vec<&'static MobCategory> =
             natural_spawner::get_filtered_spawning_categories(
                 &spawn_state,
                 true,
-                true,
+                spawn_enemies,
                 spawn_passives,
             );
 
@@ -812,15 +1105,22 @@ impl World {
 
         // TODO i think it can be multithread
         for (pos, chunk) in spawning_chunks {
+            chunk.inhabited_time.fetch_add(1, Relaxed);
             self.tick_spawning_chunk(pos, &chunk, &spawn_list, &mut spawn_state)
                 .await;
         }
 
         let world: Arc<dyn SimpleWorld> = self.clone();
 
+        let block_entity_start = tokio::time::Instant::now();
         for block_entity in tick_data.block_entities {
             block_entity.tick(&world).await;
         }
+        if let Some(server) = self.server.upgrade() {
+            server
+                .tick_profiler
+                .record_section(TickSection::BlockEntities, block_entity_start.elapsed());
+        }
     }
 
     pub async fn get_fluid_collisions(self: &Arc<Self>, bounding_box: BoundingBox) -> Vec<&Fluid> {
@@ -1120,8 +1420,11 @@ impl World {
     ) {
         // this.level.tickThunder(chunk);
         //TODO check in simulation distance
-        let weather = self.weather.lock().await;
-        if weather.raining && weather.thundering && rng().random_range(0..100_000) == 0 {
+        let thundering = {
+            let weather = self.weather.lock().await;
+            weather.raining && weather.thundering
+        };
+        if thundering && rng().random_range(0..100_000) == 0 {
             let rand_value = rng().random::<i32>() >> 2;
             let delta = Vector3::new(rand_value & 15, rand_value >> 16 & 15, rand_value >> 8 & 15);
             let random_pos = Vector3::new(
@@ -1147,22 +1450,24 @@ impl World {
                     && self.get_block(&random_pos.to_block_pos().down()).await
                         != &Block::LIGHTNING_ROD
                 {
-                    let entity = Entity::new(
+                    let horse = SkeletonHorseEntity::new(Entity::new(
                         self.clone(),
                         random_pos.to_f64(),
                         &EntityType::SKELETON_HORSE,
-                    );
-                    self.spawn_entity(Arc::new(entity)).await;
+                    ))
+                    .await;
+                    horse.trap.store(true, Relaxed);
+                    self.spawn_entity(horse).await;
                 }
                 let entity = Entity::new(
                     self.clone(),
                     random_pos.to_f64().add_raw(0.5, 0., 0.5),
                     &EntityType::LIGHTNING_BOLT,
                 );
-                self.spawn_entity(Arc::new(entity)).await;
+                self.spawn_entity(Arc::new(LightningEntity::new(entity, false)))
+                    .await;
             }
         }
-        drop(weather);
 
         if spawn_list.is_empty() {
             return;
@@ -1203,7 +1508,11 @@ impl World {
         server: &Server,
     ) {
         let level_info = server.level_info.load();
-        let weather = self.weather.lock().await;
+        // Copied out, finding the spawn height may have to wait for the chunk to generate
+        let (rain_level, thunder_level) = {
+            let weather = self.weather.lock().await;
+            (weather.rain_level, weather.thunder_level)
+        };
         let runtime_id = player.entity_id() as u64;
         let (position, yaw, pitch) = if player.has_played_before.load(Ordering::Relaxed) {
             let position = player.position();
@@ -1223,13 +1532,19 @@ impl World {
             (position, level_info.spawn_yaw, level_info.spawn_pitch)
         };
         // Todo make the data less spread
+        // Bedrock clients only show the seed, so it's left out unless clients may know it
+        let seed_privacy = &server.advanced_config.world.seed_privacy;
         let level_settings = LevelSettings {
-            seed: self.level.seed.0,
+            seed: if seed_privacy.hashed_seed == HashedSeedMode::Real {
+                self.level.seed.0
+            } else {
+                0
+            },
             spawn_biome_type: 0,
             custom_biome_name: String::new(),
             dimension: VarInt(0),
             generator_type: VarInt(1),
-            world_gamemode: server.defaultgamemode.lock().await.gamemode,
+            world_gamemode: server.defaultgamemode.load().gamemode,
             hardcore: base_config.hardcore,
             difficulty: VarInt(level_info.difficulty as i32),
             spawn_position: NetworkPos(BlockPos::new(
@@ -1245,8 +1560,8 @@ impl World {
             education_edition_offer: VarInt(0),
             has_education_features_enabled: false,
             education_product_id: String::new(),
-            rain_level: weather.rain_level,
-            lightning_level: weather.thunder_level,
+            rain_level,
+            lightning_level: thunder_level,
             has_confirmed_platform_locked_content: false,
             was_multiplayer_intended: true,
             was_lan_broadcasting_intended: true,
@@ -1291,7 +1606,6 @@ impl World {
             owner_id: String::new(),
         };
         drop(level_info);
-        drop(weather);
 
         let client = player.client.bedrock();
 
@@ -1488,7 +1802,7 @@ impl World {
                 false,
                 (self.dimension.id).into(),
                 ResourceLocation::from(self.dimension.minecraft_name),
-                biome::hash_seed(self.level.seed.0), // seed
+                self.client_hashed_seed(player), // seed
                 gamemode as u8,
                 player
                     .previous_gamemode
@@ -1506,7 +1820,10 @@ impl World {
             .await;
 
         // Send the current ticking state to the new player so they are in sync.
-        server.tick_rate_manager.update_joining_player(player).await;
+        server
+            .tick_rate_manager
+            .update_joining_player(player, self)
+            .await;
 
         // Permissions, i.e. the commands a player may use.
         player.send_permission_lvl_update().await;
@@ -1739,7 +2056,9 @@ impl World {
             .send_packet_now(&CGameEvent::new(GameEvent::StartWaitingChunks, 0.0))
             .await;
 
-        self.worldborder.lock().await.init_client(client).await;
+        let border = self.worldborder.lock().await.init_packet();
+        client.enqueue_packet(&border).await;
+        self.scoreboard.lock().await.send_teams(player).await;
 
         // Sends initial time
         player.send_time(self).await;
@@ -1767,17 +2086,18 @@ impl World {
             .await;
 
         // Send initial weather state
-        let weather = self.weather.lock().await;
-        if weather.raining {
+        let (raining, rain_level, thunder_level) = {
+            let weather = self.weather.lock().await;
+            (
+                weather.raining,
+                weather.rain_level.clamp(0.0, 1.0),
+                weather.thunder_level.clamp(0.0, 1.0),
+            )
+        };
+        if raining {
             client
                 .enqueue_packet(&CGameEvent::new(GameEvent::BeginRaining, 0.0))
                 .await;
-
-            // Calculate rain and thunder levels directly from public fields
-            let rain_level = weather.rain_level.clamp(0.0, 1.0);
-            let thunder_level = weather.thunder_level.clamp(0.0, 1.0);
-            drop(weather);
-
             client
                 .enqueue_packet(&CGameEvent::new(GameEvent::RainLevelChange, rain_level))
                 .await;
@@ -1828,6 +2148,27 @@ impl World {
         .await;
     }
 
+    /// The hashed seed sent to `player`, which clients use to blend biome colors, see
+    /// [`HashedSeedMode`].
+    pub fn client_hashed_seed(&self, player: &Player) -> i64 {
+        static SALT: OnceLock<u64> = OnceLock::new();
+        let mode = self
+            .server
+            .upgrade()
+            .map_or(HashedSeedMode::Real, |server| {
+                server.advanced_config.world.seed_privacy.hashed_seed
+            });
+        match mode {
+            HashedSeedMode::Real => biome::hash_seed(self.level.seed.0),
+            HashedSeedMode::Salted => {
+                let (most, least) = player.gameprofile.id.as_u64_pair();
+                let salt = *SALT.get_or_init(|| rng().random());
+                biome::hash_seed(self.level.seed.0 ^ salt ^ most ^ least.rotate_left(32))
+            }
+            HashedSeedMode::Zero => 0,
+        }
+    }
+
     pub async fn send_world_info(
         &self,
         player: &Arc<Player>,
@@ -1836,7 +2177,15 @@ impl World {
         pitch: f32,
     ) {
         if let ClientPlatform::Java(client) = &player.client {
-            self.worldborder.lock().await.init_client(client).await;
+            let border = self.worldborder.lock().await.init_packet();
+            client.enqueue_packet(&border).await;
+        }
+        self.scoreboard.lock().await.send_teams(player).await;
+        if let Some(server) = self.server.upgrade() {
+            server
+                .tick_rate_manager
+                .update_joining_player(player, self)
+                .await;
         }
 
         // TODO: World spawn (compass stuff)
@@ -1874,8 +2223,20 @@ impl World {
     }
 
     pub async fn explode(self: &Arc<Self>, position: Vector3<f64>, power: f32) {
+        self.explode_with_source(position, power, None, None).await;
+    }
+
+    /// Like [`Self::explode`], for explosions of `source`, which is not hurt by its own
+    /// explosion. `cause` is who set it off, if anyone.
+    pub async fn explode_with_source(
+        self: &Arc<Self>,
+        position: Vector3<f64>,
+        power: f32,
+        source: Option<&dyn EntityBase>,
+        cause: Option<&dyn EntityBase>,
+    ) {
         let explosion = Explosion::new(power, position);
-        let block_count = explosion.explode(self).await;
+        let block_count = explosion.explode(self, source, cause).await;
         let particle = if power < 2.0 {
             Particle::Explosion
         } else {
@@ -2039,7 +2400,7 @@ impl World {
             .send_packet_now(&CRespawn::new(
                 (target_world.dimension.id).into(),
                 ResourceLocation::from(target_world.dimension.minecraft_name),
-                biome::hash_seed(target_world.level.seed.0),
+                target_world.client_hashed_seed(player),
                 player.gamemode.load() as u8,
                 player.gamemode.load() as i8,
                 false,
@@ -2069,15 +2430,17 @@ impl World {
             ))
             .await;
 
-        player.living_entity.reset_state().await;
-
         player.send_permission_lvl_update().await;
 
-        player.hunger_manager.restart();
+        // Players respawning alive, like after the credits, keep their health and items
+        if !alive {
+            player.living_entity.reset_state().await;
+            player.hunger_manager.restart();
 
-        if !keep_inventory {
-            player.set_experience(0, 0.0, 0).await;
-            player.inventory.clear().await;
+            if !keep_inventory {
+                player.set_experience(0, 0.0, 0).await;
+                player.inventory.clear().await;
+            }
         }
 
         // Set entity position BEFORE loading chunks, so chunks load at the right location
@@ -2125,7 +2488,13 @@ impl World {
 
             if !send_cancelled {
                 java_client.send_packet_now(&CChunkBatchStart).await;
-                java_client.send_packet_now(&CChunkData(&chunk)).await;
+                java_client
+                    .send_packet_now_data(target_world.chunk_packet_cache.encode(
+                        java_client,
+                        &chunk,
+                        &target_world,
+                    ))
+                    .await;
                 java_client
                     .send_packet_now(&CChunkBatchEnd::new(1u16))
                     .await;
@@ -2256,6 +2625,10 @@ impl World {
                             });
                             new_entities
                         });
+                        for id in &ids_to_remove {
+                            world.entity_ids.remove(&id.0);
+                            world.chunk_entity_counts.remove(id.0);
+                        }
                         player
                             .client
                             .enqueue_packet(&CRemoveEntities::new(&ids_to_remove))
@@ -2315,6 +2688,12 @@ impl World {
                         new_entities.extend(entities_to_add.iter().cloned());
                         new_entities
                     });
+                    for entity in &entities_to_add {
+                        world
+                            .entity_ids
+                            .insert(entity.get_entity().entity_id, entity.clone());
+                        world.chunk_entity_counts.add(entity.get_entity());
+                    }
                 }
             }
 
@@ -2335,17 +2714,38 @@ impl World {
 
     /// Gets an entity by an entity id
     pub fn get_entity_by_id(&self, id: i32) -> Option<Arc<dyn EntityBase>> {
-        for entity in self.entities.load().iter() {
-            if entity.get_entity().entity_id == id {
-                return Some(entity.clone());
-            }
+        if let Some(entity) = self.entity_ids.get(&id) {
+            return Some(entity.clone());
         }
         for player in self.players.load().iter() {
             if player.get_entity().entity_id == id {
                 return Some(player.clone() as Arc<dyn EntityBase>);
             }
         }
-        None
+        // Clients attack the parts of the ender dragon, numbered after it
+        self.entities
+            .load()
+            .iter()
+            .find(|entity| {
+                let entity = entity.get_entity();
+                entity.entity_type == &EntityType::ENDER_DRAGON
+                    && (1..=ender_dragon::PART_COUNT).contains(&id.wrapping_sub(entity.entity_id))
+            })
+            .cloned()
+    }
+
+    /// Gets an entity or player by its UUID
+    pub fn get_entity_by_uuid(&self, uuid: uuid::Uuid) -> Option<Arc<dyn EntityBase>> {
+        if let Some(entity) = self
+            .entities
+            .load()
+            .iter()
+            .find(|entity| entity.get_entity().entity_uuid == uuid)
+        {
+            return Some(entity.clone());
+        }
+        self.get_player_by_uuid(uuid)
+            .map(|player| player as Arc<dyn EntityBase>)
     }
 
     /// Gets a `Player` by a username
@@ -2457,26 +2857,13 @@ impl World {
             .collect()
     }
 
+    /// Returns the closest player within `radius` of `pos`, looked up in the players of the
+    /// chunks around it.
     pub fn get_closest_player(&self, pos: Vector3<f64>, radius: f64) -> Option<Arc<Player>> {
-        let players = self.get_nearby_players(pos, radius);
-        players
-            .iter()
-            .min_by(|a, b| {
-                a.living_entity
-                    .entity
-                    .pos
-                    .load()
-                    .squared_distance_to_vec(&pos)
-                    .partial_cmp(
-                        &b.living_entity
-                            .entity
-                            .pos
-                            .load()
-                            .squared_distance_to_vec(&pos),
-                    )
-                    .unwrap()
-            })
-            .cloned()
+        self.nearby_players
+            .load()
+            .nearest(pos, radius)
+            .map(|(player, _)| player)
     }
 
     /// Gets the closest entity to a position, with optional filtering by entity type.
@@ -2559,6 +2946,9 @@ impl World {
                 for player in current_players.iter() {
                     player.send_system_message(&event.join_message).await;
                 }
+                server
+                    .chat_bridge
+                    .relay_event(BridgeEvent::Join, &event.join_message);
                 log::info!("{}", event.join_message.to_pretty_console());
             }
         });
@@ -2602,6 +2992,8 @@ impl World {
             }
             new_list
         });
+        // Don't let mobs keep finding the player until the next tick
+        self.refresh_nearby_players();
         if let Some(ref player) = removed_player {
             let uuid = player.gameprofile.id;
             self.broadcast_packet_all(&CRemovePlayerInfo::new(&[uuid]))
@@ -2617,18 +3009,16 @@ impl World {
                 .color_named(NamedColor::Yellow);
                 let event = PlayerLeaveEvent::new(player.clone(), msg_comp);
 
-                let event = self
-                    .server
-                    .upgrade()
-                    .unwrap()
-                    .plugin_manager
-                    .fire(event)
-                    .await;
+                let server = self.server.upgrade().unwrap();
+                let event = server.plugin_manager.fire(event).await;
 
                 if !event.cancelled {
                     for player in self.players.load().iter() {
                         player.send_system_message(&event.leave_message).await;
                     }
+                    server
+                        .chat_bridge
+                        .relay_event(BridgeEvent::Leave, &event.leave_message);
                     log::info!("{}", event.leave_message.to_pretty_console());
                 }
             }
@@ -2651,26 +3041,70 @@ impl World {
             if event.cancelled {
                 return;
             }
+
+            let chunk = base_entity.block_pos.load().chunk_position();
+            if let Some(kind) = ChunkLimitKind::of_entity(base_entity.entity_type)
+                && !server.chunk_limits.allow(self, chunk, kind).await
+            {
+                return;
+            }
         }
 
         self.broadcast_packet_all(&base_entity.create_spawn_packet())
             .await;
         entity.init_data_tracker().await;
 
-        let chunk_coordinate = base_entity.block_pos.load().chunk_position();
-        let chunk = self.level.get_entity_chunk(chunk_coordinate).await;
-        {
+        // Entities like lightning bolts are never saved
+        if base_entity.entity_type.saveable {
+            let chunk_coordinate = base_entity.block_pos.load().chunk_position();
+            let chunk = self.level.get_entity_chunk(chunk_coordinate).await;
             let mut nbt = NbtCompound::new();
             entity.write_nbt(&mut nbt).await;
             chunk.data.lock().await.insert(base_entity.entity_uuid, nbt);
             chunk.mark_dirty(true);
-        };
+        }
 
         self.entities.rcu(|current_entities| {
             let mut new_entities = (**current_entities).clone();
             new_entities.push(entity.clone());
             new_entities
         });
+        self.entity_ids
+            .insert(base_entity.entity_id, entity.clone());
+        self.chunk_entity_counts.add(base_entity);
+    }
+
+    /// Makes `passenger` ride `vehicle` and tells the clients.
+    pub async fn start_riding(&self, vehicle: Arc<dyn EntityBase>, passenger: Arc<dyn EntityBase>) {
+        *passenger.get_entity().vehicle.lock().await = Some(vehicle.clone());
+        let vehicle_entity = vehicle.get_entity();
+        let mut passengers = vehicle_entity.passengers.lock().await;
+        passengers.push(passenger);
+        let ids: Vec<VarInt> = passengers
+            .iter()
+            .map(|passenger| passenger.get_entity().entity_id.into())
+            .collect();
+        drop(passengers);
+        self.broadcast_packet_all(&CSetPassengers::new(vehicle_entity.entity_id.into(), &ids))
+            .await;
+    }
+
+    /// Makes `passenger` get off its vehicle, if it rides one, and tells the clients.
+    pub async fn stop_riding(&self, passenger: &dyn EntityBase) {
+        let passenger_entity = passenger.get_entity();
+        let Some(vehicle) = passenger_entity.vehicle.lock().await.take() else {
+            return;
+        };
+        let vehicle_entity = vehicle.get_entity();
+        let mut passengers = vehicle_entity.passengers.lock().await;
+        passengers.retain(|other| other.get_entity().entity_id != passenger_entity.entity_id);
+        let ids: Vec<VarInt> = passengers
+            .iter()
+            .map(|passenger| passenger.get_entity().entity_id.into())
+            .collect();
+        drop(passengers);
+        self.broadcast_packet_all(&CSetPassengers::new(vehicle_entity.entity_id.into(), &ids))
+            .await;
     }
 
     pub async fn remove_entity(&self, entity: &Entity) {
@@ -2679,6 +3113,8 @@ impl World {
             new_entities.retain(|e| e.get_entity().entity_uuid != entity.entity_uuid);
             new_entities
         });
+        self.entity_ids.remove(&entity.entity_id);
+        self.chunk_entity_counts.remove(entity.entity_id);
 
         self.broadcast_packet_all(&CRemoveEntities::new(&[entity.entity_id.into()]))
             .await;
@@ -2694,184 +3130,19 @@ impl World {
         .await;
     }
 
-    pub fn queue_block_light_decrease(self: &Arc<Self>, pos: BlockPos, level: u8) {
-        self.decrease_block_light_queue.push((pos, level));
-    }
-
-    pub fn queue_block_light_increase(self: &Arc<Self>, pos: BlockPos, level: u8) {
-        self.increase_block_light_queue.push((pos, level));
-    }
-
-    pub async fn perform_block_light_updates(self: &Arc<Self>) -> i32 {
-        let mut updates = 0;
-
-        updates += self.perform_block_light_decrease_updates().await;
-
-        updates += self.perform_block_light_increase_updates().await;
-
-        updates
-    }
-
-    async fn perform_block_light_decrease_updates(self: &Arc<Self>) -> i32 {
-        let mut updates = 0;
-
-        while let Some((pos, expected_light)) = self.decrease_block_light_queue.pop() {
-            self.propagate_block_light_decrease(&pos, expected_light)
-                .await;
-            updates += 1;
-        }
-
-        updates
-    }
-
-    async fn perform_block_light_increase_updates(self: &Arc<Self>) -> i32 {
-        let mut updates = 0;
-
-        while let Some((pos, expected_light)) = self.increase_block_light_queue.pop() {
-            self.propagate_block_light_increase(&pos, expected_light)
-                .await;
-            updates += 1;
-        }
-
-        updates
-    }
-
-    async fn propagate_block_light_increase(self: &Arc<Self>, pos: &BlockPos, light_level: u8) {
-        for dir in BlockDirection::all() {
-            let neighbor_pos = pos.offset(dir.to_offset());
-
-            if let Some(neighbor_light) = self.get_block_light_level(&neighbor_pos).await {
-                let neighbor_state = self.get_block_state(&neighbor_pos).await;
-                let new_light = light_level.saturating_sub(neighbor_state.opacity.max(1));
-
-                if new_light > neighbor_light {
-                    // TODO: Add shape checking for non-trivial blocks
-
-                    self.set_block_light_level(&neighbor_pos, new_light)
-                        .await
-                        .unwrap();
-
-                    if new_light > 1 {
-                        self.queue_block_light_increase(neighbor_pos, new_light);
-                    }
-                }
-            }
-        }
-    }
-
-    async fn propagate_block_light_decrease(
-        self: &Arc<Self>,
-        pos: &BlockPos,
-        removed_light_level: u8,
-    ) {
-        for dir in BlockDirection::all() {
-            let neighbor_pos = pos.offset(dir.to_offset());
-
-            if let Some(neighbor_light) = self.get_block_light_level(&neighbor_pos).await {
-                if neighbor_light == 0 {
-                    continue; // Skip if already 0
-                }
-
-                let neighbor_state = self.get_block_state(&neighbor_pos).await;
-                let opacity = neighbor_state.opacity.max(1);
-
-                let expected_from_removed_source = removed_light_level.saturating_sub(opacity);
-
-                if neighbor_light <= expected_from_removed_source {
-                    let neighbor_luminance = neighbor_state.luminance;
-
-                    self.set_block_light_level(&neighbor_pos, 0).await.unwrap();
-
-                    if neighbor_luminance == 0 {
-                        self.queue_block_light_decrease(neighbor_pos, neighbor_light);
-                    } else {
-                        self.set_block_light_level(&neighbor_pos, neighbor_luminance)
-                            .await
-                            .unwrap();
-                        self.queue_block_light_increase(neighbor_pos, neighbor_luminance);
-                    }
-                } else {
-                    self.queue_block_light_increase(neighbor_pos, neighbor_light);
-                }
-            }
-        }
-    }
-
-    pub async fn check_block_light_updates(self: &Arc<Self>, pos: BlockPos) {
-        let current_light = self.get_block_light_level(&pos).await.unwrap_or(0);
-        let block_state = self.get_block_state(&pos).await;
-        let expected_light = block_state.luminance;
-
-        if expected_light < current_light {
-            self.set_block_light_level(&pos, 0).await.unwrap();
-            self.queue_block_light_decrease(pos, current_light);
-        }
-
-        if expected_light > 0 {
-            self.set_block_light_level(&pos, expected_light)
-                .await
-                .unwrap();
-            self.queue_block_light_increase(pos, expected_light);
-        }
-
-        //TODO check sky light updates
-
-        self.check_neighbors_light_updates(pos, current_light).await;
-    }
-
-    pub async fn check_neighbors_light_updates(self: &Arc<Self>, pos: BlockPos, current_light: u8) {
-        for dir in BlockDirection::all() {
-            let neighbor_pos = pos.offset(dir.to_offset());
-            if let Some(neighbor_light) = self.get_block_light_level(&neighbor_pos).await
-                && neighbor_light > current_light + 1
-            {
-                self.queue_block_light_increase(neighbor_pos, neighbor_light);
-            }
-        }
-        // TODO check sky light updates
-    }
-
     pub async fn get_block_light_level(&self, position: &BlockPos) -> Option<u8> {
         let (chunk_coordinate, relative) = position.chunk_and_chunk_relative_position();
         let chunk = self.level.get_chunk(chunk_coordinate).await;
 
         let section_index = (relative.y - chunk.section.min_y) as usize / BlockPalette::SIZE;
-        // +1 since block light has 1 section padding on both top and bottom
-        if section_index + 1 >= chunk.light_engine.block_light.len() {
-            return None;
-        }
-        Some(chunk.light_engine.block_light[section_index + 1].get(
+        let light = chunk.light_engine.read().unwrap();
+        Some(light.block_light.get(section_index)?.get(
             relative.x as usize,
             (relative.y - chunk.section.min_y) as usize % BlockPalette::SIZE,
             relative.z as usize,
         ))
     }
 
-    #[expect(clippy::unused_async)]
-    pub async fn set_block_light_level(
-        &self,
-        _position: &BlockPos,
-        _light_level: u8,
-    ) -> Result<(), String> {
-        // TODO
-        // let (chunk_coordinate, relative) = position.chunk_and_chunk_relative_position();
-        // let chunk = self.level.get_chunk(chunk_coordinate).await;
-
-        // let section_index = (relative.y - chunk.section.min_y) as usize / BlockPalette::SIZE;
-        // if section_index >= chunk.light_engine.block_light.len() {
-        //     return Err("Invalid section index".to_string());
-        // }
-        // let relative_y = (relative.y - chunk.section.min_y) as usize % BlockPalette::SIZE;
-        // // chunk.light_engine.block_light[section_index + 1].set(
-        // //     relative.x as usize,
-        // //     relative_y,
-        // //     relative.z as usize,
-        // //     light_level,
-        // // );
-        // chunk.mark_dirty(true);
-        Ok(())
-    }
-
     /// Sets a block and returns the old block id
     #[expect(clippy::too_many_lines)]
     pub async fn set_block_state(
@@ -2999,8 +3270,11 @@ impl World {
             }
         }
 
-        self.check_block_light_updates(*position).await;
-        self.perform_block_light_updates().await;
+        let old_state = BlockState::from_id(replaced_block_state_id);
+        let new_state = BlockState::from_id(block_state_id);
+        if old_state.opacity != new_state.opacity || old_state.luminance != new_state.luminance {
+            self.lighting.queue_block(*position);
+        }
 
         replaced_block_state_id
     }
@@ -3047,13 +3321,8 @@ impl World {
         let (broken_block, broken_block_state) = self.get_block_and_state_id(position).await;
         let event = BlockBreakEvent::new(cause.clone(), broken_block, *position, 0, false);
 
-        let event = self
-            .server
-            .upgrade()
-            .unwrap()
-            .plugin_manager
-            .fire::<BlockBreakEvent>(event)
-            .await;
+        let server = self.server.upgrade().unwrap();
+        let event = server.plugin_manager.fire::<BlockBreakEvent>(event).await;
 
         if !event.cancelled {
             let new_state_id = if broken_block
@@ -3076,6 +3345,16 @@ impl World {
             };
 
             let broken_state_id = self.set_block_state(position, new_state_id, flags).await;
+            if let Some(player) = &cause {
+                server.block_log.record(
+                    player.gameprofile.id,
+                    &player.gameprofile.name,
+                    self.dimension.id,
+                    *position,
+                    broken_state_id,
+                    new_state_id,
+                );
+            }
 
             if Block::from_state_id(broken_state_id) != &Block::FIRE {
                 let particles_packet = CWorldEvent::new(
@@ -3173,6 +3452,24 @@ impl World {
         self.broadcast_packet_all(&CWorldEvent::new(world_event as i32, position, data, false))
             .await;
     }
+
+    /// Like [`Self::sync_world_event`], but heard by every player no matter how far away, unless
+    /// the `global_sound_events` game rule is off.
+    pub async fn sync_global_world_event(
+        &self,
+        world_event: WorldEvent,
+        position: BlockPos,
+        data: i32,
+    ) {
+        let global = self.level_info.load().game_rules.global_sound_events;
+        self.broadcast_packet_all(&CWorldEvent::new(
+            world_event as i32,
+            position,
+            data,
+            global,
+        ))
+        .await;
+    }
     #[must_use]
     pub fn is_valid(dest: BlockPos) -> bool {
         Self::is_valid_horizontally(dest) && Self::is_valid_vertically(dest.0.y)
@@ -3450,12 +3747,14 @@ impl World {
         if let Some(nbt) = &block_entity_nbt {
             let mut bytes = Vec::new();
             to_bytes_unnamed(nbt, &mut bytes).unwrap();
-            self.broadcast_packet_all(&CBlockEntityData::new(
-                block_entity.get_position(),
-                VarInt(block_entity.get_id() as i32),
-                bytes.into_boxed_slice(),
-            ))
-            .await;
+            self.unsent_block_entity_updates.lock().await.insert(
+                block_pos,
+                CBlockEntityData::new(
+                    block_pos,
+                    VarInt(block_entity.get_id() as i32),
+                    bytes.into_boxed_slice(),
+                ),
+            );
         }
 
         chunk
@@ -3464,9 +3763,15 @@ impl World {
             .unwrap()
             .insert(block_pos, block_entity);
         chunk.mark_dirty(true);
+        self.chunk_packet_cache
+            .invalidate(block_pos.chunk_position());
     }
 
     pub async fn remove_block_entity(&self, block_pos: &BlockPos) {
+        self.unsent_block_entity_updates
+            .lock()
+            .await
+            .remove(block_pos);
         let chunk = self.level.get_chunk(block_pos.chunk_position()).await;
         if chunk
             .block_entities
@@ -3476,6 +3781,8 @@ impl World {
             .is_some()
         {
             chunk.mark_dirty(true);
+            self.chunk_packet_cache
+                .invalidate(block_pos.chunk_position());
         }
     }
 
@@ -3487,14 +3794,18 @@ impl World {
         if let Some(nbt) = &block_entity_nbt {
             let mut bytes = Vec::new();
             to_bytes_unnamed(nbt, &mut bytes).unwrap();
-            self.broadcast_packet_all(&CBlockEntityData::new(
-                block_entity.get_position(),
-                VarInt(block_entity.get_id() as i32),
-                bytes.into_boxed_slice(),
-            ))
-            .await;
+            self.unsent_block_entity_updates.lock().await.insert(
+                block_pos,
+                CBlockEntityData::new(
+                    block_pos,
+                    VarInt(block_entity.get_id() as i32),
+                    bytes.into_boxed_slice(),
+                ),
+            );
         }
         chunk.mark_dirty(true);
+        self.chunk_packet_cache
+            .invalidate(block_pos.chunk_position());
     }
 
     fn intersects_aabb_with_direction(
@@ -3852,3 +4163,87 @@ impl BlockAccessor for World {
         Box::pin(async move { self.get_block_and_state(position).await })
     }
 }
+
+#[cfg(test)]
+impl World {
+    /// An empty overworld without a server, saved in `folder`.
+    pub(crate) fn for_test(folder: &std::path::Path) -> Arc<Self> {
+        let registry = crate::block::registry::default_registry();
+        let level = Level::from_root_folder(
+            &pumpkin_config::world::LevelConfig::default(),
+            folder.to_path_buf(),
+            registry.clone(),
+            0,
+            Dimension::OVERWORLD,
+        );
+        Arc::new(Self::load(
+            level,
+            Arc::new(ArcSwap::from_pointee(LevelData::default(
+                pumpkin_util::world_seed::Seed(0),
+            ))),
+            Dimension::OVERWORLD,
+            registry,
+            Weak::new(),
+        ))
+    }
+}
+
+/// The viewers whose view, given by `view`, includes `chunk`.
+fn chunk_viewers<T>(
+    viewers: &[T],
+    chunk: Vector2<i32>,
+    view: impl Fn(&T) -> Cylindrical,
+) -> impl Iterator<Item = &T> {
+    viewers
+        .iter()
+        .filter(move |viewer| view(viewer).is_within_distance(chunk.x, chunk.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU8;
+
+    use super::*;
+
+    #[test]
+    fn only_viewers_of_the_chunk_receive_it() {
+        let viewer_at = |x, view_distance| {
+            Cylindrical::new(Vector2::new(x, 0), NonZeroU8::new(view_distance).unwrap())
+        };
+        let viewers = [
+            viewer_at(0, 8),
+            viewer_at(100, 8),
+            viewer_at(-12, 16),
+            // Clients with a view distance of one don't see any chunks
+            viewer_at(0, 1),
+        ];
+        let receivers: Vec<_> = chunk_viewers(&viewers, Vector2::new(5, 0), |view| *view)
+            .map(|view| view.center.x)
+            .collect();
+        assert_eq!(receivers, [0, -12]);
+        assert_eq!(
+            chunk_viewers(&viewers, Vector2::new(500, 500), |view| *view).count(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn block_updates_are_sent_once() {
+        let folder = tempfile::tempdir().unwrap();
+        let world = World::for_test(folder.path());
+        let position = BlockPos::new(1, 64, 1);
+        world
+            .unsent_block_changes
+            .lock()
+            .await
+            .insert(position, Block::CHEST.default_state.id);
+        world.unsent_block_entity_updates.lock().await.insert(
+            position,
+            CBlockEntityData::new(position, VarInt(0), Box::new([])),
+        );
+
+        world.flush_block_updates().await;
+        assert!(world.unsent_block_changes.lock().await.is_empty());
+        assert!(world.unsent_block_entity_updates.lock().await.is_empty());
+    }
+}
@@ -1,9 +1,11 @@
 use crate::entity::EntityBase;
+use crate::entity::mob::slime::SlimeEntity;
 use crate::entity::r#type::from_type;
 use crate::plugin::entity::entity_spawn::EntitySpawnEvent;
 use crate::world::World;
 use arc_swap::ArcSwap;
 use pumpkin_data::biome::Spawner;
+use pumpkin_data::dimension::Dimension;
 use pumpkin_data::entity::{EntityType, MobCategory, SpawnLocation};
 use pumpkin_data::tag::Block::MINECRAFT_PREVENT_MOB_SPAWNING_INSIDE;
 use pumpkin_data::tag::Fluid::{MINECRAFT_LAVA, MINECRAFT_WATER};
@@ -362,7 +364,7 @@ pub async fn spawn_category_for_position(
     let mut batch_buffer = vec![];
     let mut spawn_cluster_size = 0;
     let mut new_pos = pos;
-    let player_positions: Vec<_> = world.players.load().iter().map(|p| p.position()).collect();
+    let nearby_players = world.nearby_players.load_full();
     for _ in 0..3 {
         let mut new_x = new_pos.0.x;
         let mut new_z = new_pos.0.z;
@@ -375,7 +377,10 @@ pub async fn spawn_category_for_position(
             new_z += rng().random_range(0..6) - rng().random_range(0..6);
             new_pos = BlockPos::new(new_x, new_pos.0.y, new_z);
             let new_pos_center = new_pos.to_centered_f64();
-            let player_distance = get_nearest_player(&new_pos_center, &player_positions);
+            // Players beyond the despawn distance don't affect spawning
+            let player_distance = nearby_players
+                .nearest(new_pos_center, f64::from(category.despawn_distance))
+                .map_or(f64::MAX, |(_, distance)| distance);
             if !is_right_distance_to_player_and_spawn_point(&new_pos, player_distance, chunk_pos) {
                 inc += 1;
                 continue;
@@ -407,7 +412,8 @@ pub async fn spawn_category_for_position(
                 .get_entity()
                 .set_rotation(rng().random::<f32>() * 360., 0.);
             // TODO isValidPositionForMob(level, mob, f)
-            // TODO spawnGroupData = mob.finalizeSpawn(level, level.getCurrentDifficultyAt(mob.blockPosition()), EntitySpawnReason.NATURAL, spawnGroupData);
+            let difficulty = world.get_regional_difficulty(&new_pos).await;
+            entity.finalize_spawn(&difficulty).await;
             spawn_cluster_size += 1;
             //group_size += 1;
             batch_buffer.push(entity);
@@ -491,24 +497,20 @@ async fn spawn_entity_batch(
             }
             new_entities
         });
+        for (_, _, _, entity_ref) in &prepared_data {
+            world
+                .entity_ids
+                .insert(entity_ref.get_entity().entity_id, entity_ref.clone());
+            world.chunk_entity_counts.add(entity_ref.get_entity());
+        }
     };
 
-    for (_, _, packet, _) in prepared_data {
+    for (_, _, packet, entity) in prepared_data {
         world.broadcast_packet_all(&packet).await;
-    }
-}
-
-#[must_use]
-pub fn get_nearest_player(pos: &Vector3<f64>, player_positions: &[Vector3<f64>]) -> f64 {
-    let mut min_dst_sq = f64::MAX;
-
-    for player_pos in player_positions {
-        let cur_dst_sq = player_pos.squared_distance_to_vec(pos);
-        if cur_dst_sq < min_dst_sq {
-            min_dst_sq = cur_dst_sq;
+        if let Some(living) = entity.get_living_entity() {
+            living.send_all_equipment().await;
         }
     }
-    min_dst_sq
 }
 
 #[must_use]
@@ -570,10 +572,12 @@ pub async fn is_valid_spawn_position_for_type(
     entity_type: &'static EntityType,
     distance: f64,
 ) -> bool {
-    // TODO !SpawnPlacements.checkSpawnRules(entityType, level, EntitySpawnReason.NATURAL, pos, level.random)
     if category == &MobCategory::MISC {
         return false;
     }
+    if !check_spawn_rules(world, block_pos, entity_type).await {
+        return false;
+    }
     if !entity_type.can_spawn_far_from_player
         && distance
             > f64::from(entity_type.category.despawn_distance)
@@ -602,6 +606,147 @@ pub async fn is_valid_spawn_position_for_type(
         .await
 }
 
+/// The spawn rules of each mob type on top of the spawn restrictions, like `SpawnPlacements` in
+/// vanilla, and the limits of the spawning config.
+async fn check_spawn_rules(
+    world: &Arc<World>,
+    block_pos: &BlockPos,
+    entity_type: &'static EntityType,
+) -> bool {
+    let spawning = world.spawning();
+    if let Some(heights) = spawning.heights(entity_type.resource_name)
+        && !heights.contains(block_pos.0.y)
+    {
+        return false;
+    }
+    if !spawning.cave_spawns || !spawning.surface_spawns {
+        let surface = block_pos.0.y
+            > world
+                .get_motion_blocking_height(block_pos.0.x, block_pos.0.z)
+                .await;
+        let allowed = if surface {
+            spawning.surface_spawns
+        } else {
+            spawning.cave_spawns
+        };
+        if !allowed {
+            return false;
+        }
+    }
+
+    match entity_type.id {
+        id if id == EntityType::SLIME.id => {
+            SlimeEntity::can_spawn_naturally(world, block_pos).await
+        }
+        // Nether monsters spawn in any light
+        id if [
+            EntityType::GHAST.id,
+            EntityType::MAGMA_CUBE.id,
+            EntityType::BLAZE.id,
+            EntityType::PIGLIN.id,
+            EntityType::HOGLIN.id,
+            EntityType::ZOMBIFIED_PIGLIN.id,
+        ]
+        .contains(&id) =>
+        {
+            true
+        }
+        _ if entity_type.category == &MobCategory::MONSTER => {
+            is_dark_enough_to_spawn(world, block_pos).await
+        }
+        // TODO: The rules of the other mobs
+        _ => true,
+    }
+}
+
+/// Whether a monster spawns at `block_pos`, by the sky and block light there and the time of day.
+async fn is_dark_enough_to_spawn(world: &Arc<World>, block_pos: &BlockPos) -> bool {
+    let light = SpawnLight::at(world, block_pos).await;
+    let spawning = world.spawning();
+    let block_light_limit =
+        spawning
+            .monster_block_light_limit
+            .unwrap_or(if world.dimension == Dimension::THE_NETHER {
+                15
+            } else {
+                0
+            });
+    let mut rng = rng();
+    is_dark_enough(
+        light,
+        block_light_limit,
+        (
+            rng.random_range(0..32),
+            rng.random_range(0..=spawning.monster_max_light),
+        ),
+    )
+}
+
+/// The light at a spawn position.
+#[derive(Clone, Copy)]
+pub struct SpawnLight {
+    sky: u8,
+    block: u8,
+    /// How much the time of day and the weather darken the sky light.
+    sky_darken: u8,
+}
+
+impl SpawnLight {
+    pub async fn at(world: &World, block_pos: &BlockPos) -> Self {
+        // The sky light isn't spread yet, so it is full wherever the sky can be seen
+        let sky = if world.dimension.has_skylight
+            && block_pos.0.y
+                > world
+                    .get_motion_blocking_height(block_pos.0.x, block_pos.0.z)
+                    .await
+        {
+            15
+        } else {
+            0
+        };
+        let block = world.get_block_light_level(block_pos).await.unwrap_or(0);
+        let sky_darken = {
+            let weather = world.weather.lock().await;
+            let (rain_level, thunder_level) = (weather.rain_level, weather.thunder_level);
+            drop(weather);
+            world
+                .level_time
+                .lock()
+                .await
+                .sky_darken(rain_level, thunder_level)
+        };
+        Self {
+            sky,
+            block,
+            sky_darken,
+        }
+    }
+
+    /// The brighter of the block light and the darkened sky light, vanilla's
+    /// `getMaxLocalRawBrightness`.
+    #[must_use]
+    pub const fn brightness(self) -> u8 {
+        let sky = self.sky.saturating_sub(self.sky_darken);
+        if sky > self.block { sky } else { self.block }
+    }
+}
+
+/// Vanilla's `Monster::isDarkEnoughToSpawn`, with the rolls `(0..32, 0..=max_light)` made up
+/// front.
+const fn is_dark_enough(
+    light: SpawnLight,
+    block_light_limit: u8,
+    (sky_roll, light_roll): (u8, u8),
+) -> bool {
+    if light.sky > sky_roll {
+        return false;
+    }
+    if light.block > block_light_limit {
+        return false;
+    }
+    light.brightness() <= light_roll
+}
+
 pub async fn is_spawn_position_ok(
     world: &Arc<World>,
     block_pos: &BlockPos,
@@ -637,3 +782,39 @@ pub fn is_valid_empty_spawn_block(state: &'static BlockState) -> bool {
     // TODO !entityType.isBlockDangerous(blockState);
     !Block::from_state_id(state.id).has_tag(&MINECRAFT_PREVENT_MOB_SPAWNING_INSIDE)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monsters_spawn_in_the_dark() {
+        let light = |sky, block, sky_darken| SpawnLight {
+            sky,
+            block,
+            sky_darken,
+        };
+        // Open sky at noon and at midnight
+        assert!(!is_dark_enough(light(15, 0, 0), 0, (31, 7)));
+        assert!(is_dark_enough(light(15, 0, 11), 0, (31, 7)));
+        assert!(!is_dark_enough(light(15, 0, 11), 0, (3, 7)));
+        // A torch-lit cave, only spawning with the light level 7 rules from before 1.18
+        assert!(!is_dark_enough(light(0, 7, 0), 0, (0, 7)));
+        assert!(is_dark_enough(light(0, 7, 0), 15, (0, 7)));
+        assert!(!is_dark_enough(light(0, 8, 0), 15, (0, 7)));
+    }
+
+    #[test]
+    fn brightness_combines_sky_and_block_light() {
+        let light = |sky, block, sky_darken| SpawnLight {
+            sky,
+            block,
+            sky_darken,
+        };
+        // A swamp in daylight is bright even without torches
+        assert_eq!(light(15, 0, 0).brightness(), 15);
+        assert_eq!(light(15, 0, 11).brightness(), 4);
+        assert_eq!(light(15, 7, 11).brightness(), 7);
+        assert_eq!(light(0, 3, 0).brightness(), 3);
+    }
+}
@@ -0,0 +1,251 @@
+//! Players of a world bucketed by the chunk they are in, so that distance checks of mobs and
+//! spawning only look at the players around them instead of every player in the world.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_util::math::vector3::Vector3;
+
+use super::World;
+use crate::entity::player::Player;
+
+/// Something that can be bucketed by the chunk it is in.
+pub trait Tracked {
+    fn position(&self) -> Vector3<f64>;
+    fn chunk(&self) -> Vector2<i32>;
+}
+
+impl Tracked for Player {
+    fn position(&self) -> Vector3<f64> {
+        self.living_entity.entity.pos.load()
+    }
+
+    fn chunk(&self) -> Vector2<i32> {
+        self.living_entity.entity.chunk_pos.load()
+    }
+}
+
+pub struct NearbyPlayers<P: Tracked = Player> {
+    players: Vec<Arc<P>>,
+    /// The chunk of every player when the buckets were built, in the order of `players`.
+    chunks: Vec<Vector2<i32>>,
+    /// Indices into `players` by chunk.
+    by_chunk: HashMap<Vector2<i32>, Vec<usize>>,
+}
+
+impl<P: Tracked> Default for NearbyPlayers<P> {
+    fn default() -> Self {
+        Self {
+            players: Vec::new(),
+            chunks: Vec::new(),
+            by_chunk: HashMap::new(),
+        }
+    }
+}
+
+impl<P: Tracked> NearbyPlayers<P> {
+    #[must_use]
+    pub fn new(players: Vec<Arc<P>>) -> Self {
+        let chunks: Vec<_> = players.iter().map(|player| player.chunk()).collect();
+        let mut by_chunk: HashMap<_, Vec<_>> = HashMap::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            by_chunk.entry(*chunk).or_default().push(index);
+        }
+        Self {
+            players,
+            chunks,
+            by_chunk,
+        }
+    }
+
+    /// Whether the buckets still match `players` and the chunks they are in.
+    fn is_current(&self, players: &[Arc<P>]) -> bool {
+        self.players.len() == players.len()
+            && players.iter().zip(&self.players).zip(&self.chunks).all(
+                |((player, cached), chunk)| Arc::ptr_eq(player, cached) && player.chunk() == *chunk,
+            )
+    }
+
+    /// Calls `f` with every player within `radius` of `pos` and their squared distance.
+    fn for_each_within<'a>(
+        &'a self,
+        pos: Vector3<f64>,
+        radius: f64,
+        mut f: impl FnMut(&'a Arc<P>, f64),
+    ) {
+        let radius_squared = radius.powi(2);
+        let mut check = |index: usize| {
+            let player = &self.players[index];
+            let distance = player.position().squared_distance_to_vec(&pos);
+            if distance <= radius_squared {
+                f(player, distance);
+            }
+        };
+
+        // One more chunk covers players that left their bucket since it was built
+        let chunk_radius = (radius / 16.0).ceil() + 1.0;
+        let center = Vector2::new((pos.x.floor() as i32) >> 4, (pos.z.floor() as i32) >> 4);
+        if chunk_radius.mul_add(2.0, 1.0).powi(2) >= self.by_chunk.len() as f64 {
+            for indices in self.by_chunk.values() {
+                indices.iter().copied().for_each(&mut check);
+            }
+            return;
+        }
+        let chunk_radius = chunk_radius as i32;
+        for x in center.x - chunk_radius..=center.x + chunk_radius {
+            for z in center.y - chunk_radius..=center.y + chunk_radius {
+                if let Some(indices) = self.by_chunk.get(&Vector2::new(x, z)) {
+                    indices.iter().copied().for_each(&mut check);
+                }
+            }
+        }
+    }
+
+    /// Returns the closest player within `radius` of `pos` and their squared distance.
+    #[must_use]
+    pub fn nearest(&self, pos: Vector3<f64>, radius: f64) -> Option<(Arc<P>, f64)> {
+        let mut nearest: Option<(&Arc<P>, f64)> = None;
+        self.for_each_within(pos, radius, |player, distance| {
+            if nearest.is_none_or(|(_, closest)| distance < closest) {
+                nearest = Some((player, distance));
+            }
+        });
+        nearest.map(|(player, distance)| (player.clone(), distance))
+    }
+
+    /// Returns the players within `radius` of `pos`, closest first.
+    #[must_use]
+    pub fn within(&self, pos: Vector3<f64>, radius: f64) -> Vec<Arc<P>> {
+        let mut players = Vec::new();
+        self.for_each_within(pos, radius, |player, distance| {
+            players.push((distance, player.clone()));
+        });
+        players.sort_by(|a, b| a.0.total_cmp(&b.0));
+        players.into_iter().map(|(_, player)| player).collect()
+    }
+}
+
+impl World {
+    /// Rebuilds the nearby player buckets if a player joined, left or moved to another chunk.
+    pub(super) fn refresh_nearby_players(&self) {
+        let players = self.players.load();
+        if !self.nearby_players.load().is_current(&players) {
+            self.nearby_players
+                .store(Arc::new(NearbyPlayers::new(players.to_vec())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossbeam::atomic::AtomicCell;
+
+    use super::*;
+
+    struct Marker {
+        pos: AtomicCell<Vector3<f64>>,
+    }
+
+    impl Tracked for Marker {
+        fn position(&self) -> Vector3<f64> {
+            self.pos.load()
+        }
+
+        fn chunk(&self) -> Vector2<i32> {
+            let pos = self.pos.load();
+            Vector2::new((pos.x.floor() as i32) >> 4, (pos.z.floor() as i32) >> 4)
+        }
+    }
+
+    fn marker(x: f64, z: f64) -> Arc<Marker> {
+        Arc::new(Marker {
+            pos: AtomicCell::new(Vector3::new(x, 64.0, z)),
+        })
+    }
+
+    /// Markers spread over many chunks, so queries only look at the chunks around them.
+    fn spread_out(near: &[Arc<Marker>]) -> Vec<Arc<Marker>> {
+        let mut markers = near.to_vec();
+        for i in 0..120 {
+            markers.push(marker(f64::from(i) * 1000.0 + 5000.0, -3000.0));
+        }
+        markers
+    }
+
+    #[test]
+    fn within_is_sorted_and_limited_to_the_radius() {
+        let a = marker(3.0, 3.0);
+        let b = marker(-20.0, 0.0);
+        let c = marker(0.0, 40.0);
+        let far = marker(0.0, 200.0);
+        let nearby =
+            NearbyPlayers::new(spread_out(&[c.clone(), far.clone(), b.clone(), a.clone()]));
+
+        let found = nearby.within(Vector3::new(0.0, 64.0, 0.0), 48.0);
+        assert_eq!(found.len(), 3);
+        assert!(Arc::ptr_eq(&found[0], &a));
+        assert!(Arc::ptr_eq(&found[1], &b));
+        assert!(Arc::ptr_eq(&found[2], &c));
+
+        let (nearest, distance) = nearby
+            .nearest(Vector3::new(0.0, 64.0, 190.0), 48.0)
+            .unwrap();
+        assert!(Arc::ptr_eq(&nearest, &far));
+        assert_eq!(distance, 100.0);
+        assert!(
+            nearby
+                .nearest(Vector3::new(0.0, 64.0, 120.0), 30.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn few_buckets_are_all_checked() {
+        let a = marker(-700.0, 900.0);
+        let nearby = NearbyPlayers::new(vec![a.clone()]);
+        let (nearest, _) = nearby
+            .nearest(Vector3::new(-700.0, 64.0, 1000.0), 128.0)
+            .unwrap();
+        assert!(Arc::ptr_eq(&nearest, &a));
+        assert!(
+            nearby
+                .within(Vector3::new(0.0, 64.0, 0.0), 128.0)
+                .is_empty()
+        );
+        assert!(
+            NearbyPlayers::<Marker>::default()
+                .nearest(Vector3::new(0.0, 64.0, 0.0), 128.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn players_that_moved_are_still_found() {
+        let a = marker(8.0, 8.0);
+        let nearby = NearbyPlayers::new(spread_out(&[a.clone()]));
+        // Into the next chunk, without rebuilding the buckets
+        a.pos.store(Vector3::new(24.0, 64.0, 8.0));
+        let (nearest, _) = nearby.nearest(Vector3::new(30.0, 64.0, 8.0), 8.0).unwrap();
+        assert!(Arc::ptr_eq(&nearest, &a));
+        assert!(nearby.nearest(Vector3::new(8.0, 64.0, 8.0), 8.0).is_none());
+    }
+
+    #[test]
+    fn buckets_are_current_until_players_change() {
+        let a = marker(8.0, 8.0);
+        let b = marker(100.0, 8.0);
+        let players = vec![a.clone(), b.clone()];
+        let nearby = NearbyPlayers::new(players.clone());
+        assert!(nearby.is_current(&players));
+
+        a.pos.store(Vector3::new(10.0, 64.0, 12.0));
+        assert!(nearby.is_current(&players));
+        a.pos.store(Vector3::new(-10.0, 64.0, 12.0));
+        assert!(!nearby.is_current(&players));
+
+        let nearby = NearbyPlayers::new(players);
+        assert!(!nearby.is_current(&[a]));
+        assert!(!nearby.is_current(&[b.clone(), b]));
+    }
+}
@@ -0,0 +1,490 @@
+//! Particles with their extra data, shaped emitters and culling by distance.
+//!
+//! A [`ParticleEffect`] describes what to spawn, the functions in [`shapes`] where to spawn it.
+//! Spawning in a world only reaches players close enough to see the particles, like vanilla.
+
+use std::borrow::Cow;
+
+use pumpkin_data::particle::Particle;
+use pumpkin_protocol::codec::item_stack_seralizer::ItemStackSerializer;
+use pumpkin_protocol::codec::var_int::VarInt;
+use pumpkin_protocol::java::client::play::CParticle;
+use pumpkin_protocol::ser::serializer::Serializer;
+use pumpkin_protocol::ser::{NetworkWriteExt, WritingError};
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_world::BlockStateId;
+use pumpkin_world::item::ItemStack;
+use serde::Serialize;
+
+use crate::entity::player::Player;
+use crate::world::World;
+
+/// Players further away than this do not get normal particles.
+pub const RANGE: f64 = 32.0;
+/// Players further away than this do not get long distance particles.
+pub const LONG_DISTANCE_RANGE: f64 = 512.0;
+
+/// The extra data some particles need. Colors are RGB, or ARGB where noted.
+#[derive(Clone, Debug)]
+pub enum ParticleData {
+    None,
+    /// For `dust`.
+    Dust {
+        color: i32,
+        scale: f32,
+    },
+    /// For `dust_color_transition`.
+    DustTransition {
+        from: i32,
+        to: i32,
+        scale: f32,
+    },
+    /// For `block`, `block_marker`, `falling_dust`, `dust_pillar` and `block_crumble`.
+    Block(BlockStateId),
+    /// For `item`.
+    Item(ItemStack),
+    /// An ARGB color, for `entity_effect`, `tinted_leaves` and `flash`.
+    Color(i32),
+    /// For `effect` and `instant_effect`.
+    Spell {
+        color: i32,
+        power: f32,
+    },
+    /// For `dragon_breath`.
+    Power(f32),
+    /// The rotation of `sculk_charge`, in radians.
+    Roll(f32),
+    /// The ticks `shriek` waits before showing.
+    Delay(i32),
+    /// For `trail`, which moves towards `target`.
+    Trail {
+        target: Vector3<f64>,
+        color: i32,
+        duration: i32,
+    },
+    /// For `vibration`, which moves towards `destination` in `ticks`.
+    Vibration {
+        destination: BlockPos,
+        ticks: i32,
+    },
+}
+
+impl ParticleData {
+    /// Whether `particle` takes this data. Particles without data take [`Self::None`].
+    #[must_use]
+    pub const fn fits(&self, particle: Particle) -> bool {
+        match self {
+            Self::None => !Self::needs_data(particle),
+            Self::Dust { .. } => matches!(particle, Particle::Dust),
+            Self::DustTransition { .. } => matches!(particle, Particle::DustColorTransition),
+            Self::Block(_) => matches!(
+                particle,
+                Particle::Block
+                    | Particle::BlockMarker
+                    | Particle::FallingDust
+                    | Particle::DustPillar
+                    | Particle::BlockCrumble
+            ),
+            Self::Item(_) => matches!(particle, Particle::Item),
+            Self::Color(_) => matches!(
+                particle,
+                Particle::EntityEffect | Particle::TintedLeaves | Particle::Flash
+            ),
+            Self::Spell { .. } => matches!(particle, Particle::Effect | Particle::InstantEffect),
+            Self::Power(_) => matches!(particle, Particle::DragonBreath),
+            Self::Roll(_) => matches!(particle, Particle::SculkCharge),
+            Self::Delay(_) => matches!(particle, Particle::Shriek),
+            Self::Trail { .. } => matches!(particle, Particle::Trail),
+            Self::Vibration { .. } => matches!(particle, Particle::Vibration),
+        }
+    }
+
+    const fn needs_data(particle: Particle) -> bool {
+        matches!(
+            particle,
+            Particle::Dust
+                | Particle::DustColorTransition
+                | Particle::Block
+                | Particle::BlockMarker
+                | Particle::FallingDust
+                | Particle::DustPillar
+                | Particle::BlockCrumble
+                | Particle::Item
+                | Particle::EntityEffect
+                | Particle::TintedLeaves
+                | Particle::Flash
+                | Particle::Effect
+                | Particle::InstantEffect
+                | Particle::DragonBreath
+                | Particle::SculkCharge
+                | Particle::Shriek
+                | Particle::Trail
+                | Particle::Vibration
+        )
+    }
+
+    /// The data as it follows the particle id in the particle packet.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        if let Err(e) = self.write(&mut buf) {
+            log::warn!("Failed to encode particle data: {e}");
+        }
+        buf
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) -> Result<(), WritingError> {
+        match self {
+            Self::None => {}
+            Self::Dust { color, scale } => {
+                buf.write_i32_be(*color)?;
+                buf.write_f32_be(*scale)?;
+            }
+            Self::DustTransition { from, to, scale } => {
+                buf.write_i32_be(*from)?;
+                buf.write_i32_be(*to)?;
+                buf.write_f32_be(*scale)?;
+            }
+            Self::Block(state) => buf.write_var_int(&VarInt(i32::from(*state)))?,
+            Self::Item(stack) => {
+                ItemStackSerializer(Cow::Borrowed(stack)).serialize(&mut Serializer::new(buf))?;
+            }
+            Self::Color(color) => buf.write_i32_be(*color)?,
+            Self::Spell { color, power } => {
+                buf.write_i32_be(*color)?;
+                buf.write_f32_be(*power)?;
+            }
+            Self::Power(value) | Self::Roll(value) => buf.write_f32_be(*value)?,
+            Self::Delay(delay) => buf.write_var_int(&VarInt(*delay))?,
+            Self::Trail {
+                target,
+                color,
+                duration,
+            } => {
+                buf.write_f64_be(target.x)?;
+                buf.write_f64_be(target.y)?;
+                buf.write_f64_be(target.z)?;
+                buf.write_i32_be(*color)?;
+                buf.write_var_int(&VarInt(*duration))?;
+            }
+            Self::Vibration { destination, ticks } => {
+                // A block position source
+                buf.write_var_int(&VarInt(0))?;
+                buf.write_block_pos(destination)?;
+                buf.write_var_int(&VarInt(*ticks))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Particles to spawn, with their data, spread and count.
+#[derive(Clone, Debug)]
+pub struct ParticleEffect {
+    particle: Particle,
+    data: ParticleData,
+    offset: Vector3<f32>,
+    speed: f32,
+    count: i32,
+    /// Shown even if the client is set to show fewer particles.
+    force: bool,
+    /// Sent to players up to [`LONG_DISTANCE_RANGE`] away instead of [`RANGE`].
+    long_distance: bool,
+}
+
+impl ParticleEffect {
+    /// A single particle without data, e.g. `flame`.
+    #[must_use]
+    pub const fn new(particle: Particle) -> Self {
+        Self {
+            particle,
+            data: ParticleData::None,
+            offset: Vector3::new(0.0, 0.0, 0.0),
+            speed: 0.0,
+            count: 1,
+            force: false,
+            long_distance: false,
+        }
+    }
+
+    /// `particle` with `data`, or `None` if the particle does not take that data.
+    #[must_use]
+    pub fn with_data(particle: Particle, data: ParticleData) -> Option<Self> {
+        data.fits(particle).then(|| Self {
+            data,
+            ..Self::new(particle)
+        })
+    }
+
+    /// Redstone dust of an RGB `color`. Scales are clamped to 0.01 to 4 like vanilla.
+    #[must_use]
+    pub fn dust(color: i32, scale: f32) -> Self {
+        Self {
+            data: ParticleData::Dust {
+                color,
+                scale: scale.clamp(0.01, 4.0),
+            },
+            ..Self::new(Particle::Dust)
+        }
+    }
+
+    /// Pieces of the block with `state`.
+    #[must_use]
+    pub fn block(state: BlockStateId) -> Self {
+        Self {
+            data: ParticleData::Block(state),
+            ..Self::new(Particle::Block)
+        }
+    }
+
+    /// Pieces of `stack`.
+    #[must_use]
+    pub fn item(stack: ItemStack) -> Self {
+        Self {
+            data: ParticleData::Item(stack),
+            ..Self::new(Particle::Item)
+        }
+    }
+
+    /// The swirls of potion effects, in an ARGB `color`.
+    #[must_use]
+    pub fn entity_effect(color: i32) -> Self {
+        Self {
+            data: ParticleData::Color(color),
+            ..Self::new(Particle::EntityEffect)
+        }
+    }
+
+    /// The purple breath of the ender dragon.
+    #[must_use]
+    pub fn dragon_breath(power: f32) -> Self {
+        Self {
+            data: ParticleData::Power(power),
+            ..Self::new(Particle::DragonBreath)
+        }
+    }
+
+    #[must_use]
+    pub const fn particle(&self) -> Particle {
+        self.particle
+    }
+
+    #[must_use]
+    pub const fn data(&self) -> &ParticleData {
+        &self.data
+    }
+
+    /// The particle with its data as an entity metadata value, like the particle of an area
+    /// effect cloud.
+    #[must_use]
+    pub fn metadata(&self) -> ParticleMetadata {
+        let mut buf = Vec::new();
+        let written = buf.write_var_int(&VarInt(self.particle as i32));
+        if let Err(e) = written.and_then(|()| self.data.write(&mut buf)) {
+            log::warn!("Failed to encode particle metadata: {e}");
+        }
+        ParticleMetadata(buf)
+    }
+
+    #[must_use]
+    pub const fn count(mut self, count: i32) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// How far from the position particles spawn on each axis. With a count of 0 a single
+    /// particle is spawned which moves in this direction instead.
+    #[must_use]
+    pub const fn offset(mut self, offset: Vector3<f32>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[must_use]
+    pub const fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    #[must_use]
+    pub const fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    #[must_use]
+    pub const fn long_distance(mut self, long_distance: bool) -> Self {
+        self.long_distance = long_distance;
+        self
+    }
+
+    /// Whether `player` is close enough to see particles at `position`: within range and within
+    /// the player's view distance.
+    #[must_use]
+    pub fn can_see(&self, player: &Player, position: Vector3<f64>) -> bool {
+        let range = if self.long_distance {
+            LONG_DISTANCE_RANGE
+        } else {
+            RANGE
+        };
+        let player_pos = player.position();
+        if player_pos.squared_distance_to_vec(&position) > range * range {
+            return false;
+        }
+        let view_distance = i32::from(player.config.load().view_distance.get());
+        let player_chunk = BlockPos::floored_v(player_pos).chunk_position();
+        let chunk = BlockPos::floored_v(position).chunk_position();
+        (player_chunk.x - chunk.x).abs() <= view_distance
+            && (player_chunk.y - chunk.y).abs() <= view_distance
+    }
+
+    async fn send(&self, player: &Player, positions: &[Vector3<f64>], data: &[u8]) {
+        for position in positions {
+            player
+                .client
+                .enqueue_packet(&CParticle::new(
+                    self.force,
+                    self.long_distance,
+                    *position,
+                    self.offset,
+                    self.speed,
+                    self.count,
+                    VarInt(self.particle as i32),
+                    data,
+                ))
+                .await;
+        }
+    }
+
+    /// Shows the particles at `position` to `player` alone, however far away it is.
+    pub async fn show_to(&self, player: &Player, position: Vector3<f64>) {
+        self.send(player, &[position], &self.data.encode()).await;
+    }
+
+    /// Shows the particles at all `positions` to `player` alone, e.g. along a shape.
+    pub async fn show_all_to(&self, player: &Player, positions: &[Vector3<f64>]) {
+        self.send(player, positions, &self.data.encode()).await;
+    }
+
+    /// Spawns the particles at `position` for the players in `world` that can see them.
+    pub async fn spawn(&self, world: &World, position: Vector3<f64>) {
+        self.spawn_all(world, &[position]).await;
+    }
+
+    /// Spawns the particles at all `positions`, e.g. along a shape, for the players in `world`
+    /// that can see them.
+    pub async fn spawn_all(&self, world: &World, positions: &[Vector3<f64>]) {
+        let data = self.data.encode();
+        for player in world.players.load().iter() {
+            let visible: Vec<_> = positions
+                .iter()
+                .copied()
+                .filter(|position| self.can_see(player, *position))
+                .collect();
+            self.send(player, &visible, &data).await;
+        }
+    }
+}
+
+/// A particle encoded as entity metadata, see [`ParticleEffect::metadata`].
+pub struct ParticleMetadata(Vec<u8>);
+
+impl Serialize for ParticleMetadata {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+/// Positions along common shapes, to spawn particles at.
+pub mod shapes {
+    use std::f64::consts::{PI, TAU};
+
+    use pumpkin_util::math::vector3::Vector3;
+
+    /// Points from `from` to `to`, both included, about `spacing` blocks apart.
+    #[must_use]
+    pub fn line(from: Vector3<f64>, to: Vector3<f64>, spacing: f64) -> Vec<Vector3<f64>> {
+        let delta = to.sub(&from);
+        let steps = (delta.length() / spacing.max(0.01)).ceil().max(1.0) as usize;
+        (0..=steps)
+            .map(|step| {
+                let t = step as f64 / steps as f64;
+                from.add(&delta.multiply(t, t, t))
+            })
+            .collect()
+    }
+
+    /// `points` points on a horizontal circle around `center`.
+    #[must_use]
+    pub fn circle(center: Vector3<f64>, radius: f64, points: usize) -> Vec<Vector3<f64>> {
+        (0..points)
+            .map(|point| {
+                let angle = TAU * point as f64 / points as f64;
+                center.add_raw(radius * angle.cos(), 0.0, radius * angle.sin())
+            })
+            .collect()
+    }
+
+    /// `points` points spread evenly over a sphere around `center`.
+    #[must_use]
+    pub fn sphere(center: Vector3<f64>, radius: f64, points: usize) -> Vec<Vector3<f64>> {
+        // A Fibonacci lattice, which keeps the points apart without clustering at the poles
+        let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+        (0..points)
+            .map(|point| {
+                let y = if points == 1 {
+                    0.0
+                } else {
+                    1.0 - 2.0 * point as f64 / (points - 1) as f64
+                };
+                let ring = (1.0 - y * y).sqrt();
+                let angle = golden_angle * point as f64;
+                center.add_raw(
+                    radius * ring * angle.cos(),
+                    radius * y,
+                    radius * ring * angle.sin(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_must_fit_the_particle() {
+        assert!(ParticleEffect::with_data(Particle::Flame, ParticleData::None).is_some());
+        assert!(ParticleEffect::with_data(Particle::Dust, ParticleData::None).is_none());
+        assert!(ParticleEffect::with_data(Particle::FallingDust, ParticleData::Block(1)).is_some());
+        assert!(ParticleEffect::with_data(Particle::Flame, ParticleData::Color(-1)).is_none());
+    }
+
+    #[test]
+    fn dust_is_encoded_as_color_and_scale() {
+        let data = ParticleData::Dust {
+            color: 0x00FF_0000,
+            scale: 1.0,
+        };
+        assert_eq!(data.encode(), [0, 0xFF, 0, 0, 0x3F, 0x80, 0, 0]);
+    }
+
+    #[test]
+    fn shapes_have_the_requested_points() {
+        let origin = Vector3::new(0.0, 64.0, 0.0);
+        let line = shapes::line(origin, origin.add_raw(3.0, 0.0, 0.0), 1.0);
+        assert_eq!(line.len(), 4);
+        assert_eq!(line[3], origin.add_raw(3.0, 0.0, 0.0));
+
+        for point in shapes::circle(origin, 2.0, 16) {
+            assert!((point.squared_distance_to_vec(&origin) - 4.0).abs() < 1e-9);
+        }
+        let sphere = shapes::sphere(origin, 2.0, 50);
+        assert_eq!(sphere.len(), 50);
+        for point in sphere {
+            assert!((point.squared_distance_to_vec(&origin) - 4.0).abs() < 1e-9);
+        }
+    }
+}
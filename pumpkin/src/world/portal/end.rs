@@ -1,19 +1,87 @@
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
-use pumpkin_data::{Block, BlockDirection, block_properties::BlockProperties};
-use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
+use pumpkin_data::{
+    Block, BlockDirection, block_properties::BlockProperties, dimension::Dimension,
+};
+use pumpkin_protocol::java::client::play::{CGameEvent, GameEvent};
+use pumpkin_util::math::{position::BlockPos, vector2::Vector2, vector3::Vector3};
 use pumpkin_world::world::BlockFlags;
 
+use crate::entity::EntityBase;
 use crate::world::World;
 
 type EndPortalFrameProperties = pumpkin_data::block_properties::EndPortalFrameLikeProperties;
 
+/// Where entities arrive in the End, just above the obsidian platform built for them.
+pub const END_SPAWN_POINT: BlockPos = BlockPos(Vector3::new(100, 50, 0));
+
 pub struct EndPortal;
 
 impl EndPortal {
     const FRAME_BLOCK: Block = Block::END_PORTAL_FRAME;
     const FRAME_BLOCK_ID: u16 = Self::FRAME_BLOCK.id;
 
+    /// Sends `entity` through an end portal to `dest_world`. Entities arrive in the End on an
+    /// obsidian platform, and leave it for the world spawn. Players leaving the End watch the
+    /// credits first, and come back once they respawn.
+    pub async fn teleport(entity: Arc<dyn EntityBase>, dest_world: Arc<World>) {
+        if dest_world.dimension == Dimension::THE_END {
+            Self::create_platform(&dest_world, END_SPAWN_POINT.down()).await;
+            let pos = END_SPAWN_POINT.to_f64().add_raw(0.5, 0.0, 0.5);
+            entity
+                .teleport(pos, Some(90.0), Some(0.0), dest_world)
+                .await;
+            return;
+        }
+
+        if let Some(player) = entity.get_player() {
+            if !player.won_game.swap(true, Ordering::Relaxed) {
+                let seen_credits = player.seen_credits.swap(true, Ordering::Relaxed);
+                player
+                    .client
+                    .enqueue_packet(&CGameEvent::new(
+                        GameEvent::WinGame,
+                        if seen_credits { 0.0 } else { 1.0 },
+                    ))
+                    .await;
+            }
+            return;
+        }
+
+        let (spawn_x, spawn_z) = {
+            let info = dest_world.level_info.load();
+            (info.spawn_x, info.spawn_z)
+        };
+        let top = dest_world
+            .get_top_block(Vector2::new(spawn_x, spawn_z))
+            .await;
+        let pos = Vector3::new(
+            f64::from(spawn_x) + 0.5,
+            f64::from(top + 1),
+            f64::from(spawn_z) + 0.5,
+        );
+        entity.teleport(pos, None, None, dest_world).await;
+    }
+
+    /// Builds the 5x5 obsidian platform below `pos`, clearing the space above it.
+    pub async fn create_platform(world: &Arc<World>, pos: BlockPos) {
+        for offset in BlockPos::iterate(BlockPos::new(-2, -1, -2), BlockPos::new(2, 2, 2)) {
+            let block = if offset.0.y == -1 {
+                &Block::OBSIDIAN
+            } else {
+                &Block::AIR
+            };
+            world
+                .set_block_state(
+                    &pos.offset(offset.0),
+                    block.default_state.id,
+                    BlockFlags::NOTIFY_ALL,
+                )
+                .await;
+        }
+    }
+
     pub async fn get_new_portal(world: &Arc<World>, pos: BlockPos) {
         let mid_pos = Self::get_mid_pos(world, pos);
         if let Some(mid_pos) = mid_pos.await
@@ -0,0 +1,113 @@
+//! The difficulty at a position, which grows the longer players spend in its chunk, the older the
+//! world is and the fuller the moon. It decides how well mobs are equipped when they spawn.
+
+use std::sync::atomic::Ordering::Relaxed;
+
+use pumpkin_util::Difficulty;
+use pumpkin_util::math::position::BlockPos;
+
+use super::World;
+
+/// Ticks after which the age of the world starts to raise the difficulty, three days.
+const WORLD_AGE_DELAY: f32 = 72_000.0;
+/// Ticks over which the age of the world raises the difficulty to its maximum, 60 days.
+const WORLD_AGE_RAMP: f32 = 1_440_000.0;
+/// Ticks players have to spend in a chunk to raise its difficulty to the maximum, 50 hours.
+const INHABITED_RAMP: f32 = 3_600_000.0;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RegionalDifficulty {
+    pub base: Difficulty,
+    effective: f32,
+}
+
+impl RegionalDifficulty {
+    #[must_use]
+    pub fn new(base: Difficulty, day_time: i64, inhabited_time: i64, moon_brightness: f32) -> Self {
+        Self {
+            base,
+            effective: Self::calculate(base, day_time, inhabited_time, moon_brightness),
+        }
+    }
+
+    fn calculate(
+        base: Difficulty,
+        day_time: i64,
+        inhabited_time: i64,
+        moon_brightness: f32,
+    ) -> f32 {
+        if base == Difficulty::Peaceful {
+            return 0.0;
+        }
+
+        let world_age =
+            ((day_time as f32 - WORLD_AGE_DELAY) / WORLD_AGE_RAMP).clamp(0.0, 1.0) * 0.25;
+        let mut local = (inhabited_time as f32 / INHABITED_RAMP).clamp(0.0, 1.0)
+            * if base == Difficulty::Hard { 1.0 } else { 0.75 };
+        local += (moon_brightness * 0.25).clamp(0.0, world_age);
+        if base == Difficulty::Easy {
+            local *= 0.5;
+        }
+        f32::from(base as u8) * (0.75 + world_age + local)
+    }
+
+    /// The regional difficulty shown on the debug screen, from 0 up to 6.75 on hard.
+    #[must_use]
+    pub const fn effective(&self) -> f32 {
+        self.effective
+    }
+
+    #[must_use]
+    pub fn is_harder_than(&self, difficulty: f32) -> bool {
+        self.effective > difficulty
+    }
+
+    /// How likely mobs are to spawn with equipment and effects, from 0 below an effective
+    /// difficulty of 2 up to 1 above 4.
+    #[must_use]
+    pub fn special_multiplier(&self) -> f32 {
+        ((self.effective - 2.0) / 2.0).clamp(0.0, 1.0)
+    }
+}
+
+impl World {
+    #[must_use]
+    pub fn get_difficulty(&self) -> Difficulty {
+        self.level_info.load().difficulty
+    }
+
+    /// Returns the regional difficulty at `pos`. Chunks that are not loaded count as never
+    /// inhabited.
+    pub async fn get_regional_difficulty(&self, pos: &BlockPos) -> RegionalDifficulty {
+        let inhabited_time = self
+            .level
+            .try_get_chunk(&pos.chunk_position())
+            .map_or(0, |chunk| chunk.inhabited_time.load(Relaxed));
+        let level_time = self.level_time.lock().await;
+        RegionalDifficulty::new(
+            self.get_difficulty(),
+            level_time.time_of_day,
+            inhabited_time,
+            level_time.moon_brightness(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regional_difficulty_grows_with_time() {
+        let fresh = RegionalDifficulty::new(Difficulty::Normal, 0, 0, 1.0);
+        assert!((fresh.effective() - 1.5).abs() < f32::EPSILON);
+        assert!(fresh.special_multiplier().abs() < f32::EPSILON);
+
+        let old = RegionalDifficulty::new(Difficulty::Hard, 1_512_000, 3_600_000, 1.0);
+        assert!((old.effective() - 6.75).abs() < f32::EPSILON);
+        assert!((old.special_multiplier() - 1.0).abs() < f32::EPSILON);
+
+        let peaceful = RegionalDifficulty::new(Difficulty::Peaceful, 1_512_000, 3_600_000, 1.0);
+        assert!(!peaceful.is_harder_than(0.0));
+    }
+}
@@ -0,0 +1,303 @@
+//! Experimental Folia-style entity and block ticking. Chunks are grouped into square regions, and
+//! regions that hold entities or scheduled ticks and touch each other are merged into one group.
+//! Each group is ticked on its own task.
+//!
+//! A group only runs the entity and block ticks that stay within its own regions, that is, the
+//! ones further than [`REACH`] blocks from any region outside the group. Damage, spawns,
+//! explosions and block updates of those ticks can't touch another group. The remaining ticks near
+//! the edge of a group run one at a time once every group has finished.
+//!
+//! Anything that reaches further than that is queued with [`World::queue_synchronized`] and runs
+//! at the end of the entity tick.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use pumpkin_data::Block;
+use pumpkin_data::fluid::Fluid;
+use pumpkin_util::math::position::BlockPos;
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_world::tick::{OrderedTick, ScheduledTick};
+use tokio::task::JoinSet;
+
+use super::World;
+use crate::entity::EntityBase;
+use crate::entity::player::Player;
+use crate::server::Server;
+
+/// Runs after every region has finished ticking its entities.
+pub type SynchronizedAction =
+    Box<dyn FnOnce(Arc<World>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+/// How far the tick of an entity or block may reach in blocks, e.g. to hit, explode, spread or
+/// power blocks.
+const REACH: i32 = 32;
+
+/// Scheduled and random ticks of neighbouring regions, in the order they were scheduled.
+#[derive(Default)]
+struct BlockTickGroup {
+    block_ticks: Vec<OrderedTick<&'static Block>>,
+    fluid_ticks: Vec<OrderedTick<&'static Fluid>>,
+    random_ticks: Vec<ScheduledTick<()>>,
+}
+
+/// Maps a chunk to its region.
+fn region_of(chunk: Vector2<i32>, region_size: u8) -> Vector2<i32> {
+    let shift = region_size.max(1).next_power_of_two().trailing_zeros();
+    Vector2::new(chunk.x >> shift, chunk.y >> shift)
+}
+
+/// Whether every region within [`REACH`] of `pos` belongs to `group`.
+fn is_confined(
+    pos: BlockPos,
+    group: usize,
+    group_of: &HashMap<Vector2<i32>, usize>,
+    region_size: u8,
+) -> bool {
+    let corner = |offset: i32| {
+        let chunk = Vector2::new((pos.0.x + offset) >> 4, (pos.0.z + offset) >> 4);
+        region_of(chunk, region_size)
+    };
+    let (min, max) = (corner(-REACH), corner(REACH));
+    (min.x..=max.x)
+        .all(|x| (min.y..=max.y).all(|z| group_of.get(&Vector2::new(x, z)) == Some(&group)))
+}
+
+/// Returns the group of every region in `regions`. Regions that touch, including diagonally,
+/// share a group. Groups are numbered from 0.
+fn group_regions(regions: impl IntoIterator<Item = Vector2<i32>>) -> HashMap<Vector2<i32>, usize> {
+    let mut index = HashMap::new();
+    let mut parents = Vec::new();
+    for region in regions {
+        index.entry(region).or_insert_with(|| {
+            parents.push(parents.len());
+            parents.len() - 1
+        });
+    }
+
+    for (region, &node) in &index {
+        for (dx, dz) in [(1, -1), (1, 0), (1, 1), (0, 1)] {
+            if let Some(&neighbour) = index.get(&Vector2::new(region.x + dx, region.y + dz)) {
+                let (a, b) = (root(&mut parents, node), root(&mut parents, neighbour));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    let mut groups = HashMap::new();
+    index
+        .iter()
+        .map(|(&region, &node)| {
+            let root = root(&mut parents, node);
+            let next = groups.len();
+            (region, *groups.entry(root).or_insert(next))
+        })
+        .collect()
+}
+
+const fn root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+impl World {
+    /// Queues an action that reaches into other regions. It runs at the end of the entity tick,
+    /// once every region has finished ticking its entities.
+    pub async fn queue_synchronized(&self, action: SynchronizedAction) {
+        self.synchronized_actions.lock().await.push(action);
+    }
+
+    pub(super) async fn run_synchronized_actions(self: &Arc<Self>) {
+        // Actions may queue further actions, which run right after
+        loop {
+            let actions = std::mem::take(&mut *self.synchronized_actions.lock().await);
+            if actions.is_empty() {
+                break;
+            }
+            for action in actions {
+                action(self.clone()).await;
+            }
+        }
+    }
+
+    /// Ticks the entities of every group of regions on its own task and waits for all of them,
+    /// then ticks the entities near the edge of a group in turn.
+    pub(super) async fn tick_entities_in_regions(
+        self: &Arc<Self>,
+        server: &Arc<Server>,
+        entities: &[Arc<dyn EntityBase>],
+        players: &[Arc<Player>],
+        region_size: u8,
+    ) {
+        let positions: Vec<_> = entities
+            .iter()
+            .map(|entity| entity.get_entity().block_pos.load())
+            .collect();
+        let group_of = group_regions(
+            positions
+                .iter()
+                .map(|pos| region_of(pos.chunk_position(), region_size)),
+        );
+
+        let mut groups: Vec<Vec<Arc<dyn EntityBase>>> = Vec::new();
+        groups.resize_with(
+            group_of.values().max().map_or(0, |max| max + 1),
+            Default::default,
+        );
+        let mut edge = Vec::new();
+        for (entity, &pos) in entities.iter().zip(&positions) {
+            let group = group_of[&region_of(pos.chunk_position(), region_size)];
+            if is_confined(pos, group, &group_of, region_size) {
+                groups[group].push(entity.clone());
+            } else {
+                edge.push(entity.clone());
+            }
+        }
+
+        let players: Arc<[Arc<Player>]> = players.into();
+        let mut tasks = JoinSet::new();
+        for group in groups.into_iter().filter(|group| !group.is_empty()) {
+            let world = self.clone();
+            let server = server.clone();
+            let players = players.clone();
+            tasks.spawn(async move {
+                for entity in &group {
+                    world.tick_entity(entity, &players, &server).await;
+                }
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            if let Err(err) = result {
+                log::error!("A region failed to tick its entities: {err}");
+            }
+        }
+
+        for entity in &edge {
+            self.tick_entity(entity, &players, server).await;
+        }
+    }
+
+    /// Runs the scheduled and random ticks of every group of regions on its own task and waits
+    /// for all of them, then runs the ticks near the edge of a group in order.
+    pub(super) async fn tick_blocks_in_regions(
+        self: &Arc<Self>,
+        block_ticks: Vec<OrderedTick<&'static Block>>,
+        fluid_ticks: Vec<OrderedTick<&'static Fluid>>,
+        random_ticks: Vec<ScheduledTick<()>>,
+        region_size: u8,
+    ) {
+        let region = |pos: &BlockPos| region_of(pos.chunk_position(), region_size);
+        let group_of = group_regions(
+            block_ticks
+                .iter()
+                .map(|tick| region(&tick.position))
+                .chain(fluid_ticks.iter().map(|tick| region(&tick.position)))
+                .chain(random_ticks.iter().map(|tick| region(&tick.position))),
+        );
+        // Ticks near the edge of their group may update blocks of another group
+        let group_at = |pos: &BlockPos| {
+            let group = group_of[&region(pos)];
+            is_confined(*pos, group, &group_of, region_size).then_some(group)
+        };
+
+        let mut groups: Vec<BlockTickGroup> = Vec::new();
+        groups.resize_with(
+            group_of.values().max().map_or(0, |max| max + 1),
+            Default::default,
+        );
+        let mut edge = BlockTickGroup::default();
+        for tick in block_ticks {
+            match group_at(&tick.position) {
+                Some(group) => groups[group].block_ticks.push(tick),
+                None => edge.block_ticks.push(tick),
+            }
+        }
+        for tick in fluid_ticks {
+            match group_at(&tick.position) {
+                Some(group) => groups[group].fluid_ticks.push(tick),
+                None => edge.fluid_ticks.push(tick),
+            }
+        }
+        for tick in random_ticks {
+            match group_at(&tick.position) {
+                Some(group) => groups[group].random_ticks.push(tick),
+                None => edge.random_ticks.push(tick),
+            }
+        }
+
+        let mut tasks = JoinSet::new();
+        for group in groups {
+            let world = self.clone();
+            tasks.spawn(async move {
+                world
+                    .run_block_ticks(&group.block_ticks, &group.fluid_ticks, &group.random_ticks)
+                    .await;
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            if let Err(err) = result {
+                log::error!("A region failed to tick its blocks: {err}");
+            }
+        }
+
+        self.run_block_ticks(&edge.block_ticks, &edge.fluid_ticks, &edge.random_ticks)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_sizes_round_up() {
+        assert_eq!(region_of(Vector2::new(-1, 8), 8), Vector2::new(-1, 1));
+        assert_eq!(region_of(Vector2::new(7, -9), 6), Vector2::new(0, -2));
+        assert_eq!(region_of(Vector2::new(3, -3), 0), Vector2::new(3, -3));
+    }
+
+    #[test]
+    fn entities_near_other_regions_are_not_confined() {
+        let group_of = HashMap::from([
+            (Vector2::new(0, 0), 0),
+            (Vector2::new(1, 0), 0),
+            (Vector2::new(0, 1), 1),
+        ]);
+        let confined = |x, z| is_confined(BlockPos::new(x, 64, z), 0, &group_of, 8);
+        assert!(confined(64, 64));
+        assert!(confined(200, 80));
+        // Within reach of the region of group 1, or of regions without a group
+        assert!(!confined(64, 100));
+        assert!(!confined(10, 64));
+        assert!(!confined(240, 64));
+    }
+
+    #[test]
+    fn touching_regions_share_a_group() {
+        let regions = [
+            Vector2::new(0, 0),
+            Vector2::new(1, 1),
+            Vector2::new(2, 0),
+            Vector2::new(5, 5),
+            Vector2::new(-3, 4),
+            Vector2::new(-2, 4),
+            Vector2::new(0, 0),
+        ];
+        let groups = group_regions(regions);
+        assert_eq!(groups.len(), 6);
+        assert_eq!(groups[&regions[0]], groups[&regions[1]]);
+        assert_eq!(groups[&regions[1]], groups[&regions[2]]);
+        assert_eq!(groups[&regions[4]], groups[&regions[5]]);
+        assert_ne!(groups[&regions[0]], groups[&regions[3]]);
+        assert_ne!(groups[&regions[0]], groups[&regions[4]]);
+        assert_ne!(groups[&regions[3]], groups[&regions[4]]);
+        let mut numbers: Vec<_> = groups.values().copied().collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        assert_eq!(numbers, [0, 1, 2]);
+    }
+}
@@ -4,16 +4,22 @@ use pumpkin_data::scoreboard::ScoreboardDisplaySlot;
 use pumpkin_protocol::{
     NumberFormat,
     codec::var_int::VarInt,
-    java::client::play::{CDisplayObjective, CUpdateObjectives, CUpdateScore, RenderType},
+    java::client::play::{
+        CDisplayObjective, CUpdateObjectives, CUpdateScore, CUpdateTeams, CollisionRule,
+        NameTagVisibility, RenderType, TeamAction, TeamInfo,
+    },
 };
-use pumpkin_util::text::TextComponent;
+use pumpkin_util::text::{TextComponent, color::NamedColor, hover::HoverEvent};
 
 use super::World;
+use crate::entity::{EntityBase, player::Player};
 
 #[derive(Default)]
 pub struct Scoreboard {
     objectives: HashMap<String, ScoreboardObjective<'static>>,
-    //  teams: HashMap<String, Team>,
+    teams: HashMap<String, Team>,
+    /// The team of each member, by scoreboard name.
+    member_teams: HashMap<String, String>,
 }
 
 impl Scoreboard {
@@ -62,12 +68,281 @@ impl Scoreboard {
             .await;
     }
 
-    // pub fn add_team(&mut self, name: String) {
-    //     if self.teams.contains_key(&name) {
-    //         // Maybe make this an error ?
-    //         log::warn!("Tried to create Team which does already exist, {}", name);
-    //     }
-    // }
+    /// The name entities are known by on the scoreboard: the name of players and the UUID of
+    /// everything else.
+    #[must_use]
+    pub fn holder_name(entity: &dyn EntityBase) -> String {
+        entity.get_player().map_or_else(
+            || entity.get_entity().entity_uuid.to_string(),
+            |player| player.gameprofile.name.clone(),
+        )
+    }
+
+    #[must_use]
+    pub fn get_team(&self, name: &str) -> Option<&Team> {
+        self.teams.get(name)
+    }
+
+    pub fn teams(&self) -> impl Iterator<Item = &Team> {
+        self.teams.values()
+    }
+
+    /// The team `member` is on, by scoreboard name.
+    #[must_use]
+    pub fn team_of(&self, member: &str) -> Option<&Team> {
+        self.member_teams
+            .get(member)
+            .and_then(|team| self.teams.get(team))
+    }
+
+    #[must_use]
+    pub fn team_of_entity(&self, entity: &dyn EntityBase) -> Option<&Team> {
+        self.team_of(&Self::holder_name(entity))
+    }
+
+    /// Creates a team with default settings. Returns `false` if it already exists.
+    pub async fn add_team(
+        &mut self,
+        world: &World,
+        name: &str,
+        display_name: TextComponent,
+    ) -> bool {
+        if self.teams.contains_key(name) {
+            return false;
+        }
+        let team = Team::new(name.to_string(), display_name);
+        world
+            .broadcast_packet_all(&CUpdateTeams::new(
+                name,
+                TeamAction::Create {
+                    info: team.info(),
+                    members: &team.members,
+                },
+            ))
+            .await;
+        self.teams.insert(name.to_string(), team);
+        true
+    }
+
+    /// Removes a team and lets all of its members go.
+    pub async fn remove_team(&mut self, world: &World, name: &str) -> Option<Team> {
+        let team = self.teams.remove(name)?;
+        for member in &team.members {
+            self.member_teams.remove(member);
+        }
+        world
+            .broadcast_packet_all(&CUpdateTeams::new(name, TeamAction::Remove))
+            .await;
+        Some(team)
+    }
+
+    /// Changes the settings of a team with `update`, which returns whether anything changed, and
+    /// sends the changes to all players. Returns `None` if there is no such team.
+    pub async fn update_team(
+        &mut self,
+        world: &World,
+        name: &str,
+        update: impl FnOnce(&mut Team) -> bool,
+    ) -> Option<bool> {
+        let team = self.teams.get_mut(name)?;
+        let changed = update(team);
+        if changed {
+            world
+                .broadcast_packet_all(&CUpdateTeams::new(name, TeamAction::Update(team.info())))
+                .await;
+        }
+        Some(changed)
+    }
+
+    /// Puts `members` on a team, taking them off their previous teams. Returns `false` if there
+    /// is no such team.
+    pub async fn join_team(&mut self, world: &World, name: &str, members: &[String]) -> bool {
+        if !self.teams.contains_key(name) {
+            return false;
+        }
+        for member in members {
+            if self
+                .member_teams
+                .get(member)
+                .is_some_and(|team| team == name)
+            {
+                continue;
+            }
+            self.leave_team(world, member).await;
+        }
+        let Some(team) = self.teams.get_mut(name) else {
+            return false;
+        };
+        let joined: Vec<String> = members
+            .iter()
+            .filter(|member| !team.members.contains(*member))
+            .cloned()
+            .collect();
+        for member in &joined {
+            self.member_teams.insert(member.clone(), name.to_string());
+        }
+        team.members.extend_from_slice(&joined);
+        world
+            .broadcast_packet_all(&CUpdateTeams::new(name, TeamAction::AddMembers(&joined)))
+            .await;
+        true
+    }
+
+    /// Takes `member` off their team. Returns `false` if they were not on one.
+    pub async fn leave_team(&mut self, world: &World, member: &str) -> bool {
+        let Some(name) = self.member_teams.remove(member) else {
+            return false;
+        };
+        if let Some(team) = self.teams.get_mut(&name) {
+            team.members.retain(|other| other != member);
+        }
+        world
+            .broadcast_packet_all(&CUpdateTeams::new(
+                &name,
+                TeamAction::RemoveMembers(&[member.to_string()]),
+            ))
+            .await;
+        true
+    }
+
+    /// Takes everyone off a team. Returns how many members it had, or `None` if there is no such
+    /// team.
+    pub async fn empty_team(&mut self, world: &World, name: &str) -> Option<usize> {
+        let team = self.teams.get_mut(name)?;
+        let members = std::mem::take(&mut team.members);
+        for member in &members {
+            self.member_teams.remove(member);
+        }
+        if !members.is_empty() {
+            world
+                .broadcast_packet_all(&CUpdateTeams::new(
+                    name,
+                    TeamAction::RemoveMembers(&members),
+                ))
+                .await;
+        }
+        Some(members.len())
+    }
+
+    /// Sends all teams to a player that just joined the world.
+    pub async fn send_teams(&self, player: &Player) {
+        for team in self.teams.values() {
+            player
+                .client
+                .enqueue_packet(&CUpdateTeams::new(
+                    &team.name,
+                    TeamAction::Create {
+                        info: team.info(),
+                        members: &team.members,
+                    },
+                ))
+                .await;
+        }
+    }
+
+    /// Whether an entity on `team` may hurt a player on `other`.
+    #[must_use]
+    pub fn can_harm(team: Option<&Team>, other: Option<&Team>) -> bool {
+        match (team, other) {
+            (Some(team), Some(other)) if team.name == other.name => team.friendly_fire,
+            _ => true,
+        }
+    }
+
+    /// Whether entities on `team` and `other` push each other apart, following both of their
+    /// collision rules.
+    #[must_use]
+    pub fn can_push(team: Option<&Team>, other: Option<&Team>) -> bool {
+        let rule = team.map_or(CollisionRule::Always, |team| team.collision_rule);
+        let other_rule = other.map_or(CollisionRule::Always, |team| team.collision_rule);
+        let same_team =
+            matches!((team, other), (Some(team), Some(other)) if team.name == other.name);
+        [rule, other_rule].into_iter().all(|rule| match rule {
+            CollisionRule::Always => true,
+            CollisionRule::Never => false,
+            CollisionRule::PushOtherTeams => !same_team,
+            CollisionRule::PushOwnTeam => same_team,
+        })
+    }
+}
+
+/// A scoreboard team. Members are known by their scoreboard name, see
+/// [`Scoreboard::holder_name`].
+pub struct Team {
+    pub name: String,
+    pub display_name: TextComponent,
+    /// Colors the names of the members. `None` leaves them uncolored.
+    pub color: Option<NamedColor>,
+    pub prefix: TextComponent,
+    pub suffix: TextComponent,
+    pub friendly_fire: bool,
+    pub see_friendly_invisibles: bool,
+    pub name_tag_visibility: NameTagVisibility,
+    pub collision_rule: CollisionRule,
+    members: Vec<String>,
+}
+
+impl Team {
+    #[must_use]
+    pub fn new(name: String, display_name: TextComponent) -> Self {
+        Self {
+            name,
+            display_name,
+            color: None,
+            prefix: TextComponent::text(""),
+            suffix: TextComponent::text(""),
+            friendly_fire: true,
+            see_friendly_invisibles: true,
+            name_tag_visibility: NameTagVisibility::Always,
+            collision_rule: CollisionRule::Always,
+            members: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+
+    /// Decorates the name of a member with the prefix, color and suffix of the team.
+    #[must_use]
+    pub fn format_name(&self, name: TextComponent) -> TextComponent {
+        let mut name = name;
+        if let Some(color) = self.color {
+            name = name.color_named(color);
+        }
+        TextComponent::text("")
+            .add_child(self.prefix.clone())
+            .add_child(name)
+            .add_child(self.suffix.clone())
+    }
+
+    /// The display name in brackets as commands show teams, with the name of the team on hover.
+    #[must_use]
+    pub fn formatted_display_name(&self) -> TextComponent {
+        let mut name = TextComponent::text("[")
+            .add_child(self.display_name.clone())
+            .add_child(TextComponent::text("]"));
+        if let Some(color) = self.color {
+            name = name.color_named(color);
+        }
+        name.hover_event(HoverEvent::show_text(TextComponent::text(
+            self.name.clone(),
+        )))
+    }
+
+    const fn info(&self) -> TeamInfo<'_> {
+        TeamInfo {
+            display_name: &self.display_name,
+            friendly_fire: self.friendly_fire,
+            see_friendly_invisibles: self.see_friendly_invisibles,
+            name_tag_visibility: self.name_tag_visibility,
+            collision_rule: self.collision_rule,
+            color: self.color,
+            prefix: &self.prefix,
+            suffix: &self.suffix,
+        }
+    }
 }
 
 pub struct ScoreboardObjective<'a> {
@@ -120,3 +395,43 @@ impl<'a> ScoreboardScore<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(name: &str, collision_rule: CollisionRule) -> Team {
+        let mut team = Team::new(name.to_string(), TextComponent::text(name));
+        team.collision_rule = collision_rule;
+        team
+    }
+
+    #[test]
+    fn collision_rules() {
+        let always = team("a", CollisionRule::Always);
+        let never = team("b", CollisionRule::Never);
+        let own = team("c", CollisionRule::PushOwnTeam);
+        let other = team("d", CollisionRule::PushOtherTeams);
+
+        assert!(Scoreboard::can_push(None, None));
+        assert!(Scoreboard::can_push(Some(&always), None));
+        assert!(!Scoreboard::can_push(Some(&never), None));
+        assert!(!Scoreboard::can_push(None, Some(&never)));
+        assert!(Scoreboard::can_push(Some(&own), Some(&own)));
+        assert!(!Scoreboard::can_push(Some(&own), Some(&always)));
+        assert!(!Scoreboard::can_push(Some(&other), Some(&other)));
+        assert!(Scoreboard::can_push(Some(&other), Some(&always)));
+    }
+
+    #[test]
+    fn friendly_fire() {
+        let mut peaceful = team("a", CollisionRule::Always);
+        peaceful.friendly_fire = false;
+        let rivals = team("b", CollisionRule::Always);
+
+        assert!(!Scoreboard::can_harm(Some(&peaceful), Some(&peaceful)));
+        assert!(Scoreboard::can_harm(Some(&peaceful), Some(&rivals)));
+        assert!(Scoreboard::can_harm(Some(&rivals), Some(&rivals)));
+        assert!(Scoreboard::can_harm(None, Some(&peaceful)));
+    }
+}
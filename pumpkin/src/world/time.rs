@@ -2,6 +2,9 @@ use pumpkin_protocol::{bedrock::client::set_time::CSetTime, java::client::play::
 
 use super::World;
 
+/// How bright the moon is in each of its phases, from full moon to waxing gibbous.
+const MOON_BRIGHTNESS: [f32; 8] = [1.0, 0.75, 0.5, 0.25, 0.0, 0.25, 0.5, 0.75];
+
 pub struct LevelTime {
     pub world_age: i64,
     pub time_of_day: i64,
@@ -35,9 +38,11 @@ impl LevelTime {
     }
 
     pub async fn send_time(&self, world: &World) {
+        // Clients only advance the time between updates while the daylight cycle is on
+        let advance_time = world.level_info.load().game_rules.advance_time;
         world
             .broadcast_editioned(
-                &CUpdateTime::new(self.world_age, self.time_of_day, true),
+                &CUpdateTime::new(self.world_age, self.time_of_day, advance_time),
                 &CSetTime::new(self.time_of_day as _),
             )
             .await;
@@ -66,6 +71,35 @@ impl LevelTime {
         self.time_of_day / 24000
     }
 
+    /// The phase of the moon from 0, full moon, to 7. It changes every day.
+    #[must_use]
+    pub const fn moon_phase(&self) -> usize {
+        (self.time_of_day / 24000).rem_euclid(8) as usize
+    }
+
+    #[must_use]
+    pub const fn moon_brightness(&self) -> f32 {
+        MOON_BRIGHTNESS[self.moon_phase()]
+    }
+
+    /// The angle of the sun in turns, from 0 at noon through 0.5 at midnight.
+    #[must_use]
+    pub fn sky_angle(&self) -> f64 {
+        let day = (self.time_of_day.rem_euclid(24000) as f64 / 24000.0 - 0.25).rem_euclid(1.0);
+        (day * 2.0 + 0.5 - (day * std::f64::consts::PI).cos() / 2.0) / 3.0
+    }
+
+    /// How much the sky light is darkened, from 0 at noon to 11 at midnight. Rain and thunder,
+    /// from 0 to 1, darken it further.
+    #[must_use]
+    pub fn sky_darken(&self, rain_level: f32, thunder_level: f32) -> u8 {
+        let sun_angle = self.sky_angle();
+        let brightness = (0.5 + 2.0 * (sun_angle * std::f64::consts::TAU).cos().clamp(-0.25, 0.25))
+            * (1.0 - f64::from(rain_level) * 5.0 / 16.0)
+            * (1.0 - f64::from(thunder_level) * 5.0 / 16.0);
+        ((1.0 - brightness) * 11.0) as u8
+    }
+
     #[must_use]
     pub const fn is_night(&self) -> bool {
         (self.time_of_day % 24000) >= 12000 && (self.time_of_day % 24000) <= 23999
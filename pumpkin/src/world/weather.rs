@@ -82,7 +82,7 @@ impl Weather {
     }
 
     pub async fn tick_weather(&mut self, world: &World) {
-        if !self.weather_cycle_enabled {
+        if self.weather_cycle_enabled {
             self.advance_weather_cycle();
         }
 
@@ -123,7 +123,6 @@ impl Weather {
     }
 
     fn advance_weather_cycle(&mut self) {
-        // Removed async since there are no await calls
         if self.clear_weather_time > 0 {
             self.clear_weather_time -= 1;
             self.thunder_time = i32::from(!self.thundering);